@@ -0,0 +1,23 @@
+@group(0) @binding(0)
+var<storage, read_write> values: array<u32>;
+
+@compute @workgroup_size(64)
+fn collatz(@builtin(global_invocation_id) id: vec3<u32>) {
+    let index = id.x;
+    if index >= arrayLength(&values) {
+        return;
+    }
+
+    // Counts the steps needed to reach 1, replacing the starting value
+    var n = values[index];
+    var steps = 0u;
+    while n > 1u {
+        if n % 2u == 0u {
+            n = n / 2u;
+        } else {
+            n = 3u * n + 1u;
+        }
+        steps = steps + 1u;
+    }
+    values[index] = steps;
+}
@@ -12,7 +12,7 @@ var<uniform> material: CustomMaterial;
 
 struct Vertex {
     @location(0) position: vec3<f32>,
-    @location(1) blend_color: vec4<f32>,
+    @location(7) blend_color: vec4<f32>,
 };
 
 struct VertexOutput {
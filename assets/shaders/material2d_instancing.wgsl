@@ -0,0 +1,19 @@
+#import bevy_sprite::mesh2d_view_bindings
+
+struct BlinkingMaterial {
+    color: vec4<f32>,
+};
+
+@group(1) @binding(0)
+var<uniform> material: BlinkingMaterial;
+
+struct FragmentInput {
+    #import bevy_sprite::mesh2d_vertex_output
+};
+
+@fragment
+fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
+    // The instance data of each entity holds the phase and the speed of its blinking
+    let blink = 0.5 + 0.5 * sin(globals.time * in.instance_data.y + in.instance_data.x);
+    return vec4<f32>(material.color.rgb * blink, material.color.a);
+}
@@ -0,0 +1,30 @@
+struct ProgressBarMaterial {
+    from_color: vec4<f32>,
+    to_color: vec4<f32>,
+    progress: f32,
+};
+
+@group(1) @binding(0)
+var<uniform> material: ProgressBarMaterial;
+
+@fragment
+fn fragment(
+    #import bevy_ui::ui_vertex_output
+) -> @location(0) vec4<f32> {
+    // The borders of the node are left empty
+    let point = uv * size;
+    let inner_min = border_widths.xy;
+    let inner_max = size - border_widths.zw;
+    if any(point < inner_min) || any(point > inner_max) {
+        return vec4<f32>(0.8, 0.8, 0.8, 1.0);
+    }
+
+    let x = (point.x - inner_min.x) / (inner_max.x - inner_min.x);
+    if x > material.progress {
+        return vec4<f32>(0.1, 0.1, 0.1, 1.0);
+    }
+
+    // Darken every other line of pixels for a scanline effect
+    let scanline = 0.85 + 0.15 * step(0.5, fract(point.y / 4.0));
+    return mix(material.from_color, material.to_color, x) * vec4<f32>(vec3<f32>(scanline), 1.0);
+}
@@ -883,6 +883,46 @@ impl App {
         self
     }
 
+    /// Registers a migration of the values of `P`, the previous version of the type `T`, to `T`
+    /// in the [`TypeRegistry`](bevy_reflect::TypeRegistry) resource, so that the scenes and
+    /// reflected values saved with `P` are loaded as `T`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use bevy_app::App;
+    /// use bevy_reflect::{FromReflect, Reflect};
+    ///
+    /// // the component as it was saved in the first scenes
+    /// #[derive(Reflect, FromReflect)]
+    /// struct HealthV0(u32);
+    ///
+    /// #[derive(Reflect, FromReflect)]
+    /// struct Health {
+    ///     current: f32,
+    ///     max: f32,
+    /// }
+    ///
+    /// App::new().register_type_migration(|HealthV0(hp)| Health {
+    ///     current: hp as f32,
+    ///     max: 100.0,
+    /// });
+    /// ```
+    ///
+    /// See [`bevy_reflect::TypeRegistry::register_migration`].
+    #[cfg(feature = "bevy_reflect")]
+    pub fn register_type_migration<P, T>(
+        &mut self,
+        migrate: impl Fn(P) -> T + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        P: bevy_reflect::FromReflect + bevy_reflect::GetTypeRegistration,
+        T: bevy_reflect::Reflect + bevy_reflect::GetTypeRegistration,
+    {
+        let registry = self.world.resource_mut::<AppTypeRegistry>();
+        registry.write().register_migration(migrate);
+        self
+    }
+
     /// Retrieves a `SubApp` stored inside this [`App`].
     ///
     /// # Panics
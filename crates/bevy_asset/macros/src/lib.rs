@@ -0,0 +1,165 @@
+use bevy_macro_utils::BevyManifest;
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    parse_macro_input, Data, DeriveInput, Error, Fields, Lit, LitStr, Meta, NestedMeta, Path,
+    Result,
+};
+
+const ASSET_ATTRIBUTE_NAME: &str = "asset";
+
+pub(crate) fn bevy_asset_path() -> Path {
+    BevyManifest::default()
+        .maybe_get_path("bevy_asset")
+        // NOTE: If the derivation is within bevy_asset, then we need to return 'crate'
+        .unwrap_or_else(|| BevyManifest::parse_str("crate"))
+}
+
+/// Implements `AssetCollection` for a struct, loading its fields from the paths of their `asset`
+/// attribute.
+///
+/// - `#[asset(path = "...")]` loads a `Handle<T>` or a `HandleUntyped`, with a label if any.
+/// - `#[asset(paths("...", "..."))]` loads a `Vec` of handles.
+/// - `#[asset(folder = "...")]` loads a `Handle<LoadedFolder>`, with a glob if any.
+///
+/// The fields without an `asset` attribute are set to their default value.
+///
+/// # Example
+///
+/// ```no_compile
+/// #[derive(Resource, AssetCollection)]
+/// struct LevelAssets {
+///     #[asset(path = "models/level.glb#Scene0")]
+///     level: Handle<Scene>,
+///     #[asset(paths("sounds/step_1.ogg", "sounds/step_2.ogg"))]
+///     steps: Vec<Handle<AudioSource>>,
+///     #[asset(folder = "textures/enemies")]
+///     enemies: Handle<LoadedFolder>,
+///     enemies_killed: u32,
+/// }
+/// ```
+#[proc_macro_derive(AssetCollection, attributes(asset))]
+pub fn derive_asset_collection(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    derive_collection(ast)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
+
+/// How a field of the collection is loaded
+enum FieldAssets {
+    Path(LitStr),
+    Paths(Vec<LitStr>),
+    Folder(LitStr),
+    Default,
+}
+
+fn derive_collection(ast: DeriveInput) -> Result<TokenStream2> {
+    let bevy_asset_path = bevy_asset_path();
+    let Data::Struct(data) = &ast.data else {
+        return Err(Error::new_spanned(
+            &ast,
+            "AssetCollection can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new_spanned(
+            &ast,
+            "AssetCollection can only be derived for structs with named fields",
+        ));
+    };
+
+    let mut field_loads = Vec::new();
+    let mut field_ids = Vec::new();
+    for field in &fields.named {
+        let field_name = field.ident.as_ref().unwrap();
+        let (load, ids) = match field_assets(&field.attrs)? {
+            FieldAssets::Path(path) => (
+                quote!(#bevy_asset_path::CollectionHandle::load(asset_server, #path)),
+                quote!(ids.push(#bevy_asset_path::CollectionHandle::id(&self.#field_name));),
+            ),
+            FieldAssets::Paths(paths) => (
+                quote!(vec![#(#bevy_asset_path::CollectionHandle::load(asset_server, #paths)),*]),
+                quote!(ids.extend(self.#field_name.iter().map(#bevy_asset_path::CollectionHandle::id));),
+            ),
+            FieldAssets::Folder(path) => (
+                quote!(asset_server.load_folder(#path)),
+                quote!(ids.push(#bevy_asset_path::CollectionHandle::id(&self.#field_name));),
+            ),
+            FieldAssets::Default => (quote!(::core::default::Default::default()), quote!()),
+        };
+        field_loads.push(quote!(#field_name: #load));
+        field_ids.push(ids);
+    }
+
+    let struct_name = &ast.ident;
+    let (impl_generics, type_generics, where_clause) = &ast.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics #bevy_asset_path::AssetCollection for #struct_name #type_generics #where_clause {
+            fn load(asset_server: &#bevy_asset_path::AssetServer) -> Self {
+                Self {
+                    #(#field_loads,)*
+                }
+            }
+
+            fn handle_ids(&self) -> ::std::vec::Vec<#bevy_asset_path::HandleId> {
+                let mut ids = ::std::vec::Vec::new();
+                #(#field_ids)*
+                ids
+            }
+        }
+    })
+}
+
+fn field_assets(attrs: &[syn::Attribute]) -> Result<FieldAssets> {
+    let mut assets = FieldAssets::Default;
+    for attr in attrs {
+        if !attr.path.is_ident(ASSET_ATTRIBUTE_NAME) {
+            continue;
+        }
+        let Meta::List(list) = attr.parse_meta()? else {
+            return Err(Error::new_spanned(attr, "expected `#[asset(...)]`"));
+        };
+        for nested in &list.nested {
+            assets = match nested {
+                NestedMeta::Meta(Meta::NameValue(name_value)) => {
+                    let Lit::Str(path) = &name_value.lit else {
+                        return Err(Error::new_spanned(
+                            &name_value.lit,
+                            "expected a string path",
+                        ));
+                    };
+                    if name_value.path.is_ident("path") {
+                        FieldAssets::Path(path.clone())
+                    } else if name_value.path.is_ident("folder") {
+                        FieldAssets::Folder(path.clone())
+                    } else {
+                        return Err(Error::new_spanned(
+                            &name_value.path,
+                            "expected `path`, `paths` or `folder`",
+                        ));
+                    }
+                }
+                NestedMeta::Meta(Meta::List(paths)) if paths.path.is_ident("paths") => {
+                    let paths = paths
+                        .nested
+                        .iter()
+                        .map(|path| match path {
+                            NestedMeta::Lit(Lit::Str(path)) => Ok(path.clone()),
+                            _ => Err(Error::new_spanned(path, "expected a string path")),
+                        })
+                        .collect::<Result<_>>()?;
+                    FieldAssets::Paths(paths)
+                }
+                _ => {
+                    return Err(Error::new_spanned(
+                        nested,
+                        "expected `path = \"...\"`, `paths(\"...\")` or `folder = \"...\"`",
+                    ))
+                }
+            };
+        }
+    }
+    Ok(assets)
+}
@@ -1,17 +1,28 @@
 use crate::{
+    folder::{glob_matches, split_folder_glob},
     path::{AssetPath, AssetPathId, SourcePathId},
-    Asset, AssetIo, AssetIoError, AssetLifecycle, AssetLifecycleChannel, AssetLifecycleEvent,
-    AssetLoader, Assets, Handle, HandleId, HandleUntyped, LabelId, LoadContext, LoadState,
-    RefChange, RefChangeChannel, SourceInfo, SourceMeta,
+    split_asset_scheme, Asset, AssetHandles, AssetIo, AssetIoError, AssetLifecycle,
+    AssetLifecycleChannel, AssetLifecycleEvent, AssetLoader, AssetManifest, AssetMeta, AssetSaver,
+    Assets, ErasedAssetSaver, Handle, HandleHolders, HandleId, HandleReport, HandleUntyped,
+    LabelId, LoadContext, LoadState, LoadedFolder, LoaderSettings, RefChange, RefChangeChannel,
+    RetentionPolicy, SchemeAssetIo, SettingsOverride, SourceInfo, SourceMeta,
 };
 use anyhow::Result;
-use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_ecs::{
+    event::EventWriter,
+    system::{Res, ResMut, Resource},
+};
 use bevy_log::warn;
+use bevy_reflect::TypeUuid;
 use bevy_tasks::IoTaskPool;
-use bevy_utils::{Entry, HashMap, Uuid};
+use bevy_utils::{Entry, HashMap, HashSet, Uuid};
 use crossbeam_channel::TryRecvError;
 use parking_lot::{Mutex, RwLock};
-use std::{path::Path, sync::Arc};
+use std::{
+    any::Any,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
 use thiserror::Error;
 
 /// Errors that occur while loading assets with an `AssetServer`.
@@ -38,9 +49,30 @@ pub enum AssetServerError {
     #[error("encountered an error while loading an asset: {0}")]
     AssetLoaderError(anyhow::Error),
 
+    /// The loader of an asset loaded directly didn't load an asset with its label.
+    #[error("the loader did not load the asset {0}")]
+    MissingLoadedAsset(String),
+
     /// Encountered an error while reading an asset from disk.
     #[error("encountered an error while reading an asset: {0}")]
     AssetIoError(#[from] AssetIoError),
+
+    /// No asset saver was found for the type of the asset and the specified extensions.
+    #[error("no `AssetSaver` found{}", format_missing_asset_ext(.extensions))]
+    MissingAssetSaver {
+        /// The list of extensions detected on the path the asset was saved to.
+        ///
+        /// The list may be empty if the path is invalid or doesn't have an extension.
+        extensions: Vec<String>,
+    },
+
+    /// The asset to save doesn't exist in its [`Assets`] collection.
+    #[error("the asset to save does not exist")]
+    MissingAsset,
+
+    /// Encountered an error while writing an asset with its saver.
+    #[error("encountered an error while saving an asset: {0}")]
+    AssetSaverError(anyhow::Error),
 }
 
 fn format_missing_asset_ext(exts: &[String]) -> String {
@@ -60,18 +92,59 @@ pub(crate) struct AssetRefCounter {
     pub(crate) channel: Arc<RefChangeChannel>,
     pub(crate) ref_counts: Arc<RwLock<HashMap<HandleId, usize>>>,
     pub(crate) mark_unused_assets: Arc<Mutex<Vec<HandleId>>>,
+    /// The holders of the strong handles, tracked once enabled
+    pub(crate) handle_holders: Mutex<Option<HandleHolders>>,
+}
+
+/// A folder listed by the [`AssetServer`], whose assets are loading
+struct PendingFolder {
+    id: AssetPathId,
+    version: usize,
+    handles: Vec<HandleUntyped>,
+}
+
+/// Why an asset was reloaded by the [`AssetServer`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReloadCause {
+    /// The asset itself changed, or was reloaded with [`AssetServer::reload_asset`].
+    Changed,
+    /// An asset it depends on changed, directly or through other dependencies, like the texture
+    /// of a material of a scene.
+    Dependency(AssetPath<'static>),
+}
+
+/// An event sent once the assets of a path were reloaded
+///
+/// The event is sent in the frame the reloaded assets are updated in their [`Assets`], before
+/// their [`AssetEvent::Modified`](crate::AssetEvent::Modified) events.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetReloaded {
+    /// The path of the reloaded assets, without a label
+    pub path: AssetPath<'static>,
+    /// Why the assets were reloaded
+    pub cause: ReloadCause,
 }
 
 /// Internal data for the asset server.
 ///
 /// [`AssetServer`] is the public API for interacting with the asset server.
 pub struct AssetServerInternal {
-    pub(crate) asset_io: Box<dyn AssetIo>,
+    pub(crate) asset_io: SchemeAssetIo,
     pub(crate) asset_ref_counter: AssetRefCounter,
     pub(crate) asset_sources: Arc<RwLock<HashMap<SourcePathId, SourceInfo>>>,
     pub(crate) asset_lifecycles: Arc<RwLock<HashMap<Uuid, Box<dyn AssetLifecycle>>>>,
     loaders: RwLock<Vec<Arc<dyn AssetLoader>>>,
     extension_to_loader_index: RwLock<HashMap<String, usize>>,
+    savers: RwLock<HashMap<(Uuid, String), Arc<dyn ErasedAssetSaver>>>,
+    settings_overrides: RwLock<HashMap<SourcePathId, SettingsOverride>>,
+    reload_causes: Mutex<HashMap<SourcePathId, ReloadCause>>,
+    reloaded_assets: Mutex<Vec<AssetReloaded>>,
+    retention_policies: RwLock<HashMap<Uuid, RetentionPolicy>>,
+    folders: RwLock<HashSet<SourcePathId>>,
+    pending_folders: Mutex<Vec<PendingFolder>>,
+    manifest: RwLock<Option<Option<Arc<AssetManifest>>>>,
+    /// The assets kept by their retention policy without strong handles, least recently used first
+    unused_assets: Mutex<HashMap<Uuid, Vec<HandleId>>>,
     handle_to_path: Arc<RwLock<HashMap<HandleId, AssetPath<'static>>>>,
 }
 
@@ -128,18 +201,57 @@ impl AssetServer {
             server: Arc::new(AssetServerInternal {
                 loaders: Default::default(),
                 extension_to_loader_index: Default::default(),
+                savers: Default::default(),
+                settings_overrides: Default::default(),
+                reload_causes: Default::default(),
+                reloaded_assets: Default::default(),
+                retention_policies: Default::default(),
+                folders: Default::default(),
+                pending_folders: Default::default(),
+                manifest: Default::default(),
+                unused_assets: Default::default(),
                 asset_sources: Default::default(),
                 asset_ref_counter: Default::default(),
                 handle_to_path: Default::default(),
                 asset_lifecycles: Default::default(),
-                asset_io,
+                asset_io: SchemeAssetIo::new(asset_io),
             }),
         }
     }
 
-    /// Returns the associated asset I/O.
+    /// Returns the associated asset I/O, reading the paths without a URI scheme.
+    ///
+    /// The paths starting with a scheme, like `pak://textures/wall.png`, are read from the asset
+    /// source added for that scheme with [`add_source`](AssetServer::add_source) instead.
     pub fn asset_io(&self) -> &dyn AssetIo {
-        &*self.server.asset_io
+        self.server.asset_io.default_io()
+    }
+
+    /// Adds an asset source reading the assets whose path starts with the URI `scheme`, like
+    /// `pak` for `pak://textures/wall.png`.
+    ///
+    /// The paths given to the asset I/O of the source are relative to its root, without the
+    /// scheme, like `textures/wall.png`. The source can read the assets from anywhere, like an
+    /// archive shipped with the game, a web server, or the memory with an [`EmbeddedAssetIo`].
+    /// Adding a source for a scheme replaces the source already added for it, and loading a path
+    /// with a scheme that has no source fails with [`AssetIoError::UnknownScheme`].
+    ///
+    /// [`EmbeddedAssetIo`]: crate::EmbeddedAssetIo
+    pub fn add_source<T: AssetIo>(&self, scheme: impl Into<String>, asset_io: T) {
+        self.server
+            .asset_io
+            .add_source(scheme.into(), Arc::new(asset_io));
+    }
+
+    /// Calls `f` with the default asset I/O, then with the scheme and the asset I/O of each
+    /// asset source.
+    pub(crate) fn for_each_source(&self, f: impl FnMut(Option<&str>, &dyn AssetIo)) {
+        self.server.asset_io.for_each_source(f);
+    }
+
+    /// The asset I/O reading the paths of all the asset sources
+    fn source_io(&self) -> &dyn AssetIo {
+        &self.server.asset_io
     }
 
     pub(crate) fn register_asset_type<T: Asset>(&self) -> Assets<T> {
@@ -175,13 +287,33 @@ impl AssetServer {
         loaders.push(Arc::new(loader));
     }
 
+    /// Adds the provided asset saver to the server.
+    ///
+    /// If `saver` has one or more supported extensions in conflict with savers of the same asset
+    /// type that came before it, it will replace them.
+    pub fn add_saver<T>(&self, saver: T)
+    where
+        T: AssetSaver,
+    {
+        let saver: Arc<dyn ErasedAssetSaver> = Arc::new(saver);
+        let mut savers = self.server.savers.write();
+        for extension in saver.extensions() {
+            savers.insert(
+                (saver.asset_type_uuid(), extension.to_string()),
+                saver.clone(),
+            );
+        }
+    }
+
     /// Gets a strong handle for an asset with the provided id.
+    #[track_caller]
     pub fn get_handle<T: Asset, I: Into<HandleId>>(&self, id: I) -> Handle<T> {
         let sender = self.server.asset_ref_counter.channel.sender.clone();
         Handle::strong(id.into(), sender)
     }
 
     /// Gets an untyped strong handle for an asset with the provided id.
+    #[track_caller]
     pub fn get_handle_untyped<I: Into<HandleId>>(&self, id: I) -> HandleUntyped {
         let sender = self.server.asset_ref_counter.channel.sender.clone();
         HandleUntyped::strong(id.into(), sender)
@@ -200,7 +332,7 @@ impl AssetServer {
             })
     }
 
-    fn get_path_asset_loader<P: AsRef<Path>>(
+    pub(crate) fn get_path_asset_loader<P: AsRef<Path>>(
         &self,
         path: P,
     ) -> Result<Arc<dyn AssetLoader>, AssetServerError> {
@@ -230,6 +362,34 @@ impl AssetServer {
         })
     }
 
+    fn get_path_asset_saver(
+        &self,
+        type_uuid: Uuid,
+        path: &Path,
+    ) -> Result<Arc<dyn ErasedAssetSaver>, AssetServerError> {
+        let file_name = path
+            .file_name()
+            .and_then(|file_name| file_name.to_str())
+            .map(|file_name| file_name.to_lowercase())
+            .ok_or(AssetServerError::MissingAssetSaver {
+                extensions: Vec::new(),
+            })?;
+
+        let savers = self.server.savers.read();
+        let mut exts = Vec::new();
+        let mut ext = file_name.as_str();
+        while let Some(idx) = ext.find('.') {
+            ext = &ext[idx + 1..];
+            exts.push(ext);
+            if let Some(saver) = savers.get(&(type_uuid, ext.to_string())) {
+                return Ok(saver.clone());
+            }
+        }
+        Err(AssetServerError::MissingAssetSaver {
+            extensions: exts.into_iter().map(String::from).collect(),
+        })
+    }
+
     /// Gets the source path of an asset from the provided handle.
     pub fn get_handle_path<H: Into<HandleId>>(&self, handle: H) -> Option<AssetPath<'_>> {
         self.server
@@ -252,6 +412,81 @@ impl AssetServer {
         }
     }
 
+    /// Gets the load state of an asset from the provided handle, and of all the assets it depends
+    /// on, recursively.
+    ///
+    /// The dependencies of an asset are the ones added by the [`AssetLoader`] of its source to any
+    /// of the assets loaded from that source, like the image of a texture atlas. This method only
+    /// returns [`LoadState::Loaded`] once the asset and all its dependencies were loaded, so that
+    /// the asset is fully usable. The dependencies that aren't loading yet are
+    /// [`LoadState::Loading`], as they're queued for loading with the asset.
+    pub fn get_recursive_load_state<H: Into<HandleId>>(&self, handle: H) -> LoadState {
+        let HandleId::AssetPathId(id) = handle.into() else {
+            return LoadState::NotLoaded;
+        };
+        let asset_sources = self.server.asset_sources.read();
+        let Some(source_info) = asset_sources.get(&id.source_path_id()) else {
+            return LoadState::NotLoaded;
+        };
+
+        let mut load_state = LoadState::Loaded;
+        let mut visited = HashSet::default();
+        visited.insert(id.source_path_id());
+        let mut stack = vec![source_info];
+        while let Some(source_info) = stack.pop() {
+            match source_info.load_state {
+                LoadState::Loaded => {}
+                LoadState::Loading => {
+                    load_state = LoadState::Loading;
+                    continue;
+                }
+                state => return state,
+            }
+            let dependencies = source_info
+                .meta
+                .iter()
+                .flat_map(|meta| &meta.assets)
+                .flat_map(|asset_meta| &asset_meta.dependencies);
+            for dependency in dependencies {
+                let source_path_id = dependency.get_id().source_path_id();
+                if !visited.insert(source_path_id) {
+                    continue;
+                }
+                match asset_sources.get(&source_path_id) {
+                    Some(dependency_info) => stack.push(dependency_info),
+                    None => load_state = LoadState::Loading,
+                }
+            }
+        }
+
+        load_state
+    }
+
+    /// Gets the paths of the assets that the asset from the provided handle directly depends on.
+    ///
+    /// See [`get_recursive_load_state`](AssetServer::get_recursive_load_state) for the
+    /// dependencies of an asset. They're only known once its source was loaded.
+    pub fn get_dependencies<H: Into<HandleId>>(&self, handle: H) -> Vec<AssetPath<'static>> {
+        let HandleId::AssetPathId(id) = handle.into() else {
+            return Vec::new();
+        };
+        let asset_sources = self.server.asset_sources.read();
+        let mut dependencies = Vec::new();
+        let metas = asset_sources
+            .get(&id.source_path_id())
+            .and_then(|source_info| source_info.meta.as_ref())
+            .into_iter()
+            .flat_map(|meta| &meta.assets);
+        for asset_meta in metas {
+            for dependency in &asset_meta.dependencies {
+                if !dependencies.contains(dependency) {
+                    dependencies.push(dependency.clone());
+                }
+            }
+        }
+        dependencies
+    }
+
     /// Gets the overall load state of a group of assets from the provided handles.
     ///
     /// This method will only return [`LoadState::Loaded`] if all assets in the
@@ -300,57 +535,146 @@ impl AssetServer {
     ///
     /// [asset loader]: AssetLoader
     #[must_use = "not using the returned strong handle may result in the unexpected release of the asset"]
+    #[track_caller]
     pub fn load<'a, T: Asset, P: Into<AssetPath<'a>>>(&self, path: P) -> Handle<T> {
         self.load_untyped(path).typed()
     }
 
-    async fn load_async(
+    /// Queues an [`Asset`] at the provided relative path for asynchronous loading, with
+    /// [`LoaderSettings`] modified by `settings`.
+    ///
+    /// The settings of the asset are read from its meta file first, then `settings` modifies
+    /// them before they're used by its [`AssetLoader`], if the loader reads settings of type
+    /// `S`. They're kept for the assets of the same path and also modify the settings when the
+    /// asset is reloaded, until other settings are passed for that path.
+    ///
+    /// ```
+    /// # use bevy_asset::*;
+    /// # use bevy_reflect::TypeUuid;
+    /// # use serde::{Deserialize, Serialize};
+    /// # #[derive(TypeUuid)]
+    /// # #[uuid = "e3a5b8d2-6f1c-4b7e-9d24-1c8f0a6b3e57"]
+    /// # struct Image;
+    /// # #[derive(Serialize, Deserialize, Default)]
+    /// # struct ImageLoaderSettings { is_srgb: bool }
+    /// # fn load(asset_server: &AssetServer) {
+    /// let normal_map: Handle<Image> = asset_server.load_with_settings(
+    ///     "textures/normal_map.png",
+    ///     |settings: &mut ImageLoaderSettings| settings.is_srgb = false,
+    /// );
+    /// # }
+    /// ```
+    ///
+    /// Unlike [`load`](AssetServer::load), the asset is loaded again if it was already loaded,
+    /// so that the settings are applied.
+    #[must_use = "not using the returned strong handle may result in the unexpected release of the asset"]
+    #[track_caller]
+    pub fn load_with_settings<'a, T, S, P>(
         &self,
-        asset_path: AssetPath<'_>,
-        force: bool,
-    ) -> Result<AssetPathId, AssetServerError> {
-        let asset_path_id: AssetPathId = asset_path.get_id();
+        path: P,
+        settings: impl Fn(&mut S) + Send + Sync + 'static,
+    ) -> Handle<T>
+    where
+        T: Asset,
+        S: LoaderSettings,
+        P: Into<AssetPath<'a>>,
+    {
+        let path = path.into();
+        let settings_override: SettingsOverride = Arc::new(move |loader_settings: &mut dyn Any| {
+            if let Some(loader_settings) = loader_settings.downcast_mut::<S>() {
+                settings(loader_settings);
+            }
+        });
+        self.server
+            .settings_overrides
+            .write()
+            .insert(path.get_id().source_path_id(), settings_override);
+        let handle_id = self.load_untracked(path, true);
+        self.get_handle(handle_id)
+    }
 
-        // load metadata and update source info. this is done in a scope to ensure we release the
-        // locks before loading
-        let version = {
-            let mut asset_sources = self.server.asset_sources.write();
-            let source_info = match asset_sources.entry(asset_path_id.source_path_id()) {
-                Entry::Occupied(entry) => entry.into_mut(),
-                Entry::Vacant(entry) => entry.insert(SourceInfo {
-                    asset_types: Default::default(),
-                    committed_assets: Default::default(),
-                    load_state: LoadState::NotLoaded,
-                    meta: None,
-                    path: asset_path.path().to_owned(),
-                    version: 0,
-                }),
-            };
+    /// The path of the meta file of the asset at `path`, with the `.meta` extension added
+    fn meta_path(path: &Path) -> PathBuf {
+        let mut meta_path = path.as_os_str().to_owned();
+        meta_path.push(".meta");
+        meta_path.into()
+    }
 
-            // if asset is already loaded or is loading, don't load again
-            if !force
-                && (source_info
-                    .committed_assets
-                    .contains(&asset_path_id.label_id())
-                    || source_info.load_state == LoadState::Loading)
-            {
-                return Ok(asset_path_id);
-            }
+    /// Reads the meta file of the asset at `path`, or returns `None` if it doesn't have one.
+    pub(crate) async fn load_meta(&self, path: &Path) -> Result<Option<Vec<u8>>, AssetIoError> {
+        match self.source_io().load_path(&Self::meta_path(path)).await {
+            Ok(meta) => Ok(Some(meta)),
+            Err(AssetIoError::NotFound(_)) => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
 
-            source_info.load_state = LoadState::Loading;
-            source_info.committed_assets.clear();
-            source_info.version += 1;
-            source_info.meta = None;
-            source_info.version
+    /// The settings passed to [`load_with_settings`](Self::load_with_settings) for the assets of
+    /// `source_path_id`
+    pub(crate) fn get_settings_override(
+        &self,
+        source_path_id: SourcePathId,
+    ) -> Option<SettingsOverride> {
+        self.server
+            .settings_overrides
+            .read()
+            .get(&source_path_id)
+            .cloned()
+    }
+
+    /// Updates the source info of `asset_path` before loading it, and returns the version of the
+    /// source being loaded, or `None` if it doesn't have to be loaded again.
+    fn start_loading(&self, asset_path: &AssetPath, force: bool) -> Option<usize> {
+        let asset_path_id = asset_path.get_id();
+        let mut asset_sources = self.server.asset_sources.write();
+        let source_info = match asset_sources.entry(asset_path_id.source_path_id()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(SourceInfo {
+                asset_types: Default::default(),
+                committed_assets: Default::default(),
+                load_state: LoadState::NotLoaded,
+                meta: None,
+                path: asset_path.path().to_owned(),
+                version: 0,
+            }),
         };
 
-        let set_asset_failed = || {
-            let mut asset_sources = self.server.asset_sources.write();
-            let source_info = asset_sources
-                .get_mut(&asset_path_id.source_path_id())
-                .expect("`AssetSource` should exist at this point.");
-            source_info.load_state = LoadState::Failed;
+        // if asset is already loaded or is loading, don't load again
+        if !force
+            && (source_info
+                .committed_assets
+                .contains(&asset_path_id.label_id())
+                || source_info.load_state == LoadState::Loading)
+        {
+            return None;
+        }
+
+        source_info.load_state = LoadState::Loading;
+        source_info.committed_assets.clear();
+        source_info.version += 1;
+        source_info.meta = None;
+        Some(source_info.version)
+    }
+
+    fn set_load_failed(&self, source_path_id: SourcePathId) {
+        let mut asset_sources = self.server.asset_sources.write();
+        let source_info = asset_sources
+            .get_mut(&source_path_id)
+            .expect("`AssetSource` should exist at this point.");
+        source_info.load_state = LoadState::Failed;
+        self.server.reload_causes.lock().remove(&source_path_id);
+    }
+
+    async fn load_async(
+        &self,
+        asset_path: AssetPath<'_>,
+        force: bool,
+    ) -> Result<AssetPathId, AssetServerError> {
+        let asset_path_id: AssetPathId = asset_path.get_id();
+        let Some(version) = self.start_loading(&asset_path, force) else {
+            return Ok(asset_path_id);
         };
+        let set_asset_failed = || self.set_load_failed(asset_path_id.source_path_id());
 
         // get the according asset loader
         let asset_loader = match self.get_path_asset_loader(asset_path.path()) {
@@ -362,7 +686,7 @@ impl AssetServer {
         };
 
         // load the asset bytes
-        let bytes = match self.asset_io().load_path(asset_path.path()).await {
+        let bytes = match self.source_io().load_path(asset_path.path()).await {
             Ok(bytes) => bytes,
             Err(err) => {
                 set_asset_failed();
@@ -370,12 +694,25 @@ impl AssetServer {
             }
         };
 
+        // load the meta file of the asset, if it has one
+        let meta = match self.load_meta(asset_path.path()).await {
+            Ok(meta) => meta,
+            Err(err) => {
+                set_asset_failed();
+                return Err(AssetServerError::AssetIoError(err));
+            }
+        };
+        let settings_override = self.get_settings_override(asset_path_id.source_path_id());
+
         // load the asset source using the corresponding AssetLoader
         let mut load_context = LoadContext::new(
             asset_path.path(),
             &self.server.asset_ref_counter.channel,
-            self.asset_io(),
+            self.source_io(),
+            self,
             version,
+            meta.as_deref(),
+            settings_override,
         );
 
         if let Err(err) = asset_loader
@@ -409,6 +746,7 @@ impl AssetServer {
 
         source_info.meta = Some(SourceMeta {
             assets: load_context.get_asset_metas(),
+            direct_dependencies: load_context.direct_dependencies.clone(),
         });
 
         // load asset dependencies and prepare asset type hashmap
@@ -420,18 +758,114 @@ impl AssetServer {
                 self.load_untracked(dependency.clone(), false);
             }
         }
+        for dependency in &load_context.dependencies {
+            self.load_untracked(dependency.clone(), false);
+        }
 
-        self.asset_io()
+        self.source_io()
             .watch_path_for_changes(asset_path.path())
             .unwrap();
+        if meta.is_some() {
+            self.source_io()
+                .watch_path_for_changes(&Self::meta_path(asset_path.path()))
+                .unwrap();
+        }
+        for direct_dependency in &load_context.direct_dependencies {
+            self.source_io()
+                .watch_path_for_changes(direct_dependency.path())
+                .unwrap();
+        }
         self.create_assets_in_load_context(&mut load_context);
+        drop(asset_sources);
+
+        let reload_cause = self
+            .server
+            .reload_causes
+            .lock()
+            .remove(&asset_path_id.source_path_id());
+        if let Some(cause) = reload_cause {
+            self.server.reloaded_assets.lock().push(AssetReloaded {
+                path: AssetPath::new(asset_path.path().to_owned(), None),
+                cause,
+            });
+        }
         Ok(asset_path_id)
     }
 
+    /// Saves the asset of the provided handle to the provided path, relative to the asset folder
+    /// like the paths of [`load`](AssetServer::load).
+    ///
+    /// The asset is written right away by the [`AssetSaver`] added for its type and the extension
+    /// of the path, then saved in the background through the [`AssetIo`] of the path. Failing to
+    /// save it is only logged, like failing to load an asset. When watching for changes, the
+    /// assets loaded from the path are reloaded once it's saved.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the asset doesn't exist in `assets`, when no saver was added for its type and
+    /// the extension of the path, or when its saver fails.
+    pub fn save<T: Asset, P: AsRef<Path>>(
+        &self,
+        assets: &Assets<T>,
+        handle: &Handle<T>,
+        path: P,
+    ) -> Result<(), AssetServerError> {
+        let path = path.as_ref().to_owned();
+        let saver = self.get_path_asset_saver(T::TYPE_UUID, &path)?;
+        let asset = assets.get(handle).ok_or(AssetServerError::MissingAsset)?;
+        let bytes = saver
+            .save(asset)
+            .map_err(AssetServerError::AssetSaverError)?;
+
+        let server = self.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                if let Err(err) = server.source_io().save_path(&path, &bytes).await {
+                    warn!("{}", err);
+                }
+            })
+            .detach();
+        Ok(())
+    }
+
+    /// Saves `settings` to the meta file of the asset at the provided path, so that they're used
+    /// the next times the asset is loaded.
+    ///
+    /// The meta file is written in the background like the assets of [`save`](AssetServer::save).
+    /// When watching for changes, the asset is reloaded with the settings once they're saved.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the settings can't be serialized.
+    pub fn save_settings<S: LoaderSettings, P: AsRef<Path>>(
+        &self,
+        path: P,
+        settings: &S,
+    ) -> Result<(), AssetServerError> {
+        let meta_path = Self::meta_path(path.as_ref());
+        let meta = ron::ser::to_string_pretty(settings, Default::default())
+            .map_err(|err| AssetServerError::AssetSaverError(err.into()))?;
+
+        let server = self.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                if let Err(err) = server
+                    .source_io()
+                    .save_path(&meta_path, meta.as_bytes())
+                    .await
+                {
+                    warn!("{}", err);
+                }
+            })
+            .detach();
+        Ok(())
+    }
+
     /// Queues the [`Asset`] at the provided path for loading and returns an untyped handle.
     ///
     /// See [`load`](AssetServer::load).
     #[must_use = "not using the returned strong handle may result in the unexpected release of the asset"]
+    #[track_caller]
     pub fn load_untyped<'a, P: Into<AssetPath<'a>>>(&self, path: P) -> HandleUntyped {
         let handle_id = self.load_untracked(path.into(), false);
         self.get_handle_untyped(handle_id)
@@ -439,18 +873,93 @@ impl AssetServer {
 
     /// Force an [`Asset`] to be reloaded.
     ///
+    /// The assets depending on it, directly or through other dependencies, are reloaded too, like
+    /// the materials and the scenes using a texture. An [`AssetReloaded`] event is sent for each
+    /// reloaded path once its assets are updated.
+    ///
     /// This is useful for custom hot-reloading or for supporting `watch_for_changes`
     /// in custom [`AssetIo`] implementations.
     pub fn reload_asset<'a, P: Into<AssetPath<'a>>>(&self, path: P) {
-        self.load_untracked(path.into(), true);
+        let path: AssetPath = path.into();
+        let source_path = AssetPath::new(path.path().to_owned(), None);
+        let dependents = self.get_dependents(source_path.get_id().source_path_id());
+
+        let mut reload_causes = self.server.reload_causes.lock();
+        reload_causes.insert(source_path.get_id().source_path_id(), ReloadCause::Changed);
+        for dependent in &dependents {
+            reload_causes.insert(
+                dependent.get_id().source_path_id(),
+                ReloadCause::Dependency(source_path.clone()),
+            );
+        }
+        drop(reload_causes);
+
+        // the sources only loaded directly by the loaders of their dependents aren't assets
+        let source_is_loaded = self
+            .server
+            .asset_sources
+            .read()
+            .contains_key(&source_path.get_id().source_path_id());
+        if source_is_loaded || dependents.is_empty() {
+            self.load_untracked(source_path, true);
+        } else {
+            self.server
+                .reload_causes
+                .lock()
+                .remove(&source_path.get_id().source_path_id());
+        }
+        for dependent in dependents {
+            self.load_untracked(dependent, true);
+        }
+    }
+
+    /// The paths of the loaded assets depending on the assets of `source_path_id`, directly or
+    /// through other dependencies
+    fn get_dependents(&self, source_path_id: SourcePathId) -> Vec<AssetPath<'static>> {
+        let asset_sources = self.server.asset_sources.read();
+        let mut dependents = Vec::new();
+        let mut visited = HashSet::default();
+        visited.insert(source_path_id);
+        let mut queue = vec![source_path_id];
+        while let Some(dependency_id) = queue.pop() {
+            for (id, source_info) in asset_sources.iter() {
+                let Some(meta) = &source_info.meta else {
+                    continue;
+                };
+                if visited.contains(id)
+                    || !meta
+                        .assets
+                        .iter()
+                        .flat_map(|asset_meta| &asset_meta.dependencies)
+                        .chain(&meta.direct_dependencies)
+                        .any(|dependency| dependency.get_id().source_path_id() == dependency_id)
+                {
+                    continue;
+                }
+                visited.insert(*id);
+                queue.push(*id);
+                dependents.push(AssetPath::new(source_info.path.clone(), None));
+            }
+        }
+        dependents
     }
 
     pub(crate) fn load_untracked(&self, asset_path: AssetPath<'_>, force: bool) -> HandleId {
         let server = self.clone();
         let owned_path = asset_path.to_owned();
+        let is_folder = self
+            .server
+            .folders
+            .read()
+            .contains(&asset_path.get_id().source_path_id());
         IoTaskPool::get()
             .spawn(async move {
-                if let Err(err) = server.load_async(owned_path, force).await {
+                let result = if is_folder {
+                    server.load_folder_async(owned_path, force).await
+                } else {
+                    server.load_async(owned_path, force).await
+                };
+                if let Err(err) = result {
                     warn!("{}", err);
                 }
             })
@@ -466,44 +975,333 @@ impl AssetServer {
         asset_path.into()
     }
 
-    /// Loads assets from the specified folder recursively.
+    /// Loads the assets of the specified folder and of its subfolders, and returns a handle to
+    /// the [`LoadedFolder`] holding their handles.
     ///
-    /// # Errors
+    /// The path can end with a glob pattern the paths of the assets have to match, relative to
+    /// the folder, like `textures/**/*.png` for the PNG images of the `textures` folder and of its
+    /// subfolders. Only the files with an [`AssetLoader`] are loaded.
     ///
-    /// - If the provided path is not a directory, it will fail with
-    /// [`AssetServerError::AssetFolderNotADirectory`].
-    /// - If something unexpected happened while loading an asset, other
-    /// [`AssetServerError`]s may be returned.
-    #[must_use = "not using the returned strong handles may result in the unexpected release of the assets"]
-    pub fn load_folder<P: AsRef<Path>>(
+    /// The folder is listed in the background, through the [`AssetManifest`] of the asset folder
+    /// when its directories can't be listed, like in WASM. The [`LoadedFolder`] is added to its
+    /// [`Assets`] once all the assets of the folder finished loading, and is loaded again when
+    /// files are added to or removed from the folder while watching for changes.
+    ///
+    /// The folder fails to load if it isn't a directory and isn't in the manifest.
+    #[must_use = "not using the returned strong handle may result in the unexpected release of the assets"]
+    #[track_caller]
+    pub fn load_folder<P: AsRef<Path>>(&self, path: P) -> Handle<LoadedFolder> {
+        let path = AssetPath::new(path.as_ref().to_owned(), None);
+        self.server
+            .folders
+            .write()
+            .insert(path.get_id().source_path_id());
+        let handle_id = self.load_untracked(path, false);
+        self.get_handle(handle_id)
+    }
+
+    async fn load_folder_async(
         &self,
-        path: P,
-    ) -> Result<Vec<HandleUntyped>, AssetServerError> {
-        let path = path.as_ref();
-        if !self.asset_io().is_dir(path) {
+        asset_path: AssetPath<'_>,
+        force: bool,
+    ) -> Result<AssetPathId, AssetServerError> {
+        let asset_path_id = asset_path.get_id();
+        let Some(version) = self.start_loading(&asset_path, force) else {
+            return Ok(asset_path_id);
+        };
+        let files = match self.list_folder(asset_path.path()).await {
+            Ok(files) => files,
+            Err(err) => {
+                self.set_load_failed(asset_path_id.source_path_id());
+                return Err(err);
+            }
+        };
+        let handles: Vec<HandleUntyped> = files
+            .iter()
+            .map(|file| self.load_untyped(file.as_path()))
+            .collect();
+
+        let mut asset_sources = self.server.asset_sources.write();
+        let source_info = asset_sources
+            .get_mut(&asset_path_id.source_path_id())
+            .expect("`AssetSource` should exist at this point.");
+        if version != source_info.version {
+            return Ok(asset_path_id);
+        }
+        source_info.meta = Some(SourceMeta {
+            assets: vec![AssetMeta {
+                label: None,
+                dependencies: files.into_iter().map(AssetPath::from).collect(),
+                type_uuid: LoadedFolder::TYPE_UUID,
+            }],
+            direct_dependencies: Vec::new(),
+        });
+        source_info.asset_types.clear();
+        source_info
+            .asset_types
+            .insert(asset_path_id.label_id(), LoadedFolder::TYPE_UUID);
+        self.server.pending_folders.lock().push(PendingFolder {
+            id: asset_path_id,
+            version,
+            handles,
+        });
+        Ok(asset_path_id)
+    }
+
+    /// Lists the files with an asset loader of the folder at `path` and of its subfolders, which
+    /// match the glob pattern at the end of the path.
+    async fn list_folder(&self, path: &Path) -> Result<Vec<PathBuf>, AssetServerError> {
+        let (folder, pattern) = split_folder_glob(path);
+        let mut files = Vec::new();
+        if self.source_io().is_dir(&folder) {
+            self.read_folder(&folder, &mut files)?;
+            self.source_io().watch_path_for_changes(&folder)?;
+        } else if let Some(manifest) = self.load_manifest(&folder).await? {
+            files.extend(manifest.files_in(&folder).map(Path::to_owned));
+        } else {
             return Err(AssetServerError::AssetFolderNotADirectory(
-                path.to_str().unwrap().to_string(),
+                folder.to_string_lossy().to_string(),
             ));
         }
 
-        let mut handles = Vec::new();
-        for child_path in self.asset_io().read_directory(path.as_ref())? {
-            if self.asset_io().is_dir(&child_path) {
-                handles.extend(self.load_folder(&child_path)?);
+        files.retain(|file| {
+            if matches!(file.extension(), Some(extension) if extension == "meta")
+                || self.get_path_asset_loader(file).is_err()
+            {
+                return false;
+            }
+            let Some(pattern) = &pattern else {
+                return true;
+            };
+            let relative_path = file.strip_prefix(&folder).unwrap_or(file);
+            glob_matches(pattern, &relative_path.to_string_lossy().replace('\\', "/"))
+        });
+        Ok(files)
+    }
+
+    fn read_folder(&self, folder: &Path, files: &mut Vec<PathBuf>) -> Result<(), AssetIoError> {
+        for child_path in self.source_io().read_directory(folder)? {
+            if self.source_io().is_dir(&child_path) {
+                self.read_folder(&child_path, files)?;
             } else {
-                if self.get_path_asset_loader(&child_path).is_err() {
+                files.push(child_path);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reads the [`AssetManifest`] of the asset folder once, or returns `None` if there is no
+    /// manifest or if `folder` is in another asset source.
+    async fn load_manifest(
+        &self,
+        folder: &Path,
+    ) -> Result<Option<Arc<AssetManifest>>, AssetServerError> {
+        if split_asset_scheme(folder).is_some() {
+            return Ok(None);
+        }
+        if let Some(manifest) = &*self.server.manifest.read() {
+            return Ok(manifest.clone());
+        }
+        let manifest = match self
+            .server
+            .asset_io
+            .default_io()
+            .load_path(Path::new(AssetManifest::PATH))
+            .await
+        {
+            Ok(bytes) => {
+                Some(Arc::new(ron::de::from_bytes(&bytes).map_err(|err| {
+                    AssetServerError::AssetLoaderError(err.into())
+                })?))
+            }
+            Err(AssetIoError::NotFound(_)) => None,
+            Err(err) => return Err(err.into()),
+        };
+        *self.server.manifest.write() = Some(manifest.clone());
+        Ok(manifest)
+    }
+
+    /// Adds the [`LoadedFolder`]s whose assets all finished loading to their [`Assets`].
+    pub(crate) fn create_loaded_folders(&self) {
+        let mut pending_folders = self.server.pending_folders.lock();
+        if pending_folders.is_empty() {
+            return;
+        }
+        let asset_sources = self.server.asset_sources.read();
+        let asset_lifecycles = self.server.asset_lifecycles.read();
+        for folder in std::mem::take(&mut *pending_folders) {
+            match asset_sources.get(&folder.id.source_path_id()) {
+                Some(source_info) if source_info.version == folder.version => {}
+                // a newer version of the folder is being loaded
+                _ => continue,
+            }
+
+            let mut handles_by_type: HashMap<Uuid, Vec<HandleUntyped>> = HashMap::default();
+            let mut finished = true;
+            for handle in &folder.handles {
+                let HandleId::AssetPathId(id) = handle.id() else {
                     continue;
+                };
+                match asset_sources.get(&id.source_path_id()) {
+                    Some(source_info) if source_info.load_state == LoadState::Loaded => {
+                        if let Some(type_uuid) = source_info.get_asset_type(id.label_id()) {
+                            handles_by_type
+                                .entry(type_uuid)
+                                .or_default()
+                                .push(handle.clone());
+                        }
+                    }
+                    Some(source_info)
+                        if matches!(
+                            source_info.load_state,
+                            LoadState::Failed | LoadState::Unloaded
+                        ) => {}
+                    _ => finished = false,
                 }
-                let handle =
-                    self.load_untyped(child_path.to_str().expect("Path should be a valid string."));
-                handles.push(handle);
+            }
+            if !finished {
+                pending_folders.push(folder);
+                continue;
+            }
+
+            if let Some(asset_lifecycle) = asset_lifecycles.get(&LoadedFolder::TYPE_UUID) {
+                asset_lifecycle.create_asset(
+                    folder.id.into(),
+                    Box::new(LoadedFolder {
+                        handles: folder.handles,
+                        handles_by_type,
+                    }),
+                    folder.version,
+                );
+            }
+            let reload_cause = self
+                .server
+                .reload_causes
+                .lock()
+                .remove(&folder.id.source_path_id());
+            if let Some(cause) = reload_cause {
+                self.server.reloaded_assets.lock().push(AssetReloaded {
+                    path: AssetPath::new(
+                        asset_sources[&folder.id.source_path_id()].path.clone(),
+                        None,
+                    ),
+                    cause,
+                });
+            }
+        }
+    }
+
+    /// Reloads the asset at `path` when it changed, if it's loaded or if assets depend on it.
+    pub fn reload_changed_path(&self, path: &Path) {
+        let source_path_id = AssetPath::from(path).get_id().source_path_id();
+        let is_loaded = self
+            .server
+            .asset_sources
+            .read()
+            .contains_key(&source_path_id);
+        if is_loaded || !self.get_dependents(source_path_id).is_empty() {
+            self.reload_asset(path);
+        }
+    }
+
+    /// Reloads the loaded folders containing `path`, when a file is added or removed.
+    pub fn reload_folders_containing(&self, path: &Path) {
+        let folder_paths: Vec<PathBuf> = {
+            let folders = self.server.folders.read();
+            let asset_sources = self.server.asset_sources.read();
+            folders
+                .iter()
+                .filter_map(|id| asset_sources.get(id))
+                .map(|source_info| source_info.path.clone())
+                .collect()
+        };
+        for folder_path in folder_paths {
+            if path.starts_with(split_folder_glob(&folder_path).0) {
+                self.reload_asset(folder_path.as_path());
             }
         }
+    }
+
+    /// Sets the [`RetentionPolicy`] of the assets of type `T`, deciding when they're freed once they
+    /// have no strong handles.
+    ///
+    /// The unused assets kept by the previous policy are freed if the new policy is
+    /// [`RetentionPolicy::KeepUntilUnused`].
+    pub fn set_retention_policy<T: Asset>(&self, policy: RetentionPolicy) {
+        self.server
+            .retention_policies
+            .write()
+            .insert(T::TYPE_UUID, policy);
+        if policy == RetentionPolicy::KeepUntilUnused {
+            if let Some(unused_assets) = self.server.unused_assets.lock().remove(&T::TYPE_UUID) {
+                self.server
+                    .asset_ref_counter
+                    .mark_unused_assets
+                    .lock()
+                    .extend(unused_assets);
+            }
+        }
+    }
+
+    /// The number of assets of the type of `type_uuid` kept by their [`RetentionPolicy`] without
+    /// strong handles
+    pub fn unused_asset_count(&self, type_uuid: Uuid) -> usize {
+        self.server
+            .unused_assets
+            .lock()
+            .get(&type_uuid)
+            .map_or(0, Vec::len)
+    }
+
+    /// Starts tracking the code locations that create strong handles, reported by
+    /// [`handle_report`](Self::handle_report), to find what keeps assets loaded.
+    ///
+    /// The strong handles created before the tracking was enabled aren't reported. The location
+    /// of a handle is the call creating it, like a call to [`load`](Self::load), to
+    /// [`Assets::add`] or to [`Clone::clone`], so the handles created by a function called with
+    /// different handles all come from the same location.
+    pub fn track_handle_holders(&self) {
+        // counts the handles created so far without tracking them
+        self.mark_unused_assets();
+        self.server
+            .asset_ref_counter
+            .handle_holders
+            .lock()
+            .get_or_insert_with(HandleHolders::default);
+    }
 
-        Ok(handles)
+    /// Reports the assets kept alive by strong handles, with the locations of the code that
+    /// created them when the holders are tracked with
+    /// [`track_handle_holders`](Self::track_handle_holders).
+    ///
+    /// The handles are counted each frame by [`free_unused_assets_system`], so the report
+    /// doesn't include the handles created or dropped since.
+    pub fn handle_report(&self) -> HandleReport {
+        let ref_counts = self.server.asset_ref_counter.ref_counts.read();
+        let handle_holders = self.server.asset_ref_counter.handle_holders.lock();
+        let mut assets: Vec<AssetHandles> = ref_counts
+            .iter()
+            .filter(|(_, strong_handles)| **strong_handles > 0)
+            .map(|(id, strong_handles)| AssetHandles {
+                id: *id,
+                path: self.get_handle_path(*id).map(|path| path.to_owned()),
+                type_name: handle_holders
+                    .as_ref()
+                    .and_then(|holders| holders.type_name(id)),
+                strong_handles: *strong_handles,
+                holders: handle_holders
+                    .as_ref()
+                    .map(|holders| holders.holders(id))
+                    .unwrap_or_default(),
+            })
+            .collect();
+        assets.sort_by_key(|asset| std::cmp::Reverse(asset.strong_handles));
+        HandleReport { assets }
     }
 
     /// Frees unused assets, unloading them from memory.
+    ///
+    /// The assets whose [`RetentionPolicy`] keeps them without strong handles aren't freed.
     pub fn free_unused_assets(&self) {
         let mut potential_frees = self.server.asset_ref_counter.mark_unused_assets.lock();
 
@@ -511,6 +1309,7 @@ impl AssetServer {
             let ref_counts = self.server.asset_ref_counter.ref_counts.read();
             let asset_sources = self.server.asset_sources.read();
             let asset_lifecycles = self.server.asset_lifecycles.read();
+            let retention_policies = self.server.retention_policies.read();
             for potential_free in potential_frees.drain(..) {
                 if let Some(&0) = ref_counts.get(&potential_free) {
                     let type_uuid = match potential_free {
@@ -520,9 +1319,21 @@ impl AssetServer {
                             .and_then(|source_info| source_info.get_asset_type(id.label_id())),
                     };
 
-                    if let Some(type_uuid) = type_uuid {
-                        if let Some(asset_lifecycle) = asset_lifecycles.get(&type_uuid) {
-                            asset_lifecycle.free_asset(potential_free);
+                    let Some(type_uuid) = type_uuid else {
+                        continue;
+                    };
+                    match retention_policies.get(&type_uuid) {
+                        None | Some(RetentionPolicy::KeepUntilUnused) => {
+                            if let Some(asset_lifecycle) = asset_lifecycles.get(&type_uuid) {
+                                asset_lifecycle.free_asset(potential_free);
+                            }
+                        }
+                        Some(RetentionPolicy::KeepForever | RetentionPolicy::Budget { .. }) => {
+                            // the asset becomes the most recently used of the unused assets
+                            let mut unused_assets = self.server.unused_assets.lock();
+                            let unused_assets = unused_assets.entry(type_uuid).or_default();
+                            unused_assets.retain(|id| *id != potential_free);
+                            unused_assets.push(potential_free);
                         }
                     }
                 }
@@ -534,6 +1345,7 @@ impl AssetServer {
     pub fn mark_unused_assets(&self) {
         let receiver = &self.server.asset_ref_counter.channel.receiver;
         let mut ref_counts = self.server.asset_ref_counter.ref_counts.write();
+        let mut handle_holders = self.server.asset_ref_counter.handle_holders.lock();
         let mut potential_frees = None;
         loop {
             let ref_change = match receiver.try_recv() {
@@ -542,8 +1354,16 @@ impl AssetServer {
                 Err(TryRecvError::Disconnected) => panic!("RefChange channel disconnected."),
             };
             match ref_change {
-                RefChange::Increment(handle_id) => *ref_counts.entry(handle_id).or_insert(0) += 1,
-                RefChange::Decrement(handle_id) => {
+                RefChange::Increment(handle_id, location, type_name) => {
+                    *ref_counts.entry(handle_id).or_insert(0) += 1;
+                    if let Some(handle_holders) = handle_holders.as_mut() {
+                        handle_holders.increment(handle_id, location, type_name);
+                    }
+                }
+                RefChange::Decrement(handle_id, location) => {
+                    if let Some(handle_holders) = handle_holders.as_mut() {
+                        handle_holders.decrement(handle_id, location);
+                    }
                     let entry = ref_counts.entry(handle_id).or_insert(0);
                     *entry -= 1;
                     if *entry == 0 {
@@ -590,6 +1410,7 @@ impl AssetServer {
         let channel = asset_lifecycle
             .downcast_ref::<AssetLifecycleChannel<T>>()
             .unwrap();
+        self.free_over_budget_assets(&assets, asset_lifecycle.as_ref());
 
         loop {
             match channel.receiver.try_recv() {
@@ -609,6 +1430,9 @@ impl AssetServer {
                     }
 
                     assets.set_untracked(result.id, *result.asset);
+                    if let HandleId::AssetPathId(_) = result.id {
+                        assets.wait_for_dependencies(result.id);
+                    }
                 }
                 Ok(AssetLifecycleEvent::Free(handle_id)) => {
                     if let HandleId::AssetPathId(id) = handle_id {
@@ -627,6 +1451,60 @@ impl AssetServer {
                 Err(TryRecvError::Disconnected) => panic!("AssetChannel disconnected."),
             }
         }
+        drop(asset_sources_guard);
+
+        // only borrow the assets mutably once some of them stopped waiting for their dependencies
+        let load_states: Vec<LoadState> = assets
+            .waiting_for_dependencies()
+            .iter()
+            .map(|id| self.get_recursive_load_state(*id))
+            .collect();
+        if load_states.iter().any(|state| *state != LoadState::Loading) {
+            assets.finish_waiting_for_dependencies(&load_states);
+        }
+    }
+
+    /// Forgets the unused assets of type `T` that are used again or were removed, and frees the
+    /// least recently used ones while they're over their [`RetentionPolicy::Budget`].
+    fn free_over_budget_assets<T: Asset>(
+        &self,
+        assets: &Assets<T>,
+        asset_lifecycle: &dyn AssetLifecycle,
+    ) {
+        let mut unused_assets = self.server.unused_assets.lock();
+        let Some(unused_assets) = unused_assets.get_mut(&T::TYPE_UUID) else {
+            return;
+        };
+        let ref_counts = self.server.asset_ref_counter.ref_counts.read();
+        let mut sizes = Vec::with_capacity(unused_assets.len());
+        unused_assets.retain(|id| {
+            let size = assets.estimated_size_of(*id);
+            let unused = matches!(ref_counts.get(id), None | Some(0));
+            if let (Some(size), true) = (size, unused) {
+                sizes.push(size);
+                true
+            } else {
+                false
+            }
+        });
+
+        let Some(RetentionPolicy::Budget { max_bytes }) = self
+            .server
+            .retention_policies
+            .read()
+            .get(&T::TYPE_UUID)
+            .copied()
+        else {
+            return;
+        };
+        let mut total_size: usize = sizes.iter().sum();
+        let mut freed = 0;
+        while total_size > max_bytes {
+            asset_lifecycle.free_asset(unused_assets[freed]);
+            total_size -= sizes[freed];
+            freed += 1;
+        }
+        unused_assets.drain(..freed);
     }
 }
 
@@ -635,6 +1513,22 @@ fn free_unused_assets_system_impl(asset_server: &AssetServer) {
     asset_server.mark_unused_assets();
 }
 
+/// A system adding the [`LoadedFolder`]s whose assets finished loading to their [`Assets`].
+pub fn create_loaded_folders_system(asset_server: Res<AssetServer>) {
+    asset_server.create_loaded_folders();
+}
+
+/// A system sending the [`AssetReloaded`] events of the assets reloaded since it last ran.
+pub fn asset_reloaded_system(
+    asset_server: Res<AssetServer>,
+    mut reloaded_events: EventWriter<AssetReloaded>,
+) {
+    let mut reloaded_assets = asset_server.server.reloaded_assets.lock();
+    if !reloaded_assets.is_empty() {
+        reloaded_events.send_batch(reloaded_assets.drain(..));
+    }
+}
+
 /// A system for freeing assets that have no active handles.
 pub fn free_unused_assets_system(asset_server: Res<AssetServer>) {
     free_unused_assets_system_impl(&asset_server);
@@ -643,7 +1537,7 @@ pub fn free_unused_assets_system(asset_server: Res<AssetServer>) {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::{loader::LoadedAsset, update_asset_storage_system};
+    use crate::{loader::LoadedAsset, update_asset_storage_system, AssetEvent};
     use bevy_app::App;
     use bevy_ecs::prelude::*;
     use bevy_reflect::TypeUuid;
@@ -684,6 +1578,28 @@ mod test {
         }
     }
 
+    /// Loads a `PngAsset` depending on the asset at the path written in the file
+    struct FakeDependentLoader;
+    impl AssetLoader for FakeDependentLoader {
+        fn load<'a>(
+            &'a self,
+            bytes: &'a [u8],
+            ctx: &'a mut LoadContext,
+        ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+            Box::pin(async move {
+                let dependency = std::str::from_utf8(bytes)?.to_string();
+                ctx.set_default_asset(
+                    LoadedAsset::new(PngAsset).with_dependency(dependency.into()),
+                );
+                Ok(())
+            })
+        }
+
+        fn extensions(&self) -> &[&str] {
+            &["dep"]
+        }
+    }
+
     struct FakeMultipleDotLoader;
     impl AssetLoader for FakeMultipleDotLoader {
         fn load<'a>(
@@ -917,6 +1833,490 @@ mod test {
         assert!(get_asset(&handle, &app.world).is_some());
     }
 
+    #[test]
+    fn test_retention_policies() {
+        let dir = create_dir_and_file("a.png");
+        std::fs::write(dir.path().join("b.png"), "").unwrap();
+        std::fs::write(dir.path().join("c.png"), "").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        let mut assets = asset_server.register_asset_type::<PngAsset>();
+        assets.set_size_estimator(|_| 10);
+        asset_server.set_retention_policy::<PngAsset>(RetentionPolicy::Budget { max_bytes: 15 });
+
+        #[derive(SystemSet, Clone, Hash, Debug, PartialEq, Eq)]
+        struct FreeUnusedAssets;
+        let mut app = App::new();
+        app.insert_resource(assets);
+        app.insert_resource(asset_server.clone());
+        app.init_resource::<crate::AssetStats>();
+        app.add_system(free_unused_assets_system.in_set(FreeUnusedAssets));
+        app.add_system(update_asset_storage_system::<PngAsset>.after(FreeUnusedAssets));
+        app.add_system(
+            crate::asset_stats_system::<PngAsset>.after(update_asset_storage_system::<PngAsset>),
+        );
+
+        let handles: Vec<Handle<PngAsset>> = ["a.png", "b.png", "c.png"]
+            .into_iter()
+            .map(|path| {
+                let id = futures_lite::future::block_on(asset_server.load_async(path.into(), true));
+                asset_server.get_handle(id.unwrap())
+            })
+            .collect();
+        let weak_handles: Vec<_> = handles.iter().map(Handle::clone_weak).collect();
+        app.update();
+        let stats = app.world.resource::<crate::AssetStats>();
+        assert_eq!(stats.get::<PngAsset>().unwrap().count, 3);
+        assert_eq!(stats.total_estimated_bytes(), 30);
+
+        // the least recently used assets are freed until the unused assets fit in the budget
+        drop(handles);
+        app.update();
+        app.update();
+        let assets = app.world.resource::<Assets<PngAsset>>();
+        assert!(!assets.contains(&weak_handles[0]));
+        assert!(!assets.contains(&weak_handles[1]));
+        assert!(assets.contains(&weak_handles[2]));
+        assert_eq!(asset_server.unused_asset_count(PngAsset::TYPE_UUID), 1);
+        assert_eq!(
+            asset_server.get_load_state(&weak_handles[2]),
+            LoadState::Loaded
+        );
+
+        // the unused assets kept are freed when they don't have to be kept anymore
+        asset_server.set_retention_policy::<PngAsset>(RetentionPolicy::KeepUntilUnused);
+        app.update();
+        assert!(app.world.resource::<Assets<PngAsset>>().is_empty());
+        let stats = app.world.resource::<crate::AssetStats>();
+        assert_eq!(stats.get::<PngAsset>().unwrap().count, 0);
+        assert_eq!(stats.total_unused(), 0);
+    }
+
+    #[test]
+    fn test_recursive_load_state() {
+        let dir = create_dir_and_file("fake.png");
+        std::fs::write(dir.path().join("fake.dep"), "fake.png").unwrap();
+        std::fs::write(dir.path().join("missing.dep"), "missing.png").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        asset_server.add_loader(FakeDependentLoader);
+        let assets = asset_server.register_asset_type::<PngAsset>();
+
+        let mut app = App::new();
+        app.insert_resource(assets);
+        app.insert_resource(asset_server.clone());
+        app.add_event::<AssetEvent<PngAsset>>();
+        app.add_system(update_asset_storage_system::<PngAsset>);
+        app.add_system(
+            Assets::<PngAsset>::asset_event_system.after(update_asset_storage_system::<PngAsset>),
+        );
+
+        // load the dependencies first, so that they aren't loaded again in the background
+        let load = |path: &str| {
+            let id = futures_lite::future::block_on(asset_server.load_async(path.into(), true));
+            asset_server.get_handle_untyped(id.unwrap_or_else(|_| AssetPath::from(path).get_id()))
+        };
+        let image = load("fake.png");
+        let _missing = load("missing.png");
+        let dependent = load("fake.dep");
+        let missing_dependent = load("missing.dep");
+
+        assert_eq!(
+            asset_server.get_dependencies(&dependent),
+            vec![AssetPath::from("fake.png")]
+        );
+        assert!(asset_server.get_dependencies(&image).is_empty());
+        assert_eq!(
+            asset_server.get_recursive_load_state(&dependent),
+            LoadState::Loading
+        );
+
+        app.update();
+        assert_eq!(
+            asset_server.get_recursive_load_state(&dependent),
+            LoadState::Loaded
+        );
+        assert_eq!(
+            asset_server.get_load_state(&missing_dependent),
+            LoadState::Loaded
+        );
+        assert_eq!(
+            asset_server.get_recursive_load_state(&missing_dependent),
+            LoadState::Failed
+        );
+
+        let events = app.world.resource::<Events<AssetEvent<PngAsset>>>();
+        let loaded: Vec<_> = events
+            .iter_current_update_events()
+            .filter_map(|event| match event {
+                AssetEvent::LoadedWithDependencies { handle } => Some(handle.id()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(loaded.len(), 2);
+        assert!(loaded.contains(&image.id()));
+        assert!(loaded.contains(&dependent.id()));
+    }
+
+    #[test]
+    fn test_reload_dependents() {
+        let dir = create_dir_and_file("texture.png");
+        std::fs::write(dir.path().join("material.dep"), "texture.png").unwrap();
+        std::fs::write(dir.path().join("scene.dep"), "material.dep").unwrap();
+        std::fs::write(dir.path().join("other.png"), "").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        asset_server.add_loader(FakeDependentLoader);
+        let _assets = asset_server.register_asset_type::<PngAsset>();
+
+        for path in ["texture.png", "material.dep", "scene.dep", "other.png"] {
+            futures_lite::future::block_on(asset_server.load_async(path.into(), false)).unwrap();
+        }
+        let mut dependents =
+            asset_server.get_dependents(AssetPath::from("texture.png").get_id().source_path_id());
+        dependents.sort_by(|a, b| a.path().cmp(b.path()));
+        assert_eq!(
+            dependents,
+            vec![
+                AssetPath::from("material.dep"),
+                AssetPath::from("scene.dep")
+            ]
+        );
+
+        let mut app = App::new();
+        app.insert_resource(asset_server.clone());
+        app.add_event::<AssetReloaded>();
+        app.add_system(asset_reloaded_system);
+
+        asset_server.reload_asset("texture.png");
+        // the assets are reloaded in the background
+        while asset_server.server.reloaded_assets.lock().len() < 3 {
+            std::thread::yield_now();
+        }
+        app.update();
+
+        let events = app.world.resource::<Events<AssetReloaded>>();
+        let mut reloaded: Vec<_> = events.get_reader().iter(events).cloned().collect();
+        reloaded.sort_by(|a, b| a.path.path().cmp(b.path.path()));
+        assert_eq!(
+            reloaded,
+            vec![
+                AssetReloaded {
+                    path: "material.dep".into(),
+                    cause: ReloadCause::Dependency("texture.png".into()),
+                },
+                AssetReloaded {
+                    path: "scene.dep".into(),
+                    cause: ReloadCause::Dependency("texture.png".into()),
+                },
+                AssetReloaded {
+                    path: "texture.png".into(),
+                    cause: ReloadCause::Changed,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_other_assets() {
+        struct FakeMaterialLoader;
+        impl AssetLoader for FakeMaterialLoader {
+            fn load<'a>(
+                &'a self,
+                bytes: &'a [u8],
+                ctx: &'a mut LoadContext,
+            ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+                Box::pin(async move {
+                    let texture = std::str::from_utf8(bytes)?.to_string();
+                    let _texture: PngAsset = ctx.load_direct(texture.as_str()).await?;
+                    let _normal_map: Handle<PngAsset> = ctx.load("./normal.png");
+                    ctx.set_default_asset(LoadedAsset::new(PngAsset));
+                    Ok(())
+                })
+            }
+
+            fn extensions(&self) -> &[&str] {
+                &["mat"]
+            }
+        }
+
+        let dir = create_dir_and_file("texture.png");
+        std::fs::create_dir(dir.path().join("materials")).unwrap();
+        std::fs::write(dir.path().join("materials/normal.png"), "").unwrap();
+        std::fs::write(dir.path().join("materials/wall.mat"), "../texture.png").unwrap();
+        std::fs::write(dir.path().join("materials/missing.mat"), "missing.png").unwrap();
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        asset_server.add_loader(FakeMaterialLoader);
+        let _assets = asset_server.register_asset_type::<PngAsset>();
+
+        let path: AssetPath = "materials/wall.mat".into();
+        futures_lite::future::block_on(asset_server.load_async(path.clone(), true)).unwrap();
+        assert_eq!(
+            asset_server.get_dependencies(path.get_id()),
+            vec![AssetPath::from("materials/normal.png")]
+        );
+        // the material is reloaded when its texture changes, without loading the texture
+        assert_eq!(
+            asset_server.get_dependents(AssetPath::from("texture.png").get_id().source_path_id()),
+            vec![path]
+        );
+        assert_eq!(
+            asset_server.get_load_state(AssetPath::from("texture.png").get_id()),
+            LoadState::NotLoaded
+        );
+
+        let path: AssetPath = "materials/missing.mat".into();
+        let err = futures_lite::future::block_on(asset_server.load_async(path, true)).unwrap_err();
+        assert!(matches!(err, AssetServerError::AssetLoaderError(_)));
+    }
+
+    #[test]
+    fn test_asset_source_scheme() {
+        let asset_server = setup(".");
+        asset_server.add_loader(FakePngLoader);
+        let _assets = asset_server.register_asset_type::<PngAsset>();
+        let embedded = crate::EmbeddedAssetIo::default();
+        embedded.insert("images/fake.png", Vec::new());
+        asset_server.add_source("embedded", embedded);
+
+        let path: AssetPath = "embedded://images/fake.png".into();
+        futures_lite::future::block_on(asset_server.load_async(path, true)).unwrap();
+
+        let path: AssetPath = "embedded://images".into();
+        asset_server
+            .server
+            .folders
+            .write()
+            .insert(path.get_id().source_path_id());
+        futures_lite::future::block_on(asset_server.load_folder_async(path, true)).unwrap();
+        let pending_folders = asset_server.server.pending_folders.lock();
+        assert_eq!(
+            asset_server
+                .get_handle_path(&pending_folders[0].handles[0])
+                .unwrap()
+                .path(),
+            Path::new("embedded://images/fake.png")
+        );
+        drop(pending_folders);
+
+        let path: AssetPath = "pak://fake.png".into();
+        let err = futures_lite::future::block_on(asset_server.load_async(path, true)).unwrap_err();
+        assert!(matches!(
+            err,
+            AssetServerError::AssetIoError(AssetIoError::UnknownScheme(scheme)) if scheme == "pak"
+        ));
+    }
+
+    #[test]
+    fn test_load_folder() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("textures/walls")).unwrap();
+        for file in [
+            "textures/a.png",
+            "textures/walls/b.png",
+            "textures/walls/b.png.meta",
+            "textures/notes.txt",
+        ] {
+            std::fs::write(dir.path().join(file), "").unwrap();
+        }
+        let asset_server = setup(dir.path());
+        asset_server.add_loader(FakePngLoader);
+        let png_assets = asset_server.register_asset_type::<PngAsset>();
+        let folder_assets = asset_server.register_asset_type::<LoadedFolder>();
+
+        let mut app = App::new();
+        app.insert_resource(png_assets);
+        app.insert_resource(folder_assets);
+        app.insert_resource(asset_server.clone());
+        app.add_system(update_asset_storage_system::<PngAsset>);
+        app.add_system(
+            create_loaded_folders_system
+                .after(update_asset_storage_system::<PngAsset>)
+                .before(update_asset_storage_system::<LoadedFolder>),
+        );
+        app.add_system(update_asset_storage_system::<LoadedFolder>);
+
+        for path in ["textures/a.png", "textures/walls/b.png"] {
+            futures_lite::future::block_on(asset_server.load_async(path.into(), true)).unwrap();
+        }
+        let load_folder = |path: &str| {
+            let path: AssetPath = path.into();
+            asset_server
+                .server
+                .folders
+                .write()
+                .insert(path.get_id().source_path_id());
+            let result = futures_lite::future::block_on(asset_server.load_folder_async(path, true));
+            result.map(|id| asset_server.get_handle::<LoadedFolder, _>(id))
+        };
+        let folder = load_folder("textures").unwrap();
+        let pngs = load_folder("textures/walls/*.png").unwrap();
+        app.update();
+
+        // the files without a loader and the meta files aren't loaded
+        let folders = app.world.resource::<Assets<LoadedFolder>>();
+        assert_eq!(asset_server.get_load_state(&folder), LoadState::Loaded);
+        assert_eq!(folders.get(&folder).unwrap().len(), 2);
+        assert_eq!(
+            folders
+                .get(&folder)
+                .unwrap()
+                .handles_of::<PngAsset>()
+                .count(),
+            2
+        );
+        let pngs = folders.get(&pngs).unwrap();
+        assert_eq!(
+            asset_server
+                .get_handle_path(&pngs.handles()[0])
+                .unwrap()
+                .path(),
+            Path::new("textures/walls/b.png")
+        );
+
+        let err = load_folder("missing").unwrap_err();
+        assert!(matches!(err, AssetServerError::AssetFolderNotADirectory(_)));
+        assert_eq!(
+            asset_server.get_load_state(AssetPath::from("missing").get_id()),
+            LoadState::Failed
+        );
+    }
+
+    #[test]
+    fn test_loader_settings() {
+        #[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+        #[serde(default)]
+        struct FakeSettings {
+            scale: u32,
+            flip: bool,
+        }
+
+        #[derive(Default, Clone)]
+        struct FakeSettingsLoader(Arc<Mutex<Option<FakeSettings>>>);
+        impl AssetLoader for FakeSettingsLoader {
+            fn load<'a>(
+                &'a self,
+                _: &'a [u8],
+                ctx: &'a mut LoadContext,
+            ) -> BoxedFuture<'a, Result<(), anyhow::Error>> {
+                Box::pin(async move {
+                    *self.0.lock() = Some(ctx.settings::<FakeSettings>()?);
+                    ctx.set_default_asset(LoadedAsset::new(PngAsset));
+                    Ok(())
+                })
+            }
+
+            fn extensions(&self) -> &[&str] {
+                &["cfg"]
+            }
+        }
+
+        let asset_server = setup(".");
+        let loader = FakeSettingsLoader::default();
+        asset_server.add_loader(loader.clone());
+        let _assets = asset_server.register_asset_type::<PngAsset>();
+        let embedded = crate::EmbeddedAssetIo::default();
+        embedded.insert("a.cfg", Vec::new());
+        embedded.insert("a.cfg.meta", b"(scale: 2)".as_slice());
+        embedded.insert("b.cfg", Vec::new());
+        embedded.insert("c.cfg", Vec::new());
+        embedded.insert("c.cfg.meta", b"[1, 2]".as_slice());
+        asset_server.add_source("embedded", embedded);
+
+        let load = |path: &str| {
+            futures_lite::future::block_on(asset_server.load_async(path.into(), true))
+                .map(|_| loader.0.lock().take().unwrap())
+        };
+        assert_eq!(
+            load("embedded://a.cfg").unwrap(),
+            FakeSettings {
+                scale: 2,
+                flip: false
+            }
+        );
+        assert_eq!(load("embedded://b.cfg").unwrap(), FakeSettings::default());
+        assert!(matches!(
+            load("embedded://c.cfg"),
+            Err(AssetServerError::AssetLoaderError(_))
+        ));
+
+        asset_server
+            .save_settings(
+                "embedded://b.cfg",
+                &FakeSettings {
+                    scale: 3,
+                    flip: false,
+                },
+            )
+            .unwrap();
+        // the meta file is written in the background
+        while load("embedded://b.cfg").unwrap().scale != 3 {
+            std::thread::yield_now();
+        }
+
+        // the settings passed when loading are applied after the meta file, also when reloading
+        let _handle: Handle<PngAsset> =
+            asset_server.load_with_settings("embedded://a.cfg", |settings: &mut FakeSettings| {
+                settings.flip = true;
+            });
+        assert_eq!(
+            load("embedded://a.cfg").unwrap(),
+            FakeSettings {
+                scale: 2,
+                flip: true
+            }
+        );
+    }
+
+    #[test]
+    fn test_save_asset() {
+        struct FakePngSaver;
+        impl AssetSaver for FakePngSaver {
+            type Asset = PngAsset;
+
+            fn save(&self, _: &PngAsset) -> Result<Vec<u8>, anyhow::Error> {
+                Ok(b"png".to_vec())
+            }
+
+            fn extensions(&self) -> &[&str] {
+                &["png"]
+            }
+        }
+
+        let asset_server = setup(".");
+        asset_server.add_saver(FakePngSaver);
+        let mut assets = asset_server.register_asset_type::<PngAsset>();
+        let handle = assets.add(PngAsset);
+        let embedded = crate::EmbeddedAssetIo::default();
+        asset_server.add_source("embedded", embedded.clone());
+
+        asset_server
+            .save(&assets, &handle, "embedded://saved/image.PNG")
+            .unwrap();
+        assert!(matches!(
+            asset_server.save(&assets, &handle, "embedded://image.jpg"),
+            Err(AssetServerError::MissingAssetSaver { extensions }) if extensions == ["jpg"]
+        ));
+        assets.remove(&handle);
+        assert!(matches!(
+            asset_server.save(&assets, &handle, "embedded://image.png"),
+            Err(AssetServerError::MissingAsset)
+        ));
+
+        // the asset is written in the background
+        let path = Path::new("saved/image.PNG");
+        let mut bytes = None;
+        for _ in 0..1000 {
+            bytes = futures_lite::future::block_on(embedded.load_path(path)).ok();
+            if bytes.is_some() {
+                break;
+            }
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+        assert_eq!(bytes.unwrap(), b"png");
+    }
+
     #[test]
     fn test_get_handle_path() {
         const PATH: &str = "path/file.png";
@@ -946,4 +2346,51 @@ mod test {
         let invalid_path = AssetPath::new("some/path.ext".into(), None);
         assert!(server.get_handle_path(invalid_path).is_none());
     }
+
+    #[test]
+    fn handle_report() {
+        let server = setup(".");
+        let mut assets = server.register_asset_type::<PngAsset>();
+        let untracked = assets.add(PngAsset);
+        server.track_handle_holders();
+
+        let loaded: Handle<PngAsset> = server.load("path/file.png");
+        let clones: Vec<_> = (0..2).map(|_| loaded.clone()).collect();
+        let clone_location = clones[0].clone();
+        let _untracked_clone = untracked.clone();
+        drop(clone_location);
+        server.mark_unused_assets();
+
+        let report = server.handle_report();
+        assert_eq!(report.total_strong_handles(), 5);
+        let loaded_handles = &report.assets[0];
+        assert_eq!(loaded_handles.id, loaded.id());
+        assert_eq!(
+            loaded_handles.path.as_ref().unwrap().path(),
+            Path::new("path/file.png")
+        );
+        assert_eq!(
+            loaded_handles.type_name,
+            Some(std::any::type_name::<PngAsset>())
+        );
+        assert_eq!(loaded_handles.strong_handles, 3);
+        let holders: Vec<_> = loaded_handles
+            .holders
+            .iter()
+            .map(|holder| (holder.location.file(), holder.count))
+            .collect();
+        assert_eq!(holders, [(file!(), 2), (file!(), 1)]);
+
+        // the handles created before the tracking are counted, without holders
+        let untracked_handles = &report.assets[1];
+        assert_eq!(untracked_handles.strong_handles, 2);
+        assert_eq!(untracked_handles.holders.len(), 1);
+        assert_eq!(untracked_handles.holders[0].count, 1);
+
+        drop(clones);
+        server.mark_unused_assets();
+        let report = server.handle_report();
+        assert_eq!(report.assets[0].holders.len(), 1);
+        assert!(report.to_string().starts_with("2 strong handles of "));
+    }
 }
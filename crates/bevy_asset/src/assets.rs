@@ -1,6 +1,7 @@
 use crate::{
-    update_asset_storage_system, Asset, AssetLoader, AssetServer, AssetSet, Handle, HandleId,
-    RefChange, ReflectAsset, ReflectHandle,
+    asset_stats_system, update_asset_storage_system, Asset, AssetIo, AssetLoader, AssetSaver,
+    AssetServer, AssetSet, AssetStats, Handle, HandleId, LoadState, RefChange, ReflectAsset,
+    ReflectHandle, RetentionPolicy,
 };
 use bevy_app::{App, AppTypeRegistry};
 use bevy_ecs::prelude::*;
@@ -20,6 +21,11 @@ pub enum AssetEvent<T: Asset> {
     Modified { handle: Handle<T> },
     #[allow(missing_docs)]
     Removed { handle: Handle<T> },
+    /// Sent once an asset loaded by the [`AssetServer`] and all its dependencies were loaded, as
+    /// reported by [`AssetServer::get_recursive_load_state`], after its
+    /// [`Created`](AssetEvent::Created) or [`Modified`](AssetEvent::Modified) event
+    #[allow(missing_docs)]
+    LoadedWithDependencies { handle: Handle<T> },
 }
 
 impl<T: Asset> Debug for AssetEvent<T> {
@@ -46,6 +52,13 @@ impl<T: Asset> Debug for AssetEvent<T> {
                 ))
                 .field("handle", &handle.id())
                 .finish(),
+            AssetEvent::LoadedWithDependencies { handle } => f
+                .debug_struct(&format!(
+                    "AssetEvent<{}>::LoadedWithDependencies",
+                    std::any::type_name::<T>()
+                ))
+                .field("handle", &handle.id())
+                .finish(),
         }
     }
 }
@@ -67,6 +80,8 @@ impl<T: Asset> Debug for AssetEvent<T> {
 pub struct Assets<T: Asset> {
     assets: HashMap<HandleId, T>,
     events: Events<AssetEvent<T>>,
+    waiting_for_dependencies: Vec<HandleId>,
+    size_estimator: fn(&T) -> usize,
     pub(crate) ref_change_sender: Sender<RefChange>,
 }
 
@@ -75,6 +90,8 @@ impl<T: Asset> Assets<T> {
         Assets {
             assets: HashMap::default(),
             events: Events::default(),
+            waiting_for_dependencies: Vec::new(),
+            size_estimator: |_| std::mem::size_of::<T>(),
             ref_change_sender,
         }
     }
@@ -84,6 +101,7 @@ impl<T: Asset> Assets<T> {
     /// # Events
     ///
     /// * [`AssetEvent::Created`]
+    #[track_caller]
     pub fn add(&mut self, asset: T) -> Handle<T> {
         let id = HandleId::random::<T>();
         self.assets.insert(id, asset);
@@ -100,6 +118,7 @@ impl<T: Asset> Assets<T> {
     ///
     /// See [`set_untracked`](Assets::set_untracked) for more info.
     #[must_use = "not using the returned strong handle may result in the unexpected release of the asset"]
+    #[track_caller]
     pub fn set<H: Into<HandleId>>(&mut self, handle: H, asset: T) -> Handle<T> {
         let id: HandleId = handle.into();
         self.set_untracked(id, asset);
@@ -154,6 +173,7 @@ impl<T: Asset> Assets<T> {
     }
 
     /// Gets a _Strong_ handle pointing to the same asset as the given one.
+    #[track_caller]
     pub fn get_handle<H: Into<HandleId>>(&self, handle: H) -> Handle<T> {
         Handle::strong(handle.into(), self.ref_change_sender.clone())
     }
@@ -243,6 +263,58 @@ impl<T: Asset> Assets<T> {
         self.assets.shrink_to_fit();
     }
 
+    /// Sets the function estimating the memory used by an asset, in bytes, for the [`AssetStats`]
+    /// and the [`RetentionPolicy::Budget`] of the assets.
+    ///
+    /// The default estimator returns the size of `T`, without the memory it allocates.
+    pub fn set_size_estimator(&mut self, size_estimator: fn(&T) -> usize) {
+        self.size_estimator = size_estimator;
+    }
+
+    /// The estimated memory used by the asset of `id`, or `None` if it's not in the collection
+    pub fn estimated_size_of<H: Into<HandleId>>(&self, id: H) -> Option<usize> {
+        self.assets.get(&id.into()).map(self.size_estimator)
+    }
+
+    /// The estimated memory used by all the assets of the collection, in bytes
+    pub fn estimated_size(&self) -> usize {
+        self.assets.values().map(self.size_estimator).sum()
+    }
+
+    /// The assets loaded by the [`AssetServer`] that wait for their dependencies to be loaded
+    pub(crate) fn waiting_for_dependencies(&self) -> &[HandleId] {
+        &self.waiting_for_dependencies
+    }
+
+    pub(crate) fn wait_for_dependencies(&mut self, id: HandleId) {
+        if !self.waiting_for_dependencies.contains(&id) {
+            self.waiting_for_dependencies.push(id);
+        }
+    }
+
+    /// Stops waiting for the dependencies of the assets that aren't loading anymore, with the
+    /// `load_states` of the [`waiting_for_dependencies`](Self::waiting_for_dependencies) assets
+    ///
+    /// # Events
+    ///
+    /// * [`AssetEvent::LoadedWithDependencies`]: Sent for the assets whose dependencies were all
+    ///   loaded.
+    pub(crate) fn finish_waiting_for_dependencies(&mut self, load_states: &[LoadState]) {
+        let mut load_states = load_states.iter();
+        let events = &mut self.events;
+        self.waiting_for_dependencies
+            .retain(|&id| match load_states.next() {
+                Some(LoadState::Loading) => true,
+                Some(LoadState::Loaded) => {
+                    events.send(AssetEvent::LoadedWithDependencies {
+                        handle: Handle::weak(id),
+                    });
+                    false
+                }
+                _ => false,
+            });
+    }
+
     /// A system that creates [`AssetEvent`]s at the end of the frame based on changes in the
     /// asset storage.
     pub fn asset_event_system(
@@ -315,6 +387,40 @@ pub trait AddAsset {
     fn add_asset_loader<T>(&mut self, loader: T) -> &mut Self
     where
         T: AssetLoader;
+
+    /// Adds an asset saver `T` using default values.
+    ///
+    /// The default values may come from the `World` or from `T::default()`.
+    fn init_asset_saver<T>(&mut self) -> &mut Self
+    where
+        T: AssetSaver + FromWorld;
+
+    /// Adds the provided asset saver to the application.
+    fn add_asset_saver<T>(&mut self, saver: T) -> &mut Self
+    where
+        T: AssetSaver;
+
+    /// Sets the [`RetentionPolicy`] of the assets of type `T`.
+    ///
+    /// See [`AssetServer::set_retention_policy`].
+    fn set_asset_retention_policy<T>(&mut self, policy: RetentionPolicy) -> &mut Self
+    where
+        T: Asset;
+
+    /// Sets the function estimating the memory used by an asset of type `T`.
+    ///
+    /// See [`Assets::set_size_estimator`].
+    fn set_asset_size_estimator<T>(&mut self, size_estimator: fn(&T) -> usize) -> &mut Self
+    where
+        T: Asset;
+
+    /// Adds the provided asset I/O as the source of the assets whose path starts with the URI
+    /// `scheme`.
+    ///
+    /// See [`AssetServer::add_source`].
+    fn add_asset_source<T>(&mut self, scheme: impl Into<String>, asset_io: T) -> &mut Self
+    where
+        T: AssetIo;
 }
 
 impl AddAsset for App {
@@ -333,6 +439,8 @@ impl AddAsset for App {
         self.insert_resource(assets)
             .add_system(Assets::<T>::asset_event_system.in_base_set(AssetSet::AssetEvents))
             .add_system(update_asset_storage_system::<T>.in_base_set(AssetSet::LoadAssets))
+            .init_resource::<AssetStats>()
+            .add_system(asset_stats_system::<T>.in_base_set(AssetSet::AssetEvents))
             .register_type::<Handle<T>>()
             .add_event::<AssetEvent<T>>()
     }
@@ -402,6 +510,52 @@ impl AddAsset for App {
         self.world.resource_mut::<AssetServer>().add_loader(loader);
         self
     }
+
+    fn init_asset_saver<T>(&mut self) -> &mut Self
+    where
+        T: AssetSaver + FromWorld,
+    {
+        let result = T::from_world(&mut self.world);
+        self.add_asset_saver(result)
+    }
+
+    fn add_asset_saver<T>(&mut self, saver: T) -> &mut Self
+    where
+        T: AssetSaver,
+    {
+        self.world.resource::<AssetServer>().add_saver(saver);
+        self
+    }
+
+    fn set_asset_retention_policy<T>(&mut self, policy: RetentionPolicy) -> &mut Self
+    where
+        T: Asset,
+    {
+        self.world
+            .resource::<AssetServer>()
+            .set_retention_policy::<T>(policy);
+        self
+    }
+
+    fn set_asset_size_estimator<T>(&mut self, size_estimator: fn(&T) -> usize) -> &mut Self
+    where
+        T: Asset,
+    {
+        self.world
+            .resource_mut::<Assets<T>>()
+            .set_size_estimator(size_estimator);
+        self
+    }
+
+    fn add_asset_source<T>(&mut self, scheme: impl Into<String>, asset_io: T) -> &mut Self
+    where
+        T: AssetIo,
+    {
+        self.world
+            .resource::<AssetServer>()
+            .add_source(scheme, asset_io);
+        self
+    }
 }
 
 /// Loads an internal asset.
@@ -0,0 +1,291 @@
+use crate::{Asset, AssetServer, Handle, HandleId, HandleUntyped, LoadingToken, LoadingTracker};
+use bevy_app::{App, CoreSet, IntoSystemAppConfig};
+use bevy_ecs::{
+    prelude::*,
+    schedule::{OnEnter, OnUpdate, States},
+};
+use bevy_log::error;
+use bevy_utils::HashMap;
+
+pub use bevy_asset_macros::AssetCollection;
+
+/// A resource of handles loaded together, like the assets of a level
+///
+/// The collection is usually derived, loading each field from the path of its `asset` attribute,
+/// and setting the fields without attribute to their default value:
+///
+/// ```ignore
+/// #[derive(Resource, AssetCollection)]
+/// struct LevelAssets {
+///     #[asset(path = "models/level.glb#Scene0")]
+///     level: Handle<Scene>,
+///     #[asset(paths("sounds/step_1.ogg", "sounds/step_2.ogg"))]
+///     steps: Vec<Handle<AudioSource>>,
+///     #[asset(folder = "textures/enemies")]
+///     enemies: Handle<LoadedFolder>,
+/// }
+///
+/// app.add_state::<GameState>()
+///     .add_loading_state(GameState::Loading, GameState::Playing)
+///     .add_collection_to_loading_state::<LevelAssets, _>(GameState::Loading);
+/// ```
+///
+/// The collection is loaded when entering its loading state, and inserted as a resource once all
+/// its assets are loaded with their dependencies. See [`AddAssetCollection`].
+pub trait AssetCollection: Resource {
+    /// Starts loading the assets of the collection.
+    fn load(asset_server: &AssetServer) -> Self;
+
+    /// The ids of the assets of the collection, loaded before the collection is inserted
+    fn handle_ids(&self) -> Vec<HandleId>;
+}
+
+/// A handle of an [`AssetCollection`] loaded from an asset path
+pub trait CollectionHandle {
+    /// Starts loading the asset at `path`.
+    fn load(asset_server: &AssetServer, path: &str) -> Self;
+
+    /// The id of the handle
+    fn id(&self) -> HandleId;
+}
+
+impl<T: Asset> CollectionHandle for Handle<T> {
+    #[track_caller]
+    fn load(asset_server: &AssetServer, path: &str) -> Self {
+        asset_server.load(path)
+    }
+
+    fn id(&self) -> HandleId {
+        self.id()
+    }
+}
+
+impl CollectionHandle for HandleUntyped {
+    #[track_caller]
+    fn load(asset_server: &AssetServer, path: &str) -> Self {
+        asset_server.load_untyped(path)
+    }
+
+    fn id(&self) -> HandleId {
+        self.id()
+    }
+}
+
+/// The loading states of type `S`, and the number of collections loading in each of them
+#[derive(Resource)]
+struct LoadingStates<S: States> {
+    next_states: HashMap<S, S>,
+    loading_collections: HashMap<S, usize>,
+}
+
+impl<S: States> Default for LoadingStates<S> {
+    fn default() -> Self {
+        Self {
+            next_states: HashMap::default(),
+            loading_collections: HashMap::default(),
+        }
+    }
+}
+
+/// A collection whose assets are loading
+#[derive(Resource)]
+struct LoadingCollection<C> {
+    collection: Option<C>,
+    token: LoadingToken,
+    failed: bool,
+}
+
+/// [`App`] extension methods for loading [`AssetCollection`]s in loading states.
+pub trait AddAssetCollection {
+    /// Makes `loading_state` a loading state, switching to `next_state` once all its collections
+    /// are inserted.
+    ///
+    /// The state `S` must have been added with [`App::add_state`].
+    fn add_loading_state<S: States>(&mut self, loading_state: S, next_state: S) -> &mut Self;
+
+    /// Loads the collection `C` each time `loading_state` is entered, and inserts it as a
+    /// resource once all its assets are loaded with their dependencies.
+    ///
+    /// When any of its assets fails to load, an error is logged and the collection isn't
+    /// inserted, so the app stays in the loading state. The [`LoadingProgress`] resource has the
+    /// progress of the collections still loading.
+    ///
+    /// [`LoadingProgress`]: crate::LoadingProgress
+    fn add_collection_to_loading_state<C: AssetCollection, S: States>(
+        &mut self,
+        loading_state: S,
+    ) -> &mut Self;
+}
+
+impl AddAssetCollection for App {
+    fn add_loading_state<S: States>(&mut self, loading_state: S, next_state: S) -> &mut Self {
+        self.init_resource::<LoadingStates<S>>();
+        self.world
+            .resource_mut::<LoadingStates<S>>()
+            .next_states
+            .insert(loading_state.clone(), next_state);
+        self.add_system(
+            continue_to_next_state::<S>
+                .in_base_set(CoreSet::PostUpdate)
+                .run_if(in_state(loading_state)),
+        )
+    }
+
+    fn add_collection_to_loading_state<C: AssetCollection, S: States>(
+        &mut self,
+        loading_state: S,
+    ) -> &mut Self {
+        self.init_resource::<LoadingStates<S>>()
+            .add_system(
+                start_loading_collection::<C, S>.in_schedule(OnEnter(loading_state.clone())),
+            )
+            .add_system(insert_loaded_collection::<C, S>.in_set(OnUpdate(loading_state)))
+    }
+}
+
+fn start_loading_collection<C: AssetCollection, S: States>(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    state: Res<State<S>>,
+    previous: Option<Res<LoadingCollection<C>>>,
+    mut tracker: ResMut<LoadingTracker>,
+    mut loading_states: ResMut<LoadingStates<S>>,
+) {
+    if let Some(previous) = previous {
+        tracker.remove(previous.token);
+    }
+    let collection = C::load(&asset_server);
+    let token = tracker.new_token();
+    tracker.track_all(token, collection.handle_ids());
+    *loading_states
+        .loading_collections
+        .entry(state.0.clone())
+        .or_default() += 1;
+    commands.insert_resource(LoadingCollection {
+        collection: Some(collection),
+        token,
+        failed: false,
+    });
+}
+
+fn insert_loaded_collection<C: AssetCollection, S: States>(
+    mut commands: Commands,
+    state: Res<State<S>>,
+    loading: Option<ResMut<LoadingCollection<C>>>,
+    mut tracker: ResMut<LoadingTracker>,
+    mut loading_states: ResMut<LoadingStates<S>>,
+) {
+    let Some(mut loading) = loading else {
+        return;
+    };
+    let Some(progress) = tracker.progress(loading.token) else {
+        return;
+    };
+    if progress.is_loaded() {
+        let Some(collection) = loading.collection.take() else {
+            return;
+        };
+        tracker.remove(loading.token);
+        commands.remove_resource::<LoadingCollection<C>>();
+        commands.insert_resource(collection);
+        if let Some(count) = loading_states.loading_collections.get_mut(&state.0) {
+            *count = count.saturating_sub(1);
+        }
+    } else if progress.is_finished() && !loading.failed {
+        loading.failed = true;
+        error!(
+            "{} of the {} assets of the collection {} failed to load",
+            progress.failed,
+            progress.total,
+            std::any::type_name::<C>()
+        );
+    }
+}
+
+fn continue_to_next_state<S: States>(
+    state: Res<State<S>>,
+    loading_states: Res<LoadingStates<S>>,
+    mut next_state: ResMut<NextState<S>>,
+) {
+    let loading_collections = loading_states
+        .loading_collections
+        .get(&state.0)
+        .copied()
+        .unwrap_or_default();
+    if loading_collections == 0 {
+        if let Some(next) = loading_states.next_states.get(&state.0) {
+            next_state.set(next.clone());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AssetPath, AssetPlugin, LoadedFolder};
+    use bevy_reflect::TypeUuid;
+
+    #[derive(TypeUuid)]
+    #[uuid = "3a7b6f09-5d84-4b3e-9b8a-2f3c1d6e7a90"]
+    struct Text;
+
+    #[derive(Resource, AssetCollection)]
+    struct LevelAssets {
+        #[asset(path = "level.txt")]
+        level: Handle<Text>,
+        #[asset(paths("intro.txt", "outro.txt#credits"))]
+        dialogs: Vec<HandleUntyped>,
+        #[asset(folder = "notes")]
+        notes: Handle<LoadedFolder>,
+        visits: u32,
+    }
+
+    #[derive(Resource, AssetCollection)]
+    struct Settings {
+        volume: f32,
+    }
+
+    #[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+    enum GameState {
+        #[default]
+        Loading,
+        Playing,
+    }
+
+    #[test]
+    fn derived_collection() {
+        bevy_tasks::IoTaskPool::init(Default::default);
+        let asset_server = AssetServer::new(crate::EmbeddedAssetIo::default());
+        let assets = LevelAssets::load(&asset_server);
+        assert_eq!(assets.dialogs.len(), 2);
+        assert_eq!(assets.visits, 0);
+        assert_eq!(
+            assets.handle_ids(),
+            [
+                HandleId::from(AssetPath::from("level.txt")),
+                AssetPath::from("intro.txt").into(),
+                AssetPath::from("outro.txt#credits").into(),
+                assets.notes.id(),
+            ]
+        );
+        assert_eq!(Settings::load(&asset_server).handle_ids(), []);
+    }
+
+    #[test]
+    fn loading_state() {
+        let mut app = App::new();
+        app.add_plugin(bevy_core::TaskPoolPlugin::default())
+            .add_plugin(AssetPlugin::default())
+            .add_state::<GameState>()
+            .add_loading_state(GameState::Loading, GameState::Playing)
+            .add_collection_to_loading_state::<Settings, _>(GameState::Loading);
+
+        app.update();
+        assert_eq!(app.world.resource::<Settings>().volume, 0.0);
+        app.update();
+        assert_eq!(
+            app.world.resource::<State<GameState>>().0,
+            GameState::Playing
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use crate::AssetStats;
+use bevy_app::prelude::*;
+use bevy_diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy_ecs::prelude::*;
+
+/// Adds `asset_count`, `asset_unused` and `asset_bytes` diagnostics to an [`App`], with the totals
+/// of the [`AssetStats`] of all the asset types.
+#[derive(Default)]
+pub struct AssetStatsDiagnosticsPlugin;
+
+impl Plugin for AssetStatsDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AssetStats>()
+            .add_startup_system(Self::setup_system)
+            .add_system(Self::diagnostic_system);
+    }
+}
+
+impl AssetStatsDiagnosticsPlugin {
+    /// The number of assets of all types
+    pub const COUNT: DiagnosticId =
+        DiagnosticId::from_u128(264015656815535232647735276315400930726);
+    /// The number of assets kept without strong handles
+    pub const UNUSED: DiagnosticId =
+        DiagnosticId::from_u128(91384889163772976137988241846203593589);
+    /// The estimated memory used by the assets, in bytes
+    pub const ESTIMATED_BYTES: DiagnosticId =
+        DiagnosticId::from_u128(178059806607550983920404712206540924548);
+
+    /// Registers the asset stats diagnostics for the current application.
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(Self::COUNT, "asset_count", 20));
+        diagnostics.add(Diagnostic::new(Self::UNUSED, "asset_unused", 20));
+        diagnostics.add(Diagnostic::new(Self::ESTIMATED_BYTES, "asset_bytes", 20));
+    }
+
+    /// Updates the asset stats diagnostics.
+    pub fn diagnostic_system(mut diagnostics: ResMut<Diagnostics>, stats: Res<AssetStats>) {
+        diagnostics.add_measurement(Self::COUNT, || stats.total_count() as f64);
+        diagnostics.add_measurement(Self::UNUSED, || stats.total_unused() as f64);
+        diagnostics.add_measurement(Self::ESTIMATED_BYTES, || {
+            stats.total_estimated_bytes() as f64
+        });
+    }
+}
@@ -0,0 +1,54 @@
+use crate::AssetServer;
+use bevy_app::prelude::*;
+use bevy_diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy_ecs::prelude::*;
+
+/// Adds `asset_strong_handles` and `asset_held` diagnostics to an [`App`], with the number of
+/// strong handles and of the assets they keep loaded, and tracks the holders of the strong handles
+/// reported by [`AssetServer::handle_report`].
+///
+/// A number of strong handles growing for the whole session usually means some code keeps
+/// creating handles it never drops. The [report](AssetServer::handle_report) has the location of
+/// that code.
+///
+/// The plugin must be added after the [`AssetPlugin`](crate::AssetPlugin).
+#[derive(Default)]
+pub struct HandleDiagnosticsPlugin;
+
+impl Plugin for HandleDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.world.resource::<AssetServer>().track_handle_holders();
+        app.add_startup_system(Self::setup_system)
+            .add_system(Self::diagnostic_system);
+    }
+}
+
+impl HandleDiagnosticsPlugin {
+    /// The number of strong handles of all the assets
+    pub const STRONG_HANDLES: DiagnosticId =
+        DiagnosticId::from_u128(185480638440314809502284997345802139576);
+    /// The number of assets with strong handles
+    pub const HELD_ASSETS: DiagnosticId =
+        DiagnosticId::from_u128(28588968565207122174397062247164690219);
+
+    /// Registers the handle diagnostics for the current application.
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(
+            Self::STRONG_HANDLES,
+            "asset_strong_handles",
+            20,
+        ));
+        diagnostics.add(Diagnostic::new(Self::HELD_ASSETS, "asset_held", 20));
+    }
+
+    /// Updates the handle diagnostics.
+    pub fn diagnostic_system(mut diagnostics: ResMut<Diagnostics>, asset_server: Res<AssetServer>) {
+        let ref_counts = asset_server.server.asset_ref_counter.ref_counts.read();
+        diagnostics.add_measurement(Self::STRONG_HANDLES, || {
+            ref_counts.values().sum::<usize>() as f64
+        });
+        diagnostics.add_measurement(Self::HELD_ASSETS, || {
+            ref_counts.values().filter(|count| **count > 0).count() as f64
+        });
+    }
+}
@@ -1,4 +1,8 @@
 //! Diagnostic providers for `bevy_diagnostic`.
 
 mod asset_count_diagnostics_plugin;
+mod asset_stats_diagnostics_plugin;
+mod handle_diagnostics_plugin;
 pub use asset_count_diagnostics_plugin::AssetCountDiagnosticsPlugin;
+pub use asset_stats_diagnostics_plugin::AssetStatsDiagnosticsPlugin;
+pub use handle_diagnostics_plugin::HandleDiagnosticsPlugin;
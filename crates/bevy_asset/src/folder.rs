@@ -0,0 +1,214 @@
+use crate::{Asset, Handle, HandleUntyped};
+use bevy_reflect::TypeUuid;
+use bevy_utils::{HashMap, Uuid};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
+
+/// The assets of a folder loaded with [`AssetServer::load_folder`](crate::AssetServer::load_folder)
+///
+/// The folder is added to its [`Assets`](crate::Assets) once all its assets finished loading,
+/// successfully or not, and is updated when assets are added to or removed from the folder while
+/// watching for changes.
+#[derive(Debug, TypeUuid)]
+#[uuid = "4f5a8a6e-3b62-46c1-9d0b-62d7a31f8c25"]
+pub struct LoadedFolder {
+    pub(crate) handles: Vec<HandleUntyped>,
+    pub(crate) handles_by_type: HashMap<Uuid, Vec<HandleUntyped>>,
+}
+
+impl LoadedFolder {
+    /// The strong handles of all the assets of the folder, including the ones that failed to load
+    pub fn handles(&self) -> &[HandleUntyped] {
+        &self.handles
+    }
+
+    /// Iterates over the strong handles of the assets of type `T` of the folder.
+    pub fn handles_of<T: Asset>(&self) -> impl Iterator<Item = Handle<T>> + '_ {
+        self.handles_by_type
+            .get(&T::TYPE_UUID)
+            .into_iter()
+            .flatten()
+            .map(|handle| handle.clone().typed())
+    }
+
+    /// The number of assets of the folder
+    pub fn len(&self) -> usize {
+        self.handles.len()
+    }
+
+    /// Returns `true` if the folder has no assets.
+    pub fn is_empty(&self) -> bool {
+        self.handles.is_empty()
+    }
+}
+
+/// The list of the files of the asset folder, used to load folders with
+/// [`AssetServer::load_folder`](crate::AssetServer::load_folder) when the asset I/O can't list
+/// directories, like in WASM or on Android
+///
+/// The manifest is read from [`AssetManifest::PATH`] in the asset folder. It can be generated
+/// with [`AssetManifest::from_asset_folder`] when building the app, like in a build script:
+///
+/// ```no_run
+/// # use bevy_asset::AssetManifest;
+/// let manifest = AssetManifest::from_asset_folder("assets").unwrap();
+/// std::fs::write(
+///     std::path::Path::new("assets").join(AssetManifest::PATH),
+///     manifest.to_ron(),
+/// )
+/// .unwrap();
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetManifest {
+    /// The paths of the files, relative to the asset folder
+    pub paths: Vec<PathBuf>,
+}
+
+impl AssetManifest {
+    /// The path of the manifest, relative to the asset folder
+    pub const PATH: &'static str = "assets.manifest.ron";
+
+    /// Lists the files of the asset folder at `path`, recursively.
+    ///
+    /// The meta files and the manifest itself aren't listed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_asset_folder(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        fn visit(root: &Path, directory: &Path, paths: &mut Vec<PathBuf>) -> std::io::Result<()> {
+            for entry in std::fs::read_dir(directory)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    visit(root, &path, paths)?;
+                } else {
+                    let relative_path = path.strip_prefix(root).unwrap();
+                    if relative_path != Path::new(AssetManifest::PATH)
+                        && !matches!(path.extension(), Some(extension) if extension == "meta")
+                    {
+                        paths.push(relative_path.to_owned());
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        let mut paths = Vec::new();
+        visit(path.as_ref(), path.as_ref(), &mut paths)?;
+        paths.sort();
+        Ok(Self { paths })
+    }
+
+    /// Writes the manifest in RON.
+    pub fn to_ron(&self) -> String {
+        ron::ser::to_string_pretty(self, Default::default())
+            .expect("a list of paths can be serialized")
+    }
+
+    /// Iterates over the files of the folder at `path`, and of its subfolders.
+    pub fn files_in<'a>(&'a self, path: &'a Path) -> impl Iterator<Item = &'a Path> + 'a {
+        self.paths
+            .iter()
+            .map(PathBuf::as_path)
+            .filter(move |file| file.starts_with(path))
+    }
+}
+
+/// Splits a folder path into the folder to list and the glob pattern its files have to match,
+/// relative to that folder, like `textures/**/*.png` into `textures` and `**/*.png`.
+pub(crate) fn split_folder_glob(path: &Path) -> (PathBuf, Option<String>) {
+    let mut folder = PathBuf::new();
+    let mut components = path.components();
+    for component in components.by_ref() {
+        if let Component::Normal(name) = component {
+            if name.to_string_lossy().contains(['*', '?']) {
+                let mut pattern = PathBuf::from(name);
+                pattern.extend(components);
+                return (folder, Some(pattern.to_string_lossy().replace('\\', "/")));
+            }
+        }
+        folder.push(component);
+    }
+    (folder, None)
+}
+
+/// Returns `true` if `path` matches the glob `pattern`.
+///
+/// A `*` matches any characters of a path component, a `?` matches a single character, and a
+/// `**` component matches any number of components.
+pub(crate) fn glob_matches(pattern: &str, path: &str) -> bool {
+    fn matches_component(pattern: &[u8], name: &[u8]) -> bool {
+        match (pattern.first(), name.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) => {
+                matches_component(&pattern[1..], name)
+                    || (!name.is_empty() && matches_component(pattern, &name[1..]))
+            }
+            (Some(b'?'), Some(_)) => matches_component(&pattern[1..], &name[1..]),
+            (Some(p), Some(n)) if p == n => matches_component(&pattern[1..], &name[1..]),
+            _ => false,
+        }
+    }
+
+    fn matches_components(pattern: &[&str], path: &[&str]) -> bool {
+        match (pattern.first(), path.first()) {
+            (None, None) => true,
+            (Some(&"**"), _) => {
+                matches_components(&pattern[1..], path)
+                    || (!path.is_empty() && matches_components(pattern, &path[1..]))
+            }
+            (Some(p), Some(n)) => {
+                matches_component(p.as_bytes(), n.as_bytes())
+                    && matches_components(&pattern[1..], &path[1..])
+            }
+            _ => false,
+        }
+    }
+
+    let pattern: Vec<&str> = pattern.split('/').filter(|c| !c.is_empty()).collect();
+    let path: Vec<&str> = path.split('/').filter(|c| !c.is_empty()).collect();
+    matches_components(&pattern, &path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn folder_globs() {
+        assert_eq!(
+            split_folder_glob(Path::new("textures/**/*.png")),
+            (PathBuf::from("textures"), Some("**/*.png".to_string()))
+        );
+        assert_eq!(
+            split_folder_glob(Path::new("textures/rpg")),
+            (PathBuf::from("textures/rpg"), None)
+        );
+
+        assert!(glob_matches("*.png", "wall.png"));
+        assert!(!glob_matches("*.png", "walls/wall.png"));
+        assert!(glob_matches("**/*.png", "wall.png"));
+        assert!(glob_matches("**/*.png", "walls/stone/wall.png"));
+        assert!(glob_matches("walls/wall_?.png", "walls/wall_1.png"));
+        assert!(!glob_matches("walls/wall_?.png", "walls/wall_10.png"));
+        assert!(!glob_matches("**/*.png", "walls/wall.jpg"));
+    }
+
+    #[test]
+    fn manifest_files() {
+        let manifest = AssetManifest {
+            paths: ["icon.png", "textures/wall.png", "textures/walls/stone.png"]
+                .map(PathBuf::from)
+                .to_vec(),
+        };
+        let files: Vec<_> = manifest.files_in(Path::new("textures")).collect();
+        assert_eq!(
+            files,
+            [
+                Path::new("textures/wall.png"),
+                Path::new("textures/walls/stone.png")
+            ]
+        );
+        assert_eq!(manifest.files_in(Path::new("")).count(), 3);
+
+        let ron = manifest.to_ron();
+        assert_eq!(ron::from_str::<AssetManifest>(&ron).unwrap(), manifest);
+    }
+}
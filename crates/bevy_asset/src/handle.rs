@@ -3,6 +3,7 @@ use std::{
     fmt::Debug,
     hash::{Hash, Hasher},
     marker::PhantomData,
+    panic::Location,
 };
 
 use crate::{
@@ -118,24 +119,33 @@ where
 
 enum HandleType {
     Weak,
-    Strong(Sender<RefChange>),
+    /// A strong handle, with the location of the code that created it
+    Strong(Sender<RefChange>, &'static Location<'static>),
 }
 
 impl Debug for HandleType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             HandleType::Weak => f.write_str("Weak"),
-            HandleType::Strong(_) => f.write_str("Strong"),
+            HandleType::Strong(..) => f.write_str("Strong"),
         }
     }
 }
 
 impl<T: Asset> Handle<T> {
+    #[track_caller]
     pub(crate) fn strong(id: HandleId, ref_change_sender: Sender<RefChange>) -> Self {
-        ref_change_sender.send(RefChange::Increment(id)).unwrap();
+        let location = Location::caller();
+        ref_change_sender
+            .send(RefChange::Increment(
+                id,
+                location,
+                Some(std::any::type_name::<T>()),
+            ))
+            .unwrap();
         Self {
             id,
-            handle_type: HandleType::Strong(ref_change_sender),
+            handle_type: HandleType::Strong(ref_change_sender, location),
             marker: PhantomData,
         }
     }
@@ -178,19 +188,27 @@ impl<T: Asset> Handle<T> {
 
     /// Returns `true` if this is a strong handle.
     pub fn is_strong(&self) -> bool {
-        matches!(self.handle_type, HandleType::Strong(_))
+        matches!(self.handle_type, HandleType::Strong(..))
     }
 
     /// Makes this handle Strong if it wasn't already.
     ///
     /// This method requires the corresponding [`Assets`](crate::Assets) collection.
+    #[track_caller]
     pub fn make_strong(&mut self, assets: &Assets<T>) {
         if self.is_strong() {
             return;
         }
         let sender = assets.ref_change_sender.clone();
-        sender.send(RefChange::Increment(self.id)).unwrap();
-        self.handle_type = HandleType::Strong(sender);
+        let location = Location::caller();
+        sender
+            .send(RefChange::Increment(
+                self.id,
+                location,
+                Some(std::any::type_name::<T>()),
+            ))
+            .unwrap();
+        self.handle_type = HandleType::Strong(sender, location);
     }
 
     /// Creates a weak copy of this handle.
@@ -201,9 +219,10 @@ impl<T: Asset> Handle<T> {
     }
 
     /// Creates an untyped copy of this handle.
+    #[track_caller]
     pub fn clone_untyped(&self) -> HandleUntyped {
         match &self.handle_type {
-            HandleType::Strong(sender) => HandleUntyped::strong(self.id, sender.clone()),
+            HandleType::Strong(sender, _) => HandleUntyped::strong(self.id, sender.clone()),
             HandleType::Weak => HandleUntyped::weak(self.id),
         }
     }
@@ -217,10 +236,10 @@ impl<T: Asset> Handle<T> {
 impl<T: Asset> Drop for Handle<T> {
     fn drop(&mut self) {
         match self.handle_type {
-            HandleType::Strong(ref sender) => {
+            HandleType::Strong(ref sender, location) => {
                 // ignore send errors because this means the channel is shut down / the game has
                 // stopped
-                let _ = sender.send(RefChange::Decrement(self.id));
+                let _ = sender.send(RefChange::Decrement(self.id, location));
             }
             HandleType::Weak => {}
         }
@@ -303,9 +322,10 @@ impl<T: Asset> Debug for Handle<T> {
 }
 
 impl<T: Asset> Clone for Handle<T> {
+    #[track_caller]
     fn clone(&self) -> Self {
         match self.handle_type {
-            HandleType::Strong(ref sender) => Handle::strong(self.id, sender.clone()),
+            HandleType::Strong(ref sender, _) => Handle::strong(self.id, sender.clone()),
             HandleType::Weak => Handle::weak(self.id),
         }
     }
@@ -332,11 +352,15 @@ impl HandleUntyped {
         }
     }
 
+    #[track_caller]
     pub(crate) fn strong(id: HandleId, ref_change_sender: Sender<RefChange>) -> Self {
-        ref_change_sender.send(RefChange::Increment(id)).unwrap();
+        let location = Location::caller();
+        ref_change_sender
+            .send(RefChange::Increment(id, location, None))
+            .unwrap();
         Self {
             id,
-            handle_type: HandleType::Strong(ref_change_sender),
+            handle_type: HandleType::Strong(ref_change_sender, location),
         }
     }
 
@@ -367,7 +391,7 @@ impl HandleUntyped {
 
     /// Returns `true` if this is a strong handle.
     pub fn is_strong(&self) -> bool {
-        matches!(self.handle_type, HandleType::Strong(_))
+        matches!(self.handle_type, HandleType::Strong(..))
     }
 
     /// Create a weak typed [`Handle`] from this handle.
@@ -393,7 +417,7 @@ impl HandleUntyped {
             );
         }
         let handle_type = match &self.handle_type {
-            HandleType::Strong(sender) => HandleType::Strong(sender.clone()),
+            HandleType::Strong(sender, location) => HandleType::Strong(sender.clone(), location),
             HandleType::Weak => HandleType::Weak,
         };
         // ensure we don't send the RefChange event when "self" is dropped
@@ -409,10 +433,10 @@ impl HandleUntyped {
 impl Drop for HandleUntyped {
     fn drop(&mut self) {
         match self.handle_type {
-            HandleType::Strong(ref sender) => {
+            HandleType::Strong(ref sender, location) => {
                 // ignore send errors because this means the channel is shut down / the game has
                 // stopped
-                let _ = sender.send(RefChange::Decrement(self.id));
+                let _ = sender.send(RefChange::Decrement(self.id, location));
             }
             HandleType::Weak => {}
         }
@@ -450,17 +474,20 @@ impl PartialEq for HandleUntyped {
 impl Eq for HandleUntyped {}
 
 impl Clone for HandleUntyped {
+    #[track_caller]
     fn clone(&self) -> Self {
         match self.handle_type {
-            HandleType::Strong(ref sender) => HandleUntyped::strong(self.id, sender.clone()),
+            HandleType::Strong(ref sender, _) => HandleUntyped::strong(self.id, sender.clone()),
             HandleType::Weak => HandleUntyped::weak(self.id),
         }
     }
 }
 
+/// A strong handle created or dropped, with the location of the code that created it, and the
+/// type of the asset if the handle is typed
 pub(crate) enum RefChange {
-    Increment(HandleId),
-    Decrement(HandleId),
+    Increment(HandleId, &'static Location<'static>, Option<&'static str>),
+    Decrement(HandleId, &'static Location<'static>),
 }
 
 #[derive(Clone)]
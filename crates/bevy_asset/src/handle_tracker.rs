@@ -0,0 +1,152 @@
+use crate::{AssetPath, HandleId};
+use bevy_utils::HashMap;
+use std::{fmt, panic::Location};
+
+/// The code locations that created the strong handles of each asset, tracked by the
+/// [`AssetServer`](crate::AssetServer) once enabled with
+/// [`track_handle_holders`](crate::AssetServer::track_handle_holders)
+#[derive(Default)]
+pub(crate) struct HandleHolders {
+    assets: HashMap<HandleId, TrackedAsset>,
+}
+
+#[derive(Default)]
+struct TrackedAsset {
+    type_name: Option<&'static str>,
+    holders: HashMap<&'static Location<'static>, usize>,
+}
+
+impl HandleHolders {
+    pub(crate) fn increment(
+        &mut self,
+        id: HandleId,
+        location: &'static Location<'static>,
+        type_name: Option<&'static str>,
+    ) {
+        let asset = self.assets.entry(id).or_default();
+        asset.type_name = asset.type_name.or(type_name);
+        *asset.holders.entry(location).or_default() += 1;
+    }
+
+    /// Removes a strong handle, ignoring the handles created before the tracking was enabled.
+    pub(crate) fn decrement(&mut self, id: HandleId, location: &'static Location<'static>) {
+        let Some(asset) = self.assets.get_mut(&id) else {
+            return;
+        };
+        if let Some(count) = asset.holders.get_mut(location) {
+            *count -= 1;
+            if *count == 0 {
+                asset.holders.remove(location);
+            }
+        }
+        if asset.holders.is_empty() {
+            self.assets.remove(&id);
+        }
+    }
+
+    pub(crate) fn type_name(&self, id: &HandleId) -> Option<&'static str> {
+        self.assets.get(id).and_then(|asset| asset.type_name)
+    }
+
+    /// The holders of the strong handles of an asset, the most handles first
+    pub(crate) fn holders(&self, id: &HandleId) -> Vec<HandleHolder> {
+        let Some(asset) = self.assets.get(id) else {
+            return Vec::new();
+        };
+        let mut holders: Vec<HandleHolder> = asset
+            .holders
+            .iter()
+            .map(|(location, count)| HandleHolder {
+                location,
+                count: *count,
+            })
+            .collect();
+        holders.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.location.to_string().cmp(&b.location.to_string()))
+        });
+        holders
+    }
+}
+
+/// The code that created strong handles of an asset still alive
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HandleHolder {
+    /// The location of the call that created the handles, like a call to
+    /// [`AssetServer::load`](crate::AssetServer::load) or to [`Clone::clone`]
+    pub location: &'static Location<'static>,
+    /// The number of handles created by that call that are still alive
+    pub count: usize,
+}
+
+/// The strong handles keeping an asset loaded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetHandles {
+    /// The id of the asset
+    pub id: HandleId,
+    /// The path of the asset, if it was loaded by the asset server
+    pub path: Option<AssetPath<'static>>,
+    /// The name of the type of the asset, if a typed handle was created while tracking the handles
+    pub type_name: Option<&'static str>,
+    /// The number of strong handles of the asset
+    pub strong_handles: usize,
+    /// Where the strong handles of the asset were created, the most handles first
+    ///
+    /// Empty unless the holders are tracked, and missing the handles created before that.
+    pub holders: Vec<HandleHolder>,
+}
+
+/// The assets kept alive by strong handles, returned by
+/// [`AssetServer::handle_report`](crate::AssetServer::handle_report)
+///
+/// The assets with the most strong handles come first. An asset whose handles are never released
+/// in a long session, like a handle cloned each frame into a collection that is never cleared,
+/// shows up with the location of the code creating them once the holders are tracked with
+/// [`AssetServer::track_handle_holders`](crate::AssetServer::track_handle_holders).
+///
+/// The report is formatted with one line per asset followed by its holders:
+///
+/// ```text
+/// 3 strong handles of "textures/player.png" (bevy_render::texture::image::Image)
+///     2 from src/player.rs:42:30
+///     1 from src/ui.rs:17:12
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HandleReport {
+    /// The assets with strong handles
+    pub assets: Vec<AssetHandles>,
+}
+
+impl HandleReport {
+    /// The number of strong handles of all the assets
+    pub fn total_strong_handles(&self) -> usize {
+        self.assets.iter().map(|asset| asset.strong_handles).sum()
+    }
+}
+
+impl fmt::Display for HandleReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for asset in &self.assets {
+            write!(f, "{} strong handles of ", asset.strong_handles)?;
+            match &asset.path {
+                Some(path) => {
+                    write!(f, "\"{}", path.path().display())?;
+                    if let Some(label) = path.label() {
+                        write!(f, "#{label}")?;
+                    }
+                    write!(f, "\"")?;
+                }
+                None => write!(f, "{:?}", asset.id)?,
+            }
+            if let Some(type_name) = asset.type_name {
+                write!(f, " ({type_name})")?;
+            }
+            writeln!(f)?;
+            for holder in &asset.holders {
+                writeln!(f, "    {} from {}", holder.count, holder.location)?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -8,6 +8,10 @@ use std::path::PathBuf;
 pub struct SourceMeta {
     /// A collection of asset metadata.
     pub assets: Vec<AssetMeta>,
+    /// The paths of the sources loaded directly by the loader of the source with
+    /// [`LoadContext::load_direct`](crate::LoadContext::load_direct), whose changes reload the
+    /// source.
+    pub direct_dependencies: Vec<AssetPath<'static>>,
 }
 
 /// Metadata for an asset.
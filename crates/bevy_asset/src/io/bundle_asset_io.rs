@@ -0,0 +1,543 @@
+use crate::{AssetIo, AssetIoError, AssetServer, FileType, Metadata};
+use anyhow::Result;
+use bevy_ecs::{event::EventWriter, system::Res};
+use bevy_utils::{BoxedFuture, HashMap};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    io,
+    ops::Range,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+const BUNDLE_MAGIC: &[u8; 8] = b"BEVYBNDL";
+const BUNDLE_VERSION: u32 = 1;
+/// The size of the header of the bundles, before their index
+const HEADER_SIZE: u64 = 24;
+
+/// Writes asset bundles, the files packing many assets read by an [`AssetBundleIo`]
+///
+/// A bundle starts with a header and an index of its assets, followed by the bytes of the assets:
+///
+/// - the header has the `BEVYBNDL` magic bytes, the version of the format as a little-endian `u32`,
+///   the size of the index as a `u32`, and a `u64` hash of the content of the bundle;
+/// - the index has the number of assets as a `u32`, then for each asset the size of its path as a
+///   `u32`, its UTF-8 path with `/` separators, and the offset and the size of its bytes in the
+///   bundle as `u64`s.
+///
+/// ```no_run
+/// # use bevy_asset::AssetBundleWriter;
+/// let mut bundle = AssetBundleWriter::default();
+/// bundle.add_directory("assets").unwrap();
+/// std::fs::write("web/assets.bundle", bundle.write()).unwrap();
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct AssetBundleWriter {
+    assets: Vec<(PathBuf, Vec<u8>)>,
+}
+
+impl AssetBundleWriter {
+    /// Adds the asset at `path`, relative to the root of the bundle, replacing the asset already
+    /// at that path.
+    pub fn add(&mut self, path: impl Into<PathBuf>, bytes: impl Into<Vec<u8>>) {
+        let path = path.into();
+        self.assets.retain(|(asset_path, _)| *asset_path != path);
+        self.assets.push((path, bytes.into()));
+    }
+
+    /// Adds the files of the directory at `root` and of its subdirectories, with their paths
+    /// relative to `root`.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn add_directory(&mut self, root: impl AsRef<Path>) -> io::Result<()> {
+        let root = root.as_ref();
+        let mut directories = vec![root.to_owned()];
+        while let Some(directory) = directories.pop() {
+            for entry in std::fs::read_dir(directory)? {
+                let path = entry?.path();
+                if path.is_dir() {
+                    directories.push(path);
+                } else {
+                    let bytes = std::fs::read(&path)?;
+                    self.add(path.strip_prefix(root).unwrap(), bytes);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes the bundle, with its assets sorted by path.
+    pub fn write(&self) -> Vec<u8> {
+        let mut assets: Vec<_> = self
+            .assets
+            .iter()
+            .map(|(path, bytes)| (bundle_path(path), bytes))
+            .collect();
+        assets.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let index_size = 4 + assets
+            .iter()
+            .map(|(path, _)| 4 + path.len() + 16)
+            .sum::<usize>();
+        let mut index = Vec::with_capacity(index_size);
+        index.extend_from_slice(&(assets.len() as u32).to_le_bytes());
+        let mut offset = HEADER_SIZE + index_size as u64;
+        for (path, bytes) in &assets {
+            index.extend_from_slice(&(path.len() as u32).to_le_bytes());
+            index.extend_from_slice(path.as_bytes());
+            index.extend_from_slice(&offset.to_le_bytes());
+            index.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+            offset += bytes.len() as u64;
+        }
+
+        let mut hash = Fnv1a::default();
+        hash.write(&index);
+        for (_, bytes) in &assets {
+            hash.write(bytes);
+        }
+
+        let mut bundle = Vec::with_capacity(offset as usize);
+        bundle.extend_from_slice(BUNDLE_MAGIC);
+        bundle.extend_from_slice(&BUNDLE_VERSION.to_le_bytes());
+        bundle.extend_from_slice(&(index.len() as u32).to_le_bytes());
+        bundle.extend_from_slice(&hash.0.to_le_bytes());
+        bundle.extend_from_slice(&index);
+        for (_, bytes) in assets {
+            bundle.extend_from_slice(bytes);
+        }
+        bundle
+    }
+}
+
+/// The path of an asset in a bundle, with `/` separators
+fn bundle_path(path: &Path) -> String {
+    path.components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(name.to_string_lossy()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The 64-bit FNV-1a hash, stable across platforms and versions
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = (self.0 ^ *byte as u64).wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+fn invalid_bundle(message: &str) -> AssetIoError {
+    AssetIoError::Io(io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid asset bundle: {message}"),
+    ))
+}
+
+/// The index of an asset bundle, with the ranges of the bytes of its assets
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetBundleIndex {
+    assets: HashMap<PathBuf, Range<u64>>,
+    hash: u64,
+}
+
+impl AssetBundleIndex {
+    /// Reads the header of a bundle, returning the range of its index.
+    fn read_header(header: &[u8]) -> Result<(Range<u64>, u64), AssetIoError> {
+        if header.len() < HEADER_SIZE as usize || &header[..8] != BUNDLE_MAGIC {
+            return Err(invalid_bundle("missing header"));
+        }
+        let version = u32::from_le_bytes(header[8..12].try_into().unwrap());
+        if version != BUNDLE_VERSION {
+            return Err(invalid_bundle(&format!("unsupported version {version}")));
+        }
+        let index_size = u32::from_le_bytes(header[12..16].try_into().unwrap()) as u64;
+        let hash = u64::from_le_bytes(header[16..24].try_into().unwrap());
+        Ok((HEADER_SIZE..HEADER_SIZE + index_size, hash))
+    }
+
+    /// Reads the index of a bundle.
+    fn read(index: &[u8], hash: u64) -> Result<Self, AssetIoError> {
+        let mut cursor = index;
+        let mut take = |size: usize| {
+            if cursor.len() < size {
+                return Err(invalid_bundle("truncated index"));
+            }
+            let (bytes, rest) = cursor.split_at(size);
+            cursor = rest;
+            Ok(bytes)
+        };
+        let count = u32::from_le_bytes(take(4)?.try_into().unwrap());
+        let mut assets = HashMap::default();
+        for _ in 0..count {
+            let path_size = u32::from_le_bytes(take(4)?.try_into().unwrap()) as usize;
+            let path = std::str::from_utf8(take(path_size)?)
+                .map_err(|_| invalid_bundle("the path of an asset isn't UTF-8"))?;
+            let offset = u64::from_le_bytes(take(8)?.try_into().unwrap());
+            let size = u64::from_le_bytes(take(8)?.try_into().unwrap());
+            let end = offset
+                .checked_add(size)
+                .ok_or_else(|| invalid_bundle("asset range overflows"))?;
+            assets.insert(PathBuf::from(path), offset..end);
+        }
+        Ok(Self { assets, hash })
+    }
+
+    /// Reads the index of the bundle from its `source`, with two range reads.
+    pub async fn fetch(source: &dyn BundleSource) -> Result<Self, AssetIoError> {
+        let header = source.read_range(0..HEADER_SIZE).await?;
+        let (index_range, hash) = Self::read_header(&header)?;
+        let index = source.read_range(index_range).await?;
+        Self::read(&index, hash)
+    }
+
+    /// The range of the bytes of the asset at `path` in the bundle
+    pub fn get(&self, path: &Path) -> Option<Range<u64>> {
+        self.assets.get(&PathBuf::from(bundle_path(path))).cloned()
+    }
+
+    /// Iterates over the paths of the assets of the bundle.
+    pub fn paths(&self) -> impl Iterator<Item = &Path> {
+        self.assets.keys().map(PathBuf::as_path)
+    }
+
+    /// The hash of the content of the bundle, which changes when any asset changes
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// The size of all the assets of the bundle, in bytes
+    pub fn total_bytes(&self) -> u64 {
+        self.assets
+            .values()
+            .map(|range| range.end - range.start)
+            .sum()
+    }
+}
+
+/// The bytes of an asset bundle read by an [`AssetBundleIo`], like a file served over HTTP
+///
+/// The assets are read with one range of bytes each, so that a bundle can be fetched asset by
+/// asset with HTTP range requests, like with the `HttpBundleSource` of web builds.
+pub trait BundleSource: Send + Sync + 'static {
+    /// Returns a future reading the `range` of bytes of the bundle.
+    fn read_range<'a>(
+        &'a self,
+        range: Range<u64>,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>>;
+}
+
+impl BundleSource for Vec<u8> {
+    fn read_range<'a>(
+        &'a self,
+        range: Range<u64>,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move { read_slice(self, range) })
+    }
+}
+
+/// The bytes of a bundle embedded in the executable with [`include_bytes!`]
+impl BundleSource for &'static [u8] {
+    fn read_range<'a>(
+        &'a self,
+        range: Range<u64>,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move { read_slice(self, range) })
+    }
+}
+
+fn read_slice(bytes: &[u8], range: Range<u64>) -> Result<Vec<u8>, AssetIoError> {
+    bytes
+        .get(range.start as usize..range.end as usize)
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| invalid_bundle("truncated bundle"))
+}
+
+/// A persistent cache of the assets fetched by an [`AssetBundleIo`], like the `IndexedDbCache`
+/// of web builds
+///
+/// The keys contain the hash of the content of the bundle, so that the assets of a bundle that
+/// changed are fetched again.
+pub trait BundleCache: Send + Sync + 'static {
+    /// Returns a future reading the bytes cached for `key`, or `None` if they weren't cached.
+    fn get<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Option<Vec<u8>>>;
+
+    /// Returns a future caching `bytes` for `key`.
+    fn put<'a>(&'a self, key: &'a str, bytes: &'a [u8]) -> BoxedFuture<'a, ()>;
+}
+
+/// I/O implementation reading the assets of an asset bundle, written by an
+/// [`AssetBundleWriter`], from a [`BundleSource`].
+///
+/// On the web, fetching thousands of small files makes thousands of requests. A bundle is a single
+/// file instead, whose index is fetched first, then each asset is fetched with one HTTP range
+/// request when it's loaded, and cached in `IndexedDB` for the next sessions:
+///
+/// ```ignore
+/// let bundle = AssetBundleIo::new(HttpBundleSource::new("assets.bundle"))
+///     .with_cache(IndexedDbCache::new("my_game_assets"));
+/// app.add_asset_source("bundle", bundle);
+/// // "bundle://textures/wall.png" is read from the bundle
+/// ```
+///
+/// An [`AssetBundleFetched`] event is sent for each asset read from a bundle added to the
+/// [`AssetServer`], with the progress of the bundle.
+///
+/// The index is fetched when the first asset is loaded, the directories of the bundle can only be
+/// listed once it's fetched. Watching for changes isn't supported.
+#[derive(Clone)]
+pub struct AssetBundleIo {
+    source: Arc<dyn BundleSource>,
+    cache: Option<Arc<dyn BundleCache>>,
+    index: Arc<RwLock<Option<Arc<AssetBundleIndex>>>>,
+    fetched: Arc<Mutex<BundleFetches>>,
+}
+
+#[derive(Default)]
+struct BundleFetches {
+    fetched_bytes: u64,
+    events: Vec<AssetBundleFetched>,
+}
+
+/// An event sent when an asset of an [`AssetBundleIo`] added to the [`AssetServer`] is read
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetBundleFetched {
+    /// The path of the asset, starting with the scheme of the bundle source if it has one
+    pub path: PathBuf,
+    /// The size of the asset, in bytes
+    pub bytes: u64,
+    /// Whether the asset was read from the [`BundleCache`] instead of its [`BundleSource`]
+    pub from_cache: bool,
+    /// The size of the assets of the bundle read so far, including this asset, in bytes
+    pub fetched_bytes: u64,
+    /// The size of all the assets of the bundle, in bytes
+    pub total_bytes: u64,
+}
+
+impl AssetBundleIo {
+    /// Creates an I/O reading the assets of the bundle of `source`.
+    pub fn new(source: impl BundleSource) -> Self {
+        Self {
+            source: Arc::new(source),
+            cache: None,
+            index: Default::default(),
+            fetched: Default::default(),
+        }
+    }
+
+    /// Caches the assets read from the source in `cache`, reading them from the cache when
+    /// they're loaded again.
+    #[must_use]
+    pub fn with_cache(mut self, cache: impl BundleCache) -> Self {
+        self.cache = Some(Arc::new(cache));
+        self
+    }
+
+    /// The index of the bundle, or `None` if it wasn't fetched yet
+    pub fn index(&self) -> Option<Arc<AssetBundleIndex>> {
+        self.index.read().clone()
+    }
+
+    /// Fetches the index of the bundle, if it wasn't fetched yet.
+    pub async fn fetch_index(&self) -> Result<Arc<AssetBundleIndex>, AssetIoError> {
+        if let Some(index) = self.index() {
+            return Ok(index);
+        }
+        let index = Arc::new(AssetBundleIndex::fetch(&*self.source).await?);
+        *self.index.write() = Some(index.clone());
+        Ok(index)
+    }
+}
+
+impl AssetIo for AssetBundleIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            let index = self.fetch_index().await?;
+            let Some(range) = index.get(path) else {
+                return Err(AssetIoError::NotFound(path.to_owned()));
+            };
+            let cache_key = format!("{:016x}/{}", index.hash, bundle_path(path));
+            let cached = match &self.cache {
+                Some(cache) => cache.get(&cache_key).await,
+                None => None,
+            };
+            let from_cache = cached.is_some();
+            let bytes = match cached {
+                Some(bytes) => bytes,
+                None => {
+                    let bytes = self.source.read_range(range).await?;
+                    if let Some(cache) = &self.cache {
+                        cache.put(&cache_key, &bytes).await;
+                    }
+                    bytes
+                }
+            };
+
+            let mut fetched = self.fetched.lock();
+            fetched.fetched_bytes += bytes.len() as u64;
+            let event = AssetBundleFetched {
+                path: path.to_owned(),
+                bytes: bytes.len() as u64,
+                from_cache,
+                fetched_bytes: fetched.fetched_bytes,
+                total_bytes: index.total_bytes(),
+            };
+            fetched.events.push(event);
+            Ok(bytes)
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        if !self.is_dir(path) {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        let index = self.index().unwrap();
+        let mut entries: Vec<PathBuf> = Vec::new();
+        for asset_path in index.paths() {
+            let Ok(relative_path) = asset_path.strip_prefix(path) else {
+                continue;
+            };
+            // the files in the directory, and its subdirectories
+            if let Some(name) = relative_path.components().next() {
+                let entry = path.join(name);
+                if !entries.contains(&entry) {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries.sort();
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn get_metadata(&self, path: &Path) -> Result<Metadata, AssetIoError> {
+        let Some(index) = self.index() else {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        };
+        if index.get(path).is_some() {
+            Ok(Metadata::new(FileType::File))
+        } else if index
+            .paths()
+            .any(|asset_path| asset_path != path && asset_path.starts_with(path))
+        {
+            Ok(Metadata::new(FileType::Directory))
+        } else {
+            Err(AssetIoError::NotFound(path.to_owned()))
+        }
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        bevy_log::warn!("Watching for changes is not supported by asset bundles");
+        Ok(())
+    }
+}
+
+/// A system sending the [`AssetBundleFetched`] events of the [`AssetBundleIo`]s of the
+/// [`AssetServer`].
+pub fn asset_bundle_io_system(
+    asset_server: Res<AssetServer>,
+    mut events: EventWriter<AssetBundleFetched>,
+) {
+    asset_server.for_each_source(|scheme, asset_io| {
+        if let Some(bundle) = asset_io.downcast_ref::<AssetBundleIo>() {
+            let fetched = std::mem::take(&mut bundle.fetched.lock().events);
+            events.send_batch(fetched.into_iter().map(|mut event| {
+                if let Some(scheme) = scheme {
+                    event.path = PathBuf::from(format!("{scheme}://")).join(event.path);
+                }
+                event
+            }));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct MemoryCache(Mutex<HashMap<String, Vec<u8>>>);
+
+    impl BundleCache for Arc<MemoryCache> {
+        fn get<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Option<Vec<u8>>> {
+            Box::pin(async move { self.0.lock().get(key).cloned() })
+        }
+
+        fn put<'a>(&'a self, key: &'a str, bytes: &'a [u8]) -> BoxedFuture<'a, ()> {
+            Box::pin(async move {
+                self.0.lock().insert(key.to_string(), bytes.to_vec());
+            })
+        }
+    }
+
+    #[test]
+    fn bundle_roundtrip() {
+        let mut writer = AssetBundleWriter::default();
+        writer.add("textures/wall.png", b"wall".as_slice());
+        writer.add("textures/floor.png", b"old floor".as_slice());
+        writer.add("textures/floor.png", b"floor".as_slice());
+        writer.add("levels/1.ron", b"(level: 1)".as_slice());
+        let cache = Arc::<MemoryCache>::default();
+        let bundle = AssetBundleIo::new(writer.write()).with_cache(cache.clone());
+        let load = |path: &str| {
+            futures_lite::future::block_on(bundle.load_path(Path::new(path))).map_err(|_| ())
+        };
+
+        // the directories are listed once the index is fetched
+        assert!(bundle.read_directory(Path::new("textures")).is_err());
+        assert_eq!(load("textures/wall.png"), Ok(b"wall".to_vec()));
+        assert_eq!(load("textures/floor.png"), Ok(b"floor".to_vec()));
+        assert_eq!(load("textures/floor.png"), Ok(b"floor".to_vec()));
+        assert_eq!(load("missing.png"), Err(()));
+        let entries: Vec<_> = bundle.read_directory(Path::new("")).unwrap().collect();
+        assert_eq!(entries, ["levels", "textures"].map(PathBuf::from).to_vec());
+        assert!(bundle.is_file(Path::new("levels/1.ron")));
+
+        let events = std::mem::take(&mut bundle.fetched.lock().events);
+        assert_eq!(
+            events
+                .iter()
+                .map(|event| (event.bytes, event.from_cache, event.fetched_bytes))
+                .collect::<Vec<_>>(),
+            [(4, false, 4), (5, false, 9), (5, true, 14)]
+        );
+        assert_eq!(events[0].total_bytes, 19);
+        assert_eq!(cache.0.lock().len(), 2);
+
+        // the cache keys change with the content of the bundle
+        writer.add("textures/wall.png", b"new wall".as_slice());
+        let bundle = AssetBundleIo::new(writer.write()).with_cache(cache.clone());
+        let bytes =
+            futures_lite::future::block_on(bundle.load_path(Path::new("textures/wall.png")));
+        assert_eq!(bytes.unwrap(), b"new wall");
+        assert_eq!(cache.0.lock().len(), 3);
+
+        let invalid = AssetBundleIo::new(b"not a bundle".to_vec());
+        assert!(futures_lite::future::block_on(invalid.load_path(Path::new("a.png"))).is_err());
+    }
+
+    #[test]
+    fn overflowing_index_is_invalid() {
+        let mut index = 1u32.to_le_bytes().to_vec();
+        index.extend(5u32.to_le_bytes());
+        index.extend(b"a.png");
+        index.extend(u64::MAX.to_le_bytes());
+        index.extend(2u64.to_le_bytes());
+        assert!(AssetBundleIndex::read(&index, 0).is_err());
+    }
+}
@@ -0,0 +1,147 @@
+use crate::{AssetIo, AssetIoError, FileType, Metadata};
+use anyhow::Result;
+use bevy_utils::{BoxedFuture, HashMap};
+use parking_lot::RwLock;
+use std::{
+    borrow::Cow,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// I/O implementation for assets stored in memory, like the assets embedded in the executable.
+///
+/// The assets are added with their path, usually from the bytes of an [`include_bytes!`], and the
+/// I/O is then registered as an asset source, so that the assets are loaded like any other:
+///
+/// ```
+/// # use bevy_asset::*;
+/// # use bevy_app::*;
+/// # let mut app = App::new();
+/// # app.add_plugin(bevy_core::TaskPoolPlugin::default());
+/// # app.add_plugin(AssetPlugin::default());
+/// let embedded = EmbeddedAssetIo::default();
+/// embedded.insert("shaders/outline.wgsl", b"// the source of the shader".as_slice());
+/// app.add_asset_source("embedded", embedded);
+/// // the shader can now be loaded from "embedded://shaders/outline.wgsl"
+/// ```
+///
+/// The clones of the I/O share its assets, so that assets can still be added once it's registered.
+#[derive(Clone, Default)]
+pub struct EmbeddedAssetIo {
+    assets: Arc<RwLock<HashMap<PathBuf, Cow<'static, [u8]>>>>,
+}
+
+impl EmbeddedAssetIo {
+    /// Adds the asset at `path`, relative to the root of the source, replacing the asset already
+    /// at that path.
+    pub fn insert(&self, path: impl Into<PathBuf>, bytes: impl Into<Cow<'static, [u8]>>) {
+        self.assets.write().insert(path.into(), bytes.into());
+    }
+
+    /// Removes the asset at `path`, returning whether there was one.
+    pub fn remove(&self, path: &Path) -> bool {
+        self.assets.write().remove(path).is_some()
+    }
+}
+
+impl AssetIo for EmbeddedAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            self.assets
+                .read()
+                .get(path)
+                .map(|bytes| bytes.to_vec())
+                .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn save_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            self.insert(path, bytes.to_vec());
+            Ok(())
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        if !self.is_dir(path) {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        let mut entries: Vec<PathBuf> = Vec::new();
+        for asset_path in self.assets.read().keys() {
+            let Ok(relative_path) = asset_path.strip_prefix(path) else {
+                continue;
+            };
+            // the files in the directory, and its subdirectories
+            if let Some(name) = relative_path.components().next() {
+                let entry = path.join(name);
+                if !entries.contains(&entry) {
+                    entries.push(entry);
+                }
+            }
+        }
+        entries.sort();
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn get_metadata(&self, path: &Path) -> Result<Metadata, AssetIoError> {
+        let assets = self.assets.read();
+        if assets.contains_key(path) {
+            Ok(Metadata::new(FileType::File))
+        } else if assets
+            .keys()
+            .any(|asset_path| asset_path != path && asset_path.starts_with(path))
+        {
+            Ok(Metadata::new(FileType::Directory))
+        } else {
+            Err(AssetIoError::NotFound(path.to_owned()))
+        }
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_directories() {
+        let embedded = EmbeddedAssetIo::default();
+        embedded.insert("icon.png", b"icon".as_slice());
+        embedded.insert("shaders/outline.wgsl", b"outline".as_slice());
+        embedded.insert("shaders/blur.wgsl", b"blur".as_slice());
+        embedded.insert("shaders/post/fxaa.wgsl", b"fxaa".as_slice());
+
+        assert!(embedded.is_file(Path::new("icon.png")));
+        assert!(embedded.is_dir(Path::new("shaders")));
+        assert!(embedded.is_dir(Path::new("")));
+        assert!(embedded.get_metadata(Path::new("missing.png")).is_err());
+
+        let entries: Vec<_> = embedded
+            .read_directory(Path::new("shaders"))
+            .unwrap()
+            .collect();
+        assert_eq!(
+            entries,
+            ["shaders/blur.wgsl", "shaders/outline.wgsl", "shaders/post"]
+                .map(PathBuf::from)
+                .to_vec()
+        );
+
+        let bytes = futures_lite::future::block_on(embedded.load_path(Path::new("icon.png")));
+        assert_eq!(bytes.unwrap(), b"icon");
+    }
+}
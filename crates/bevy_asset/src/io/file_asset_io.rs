@@ -109,6 +109,21 @@ impl AssetIo for FileAssetIo {
         })
     }
 
+    fn save_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            let full_path = self.root_path.join(path);
+            if let Some(parent) = full_path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(full_path, bytes)?;
+            Ok(())
+        })
+    }
+
     fn read_directory(
         &self,
         path: &Path,
@@ -170,12 +185,11 @@ impl AssetIo for FileAssetIo {
 ))]
 pub fn filesystem_watcher_system(asset_server: Res<AssetServer>) {
     let mut changed = HashSet::default();
-    let asset_io =
-        if let Some(asset_io) = asset_server.server.asset_io.downcast_ref::<FileAssetIo>() {
-            asset_io
-        } else {
-            return;
-        };
+    let asset_io = if let Some(asset_io) = asset_server.asset_io().downcast_ref::<FileAssetIo>() {
+        asset_io
+    } else {
+        return;
+    };
     let watcher = asset_io.filesystem_watcher.read();
     if let Some(ref watcher) = *watcher {
         loop {
@@ -184,8 +198,22 @@ pub fn filesystem_watcher_system(asset_server: Res<AssetServer>) {
                 Err(TryRecvError::Empty) => break,
                 Err(TryRecvError::Disconnected) => panic!("FilesystemWatcher disconnected."),
             };
+            use notify::event::{EventKind, ModifyKind};
+            // adding or removing files reloads the loaded folders containing them
+            if matches!(
+                event.kind,
+                EventKind::Create(_)
+                    | EventKind::Remove(_)
+                    | EventKind::Modify(ModifyKind::Name(_))
+            ) {
+                for path in &event.paths {
+                    if let Ok(relative_path) = path.strip_prefix(&asset_io.root_path) {
+                        asset_server.reload_folders_containing(relative_path);
+                    }
+                }
+            }
             if let notify::event::Event {
-                kind: notify::event::EventKind::Modify(_),
+                kind: EventKind::Modify(_),
                 paths,
                 ..
             } = event
@@ -193,7 +221,12 @@ pub fn filesystem_watcher_system(asset_server: Res<AssetServer>) {
                 for path in &paths {
                     if !changed.contains(path) {
                         let relative_path = path.strip_prefix(&asset_io.root_path).unwrap();
-                        let _ = asset_server.load_untracked(relative_path.into(), true);
+                        // a change to the meta file of an asset reloads the asset
+                        let relative_path = relative_path
+                            .to_str()
+                            .and_then(|path| path.strip_suffix(".meta"))
+                            .map_or(relative_path, Path::new);
+                        asset_server.reload_changed_path(relative_path);
                     }
                 }
                 changed.extend(paths);
@@ -0,0 +1,328 @@
+use crate::{AssetIo, AssetIoError, AssetPath, AssetServer, Metadata};
+use anyhow::Result;
+use bevy_ecs::{event::EventWriter, system::Res};
+use bevy_utils::{BoxedFuture, HashSet};
+use parking_lot::{Mutex, RwLock};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+/// I/O implementation stacking asset sources in layers, where the files of the layers mounted
+/// last override the files at the same paths in the layers below, like mods overriding the
+/// assets of a game.
+///
+/// Any asset I/O can be mounted as a layer, like a [`FileAssetIo`](crate::FileAssetIo) reading a
+/// directory, or a custom I/O reading an archive. Layers can be mounted and unmounted at runtime:
+/// the [`AssetServer`] then reloads the assets whose files changed, and sends an
+/// [`AssetLayerChanged`] event.
+///
+/// ```
+/// # use bevy_asset::*;
+/// # use bevy_app::*;
+/// # let mut app = App::new();
+/// # app.add_plugin(bevy_core::TaskPoolPlugin::default());
+/// # app.add_plugin(AssetPlugin::default());
+/// let base = EmbeddedAssetIo::default();
+/// base.insert("textures/wall.png", b"the base wall".as_slice());
+/// let layers = LayeredAssetIo::default();
+/// layers.mount("base", base);
+/// app.add_asset_source("game", layers.clone());
+///
+/// // later, when a mod is enabled
+/// let mod_assets = EmbeddedAssetIo::default();
+/// mod_assets.insert("textures/wall.png", b"the wall of the mod".as_slice());
+/// layers.mount("mod", mod_assets);
+/// // "game://textures/wall.png" is now read from the mod, and reloaded
+/// ```
+///
+/// The clones of the I/O share its layers, so that layers can still be mounted once it's
+/// registered.
+#[derive(Clone, Default)]
+pub struct LayeredAssetIo {
+    layers: Arc<RwLock<Vec<(String, Arc<dyn AssetIo>)>>>,
+    changes: Arc<Mutex<Vec<LayerChange>>>,
+}
+
+/// A layer mounted or unmounted, and the files it changed
+struct LayerChange {
+    layer: String,
+    kind: AssetLayerChange,
+    paths: Vec<PathBuf>,
+}
+
+/// Whether a layer of a [`LayeredAssetIo`] was mounted or unmounted
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AssetLayerChange {
+    /// The layer was mounted over the other layers.
+    Mounted,
+    /// The layer was unmounted.
+    Unmounted,
+}
+
+/// An event sent when a layer of a [`LayeredAssetIo`] added to the [`AssetServer`] is mounted or
+/// unmounted
+#[derive(Clone, Debug)]
+pub struct AssetLayerChanged {
+    /// The name of the layer
+    pub layer: String,
+    /// Whether the layer was mounted or unmounted
+    pub change: AssetLayerChange,
+    /// The paths of the files read from another layer since the change, which are reloaded
+    pub paths: Vec<AssetPath<'static>>,
+}
+
+impl LayeredAssetIo {
+    /// Mounts `asset_io` as the layer `name`, over the layers already mounted.
+    ///
+    /// A layer already mounted with that name is unmounted first.
+    pub fn mount(&self, name: impl Into<String>, asset_io: impl AssetIo) {
+        let name = name.into();
+        self.unmount(&name);
+        let asset_io: Arc<dyn AssetIo> = Arc::new(asset_io);
+        let mut paths = Vec::new();
+        list_files(&*asset_io, Path::new(""), &mut paths);
+        self.layers.write().push((name.clone(), asset_io));
+        self.changes.lock().push(LayerChange {
+            layer: name,
+            kind: AssetLayerChange::Mounted,
+            paths,
+        });
+    }
+
+    /// Unmounts the layer `name`, returning whether it was mounted.
+    pub fn unmount(&self, name: &str) -> bool {
+        let mut layers = self.layers.write();
+        let Some(index) = layers.iter().position(|(layer, _)| layer == name) else {
+            return false;
+        };
+        let (name, asset_io) = layers.remove(index);
+        // the files overridden by the layers above didn't change
+        let mut paths = Vec::new();
+        list_files(&*asset_io, Path::new(""), &mut paths);
+        paths.retain(|path| {
+            !layers[index..]
+                .iter()
+                .any(|(_, asset_io)| asset_io.is_file(path))
+        });
+        self.changes.lock().push(LayerChange {
+            layer: name,
+            kind: AssetLayerChange::Unmounted,
+            paths,
+        });
+        true
+    }
+
+    /// The names of the mounted layers, from the bottom to the top
+    pub fn layers(&self) -> Vec<String> {
+        self.layers
+            .read()
+            .iter()
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+
+    /// The name of the layer the file at `path` is read from, or `None` if no layer has the file
+    pub fn layer_of(&self, path: &Path) -> Option<String> {
+        self.layers
+            .read()
+            .iter()
+            .rev()
+            .find(|(_, asset_io)| asset_io.is_file(path))
+            .map(|(name, _)| name.clone())
+    }
+
+    /// The asset I/Os of the layers, from the top to the bottom
+    fn layers_from_top(&self) -> Vec<Arc<dyn AssetIo>> {
+        let layers = self.layers.read();
+        layers
+            .iter()
+            .rev()
+            .map(|(_, asset_io)| asset_io.clone())
+            .collect()
+    }
+}
+
+/// Lists the files of the directory at `path` of `asset_io` and of its subdirectories.
+fn list_files(asset_io: &dyn AssetIo, path: &Path, files: &mut Vec<PathBuf>) {
+    let Ok(entries) = asset_io.read_directory(path) else {
+        return;
+    };
+    for entry in entries {
+        if asset_io.is_dir(&entry) {
+            list_files(asset_io, &entry, files);
+        } else {
+            files.push(entry);
+        }
+    }
+}
+
+impl AssetIo for LayeredAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            for asset_io in self.layers_from_top() {
+                match asset_io.load_path(path).await {
+                    Err(AssetIoError::NotFound(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    /// Saves the file in the top layer supporting saving.
+    fn save_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move {
+            for asset_io in self.layers_from_top() {
+                match asset_io.save_path(path, bytes).await {
+                    Err(AssetIoError::SaveNotSupported(_)) => continue,
+                    result => return result,
+                }
+            }
+            Err(AssetIoError::SaveNotSupported(path.to_owned()))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let mut found = false;
+        let mut entries = HashSet::default();
+        for asset_io in self.layers_from_top() {
+            if let Ok(layer_entries) = asset_io.read_directory(path) {
+                found = true;
+                entries.extend(layer_entries);
+            }
+        }
+        if !found {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+        let mut entries: Vec<PathBuf> = entries.into_iter().collect();
+        entries.sort();
+        Ok(Box::new(entries.into_iter()))
+    }
+
+    fn get_metadata(&self, path: &Path) -> Result<Metadata, AssetIoError> {
+        self.layers_from_top()
+            .iter()
+            .find_map(|asset_io| asset_io.get_metadata(path).ok())
+            .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+    }
+
+    /// Watches the path in all the layers, failing only if no layer can watch it.
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        let mut result = Err(AssetIoError::PathWatchError(path.to_owned()));
+        for asset_io in self.layers_from_top() {
+            match asset_io.watch_path_for_changes(path) {
+                Ok(()) => result = Ok(()),
+                Err(err) if result.is_err() => result = Err(err),
+                Err(_) => {}
+            }
+        }
+        result
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        for asset_io in self.layers_from_top() {
+            asset_io.watch_for_changes()?;
+        }
+        Ok(())
+    }
+}
+
+/// A system reloading the assets whose files changed when layers of the [`LayeredAssetIo`]s of
+/// the [`AssetServer`] are mounted or unmounted, and sending the [`AssetLayerChanged`] events.
+pub fn layered_asset_io_system(
+    asset_server: Res<AssetServer>,
+    mut events: EventWriter<AssetLayerChanged>,
+) {
+    let mut changes = Vec::new();
+    asset_server.for_each_source(|scheme, asset_io| {
+        if let Some(layered) = asset_io.downcast_ref::<LayeredAssetIo>() {
+            let root = scheme.map(|scheme| PathBuf::from(format!("{scheme}://")));
+            changes.extend(
+                std::mem::take(&mut *layered.changes.lock())
+                    .into_iter()
+                    .map(|change| (root.clone(), change)),
+            );
+        }
+    });
+
+    for (root, change) in changes {
+        let paths: Vec<AssetPath<'static>> = change
+            .paths
+            .into_iter()
+            .map(|path| match &root {
+                Some(root) => root.join(path),
+                None => path,
+            })
+            .map(|path| AssetPath::new(path, None))
+            .collect();
+        for path in &paths {
+            asset_server.reload_folders_containing(path.path());
+            asset_server.reload_changed_path(path.path());
+        }
+        events.send(AssetLayerChanged {
+            layer: change.layer,
+            change: change.kind,
+            paths,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EmbeddedAssetIo;
+
+    #[test]
+    fn layered_overrides() {
+        let base = EmbeddedAssetIo::default();
+        base.insert("icon.png", b"base icon".as_slice());
+        base.insert("textures/wall.png", b"base wall".as_slice());
+        let mod_assets = EmbeddedAssetIo::default();
+        mod_assets.insert("textures/wall.png", b"mod wall".as_slice());
+        mod_assets.insert("textures/floor.png", b"mod floor".as_slice());
+
+        let layered = LayeredAssetIo::default();
+        layered.mount("base", base);
+        layered.mount("mod", mod_assets);
+        assert_eq!(layered.layers(), ["base", "mod"]);
+        let load = |path: &str| {
+            futures_lite::future::block_on(layered.load_path(Path::new(path))).map_err(|_| ())
+        };
+        assert_eq!(load("textures/wall.png"), Ok(b"mod wall".to_vec()));
+        assert_eq!(load("icon.png"), Ok(b"base icon".to_vec()));
+        assert_eq!(load("missing.png"), Err(()));
+        assert_eq!(
+            layered.layer_of(Path::new("textures/wall.png")).as_deref(),
+            Some("mod")
+        );
+
+        let entries: Vec<_> = layered
+            .read_directory(Path::new("textures"))
+            .unwrap()
+            .collect();
+        assert_eq!(
+            entries,
+            ["textures/floor.png", "textures/wall.png"]
+                .map(PathBuf::from)
+                .to_vec()
+        );
+
+        // unmounting a layer changes the files it provided
+        layered.changes.lock().clear();
+        assert!(layered.unmount("mod"));
+        assert!(!layered.unmount("mod"));
+        assert_eq!(load("textures/wall.png"), Ok(b"base wall".to_vec()));
+        assert_eq!(load("textures/floor.png"), Err(()));
+        let changes = layered.changes.lock();
+        assert_eq!(changes[0].kind, AssetLayerChange::Unmounted);
+        assert_eq!(changes[0].paths.len(), 2);
+    }
+}
@@ -4,8 +4,14 @@ mod android_asset_io;
 mod file_asset_io;
 #[cfg(target_arch = "wasm32")]
 mod wasm_asset_io;
+#[cfg(target_arch = "wasm32")]
+mod wasm_bundle_io;
 
+mod bundle_asset_io;
+mod embedded_asset_io;
+mod layered_asset_io;
 mod metadata;
+mod scheme_asset_io;
 
 #[cfg(target_os = "android")]
 pub use android_asset_io::*;
@@ -13,8 +19,14 @@ pub use android_asset_io::*;
 pub use file_asset_io::*;
 #[cfg(target_arch = "wasm32")]
 pub use wasm_asset_io::*;
+#[cfg(target_arch = "wasm32")]
+pub use wasm_bundle_io::*;
 
+pub use bundle_asset_io::*;
+pub use embedded_asset_io::*;
+pub use layered_asset_io::*;
 pub use metadata::*;
+pub use scheme_asset_io::*;
 
 use anyhow::Result;
 use bevy_utils::BoxedFuture;
@@ -39,6 +51,14 @@ pub enum AssetIoError {
     /// Failed to watch path.
     #[error("failed to watch path: {0}")]
     PathWatchError(PathBuf),
+
+    /// The asset I/O doesn't support saving assets.
+    #[error("saving assets is not supported, cannot save: {0}")]
+    SaveNotSupported(PathBuf),
+
+    /// No asset source was added for the URI scheme of the path.
+    #[error("no asset source found for the scheme: {0}")]
+    UnknownScheme(String),
 }
 
 /// A storage provider for an [`AssetServer`].
@@ -48,6 +68,10 @@ pub enum AssetIoError {
 /// can easily use your own custom I/O to, for example, load assets from cloud storage or create a
 /// seamless VFS layout using custom containers.
 ///
+/// The asset I/O can also be added as the source of the paths starting with a URI scheme with
+/// [`AssetServer::add_source`], next to the default one, or be mounted as a layer of a
+/// [`LayeredAssetIo`] overriding the files of other asset I/Os.
+///
 /// See the [`custom_asset_io`]  example in the repository for more details.
 ///
 /// [`AssetServer`]: struct.AssetServer.html
@@ -56,6 +80,18 @@ pub trait AssetIo: Downcast + Send + Sync + 'static {
     /// Returns a future to load the full file data at the provided path.
     fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>>;
 
+    /// Returns a future to write the full file data at the provided path, replacing the file
+    /// already there.
+    ///
+    /// Saving is unsupported by default.
+    fn save_path<'a>(
+        &'a self,
+        path: &'a Path,
+        _bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        Box::pin(async move { Err(AssetIoError::SaveNotSupported(path.to_owned())) })
+    }
+
     /// Returns an iterator of directory entry names at the provided path.
     fn read_directory(
         &self,
@@ -0,0 +1,167 @@
+use crate::{AssetIo, AssetIoError, Metadata};
+use anyhow::Result;
+use bevy_utils::{BoxedFuture, HashMap};
+use parking_lot::RwLock;
+use std::{
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
+
+/// Splits a path starting with a URI scheme, like `pak://textures/wall.png`, into its scheme and
+/// the path in the asset source of the scheme.
+///
+/// The separator after the scheme can have fewer slashes than `://`, as joining paths to the
+/// parent of a path like `pak://wall.png` removes them. Paths whose first component is a single
+/// letter, like the Windows drive `C:`, don't have a scheme.
+pub fn split_asset_scheme(path: &Path) -> Option<(&str, PathBuf)> {
+    let mut components = path.components();
+    let Some(Component::Normal(first)) = components.next() else {
+        return None;
+    };
+    let scheme = first.to_str()?.strip_suffix(':')?;
+    if scheme.len() < 2
+        || !scheme
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+    {
+        return None;
+    }
+    Some((scheme, components.collect()))
+}
+
+/// The [`AssetIo`] of an [`AssetServer`](crate::AssetServer), reading the paths starting with a
+/// URI scheme from the asset source registered for that scheme, and the other paths from the
+/// default asset I/O
+pub(crate) struct SchemeAssetIo {
+    default: Box<dyn AssetIo>,
+    sources: RwLock<HashMap<String, Arc<dyn AssetIo>>>,
+}
+
+impl SchemeAssetIo {
+    pub(crate) fn new(default: Box<dyn AssetIo>) -> Self {
+        Self {
+            default,
+            sources: Default::default(),
+        }
+    }
+
+    /// The asset I/O of the paths without a scheme
+    pub(crate) fn default_io(&self) -> &dyn AssetIo {
+        &*self.default
+    }
+
+    pub(crate) fn add_source(&self, scheme: String, asset_io: Arc<dyn AssetIo>) {
+        self.sources.write().insert(scheme, asset_io);
+    }
+
+    /// Calls `f` with the default asset I/O, then with the scheme and the asset I/O of each
+    /// asset source.
+    pub(crate) fn for_each_source(&self, mut f: impl FnMut(Option<&str>, &dyn AssetIo)) {
+        f(None, self.default_io());
+        for (scheme, asset_io) in self.sources.read().iter() {
+            f(Some(scheme), &**asset_io);
+        }
+    }
+
+    /// The asset source of the scheme of `path` and the path in that source, or `None` for the
+    /// paths read from the default asset I/O
+    fn source(&self, path: &Path) -> Result<Option<(Arc<dyn AssetIo>, PathBuf)>, AssetIoError> {
+        let Some((scheme, source_path)) = split_asset_scheme(path) else {
+            return Ok(None);
+        };
+        match self.sources.read().get(scheme) {
+            Some(asset_io) => Ok(Some((asset_io.clone(), source_path))),
+            None => Err(AssetIoError::UnknownScheme(scheme.to_string())),
+        }
+    }
+}
+
+impl AssetIo for SchemeAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        match self.source(path) {
+            Ok(None) => self.default.load_path(path),
+            Ok(Some((asset_io, source_path))) => {
+                Box::pin(async move { asset_io.load_path(&source_path).await })
+            }
+            Err(err) => Box::pin(async move { Err(err) }),
+        }
+    }
+
+    fn save_path<'a>(
+        &'a self,
+        path: &'a Path,
+        bytes: &'a [u8],
+    ) -> BoxedFuture<'a, Result<(), AssetIoError>> {
+        match self.source(path) {
+            Ok(None) => self.default.save_path(path, bytes),
+            Ok(Some((asset_io, source_path))) => {
+                Box::pin(async move { asset_io.save_path(&source_path, bytes).await })
+            }
+            Err(err) => Box::pin(async move { Err(err) }),
+        }
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let Some((asset_io, source_path)) = self.source(path)? else {
+            return self.default.read_directory(path);
+        };
+        // the entries are relative to the root of the source, without the scheme
+        let (scheme, _) = split_asset_scheme(path).unwrap();
+        let root = PathBuf::from(format!("{scheme}://"));
+        Ok(Box::new(
+            asset_io
+                .read_directory(&source_path)?
+                .map(move |entry| root.join(entry)),
+        ))
+    }
+
+    fn get_metadata(&self, path: &Path) -> Result<Metadata, AssetIoError> {
+        match self.source(path)? {
+            None => self.default.get_metadata(path),
+            Some((asset_io, source_path)) => asset_io.get_metadata(&source_path),
+        }
+    }
+
+    fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        match self.source(path)? {
+            None => self.default.watch_path_for_changes(path),
+            Some((asset_io, source_path)) => asset_io.watch_path_for_changes(&source_path),
+        }
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        self.default.watch_for_changes()?;
+        for asset_io in self.sources.read().values() {
+            asset_io.watch_for_changes()?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn asset_schemes() {
+        assert_eq!(
+            split_asset_scheme(Path::new("pak://textures/wall.png")),
+            Some(("pak", PathBuf::from("textures/wall.png")))
+        );
+        assert_eq!(
+            split_asset_scheme(
+                &Path::new("embedded://icon.png")
+                    .parent()
+                    .unwrap()
+                    .join("a.png")
+            ),
+            Some(("embedded", PathBuf::from("a.png")))
+        );
+        assert_eq!(split_asset_scheme(Path::new("textures/wall.png")), None);
+        assert_eq!(split_asset_scheme(Path::new("c:/textures/wall.png")), None);
+        assert_eq!(split_asset_scheme(Path::new("/pak://wall.png")), None);
+    }
+}
@@ -0,0 +1,178 @@
+use crate::{AssetIoError, BundleCache, BundleSource};
+use bevy_utils::BoxedFuture;
+use js_sys::{Promise, Uint8Array};
+use std::{io, ops::Range, path::PathBuf};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Event, Headers, IdbDatabase, IdbOpenDbRequest, IdbRequest, IdbTransactionMode, Request,
+    RequestInit, Response,
+};
+
+fn js_error(err: JsValue) -> AssetIoError {
+    AssetIoError::Io(io::Error::new(io::ErrorKind::Other, format!("{err:?}")))
+}
+
+/// A [`BundleSource`] fetching the asset bundle at `url` with HTTP range requests, reading one
+/// asset per request
+///
+/// The server must support range requests, like most static file servers. When it doesn't, each
+/// request downloads the whole bundle.
+pub struct HttpBundleSource {
+    url: String,
+}
+
+impl HttpBundleSource {
+    /// Creates a source fetching the bundle at `url`, relative to the page.
+    pub fn new(url: impl Into<String>) -> Self {
+        Self { url: url.into() }
+    }
+}
+
+impl BundleSource for HttpBundleSource {
+    fn read_range<'a>(
+        &'a self,
+        range: Range<u64>,
+    ) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            if range.is_empty() {
+                return Ok(Vec::new());
+            }
+            let headers = Headers::new().map_err(js_error)?;
+            headers
+                .set("Range", &format!("bytes={}-{}", range.start, range.end - 1))
+                .map_err(js_error)?;
+            let init = RequestInit::new();
+            init.set_headers(&headers);
+            let request = Request::new_with_str_and_init(&self.url, &init).map_err(js_error)?;
+            let window = web_sys::window().unwrap();
+            let response: Response = JsFuture::from(window.fetch_with_request(&request))
+                .await
+                .map_err(js_error)?
+                .dyn_into()
+                .map_err(js_error)?;
+            let status = response.status();
+            if status == 404 {
+                return Err(AssetIoError::NotFound(PathBuf::from(&self.url)));
+            }
+            if status != 200 && status != 206 {
+                return Err(AssetIoError::Io(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!("fetching {} returned the status {status}", self.url),
+                )));
+            }
+            let data = JsFuture::from(response.array_buffer().map_err(js_error)?)
+                .await
+                .map_err(js_error)?;
+            let bytes = Uint8Array::new(&data).to_vec();
+            if status == 206 {
+                return Ok(bytes);
+            }
+            // the server ignored the range and sent the whole bundle
+            bytes
+                .get(range.start as usize..range.end as usize)
+                .map(<[u8]>::to_vec)
+                .ok_or_else(|| AssetIoError::NotFound(PathBuf::from(&self.url)))
+        })
+    }
+}
+
+/// A [`BundleCache`] storing the assets in an `IndexedDB` database of the browser, so that they're
+/// only downloaded once across sessions
+///
+/// The database is created when the first asset is cached. The errors of the database are
+/// logged, and the assets are then downloaded again.
+pub struct IndexedDbCache {
+    database: String,
+}
+
+const OBJECT_STORE: &str = "assets";
+
+impl IndexedDbCache {
+    /// Creates a cache storing the assets in the `IndexedDB` database named `database`.
+    pub fn new(database: impl Into<String>) -> Self {
+        Self {
+            database: database.into(),
+        }
+    }
+
+    async fn open(&self) -> Result<IdbDatabase, JsValue> {
+        let factory = web_sys::window()
+            .unwrap()
+            .indexed_db()?
+            .ok_or_else(|| JsValue::from_str("IndexedDB is not supported"))?;
+        let request = factory.open_with_u32(&self.database, 1)?;
+        let on_upgrade_needed = Closure::<dyn FnMut(Event)>::new(|event: Event| {
+            let database = event
+                .target()
+                .and_then(|target| target.dyn_into::<IdbOpenDbRequest>().ok())
+                .and_then(|request| request.result().ok())
+                .and_then(|database| database.dyn_into::<IdbDatabase>().ok());
+            if let Some(database) = database {
+                let _ = database.create_object_store(OBJECT_STORE);
+            }
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.as_ref().unchecked_ref()));
+        let database = request_result(&request).await;
+        drop(on_upgrade_needed);
+        database?.dyn_into()
+    }
+
+    async fn get_bytes(&self, key: &str) -> Result<Option<Vec<u8>>, JsValue> {
+        let database = self.open().await?;
+        let request = database
+            .transaction_with_str(OBJECT_STORE)?
+            .object_store(OBJECT_STORE)?
+            .get(&JsValue::from_str(key))?;
+        let bytes = request_result(&request).await?;
+        if bytes.is_undefined() {
+            return Ok(None);
+        }
+        Ok(Some(Uint8Array::new(&bytes).to_vec()))
+    }
+
+    async fn put_bytes(&self, key: &str, bytes: &[u8]) -> Result<(), JsValue> {
+        let database = self.open().await?;
+        let request = database
+            .transaction_with_str_and_mode(OBJECT_STORE, IdbTransactionMode::Readwrite)?
+            .object_store(OBJECT_STORE)?
+            .put_with_key(&Uint8Array::from(bytes), &JsValue::from_str(key))?;
+        request_result(&request).await?;
+        Ok(())
+    }
+}
+
+/// Waits for the result of an `IndexedDB` request.
+async fn request_result(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    let result = JsFuture::from(promise).await;
+    request.set_onsuccess(None);
+    request.set_onerror(None);
+    result?;
+    request.result()
+}
+
+impl BundleCache for IndexedDbCache {
+    fn get<'a>(&'a self, key: &'a str) -> BoxedFuture<'a, Option<Vec<u8>>> {
+        Box::pin(async move {
+            match self.get_bytes(key).await {
+                Ok(bytes) => bytes,
+                Err(err) => {
+                    bevy_log::warn!("failed to read {key} from the IndexedDB cache: {err:?}");
+                    None
+                }
+            }
+        })
+    }
+
+    fn put<'a>(&'a self, key: &'a str, bytes: &'a [u8]) -> BoxedFuture<'a, ()> {
+        Box::pin(async move {
+            if let Err(err) = self.put_bytes(key, bytes).await {
+                bevy_log::warn!("failed to write {key} to the IndexedDB cache: {err:?}");
+            }
+        })
+    }
+}
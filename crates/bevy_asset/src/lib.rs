@@ -12,6 +12,7 @@
 
 mod asset_server;
 mod assets;
+mod collection;
 #[cfg(feature = "debug_asset_server")]
 pub mod debug_asset_server;
 pub mod diagnostic;
@@ -20,18 +21,25 @@ pub mod diagnostic;
     all(not(target_arch = "wasm32"), not(target_os = "android"))
 ))]
 mod filesystem_watcher;
+mod folder;
 mod handle;
+mod handle_tracker;
 mod info;
 mod io;
 mod loader;
+mod loading;
 mod path;
 mod reflect;
+mod retention;
+mod saver;
 
 /// The `bevy_asset` prelude.
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        AddAsset, AssetEvent, AssetPlugin, AssetServer, Assets, Handle, HandleUntyped,
+        AddAsset, AddAssetCollection, AssetCollection, AssetEvent, AssetPlugin, AssetReloaded,
+        AssetServer, Assets, Handle, HandleUntyped, LoadedFolder, LoadingFinished, LoadingProgress,
+        LoadingTracker,
     };
 }
 
@@ -39,12 +47,18 @@ pub use anyhow::Error;
 pub use asset_server::*;
 pub use assets::*;
 pub use bevy_utils::BoxedFuture;
+pub use collection::*;
+pub use folder::*;
 pub use handle::*;
+pub use handle_tracker::*;
 pub use info::*;
 pub use io::*;
 pub use loader::*;
+pub use loading::*;
 pub use path::*;
 pub use reflect::*;
+pub use retention::*;
+pub use saver::*;
 
 use bevy_app::prelude::*;
 use bevy_ecs::prelude::*;
@@ -106,7 +120,15 @@ impl Plugin for AssetPlugin {
             app.insert_resource(asset_server);
         }
 
-        app.register_type::<HandleId>();
+        app.register_type::<HandleId>()
+            .init_resource::<LoadingTracker>()
+            .init_resource::<LoadingProgress>()
+            .init_resource::<AssetStats>()
+            .add_event::<LoadingFinished>()
+            .add_event::<AssetReloaded>()
+            .add_event::<AssetLayerChanged>()
+            .add_event::<AssetBundleFetched>()
+            .add_asset::<LoadedFolder>();
 
         app.configure_set(
             AssetSet::LoadAssets
@@ -118,7 +140,12 @@ impl Plugin for AssetPlugin {
                 .after(CoreSet::PostUpdate)
                 .before(CoreSet::Last),
         )
-        .add_system(asset_server::free_unused_assets_system.in_base_set(CoreSet::PreUpdate));
+        .add_system(asset_server::create_loaded_folders_system.in_base_set(CoreSet::First))
+        .add_system(asset_server::asset_reloaded_system.in_base_set(CoreSet::First))
+        .add_system(asset_server::free_unused_assets_system.in_base_set(CoreSet::PreUpdate))
+        .add_system(loading::loading_tracker_system.in_base_set(CoreSet::PreUpdate))
+        .add_system(io::layered_asset_io_system.in_base_set(AssetSet::LoadAssets))
+        .add_system(io::asset_bundle_io_system.in_base_set(AssetSet::LoadAssets));
 
         #[cfg(all(
             feature = "filesystem_watcher",
@@ -1,6 +1,6 @@
 use crate::{
-    path::AssetPath, AssetIo, AssetIoError, AssetMeta, AssetServer, Assets, Handle, HandleId,
-    RefChangeChannel,
+    path::AssetPath, AssetIo, AssetIoError, AssetMeta, AssetServer, AssetServerError, Assets,
+    Handle, HandleId, RefChangeChannel,
 };
 use anyhow::Error;
 use anyhow::Result;
@@ -9,7 +9,12 @@ use bevy_reflect::{TypeUuid, TypeUuidDynamic};
 use bevy_utils::{BoxedFuture, HashMap};
 use crossbeam_channel::{Receiver, Sender};
 use downcast_rs::{impl_downcast, Downcast};
-use std::path::Path;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    any::Any,
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+};
 
 /// A loader for an asset source.
 ///
@@ -27,6 +32,31 @@ pub trait AssetLoader: Send + Sync + 'static {
     fn extensions(&self) -> &[&str];
 }
 
+/// The settings of an [`AssetLoader`] for an asset, like whether an image is in the sRGB color
+/// space.
+///
+/// The settings of an asset are read in the loader with [`LoadContext::settings`]. They start
+/// from their [`Default`] and are replaced by the settings of the meta file of the asset, if there
+/// is one, then are modified by the settings passed to
+/// [`AssetServer::load_with_settings`]. The meta file of an asset is next to it, with the `.meta`
+/// extension added to its name, like `hero.png.meta`, and contains its settings in RON:
+///
+/// ```ron
+/// (
+///     is_srgb: false,
+///     sampler: Nearest,
+/// )
+/// ```
+///
+/// This trait is implemented for any type that can be serialized and has a default.
+pub trait LoaderSettings: Serialize + DeserializeOwned + Default + Send + Sync + 'static {}
+
+impl<S> LoaderSettings for S where S: Serialize + DeserializeOwned + Default + Send + Sync + 'static {}
+
+/// The settings passed to [`AssetServer::load_with_settings`], modifying the [`LoaderSettings`]
+/// of the type they were passed for
+pub(crate) type SettingsOverride = Arc<dyn Fn(&mut dyn Any) + Send + Sync>;
+
 /// An essential piece of data of an application.
 ///
 /// Assets are the building blocks of games. They can be anything, from images and sounds to scenes
@@ -119,12 +149,22 @@ impl<T: Asset> From<LoadedAsset<T>> for BoxedLoadedAsset {
 /// An asset source can define one or more assets from a single source path. The main asset is set
 /// using [`LoadContext::set_default_asset`] and sub-assets are defined with
 /// [`LoadContext::set_labeled_asset`].
+///
+/// The assets of other sources can be used by the assets of the context, like the textures of a
+/// material file, with their path relative to the source. They're either loaded by the asset
+/// server after the source with [`LoadContext::load`], or loaded and awaited by the loader with
+/// [`LoadContext::load_direct`].
 pub struct LoadContext<'a> {
     pub(crate) ref_change_channel: &'a RefChangeChannel,
     pub(crate) asset_io: &'a dyn AssetIo,
+    pub(crate) asset_server: &'a AssetServer,
     pub(crate) labeled_assets: HashMap<Option<String>, BoxedLoadedAsset>,
     pub(crate) path: &'a Path,
     pub(crate) version: usize,
+    pub(crate) meta: Option<&'a [u8]>,
+    pub(crate) settings_override: Option<SettingsOverride>,
+    pub(crate) dependencies: Vec<AssetPath<'static>>,
+    pub(crate) direct_dependencies: Vec<AssetPath<'static>>,
 }
 
 impl<'a> LoadContext<'a> {
@@ -132,14 +172,22 @@ impl<'a> LoadContext<'a> {
         path: &'a Path,
         ref_change_channel: &'a RefChangeChannel,
         asset_io: &'a dyn AssetIo,
+        asset_server: &'a AssetServer,
         version: usize,
+        meta: Option<&'a [u8]>,
+        settings_override: Option<SettingsOverride>,
     ) -> Self {
         Self {
             ref_change_channel,
             asset_io,
+            asset_server,
             labeled_assets: Default::default(),
             version,
             path,
+            meta,
+            settings_override,
+            dependencies: Vec::new(),
+            direct_dependencies: Vec::new(),
         }
     }
 
@@ -159,6 +207,7 @@ impl<'a> LoadContext<'a> {
     }
 
     /// Sets a secondary asset loaded from the asset source.
+    #[track_caller]
     pub fn set_labeled_asset<T: Asset>(&mut self, label: &str, asset: LoadedAsset<T>) -> Handle<T> {
         assert!(!label.is_empty());
         self.labeled_assets
@@ -167,22 +216,164 @@ impl<'a> LoadContext<'a> {
     }
 
     /// Gets a handle to an asset of type `T` from its id.
+    #[track_caller]
     pub fn get_handle<I: Into<HandleId>, T: Asset>(&self, id: I) -> Handle<T> {
         Handle::strong(id.into(), self.ref_change_channel.sender.clone())
     }
 
+    /// Gets the path of an asset from its path relative to the directory of the source, like the
+    /// path of a texture referenced by a material file.
+    ///
+    /// The `..` components of the path go to the parent directories, and the label of the path
+    /// is kept.
+    pub fn resolve_path<'b, P: Into<AssetPath<'b>>>(&self, path: P) -> AssetPath<'static> {
+        let path: AssetPath = path.into();
+        let mut resolved_path = PathBuf::new();
+        let directory = self.path.parent().unwrap_or_else(|| Path::new(""));
+        for component in directory.components().chain(path.path().components()) {
+            match component {
+                Component::CurDir => {}
+                Component::ParentDir => {
+                    resolved_path.pop();
+                }
+                component => resolved_path.push(component),
+            }
+        }
+        AssetPath::new(resolved_path, path.label().map(ToString::to_string))
+    }
+
+    /// Gets a strong handle to the asset at `path`, relative to the directory of the source, and
+    /// adds it as a dependency of the assets of the source.
+    ///
+    /// The asset is loaded by the asset server once the loader finishes, like the other
+    /// dependencies. See [`resolve_path`](Self::resolve_path).
+    #[track_caller]
+    pub fn load<'b, T: Asset, P: Into<AssetPath<'b>>>(&mut self, path: P) -> Handle<T> {
+        let path = self.resolve_path(path);
+        let handle = self.get_handle(path.get_id());
+        if !self.dependencies.contains(&path) {
+            self.dependencies.push(path);
+        }
+        handle
+    }
+
+    /// Loads the asset at `path`, relative to the directory of the source, with its loader and
+    /// returns it, so that it can be used while loading the assets of the source.
+    ///
+    /// The asset is the default asset of its source, or its labeled asset if the path has a
+    /// label, and isn't added to its [`Assets`]. The other assets of its source are dropped,
+    /// except the dependencies they loaded with [`load`](Self::load), which are loaded after the
+    /// source. The source is reloaded when the asset changes. See
+    /// [`resolve_path`](Self::resolve_path).
+    ///
+    /// # Errors
+    ///
+    /// Fails when the asset can't be loaded, or isn't of type `T`.
+    pub async fn load_direct<'b, T: Asset, P: Into<AssetPath<'b>>>(
+        &mut self,
+        path: P,
+    ) -> Result<T, AssetServerError> {
+        let path = self.resolve_path(path);
+        let loader = self.asset_server.get_path_asset_loader(path.path())?;
+        let bytes = self.asset_io.load_path(path.path()).await?;
+        let meta = self.asset_server.load_meta(path.path()).await?;
+        let settings_override = self
+            .asset_server
+            .get_settings_override(path.get_id().source_path_id());
+
+        let mut load_context = LoadContext::new(
+            path.path(),
+            self.ref_change_channel,
+            self.asset_io,
+            self.asset_server,
+            self.version,
+            meta.as_deref(),
+            settings_override,
+        );
+        loader
+            .load(&bytes, &mut load_context)
+            .await
+            .map_err(AssetServerError::AssetLoaderError)?;
+
+        // the dependencies of the loaded assets are loaded after this source
+        for dependency in load_context
+            .labeled_assets
+            .values()
+            .flat_map(|asset| &asset.dependencies)
+            .chain(&load_context.dependencies)
+        {
+            if !self.dependencies.contains(dependency) {
+                self.dependencies.push(dependency.clone());
+            }
+        }
+        let source_path = AssetPath::new(path.path().to_owned(), None);
+        for direct_dependency in load_context
+            .direct_dependencies
+            .drain(..)
+            .chain(std::iter::once(source_path))
+        {
+            if !self.direct_dependencies.contains(&direct_dependency) {
+                self.direct_dependencies.push(direct_dependency);
+            }
+        }
+
+        let label = path.label().map(ToString::to_string);
+        let asset = load_context
+            .labeled_assets
+            .remove(&label)
+            .and_then(|asset| asset.value)
+            .ok_or_else(|| {
+                AssetServerError::MissingLoadedAsset(match &label {
+                    Some(label) => format!("{}#{}", path.path().display(), label),
+                    None => path.path().display().to_string(),
+                })
+            })?;
+        asset
+            .downcast::<T>()
+            .map(|asset| *asset)
+            .map_err(|_| AssetServerError::IncorrectHandleType)
+    }
+
     /// Reads the contents of the file at the specified path through the [`AssetIo`] associated
     /// with this context.
     pub async fn read_asset_bytes<P: AsRef<Path>>(&self, path: P) -> Result<Vec<u8>, AssetIoError> {
         self.asset_io.load_path(path.as_ref()).await
     }
 
+    /// Gets the [`LoaderSettings`] of the asset, read from its meta file and modified by the
+    /// settings passed to [`AssetServer::load_with_settings`].
+    ///
+    /// The settings passed when loading the asset are only applied if they were passed for the
+    /// type `S`.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the meta file of the asset doesn't contain settings of type `S`.
+    pub fn settings<S: LoaderSettings>(&self) -> Result<S, Error> {
+        let mut settings = match self.meta {
+            Some(meta) => ron::de::from_bytes(meta).map_err(|err| {
+                anyhow::anyhow!(
+                    "invalid settings in the meta file of {}: {}",
+                    self.path.display(),
+                    err
+                )
+            })?,
+            None => S::default(),
+        };
+        if let Some(settings_override) = &self.settings_override {
+            settings_override(&mut settings);
+        }
+        Ok(settings)
+    }
+
     /// Generates metadata for the assets managed by this load context.
     pub fn get_asset_metas(&self) -> Vec<AssetMeta> {
         let mut asset_metas = Vec::new();
         for (label, asset) in &self.labeled_assets {
+            let mut dependencies = asset.dependencies.clone();
+            dependencies.extend(self.dependencies.iter().cloned());
             asset_metas.push(AssetMeta {
-                dependencies: asset.dependencies.clone(),
+                dependencies,
                 label: label.clone(),
                 type_uuid: asset.value.as_ref().unwrap().type_uuid(),
             });
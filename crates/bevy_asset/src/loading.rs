@@ -0,0 +1,265 @@
+use crate::{AssetServer, Handle, HandleId, LoadState, LoadedFolder};
+use bevy_ecs::{
+    event::EventWriter,
+    system::{Res, ResMut, Resource},
+};
+use bevy_utils::HashMap;
+use std::path::Path;
+
+/// Identifies a group of assets tracked by the [`LoadingTracker`], like the assets of a level
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct LoadingToken(u32);
+
+/// The number of assets of a group that were loaded, or failed to load
+///
+/// As a resource, it's the progress of all the groups of the [`LoadingTracker`], updated by
+/// [`loading_tracker_system`].
+#[derive(Resource, Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct LoadingProgress {
+    /// The assets loaded with all their dependencies
+    pub loaded: usize,
+    /// The assets tracked
+    pub total: usize,
+    /// The assets that failed to load, or that have a dependency that failed to load
+    pub failed: usize,
+}
+
+impl LoadingProgress {
+    /// The part of the assets that finished loading, successfully or not, from 0 to 1
+    ///
+    /// An empty group is finished, with a fraction of 1.
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            (self.loaded + self.failed) as f32 / self.total as f32
+        }
+    }
+
+    /// Returns `true` if all the assets finished loading, successfully or not.
+    pub fn is_finished(&self) -> bool {
+        self.loaded + self.failed >= self.total
+    }
+
+    /// Returns `true` if all the assets were loaded successfully.
+    pub fn is_loaded(&self) -> bool {
+        self.loaded >= self.total
+    }
+}
+
+impl std::ops::Add for LoadingProgress {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Self {
+            loaded: self.loaded + other.loaded,
+            total: self.total + other.total,
+            failed: self.failed + other.failed,
+        }
+    }
+}
+
+/// An event sent once all the assets tracked for a [`LoadingToken`] finished loading, successfully
+/// or not
+#[derive(Clone, Debug)]
+pub struct LoadingFinished {
+    /// The group of assets
+    pub token: LoadingToken,
+    /// The progress of the group, with the number of assets that failed to load
+    pub progress: LoadingProgress,
+}
+
+#[derive(Debug, Default)]
+struct LoadingGroup {
+    handles: Vec<HandleId>,
+    progress: LoadingProgress,
+    finished: bool,
+}
+
+/// Tracks the loading of groups of assets, like the assets needed by a loading screen
+///
+/// The assets of a group are added with [`track`](Self::track) to the [`LoadingToken`] of the
+/// group. An asset is loaded once its [recursive load state] is [`LoadState::Loaded`], so that
+/// all its dependencies are loaded too. The progress of the groups is updated each frame before
+/// [`CoreSet::Update`](bevy_app::CoreSet::Update), and a [`LoadingFinished`] event is sent once
+/// all the assets of a group finished loading.
+///
+/// The tracker only keeps the ids of the assets, so their strong handles still have to be kept
+/// for them to stay loaded.
+///
+/// [recursive load state]: AssetServer::get_recursive_load_state
+#[derive(Resource, Debug, Default)]
+pub struct LoadingTracker {
+    groups: HashMap<LoadingToken, LoadingGroup>,
+    next_token: u32,
+}
+
+impl LoadingTracker {
+    /// Creates a new empty group of assets to track.
+    pub fn new_token(&mut self) -> LoadingToken {
+        let token = LoadingToken(self.next_token);
+        self.next_token += 1;
+        self.groups.insert(token, LoadingGroup::default());
+        token
+    }
+
+    /// Adds an asset to the group of `token`.
+    ///
+    /// A group that finished loading sends a new [`LoadingFinished`] event once the asset is
+    /// loaded. Tracking an asset for a removed token does nothing.
+    pub fn track<H: Into<HandleId>>(&mut self, token: LoadingToken, handle: H) {
+        self.track_all(token, [handle.into()]);
+    }
+
+    /// Adds assets to the group of `token`, like the handles of a [`LoadedFolder`].
+    ///
+    /// See [`track`](Self::track).
+    pub fn track_all(&mut self, token: LoadingToken, handles: impl IntoIterator<Item = HandleId>) {
+        let Some(group) = self.groups.get_mut(&token) else {
+            return;
+        };
+        for handle in handles {
+            if !group.handles.contains(&handle) {
+                group.handles.push(handle);
+                group.progress.total += 1;
+                group.finished = false;
+            }
+        }
+    }
+
+    /// Loads a folder with [`AssetServer::load_folder`], and adds it to the group of `token`.
+    ///
+    /// The folder is loaded once all its assets finished loading.
+    #[must_use = "not using the returned strong handle may result in the unexpected release of the assets"]
+    #[track_caller]
+    pub fn track_folder<P: AsRef<Path>>(
+        &mut self,
+        token: LoadingToken,
+        asset_server: &AssetServer,
+        path: P,
+    ) -> Handle<LoadedFolder> {
+        let handle = asset_server.load_folder(path);
+        self.track(token, &handle);
+        handle
+    }
+
+    /// Stops tracking the group of `token`.
+    pub fn remove(&mut self, token: LoadingToken) {
+        self.groups.remove(&token);
+    }
+
+    /// The progress of the group of `token`, updated each frame, or `None` if it was removed
+    pub fn progress(&self, token: LoadingToken) -> Option<LoadingProgress> {
+        self.groups.get(&token).map(|group| group.progress)
+    }
+
+    /// The progress of all the groups tracked
+    pub fn total_progress(&self) -> LoadingProgress {
+        self.groups
+            .values()
+            .fold(LoadingProgress::default(), |progress, group| {
+                progress + group.progress
+            })
+    }
+}
+
+/// The system that updates the progress of the groups of the [`LoadingTracker`], and sends the
+/// [`LoadingFinished`] events
+pub fn loading_tracker_system(
+    asset_server: Res<AssetServer>,
+    mut tracker: ResMut<LoadingTracker>,
+    mut total_progress: ResMut<LoadingProgress>,
+    mut finished_events: EventWriter<LoadingFinished>,
+) {
+    // only borrow the tracker mutably while some of its groups are loading
+    if tracker.groups.values().any(|group| !group.finished) {
+        for (token, group) in &mut tracker.groups {
+            if group.finished {
+                continue;
+            }
+            let mut progress = LoadingProgress {
+                total: group.handles.len(),
+                ..Default::default()
+            };
+            for handle in &group.handles {
+                match asset_server.get_recursive_load_state(*handle) {
+                    LoadState::Loaded => progress.loaded += 1,
+                    LoadState::Failed => progress.failed += 1,
+                    _ => {}
+                }
+            }
+            group.progress = progress;
+            if progress.is_finished() {
+                group.finished = true;
+                finished_events.send(LoadingFinished {
+                    token: *token,
+                    progress,
+                });
+            }
+        }
+    }
+
+    let progress = tracker.total_progress();
+    if *total_progress != progress {
+        *total_progress = progress;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AssetPath;
+
+    #[test]
+    fn loading_progress() {
+        let progress = LoadingProgress {
+            loaded: 2,
+            total: 4,
+            failed: 1,
+        };
+        assert_eq!(progress.fraction(), 0.75);
+        assert!(!progress.is_finished());
+        assert!(LoadingProgress::default().is_finished());
+
+        assert_eq!(
+            progress
+                + LoadingProgress {
+                    loaded: 1,
+                    total: 1,
+                    failed: 0,
+                },
+            LoadingProgress {
+                loaded: 3,
+                total: 5,
+                failed: 1,
+            }
+        );
+
+        let failed = LoadingProgress {
+            loaded: 1,
+            total: 2,
+            failed: 1,
+        };
+        assert!(failed.is_finished());
+        assert!(!failed.is_loaded());
+    }
+
+    #[test]
+    fn tracked_groups() {
+        let mut tracker = LoadingTracker::default();
+        let level = tracker.new_token();
+        let menu = tracker.new_token();
+        let image: HandleId = AssetPath::from("image.png").into();
+        let sound: HandleId = AssetPath::from("sound.ogg").into();
+        tracker.track_all(level, [image, sound, image]);
+        tracker.track(menu, image);
+
+        assert_eq!(tracker.progress(level).unwrap().total, 2);
+        assert_eq!(tracker.total_progress().total, 3);
+
+        tracker.remove(menu);
+        tracker.track(menu, sound);
+        assert_eq!(tracker.progress(menu), None);
+        assert_eq!(tracker.total_progress().total, 2);
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{Asset, AssetServer, Assets};
+use bevy_ecs::system::{Res, ResMut, Resource};
+use bevy_utils::{HashMap, Uuid};
+
+/// How long the [`AssetServer`] keeps the assets of a type without strong handles in memory
+///
+/// The policy of an asset type is set with
+/// [`set_asset_retention_policy`](crate::AddAsset::set_asset_retention_policy). The assets kept
+/// without strong handles are used again when they're loaded again, without reading their source.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum RetentionPolicy {
+    /// The assets are freed once they have no strong handles.
+    #[default]
+    KeepUntilUnused,
+    /// The assets are never freed by the asset server, even without strong handles.
+    KeepForever,
+    /// The assets without strong handles are kept while their estimated size stays under a
+    /// budget, freeing the least recently used first.
+    ///
+    /// The sizes of the assets are estimated by the [size estimator] of their [`Assets`].
+    ///
+    /// [size estimator]: Assets::set_size_estimator
+    Budget {
+        /// The estimated size of the unused assets kept, in bytes
+        max_bytes: usize,
+    },
+}
+
+/// The number of assets of a type, and the memory they're estimated to use
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AssetTypeStats {
+    /// The name of the asset type
+    pub type_name: &'static str,
+    /// The assets in the [`Assets`] of the type
+    pub count: usize,
+    /// The assets kept by their [`RetentionPolicy`] without strong handles
+    pub unused: usize,
+    /// The memory used by the assets as estimated by their [size estimator], in bytes
+    ///
+    /// [size estimator]: Assets::set_size_estimator
+    pub estimated_bytes: usize,
+}
+
+/// The [`AssetTypeStats`] of each asset type added to the app, updated each frame by
+/// [`asset_stats_system`]
+///
+/// The totals can be added to the [`Diagnostics`](bevy_diagnostic::Diagnostics) with the
+/// [`AssetStatsDiagnosticsPlugin`](crate::diagnostic::AssetStatsDiagnosticsPlugin).
+#[derive(Resource, Clone, Debug, Default)]
+pub struct AssetStats {
+    types: HashMap<Uuid, AssetTypeStats>,
+}
+
+impl AssetStats {
+    /// The stats of the assets of type `T`, or `None` if it wasn't added to the app
+    pub fn get<T: Asset>(&self) -> Option<&AssetTypeStats> {
+        self.types.get(&T::TYPE_UUID)
+    }
+
+    /// Iterates over the stats of all the asset types.
+    pub fn iter(&self) -> impl Iterator<Item = &AssetTypeStats> {
+        self.types.values()
+    }
+
+    /// The number of assets of all types
+    pub fn total_count(&self) -> usize {
+        self.iter().map(|stats| stats.count).sum()
+    }
+
+    /// The number of assets of all types kept without strong handles
+    pub fn total_unused(&self) -> usize {
+        self.iter().map(|stats| stats.unused).sum()
+    }
+
+    /// The estimated memory used by the assets of all types, in bytes
+    pub fn total_estimated_bytes(&self) -> usize {
+        self.iter().map(|stats| stats.estimated_bytes).sum()
+    }
+}
+
+/// Updates the [`AssetTypeStats`] of the assets of type `T` in the [`AssetStats`].
+pub fn asset_stats_system<T: Asset>(
+    asset_server: Res<AssetServer>,
+    assets: Res<Assets<T>>,
+    mut asset_stats: ResMut<AssetStats>,
+) {
+    let stats = AssetTypeStats {
+        type_name: std::any::type_name::<T>(),
+        count: assets.len(),
+        unused: asset_server.unused_asset_count(T::TYPE_UUID),
+        estimated_bytes: assets.estimated_size(),
+    };
+    // only borrow the stats mutably when they changed
+    if asset_stats.types.get(&T::TYPE_UUID) != Some(&stats) {
+        asset_stats.types.insert(T::TYPE_UUID, stats);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::Asset;
+use anyhow::Error;
+use bevy_utils::Uuid;
+use std::any::Any;
+
+/// A saver for assets of a type.
+///
+/// Types implementing this trait are used by the asset server to write the assets back to an
+/// asset source, in a format that an [`AssetLoader`](crate::AssetLoader) can load again, like
+/// the scenes or the images modified in an editor.
+pub trait AssetSaver: Send + Sync + 'static {
+    /// The type of the assets saved.
+    type Asset: Asset;
+
+    /// Writes the asset in the format of the saver, like the contents of a file.
+    fn save(&self, asset: &Self::Asset) -> Result<Vec<u8>, Error>;
+
+    /// Returns a list of extensions supported by this asset saver, without the preceding dot.
+    fn extensions(&self) -> &[&str];
+}
+
+/// An [`AssetSaver`] for any type of assets, stored by the asset server
+pub(crate) trait ErasedAssetSaver: Send + Sync + 'static {
+    /// Writes the asset, which has to be of the type of the saver.
+    fn save(&self, asset: &dyn Any) -> Result<Vec<u8>, Error>;
+
+    fn asset_type_uuid(&self) -> Uuid;
+
+    fn extensions(&self) -> &[&str];
+}
+
+impl<S: AssetSaver> ErasedAssetSaver for S {
+    fn save(&self, asset: &dyn Any) -> Result<Vec<u8>, Error> {
+        let asset = asset
+            .downcast_ref::<S::Asset>()
+            .expect("the asset should have the type of the saver");
+        AssetSaver::save(self, asset)
+    }
+
+    fn asset_type_uuid(&self) -> Uuid {
+        <S::Asset as bevy_reflect::TypeUuid>::TYPE_UUID
+    }
+
+    fn extensions(&self) -> &[&str] {
+        AssetSaver::extensions(self)
+    }
+}
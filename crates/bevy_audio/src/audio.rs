@@ -1,7 +1,8 @@
-use crate::{AudioSink, AudioSource, Decodable, SpatialAudioSink};
+use crate::{AudioBus, AudioSink, AudioSource, Decodable, LoopRegion, SpatialAudioSink};
 use bevy_asset::{Asset, Handle, HandleId};
 use bevy_ecs::system::Resource;
 use bevy_math::Vec3;
+use bevy_time::Time;
 use bevy_transform::prelude::Transform;
 use parking_lot::RwLock;
 use std::{collections::VecDeque, fmt};
@@ -128,9 +129,9 @@ where
     /// transform, an ear on each side separated by `gap`. The audio emitter will placed at
     /// `emitter`.
     ///
-    /// `bevy_audio` is not using HRTF for spatial audio, but is transforming the sound to a mono
-    /// track, and then changing the level of each stereo channel according to the distance between
-    /// the emitter and each ear by amplifying the difference between what the two ears hear.
+    /// `bevy_audio` transforms the sound to a mono track, then attenuates it with the distance
+    /// between the emitter and the listener and pans it between the ears. The
+    /// [`SpatialPanning::Binaural`](crate::SpatialPanning::Binaural) panning approximates an HRTF.
     ///
     /// ```
     /// # use bevy_ecs::system::Res;
@@ -203,9 +204,9 @@ where
     /// transform, an ear on each side separated by `gap`. The audio emitter is placed at
     /// `emitter`.
     ///
-    /// `bevy_audio` is not using HRTF for spatial audio, but is transforming the sound to a mono
-    /// track, and then changing the level of each stereo channel according to the distance between
-    /// the emitter and each ear by amplifying the difference between what the two ears hear.
+    /// `bevy_audio` transforms the sound to a mono track, then attenuates it with the distance
+    /// between the emitter and the listener and pans it between the ears. The
+    /// [`SpatialPanning::Binaural`](crate::SpatialPanning::Binaural) panning approximates an HRTF.
     ///
     /// ```
     /// # use bevy_ecs::system::Res;
@@ -256,10 +257,19 @@ where
 pub struct PlaybackSettings {
     /// Play in repeat
     pub repeat: bool,
+    /// The region played in repeat when [`repeat`](Self::repeat) is set, after the part of the
+    /// sound before it. The whole sound is repeated when it's `None`.
+    pub loop_region: Option<LoopRegion>,
     /// Volume to play at.
     pub volume: f32,
-    /// Speed to play at.
+    /// Speed to play at, changing the pitch with the speed.
     pub speed: f32,
+    /// Play at the speed multiplied by the [`GlobalAudioSpeed`], like the sound effects of a
+    /// game slowed down by its [`Time`]. It can be disabled for the sounds that shouldn't be
+    /// slowed down, like the music or the sounds of the menus.
+    pub global_speed: bool,
+    /// Bus of the [`AudioMixer`](crate::AudioMixer) to play into.
+    pub bus: AudioBus,
 }
 
 impl Default for PlaybackSettings {
@@ -272,17 +282,30 @@ impl PlaybackSettings {
     /// Will play the associate audio source once.
     pub const ONCE: PlaybackSettings = PlaybackSettings {
         repeat: false,
+        loop_region: None,
         volume: 1.0,
         speed: 1.0,
+        global_speed: true,
+        bus: AudioBus::MASTER,
     };
 
     /// Will play the associate audio source in a loop.
     pub const LOOP: PlaybackSettings = PlaybackSettings {
         repeat: true,
+        loop_region: None,
         volume: 1.0,
         speed: 1.0,
+        global_speed: true,
+        bus: AudioBus::MASTER,
     };
 
+    /// Helper to play the sound until the end of `region`, then repeat the region.
+    pub const fn with_loop_region(mut self, region: LoopRegion) -> Self {
+        self.repeat = true;
+        self.loop_region = Some(region);
+        self
+    }
+
     /// Helper to set the volume from start of playback.
     pub const fn with_volume(mut self, volume: f32) -> Self {
         self.volume = volume;
@@ -294,6 +317,65 @@ impl PlaybackSettings {
         self.speed = speed;
         self
     }
+
+    /// Helper to set if the speed is multiplied by the [`GlobalAudioSpeed`].
+    pub const fn with_global_speed(mut self, global_speed: bool) -> Self {
+        self.global_speed = global_speed;
+        self
+    }
+
+    /// Helper to set the bus to play into.
+    pub const fn with_bus(mut self, bus: AudioBus) -> Self {
+        self.bus = bus;
+        self
+    }
+
+    /// The region played in a loop, if the sound is repeated
+    pub(crate) fn played_loop(&self) -> Option<LoopRegion> {
+        self.repeat.then(|| self.loop_region.unwrap_or_default())
+    }
+}
+
+/// The speed multiplying the speed of all the sounds played with
+/// [`PlaybackSettings::global_speed`]
+///
+/// As the pitch of a sound changes with its speed, following the relative speed of the [`Time`]
+/// slows down and lowers the sounds during a slow-motion, and pauses them while the time is
+/// paused.
+///
+/// ```
+/// # use bevy_ecs::system::ResMut;
+/// # use bevy_audio::GlobalAudioSpeed;
+/// fn follow_time(mut global_speed: ResMut<GlobalAudioSpeed>) {
+///     global_speed.follow_time = true;
+/// }
+/// ```
+#[derive(Resource, Clone, Copy, Debug)]
+pub struct GlobalAudioSpeed {
+    /// The speed multiplying the speed of the sounds.
+    pub speed: f32,
+    /// Also multiply the speed by the [relative speed](Time::relative_speed) of the [`Time`].
+    pub follow_time: bool,
+}
+
+impl Default for GlobalAudioSpeed {
+    fn default() -> Self {
+        Self {
+            speed: 1.0,
+            follow_time: false,
+        }
+    }
+}
+
+impl GlobalAudioSpeed {
+    /// The speed multiplying the speed of the sounds, with the relative speed of `time` if they
+    /// follow it
+    pub fn factor(&self, time: Option<&Time>) -> f32 {
+        match time {
+            Some(time) if self.follow_time => self.speed * time.relative_speed(),
+            _ => self.speed,
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -326,3 +408,35 @@ where
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_audio_speed_factor() {
+        let mut time = Time::default();
+        time.set_relative_speed(0.5);
+        // (speed, follow the time, with a time, factor)
+        let cases = [
+            (1.0, false, true, 1.0),
+            (2.0, false, true, 2.0),
+            (1.0, true, true, 0.5),
+            (2.0, true, true, 1.0),
+            // without a time, only the speed is used
+            (2.0, true, false, 2.0),
+        ];
+        for (speed, follow_time, with_time, expected) in cases {
+            let global_speed = GlobalAudioSpeed { speed, follow_time };
+            let time = with_time.then_some(&time);
+            assert_eq!(global_speed.factor(time), expected);
+        }
+
+        time.set_relative_speed(0.0);
+        let global_speed = GlobalAudioSpeed {
+            speed: 2.0,
+            follow_time: true,
+        };
+        assert_eq!(global_speed.factor(Some(&time)), 0.0);
+    }
+}
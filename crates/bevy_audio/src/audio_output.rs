@@ -1,30 +1,24 @@
-use crate::{Audio, AudioSource, Decodable, SpatialAudioSink, SpatialSettings};
+use crate::{
+    append_spatialized, Audio, AudioMixer, AudioSinkPlayback, AudioSource, Decodable,
+    GlobalAudioSpeed, LoopRegion, Playback, PlaybackControls, PlaybackSettings, SinkSpeed,
+    SpatialAudioSink, SpatialSettings, SpatialState, StreamedAudioSource, StreamedPlayback,
+};
 use bevy_asset::{Asset, Assets};
-use bevy_ecs::system::{Res, ResMut, Resource};
-use bevy_utils::tracing::warn;
-use rodio::{OutputStream, OutputStreamHandle, Sink, Source, SpatialSink};
-use std::marker::PhantomData;
+use bevy_ecs::system::{Local, Res, ResMut, Resource};
+use bevy_math::Vec3;
+use bevy_time::Time;
+use parking_lot::Mutex;
+use rodio::{Sink, Source};
+use std::{any::Any, marker::PhantomData};
 
 use crate::AudioSink;
 
-/// Used internally to play audio on the current "audio device"
-///
-/// ## Note
-///
-/// Initializing this resource will leak [`rodio::OutputStream`](rodio::OutputStream)
-/// using [`std::mem::forget`].
-/// This is done to avoid storing this in the struct (and making this `!Send`)
-/// while preventing it from dropping (to avoid halting of audio).
-///
-/// This is fine when initializing this once (as is default when adding this plugin),
-/// since the memory cost will be the same.
-/// However, repeatedly inserting this resource into the app will **leak more memory**.
+/// Used internally to play the audio sources of type `Source` through the [`AudioMixer`]
 #[derive(Resource)]
 pub struct AudioOutput<Source = AudioSource>
 where
     Source: Decodable,
 {
-    stream_handle: Option<OutputStreamHandle>,
     phantom: PhantomData<Source>,
 }
 
@@ -33,19 +27,8 @@ where
     Source: Decodable,
 {
     fn default() -> Self {
-        if let Ok((stream, stream_handle)) = OutputStream::try_default() {
-            // We leak `OutputStream` to prevent the audio from stopping.
-            std::mem::forget(stream);
-            Self {
-                stream_handle: Some(stream_handle),
-                phantom: PhantomData,
-            }
-        } else {
-            warn!("No audio device found.");
-            Self {
-                stream_handle: None,
-                phantom: PhantomData,
-            }
+        Self {
+            phantom: PhantomData,
         }
     }
 }
@@ -55,56 +38,53 @@ where
     Source: Asset + Decodable,
     f32: rodio::cpal::FromSample<Source::DecoderItem>,
 {
-    fn play_source(&self, audio_source: &Source, repeat: bool) -> Option<Sink> {
-        self.stream_handle
-            .as_ref()
-            .and_then(|stream_handle| match Sink::try_new(stream_handle) {
-                Ok(sink) => {
-                    if repeat {
-                        sink.append(audio_source.decoder().repeat_infinite());
-                    } else {
-                        sink.append(audio_source.decoder());
-                    }
-                    Some(sink)
-                }
-                Err(err) => {
-                    warn!("Error playing sound: {err:?}");
-                    None
-                }
-            })
+    fn play_source(
+        &self,
+        mixer: &AudioMixer,
+        audio_source: &Source,
+        settings: &PlaybackSettings,
+        global_speed: f32,
+    ) -> Option<AudioSink> {
+        let (sink, effects) = mixer.new_sink(settings.bus)?;
+        let (source, playback) = playback_source(audio_source, settings.played_loop());
+        append_source(&sink, source);
+        Some(AudioSink {
+            sink: Some(sink),
+            effects,
+            speed: SinkSpeed::new(settings.global_speed, global_speed),
+            playback,
+        })
     }
 
     fn play_spatial_source(
         &self,
+        mixer: &AudioMixer,
         audio_source: &Source,
-        repeat: bool,
+        settings: &PlaybackSettings,
+        global_speed: f32,
         spatial: SpatialSettings,
-    ) -> Option<SpatialSink> {
-        self.stream_handle.as_ref().and_then(|stream_handle| {
-            match SpatialSink::try_new(
-                stream_handle,
-                spatial.emitter,
-                spatial.left_ear,
-                spatial.right_ear,
-            ) {
-                Ok(sink) => {
-                    if repeat {
-                        sink.append(audio_source.decoder().repeat_infinite());
-                    } else {
-                        sink.append(audio_source.decoder());
-                    }
-                    Some(sink)
-                }
-                Err(err) => {
-                    warn!("Error playing spatial sound: {err:?}");
-                    None
-                }
-            }
+    ) -> Option<SpatialAudioSink> {
+        let (sink, effects) = mixer.new_sink(settings.bus)?;
+        let spatial = SpatialState::new(
+            Vec3::from_array(spatial.left_ear),
+            Vec3::from_array(spatial.right_ear),
+            Vec3::from_array(spatial.emitter),
+        );
+        let (source, playback) = playback_source(audio_source, settings.played_loop());
+        append_spatialized(&sink, source, spatial.params.clone());
+        Some(SpatialAudioSink {
+            sink: Some(sink),
+            effects,
+            speed: SinkSpeed::new(settings.global_speed, global_speed),
+            playback,
+            spatial: Mutex::new(spatial),
         })
     }
 
     fn try_play_queued(
         &self,
+        mixer: &AudioMixer,
+        global_speed: f32,
         audio_sources: &Assets<Source>,
         audio: &mut Audio<Source>,
         sinks: &mut Assets<AudioSink>,
@@ -117,22 +97,27 @@ where
             let config = queue.pop_front().unwrap();
             if let Some(audio_source) = audio_sources.get(&config.source_handle) {
                 if let Some(spatial) = config.spatial {
-                    if let Some(sink) =
-                        self.play_spatial_source(audio_source, config.settings.repeat, spatial)
-                    {
+                    if let Some(sink) = self.play_spatial_source(
+                        mixer,
+                        audio_source,
+                        &config.settings,
+                        global_speed,
+                        spatial,
+                    ) {
                         sink.set_speed(config.settings.speed);
                         sink.set_volume(config.settings.volume);
 
                         // don't keep the strong handle. there is no way to return it to the user here as it is async
-                        let _ = spatial_sinks
-                            .set(config.sink_handle, SpatialAudioSink { sink: Some(sink) });
+                        let _ = spatial_sinks.set(config.sink_handle, sink);
                     }
-                } else if let Some(sink) = self.play_source(audio_source, config.settings.repeat) {
+                } else if let Some(sink) =
+                    self.play_source(mixer, audio_source, &config.settings, global_speed)
+                {
                     sink.set_speed(config.settings.speed);
                     sink.set_volume(config.settings.volume);
 
                     // don't keep the strong handle. there is no way to return it to the user here as it is async
-                    let _ = sinks.set(config.sink_handle, AudioSink { sink: Some(sink) });
+                    let _ = sinks.set(config.sink_handle, sink);
                 }
             } else {
                 // audio source hasn't loaded yet. add it back to the queue
@@ -143,9 +128,37 @@ where
     }
 }
 
-/// Plays audio currently queued in the [`Audio`] resource through the [`AudioOutput`] resource
+/// Creates the source of a sink playing `audio_source`, decoded in the background if it's a
+/// [`StreamedAudioSource`]
+fn playback_source<Source>(
+    audio_source: &Source,
+    loop_region: Option<LoopRegion>,
+) -> (Box<dyn rodio::Source<Item = f32> + Send>, PlaybackControls)
+where
+    Source: Decodable,
+    f32: rodio::cpal::FromSample<Source::DecoderItem>,
+{
+    if let Some(streamed) = (audio_source as &dyn Any).downcast_ref::<StreamedAudioSource>() {
+        let (source, playback) = StreamedPlayback::new(streamed, loop_region);
+        return (Box::new(source), playback);
+    }
+    let (source, playback) = Playback::new(audio_source.decoder().buffered(), loop_region);
+    (Box::new(source.convert_samples()), playback)
+}
+
+/// Appends the source of a sink, out of the impls where `f32: FromSample<Source::DecoderItem>`
+/// keeps `f32` from being found as a sample of `source`
+fn append_source(sink: &Sink, source: Box<dyn rodio::Source<Item = f32> + Send>) {
+    sink.append(source);
+}
+
+/// Plays audio currently queued in the [`Audio`] resource through the [`AudioOutput`] resource,
+/// into the buses of the [`AudioMixer`]
 pub fn play_queued_audio_system<Source: Asset + Decodable>(
     audio_output: Res<AudioOutput<Source>>,
+    mixer: Res<AudioMixer>,
+    global_speed: Res<GlobalAudioSpeed>,
+    time: Option<Res<Time>>,
     audio_sources: Option<Res<Assets<Source>>>,
     mut audio: ResMut<Audio<Source>>,
     mut sinks: ResMut<Assets<AudioSink>>,
@@ -154,6 +167,35 @@ pub fn play_queued_audio_system<Source: Asset + Decodable>(
     f32: rodio::cpal::FromSample<Source::DecoderItem>,
 {
     if let Some(audio_sources) = audio_sources {
-        audio_output.try_play_queued(&*audio_sources, &mut *audio, &mut sinks, &mut spatial_sinks);
+        audio_output.try_play_queued(
+            &mixer,
+            global_speed.factor(time.as_deref()),
+            &*audio_sources,
+            &mut *audio,
+            &mut sinks,
+            &mut spatial_sinks,
+        );
     };
 }
+
+/// Updates the speed of the sinks playing with [`PlaybackSettings::global_speed`] when the
+/// [`GlobalAudioSpeed`] changes
+pub fn update_global_audio_speed_system(
+    global_speed: Res<GlobalAudioSpeed>,
+    time: Option<Res<Time>>,
+    sinks: Res<Assets<AudioSink>>,
+    spatial_sinks: Res<Assets<SpatialAudioSink>>,
+    mut last_factor: Local<Option<f32>>,
+) {
+    let factor = global_speed.factor(time.as_deref());
+    if *last_factor == Some(factor) {
+        return;
+    }
+    *last_factor = Some(factor);
+    for (_, sink) in sinks.iter() {
+        sink.set_global_speed(factor);
+    }
+    for (_, sink) in spatial_sinks.iter() {
+        sink.set_global_speed(factor);
+    }
+}
@@ -63,6 +63,72 @@ impl AssetLoader for AudioLoader {
     }
 }
 
+/// A source of audio data decoded in the background while it plays, for the long sounds like the
+/// music
+///
+/// An [`AudioSource`] is buffered as it's decoded, so that it can be looped and sought without
+/// decoding it again, which for a sound of several minutes adds up to tens of megabytes. A
+/// streamed source is instead decoded by a background thread a few chunks ahead of the
+/// playback, seeking and looping by decoding the sound again.
+///
+/// The files with a `.stream` extension before their audio extension, like `music.stream.ogg`,
+/// are loaded as streamed sources, which can be played with
+/// [`Audio<StreamedAudioSource>`](crate::Audio).
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "3c2b1b8e-5f0e-4a47-9d3c-2b6e7f1d9a54"]
+pub struct StreamedAudioSource {
+    /// Raw data of the audio source, in one of the file formats supported by Bevy like the data
+    /// of an [`AudioSource`].
+    pub bytes: Arc<[u8]>,
+}
+
+impl AsRef<[u8]> for StreamedAudioSource {
+    fn as_ref(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl From<AudioSource> for StreamedAudioSource {
+    fn from(source: AudioSource) -> Self {
+        Self {
+            bytes: source.bytes,
+        }
+    }
+}
+
+/// Loads the files with a `.stream` extension before their audio extension, like
+/// `music.stream.ogg`, as [`StreamedAudioSource`] [`Assets`](bevy_asset::Assets)
+///
+/// It supports the same audio formats as the [`AudioLoader`].
+#[derive(Default)]
+pub struct StreamedAudioLoader;
+
+impl AssetLoader for StreamedAudioLoader {
+    fn load(&self, bytes: &[u8], load_context: &mut LoadContext) -> BoxedFuture<Result<()>> {
+        load_context.set_default_asset(LoadedAsset::new(StreamedAudioSource {
+            bytes: bytes.into(),
+        }));
+        Box::pin(async move { Ok(()) })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &[
+            #[cfg(feature = "mp3")]
+            "stream.mp3",
+            #[cfg(feature = "flac")]
+            "stream.flac",
+            #[cfg(feature = "wav")]
+            "stream.wav",
+            #[cfg(feature = "vorbis")]
+            "stream.oga",
+            #[cfg(feature = "vorbis")]
+            "stream.ogg",
+            #[cfg(feature = "vorbis")]
+            "stream.spx",
+        ]
+    }
+}
+
 /// A type implementing this trait can be converted to a [`rodio::Source`] type.
 /// It must be [`Send`] and [`Sync`], and usually implements [`Asset`] so needs to be [`TypeUuid`],
 /// in order to be registered.
@@ -93,6 +159,15 @@ impl Decodable for AudioSource {
     }
 }
 
+impl Decodable for StreamedAudioSource {
+    type Decoder = rodio::Decoder<Cursor<StreamedAudioSource>>;
+    type DecoderItem = <rodio::Decoder<Cursor<StreamedAudioSource>> as Iterator>::Item;
+
+    fn decoder(&self) -> Self::Decoder {
+        rodio::Decoder::new(Cursor::new(self.clone())).unwrap()
+    }
+}
+
 /// A trait that allows adding a custom audio source to the object.
 /// This is implemented for [`App`][bevy_app::App] to allow registering custom [`Decodable`] types.
 pub trait AddAudioSource {
@@ -0,0 +1,697 @@
+use parking_lot::Mutex;
+use rodio::Source;
+use std::{
+    f32::consts::TAU,
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// An effect processing a sound on the audio thread, added to an [`AudioBus`](crate::AudioBus)
+/// with [`AudioBusControls::add_effect`](crate::AudioBusControls::add_effect) or to a sink with
+/// [`AudioSink::add_effect`](crate::AudioSink::add_effect)
+///
+/// The effects are applied in the order they were added, before the volume.
+pub trait AudioEffect: Send + 'static {
+    /// Processes a frame of samples in place, with a sample for each channel.
+    fn process(&mut self, frame: &mut [f32], sample_rate: u32);
+}
+
+/// A parameter of an effect that can be changed while the effect is playing, like the cutoff of
+/// a [`LowPassFilter`] muffling the sounds when the player is underwater
+///
+/// The parameter is shared by its clones, so a clone can be kept to change it after the effect
+/// was added:
+///
+/// ```
+/// # use bevy_ecs::system::Res;
+/// # use bevy_audio::{AudioBus, AudioMixer, LowPassFilter};
+/// fn dive(mixer: Res<AudioMixer>) {
+///     let filter = LowPassFilter::new(20000.0);
+///     let cutoff = filter.cutoff.clone();
+///     mixer.bus(AudioBus::SFX).add_effect(filter);
+///     // later, when the player is underwater
+///     cutoff.set(500.0);
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct EffectParam(Arc<AtomicU32>);
+
+impl EffectParam {
+    /// Creates a parameter with the value `value`.
+    pub fn new(value: f32) -> Self {
+        Self(Arc::new(AtomicU32::new(value.to_bits())))
+    }
+
+    /// Gets the value of the parameter.
+    pub fn get(&self) -> f32 {
+        f32::from_bits(self.0.load(Ordering::Relaxed))
+    }
+
+    /// Changes the value of the parameter.
+    pub fn set(&self, value: f32) {
+        self.0.store(value.to_bits(), Ordering::Relaxed);
+    }
+}
+
+enum EffectCommand {
+    Add(Box<dyn AudioEffect>),
+    Clear,
+}
+
+/// Adds effects to a [`WithEffects`] source from the main thread
+pub(crate) struct EffectChain {
+    // the sender isn't `Sync` before Rust 1.72
+    commands: Mutex<Sender<EffectCommand>>,
+}
+
+impl EffectChain {
+    pub(crate) fn add(&self, effect: impl AudioEffect) {
+        let _ = self
+            .commands
+            .lock()
+            .send(EffectCommand::Add(Box::new(effect)));
+    }
+
+    pub(crate) fn clear(&self) {
+        let _ = self.commands.lock().send(EffectCommand::Clear);
+    }
+}
+
+/// A source playing `input` through the effects added to its [`EffectChain`]
+pub(crate) struct WithEffects<I> {
+    input: I,
+    commands: Receiver<EffectCommand>,
+    effects: Vec<Box<dyn AudioEffect>>,
+    frame: Vec<f32>,
+    index: usize,
+}
+
+impl<I: Source<Item = f32>> WithEffects<I> {
+    pub(crate) fn new(input: I) -> (Self, EffectChain) {
+        let (sender, commands) = mpsc::channel();
+        let source = Self {
+            input,
+            commands,
+            effects: Vec::new(),
+            frame: Vec::new(),
+            index: 0,
+        };
+        let chain = EffectChain {
+            commands: Mutex::new(sender),
+        };
+        (source, chain)
+    }
+
+    /// Reads the next frame of the input and processes it, returning false when the input ended.
+    fn next_frame(&mut self) -> bool {
+        for command in self.commands.try_iter() {
+            match command {
+                EffectCommand::Add(effect) => self.effects.push(effect),
+                EffectCommand::Clear => self.effects.clear(),
+            }
+        }
+
+        self.frame.clear();
+        for _ in 0..self.input.channels() {
+            match self.input.next() {
+                Some(sample) => self.frame.push(sample),
+                None => break,
+            }
+        }
+        if self.frame.is_empty() {
+            return false;
+        }
+
+        let sample_rate = self.input.sample_rate();
+        for effect in &mut self.effects {
+            effect.process(&mut self.frame, sample_rate);
+        }
+        self.index = 0;
+        true
+    }
+}
+
+impl<I: Source<Item = f32>> Iterator for WithEffects<I> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        if self.index >= self.frame.len() && !self.next_frame() {
+            return None;
+        }
+        let sample = self.frame[self.index];
+        self.index += 1;
+        Some(sample)
+    }
+}
+
+impl<I: Source<Item = f32>> Source for WithEffects<I> {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
+
+/// Converts decibels to a gain
+fn decibels_to_gain(decibels: f32) -> f32 {
+    10.0f32.powf(decibels / 20.0)
+}
+
+/// Converts a gain to decibels
+fn gain_to_decibels(gain: f32) -> f32 {
+    20.0 * gain.max(1e-9).log10()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BiquadKind {
+    LowPass,
+    HighPass,
+}
+
+/// A biquad filter, with the coefficients of the Audio EQ Cookbook
+struct Biquad {
+    kind: BiquadKind,
+    // the parameters the coefficients were computed with
+    params: (f32, f32, u32),
+    b: [f32; 3],
+    a: [f32; 2],
+    // for each channel, the last two inputs and outputs
+    history: Vec<[f32; 4]>,
+}
+
+impl Biquad {
+    fn new(kind: BiquadKind) -> Self {
+        Self {
+            kind,
+            params: (0.0, 0.0, 0),
+            b: [1.0, 0.0, 0.0],
+            a: [0.0, 0.0],
+            history: Vec::new(),
+        }
+    }
+
+    fn process(&mut self, frame: &mut [f32], cutoff: f32, q: f32, sample_rate: u32) {
+        if self.params != (cutoff, q, sample_rate) {
+            self.params = (cutoff, q, sample_rate);
+            let nyquist = sample_rate as f32 / 2.0;
+            let w0 = TAU * cutoff.clamp(1.0, nyquist * 0.99) / sample_rate as f32;
+            let alpha = w0.sin() / (2.0 * q.max(0.01));
+            let cos = w0.cos();
+            let a0 = 1.0 + alpha;
+            let (b0, b1) = match self.kind {
+                BiquadKind::LowPass => ((1.0 - cos) / 2.0, 1.0 - cos),
+                BiquadKind::HighPass => ((1.0 + cos) / 2.0, -(1.0 + cos)),
+            };
+            self.b = [b0 / a0, b1 / a0, b0 / a0];
+            self.a = [-2.0 * cos / a0, (1.0 - alpha) / a0];
+        }
+        self.history.resize(frame.len(), [0.0; 4]);
+        for (sample, [x1, x2, y1, y2]) in frame.iter_mut().zip(&mut self.history) {
+            let x = *sample;
+            let y = self.b[0] * x + self.b[1] * *x1 + self.b[2] * *x2
+                - self.a[0] * *y1
+                - self.a[1] * *y2;
+            (*x2, *x1, *y2, *y1) = (*x1, x, *y1, y);
+            *sample = y;
+        }
+    }
+}
+
+/// A filter removing the frequencies above its cutoff, making the sounds muffled like behind a
+/// wall or underwater
+pub struct LowPassFilter {
+    /// The frequency above which the sound is attenuated, in Hertz
+    pub cutoff: EffectParam,
+    /// The resonance of the filter around the cutoff, `0.707` for a flat response
+    pub q: EffectParam,
+    biquad: Biquad,
+}
+
+impl LowPassFilter {
+    /// Creates a filter removing the frequencies above `cutoff`, in Hertz.
+    pub fn new(cutoff: f32) -> Self {
+        Self {
+            cutoff: EffectParam::new(cutoff),
+            q: EffectParam::new(std::f32::consts::FRAC_1_SQRT_2),
+            biquad: Biquad::new(BiquadKind::LowPass),
+        }
+    }
+}
+
+impl AudioEffect for LowPassFilter {
+    fn process(&mut self, frame: &mut [f32], sample_rate: u32) {
+        self.biquad
+            .process(frame, self.cutoff.get(), self.q.get(), sample_rate);
+    }
+}
+
+/// A filter removing the frequencies below its cutoff, making the sounds thin like through a
+/// radio or a phone
+pub struct HighPassFilter {
+    /// The frequency below which the sound is attenuated, in Hertz
+    pub cutoff: EffectParam,
+    /// The resonance of the filter around the cutoff, `0.707` for a flat response
+    pub q: EffectParam,
+    biquad: Biquad,
+}
+
+impl HighPassFilter {
+    /// Creates a filter removing the frequencies below `cutoff`, in Hertz.
+    pub fn new(cutoff: f32) -> Self {
+        Self {
+            cutoff: EffectParam::new(cutoff),
+            q: EffectParam::new(std::f32::consts::FRAC_1_SQRT_2),
+            biquad: Biquad::new(BiquadKind::HighPass),
+        }
+    }
+}
+
+impl AudioEffect for HighPassFilter {
+    fn process(&mut self, frame: &mut [f32], sample_rate: u32) {
+        self.biquad
+            .process(frame, self.cutoff.get(), self.q.get(), sample_rate);
+    }
+}
+
+/// The longest time of a [`Delay`]
+const MAX_DELAY_TIME: f32 = 2.0;
+
+/// An echo, repeating the sound after a delay
+pub struct Delay {
+    /// The time between the sound and its echo, in seconds, up to 2 seconds
+    pub time: EffectParam,
+    /// How much of the echo is repeated again, from 0 to 1
+    pub feedback: EffectParam,
+    /// The volume of the echo
+    pub wet: EffectParam,
+    buffers: Vec<Vec<f32>>,
+    position: usize,
+    sample_rate: u32,
+}
+
+impl Delay {
+    /// Creates an echo repeating the sound after `time`, with half the volume each time.
+    pub fn new(time: Duration) -> Self {
+        Self {
+            time: EffectParam::new(time.as_secs_f32()),
+            feedback: EffectParam::new(0.5),
+            wet: EffectParam::new(0.5),
+            buffers: Vec::new(),
+            position: 0,
+            sample_rate: 0,
+        }
+    }
+}
+
+impl AudioEffect for Delay {
+    fn process(&mut self, frame: &mut [f32], sample_rate: u32) {
+        if self.sample_rate != sample_rate || self.buffers.len() != frame.len() {
+            self.sample_rate = sample_rate;
+            let len = (MAX_DELAY_TIME * sample_rate as f32) as usize + 1;
+            self.buffers = vec![vec![0.0; len]; frame.len()];
+            self.position = 0;
+        }
+        let len = self.buffers[0].len();
+        let delay = ((self.time.get().clamp(0.0, MAX_DELAY_TIME) * sample_rate as f32) as usize)
+            .clamp(1, len - 1);
+        let feedback = self.feedback.get().clamp(0.0, 0.99);
+        let wet = self.wet.get();
+        let read = (self.position + len - delay) % len;
+        for (sample, buffer) in frame.iter_mut().zip(&mut self.buffers) {
+            let echo = buffer[read];
+            buffer[self.position] = *sample + echo * feedback;
+            *sample += echo * wet;
+        }
+        self.position = (self.position + 1) % len;
+    }
+}
+
+/// The lengths of the comb filters of the [`Reverb`], at 44100 Hz
+const COMB_LENGTHS: [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+
+/// The lengths of the all-pass filters of the [`Reverb`], at 44100 Hz
+const ALL_PASS_LENGTHS: [usize; 4] = [556, 441, 341, 225];
+
+/// The difference between the lengths of the filters of two channels, for a wider reverb
+const STEREO_SPREAD: usize = 23;
+
+struct ReverbChannel {
+    combs: Vec<(Vec<f32>, usize, f32)>,
+    all_passes: Vec<(Vec<f32>, usize)>,
+}
+
+impl ReverbChannel {
+    fn new(channel: usize, sample_rate: u32) -> Self {
+        let scale = |length: usize| {
+            ((length + channel * STEREO_SPREAD) as f32 * sample_rate as f32 / 44100.0) as usize + 1
+        };
+        Self {
+            combs: COMB_LENGTHS
+                .iter()
+                .map(|length| (vec![0.0; scale(*length)], 0, 0.0))
+                .collect(),
+            all_passes: ALL_PASS_LENGTHS
+                .iter()
+                .map(|length| (vec![0.0; scale(*length)], 0))
+                .collect(),
+        }
+    }
+
+    fn process(&mut self, input: f32, feedback: f32, damping: f32) -> f32 {
+        let mut output = 0.0;
+        for (buffer, position, filtered) in &mut self.combs {
+            let delayed = buffer[*position];
+            *filtered = delayed * (1.0 - damping) + *filtered * damping;
+            buffer[*position] = input + *filtered * feedback;
+            *position = (*position + 1) % buffer.len();
+            output += delayed;
+        }
+        for (buffer, position) in &mut self.all_passes {
+            let delayed = buffer[*position];
+            buffer[*position] = output + delayed * 0.5;
+            *position = (*position + 1) % buffer.len();
+            output = delayed - output;
+        }
+        output
+    }
+}
+
+/// The reflections of a room, making the sounds sound like they're played in a cave or a hall
+///
+/// This is the Freeverb algorithm. Its `wet` level is the amount of the sound sent to the reverb,
+/// and the `dry` level the amount of the sound played as is.
+pub struct Reverb {
+    /// The size of the room, from 0 to 1, making the reflections last longer
+    pub room_size: EffectParam,
+    /// How much the walls absorb the high frequencies, from 0 to 1
+    pub damping: EffectParam,
+    /// The volume of the reflections
+    pub wet: EffectParam,
+    /// The volume of the original sound
+    pub dry: EffectParam,
+    channels: Vec<ReverbChannel>,
+    sample_rate: u32,
+}
+
+impl Reverb {
+    /// Creates the reverb of a room of `room_size`, from 0 to 1.
+    pub fn new(room_size: f32) -> Self {
+        Self {
+            room_size: EffectParam::new(room_size),
+            damping: EffectParam::new(0.5),
+            wet: EffectParam::new(0.3),
+            dry: EffectParam::new(1.0),
+            channels: Vec::new(),
+            sample_rate: 0,
+        }
+    }
+}
+
+impl AudioEffect for Reverb {
+    fn process(&mut self, frame: &mut [f32], sample_rate: u32) {
+        if self.sample_rate != sample_rate || self.channels.len() != frame.len() {
+            self.sample_rate = sample_rate;
+            self.channels = (0..frame.len())
+                .map(|channel| ReverbChannel::new(channel, sample_rate))
+                .collect();
+        }
+        let feedback = self.room_size.get().clamp(0.0, 1.0) * 0.28 + 0.7;
+        let damping = self.damping.get().clamp(0.0, 1.0) * 0.4;
+        let (wet, dry) = (self.wet.get(), self.dry.get());
+        // the reverb of all the channels is fed with the same input
+        let input = frame.iter().sum::<f32>() / frame.len() as f32 * 0.015;
+        for (sample, channel) in frame.iter_mut().zip(&mut self.channels) {
+            let reflections = channel.process(input, feedback, damping);
+            *sample = *sample * dry + reflections * wet;
+        }
+    }
+}
+
+/// Reduces the volume of the sounds louder than a threshold, evening out the loud and quiet
+/// sounds
+pub struct Compressor {
+    /// The volume above which the sound is compressed, in decibels
+    pub threshold: EffectParam,
+    /// How much the volume above the threshold is reduced, `4.0` dividing it by 4
+    pub ratio: EffectParam,
+    /// The time to reduce the volume when the sound gets louder, in seconds
+    pub attack: EffectParam,
+    /// The time to restore the volume when the sound gets quieter, in seconds
+    pub release: EffectParam,
+    /// The gain applied after the compression, in decibels
+    pub makeup_gain: EffectParam,
+    envelope: f32,
+}
+
+impl Compressor {
+    /// Creates a compressor reducing the volume above `threshold`, in decibels, by `ratio`.
+    pub fn new(threshold: f32, ratio: f32) -> Self {
+        Self {
+            threshold: EffectParam::new(threshold),
+            ratio: EffectParam::new(ratio),
+            attack: EffectParam::new(0.01),
+            release: EffectParam::new(0.1),
+            makeup_gain: EffectParam::new(0.0),
+            envelope: 0.0,
+        }
+    }
+}
+
+impl AudioEffect for Compressor {
+    fn process(&mut self, frame: &mut [f32], sample_rate: u32) {
+        let level = frame
+            .iter()
+            .fold(0.0f32, |level, sample| level.max(sample.abs()));
+        let time = if level > self.envelope {
+            self.attack.get()
+        } else {
+            self.release.get()
+        };
+        let coefficient = (-1.0 / (time.max(1e-4) * sample_rate as f32)).exp();
+        self.envelope = level + (self.envelope - level) * coefficient;
+
+        let threshold = self.threshold.get();
+        let envelope = gain_to_decibels(self.envelope);
+        let reduction = if envelope > threshold {
+            (threshold - envelope) * (1.0 - 1.0 / self.ratio.get().max(1.0))
+        } else {
+            0.0
+        };
+        let gain = decibels_to_gain(reduction + self.makeup_gain.get());
+        for sample in frame {
+            *sample *= gain;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+    use std::f32::consts::FRAC_1_SQRT_2;
+
+    const SAMPLE_RATE: u32 = 44_100;
+
+    /// Processes `input`, a sample for each frame of one channel, through `effect`.
+    fn process(effect: &mut impl AudioEffect, input: impl IntoIterator<Item = f32>) -> Vec<f32> {
+        input
+            .into_iter()
+            .map(|sample| {
+                let mut frame = [sample];
+                effect.process(&mut frame, SAMPLE_RATE);
+                frame[0]
+            })
+            .collect()
+    }
+
+    fn dc(len: usize) -> Vec<f32> {
+        vec![1.0; len]
+    }
+
+    fn nyquist(len: usize) -> impl Iterator<Item = f32> {
+        (0..len).map(|i| if i % 2 == 0 { 1.0 } else { -1.0 })
+    }
+
+    #[test]
+    fn biquad_response() {
+        // (filter, input, amplitude once the filter settled)
+        let cases: [(BiquadKind, fn(usize) -> Vec<f32>, f32); 4] = [
+            (BiquadKind::LowPass, dc, 1.0),
+            (BiquadKind::LowPass, |len| nyquist(len).collect(), 0.0),
+            (BiquadKind::HighPass, dc, 0.0),
+            (BiquadKind::HighPass, |len| nyquist(len).collect(), 1.0),
+        ];
+        for (kind, input, expected) in cases {
+            let mut biquad = Biquad::new(kind);
+            let amplitude = input(4096)
+                .into_iter()
+                .map(|sample| {
+                    let mut frame = [sample];
+                    biquad.process(&mut frame, 1000.0, FRAC_1_SQRT_2, SAMPLE_RATE);
+                    frame[0].abs()
+                })
+                .skip(4000)
+                .fold(0.0, f32::max);
+            assert!(
+                (amplitude - expected).abs() < 1e-3,
+                "{}: amplitude {amplitude} instead of {expected}",
+                if kind == BiquadKind::LowPass {
+                    "low-pass"
+                } else {
+                    "high-pass"
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn biquad_filters_channels_separately() {
+        let mut filter = LowPassFilter::new(1000.0);
+        let mut frame = [0.0; 2];
+        for i in 0..4096 {
+            frame = [1.0, if i % 2 == 0 { 1.0 } else { -1.0 }];
+            filter.process(&mut frame, SAMPLE_RATE);
+        }
+        assert!((frame[0] - 1.0).abs() < 1e-3);
+        assert!(frame[1].abs() < 1e-3);
+    }
+
+    #[test]
+    fn delay_echoes_after_its_time() {
+        const SAMPLE_RATE: u32 = 100;
+        let mut delay = Delay::new(Duration::from_millis(500));
+        let output: Vec<f32> = (0..160)
+            .map(|i| {
+                let mut frame = [if i == 0 { 1.0 } else { 0.0 }];
+                delay.process(&mut frame, SAMPLE_RATE);
+                frame[0]
+            })
+            .collect();
+        for (i, sample) in output.into_iter().enumerate() {
+            let expected = match i {
+                0 => 1.0,
+                // the echo, at half the volume
+                50 => 0.5,
+                // the echo of the echo, with the feedback
+                100 => 0.25,
+                150 => 0.125,
+                _ => 0.0,
+            };
+            assert_eq!(sample, expected, "sample {i}");
+        }
+    }
+
+    #[test]
+    fn compressor_reduces_loud_sounds() {
+        // (threshold, ratio, makeup gain, input level, expected output level), in decibels
+        let cases = [
+            (-20.0, 4.0, 0.0, 0.0, -15.0),
+            (-20.0, 2.0, 0.0, 0.0, -10.0),
+            (-20.0, 4.0, 6.0, 0.0, -9.0),
+            // below the threshold, only the makeup gain is applied
+            (-20.0, 4.0, 0.0, -30.0, -30.0),
+            (-20.0, 4.0, 3.0, -30.0, -27.0),
+            // a ratio below 1 doesn't expand the sound
+            (-20.0, 0.5, 0.0, 0.0, 0.0),
+        ];
+        for (threshold, ratio, makeup_gain, input, expected) in cases {
+            let mut compressor = Compressor::new(threshold, ratio);
+            compressor.makeup_gain.set(makeup_gain);
+            let level = decibels_to_gain(input);
+            let output = process(&mut compressor, vec![level; SAMPLE_RATE as usize]);
+            let output = gain_to_decibels(*output.last().unwrap());
+            assert!(
+                (output - expected).abs() < 0.01,
+                "threshold {threshold}, ratio {ratio}, makeup gain {makeup_gain}, input {input}: \
+                {output} dB instead of {expected} dB"
+            );
+        }
+    }
+
+    #[test]
+    fn compressor_attack_is_smooth() {
+        let mut compressor = Compressor::new(-20.0, 4.0);
+        let output = process(&mut compressor, dc(SAMPLE_RATE as usize / 10));
+        // the first sample is barely reduced, then the gain decreases until the envelope settled
+        assert!(output[0] > 0.9);
+        assert!(output.windows(2).all(|pair| pair[1] <= pair[0]));
+    }
+
+    /// Records the frames it processes and multiplies each channel by its index plus one
+    struct ChannelGain(Arc<Mutex<Vec<Vec<f32>>>>);
+
+    impl AudioEffect for ChannelGain {
+        fn process(&mut self, frame: &mut [f32], _sample_rate: u32) {
+            self.0.lock().push(frame.to_vec());
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                *sample *= (channel + 1) as f32;
+            }
+        }
+    }
+
+    #[test]
+    fn effects_process_whole_frames() {
+        let samples = vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let (mut source, chain) = WithEffects::new(SamplesBuffer::new(2, SAMPLE_RATE, samples));
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        chain.add(ChannelGain(frames.clone()));
+
+        assert_eq!(
+            source.by_ref().collect::<Vec<_>>(),
+            [1.0, 4.0, 3.0, 8.0, 5.0, 12.0]
+        );
+        assert_eq!(
+            *frames.lock(),
+            [vec![1.0, 2.0], vec![3.0, 4.0], vec![5.0, 6.0]]
+        );
+        assert_eq!(source.next(), None);
+    }
+
+    #[test]
+    fn effects_are_added_and_cleared_between_frames() {
+        let samples = vec![1.0; 8];
+        let (mut source, chain) = WithEffects::new(SamplesBuffer::new(2, SAMPLE_RATE, samples));
+        let frames = Arc::new(Mutex::new(Vec::new()));
+
+        assert_eq!(source.next(), Some(1.0));
+        // added in the middle of a frame, the effect starts with the next frame
+        chain.add(ChannelGain(frames.clone()));
+        assert_eq!(source.next(), Some(1.0));
+        assert_eq!([source.next(), source.next()], [Some(1.0), Some(2.0)]);
+
+        // the effects are applied in the order they were added
+        chain.add(ChannelGain(frames.clone()));
+        assert_eq!([source.next(), source.next()], [Some(1.0), Some(4.0)]);
+        assert_eq!(frames.lock().last().unwrap(), &[1.0, 2.0]);
+
+        chain.clear();
+        assert_eq!([source.next(), source.next()], [Some(1.0), Some(1.0)]);
+        assert_eq!(frames.lock().len(), 3);
+        assert_eq!(source.next(), None);
+    }
+
+    #[test]
+    fn partial_last_frame_is_processed() {
+        let samples = vec![1.0, 1.0, 1.0];
+        let (source, chain) = WithEffects::new(SamplesBuffer::new(2, SAMPLE_RATE, samples));
+        let frames = Arc::new(Mutex::new(Vec::new()));
+        chain.add(ChannelGain(frames.clone()));
+
+        assert_eq!(source.collect::<Vec<_>>(), [1.0, 2.0, 1.0]);
+        assert_eq!(frames.lock()[1], [1.0]);
+    }
+}
@@ -25,47 +25,68 @@
 mod audio;
 mod audio_output;
 mod audio_source;
+mod effects;
+mod mixer;
+mod playback;
 mod sinks;
+mod spatial;
 
 #[allow(missing_docs)]
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        Audio, AudioOutput, AudioSink, AudioSinkPlayback, AudioSource, Decodable, PlaybackSettings,
-        SpatialAudioSink,
+        Audio, AudioBus, AudioListener, AudioMixer, AudioOutput, AudioSink, AudioSinkPlayback,
+        AudioSource, Decodable, GlobalAudioSpeed, LoopRegion, PlaybackSettings, SpatialAudioSink,
+        SpatialAudioSource, StreamedAudioSource,
     };
 }
 
 pub use audio::*;
 pub use audio_output::*;
 pub use audio_source::*;
+pub use effects::*;
+pub use mixer::*;
+pub use playback::*;
 
 pub use rodio::cpal::Sample as CpalSample;
 pub use rodio::source::Source;
 pub use rodio::Sample;
 pub use sinks::*;
+pub use spatial::*;
 
 use bevy_app::prelude::*;
 use bevy_asset::{AddAsset, Asset};
 use bevy_ecs::prelude::*;
+use bevy_transform::TransformSystem;
 
 /// Adds support for audio playback to a Bevy Application
 ///
-/// Use the [`Audio`] resource to play audio.
+/// Use the [`Audio`] resource to play audio, and the [`AudioMixer`] resource to control the volume
+/// and effects of its buses.
 #[derive(Default)]
 pub struct AudioPlugin;
 
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
-        app.init_resource::<AudioOutput<AudioSource>>()
+        app.init_resource::<AudioMixer>()
+            .init_resource::<GlobalAudioSpeed>()
+            .init_resource::<AudioOutput<AudioSource>>()
             .add_asset::<AudioSource>()
             .add_asset::<AudioSink>()
             .add_asset::<SpatialAudioSink>()
             .init_resource::<Audio<AudioSource>>()
-            .add_system(play_queued_audio_system::<AudioSource>.in_base_set(CoreSet::PostUpdate));
+            .add_audio_source::<StreamedAudioSource>()
+            .add_system(play_queued_audio_system::<AudioSource>.in_base_set(CoreSet::PostUpdate))
+            .add_system(update_global_audio_speed_system.in_base_set(CoreSet::PostUpdate))
+            .add_system(
+                update_spatial_audio_system
+                    .in_base_set(CoreSet::PostUpdate)
+                    .after(TransformSystem::TransformPropagate),
+            );
 
         #[cfg(any(feature = "mp3", feature = "flac", feature = "wav", feature = "vorbis"))]
-        app.init_asset_loader::<AudioLoader>();
+        app.init_asset_loader::<AudioLoader>()
+            .init_asset_loader::<StreamedAudioLoader>();
     }
 }
 
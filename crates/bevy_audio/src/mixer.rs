@@ -0,0 +1,422 @@
+use crate::{AudioEffect, EffectChain, WithEffects};
+use bevy_ecs::system::Resource;
+use bevy_utils::{tracing::warn, HashMap};
+use parking_lot::RwLock;
+use rodio::{
+    cpal::traits::{DeviceTrait, HostTrait},
+    dynamic_mixer::{self, DynamicMixer, DynamicMixerController},
+    OutputStream, OutputStreamHandle, Sink, Source,
+};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// The number of channels of the buses
+const BUS_CHANNELS: u16 = 2;
+
+/// The sample rate of the buses when the audio device doesn't have a default one
+const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+
+/// The name of a bus of the [`AudioMixer`], mixing the sounds played into it
+///
+/// The buses other than [`AudioBus::MASTER`] are mixed into the master bus, which is played on
+/// the audio device. A bus is created the first time it's used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct AudioBus(pub &'static str);
+
+impl AudioBus {
+    /// The bus played on the audio device, with all the other buses mixed into it
+    pub const MASTER: AudioBus = AudioBus("master");
+    /// The bus for the music
+    pub const MUSIC: AudioBus = AudioBus("music");
+    /// The bus for the sound effects
+    pub const SFX: AudioBus = AudioBus("sfx");
+    /// The bus for the voices and dialogs
+    pub const VOICE: AudioBus = AudioBus("voice");
+}
+
+impl Default for AudioBus {
+    fn default() -> Self {
+        Self::MASTER
+    }
+}
+
+struct BusState {
+    volume: AtomicU32,
+    muted: AtomicBool,
+}
+
+/// Controls the volume and the effects of an [`AudioBus`], returned by [`AudioMixer::bus`]
+///
+/// ```
+/// # use bevy_ecs::system::Res;
+/// # use bevy_audio::{AudioBus, AudioMixer};
+/// fn set_music_volume(mixer: Res<AudioMixer>) {
+///     mixer.bus(AudioBus::MUSIC).set_volume(0.5);
+/// }
+/// ```
+#[derive(Clone)]
+pub struct AudioBusControls {
+    state: Arc<BusState>,
+    effects: Arc<EffectChain>,
+}
+
+impl AudioBusControls {
+    /// Gets the volume of the bus.
+    ///
+    /// The value `1.0` is the "normal" volume, any other value multiplies each sample of the bus.
+    pub fn volume(&self) -> f32 {
+        f32::from_bits(self.state.volume.load(Ordering::Relaxed))
+    }
+
+    /// Changes the volume of the bus.
+    ///
+    /// The value `1.0` is the "normal" volume, any other value multiplies each sample of the bus.
+    pub fn set_volume(&self, volume: f32) {
+        self.state.volume.store(volume.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Is this bus muted?
+    pub fn is_muted(&self) -> bool {
+        self.state.muted.load(Ordering::Relaxed)
+    }
+
+    /// Mutes or unmutes the bus, without changing its volume.
+    pub fn set_muted(&self, muted: bool) {
+        self.state.muted.store(muted, Ordering::Relaxed);
+    }
+
+    /// Toggles the mute of the bus.
+    pub fn toggle_mute(&self) {
+        self.state.muted.fetch_xor(true, Ordering::Relaxed);
+    }
+
+    /// Adds an effect at the end of the effect chain of the bus, applied to all the sounds
+    /// played into the bus.
+    pub fn add_effect(&self, effect: impl AudioEffect) {
+        self.effects.add(effect);
+    }
+
+    /// Removes all the effects of the bus.
+    pub fn clear_effects(&self) {
+        self.effects.clear();
+    }
+}
+
+/// A source playing the mixer of a bus through its effects and its volume
+struct BusOutput {
+    input: WithEffects<BusMixer>,
+    state: Arc<BusState>,
+}
+
+impl BusOutput {
+    fn new(mixer: BusMixer) -> (Self, AudioBusControls) {
+        let (input, effects) = WithEffects::new(mixer);
+        let state = Arc::new(BusState {
+            volume: AtomicU32::new(1.0f32.to_bits()),
+            muted: AtomicBool::new(false),
+        });
+        let output = Self {
+            input,
+            state: state.clone(),
+        };
+        let controls = AudioBusControls {
+            state,
+            effects: Arc::new(effects),
+        };
+        (output, controls)
+    }
+}
+
+impl Iterator for BusOutput {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        let sample = self.input.next()?;
+        if self.state.muted.load(Ordering::Relaxed) {
+            Some(0.0)
+        } else {
+            Some(sample * f32::from_bits(self.state.volume.load(Ordering::Relaxed)))
+        }
+    }
+}
+
+impl Source for BusOutput {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
+
+/// The mixer of a bus, playing silence instead of ending when it has no sound to mix
+struct BusMixer(DynamicMixer<f32>);
+
+impl Iterator for BusMixer {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        Some(self.0.next().unwrap_or_default())
+    }
+}
+
+impl Source for BusMixer {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.0.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.0.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+struct Bus {
+    input: Arc<DynamicMixerController<f32>>,
+    controls: AudioBusControls,
+}
+
+/// Mixes the sounds into [`AudioBus`]es, each with its own volume and effects, and plays them on
+/// the current "audio device"
+///
+/// A settings menu can control the volume of the music or of the sound effects through their bus,
+/// without tracking the sinks playing them:
+///
+/// ```
+/// # use bevy_ecs::system::Res;
+/// # use bevy_asset::AssetServer;
+/// # use bevy_audio::{Audio, AudioBus, AudioMixer, PlaybackSettings};
+/// fn play_music(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+///     audio.play_with_settings(
+///         asset_server.load("music.ogg"),
+///         PlaybackSettings::LOOP.with_bus(AudioBus::MUSIC),
+///     );
+/// }
+///
+/// fn mute_music(mixer: Res<AudioMixer>) {
+///     mixer.bus(AudioBus::MUSIC).set_muted(true);
+/// }
+/// ```
+///
+/// ## Note
+///
+/// Initializing this resource will leak [`rodio::OutputStream`](rodio::OutputStream)
+/// using [`std::mem::forget`].
+/// This is done to avoid storing this in the struct (and making this `!Send`)
+/// while preventing it from dropping (to avoid halting of audio).
+///
+/// This is fine when initializing this once (as is default when adding this plugin),
+/// since the memory cost will be the same.
+/// However, repeatedly inserting this resource into the app will **leak more memory**.
+#[derive(Resource)]
+pub struct AudioMixer {
+    buses: RwLock<HashMap<AudioBus, Bus>>,
+    sample_rate: u32,
+    has_device: bool,
+}
+
+impl Default for AudioMixer {
+    fn default() -> Self {
+        let sample_rate = rodio::cpal::default_host()
+            .default_output_device()
+            .and_then(|device| device.default_output_config().ok())
+            .map(|config| config.sample_rate().0)
+            .unwrap_or(DEFAULT_SAMPLE_RATE);
+        let (mut audio_mixer, master) = Self::new(sample_rate);
+        audio_mixer.has_device = match OutputStream::try_default() {
+            Ok((stream, stream_handle)) => {
+                // We leak `OutputStream` to prevent the audio from stopping.
+                std::mem::forget(stream);
+                Self::play_master(&stream_handle, master)
+            }
+            Err(_) => {
+                warn!("No audio device found.");
+                false
+            }
+        };
+        audio_mixer
+    }
+}
+
+impl AudioMixer {
+    /// Creates a mixer without an audio device, returning the output of its master bus
+    fn new(sample_rate: u32) -> (Self, BusOutput) {
+        let (input, mixer) = dynamic_mixer::mixer(BUS_CHANNELS, sample_rate);
+        let (master, controls) = BusOutput::new(BusMixer(mixer));
+        let mut buses = HashMap::default();
+        buses.insert(AudioBus::MASTER, Bus { input, controls });
+        let audio_mixer = Self {
+            buses: RwLock::new(buses),
+            sample_rate,
+            has_device: false,
+        };
+        (audio_mixer, master)
+    }
+
+    fn play_master(stream_handle: &OutputStreamHandle, master: BusOutput) -> bool {
+        match stream_handle.play_raw(master) {
+            Ok(()) => true,
+            Err(err) => {
+                warn!("Error playing the audio mixer: {err:?}");
+                false
+            }
+        }
+    }
+
+    /// Gets the controls of the volume and effects of a bus, creating the bus if it doesn't
+    /// exist yet.
+    pub fn bus(&self, bus: AudioBus) -> AudioBusControls {
+        if let Some(existing) = self.buses.read().get(&bus) {
+            return existing.controls.clone();
+        }
+        self.bus_input(bus);
+        self.buses.read()[&bus].controls.clone()
+    }
+
+    /// The buses created so far, including the master bus
+    pub fn buses(&self) -> Vec<AudioBus> {
+        self.buses.read().keys().copied().collect()
+    }
+
+    /// The input of a bus, creating the bus if it doesn't exist yet
+    fn bus_input(&self, bus: AudioBus) -> Arc<DynamicMixerController<f32>> {
+        let mut buses = self.buses.write();
+        if let Some(existing) = buses.get(&bus) {
+            return existing.input.clone();
+        }
+        let (input, mixer) = dynamic_mixer::mixer(BUS_CHANNELS, self.sample_rate);
+        let (output, controls) = BusOutput::new(BusMixer(mixer));
+        buses[&AudioBus::MASTER].input.add(output);
+        buses.insert(
+            bus,
+            Bus {
+                input: input.clone(),
+                controls,
+            },
+        );
+        input
+    }
+
+    /// Creates a sink playing into `bus` through its own effects, or returns `None` if there is
+    /// no audio device.
+    pub(crate) fn new_sink(&self, bus: AudioBus) -> Option<(Sink, EffectChain)> {
+        if !self.has_device {
+            return None;
+        }
+        let (sink, output) = Sink::new_idle();
+        let (output, effects) = WithEffects::new(output);
+        self.bus_input(bus).add(output);
+        Some((sink, effects))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::buffer::SamplesBuffer;
+
+    /// Plays a constant `value` into `bus` and returns the first frame of the master bus.
+    fn first_frame(
+        mixer: &AudioMixer,
+        master: &mut BusOutput,
+        bus: AudioBus,
+        value: f32,
+    ) -> [f32; 2] {
+        let samples = vec![value; 64];
+        mixer.bus_input(bus).add(SamplesBuffer::new(
+            BUS_CHANNELS,
+            DEFAULT_SAMPLE_RATE,
+            samples,
+        ));
+        [master.next().unwrap(), master.next().unwrap()]
+    }
+
+    #[test]
+    fn bus_volume_scales_samples() {
+        let (mixer, mut master) = AudioMixer::new(DEFAULT_SAMPLE_RATE);
+        mixer.bus(AudioBus::MUSIC).set_volume(0.5);
+        let frame = first_frame(&mixer, &mut master, AudioBus::MUSIC, 0.8);
+        assert_eq!(frame, [0.4, 0.4]);
+    }
+
+    #[test]
+    fn bus_volumes_multiply_with_master() {
+        let (mixer, mut master) = AudioMixer::new(DEFAULT_SAMPLE_RATE);
+        mixer.bus(AudioBus::MASTER).set_volume(0.5);
+        mixer.bus(AudioBus::SFX).set_volume(0.25);
+        let frame = first_frame(&mixer, &mut master, AudioBus::SFX, 0.8);
+        assert_eq!(frame, [0.1, 0.1]);
+    }
+
+    #[test]
+    fn buses_are_summed_into_master() {
+        let (mixer, mut master) = AudioMixer::new(DEFAULT_SAMPLE_RATE);
+        mixer.bus(AudioBus::MUSIC).set_volume(0.5);
+        mixer.bus_input(AudioBus::VOICE).add(SamplesBuffer::new(
+            BUS_CHANNELS,
+            DEFAULT_SAMPLE_RATE,
+            vec![0.25; 64],
+        ));
+        let frame = first_frame(&mixer, &mut master, AudioBus::MUSIC, 0.5);
+        assert_eq!(frame, [0.5, 0.5]);
+    }
+
+    #[test]
+    fn muted_bus_is_silent() {
+        let (mixer, mut master) = AudioMixer::new(DEFAULT_SAMPLE_RATE);
+        let music = mixer.bus(AudioBus::MUSIC);
+        music.set_volume(0.5);
+        music.set_muted(true);
+        assert_eq!(
+            first_frame(&mixer, &mut master, AudioBus::MUSIC, 0.8),
+            [0.0, 0.0]
+        );
+
+        // unmuting keeps the volume
+        music.toggle_mute();
+        assert!(!music.is_muted());
+        assert_eq!(music.volume(), 0.5);
+        assert_eq!([master.next().unwrap(), master.next().unwrap()], [0.4, 0.4]);
+    }
+
+    #[test]
+    fn muted_master_silences_all_buses() {
+        let (mixer, mut master) = AudioMixer::new(DEFAULT_SAMPLE_RATE);
+        mixer.bus(AudioBus::MASTER).set_muted(true);
+        assert_eq!(
+            first_frame(&mixer, &mut master, AudioBus::SFX, 0.8),
+            [0.0, 0.0]
+        );
+        mixer.bus(AudioBus::MASTER).set_muted(false);
+        assert_eq!([master.next().unwrap(), master.next().unwrap()], [0.8, 0.8]);
+    }
+
+    #[test]
+    fn empty_bus_plays_silence() {
+        let (mixer, mut master) = AudioMixer::new(DEFAULT_SAMPLE_RATE);
+        mixer.bus(AudioBus::MUSIC);
+        assert_eq!(master.by_ref().take(8).collect::<Vec<_>>(), vec![0.0; 8]);
+        assert!(mixer.new_sink(AudioBus::MUSIC).is_none());
+    }
+}
@@ -0,0 +1,868 @@
+use crate::{Decodable, StreamedAudioSource};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy_utils::tracing::warn;
+use parking_lot::Mutex;
+use rodio::{cpal::Sample as CpalSample, Sample, Source};
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// A region of a sound played in a loop, after the part of the sound before it
+///
+/// A music with an intro loops its body without replaying the intro:
+///
+/// ```
+/// # use std::time::Duration;
+/// # use bevy_ecs::system::Res;
+/// # use bevy_asset::AssetServer;
+/// # use bevy_audio::{Audio, LoopRegion, PlaybackSettings};
+/// fn play_music(asset_server: Res<AssetServer>, audio: Res<Audio>) {
+///     audio.play_with_settings(
+///         asset_server.load("music.ogg"),
+///         PlaybackSettings::LOOP.with_loop_region(LoopRegion::new(
+///             Duration::from_secs_f32(12.5),
+///             Some(Duration::from_secs(96)),
+///         )),
+///     );
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LoopRegion {
+    /// The start of the region
+    pub start: Duration,
+    /// The end of the region, or `None` to loop until the end of the sound
+    pub end: Option<Duration>,
+}
+
+impl LoopRegion {
+    /// Creates a region from `start` to `end`, or to the end of the sound if `end` is `None`.
+    pub const fn new(start: Duration, end: Option<Duration>) -> Self {
+        Self { start, end }
+    }
+}
+
+enum PlaybackCommand {
+    Seek(Duration),
+    SetLoopRegion(Option<LoopRegion>),
+}
+
+/// Seeks and loops a [`Playback`] source from the main thread
+pub(crate) struct PlaybackControls {
+    // the sender isn't `Sync` before Rust 1.72
+    commands: Mutex<Sender<PlaybackCommand>>,
+    loop_region: Mutex<Option<LoopRegion>>,
+    played: Arc<AtomicU64>,
+    // the number of seeks requested
+    seeks: Arc<AtomicU64>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl PlaybackControls {
+    pub(crate) fn position(&self) -> Duration {
+        let frames = self.played.load(Ordering::Relaxed) / self.channels.max(1) as u64;
+        Duration::from_secs_f64(frames as f64 / self.sample_rate.max(1) as f64)
+    }
+
+    pub(crate) fn seek(&self, position: Duration) {
+        self.seeks.fetch_add(1, Ordering::Relaxed);
+        let _ = self.commands.lock().send(PlaybackCommand::Seek(position));
+    }
+
+    pub(crate) fn loop_region(&self) -> Option<LoopRegion> {
+        *self.loop_region.lock()
+    }
+
+    pub(crate) fn set_loop_region(&self, region: Option<LoopRegion>) {
+        *self.loop_region.lock() = region;
+        let _ = self
+            .commands
+            .lock()
+            .send(PlaybackCommand::SetLoopRegion(region));
+    }
+}
+
+/// A source playing a sound from the position it was sought to, looping its [`LoopRegion`]
+///
+/// The input is cloned to play it again from the position it was at, so it's usually
+/// [buffered](Source::buffered) to not decode the sound again.
+pub(crate) struct Playback<I>
+where
+    I: Source + Clone,
+    I::Item: Sample,
+{
+    start: I,
+    input: I,
+    // the number of samples of the sound before the one `input` is at
+    samples: u64,
+    // the channel of the sample `input` is at
+    channel: u16,
+    commands: Receiver<PlaybackCommand>,
+    // the samples the loop region starts and ends at
+    loop_region: Option<(u64, Option<u64>)>,
+    // the input at the start of the loop region, once it was reached
+    loop_start: Option<I>,
+    // the number of seeks done
+    seeks: u64,
+    played: Arc<AtomicU64>,
+}
+
+impl<I> Playback<I>
+where
+    I: Source + Clone,
+    I::Item: Sample,
+{
+    pub(crate) fn new(input: I, loop_region: Option<LoopRegion>) -> (Self, PlaybackControls) {
+        let (sender, commands) = mpsc::channel();
+        let played = Arc::new(AtomicU64::new(0));
+        let controls = PlaybackControls {
+            commands: Mutex::new(sender),
+            loop_region: Mutex::new(loop_region),
+            played: played.clone(),
+            seeks: Arc::new(AtomicU64::new(0)),
+            channels: input.channels(),
+            sample_rate: input.sample_rate(),
+        };
+        let mut source = Self {
+            start: input.clone(),
+            input,
+            samples: 0,
+            channel: 0,
+            commands,
+            loop_region: None,
+            loop_start: None,
+            seeks: 0,
+            played,
+        };
+        source.set_loop_region(loop_region);
+        (source, controls)
+    }
+
+    /// The index of the first sample of the frame playing at `position`
+    fn sample_at(&self, position: Duration) -> u64 {
+        let frame = position.as_secs_f64() * self.start.sample_rate() as f64;
+        frame as u64 * self.start.channels() as u64
+    }
+
+    fn set_loop_region(&mut self, region: Option<LoopRegion>) {
+        self.loop_region = region.map(|region| {
+            (
+                self.sample_at(region.start),
+                region.end.map(|end| self.sample_at(end)),
+            )
+        });
+        self.loop_start = None;
+        if let Some((start, _)) = self.loop_region {
+            if start == self.samples {
+                self.loop_start = Some(self.input.clone());
+            }
+        }
+    }
+
+    /// Moves the input to the sample `target`, or to the end of the sound if it's shorter
+    fn seek_to_sample(&mut self, target: u64) {
+        if target < self.samples {
+            match (&self.loop_start, self.loop_region) {
+                (Some(loop_start), Some((start, _))) if start <= target => {
+                    self.input = loop_start.clone();
+                    self.samples = start;
+                }
+                _ => {
+                    self.input = self.start.clone();
+                    self.samples = 0;
+                }
+            }
+        }
+        while self.samples < target && self.input.next().is_some() {
+            self.samples += 1;
+        }
+        if let Some((start, _)) = self.loop_region {
+            if start == self.samples && self.loop_start.is_none() {
+                self.loop_start = Some(self.input.clone());
+            }
+        }
+    }
+
+    /// Moves the input back to the start of the loop region, returning false if there isn't one
+    /// or it's after the end of the sound
+    fn restart_loop(&mut self) -> bool {
+        let Some((start, _)) = self.loop_region else {
+            return false;
+        };
+        match &self.loop_start {
+            Some(loop_start) => {
+                self.input = loop_start.clone();
+                self.samples = start;
+            }
+            None => self.seek_to_sample(start),
+        }
+        self.samples == start
+    }
+
+    fn handle_commands(&mut self) {
+        while let Ok(command) = self.commands.try_recv() {
+            match command {
+                PlaybackCommand::Seek(position) => {
+                    self.seek_to_sample(self.sample_at(position));
+                    self.seeks += 1;
+                }
+                PlaybackCommand::SetLoopRegion(region) => self.set_loop_region(region),
+            }
+        }
+    }
+}
+
+impl<I> Iterator for Playback<I>
+where
+    I: Source + Clone,
+    I::Item: Sample,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        // only move the input between frames, to keep the channels in order
+        if self.channel == 0 {
+            self.handle_commands();
+            if let Some((start, end)) = self.loop_region {
+                if start == self.samples && self.loop_start.is_none() {
+                    self.loop_start = Some(self.input.clone());
+                } else if matches!(end, Some(end) if self.samples >= end && end > start) {
+                    self.restart_loop();
+                }
+            }
+        }
+        let sample = match self.input.next() {
+            Some(sample) => sample,
+            None => {
+                if self.samples == 0 || !self.restart_loop() {
+                    return None;
+                }
+                self.channel = 0;
+                self.input.next()?
+            }
+        };
+        self.samples += 1;
+        self.channel = (self.channel + 1) % self.start.channels().max(1);
+        self.played.store(self.samples, Ordering::Relaxed);
+        Some(sample)
+    }
+}
+
+impl<I> Source for Playback<I>
+where
+    I: Source + Clone,
+    I::Item: Sample,
+{
+    fn current_frame_len(&self) -> Option<usize> {
+        self.input.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.input.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        if self.loop_region.is_some() {
+            None
+        } else {
+            self.start.total_duration()
+        }
+    }
+}
+
+/// The number of frames of the chunks decoded by a stream
+const STREAM_CHUNK_FRAMES: usize = 2048;
+
+/// The number of chunks a stream decodes ahead of the playback
+#[cfg(not(target_arch = "wasm32"))]
+const STREAM_AHEAD_CHUNKS: usize = 8;
+
+/// A decoder of a [`StreamedAudioSource`], which is cloned by decoding the sound again up to
+/// its position, instead of being buffered
+struct StreamedDecoder {
+    source: StreamedAudioSource,
+    decoder: <StreamedAudioSource as Decodable>::Decoder,
+    samples: u64,
+}
+
+impl StreamedDecoder {
+    fn new(source: StreamedAudioSource) -> Self {
+        Self {
+            decoder: source.decoder(),
+            source,
+            samples: 0,
+        }
+    }
+}
+
+impl Clone for StreamedDecoder {
+    fn clone(&self) -> Self {
+        let mut decoder = Self::new(self.source.clone());
+        while decoder.samples < self.samples && decoder.next().is_some() {}
+        decoder
+    }
+}
+
+impl Iterator for StreamedDecoder {
+    type Item = <StreamedAudioSource as Decodable>::DecoderItem;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let sample = self.decoder.next()?;
+        self.samples += 1;
+        Some(sample)
+    }
+}
+
+impl Source for StreamedDecoder {
+    fn current_frame_len(&self) -> Option<usize> {
+        self.decoder.current_frame_len()
+    }
+
+    fn channels(&self) -> u16 {
+        self.decoder.channels()
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.decoder.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.decoder.total_duration()
+    }
+}
+
+/// Consecutive samples of a stream
+struct StreamChunk {
+    // the number of seeks done before the chunk was decoded
+    seeks: u64,
+    // the index of the first sample of the chunk in the sound
+    start: u64,
+    samples: Vec<f32>,
+}
+
+/// Decodes the chunks of a stream, in the background on the platforms with threads
+struct StreamDecoder {
+    playback: Playback<StreamedDecoder>,
+    // the first sample of the next chunk, read at the end of the previous one
+    next: Option<(f32, u64, u64)>,
+}
+
+impl StreamDecoder {
+    /// Reads the next sample of the playback, with its index and the seeks done before it
+    fn next_sample(&mut self) -> Option<(f32, u64, u64)> {
+        let sample = self.playback.next()?;
+        Some((
+            sample.to_sample(),
+            self.playback.samples - 1,
+            self.playback.seeks,
+        ))
+    }
+
+    /// Decodes the next chunk, or returns `None` at the end of the sound
+    fn next_chunk(&mut self) -> Option<StreamChunk> {
+        let (sample, start, seeks) = self.next.take().or_else(|| self.next_sample())?;
+        let len = STREAM_CHUNK_FRAMES * self.playback.channels() as usize;
+        let mut chunk = StreamChunk {
+            seeks,
+            start,
+            samples: Vec::with_capacity(len),
+        };
+        chunk.samples.push(sample);
+        while chunk.samples.len() < len {
+            let Some(next) = self.next_sample() else {
+                break;
+            };
+            // a chunk ends where the playback seeks or loops
+            if next.1 != chunk.start + chunk.samples.len() as u64 || next.2 != chunk.seeks {
+                self.next = Some(next);
+                break;
+            }
+            chunk.samples.push(next.0);
+        }
+        Some(chunk)
+    }
+}
+
+/// A source playing a [`StreamedAudioSource`] decoded in the background
+///
+/// The sound is decoded a few chunks ahead of the playback, playing silence if the decoding
+/// falls behind. The chunks decoded before a seek are skipped.
+pub(crate) struct StreamedPlayback {
+    #[cfg(not(target_arch = "wasm32"))]
+    chunks: Receiver<StreamChunk>,
+    #[cfg(target_arch = "wasm32")]
+    decoder: StreamDecoder,
+    chunk: StreamChunk,
+    index: usize,
+    // the channel of the next sample
+    channel: u16,
+    // playing silence until the end of the frame, as the next chunk isn't decoded yet
+    silent: bool,
+    seeks: Arc<AtomicU64>,
+    // the seeks requested when the frame started
+    seeks_requested: u64,
+    played: Arc<AtomicU64>,
+    channels: u16,
+    sample_rate: u32,
+}
+
+impl StreamedPlayback {
+    pub(crate) fn new(
+        source: &StreamedAudioSource,
+        loop_region: Option<LoopRegion>,
+    ) -> (Self, PlaybackControls) {
+        let (mut playback, controls) =
+            Playback::new(StreamedDecoder::new(source.clone()), loop_region);
+        // the position is the one of the samples played, not of the ones decoded
+        playback.played = Arc::default();
+        let decoder = StreamDecoder {
+            playback,
+            next: None,
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let chunks = {
+            let (sender, chunks) = mpsc::sync_channel(STREAM_AHEAD_CHUNKS);
+            let mut decoder = decoder;
+            let spawned = std::thread::Builder::new()
+                .name("audio stream".to_string())
+                .spawn(move || {
+                    // stops when the sound ends or when its sink is dropped
+                    while let Some(chunk) = decoder.next_chunk() {
+                        if sender.send(chunk).is_err() {
+                            break;
+                        }
+                    }
+                });
+            if let Err(err) = spawned {
+                warn!("Error spawning the thread decoding an audio stream: {err:?}");
+            }
+            chunks
+        };
+
+        let source = Self {
+            #[cfg(not(target_arch = "wasm32"))]
+            chunks,
+            #[cfg(target_arch = "wasm32")]
+            decoder,
+            chunk: StreamChunk {
+                seeks: 0,
+                start: 0,
+                samples: Vec::new(),
+            },
+            index: 0,
+            channel: 0,
+            silent: false,
+            seeks: controls.seeks.clone(),
+            seeks_requested: 0,
+            played: controls.played.clone(),
+            channels: controls.channels,
+            sample_rate: controls.sample_rate,
+        };
+        (source, controls)
+    }
+
+    /// The next decoded chunk, `Err(true)` if the sound ended and `Err(false)` if the chunk
+    /// isn't decoded yet
+    #[cfg(not(target_arch = "wasm32"))]
+    fn next_chunk(&mut self) -> Result<StreamChunk, bool> {
+        self.chunks
+            .try_recv()
+            .map_err(|err| err == mpsc::TryRecvError::Disconnected)
+    }
+
+    /// The next decoded chunk, decoded on the audio thread as there are no threads to decode
+    /// it in the background, or `Err(true)` if the sound ended
+    #[cfg(target_arch = "wasm32")]
+    fn next_chunk(&mut self) -> Result<StreamChunk, bool> {
+        self.decoder.next_chunk().ok_or(true)
+    }
+}
+
+impl Iterator for StreamedPlayback {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        // only skip the chunks decoded before a seek between frames, to keep the channels in
+        // order, as the chunks always end between frames
+        if self.channel == 0 {
+            self.seeks_requested = self.seeks.load(Ordering::Relaxed);
+            self.silent = false;
+        }
+        let sample = if self.silent {
+            0.0
+        } else {
+            loop {
+                if self.chunk.seeks == self.seeks_requested {
+                    if let Some(&sample) = self.chunk.samples.get(self.index) {
+                        self.index += 1;
+                        self.played
+                            .store(self.chunk.start + self.index as u64, Ordering::Relaxed);
+                        break sample;
+                    }
+                }
+                match self.next_chunk() {
+                    Ok(chunk) => {
+                        self.chunk = chunk;
+                        self.index = 0;
+                    }
+                    Err(true) => return None,
+                    Err(false) => {
+                        self.silent = true;
+                        break 0.0;
+                    }
+                }
+            }
+        };
+        self.channel = (self.channel + 1) % self.channels.max(1);
+        Some(sample)
+    }
+}
+
+impl Source for StreamedPlayback {
+    fn current_frame_len(&self) -> Option<usize> {
+        None
+    }
+
+    fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rodio::{buffer::SamplesBuffer, source::Buffered};
+
+    /// A sound of 4 seconds with 2 channels at 10 Hz, where each sample is its index
+    fn sound() -> Buffered<SamplesBuffer<f32>> {
+        let samples = (0..80).map(|sample| sample as f32).collect::<Vec<_>>();
+        SamplesBuffer::new(2, 10, samples).buffered()
+    }
+
+    fn ms(millis: u64) -> Duration {
+        Duration::from_millis(millis)
+    }
+
+    /// The indices of the next `len` samples played
+    fn take(playback: &mut impl Iterator<Item = f32>, len: usize) -> Vec<u64> {
+        playback.take(len).map(|sample| sample as u64).collect()
+    }
+
+    #[test]
+    fn position_follows_played_samples() {
+        let (mut playback, controls) = Playback::new(sound(), None);
+        assert_eq!(controls.position(), Duration::ZERO);
+        take(&mut playback, 10);
+        assert_eq!(controls.position(), ms(500));
+        // the position is the one of the last frame started
+        take(&mut playback, 1);
+        assert_eq!(controls.position(), ms(500));
+        take(&mut playback, 1);
+        assert_eq!(controls.position(), ms(600));
+    }
+
+    #[test]
+    fn seek_moves_to_frame() {
+        // (position before the seek, position sought, first sample after the seek)
+        let cases = [
+            (0, 1000, 20),
+            (0, 2550, 50),
+            (2000, 500, 10),
+            (2000, 2000, 40),
+            (2000, 0, 0),
+        ];
+        for (before, position, expected) in cases {
+            let (mut playback, controls) = Playback::new(sound(), None);
+            take(&mut playback, before / 50);
+            controls.seek(ms(position));
+            assert_eq!(
+                take(&mut playback, 2),
+                [expected, expected + 1],
+                "seek from {before}ms to {position}ms"
+            );
+            assert_eq!(controls.position(), ms(expected * 50 + 100));
+        }
+    }
+
+    #[test]
+    fn seek_waits_for_the_end_of_the_frame() {
+        let (mut playback, controls) = Playback::new(sound(), None);
+        take(&mut playback, 1);
+        controls.seek(ms(3000));
+        assert_eq!(take(&mut playback, 3), [1, 60, 61]);
+    }
+
+    #[test]
+    fn seek_after_the_end_ends_the_sound() {
+        let (mut playback, controls) = Playback::new(sound(), None);
+        controls.seek(ms(10000));
+        assert_eq!(playback.next(), None);
+    }
+
+    #[test]
+    fn loop_region_wraps_at_its_end() {
+        // (region, indices of the samples played)
+        let cases = [
+            (
+                LoopRegion::new(ms(1000), Some(ms(1500))),
+                [
+                    (0..30).collect::<Vec<_>>(),
+                    (20..30).collect(),
+                    (20..30).collect(),
+                ],
+            ),
+            // to the end of the sound
+            (
+                LoopRegion::new(ms(3000), None),
+                [(0..80).collect(), (60..80).collect(), (60..80).collect()],
+            ),
+            // the whole sound
+            (
+                LoopRegion::new(Duration::ZERO, None),
+                [(0..80).collect(), (0..80).collect(), (0..80).collect()],
+            ),
+            // an end after the end of the sound
+            (
+                LoopRegion::new(ms(3500), Some(ms(10000))),
+                [(0..80).collect(), (70..80).collect(), (70..80).collect()],
+            ),
+        ];
+        for (region, expected) in cases {
+            let (mut playback, _) = Playback::new(sound(), Some(region));
+            let expected = expected.concat();
+            assert_eq!(take(&mut playback, expected.len()), expected, "{region:?}");
+        }
+    }
+
+    #[test]
+    fn loop_region_after_the_end_ends_the_sound() {
+        let (playback, _) = Playback::new(sound(), Some(LoopRegion::new(ms(5000), None)));
+        assert_eq!(playback.count(), 80);
+    }
+
+    #[test]
+    fn loop_region_end_before_its_start_is_ignored() {
+        let region = LoopRegion::new(ms(2000), Some(ms(1000)));
+        let (mut playback, _) = Playback::new(sound(), Some(region));
+        let expected = [(0..80).collect::<Vec<_>>(), (40..80).collect()].concat();
+        assert_eq!(take(&mut playback, expected.len()), expected);
+    }
+
+    #[test]
+    fn position_wraps_with_loop() {
+        let region = LoopRegion::new(ms(1000), Some(ms(1500)));
+        let (mut playback, controls) = Playback::new(sound(), Some(region));
+        take(&mut playback, 30);
+        assert_eq!(controls.position(), ms(1500));
+        take(&mut playback, 2);
+        assert_eq!(controls.position(), ms(1100));
+    }
+
+    #[test]
+    fn seek_in_and_out_of_loop_region() {
+        let region = LoopRegion::new(ms(1000), Some(ms(1500)));
+        let (mut playback, controls) = Playback::new(sound(), Some(region));
+        take(&mut playback, 24);
+
+        // before the region, the sound plays until the region and loops it
+        controls.seek(ms(500));
+        assert_eq!(
+            take(&mut playback, 30),
+            [(10..30).collect::<Vec<_>>(), (20..30).collect()].concat()
+        );
+
+        // after the region, the sound moves back to the start of the region
+        controls.seek(ms(3500));
+        assert_eq!(
+            take(&mut playback, 12),
+            [(20..30).collect::<Vec<_>>(), vec![20, 21]].concat()
+        );
+    }
+
+    #[test]
+    fn loop_region_changed_while_playing() {
+        let (mut playback, controls) = Playback::new(sound(), None);
+        take(&mut playback, 40);
+        controls.set_loop_region(Some(LoopRegion::new(ms(500), Some(ms(2500)))));
+        assert_eq!(
+            controls.loop_region(),
+            Some(LoopRegion::new(ms(500), Some(ms(2500))))
+        );
+        assert_eq!(
+            take(&mut playback, 20),
+            [(40..50).collect::<Vec<_>>(), (10..20).collect()].concat()
+        );
+
+        controls.set_loop_region(None);
+        take(&mut playback, 30);
+        assert_eq!(take(&mut playback, 32), (50..80).collect::<Vec<_>>());
+        assert_eq!(playback.total_duration(), sound().total_duration());
+    }
+
+    /// A WAV sound of `frames` frames with 2 channels at 8000 Hz, where the sample `i` is
+    /// `i % 30000 + 1`, so that it's never silent
+    fn streamed_sound(frames: usize) -> StreamedAudioSource {
+        let len = frames as u32 * 4;
+        let mut bytes = Vec::with_capacity(44 + len as usize);
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + len).to_le_bytes());
+        bytes.extend_from_slice(b"WAVEfmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        // PCM, 2 channels, 8000 Hz, 32000 bytes per second, 4 bytes per frame, 16 bits
+        for value in [1u16, 2] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [8000u32, 32000] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        for value in [4u16, 16] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&len.to_le_bytes());
+        for sample in 0..frames * 2 {
+            bytes.extend_from_slice(&((sample % 30000) as i16 + 1).to_le_bytes());
+        }
+        StreamedAudioSource {
+            bytes: bytes.into(),
+        }
+    }
+
+    /// The samples of the whole sound, decoded at once
+    fn full_decode(source: &StreamedAudioSource) -> Vec<f32> {
+        source.decoder().map(|sample| sample.to_sample()).collect()
+    }
+
+    fn stream_decoder(
+        source: &StreamedAudioSource,
+        loop_region: Option<LoopRegion>,
+    ) -> (StreamDecoder, PlaybackControls) {
+        let (playback, controls) = Playback::new(StreamedDecoder::new(source.clone()), loop_region);
+        let decoder = StreamDecoder {
+            playback,
+            next: None,
+        };
+        (decoder, controls)
+    }
+
+    /// Plays the next `len` samples of the stream, skipping the silence played while the
+    /// chunks are decoded
+    fn play_streamed(playback: &mut StreamedPlayback, len: usize) -> Vec<f32> {
+        let mut samples = Vec::new();
+        while samples.len() < len {
+            match playback.next() {
+                Some(sample) if sample != 0.0 => samples.push(sample),
+                Some(_) => std::thread::yield_now(),
+                None => break,
+            }
+        }
+        samples
+    }
+
+    #[test]
+    fn stream_is_decoded_in_chunks() {
+        let frames = STREAM_CHUNK_FRAMES * 3 + 100;
+        let source = streamed_sound(frames);
+        let (mut decoder, _) = stream_decoder(&source, None);
+        let chunks = std::iter::from_fn(|| decoder.next_chunk()).collect::<Vec<_>>();
+
+        let chunk_len = STREAM_CHUNK_FRAMES * 2;
+        let lens = chunks
+            .iter()
+            .map(|chunk| chunk.samples.len())
+            .collect::<Vec<_>>();
+        assert_eq!(lens, [chunk_len, chunk_len, chunk_len, 200]);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.start, (i * chunk_len) as u64);
+            assert_eq!(chunk.seeks, 0);
+        }
+        let samples = chunks
+            .into_iter()
+            .flat_map(|chunk| chunk.samples)
+            .collect::<Vec<_>>();
+        assert_eq!(samples, full_decode(&source));
+    }
+
+    #[test]
+    fn stream_chunk_starts_at_seek() {
+        let source = streamed_sound(STREAM_CHUNK_FRAMES * 4);
+        let full = full_decode(&source);
+        let (mut decoder, controls) = stream_decoder(&source, None);
+        decoder.next_chunk().unwrap();
+
+        controls.seek(ms(500));
+        let chunk = decoder.next_chunk().unwrap();
+        assert_eq!((chunk.seeks, chunk.start), (1, 8000));
+        assert_eq!(chunk.samples, full[8000..8000 + STREAM_CHUNK_FRAMES * 2]);
+    }
+
+    #[test]
+    fn stream_chunk_ends_at_loop() {
+        // from the frame 1000 to the frame 2400
+        let region = LoopRegion::new(ms(125), Some(ms(300)));
+        let source = streamed_sound(STREAM_CHUNK_FRAMES * 4);
+        let full = full_decode(&source);
+        let (mut decoder, _) = stream_decoder(&source, Some(region));
+
+        decoder.next_chunk().unwrap();
+        let chunk = decoder.next_chunk().unwrap();
+        assert_eq!(chunk.samples, full[STREAM_CHUNK_FRAMES * 2..4800]);
+        let chunk = decoder.next_chunk().unwrap();
+        assert_eq!((chunk.seeks, chunk.start), (0, 2000));
+        assert_eq!(chunk.samples, full[2000..4800]);
+    }
+
+    #[test]
+    fn streamed_playback_matches_full_decode() {
+        let source = streamed_sound(STREAM_CHUNK_FRAMES * 12 + 100);
+        let full = full_decode(&source);
+        let (mut playback, controls) = StreamedPlayback::new(&source, None);
+        assert_eq!(play_streamed(&mut playback, usize::MAX), full);
+        assert_eq!(playback.next(), None);
+        assert_eq!(
+            controls.position(),
+            Duration::from_secs_f64(full.len() as f64 / 2.0 / 8000.0)
+        );
+    }
+
+    #[test]
+    fn streamed_playback_skips_chunks_decoded_before_seek() {
+        let source = streamed_sound(STREAM_CHUNK_FRAMES * 12);
+        let full = full_decode(&source);
+        let (mut playback, controls) = StreamedPlayback::new(&source, None);
+        assert_eq!(play_streamed(&mut playback, 100), full[..100]);
+
+        controls.seek(ms(2000));
+        let samples = play_streamed(&mut playback, usize::MAX);
+        assert_eq!(samples, full[32000..]);
+    }
+
+    #[test]
+    fn streamed_playback_loops() {
+        let source = streamed_sound(STREAM_CHUNK_FRAMES * 3);
+        let full = full_decode(&source);
+        let region = LoopRegion::new(ms(250), Some(ms(500)));
+        let (mut playback, controls) = StreamedPlayback::new(&source, Some(region));
+        let expected = [&full[..8000], &full[4000..8000], &full[4000..8000]].concat();
+        assert_eq!(play_streamed(&mut playback, expected.len()), expected);
+        assert_eq!(controls.position(), ms(500));
+    }
+}
@@ -1,7 +1,16 @@
+use crate::{
+    AudioEffect, DistanceAttenuation, EffectChain, LoopRegion, PlaybackControls, SpatialPanning,
+    SpatialState,
+};
 use bevy_math::Vec3;
 use bevy_reflect::TypeUuid;
 use bevy_transform::prelude::Transform;
-use rodio::{Sink, SpatialSink};
+use parking_lot::Mutex;
+use rodio::Sink;
+use std::{
+    sync::atomic::{AtomicBool, AtomicU32, Ordering},
+    time::Duration,
+};
 
 /// Common interactions with an audio sink.
 pub trait AudioSinkPlayback {
@@ -20,13 +29,20 @@ pub trait AudioSinkPlayback {
     /// Gets the speed of the sound.
     ///
     /// The value `1.0` is the "normal" speed (unfiltered input). Any value other than `1.0`
-    /// will change the play speed of the sound.
+    /// will change the play speed of the sound, and its pitch with it.
+    ///
+    /// It doesn't include the [`GlobalAudioSpeed`](crate::GlobalAudioSpeed) the sound may be
+    /// played at.
     fn speed(&self) -> f32;
 
     /// Changes the speed of the sound.
     ///
     /// The value `1.0` is the "normal" speed (unfiltered input). Any value other than `1.0`
-    /// will change the play speed of the sound.
+    /// will change the play speed of the sound, and its pitch with it.
+    ///
+    /// Unless the sound was played without
+    /// [`PlaybackSettings::global_speed`](crate::PlaybackSettings::global_speed), it's played at
+    /// this speed multiplied by the [`GlobalAudioSpeed`](crate::GlobalAudioSpeed).
     fn set_speed(&self, speed: f32);
 
     /// Resumes playback of a paused sink.
@@ -60,6 +76,79 @@ pub trait AudioSinkPlayback {
     ///
     /// It won't be possible to restart it afterwards.
     fn stop(&self);
+
+    /// The position of the playback in the sound, like the beat of a music a rhythm game is
+    /// synchronized to.
+    ///
+    /// It's the position of the sample the sink last played, so it doesn't include the
+    /// [speed](Self::set_speed): playing a sound faster moves the position faster.
+    fn position(&self) -> Duration;
+
+    /// Moves the playback to `position`, or to the end of the sound if it's shorter.
+    ///
+    /// The sink seeks on the audio thread, before playing its next sample. It can't seek once the
+    /// sound has ended, unless it's looping, and seeking after the end of its
+    /// [loop region](Self::loop_region) moves the playback back to the start of the region.
+    fn seek(&self, position: Duration);
+
+    /// Gets the region of the sound played in a loop, if any.
+    fn loop_region(&self) -> Option<LoopRegion>;
+
+    /// Changes the region of the sound played in a loop, or stops looping the sound if it is
+    /// `None`.
+    fn set_loop_region(&self, region: Option<LoopRegion>);
+}
+
+/// The speed of a sink, and the global speed it's multiplied by
+pub(crate) struct SinkSpeed {
+    speed: AtomicU32,
+    global: AtomicU32,
+    follows_global: bool,
+    paused_by_global: AtomicBool,
+}
+
+impl SinkSpeed {
+    pub(crate) fn new(follows_global: bool, global: f32) -> Self {
+        Self {
+            speed: AtomicU32::new(1.0f32.to_bits()),
+            global: AtomicU32::new(if follows_global { global } else { 1.0 }.to_bits()),
+            follows_global,
+            paused_by_global: AtomicBool::new(false),
+        }
+    }
+
+    fn speed(&self) -> f32 {
+        f32::from_bits(self.speed.load(Ordering::Relaxed))
+    }
+
+    fn set_speed(&self, sink: &Sink, speed: f32) {
+        self.speed.store(speed.to_bits(), Ordering::Relaxed);
+        self.apply(sink);
+    }
+
+    pub(crate) fn set_global(&self, sink: &Sink, global: f32) {
+        if self.follows_global {
+            self.global.store(global.to_bits(), Ordering::Relaxed);
+            self.apply(sink);
+        }
+    }
+
+    fn apply(&self, sink: &Sink) {
+        let global = f32::from_bits(self.global.load(Ordering::Relaxed));
+        // a sink can't play at a speed of zero, it's paused instead until the global speed
+        // increases
+        if global <= 0.0 {
+            if !sink.is_paused() {
+                sink.pause();
+                self.paused_by_global.store(true, Ordering::Relaxed);
+            }
+            return;
+        }
+        if self.paused_by_global.swap(false, Ordering::Relaxed) {
+            sink.play();
+        }
+        sink.set_speed(self.speed() * global);
+    }
 }
 
 /// Asset controlling the playback of a sound
@@ -90,6 +179,9 @@ pub struct AudioSink {
     // This field is an Option in order to allow us to have a safe drop that will detach the sink.
     // It will never be None during its life
     pub(crate) sink: Option<Sink>,
+    pub(crate) effects: EffectChain,
+    pub(crate) speed: SinkSpeed,
+    pub(crate) playback: PlaybackControls,
 }
 
 impl Drop for AudioSink {
@@ -108,14 +200,15 @@ impl AudioSinkPlayback for AudioSink {
     }
 
     fn speed(&self) -> f32 {
-        self.sink.as_ref().unwrap().speed()
+        self.speed.speed()
     }
 
     fn set_speed(&self, speed: f32) {
-        self.sink.as_ref().unwrap().set_speed(speed);
+        self.speed.set_speed(self.sink.as_ref().unwrap(), speed);
     }
 
     fn play(&self) {
+        self.speed.paused_by_global.store(false, Ordering::Relaxed);
         self.sink.as_ref().unwrap().play();
     }
 
@@ -130,10 +223,47 @@ impl AudioSinkPlayback for AudioSink {
     fn stop(&self) {
         self.sink.as_ref().unwrap().stop();
     }
+
+    fn position(&self) -> Duration {
+        self.playback.position()
+    }
+
+    fn seek(&self, position: Duration) {
+        self.playback.seek(position);
+    }
+
+    fn loop_region(&self) -> Option<LoopRegion> {
+        self.playback.loop_region()
+    }
+
+    fn set_loop_region(&self, region: Option<LoopRegion>) {
+        self.playback.set_loop_region(region);
+    }
+}
+
+impl AudioSink {
+    /// Adds an effect at the end of the effect chain of the sink, applied before the effects of
+    /// its [`AudioBus`](crate::AudioBus).
+    pub fn add_effect(&self, effect: impl AudioEffect) {
+        self.effects.add(effect);
+    }
+
+    /// Removes all the effects of the sink.
+    pub fn clear_effects(&self) {
+        self.effects.clear();
+    }
+
+    pub(crate) fn set_global_speed(&self, global: f32) {
+        self.speed.set_global(self.sink.as_ref().unwrap(), global);
+    }
 }
 
 /// Asset controlling the playback of a sound, or the locations of its listener and emitter.
 ///
+/// The locations are usually set each frame from the transforms of a
+/// [`SpatialAudioSource`](crate::SpatialAudioSource) and of an
+/// [`AudioListener`](crate::AudioListener).
+///
 /// ```
 /// # use bevy_ecs::system::{Local, Res};
 /// # use bevy_asset::{Assets, Handle};
@@ -156,7 +286,11 @@ impl AudioSinkPlayback for AudioSink {
 pub struct SpatialAudioSink {
     // This field is an Option in order to allow us to have a safe drop that will detach the sink.
     // It will never be None during its life
-    pub(crate) sink: Option<SpatialSink>,
+    pub(crate) sink: Option<Sink>,
+    pub(crate) effects: EffectChain,
+    pub(crate) speed: SinkSpeed,
+    pub(crate) playback: PlaybackControls,
+    pub(crate) spatial: Mutex<SpatialState>,
 }
 
 impl Drop for SpatialAudioSink {
@@ -175,14 +309,15 @@ impl AudioSinkPlayback for SpatialAudioSink {
     }
 
     fn speed(&self) -> f32 {
-        self.sink.as_ref().unwrap().speed()
+        self.speed.speed()
     }
 
     fn set_speed(&self, speed: f32) {
-        self.sink.as_ref().unwrap().set_speed(speed);
+        self.speed.set_speed(self.sink.as_ref().unwrap(), speed);
     }
 
     fn play(&self) {
+        self.speed.paused_by_global.store(false, Ordering::Relaxed);
         self.sink.as_ref().unwrap().play();
     }
 
@@ -197,14 +332,31 @@ impl AudioSinkPlayback for SpatialAudioSink {
     fn stop(&self) {
         self.sink.as_ref().unwrap().stop();
     }
+
+    fn position(&self) -> Duration {
+        self.playback.position()
+    }
+
+    fn seek(&self, position: Duration) {
+        self.playback.seek(position);
+    }
+
+    fn loop_region(&self) -> Option<LoopRegion> {
+        self.playback.loop_region()
+    }
+
+    fn set_loop_region(&self, region: Option<LoopRegion>) {
+        self.playback.set_loop_region(region);
+    }
 }
 
 impl SpatialAudioSink {
     /// Set the two ears position.
     pub fn set_ears_position(&self, left_position: Vec3, right_position: Vec3) {
-        let sink = self.sink.as_ref().unwrap();
-        sink.set_left_ear_position(left_position.to_array());
-        sink.set_right_ear_position(right_position.to_array());
+        let mut spatial = self.spatial.lock();
+        spatial.left_ear = left_position;
+        spatial.right_ear = right_position;
+        spatial.update_params();
     }
 
     /// Set the listener position, with an ear on each side separated by `gap`.
@@ -215,11 +367,112 @@ impl SpatialAudioSink {
         );
     }
 
+    /// Adds an effect at the end of the effect chain of the sink, applied before the effects of
+    /// its [`AudioBus`](crate::AudioBus).
+    pub fn add_effect(&self, effect: impl AudioEffect) {
+        self.effects.add(effect);
+    }
+
+    /// Removes all the effects of the sink.
+    pub fn clear_effects(&self) {
+        self.effects.clear();
+    }
+
+    pub(crate) fn set_global_speed(&self, global: f32) {
+        self.speed.set_global(self.sink.as_ref().unwrap(), global);
+    }
+
     /// Set the emitter position.
     pub fn set_emitter_position(&self, position: Vec3) {
-        self.sink
-            .as_ref()
-            .unwrap()
-            .set_emitter_position(position.to_array());
+        let mut spatial = self.spatial.lock();
+        spatial.emitter = position;
+        spatial.update_params();
+    }
+
+    /// Gets how the volume decreases with the distance between the emitter and the listener.
+    pub fn attenuation(&self) -> DistanceAttenuation {
+        self.spatial.lock().attenuation
+    }
+
+    /// Changes how the volume decreases with the distance between the emitter and the listener.
+    pub fn set_attenuation(&self, attenuation: DistanceAttenuation) {
+        let mut spatial = self.spatial.lock();
+        spatial.attenuation = attenuation;
+        spatial.update_params();
+    }
+
+    /// Gets how the sound is split between the ears of the listener.
+    pub fn panning(&self) -> SpatialPanning {
+        self.spatial.lock().panning
+    }
+
+    /// Changes how the sound is split between the ears of the listener.
+    pub fn set_panning(&self, panning: SpatialPanning) {
+        let mut spatial = self.spatial.lock();
+        spatial.panning = panning;
+        spatial.update_params();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sink_speed_is_multiplied_by_global_speed() {
+        // (follows the global speed, speed, global speed, speed of the sink)
+        let cases = [
+            (true, 1.0, 1.0, 1.0),
+            (true, 2.0, 0.5, 1.0),
+            (true, 1.5, 2.0, 3.0),
+            (false, 1.5, 2.0, 1.5),
+            (false, 1.0, 0.25, 1.0),
+        ];
+        for (follows_global, speed, global, expected) in cases {
+            let (sink, _output) = Sink::new_idle();
+            let sink_speed = SinkSpeed::new(follows_global, 1.0);
+            sink_speed.set_speed(&sink, speed);
+            sink_speed.set_global(&sink, global);
+            assert_eq!(sink_speed.speed(), speed);
+            assert_eq!(
+                sink.speed(),
+                expected,
+                "speed {speed}, global speed {global}, follows global {follows_global}"
+            );
+
+            // the global speed at creation is applied with the speed
+            let (sink, _output) = Sink::new_idle();
+            SinkSpeed::new(follows_global, global).set_speed(&sink, speed);
+            assert_eq!(sink.speed(), expected);
+        }
+    }
+
+    #[test]
+    fn zero_global_speed_pauses_sink() {
+        let (sink, _output) = Sink::new_idle();
+        let sink_speed = SinkSpeed::new(true, 1.0);
+        sink_speed.set_speed(&sink, 2.0);
+
+        sink_speed.set_global(&sink, 0.0);
+        assert!(sink.is_paused());
+        assert_eq!(sink.speed(), 2.0);
+        // changing the speed while paused doesn't resume the sink
+        sink_speed.set_speed(&sink, 3.0);
+        assert!(sink.is_paused());
+
+        sink_speed.set_global(&sink, 0.5);
+        assert!(!sink.is_paused());
+        assert_eq!(sink.speed(), 1.5);
+    }
+
+    #[test]
+    fn sink_paused_before_zero_global_speed_stays_paused() {
+        let (sink, _output) = Sink::new_idle();
+        let sink_speed = SinkSpeed::new(true, 1.0);
+        sink.pause();
+
+        sink_speed.set_global(&sink, 0.0);
+        sink_speed.set_global(&sink, 1.0);
+        assert!(sink.is_paused());
     }
 }
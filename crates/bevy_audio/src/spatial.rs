@@ -0,0 +1,582 @@
+use crate::SpatialAudioSink;
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::prelude::*;
+use bevy_math::Vec3;
+use bevy_transform::prelude::GlobalTransform;
+use rodio::{Sink, Source};
+use std::{
+    f32::consts::{FRAC_PI_4, TAU},
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+/// The largest delay between the two ears, for a sound coming from one side of the listener
+const MAX_INTERAURAL_DELAY: f32 = 0.00066;
+
+/// The cutoff frequency of the head shadow on the far ear, for a sound coming from one side of
+/// the listener
+const MIN_HEAD_SHADOW_CUTOFF: f32 = 2500.0;
+
+const MAX_HEAD_SHADOW_CUTOFF: f32 = 20000.0;
+
+/// The smallest reference distance of a [`DistanceAttenuation`], to not divide by zero
+const MIN_REFERENCE_DISTANCE: f32 = 1e-4;
+
+/// How fast the gains and delays of a [`SpatialAudioSink`] follow their new values, to avoid
+/// clicks when the emitter or the listener moves
+const SMOOTHING_TIME: f32 = 0.01;
+
+/// Marks the entity whose [`GlobalTransform`] is the position of the listener of the
+/// [`SpatialAudioSource`]s
+///
+/// It's usually added to the camera. The ears of the listener are on its left and right, and
+/// when there are several listeners, the first one found is used.
+#[derive(Component, Clone, Copy, Debug, Default)]
+pub struct AudioListener;
+
+/// Moves the emitter of a [`SpatialAudioSink`] with the [`GlobalTransform`] of the entity, and its
+/// listener with the [`AudioListener`], each frame
+///
+/// ```
+/// # use bevy_ecs::system::{Commands, Res};
+/// # use bevy_asset::{AssetServer, Assets};
+/// # use bevy_audio::{Audio, SpatialAudioSink, SpatialAudioSource};
+/// # use bevy_math::Vec3;
+/// # use bevy_transform::prelude::{GlobalTransform, Transform};
+/// fn spawn_waterfall(
+///     mut commands: Commands,
+///     asset_server: Res<AssetServer>,
+///     audio: Res<Audio>,
+///     spatial_audio_sinks: Res<Assets<SpatialAudioSink>>,
+/// ) {
+///     let sink = audio.play_spatial(
+///         asset_server.load("waterfall.ogg"),
+///         Transform::IDENTITY,
+///         1.0,
+///         Vec3::ZERO,
+///     );
+///     commands.spawn((
+///         SpatialAudioSource::new(spatial_audio_sinks.get_handle(sink)),
+///         Transform::from_xyz(10.0, 0.0, -4.0),
+///         GlobalTransform::default(),
+///     ));
+/// }
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct SpatialAudioSource {
+    /// The sink playing the sound emitted by the entity
+    pub sink: Handle<SpatialAudioSink>,
+    /// How the volume decreases with the distance to the listener
+    pub attenuation: DistanceAttenuation,
+    /// How the sound is split between the ears of the listener
+    pub panning: SpatialPanning,
+}
+
+impl SpatialAudioSource {
+    /// Emits the sound of `sink` from the entity, with the default attenuation and panning.
+    pub fn new(sink: Handle<SpatialAudioSink>) -> Self {
+        Self {
+            sink,
+            attenuation: DistanceAttenuation::default(),
+            panning: SpatialPanning::default(),
+        }
+    }
+
+    /// Helper to set the attenuation of the sound.
+    pub fn with_attenuation(mut self, attenuation: DistanceAttenuation) -> Self {
+        self.attenuation = attenuation;
+        self
+    }
+
+    /// Helper to set the panning of the sound.
+    pub fn with_panning(mut self, panning: SpatialPanning) -> Self {
+        self.panning = panning;
+        self
+    }
+}
+
+/// How the volume of a spatial sound decreases with the distance between the emitter and the
+/// listener
+///
+/// The reference distances below `0.0001`, including the negative ones, are clamped to it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DistanceAttenuation {
+    /// The volume doesn't depend on the distance.
+    None,
+    /// The volume is divided by the distance beyond `reference_distance`, like a sound in the
+    /// open.
+    Inverse {
+        /// The distance up to which the sound is played at full volume
+        reference_distance: f32,
+    },
+    /// The volume decreases linearly from `reference_distance` to `max_distance`, where the
+    /// sound stops being heard.
+    Linear {
+        /// The distance up to which the sound is played at full volume
+        reference_distance: f32,
+        /// The distance from which the sound isn't heard anymore
+        max_distance: f32,
+    },
+    /// The volume is divided by the distance beyond `reference_distance` raised to the power
+    /// `rolloff`, decreasing faster than [`Inverse`](Self::Inverse) when `rolloff` is above 1.
+    Exponential {
+        /// The distance up to which the sound is played at full volume
+        reference_distance: f32,
+        /// The exponent of the distance
+        rolloff: f32,
+    },
+}
+
+impl Default for DistanceAttenuation {
+    fn default() -> Self {
+        Self::Inverse {
+            reference_distance: 1.0,
+        }
+    }
+}
+
+impl DistanceAttenuation {
+    /// The volume multiplier of a sound emitted at `distance` from the listener
+    pub fn gain(&self, distance: f32) -> f32 {
+        let inverse = |reference_distance: f32| {
+            let reference_distance = reference_distance.max(MIN_REFERENCE_DISTANCE);
+            reference_distance / distance.max(reference_distance)
+        };
+        match *self {
+            DistanceAttenuation::None => 1.0,
+            DistanceAttenuation::Inverse { reference_distance } => inverse(reference_distance),
+            DistanceAttenuation::Linear {
+                reference_distance,
+                max_distance,
+            } => {
+                if distance <= reference_distance {
+                    1.0
+                } else if distance >= max_distance {
+                    0.0
+                } else {
+                    1.0 - (distance - reference_distance) / (max_distance - reference_distance)
+                }
+            }
+            DistanceAttenuation::Exponential {
+                reference_distance,
+                rolloff,
+            } => inverse(reference_distance).powf(rolloff),
+        }
+    }
+}
+
+/// How a spatial sound, mixed to mono, is split between the ears of the listener
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum SpatialPanning {
+    /// The sound is panned between the ears with a constant power.
+    #[default]
+    Stereo,
+    /// The sound is panned between the ears, then reaches the far ear later and muffled by the
+    /// head.
+    ///
+    /// This approximates a head-related transfer function (HRTF) from the direction of the
+    /// emitter without measured data, which localizes the sounds better with headphones. It
+    /// doesn't tell a sound in front of the listener from a sound behind it.
+    Binaural,
+}
+
+/// The gains, delays and head shadows of the ears of a spatial sound, computed on the main thread
+/// and read by the [`Spatializer`] on the audio thread
+#[derive(Debug, Default)]
+pub(crate) struct SpatialParams {
+    left_gain: AtomicU32,
+    right_gain: AtomicU32,
+    left_delay: AtomicU32,
+    right_delay: AtomicU32,
+    left_cutoff: AtomicU32,
+    right_cutoff: AtomicU32,
+}
+
+fn store(value: &AtomicU32, f: f32) {
+    value.store(f.to_bits(), Ordering::Relaxed);
+}
+
+fn load(value: &AtomicU32) -> f32 {
+    f32::from_bits(value.load(Ordering::Relaxed))
+}
+
+/// The positions and settings of a [`SpatialAudioSink`]
+pub(crate) struct SpatialState {
+    pub(crate) left_ear: Vec3,
+    pub(crate) right_ear: Vec3,
+    pub(crate) emitter: Vec3,
+    pub(crate) attenuation: DistanceAttenuation,
+    pub(crate) panning: SpatialPanning,
+    pub(crate) params: Arc<SpatialParams>,
+}
+
+impl SpatialState {
+    pub(crate) fn new(left_ear: Vec3, right_ear: Vec3, emitter: Vec3) -> Self {
+        let state = Self {
+            left_ear,
+            right_ear,
+            emitter,
+            attenuation: DistanceAttenuation::default(),
+            panning: SpatialPanning::default(),
+            params: Arc::default(),
+        };
+        state.update_params();
+        state
+    }
+
+    /// Computes the parameters of the ears from the positions, for the [`Spatializer`].
+    pub(crate) fn update_params(&self) {
+        let center = (self.left_ear + self.right_ear) / 2.0;
+        let right = (self.right_ear - self.left_ear)
+            .try_normalize()
+            .unwrap_or(Vec3::X);
+        let to_emitter = self.emitter - center;
+        let distance = to_emitter.length();
+        let pan = if distance > f32::EPSILON {
+            (to_emitter / distance).dot(right).clamp(-1.0, 1.0)
+        } else {
+            0.0
+        };
+
+        let gain = self.attenuation.gain(distance);
+        let angle = (pan + 1.0) * FRAC_PI_4;
+        store(&self.params.left_gain, gain * angle.cos());
+        store(&self.params.right_gain, gain * angle.sin());
+
+        let (delay, cutoff) = match self.panning {
+            SpatialPanning::Stereo => (0.0, 0.0),
+            SpatialPanning::Binaural => (
+                MAX_INTERAURAL_DELAY * pan.abs(),
+                MAX_HEAD_SHADOW_CUTOFF
+                    + (MIN_HEAD_SHADOW_CUTOFF - MAX_HEAD_SHADOW_CUTOFF) * pan.abs(),
+            ),
+        };
+        // only the ear away from the emitter is delayed and muffled
+        let (left, right) = if pan > 0.0 {
+            ((delay, cutoff), (0.0, 0.0))
+        } else {
+            ((0.0, 0.0), (delay, cutoff))
+        };
+        store(&self.params.left_delay, left.0);
+        store(&self.params.left_cutoff, left.1);
+        store(&self.params.right_delay, right.0);
+        store(&self.params.right_cutoff, right.1);
+    }
+}
+
+/// An ear of the listener of a [`Spatializer`]
+#[derive(Default)]
+struct Ear {
+    gain: f32,
+    delay: f32,
+    filter: f32,
+}
+
+impl Ear {
+    fn process(
+        &mut self,
+        delay_line: &[f32],
+        write_index: usize,
+        smoothing: f32,
+        sample_rate: f32,
+        (gain, delay, cutoff): (f32, f32, f32),
+    ) -> f32 {
+        self.gain += (gain - self.gain) * smoothing;
+        self.delay += (delay * sample_rate - self.delay) * smoothing;
+
+        // read the delayed sample between the two closest ones
+        let len = delay_line.len();
+        let delay = self.delay.clamp(0.0, (len - 2) as f32);
+        let offset = delay.floor();
+        let fraction = delay - offset;
+        let newer = delay_line[(write_index + len - offset as usize) % len];
+        let older = delay_line[(write_index + len - offset as usize - 1) % len];
+        let sample = newer + (older - newer) * fraction;
+
+        let sample = if cutoff > 0.0 && cutoff < sample_rate / 2.0 {
+            let coefficient = 1.0 - (-TAU * cutoff / sample_rate).exp();
+            self.filter += (sample - self.filter) * coefficient;
+            self.filter
+        } else {
+            self.filter = sample;
+            sample
+        };
+        sample * self.gain
+    }
+}
+
+/// Mixes a source to mono and plays it in stereo, with the gains, delays and head shadows of the
+/// [`SpatialParams`] of its [`SpatialAudioSink`]
+pub(crate) struct Spatializer<I> {
+    input: I,
+    params: Arc<SpatialParams>,
+    sample_rate: u32,
+    smoothing: f32,
+    delay_line: Vec<f32>,
+    write_index: usize,
+    left: Ear,
+    right: Ear,
+    next_sample: Option<f32>,
+}
+
+impl<I: Source<Item = f32>> Spatializer<I> {
+    pub(crate) fn new(input: I, params: Arc<SpatialParams>) -> Self {
+        let mut spatializer = Self {
+            input,
+            params,
+            sample_rate: 0,
+            smoothing: 1.0,
+            delay_line: Vec::new(),
+            write_index: 0,
+            left: Ear::default(),
+            right: Ear::default(),
+            next_sample: None,
+        };
+        // start at the current gains instead of fading in
+        spatializer.left.gain = load(&spatializer.params.left_gain);
+        spatializer.right.gain = load(&spatializer.params.right_gain);
+        spatializer
+    }
+}
+
+impl<I: Source<Item = f32>> Iterator for Spatializer<I> {
+    type Item = f32;
+
+    fn next(&mut self) -> Option<f32> {
+        // the right sample of the frame was computed with the left one
+        if let Some(sample) = self.next_sample.take() {
+            return Some(sample);
+        }
+
+        let channels = self.input.channels().max(1);
+        let mut mono = self.input.next()?;
+        for _ in 1..channels {
+            mono += self.input.next().unwrap_or_default();
+        }
+        mono /= channels as f32;
+
+        let sample_rate = self.input.sample_rate();
+        if sample_rate != self.sample_rate {
+            self.sample_rate = sample_rate;
+            let len = (MAX_INTERAURAL_DELAY * sample_rate as f32).ceil() as usize + 2;
+            self.delay_line = vec![0.0; len];
+            self.write_index = 0;
+            self.smoothing = 1.0 - (-1.0 / (SMOOTHING_TIME * sample_rate as f32)).exp();
+        }
+        self.write_index = (self.write_index + 1) % self.delay_line.len();
+        self.delay_line[self.write_index] = mono;
+
+        let sample_rate = sample_rate as f32;
+        let smoothing = self.smoothing;
+        let params = &self.params;
+        let left = self.left.process(
+            &self.delay_line,
+            self.write_index,
+            smoothing,
+            sample_rate,
+            (
+                load(&params.left_gain),
+                load(&params.left_delay),
+                load(&params.left_cutoff),
+            ),
+        );
+        let right = self.right.process(
+            &self.delay_line,
+            self.write_index,
+            smoothing,
+            sample_rate,
+            (
+                load(&params.right_gain),
+                load(&params.right_delay),
+                load(&params.right_cutoff),
+            ),
+        );
+        self.next_sample = Some(right);
+        Some(left)
+    }
+}
+
+impl<I: Source<Item = f32>> Source for Spatializer<I> {
+    fn current_frame_len(&self) -> Option<usize> {
+        let channels = self.input.channels().max(1) as usize;
+        self.input
+            .current_frame_len()
+            .map(|len| len / channels * 2 + self.next_sample.is_some() as usize)
+    }
+
+    fn channels(&self) -> u16 {
+        2
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.input.sample_rate()
+    }
+
+    fn total_duration(&self) -> Option<Duration> {
+        self.input.total_duration()
+    }
+}
+
+/// Appends `source` to `sink`, spatialized with `params`.
+///
+/// This is outside of [`AudioOutput`](crate::AudioOutput) so that its bounds on the samples of the
+/// decoders don't apply to the samples of the [`Spatializer`].
+pub(crate) fn append_spatialized<I>(sink: &Sink, source: I, params: Arc<SpatialParams>)
+where
+    I: Source<Item = f32> + Send + 'static,
+{
+    sink.append(Spatializer::new(source, params));
+}
+
+/// Moves the emitters of the [`SpatialAudioSource`]s to their entity, and their listener to the
+/// [`AudioListener`]
+pub fn update_spatial_audio_system(
+    listeners: Query<&GlobalTransform, With<AudioListener>>,
+    sources: Query<(&SpatialAudioSource, &GlobalTransform)>,
+    spatial_sinks: Res<Assets<SpatialAudioSink>>,
+) {
+    let Some(listener) = listeners.iter().next() else {
+        return;
+    };
+    let listener = listener.compute_transform();
+    let left_ear = listener.translation + listener.left() * 0.5;
+    let right_ear = listener.translation + listener.right() * 0.5;
+    for (source, transform) in &sources {
+        let Some(sink) = spatial_sinks.get(&source.sink) else {
+            continue;
+        };
+        let mut state = sink.spatial.lock();
+        state.left_ear = left_ear;
+        state.right_ear = right_ear;
+        state.emitter = transform.translation();
+        state.attenuation = source.attenuation;
+        state.panning = source.panning;
+        state.update_params();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_attenuation_gain() {
+        let linear = DistanceAttenuation::Linear {
+            reference_distance: 2.0,
+            max_distance: 6.0,
+        };
+        let cases = [
+            (DistanceAttenuation::None, 100.0, 1.0),
+            (DistanceAttenuation::default(), 0.0, 1.0),
+            (DistanceAttenuation::default(), 0.5, 1.0),
+            (DistanceAttenuation::default(), 4.0, 0.25),
+            (linear, 1.0, 1.0),
+            (linear, 3.0, 0.75),
+            (linear, 6.0, 0.0),
+            (linear, 10.0, 0.0),
+            (
+                DistanceAttenuation::Exponential {
+                    reference_distance: 1.0,
+                    rolloff: 2.0,
+                },
+                4.0,
+                0.0625,
+            ),
+        ];
+        for (attenuation, distance, gain) in cases {
+            assert_eq!(
+                attenuation.gain(distance),
+                gain,
+                "{attenuation:?} at {distance}"
+            );
+        }
+    }
+
+    #[test]
+    fn zero_reference_distance_is_finite() {
+        for reference_distance in [0.0, -1.0] {
+            for attenuation in [
+                DistanceAttenuation::Inverse { reference_distance },
+                DistanceAttenuation::Exponential {
+                    reference_distance,
+                    rolloff: 2.0,
+                },
+            ] {
+                assert_eq!(attenuation.gain(0.0), 1.0, "{attenuation:?}");
+                let gain = attenuation.gain(1.0);
+                assert!(gain.is_finite() && gain < 1e-3, "{attenuation:?}: {gain}");
+            }
+        }
+    }
+
+    fn ears(state: &SpatialState) -> [f32; 6] {
+        let params = &state.params;
+        [
+            &params.left_gain,
+            &params.right_gain,
+            &params.left_delay,
+            &params.right_delay,
+            &params.left_cutoff,
+            &params.right_cutoff,
+        ]
+        .map(load)
+    }
+
+    #[test]
+    fn spatial_params_pan() {
+        let mut state = SpatialState::new(Vec3::NEG_X, Vec3::X, Vec3::ZERO);
+        state.attenuation = DistanceAttenuation::None;
+
+        // in front of the listener, and at its center
+        for emitter in [Vec3::new(0.0, 0.0, -4.0), Vec3::ZERO] {
+            state.emitter = emitter;
+            state.update_params();
+            let [left, right, ..] = ears(&state);
+            assert!((left - right).abs() < 1e-6, "{emitter}");
+            assert!(
+                (left * left + right * right - 1.0).abs() < 1e-6,
+                "{emitter}"
+            );
+        }
+
+        // fully on the right, with a constant power
+        state.emitter = Vec3::new(4.0, 0.0, 0.0);
+        state.update_params();
+        let [left, right, ..] = ears(&state);
+        assert!(left.abs() < 1e-6 && (right - 1.0).abs() < 1e-6);
+
+        // on the left, halfway to the front
+        state.emitter = Vec3::new(-1.0, 0.0, -1.0);
+        state.update_params();
+        let [left, right, ..] = ears(&state);
+        assert!(left > right && right > 0.0);
+        assert!((left * left + right * right - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn spatial_params_binaural_delay() {
+        let mut state = SpatialState::new(Vec3::NEG_X, Vec3::X, Vec3::new(4.0, 0.0, 0.0));
+        state.update_params();
+        // stereo panning has no delay nor head shadow
+        assert_eq!(ears(&state)[2..], [0.0; 4]);
+
+        state.panning = SpatialPanning::Binaural;
+        state.update_params();
+        let [_, _, left_delay, right_delay, left_cutoff, right_cutoff] = ears(&state);
+        // only the far ear is delayed and muffled
+        assert_eq!((right_delay, right_cutoff), (0.0, 0.0));
+        assert!((left_delay - MAX_INTERAURAL_DELAY).abs() < 1e-9);
+        assert!((left_cutoff - MIN_HEAD_SHADOW_CUTOFF).abs() < 1e-3);
+
+        state.emitter = Vec3::new(-1.0, 0.0, -1.0);
+        state.update_params();
+        let [_, _, left_delay, right_delay, left_cutoff, right_cutoff] = ears(&state);
+        assert_eq!((left_delay, left_cutoff), (0.0, 0.0));
+        let pan = std::f32::consts::FRAC_1_SQRT_2;
+        assert!((right_delay - MAX_INTERAURAL_DELAY * pan).abs() < 1e-9);
+        assert!(right_cutoff > MIN_HEAD_SHADOW_CUTOFF && right_cutoff < MAX_HEAD_SHADOW_CUTOFF);
+    }
+}
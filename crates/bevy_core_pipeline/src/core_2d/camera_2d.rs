@@ -8,7 +8,7 @@ use bevy_render::{
     camera::{Camera, CameraProjection, CameraRenderGraph, OrthographicProjection},
     extract_component::ExtractComponent,
     primitives::Frustum,
-    view::VisibleEntities,
+    view::{Msaa, VisibleEntities},
 };
 use bevy_transform::prelude::{GlobalTransform, Transform};
 
@@ -31,6 +31,7 @@ pub struct Camera2dBundle {
     pub camera_2d: Camera2d,
     pub tonemapping: Tonemapping,
     pub deband_dither: DebandDither,
+    pub msaa: Msaa,
 }
 
 impl Default for Camera2dBundle {
@@ -73,6 +74,7 @@ impl Camera2dBundle {
             camera_2d: Camera2d::default(),
             tonemapping: Tonemapping::None,
             deband_dither: DebandDither::Disabled,
+            msaa: Msaa::default(),
         }
     }
 }
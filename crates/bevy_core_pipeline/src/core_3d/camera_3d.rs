@@ -9,7 +9,7 @@ use bevy_render::{
     extract_component::ExtractComponent,
     primitives::Frustum,
     render_resource::LoadOp,
-    view::{ColorGrading, VisibleEntities},
+    view::{ColorGrading, Msaa, VisibleEntities},
 };
 use bevy_transform::prelude::{GlobalTransform, Transform};
 use serde::{Deserialize, Serialize};
@@ -64,6 +64,7 @@ pub struct Camera3dBundle {
     pub tonemapping: Tonemapping,
     pub dither: DebandDither,
     pub color_grading: ColorGrading,
+    pub msaa: Msaa,
 }
 
 // NOTE: ideally Perspective and Orthographic defaults can share the same impl, but sadly it breaks rust's type inference
@@ -81,6 +82,7 @@ impl Default for Camera3dBundle {
             tonemapping: Tonemapping::ReinhardLuminance,
             dither: DebandDither::Enabled,
             color_grading: ColorGrading::default(),
+            msaa: Msaa::default(),
         }
     }
 }
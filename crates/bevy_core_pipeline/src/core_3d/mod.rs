@@ -68,11 +68,7 @@ impl Plugin for Core3dPlugin {
             .init_resource::<DrawFunctions<AlphaMask3d>>()
             .init_resource::<DrawFunctions<Transparent3d>>()
             .add_system(extract_core_3d_camera_phases.in_schedule(ExtractSchedule))
-            .add_system(
-                prepare_core_3d_depth_textures
-                    .in_set(RenderSet::Prepare)
-                    .after(bevy_render::view::prepare_windows),
-            )
+            .add_system(prepare_core_3d_depth_textures.in_set(RenderSet::Prepare))
             .add_system(sort_phase_system::<Opaque3d>.in_set(RenderSet::PhaseSort))
             .add_system(sort_phase_system::<AlphaMask3d>.in_set(RenderSet::PhaseSort))
             .add_system(sort_phase_system::<Transparent3d>.in_set(RenderSet::PhaseSort));
@@ -269,10 +265,9 @@ pub fn extract_core_3d_camera_phases(
 pub fn prepare_core_3d_depth_textures(
     mut commands: Commands,
     mut texture_cache: ResMut<TextureCache>,
-    msaa: Res<Msaa>,
     render_device: Res<RenderDevice>,
     views_3d: Query<
-        (Entity, &ExtractedCamera, Option<&DepthPrepass>),
+        (Entity, &ExtractedCamera, &Msaa, Option<&DepthPrepass>),
         (
             With<RenderPhase<Opaque3d>>,
             With<RenderPhase<AlphaMask3d>>,
@@ -281,13 +276,13 @@ pub fn prepare_core_3d_depth_textures(
     >,
 ) {
     let mut textures = HashMap::default();
-    for (entity, camera, depth_prepass) in &views_3d {
+    for (entity, camera, msaa, depth_prepass) in &views_3d {
         let Some(physical_target_size) = camera.physical_target_size else {
             continue;
         };
 
         let cached_texture = textures
-            .entry(camera.target.clone())
+            .entry((camera.target.clone(), *msaa))
             .or_insert_with(|| {
                 // Default usage required to write to the depth texture
                 let mut usage = TextureUsages::RENDER_ATTACHMENT;
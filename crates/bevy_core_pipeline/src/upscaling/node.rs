@@ -3,6 +3,7 @@ use std::sync::Mutex;
 use bevy_ecs::prelude::*;
 use bevy_ecs::query::QueryState;
 use bevy_render::{
+    camera::{CameraOutputMode, ExtractedCamera},
     render_graph::{Node, NodeRunError, RenderGraphContext, SlotInfo, SlotType},
     render_resource::{
         BindGroup, BindGroupDescriptor, BindGroupEntry, BindingResource, LoadOp, Operations,
@@ -16,7 +17,14 @@ use bevy_render::{
 use super::{UpscalingPipeline, ViewUpscalingPipeline};
 
 pub struct UpscalingNode {
-    query: QueryState<(&'static ViewTarget, &'static ViewUpscalingPipeline), With<ExtractedView>>,
+    query: QueryState<
+        (
+            &'static ViewTarget,
+            &'static ViewUpscalingPipeline,
+            Option<&'static ExtractedCamera>,
+        ),
+        With<ExtractedView>,
+    >,
     cached_texture_bind_group: Mutex<Option<(TextureViewId, BindGroup)>>,
 }
 
@@ -51,11 +59,20 @@ impl Node for UpscalingNode {
         let pipeline_cache = world.get_resource::<PipelineCache>().unwrap();
         let upscaling_pipeline = world.get_resource::<UpscalingPipeline>().unwrap();
 
-        let (target, upscaling_target) = match self.query.get_manual(world, view_entity) {
+        let (target, upscaling_target, camera) = match self.query.get_manual(world, view_entity) {
             Ok(query) => query,
             Err(_) => return Ok(()),
         };
 
+        let color_attachment_load_op = match camera.map(|camera| camera.output_mode) {
+            Some(CameraOutputMode::Write {
+                color_attachment_load_op,
+                ..
+            }) => color_attachment_load_op,
+            Some(CameraOutputMode::Skip) => return Ok(()),
+            None => LoadOp::Clear(Default::default()),
+        };
+
         let upscaled_texture = target.main_texture();
 
         let mut cached_bind_group = self.cached_texture_bind_group.lock().unwrap();
@@ -100,7 +117,7 @@ impl Node for UpscalingNode {
                 view: target.out_texture(),
                 resolve_target: None,
                 ops: Operations {
-                    load: LoadOp::Clear(Default::default()),
+                    load: color_attachment_load_op,
                     store: true,
                 },
             })],
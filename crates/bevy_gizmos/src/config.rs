@@ -0,0 +1,98 @@
+use bevy_ecs::prelude::*;
+use bevy_utils::HashMap;
+use std::any::{type_name, TypeId};
+
+/// A group of gizmos sharing a [`GizmoConfig`], so that for example the gizmos of a physics
+/// debugger can be toggled independently of the others.
+///
+/// Groups are registered with [`AddGizmoGroup::add_gizmo_group`](crate::AddGizmoGroup), and
+/// drawn with [`Gizmos<G>`](crate::Gizmos). Gizmos drawn with a plain [`Gizmos`](crate::Gizmos)
+/// use the [`DefaultGizmoConfigGroup`].
+///
+/// ```
+/// # use bevy_gizmos::GizmoConfigGroup;
+/// struct PhysicsGizmos;
+///
+/// impl GizmoConfigGroup for PhysicsGizmos {}
+/// ```
+pub trait GizmoConfigGroup: Send + Sync + 'static {}
+
+/// The group of the gizmos drawn with a plain [`Gizmos`](crate::Gizmos).
+#[derive(Default)]
+pub struct DefaultGizmoConfigGroup;
+
+impl GizmoConfigGroup for DefaultGizmoConfigGroup {}
+
+/// How the gizmos of a [`GizmoConfigGroup`] are drawn.
+#[derive(Clone, Debug)]
+pub struct GizmoConfig {
+    /// Set to `false` to stop drawing the gizmos of the group.
+    ///
+    /// Defaults to `true`.
+    pub enabled: bool,
+    /// Whether the gizmos are hidden behind the geometry of 3D scenes.
+    ///
+    /// When `false`, the gizmos are drawn on top of everything. 2D gizmos are always drawn on top.
+    ///
+    /// Defaults to `true`.
+    pub depth_test: bool,
+}
+
+impl Default for GizmoConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            depth_test: true,
+        }
+    }
+}
+
+/// The [`GizmoConfig`] of every [`GizmoConfigGroup`].
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_gizmos::{DefaultGizmoConfigGroup, GizmoConfigStore};
+/// fn draw_on_top(mut config_store: ResMut<GizmoConfigStore>) {
+///     config_store.config_mut::<DefaultGizmoConfigGroup>().depth_test = false;
+/// }
+/// # bevy_ecs::system::assert_is_system(draw_on_top);
+/// ```
+#[derive(Resource, Default)]
+pub struct GizmoConfigStore {
+    configs: HashMap<TypeId, GizmoConfig>,
+}
+
+impl GizmoConfigStore {
+    /// Sets the config of the group `G`, registering it if needed.
+    pub fn insert<G: GizmoConfigGroup>(&mut self, config: GizmoConfig) {
+        self.configs.insert(TypeId::of::<G>(), config);
+    }
+
+    /// Returns the config of the group `G`, or `None` if it wasn't registered.
+    pub fn get<G: GizmoConfigGroup>(&self) -> Option<&GizmoConfig> {
+        self.configs.get(&TypeId::of::<G>())
+    }
+
+    /// Returns the config of the group `G`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group wasn't registered with
+    /// [`AddGizmoGroup::add_gizmo_group`](crate::AddGizmoGroup).
+    pub fn config<G: GizmoConfigGroup>(&self) -> &GizmoConfig {
+        self.get::<G>()
+            .unwrap_or_else(|| panic!("Gizmo group {} wasn't registered", type_name::<G>()))
+    }
+
+    /// Returns the config of the group `G` mutably.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the group wasn't registered with
+    /// [`AddGizmoGroup::add_gizmo_group`](crate::AddGizmoGroup).
+    pub fn config_mut<G: GizmoConfigGroup>(&mut self) -> &mut GizmoConfig {
+        self.configs
+            .get_mut(&TypeId::of::<G>())
+            .unwrap_or_else(|| panic!("Gizmo group {} wasn't registered", type_name::<G>()))
+    }
+}
@@ -0,0 +1,580 @@
+use crate::{DefaultGizmoConfigGroup, GizmoConfigGroup, GizmoConfigStore};
+use bevy_ecs::{
+    prelude::*,
+    system::{Deferred, SystemBuffer, SystemMeta, SystemParam},
+};
+use bevy_math::{Quat, Vec2, Vec3};
+use bevy_render::color::Color;
+use bevy_transform::components::Transform;
+use std::{f32::consts::TAU, marker::PhantomData};
+
+/// The number of line segments used to draw circles and spheres.
+pub const CIRCLE_SEGMENTS: usize = 32;
+
+/// The lines drawn in the gizmo group `G` during the current frame, as pairs of vertices.
+#[derive(Resource)]
+pub(crate) struct GizmoStorage<G: GizmoConfigGroup> {
+    pub positions: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 4]>,
+    marker: PhantomData<fn() -> G>,
+}
+
+impl<G: GizmoConfigGroup> Default for GizmoStorage<G> {
+    fn default() -> Self {
+        Self {
+            positions: Vec::new(),
+            colors: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+pub(crate) fn clear_gizmos<G: GizmoConfigGroup>(mut storage: ResMut<GizmoStorage<G>>) {
+    storage.positions.clear();
+    storage.colors.clear();
+}
+
+/// The lines drawn by a single system, moved to the [`GizmoStorage`] when the system buffers are
+/// applied, so that systems drawing gizmos can run in parallel.
+pub struct GizmoBuffer<G: GizmoConfigGroup> {
+    positions: Vec<[f32; 3]>,
+    colors: Vec<[f32; 4]>,
+    marker: PhantomData<fn() -> G>,
+}
+
+impl<G: GizmoConfigGroup> FromWorld for GizmoBuffer<G> {
+    fn from_world(_world: &mut World) -> Self {
+        Self {
+            positions: Vec::new(),
+            colors: Vec::new(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<G: GizmoConfigGroup> SystemBuffer for GizmoBuffer<G> {
+    fn apply(&mut self, _system_meta: &SystemMeta, world: &mut World) {
+        let enabled = world
+            .get_resource::<GizmoConfigStore>()
+            .and_then(|config_store| config_store.get::<G>())
+            .map_or(false, |config| config.enabled);
+        if enabled {
+            let mut storage = world.resource_mut::<GizmoStorage<G>>();
+            storage.positions.append(&mut self.positions);
+            storage.colors.append(&mut self.colors);
+        } else {
+            self.positions.clear();
+            self.colors.clear();
+        }
+    }
+}
+
+/// A [`SystemParam`] drawing lines and shapes for debugging, in the gizmo group `G`.
+///
+/// Gizmos are immediate mode: they are only drawn during the frame they were added in, so systems
+/// must draw them again every frame. They are drawn by every 3D and 2D camera, as lines a single
+/// pixel wide, following the [`GizmoConfig`](crate::GizmoConfig) of their group.
+///
+/// ```
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_gizmos::Gizmos;
+/// # use bevy_math::Vec3;
+/// # use bevy_render::color::Color;
+/// fn draw_axes(mut gizmos: Gizmos) {
+///     gizmos.arrow(Vec3::ZERO, Vec3::X, Color::RED);
+///     gizmos.arrow(Vec3::ZERO, Vec3::Y, Color::GREEN);
+///     gizmos.arrow(Vec3::ZERO, Vec3::Z, Color::BLUE);
+/// }
+/// # bevy_ecs::system::assert_is_system(draw_axes);
+/// ```
+#[derive(SystemParam)]
+pub struct Gizmos<'s, G: GizmoConfigGroup = DefaultGizmoConfigGroup> {
+    buffer: Deferred<'s, GizmoBuffer<G>>,
+}
+
+impl<'s, G: GizmoConfigGroup> Gizmos<'s, G> {
+    /// Draws a line from `start` to `end`.
+    #[inline]
+    pub fn line(&mut self, start: Vec3, end: Vec3, color: Color) {
+        self.line_gradient(start, end, color, color);
+    }
+
+    /// Draws a line from `start` to `end`, with its color fading from `start_color` to
+    /// `end_color`.
+    #[inline]
+    pub fn line_gradient(&mut self, start: Vec3, end: Vec3, start_color: Color, end_color: Color) {
+        self.buffer
+            .positions
+            .extend([start.to_array(), end.to_array()]);
+        self.buffer.colors.extend([
+            start_color.as_linear_rgba_f32(),
+            end_color.as_linear_rgba_f32(),
+        ]);
+    }
+
+    /// Draws a line from `start` to `start + vector`.
+    #[inline]
+    pub fn ray(&mut self, start: Vec3, vector: Vec3, color: Color) {
+        self.line(start, start + vector, color);
+    }
+
+    /// Draws lines joining each of the `positions` to the next one.
+    pub fn linestrip(&mut self, positions: impl IntoIterator<Item = Vec3>, color: Color) {
+        self.linestrip_gradient(positions.into_iter().map(|position| (position, color)));
+    }
+
+    /// Draws lines joining each of the `points` to the next one, with their colors fading from
+    /// one point to the next.
+    pub fn linestrip_gradient(&mut self, points: impl IntoIterator<Item = (Vec3, Color)>) {
+        let mut points = points.into_iter();
+        let mut previous = match points.next() {
+            Some(point) => point,
+            None => return,
+        };
+        for point in points {
+            self.line_gradient(previous.0, point.0, previous.1, point.1);
+            previous = point;
+        }
+    }
+
+    /// Draws an arrow from `start` to `end`, with its head at `end`.
+    pub fn arrow(&mut self, start: Vec3, end: Vec3, color: Color) {
+        self.line(start, end, color);
+        let vector = end - start;
+        let head_length = vector.length() * 0.1;
+        let direction = match vector.try_normalize() {
+            Some(direction) => direction,
+            None => return,
+        };
+        let (tangent, bitangent) = direction.any_orthonormal_pair();
+        let head_base = end - direction * head_length;
+        for offset in [tangent, -tangent, bitangent, -bitangent] {
+            self.line(end, head_base + offset * head_length * 0.5, color);
+        }
+    }
+
+    /// Draws the edges of a cube of size 1 centered on the origin, transformed by `transform`.
+    pub fn cuboid(&mut self, transform: Transform, color: Color) {
+        let corner = |x: f32, y: f32, z: f32| transform.transform_point(Vec3::new(x, y, z) * 0.5);
+        let [a, b, c, d, e, f, g, h] = [
+            corner(-1.0, -1.0, -1.0),
+            corner(1.0, -1.0, -1.0),
+            corner(1.0, 1.0, -1.0),
+            corner(-1.0, 1.0, -1.0),
+            corner(-1.0, -1.0, 1.0),
+            corner(1.0, -1.0, 1.0),
+            corner(1.0, 1.0, 1.0),
+            corner(-1.0, 1.0, 1.0),
+        ];
+        self.linestrip([a, b, c, d, a], color);
+        self.linestrip([e, f, g, h, e], color);
+        for (start, end) in [(a, e), (b, f), (c, g), (d, h)] {
+            self.line(start, end, color);
+        }
+    }
+
+    /// Draws a circle of `radius` around `position`, in the plane facing `normal`.
+    pub fn circle(&mut self, position: Vec3, normal: Vec3, radius: f32, color: Color) {
+        let rotation = Quat::from_rotation_arc(Vec3::Z, normal.try_normalize().unwrap_or(Vec3::Z));
+        self.linestrip(
+            circle_points(radius).map(|point| position + rotation * point.extend(0.0)),
+            color,
+        );
+    }
+
+    /// Draws a sphere of `radius` around `position`, as one circle around each of its axes
+    /// rotated by `rotation`.
+    pub fn sphere(&mut self, position: Vec3, rotation: Quat, radius: f32, color: Color) {
+        for axis in [Vec3::X, Vec3::Y, Vec3::Z] {
+            self.circle(position, rotation * axis, radius, color);
+        }
+    }
+
+    /// Draws a rectangle of `size` centered on `position`, in the XY plane rotated by `rotation`.
+    pub fn rect(&mut self, position: Vec3, rotation: Quat, size: Vec2, color: Color) {
+        self.linestrip(
+            rect_corners(size).map(|corner| position + rotation * corner.extend(0.0)),
+            color,
+        );
+    }
+
+    /// Draws a line from `start` to `end` in the XY plane.
+    #[inline]
+    pub fn line_2d(&mut self, start: Vec2, end: Vec2, color: Color) {
+        self.line(start.extend(0.0), end.extend(0.0), color);
+    }
+
+    /// Draws a line from `start` to `end` in the XY plane, with its color fading from
+    /// `start_color` to `end_color`.
+    #[inline]
+    pub fn line_gradient_2d(
+        &mut self,
+        start: Vec2,
+        end: Vec2,
+        start_color: Color,
+        end_color: Color,
+    ) {
+        self.line_gradient(start.extend(0.0), end.extend(0.0), start_color, end_color);
+    }
+
+    /// Draws a line from `start` to `start + vector` in the XY plane.
+    #[inline]
+    pub fn ray_2d(&mut self, start: Vec2, vector: Vec2, color: Color) {
+        self.line_2d(start, start + vector, color);
+    }
+
+    /// Draws lines joining each of the `positions` to the next one in the XY plane.
+    pub fn linestrip_2d(&mut self, positions: impl IntoIterator<Item = Vec2>, color: Color) {
+        self.linestrip(
+            positions.into_iter().map(|position| position.extend(0.0)),
+            color,
+        );
+    }
+
+    /// Draws an arrow from `start` to `end` in the XY plane, with its head at `end`.
+    pub fn arrow_2d(&mut self, start: Vec2, end: Vec2, color: Color) {
+        self.line_2d(start, end, color);
+        let vector = end - start;
+        let head_length = vector.length() * 0.1;
+        let direction = match vector.try_normalize() {
+            Some(direction) => direction,
+            None => return,
+        };
+        let head_base = end - direction * head_length;
+        for offset in [direction.perp(), -direction.perp()] {
+            self.line_2d(end, head_base + offset * head_length * 0.5, color);
+        }
+    }
+
+    /// Draws a circle of `radius` around `position` in the XY plane.
+    pub fn circle_2d(&mut self, position: Vec2, radius: f32, color: Color) {
+        self.linestrip_2d(circle_points(radius).map(|point| position + point), color);
+    }
+
+    /// Draws a rectangle of `size` centered on `position` in the XY plane, rotated by `rotation`
+    /// radians.
+    pub fn rect_2d(&mut self, position: Vec2, rotation: f32, size: Vec2, color: Color) {
+        let rotation = Vec2::from_angle(rotation);
+        self.linestrip_2d(
+            rect_corners(size).map(|corner| position + rotation.rotate(corner)),
+            color,
+        );
+    }
+}
+
+/// The points of a closed circle of `radius` around the origin.
+fn circle_points(radius: f32) -> impl Iterator<Item = Vec2> {
+    (0..=CIRCLE_SEGMENTS).map(move |i| {
+        let angle = i as f32 * TAU / CIRCLE_SEGMENTS as f32;
+        Vec2::from_angle(angle) * radius
+    })
+}
+
+/// The corners of a rectangle of `size` centered on the origin, the first one repeated to close
+/// it.
+fn rect_corners(size: Vec2) -> impl Iterator<Item = Vec2> {
+    let half_size = size / 2.0;
+    [
+        Vec2::new(-half_size.x, -half_size.y),
+        Vec2::new(half_size.x, -half_size.y),
+        Vec2::new(half_size.x, half_size.y),
+        Vec2::new(-half_size.x, half_size.y),
+        Vec2::new(-half_size.x, -half_size.y),
+    ]
+    .into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AddGizmoGroup, GizmoConfig};
+    use bevy_app::App;
+    use std::f32::consts::FRAC_PI_2;
+
+    /// An app drawing gizmos with `draw` every frame
+    fn app(draw: impl Fn(&mut Gizmos) + Send + Sync + 'static) -> App {
+        let mut app = App::new();
+        app.add_gizmo_group::<DefaultGizmoConfigGroup>(GizmoConfig::default())
+            .add_system(move |mut gizmos: Gizmos| draw(&mut gizmos));
+        app
+    }
+
+    /// The lines drawn by `draw` during a frame, as pairs of points
+    fn lines(draw: impl Fn(&mut Gizmos) + Send + Sync + 'static) -> Vec<(Vec3, Vec3)> {
+        let mut app = app(draw);
+        app.update();
+        stored_lines(&app)
+    }
+
+    fn stored_lines(app: &App) -> Vec<(Vec3, Vec3)> {
+        let storage = app
+            .world
+            .resource::<GizmoStorage<DefaultGizmoConfigGroup>>();
+        assert_eq!(storage.positions.len(), storage.colors.len());
+        assert_eq!(storage.positions.len() % 2, 0);
+        storage
+            .positions
+            .chunks(2)
+            .map(|line| (Vec3::from(line[0]), Vec3::from(line[1])))
+            .collect()
+    }
+
+    fn assert_close(a: Vec3, b: Vec3) {
+        assert!(a.abs_diff_eq(b, 1e-5), "{a} != {b}");
+    }
+
+    /// Whether the lines form a single closed loop, each starting at the end of the previous one
+    fn assert_closed_strip(lines: &[(Vec3, Vec3)]) {
+        for (line, next) in lines.iter().zip(lines.iter().cycle().skip(1)) {
+            assert_close(line.1, next.0);
+        }
+    }
+
+    #[test]
+    fn lines_and_rays() {
+        let lines = lines(|gizmos| {
+            gizmos.line(Vec3::ZERO, Vec3::X, Color::RED);
+            gizmos.ray(Vec3::Y, Vec3::new(1.0, 2.0, 3.0), Color::RED);
+            gizmos.linestrip([Vec3::X, Vec3::Y, Vec3::Z], Color::RED);
+            gizmos.linestrip([Vec3::X], Color::RED);
+            gizmos.ray_2d(Vec2::new(1.0, 2.0), Vec2::X, Color::RED);
+        });
+        assert_eq!(
+            lines,
+            [
+                (Vec3::ZERO, Vec3::X),
+                (Vec3::Y, Vec3::new(1.0, 3.0, 3.0)),
+                (Vec3::X, Vec3::Y),
+                (Vec3::Y, Vec3::Z),
+                (Vec3::new(1.0, 2.0, 0.0), Vec3::new(2.0, 2.0, 0.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn gradients_fade_between_the_colors() {
+        let mut app = app(|gizmos| {
+            gizmos.line_gradient(Vec3::ZERO, Vec3::X, Color::RED, Color::BLUE);
+            gizmos.linestrip_gradient([
+                (Vec3::ZERO, Color::RED),
+                (Vec3::X, Color::GREEN),
+                (Vec3::Y, Color::BLUE),
+            ]);
+        });
+        app.update();
+        let storage = app
+            .world
+            .resource::<GizmoStorage<DefaultGizmoConfigGroup>>();
+        let [red, green, blue] =
+            [Color::RED, Color::GREEN, Color::BLUE].map(|color| color.as_linear_rgba_f32());
+        assert_eq!(storage.colors, [red, blue, red, green, green, blue]);
+    }
+
+    #[test]
+    fn circles_have_a_segment_count() {
+        let center = Vec3::new(1.0, 2.0, 3.0);
+        let normal = Vec3::new(1.0, 1.0, 0.0);
+        let circle = lines(move |gizmos| gizmos.circle(center, normal, 2.0, Color::RED));
+        assert_eq!(circle.len(), CIRCLE_SEGMENTS);
+        assert_closed_strip(&circle);
+        for (start, _) in &circle {
+            let offset = *start - center;
+            assert!((offset.length() - 2.0).abs() < 1e-5, "{offset}");
+            assert!(offset.dot(normal).abs() < 1e-5, "{offset}");
+        }
+
+        let circle_2d = lines(|gizmos| gizmos.circle_2d(Vec2::new(1.0, 2.0), 3.0, Color::RED));
+        assert_eq!(circle_2d.len(), CIRCLE_SEGMENTS);
+        assert_closed_strip(&circle_2d);
+        for (start, _) in &circle_2d {
+            assert_eq!(start.z, 0.0);
+            assert!((start.truncate().distance(Vec2::new(1.0, 2.0)) - 3.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn circles_without_a_normal_face_z() {
+        let circle = lines(|gizmos| gizmos.circle(Vec3::ZERO, Vec3::ZERO, 1.0, Color::RED));
+        assert_eq!(circle.len(), CIRCLE_SEGMENTS);
+        assert!(circle
+            .iter()
+            .all(|(start, end)| start.z == 0.0 && end.z == 0.0));
+    }
+
+    #[test]
+    fn spheres_are_a_circle_around_each_axis() {
+        let rotation = Quat::from_rotation_y(FRAC_PI_2);
+        let sphere = lines(move |gizmos| gizmos.sphere(Vec3::ZERO, rotation, 1.0, Color::RED));
+        assert_eq!(sphere.len(), 3 * CIRCLE_SEGMENTS);
+        for (circle, axis) in sphere
+            .chunks(CIRCLE_SEGMENTS)
+            .zip([Vec3::X, Vec3::Y, Vec3::Z])
+        {
+            assert_closed_strip(circle);
+            let normal = rotation * axis;
+            for (start, _) in circle {
+                assert!((start.length() - 1.0).abs() < 1e-5);
+                assert!(start.dot(normal).abs() < 1e-5, "{start} around {normal}");
+            }
+        }
+    }
+
+    #[test]
+    fn rects_have_four_edges() {
+        let rect = lines(|gizmos| {
+            gizmos.rect(
+                Vec3::new(0.0, 0.0, 1.0),
+                Quat::from_rotation_x(FRAC_PI_2),
+                Vec2::new(4.0, 2.0),
+                Color::RED,
+            );
+        });
+        assert_eq!(rect.len(), 4);
+        assert_closed_strip(&rect);
+        for (corner, expected) in rect.iter().map(|line| line.0).zip([
+            Vec3::new(-2.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 0.0),
+            Vec3::new(2.0, 0.0, 2.0),
+            Vec3::new(-2.0, 0.0, 2.0),
+        ]) {
+            assert_close(corner, expected);
+        }
+
+        let rect_2d = lines(|gizmos| {
+            gizmos.rect_2d(
+                Vec2::new(1.0, 1.0),
+                FRAC_PI_2,
+                Vec2::new(4.0, 2.0),
+                Color::RED,
+            );
+        });
+        assert_eq!(rect_2d.len(), 4);
+        assert_closed_strip(&rect_2d);
+        for (corner, expected) in rect_2d.iter().map(|line| line.0).zip([
+            Vec3::new(2.0, -1.0, 0.0),
+            Vec3::new(2.0, 3.0, 0.0),
+            Vec3::new(0.0, 3.0, 0.0),
+            Vec3::new(0.0, -1.0, 0.0),
+        ]) {
+            assert_close(corner, expected);
+        }
+    }
+
+    #[test]
+    fn cuboids_have_twelve_edges() {
+        let transform = Transform::from_xyz(1.0, 0.0, 0.0).with_scale(Vec3::new(2.0, 4.0, 6.0));
+        let cuboid = lines(move |gizmos| gizmos.cuboid(transform, Color::RED));
+        assert_eq!(cuboid.len(), 12);
+
+        // each edge is along an axis, four times for each
+        let mut edges_along = [0; 3];
+        for (start, end) in &cuboid {
+            let edge = (*end - *start).abs();
+            let axis = [Vec3::X, Vec3::Y, Vec3::Z]
+                .iter()
+                .position(|axis| edge == *axis * edge.dot(*axis))
+                .unwrap();
+            assert_eq!(edge[axis], transform.scale[axis]);
+            edges_along[axis] += 1;
+        }
+        assert_eq!(edges_along, [4, 4, 4]);
+
+        // between the 8 corners, 3 edges at each
+        let mut corners: Vec<(Vec3, usize)> = Vec::new();
+        for point in cuboid.iter().flat_map(|(start, end)| [*start, *end]) {
+            assert_eq!(
+                (point - transform.translation).abs(),
+                transform.scale / 2.0,
+                "{point} isn't a corner"
+            );
+            match corners.iter_mut().find(|(corner, _)| *corner == point) {
+                Some((_, count)) => *count += 1,
+                None => corners.push((point, 1)),
+            }
+        }
+        assert_eq!(corners.len(), 8);
+        assert!(corners.iter().all(|(_, count)| *count == 3));
+    }
+
+    #[test]
+    fn arrows_have_a_head() {
+        let start = Vec3::new(1.0, 0.0, 0.0);
+        let end = Vec3::new(1.0, 10.0, 0.0);
+        let arrow = lines(move |gizmos| gizmos.arrow(start, end, Color::RED));
+        assert_eq!(arrow.len(), 5);
+        assert_eq!(arrow[0], (start, end));
+        // the head is a cone a tenth of the length of the arrow
+        let mut tips = Vec::new();
+        for (head_start, tip) in &arrow[1..] {
+            assert_eq!(*head_start, end);
+            let offset = *tip - Vec3::new(1.0, 9.0, 0.0);
+            assert!(offset.y.abs() < 1e-5, "{offset}");
+            assert!((offset.length() - 0.5).abs() < 1e-5, "{offset}");
+            tips.push(offset);
+        }
+        assert_close(tips[0], -tips[1]);
+        assert_close(tips[2], -tips[3]);
+        assert!(tips[0].dot(tips[2]).abs() < 1e-5);
+
+        let arrow_2d =
+            lines(|gizmos| gizmos.arrow_2d(Vec2::ZERO, Vec2::new(-10.0, 0.0), Color::RED));
+        assert_eq!(arrow_2d.len(), 3);
+        for (head_start, tip) in &arrow_2d[1..] {
+            assert_eq!(*head_start, Vec3::new(-10.0, 0.0, 0.0));
+            assert_close(tip.abs(), Vec3::new(9.0, 0.5, 0.0));
+        }
+    }
+
+    #[test]
+    fn empty_arrows_have_no_head() {
+        let arrows = lines(|gizmos| {
+            gizmos.arrow(Vec3::ONE, Vec3::ONE, Color::RED);
+            gizmos.arrow_2d(Vec2::ONE, Vec2::ONE, Color::RED);
+        });
+        assert_eq!(
+            arrows,
+            [
+                (Vec3::ONE, Vec3::ONE),
+                (Vec3::new(1.0, 1.0, 0.0), Vec3::new(1.0, 1.0, 0.0))
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_only_last_a_frame() {
+        let mut app = App::new();
+        app.add_gizmo_group::<DefaultGizmoConfigGroup>(GizmoConfig::default())
+            .add_system(|mut gizmos: Gizmos, mut frame: Local<u32>| {
+                *frame += 1;
+                if *frame == 1 {
+                    gizmos.line(Vec3::ZERO, Vec3::X, Color::RED);
+                }
+                gizmos.line(Vec3::ZERO, Vec3::Y, Color::RED);
+            });
+        app.update();
+        assert_eq!(
+            stored_lines(&app),
+            [(Vec3::ZERO, Vec3::X), (Vec3::ZERO, Vec3::Y)]
+        );
+        app.update();
+        assert_eq!(stored_lines(&app), [(Vec3::ZERO, Vec3::Y)]);
+    }
+
+    #[test]
+    fn disabled_groups_drop_their_lines() {
+        let mut app = app(|gizmos| gizmos.line(Vec3::ZERO, Vec3::X, Color::RED));
+        app.world
+            .resource_mut::<GizmoConfigStore>()
+            .config_mut::<DefaultGizmoConfigGroup>()
+            .enabled = false;
+        app.update();
+        assert!(stored_lines(&app).is_empty());
+
+        app.world
+            .resource_mut::<GizmoConfigStore>()
+            .config_mut::<DefaultGizmoConfigGroup>()
+            .enabled = true;
+        app.update();
+        assert_eq!(stored_lines(&app), [(Vec3::ZERO, Vec3::X)]);
+    }
+}
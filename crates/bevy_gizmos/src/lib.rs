@@ -0,0 +1,109 @@
+//! Immediate mode debug drawing for the game engine Bevy.
+//!
+//! Systems draw lines and shapes with the [`Gizmos`] system parameter. Gizmos only last for the
+//! frame they were drawn in, so there are no entities or meshes to spawn and clean up:
+//!
+//! ```
+//! # use bevy_ecs::prelude::*;
+//! # use bevy_gizmos::prelude::*;
+//! # use bevy_math::prelude::*;
+//! # use bevy_render::prelude::*;
+//! fn draw_bounds(mut gizmos: Gizmos) {
+//!     gizmos.sphere(Vec3::ZERO, Quat::IDENTITY, 2.0, Color::YELLOW);
+//! }
+//! # bevy_ecs::system::assert_is_system(draw_bounds);
+//! ```
+//!
+//! Gizmos are drawn in the [`Transparent3d`] and [`Transparent2d`] phases, after everything else.
+
+mod config;
+mod gizmos;
+pub mod render;
+
+pub use config::*;
+pub use gizmos::*;
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{
+        AddGizmoGroup, DefaultGizmoConfigGroup, GizmoConfig, GizmoConfigGroup, GizmoConfigStore,
+        GizmoPlugin, Gizmos,
+    };
+}
+
+use bevy_app::prelude::*;
+use bevy_asset::{load_internal_asset, HandleUntyped};
+use bevy_core_pipeline::{core_2d::Transparent2d, core_3d::Transparent3d};
+use bevy_ecs::prelude::*;
+use bevy_reflect::TypeUuid;
+use bevy_render::{
+    render_phase::AddRenderCommand,
+    render_resource::{Shader, SpecializedRenderPipelines},
+    ExtractSchedule, RenderApp, RenderSet,
+};
+use gizmos::{clear_gizmos, GizmoStorage};
+use render::{
+    extract_gizmos, prepare_line_gizmos, queue_line_gizmos, DrawLineGizmos, LineGizmoPipeline,
+    LineGizmoViewBindGroup,
+};
+
+pub const LINE_GIZMO_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3688319631424740001);
+
+/// Adds support for drawing [`Gizmos`] to an App, with the [`DefaultGizmoConfigGroup`].
+#[derive(Default)]
+pub struct GizmoPlugin;
+
+impl Plugin for GizmoPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            LINE_GIZMO_SHADER_HANDLE,
+            "render/lines.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.add_gizmo_group::<DefaultGizmoConfigGroup>(GizmoConfig::default());
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<LineGizmoPipeline>()
+                .init_resource::<SpecializedRenderPipelines<LineGizmoPipeline>>()
+                .init_resource::<LineGizmoViewBindGroup>()
+                .add_render_command::<Transparent3d, DrawLineGizmos>()
+                .add_render_command::<Transparent2d, DrawLineGizmos>()
+                .add_system(prepare_line_gizmos.in_set(RenderSet::Prepare))
+                .add_system(queue_line_gizmos.in_set(RenderSet::Queue));
+        }
+    }
+}
+
+/// Registers [`GizmoConfigGroup`]s to an App.
+pub trait AddGizmoGroup {
+    /// Registers the gizmo group `G` with its initial `config`, so that it can be drawn with
+    /// [`Gizmos<G>`].
+    ///
+    /// The config can then be changed through the [`GizmoConfigStore`].
+    fn add_gizmo_group<G: GizmoConfigGroup>(&mut self, config: GizmoConfig) -> &mut Self;
+}
+
+impl AddGizmoGroup for App {
+    fn add_gizmo_group<G: GizmoConfigGroup>(&mut self, config: GizmoConfig) -> &mut Self {
+        let mut config_store = self
+            .world
+            .get_resource_or_insert_with(GizmoConfigStore::default);
+        if config_store.get::<G>().is_some() {
+            config_store.insert::<G>(config);
+            return self;
+        }
+        config_store.insert::<G>(config);
+
+        self.init_resource::<GizmoStorage<G>>()
+            .add_system(clear_gizmos::<G>.in_base_set(CoreSet::First));
+
+        if let Ok(render_app) = self.get_sub_app_mut(RenderApp) {
+            render_app.add_system(extract_gizmos::<G>.in_schedule(ExtractSchedule));
+        }
+        self
+    }
+}
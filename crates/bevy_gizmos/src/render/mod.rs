@@ -0,0 +1,340 @@
+use crate::{gizmos::GizmoStorage, GizmoConfigGroup, GizmoConfigStore, LINE_GIZMO_SHADER_HANDLE};
+use bevy_core_pipeline::{core_2d::Transparent2d, core_3d::Transparent3d};
+use bevy_ecs::{
+    prelude::*,
+    system::{
+        lifetimeless::{Read, SRes},
+        SystemParamItem,
+    },
+};
+use bevy_render::{
+    render_phase::{
+        DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult, RenderPhase, SetItemPipeline,
+        TrackedRenderPass,
+    },
+    render_resource::*,
+    renderer::RenderDevice,
+    texture::BevyDefault,
+    view::{ExtractedView, Msaa, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms},
+    Extract,
+};
+use bevy_utils::FloatOrd;
+
+/// The lines of a gizmo group, extracted to an entity of the render world.
+#[derive(Component)]
+pub struct ExtractedLineGizmos {
+    pub positions: Vec<[f32; 3]>,
+    pub colors: Vec<[f32; 4]>,
+    pub depth_test: bool,
+}
+
+pub(crate) fn extract_gizmos<G: GizmoConfigGroup>(
+    mut commands: Commands,
+    storage: Extract<Res<GizmoStorage<G>>>,
+    config_store: Extract<Res<GizmoConfigStore>>,
+) {
+    let config = config_store.config::<G>();
+    if !config.enabled || storage.positions.is_empty() {
+        return;
+    }
+    commands.spawn(ExtractedLineGizmos {
+        positions: storage.positions.clone(),
+        colors: storage.colors.clone(),
+        depth_test: config.depth_test,
+    });
+}
+
+#[derive(Component)]
+pub struct LineGizmoBuffers {
+    positions: Buffer,
+    colors: Buffer,
+    vertex_count: u32,
+}
+
+pub fn prepare_line_gizmos(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    gizmos: Query<(Entity, &ExtractedLineGizmos)>,
+) {
+    for (entity, gizmos) in &gizmos {
+        let positions = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("line_gizmo_position_buffer"),
+            contents: bytemuck::cast_slice(&gizmos.positions),
+            usage: BufferUsages::VERTEX,
+        });
+        let colors = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("line_gizmo_color_buffer"),
+            contents: bytemuck::cast_slice(&gizmos.colors),
+            usage: BufferUsages::VERTEX,
+        });
+        commands.entity(entity).insert(LineGizmoBuffers {
+            positions,
+            colors,
+            vertex_count: gizmos.positions.len() as u32,
+        });
+    }
+}
+
+#[derive(Resource)]
+pub struct LineGizmoPipeline {
+    view_layout: BindGroupLayout,
+}
+
+impl FromWorld for LineGizmoPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let view_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: Some(ViewUniform::min_size()),
+                },
+                count: None,
+            }],
+            label: Some("line_gizmo_view_layout"),
+        });
+        LineGizmoPipeline { view_layout }
+    }
+}
+
+bitflags::bitflags! {
+    #[repr(transparent)]
+    pub struct LineGizmoPipelineKey: u32 {
+        const NONE                   = 0;
+        const HDR                    = (1 << 0);
+        /// Draws to the [`Transparent2d`] phase, which has no depth buffer.
+        const VIEW_2D                = (1 << 1);
+        const DEPTH_TEST             = (1 << 2);
+        const MSAA_RESERVED_BITS     = Self::MSAA_MASK_BITS << Self::MSAA_SHIFT_BITS;
+    }
+}
+
+impl LineGizmoPipelineKey {
+    const MSAA_MASK_BITS: u32 = 0b111;
+    const MSAA_SHIFT_BITS: u32 = 32 - Self::MSAA_MASK_BITS.count_ones();
+
+    #[inline]
+    pub const fn from_msaa_samples(msaa_samples: u32) -> Self {
+        let msaa_bits =
+            (msaa_samples.trailing_zeros() & Self::MSAA_MASK_BITS) << Self::MSAA_SHIFT_BITS;
+        Self::from_bits_truncate(msaa_bits)
+    }
+
+    #[inline]
+    pub const fn msaa_samples(&self) -> u32 {
+        1 << ((self.bits >> Self::MSAA_SHIFT_BITS) & Self::MSAA_MASK_BITS)
+    }
+
+    #[inline]
+    pub const fn from_hdr(hdr: bool) -> Self {
+        if hdr {
+            LineGizmoPipelineKey::HDR
+        } else {
+            LineGizmoPipelineKey::NONE
+        }
+    }
+
+    #[inline]
+    pub const fn from_depth_test(depth_test: bool) -> Self {
+        if depth_test {
+            LineGizmoPipelineKey::DEPTH_TEST
+        } else {
+            LineGizmoPipelineKey::NONE
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for LineGizmoPipeline {
+    type Key = LineGizmoPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let format = match key.contains(LineGizmoPipelineKey::HDR) {
+            true => ViewTarget::TEXTURE_FORMAT_HDR,
+            false => TextureFormat::bevy_default(),
+        };
+        let depth_stencil = if key.contains(LineGizmoPipelineKey::VIEW_2D) {
+            None
+        } else {
+            let depth_compare = if key.contains(LineGizmoPipelineKey::DEPTH_TEST) {
+                CompareFunction::GreaterEqual
+            } else {
+                CompareFunction::Always
+            };
+            Some(DepthStencilState {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            })
+        };
+
+        RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: LINE_GIZMO_SHADER_HANDLE.typed::<Shader>(),
+                entry_point: "vertex".into(),
+                shader_defs: Vec::new(),
+                buffers: vec![
+                    VertexBufferLayout::from_vertex_formats(
+                        VertexStepMode::Vertex,
+                        [VertexFormat::Float32x3],
+                    ),
+                    VertexBufferLayout {
+                        array_stride: VertexFormat::Float32x4.size(),
+                        step_mode: VertexStepMode::Vertex,
+                        attributes: vec![VertexAttribute {
+                            format: VertexFormat::Float32x4,
+                            offset: 0,
+                            shader_location: 1,
+                        }],
+                    },
+                ],
+            },
+            fragment: Some(FragmentState {
+                shader: LINE_GIZMO_SHADER_HANDLE.typed::<Shader>(),
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            layout: vec![self.view_layout.clone()],
+            primitive: PrimitiveState {
+                topology: PrimitiveTopology::LineList,
+                ..Default::default()
+            },
+            depth_stencil,
+            multisample: MultisampleState {
+                count: key.msaa_samples(),
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            label: Some("line_gizmo_pipeline".into()),
+            push_constant_ranges: Vec::new(),
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+pub struct LineGizmoViewBindGroup {
+    value: Option<BindGroup>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn queue_line_gizmos(
+    draw_functions_3d: Res<DrawFunctions<Transparent3d>>,
+    draw_functions_2d: Res<DrawFunctions<Transparent2d>>,
+    render_device: Res<RenderDevice>,
+    line_gizmo_pipeline: Res<LineGizmoPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<LineGizmoPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    view_uniforms: Res<ViewUniforms>,
+    mut view_bind_group: ResMut<LineGizmoViewBindGroup>,
+    gizmos: Query<(Entity, &ExtractedLineGizmos)>,
+    mut views_3d: Query<(&ExtractedView, &Msaa, &mut RenderPhase<Transparent3d>)>,
+    mut views_2d: Query<(&ExtractedView, &Msaa, &mut RenderPhase<Transparent2d>)>,
+) {
+    if gizmos.is_empty() {
+        return;
+    }
+    let view_binding = match view_uniforms.uniforms.binding() {
+        Some(view_binding) => view_binding,
+        None => return,
+    };
+    view_bind_group.value = Some(render_device.create_bind_group(&BindGroupDescriptor {
+        entries: &[BindGroupEntry {
+            binding: 0,
+            resource: view_binding,
+        }],
+        label: Some("line_gizmo_view_bind_group"),
+        layout: &line_gizmo_pipeline.view_layout,
+    }));
+
+    let draw_line_gizmos = draw_functions_3d.read().id::<DrawLineGizmos>();
+    for (view, msaa, mut transparent_phase) in &mut views_3d {
+        let view_key = LineGizmoPipelineKey::from_msaa_samples(msaa.samples())
+            | LineGizmoPipelineKey::from_hdr(view.hdr);
+        for (entity, gizmos) in &gizmos {
+            let key = view_key | LineGizmoPipelineKey::from_depth_test(gizmos.depth_test);
+            let pipeline = pipelines.specialize(&pipeline_cache, &line_gizmo_pipeline, key);
+            transparent_phase.add(Transparent3d {
+                entity,
+                pipeline,
+                draw_function: draw_line_gizmos,
+                // Values increase towards the camera, so gizmos are drawn after everything else
+                distance: f32::MAX,
+            });
+        }
+    }
+
+    let draw_line_gizmos = draw_functions_2d.read().id::<DrawLineGizmos>();
+    for (view, msaa, mut transparent_phase) in &mut views_2d {
+        let key = LineGizmoPipelineKey::from_msaa_samples(msaa.samples())
+            | LineGizmoPipelineKey::from_hdr(view.hdr)
+            | LineGizmoPipelineKey::VIEW_2D;
+        let pipeline = pipelines.specialize(&pipeline_cache, &line_gizmo_pipeline, key);
+        for (entity, _) in &gizmos {
+            transparent_phase.add(Transparent2d {
+                entity,
+                pipeline,
+                draw_function: draw_line_gizmos,
+                sort_key: FloatOrd(f32::MAX),
+                batch_range: None,
+            });
+        }
+    }
+}
+
+pub type DrawLineGizmos = (SetItemPipeline, SetLineGizmoViewBindGroup<0>, DrawLineGizmo);
+
+pub struct SetLineGizmoViewBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetLineGizmoViewBindGroup<I> {
+    type Param = SRes<LineGizmoViewBindGroup>;
+    type ViewWorldQuery = Read<ViewUniformOffset>;
+    type ItemWorldQuery = ();
+
+    fn render<'w>(
+        _item: &P,
+        view_uniform: &'_ ViewUniformOffset,
+        _entity: (),
+        view_bind_group: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        match view_bind_group.into_inner().value.as_ref() {
+            Some(bind_group) => {
+                pass.set_bind_group(I, bind_group, &[view_uniform.offset]);
+                RenderCommandResult::Success
+            }
+            None => RenderCommandResult::Failure,
+        }
+    }
+}
+
+pub struct DrawLineGizmo;
+impl<P: PhaseItem> RenderCommand<P> for DrawLineGizmo {
+    type Param = ();
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Option<Read<LineGizmoBuffers>>;
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        buffers: Option<&'w LineGizmoBuffers>,
+        _param: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let buffers = match buffers {
+            Some(buffers) => buffers,
+            None => return RenderCommandResult::Failure,
+        };
+        pass.set_vertex_buffer(0, buffers.positions.slice(..));
+        pass.set_vertex_buffer(1, buffers.colors.slice(..));
+        pass.draw(0..buffers.vertex_count, 0..1);
+        RenderCommandResult::Success
+    }
+}
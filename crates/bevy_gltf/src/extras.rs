@@ -0,0 +1,140 @@
+use crate::GltfExtras;
+use bevy_ecs::{
+    prelude::{Entity, World},
+    reflect::ReflectComponent,
+    system::Resource,
+    world::EntityMut,
+};
+use bevy_log::warn;
+use bevy_reflect::{
+    serde::TypedReflectDeserializer, std_traits::ReflectDefault, Reflect, TypeRegistration,
+    TypeRegistry,
+};
+use serde::de::DeserializeSeed;
+use serde_json::Value;
+use std::sync::{Arc, RwLock};
+
+/// A function adding components to an entity of a glTF scene from the `extras` of its node,
+/// mesh primitive or light
+pub type GltfExtrasHook = dyn Fn(&Value, &mut EntityMut, &TypeRegistry) + Send + Sync;
+
+/// The hooks the [`GltfLoader`](crate::GltfLoader) calls with the `extras` of the entities of the
+/// glTF scenes it loads, like the custom properties of the objects of a level made in Blender
+///
+/// Before calling the hooks, the loader adds the registered components whose type name, or short
+/// type name, is a key of the `extras` object. The value is deserialized from its JSON, or from
+/// [RON](ron) when it's a string, like an `Enemy` property set to `(health: 10)`. Components
+/// implementing [`Default`] can also be added with a `null`, `true` or empty value. Both the
+/// component and its [`ReflectComponent`] must be registered in the type registry.
+///
+/// ```
+/// # use bevy_app::App;
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_gltf::GltfExtrasHooks;
+/// #[derive(Component)]
+/// struct Collider;
+///
+/// # let mut app = App::new();
+/// # app.init_resource::<GltfExtrasHooks>();
+/// app.world
+///     .resource::<GltfExtrasHooks>()
+///     .add(|extras, entity, _registry| {
+///         if extras.get("collider").is_some() {
+///             entity.insert(Collider);
+///         }
+///     });
+/// ```
+///
+/// The hooks are shared with the loader, so they can still be added once the `GltfPlugin` was
+/// added, but they're only called for the files loaded after they were added.
+#[derive(Resource, Clone, Default)]
+pub struct GltfExtrasHooks {
+    hooks: Arc<RwLock<Vec<Arc<GltfExtrasHook>>>>,
+}
+
+impl GltfExtrasHooks {
+    /// Adds a hook, called after the hooks already added.
+    pub fn add(
+        &self,
+        hook: impl Fn(&Value, &mut EntityMut, &TypeRegistry) + Send + Sync + 'static,
+    ) {
+        self.hooks.write().unwrap().push(Arc::new(hook));
+    }
+
+    /// Adds the components of the `extras` of the entities of `world`, and calls the hooks.
+    pub(crate) fn apply(&self, world: &mut World, registry: &TypeRegistry) {
+        let mut query = world.query::<(Entity, &GltfExtras)>();
+        let extras: Vec<(Entity, Value)> = query
+            .iter(world)
+            .filter_map(
+                |(entity, extras)| match serde_json::from_str(&extras.value) {
+                    Ok(value) => Some((entity, value)),
+                    Err(err) => {
+                        warn!("invalid glTF extras {:?}: {}", extras.value, err);
+                        None
+                    }
+                },
+            )
+            .collect();
+        let hooks = self.hooks.read().unwrap().clone();
+        for (entity, extras) in extras {
+            let mut entity = world.entity_mut(entity);
+            insert_reflected_components(&extras, &mut entity, registry);
+            for hook in &hooks {
+                hook(&extras, &mut entity, registry);
+            }
+        }
+    }
+}
+
+/// Inserts the registered components named by the keys of `extras`.
+fn insert_reflected_components(extras: &Value, entity: &mut EntityMut, registry: &TypeRegistry) {
+    let Value::Object(properties) = extras else {
+        return;
+    };
+    for (name, value) in properties {
+        let Some(registration) = registry
+            .get_with_name(name)
+            .or_else(|| registry.get_with_short_name(name))
+        else {
+            continue;
+        };
+        let Some(reflect_component) = registration.data::<ReflectComponent>() else {
+            continue;
+        };
+        match deserialize_component(registration, value, registry) {
+            Ok(component) => reflect_component.insert(entity, &*component),
+            Err(err) => warn!(
+                "failed to add the component {} from the glTF extras: {}",
+                registration.type_name(),
+                err
+            ),
+        }
+    }
+}
+
+fn deserialize_component(
+    registration: &TypeRegistration,
+    value: &Value,
+    registry: &TypeRegistry,
+) -> Result<Box<dyn Reflect>, anyhow::Error> {
+    let is_empty = match value {
+        Value::Null | Value::Bool(true) => true,
+        Value::String(string) => string.is_empty(),
+        _ => false,
+    };
+    if is_empty {
+        if let Some(reflect_default) = registration.data::<ReflectDefault>() {
+            return Ok(reflect_default.default());
+        }
+    }
+
+    let deserializer = TypedReflectDeserializer::new(registration, registry);
+    match value {
+        Value::String(ron) => {
+            let mut ron_deserializer = ron::de::Deserializer::from_str(ron)?;
+            Ok(deserializer.deserialize(&mut ron_deserializer)?)
+        }
+        value => Ok(deserializer.deserialize(value)?),
+    }
+}
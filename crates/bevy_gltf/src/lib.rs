@@ -2,7 +2,9 @@
 use bevy_animation::AnimationClip;
 use bevy_utils::HashMap;
 
+mod extras;
 mod loader;
+pub use extras::*;
 pub use loader::*;
 
 use bevy_app::prelude::*;
@@ -19,7 +21,8 @@ pub struct GltfPlugin;
 
 impl Plugin for GltfPlugin {
     fn build(&self, app: &mut App) {
-        app.init_asset_loader::<GltfLoader>()
+        app.init_resource::<GltfExtrasHooks>()
+            .init_asset_loader::<GltfLoader>()
             .register_type::<GltfExtras>()
             .add_asset::<Gltf>()
             .add_asset::<GltfNode>()
@@ -77,6 +80,10 @@ pub struct GltfPrimitive {
     pub material_extras: Option<GltfExtras>,
 }
 
+/// The JSON `extras` of a glTF node, mesh primitive or light, added to its entity
+///
+/// The registered components named in the extras are also added to the entity, and the
+/// [`GltfExtrasHooks`] are called with them.
 #[derive(Clone, Debug, Reflect, Default, Component)]
 #[reflect(Component)]
 pub struct GltfExtras {
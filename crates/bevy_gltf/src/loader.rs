@@ -1,4 +1,5 @@
 use anyhow::Result;
+use bevy_app::AppTypeRegistry;
 use bevy_asset::{
     AssetIoError, AssetLoader, AssetPath, BoxedFuture, Handle, LoadContext, LoadedAsset,
 };
@@ -12,6 +13,7 @@ use bevy_pbr::{
     AlphaMode, DirectionalLight, DirectionalLightBundle, PbrBundle, PointLight, PointLightBundle,
     SpotLight, SpotLightBundle, StandardMaterial,
 };
+use bevy_reflect::TypeRegistryArc;
 use bevy_render::{
     camera::{
         Camera, CameraRenderGraph, OrthographicProjection, PerspectiveProjection, Projection,
@@ -43,7 +45,7 @@ use gltf::{
 use std::{collections::VecDeque, path::Path};
 use thiserror::Error;
 
-use crate::{Gltf, GltfExtras, GltfNode};
+use crate::{Gltf, GltfExtras, GltfExtrasHooks, GltfNode};
 
 /// An error that occurs when loading a glTF file.
 #[derive(Error, Debug)]
@@ -73,6 +75,8 @@ pub enum GltfError {
 /// Loads glTF files with all of their data as their corresponding bevy representations.
 pub struct GltfLoader {
     supported_compressed_formats: CompressedImageFormats,
+    type_registry: TypeRegistryArc,
+    extras_hooks: GltfExtrasHooks,
 }
 
 impl AssetLoader for GltfLoader {
@@ -81,9 +85,7 @@ impl AssetLoader for GltfLoader {
         bytes: &'a [u8],
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<()>> {
-        Box::pin(async move {
-            Ok(load_gltf(bytes, load_context, self.supported_compressed_formats).await?)
-        })
+        Box::pin(async move { Ok(load_gltf(bytes, load_context, self).await?) })
     }
 
     fn extensions(&self) -> &[&str] {
@@ -100,6 +102,10 @@ impl FromWorld for GltfLoader {
         };
         Self {
             supported_compressed_formats,
+            type_registry: world.resource::<AppTypeRegistry>().0.clone(),
+            extras_hooks: world
+                .get_resource_or_insert_with(GltfExtrasHooks::default)
+                .clone(),
         }
     }
 }
@@ -108,8 +114,9 @@ impl FromWorld for GltfLoader {
 async fn load_gltf<'a, 'b>(
     bytes: &'a [u8],
     load_context: &'a mut LoadContext<'b>,
-    supported_compressed_formats: CompressedImageFormats,
+    loader: &GltfLoader,
 ) -> Result<(), GltfError> {
+    let supported_compressed_formats = loader.supported_compressed_formats;
     let gltf = gltf::Gltf::from_slice(bytes)?;
     let buffer_data = load_buffers(&gltf, load_context, load_context.path()).await?;
 
@@ -519,6 +526,10 @@ async fn load_gltf<'a, 'b>(
             });
         }
 
+        loader
+            .extras_hooks
+            .apply(&mut world, &loader.type_registry.read());
+
         let scene_handle = load_context
             .set_labeled_asset(&scene_label(&scene), LoadedAsset::new(Scene::new(world)));
 
@@ -669,7 +680,10 @@ fn load_material(material: &Material, load_context: &mut LoadContext) -> Handle<
         load_context.get_handle(path)
     });
 
-    let emissive = material.emissive_factor();
+    // KHR_materials_emissive_strength scales the emissive color over 1.0
+    let emissive = material
+        .emissive_factor()
+        .map(|factor| factor * material.emissive_strength().unwrap_or(1.0));
     let emissive_texture = material.emissive_texture().map(|info| {
         // TODO: handle occlusion_texture.tex_coord() (the *set* index for the right texcoords)
         // TODO: handle occlusion_texture.strength() (a scalar multiplier for occlusion strength)
@@ -678,6 +692,28 @@ fn load_material(material: &Material, load_context: &mut LoadContext) -> Handle<
         load_context.get_handle(path)
     });
 
+    // KHR_materials_clearcoat isn't parsed by the gltf crate, its textures aren't supported
+    let clearcoat_extension = material.extension_value("KHR_materials_clearcoat");
+    let clearcoat_factor = |name| {
+        clearcoat_extension
+            .and_then(|extension| extension.get(name))
+            .and_then(|factor| factor.as_f64())
+            .unwrap_or(0.0) as f32
+    };
+    let clearcoat = clearcoat_factor("clearcoatFactor");
+    let clearcoat_perceptual_roughness = clearcoat_factor("clearcoatRoughnessFactor");
+
+    // the transmitted light is blended over what's behind the material, keeping its reflections
+    let specular_transmission = material
+        .transmission()
+        .map_or(0.0, |transmission| transmission.transmission_factor());
+    let alpha_mode = match alpha_mode(material) {
+        AlphaMode::Opaque | AlphaMode::Blend if specular_transmission > 0.0 => {
+            AlphaMode::Premultiplied
+        }
+        alpha_mode => alpha_mode,
+    };
+
     load_context.set_labeled_asset(
         &material_label,
         LoadedAsset::new(StandardMaterial {
@@ -697,12 +733,30 @@ fn load_material(material: &Material, load_context: &mut LoadContext) -> Handle<
             emissive: Color::rgb_linear(emissive[0], emissive[1], emissive[2]),
             emissive_texture,
             unlit: material.unlit(),
-            alpha_mode: alpha_mode(material),
+            alpha_mode,
+            reflectance: reflectance(material),
+            clearcoat,
+            clearcoat_perceptual_roughness,
+            specular_transmission,
             ..Default::default()
         }),
     )
 }
 
+/// Returns the reflectance of the [`StandardMaterial`] matching the f0 of the index of refraction
+/// of `KHR_materials_ior`, scaled by `KHR_materials_specular`.
+fn reflectance(material: &Material) -> f32 {
+    let ior = material.ior().unwrap_or(1.5);
+    let mut f0 = ((ior - 1.0) / (ior + 1.0)).powi(2);
+    if let Some(specular) = material.specular() {
+        // the color of the specular reflections of dielectrics isn't supported, only its brightness
+        let [r, g, b] = specular.specular_color_factor();
+        f0 = (f0 * specular.specular_factor() * (r + g + b) / 3.0).min(1.0);
+    }
+    // the shader remaps the reflectance to f0 = 0.16 * reflectance^2
+    (f0 / 0.16).sqrt().min(1.0)
+}
+
 /// Loads a glTF node.
 fn load_node(
     gltf_node: &gltf::Node,
@@ -23,6 +23,8 @@ use bevy_app::{PluginGroup, PluginGroupBuilder};
 /// * [`TextPlugin`](crate::text::TextPlugin) - with feature `bevy_text`
 /// * [`UiPlugin`](crate::ui::UiPlugin) - with feature `bevy_ui`
 /// * [`PbrPlugin`](crate::pbr::PbrPlugin) - with feature `bevy_pbr`
+/// * [`ParticlesPlugin`](crate::particles::ParticlesPlugin) - with feature `bevy_particles`
+/// * [`GizmoPlugin`](crate::gizmos::GizmoPlugin) - with feature `bevy_gizmos`
 /// * [`GltfPlugin`](crate::gltf::GltfPlugin) - with feature `bevy_gltf`
 /// * [`AudioPlugin`](crate::audio::AudioPlugin) - with feature `bevy_audio`
 /// * [`GilrsPlugin`](crate::gilrs::GilrsPlugin) - with feature `bevy_gilrs`
@@ -112,6 +114,16 @@ impl PluginGroup for DefaultPlugins {
             group = group.add(bevy_pbr::PbrPlugin::default());
         }
 
+        #[cfg(feature = "bevy_particles")]
+        {
+            group = group.add(bevy_particles::ParticlesPlugin::default());
+        }
+
+        #[cfg(feature = "bevy_gizmos")]
+        {
+            group = group.add(bevy_gizmos::GizmoPlugin::default());
+        }
+
         // NOTE: Load this after renderer initialization so that it knows about the supported
         // compressed texture formats
         #[cfg(feature = "bevy_gltf")]
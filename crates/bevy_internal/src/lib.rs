@@ -121,12 +121,24 @@ pub mod gilrs {
     pub use bevy_gilrs::*;
 }
 
+#[cfg(feature = "bevy_gizmos")]
+pub mod gizmos {
+    //! Immediate mode debug drawing.
+    pub use bevy_gizmos::*;
+}
+
 #[cfg(feature = "bevy_gltf")]
 pub mod gltf {
     //! Support for GLTF file loading.
     pub use bevy_gltf::*;
 }
 
+#[cfg(feature = "bevy_particles")]
+pub mod particles {
+    //! GPU simulated particle effects.
+    pub use bevy_particles::*;
+}
+
 #[cfg(feature = "bevy_pbr")]
 pub mod pbr {
     //! Physically based rendering.
@@ -23,6 +23,14 @@ pub use crate::animation::prelude::*;
 #[cfg(feature = "bevy_core_pipeline")]
 pub use crate::core_pipeline::prelude::*;
 
+#[doc(hidden)]
+#[cfg(feature = "bevy_gizmos")]
+pub use crate::gizmos::prelude::*;
+
+#[doc(hidden)]
+#[cfg(feature = "bevy_particles")]
+pub use crate::particles::prelude::*;
+
 #[doc(hidden)]
 #[cfg(feature = "bevy_pbr")]
 pub use crate::pbr::prelude::*;
@@ -0,0 +1,270 @@
+use anyhow::Result;
+use bevy_asset::{AssetLoader, LoadContext, LoadedAsset};
+use bevy_math::Vec3;
+use bevy_reflect::TypeUuid;
+use bevy_render::color::Color;
+use bevy_utils::BoxedFuture;
+use serde::{Deserialize, Serialize};
+
+/// The maximum number of keys of a [`Gradient`] that are uploaded to the GPU.
+///
+/// Keys beyond this limit are ignored when the effect is simulated.
+pub const MAX_GRADIENT_KEYS: usize = 4;
+
+/// Describes how particles of an effect are spawned, simulated and drawn.
+///
+/// Effects are assets, so they can be shared by many [`ParticleEmitter`](crate::ParticleEmitter)s
+/// and loaded from `.particles.ron` files with the [`ParticleEffectLoader`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, TypeUuid)]
+#[uuid = "6a0e3b4c-8d1f-4f5e-9c2a-0b7d5e3f1a84"]
+#[serde(default)]
+pub struct ParticleEffect {
+    /// The maximum number of particles alive at once.
+    ///
+    /// When the capacity is exhausted, the oldest particles are recycled.
+    pub capacity: u32,
+    /// The number of particles spawned per second.
+    pub spawn_rate: f32,
+    /// The number of particles spawned at once when an emitter starts playing.
+    pub initial_burst: u32,
+    /// The lifetime of each particle, in seconds.
+    pub lifetime: ValueRange,
+    /// The volume particles are spawned in.
+    pub shape: EmitterShape,
+    /// The speed of each particle when spawned, in units per second.
+    pub initial_speed: ValueRange,
+    /// A constant acceleration applied to every particle, such as gravity.
+    pub acceleration: Vec3,
+    /// Linear damping of the particle velocity, per second.
+    pub drag: f32,
+    /// A multiplier applied to the particle velocity, sampled over its normalized lifetime.
+    pub speed_over_lifetime: Gradient<f32>,
+    /// The color of a particle, sampled over its normalized lifetime.
+    pub color_over_lifetime: Gradient<Color>,
+    /// The size of a particle, sampled over its normalized lifetime.
+    pub size_over_lifetime: Gradient<f32>,
+    /// How particles are blended with what is behind them.
+    pub blend_mode: ParticleBlendMode,
+}
+
+impl Default for ParticleEffect {
+    fn default() -> Self {
+        Self {
+            capacity: 1024,
+            spawn_rate: 64.0,
+            initial_burst: 0,
+            lifetime: ValueRange::new(1.0, 2.0),
+            shape: EmitterShape::default(),
+            initial_speed: ValueRange::constant(1.0),
+            acceleration: Vec3::ZERO,
+            drag: 0.0,
+            speed_over_lifetime: Gradient::constant(1.0),
+            color_over_lifetime: Gradient::constant(Color::WHITE),
+            size_over_lifetime: Gradient::constant(0.1),
+            blend_mode: ParticleBlendMode::default(),
+        }
+    }
+}
+
+/// A range a value is picked from uniformly at random for every particle.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ValueRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+impl ValueRange {
+    pub const fn new(min: f32, max: f32) -> Self {
+        Self { min, max }
+    }
+
+    /// Creates a range that always yields `value`.
+    pub const fn constant(value: f32) -> Self {
+        Self::new(value, value)
+    }
+
+    /// Returns the value at `t` in `[0, 1]` between `min` and `max`.
+    pub fn lerp(&self, t: f32) -> f32 {
+        self.min + (self.max - self.min) * t
+    }
+}
+
+/// The volume particles are spawned in, relative to the emitter transform.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum EmitterShape {
+    /// Particles are spawned at the emitter origin, moving in a random direction.
+    #[default]
+    Point,
+    /// Particles are spawned inside a sphere, moving away from its center.
+    Sphere { radius: f32 },
+    /// Particles are spawned on a disc of the given radius, moving along the local Y axis
+    /// within `angle` radians of it.
+    Cone { angle: f32, radius: f32 },
+    /// Particles are spawned inside a box, moving along the local Y axis.
+    Box { half_extents: Vec3 },
+}
+
+/// How particles are blended with the render target.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ParticleBlendMode {
+    /// Standard alpha blending, suited for smoke and dust.
+    #[default]
+    Alpha,
+    /// Additive blending, suited for fire, sparks and magic effects.
+    Additive,
+}
+
+/// A key of a [`Gradient`].
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GradientKey<T> {
+    /// The normalized particle lifetime of this key, in `[0, 1]`.
+    pub time: f32,
+    pub value: T,
+}
+
+/// A value interpolated linearly between keys over the lifetime of a particle.
+///
+/// Keys are expected to be sorted by time. Before the first key and after the last one,
+/// the value of the nearest key is used.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Gradient<T> {
+    pub keys: Vec<GradientKey<T>>,
+}
+
+impl<T: Copy> Gradient<T> {
+    /// Creates a gradient that has the same value over the whole particle lifetime.
+    pub fn constant(value: T) -> Self {
+        Self {
+            keys: vec![GradientKey { time: 0.0, value }],
+        }
+    }
+
+    /// Creates a gradient going from `start` to `end` over the particle lifetime.
+    pub fn linear(start: T, end: T) -> Self {
+        Self {
+            keys: vec![
+                GradientKey {
+                    time: 0.0,
+                    value: start,
+                },
+                GradientKey {
+                    time: 1.0,
+                    value: end,
+                },
+            ],
+        }
+    }
+
+    /// Adds a key at `time`, keeping the keys sorted.
+    pub fn with_key(mut self, time: f32, value: T) -> Self {
+        let index = self.keys.partition_point(|key| key.time <= time);
+        self.keys.insert(index, GradientKey { time, value });
+        self
+    }
+}
+
+impl<T: Copy + GradientValue> Gradient<T> {
+    /// Samples the gradient at the normalized lifetime `t`.
+    ///
+    /// Returns `None` if the gradient has no keys.
+    pub fn sample(&self, t: f32) -> Option<T> {
+        let first = self.keys.first()?;
+        if t <= first.time {
+            return Some(first.value);
+        }
+        for window in self.keys.windows(2) {
+            let (a, b) = (&window[0], &window[1]);
+            if t <= b.time {
+                let span = b.time - a.time;
+                if span <= f32::EPSILON {
+                    return Some(b.value);
+                }
+                return Some(T::lerp(a.value, b.value, (t - a.time) / span));
+            }
+        }
+        self.keys.last().map(|key| key.value)
+    }
+}
+
+/// A value that can be interpolated by a [`Gradient`].
+pub trait GradientValue {
+    fn lerp(a: Self, b: Self, t: f32) -> Self;
+}
+
+impl GradientValue for f32 {
+    fn lerp(a: Self, b: Self, t: f32) -> Self {
+        a + (b - a) * t
+    }
+}
+
+impl GradientValue for Color {
+    fn lerp(a: Self, b: Self, t: f32) -> Self {
+        let a = a.as_rgba_f32();
+        let b = b.as_rgba_f32();
+        Color::rgba(
+            f32::lerp(a[0], b[0], t),
+            f32::lerp(a[1], b[1], t),
+            f32::lerp(a[2], b[2], t),
+            f32::lerp(a[3], b[3], t),
+        )
+    }
+}
+
+/// Loads [`ParticleEffect`]s from RON files.
+#[derive(Default)]
+pub struct ParticleEffectLoader;
+
+impl AssetLoader for ParticleEffectLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let effect = ron::de::from_bytes::<ParticleEffect>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(effect));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["particles.ron"]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gradient_sample() {
+        let gradient = Gradient::linear(0.0, 1.0).with_key(0.5, 4.0);
+        assert_eq!(gradient.sample(-1.0), Some(0.0));
+        assert_eq!(gradient.sample(0.25), Some(2.0));
+        assert_eq!(gradient.sample(0.5), Some(4.0));
+        assert_eq!(gradient.sample(0.75), Some(2.5));
+        assert_eq!(gradient.sample(2.0), Some(1.0));
+        assert_eq!(Gradient::<f32> { keys: Vec::new() }.sample(0.5), None);
+    }
+
+    #[test]
+    fn effect_ron_round_trip() {
+        let effect = ParticleEffect {
+            shape: EmitterShape::Cone {
+                angle: 0.3,
+                radius: 0.5,
+            },
+            color_over_lifetime: Gradient::linear(Color::WHITE, Color::rgba(1.0, 0.0, 0.0, 0.0)),
+            blend_mode: ParticleBlendMode::Additive,
+            ..Default::default()
+        };
+        let serialized = ron::to_string(&effect).unwrap();
+        let deserialized: ParticleEffect = ron::from_str(&serialized).unwrap();
+        assert_eq!(effect, deserialized);
+
+        // Missing fields fall back to their defaults
+        let partial: ParticleEffect = ron::from_str("(capacity: 16)").unwrap();
+        assert_eq!(partial.capacity, 16);
+        assert_eq!(partial.spawn_rate, ParticleEffect::default().spawn_rate);
+    }
+}
@@ -0,0 +1,167 @@
+use crate::ParticleEffect;
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::{prelude::*, reflect::ReflectComponent};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::prelude::{Image, InheritedVisibility, Mesh, ViewVisibility, Visibility};
+use bevy_time::Time;
+use bevy_transform::components::{GlobalTransform, Transform};
+
+/// Spawns and simulates the particles of a [`ParticleEffect`].
+///
+/// Particles are simulated in world space, so moving the emitter leaves already spawned
+/// particles behind.
+#[derive(Component, Debug, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct ParticleEmitter {
+    /// Whether new particles are spawned. Particles that are already alive keep being simulated.
+    pub playing: bool,
+    /// A multiplier applied to the spawn rate of the effect.
+    pub rate_multiplier: f32,
+}
+
+impl Default for ParticleEmitter {
+    fn default() -> Self {
+        Self {
+            playing: true,
+            rate_multiplier: 1.0,
+        }
+    }
+}
+
+/// The spawning state of a [`ParticleEmitter`], updated by [`tick_particle_emitters`].
+///
+/// Particles are stored in a ring buffer of [`ParticleEffect::capacity`] slots on the GPU.
+/// Each frame, the `spawn_count` slots starting at `spawn_offset` are (re)spawned.
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct ParticleEmitterState {
+    /// The index of the first slot spawned this frame.
+    pub spawn_offset: u32,
+    /// The number of particles spawned this frame.
+    pub spawn_count: u32,
+    /// The time elapsed since the last frame, in seconds, used to advance the simulation.
+    pub delta_seconds: f32,
+    /// The seed used to randomize the particles spawned this frame.
+    pub seed: u32,
+    accumulator: f32,
+    started: bool,
+}
+
+impl ParticleEmitterState {
+    /// Advances the spawner by `delta_seconds`, updating the range of slots spawned this frame.
+    pub fn tick(&mut self, emitter: &ParticleEmitter, effect: &ParticleEffect, delta_seconds: f32) {
+        let capacity = effect.capacity.max(1);
+        self.spawn_offset = (self.spawn_offset + self.spawn_count) % capacity;
+        self.delta_seconds = delta_seconds;
+        self.seed = self
+            .seed
+            .wrapping_mul(747_796_405)
+            .wrapping_add(2_891_336_453);
+
+        let mut spawn_count = 0;
+        if emitter.playing {
+            if !self.started {
+                self.started = true;
+                spawn_count += effect.initial_burst;
+            }
+            self.accumulator += effect.spawn_rate * emitter.rate_multiplier * delta_seconds;
+            let whole = self.accumulator.floor();
+            self.accumulator -= whole;
+            spawn_count += whole as u32;
+        } else {
+            // Restart with the initial burst when playing again
+            self.started = false;
+            self.accumulator = 0.0;
+        }
+        self.spawn_count = spawn_count.min(capacity);
+    }
+}
+
+/// Updates the [`ParticleEmitterState`] of every emitter.
+pub fn tick_particle_emitters(
+    time: Res<Time>,
+    effects: Res<Assets<ParticleEffect>>,
+    mut emitters: Query<(
+        &ParticleEmitter,
+        &Handle<ParticleEffect>,
+        &mut ParticleEmitterState,
+    )>,
+) {
+    let delta_seconds = time.delta_seconds();
+    for (emitter, handle, mut state) in &mut emitters {
+        if let Some(effect) = effects.get(handle) {
+            state.tick(emitter, effect, delta_seconds);
+        }
+    }
+}
+
+/// Draws the particles of an emitter as instances of a mesh, instead of camera facing quads.
+///
+/// Each instance is moved to its particle and scaled by its size, keeping the orientation of the
+/// mesh. Meshes are drawn unlit and tinted by the particle color, with the emitter texture mapped
+/// using their [`Mesh::ATTRIBUTE_UV_0`], if any.
+///
+/// The handle is wrapped rather than added as a `Handle<Mesh>` component so that the emitter
+/// doesn't get an [`Aabb`](bevy_render::primitives::Aabb) and is never frustum culled.
+#[derive(Component, Debug, Default, Clone, Reflect)]
+#[reflect(Component, Default)]
+pub struct ParticleMesh(pub Handle<Mesh>);
+
+/// A component bundle for entities emitting particles.
+///
+/// Emitters have no [`Aabb`](bevy_render::primitives::Aabb), so they are never frustum culled:
+/// their particles can travel arbitrarily far away from them. Add a [`ParticleMesh`] to draw
+/// particles as meshes.
+#[derive(Bundle, Clone, Default)]
+pub struct ParticleEffectBundle {
+    pub effect: Handle<ParticleEffect>,
+    /// The texture drawn on every particle, white by default.
+    pub texture: Handle<Image>,
+    pub emitter: ParticleEmitter,
+    pub state: ParticleEmitterState,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    pub visibility: Visibility,
+    pub inherited_visibility: InheritedVisibility,
+    pub view_visibility: ViewVisibility,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spawn_accumulates_over_frames() {
+        let effect = ParticleEffect {
+            capacity: 8,
+            spawn_rate: 10.0,
+            initial_burst: 3,
+            ..Default::default()
+        };
+        let emitter = ParticleEmitter::default();
+        let mut state = ParticleEmitterState::default();
+
+        state.tick(&emitter, &effect, 0.25);
+        assert_eq!((state.spawn_offset, state.spawn_count), (0, 5));
+        state.tick(&emitter, &effect, 0.25);
+        assert_eq!((state.spawn_offset, state.spawn_count), (5, 3));
+        // Wraps around the ring buffer
+        state.tick(&emitter, &effect, 0.1);
+        assert_eq!((state.spawn_offset, state.spawn_count), (0, 1));
+        // Never spawns more than the capacity in a single frame
+        state.tick(&emitter, &effect, 10.0);
+        assert_eq!(state.spawn_count, 8);
+    }
+
+    #[test]
+    fn paused_emitter_does_not_spawn() {
+        let effect = ParticleEffect::default();
+        let emitter = ParticleEmitter {
+            playing: false,
+            ..Default::default()
+        };
+        let mut state = ParticleEmitterState::default();
+        state.tick(&emitter, &effect, 1.0);
+        assert_eq!(state.spawn_count, 0);
+    }
+}
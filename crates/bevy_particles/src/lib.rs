@@ -0,0 +1,102 @@
+//! GPU simulated particle effects for the game engine Bevy.
+//!
+//! Particles are spawned and simulated by a compute shader, and drawn in the
+//! [`Transparent3d`](bevy_core_pipeline::core_3d::Transparent3d) phase, either as camera facing
+//! quads or as instances of a [`ParticleMesh`]. Since particle data lives in storage buffers,
+//! this plugin requires a backend supporting them, which excludes `WebGL2`.
+
+mod effect;
+mod emitter;
+pub mod render;
+
+pub use effect::*;
+pub use emitter::*;
+
+pub mod prelude {
+    #[doc(hidden)]
+    pub use crate::{
+        EmitterShape, Gradient, ParticleBlendMode, ParticleEffect, ParticleEffectBundle,
+        ParticleEmitter, ParticleMesh, ParticlesPlugin, ValueRange,
+    };
+}
+
+use bevy_app::prelude::*;
+use bevy_asset::{load_internal_asset, AddAsset, HandleUntyped};
+use bevy_core_pipeline::core_3d::Transparent3d;
+use bevy_ecs::prelude::*;
+use bevy_reflect::TypeUuid;
+use bevy_render::{
+    main_graph,
+    render_graph::RenderGraph,
+    render_phase::AddRenderCommand,
+    render_resource::{Shader, SpecializedMeshPipelines, SpecializedRenderPipelines},
+    ExtractSchedule, RenderApp, RenderSet,
+};
+use render::{
+    extract_particle_emitters, prepare_particle_emitters, queue_particle_emitters, DrawParticles,
+    ParticleBuffers, ParticleEmitterUniforms, ParticlePipeline, ParticleSimulationNode,
+    ParticleViewBindGroup,
+};
+
+pub const PARTICLES_TYPES_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1617420324861731398);
+pub const PARTICLES_SIMULATE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 9384214767632683335);
+pub const PARTICLES_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 5870303463763617465);
+
+/// Adds support for [`ParticleEffect`]s to an App.
+#[derive(Default)]
+pub struct ParticlesPlugin;
+
+impl Plugin for ParticlesPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            PARTICLES_TYPES_SHADER_HANDLE,
+            "render/particles_types.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            PARTICLES_SIMULATE_SHADER_HANDLE,
+            "render/particles_simulate.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            PARTICLES_SHADER_HANDLE,
+            "render/particles.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.add_asset::<ParticleEffect>()
+            .init_asset_loader::<ParticleEffectLoader>()
+            .register_type::<ParticleEmitter>()
+            .register_type::<ParticleEmitterState>()
+            .register_type::<ParticleMesh>()
+            .add_system(tick_particle_emitters.in_base_set(CoreSet::PostUpdate));
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<ParticlePipeline>()
+                .init_resource::<SpecializedRenderPipelines<ParticlePipeline>>()
+                .init_resource::<SpecializedMeshPipelines<ParticlePipeline>>()
+                .init_resource::<ParticleBuffers>()
+                .init_resource::<ParticleEmitterUniforms>()
+                .init_resource::<ParticleViewBindGroup>()
+                .add_render_command::<Transparent3d, DrawParticles>()
+                .add_system(extract_particle_emitters.in_schedule(ExtractSchedule))
+                .add_system(prepare_particle_emitters.in_set(RenderSet::Prepare))
+                .add_system(queue_particle_emitters.in_set(RenderSet::Queue));
+
+            let simulation_node = ParticleSimulationNode::new(&mut render_app.world);
+            let mut graph = render_app.world.resource_mut::<RenderGraph>();
+            graph.add_node(ParticleSimulationNode::NAME, simulation_node);
+            graph.add_node_edge(
+                ParticleSimulationNode::NAME,
+                main_graph::node::CAMERA_DRIVER,
+            );
+        }
+    }
+}
@@ -0,0 +1,849 @@
+use crate::{
+    EmitterShape, Gradient, ParticleBlendMode, ParticleEffect, ParticleEmitterState, ParticleMesh,
+    MAX_GRADIENT_KEYS, PARTICLES_SHADER_HANDLE, PARTICLES_SIMULATE_SHADER_HANDLE,
+};
+use bevy_asset::{Assets, Handle};
+use bevy_core_pipeline::core_3d::Transparent3d;
+use bevy_ecs::{
+    prelude::*,
+    query::QueryState,
+    system::{lifetimeless::*, SystemParamItem},
+};
+use bevy_math::{Mat4, Vec3, Vec4};
+use bevy_render::{
+    color::Color,
+    mesh::{GpuBufferInfo, Mesh, MeshVertexBufferLayout},
+    render_asset::RenderAssets,
+    render_graph::{Node, NodeRunError, RenderGraphContext},
+    render_phase::{
+        DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult, RenderPhase, SetItemPipeline,
+        TrackedRenderPass,
+    },
+    render_resource::*,
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    texture::{BevyDefault, Image},
+    view::{
+        ExtractedView, InheritedVisibility, Msaa, ViewTarget, ViewUniform, ViewUniformOffset,
+        ViewUniforms, VisibleEntities,
+    },
+    Extract,
+};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::{tracing::error, HashMap, HashSet};
+use std::num::NonZeroU64;
+
+/// The number of particles simulated by a single compute workgroup.
+const WORKGROUP_SIZE: u32 = 64;
+
+/// The size of a particle on the GPU: a position, an age, a velocity and a lifetime.
+const PARTICLE_SIZE: u64 = 32;
+
+/// The per-emitter data used by the simulation and rendering shaders.
+#[derive(Clone, ShaderType)]
+pub struct ParticleEmitterUniform {
+    pub transform: Mat4,
+    pub color_keys: [Vec4; MAX_GRADIENT_KEYS],
+    pub color_times: Vec4,
+    pub size_keys: Vec4,
+    pub size_times: Vec4,
+    pub speed_keys: Vec4,
+    pub speed_times: Vec4,
+    pub shape_params: Vec4,
+    pub acceleration: Vec3,
+    pub drag: f32,
+    pub lifetime_min: f32,
+    pub lifetime_max: f32,
+    pub speed_min: f32,
+    pub speed_max: f32,
+    pub color_key_count: u32,
+    pub size_key_count: u32,
+    pub speed_key_count: u32,
+    pub shape: u32,
+    pub capacity: u32,
+    pub spawn_offset: u32,
+    pub spawn_count: u32,
+    pub seed: u32,
+    pub delta_seconds: f32,
+}
+
+impl ParticleEmitterUniform {
+    pub fn new(effect: &ParticleEffect, state: &ParticleEmitterState, transform: Mat4) -> Self {
+        let (shape, shape_params) = match effect.shape {
+            EmitterShape::Point => (0, Vec4::ZERO),
+            EmitterShape::Sphere { radius } => (1, Vec4::new(radius, 0.0, 0.0, 0.0)),
+            EmitterShape::Cone { angle, radius } => (2, Vec4::new(angle, radius, 0.0, 0.0)),
+            EmitterShape::Box { half_extents } => (3, half_extents.extend(0.0)),
+        };
+        let (size_keys, size_times, size_key_count) = pack_f32_gradient(&effect.size_over_lifetime);
+        let (speed_keys, speed_times, speed_key_count) =
+            pack_f32_gradient(&effect.speed_over_lifetime);
+
+        let mut color_keys = [Vec4::ZERO; MAX_GRADIENT_KEYS];
+        let mut color_times = Vec4::ZERO;
+        let color_key_count = effect.color_over_lifetime.keys.len().min(MAX_GRADIENT_KEYS);
+        for (i, key) in effect
+            .color_over_lifetime
+            .keys
+            .iter()
+            .take(MAX_GRADIENT_KEYS)
+            .enumerate()
+        {
+            color_keys[i] = key.value.as_linear_rgba_f32().into();
+            color_times[i] = key.time;
+        }
+        if color_key_count == 0 {
+            color_keys[0] = Color::WHITE.as_linear_rgba_f32().into();
+        }
+
+        Self {
+            transform,
+            color_keys,
+            color_times,
+            size_keys,
+            size_times,
+            speed_keys,
+            speed_times,
+            shape_params,
+            acceleration: effect.acceleration,
+            drag: effect.drag,
+            lifetime_min: effect.lifetime.min,
+            lifetime_max: effect.lifetime.max,
+            speed_min: effect.initial_speed.min,
+            speed_max: effect.initial_speed.max,
+            color_key_count: color_key_count as u32,
+            size_key_count,
+            speed_key_count,
+            shape,
+            capacity: effect.capacity,
+            spawn_offset: state.spawn_offset,
+            spawn_count: state.spawn_count,
+            seed: state.seed,
+            delta_seconds: state.delta_seconds,
+        }
+    }
+}
+
+fn pack_f32_gradient(gradient: &Gradient<f32>) -> (Vec4, Vec4, u32) {
+    let mut keys = Vec4::ZERO;
+    let mut times = Vec4::ZERO;
+    for (i, key) in gradient.keys.iter().take(MAX_GRADIENT_KEYS).enumerate() {
+        keys[i] = key.value;
+        times[i] = key.time;
+    }
+    (
+        keys,
+        times,
+        gradient.keys.len().min(MAX_GRADIENT_KEYS) as u32,
+    )
+}
+
+#[derive(Component)]
+pub struct ExtractedParticleEmitter {
+    pub uniform: ParticleEmitterUniform,
+    pub texture: Handle<Image>,
+    pub mesh: Option<Handle<Mesh>>,
+    pub blend_mode: ParticleBlendMode,
+    pub translation: Vec3,
+}
+
+pub fn extract_particle_emitters(
+    mut commands: Commands,
+    mut previous_len: Local<usize>,
+    effects: Extract<Res<Assets<ParticleEffect>>>,
+    emitters: Extract<
+        Query<(
+            Entity,
+            &InheritedVisibility,
+            &Handle<ParticleEffect>,
+            &Handle<Image>,
+            Option<&ParticleMesh>,
+            &ParticleEmitterState,
+            &GlobalTransform,
+        )>,
+    >,
+) {
+    let mut values = Vec::with_capacity(*previous_len);
+    for (entity, visibility, effect, texture, mesh, state, transform) in &emitters {
+        // Hidden emitters are not simulated either, so that their particles freeze in place
+        if !visibility.get() {
+            continue;
+        }
+        let effect = match effects.get(effect) {
+            Some(effect) => effect,
+            None => continue,
+        };
+        if effect.capacity == 0 {
+            continue;
+        }
+        values.push((
+            entity,
+            ExtractedParticleEmitter {
+                uniform: ParticleEmitterUniform::new(effect, state, transform.compute_matrix()),
+                texture: texture.clone_weak(),
+                mesh: mesh.map(|mesh| mesh.0.clone_weak()),
+                blend_mode: effect.blend_mode,
+                translation: transform.translation(),
+            },
+        ));
+    }
+    *previous_len = values.len();
+    commands.insert_or_spawn_batch(values);
+}
+
+/// The particle storage buffers of every emitter, persisted across frames.
+#[derive(Resource, Default)]
+pub struct ParticleBuffers {
+    buffers: HashMap<Entity, (Buffer, u32)>,
+}
+
+#[derive(Resource, Default)]
+pub struct ParticleEmitterUniforms {
+    pub uniforms: DynamicUniformBuffer<ParticleEmitterUniform>,
+}
+
+#[derive(Component)]
+pub struct ParticleEmitterUniformOffset {
+    pub offset: u32,
+}
+
+pub fn prepare_particle_emitters(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut particle_buffers: ResMut<ParticleBuffers>,
+    mut emitter_uniforms: ResMut<ParticleEmitterUniforms>,
+    emitters: Query<(Entity, &ExtractedParticleEmitter)>,
+) {
+    emitter_uniforms.uniforms.clear();
+    let mut alive = HashSet::default();
+    for (entity, emitter) in &emitters {
+        alive.insert(entity);
+        let capacity = emitter.uniform.capacity;
+        let needs_buffer = particle_buffers
+            .buffers
+            .get(&entity)
+            .map_or(true, |(_, buffer_capacity)| *buffer_capacity != capacity);
+        if needs_buffer {
+            // New buffers are zero-initialized, which means every particle starts out dead
+            let buffer = render_device.create_buffer(&BufferDescriptor {
+                label: Some("particle_buffer"),
+                size: capacity as u64 * PARTICLE_SIZE,
+                usage: BufferUsages::STORAGE | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            });
+            particle_buffers.buffers.insert(entity, (buffer, capacity));
+        }
+
+        let offset = emitter_uniforms.uniforms.push(emitter.uniform.clone());
+        commands
+            .entity(entity)
+            .insert(ParticleEmitterUniformOffset { offset });
+    }
+    particle_buffers
+        .buffers
+        .retain(|entity, _| alive.contains(entity));
+
+    emitter_uniforms
+        .uniforms
+        .write_buffer(&render_device, &render_queue);
+}
+
+#[derive(Resource)]
+pub struct ParticlePipeline {
+    view_layout: BindGroupLayout,
+    render_layout: BindGroupLayout,
+    simulate_layout: BindGroupLayout,
+    simulate_pipeline: CachedComputePipelineId,
+}
+
+impl FromWorld for ParticlePipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let view_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: Some(ViewUniform::min_size()),
+                },
+                count: None,
+            }],
+            label: Some("particle_view_layout"),
+        });
+
+        let emitter_entry = |visibility| BindGroupLayoutEntry {
+            binding: 0,
+            visibility,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: true,
+                min_binding_size: Some(ParticleEmitterUniform::min_size()),
+            },
+            count: None,
+        };
+        let particles_entry = |visibility, read_only| BindGroupLayoutEntry {
+            binding: 1,
+            visibility,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size: NonZeroU64::new(PARTICLE_SIZE),
+            },
+            count: None,
+        };
+
+        let render_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                emitter_entry(ShaderStages::VERTEX),
+                particles_entry(ShaderStages::VERTEX, true),
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("particle_render_layout"),
+        });
+
+        let simulate_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                emitter_entry(ShaderStages::COMPUTE),
+                particles_entry(ShaderStages::COMPUTE, false),
+            ],
+            label: Some("particle_simulate_layout"),
+        });
+
+        let simulate_pipeline =
+            world
+                .resource::<PipelineCache>()
+                .queue_compute_pipeline(ComputePipelineDescriptor {
+                    label: Some("particle_simulate_pipeline".into()),
+                    layout: vec![simulate_layout.clone()],
+                    push_constant_ranges: Vec::new(),
+                    shader: PARTICLES_SIMULATE_SHADER_HANDLE.typed(),
+                    shader_defs: Vec::new(),
+                    entry_point: "simulate".into(),
+                });
+
+        ParticlePipeline {
+            view_layout,
+            render_layout,
+            simulate_layout,
+            simulate_pipeline,
+        }
+    }
+}
+
+bitflags::bitflags! {
+    #[repr(transparent)]
+    pub struct ParticlePipelineKey: u32 {
+        const NONE                   = 0;
+        const HDR                    = (1 << 0);
+        const ADDITIVE               = (1 << 1);
+        const MESH                   = (1 << 2);
+        const MSAA_RESERVED_BITS     = Self::MSAA_MASK_BITS << Self::MSAA_SHIFT_BITS;
+        const PRIMITIVE_TOPOLOGY_RESERVED_BITS = Self::PRIMITIVE_TOPOLOGY_MASK_BITS << Self::PRIMITIVE_TOPOLOGY_SHIFT_BITS;
+    }
+}
+
+impl ParticlePipelineKey {
+    const MSAA_MASK_BITS: u32 = 0b111;
+    const MSAA_SHIFT_BITS: u32 = 32 - Self::MSAA_MASK_BITS.count_ones();
+    const PRIMITIVE_TOPOLOGY_MASK_BITS: u32 = 0b111;
+    const PRIMITIVE_TOPOLOGY_SHIFT_BITS: u32 =
+        Self::MSAA_SHIFT_BITS - Self::PRIMITIVE_TOPOLOGY_MASK_BITS.count_ones();
+
+    #[inline]
+    pub const fn from_msaa_samples(msaa_samples: u32) -> Self {
+        let msaa_bits =
+            (msaa_samples.trailing_zeros() & Self::MSAA_MASK_BITS) << Self::MSAA_SHIFT_BITS;
+        Self::from_bits_truncate(msaa_bits)
+    }
+
+    #[inline]
+    pub const fn msaa_samples(&self) -> u32 {
+        1 << ((self.bits >> Self::MSAA_SHIFT_BITS) & Self::MSAA_MASK_BITS)
+    }
+
+    #[inline]
+    pub const fn from_primitive_topology(primitive_topology: PrimitiveTopology) -> Self {
+        let primitive_topology_bits = ((primitive_topology as u32)
+            & Self::PRIMITIVE_TOPOLOGY_MASK_BITS)
+            << Self::PRIMITIVE_TOPOLOGY_SHIFT_BITS;
+        Self::from_bits_truncate(primitive_topology_bits)
+    }
+
+    #[inline]
+    pub const fn primitive_topology(&self) -> PrimitiveTopology {
+        let primitive_topology_bits =
+            (self.bits >> Self::PRIMITIVE_TOPOLOGY_SHIFT_BITS) & Self::PRIMITIVE_TOPOLOGY_MASK_BITS;
+        match primitive_topology_bits {
+            x if x == PrimitiveTopology::PointList as u32 => PrimitiveTopology::PointList,
+            x if x == PrimitiveTopology::LineList as u32 => PrimitiveTopology::LineList,
+            x if x == PrimitiveTopology::LineStrip as u32 => PrimitiveTopology::LineStrip,
+            x if x == PrimitiveTopology::TriangleStrip as u32 => PrimitiveTopology::TriangleStrip,
+            _ => PrimitiveTopology::TriangleList,
+        }
+    }
+
+    #[inline]
+    pub const fn from_hdr(hdr: bool) -> Self {
+        if hdr {
+            ParticlePipelineKey::HDR
+        } else {
+            ParticlePipelineKey::NONE
+        }
+    }
+
+    #[inline]
+    pub const fn from_blend_mode(blend_mode: ParticleBlendMode) -> Self {
+        match blend_mode {
+            ParticleBlendMode::Alpha => ParticlePipelineKey::NONE,
+            ParticleBlendMode::Additive => ParticlePipelineKey::ADDITIVE,
+        }
+    }
+}
+
+impl ParticlePipeline {
+    fn descriptor(
+        &self,
+        key: ParticlePipelineKey,
+        buffers: Vec<VertexBufferLayout>,
+        shader_defs: Vec<ShaderDefVal>,
+    ) -> RenderPipelineDescriptor {
+        let format = match key.contains(ParticlePipelineKey::HDR) {
+            true => ViewTarget::TEXTURE_FORMAT_HDR,
+            false => TextureFormat::bevy_default(),
+        };
+        let blend = if key.contains(ParticlePipelineKey::ADDITIVE) {
+            BlendState {
+                color: BlendComponent {
+                    src_factor: BlendFactor::SrcAlpha,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+                alpha: BlendComponent {
+                    src_factor: BlendFactor::Zero,
+                    dst_factor: BlendFactor::One,
+                    operation: BlendOperation::Add,
+                },
+            }
+        } else {
+            BlendState::ALPHA_BLENDING
+        };
+
+        RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: PARTICLES_SHADER_HANDLE.typed::<Shader>(),
+                entry_point: "vertex".into(),
+                shader_defs: shader_defs.clone(),
+                buffers,
+            },
+            fragment: Some(FragmentState {
+                shader: PARTICLES_SHADER_HANDLE.typed::<Shader>(),
+                shader_defs,
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: Some(blend),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            layout: vec![self.view_layout.clone(), self.render_layout.clone()],
+            primitive: PrimitiveState {
+                front_face: FrontFace::Ccw,
+                // Quads always face the camera, but the back faces of meshes are hidden
+                cull_mode: key
+                    .contains(ParticlePipelineKey::MESH)
+                    .then_some(Face::Back),
+                unclipped_depth: false,
+                polygon_mode: PolygonMode::Fill,
+                conservative: false,
+                topology: key.primitive_topology(),
+                strip_index_format: None,
+            },
+            // Particles are depth tested against the scene, but don't write depth so that
+            // overlapping particles blend with each other
+            depth_stencil: Some(DepthStencilState {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: CompareFunction::GreaterEqual,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: MultisampleState {
+                count: key.msaa_samples(),
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            label: Some("particle_pipeline".into()),
+            push_constant_ranges: Vec::new(),
+        }
+    }
+}
+
+impl SpecializedRenderPipeline for ParticlePipeline {
+    type Key = ParticlePipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        // Quad corners are computed from the vertex index, without any vertex buffer
+        self.descriptor(key, Vec::new(), Vec::new())
+    }
+}
+
+impl SpecializedMeshPipeline for ParticlePipeline {
+    type Key = ParticlePipelineKey;
+
+    fn specialize(
+        &self,
+        key: Self::Key,
+        layout: &MeshVertexBufferLayout,
+    ) -> Result<RenderPipelineDescriptor, SpecializedMeshPipelineError> {
+        let mut shader_defs = vec!["MESH".into()];
+        let mut vertex_attributes = vec![Mesh::ATTRIBUTE_POSITION.at_shader_location(0)];
+        if layout.contains(Mesh::ATTRIBUTE_UV_0) {
+            shader_defs.push("VERTEX_UVS".into());
+            vertex_attributes.push(Mesh::ATTRIBUTE_UV_0.at_shader_location(1));
+        }
+        let vertex_buffer_layout = layout.get_layout(&vertex_attributes)?;
+        Ok(self.descriptor(key, vec![vertex_buffer_layout], shader_defs))
+    }
+}
+
+#[derive(Component)]
+pub struct ParticleBindGroups {
+    simulate: BindGroup,
+    render: BindGroup,
+    capacity: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct ParticleViewBindGroup {
+    value: Option<BindGroup>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn queue_particle_emitters(
+    mut commands: Commands,
+    draw_functions: Res<DrawFunctions<Transparent3d>>,
+    render_device: Res<RenderDevice>,
+    particle_pipeline: Res<ParticlePipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<ParticlePipeline>>,
+    mut mesh_pipelines: ResMut<SpecializedMeshPipelines<ParticlePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    particle_buffers: Res<ParticleBuffers>,
+    emitter_uniforms: Res<ParticleEmitterUniforms>,
+    view_uniforms: Res<ViewUniforms>,
+    mut view_bind_group: ResMut<ParticleViewBindGroup>,
+    gpu_images: Res<RenderAssets<Image>>,
+    render_meshes: Res<RenderAssets<Mesh>>,
+    emitters: Query<(Entity, &ExtractedParticleEmitter)>,
+    mut views: Query<(
+        &ExtractedView,
+        &Msaa,
+        &VisibleEntities,
+        &mut RenderPhase<Transparent3d>,
+    )>,
+) {
+    let (view_binding, emitter_binding) = match (
+        view_uniforms.uniforms.binding(),
+        emitter_uniforms.uniforms.binding(),
+    ) {
+        (Some(view_binding), Some(emitter_binding)) => (view_binding, emitter_binding),
+        _ => return,
+    };
+
+    view_bind_group.value = Some(render_device.create_bind_group(&BindGroupDescriptor {
+        entries: &[BindGroupEntry {
+            binding: 0,
+            resource: view_binding,
+        }],
+        label: Some("particle_view_bind_group"),
+        layout: &particle_pipeline.view_layout,
+    }));
+
+    for (entity, emitter) in &emitters {
+        let (buffer, capacity, gpu_image) = match (
+            particle_buffers.buffers.get(&entity),
+            gpu_images.get(&emitter.texture),
+        ) {
+            (Some((buffer, capacity)), Some(gpu_image)) => (buffer, capacity, gpu_image),
+            // Skip this emitter if its texture is not ready
+            _ => continue,
+        };
+        let simulate = render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: emitter_binding.clone(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: buffer.as_entire_binding(),
+                },
+            ],
+            label: Some("particle_simulate_bind_group"),
+            layout: &particle_pipeline.simulate_layout,
+        });
+        let render = render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: emitter_binding.clone(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: buffer.as_entire_binding(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(&gpu_image.texture_view),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindingResource::Sampler(&gpu_image.sampler),
+                },
+            ],
+            label: Some("particle_render_bind_group"),
+            layout: &particle_pipeline.render_layout,
+        });
+        commands.entity(entity).insert(ParticleBindGroups {
+            simulate,
+            render,
+            capacity: *capacity,
+        });
+    }
+
+    let draw_particles = draw_functions.read().id::<DrawParticles>();
+    for (view, msaa, visible_entities, mut transparent_phase) in &mut views {
+        let view_key = ParticlePipelineKey::from_msaa_samples(msaa.samples())
+            | ParticlePipelineKey::from_hdr(view.hdr);
+        let rangefinder = view.rangefinder3d();
+        for visible_entity in &visible_entities.entities {
+            let (entity, emitter) = match emitters.get(*visible_entity) {
+                Ok(emitter) => emitter,
+                Err(_) => continue,
+            };
+            let key = view_key | ParticlePipelineKey::from_blend_mode(emitter.blend_mode);
+            let pipeline = match &emitter.mesh {
+                Some(mesh) => {
+                    // Skip this emitter if its mesh is not ready
+                    let gpu_mesh = match render_meshes.get(mesh) {
+                        Some(gpu_mesh) => gpu_mesh,
+                        None => continue,
+                    };
+                    let key = key
+                        | ParticlePipelineKey::MESH
+                        | ParticlePipelineKey::from_primitive_topology(gpu_mesh.primitive_topology);
+                    match mesh_pipelines.specialize(
+                        &pipeline_cache,
+                        &particle_pipeline,
+                        key,
+                        &gpu_mesh.layout,
+                    ) {
+                        Ok(pipeline) => pipeline,
+                        Err(err) => {
+                            error!("{}", err);
+                            continue;
+                        }
+                    }
+                }
+                None => {
+                    let key = key
+                        | ParticlePipelineKey::from_primitive_topology(
+                            PrimitiveTopology::TriangleList,
+                        );
+                    pipelines.specialize(&pipeline_cache, &particle_pipeline, key)
+                }
+            };
+            transparent_phase.add(Transparent3d {
+                entity,
+                pipeline,
+                draw_function: draw_particles,
+                distance: rangefinder.distance(&Mat4::from_translation(emitter.translation)),
+            });
+        }
+    }
+}
+
+/// Simulates the particles of every emitter before any camera is rendered.
+pub struct ParticleSimulationNode {
+    emitters: QueryState<(
+        &'static ParticleBindGroups,
+        &'static ParticleEmitterUniformOffset,
+    )>,
+}
+
+impl ParticleSimulationNode {
+    pub const NAME: &str = "particle_simulation";
+
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            emitters: QueryState::new(world),
+        }
+    }
+}
+
+impl Node for ParticleSimulationNode {
+    fn update(&mut self, world: &mut World) {
+        self.emitters.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let particle_pipeline = world.resource::<ParticlePipeline>();
+        let simulate_pipeline =
+            match pipeline_cache.get_compute_pipeline(particle_pipeline.simulate_pipeline) {
+                Some(pipeline) => pipeline,
+                None => return Ok(()),
+            };
+
+        let mut pass =
+            render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("particle_simulation_pass"),
+                });
+        pass.set_pipeline(simulate_pipeline);
+        for (bind_groups, uniform_offset) in self.emitters.iter_manual(world) {
+            pass.set_bind_group(0, &bind_groups.simulate, &[uniform_offset.offset]);
+            let workgroups = (bind_groups.capacity + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+            pass.dispatch_workgroups(workgroups, 1, 1);
+        }
+        Ok(())
+    }
+}
+
+pub type DrawParticles = (
+    SetItemPipeline,
+    SetParticleViewBindGroup<0>,
+    DrawParticleEmitter<1>,
+);
+
+pub struct SetParticleViewBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetParticleViewBindGroup<I> {
+    type Param = SRes<ParticleViewBindGroup>;
+    type ViewWorldQuery = Read<ViewUniformOffset>;
+    type ItemWorldQuery = ();
+
+    fn render<'w>(
+        _item: &P,
+        view_uniform: &'_ ViewUniformOffset,
+        _entity: (),
+        view_bind_group: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        match view_bind_group.into_inner().value.as_ref() {
+            Some(bind_group) => {
+                pass.set_bind_group(I, bind_group, &[view_uniform.offset]);
+                RenderCommandResult::Success
+            }
+            None => RenderCommandResult::Failure,
+        }
+    }
+}
+
+pub struct DrawParticleEmitter<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for DrawParticleEmitter<I> {
+    type Param = SRes<RenderAssets<Mesh>>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = (
+        Read<ExtractedParticleEmitter>,
+        Option<Read<ParticleBindGroups>>,
+        Read<ParticleEmitterUniformOffset>,
+    );
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        (emitter, bind_groups, uniform_offset): (
+            &'w ExtractedParticleEmitter,
+            Option<&'w ParticleBindGroups>,
+            &'w ParticleEmitterUniformOffset,
+        ),
+        meshes: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let bind_groups = match bind_groups {
+            Some(bind_groups) => bind_groups,
+            None => return RenderCommandResult::Failure,
+        };
+        pass.set_bind_group(I, &bind_groups.render, &[uniform_offset.offset]);
+        let instances = 0..bind_groups.capacity;
+        let mesh = match &emitter.mesh {
+            Some(mesh) => mesh,
+            None => {
+                // Each particle is a quad made of two triangles
+                pass.draw(0..6, instances);
+                return RenderCommandResult::Success;
+            }
+        };
+        let gpu_mesh = match meshes.into_inner().get(mesh) {
+            Some(gpu_mesh) => gpu_mesh,
+            None => return RenderCommandResult::Failure,
+        };
+        // Each particle is an instance of the mesh
+        pass.set_vertex_buffer(0, gpu_mesh.vertex_buffer.slice(..));
+        match &gpu_mesh.buffer_info {
+            GpuBufferInfo::Indexed {
+                buffer,
+                index_format,
+                count,
+            } => {
+                pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(0..*count, 0, instances);
+            }
+            GpuBufferInfo::NonIndexed { vertex_count } => {
+                pass.draw(0..*vertex_count, instances);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pipeline_key_packing() {
+        let topologies = [
+            PrimitiveTopology::PointList,
+            PrimitiveTopology::LineList,
+            PrimitiveTopology::LineStrip,
+            PrimitiveTopology::TriangleList,
+            PrimitiveTopology::TriangleStrip,
+        ];
+        for msaa_samples in [1, 4] {
+            for topology in topologies {
+                let key = ParticlePipelineKey::from_msaa_samples(msaa_samples)
+                    | ParticlePipelineKey::from_primitive_topology(topology)
+                    | ParticlePipelineKey::MESH
+                    | ParticlePipelineKey::ADDITIVE;
+                assert_eq!(key.msaa_samples(), msaa_samples, "{topology:?}");
+                assert_eq!(key.primitive_topology(), topology, "{msaa_samples} samples");
+                assert!(key.contains(ParticlePipelineKey::MESH | ParticlePipelineKey::ADDITIVE));
+                assert!(!key.contains(ParticlePipelineKey::HDR));
+            }
+        }
+    }
+}
@@ -0,0 +1,97 @@
+#import bevy_render::view
+#import bevy_particles::types
+
+@group(0) @binding(0)
+var<uniform> view: View;
+
+@group(1) @binding(0)
+var<uniform> emitter: ParticleEmitter;
+@group(1) @binding(1)
+var<storage, read> particles: array<Particle>;
+@group(1) @binding(2)
+var particle_texture: texture_2d<f32>;
+@group(1) @binding(3)
+var particle_sampler: sampler;
+
+struct Vertex {
+    @builtin(instance_index) instance_index: u32,
+#ifdef MESH
+    @location(0) position: vec3<f32>,
+#ifdef VERTEX_UVS
+    @location(1) uv: vec2<f32>,
+#endif
+#else
+    @builtin(vertex_index) vertex_index: u32,
+#endif
+};
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+};
+
+fn sample_color(t: f32) -> vec4<f32> {
+    let count = emitter.color_key_count;
+    if count == 0u || t <= emitter.color_times[0] {
+        return emitter.color_keys[0];
+    }
+    for (var i = 1u; i < count; i += 1u) {
+        if t <= emitter.color_times[i] {
+            let span = emitter.color_times[i] - emitter.color_times[i - 1u];
+            let factor = select((t - emitter.color_times[i - 1u]) / span, 1.0, span <= 0.0);
+            return mix(emitter.color_keys[i - 1u], emitter.color_keys[i], factor);
+        }
+    }
+    return emitter.color_keys[count - 1u];
+}
+
+@vertex
+fn vertex(vertex: Vertex) -> VertexOutput {
+    var out: VertexOutput;
+    let particle = particles[vertex.instance_index];
+    if particle.age >= particle.lifetime {
+        // Dead particles collapse into degenerate triangles
+        out.position = vec4<f32>(0.0);
+        out.uv = vec2<f32>(0.0);
+        out.color = vec4<f32>(0.0);
+        return out;
+    }
+
+    let t = particle.age / particle.lifetime;
+    let size = sample_gradient_f32(emitter.size_keys, emitter.size_times, emitter.size_key_count, t);
+#ifdef MESH
+    // Mesh instances keep their orientation and are scaled by the particle size
+    let world_position = particle.position + vertex.position * size;
+#ifdef VERTEX_UVS
+    out.uv = vertex.uv;
+#else
+    out.uv = vec2<f32>(0.5);
+#endif
+#else
+    var corners = array<vec2<f32>, 6>(
+        vec2<f32>(-0.5, -0.5),
+        vec2<f32>(0.5, -0.5),
+        vec2<f32>(0.5, 0.5),
+        vec2<f32>(-0.5, -0.5),
+        vec2<f32>(0.5, 0.5),
+        vec2<f32>(-0.5, 0.5),
+    );
+    let corner = corners[vertex.vertex_index];
+
+    // Billboard the quad using the camera right and up vectors
+    let right = view.view[0].xyz;
+    let up = view.view[1].xyz;
+    let world_position = particle.position + (right * corner.x + up * corner.y) * size;
+    out.uv = vec2<f32>(corner.x + 0.5, 0.5 - corner.y);
+#endif
+
+    out.position = view.view_proj * vec4<f32>(world_position, 1.0);
+    out.color = sample_color(t);
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(particle_texture, particle_sampler, in.uv) * in.color;
+}
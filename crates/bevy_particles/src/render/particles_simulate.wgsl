@@ -0,0 +1,94 @@
+#import bevy_particles::types
+
+@group(0) @binding(0)
+var<uniform> emitter: ParticleEmitter;
+@group(0) @binding(1)
+var<storage, read_write> particles: array<Particle>;
+
+const TAU: f32 = 6.28318530718;
+
+// From https://www.pcg-random.org/
+fn pcg_hash(input: u32) -> u32 {
+    let state = input * 747796405u + 2891336453u;
+    let word = ((state >> ((state >> 28u) + 4u)) ^ state) * 277803737u;
+    return (word >> 22u) ^ word;
+}
+
+fn rand(state: ptr<function, u32>) -> f32 {
+    *state = pcg_hash(*state);
+    return f32(*state) / 4294967295.0;
+}
+
+fn random_unit_vector(state: ptr<function, u32>) -> vec3<f32> {
+    let z = rand(state) * 2.0 - 1.0;
+    let phi = rand(state) * TAU;
+    let r = sqrt(max(1.0 - z * z, 0.0));
+    return vec3<f32>(r * cos(phi), r * sin(phi), z);
+}
+
+fn spawn_particle(index: u32) -> Particle {
+    var state = pcg_hash(index ^ emitter.seed);
+
+    var local_position = vec3<f32>(0.0);
+    var local_direction = random_unit_vector(&state);
+    switch emitter.shape {
+        // Sphere
+        case 1u: {
+            let radius = emitter.shape_params.x * pow(rand(&state), 1.0 / 3.0);
+            local_position = local_direction * radius;
+        }
+        // Cone
+        case 2u: {
+            let theta = rand(&state) * TAU;
+            let radius = emitter.shape_params.y * sqrt(rand(&state));
+            local_position = vec3<f32>(cos(theta) * radius, 0.0, sin(theta) * radius);
+            let cos_angle = mix(cos(emitter.shape_params.x), 1.0, rand(&state));
+            let sin_angle = sqrt(max(1.0 - cos_angle * cos_angle, 0.0));
+            let phi = rand(&state) * TAU;
+            local_direction = vec3<f32>(cos(phi) * sin_angle, cos_angle, sin(phi) * sin_angle);
+        }
+        // Box
+        case 3u: {
+            let unit = vec3<f32>(rand(&state), rand(&state), rand(&state)) * 2.0 - 1.0;
+            local_position = unit * emitter.shape_params.xyz;
+            local_direction = vec3<f32>(0.0, 1.0, 0.0);
+        }
+        default: {}
+    }
+
+    var particle: Particle;
+    particle.position = (emitter.transform * vec4<f32>(local_position, 1.0)).xyz;
+    let direction = (emitter.transform * vec4<f32>(local_direction, 0.0)).xyz;
+    let speed = mix(emitter.speed_min, emitter.speed_max, rand(&state));
+    particle.velocity = normalize(direction) * speed;
+    particle.age = 0.0;
+    particle.lifetime = mix(emitter.lifetime_min, emitter.lifetime_max, rand(&state));
+    return particle;
+}
+
+@compute @workgroup_size(64, 1, 1)
+fn simulate(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let index = invocation_id.x;
+    if index >= emitter.capacity {
+        return;
+    }
+
+    // Slots are spawned in a ring: this frame spawns `spawn_count` slots from `spawn_offset`
+    let slot = (index + emitter.capacity - emitter.spawn_offset) % emitter.capacity;
+    if slot < emitter.spawn_count {
+        particles[index] = spawn_particle(index);
+        return;
+    }
+
+    var particle = particles[index];
+    if particle.age >= particle.lifetime {
+        return;
+    }
+    let dt = emitter.delta_seconds;
+    let t = particle.age / particle.lifetime;
+    let speed = sample_gradient_f32(emitter.speed_keys, emitter.speed_times, emitter.speed_key_count, t);
+    particle.velocity = (particle.velocity + emitter.acceleration * dt) * max(1.0 - emitter.drag * dt, 0.0);
+    particle.position += particle.velocity * speed * dt;
+    particle.age += dt;
+    particles[index] = particle;
+}
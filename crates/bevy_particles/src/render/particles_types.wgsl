@@ -0,0 +1,51 @@
+#define_import_path bevy_particles::types
+
+struct Particle {
+    position: vec3<f32>,
+    // The time since the particle was spawned, in seconds
+    age: f32,
+    velocity: vec3<f32>,
+    // A particle is dead once its age reaches its lifetime
+    lifetime: f32,
+};
+
+struct ParticleEmitter {
+    transform: mat4x4<f32>,
+    color_keys: array<vec4<f32>, 4>,
+    color_times: vec4<f32>,
+    size_keys: vec4<f32>,
+    size_times: vec4<f32>,
+    speed_keys: vec4<f32>,
+    speed_times: vec4<f32>,
+    shape_params: vec4<f32>,
+    acceleration: vec3<f32>,
+    drag: f32,
+    lifetime_min: f32,
+    lifetime_max: f32,
+    speed_min: f32,
+    speed_max: f32,
+    color_key_count: u32,
+    size_key_count: u32,
+    speed_key_count: u32,
+    // 0: point, 1: sphere, 2: cone, 3: box
+    shape: u32,
+    capacity: u32,
+    spawn_offset: u32,
+    spawn_count: u32,
+    seed: u32,
+    delta_seconds: f32,
+};
+
+fn sample_gradient_f32(keys: vec4<f32>, times: vec4<f32>, count: u32, t: f32) -> f32 {
+    if count == 0u || t <= times[0] {
+        return keys[0];
+    }
+    for (var i = 1u; i < count; i += 1u) {
+        if t <= times[i] {
+            let span = times[i] - times[i - 1u];
+            let factor = select((t - times[i - 1u]) / span, 1.0, span <= 0.0);
+            return mix(keys[i - 1u], keys[i], factor);
+        }
+    }
+    return keys[count - 1u];
+}
@@ -0,0 +1,152 @@
+#import bevy_pbr::mesh_view_bindings
+#import bevy_pbr::mesh_bindings
+
+#import bevy_pbr::pbr_types
+#import bevy_pbr::utils
+#import bevy_pbr::clustered_forward
+#import bevy_pbr::lighting
+#import bevy_pbr::shadows
+#import bevy_pbr::fog
+#import bevy_pbr::pbr_functions
+#import bevy_pbr::pbr_ambient
+#import bevy_pbr::prepass_utils
+
+struct DecalMaterial {
+    base_color: vec4<f32>,
+    emissive: vec4<f32>,
+    perceptual_roughness: f32,
+    metallic: f32,
+    reflectance: f32,
+    normal_map_strength: f32,
+    angle_fade: f32,
+    // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+    flags: u32,
+};
+
+// NOTE: These must match the bit flags in bevy_pbr/src/decal/mod.rs!
+const DECAL_MATERIAL_FLAGS_BASE_COLOR_TEXTURE_BIT: u32 = 1u;
+const DECAL_MATERIAL_FLAGS_NORMAL_MAP_TEXTURE_BIT: u32 = 2u;
+const DECAL_MATERIAL_FLAGS_FLIP_NORMAL_MAP_Y_BIT: u32  = 4u;
+const DECAL_MATERIAL_FLAGS_UNLIT_BIT: u32              = 8u;
+const DECAL_MATERIAL_FLAGS_FOG_ENABLED_BIT: u32        = 16u;
+
+@group(1) @binding(0)
+var<uniform> material: DecalMaterial;
+@group(1) @binding(1)
+var base_color_texture: texture_2d<f32>;
+@group(1) @binding(2)
+var base_color_sampler: sampler;
+@group(1) @binding(3)
+var normal_map_texture: texture_2d<f32>;
+@group(1) @binding(4)
+var normal_map_sampler: sampler;
+
+struct FragmentInput {
+    @builtin(position) frag_coord: vec4<f32>,
+    #import bevy_pbr::mesh_vertex_output
+};
+
+@fragment
+fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
+    // Reconstruct the position of the opaque surface covered by this fragment from the depth prepass
+    let depth = prepass_depth(in.frag_coord, 0u);
+    let viewport_uv = (in.frag_coord.xy - view.viewport.xy) / view.viewport.zw;
+    let ndc = vec4<f32>(viewport_uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), depth, 1.0);
+    let world_position_w = view.inverse_view_proj * ndc;
+    let world_position = world_position_w.xyz / world_position_w.w;
+
+    // The decal covers the unit cube centered on its origin
+    let local_position = (transpose(mesh.inverse_transpose_model) * vec4<f32>(world_position, 1.0)).xyz;
+    // The decal is projected along its local negative Y axis
+    let uv = local_position.xz + vec2<f32>(0.5);
+
+    // NOTE: Derivatives must be computed before any fragment is discarded
+    let world_position_dx = dpdx(world_position);
+    let world_position_dy = dpdy(world_position);
+    let uv_dx = dpdx(uv);
+    let uv_dy = dpdy(uv);
+
+    if any(abs(local_position) > vec3<f32>(0.5)) {
+        discard;
+    }
+
+    let is_orthographic = view.projection[3].w == 1.0;
+    let V = calculate_view(vec4<f32>(world_position, 1.0), is_orthographic);
+
+    // The surface normal is reconstructed from the depth, so a normal prepass isn't required
+    var surface_normal = normalize(cross(world_position_dy, world_position_dx));
+    if dot(surface_normal, V) < 0.0 {
+        surface_normal = -surface_normal;
+    }
+
+    var output_color = material.base_color;
+    if (material.flags & DECAL_MATERIAL_FLAGS_BASE_COLOR_TEXTURE_BIT) != 0u {
+        output_color = output_color * textureSampleGrad(base_color_texture, base_color_sampler, uv, uv_dx, uv_dy);
+    }
+
+    // Fade the decal out on surfaces that don't face its projection direction
+    let decal_y = normalize(mesh.model[1].xyz);
+    output_color.a = output_color.a * saturate(dot(surface_normal, decal_y) / max(material.angle_fade, 0.0001));
+
+    if (material.flags & DECAL_MATERIAL_FLAGS_UNLIT_BIT) == 0u {
+        // Build a tangent frame on the surface from the decal X axis
+        let decal_x = mesh.model[0].xyz;
+        var T = decal_x - surface_normal * dot(surface_normal, decal_x);
+        if dot(T, T) < 0.000001 {
+            T = cross(surface_normal, normalize(mesh.model[2].xyz));
+        }
+        T = normalize(T);
+        let B = cross(surface_normal, T);
+
+        var N = surface_normal;
+        if (material.flags & DECAL_MATERIAL_FLAGS_NORMAL_MAP_TEXTURE_BIT) != 0u {
+            var Nt = textureSampleGrad(normal_map_texture, normal_map_sampler, uv, uv_dx, uv_dy).rgb * 2.0 - 1.0;
+            // Normal maps authored for DirectX require flipping the y component
+            if (material.flags & DECAL_MATERIAL_FLAGS_FLIP_NORMAL_MAP_Y_BIT) != 0u {
+                Nt.y = -Nt.y;
+            }
+            Nt = mix(vec3<f32>(0.0, 0.0, 1.0), Nt, material.normal_map_strength);
+            N = normalize(Nt.x * T + Nt.y * B + Nt.z * surface_normal);
+        }
+
+        var pbr_input: PbrInput = pbr_input_new();
+        pbr_input.material.base_color = output_color;
+        pbr_input.material.emissive = material.emissive;
+        pbr_input.material.perceptual_roughness = material.perceptual_roughness;
+        pbr_input.material.metallic = material.metallic;
+        pbr_input.material.reflectance = material.reflectance;
+        pbr_input.material.flags = STANDARD_MATERIAL_FLAGS_ALPHA_MODE_BLEND;
+
+        pbr_input.frag_coord = in.frag_coord;
+        pbr_input.world_position = vec4<f32>(world_position, 1.0);
+        pbr_input.world_normal = surface_normal;
+        pbr_input.is_orthographic = is_orthographic;
+        pbr_input.N = N;
+        pbr_input.V = V;
+        pbr_input.flags = mesh.flags;
+
+        output_color = pbr(pbr_input);
+    }
+
+    // fog
+    if (fog.mode != FOG_MODE_OFF && (material.flags & DECAL_MATERIAL_FLAGS_FOG_ENABLED_BIT) != 0u) {
+        output_color = apply_fog(output_color, world_position, view.world_position.xyz);
+    }
+
+#ifdef TONEMAP_IN_SHADER
+    output_color = tone_mapping(output_color);
+#endif
+#ifdef DEBAND_DITHER
+    var output_rgb = output_color.rgb;
+    output_rgb = powsafe(output_rgb, 1.0 / 2.2);
+    output_rgb = output_rgb + screen_space_dither(in.frag_coord.xy);
+    // This conversion back to linear space is required because our output texture format is
+    // SRGB; the GPU will assume our output is linear and will apply an SRGB conversion.
+    output_rgb = powsafe(output_rgb, 2.2);
+    output_color = vec4(output_rgb, output_color.a);
+#endif
+#ifdef PREMULTIPLY_ALPHA
+    output_color = premultiply_alpha(STANDARD_MATERIAL_FLAGS_ALPHA_MODE_BLEND, output_color);
+#endif
+    return output_color;
+}
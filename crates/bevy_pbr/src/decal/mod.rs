@@ -0,0 +1,281 @@
+use crate::{
+    AlphaMode, Material, MaterialPipeline, MaterialPipelineKey, MaterialPlugin, NotShadowCaster,
+};
+use bevy_app::prelude::*;
+use bevy_asset::{load_internal_asset, AddAsset, Assets, Handle, HandleUntyped};
+use bevy_ecs::prelude::*;
+use bevy_math::{Vec3, Vec4};
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect, TypeUuid};
+use bevy_render::{
+    color::Color,
+    mesh::{shape, Mesh, MeshVertexBufferLayout},
+    primitives::Aabb,
+    render_asset::RenderAssets,
+    render_resource::*,
+    texture::Image,
+    view::{InheritedVisibility, ViewVisibility, Visibility},
+};
+use bevy_transform::components::{GlobalTransform, Transform};
+
+pub const DECAL_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3825347970315954749);
+
+/// The unit cube used as the mesh of every [`Decal`].
+pub const DECAL_MESH_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Mesh::TYPE_UUID, 9156304600896190062);
+
+/// Adds support for rendering [`Decal`]s.
+pub struct DecalPlugin;
+
+impl Plugin for DecalPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, DECAL_SHADER_HANDLE, "decal.wgsl", Shader::from_wgsl);
+
+        app.world
+            .resource_mut::<Assets<Mesh>>()
+            .set_untracked(DECAL_MESH_HANDLE, Mesh::from(shape::Cube { size: 1.0 }));
+
+        app.register_asset_reflect::<DecalMaterial>()
+            .register_type::<Decal>()
+            .add_plugin(MaterialPlugin::<DecalMaterial> {
+                // Decals are projected onto the depth prepass, so they must not be part of it
+                prepass_enabled: false,
+                ..Default::default()
+            })
+            .add_system(add_decal_meshes.in_base_set(CoreSet::PostUpdate));
+    }
+}
+
+/// A box decal, projecting its [`DecalMaterial`] onto the surfaces it encloses.
+///
+/// The decal covers the unit cube centered on its [`Transform`], which can be scaled to change
+/// its size, and is projected along its local negative Y axis. It is drawn on top of the opaque
+/// surfaces written to the depth prepass, so cameras rendering decals need a
+/// [`DepthPrepass`](bevy_core_pipeline::prepass::DepthPrepass). Transparent meshes,
+/// and meshes of materials with the prepass disabled, are not affected by decals.
+///
+/// Entities with a [`Decal`] are given the decal mesh and a [`NotShadowCaster`] automatically.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+pub struct Decal;
+
+/// Gives the decal mesh to entities with a [`Decal`] but no [`Mesh`].
+pub fn add_decal_meshes(
+    mut commands: Commands,
+    decals: Query<Entity, (With<Decal>, Without<Handle<Mesh>>)>,
+) {
+    for entity in &decals {
+        commands.entity(entity).insert((
+            DECAL_MESH_HANDLE.typed::<Mesh>(),
+            Aabb::from_min_max(Vec3::splat(-0.5), Vec3::splat(0.5)),
+            NotShadowCaster,
+        ));
+    }
+}
+
+/// A component bundle for [`Decal`] entities.
+#[derive(Bundle, Clone, Default)]
+pub struct DecalBundle {
+    pub decal: Decal,
+    pub material: Handle<DecalMaterial>,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    /// User indication of whether an entity is visible
+    pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+}
+
+/// The material of a [`Decal`].
+///
+/// Decals are alpha blended over the surfaces they are projected onto. Lit decals are shaded
+/// with their own normal, roughness and metallic values, so a wet puddle or a scratched coat
+/// of paint can differ from the surface below it.
+#[derive(AsBindGroup, Reflect, FromReflect, Debug, Clone, TypeUuid)]
+#[uuid = "c4a1d2a9-3e5f-4b8c-9a27-61f0d8e4b5c3"]
+#[uniform(0, DecalMaterialUniform)]
+#[reflect(Default, Debug)]
+pub struct DecalMaterial {
+    /// The color of the decal before lighting. Its alpha controls the opacity of the decal.
+    ///
+    /// Defaults to [`Color::WHITE`].
+    pub base_color: Color,
+
+    /// The texture projected by the decal, laid out on its local XZ plane.
+    /// The actual pre-lighting color is `base_color * this_texture`.
+    #[texture(1)]
+    #[sampler(2)]
+    pub base_color_texture: Option<Handle<Image>>,
+
+    /// Color the decal "emits" to the camera.
+    ///
+    /// Defaults to [`Color::BLACK`].
+    pub emissive: Color,
+
+    /// Linear perceptual roughness of the decal, clamped to `[0.089, 1.0]` in the shader.
+    ///
+    /// Defaults to `0.5`.
+    pub perceptual_roughness: f32,
+
+    /// How "metallic" the decal appears, within `[0.0, 1.0]`.
+    ///
+    /// Defaults to `0.0`.
+    pub metallic: f32,
+
+    /// Specular intensity for non-metals on a linear scale of `[0.0, 1.0]`.
+    ///
+    /// Defaults to `0.5`.
+    pub reflectance: f32,
+
+    /// A tangent space normal map applied on top of the surface normal.
+    ///
+    /// The tangent frame follows the local X and Z axes of the decal.
+    #[texture(3)]
+    #[sampler(4)]
+    pub normal_map_texture: Option<Handle<Image>>,
+
+    /// How much the normal map is blended with the surface normal, within `[0.0, 1.0]`.
+    ///
+    /// Defaults to `1.0`.
+    pub normal_map_strength: f32,
+
+    /// Normal map textures authored for `DirectX` have their y-component flipped. Set this to flip
+    /// it to right-handed conventions.
+    pub flip_normal_map_y: bool,
+
+    /// Surfaces fade out as their normal turns away from the local Y axis of the decal. The decal
+    /// is fully opaque while the cosine of the angle between them is above this value.
+    ///
+    /// This prevents decals from stretching over surfaces parallel to their projection direction.
+    ///
+    /// Defaults to `0.3`.
+    pub angle_fade: f32,
+
+    /// Whether to shade the decal with the lights of the scene, or only use its base color.
+    pub unlit: bool,
+
+    /// Whether the decal is affected by [`FogSettings`](crate::FogSettings).
+    ///
+    /// Defaults to `true`.
+    pub fog_enabled: bool,
+}
+
+impl Default for DecalMaterial {
+    fn default() -> Self {
+        DecalMaterial {
+            base_color: Color::WHITE,
+            base_color_texture: None,
+            emissive: Color::BLACK,
+            perceptual_roughness: 0.5,
+            metallic: 0.0,
+            reflectance: 0.5,
+            normal_map_texture: None,
+            normal_map_strength: 1.0,
+            flip_normal_map_y: false,
+            angle_fade: 0.3,
+            unlit: false,
+            fog_enabled: true,
+        }
+    }
+}
+
+impl From<Handle<Image>> for DecalMaterial {
+    fn from(texture: Handle<Image>) -> Self {
+        DecalMaterial {
+            base_color_texture: Some(texture),
+            ..Default::default()
+        }
+    }
+}
+
+// NOTE: These must match the bit flags in bevy_pbr/src/decal/decal.wgsl!
+bitflags::bitflags! {
+    #[repr(transparent)]
+    pub struct DecalMaterialFlags: u32 {
+        const BASE_COLOR_TEXTURE = (1 << 0);
+        const NORMAL_MAP_TEXTURE = (1 << 1);
+        const FLIP_NORMAL_MAP_Y  = (1 << 2);
+        const UNLIT              = (1 << 3);
+        const FOG_ENABLED        = (1 << 4);
+        const NONE               = 0;
+    }
+}
+
+/// The GPU representation of the uniform data of a [`DecalMaterial`].
+#[derive(Clone, Default, ShaderType)]
+pub struct DecalMaterialUniform {
+    pub base_color: Vec4,
+    pub emissive: Vec4,
+    pub roughness: f32,
+    pub metallic: f32,
+    pub reflectance: f32,
+    pub normal_map_strength: f32,
+    pub angle_fade: f32,
+    /// The [`DecalMaterialFlags`] accessible in the `wgsl` shader.
+    pub flags: u32,
+}
+
+impl AsBindGroupShaderType<DecalMaterialUniform> for DecalMaterial {
+    fn as_bind_group_shader_type(&self, _images: &RenderAssets<Image>) -> DecalMaterialUniform {
+        let mut flags = DecalMaterialFlags::NONE;
+        if self.base_color_texture.is_some() {
+            flags |= DecalMaterialFlags::BASE_COLOR_TEXTURE;
+        }
+        if self.normal_map_texture.is_some() {
+            flags |= DecalMaterialFlags::NORMAL_MAP_TEXTURE;
+        }
+        if self.flip_normal_map_y {
+            flags |= DecalMaterialFlags::FLIP_NORMAL_MAP_Y;
+        }
+        if self.unlit {
+            flags |= DecalMaterialFlags::UNLIT;
+        }
+        if self.fog_enabled {
+            flags |= DecalMaterialFlags::FOG_ENABLED;
+        }
+
+        DecalMaterialUniform {
+            base_color: self.base_color.as_linear_rgba_f32().into(),
+            emissive: self.emissive.into(),
+            roughness: self.perceptual_roughness,
+            metallic: self.metallic,
+            reflectance: self.reflectance,
+            normal_map_strength: self.normal_map_strength,
+            angle_fade: self.angle_fade,
+            flags: flags.bits(),
+        }
+    }
+}
+
+impl Material for DecalMaterial {
+    fn specialize(
+        _pipeline: &MaterialPipeline<Self>,
+        descriptor: &mut RenderPipelineDescriptor,
+        _layout: &MeshVertexBufferLayout,
+        _key: MaterialPipelineKey<Self>,
+    ) -> Result<(), SpecializedMeshPipelineError> {
+        // Only the back faces are drawn, without depth testing, so that decals are still
+        // visible when the camera is inside of them. Fragments outside of the decal are
+        // discarded in the shader.
+        descriptor.primitive.cull_mode = Some(Face::Front);
+        if let Some(depth_stencil) = descriptor.depth_stencil.as_mut() {
+            depth_stencil.depth_write_enabled = false;
+            depth_stencil.depth_compare = CompareFunction::Always;
+        }
+        if let Some(label) = &mut descriptor.label {
+            *label = format!("decal_{}", *label).into();
+        }
+        Ok(())
+    }
+
+    fn fragment_shader() -> ShaderRef {
+        DECAL_SHADER_HANDLE.typed().into()
+    }
+
+    #[inline]
+    fn alpha_mode(&self) -> AlphaMode {
+        AlphaMode::Blend
+    }
+}
@@ -24,6 +24,20 @@ fn environment_map_light(
     let irradiance = textureSample(environment_map_diffuse, environment_map_sampler, N).rgb;
     let radiance = textureSampleLevel(environment_map_specular, environment_map_sampler, R, radiance_level).rgb;
 
+    return environment_light(irradiance, radiance, roughness, diffuse_color, NdotV, f_ab, F0);
+}
+
+// Applies the split-sum approximation to the irradiance and radiance sampled from prefiltered
+// environment maps
+fn environment_light(
+    irradiance: vec3<f32>,
+    radiance: vec3<f32>,
+    roughness: f32,
+    diffuse_color: vec3<f32>,
+    NdotV: f32,
+    f_ab: vec2<f32>,
+    F0: vec3<f32>,
+) -> EnvironmentMapLight {
     // Multiscattering approximation: https://www.jcgt.org/published/0008/01/03/paper.pdf
     // Useful reference: https://bruop.github.io/ibl
     let Fr = max(vec3(1.0 - roughness), F0) - F0;
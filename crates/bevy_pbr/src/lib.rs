@@ -2,23 +2,37 @@ pub mod wireframe;
 
 mod alpha;
 mod bundle;
+mod decal;
 mod environment_map;
 mod fog;
 mod light;
 mod material;
 mod pbr_material;
 mod prepass;
+mod reflection_probe;
 mod render;
+mod ssr;
+mod texture_streaming;
+mod volumetric_fog;
 
 pub use alpha::*;
 pub use bundle::*;
+pub use decal::*;
 pub use environment_map::EnvironmentMapLight;
 pub use fog::*;
 pub use light::*;
 pub use material::*;
 pub use pbr_material::*;
 pub use prepass::*;
+pub use reflection_probe::{
+    ReflectionProbe, ReflectionProbeBundle, ReflectionProbeMeta, MAX_REFLECTION_PROBES,
+};
 pub use render::*;
+pub use ssr::{
+    ScreenSpaceReflectionsMeta, ScreenSpaceReflectionsSettings, ViewScreenSpaceReflections,
+};
+pub use texture_streaming::*;
+pub use volumetric_fog::{VolumetricFogMeta, VolumetricFogSettings, VolumetricLight};
 
 pub mod prelude {
     #[doc(hidden)]
@@ -28,11 +42,15 @@ pub mod prelude {
             DirectionalLightBundle, MaterialMeshBundle, PbrBundle, PointLightBundle,
             SpotLightBundle,
         },
+        decal::{Decal, DecalBundle, DecalMaterial},
         environment_map::EnvironmentMapLight,
         fog::{FogFalloff, FogSettings},
         light::{AmbientLight, DirectionalLight, PointLight, SpotLight},
         material::{Material, MaterialPlugin},
         pbr_material::StandardMaterial,
+        reflection_probe::{ReflectionProbe, ReflectionProbeBundle},
+        ssr::ScreenSpaceReflectionsSettings,
+        volumetric_fog::{VolumetricFogSettings, VolumetricLight},
     };
 }
 
@@ -40,6 +58,10 @@ pub mod draw_3d_graph {
     pub mod node {
         /// Label for the shadow pass node.
         pub const SHADOW_PASS: &str = "shadow_pass";
+        /// Label for the node keeping the previous frame for screen space reflections.
+        pub const SCREEN_SPACE_REFLECTIONS: &str = "screen_space_reflections";
+        /// Label for the volumetric fog node.
+        pub const VOLUMETRIC_FOG: &str = "volumetric_fog";
     }
 }
 
@@ -49,16 +71,21 @@ use bevy_ecs::prelude::*;
 use bevy_reflect::TypeUuid;
 use bevy_render::{
     camera::CameraUpdateSystem,
+    extract_component::ExtractComponentPlugin,
     extract_resource::ExtractResourcePlugin,
     prelude::Color,
     render_graph::RenderGraph,
     render_phase::{sort_phase_system, AddRenderCommand, DrawFunctions},
     render_resource::{Shader, SpecializedMeshPipelines},
+    texture::TextureStreaming,
     view::{ViewSet, VisibilitySystems},
     ExtractSchedule, RenderApp, RenderSet,
 };
 use bevy_transform::TransformSystem;
 use environment_map::EnvironmentMapPlugin;
+use reflection_probe::ReflectionProbePlugin;
+use ssr::ScreenSpaceReflectionsPlugin;
+use volumetric_fog::VolumetricFogPlugin;
 
 pub const PBR_TYPES_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1708015359337029744);
@@ -163,25 +190,34 @@ impl Plugin for PbrPlugin {
             .register_type::<Cascades>()
             .register_type::<CascadesVisibleEntities>()
             .register_type::<ClusterConfig>()
+            .register_type::<ClusterDebugVisualization>()
             .register_type::<ClusterFarZMode>()
             .register_type::<ClusterZConfig>()
             .register_type::<CubemapVisibleEntities>()
             .register_type::<DirectionalLight>()
             .register_type::<DirectionalLightShadowMap>()
+            .register_type::<NotShadowCaster>()
+            .register_type::<NotShadowReceiver>()
             .register_type::<PointLight>()
             .register_type::<PointLightShadowMap>()
             .register_type::<SpotLight>()
+            .register_type::<TransmittedShadowReceiver>()
             .add_plugin(MeshRenderPlugin)
             .add_plugin(MaterialPlugin::<StandardMaterial> {
                 prepass_enabled: self.prepass_enabled,
                 ..Default::default()
             })
             .add_plugin(EnvironmentMapPlugin)
+            .add_plugin(ReflectionProbePlugin)
+            .add_plugin(ScreenSpaceReflectionsPlugin)
+            .add_plugin(VolumetricFogPlugin)
+            .add_plugin(DecalPlugin)
             .init_resource::<AmbientLight>()
             .init_resource::<GlobalVisiblePointLights>()
             .init_resource::<DirectionalLightShadowMap>()
             .init_resource::<PointLightShadowMap>()
             .add_plugin(ExtractResourcePlugin::<AmbientLight>::default())
+            .add_plugin(ExtractComponentPlugin::<ClusterDebugVisualization>::default())
             .configure_sets(
                 (
                     SimulationLightSystems::AddClusters,
@@ -196,6 +232,7 @@ impl Plugin for PbrPlugin {
                     .in_base_set(CoreSet::PostUpdate),
             )
             .add_plugin(FogPlugin)
+            .add_plugin(IndirectDrawingPlugin)
             .add_system(add_clusters.in_set(SimulationLightSystems::AddClusters))
             .add_system(apply_system_buffers.in_set(SimulationLightSystems::AddClustersFlush))
             .add_system(
@@ -214,7 +251,7 @@ impl Plugin for PbrPlugin {
             .add_system(
                 update_directional_light_frusta
                     .in_set(SimulationLightSystems::UpdateLightFrusta)
-                    // This must run after CheckVisibility because it relies on ComputedVisibility::is_visible()
+                    // This must run after CheckVisibility because it relies on ViewVisibility::get()
                     .after(VisibilitySystems::CheckVisibility)
                     .after(TransformSystem::TransformPropagate)
                     .after(SimulationLightSystems::UpdateDirectionalLightCascades)
@@ -242,9 +279,19 @@ impl Plugin for PbrPlugin {
                     .after(TransformSystem::TransformPropagate)
                     .after(SimulationLightSystems::UpdateLightFrusta)
                     // NOTE: This MUST be scheduled AFTER the core renderer visibility check
-                    // because that resets entity ComputedVisibility for the first view
-                    // which would override any results from this otherwise
+                    // because both mark entities as visible in their ViewVisibility, which is
+                    // only reset once per frame before the core check runs
                     .after(VisibilitySystems::CheckVisibility),
+            )
+            .add_systems(
+                (
+                    request_standard_material_textures
+                        .run_if(resource_exists::<TextureStreaming>())
+                        .after(VisibilitySystems::CheckVisibility)
+                        .after(TransformSystem::TransformPropagate),
+                    update_standard_material_textures.run_if(resource_exists::<TextureStreaming>()),
+                )
+                    .in_base_set(CoreSet::PostUpdate),
             );
 
         app.world
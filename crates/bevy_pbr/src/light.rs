@@ -6,12 +6,13 @@ use bevy_reflect::prelude::*;
 use bevy_render::{
     camera::Camera,
     color::Color,
+    extract_component::ExtractComponent,
     extract_resource::ExtractResource,
     prelude::Projection,
     primitives::{Aabb, CascadesFrusta, CubemapFrusta, Frustum, Plane, Sphere},
     render_resource::BufferBindingType,
     renderer::RenderDevice,
-    view::{ComputedVisibility, RenderLayers, VisibleEntities},
+    view::{InheritedVisibility, RenderLayers, ViewVisibility, VisibleEntities},
 };
 use bevy_transform::{components::GlobalTransform, prelude::Transform};
 use bevy_utils::{tracing::warn, HashMap};
@@ -586,13 +587,29 @@ impl Default for AmbientLight {
 }
 
 /// Add this component to make a [`Mesh`](bevy_render::mesh::Mesh) not cast shadows.
+///
+/// The mesh is left out of the shadow maps of every light, which saves rendering them for meshes
+/// whose shadows are never seen, like huge terrains lit from above or first-person arms.
 #[derive(Component, Reflect, Default)]
 #[reflect(Component, Default)]
 pub struct NotShadowCaster;
 /// Add this component to make a [`Mesh`](bevy_render::mesh::Mesh) not receive shadows.
+///
+/// The mesh still casts shadows on others, unless it also has a [`NotShadowCaster`].
 #[derive(Component, Reflect, Default)]
 #[reflect(Component, Default)]
 pub struct NotShadowReceiver;
+/// Add this component to make a [`Mesh`](bevy_render::mesh::Mesh) receive the light transmitted
+/// through it from behind, like the light shining through foliage or paper, and the shadows on
+/// its back side.
+///
+/// The light from behind the surface is diffused through it by the PBR shader, and shadowed by
+/// sampling the shadow maps on the other side of the surface, which costs a second shadow lookup
+/// per light. Custom material shaders can read `MESH_FLAGS_TRANSMITTED_SHADOW_RECEIVER_BIT` in
+/// the flags of the mesh to do the same. It has no effect on a mesh with a [`NotShadowReceiver`].
+#[derive(Component, Reflect, Default)]
+#[reflect(Component, Default)]
+pub struct TransmittedShadowReceiver;
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum SimulationLightSystems {
@@ -759,6 +776,31 @@ impl ClusterConfig {
     }
 }
 
+/// The number of point and spot lights affecting a single cluster above which shading gets
+/// expensive.
+///
+/// Each fragment is shaded by all of the lights of its cluster, so
+/// [`assign_lights_to_clusters`] warns when a cluster exceeds this, and
+/// [`ClusterDebugVisualization::LightCount`] colors such clusters red.
+pub const MAX_LIGHTS_PER_CLUSTER: usize = 64;
+
+/// Overlays the clusters of the point and spot lights of a camera on the meshes it renders, to
+/// help tuning its [`ClusterConfig`].
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Component, Reflect, ExtractComponent)]
+#[reflect(Component, Default)]
+pub enum ClusterDebugVisualization {
+    /// No overlay.
+    #[default]
+    None,
+    /// Colors each depth slice of the clusters differently.
+    ZSlices,
+    /// Colors the clusters from green to red as the number of lights affecting them approaches
+    /// [`MAX_LIGHTS_PER_CLUSTER`].
+    LightCount,
+    /// Colors each cluster differently, to show their extent.
+    Coherency,
+}
+
 #[derive(Component, Debug, Default)]
 pub struct Clusters {
     /// Tile size
@@ -773,6 +815,21 @@ pub struct Clusters {
 }
 
 impl Clusters {
+    /// The number of clusters in `X` / `Y` / `Z` in the view frustum, as computed from the
+    /// [`ClusterConfig`] and the size of the viewport.
+    pub fn dimensions(&self) -> UVec3 {
+        self.dimensions
+    }
+
+    /// The highest number of point and spot lights affecting a single cluster.
+    pub fn max_lights_per_cluster(&self) -> usize {
+        self.lights
+            .iter()
+            .map(|lights| lights.entities.len())
+            .max()
+            .unwrap_or(0)
+    }
+
     fn update(&mut self, screen_size: UVec2, requested_dimensions: UVec3) {
         debug_assert!(
             requested_dimensions.x > 0 && requested_dimensions.y > 0 && requested_dimensions.z > 0
@@ -1166,11 +1223,12 @@ pub(crate) fn assign_lights_to_clusters(
         &mut Clusters,
         Option<&mut VisiblePointLights>,
     )>,
-    point_lights_query: Query<(Entity, &GlobalTransform, &PointLight, &ComputedVisibility)>,
-    spot_lights_query: Query<(Entity, &GlobalTransform, &SpotLight, &ComputedVisibility)>,
+    point_lights_query: Query<(Entity, &GlobalTransform, &PointLight, &ViewVisibility)>,
+    spot_lights_query: Query<(Entity, &GlobalTransform, &SpotLight, &ViewVisibility)>,
     mut lights: Local<Vec<PointLightAssignmentData>>,
     mut cluster_aabb_spheres: Local<Vec<Option<Sphere>>>,
     mut max_point_lights_warning_emitted: Local<bool>,
+    mut max_lights_per_cluster_warning_emitted: Local<bool>,
     render_device: Option<Res<RenderDevice>>,
 ) {
     let render_device = match render_device {
@@ -1184,7 +1242,7 @@ pub(crate) fn assign_lights_to_clusters(
     lights.extend(
         point_lights_query
             .iter()
-            .filter(|(.., visibility)| visibility.is_visible())
+            .filter(|(.., visibility)| visibility.get())
             .map(
                 |(entity, transform, point_light, _visibility)| PointLightAssignmentData {
                     entity,
@@ -1198,7 +1256,7 @@ pub(crate) fn assign_lights_to_clusters(
     lights.extend(
         spot_lights_query
             .iter()
-            .filter(|(.., visibility)| visibility.is_visible())
+            .filter(|(.., visibility)| visibility.get())
             .map(
                 |(entity, transform, spot_light, _visibility)| PointLightAssignmentData {
                     entity,
@@ -1721,6 +1779,21 @@ pub(crate) fn assign_lights_to_clusters(
                 ..Default::default()
             });
         }
+
+        if !*max_lights_per_cluster_warning_emitted {
+            let max_lights_per_cluster = clusters.max_lights_per_cluster();
+            if max_lights_per_cluster > MAX_LIGHTS_PER_CLUSTER {
+                warn!(
+                    "A cluster of camera {view_entity:?} is affected by {max_lights_per_cluster} \
+                    point and spot lights, more than MAX_LIGHTS_PER_CLUSTER \
+                    ({MAX_LIGHTS_PER_CLUSTER}). Consider reducing the range of the lights, or \
+                    using more clusters with a ClusterConfig. Add \
+                    ClusterDebugVisualization::LightCount to the camera to see the affected \
+                    clusters."
+                );
+                *max_lights_per_cluster_warning_emitted = true;
+            }
+        }
     }
 }
 
@@ -1781,7 +1854,7 @@ pub fn update_directional_light_frusta(
         (
             &Cascades,
             &DirectionalLight,
-            &ComputedVisibility,
+            &ViewVisibility,
             &mut CascadesFrusta,
         ),
         (
@@ -1794,7 +1867,7 @@ pub fn update_directional_light_frusta(
         // The frustum is used for culling meshes to the light for shadow mapping
         // so if shadow mapping is disabled for this light, then the frustum is
         // not needed.
-        if !directional_light.shadows_enabled || !visibility.is_visible() {
+        if !directional_light.shadows_enabled || !visibility.get() {
             continue;
         }
 
@@ -1915,14 +1988,15 @@ pub fn check_light_mesh_visibility(
             &CascadesFrusta,
             &mut CascadesVisibleEntities,
             Option<&RenderLayers>,
-            &mut ComputedVisibility,
+            &ViewVisibility,
         ),
         Without<SpotLight>,
     >,
     mut visible_entity_query: Query<
         (
             Entity,
-            &mut ComputedVisibility,
+            &InheritedVisibility,
+            &mut ViewVisibility,
             Option<&RenderLayers>,
             Option<&Aabb>,
             Option<&GlobalTransform>,
@@ -1947,13 +2021,8 @@ pub fn check_light_mesh_visibility(
     }
 
     // Directional lights
-    for (
-        directional_light,
-        frusta,
-        mut visible_entities,
-        maybe_view_mask,
-        light_computed_visibility,
-    ) in &mut directional_lights
+    for (directional_light, frusta, mut visible_entities, maybe_view_mask, light_view_visibility) in
+        &mut directional_lights
     {
         // Re-use already allocated entries where possible.
         let mut views_to_remove = Vec::new();
@@ -1979,16 +2048,22 @@ pub fn check_light_mesh_visibility(
         }
 
         // NOTE: If shadow mapping is disabled for the light then it must have no visible entities
-        if !directional_light.shadows_enabled || !light_computed_visibility.is_visible() {
+        if !directional_light.shadows_enabled || !light_view_visibility.get() {
             continue;
         }
 
         let view_mask = maybe_view_mask.copied().unwrap_or_default();
 
-        for (entity, mut computed_visibility, maybe_entity_mask, maybe_aabb, maybe_transform) in
-            &mut visible_entity_query
+        for (
+            entity,
+            inherited_visibility,
+            mut view_visibility,
+            maybe_entity_mask,
+            maybe_aabb,
+            maybe_transform,
+        ) in &mut visible_entity_query
         {
-            if !computed_visibility.is_visible_in_hierarchy() {
+            if !inherited_visibility.get() {
                 continue;
             }
 
@@ -2013,7 +2088,7 @@ pub fn check_light_mesh_visibility(
                             continue;
                         }
 
-                        computed_visibility.set_visible_in_view();
+                        view_visibility.set();
                         frustum_visible_entities.entities.push(entity);
                     }
                 }
@@ -2053,13 +2128,14 @@ pub fn check_light_mesh_visibility(
 
                 for (
                     entity,
-                    mut computed_visibility,
+                    inherited_visibility,
+                    mut view_visibility,
                     maybe_entity_mask,
                     maybe_aabb,
                     maybe_transform,
                 ) in &mut visible_entity_query
                 {
-                    if !computed_visibility.is_visible_in_hierarchy() {
+                    if !inherited_visibility.get() {
                         continue;
                     }
 
@@ -2081,12 +2157,12 @@ pub fn check_light_mesh_visibility(
                             .zip(cubemap_visible_entities.iter_mut())
                         {
                             if frustum.intersects_obb(aabb, &model_to_world, true, true) {
-                                computed_visibility.set_visible_in_view();
+                                view_visibility.set();
                                 visible_entities.entities.push(entity);
                             }
                         }
                     } else {
-                        computed_visibility.set_visible_in_view();
+                        view_visibility.set();
                         for visible_entities in cubemap_visible_entities.iter_mut() {
                             visible_entities.entities.push(entity);
                         }
@@ -2117,13 +2193,14 @@ pub fn check_light_mesh_visibility(
 
                 for (
                     entity,
-                    mut computed_visibility,
+                    inherited_visibility,
+                    mut view_visibility,
                     maybe_entity_mask,
                     maybe_aabb,
                     maybe_transform,
                 ) in visible_entity_query.iter_mut()
                 {
-                    if !computed_visibility.is_visible_in_hierarchy() {
+                    if !inherited_visibility.get() {
                         continue;
                     }
 
@@ -2141,11 +2218,11 @@ pub fn check_light_mesh_visibility(
                         }
 
                         if frustum.intersects_obb(aabb, &model_to_world, true, true) {
-                            computed_visibility.set_visible_in_view();
+                            view_visibility.set();
                             visible_entities.entities.push(entity);
                         }
                     } else {
-                        computed_visibility.set_visible_in_view();
+                        view_visibility.set();
                         visible_entities.entities.push(entity);
                     }
                 }
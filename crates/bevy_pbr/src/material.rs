@@ -1,6 +1,8 @@
 use crate::{
-    AlphaMode, DrawMesh, EnvironmentMapLight, MeshPipeline, MeshPipelineKey, MeshUniform,
-    PrepassPlugin, SetMeshBindGroup, SetMeshViewBindGroup,
+    AlphaMode, ClusterDebugVisualization, DrawMesh, DrawMeshIndirect, EnvironmentMapLight,
+    MeshPipeline, MeshPipelineKey, MeshUniform, PrepassPlugin, ReflectionProbeMeta,
+    SetIndirectMeshBindGroup, SetMeshBindGroup, SetMeshViewBindGroup, ViewIndirectDraws,
+    ViewScreenSpaceReflections,
 };
 use bevy_app::{App, IntoSystemAppConfig, Plugin};
 use bevy_asset::{AddAsset, AssetEvent, AssetServer, Assets, Handle};
@@ -19,8 +21,9 @@ use bevy_ecs::{
 use bevy_reflect::TypeUuid;
 use bevy_render::{
     extract_component::ExtractComponentPlugin,
-    mesh::{Mesh, MeshVertexBufferLayout},
+    mesh::{Mesh, MeshVertexBufferLayout, VertexAttributeDescriptor},
     prelude::Image,
+    primitives::Aabb,
     render_asset::{PrepareAssetSet, RenderAssets},
     render_phase::{
         AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
@@ -130,6 +133,17 @@ pub trait Material: AsBindGroup + Send + Sync + Clone + TypeUuid + Sized + 'stat
         0.0
     }
 
+    /// Returns the custom vertex attributes read by this material's shaders, in addition to the
+    /// standard attributes bound by the mesh pipeline.
+    ///
+    /// Each [`VertexAttributeDescriptor`] maps a [`MeshVertexAttribute`](bevy_render::mesh::MeshVertexAttribute)
+    /// inserted on the [`Mesh`] to a shader location, both in the main pass and in the prepass.
+    /// The standard attributes use locations `0` to `6`, so custom attributes should start at `7`.
+    /// Meshes missing one of these attributes can't be rendered with this material.
+    fn custom_vertex_attributes() -> Vec<VertexAttributeDescriptor> {
+        Vec::new()
+    }
+
     /// Returns this material's prepass vertex shader. If [`ShaderRef::Default`] is returned, the default prepass vertex shader
     /// will be used.
     fn prepass_vertex_shader() -> ShaderRef {
@@ -190,6 +204,7 @@ where
             render_app
                 .add_render_command::<Transparent3d, DrawMaterial<M>>()
                 .add_render_command::<Opaque3d, DrawMaterial<M>>()
+                .add_render_command::<Opaque3d, DrawMaterialIndirect<M>>()
                 .add_render_command::<AlphaMask3d, DrawMaterial<M>>()
                 .init_resource::<MaterialPipeline<M>>()
                 .init_resource::<ExtractedMaterials<M>>()
@@ -293,6 +308,14 @@ where
 
         descriptor.layout.insert(1, self.material_layout.clone());
 
+        let custom_vertex_attributes = M::custom_vertex_attributes();
+        if !custom_vertex_attributes.is_empty() {
+            let custom_layout = layout.get_layout(&custom_vertex_attributes)?;
+            descriptor.vertex.buffers[0]
+                .attributes
+                .extend(custom_layout.attributes);
+        }
+
         M::specialize(self, &mut descriptor, layout, key)?;
         Ok(descriptor)
     }
@@ -329,6 +352,14 @@ type DrawMaterial<M> = (
     DrawMesh,
 );
 
+type DrawMaterialIndirect<M> = (
+    SetItemPipeline,
+    SetMeshViewBindGroup<0>,
+    SetMaterialBindGroup<M, 1>,
+    SetIndirectMeshBindGroup<2>,
+    DrawMeshIndirect,
+);
+
 /// Sets the bind group for a given [`Material`] at the configured `I` index.
 pub struct SetMaterialBindGroup<M: Material, const I: usize>(PhantomData<M>);
 impl<P: PhaseItem, M: Material, const I: usize> RenderCommand<P> for SetMaterialBindGroup<M, I> {
@@ -358,13 +389,14 @@ pub fn queue_material_meshes<M: Material>(
     material_pipeline: Res<MaterialPipeline<M>>,
     mut pipelines: ResMut<SpecializedMeshPipelines<MaterialPipeline<M>>>,
     pipeline_cache: Res<PipelineCache>,
-    msaa: Res<Msaa>,
     render_meshes: Res<RenderAssets<Mesh>>,
     render_materials: Res<RenderMaterials<M>>,
-    material_meshes: Query<(&Handle<M>, &Handle<Mesh>, &MeshUniform)>,
+    material_meshes: Query<(&Handle<M>, &Handle<Mesh>, &MeshUniform, Option<&Aabb>)>,
     images: Res<RenderAssets<Image>>,
+    reflection_probe_meta: Option<Res<ReflectionProbeMeta>>,
     mut views: Query<(
         &ExtractedView,
+        &Msaa,
         &VisibleEntities,
         Option<&Tonemapping>,
         Option<&DebandDither>,
@@ -372,12 +404,16 @@ pub fn queue_material_meshes<M: Material>(
         &mut RenderPhase<Opaque3d>,
         &mut RenderPhase<AlphaMask3d>,
         &mut RenderPhase<Transparent3d>,
+        Option<&mut ViewIndirectDraws>,
+        Option<&ViewScreenSpaceReflections>,
+        Option<&ClusterDebugVisualization>,
     )>,
 ) where
     M::Data: PartialEq + Eq + Hash + Clone,
 {
     for (
         view,
+        msaa,
         visible_entities,
         tonemapping,
         dither,
@@ -385,9 +421,13 @@ pub fn queue_material_meshes<M: Material>(
         mut opaque_phase,
         mut alpha_mask_phase,
         mut transparent_phase,
+        mut indirect_draws,
+        ssr,
+        cluster_debug,
     ) in &mut views
     {
         let draw_opaque_pbr = opaque_draw_functions.read().id::<DrawMaterial<M>>();
+        let draw_opaque_indirect_pbr = opaque_draw_functions.read().id::<DrawMaterialIndirect<M>>();
         let draw_alpha_mask_pbr = alpha_mask_draw_functions.read().id::<DrawMaterial<M>>();
         let draw_transparent_pbr = transparent_draw_functions.read().id::<DrawMaterial<M>>();
 
@@ -401,6 +441,23 @@ pub fn queue_material_meshes<M: Material>(
         if environment_map_loaded {
             view_key |= MeshPipelineKey::ENVIRONMENT_MAP;
         }
+        if reflection_probe_meta
+            .as_ref()
+            .map_or(false, |meta| meta.count() > 0)
+        {
+            view_key |= MeshPipelineKey::REFLECTION_PROBES;
+        }
+        if ssr.is_some() {
+            view_key |= MeshPipelineKey::SCREEN_SPACE_REFLECTIONS;
+        }
+        if let Some(cluster_debug) = cluster_debug {
+            view_key |= match cluster_debug {
+                ClusterDebugVisualization::None => MeshPipelineKey::CLUSTER_DEBUG_NONE,
+                ClusterDebugVisualization::ZSlices => MeshPipelineKey::CLUSTER_DEBUG_Z_SLICES,
+                ClusterDebugVisualization::LightCount => MeshPipelineKey::CLUSTER_DEBUG_LIGHT_COUNT,
+                ClusterDebugVisualization::Coherency => MeshPipelineKey::CLUSTER_DEBUG_COHERENCY,
+            };
+        }
 
         if !view.hdr {
             if let Some(tonemapping) = tonemapping {
@@ -427,7 +484,7 @@ pub fn queue_material_meshes<M: Material>(
 
         let rangefinder = view.rangefinder3d();
         for visible_entity in &visible_entities.entities {
-            if let Ok((material_handle, mesh_handle, mesh_uniform)) =
+            if let Ok((material_handle, mesh_handle, mesh_uniform, aabb)) =
                 material_meshes.get(*visible_entity)
             {
                 if let (Some(mesh), Some(material)) = (
@@ -446,6 +503,14 @@ pub fn queue_material_meshes<M: Material>(
                     } else if let AlphaMode::Multiply = alpha_mode {
                         mesh_key |= MeshPipelineKey::BLEND_MULTIPLY;
                     }
+                    // Only the default vertex shader loads the mesh of indirect draws
+                    let indirect = indirect_draws.is_some()
+                        && alpha_mode == AlphaMode::Opaque
+                        && material_pipeline.vertex_shader.is_none()
+                        && ViewIndirectDraws::supports(mesh);
+                    if indirect {
+                        mesh_key |= MeshPipelineKey::INDIRECT;
+                    }
 
                     let pipeline_id = pipelines.specialize(
                         &pipeline_cache,
@@ -467,14 +532,32 @@ pub fn queue_material_meshes<M: Material>(
                     let distance = rangefinder.distance(&mesh_uniform.transform)
                         + material.properties.depth_bias;
                     match alpha_mode {
-                        AlphaMode::Opaque => {
-                            opaque_phase.add(Opaque3d {
-                                entity: *visible_entity,
-                                draw_function: draw_opaque_pbr,
-                                pipeline: pipeline_id,
-                                distance,
-                            });
-                        }
+                        AlphaMode::Opaque => match &mut indirect_draws {
+                            Some(indirect_draws) if indirect => {
+                                indirect_draws.add(
+                                    &mut opaque_phase,
+                                    Opaque3d {
+                                        entity: *visible_entity,
+                                        draw_function: draw_opaque_indirect_pbr,
+                                        pipeline: pipeline_id,
+                                        distance,
+                                    },
+                                    material_handle.id(),
+                                    mesh_handle,
+                                    mesh,
+                                    mesh_uniform,
+                                    aabb,
+                                );
+                            }
+                            _ => {
+                                opaque_phase.add(Opaque3d {
+                                    entity: *visible_entity,
+                                    draw_function: draw_opaque_pbr,
+                                    pipeline: pipeline_id,
+                                    distance,
+                                });
+                            }
+                        },
                         AlphaMode::Mask(_) => {
                             alpha_mask_phase.add(AlphaMask3d {
                                 entity: *visible_entity,
@@ -561,6 +644,7 @@ pub fn extract_materials<M: Material>(
                 changed_assets.remove(handle);
                 removed.push(handle.clone_weak());
             }
+            AssetEvent::LoadedWithDependencies { .. } => {}
         }
     }
 
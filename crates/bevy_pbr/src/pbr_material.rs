@@ -6,8 +6,11 @@ use bevy_asset::Handle;
 use bevy_math::Vec4;
 use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect, TypeUuid};
 use bevy_render::{
-    color::Color, mesh::MeshVertexBufferLayout, render_asset::RenderAssets, render_resource::*,
-    texture::Image,
+    color::Color,
+    mesh::MeshVertexBufferLayout,
+    render_asset::RenderAssets,
+    render_resource::*,
+    texture::{Flipbook, Image},
 };
 
 /// A material with "standard" properties used in PBR lighting
@@ -25,9 +28,12 @@ pub struct StandardMaterial {
     ///
     /// Doubles as diffuse albedo for non-metallic, specular for metallic and a mix for everything
     /// in between. If used together with a `base_color_texture`, this is factored into the final
-    /// base color as `base_color * base_color_texture_value`
+    /// base color as `base_color * base_color_texture_value`. The [`Mesh::ATTRIBUTE_COLOR`] vertex
+    /// colors of the mesh are factored in the same way.
     ///
     /// Defaults to [`Color::WHITE`].
+    ///
+    /// [`Mesh::ATTRIBUTE_COLOR`]: bevy_render::mesh::Mesh::ATTRIBUTE_COLOR
     pub base_color: Color,
 
     /// The texture component of the material's color before lighting.
@@ -137,6 +143,27 @@ pub struct StandardMaterial {
     #[doc(alias = "specular_intensity")]
     pub reflectance: f32,
 
+    /// The strength of a thin, glossy layer over the material, like the varnish of a car paint
+    /// or of a wooden floor, from `0.0` (no layer) to `1.0`.
+    ///
+    /// The layer reflects the direct lights on top of the rest of the material, which it
+    /// attenuates. Defaults to `0.0`.
+    pub clearcoat: f32,
+
+    /// Linear perceptual roughness of the [`clearcoat`](Self::clearcoat) layer, clamped between
+    /// `0.089` and `1.0` in the shader.
+    ///
+    /// Defaults to `0.5`.
+    pub clearcoat_perceptual_roughness: f32,
+
+    /// The fraction of the light going through the material instead of being diffused, like for
+    /// glass or thin plastic, from `0.0` (opaque) to `1.0`.
+    ///
+    /// The transmitted light isn't refracted: the material is blended over what's behind it,
+    /// keeping its specular reflections, so it should use [`AlphaMode::Premultiplied`].
+    /// Defaults to `0.0`.
+    pub specular_transmission: f32,
+
     /// Used to fake the lighting of bumps and dents on a material.
     ///
     /// A typical usage would be faking cobblestones on a flat plane mesh in 3D.
@@ -236,6 +263,13 @@ pub struct StandardMaterial {
     ///
     /// [z-fighting]: https://en.wikipedia.org/wiki/Z-fighting
     pub depth_bias: f32,
+
+    /// Plays the textures of the material as a [`Flipbook`] animation, with each of them holding
+    /// the same grid of frames.
+    ///
+    /// The frames are picked on the GPU, so animated signs, fire or water don't need their
+    /// textures swapped every frame. Defaults to `None`.
+    pub flipbook: Option<Flipbook>,
 }
 
 impl Default for StandardMaterial {
@@ -256,6 +290,9 @@ impl Default for StandardMaterial {
             // Expressed in a linear scale and equivalent to 4% reflectance see
             // <https://google.github.io/filament/Material%20Properties.pdf>
             reflectance: 0.5,
+            clearcoat: 0.0,
+            clearcoat_perceptual_roughness: 0.5,
+            specular_transmission: 0.0,
             occlusion_texture: None,
             normal_map_texture: None,
             flip_normal_map_y: false,
@@ -265,6 +302,7 @@ impl Default for StandardMaterial {
             fog_enabled: true,
             alpha_mode: AlphaMode::Opaque,
             depth_bias: 0.0,
+            flipbook: None,
         }
     }
 }
@@ -307,6 +345,7 @@ bitflags::bitflags! {
         const TWO_COMPONENT_NORMAL_MAP   = (1 << 6);
         const FLIP_NORMAL_MAP_Y          = (1 << 7);
         const FOG_ENABLED                = (1 << 8);
+        const FLIPBOOK                   = (1 << 9);
         const ALPHA_MODE_RESERVED_BITS   = (Self::ALPHA_MODE_MASK_BITS << Self::ALPHA_MODE_SHIFT_BITS); // ← Bitmask reserving bits for the `AlphaMode`
         const ALPHA_MODE_OPAQUE          = (0 << Self::ALPHA_MODE_SHIFT_BITS);                          // ← Values are just sequential values bitshifted into
         const ALPHA_MODE_MASK            = (1 << Self::ALPHA_MODE_SHIFT_BITS);                          //   the bitmask, and can range from 0 to 7.
@@ -346,6 +385,24 @@ pub struct StandardMaterialUniform {
     /// When the alpha mode mask flag is set, any base color alpha above this cutoff means fully opaque,
     /// and any below means fully transparent.
     pub alpha_cutoff: f32,
+    /// The number of frames in each row of the [`Flipbook`], if the flipbook flag is set.
+    pub flipbook_columns: u32,
+    /// The number of rows of frames in the [`Flipbook`].
+    pub flipbook_rows: u32,
+    /// The number of frames played by the [`Flipbook`].
+    pub flipbook_frame_count: u32,
+    /// The [`FlipbookMode`](bevy_render::texture::FlipbookMode) of the [`Flipbook`].
+    pub flipbook_mode: u32,
+    /// The number of frames shown per second by the [`Flipbook`].
+    pub flipbook_fps: f32,
+    /// The time at which the [`Flipbook`] starts playing.
+    pub flipbook_start_time: f32,
+    /// The strength of the clearcoat layer, from [0.0, 1.0]
+    pub clearcoat: f32,
+    /// Linear perceptual roughness of the clearcoat layer, clamped to [0.089, 1.0] in the shader
+    pub clearcoat_perceptual_roughness: f32,
+    /// The fraction of the light transmitted through the material, from [0.0, 1.0]
+    pub specular_transmission: f32,
 }
 
 impl AsBindGroupShaderType<StandardMaterialUniform> for StandardMaterial {
@@ -390,6 +447,10 @@ impl AsBindGroupShaderType<StandardMaterialUniform> for StandardMaterial {
                 flags |= StandardMaterialFlags::FLIP_NORMAL_MAP_Y;
             }
         }
+        let flipbook = self.flipbook.unwrap_or_default();
+        if self.flipbook.is_some() {
+            flags |= StandardMaterialFlags::FLIPBOOK;
+        }
         // NOTE: 0.5 is from the glTF default - do we want this?
         let mut alpha_cutoff = 0.5;
         match self.alpha_mode {
@@ -412,6 +473,15 @@ impl AsBindGroupShaderType<StandardMaterialUniform> for StandardMaterial {
             reflectance: self.reflectance,
             flags: flags.bits(),
             alpha_cutoff,
+            flipbook_columns: flipbook.columns,
+            flipbook_rows: flipbook.rows,
+            flipbook_frame_count: flipbook.frame_count,
+            flipbook_mode: flipbook.mode.as_u32(),
+            flipbook_fps: flipbook.fps,
+            flipbook_start_time: flipbook.start_time,
+            clearcoat: self.clearcoat,
+            clearcoat_perceptual_roughness: self.clearcoat_perceptual_roughness,
+            specular_transmission: self.specular_transmission,
         }
     }
 }
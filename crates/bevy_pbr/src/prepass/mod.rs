@@ -17,6 +17,7 @@ use bevy_ecs::{
 use bevy_reflect::TypeUuid;
 use bevy_render::{
     camera::ExtractedCamera,
+    globals::{GlobalsBuffer, GlobalsUniform},
     mesh::MeshVertexBufferLayout,
     prelude::{Camera, Mesh},
     render_asset::RenderAssets,
@@ -98,11 +99,7 @@ where
 
         render_app
             .add_system(extract_camera_prepass_phase.in_schedule(ExtractSchedule))
-            .add_system(
-                prepare_prepass_textures
-                    .in_set(RenderSet::Prepare)
-                    .after(bevy_render::view::prepare_windows),
-            )
+            .add_system(prepare_prepass_textures.in_set(RenderSet::Prepare))
             .add_system(queue_prepass_view_bind_group::<M>.in_set(RenderSet::Queue))
             .add_system(queue_prepass_material_meshes::<M>.in_set(RenderSet::Queue))
             .add_system(sort_phase_system::<Opaque3dPrepass>.in_set(RenderSet::PhaseSort))
@@ -147,6 +144,17 @@ impl<M: Material> FromWorld for PrepassPipeline<M> {
                     },
                     count: None,
                 },
+                // Globals
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: Some(GlobalsUniform::min_size()),
+                    },
+                    count: None,
+                },
             ],
             label: Some("prepass_view_layout"),
         });
@@ -230,6 +238,14 @@ where
             }
         }
 
+        // Vertex colors are only needed to discard the same fragments as the main pass
+        if key.mesh_key.contains(MeshPipelineKey::ALPHA_MASK)
+            && layout.contains(Mesh::ATTRIBUTE_COLOR)
+        {
+            shader_defs.push("VERTEX_COLORS".into());
+            vertex_attributes.push(Mesh::ATTRIBUTE_COLOR.at_shader_location(6));
+        }
+
         if layout.contains(Mesh::ATTRIBUTE_JOINT_INDEX)
             && layout.contains(Mesh::ATTRIBUTE_JOINT_WEIGHT)
         {
@@ -241,6 +257,7 @@ where
             bind_group_layout.insert(2, self.mesh_layout.clone());
         }
 
+        vertex_attributes.extend(M::custom_vertex_attributes());
         let vertex_buffer_layout = layout.get_layout(&vertex_attributes)?;
 
         // The fragment shader is only used when the normal prepass is enabled or the material uses an alpha mask
@@ -373,12 +390,12 @@ pub fn extract_camera_prepass_phase(
 pub fn prepare_prepass_textures(
     mut commands: Commands,
     mut texture_cache: ResMut<TextureCache>,
-    msaa: Res<Msaa>,
     render_device: Res<RenderDevice>,
     views_3d: Query<
         (
             Entity,
             &ExtractedCamera,
+            &Msaa,
             Option<&DepthPrepass>,
             Option<&NormalPrepass>,
         ),
@@ -390,7 +407,7 @@ pub fn prepare_prepass_textures(
 ) {
     let mut depth_textures = HashMap::default();
     let mut normal_textures = HashMap::default();
-    for (entity, camera, depth_prepass, normal_prepass) in &views_3d {
+    for (entity, camera, msaa, depth_prepass, normal_prepass) in &views_3d {
         let Some(physical_target_size) = camera.physical_target_size else {
             continue;
         };
@@ -403,7 +420,7 @@ pub fn prepare_prepass_textures(
 
         let cached_depth_texture = depth_prepass.is_some().then(|| {
             depth_textures
-                .entry(camera.target.clone())
+                .entry((camera.target.clone(), *msaa))
                 .or_insert_with(|| {
                     let descriptor = TextureDescriptor {
                         label: Some("prepass_depth_texture"),
@@ -424,7 +441,7 @@ pub fn prepare_prepass_textures(
 
         let cached_normals_texture = normal_prepass.is_some().then(|| {
             normal_textures
-                .entry(camera.target.clone())
+                .entry((camera.target.clone(), *msaa))
                 .or_insert_with(|| {
                     texture_cache.get(
                         &render_device,
@@ -461,15 +478,25 @@ pub fn queue_prepass_view_bind_group<M: Material>(
     render_device: Res<RenderDevice>,
     prepass_pipeline: Res<PrepassPipeline<M>>,
     view_uniforms: Res<ViewUniforms>,
+    globals_buffer: Res<GlobalsBuffer>,
     mut prepass_view_bind_group: ResMut<PrepassViewBindGroup>,
 ) {
-    if let Some(view_binding) = view_uniforms.uniforms.binding() {
+    if let (Some(view_binding), Some(globals_binding)) = (
+        view_uniforms.uniforms.binding(),
+        globals_buffer.buffer.binding(),
+    ) {
         prepass_view_bind_group.bind_group =
             Some(render_device.create_bind_group(&BindGroupDescriptor {
-                entries: &[BindGroupEntry {
-                    binding: 0,
-                    resource: view_binding,
-                }],
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: view_binding,
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: globals_binding,
+                    },
+                ],
                 label: Some("prepass_view_bind_group"),
                 layout: &prepass_pipeline.view_layout,
             }));
@@ -483,12 +510,12 @@ pub fn queue_prepass_material_meshes<M: Material>(
     prepass_pipeline: Res<PrepassPipeline<M>>,
     mut pipelines: ResMut<SpecializedMeshPipelines<PrepassPipeline<M>>>,
     pipeline_cache: Res<PipelineCache>,
-    msaa: Res<Msaa>,
     render_meshes: Res<RenderAssets<Mesh>>,
     render_materials: Res<RenderMaterials<M>>,
     material_meshes: Query<(&Handle<M>, &Handle<Mesh>, &MeshUniform)>,
     mut views: Query<(
         &ExtractedView,
+        &Msaa,
         &VisibleEntities,
         &mut RenderPhase<Opaque3dPrepass>,
         &mut RenderPhase<AlphaMask3dPrepass>,
@@ -508,6 +535,7 @@ pub fn queue_prepass_material_meshes<M: Material>(
         .unwrap();
     for (
         view,
+        msaa,
         visible_entities,
         mut opaque_phase,
         mut alpha_mask_phase,
@@ -21,6 +21,10 @@ struct Vertex {
     @location(4) joint_indices: vec4<u32>,
     @location(5) joint_weights: vec4<f32>,
 #endif // SKINNED
+
+#ifdef VERTEX_COLORS
+    @location(6) color: vec4<f32>,
+#endif // VERTEX_COLORS
 }
 
 struct VertexOutput {
@@ -36,6 +40,10 @@ struct VertexOutput {
     @location(2) world_tangent: vec4<f32>,
 #endif // VERTEX_TANGENTS
 #endif // NORMAL_PREPASS
+
+#ifdef VERTEX_COLORS
+    @location(3) color: vec4<f32>,
+#endif // VERTEX_COLORS
 }
 
 @vertex
@@ -66,6 +74,10 @@ fn vertex(vertex: Vertex) -> VertexOutput {
 #endif // VERTEX_TANGENTS
 #endif // NORMAL_PREPASS
 
+#ifdef VERTEX_COLORS
+    out.color = vertex.color;
+#endif // VERTEX_COLORS
+
     return out;
 }
 
@@ -5,6 +5,8 @@
 
 @group(0) @binding(0)
 var<uniform> view: View;
+@group(0) @binding(1)
+var<uniform> globals: Globals;
 
 // Material bindings will be in @group(1)
 
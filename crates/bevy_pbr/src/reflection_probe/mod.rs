@@ -0,0 +1,493 @@
+use bevy_app::{App, CoreSet, IntoSystemAppConfig, Plugin};
+use bevy_asset::{load_internal_asset, Assets, Handle, HandleUntyped};
+use bevy_ecs::prelude::*;
+use bevy_math::{Mat4, Vec3};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect, TypeUuid};
+use bevy_render::{
+    render_asset::RenderAssets,
+    render_resource::{
+        BindGroupEntry, BindGroupLayoutEntry, BindingResource, BindingType, BufferBindingType,
+        CommandEncoderDescriptor, Extent3d, ImageCopyTexture, Origin3d, Shader, ShaderStages,
+        ShaderType, Texture, TextureAspect, TextureDescriptor, TextureDimension, TextureFormat,
+        TextureSampleType, TextureUsages, TextureView, TextureViewDescriptor, TextureViewDimension,
+        UniformBuffer,
+    },
+    renderer::{RenderDevice, RenderQueue},
+    texture::Image,
+    Extract, ExtractSchedule, RenderApp, RenderSet,
+};
+use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_utils::tracing::warn;
+
+pub const REFLECTION_PROBE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 8410356218634950047);
+
+/// The maximum number of [`ReflectionProbe`]s that can be blended in a scene.
+///
+/// NOTE: this must be kept in sync with the size of the `ReflectionProbes` array in `mesh_view_types.wgsl`.
+pub const MAX_REFLECTION_PROBES: usize = 8;
+
+/// Adds support for [`ReflectionProbe`]s.
+pub struct ReflectionProbePlugin;
+
+impl Plugin for ReflectionProbePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            REFLECTION_PROBE_SHADER_HANDLE,
+            "reflection_probe.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<ReflectionProbe>()
+            .add_system(allow_copying_reflection_probe_maps.in_base_set(CoreSet::PostUpdate));
+
+        // Cubemap arrays aren't supported on WebGL2
+        if cfg!(feature = "webgl") {
+            return;
+        }
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<ReflectionProbeMeta>()
+                .add_system(extract_reflection_probes.in_schedule(ExtractSchedule))
+                .add_system(prepare_reflection_probes.in_set(RenderSet::Prepare));
+        }
+    }
+}
+
+/// A box-shaped volume of the scene lit by its own environment cubemaps, for local
+/// reflections in enclosed spaces where the global [`EnvironmentMapLight`](crate::EnvironmentMapLight)
+/// would otherwise reflect the sky.
+///
+/// The probe covers the unit cube centered on its [`Transform`], which can be scaled to fit
+/// the room it describes. Inside the volume, its cubemaps replace (or blend with, near the
+/// faces of the volume) the environment map of the camera. Where probes overlap, the smallest
+/// one takes precedence.
+///
+/// The cubemaps are expected to be captured from the center of the probe, with their faces
+/// aligned to the world axes, and prefiltered the same way as those of an
+/// [`EnvironmentMapLight`](crate::EnvironmentMapLight). All the probes of a scene must use
+/// diffuse maps of the same size, format and mip count, and likewise for their specular maps.
+/// Only the [`MAX_REFLECTION_PROBES`] smallest probes are used.
+///
+/// Reflection probes require cubemap array textures, so they are not supported on `WebGL2`.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component, Default)]
+pub struct ReflectionProbe {
+    pub diffuse_map: Handle<Image>,
+    pub specular_map: Handle<Image>,
+    /// The fraction of the size of the volume along each of its local axes over which the
+    /// probe fades out towards its faces, from `0.0` (a hard edge) to `1.0`.
+    pub falloff: Vec3,
+    /// Whether reflections are projected onto the faces of the volume, rather than treated as
+    /// infinitely far away. This makes reflections line up with the walls of box-shaped rooms.
+    pub parallax_correction: bool,
+}
+
+impl Default for ReflectionProbe {
+    fn default() -> Self {
+        Self {
+            diffuse_map: Default::default(),
+            specular_map: Default::default(),
+            falloff: Vec3::splat(0.1),
+            parallax_correction: true,
+        }
+    }
+}
+
+/// A component bundle for [`ReflectionProbe`] entities.
+#[derive(Bundle, Clone, Debug, Default)]
+pub struct ReflectionProbeBundle {
+    pub reflection_probe: ReflectionProbe,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+}
+
+/// The cubemaps of reflection probes are copied into texture arrays on the GPU, which requires
+/// their textures to be copyable.
+pub fn allow_copying_reflection_probe_maps(
+    probes: Query<&ReflectionProbe>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    for probe in &probes {
+        for handle in [&probe.diffuse_map, &probe.specular_map] {
+            let copyable = match images.get(handle) {
+                Some(image) => image
+                    .texture_descriptor
+                    .usage
+                    .contains(TextureUsages::COPY_SRC),
+                None => true,
+            };
+            if !copyable {
+                if let Some(image) = images.get_mut(handle) {
+                    image.texture_descriptor.usage |= TextureUsages::COPY_SRC;
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ExtractedReflectionProbe {
+    diffuse_map: Handle<Image>,
+    specular_map: Handle<Image>,
+    transform: GlobalTransform,
+    falloff: Vec3,
+    parallax_correction: bool,
+}
+
+#[derive(Resource, Default)]
+pub struct ExtractedReflectionProbes {
+    probes: Vec<ExtractedReflectionProbe>,
+}
+
+pub fn extract_reflection_probes(
+    mut commands: Commands,
+    probes: Extract<Query<(&ReflectionProbe, &GlobalTransform)>>,
+) {
+    commands.insert_resource(ExtractedReflectionProbes {
+        probes: probes
+            .iter()
+            .map(|(probe, transform)| ExtractedReflectionProbe {
+                diffuse_map: probe.diffuse_map.clone_weak(),
+                specular_map: probe.specular_map.clone_weak(),
+                transform: *transform,
+                falloff: probe.falloff,
+                parallax_correction: probe.parallax_correction,
+            })
+            .collect(),
+    });
+}
+
+const REFLECTION_PROBE_FLAGS_PARALLAX_CORRECTION_BIT: u32 = 1;
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuReflectionProbe {
+    transform: Mat4,
+    inverse_transform: Mat4,
+    falloff: Vec3,
+    flags: u32,
+}
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuReflectionProbes {
+    probes: [GpuReflectionProbe; MAX_REFLECTION_PROBES],
+    count: u32,
+    smallest_specular_mip_level: u32,
+}
+
+/// Cubemap arrays holding the maps of every reflection probe in use, in the same order as
+/// their [`GpuReflectionProbe`]s.
+struct ReflectionProbeTextures {
+    /// The diffuse and specular maps the arrays were copied from, to detect when they must be
+    /// rebuilt.
+    key: Vec<(Texture, Texture)>,
+    diffuse_view: TextureView,
+    specular_view: TextureView,
+}
+
+#[derive(Resource)]
+pub struct ReflectionProbeMeta {
+    pub gpu_reflection_probes: UniformBuffer<GpuReflectionProbes>,
+    textures: Option<ReflectionProbeTextures>,
+    fallback_view: TextureView,
+}
+
+impl FromWorld for ReflectionProbeMeta {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let fallback_texture = render_device.create_texture(&TextureDescriptor {
+            label: Some("reflection_probe_fallback_texture"),
+            size: Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 6,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8Unorm,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        Self {
+            gpu_reflection_probes: UniformBuffer::default(),
+            textures: None,
+            fallback_view: fallback_texture.create_view(&TextureViewDescriptor {
+                dimension: Some(TextureViewDimension::CubeArray),
+                ..Default::default()
+            }),
+        }
+    }
+}
+
+impl ReflectionProbeMeta {
+    /// The number of reflection probes blended in the scene, if any.
+    pub fn count(&self) -> u32 {
+        self.gpu_reflection_probes.get().count
+    }
+}
+
+/// Whether two cubemaps can be stored in the same texture array.
+fn is_compatible(texture: &Texture, other: &Texture) -> bool {
+    texture.size() == other.size()
+        && texture.format() == other.format()
+        && texture.mip_level_count() == other.mip_level_count()
+}
+
+/// Creates a cubemap array holding copies of the given cubemaps, which must be compatible.
+fn create_cubemap_array(
+    render_device: &RenderDevice,
+    render_queue: &RenderQueue,
+    label: &'static str,
+    cubemaps: &[&Texture],
+) -> TextureView {
+    let first = cubemaps[0];
+    let size = first.size();
+    let format = first.format();
+    let array = render_device.create_texture(&TextureDescriptor {
+        label: Some(label),
+        size: Extent3d {
+            depth_or_array_layers: 6 * cubemaps.len() as u32,
+            ..size
+        },
+        mip_level_count: first.mip_level_count(),
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format,
+        usage: TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+
+    let mut command_encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+        label: Some("reflection_probe_copy_encoder"),
+    });
+    let (block_width, block_height) = format.describe().block_dimensions;
+    for (index, cubemap) in cubemaps.iter().enumerate() {
+        for mip_level in 0..first.mip_level_count() {
+            // Compressed textures are copied by whole blocks, even for mips smaller than a block
+            let width = (size.width >> mip_level).max(1);
+            let height = (size.height >> mip_level).max(1);
+            command_encoder.copy_texture_to_texture(
+                ImageCopyTexture {
+                    texture: cubemap,
+                    mip_level,
+                    origin: Origin3d::ZERO,
+                    aspect: TextureAspect::All,
+                },
+                ImageCopyTexture {
+                    texture: &array,
+                    mip_level,
+                    origin: Origin3d {
+                        x: 0,
+                        y: 0,
+                        z: 6 * index as u32,
+                    },
+                    aspect: TextureAspect::All,
+                },
+                Extent3d {
+                    width: (width + block_width as u32 - 1) / block_width as u32
+                        * block_width as u32,
+                    height: (height + block_height as u32 - 1) / block_height as u32
+                        * block_height as u32,
+                    depth_or_array_layers: 6,
+                },
+            );
+        }
+    }
+    render_queue.submit([command_encoder.finish()]);
+
+    array.create_view(&TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::CubeArray),
+        ..Default::default()
+    })
+}
+
+pub fn prepare_reflection_probes(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    images: Res<RenderAssets<Image>>,
+    extracted_probes: Res<ExtractedReflectionProbes>,
+    mut reflection_probe_meta: ResMut<ReflectionProbeMeta>,
+    mut max_reflection_probes_warning_emitted: Local<bool>,
+    mut incompatible_maps_warning_emitted: Local<bool>,
+) {
+    let mut probes: Vec<_> = extracted_probes
+        .probes
+        .iter()
+        .filter_map(|probe| {
+            let diffuse_map = images.get(&probe.diffuse_map)?;
+            let specular_map = images.get(&probe.specular_map)?;
+            Some((probe, diffuse_map, specular_map))
+        })
+        .collect();
+    // Smaller probes take precedence over the larger ones they're nested in
+    probes.sort_by(|(a, ..), (b, ..)| {
+        let volume = |probe: &ExtractedReflectionProbe| {
+            let scale = probe.transform.compute_transform().scale;
+            (scale.x * scale.y * scale.z).abs()
+        };
+        volume(a).total_cmp(&volume(b))
+    });
+
+    if let Some(&(_, first_diffuse_map, first_specular_map)) = probes.first() {
+        let count = probes.len();
+        probes.retain(|(_, diffuse_map, specular_map)| {
+            is_compatible(&diffuse_map.texture, &first_diffuse_map.texture)
+                && is_compatible(&specular_map.texture, &first_specular_map.texture)
+                && diffuse_map.texture.depth_or_array_layers() == 6
+                && specular_map.texture.depth_or_array_layers() == 6
+        });
+        if probes.len() < count && !*incompatible_maps_warning_emitted {
+            warn!(
+                "The cubemaps of some reflection probes don't match the size, format or \
+                mip count of the others, or aren't cubemaps. Those probes will be ignored."
+            );
+            *incompatible_maps_warning_emitted = true;
+        }
+    }
+    if probes.len() > MAX_REFLECTION_PROBES && !*max_reflection_probes_warning_emitted {
+        warn!(
+            "The amount of reflection probes of {} is exceeding the supported limit of {}.",
+            probes.len(),
+            MAX_REFLECTION_PROBES
+        );
+        *max_reflection_probes_warning_emitted = true;
+    }
+    probes.truncate(MAX_REFLECTION_PROBES);
+
+    let key: Vec<_> = probes
+        .iter()
+        .map(|(_, diffuse_map, specular_map)| {
+            (diffuse_map.texture.clone(), specular_map.texture.clone())
+        })
+        .collect();
+    let up_to_date = match &reflection_probe_meta.textures {
+        Some(textures) => {
+            textures.key.len() == key.len()
+                && textures
+                    .key
+                    .iter()
+                    .zip(&key)
+                    .all(|(a, b)| a.0.id() == b.0.id() && a.1.id() == b.1.id())
+        }
+        None => key.is_empty(),
+    };
+    if !up_to_date {
+        reflection_probe_meta.textures = if key.is_empty() {
+            None
+        } else {
+            let diffuse_maps: Vec<_> = key.iter().map(|(diffuse_map, _)| diffuse_map).collect();
+            let specular_maps: Vec<_> = key.iter().map(|(_, specular_map)| specular_map).collect();
+            Some(ReflectionProbeTextures {
+                diffuse_view: create_cubemap_array(
+                    &render_device,
+                    &render_queue,
+                    "reflection_probe_diffuse_texture",
+                    &diffuse_maps,
+                ),
+                specular_view: create_cubemap_array(
+                    &render_device,
+                    &render_queue,
+                    "reflection_probe_specular_texture",
+                    &specular_maps,
+                ),
+                key,
+            })
+        };
+    }
+
+    let mut gpu_reflection_probes = GpuReflectionProbes {
+        count: probes.len() as u32,
+        smallest_specular_mip_level: probes
+            .first()
+            .map(|(_, _, specular_map)| specular_map.mip_level_count - 1)
+            .unwrap_or(0),
+        ..Default::default()
+    };
+    for (gpu_probe, (probe, ..)) in gpu_reflection_probes.probes.iter_mut().zip(&probes) {
+        let transform = probe.transform.compute_matrix();
+        *gpu_probe = GpuReflectionProbe {
+            transform,
+            inverse_transform: transform.inverse(),
+            falloff: probe.falloff.clamp(Vec3::ZERO, Vec3::ONE),
+            flags: if probe.parallax_correction {
+                REFLECTION_PROBE_FLAGS_PARALLAX_CORRECTION_BIT
+            } else {
+                0
+            },
+        };
+    }
+
+    reflection_probe_meta
+        .gpu_reflection_probes
+        .set(gpu_reflection_probes);
+    reflection_probe_meta
+        .gpu_reflection_probes
+        .write_buffer(&render_device, &render_queue);
+}
+
+pub fn get_bindings(
+    reflection_probe_meta: &ReflectionProbeMeta,
+    bindings: [u32; 3],
+) -> Option<[BindGroupEntry; 3]> {
+    let (diffuse_view, specular_view) = match &reflection_probe_meta.textures {
+        Some(textures) => (&textures.diffuse_view, &textures.specular_view),
+        None => (
+            &reflection_probe_meta.fallback_view,
+            &reflection_probe_meta.fallback_view,
+        ),
+    };
+
+    Some([
+        BindGroupEntry {
+            binding: bindings[0],
+            resource: reflection_probe_meta.gpu_reflection_probes.binding()?,
+        },
+        BindGroupEntry {
+            binding: bindings[1],
+            resource: BindingResource::TextureView(diffuse_view),
+        },
+        BindGroupEntry {
+            binding: bindings[2],
+            resource: BindingResource::TextureView(specular_view),
+        },
+    ])
+}
+
+pub fn get_bind_group_layout_entries(bindings: [u32; 3]) -> [BindGroupLayoutEntry; 3] {
+    [
+        BindGroupLayoutEntry {
+            binding: bindings[0],
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: Some(GpuReflectionProbes::min_size()),
+            },
+            count: None,
+        },
+        BindGroupLayoutEntry {
+            binding: bindings[1],
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: true },
+                view_dimension: TextureViewDimension::CubeArray,
+                multisampled: false,
+            },
+            count: None,
+        },
+        BindGroupLayoutEntry {
+            binding: bindings[2],
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: true },
+                view_dimension: TextureViewDimension::CubeArray,
+                multisampled: false,
+            },
+            count: None,
+        },
+    ]
+}
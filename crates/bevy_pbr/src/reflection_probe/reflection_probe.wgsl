@@ -0,0 +1,70 @@
+#define_import_path bevy_pbr::reflection_probe
+
+// The direction to sample the cubemaps of a probe in, for a reflection towards R from a point
+// inside its volume
+fn reflection_probe_direction(probe: ReflectionProbe, local_position: vec3<f32>, R: vec3<f32>) -> vec3<f32> {
+    if ((probe.flags & REFLECTION_PROBE_FLAGS_PARALLAX_CORRECTION_BIT) == 0u) {
+        return R;
+    }
+
+    // Intersect the reflected ray with the faces of the volume, and sample towards that point
+    // from the center of the probe
+    let local_R = (probe.inverse_transform * vec4<f32>(R, 0.0)).xyz;
+    let first_plane = (vec3<f32>(0.5) - local_position) / local_R;
+    let second_plane = (vec3<f32>(-0.5) - local_position) / local_R;
+    let furthest_plane = max(first_plane, second_plane);
+    let distance = min(min(furthest_plane.x, furthest_plane.y), furthest_plane.z);
+    let local_hit = local_position + local_R * distance;
+    return (probe.transform * vec4<f32>(local_hit, 0.0)).xyz;
+}
+
+// Blends the reflection probes enclosing the fragment, in order, with the environment map
+// filling in wherever they don't fully cover it
+fn reflection_probe_light(
+    world_position: vec3<f32>,
+    perceptual_roughness: f32,
+    roughness: f32,
+    diffuse_color: vec3<f32>,
+    NdotV: f32,
+    f_ab: vec2<f32>,
+    N: vec3<f32>,
+    R: vec3<f32>,
+    F0: vec3<f32>,
+) -> EnvironmentMapLight {
+    let radiance_level = perceptual_roughness * f32(reflection_probes.smallest_specular_mip_level);
+
+    var irradiance = vec3<f32>(0.0);
+    var radiance = vec3<f32>(0.0);
+    var total_weight = 0.0;
+    for (var i: u32 = 0u; i < reflection_probes.count; i = i + 1u) {
+        let probe = reflection_probes.probes[i];
+        let local_position = (probe.inverse_transform * vec4<f32>(world_position, 1.0)).xyz;
+        let distance_to_faces = vec3<f32>(0.5) - abs(local_position);
+        if (any(distance_to_faces <= vec3<f32>(0.0))) {
+            continue;
+        }
+
+        let fade = saturate(distance_to_faces / max(probe.falloff * 0.5, vec3<f32>(0.0001)));
+        let weight = fade.x * fade.y * fade.z * (1.0 - total_weight);
+        let layer = i32(i);
+        irradiance += weight * textureSampleLevel(reflection_probe_diffuse_textures, environment_map_sampler, N, layer, 0.0).rgb;
+        let direction = reflection_probe_direction(probe, local_position, R);
+        radiance += weight * textureSampleLevel(reflection_probe_specular_textures, environment_map_sampler, direction, layer, radiance_level).rgb;
+
+        total_weight += weight;
+        if (total_weight >= 1.0) {
+            break;
+        }
+    }
+
+#ifdef ENVIRONMENT_MAP
+    if (total_weight < 1.0) {
+        let environment_radiance_level = perceptual_roughness * f32(lights.environment_map_smallest_specular_mip_level);
+        let remaining_weight = 1.0 - total_weight;
+        irradiance += remaining_weight * textureSampleLevel(environment_map_diffuse, environment_map_sampler, N, 0.0).rgb;
+        radiance += remaining_weight * textureSampleLevel(environment_map_specular, environment_map_sampler, R, environment_radiance_level).rgb;
+    }
+#endif
+
+    return environment_light(irradiance, radiance, roughness, diffuse_color, NdotV, f_ab, F0);
+}
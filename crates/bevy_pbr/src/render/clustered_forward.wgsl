@@ -56,12 +56,14 @@ fn get_light_id(index: u32) -> u32 {
 }
 
 fn cluster_debug_visualization(
-    output_color: vec4<f32>,
+    input_color: vec4<f32>,
     view_z: f32,
     is_orthographic: bool,
     offset_and_counts: vec3<u32>,
     cluster_index: u32,
 ) -> vec4<f32> {
+    var output_color = input_color;
+
     // Cluster allocation debug (using 'over' alpha blending)
 #ifdef CLUSTERED_FORWARD_DEBUG_Z_SLICES
     // NOTE: This debug mode visualises the z-slices
@@ -81,11 +83,11 @@ fn cluster_debug_visualization(
     // NOTE: This debug mode visualises the number of lights within the cluster that contains
     // the fragment. It shows a sort of lighting complexity measure.
     let cluster_overlay_alpha = 0.1;
-    let max_light_complexity_per_cluster = 64.0;
+    let max_light_complexity_per_cluster = f32(#{MAX_LIGHTS_PER_CLUSTER}u);
     output_color.r = (1.0 - cluster_overlay_alpha) * output_color.r
-        + cluster_overlay_alpha * smoothStep(0.0, max_light_complexity_per_cluster, f32(offset_and_counts[1] + offset_and_counts[2]));
+        + cluster_overlay_alpha * smoothstep(0.0, max_light_complexity_per_cluster, f32(offset_and_counts[1] + offset_and_counts[2]));
     output_color.g = (1.0 - cluster_overlay_alpha) * output_color.g
-        + cluster_overlay_alpha * (1.0 - smoothStep(0.0, max_light_complexity_per_cluster, f32(offset_and_counts[1] + offset_and_counts[2])));
+        + cluster_overlay_alpha * (1.0 - smoothstep(0.0, max_light_complexity_per_cluster, f32(offset_and_counts[1] + offset_and_counts[2])));
 #endif // CLUSTERED_FORWARD_DEBUG_CLUSTER_LIGHT_COMPLEXITY
 #ifdef CLUSTERED_FORWARD_DEBUG_CLUSTER_COHERENCY
     // NOTE: Visualizes the cluster to which the fragment belongs
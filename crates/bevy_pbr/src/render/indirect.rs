@@ -0,0 +1,565 @@
+use std::ops::Range;
+
+use bevy_app::{App, IntoSystemAppConfig, IntoSystemAppConfigs, Plugin};
+use bevy_asset::{load_internal_asset, Handle, HandleId, HandleUntyped};
+use bevy_core_pipeline::core_3d::{Camera3d, Opaque3d};
+use bevy_ecs::{
+    prelude::*,
+    query::ROQueryItem,
+    system::{
+        lifetimeless::{Read, SRes},
+        SystemParamItem,
+    },
+};
+use bevy_math::{Vec3, Vec4};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect, TypeUuid};
+use bevy_render::{
+    camera::Camera,
+    main_graph,
+    mesh::{GpuBufferInfo, GpuMesh, Mesh},
+    primitives::{Aabb, Frustum},
+    render_asset::RenderAssets,
+    render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext},
+    render_phase::{PhaseItem, RenderCommand, RenderCommandResult, RenderPhase, TrackedRenderPass},
+    render_resource::*,
+    renderer::{AddRenderFallback, RenderContext, RenderDevice, RenderQueue},
+    settings::WgpuFeatures,
+    view::{ExtractedView, NoFrustumCulling, ViewVisibility},
+    Extract, ExtractSchedule, RenderApp, RenderSet,
+};
+use bevy_utils::{tracing::warn, HashMap};
+
+use crate::{MeshPipeline, MeshUniform};
+
+pub const INDIRECT_CULL_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 7395604219825174321);
+
+/// The size of the `DrawIndexedIndirect` arguments of a draw.
+const DRAW_INDEXED_INDIRECT_SIZE: u64 = 20;
+const WORKGROUP_SIZE: u32 = 64;
+
+/// Draws the opaque meshes of a 3D camera with indirect draws, whose arguments are written on
+/// the GPU by a compute pass culling the meshes against the view frustum.
+///
+/// The meshes sharing a mesh, material and pipeline are drawn together, with a single
+/// `multi_draw_indexed_indirect` call when the render device supports
+/// [`WgpuFeatures::MULTI_DRAW_INDIRECT`], instead of one draw call and bind group change per
+/// mesh. This cuts the CPU cost of submitting the draws of scenes with many copies of the same
+/// meshes.
+///
+/// Only the opaque main pass of the [`Material`](crate::Material)s using the default vertex
+/// shader is drawn this way, for indexed meshes without skinning. Shadows, prepasses and the
+/// other meshes are drawn as usual. Custom fragment shaders reading `mesh` must first load it
+/// with `mesh = meshes[in.instance_index];` when `MESH_INDIRECT` is defined.
+///
+/// Indirect drawing needs storage buffers, compute shaders and
+/// [`WgpuFeatures::INDIRECT_FIRST_INSTANCE`]. Without them, the camera draws its meshes as usual.
+#[derive(Component, Clone, Copy, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct IndirectDrawing;
+
+/// Adds support for [`IndirectDrawing`].
+pub struct IndirectDrawingPlugin;
+
+impl Plugin for IndirectDrawingPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            INDIRECT_CULL_SHADER_HANDLE,
+            "indirect_cull.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<IndirectDrawing>();
+
+        let mut first_instance = true;
+        app.add_render_feature_fallback(WgpuFeatures::INDIRECT_FIRST_INSTANCE, |_, _| {
+            first_instance = false;
+        });
+
+        let Ok(render_app) = app.get_sub_app_mut(RenderApp) else {
+            return;
+        };
+        let limits = render_app.world.resource::<RenderDevice>().limits();
+        // The culling pass binds 3 storage buffers
+        let supported = first_instance
+            && limits.max_storage_buffers_per_shader_stage >= 3
+            && limits.max_compute_invocations_per_workgroup >= WORKGROUP_SIZE;
+        if !supported {
+            render_app.add_system(warn_unsupported_indirect_drawing.in_schedule(ExtractSchedule));
+            return;
+        }
+
+        render_app
+            .init_resource::<IndirectCullPipeline>()
+            .init_resource::<IndirectDrawBuffers>()
+            .add_systems(
+                (extract_indirect_drawing, extract_mesh_aabbs).in_schedule(ExtractSchedule),
+            )
+            // The draws are batched while queuing the phases
+            .add_system(prepare_indirect_draws.in_set(RenderSet::PhaseSort));
+
+        let cull_node = IndirectCullNode::new(&mut render_app.world);
+        let mut graph = render_app.world.resource_mut::<RenderGraph>();
+        graph.add_node(IndirectCullNode::NAME, cull_node);
+        graph.add_node_edge(IndirectCullNode::NAME, main_graph::node::CAMERA_DRIVER);
+    }
+}
+
+fn warn_unsupported_indirect_drawing(
+    cameras: Extract<Query<(), (With<Camera3d>, With<IndirectDrawing>)>>,
+    mut warned: Local<bool>,
+) {
+    if !*warned && !cameras.is_empty() {
+        warn!("Indirect drawing is not supported by the render device, the cameras with `IndirectDrawing` draw their meshes directly");
+        *warned = true;
+    }
+}
+
+pub fn extract_indirect_drawing(
+    mut commands: Commands,
+    cameras: Extract<Query<(Entity, &Camera), (With<Camera3d>, With<IndirectDrawing>)>>,
+) {
+    for (entity, camera) in &cameras {
+        if camera.is_active {
+            commands
+                .get_or_spawn(entity)
+                .insert(ViewIndirectDraws::default());
+        }
+    }
+}
+
+/// Extracts the bounds of the visible meshes, for the culling pass.
+pub fn extract_mesh_aabbs(
+    mut commands: Commands,
+    mut prev_len: Local<usize>,
+    meshes: Extract<
+        Query<(Entity, &ViewVisibility, &Aabb), (With<Handle<Mesh>>, Without<NoFrustumCulling>)>,
+    >,
+) {
+    let mut aabbs = Vec::with_capacity(*prev_len);
+    for (entity, visibility, aabb) in &meshes {
+        if visibility.get() {
+            aabbs.push((entity, *aabb));
+        }
+    }
+    *prev_len = aabbs.len();
+    commands.insert_or_spawn_batch(aabbs);
+}
+
+/// The data of a mesh read by the culling pass, matching `IndirectInstance` in
+/// `indirect_cull.wgsl`.
+#[derive(ShaderType, Clone, Copy, Default)]
+struct IndirectInstance {
+    aabb_center: Vec3,
+    index_count: u32,
+    aabb_half_extents: Vec3,
+    flags: u32,
+}
+
+// NOTE: These must match the bit flags in bevy_pbr/src/render/indirect_cull.wgsl!
+const INDIRECT_INSTANCE_FLAGS_FRUSTUM_CULLED: u32 = 1 << 0;
+
+/// The view data of the culling pass, matching `IndirectCullView` in `indirect_cull.wgsl`.
+#[derive(ShaderType, Clone, Default)]
+struct IndirectCullView {
+    /// The left, right, top, bottom and near planes of the view frustum.
+    frustum: [Vec4; 5],
+    instance_count: u32,
+}
+
+/// A batch of meshes sharing a mesh, material and pipeline, drawn by a single phase item.
+struct IndirectBatch {
+    phase_item_index: usize,
+    meshes: Vec<MeshUniform>,
+    instances: Vec<IndirectInstance>,
+    /// The draws of the batch in the indirect buffer, once prepared.
+    draws: Range<u32>,
+}
+
+/// The opaque meshes of a view with [`IndirectDrawing`], batched by mesh, material and
+/// pipeline.
+#[derive(Component, Default)]
+pub struct ViewIndirectDraws {
+    batch_entities: HashMap<(CachedRenderPipelineId, HandleId, HandleId), Entity>,
+    /// The batches, by the entity of the phase item drawing them.
+    batches: HashMap<Entity, IndirectBatch>,
+    instance_count: u32,
+    mesh_bind_group: Option<BindGroup>,
+    cull_bind_group: Option<BindGroup>,
+    indirect_buffer: Option<Buffer>,
+}
+
+impl ViewIndirectDraws {
+    /// Returns `true` if `mesh` can be drawn indirectly.
+    pub fn supports(mesh: &GpuMesh) -> bool {
+        matches!(mesh.buffer_info, GpuBufferInfo::Indexed { .. })
+            && !mesh.layout.contains(Mesh::ATTRIBUTE_JOINT_INDEX)
+    }
+
+    /// Adds a mesh to the batch of the meshes sharing its mesh, material and pipeline, adding
+    /// `item` to `phase` to draw the batch if it's the first mesh of the batch.
+    ///
+    /// The batch is drawn at the distance of its closest mesh. `mesh` must be
+    /// [supported](Self::supports).
+    #[allow(clippy::too_many_arguments)]
+    pub fn add(
+        &mut self,
+        phase: &mut RenderPhase<Opaque3d>,
+        item: Opaque3d,
+        material: HandleId,
+        mesh_handle: &Handle<Mesh>,
+        mesh: &GpuMesh,
+        mesh_uniform: &MeshUniform,
+        aabb: Option<&Aabb>,
+    ) {
+        let GpuBufferInfo::Indexed { count, .. } = mesh.buffer_info else {
+            return;
+        };
+        let instance = match aabb {
+            Some(aabb) => IndirectInstance {
+                aabb_center: aabb.center.into(),
+                index_count: count,
+                aabb_half_extents: aabb.half_extents.into(),
+                flags: INDIRECT_INSTANCE_FLAGS_FRUSTUM_CULLED,
+            },
+            None => IndirectInstance {
+                index_count: count,
+                ..Default::default()
+            },
+        };
+
+        let entity = *self
+            .batch_entities
+            .entry((item.pipeline, material, mesh_handle.id()))
+            .or_insert(item.entity);
+        let batch = self.batches.entry(entity).or_insert_with(|| {
+            phase.add(Opaque3d { entity, ..item });
+            IndirectBatch {
+                phase_item_index: phase.items.len() - 1,
+                meshes: Vec::new(),
+                instances: Vec::new(),
+                draws: 0..0,
+            }
+        });
+        let batch_item = &mut phase.items[batch.phase_item_index];
+        batch_item.distance = batch_item.distance.max(item.distance);
+        batch.meshes.push(mesh_uniform.clone());
+        batch.instances.push(instance);
+    }
+}
+
+#[derive(Default)]
+struct ViewIndirectBuffers {
+    meshes: StorageBuffer<Vec<MeshUniform>>,
+    instances: StorageBuffer<Vec<IndirectInstance>>,
+    view: UniformBuffer<IndirectCullView>,
+    indirect: Option<Buffer>,
+}
+
+/// The buffers of the views with [`IndirectDrawing`], kept across frames.
+#[derive(Resource, Default)]
+pub struct IndirectDrawBuffers {
+    views: HashMap<Entity, ViewIndirectBuffers>,
+}
+
+#[derive(Resource)]
+pub struct IndirectCullPipeline {
+    cull_layout: BindGroupLayout,
+    cull_pipeline: CachedComputePipelineId,
+    /// Whether the draws of a batch are submitted with a single call.
+    multi_draw_indirect: bool,
+}
+
+impl FromWorld for IndirectCullPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let storage_entry = |binding, read_only, min_binding_size| BindGroupLayoutEntry {
+            binding,
+            visibility: ShaderStages::COMPUTE,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Storage { read_only },
+                has_dynamic_offset: false,
+                min_binding_size,
+            },
+            count: None,
+        };
+        let cull_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: Some(IndirectCullView::min_size()),
+                    },
+                    count: None,
+                },
+                storage_entry(1, true, Some(MeshUniform::min_size())),
+                storage_entry(2, true, Some(IndirectInstance::min_size())),
+                storage_entry(3, false, BufferSize::new(DRAW_INDEXED_INDIRECT_SIZE)),
+            ],
+            label: Some("indirect_cull_layout"),
+        });
+
+        let multi_draw_indirect = render_device
+            .features()
+            .contains(WgpuFeatures::MULTI_DRAW_INDIRECT);
+
+        let cull_pipeline =
+            world
+                .resource::<PipelineCache>()
+                .queue_compute_pipeline(ComputePipelineDescriptor {
+                    label: Some("indirect_cull_pipeline".into()),
+                    layout: vec![cull_layout.clone()],
+                    push_constant_ranges: Vec::new(),
+                    shader: INDIRECT_CULL_SHADER_HANDLE.typed(),
+                    shader_defs: Vec::new(),
+                    entry_point: "cull".into(),
+                });
+
+        IndirectCullPipeline {
+            cull_layout,
+            cull_pipeline,
+            multi_draw_indirect,
+        }
+    }
+}
+
+/// Writes the meshes of the batches of every view to its buffers, and assigns the batches their
+/// draws in the indirect buffer.
+pub fn prepare_indirect_draws(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mesh_pipeline: Res<MeshPipeline>,
+    cull_pipeline: Res<IndirectCullPipeline>,
+    mut buffers: ResMut<IndirectDrawBuffers>,
+    mut views: Query<(Entity, &ExtractedView, &mut ViewIndirectDraws)>,
+) {
+    let Some(mesh_layout) = &mesh_pipeline.indirect_mesh_layout else {
+        return;
+    };
+    let buffers = &mut buffers.views;
+    buffers.retain(|entity, _| views.contains(*entity));
+
+    for (entity, view, mut indirect_draws) in &mut views {
+        let view_buffers = buffers.entry(entity).or_default();
+        let meshes = view_buffers.meshes.get_mut();
+        meshes.clear();
+        let instances = view_buffers.instances.get_mut();
+        instances.clear();
+        for batch in indirect_draws.batches.values_mut() {
+            let start = instances.len() as u32;
+            meshes.append(&mut batch.meshes);
+            instances.append(&mut batch.instances);
+            batch.draws = start..instances.len() as u32;
+        }
+        let instance_count = instances.len() as u32;
+        if instance_count == 0 {
+            continue;
+        }
+
+        let view_projection = view
+            .view_projection
+            .unwrap_or_else(|| view.projection * view.transform.compute_matrix().inverse());
+        let frustum = Frustum::from_view_projection(&view_projection);
+        view_buffers.view.set(IndirectCullView {
+            frustum: std::array::from_fn(|i| frustum.planes[i].normal_d()),
+            instance_count,
+        });
+
+        view_buffers
+            .meshes
+            .write_buffer(&render_device, &render_queue);
+        view_buffers
+            .instances
+            .write_buffer(&render_device, &render_queue);
+        view_buffers
+            .view
+            .write_buffer(&render_device, &render_queue);
+
+        let indirect_size = instance_count as u64 * DRAW_INDEXED_INDIRECT_SIZE;
+        let indirect = match &view_buffers.indirect {
+            Some(buffer) if buffer.size() >= indirect_size => buffer.clone(),
+            _ => {
+                let buffer = render_device.create_buffer(&BufferDescriptor {
+                    label: Some("indirect_draw_buffer"),
+                    // Leave room for more meshes in the next frames
+                    size: indirect_size.next_power_of_two(),
+                    usage: BufferUsages::STORAGE | BufferUsages::INDIRECT,
+                    mapped_at_creation: false,
+                });
+                view_buffers.indirect = Some(buffer.clone());
+                buffer
+            }
+        };
+
+        let (Some(meshes), Some(instances), Some(view_uniform)) = (
+            view_buffers.meshes.binding(),
+            view_buffers.instances.binding(),
+            view_buffers.view.binding(),
+        ) else {
+            continue;
+        };
+        indirect_draws.cull_bind_group =
+            Some(render_device.create_bind_group(&BindGroupDescriptor {
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: view_uniform,
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: meshes.clone(),
+                    },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: instances,
+                    },
+                    BindGroupEntry {
+                        binding: 3,
+                        resource: indirect.as_entire_binding(),
+                    },
+                ],
+                label: Some("indirect_cull_bind_group"),
+                layout: &cull_pipeline.cull_layout,
+            }));
+        indirect_draws.mesh_bind_group =
+            Some(render_device.create_bind_group(&BindGroupDescriptor {
+                entries: &[BindGroupEntry {
+                    binding: 0,
+                    resource: meshes,
+                }],
+                label: Some("indirect_mesh_bind_group"),
+                layout: mesh_layout,
+            }));
+        indirect_draws.indirect_buffer = Some(indirect);
+        indirect_draws.instance_count = instance_count;
+    }
+}
+
+/// Culls the meshes of every view with [`IndirectDrawing`] and writes their indirect draws,
+/// before any camera is rendered.
+pub struct IndirectCullNode {
+    views: QueryState<&'static ViewIndirectDraws>,
+}
+
+impl IndirectCullNode {
+    pub const NAME: &str = "indirect_cull";
+
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            views: QueryState::new(world),
+        }
+    }
+}
+
+impl Node for IndirectCullNode {
+    fn update(&mut self, world: &mut World) {
+        self.views.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let cull_pipeline = world.resource::<IndirectCullPipeline>();
+        let Some(pipeline) = pipeline_cache.get_compute_pipeline(cull_pipeline.cull_pipeline)
+        else {
+            return Ok(());
+        };
+
+        let mut pass =
+            render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("indirect_cull_pass"),
+                });
+        pass.set_pipeline(pipeline);
+        for indirect_draws in self.views.iter_manual(world) {
+            if let Some(bind_group) = &indirect_draws.cull_bind_group {
+                pass.set_bind_group(0, bind_group, &[]);
+                let workgroups =
+                    (indirect_draws.instance_count + WORKGROUP_SIZE - 1) / WORKGROUP_SIZE;
+                pass.dispatch_workgroups(workgroups, 1, 1);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sets the bind group of the meshes of a view with [`IndirectDrawing`] at the `I` index.
+pub struct SetIndirectMeshBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetIndirectMeshBindGroup<I> {
+    type Param = ();
+    type ViewWorldQuery = Read<ViewIndirectDraws>;
+    type ItemWorldQuery = ();
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        indirect_draws: ROQueryItem<'w, Self::ViewWorldQuery>,
+        _entity: (),
+        _: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(bind_group) = &indirect_draws.mesh_bind_group else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+/// Draws the batch of meshes of a phase item added by [`ViewIndirectDraws::add`].
+pub struct DrawMeshIndirect;
+impl<P: PhaseItem> RenderCommand<P> for DrawMeshIndirect {
+    type Param = (SRes<RenderAssets<Mesh>>, SRes<IndirectCullPipeline>);
+    type ViewWorldQuery = Read<ViewIndirectDraws>;
+    type ItemWorldQuery = Read<Handle<Mesh>>;
+
+    #[inline]
+    fn render<'w>(
+        item: &P,
+        indirect_draws: ROQueryItem<'w, Self::ViewWorldQuery>,
+        mesh_handle: ROQueryItem<'_, Self::ItemWorldQuery>,
+        (meshes, cull_pipeline): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let (Some(gpu_mesh), Some(batch), Some(indirect_buffer)) = (
+            meshes.into_inner().get(mesh_handle),
+            indirect_draws.batches.get(&item.entity()),
+            &indirect_draws.indirect_buffer,
+        ) else {
+            return RenderCommandResult::Failure;
+        };
+        let GpuBufferInfo::Indexed {
+            buffer,
+            index_format,
+            ..
+        } = &gpu_mesh.buffer_info
+        else {
+            return RenderCommandResult::Failure;
+        };
+
+        pass.set_vertex_buffer(0, gpu_mesh.vertex_buffer.slice(..));
+        pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+        let offset = batch.draws.start as u64 * DRAW_INDEXED_INDIRECT_SIZE;
+        if cull_pipeline.multi_draw_indirect {
+            pass.multi_draw_indexed_indirect(indirect_buffer, offset, batch.draws.len() as u32);
+        } else {
+            for draw in 0..batch.draws.len() as u64 {
+                pass.draw_indexed_indirect(
+                    indirect_buffer,
+                    offset + draw * DRAW_INDEXED_INDIRECT_SIZE,
+                );
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
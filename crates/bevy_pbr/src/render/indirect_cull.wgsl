@@ -0,0 +1,78 @@
+#import bevy_pbr::mesh_types
+
+struct IndirectCullView {
+    // The left, right, top, bottom and near planes of the view frustum, with their normals
+    // pointing inside
+    frustum: array<vec4<f32>, 5>,
+    instance_count: u32,
+};
+
+struct IndirectInstance {
+    aabb_center: vec3<f32>,
+    index_count: u32,
+    aabb_half_extents: vec3<f32>,
+    // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+    flags: u32,
+};
+
+struct DrawIndexedIndirect {
+    index_count: u32,
+    instance_count: u32,
+    first_index: u32,
+    base_vertex: i32,
+    first_instance: u32,
+};
+
+const INDIRECT_INSTANCE_FLAGS_FRUSTUM_CULLED_BIT: u32 = 1u;
+
+@group(0) @binding(0)
+var<uniform> view: IndirectCullView;
+@group(0) @binding(1)
+var<storage> meshes: array<Mesh>;
+@group(0) @binding(2)
+var<storage> instances: array<IndirectInstance>;
+@group(0) @binding(3)
+var<storage, read_write> draws: array<DrawIndexedIndirect>;
+
+fn is_visible(instance: IndirectInstance, model: mat4x4<f32>) -> bool {
+    let center = model * vec4<f32>(instance.aabb_center, 1.0);
+    for (var i = 0; i < 5; i = i + 1) {
+        let plane = view.frustum[i];
+        // The extent of the oriented bounding box along the plane normal
+        let relative_radius = dot(
+            abs(vec3<f32>(
+                dot(plane.xyz, model[0].xyz),
+                dot(plane.xyz, model[1].xyz),
+                dot(plane.xyz, model[2].xyz)
+            )),
+            instance.aabb_half_extents
+        );
+        if (dot(plane, center) + relative_radius <= 0.0) {
+            return false;
+        }
+    }
+    return true;
+}
+
+@compute @workgroup_size(64)
+fn cull(@builtin(global_invocation_id) invocation_id: vec3<u32>) {
+    let index = invocation_id.x;
+    if (index >= view.instance_count) {
+        return;
+    }
+
+    let instance = instances[index];
+    var visible = true;
+    if ((instance.flags & INDIRECT_INSTANCE_FLAGS_FRUSTUM_CULLED_BIT) != 0u) {
+        visible = is_visible(instance, meshes[index].model);
+    }
+
+    // The instance index of the draw is the index of its mesh
+    draws[index] = DrawIndexedIndirect(
+        instance.index_count,
+        select(0u, 1u, visible),
+        0u,
+        0,
+        index
+    );
+}
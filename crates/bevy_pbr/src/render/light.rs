@@ -3,7 +3,7 @@ use crate::{
     Cascades, CascadesVisibleEntities, Clusters, CubemapVisibleEntities, DirectionalLight,
     DirectionalLightShadowMap, DrawMesh, EnvironmentMapLight, GlobalVisiblePointLights,
     MeshPipeline, NotShadowCaster, PointLight, PointLightShadowMap, SetMeshBindGroup, SpotLight,
-    VisiblePointLights, SHADOW_SHADER_HANDLE,
+    VisiblePointLights, VolumetricLight, SHADOW_SHADER_HANDLE,
 };
 use bevy_asset::Handle;
 use bevy_core_pipeline::core_3d::Transparent3d;
@@ -26,7 +26,7 @@ use bevy_render::{
     renderer::{RenderContext, RenderDevice, RenderQueue},
     texture::*,
     view::{
-        ComputedVisibility, ExtractedView, ViewUniform, ViewUniformOffset, ViewUniforms,
+        ExtractedView, ViewUniform, ViewUniformOffset, ViewUniforms, ViewVisibility,
         VisibleEntities,
     },
     Extract,
@@ -72,6 +72,7 @@ pub struct ExtractedDirectionalLight {
     shadow_normal_bias: f32,
     cascade_shadow_config: CascadeShadowConfig,
     cascades: HashMap<Entity, Vec<Cascade>>,
+    volumetric: bool,
 }
 
 #[derive(Copy, Clone, ShaderType, Default, Debug)]
@@ -200,6 +201,7 @@ bitflags::bitflags! {
     #[repr(transparent)]
     struct DirectionalLightFlags: u32 {
         const SHADOWS_ENABLED            = (1 << 0);
+        const VOLUMETRIC                 = (1 << 1);
         const NONE                       = 0;
         const UNINITIALIZED              = 0xFFFF;
     }
@@ -449,7 +451,7 @@ pub fn extract_lights(
             &PointLight,
             &CubemapVisibleEntities,
             &GlobalTransform,
-            &ComputedVisibility,
+            &ViewVisibility,
         )>,
     >,
     spot_lights: Extract<
@@ -457,7 +459,7 @@ pub fn extract_lights(
             &SpotLight,
             &VisibleEntities,
             &GlobalTransform,
-            &ComputedVisibility,
+            &ViewVisibility,
         )>,
     >,
     directional_lights: Extract<
@@ -469,7 +471,8 @@ pub fn extract_lights(
                 &Cascades,
                 &CascadeShadowConfig,
                 &GlobalTransform,
-                &ComputedVisibility,
+                &ViewVisibility,
+                Option<&VolumetricLight>,
             ),
             Without<SpotLight>,
         >,
@@ -499,7 +502,7 @@ pub fn extract_lights(
         if let Ok((point_light, cubemap_visible_entities, transform, visibility)) =
             point_lights.get(entity)
         {
-            if !visibility.is_visible() {
+            if !visibility.get() {
                 continue;
             }
             // TODO: This is very much not ideal. We should be able to re-use the vector memory.
@@ -536,7 +539,7 @@ pub fn extract_lights(
     let mut spot_lights_values = Vec::with_capacity(*previous_spot_lights_len);
     for entity in global_point_lights.iter().copied() {
         if let Ok((spot_light, visible_entities, transform, visibility)) = spot_lights.get(entity) {
-            if !visibility.is_visible() {
+            if !visibility.get() {
                 continue;
             }
             // TODO: This is very much not ideal. We should be able to re-use the vector memory.
@@ -584,9 +587,10 @@ pub fn extract_lights(
         cascade_config,
         transform,
         visibility,
+        volumetric_light,
     ) in directional_lights.iter()
     {
-        if !visibility.is_visible() {
+        if !visibility.get() {
             continue;
         }
 
@@ -603,6 +607,7 @@ pub fn extract_lights(
                 shadow_normal_bias: directional_light.shadow_normal_bias * std::f32::consts::SQRT_2,
                 cascade_shadow_config: cascade_config.clone(),
                 cascades: cascades.cascades.clone(),
+                volumetric: volumetric_light.is_some(),
             },
             render_visible_entities,
         ));
@@ -992,6 +997,9 @@ pub fn prepare_lights(
         if light.shadows_enabled && (index < directional_shadow_enabled_count) {
             flags |= DirectionalLightFlags::SHADOWS_ENABLED;
         }
+        if light.volumetric {
+            flags |= DirectionalLightFlags::VOLUMETRIC;
+        }
 
         // convert from illuminance (lux) to candelas
         //
@@ -1,9 +1,10 @@
 use crate::{
-    environment_map, queue_shadow_view_bind_group, EnvironmentMapLight, FogMeta, GlobalLightMeta,
-    GpuFog, GpuLights, GpuPointLights, LightMeta, NotShadowCaster, NotShadowReceiver,
-    ShadowPipeline, ViewClusterBindings, ViewFogUniformOffset, ViewLightsUniformOffset,
+    environment_map, queue_shadow_view_bind_group, reflection_probe, ssr, EnvironmentMapLight,
+    FogMeta, GlobalLightMeta, GpuFog, GpuLights, GpuPointLights, LightMeta, NotShadowCaster,
+    NotShadowReceiver, ReflectionProbeMeta, ScreenSpaceReflectionsMeta, ShadowPipeline,
+    TransmittedShadowReceiver, ViewClusterBindings, ViewFogUniformOffset, ViewLightsUniformOffset,
     ViewShadowBindings, CLUSTERED_FORWARD_STORAGE_BUFFER_COUNT, MAX_CASCADES_PER_LIGHT,
-    MAX_DIRECTIONAL_LIGHTS,
+    MAX_DIRECTIONAL_LIGHTS, MAX_LIGHTS_PER_CLUSTER,
 };
 use bevy_app::{IntoSystemAppConfigs, Plugin};
 use bevy_asset::{load_internal_asset, Assets, Handle, HandleUntyped};
@@ -36,7 +37,7 @@ use bevy_render::{
         BevyDefault, DefaultImageSampler, FallbackImageCubemap, FallbackImagesDepth,
         FallbackImagesMsaa, GpuImage, Image, ImageSampler, TextureFormatPixelInfo,
     },
-    view::{ComputedVisibility, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms},
+    view::{ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms, ViewVisibility},
     Extract, ExtractSchedule, RenderApp, RenderSet,
 };
 use bevy_transform::components::GlobalTransform;
@@ -131,12 +132,13 @@ pub struct MeshUniform {
 bitflags::bitflags! {
     #[repr(transparent)]
     struct MeshFlags: u32 {
-        const SHADOW_RECEIVER            = (1 << 0);
+        const SHADOW_RECEIVER             = (1 << 0);
+        const TRANSMITTED_SHADOW_RECEIVER = (1 << 1);
         // Indicates the sign of the determinant of the 3x3 model matrix. If the sign is positive,
         // then the flag should be set, else it should not be set.
-        const SIGN_DETERMINANT_MODEL_3X3 = (1 << 31);
-        const NONE                       = 0;
-        const UNINITIALIZED              = 0xFFFF;
+        const SIGN_DETERMINANT_MODEL_3X3  = (1 << 31);
+        const NONE                        = 0;
+        const UNINITIALIZED               = 0xFFFF;
     }
 }
 
@@ -147,24 +149,27 @@ pub fn extract_meshes(
     meshes_query: Extract<
         Query<(
             Entity,
-            &ComputedVisibility,
+            &ViewVisibility,
             &GlobalTransform,
             &Handle<Mesh>,
             Option<With<NotShadowReceiver>>,
             Option<With<NotShadowCaster>>,
+            Option<With<TransmittedShadowReceiver>>,
         )>,
     >,
 ) {
     let mut caster_commands = Vec::with_capacity(*prev_caster_commands_len);
     let mut not_caster_commands = Vec::with_capacity(*prev_not_caster_commands_len);
-    let visible_meshes = meshes_query.iter().filter(|(_, vis, ..)| vis.is_visible());
+    let visible_meshes = meshes_query.iter().filter(|(_, vis, ..)| vis.get());
 
-    for (entity, _, transform, handle, not_receiver, not_caster) in visible_meshes {
+    for (entity, _, transform, handle, not_receiver, not_caster, transmitted_receiver) in
+        visible_meshes
+    {
         let transform = transform.compute_matrix();
-        let mut flags = if not_receiver.is_some() {
-            MeshFlags::empty()
-        } else {
-            MeshFlags::SHADOW_RECEIVER
+        let mut flags = match (not_receiver, transmitted_receiver) {
+            (Some(_), _) => MeshFlags::empty(),
+            (None, Some(_)) => MeshFlags::SHADOW_RECEIVER | MeshFlags::TRANSMITTED_SHADOW_RECEIVER,
+            (None, None) => MeshFlags::SHADOW_RECEIVER,
         };
         if Mat3A::from_mat4(transform).determinant().is_sign_positive() {
             flags |= MeshFlags::SIGN_DETERMINANT_MODEL_3X3;
@@ -234,7 +239,7 @@ pub fn extract_skinned_meshes(
     mut commands: Commands,
     mut previous_len: Local<usize>,
     mut uniform: ResMut<SkinnedMeshUniform>,
-    query: Extract<Query<(Entity, &ComputedVisibility, &SkinnedMesh)>>,
+    query: Extract<Query<(Entity, &ViewVisibility, &SkinnedMesh)>>,
     inverse_bindposes: Extract<Res<Assets<SkinnedMeshInverseBindposes>>>,
     joint_query: Extract<Query<&GlobalTransform>>,
 ) {
@@ -242,8 +247,8 @@ pub fn extract_skinned_meshes(
     let mut values = Vec::with_capacity(*previous_len);
     let mut last_start = 0;
 
-    for (entity, computed_visibility, skin) in &query {
-        if !computed_visibility.is_visible() {
+    for (entity, view_visibility, skin) in &query {
+        if !view_visibility.get() {
             continue;
         }
         // PERF: This can be expensive, can we move this to prepare?
@@ -270,6 +275,9 @@ pub struct MeshPipeline {
     pub view_layout_multisampled: BindGroupLayout,
     pub mesh_layout: BindGroupLayout,
     pub skinned_mesh_layout: BindGroupLayout,
+    /// The layout of the meshes drawn with [`IndirectDrawing`](crate::IndirectDrawing), `None`
+    /// without storage buffers.
+    pub indirect_mesh_layout: Option<BindGroupLayout>,
     // This dummy white texture is to be used in place of optional StandardMaterial textures
     pub dummy_white_gpu_image: GpuImage,
     pub clustered_forward_buffer_binding_type: BufferBindingType,
@@ -455,6 +463,15 @@ impl FromWorld for MeshPipeline {
                     },
                     count: None,
                 });
+
+                // Reflection probes
+                let reflection_probe_entries =
+                    reflection_probe::get_bind_group_layout_entries([18, 19, 20]);
+                entries.extend_from_slice(&reflection_probe_entries);
+
+                // Screen space reflections
+                let ssr_entries = ssr::get_bind_group_layout_entries([21, 22, 23]);
+                entries.extend_from_slice(&ssr_entries);
             }
 
             entries
@@ -505,6 +522,28 @@ impl FromWorld for MeshPipeline {
                 label: Some("skinned_mesh_layout"),
             });
 
+        // WebGL2 doesn't support storage buffers
+        let indirect_mesh_layout =
+            if render_device.limits().max_storage_buffers_per_shader_stage > 0 {
+                Some(
+                    render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                        entries: &[BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: ShaderStages::VERTEX | ShaderStages::FRAGMENT,
+                            ty: BindingType::Buffer {
+                                ty: BufferBindingType::Storage { read_only: true },
+                                has_dynamic_offset: false,
+                                min_binding_size: Some(MeshUniform::min_size()),
+                            },
+                            count: None,
+                        }],
+                        label: Some("indirect_mesh_layout"),
+                    }),
+                )
+            } else {
+                None
+            };
+
         // A 1x1x1 'all 1.0' texture to use as a dummy texture to use in place of optional StandardMaterial textures
         let dummy_white_gpu_image = {
             let image = Image::new_fill(
@@ -560,6 +599,7 @@ impl FromWorld for MeshPipeline {
             view_layout_multisampled,
             mesh_layout,
             skinned_mesh_layout,
+            indirect_mesh_layout,
             clustered_forward_buffer_binding_type,
             dummy_white_gpu_image,
         }
@@ -597,6 +637,9 @@ bitflags::bitflags! {
         const NORMAL_PREPASS                    = (1 << 4);
         const ALPHA_MASK                        = (1 << 5);
         const ENVIRONMENT_MAP                   = (1 << 6);
+        const INDIRECT                          = (1 << 7);
+        const REFLECTION_PROBES                 = (1 << 8);
+        const SCREEN_SPACE_REFLECTIONS          = (1 << 9);
         const BLEND_RESERVED_BITS               = Self::BLEND_MASK_BITS << Self::BLEND_SHIFT_BITS; // ← Bitmask reserving bits for the blend state
         const BLEND_OPAQUE                      = (0 << Self::BLEND_SHIFT_BITS);                   // ← Values are just sequential within the mask, and can range from 0 to 3
         const BLEND_PREMULTIPLIED_ALPHA         = (1 << Self::BLEND_SHIFT_BITS);                   //
@@ -612,6 +655,11 @@ bitflags::bitflags! {
         const TONEMAP_METHOD_SOMEWHAT_BORING_DISPLAY_TRANSFORM = 5 << Self::TONEMAP_METHOD_SHIFT_BITS;
         const TONEMAP_METHOD_TONY_MC_MAPFACE    = 6 << Self::TONEMAP_METHOD_SHIFT_BITS;
         const TONEMAP_METHOD_BLENDER_FILMIC     = 7 << Self::TONEMAP_METHOD_SHIFT_BITS;
+        const CLUSTER_DEBUG_RESERVED_BITS       = Self::CLUSTER_DEBUG_MASK_BITS << Self::CLUSTER_DEBUG_SHIFT_BITS;
+        const CLUSTER_DEBUG_NONE                = 0 << Self::CLUSTER_DEBUG_SHIFT_BITS;
+        const CLUSTER_DEBUG_Z_SLICES            = 1 << Self::CLUSTER_DEBUG_SHIFT_BITS;
+        const CLUSTER_DEBUG_LIGHT_COUNT         = 2 << Self::CLUSTER_DEBUG_SHIFT_BITS;
+        const CLUSTER_DEBUG_COHERENCY           = 3 << Self::CLUSTER_DEBUG_SHIFT_BITS;
     }
 }
 
@@ -627,6 +675,9 @@ impl MeshPipelineKey {
     const TONEMAP_METHOD_MASK_BITS: u32 = 0b111;
     const TONEMAP_METHOD_SHIFT_BITS: u32 =
         Self::BLEND_SHIFT_BITS - Self::TONEMAP_METHOD_MASK_BITS.count_ones();
+    const CLUSTER_DEBUG_MASK_BITS: u32 = 0b11;
+    const CLUSTER_DEBUG_SHIFT_BITS: u32 =
+        Self::TONEMAP_METHOD_SHIFT_BITS - Self::CLUSTER_DEBUG_MASK_BITS.count_ones();
 
     pub fn from_msaa_samples(msaa_samples: u32) -> Self {
         let msaa_bits =
@@ -728,7 +779,13 @@ impl SpecializedMeshPipeline for MeshPipeline {
             vertex_attributes.push(Mesh::ATTRIBUTE_JOINT_WEIGHT.at_shader_location(6));
             bind_group_layout.push(self.skinned_mesh_layout.clone());
         } else {
-            bind_group_layout.push(self.mesh_layout.clone());
+            match &self.indirect_mesh_layout {
+                Some(indirect_mesh_layout) if key.contains(MeshPipelineKey::INDIRECT) => {
+                    shader_defs.push("MESH_INDIRECT".into());
+                    bind_group_layout.push(indirect_mesh_layout.clone());
+                }
+                _ => bind_group_layout.push(self.mesh_layout.clone()),
+            }
         };
 
         let vertex_buffer_layout = layout.get_layout(&vertex_attributes)?;
@@ -800,6 +857,27 @@ impl SpecializedMeshPipeline for MeshPipeline {
             shader_defs.push("ENVIRONMENT_MAP".into());
         }
 
+        if key.contains(MeshPipelineKey::REFLECTION_PROBES) {
+            shader_defs.push("REFLECTION_PROBES".into());
+        }
+
+        if key.contains(MeshPipelineKey::SCREEN_SPACE_REFLECTIONS) {
+            shader_defs.push("SCREEN_SPACE_REFLECTIONS".into());
+        }
+
+        let cluster_debug = key.intersection(MeshPipelineKey::CLUSTER_DEBUG_RESERVED_BITS);
+        if cluster_debug == MeshPipelineKey::CLUSTER_DEBUG_Z_SLICES {
+            shader_defs.push("CLUSTERED_FORWARD_DEBUG_Z_SLICES".into());
+        } else if cluster_debug == MeshPipelineKey::CLUSTER_DEBUG_LIGHT_COUNT {
+            shader_defs.push("CLUSTERED_FORWARD_DEBUG_CLUSTER_LIGHT_COMPLEXITY".into());
+            shader_defs.push(ShaderDefVal::UInt(
+                "MAX_LIGHTS_PER_CLUSTER".to_string(),
+                MAX_LIGHTS_PER_CLUSTER as u32,
+            ));
+        } else if cluster_debug == MeshPipelineKey::CLUSTER_DEBUG_COHERENCY {
+            shader_defs.push("CLUSTERED_FORWARD_DEBUG_CLUSTER_COHERENCY".into());
+        }
+
         let format = if key.contains(MeshPipelineKey::HDR) {
             ViewTarget::TEXTURE_FORMAT_HDR
         } else {
@@ -964,6 +1042,7 @@ pub fn queue_mesh_view_bind_groups(
         Entity,
         &ViewShadowBindings,
         &ViewClusterBindings,
+        &Msaa,
         Option<&ViewPrepassTextures>,
         Option<&EnvironmentMapLight>,
         &Tonemapping,
@@ -972,9 +1051,13 @@ pub fn queue_mesh_view_bind_groups(
     mut fallback_images: FallbackImagesMsaa,
     mut fallback_depths: FallbackImagesDepth,
     fallback_cubemap: Res<FallbackImageCubemap>,
-    msaa: Res<Msaa>,
     globals_buffer: Res<GlobalsBuffer>,
     tonemapping_luts: Res<TonemappingLuts>,
+    // Neither is available on WebGL2
+    (reflection_probe_meta, ssr_meta): (
+        Option<Res<ReflectionProbeMeta>>,
+        Option<Res<ScreenSpaceReflectionsMeta>>,
+    ),
 ) {
     if let (
         Some(view_binding),
@@ -993,6 +1076,7 @@ pub fn queue_mesh_view_bind_groups(
             entity,
             view_shadow_bindings,
             view_cluster_bindings,
+            msaa,
             prepass_textures,
             environment_map,
             tonemapping,
@@ -1095,6 +1179,18 @@ pub fn queue_mesh_view_bind_groups(
                     binding: 17,
                     resource: BindingResource::TextureView(normal_view),
                 });
+
+                if let Some(reflection_probes) = reflection_probe_meta
+                    .as_deref()
+                    .and_then(|meta| reflection_probe::get_bindings(meta, [18, 19, 20]))
+                {
+                    entries.extend_from_slice(&reflection_probes);
+                }
+
+                if let Some(ssr_meta) = &ssr_meta {
+                    let ssr = ssr::get_bindings(ssr_meta, entity, [21, 22, 23]);
+                    entries.extend_from_slice(&ssr);
+                }
             }
 
             let view_bind_group = render_device.create_bind_group(&BindGroupDescriptor {
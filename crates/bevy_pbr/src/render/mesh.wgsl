@@ -24,6 +24,9 @@ struct Vertex {
     @location(5) joint_indices: vec4<u32>,
     @location(6) joint_weights: vec4<f32>,
 #endif
+#ifdef MESH_INDIRECT
+    @builtin(instance_index) instance_index: u32,
+#endif
 };
 
 struct VertexOutput {
@@ -35,6 +38,11 @@ struct VertexOutput {
 fn vertex(vertex: Vertex) -> VertexOutput {
     var out: VertexOutput;
 
+#ifdef MESH_INDIRECT
+    mesh = meshes[vertex.instance_index];
+    out.instance_index = vertex.instance_index;
+#endif
+
 #ifdef SKINNED
     var model = skin_model(vertex.joint_indices, vertex.joint_weights);
 #else
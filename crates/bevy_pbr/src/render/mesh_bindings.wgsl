@@ -2,8 +2,15 @@
 
 #import bevy_pbr::mesh_types
 
+#ifdef MESH_INDIRECT
+@group(2) @binding(0)
+var<storage> meshes: array<Mesh>;
+// Loaded from `meshes` by the entry points, at the instance index of the draw
+var<private> mesh: Mesh;
+#else
 @group(2) @binding(0)
 var<uniform> mesh: Mesh;
+#endif
 #ifdef SKINNED
 @group(2) @binding(1)
 var<uniform> joint_matrices: SkinnedMesh;
@@ -69,3 +69,19 @@ var depth_prepass_texture: texture_depth_2d;
 @group(0) @binding(17)
 var normal_prepass_texture: texture_2d<f32>;
 #endif
+
+#ifndef NO_ARRAY_TEXTURES_SUPPORT
+@group(0) @binding(18)
+var<uniform> reflection_probes: ReflectionProbes;
+@group(0) @binding(19)
+var reflection_probe_diffuse_textures: texture_cube_array<f32>;
+@group(0) @binding(20)
+var reflection_probe_specular_textures: texture_cube_array<f32>;
+#endif
+
+@group(0) @binding(21)
+var<uniform> ssr: ScreenSpaceReflections;
+@group(0) @binding(22)
+var ssr_history_texture: texture_2d<f32>;
+@group(0) @binding(23)
+var ssr_history_sampler: sampler;
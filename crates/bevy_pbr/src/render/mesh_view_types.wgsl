@@ -39,6 +39,7 @@ struct DirectionalLight {
 };
 
 const DIRECTIONAL_LIGHT_FLAGS_SHADOWS_ENABLED_BIT: u32 = 1u;
+const DIRECTIONAL_LIGHT_FLAGS_VOLUMETRIC_BIT: u32 = 2u;
 
 struct Lights {
     // NOTE: this array size must be kept in sync with the constants defined in bevy_pbr/src/render/light.rs
@@ -111,3 +112,34 @@ struct ClusterOffsetsAndCounts {
     data: array<vec4<u32>, 1024u>,
 };
 #endif
+
+struct ReflectionProbe {
+    transform: mat4x4<f32>,
+    inverse_transform: mat4x4<f32>,
+    // The fraction of the size of the volume over which the probe fades out towards its faces
+    falloff: vec3<f32>,
+    // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+    flags: u32,
+};
+
+const REFLECTION_PROBE_FLAGS_PARALLAX_CORRECTION_BIT: u32 = 1u;
+
+struct ReflectionProbes {
+    // NOTE: this array size must be kept in sync with MAX_REFLECTION_PROBES in bevy_pbr/src/reflection_probe/mod.rs
+    probes: array<ReflectionProbe, 8u>,
+    count: u32,
+    smallest_specular_mip_level: u32,
+};
+
+struct ScreenSpaceReflections {
+    previous_view_proj: mat4x4<f32>,
+    max_roughness: f32,
+    max_distance: f32,
+    max_steps: u32,
+    thickness: f32,
+    history_mip_levels: u32,
+    // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+    flags: u32,
+};
+
+const SSR_FLAGS_HISTORY_VALID_BIT: u32 = 1u;
@@ -1,7 +1,9 @@
 mod fog;
+mod indirect;
 mod light;
 mod mesh;
 
 pub use fog::*;
+pub use indirect::*;
 pub use light::*;
 pub use mesh::*;
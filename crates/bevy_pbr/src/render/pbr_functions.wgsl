@@ -6,6 +6,18 @@
 
 #ifdef ENVIRONMENT_MAP
 #import bevy_pbr::environment_map
+#else ifdef REFLECTION_PROBES
+#import bevy_pbr::environment_map
+#else ifdef SCREEN_SPACE_REFLECTIONS
+#import bevy_pbr::environment_map
+#endif
+
+#ifdef REFLECTION_PROBES
+#import bevy_pbr::reflection_probe
+#endif
+
+#ifdef SCREEN_SPACE_REFLECTIONS
+#import bevy_pbr::ssr
 #endif
 
 fn alpha_discard(material: StandardMaterial, output_color: vec4<f32>) -> vec4<f32> {
@@ -184,8 +196,14 @@ fn pbr(
     let reflectance = in.material.reflectance;
     let F0 = 0.16 * reflectance * reflectance * (1.0 - metallic) + output_color.rgb * metallic;
 
-    // Diffuse strength inversely related to metallicity
-    let diffuse_color = output_color.rgb * (1.0 - metallic);
+    // Diffuse strength inversely related to metallicity, the transmitted light isn't diffused
+    let specular_transmission = in.material.specular_transmission;
+    let diffuse_color = output_color.rgb * (1.0 - metallic) * (1.0 - specular_transmission);
+
+    let clearcoat = vec2<f32>(
+        in.material.clearcoat,
+        perceptualRoughnessToRoughness(in.material.clearcoat_perceptual_roughness)
+    );
 
     let R = reflect(-in.V, in.N);
 
@@ -210,8 +228,18 @@ fn pbr(
                 && (point_lights.data[light_id].flags & POINT_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
             shadow = fetch_point_shadow(light_id, in.world_position, in.world_normal);
         }
-        let light_contrib = point_light(in.world_position.xyz, light_id, roughness, NdotV, in.N, in.V, R, F0, f_ab, diffuse_color);
+        let light_contrib = point_light(in.world_position.xyz, light_id, roughness, NdotV, in.N, in.V, R, F0, f_ab, diffuse_color, clearcoat);
         direct_light += light_contrib * shadow;
+
+        // The light from behind is diffused through the surface, shadowed on its back side
+        if ((in.flags & MESH_FLAGS_TRANSMITTED_SHADOW_RECEIVER_BIT) != 0u) {
+            var transmitted_shadow: f32 = 1.0;
+            if ((point_lights.data[light_id].flags & POINT_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
+                transmitted_shadow = fetch_point_shadow(light_id, in.world_position, -in.world_normal);
+            }
+            let transmitted_light = point_light(in.world_position.xyz, light_id, roughness, NdotV, -in.N, in.V, R, vec3<f32>(0.0), f_ab, diffuse_color, vec2<f32>(0.0));
+            direct_light += transmitted_light * transmitted_shadow;
+        }
     }
 
     // Spot lights (direct)
@@ -222,8 +250,17 @@ fn pbr(
                 && (point_lights.data[light_id].flags & POINT_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
             shadow = fetch_spot_shadow(light_id, in.world_position, in.world_normal);
         }
-        let light_contrib = spot_light(in.world_position.xyz, light_id, roughness, NdotV, in.N, in.V, R, F0, f_ab, diffuse_color);
+        let light_contrib = spot_light(in.world_position.xyz, light_id, roughness, NdotV, in.N, in.V, R, F0, f_ab, diffuse_color, clearcoat);
         direct_light += light_contrib * shadow;
+
+        if ((in.flags & MESH_FLAGS_TRANSMITTED_SHADOW_RECEIVER_BIT) != 0u) {
+            var transmitted_shadow: f32 = 1.0;
+            if ((point_lights.data[light_id].flags & POINT_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
+                transmitted_shadow = fetch_spot_shadow(light_id, in.world_position, -in.world_normal);
+            }
+            let transmitted_light = spot_light(in.world_position.xyz, light_id, roughness, NdotV, -in.N, in.V, R, vec3<f32>(0.0), f_ab, diffuse_color, vec2<f32>(0.0));
+            direct_light += transmitted_light * transmitted_shadow;
+        }
     }
 
     // Directional lights (direct)
@@ -234,28 +271,52 @@ fn pbr(
                 && (lights.directional_lights[i].flags & DIRECTIONAL_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
             shadow = fetch_directional_shadow(i, in.world_position, in.world_normal, view_z);
         }
-        var light_contrib = directional_light(i, roughness, NdotV, in.N, in.V, R, F0, f_ab, diffuse_color);
+        var light_contrib = directional_light(i, roughness, NdotV, in.N, in.V, R, F0, f_ab, diffuse_color, clearcoat);
 #ifdef DIRECTIONAL_LIGHT_SHADOW_MAP_DEBUG_CASCADES
         light_contrib = cascade_debug_visualization(light_contrib, i, view_z);
 #endif
         direct_light += light_contrib * shadow;
+
+        if ((in.flags & MESH_FLAGS_TRANSMITTED_SHADOW_RECEIVER_BIT) != 0u) {
+            var transmitted_shadow: f32 = 1.0;
+            if ((lights.directional_lights[i].flags & DIRECTIONAL_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
+                transmitted_shadow = fetch_directional_shadow(i, in.world_position, -in.world_normal, view_z);
+            }
+            let transmitted_light = directional_light(i, roughness, NdotV, -in.N, in.V, R, vec3<f32>(0.0), f_ab, diffuse_color, vec2<f32>(0.0));
+            direct_light += transmitted_light * transmitted_shadow;
+        }
     }
 
     // Ambient light (indirect)
     var indirect_light = ambient_light(in.world_position, in.N, in.V, NdotV, diffuse_color, F0, perceptual_roughness, occlusion);
 
-    // Environment map light (indirect)
-#ifdef ENVIRONMENT_MAP
+    // Environment map and reflection probe light (indirect)
+    var specular_environment_light = vec3<f32>(0.0);
+#ifdef REFLECTION_PROBES
+    let environment_light = reflection_probe_light(in.world_position.xyz, perceptual_roughness, roughness, diffuse_color, NdotV, f_ab, in.N, R, F0);
+    indirect_light += environment_light.diffuse * occlusion;
+    specular_environment_light = environment_light.specular;
+#else ifdef ENVIRONMENT_MAP
     let environment_light = environment_map_light(perceptual_roughness, roughness, diffuse_color, NdotV, f_ab, in.N, R, F0);
-    indirect_light += (environment_light.diffuse * occlusion) + environment_light.specular;
+    indirect_light += environment_light.diffuse * occlusion;
+    specular_environment_light = environment_light.specular;
+#endif
+
+    // Screen space reflections (indirect), replacing the specular environment light where they
+    // hit the scene
+#ifdef SCREEN_SPACE_REFLECTIONS
+    let reflection = screen_space_reflection(in.world_position.xyz, in.frag_coord.xy, R, perceptual_roughness);
+    let reflected_light = environment_light(vec3<f32>(0.0), reflection.rgb, roughness, diffuse_color, NdotV, f_ab, F0);
+    specular_environment_light = mix(specular_environment_light, reflected_light.specular, reflection.a);
 #endif
+    indirect_light += specular_environment_light;
 
     let emissive_light = emissive.rgb * output_color.a;
 
-    // Total light
+    // Total light, letting the transmitted light of what's behind through when blending
     output_color = vec4<f32>(
         direct_light + indirect_light + emissive_light,
-        output_color.a
+        output_color.a * (1.0 - specular_transmission)
     );
 
     output_color = cluster_debug_visualization(
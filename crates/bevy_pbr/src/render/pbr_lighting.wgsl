@@ -124,6 +124,28 @@ fn specular(
     return Fr;
 }
 
+// Clear coat BRDF, the specular lobe of a thin layer over the material
+// https://google.github.io/filament/Filament.html#materialsystem/clearcoatmodel
+
+// Kelemen visibility, cheaper than V_SmithGGXCorrelated for the layer
+fn V_Kelemen(LoH: f32) -> f32 {
+    return 0.25 / max(LoH * LoH, 0.0001);
+}
+
+// Returns the specular light of the clear coat in x, and its Fresnel term attenuating the light
+// of the base material in y, for `clearcoat` holding the strength and the (non-perceptual)
+// roughness of the layer
+fn clearcoat_specular(clearcoat: vec2<f32>, h: vec3<f32>, NoH: f32, LoH: f32) -> vec2<f32> {
+    if clearcoat.x <= 0.0 {
+        return vec2<f32>(0.0);
+    }
+    let D = D_GGX(clearcoat.y, NoH, h);
+    let V = V_Kelemen(LoH);
+    // the f0 of the usual 1.5 index of refraction of the coating
+    let F = F_Schlick(0.04, 1.0, LoH) * clearcoat.x;
+    return vec2<f32>(D * V * F, F);
+}
+
 // Diffuse BRDF
 // https://google.github.io/filament/Filament.html#materialsystem/diffusebrdf
 // fd(v,l) = σ/π * 1 / { |n⋅v||n⋅l| } ∫Ω D(m,α) G(v,l,m) (v⋅m) (l⋅m) dm
@@ -178,7 +200,8 @@ fn point_light(
     R: vec3<f32>,
     F0: vec3<f32>,
     f_ab: vec2<f32>,
-    diffuseColor: vec3<f32>
+    diffuseColor: vec3<f32>,
+    clearcoat: vec2<f32>
 ) -> vec3<f32> {
     let light = &point_lights.data[light_id];
     let light_to_frag = (*light).position_radius.xyz - world_position.xyz;
@@ -212,6 +235,7 @@ fn point_light(
     LoH = saturate(dot(L, H));
 
     let diffuse = diffuseColor * Fd_Burley(roughness, NdotV, NoL, LoH);
+    let coat = clearcoat_specular(clearcoat, H, NoH, LoH);
 
     // See https://google.github.io/filament/Filament.html#mjx-eqn-pointLightLuminanceEquation
     // Lout = f(v,l) Φ / { 4 π d^2 }⟨n⋅l⟩
@@ -226,7 +250,7 @@ fn point_light(
 
     // NOTE: (*light).color.rgb is premultiplied with (*light).intensity / 4 π (which would be the luminous intensity) on the CPU
 
-    return ((diffuse + specular_light) * (*light).color_inverse_square_range.rgb) * (rangeAttenuation * NoL);
+    return (((diffuse + specular_light) * (1.0 - coat.y) + coat.x) * (*light).color_inverse_square_range.rgb) * (rangeAttenuation * NoL);
 }
 
 fn spot_light(
@@ -239,10 +263,11 @@ fn spot_light(
     R: vec3<f32>,
     F0: vec3<f32>,
     f_ab: vec2<f32>,
-    diffuseColor: vec3<f32>
+    diffuseColor: vec3<f32>,
+    clearcoat: vec2<f32>
 ) -> vec3<f32> {
     // reuse the point light calculations
-    let point_light = point_light(world_position, light_id, roughness, NdotV, N, V, R, F0, f_ab, diffuseColor);
+    let point_light = point_light(world_position, light_id, roughness, NdotV, N, V, R, F0, f_ab, diffuseColor, clearcoat);
 
     let light = &point_lights.data[light_id];
 
@@ -264,7 +289,7 @@ fn spot_light(
     return point_light * spot_attenuation;
 }
 
-fn directional_light(light_id: u32, roughness: f32, NdotV: f32, normal: vec3<f32>, view: vec3<f32>, R: vec3<f32>, F0: vec3<f32>, f_ab: vec2<f32>, diffuseColor: vec3<f32>) -> vec3<f32> {
+fn directional_light(light_id: u32, roughness: f32, NdotV: f32, normal: vec3<f32>, view: vec3<f32>, R: vec3<f32>, F0: vec3<f32>, f_ab: vec2<f32>, diffuseColor: vec3<f32>, clearcoat: vec2<f32>) -> vec3<f32> {
     let light = &lights.directional_lights[light_id];
 
     let incident_light = (*light).direction_to_light.xyz;
@@ -277,6 +302,7 @@ fn directional_light(light_id: u32, roughness: f32, NdotV: f32, normal: vec3<f32
     let diffuse = diffuseColor * Fd_Burley(roughness, NdotV, NoL, LoH);
     let specularIntensity = 1.0;
     let specular_light = specular(F0, roughness, half_vector, NdotV, NoL, NoH, LoH, specularIntensity, f_ab);
+    let coat = clearcoat_specular(clearcoat, half_vector, NoH, LoH);
 
-    return (specular_light + diffuse) * (*light).color.rgb * NoL;
+    return ((specular_light + diffuse) * (1.0 - coat.y) + coat.x) * (*light).color.rgb * NoL;
 }
@@ -14,16 +14,32 @@ struct FragmentInput {
     @location(2) world_tangent: vec4<f32>,
 #endif // VERTEX_TANGENTS
 #endif // NORMAL_PREPASS
+#ifdef VERTEX_COLORS
+    @location(3) color: vec4<f32>,
+#endif // VERTEX_COLORS
 };
 
+#ifdef VERTEX_UVS
+fn prepass_uv(in: FragmentInput) -> vec2<f32> {
+    if (material.flags & STANDARD_MATERIAL_FLAGS_FLIPBOOK_BIT) != 0u {
+        return flipbook_uv(standard_material_flipbook(material), in.uv, globals.time);
+    }
+    return in.uv;
+}
+#endif // VERTEX_UVS
+
 // We can use a simplified version of alpha_discard() here since we only need to handle the alpha_cutoff
 fn prepass_alpha_discard(in: FragmentInput) {
 #ifdef ALPHA_MASK
     var output_color: vec4<f32> = material.base_color;
 
+#ifdef VERTEX_COLORS
+    output_color = output_color * in.color;
+#endif // VERTEX_COLORS
+
 #ifdef VERTEX_UVS
     if (material.flags & STANDARD_MATERIAL_FLAGS_BASE_COLOR_TEXTURE_BIT) != 0u {
-        output_color = output_color * textureSample(base_color_texture, base_color_sampler, in.uv);
+        output_color = output_color * textureSample(base_color_texture, base_color_sampler, prepass_uv(in));
     }
 #endif // VERTEX_UVS
 
@@ -56,7 +72,7 @@ fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
 #endif // STANDARDMATERIAL_NORMAL_MAP
 #endif // VERTEX_TANGENTS
 #ifdef VERTEX_UVS
-            in.uv,
+            prepass_uv(in),
 #endif // VERTEX_UVS
         );
 
@@ -1,5 +1,7 @@
 #define_import_path bevy_pbr::pbr_types
 
+#import bevy_render::flipbook
+
 struct StandardMaterial {
     base_color: vec4<f32>,
     emissive: vec4<f32>,
@@ -9,6 +11,15 @@ struct StandardMaterial {
     // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
     flags: u32,
     alpha_cutoff: f32,
+    flipbook_columns: u32,
+    flipbook_rows: u32,
+    flipbook_frame_count: u32,
+    flipbook_mode: u32,
+    flipbook_fps: f32,
+    flipbook_start_time: f32,
+    clearcoat: f32,
+    clearcoat_perceptual_roughness: f32,
+    specular_transmission: f32,
 };
 
 const STANDARD_MATERIAL_FLAGS_BASE_COLOR_TEXTURE_BIT: u32         = 1u;
@@ -20,6 +31,7 @@ const STANDARD_MATERIAL_FLAGS_UNLIT_BIT: u32                      = 32u;
 const STANDARD_MATERIAL_FLAGS_TWO_COMPONENT_NORMAL_MAP: u32       = 64u;
 const STANDARD_MATERIAL_FLAGS_FLIP_NORMAL_MAP_Y: u32              = 128u;
 const STANDARD_MATERIAL_FLAGS_FOG_ENABLED_BIT: u32                = 256u;
+const STANDARD_MATERIAL_FLAGS_FLIPBOOK_BIT: u32                   = 512u;
 const STANDARD_MATERIAL_FLAGS_ALPHA_MODE_RESERVED_BITS: u32       = 3758096384u; // (0b111u32 << 29)
 const STANDARD_MATERIAL_FLAGS_ALPHA_MODE_OPAQUE: u32              = 0u;          // (0u32 << 29)
 const STANDARD_MATERIAL_FLAGS_ALPHA_MODE_MASK: u32                = 536870912u;  // (1u32 << 29)
@@ -42,6 +54,21 @@ fn standard_material_new() -> StandardMaterial {
     material.reflectance = 0.5;
     material.flags = STANDARD_MATERIAL_FLAGS_ALPHA_MODE_OPAQUE;
     material.alpha_cutoff = 0.5;
+    material.clearcoat = 0.0;
+    material.clearcoat_perceptual_roughness = 0.5;
+    material.specular_transmission = 0.0;
 
     return material;
 }
+
+// The flipbook animating the textures of the material, when STANDARD_MATERIAL_FLAGS_FLIPBOOK_BIT is set
+fn standard_material_flipbook(material: StandardMaterial) -> Flipbook {
+    return Flipbook(
+        material.flipbook_columns,
+        material.flipbook_rows,
+        material.flipbook_frame_count,
+        material.flipbook_mode,
+        material.flipbook_fps,
+        material.flipbook_start_time,
+    );
+}
@@ -10,19 +10,32 @@ var<uniform> joint_matrices: SkinnedMesh;
 #import bevy_pbr::skinning
 #endif
 
+struct WireframeMaterial {
+    color: vec4<f32>,
+};
+
+@group(2) @binding(0)
+var<uniform> material: WireframeMaterial;
+
 // NOTE: Bindings must come before functions that use them!
 #import bevy_pbr::mesh_functions
 
 struct Vertex {
     @location(0) position: vec3<f32>,
 #ifdef SKINNED
-    @location(4) joint_indexes: vec4<u32>,
-    @location(5) joint_weights: vec4<f32>,
+    @location(5) joint_indexes: vec4<u32>,
+    @location(6) joint_weights: vec4<f32>,
+#endif
+#ifdef WIREFRAME_BARYCENTRIC
+    @location(7) barycentric: vec3<f32>,
 #endif
 };
 
 struct VertexOutput {
     @builtin(position) clip_position: vec4<f32>,
+#ifdef WIREFRAME_BARYCENTRIC
+    @location(0) barycentric: vec3<f32>,
+#endif
 };
 
 @vertex
@@ -35,10 +48,21 @@ fn vertex(vertex: Vertex) -> VertexOutput {
 
     var out: VertexOutput;
     out.clip_position = mesh_position_local_to_clip(model, vec4<f32>(vertex.position, 1.0));
+#ifdef WIREFRAME_BARYCENTRIC
+    out.barycentric = vertex.barycentric;
+#endif
     return out;
 }
 
 @fragment
-fn fragment() -> @location(0) vec4<f32> {
-    return vec4<f32>(1.0, 1.0, 1.0, 1.0);
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+#ifdef WIREFRAME_BARYCENTRIC
+    // Only keep the pixels less than a pixel away from an edge of the triangle, where one of the
+    // barycentric coordinates is 0
+    let distance = in.barycentric / fwidth(in.barycentric);
+    if min(min(distance.x, distance.y), distance.z) > 1.0 {
+        discard;
+    }
+#endif
+    return material.color;
 }
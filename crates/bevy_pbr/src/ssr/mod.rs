@@ -0,0 +1,539 @@
+use bevy_app::{App, Plugin};
+use bevy_asset::{load_internal_asset, HandleUntyped};
+use bevy_core_pipeline::{
+    core_3d::{self, Camera3d},
+    fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    prepass::ViewPrepassTextures,
+};
+use bevy_ecs::{prelude::*, query::QueryState};
+use bevy_math::{Mat4, UVec2};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect, TypeUuid};
+use bevy_render::{
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
+    render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext, SlotInfo, SlotType},
+    render_resource::*,
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    view::{ExtractedView, ViewTarget},
+    RenderApp, RenderSet,
+};
+use bevy_utils::{default, tracing::warn, HashMap, HashSet};
+
+use crate::draw_3d_graph;
+
+pub const SSR_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 13418156742351519183);
+const SSR_HISTORY_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 2840500704365971066);
+
+/// The number of mip levels of the previous frame kept for reflections off rough surfaces.
+const SSR_HISTORY_MIP_LEVELS: u32 = 6;
+
+/// Adds support for [`ScreenSpaceReflectionsSettings`].
+pub struct ScreenSpaceReflectionsPlugin;
+
+impl Plugin for ScreenSpaceReflectionsPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, SSR_SHADER_HANDLE, "ssr.wgsl", Shader::from_wgsl);
+        load_internal_asset!(
+            app,
+            SSR_HISTORY_SHADER_HANDLE,
+            "ssr_history.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<ScreenSpaceReflectionsSettings>()
+            .add_plugin(ExtractComponentPlugin::<ScreenSpaceReflectionsSettings>::default());
+
+        // The prepass textures aren't available to the main pass on WebGL2
+        if cfg!(feature = "webgl") {
+            return;
+        }
+
+        let render_app = match app.get_sub_app_mut(RenderApp) {
+            Ok(render_app) => render_app,
+            Err(_) => return,
+        };
+
+        render_app
+            .init_resource::<ScreenSpaceReflectionsPipeline>()
+            .init_resource::<ScreenSpaceReflectionsMeta>()
+            .add_system(prepare_screen_space_reflections.in_set(RenderSet::Prepare));
+
+        let ssr_node = ScreenSpaceReflectionsNode::new(&mut render_app.world);
+        let mut graph = render_app.world.resource_mut::<RenderGraph>();
+        let draw_3d_graph = graph.get_sub_graph_mut(core_3d::graph::NAME).unwrap();
+        draw_3d_graph.add_node(draw_3d_graph::node::SCREEN_SPACE_REFLECTIONS, ssr_node);
+        draw_3d_graph.add_slot_edge(
+            draw_3d_graph.input_node().id,
+            core_3d::graph::input::VIEW_ENTITY,
+            draw_3d_graph::node::SCREEN_SPACE_REFLECTIONS,
+            ScreenSpaceReflectionsNode::IN_VIEW,
+        );
+        // MAIN_PASS -> SCREEN_SPACE_REFLECTIONS -> BLOOM -> TONEMAPPING, so that the history
+        // holds the lit scene before any post processing
+        draw_3d_graph.add_node_edge(
+            core_3d::graph::node::MAIN_PASS,
+            draw_3d_graph::node::SCREEN_SPACE_REFLECTIONS,
+        );
+        if draw_3d_graph
+            .get_node_state(core_3d::graph::node::BLOOM)
+            .is_ok()
+        {
+            draw_3d_graph.add_node_edge(
+                draw_3d_graph::node::SCREEN_SPACE_REFLECTIONS,
+                core_3d::graph::node::BLOOM,
+            );
+        }
+        draw_3d_graph.add_node_edge(
+            draw_3d_graph::node::SCREEN_SPACE_REFLECTIONS,
+            core_3d::graph::node::TONEMAPPING,
+        );
+    }
+}
+
+/// Adds screen space reflections to a 3D camera.
+///
+/// Reflections are found by marching rays through the depth of the scene, and coloured with
+/// the previous frame, so that they pick up every lit surface visible on screen. Where a ray
+/// leaves the screen or hits nothing, reflections fall back to the environment lighting of
+/// the camera, if any.
+///
+/// This requires the camera to be [`hdr`](bevy_render::camera::Camera::hdr) and to have both
+/// a [`DepthPrepass`](bevy_core_pipeline::prepass::DepthPrepass) and a
+/// [`NormalPrepass`](bevy_core_pipeline::prepass::NormalPrepass). Screen space reflections
+/// are not supported on `WebGL2`.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component, Default)]
+pub struct ScreenSpaceReflectionsSettings {
+    /// Surfaces with a perceptual roughness above this only reflect the environment
+    /// (default: 0.4).
+    ///
+    /// Reflections fade out and get blurrier as the roughness approaches this value.
+    pub max_roughness: f32,
+
+    /// The length of the reflected rays, in world units (default: 20.0).
+    pub max_distance: f32,
+
+    /// The number of steps taken along each reflected ray (default: 32).
+    ///
+    /// More steps find thinner surfaces and more precise hits, at a higher cost.
+    pub max_steps: u32,
+
+    /// How far behind a surface a ray can be while still hitting it, in world units
+    /// (default: 0.25).
+    ///
+    /// Smaller values avoid reflecting objects through the gaps behind them, larger values
+    /// avoid rays passing through thin objects between two steps.
+    pub thickness: f32,
+}
+
+impl Default for ScreenSpaceReflectionsSettings {
+    fn default() -> Self {
+        Self {
+            max_roughness: 0.4,
+            max_distance: 20.0,
+            max_steps: 32,
+            thickness: 0.25,
+        }
+    }
+}
+
+impl ExtractComponent for ScreenSpaceReflectionsSettings {
+    type Query = &'static Self;
+    type Filter = With<Camera3d>;
+    type Out = Self;
+
+    fn extract_component(item: bevy_ecs::query::QueryItem<'_, Self::Query>) -> Option<Self::Out> {
+        Some(item.clone())
+    }
+}
+
+const SSR_FLAGS_HISTORY_VALID_BIT: u32 = 1;
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuScreenSpaceReflections {
+    previous_view_proj: Mat4,
+    max_roughness: f32,
+    max_distance: f32,
+    max_steps: u32,
+    thickness: f32,
+    history_mip_levels: u32,
+    flags: u32,
+}
+
+/// The previous frames of a view using screen space reflections, which persist from one
+/// frame to the next.
+struct ViewScreenSpaceReflectionsHistory {
+    size: UVec2,
+    /// One view per mip level, for the passes writing the history.
+    mip_views: Vec<TextureView>,
+    /// A view of every mip level, sampled by the main pass.
+    view: TextureView,
+    previous_view_proj: Mat4,
+    uniform: UniformBuffer<GpuScreenSpaceReflections>,
+}
+
+#[derive(Resource)]
+pub struct ScreenSpaceReflectionsMeta {
+    histories: HashMap<Entity, ViewScreenSpaceReflectionsHistory>,
+    fallback_uniform: UniformBuffer<GpuScreenSpaceReflections>,
+    fallback_history_view: TextureView,
+    sampler: Sampler,
+}
+
+impl FromWorld for ScreenSpaceReflectionsMeta {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+        let render_queue = world.resource::<RenderQueue>();
+
+        let mut fallback_uniform = UniformBuffer::default();
+        fallback_uniform.write_buffer(render_device, render_queue);
+
+        let fallback_history_texture = render_device.create_texture(&TextureDescriptor {
+            label: Some("ssr_fallback_history_texture"),
+            size: Extent3d {
+                width: 1,
+                height: 1,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: ViewTarget::TEXTURE_FORMAT_HDR,
+            usage: TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        });
+
+        Self {
+            histories: HashMap::default(),
+            fallback_uniform,
+            fallback_history_view: fallback_history_texture
+                .create_view(&TextureViewDescriptor::default()),
+            sampler: render_device.create_sampler(&SamplerDescriptor {
+                label: Some("ssr_history_sampler"),
+                address_mode_u: AddressMode::ClampToEdge,
+                address_mode_v: AddressMode::ClampToEdge,
+                mag_filter: FilterMode::Linear,
+                min_filter: FilterMode::Linear,
+                mipmap_filter: FilterMode::Linear,
+                ..default()
+            }),
+        }
+    }
+}
+
+/// Marks views rendering screen space reflections this frame.
+#[derive(Component)]
+pub struct ViewScreenSpaceReflections;
+
+#[derive(Resource)]
+pub struct ScreenSpaceReflectionsPipeline {
+    history_layout: BindGroupLayout,
+    history_pipeline: CachedRenderPipelineId,
+}
+
+impl FromWorld for ScreenSpaceReflectionsPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let history_layout =
+            world
+                .resource::<RenderDevice>()
+                .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: Some("ssr_history_bind_group_layout"),
+                    entries: &[
+                        BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Texture {
+                                sample_type: TextureSampleType::Float { filterable: true },
+                                view_dimension: TextureViewDimension::D2,
+                                multisampled: false,
+                            },
+                            count: None,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                            count: None,
+                        },
+                    ],
+                });
+
+        let history_pipeline =
+            world
+                .resource::<PipelineCache>()
+                .queue_render_pipeline(RenderPipelineDescriptor {
+                    label: Some("ssr_history_pipeline".into()),
+                    layout: vec![history_layout.clone()],
+                    vertex: fullscreen_shader_vertex_state(),
+                    fragment: Some(FragmentState {
+                        shader: SSR_HISTORY_SHADER_HANDLE.typed::<Shader>(),
+                        shader_defs: vec![],
+                        entry_point: "downsample".into(),
+                        targets: vec![Some(ColorTargetState {
+                            format: ViewTarget::TEXTURE_FORMAT_HDR,
+                            blend: None,
+                            write_mask: ColorWrites::ALL,
+                        })],
+                    }),
+                    primitive: PrimitiveState::default(),
+                    depth_stencil: None,
+                    multisample: MultisampleState::default(),
+                    push_constant_ranges: Vec::new(),
+                });
+
+        Self {
+            history_layout,
+            history_pipeline,
+        }
+    }
+}
+
+pub fn prepare_screen_space_reflections(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut meta: ResMut<ScreenSpaceReflectionsMeta>,
+    views: Query<(
+        Entity,
+        &ExtractedView,
+        &ScreenSpaceReflectionsSettings,
+        Option<&ViewPrepassTextures>,
+    )>,
+    mut unsupported_view_warning_emitted: Local<bool>,
+) {
+    let mut live_views = HashSet::new();
+    for (entity, view, settings, prepass_textures) in &views {
+        let prepass_size = match prepass_textures {
+            Some(ViewPrepassTextures {
+                depth: Some(_),
+                normal: Some(_),
+                size,
+            }) if view.hdr => UVec2::new(size.width, size.height),
+            _ => {
+                if !*unsupported_view_warning_emitted {
+                    warn!(
+                        "Screen space reflections require an HDR camera with both a depth and \
+                        a normal prepass. They will be disabled for cameras without these."
+                    );
+                    *unsupported_view_warning_emitted = true;
+                }
+                continue;
+            }
+        };
+        live_views.insert(entity);
+
+        let history = meta.histories.get(&entity);
+        let valid = history.map_or(false, |history| history.size == prepass_size);
+        if !valid {
+            let mip_level_count =
+                SSR_HISTORY_MIP_LEVELS.min(32 - prepass_size.x.max(prepass_size.y).leading_zeros());
+            let texture = render_device.create_texture(&TextureDescriptor {
+                label: Some("ssr_history_texture"),
+                size: Extent3d {
+                    width: prepass_size.x,
+                    height: prepass_size.y,
+                    depth_or_array_layers: 1,
+                },
+                mip_level_count,
+                sample_count: 1,
+                dimension: TextureDimension::D2,
+                format: ViewTarget::TEXTURE_FORMAT_HDR,
+                usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+                view_formats: &[],
+            });
+            let mip_views = (0..mip_level_count)
+                .map(|mip_level| {
+                    texture.create_view(&TextureViewDescriptor {
+                        label: Some("ssr_history_mip_view"),
+                        base_mip_level: mip_level,
+                        mip_level_count: std::num::NonZeroU32::new(1),
+                        ..default()
+                    })
+                })
+                .collect();
+            meta.histories.insert(
+                entity,
+                ViewScreenSpaceReflectionsHistory {
+                    size: prepass_size,
+                    mip_views,
+                    view: texture.create_view(&TextureViewDescriptor::default()),
+                    previous_view_proj: Mat4::IDENTITY,
+                    uniform: UniformBuffer::default(),
+                },
+            );
+        }
+
+        let history = meta.histories.get_mut(&entity).unwrap();
+        let view_proj = view.projection * view.transform.compute_matrix().inverse();
+        history.uniform.set(GpuScreenSpaceReflections {
+            previous_view_proj: history.previous_view_proj,
+            max_roughness: settings.max_roughness,
+            max_distance: settings.max_distance,
+            max_steps: settings.max_steps,
+            thickness: settings.thickness,
+            history_mip_levels: history.mip_views.len() as u32,
+            // The history is only filled in at the end of the first frame
+            flags: if valid {
+                SSR_FLAGS_HISTORY_VALID_BIT
+            } else {
+                0
+            },
+        });
+        history.uniform.write_buffer(&render_device, &render_queue);
+        history.previous_view_proj = view_proj;
+
+        commands.entity(entity).insert(ViewScreenSpaceReflections);
+    }
+
+    meta.histories
+        .retain(|entity, _| live_views.contains(entity));
+}
+
+pub fn get_bindings<'a>(
+    meta: &'a ScreenSpaceReflectionsMeta,
+    view_entity: Entity,
+    bindings: [u32; 3],
+) -> [BindGroupEntry<'a>; 3] {
+    let (uniform, history_view) = match meta.histories.get(&view_entity) {
+        Some(history) => (&history.uniform, &history.view),
+        None => (&meta.fallback_uniform, &meta.fallback_history_view),
+    };
+
+    [
+        BindGroupEntry {
+            binding: bindings[0],
+            resource: uniform.binding().unwrap(),
+        },
+        BindGroupEntry {
+            binding: bindings[1],
+            resource: BindingResource::TextureView(history_view),
+        },
+        BindGroupEntry {
+            binding: bindings[2],
+            resource: BindingResource::Sampler(&meta.sampler),
+        },
+    ]
+}
+
+pub fn get_bind_group_layout_entries(bindings: [u32; 3]) -> [BindGroupLayoutEntry; 3] {
+    [
+        BindGroupLayoutEntry {
+            binding: bindings[0],
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Buffer {
+                ty: BufferBindingType::Uniform,
+                has_dynamic_offset: false,
+                min_binding_size: Some(GpuScreenSpaceReflections::min_size()),
+            },
+            count: None,
+        },
+        BindGroupLayoutEntry {
+            binding: bindings[1],
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Texture {
+                sample_type: TextureSampleType::Float { filterable: true },
+                view_dimension: TextureViewDimension::D2,
+                multisampled: false,
+            },
+            count: None,
+        },
+        BindGroupLayoutEntry {
+            binding: bindings[2],
+            visibility: ShaderStages::FRAGMENT,
+            ty: BindingType::Sampler(SamplerBindingType::Filtering),
+            count: None,
+        },
+    ]
+}
+
+/// Keeps the lit frame of views using screen space reflections, and its downsampled mips, for
+/// the reflections of the next frame.
+pub struct ScreenSpaceReflectionsNode {
+    query: QueryState<&'static ViewTarget, With<ViewScreenSpaceReflections>>,
+}
+
+impl ScreenSpaceReflectionsNode {
+    pub const IN_VIEW: &'static str = "view";
+
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            query: QueryState::new(world),
+        }
+    }
+}
+
+impl Node for ScreenSpaceReflectionsNode {
+    fn input(&self) -> Vec<SlotInfo> {
+        vec![SlotInfo::new(Self::IN_VIEW, SlotType::Entity)]
+    }
+
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let view_entity = graph.get_input_entity(Self::IN_VIEW)?;
+        let Ok(view_target) = self.query.get_manual(world, view_entity) else {
+            return Ok(());
+        };
+        let meta = world.resource::<ScreenSpaceReflectionsMeta>();
+        let Some(history) = meta.histories.get(&view_entity) else {
+            return Ok(());
+        };
+        let pipeline = world.resource::<ScreenSpaceReflectionsPipeline>();
+        let Some(history_pipeline) = world
+            .resource::<PipelineCache>()
+            .get_render_pipeline(pipeline.history_pipeline)
+        else {
+            return Ok(());
+        };
+
+        // Copy the frame into the first mip, then downsample each mip into the next one
+        let mut source = view_target.main_texture();
+        for destination in &history.mip_views {
+            let bind_group =
+                render_context
+                    .render_device()
+                    .create_bind_group(&BindGroupDescriptor {
+                        label: Some("ssr_history_bind_group"),
+                        layout: &pipeline.history_layout,
+                        entries: &[
+                            BindGroupEntry {
+                                binding: 0,
+                                resource: BindingResource::TextureView(source),
+                            },
+                            BindGroupEntry {
+                                binding: 1,
+                                resource: BindingResource::Sampler(&meta.sampler),
+                            },
+                        ],
+                    });
+
+            let mut render_pass =
+                render_context
+                    .command_encoder()
+                    .begin_render_pass(&RenderPassDescriptor {
+                        label: Some("ssr_history_pass"),
+                        color_attachments: &[Some(RenderPassColorAttachment {
+                            view: destination,
+                            resolve_target: None,
+                            ops: Operations::default(),
+                        })],
+                        depth_stencil_attachment: None,
+                    });
+            render_pass.set_pipeline(history_pipeline);
+            render_pass.set_bind_group(0, &bind_group, &[]);
+            render_pass.draw(0..3, 0..1);
+
+            source = destination;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+#define_import_path bevy_pbr::ssr
+
+// NOTE: the last argument is the sample index for multisampled textures, and the mip level
+// otherwise, so the first sample is used either way
+fn ssr_depth(pixel: vec2<i32>) -> f32 {
+    return textureLoad(depth_prepass_texture, pixel, 0);
+}
+
+fn ssr_normal(pixel: vec2<i32>) -> vec3<f32> {
+    return textureLoad(normal_prepass_texture, pixel, 0).xyz * 2.0 - vec3(1.0);
+}
+
+// The view space depth of a world space position, along with the view space depth of the scene
+// at the pixel it projects onto
+struct SsrSample {
+    uv: vec2<f32>,
+    pixel: vec2<i32>,
+    ray_depth: f32,
+    scene_depth: f32,
+    on_screen: bool,
+};
+
+fn ssr_sample(world_position: vec3<f32>) -> SsrSample {
+    var out: SsrSample;
+    let clip_position = view.view_proj * vec4<f32>(world_position, 1.0);
+    if (clip_position.w <= 0.0) {
+        out.on_screen = false;
+        return out;
+    }
+    let ndc_position = clip_position.xyz / clip_position.w;
+    out.uv = ndc_position.xy * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5);
+    out.on_screen = all(out.uv >= vec2<f32>(0.0)) && all(out.uv < vec2<f32>(1.0));
+    if (!out.on_screen) {
+        return out;
+    }
+    out.pixel = vec2<i32>(view.viewport.xy + out.uv * view.viewport.zw);
+
+    // View space depths are negative in front of the camera
+    out.ray_depth = (view.inverse_view * vec4<f32>(world_position, 1.0)).z;
+    let scene_position = view.inverse_projection * vec4<f32>(ndc_position.xy, ssr_depth(out.pixel), 1.0);
+    out.scene_depth = scene_position.z / scene_position.w;
+    return out;
+}
+
+// Interleaved gradient noise, to offset the steps of neighboring rays and hide banding
+fn ssr_noise(frag_coord: vec2<f32>) -> f32 {
+    return fract(52.9829189 * fract(dot(frag_coord, vec2<f32>(0.06711056, 0.00583715))));
+}
+
+// Marches the reflection of the fragment through the depth prepass, and returns the color of
+// the previous frame where it hits the scene in rgb, with the confidence of that hit in a
+fn screen_space_reflection(
+    world_position: vec3<f32>,
+    frag_coord: vec2<f32>,
+    R: vec3<f32>,
+    perceptual_roughness: f32,
+) -> vec4<f32> {
+    if ((ssr.flags & SSR_FLAGS_HISTORY_VALID_BIT) == 0u || perceptual_roughness >= ssr.max_roughness) {
+        return vec4<f32>(0.0);
+    }
+
+    let step_length = ssr.max_distance / f32(ssr.max_steps);
+    let offset = ssr_noise(frag_coord);
+    var previous_distance = 0.0;
+    var hit = false;
+    var hit_distance = 0.0;
+    for (var i: u32 = 0u; i < ssr.max_steps; i = i + 1u) {
+        // Start a bit away from the fragment, so that it doesn't reflect itself
+        let distance = (f32(i) + 1.0 - 0.5 * offset) * step_length;
+        let ray_sample = ssr_sample(world_position + R * distance);
+        if (!ray_sample.on_screen) {
+            break;
+        }
+        let depth_difference = ray_sample.scene_depth - ray_sample.ray_depth;
+        if (depth_difference > 0.0) {
+            if (depth_difference < ssr.thickness) {
+                hit = true;
+                hit_distance = distance;
+            }
+            // The ray is either hitting the scene, or going behind it
+            break;
+        }
+        previous_distance = distance;
+    }
+
+    if (!hit) {
+        return vec4<f32>(0.0);
+    }
+
+    // Refine the hit between the last two steps
+    var near = previous_distance;
+    var far = hit_distance;
+    for (var i = 0; i < 4; i = i + 1) {
+        let middle = (near + far) * 0.5;
+        let ray_sample = ssr_sample(world_position + R * middle);
+        if (ray_sample.scene_depth - ray_sample.ray_depth > 0.0) {
+            far = middle;
+        } else {
+            near = middle;
+        }
+    }
+
+    let hit_position = world_position + R * far;
+    let hit_sample = ssr_sample(hit_position);
+    // Reflections can't hit the back of a surface
+    if (dot(ssr_normal(hit_sample.pixel), R) > 0.0) {
+        return vec4<f32>(0.0);
+    }
+
+    // Look the hit up in the previous frame, where its color is known
+    let previous_clip_position = ssr.previous_view_proj * vec4<f32>(hit_position, 1.0);
+    if (previous_clip_position.w <= 0.0) {
+        return vec4<f32>(0.0);
+    }
+    let previous_uv = previous_clip_position.xy / previous_clip_position.w * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5);
+    let history_size = vec2<f32>(textureDimensions(ssr_history_texture));
+    let history_uv = (view.viewport.xy + previous_uv * view.viewport.zw) / history_size;
+
+    // Rougher surfaces reflect a wider cone, covering more of the history the further it goes
+    let roughness_fraction = perceptual_roughness / ssr.max_roughness;
+    let cone_width = roughness_fraction * far / ssr.max_distance;
+    let mip_level = saturate(roughness_fraction + cone_width) * f32(ssr.history_mip_levels - 1u);
+    let color = textureSampleLevel(ssr_history_texture, ssr_history_sampler, history_uv, mip_level).rgb;
+
+    // Fade the reflections out towards the edges of the screen, the end of the rays and the
+    // roughest surfaces, where they blend into the environment
+    let edge_fade = saturate(min(min(previous_uv.x, 1.0 - previous_uv.x), min(previous_uv.y, 1.0 - previous_uv.y)) * 10.0);
+    let distance_fade = saturate(1.0 - far / ssr.max_distance);
+    let roughness_fade = 1.0 - roughness_fraction * roughness_fraction;
+    return vec4<f32>(color, edge_fade * distance_fade * roughness_fade);
+}
@@ -0,0 +1,96 @@
+use crate::StandardMaterial;
+use bevy_asset::{Assets, Handle, HandleId};
+use bevy_ecs::prelude::*;
+use bevy_render::{
+    camera::Camera,
+    primitives::Aabb,
+    texture::{Image, TextureResidencyChanged, TextureStreaming},
+    view::ViewVisibility,
+};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::HashSet;
+
+/// Requests the streamed textures of the visible [`StandardMaterial`]s, with enough detail to
+/// cover the screen size of the bounds of their meshes.
+pub fn request_standard_material_textures(
+    mut streaming: ResMut<TextureStreaming>,
+    materials: Res<Assets<StandardMaterial>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    meshes: Query<(
+        &Handle<StandardMaterial>,
+        &GlobalTransform,
+        &Aabb,
+        &ViewVisibility,
+    )>,
+) {
+    for (camera, camera_transform) in &cameras {
+        let viewport_height = match camera.physical_viewport_size() {
+            Some(size) if camera.is_active => size.y as f32,
+            _ => continue,
+        };
+        let projection = camera.projection_matrix();
+        // Orthographic projections don't scale with the distance to the camera
+        let is_perspective = projection.w_axis.w == 0.0;
+        let camera_position = camera_transform.translation_vec3a();
+
+        for (material, transform, aabb, visibility) in &meshes {
+            if !visibility.get() {
+                continue;
+            }
+            let material = match materials.get(material) {
+                Some(material) => material,
+                None => continue,
+            };
+
+            let affine = transform.affine();
+            let center = affine.transform_point3a(aabb.center);
+            let radius = affine.transform_vector3a(aabb.half_extents).length();
+            let distance = if is_perspective {
+                center.distance(camera_position).max(f32::EPSILON)
+            } else {
+                1.0
+            };
+            let pixels = radius * projection.y_axis.y * viewport_height / distance;
+
+            for texture in textures(material) {
+                streaming.request(texture, pixels);
+            }
+        }
+    }
+}
+
+/// Marks the [`StandardMaterial`]s using textures whose resident mips changed as modified, so
+/// that their bind groups are recreated with the new textures.
+pub fn update_standard_material_textures(
+    mut residency_changed: EventReader<TextureResidencyChanged>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let changed: HashSet<HandleId> = residency_changed
+        .iter()
+        .map(|event| event.image.id())
+        .collect();
+    if changed.is_empty() {
+        return;
+    }
+
+    let modified: Vec<HandleId> = materials
+        .iter()
+        .filter(|(_, material)| textures(material).any(|texture| changed.contains(&texture.id())))
+        .map(|(id, _)| id)
+        .collect();
+    for id in modified {
+        materials.get_mut(&Handle::weak(id));
+    }
+}
+
+fn textures(material: &StandardMaterial) -> impl Iterator<Item = &Handle<Image>> {
+    [
+        &material.base_color_texture,
+        &material.emissive_texture,
+        &material.metallic_roughness_texture,
+        &material.normal_map_texture,
+        &material.occlusion_texture,
+    ]
+    .into_iter()
+    .flatten()
+}
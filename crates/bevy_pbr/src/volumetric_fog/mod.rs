@@ -0,0 +1,846 @@
+use bevy_app::{App, Plugin};
+use bevy_asset::{load_internal_asset, HandleUntyped};
+use bevy_core_pipeline::{
+    core_3d::{self, Camera3d},
+    fullscreen_vertex_shader::fullscreen_shader_vertex_state,
+    prepass::ViewPrepassTextures,
+};
+use bevy_ecs::{prelude::*, query::QueryState};
+use bevy_math::{Mat4, UVec3, Vec3, Vec4};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect, TypeUuid};
+use bevy_render::{
+    color::Color,
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
+    prelude::Msaa,
+    render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext, SlotInfo, SlotType},
+    render_resource::*,
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    texture::BevyDefault,
+    view::{ExtractedView, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms},
+    RenderApp, RenderSet,
+};
+use bevy_utils::{default, tracing::warn, HashMap, HashSet};
+
+use crate::{
+    draw_3d_graph, GpuLights, LightMeta, ShadowPipeline, ViewLightsUniformOffset,
+    ViewShadowBindings, MAX_CASCADES_PER_LIGHT, MAX_DIRECTIONAL_LIGHTS,
+};
+
+pub const VOLUMETRIC_FOG_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 6351244328149393066);
+const VOLUMETRIC_FOG_SCATTER_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 11652284399727451383);
+const VOLUMETRIC_FOG_COMPOSITE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3839117023342961050);
+
+/// The number of froxels the view frustum is divided into, horizontally, vertically and in
+/// depth, up to [`VolumetricFogSettings::max_distance`].
+const VOLUMETRIC_FOG_FROXELS: UVec3 = UVec3::new(160, 90, 64);
+
+/// The size of the workgroups of the scatter and integrate passes.
+const VOLUMETRIC_FOG_WORKGROUP_SIZE: u32 = 8;
+
+/// Adds support for [`VolumetricFogSettings`] and [`VolumetricLight`].
+pub struct VolumetricFogPlugin;
+
+impl Plugin for VolumetricFogPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            VOLUMETRIC_FOG_SHADER_HANDLE,
+            "volumetric_fog.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            VOLUMETRIC_FOG_SCATTER_SHADER_HANDLE,
+            "volumetric_fog_scatter.wgsl",
+            Shader::from_wgsl
+        );
+        load_internal_asset!(
+            app,
+            VOLUMETRIC_FOG_COMPOSITE_SHADER_HANDLE,
+            "volumetric_fog_composite.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<VolumetricFogSettings>()
+            .register_type::<VolumetricLight>()
+            .add_plugin(ExtractComponentPlugin::<VolumetricFogSettings>::default());
+
+        // Compute shaders and the prepass textures aren't available on WebGL2
+        if cfg!(feature = "webgl") {
+            return;
+        }
+
+        let render_app = match app.get_sub_app_mut(RenderApp) {
+            Ok(render_app) => render_app,
+            Err(_) => return,
+        };
+
+        render_app
+            .init_resource::<VolumetricFogPipeline>()
+            .init_resource::<SpecializedRenderPipelines<VolumetricFogPipeline>>()
+            .init_resource::<VolumetricFogMeta>()
+            .add_system(prepare_volumetric_fog.in_set(RenderSet::Prepare));
+
+        let fog_node = VolumetricFogNode::new(&mut render_app.world);
+        let mut graph = render_app.world.resource_mut::<RenderGraph>();
+        let draw_3d_graph = graph.get_sub_graph_mut(core_3d::graph::NAME).unwrap();
+        draw_3d_graph.add_node(draw_3d_graph::node::VOLUMETRIC_FOG, fog_node);
+        draw_3d_graph.add_slot_edge(
+            draw_3d_graph.input_node().id,
+            core_3d::graph::input::VIEW_ENTITY,
+            draw_3d_graph::node::VOLUMETRIC_FOG,
+            VolumetricFogNode::IN_VIEW,
+        );
+        // MAIN_PASS -> SCREEN_SPACE_REFLECTIONS -> VOLUMETRIC_FOG -> BLOOM -> TONEMAPPING, so
+        // that the fog is tonemapped with the scene but isn't reflected twice
+        draw_3d_graph.add_node_edge(
+            core_3d::graph::node::MAIN_PASS,
+            draw_3d_graph::node::VOLUMETRIC_FOG,
+        );
+        if draw_3d_graph
+            .get_node_state(draw_3d_graph::node::SCREEN_SPACE_REFLECTIONS)
+            .is_ok()
+        {
+            draw_3d_graph.add_node_edge(
+                draw_3d_graph::node::SCREEN_SPACE_REFLECTIONS,
+                draw_3d_graph::node::VOLUMETRIC_FOG,
+            );
+        }
+        if draw_3d_graph
+            .get_node_state(core_3d::graph::node::BLOOM)
+            .is_ok()
+        {
+            draw_3d_graph.add_node_edge(
+                draw_3d_graph::node::VOLUMETRIC_FOG,
+                core_3d::graph::node::BLOOM,
+            );
+        }
+        draw_3d_graph.add_node_edge(
+            draw_3d_graph::node::VOLUMETRIC_FOG,
+            core_3d::graph::node::TONEMAPPING,
+        );
+    }
+}
+
+/// Fills the view of a 3D camera with fog, lit by the ambient light and by the
+/// [`VolumetricLight`]s of the scene.
+///
+/// The fog is lit in a grid of froxels, the cells of the view frustum, so that the shadows of
+/// volumetric lights cast shafts of light through it. The fog is then accumulated along each view
+/// ray and composited over the scene before tonemapping.
+///
+/// This requires the camera to have a [`DepthPrepass`](bevy_core_pipeline::prepass::DepthPrepass).
+/// Volumetric fog is not supported on `WebGL2`.
+#[derive(Component, Reflect, Clone, Debug)]
+#[reflect(Component, Default)]
+pub struct VolumetricFogSettings {
+    /// The fraction of the light going through one world unit of fog that is scattered
+    /// (default: 0.05).
+    pub density: f32,
+
+    /// The color of the light scattered by the fog (default: [`Color::WHITE`]).
+    pub albedo: Color,
+
+    /// How much light is scattered forward rather than backwards, from -1.0 to 1.0
+    /// (default: 0.6).
+    ///
+    /// Positive values make the fog glow when looking towards the lights, negative values when
+    /// looking away from them, and 0.0 scatters light evenly in all directions.
+    pub anisotropy: f32,
+
+    /// How much of the [`AmbientLight`](crate::AmbientLight) lights the fog (default: 1.0).
+    pub ambient_intensity: f32,
+
+    /// The distance up to which the fog is rendered, in world units (default: 50.0).
+    ///
+    /// The froxels get thicker with the distance up to this value, so smaller values give more
+    /// detailed light shafts.
+    pub max_distance: f32,
+
+    /// Whether the lighting of each froxel is sampled at a different depth every frame
+    /// (default: true).
+    ///
+    /// Along with the temporal filtering, this hides the banding between the slices of froxels.
+    pub jitter: bool,
+
+    /// How much of the fog of the previous frame is kept, from 0.0 to 1.0 (default: 0.9).
+    ///
+    /// Higher values give smoother fog, but make it lag behind moving lights and shadows. `0.0`
+    /// disables the temporal filtering.
+    pub history_weight: f32,
+}
+
+impl Default for VolumetricFogSettings {
+    fn default() -> Self {
+        Self {
+            density: 0.05,
+            albedo: Color::WHITE,
+            anisotropy: 0.6,
+            ambient_intensity: 1.0,
+            max_distance: 50.0,
+            jitter: true,
+            history_weight: 0.9,
+        }
+    }
+}
+
+impl ExtractComponent for VolumetricFogSettings {
+    type Query = &'static Self;
+    type Filter = With<Camera3d>;
+    type Out = Self;
+
+    fn extract_component(item: bevy_ecs::query::QueryItem<'_, Self::Query>) -> Option<Self::Out> {
+        Some(item.clone())
+    }
+}
+
+/// Makes a [`DirectionalLight`](crate::DirectionalLight) light the
+/// [`VolumetricFogSettings`] of cameras, casting shafts of light through the fog if it has
+/// shadows enabled.
+#[derive(Component, Reflect, Clone, Copy, Debug, Default)]
+#[reflect(Component, Default)]
+pub struct VolumetricLight;
+
+const VOLUMETRIC_FOG_FLAGS_HISTORY_VALID_BIT: u32 = 1;
+const VOLUMETRIC_FOG_FLAGS_JITTER_BIT: u32 = 2;
+
+#[derive(Copy, Clone, Default, ShaderType)]
+pub struct GpuVolumetricFog {
+    previous_view_proj: Mat4,
+    previous_inverse_view: Mat4,
+    albedo: Vec3,
+    density: f32,
+    anisotropy: f32,
+    ambient_intensity: f32,
+    max_distance: f32,
+    history_weight: f32,
+    jitter: f32,
+    flags: u32,
+}
+
+/// The froxels of a view using volumetric fog, which persist from one frame to the next for
+/// the temporal filtering.
+struct ViewVolumetricFogVolumes {
+    /// The scattering of this frame and of the previous one, swapped every frame.
+    scattering: [TextureView; 2],
+    /// The scattering accumulated from the camera to each froxel.
+    integrated: TextureView,
+    /// The index of the scattering written this frame.
+    current: usize,
+    frame: u32,
+    previous_view_proj: Mat4,
+    previous_inverse_view: Mat4,
+    uniform: UniformBuffer<GpuVolumetricFog>,
+}
+
+#[derive(Resource)]
+pub struct VolumetricFogMeta {
+    volumes: HashMap<Entity, ViewVolumetricFogVolumes>,
+    sampler: Sampler,
+}
+
+impl FromWorld for VolumetricFogMeta {
+    fn from_world(world: &mut World) -> Self {
+        Self {
+            volumes: HashMap::default(),
+            sampler: world
+                .resource::<RenderDevice>()
+                .create_sampler(&SamplerDescriptor {
+                    label: Some("volumetric_fog_sampler"),
+                    address_mode_u: AddressMode::ClampToEdge,
+                    address_mode_v: AddressMode::ClampToEdge,
+                    address_mode_w: AddressMode::ClampToEdge,
+                    mag_filter: FilterMode::Linear,
+                    min_filter: FilterMode::Linear,
+                    ..default()
+                }),
+        }
+    }
+}
+
+/// Marks views rendering volumetric fog this frame, with the pipeline compositing it.
+#[derive(Component)]
+pub struct ViewVolumetricFog {
+    composite_pipeline: CachedRenderPipelineId,
+}
+
+#[derive(Resource)]
+pub struct VolumetricFogPipeline {
+    scatter_layout: BindGroupLayout,
+    integrate_layout: BindGroupLayout,
+    composite_layout: BindGroupLayout,
+    composite_layout_multisampled: BindGroupLayout,
+    scatter_pipeline: CachedComputePipelineId,
+    integrate_pipeline: CachedComputePipelineId,
+}
+
+fn fog_uniform_layout_entry(binding: u32, visibility: ShaderStages) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: Some(GpuVolumetricFog::min_size()),
+        },
+        count: None,
+    }
+}
+
+fn volume_layout_entry(binding: u32, visibility: ShaderStages) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility,
+        ty: BindingType::Texture {
+            sample_type: TextureSampleType::Float { filterable: true },
+            view_dimension: TextureViewDimension::D3,
+            multisampled: false,
+        },
+        count: None,
+    }
+}
+
+fn storage_volume_layout_entry(binding: u32) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding,
+        visibility: ShaderStages::COMPUTE,
+        ty: BindingType::StorageTexture {
+            access: StorageTextureAccess::WriteOnly,
+            format: TextureFormat::Rgba16Float,
+            view_dimension: TextureViewDimension::D3,
+        },
+        count: None,
+    }
+}
+
+fn view_layout_entry(visibility: ShaderStages) -> BindGroupLayoutEntry {
+    BindGroupLayoutEntry {
+        binding: 0,
+        visibility,
+        ty: BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: true,
+            min_binding_size: Some(ViewUniform::min_size()),
+        },
+        count: None,
+    }
+}
+
+impl FromWorld for VolumetricFogPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let scatter_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("volumetric_fog_scatter_bind_group_layout"),
+            entries: &[
+                view_layout_entry(ShaderStages::COMPUTE),
+                fog_uniform_layout_entry(1, ShaderStages::COMPUTE),
+                // Lights
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(GpuLights::min_size()),
+                    },
+                    count: None,
+                },
+                // Directional Shadow Texture Array
+                BindGroupLayoutEntry {
+                    binding: 3,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        sample_type: TextureSampleType::Depth,
+                        view_dimension: TextureViewDimension::D2Array,
+                    },
+                    count: None,
+                },
+                // Directional Shadow Texture Array Sampler
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Sampler(SamplerBindingType::Comparison),
+                    count: None,
+                },
+                // The scattering of the previous frame
+                volume_layout_entry(5, ShaderStages::COMPUTE),
+                BindGroupLayoutEntry {
+                    binding: 6,
+                    visibility: ShaderStages::COMPUTE,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+                storage_volume_layout_entry(7),
+            ],
+        });
+
+        let integrate_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("volumetric_fog_integrate_bind_group_layout"),
+            entries: &[
+                fog_uniform_layout_entry(1, ShaderStages::COMPUTE),
+                volume_layout_entry(8, ShaderStages::COMPUTE),
+                storage_volume_layout_entry(9),
+            ],
+        });
+
+        let composite_layout_entries = |multisampled| {
+            [
+                view_layout_entry(ShaderStages::FRAGMENT),
+                fog_uniform_layout_entry(1, ShaderStages::FRAGMENT),
+                // Depth prepass texture
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled,
+                        sample_type: TextureSampleType::Depth,
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                volume_layout_entry(3, ShaderStages::FRAGMENT),
+                BindGroupLayoutEntry {
+                    binding: 4,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ]
+        };
+        let composite_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("volumetric_fog_composite_bind_group_layout"),
+            entries: &composite_layout_entries(false),
+        });
+        let composite_layout_multisampled =
+            render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                label: Some("volumetric_fog_composite_multisampled_bind_group_layout"),
+                entries: &composite_layout_entries(true),
+            });
+
+        let shader_defs = vec![
+            ShaderDefVal::UInt(
+                "MAX_DIRECTIONAL_LIGHTS".to_string(),
+                MAX_DIRECTIONAL_LIGHTS as u32,
+            ),
+            ShaderDefVal::UInt(
+                "MAX_CASCADES_PER_LIGHT".to_string(),
+                MAX_CASCADES_PER_LIGHT as u32,
+            ),
+        ];
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let scatter_pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("volumetric_fog_scatter_pipeline".into()),
+            layout: vec![scatter_layout.clone()],
+            push_constant_ranges: Vec::new(),
+            shader: VOLUMETRIC_FOG_SCATTER_SHADER_HANDLE.typed::<Shader>(),
+            shader_defs: shader_defs.clone(),
+            entry_point: "scatter".into(),
+        });
+        let integrate_pipeline = pipeline_cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: Some("volumetric_fog_integrate_pipeline".into()),
+            layout: vec![integrate_layout.clone()],
+            push_constant_ranges: Vec::new(),
+            shader: VOLUMETRIC_FOG_SCATTER_SHADER_HANDLE.typed::<Shader>(),
+            shader_defs,
+            entry_point: "integrate".into(),
+        });
+
+        Self {
+            scatter_layout,
+            integrate_layout,
+            composite_layout,
+            composite_layout_multisampled,
+            scatter_pipeline,
+            integrate_pipeline,
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+pub struct VolumetricFogPipelineKey {
+    texture_format: TextureFormat,
+    multisampled: bool,
+}
+
+impl SpecializedRenderPipeline for VolumetricFogPipeline {
+    type Key = VolumetricFogPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let mut shader_defs = Vec::new();
+        let layout = if key.multisampled {
+            shader_defs.push("MULTISAMPLED".into());
+            self.composite_layout_multisampled.clone()
+        } else {
+            self.composite_layout.clone()
+        };
+
+        RenderPipelineDescriptor {
+            label: Some("volumetric_fog_composite_pipeline".into()),
+            layout: vec![layout],
+            vertex: fullscreen_shader_vertex_state(),
+            fragment: Some(FragmentState {
+                shader: VOLUMETRIC_FOG_COMPOSITE_SHADER_HANDLE.typed::<Shader>(),
+                shader_defs,
+                entry_point: "composite".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: key.texture_format,
+                    // The scene is attenuated by the transmittance of the fog in front of it,
+                    // then the light scattered by that fog is added
+                    blend: Some(BlendState {
+                        color: BlendComponent {
+                            src_factor: BlendFactor::One,
+                            dst_factor: BlendFactor::SrcAlpha,
+                            operation: BlendOperation::Add,
+                        },
+                        alpha: BlendComponent {
+                            src_factor: BlendFactor::Zero,
+                            dst_factor: BlendFactor::One,
+                            operation: BlendOperation::Add,
+                        },
+                    }),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: Vec::new(),
+        }
+    }
+}
+
+fn create_volume(render_device: &RenderDevice, label: &'static str) -> TextureView {
+    render_device
+        .create_texture(&TextureDescriptor {
+            label: Some(label),
+            size: Extent3d {
+                width: VOLUMETRIC_FOG_FROXELS.x,
+                height: VOLUMETRIC_FOG_FROXELS.y,
+                depth_or_array_layers: VOLUMETRIC_FOG_FROXELS.z,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D3,
+            format: TextureFormat::Rgba16Float,
+            usage: TextureUsages::STORAGE_BINDING | TextureUsages::TEXTURE_BINDING,
+            view_formats: &[],
+        })
+        .create_view(&TextureViewDescriptor::default())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn prepare_volumetric_fog(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    pipeline_cache: Res<PipelineCache>,
+    fog_pipeline: Res<VolumetricFogPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<VolumetricFogPipeline>>,
+    mut meta: ResMut<VolumetricFogMeta>,
+    views: Query<(
+        Entity,
+        &ExtractedView,
+        &VolumetricFogSettings,
+        &Msaa,
+        Option<&ViewPrepassTextures>,
+    )>,
+    mut unsupported_view_warning_emitted: Local<bool>,
+) {
+    let mut live_views = HashSet::new();
+    for (entity, view, settings, msaa, prepass_textures) in &views {
+        if prepass_textures.map_or(true, |textures| textures.depth.is_none()) {
+            if !*unsupported_view_warning_emitted {
+                warn!(
+                    "Volumetric fog requires a camera with a depth prepass. It will be disabled \
+                    for cameras without one."
+                );
+                *unsupported_view_warning_emitted = true;
+            }
+            continue;
+        }
+        live_views.insert(entity);
+
+        let volumes = meta
+            .volumes
+            .entry(entity)
+            .or_insert_with(|| ViewVolumetricFogVolumes {
+                scattering: [
+                    create_volume(&render_device, "volumetric_fog_scattering_texture"),
+                    create_volume(&render_device, "volumetric_fog_scattering_texture"),
+                ],
+                integrated: create_volume(&render_device, "volumetric_fog_integrated_texture"),
+                current: 0,
+                frame: 0,
+                previous_view_proj: Mat4::IDENTITY,
+                previous_inverse_view: Mat4::IDENTITY,
+                uniform: UniformBuffer::default(),
+            });
+
+        let inverse_view = view.transform.compute_matrix().inverse();
+        let mut flags = 0;
+        // The previous scattering is only written at the end of the first frame
+        if volumes.frame > 0 {
+            flags |= VOLUMETRIC_FOG_FLAGS_HISTORY_VALID_BIT;
+        }
+        if settings.jitter {
+            flags |= VOLUMETRIC_FOG_FLAGS_JITTER_BIT;
+        }
+        volumes.uniform.set(GpuVolumetricFog {
+            previous_view_proj: volumes.previous_view_proj,
+            previous_inverse_view: volumes.previous_inverse_view,
+            albedo: Vec4::from_slice(&settings.albedo.as_linear_rgba_f32()).truncate(),
+            density: settings.density.max(0.0),
+            anisotropy: settings.anisotropy.clamp(-0.99, 0.99),
+            ambient_intensity: settings.ambient_intensity,
+            max_distance: settings.max_distance,
+            history_weight: settings.history_weight.clamp(0.0, 1.0),
+            // A low discrepancy sequence, so that a few consecutive frames cover the whole slice
+            jitter: (volumes.frame as f32 * 0.618_034).fract(),
+            flags,
+        });
+        volumes.uniform.write_buffer(&render_device, &render_queue);
+        volumes.current = volumes.frame as usize % 2;
+        volumes.frame = volumes.frame.wrapping_add(1);
+        volumes.previous_view_proj = view.projection * inverse_view;
+        volumes.previous_inverse_view = inverse_view;
+
+        let composite_pipeline = pipelines.specialize(
+            &pipeline_cache,
+            &fog_pipeline,
+            VolumetricFogPipelineKey {
+                texture_format: if view.hdr {
+                    ViewTarget::TEXTURE_FORMAT_HDR
+                } else {
+                    TextureFormat::bevy_default()
+                },
+                multisampled: msaa.samples() > 1,
+            },
+        );
+        commands
+            .entity(entity)
+            .insert(ViewVolumetricFog { composite_pipeline });
+    }
+
+    meta.volumes.retain(|entity, _| live_views.contains(entity));
+}
+
+/// Lights the froxels of views using volumetric fog, integrates them along the view rays and
+/// composites the fog over the scene.
+pub struct VolumetricFogNode {
+    query: QueryState<(
+        &'static ViewTarget,
+        &'static ViewUniformOffset,
+        &'static ViewLightsUniformOffset,
+        &'static ViewShadowBindings,
+        &'static ViewPrepassTextures,
+        &'static Msaa,
+        &'static ViewVolumetricFog,
+    )>,
+}
+
+impl VolumetricFogNode {
+    pub const IN_VIEW: &'static str = "view";
+
+    pub fn new(world: &mut World) -> Self {
+        Self {
+            query: QueryState::new(world),
+        }
+    }
+}
+
+impl Node for VolumetricFogNode {
+    fn input(&self) -> Vec<SlotInfo> {
+        vec![SlotInfo::new(Self::IN_VIEW, SlotType::Entity)]
+    }
+
+    fn update(&mut self, world: &mut World) {
+        self.query.update_archetypes(world);
+    }
+
+    fn run(
+        &self,
+        graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let view_entity = graph.get_input_entity(Self::IN_VIEW)?;
+        let Ok((
+            view_target,
+            view_uniform_offset,
+            view_lights_offset,
+            view_shadow_bindings,
+            prepass_textures,
+            msaa,
+            view_fog,
+        )) = self.query.get_manual(world, view_entity)
+        else {
+            return Ok(());
+        };
+        let meta = world.resource::<VolumetricFogMeta>();
+        let (Some(volumes), Some(depth)) =
+            (meta.volumes.get(&view_entity), &prepass_textures.depth)
+        else {
+            return Ok(());
+        };
+        let fog_pipeline = world.resource::<VolumetricFogPipeline>();
+        let pipeline_cache = world.resource::<PipelineCache>();
+        let (Some(scatter_pipeline), Some(integrate_pipeline), Some(composite_pipeline)) = (
+            pipeline_cache.get_compute_pipeline(fog_pipeline.scatter_pipeline),
+            pipeline_cache.get_compute_pipeline(fog_pipeline.integrate_pipeline),
+            pipeline_cache.get_render_pipeline(view_fog.composite_pipeline),
+        ) else {
+            return Ok(());
+        };
+        let (Some(view_binding), Some(lights_binding), Some(fog_binding)) = (
+            world.resource::<ViewUniforms>().uniforms.binding(),
+            world.resource::<LightMeta>().view_gpu_lights.binding(),
+            volumes.uniform.binding(),
+        ) else {
+            return Ok(());
+        };
+
+        let render_device = render_context.render_device().clone();
+        let scattering = &volumes.scattering[volumes.current];
+        let history = &volumes.scattering[1 - volumes.current];
+
+        let scatter_bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            label: Some("volumetric_fog_scatter_bind_group"),
+            layout: &fog_pipeline.scatter_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: view_binding.clone(),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: fog_binding.clone(),
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: lights_binding,
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindingResource::TextureView(
+                        &view_shadow_bindings.directional_light_depth_texture_view,
+                    ),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindingResource::Sampler(
+                        &world.resource::<ShadowPipeline>().directional_light_sampler,
+                    ),
+                },
+                BindGroupEntry {
+                    binding: 5,
+                    resource: BindingResource::TextureView(history),
+                },
+                BindGroupEntry {
+                    binding: 6,
+                    resource: BindingResource::Sampler(&meta.sampler),
+                },
+                BindGroupEntry {
+                    binding: 7,
+                    resource: BindingResource::TextureView(scattering),
+                },
+            ],
+        });
+        let integrate_bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            label: Some("volumetric_fog_integrate_bind_group"),
+            layout: &fog_pipeline.integrate_layout,
+            entries: &[
+                BindGroupEntry {
+                    binding: 1,
+                    resource: fog_binding.clone(),
+                },
+                BindGroupEntry {
+                    binding: 8,
+                    resource: BindingResource::TextureView(scattering),
+                },
+                BindGroupEntry {
+                    binding: 9,
+                    resource: BindingResource::TextureView(&volumes.integrated),
+                },
+            ],
+        });
+        let composite_bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+            label: Some("volumetric_fog_composite_bind_group"),
+            layout: if msaa.samples() > 1 {
+                &fog_pipeline.composite_layout_multisampled
+            } else {
+                &fog_pipeline.composite_layout
+            },
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: view_binding,
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: fog_binding,
+                },
+                BindGroupEntry {
+                    binding: 2,
+                    resource: BindingResource::TextureView(&depth.default_view),
+                },
+                BindGroupEntry {
+                    binding: 3,
+                    resource: BindingResource::TextureView(&volumes.integrated),
+                },
+                BindGroupEntry {
+                    binding: 4,
+                    resource: BindingResource::Sampler(&meta.sampler),
+                },
+            ],
+        });
+
+        let workgroups_x = (VOLUMETRIC_FOG_FROXELS.x + VOLUMETRIC_FOG_WORKGROUP_SIZE - 1)
+            / VOLUMETRIC_FOG_WORKGROUP_SIZE;
+        let workgroups_y = (VOLUMETRIC_FOG_FROXELS.y + VOLUMETRIC_FOG_WORKGROUP_SIZE - 1)
+            / VOLUMETRIC_FOG_WORKGROUP_SIZE;
+        {
+            let mut pass =
+                render_context
+                    .command_encoder()
+                    .begin_compute_pass(&ComputePassDescriptor {
+                        label: Some("volumetric_fog_scatter_pass"),
+                    });
+            pass.set_pipeline(scatter_pipeline);
+            pass.set_bind_group(
+                0,
+                &scatter_bind_group,
+                &[view_uniform_offset.offset, view_lights_offset.offset],
+            );
+            pass.dispatch_workgroups(workgroups_x, workgroups_y, VOLUMETRIC_FOG_FROXELS.z);
+
+            pass.set_pipeline(integrate_pipeline);
+            pass.set_bind_group(0, &integrate_bind_group, &[]);
+            pass.dispatch_workgroups(workgroups_x, workgroups_y, 1);
+        }
+
+        let mut render_pass =
+            render_context
+                .command_encoder()
+                .begin_render_pass(&RenderPassDescriptor {
+                    label: Some("volumetric_fog_composite_pass"),
+                    // The fog is blended over the resolved frame
+                    color_attachments: &[Some(view_target.get_unsampled_color_attachment(
+                        Operations {
+                            load: LoadOp::Load,
+                            store: true,
+                        },
+                    ))],
+                    depth_stencil_attachment: None,
+                });
+        render_pass.set_pipeline(composite_pipeline);
+        render_pass.set_bind_group(0, &composite_bind_group, &[view_uniform_offset.offset]);
+        render_pass.draw(0..3, 0..1);
+
+        Ok(())
+    }
+}
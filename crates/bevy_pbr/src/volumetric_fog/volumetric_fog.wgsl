@@ -0,0 +1,29 @@
+#define_import_path bevy_pbr::volumetric_fog
+
+struct VolumetricFog {
+    previous_view_proj: mat4x4<f32>,
+    previous_inverse_view: mat4x4<f32>,
+    albedo: vec3<f32>,
+    density: f32,
+    anisotropy: f32,
+    ambient_intensity: f32,
+    max_distance: f32,
+    history_weight: f32,
+    jitter: f32,
+    // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
+    flags: u32,
+};
+
+const VOLUMETRIC_FOG_FLAGS_HISTORY_VALID_BIT: u32 = 1u;
+const VOLUMETRIC_FOG_FLAGS_JITTER_BIT: u32 = 2u;
+
+// The slices of the froxels get thicker with the distance, so that the resolution is spent
+// close to the camera, where the fog is most detailed. 't' goes from 0 at the camera to 1 at
+// the end of the fog.
+fn froxel_view_depth(t: f32, max_distance: f32) -> f32 {
+    return t * t * max_distance;
+}
+
+fn froxel_t(view_depth: f32, max_distance: f32) -> f32 {
+    return sqrt(saturate(view_depth / max_distance));
+}
@@ -0,0 +1,43 @@
+#import bevy_render::view
+#import bevy_core_pipeline::fullscreen_vertex_shader
+#import bevy_pbr::volumetric_fog
+
+@group(0) @binding(0)
+var<uniform> view: View;
+@group(0) @binding(1)
+var<uniform> fog: VolumetricFog;
+#ifdef MULTISAMPLED
+@group(0) @binding(2)
+var depth_prepass_texture: texture_depth_multisampled_2d;
+#else
+@group(0) @binding(2)
+var depth_prepass_texture: texture_depth_2d;
+#endif
+@group(0) @binding(3)
+var integrated_volume: texture_3d<f32>;
+@group(0) @binding(4)
+var integrated_volume_sampler: sampler;
+
+// Outputs the light scattered in front of the fragment in rgb, and the fraction of the fragment
+// reaching the camera through the fog in a, which the blend state multiplies the frame by
+@fragment
+fn composite(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
+    // NOTE: the last argument is the sample index for multisampled textures, and the mip level
+    // otherwise, so the first sample is used either way
+    let depth = textureLoad(depth_prepass_texture, vec2<i32>(in.position.xy), 0);
+    let uv = (in.position.xy - view.viewport.xy) / view.viewport.zw;
+    let ndc_xy = uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0);
+    let view_position = view.inverse_projection * vec4<f32>(ndc_xy, depth, 1.0);
+
+    // The sky is at an infinite distance, behind all of the fog
+    var t = 1.0;
+    if (view_position.w > 0.0) {
+        t = froxel_t(-view_position.z / view_position.w, fog.max_distance);
+    }
+
+    // Each froxel holds the fog integrated up to its back, so the lookup is shifted by half of a
+    // slice to interpolate between the backs of the slices
+    let slices = f32(textureDimensions(integrated_volume).z);
+    let uvw = vec3<f32>(uv, t - 0.5 / slices);
+    return textureSampleLevel(integrated_volume, integrated_volume_sampler, uvw, 0.0);
+}
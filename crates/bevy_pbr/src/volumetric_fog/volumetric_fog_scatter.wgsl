@@ -0,0 +1,171 @@
+#import bevy_pbr::mesh_view_types
+#import bevy_pbr::volumetric_fog
+
+@group(0) @binding(0)
+var<uniform> view: View;
+@group(0) @binding(1)
+var<uniform> fog: VolumetricFog;
+
+// Bindings of the scatter pass
+@group(0) @binding(2)
+var<uniform> lights: Lights;
+@group(0) @binding(3)
+var directional_shadow_textures: texture_depth_2d_array;
+@group(0) @binding(4)
+var directional_shadow_textures_sampler: sampler_comparison;
+@group(0) @binding(5)
+var history_volume: texture_3d<f32>;
+@group(0) @binding(6)
+var history_sampler: sampler;
+@group(0) @binding(7)
+var scattering_volume_out: texture_storage_3d<rgba16float, write>;
+
+// Bindings of the integrate pass
+@group(0) @binding(8)
+var scattering_volume: texture_3d<f32>;
+@group(0) @binding(9)
+var integrated_volume_out: texture_storage_3d<rgba16float, write>;
+
+const PI: f32 = 3.141592653589793;
+
+// Interleaved gradient noise, to offset the samples of neighboring froxels and hide banding
+fn interleaved_gradient_noise(pixel: vec2<f32>) -> f32 {
+    return fract(52.9829189 * fract(dot(pixel, vec2<f32>(0.06711056, 0.00583715))));
+}
+
+// The Henyey-Greenstein phase function, giving the fraction of the light scattered towards the
+// camera depending on the angle between the light and the view ray
+fn henyey_greenstein(cos_theta: f32, g: f32) -> f32 {
+    let g2 = g * g;
+    let denominator = 1.0 + g2 - 2.0 * g * cos_theta;
+    return (1.0 - g2) / (4.0 * PI * denominator * sqrt(denominator));
+}
+
+// The world position at the given view depth along the view ray through the uv
+fn froxel_world_position(uv: vec2<f32>, view_depth: f32) -> vec3<f32> {
+    let ndc_xy = uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0);
+    // Two points along the view ray, which works for both perspective and orthographic
+    // projections
+    let near = view.inverse_projection * vec4<f32>(ndc_xy, 1.0, 1.0);
+    let middle = view.inverse_projection * vec4<f32>(ndc_xy, 0.5, 1.0);
+    let a = near.xyz / near.w;
+    let b = middle.xyz / middle.w;
+    let view_position = mix(a, b, (-view_depth - a.z) / (b.z - a.z));
+    return (view.view * vec4<f32>(view_position, 1.0)).xyz;
+}
+
+// Fog has no surface to offset along its normal, so the shadow is only biased towards the light
+fn fetch_volumetric_shadow(light_id: u32, world_position: vec3<f32>, view_depth: f32) -> f32 {
+    let light = &lights.directional_lights[light_id];
+
+    var cascade_index = (*light).num_cascades;
+    for (var i: u32 = 0u; i < (*light).num_cascades; i = i + 1u) {
+        if (view_depth < (*light).cascades[i].far_bound) {
+            cascade_index = i;
+            break;
+        }
+    }
+    if (cascade_index >= (*light).num_cascades) {
+        return 1.0;
+    }
+
+    let cascade = &(*light).cascades[cascade_index];
+    let offset_position = world_position + (*light).shadow_depth_bias * (*light).direction_to_light;
+    let clip = (*cascade).view_projection * vec4<f32>(offset_position, 1.0);
+    if (clip.w <= 0.0) {
+        return 1.0;
+    }
+    let ndc = clip.xyz / clip.w;
+    if (any(ndc.xy < vec2<f32>(-1.0)) || ndc.z < 0.0 || any(ndc > vec3<f32>(1.0))) {
+        return 1.0;
+    }
+    let light_local = ndc.xy * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5);
+    return textureSampleCompareLevel(
+        directional_shadow_textures,
+        directional_shadow_textures_sampler,
+        light_local,
+        i32((*light).depth_texture_base_index + cascade_index),
+        ndc.z
+    );
+}
+
+// Computes the light scattered towards the camera, and the extinction of the fog, in each froxel
+@compute @workgroup_size(8, 8, 1)
+fn scatter(@builtin(global_invocation_id) froxel: vec3<u32>) {
+    let size = vec3<u32>(textureDimensions(scattering_volume_out));
+    if (any(froxel >= size)) {
+        return;
+    }
+
+    // Jittering the sample along the slice every frame lets the temporal filtering average the
+    // whole froxel over a few frames
+    var offset = 0.5;
+    if ((fog.flags & VOLUMETRIC_FOG_FLAGS_JITTER_BIT) != 0u) {
+        offset = fract(fog.jitter + interleaved_gradient_noise(vec2<f32>(froxel.xy)));
+    }
+    let uv = (vec2<f32>(froxel.xy) + vec2<f32>(0.5)) / vec2<f32>(size.xy);
+    let view_depth = froxel_view_depth((f32(froxel.z) + offset) / f32(size.z), fog.max_distance);
+    let world_position = froxel_world_position(uv, view_depth);
+    let ray_direction = normalize(world_position - view.world_position);
+
+    var light = lights.ambient_color.rgb * fog.ambient_intensity;
+    for (var i: u32 = 0u; i < lights.n_directional_lights; i = i + 1u) {
+        let directional_light = &lights.directional_lights[i];
+        if (((*directional_light).flags & DIRECTIONAL_LIGHT_FLAGS_VOLUMETRIC_BIT) == 0u) {
+            continue;
+        }
+        var shadow = 1.0;
+        if (((*directional_light).flags & DIRECTIONAL_LIGHT_FLAGS_SHADOWS_ENABLED_BIT) != 0u) {
+            shadow = fetch_volumetric_shadow(i, world_position, view_depth);
+        }
+        let phase = henyey_greenstein(dot(ray_direction, (*directional_light).direction_to_light), fog.anisotropy);
+        light += (*directional_light).color.rgb * phase * shadow;
+    }
+
+    var scattering = vec4<f32>(fog.albedo * fog.density * light, fog.density);
+
+    // Blend with the scattering of the same position in the previous frame
+    if ((fog.flags & VOLUMETRIC_FOG_FLAGS_HISTORY_VALID_BIT) != 0u) {
+        let previous_clip = fog.previous_view_proj * vec4<f32>(world_position, 1.0);
+        let previous_view_depth = -(fog.previous_inverse_view * vec4<f32>(world_position, 1.0)).z;
+        if (previous_clip.w > 0.0 && previous_view_depth > 0.0) {
+            let previous_uv = previous_clip.xy / previous_clip.w * vec2<f32>(0.5, -0.5) + vec2<f32>(0.5);
+            let previous_t = froxel_t(previous_view_depth, fog.max_distance);
+            let history_uvw = vec3<f32>(previous_uv, previous_t);
+            if (all(history_uvw >= vec3<f32>(0.0)) && all(history_uvw <= vec3<f32>(1.0))) {
+                let history = textureSampleLevel(history_volume, history_sampler, history_uvw, 0.0);
+                scattering = mix(scattering, history, fog.history_weight);
+            }
+        }
+    }
+
+    textureStore(scattering_volume_out, vec3<i32>(froxel), scattering);
+}
+
+// Accumulates the scattered light and the transmittance from the camera to each froxel
+@compute @workgroup_size(8, 8, 1)
+fn integrate(@builtin(global_invocation_id) froxel: vec3<u32>) {
+    let size = vec3<u32>(textureDimensions(integrated_volume_out));
+    if (any(froxel.xy >= size.xy)) {
+        return;
+    }
+
+    var scattered_light = vec3<f32>(0.0);
+    var transmittance = 1.0;
+    var previous_view_depth = 0.0;
+    for (var z: u32 = 0u; z < size.z; z = z + 1u) {
+        let position = vec3<i32>(vec2<i32>(froxel.xy), i32(z));
+        let view_depth = froxel_view_depth(f32(z + 1u) / f32(size.z), fog.max_distance);
+        let scattering = textureLoad(scattering_volume, position, 0);
+
+        // Integrates the scattering over the thickness of the slice, as the light scattered at
+        // its back is attenuated by its front
+        let extinction = max(scattering.a, 0.00001);
+        let slice_transmittance = exp(-extinction * (view_depth - previous_view_depth));
+        scattered_light += transmittance * (scattering.rgb - scattering.rgb * slice_transmittance) / extinction;
+        transmittance *= slice_transmittance;
+
+        textureStore(integrated_volume_out, position, vec4<f32>(scattered_light, transmittance));
+        previous_view_depth = view_depth;
+    }
+}
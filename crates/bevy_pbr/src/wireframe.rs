@@ -1,29 +1,61 @@
 use crate::MeshPipeline;
-use crate::{DrawMesh, MeshPipelineKey, MeshUniform, SetMeshBindGroup, SetMeshViewBindGroup};
-use bevy_app::Plugin;
-use bevy_asset::{load_internal_asset, Handle, HandleUntyped};
+use crate::{MeshPipelineKey, MeshUniform, SetMeshBindGroup, SetMeshViewBindGroup};
+use bevy_app::{CoreSet, IntoSystemAppConfig, Plugin};
+use bevy_asset::{load_internal_asset, AssetEvent, Assets, Handle, HandleId, HandleUntyped};
 use bevy_core_pipeline::core_3d::Opaque3d;
-use bevy_ecs::{prelude::*, reflect::ReflectComponent};
+use bevy_ecs::{
+    prelude::*,
+    query::ROQueryItem,
+    reflect::ReflectComponent,
+    system::{
+        lifetimeless::{Read, SRes},
+        SystemParamItem,
+    },
+};
+use bevy_math::Vec4;
 use bevy_reflect::std_traits::ReflectDefault;
 use bevy_reflect::{Reflect, TypeUuid};
-use bevy_render::extract_component::{ExtractComponent, ExtractComponentPlugin};
 use bevy_render::{
+    color::Color,
+    extract_component::{
+        ComponentUniforms, DynamicUniformIndex, ExtractComponent, UniformComponentPlugin,
+    },
     extract_resource::{ExtractResource, ExtractResourcePlugin},
-    mesh::{Mesh, MeshVertexBufferLayout},
+    mesh::{GpuBufferInfo, Mesh, MeshVertexAttribute, MeshVertexBufferLayout},
     render_asset::RenderAssets,
-    render_phase::{AddRenderCommand, DrawFunctions, RenderPhase, SetItemPipeline},
+    render_phase::{
+        AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
+        RenderPhase, SetItemPipeline, TrackedRenderPass,
+    },
     render_resource::{
-        PipelineCache, PolygonMode, RenderPipelineDescriptor, Shader, SpecializedMeshPipeline,
-        SpecializedMeshPipelineError, SpecializedMeshPipelines,
+        BindGroup, BindGroupDescriptor, BindGroupEntry, BindGroupLayout, BindGroupLayoutDescriptor,
+        BindGroupLayoutEntry, BindingType, BufferBindingType, PipelineCache, PolygonMode,
+        PrimitiveTopology, RenderPipelineDescriptor, Shader, ShaderStages, ShaderType,
+        SpecializedMeshPipeline, SpecializedMeshPipelineError, SpecializedMeshPipelines,
+        VertexFormat, WgpuFeatures,
     },
-    view::{ExtractedView, Msaa, VisibleEntities},
-    RenderApp, RenderSet,
+    renderer::{AddRenderFallback, RenderDevice},
+    view::{ExtractedView, Msaa, ViewVisibility, VisibleEntities},
+    Extract, ExtractSchedule, RenderApp, RenderSet,
 };
-use bevy_utils::tracing::error;
+use bevy_utils::{tracing::error, HashMap, HashSet};
 
 pub const WIREFRAME_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 192598014480025766);
 
+/// The barycentric coordinates of the vertices of the meshes drawn in place of the wireframe
+/// meshes on platforms without [`WgpuFeatures::POLYGON_MODE_LINE`].
+pub const ATTRIBUTE_WIREFRAME_BARYCENTRIC: MeshVertexAttribute =
+    MeshVertexAttribute::new("Wireframe_Barycentric", 3455905992, VertexFormat::Float32x3);
+
+/// Draws the edges of meshes, either all of them with [`WireframeConfig::global`], or the ones with
+/// a [`Wireframe`] component.
+///
+/// Wireframes are drawn as lines when the [`RenderDevice`] has the
+/// [`WgpuFeatures::POLYGON_MODE_LINE`] feature. Otherwise, like on the web, they are drawn with
+/// a copy of each mesh whose triangles don't share vertices, which only keeps the pixels close to
+/// the edges of the triangles. Only meshes with a [`PrimitiveTopology::TriangleList`] topology
+/// are supported this way.
 #[derive(Debug, Default)]
 pub struct WireframePlugin;
 
@@ -37,17 +69,30 @@ impl Plugin for WireframePlugin {
         );
 
         app.register_type::<Wireframe>()
+            .register_type::<NoWireframe>()
+            .register_type::<WireframeColor>()
             .register_type::<WireframeConfig>()
             .init_resource::<WireframeConfig>()
+            .init_resource::<WireframeMeshes>()
             .add_plugin(ExtractResourcePlugin::<WireframeConfig>::default())
-            .add_plugin(ExtractComponentPlugin::<Wireframe>::default());
+            .add_plugin(UniformComponentPlugin::<WireframeUniform>::default());
+
+        // Without line polygons, the wireframes are drawn on copies of the meshes with
+        // barycentric coordinates
+        app.add_render_feature_fallback(WgpuFeatures::POLYGON_MODE_LINE, |app, _| {
+            app.add_system(update_wireframe_meshes.in_base_set(CoreSet::PostUpdate));
+        });
 
         if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .add_render_command::<Opaque3d, DrawWireframes>()
                 .init_resource::<WireframePipeline>()
                 .init_resource::<SpecializedMeshPipelines<WireframePipeline>>()
-                .add_system(queue_wireframes.in_set(RenderSet::Queue));
+                .init_resource::<WireframeBindGroup>()
+                .add_system(extract_wireframes.in_schedule(ExtractSchedule))
+                .add_systems(
+                    (queue_wireframe_bind_group, queue_wireframes).in_set(RenderSet::Queue),
+                );
         }
     }
 }
@@ -57,23 +102,186 @@ impl Plugin for WireframePlugin {
 #[reflect(Component, Default)]
 pub struct Wireframe;
 
+/// Prevents an entity from being rendered in wireframe-mode, even when
+/// [`WireframeConfig::global`] is `true`.
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct NoWireframe;
+
+/// The color of the wireframe of an entity, replacing [`WireframeConfig::default_color`].
+#[derive(Component, Debug, Clone, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct WireframeColor {
+    pub color: Color,
+}
+
 #[derive(Resource, Debug, Clone, Default, ExtractResource, Reflect)]
 #[reflect(Resource)]
 pub struct WireframeConfig {
     /// Whether to show wireframes for all meshes. If `false`, only meshes with a [Wireframe] component will be rendered.
+    ///
+    /// Meshes with a [`NoWireframe`] component are never rendered in wireframe-mode.
     pub global: bool,
+    /// The color of the wireframes of the entities without a [`WireframeColor`].
+    pub default_color: Color,
+}
+
+/// The meshes drawn in place of the meshes of wireframe entities on platforms without
+/// [`WgpuFeatures::POLYGON_MODE_LINE`], with [`ATTRIBUTE_WIREFRAME_BARYCENTRIC`] coordinates.
+#[derive(Resource, Debug, Default)]
+pub struct WireframeMeshes {
+    meshes: HashMap<HandleId, Handle<Mesh>>,
+}
+
+impl WireframeMeshes {
+    /// The mesh drawn in place of `mesh` in wireframe-mode, or `None` if it wasn't created yet.
+    pub fn get(&self, mesh: &Handle<Mesh>) -> Option<&Handle<Mesh>> {
+        self.meshes.get(&mesh.id())
+    }
+}
+
+/// Whether an entity is rendered in wireframe-mode with this `config`.
+fn has_wireframe(
+    config: &WireframeConfig,
+    wireframe: Option<&Wireframe>,
+    no_wireframe: Option<&NoWireframe>,
+) -> bool {
+    no_wireframe.is_none() && (config.global || wireframe.is_some())
+}
+
+fn update_wireframe_meshes(
+    config: Res<WireframeConfig>,
+    mut wireframe_meshes: ResMut<WireframeMeshes>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut mesh_events: EventReader<AssetEvent<Mesh>>,
+    entities: Query<(&Handle<Mesh>, Option<&Wireframe>, Option<&NoWireframe>)>,
+) {
+    for event in &mut mesh_events {
+        // Recreate the wireframe meshes of the meshes that changed
+        if let AssetEvent::Modified { handle } | AssetEvent::Removed { handle } = event {
+            wireframe_meshes.meshes.remove(&handle.id());
+        }
+    }
+
+    let mut used = HashSet::default();
+    for (mesh, wireframe, no_wireframe) in &entities {
+        if !has_wireframe(&config, wireframe, no_wireframe) {
+            continue;
+        }
+        used.insert(mesh.id());
+        if wireframe_meshes.meshes.contains_key(&mesh.id()) {
+            continue;
+        }
+        if let Some(wireframe_mesh) = meshes.get(mesh).and_then(barycentric_mesh) {
+            let wireframe_mesh = meshes.add(wireframe_mesh);
+            wireframe_meshes.meshes.insert(mesh.id(), wireframe_mesh);
+        }
+    }
+    wireframe_meshes.meshes.retain(|id, _| used.contains(id));
+}
+
+/// Creates a copy of `mesh` whose triangles don't share vertices, with the barycentric coordinates
+/// of each vertex in its triangle.
+///
+/// Returns `None` if the mesh doesn't have a [`PrimitiveTopology::TriangleList`] topology.
+fn barycentric_mesh(mesh: &Mesh) -> Option<Mesh> {
+    if mesh.primitive_topology() != PrimitiveTopology::TriangleList {
+        return None;
+    }
+    let mut mesh = mesh.clone();
+    mesh.duplicate_vertices();
+    let barycentric: Vec<[f32; 3]> = (0..mesh.count_vertices())
+        .map(|i| match i % 3 {
+            0 => [1.0, 0.0, 0.0],
+            1 => [0.0, 1.0, 0.0],
+            _ => [0.0, 0.0, 1.0],
+        })
+        .collect();
+    mesh.insert_attribute(ATTRIBUTE_WIREFRAME_BARYCENTRIC, barycentric);
+    Some(mesh)
+}
+
+/// The color of the wireframe of an entity, in linear space.
+#[derive(Component, ShaderType, Clone)]
+pub struct WireframeUniform {
+    pub color: Vec4,
+}
+
+/// The mesh drawn in place of the mesh of a wireframe entity.
+#[derive(Component)]
+pub struct WireframeMesh(pub Handle<Mesh>);
+
+fn extract_wireframes(
+    mut commands: Commands,
+    mut previous_len: Local<usize>,
+    config: Extract<Res<WireframeConfig>>,
+    wireframe_meshes: Extract<Res<WireframeMeshes>>,
+    entities: Extract<
+        Query<(
+            Entity,
+            &ViewVisibility,
+            &Handle<Mesh>,
+            Option<&Wireframe>,
+            Option<&NoWireframe>,
+            Option<&WireframeColor>,
+        )>,
+    >,
+) {
+    let mut values = Vec::with_capacity(*previous_len);
+    let mut mesh_values = Vec::new();
+    for (entity, visibility, mesh, wireframe, no_wireframe, color) in &entities {
+        if !visibility.get() || !has_wireframe(&config, wireframe, no_wireframe) {
+            continue;
+        }
+        let color = color.map_or(config.default_color, |color| color.color);
+        values.push((
+            entity,
+            WireframeUniform {
+                color: color.as_linear_rgba_f32().into(),
+            },
+        ));
+        if let Some(wireframe_mesh) = wireframe_meshes.get(mesh) {
+            mesh_values.push((entity, WireframeMesh(wireframe_mesh.clone_weak())));
+        }
+    }
+    *previous_len = values.len();
+    commands.insert_or_spawn_batch(values);
+    commands.insert_or_spawn_batch(mesh_values);
 }
 
 #[derive(Resource, Clone)]
 pub struct WireframePipeline {
     mesh_pipeline: MeshPipeline,
+    wireframe_layout: BindGroupLayout,
     shader: Handle<Shader>,
+    /// Whether wireframes are drawn with barycentric coordinates instead of lines.
+    barycentric: bool,
 }
 impl FromWorld for WireframePipeline {
     fn from_world(render_world: &mut World) -> Self {
+        let render_device = render_world.resource::<RenderDevice>();
+        let wireframe_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::FRAGMENT,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: Some(WireframeUniform::min_size()),
+                },
+                count: None,
+            }],
+            label: Some("wireframe_layout"),
+        });
+        let barycentric = !render_device
+            .features()
+            .contains(WgpuFeatures::POLYGON_MODE_LINE);
+
         WireframePipeline {
             mesh_pipeline: render_world.resource::<MeshPipeline>().clone(),
+            wireframe_layout,
             shader: WIREFRAME_SHADER_HANDLE.typed(),
+            barycentric,
         }
     }
 }
@@ -89,74 +297,114 @@ impl SpecializedMeshPipeline for WireframePipeline {
         let mut descriptor = self.mesh_pipeline.specialize(key, layout)?;
         descriptor.vertex.shader = self.shader.clone_weak();
         descriptor.fragment.as_mut().unwrap().shader = self.shader.clone_weak();
-        descriptor.primitive.polygon_mode = PolygonMode::Line;
+        descriptor.layout.push(self.wireframe_layout.clone());
+        if self.barycentric {
+            let mut vertex_attributes = vec![Mesh::ATTRIBUTE_POSITION.at_shader_location(0)];
+            if layout.contains(Mesh::ATTRIBUTE_JOINT_INDEX)
+                && layout.contains(Mesh::ATTRIBUTE_JOINT_WEIGHT)
+            {
+                vertex_attributes.push(Mesh::ATTRIBUTE_JOINT_INDEX.at_shader_location(5));
+                vertex_attributes.push(Mesh::ATTRIBUTE_JOINT_WEIGHT.at_shader_location(6));
+            }
+            vertex_attributes.push(ATTRIBUTE_WIREFRAME_BARYCENTRIC.at_shader_location(7));
+            descriptor.vertex.buffers = vec![layout.get_layout(&vertex_attributes)?];
+            descriptor
+                .vertex
+                .shader_defs
+                .push("WIREFRAME_BARYCENTRIC".into());
+            descriptor
+                .fragment
+                .as_mut()
+                .unwrap()
+                .shader_defs
+                .push("WIREFRAME_BARYCENTRIC".into());
+        } else {
+            descriptor.primitive.polygon_mode = PolygonMode::Line;
+        }
         descriptor.depth_stencil.as_mut().unwrap().bias.slope_scale = 1.0;
         Ok(descriptor)
     }
 }
 
+#[derive(Resource, Default)]
+pub struct WireframeBindGroup {
+    value: Option<BindGroup>,
+}
+
+fn queue_wireframe_bind_group(
+    render_device: Res<RenderDevice>,
+    wireframe_pipeline: Res<WireframePipeline>,
+    wireframe_uniforms: Res<ComponentUniforms<WireframeUniform>>,
+    mut wireframe_bind_group: ResMut<WireframeBindGroup>,
+) {
+    wireframe_bind_group.value = wireframe_uniforms.binding().map(|binding| {
+        render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: binding,
+            }],
+            label: Some("wireframe_bind_group"),
+            layout: &wireframe_pipeline.wireframe_layout,
+        })
+    });
+}
+
 #[allow(clippy::too_many_arguments)]
 fn queue_wireframes(
     opaque_3d_draw_functions: Res<DrawFunctions<Opaque3d>>,
     render_meshes: Res<RenderAssets<Mesh>>,
-    wireframe_config: Res<WireframeConfig>,
     wireframe_pipeline: Res<WireframePipeline>,
     mut pipelines: ResMut<SpecializedMeshPipelines<WireframePipeline>>,
     pipeline_cache: Res<PipelineCache>,
-    msaa: Res<Msaa>,
-    mut material_meshes: ParamSet<(
-        Query<(Entity, &Handle<Mesh>, &MeshUniform)>,
-        Query<(Entity, &Handle<Mesh>, &MeshUniform), With<Wireframe>>,
+    wireframes: Query<
+        (Entity, &Handle<Mesh>, &MeshUniform, Option<&WireframeMesh>),
+        With<WireframeUniform>,
+    >,
+    mut views: Query<(
+        &ExtractedView,
+        &Msaa,
+        &VisibleEntities,
+        &mut RenderPhase<Opaque3d>,
     )>,
-    mut views: Query<(&ExtractedView, &VisibleEntities, &mut RenderPhase<Opaque3d>)>,
 ) {
     let draw_custom = opaque_3d_draw_functions.read().id::<DrawWireframes>();
-    let msaa_key = MeshPipelineKey::from_msaa_samples(msaa.samples());
-    for (view, visible_entities, mut opaque_phase) in &mut views {
+    for (view, msaa, visible_entities, mut opaque_phase) in &mut views {
         let rangefinder = view.rangefinder3d();
 
-        let view_key = msaa_key | MeshPipelineKey::from_hdr(view.hdr);
-        let add_render_phase =
-            |(entity, mesh_handle, mesh_uniform): (Entity, &Handle<Mesh>, &MeshUniform)| {
-                if let Some(mesh) = render_meshes.get(mesh_handle) {
-                    let key = view_key
-                        | MeshPipelineKey::from_primitive_topology(mesh.primitive_topology);
-                    let pipeline_id = pipelines.specialize(
-                        &pipeline_cache,
-                        &wireframe_pipeline,
-                        key,
-                        &mesh.layout,
-                    );
-                    let pipeline_id = match pipeline_id {
-                        Ok(id) => id,
-                        Err(err) => {
-                            error!("{}", err);
-                            return;
-                        }
-                    };
-                    opaque_phase.add(Opaque3d {
-                        entity,
-                        pipeline: pipeline_id,
-                        draw_function: draw_custom,
-                        distance: rangefinder.distance(&mesh_uniform.transform),
-                    });
+        let view_key = MeshPipelineKey::from_msaa_samples(msaa.samples())
+            | MeshPipelineKey::from_hdr(view.hdr);
+        for visible_entity in &visible_entities.entities {
+            let (entity, mesh_handle, mesh_uniform, wireframe_mesh) =
+                match wireframes.get(*visible_entity) {
+                    Ok(wireframe) => wireframe,
+                    Err(_) => continue,
+                };
+            let mesh_handle = match wireframe_mesh {
+                Some(wireframe_mesh) => &wireframe_mesh.0,
+                // Without lines, only the meshes with barycentric coordinates can be drawn
+                None if wireframe_pipeline.barycentric => continue,
+                None => mesh_handle,
+            };
+            let mesh = match render_meshes.get(mesh_handle) {
+                Some(mesh) => mesh,
+                None => continue,
+            };
+            let key = view_key | MeshPipelineKey::from_primitive_topology(mesh.primitive_topology);
+            let pipeline_id =
+                pipelines.specialize(&pipeline_cache, &wireframe_pipeline, key, &mesh.layout);
+            let pipeline_id = match pipeline_id {
+                Ok(id) => id,
+                Err(err) => {
+                    error!("{}", err);
+                    continue;
                 }
             };
-
-        if wireframe_config.global {
-            let query = material_meshes.p0();
-            visible_entities
-                .entities
-                .iter()
-                .filter_map(|visible_entity| query.get(*visible_entity).ok())
-                .for_each(add_render_phase);
-        } else {
-            let query = material_meshes.p1();
-            visible_entities
-                .entities
-                .iter()
-                .filter_map(|visible_entity| query.get(*visible_entity).ok())
-                .for_each(add_render_phase);
+            opaque_phase.add(Opaque3d {
+                entity,
+                pipeline: pipeline_id,
+                draw_function: draw_custom,
+                distance: rangefinder.distance(&mesh_uniform.transform),
+            });
         }
     }
 }
@@ -165,5 +413,68 @@ type DrawWireframes = (
     SetItemPipeline,
     SetMeshViewBindGroup<0>,
     SetMeshBindGroup<1>,
-    DrawMesh,
+    SetWireframeBindGroup<2>,
+    DrawWireframeMesh,
 );
+
+pub struct SetWireframeBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetWireframeBindGroup<I> {
+    type Param = SRes<WireframeBindGroup>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<DynamicUniformIndex<WireframeUniform>>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        wireframe_index: ROQueryItem<'_, Self::ItemWorldQuery>,
+        wireframe_bind_group: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        match wireframe_bind_group.into_inner().value.as_ref() {
+            Some(bind_group) => {
+                pass.set_bind_group(I, bind_group, &[wireframe_index.index()]);
+                RenderCommandResult::Success
+            }
+            None => RenderCommandResult::Failure,
+        }
+    }
+}
+
+/// Draws the [`WireframeMesh`] of an entity if it has one, or else its mesh.
+pub struct DrawWireframeMesh;
+impl<P: PhaseItem> RenderCommand<P> for DrawWireframeMesh {
+    type Param = SRes<RenderAssets<Mesh>>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = (Read<Handle<Mesh>>, Option<Read<WireframeMesh>>);
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        (mesh_handle, wireframe_mesh): ROQueryItem<'_, Self::ItemWorldQuery>,
+        meshes: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let mesh_handle = wireframe_mesh.map_or(mesh_handle, |wireframe_mesh| &wireframe_mesh.0);
+        let gpu_mesh = match meshes.into_inner().get(mesh_handle) {
+            Some(gpu_mesh) => gpu_mesh,
+            None => return RenderCommandResult::Failure,
+        };
+        pass.set_vertex_buffer(0, gpu_mesh.vertex_buffer.slice(..));
+        match &gpu_mesh.buffer_info {
+            GpuBufferInfo::Indexed {
+                buffer,
+                index_format,
+                count,
+            } => {
+                pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(0..*count, 0, 0..1);
+            }
+            GpuBufferInfo::NonIndexed { vertex_count } => {
+                pass.draw(0..*vertex_count, 0..1);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
@@ -441,6 +441,7 @@ mod fields;
 mod from_reflect;
 mod list;
 mod map;
+mod migration;
 mod path;
 mod reflect;
 mod struct_trait;
@@ -489,6 +490,7 @@ pub use from_reflect::*;
 pub use impls::*;
 pub use list::*;
 pub use map::*;
+pub use migration::*;
 pub use path::*;
 pub use reflect::*;
 pub use struct_trait::*;
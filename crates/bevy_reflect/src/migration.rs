@@ -0,0 +1,239 @@
+use crate::{FromReflect, GetTypeRegistration, Reflect, TypeRegistration, TypeRegistry};
+use std::{any::TypeId, borrow::Cow, sync::Arc};
+
+/// Type data migrating the values of the previous version of a type to the type, registered with
+/// [`TypeRegistry::register_migration`]
+///
+/// The previous versions of a type are kept as other types, like a `PlayerV1` struct with the
+/// fields the `Player` struct had, so that the values saved with them can still be deserialized.
+#[derive(Clone)]
+pub struct ReflectMigration {
+    previous_type_id: TypeId,
+    migrate: Arc<dyn Fn(&dyn Reflect) -> Option<Box<dyn Reflect>> + Send + Sync>,
+}
+
+impl ReflectMigration {
+    /// The [`TypeId`] of the previous version of the type
+    pub fn previous_type_id(&self) -> TypeId {
+        self.previous_type_id
+    }
+
+    /// Migrates a value of the previous version of the type, usually the dynamic value
+    /// deserialized for it, returning `None` if it isn't a value of the previous version.
+    pub fn migrate(&self, previous: &dyn Reflect) -> Option<Box<dyn Reflect>> {
+        (self.migrate)(previous)
+    }
+}
+
+/// The registration of the type of a value saved with an older version of its type, and the
+/// migrations to its current version
+///
+/// It's returned by [`TypeRegistry::get_versioned`] for the type names written by
+/// [`TypeRegistry::versioned_type_name`].
+pub struct VersionedRegistration<'a> {
+    /// The registration of the type the value was saved with, to deserialize it
+    pub saved: &'a TypeRegistration,
+    /// The registration of the current version of the type
+    pub current: &'a TypeRegistration,
+    /// The migrations from the saved version to the current version, the oldest first
+    pub migrations: Vec<&'a ReflectMigration>,
+}
+
+impl<'a> VersionedRegistration<'a> {
+    /// Migrates a value deserialized for the [`saved`](Self::saved) type to the current version
+    /// of the type.
+    ///
+    /// The value is returned as is if it was saved with the current version.
+    pub fn migrate(&self, mut value: Box<dyn Reflect>) -> Result<Box<dyn Reflect>, String> {
+        for migration in &self.migrations {
+            value = migration.migrate(&*value).ok_or_else(|| {
+                format!(
+                    "failed to migrate a value of `{}` to `{}`",
+                    value.type_name(),
+                    self.current.type_name()
+                )
+            })?;
+        }
+        Ok(value)
+    }
+}
+
+impl TypeRegistry {
+    /// Registers a migration of the values of `P`, the previous version of `T`, to `T`, so that
+    /// the values saved with `P` are deserialized as `T`.
+    ///
+    /// The version of `T` becomes the version of `P` plus one, so the migrations of a type are
+    /// chained from its first version. The reflected values are serialized with
+    /// [the version of their type](Self::versioned_type_name), and when a value saved with an
+    /// older version is deserialized, it's deserialized as the type of that version then migrated
+    /// to the current version.
+    ///
+    /// ```
+    /// # use bevy_reflect::{FromReflect, Reflect, TypeRegistry};
+    /// // the fields `Player` had when the first saves were made
+    /// #[derive(Reflect, FromReflect)]
+    /// struct PlayerV0 {
+    ///     hp: u32,
+    /// }
+    ///
+    /// #[derive(Reflect, FromReflect)]
+    /// struct Player {
+    ///     health: f32,
+    /// }
+    ///
+    /// let mut registry = TypeRegistry::default();
+    /// registry.register_migration(|old: PlayerV0| Player {
+    ///     health: old.hp as f32,
+    /// });
+    /// ```
+    ///
+    /// As the saves of the first version of a type are written with its type name, the first
+    /// version is found from the name of the current version. The type of the first version can
+    /// then be renamed like `PlayerV0` without breaking the saves.
+    pub fn register_migration<P, T>(&mut self, migrate: impl Fn(P) -> T + Send + Sync + 'static)
+    where
+        P: FromReflect + GetTypeRegistration,
+        T: Reflect + GetTypeRegistration,
+    {
+        self.register::<P>();
+        self.register::<T>();
+        let migration = ReflectMigration {
+            previous_type_id: TypeId::of::<P>(),
+            migrate: Arc::new(move |previous| {
+                let previous = P::from_reflect(previous)?;
+                Some(Box::new(migrate(previous)))
+            }),
+        };
+        self.get_mut(TypeId::of::<T>()).unwrap().insert(migration);
+    }
+
+    /// The migrations from the first version of a type to the type, the oldest first
+    fn migrations(&self, type_id: TypeId) -> Vec<&ReflectMigration> {
+        let mut migrations = Vec::new();
+        let mut registration = self.get(type_id);
+        while let Some(migration) = registration.and_then(|r| r.data::<ReflectMigration>()) {
+            // a cycle of migrations can't be resolved
+            if migrations
+                .iter()
+                .any(|m: &&ReflectMigration| m.previous_type_id == migration.previous_type_id)
+            {
+                break;
+            }
+            migrations.push(migration);
+            registration = self.get(migration.previous_type_id);
+        }
+        migrations.reverse();
+        migrations
+    }
+
+    /// The version of a type, the number of [migrations](Self::register_migration) from its
+    /// first version, which is 0 for types without migrations
+    pub fn type_version(&self, type_id: TypeId) -> u32 {
+        self.migrations(type_id).len() as u32
+    }
+
+    /// The name the values of a type are serialized with, with its version after an `@` if it
+    /// isn't 0, like `game::Player@2`
+    pub fn versioned_type_name<'a>(&self, registration: &'a TypeRegistration) -> Cow<'a, str> {
+        match self.type_version(registration.type_id()) {
+            0 => Cow::Borrowed(registration.type_name()),
+            version => Cow::Owned(format!("{}@{version}", registration.type_name())),
+        }
+    }
+
+    /// Finds the registration of a type from the name it was serialized with by
+    /// [`versioned_type_name`](Self::versioned_type_name), with the migrations of its values to
+    /// its current version.
+    ///
+    /// Returns `None` if the type isn't registered, or if its version is newer than the current
+    /// version.
+    pub fn get_versioned(&self, versioned_type_name: &str) -> Option<VersionedRegistration<'_>> {
+        let (type_name, version) = match versioned_type_name.rsplit_once('@') {
+            Some((type_name, version)) => (type_name, version.parse::<usize>().ok()?),
+            None => (versioned_type_name, 0),
+        };
+        let current = self.get_with_name(type_name)?;
+        let mut migrations = self.migrations(current.type_id());
+        if version > migrations.len() {
+            return None;
+        }
+        let migrations = migrations.split_off(version);
+        let saved = match migrations.first() {
+            Some(migration) => self.get(migration.previous_type_id)?,
+            None => current,
+        };
+        Some(VersionedRegistration {
+            saved,
+            current,
+            migrations,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate as bevy_reflect;
+
+    #[derive(Reflect, FromReflect, Debug, PartialEq)]
+    struct PlayerV0 {
+        hp: u32,
+    }
+
+    #[derive(Reflect, FromReflect, Debug, PartialEq)]
+    struct PlayerV1 {
+        health: u32,
+        name: String,
+    }
+
+    #[derive(Reflect, FromReflect, Debug, PartialEq)]
+    struct Player {
+        health: f32,
+        name: String,
+    }
+
+    #[test]
+    fn migration_chain() {
+        let mut registry = TypeRegistry::default();
+        registry.register_migration(|old: PlayerV1| Player {
+            health: old.health as f32,
+            name: old.name,
+        });
+        registry.register_migration(|old: PlayerV0| PlayerV1 {
+            health: old.hp,
+            name: "player".to_string(),
+        });
+        let player = registry.get(TypeId::of::<Player>()).unwrap();
+        assert_eq!(registry.type_version(TypeId::of::<Player>()), 2);
+        assert_eq!(
+            registry.versioned_type_name(player),
+            format!("{}@2", player.type_name())
+        );
+
+        let versioned = registry.get_versioned(player.type_name()).unwrap();
+        assert_eq!(versioned.saved.type_id(), TypeId::of::<PlayerV0>());
+        let migrated = versioned.migrate(Box::new(PlayerV0 { hp: 5 })).unwrap();
+        assert_eq!(
+            migrated.downcast_ref::<Player>(),
+            Some(&Player {
+                health: 5.0,
+                name: "player".to_string()
+            })
+        );
+
+        let versioned = registry
+            .get_versioned(&format!("{}@2", player.type_name()))
+            .unwrap();
+        assert_eq!(versioned.saved.type_id(), TypeId::of::<Player>());
+        assert!(versioned.migrations.is_empty());
+        assert!(registry
+            .get_versioned(&format!("{}@3", player.type_name()))
+            .is_none());
+        assert!(versioned.current.type_id() == TypeId::of::<Player>());
+
+        let versioned = registry
+            .get_versioned(&format!("{}@1", player.type_name()))
+            .unwrap();
+        assert!(versioned.migrate(Box::new(PlayerV0 { hp: 5 })).is_err());
+    }
+}
@@ -4,7 +4,7 @@ use crate::{
     DynamicTupleStruct, DynamicVariant, EnumInfo, ListInfo, Map, MapInfo, NamedField, Reflect,
     ReflectDeserialize, StructInfo, StructVariantInfo, Tuple, TupleInfo, TupleStruct,
     TupleStructInfo, TupleVariantInfo, TypeInfo, TypeRegistration, TypeRegistry, UnnamedField,
-    VariantInfo,
+    VariantInfo, VersionedRegistration,
 };
 use erased_serde::Deserializer;
 use serde::de::{
@@ -223,9 +223,13 @@ impl<'de> Visitor<'de> for U32Visitor {
 ///
 /// Because the type isn't known ahead of time, the serialized data must take the form of
 /// a map containing the following entries (in order):
-/// 1. `type`: The _full_ [type name]
+/// 1. `type`: The _full_ [type name], followed by the version of the type if it has
+///    [migrations](TypeRegistry::register_migration)
 /// 2. `value`: The serialized value of the reflected type
 ///
+/// A value saved with an older version of its type is deserialized as the type of that version,
+/// then migrated to the current version, which returns a value of the concrete type.
+///
 /// If the type is already known and the [`TypeInfo`] for it can be retrieved,
 /// [`TypedReflectDeserializer`] may be used instead to avoid requiring these entries.
 ///
@@ -305,6 +309,54 @@ impl<'a, 'de> DeserializeSeed<'de> for TypeRegistrationDeserializer<'a> {
     }
 }
 
+/// A deserializer for the type registrations of values saved with a version of their type.
+///
+/// This will return a [`VersionedRegistration`] with the registration of the type the value was
+/// saved with and the migrations to the current version of the type. This deserializer expects a
+/// string containing a type name written by [`TypeRegistry::versioned_type_name`], like the
+/// _full_ [type name] of a type without migrations.
+///
+/// [type name]: std::any::type_name
+pub struct VersionedRegistrationDeserializer<'a> {
+    registry: &'a TypeRegistry,
+}
+
+impl<'a> VersionedRegistrationDeserializer<'a> {
+    pub fn new(registry: &'a TypeRegistry) -> Self {
+        Self { registry }
+    }
+}
+
+impl<'a, 'de> DeserializeSeed<'de> for VersionedRegistrationDeserializer<'a> {
+    type Value = VersionedRegistration<'a>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct VersionedRegistrationVisitor<'a>(&'a TypeRegistry);
+
+        impl<'de, 'a> Visitor<'de> for VersionedRegistrationVisitor<'a> {
+            type Value = VersionedRegistration<'a>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("string containing `type` entry for the reflected value")
+            }
+
+            fn visit_str<E>(self, type_name: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                self.0.get_versioned(type_name).ok_or_else(|| {
+                    Error::custom(format_args!("No registration found for `{type_name}`"))
+                })
+            }
+        }
+
+        deserializer.deserialize_str(VersionedRegistrationVisitor(self.registry))
+    }
+}
+
 struct UntypedReflectDeserializerVisitor<'a> {
     registry: &'a TypeRegistry,
 }
@@ -320,14 +372,14 @@ impl<'a, 'de> Visitor<'de> for UntypedReflectDeserializerVisitor<'a> {
     where
         A: MapAccess<'de>,
     {
-        let registration = map
-            .next_key_seed(TypeRegistrationDeserializer::new(self.registry))?
+        let versioned = map
+            .next_key_seed(VersionedRegistrationDeserializer::new(self.registry))?
             .ok_or_else(|| Error::invalid_length(0, &"at least one entry"))?;
         let value = map.next_value_seed(TypedReflectDeserializer {
-            registration,
+            registration: versioned.saved,
             registry: self.registry,
         })?;
-        Ok(value)
+        versioned.migrate(value).map_err(Error::custom)
     }
 }
 
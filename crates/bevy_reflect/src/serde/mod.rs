@@ -12,7 +12,7 @@ mod tests {
     use crate::{
         serde::{ReflectSerializer, UntypedReflectDeserializer},
         type_registry::TypeRegistry,
-        DynamicStruct, Reflect,
+        DynamicStruct, FromReflect, Reflect,
     };
     use serde::de::DeserializeSeed;
 
@@ -92,4 +92,47 @@ mod tests {
             "Expected {expected:?} found {deserialized:?}"
         );
     }
+
+    #[test]
+    fn test_serialization_migration() {
+        #[derive(Reflect, FromReflect)]
+        struct TestStructV0 {
+            a: i32,
+        }
+
+        #[derive(Debug, Reflect, FromReflect, PartialEq)]
+        struct TestStruct {
+            a: i64,
+            b: bool,
+        }
+
+        let mut registry = TypeRegistry::default();
+        registry.register_migration(|old: TestStructV0| TestStruct {
+            a: old.a.into(),
+            b: false,
+        });
+
+        // saved before the migration, with the name of the type
+        let input =
+            r#"{"bevy_reflect::serde::tests::test_serialization_migration::TestStruct":(a:3)}"#;
+        let mut deserializer = ron::de::Deserializer::from_str(input).unwrap();
+        let reflect_deserializer = UntypedReflectDeserializer::new(&registry);
+        let value = reflect_deserializer.deserialize(&mut deserializer).unwrap();
+        let migrated = TestStruct { a: 3, b: false };
+        assert_eq!(value.downcast_ref::<TestStruct>(), Some(&migrated));
+
+        let serializer = ReflectSerializer::new(&migrated, &registry);
+        let serialized = ron::ser::to_string(&serializer).unwrap();
+        assert_eq!(
+            serialized,
+            r#"{"bevy_reflect::serde::tests::test_serialization_migration::TestStruct@1":(a:3,b:false)}"#
+        );
+        let mut deserializer = ron::de::Deserializer::from_str(&serialized).unwrap();
+        let reflect_deserializer = UntypedReflectDeserializer::new(&registry);
+        let value = reflect_deserializer.deserialize(&mut deserializer).unwrap();
+        assert_eq!(
+            TestStruct::from_reflect(&*value),
+            Some(TestStruct { a: 3, b: false })
+        );
+    }
 }
@@ -66,7 +66,8 @@ fn get_type_info<E: Error>(
 /// A general purpose serializer for reflected types.
 ///
 /// The serialized data will take the form of a map containing the following entries:
-/// 1. `type`: The _full_ [type name]
+/// 1. `type`: The _full_ [type name], followed by the version of the type if it has
+///    [migrations](TypeRegistry::register_migration)
 /// 2. `value`: The serialized value of the reflected type
 ///
 /// [type name]: std::any::type_name
@@ -86,9 +87,13 @@ impl<'a> Serialize for ReflectSerializer<'a> {
     where
         S: serde::Serializer,
     {
+        let type_name = match self.registry.get_with_name(self.value.type_name()) {
+            Some(registration) => self.registry.versioned_type_name(registration),
+            None => self.value.type_name().into(),
+        };
         let mut state = serializer.serialize_map(Some(1))?;
         state.serialize_entry(
-            self.value.type_name(),
+            &*type_name,
             &TypedReflectSerializer::new(self.value, self.registry),
         )?;
         state.end()
@@ -3,7 +3,7 @@ use crate::{
     prelude::Image,
     render_asset::RenderAssets,
     render_resource::TextureView,
-    view::{ColorGrading, ExtractedView, ExtractedWindows, VisibleEntities},
+    view::{ColorGrading, ExtractedView, ExtractedWindows, Msaa, VisibleEntities},
     Extract,
 };
 use bevy_asset::{AssetEvent, Assets, Handle};
@@ -15,19 +15,19 @@ use bevy_ecs::{
     event::EventReader,
     prelude::With,
     reflect::ReflectComponent,
-    system::{Commands, Query, Res},
+    system::{Commands, Query, Res, ResMut, Resource},
 };
 use bevy_math::{Mat4, Ray, UVec2, UVec4, Vec2, Vec3};
 use bevy_reflect::prelude::*;
 use bevy_reflect::FromReflect;
 use bevy_transform::components::GlobalTransform;
-use bevy_utils::HashSet;
+use bevy_utils::{tracing::warn, HashMap, HashSet};
 use bevy_window::{
     NormalizedWindowRef, PrimaryWindow, Window, WindowCreated, WindowRef, WindowResized,
 };
 
 use std::{borrow::Cow, ops::Range};
-use wgpu::{Extent3d, TextureFormat};
+use wgpu::{BlendState, Extent3d, LoadOp, TextureFormat};
 
 /// Render viewport configuration for the [`Camera`] component.
 ///
@@ -91,6 +91,18 @@ pub struct Camera {
     /// If set, this camera will render to the given [`Viewport`] rectangle within the configured [`RenderTarget`].
     pub viewport: Option<Viewport>,
     /// Cameras with a higher order are rendered later, and thus on top of lower order cameras.
+    ///
+    /// Cameras rendering to the same [`RenderTarget`] with the same [`hdr`](Self::hdr) setting
+    /// and [`Msaa`] share their intermediate textures: a camera drawing over the lower order
+    /// ones, like a 2D camera drawing a HUD over a 3D scene, should not clear it, for example by
+    /// setting the `clear_color` of its `Camera2d` to `ClearColorConfig::None`.
+    ///
+    /// Cameras with different [`hdr`](Self::hdr) or [`Msaa`] settings don't share them, so a
+    /// higher order camera has to blend its output over the target instead, see
+    /// [`CameraOutputMode`].
+    ///
+    /// No two active cameras rendering to the same target should have the same order, as the
+    /// order they are rendered in would then be unspecified.
     pub order: isize,
     /// If this is set to `true`, this camera will be rendered to its specified [`RenderTarget`]. If `false`, this
     /// camera will not be rendered.
@@ -107,6 +119,44 @@ pub struct Camera {
     /// See <https://github.com/bevyengine/bevy/pull/3425> for details.
     // TODO: resolve the issues mentioned in the doc comment above, then remove the warning.
     pub hdr: bool,
+    /// How the output of this camera is written to its [`RenderTarget`].
+    #[reflect(ignore)]
+    pub output_mode: CameraOutputMode,
+}
+
+/// How the output of a [`Camera`] is written to its [`RenderTarget`], after it was rendered to
+/// its intermediate textures.
+#[derive(Debug, Clone, Copy)]
+pub enum CameraOutputMode {
+    /// Writes the output of the camera to its render target.
+    Write {
+        /// How the output is blended with the render target.
+        ///
+        /// `None` replaces the render target, while for example [`BlendState::ALPHA_BLENDING`]
+        /// draws the output over it: clear the camera to a transparent color and use this to
+        /// draw it over the output of a lower order camera with a different `hdr` setting.
+        blend_state: Option<BlendState>,
+        /// What the render target is initialized with before the output is written to it.
+        ///
+        /// Blending the output over the render target requires [`LoadOp::Load`].
+        color_attachment_load_op: LoadOp<wgpu::Color>,
+    },
+    /// Leaves the output of the camera in its intermediate textures, without writing it to its
+    /// render target.
+    ///
+    /// A higher order camera sharing the intermediate textures then writes them, so this removes
+    /// redundant writes when several cameras render to the same target. The output of the camera
+    /// is lost otherwise.
+    Skip,
+}
+
+impl Default for CameraOutputMode {
+    fn default() -> Self {
+        CameraOutputMode::Write {
+            blend_state: None,
+            color_attachment_load_op: LoadOp::Clear(Default::default()),
+        }
+    }
 }
 
 impl Default for Camera {
@@ -118,6 +168,7 @@ impl Default for Camera {
             computed: Default::default(),
             target: Default::default(),
             hdr: false,
+            output_mode: Default::default(),
         }
     }
 }
@@ -197,6 +248,13 @@ impl Camera {
         self.computed.target_info.as_ref().map(|t| t.physical_size)
     }
 
+    /// The scale factor of this camera's [`RenderTarget`], from its logical pixels to its physical
+    /// pixels, like the scale factor of its window. Image targets have a scale factor of `1.0`.
+    #[inline]
+    pub fn target_scaling_factor(&self) -> Option<f64> {
+        self.computed.target_info.as_ref().map(|t| t.scale_factor)
+    }
+
     /// The projection matrix computed using this camera's [`CameraProjection`].
     #[inline]
     pub fn projection_matrix(&self) -> Mat4 {
@@ -519,6 +577,12 @@ pub struct ExtractedCamera {
     pub viewport: Option<Viewport>,
     pub render_graph: Cow<'static, str>,
     pub order: isize,
+    pub output_mode: CameraOutputMode,
+    /// The index of this camera among the cameras rendering to the same target, sorted by
+    /// [`order`](Camera::order).
+    ///
+    /// Set by [`sort_cameras`].
+    pub sorted_camera_index_for_target: usize,
 }
 
 pub fn extract_cameras(
@@ -531,12 +595,13 @@ pub fn extract_cameras(
             &GlobalTransform,
             &VisibleEntities,
             Option<&ColorGrading>,
+            Option<&Msaa>,
         )>,
     >,
     primary_window: Extract<Query<Entity, With<PrimaryWindow>>>,
 ) {
     let primary_window = primary_window.iter().next();
-    for (entity, camera, camera_render_graph, transform, visible_entities, color_grading) in
+    for (entity, camera, camera_render_graph, transform, visible_entities, color_grading, msaa) in
         query.iter()
     {
         let color_grading = *color_grading.unwrap_or(&ColorGrading::default());
@@ -560,6 +625,8 @@ pub fn extract_cameras(
                     physical_target_size: Some(target_size),
                     render_graph: camera_render_graph.0.clone(),
                     order: camera.order,
+                    output_mode: camera.output_mode,
+                    sorted_camera_index_for_target: 0,
                 },
                 ExtractedView {
                     projection: camera.projection_matrix(),
@@ -575,7 +642,127 @@ pub fn extract_cameras(
                     color_grading,
                 },
                 visible_entities.clone(),
+                // Cameras without an `Msaa` use the default sample count
+                msaa.copied().unwrap_or_default(),
             ));
         }
     }
 }
+
+/// The active cameras of the render world, sorted by [`order`](Camera::order).
+#[derive(Resource, Default, Debug)]
+pub struct SortedCameras(pub Vec<SortedCamera>);
+
+#[derive(Debug)]
+pub struct SortedCamera {
+    pub entity: Entity,
+    pub order: isize,
+    pub target: Option<NormalizedRenderTarget>,
+}
+
+/// Sorts the [`ExtractedCamera`]s into the [`SortedCameras`], and sets their
+/// [`sorted_camera_index_for_target`](ExtractedCamera::sorted_camera_index_for_target).
+///
+/// Warns about the cameras rendering to the same target with the same order.
+pub fn sort_cameras(
+    mut sorted_cameras: ResMut<SortedCameras>,
+    mut cameras: Query<(Entity, &mut ExtractedCamera)>,
+) {
+    sorted_cameras.0.clear();
+    for (entity, camera) in cameras.iter() {
+        sorted_cameras.0.push(SortedCamera {
+            entity,
+            order: camera.order,
+            target: camera.target.clone(),
+        });
+    }
+    // sort by order and ensure within an order, RenderTargets of the same type are packed together
+    sorted_cameras
+        .0
+        .sort_by(|c1, c2| match c1.order.cmp(&c2.order) {
+            std::cmp::Ordering::Equal => c1.target.cmp(&c2.target),
+            ord => ord,
+        });
+
+    let mut previous_order_target = None;
+    let mut ambiguities = HashSet::new();
+    let mut target_counts = HashMap::new();
+    for sorted_camera in &sorted_cameras.0 {
+        let new_order_target = (sorted_camera.order, sorted_camera.target.clone());
+        if let Some(previous_order_target) = previous_order_target {
+            if previous_order_target == new_order_target {
+                ambiguities.insert(new_order_target.clone());
+            }
+        }
+        if let Some(target) = &sorted_camera.target {
+            let count = target_counts.entry(target.clone()).or_insert(0usize);
+            let (_, mut camera) = cameras.get_mut(sorted_camera.entity).unwrap();
+            camera.sorted_camera_index_for_target = *count;
+            *count += 1;
+        }
+        previous_order_target = Some(new_order_target);
+    }
+
+    if !ambiguities.is_empty() {
+        warn!(
+            "Camera order ambiguities detected for active cameras with the following priorities: {:?}. \
+            To fix this, ensure there is exactly one Camera entity spawned with a given order for a given RenderTarget. \
+            Ambiguities should be resolved because either (1) multiple active cameras were spawned accidentally, which will \
+            result in rendering multiple instances of the scene or (2) for cases where multiple active cameras is intentional, \
+            ambiguities could result in unpredictable render results.",
+            ambiguities
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_asset::HandleId;
+    use bevy_ecs::{schedule::Schedule, world::World};
+
+    fn extracted_camera(order: isize, target: &Handle<Image>) -> ExtractedCamera {
+        ExtractedCamera {
+            target: Some(NormalizedRenderTarget::Image(target.clone_weak())),
+            physical_viewport_size: None,
+            physical_target_size: None,
+            viewport: None,
+            render_graph: Cow::Borrowed("graph"),
+            order,
+            output_mode: CameraOutputMode::default(),
+            sorted_camera_index_for_target: 0,
+        }
+    }
+
+    #[test]
+    fn sort_cameras_by_order_per_target() {
+        let mut world = World::new();
+        world.init_resource::<SortedCameras>();
+        let target_a = Handle::weak(HandleId::random::<Image>());
+        let target_b = Handle::weak(HandleId::random::<Image>());
+        let hud = world.spawn(extracted_camera(1, &target_a)).id();
+        let scene = world.spawn(extracted_camera(0, &target_a)).id();
+        let other = world.spawn(extracted_camera(-1, &target_b)).id();
+
+        let mut schedule = Schedule::new();
+        schedule.add_system(sort_cameras);
+        schedule.run(&mut world);
+
+        let sorted: Vec<Entity> = world
+            .resource::<SortedCameras>()
+            .0
+            .iter()
+            .map(|camera| camera.entity)
+            .collect();
+        assert_eq!(sorted, vec![other, scene, hud]);
+        let index = |entity| {
+            world
+                .get::<ExtractedCamera>(entity)
+                .unwrap()
+                .sorted_camera_index_for_target
+        };
+        assert_eq!(index(scene), 0);
+        assert_eq!(index(hud), 1);
+        assert_eq!(index(other), 0);
+    }
+}
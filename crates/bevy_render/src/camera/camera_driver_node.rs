@@ -1,11 +1,11 @@
 use crate::{
-    camera::{ExtractedCamera, NormalizedRenderTarget},
+    camera::{ExtractedCamera, NormalizedRenderTarget, SortedCameras},
     render_graph::{Node, NodeRunError, RenderGraphContext, SlotValue},
     renderer::RenderContext,
     view::ExtractedWindows,
 };
 use bevy_ecs::{entity::Entity, prelude::QueryState, world::World};
-use bevy_utils::{tracing::warn, HashSet};
+use bevy_utils::HashSet;
 use wgpu::{LoadOp, Operations, RenderPassColorAttachment, RenderPassDescriptor};
 
 pub struct CameraDriverNode {
@@ -30,47 +30,20 @@ impl Node for CameraDriverNode {
         render_context: &mut RenderContext,
         world: &World,
     ) -> Result<(), NodeRunError> {
-        let mut sorted_cameras = self
-            .cameras
-            .iter_manual(world)
-            .map(|(e, c)| (e, c.order, c.target.clone()))
-            .collect::<Vec<_>>();
-        // sort by order and ensure within an order, RenderTargets of the same type are packed together
-        sorted_cameras.sort_by(|(_, p1, t1), (_, p2, t2)| match p1.cmp(p2) {
-            std::cmp::Ordering::Equal => t1.cmp(t2),
-            ord => ord,
-        });
+        let sorted_cameras = world.resource::<SortedCameras>();
         let mut camera_windows = HashSet::new();
-        let mut previous_order_target = None;
-        let mut ambiguities = HashSet::new();
-        for (entity, order, target) in sorted_cameras {
-            let new_order_target = (order, target);
-            if let Some(previous_order_target) = previous_order_target {
-                if previous_order_target == new_order_target {
-                    ambiguities.insert(new_order_target.clone());
-                }
-            }
-            previous_order_target = Some(new_order_target);
-            if let Ok((_, camera)) = self.cameras.get_manual(world, entity) {
+        for sorted_camera in &sorted_cameras.0 {
+            if let Ok((_, camera)) = self.cameras.get_manual(world, sorted_camera.entity) {
                 if let Some(NormalizedRenderTarget::Window(window_ref)) = camera.target {
                     camera_windows.insert(window_ref.entity());
                 }
-                graph
-                    .run_sub_graph(camera.render_graph.clone(), vec![SlotValue::Entity(entity)])?;
+                graph.run_sub_graph(
+                    camera.render_graph.clone(),
+                    vec![SlotValue::Entity(sorted_camera.entity)],
+                )?;
             }
         }
 
-        if !ambiguities.is_empty() {
-            warn!(
-                "Camera order ambiguities detected for active cameras with the following priorities: {:?}. \
-                To fix this, ensure there is exactly one Camera entity spawned with a given order for a given RenderTarget. \
-                Ambiguities should be resolved because either (1) multiple active cameras were spawned accidentally, which will \
-                result in rendering multiple instances of the scene or (2) for cases where multiple active cameras is intentional, \
-                ambiguities could result in unpredictable render results.",
-                ambiguities
-            );
-        }
-
         // wgpu (and some backends) require doing work for swap chains if you call `get_current_texture()` and `present()`
         // This ensures that Bevy doesn't crash, even when there are no cameras (and therefore no work submitted).
         for (id, window) in world.resource::<ExtractedWindows>().iter() {
@@ -0,0 +1,772 @@
+use crate::{
+    main_graph,
+    render_asset::{PrepareAssetError, RenderAsset, RenderAssetPlugin, RenderAssets},
+    render_graph::{Node, NodeId, NodeRunError, RenderGraph, RenderGraphContext},
+    render_resource::{
+        encase::{
+            internal::{CreateFrom, WriteInto},
+            StorageBuffer,
+        },
+        BindGroup, BindGroupDescriptor, BindGroupEntry, BindingResource, Buffer, BufferDescriptor,
+        BufferInitDescriptor, BufferUsages, CachedComputePipelineId, ComputePassDescriptor,
+        ComputePipelineDescriptor, MapMode, PipelineCache, ShaderType,
+    },
+    renderer::{RenderContext, RenderDevice},
+    texture::Image,
+    RenderApp, RenderSet,
+};
+use bevy_app::prelude::*;
+use bevy_asset::{AddAsset, Handle, HandleId};
+use bevy_ecs::{
+    prelude::*,
+    system::{lifetimeless::SRes, SystemParamItem},
+};
+use bevy_reflect::TypeUuid;
+use bevy_utils::HashMap;
+use std::{
+    borrow::Cow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+/// Adds support for running compute shaders with [`ComputeDispatch`]es, reading from and
+/// writing to [`ComputeBuffer`] assets.
+#[derive(Default)]
+pub struct ComputePlugin;
+
+impl Plugin for ComputePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<ComputePipelineDescriptor>()
+            .add_asset::<ComputeBuffer>()
+            .init_resource::<ComputeReadbacks>()
+            .add_plugin(RenderAssetPlugin::<ComputePipelineDescriptor>::default())
+            .add_plugin(RenderAssetPlugin::<ComputeBuffer>::default());
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            let (sender, receiver) = async_channel::unbounded();
+            render_app
+                .insert_resource(ComputeReadbackSender(sender))
+                .init_resource::<ComputeDispatchOrder>()
+                .add_system(map_compute_readbacks.in_set(RenderSet::Cleanup));
+
+            let mut graph = render_app.world.resource_mut::<RenderGraph>();
+            graph.add_node(ComputeReadbackNode::NAME, ComputeReadbackNode);
+            graph.add_node_edge(ComputeReadbackNode::NAME, main_graph::node::CAMERA_DRIVER);
+
+            app.insert_resource(ComputeReadbackReceiver(receiver))
+                .add_system(receive_compute_readbacks.in_base_set(CoreSet::PreUpdate));
+        }
+    }
+}
+
+/// The raw contents of a GPU buffer used by compute shaders.
+///
+/// The buffer can be bound both as a storage and as a uniform buffer. Modifying the asset
+/// uploads its new contents to a new GPU buffer, discarding what shaders wrote to the previous one.
+#[derive(Debug, Clone, TypeUuid)]
+#[uuid = "a8d3b5e2-6c1f-4e79-b0a4-3f2e8c9d1b67"]
+pub struct ComputeBuffer {
+    /// The initial contents of the buffer, padded to a multiple of 4 bytes on the GPU.
+    pub data: Vec<u8>,
+    /// Whether the contents of the buffer are copied to the [`ComputeReadbacks`] every frame,
+    /// after all dispatches ran.
+    ///
+    /// Reading a buffer back waits for the GPU to finish the frame, so only enable this for
+    /// buffers whose results are needed on the CPU.
+    pub readback: bool,
+}
+
+impl ComputeBuffer {
+    pub fn new(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            readback: false,
+        }
+    }
+
+    /// Creates a buffer of `size` bytes filled with zeros.
+    pub fn zeroed(size: usize) -> Self {
+        Self::new(vec![0; size])
+    }
+
+    /// Creates a buffer containing `value`, laid out following the storage buffer rules of WGSL.
+    pub fn from_value<T: ShaderType + WriteInto>(value: &T) -> Self {
+        let mut buffer = StorageBuffer::new(Vec::new());
+        buffer.write(value).unwrap();
+        Self::new(buffer.into_inner())
+    }
+
+    /// Enables [`readback`](Self::readback) of the buffer.
+    pub fn with_readback(mut self) -> Self {
+        self.readback = true;
+        self
+    }
+}
+
+/// The GPU representation of a [`ComputeBuffer`].
+#[derive(Debug, Clone)]
+pub struct GpuComputeBuffer {
+    pub buffer: Buffer,
+    /// The buffer the contents are copied to, when read back.
+    pub readback_buffer: Option<Buffer>,
+    /// The size of the buffers, in bytes.
+    pub size: u64,
+}
+
+impl RenderAsset for ComputeBuffer {
+    type ExtractedAsset = ComputeBuffer;
+    type PreparedAsset = GpuComputeBuffer;
+    type Param = SRes<RenderDevice>;
+
+    fn extract_asset(&self) -> Self::ExtractedAsset {
+        self.clone()
+    }
+
+    fn prepare_asset(
+        mut extracted_asset: Self::ExtractedAsset,
+        render_device: &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        // Copies and bindings need a non empty size aligned to 4 bytes
+        let size = (extracted_asset.data.len().max(1) + 3) & !3;
+        extracted_asset.data.resize(size, 0);
+
+        let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+            label: Some("compute_buffer"),
+            contents: &extracted_asset.data,
+            usage: BufferUsages::STORAGE
+                | BufferUsages::UNIFORM
+                | BufferUsages::COPY_SRC
+                | BufferUsages::COPY_DST,
+        });
+        let readback_buffer = extracted_asset.readback.then(|| {
+            render_device.create_buffer(&BufferDescriptor {
+                label: Some("compute_readback_buffer"),
+                size: size as u64,
+                usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+
+        Ok(GpuComputeBuffer {
+            buffer,
+            readback_buffer,
+            size: size as u64,
+        })
+    }
+}
+
+impl RenderAsset for ComputePipelineDescriptor {
+    type ExtractedAsset = ComputePipelineDescriptor;
+    type PreparedAsset = CachedComputePipelineId;
+    type Param = SRes<PipelineCache>;
+
+    fn extract_asset(&self) -> Self::ExtractedAsset {
+        self.clone()
+    }
+
+    fn prepare_asset(
+        extracted_asset: Self::ExtractedAsset,
+        pipeline_cache: &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        Ok(pipeline_cache.queue_compute_pipeline(extracted_asset))
+    }
+}
+
+/// The contents of the [`ComputeBuffer`]s flagged for [`readback`](ComputeBuffer::readback),
+/// as of the last rendered frame.
+///
+/// Results lag a frame or two behind the main world, since the render world reads them
+/// back at the end of its frame.
+#[derive(Resource, Debug, Default)]
+pub struct ComputeReadbacks {
+    data: HashMap<HandleId, Vec<u8>>,
+}
+
+impl ComputeReadbacks {
+    /// Returns the raw contents of `buffer`, if it has been read back yet.
+    pub fn get(&self, buffer: &Handle<ComputeBuffer>) -> Option<&[u8]> {
+        self.data.get(&buffer.id()).map(Vec::as_slice)
+    }
+
+    /// Reads the contents of `buffer` as a `T`, laid out following the storage buffer
+    /// rules of WGSL.
+    ///
+    /// Returns `None` if the buffer hasn't been read back yet, or is too small to hold a `T`.
+    pub fn read<T: ShaderType + CreateFrom>(&self, buffer: &Handle<ComputeBuffer>) -> Option<T> {
+        StorageBuffer::new(self.get(buffer)?).create().ok()
+    }
+}
+
+#[derive(Resource)]
+struct ComputeReadbackSender(async_channel::Sender<(HandleId, Vec<u8>)>);
+
+#[derive(Resource)]
+struct ComputeReadbackReceiver(async_channel::Receiver<(HandleId, Vec<u8>)>);
+
+fn receive_compute_readbacks(
+    receiver: Res<ComputeReadbackReceiver>,
+    mut readbacks: ResMut<ComputeReadbacks>,
+) {
+    while let Ok((id, data)) = receiver.0.try_recv() {
+        readbacks.data.insert(id, data);
+    }
+}
+
+/// Maps the readback buffers filled by the [`ComputeReadbackNode`] and sends their contents to
+/// the main world.
+fn map_compute_readbacks(
+    buffers: Res<RenderAssets<ComputeBuffer>>,
+    render_device: Res<RenderDevice>,
+    sender: Res<ComputeReadbackSender>,
+) {
+    let mut pending = Vec::new();
+    for (handle, gpu_buffer) in buffers.iter() {
+        if let Some(readback_buffer) = &gpu_buffer.readback_buffer {
+            let mapped = Arc::new(AtomicBool::new(false));
+            let mapped_clone = mapped.clone();
+            readback_buffer
+                .slice(..)
+                .map_async(MapMode::Read, move |result| {
+                    mapped_clone.store(result.is_ok(), Ordering::Release);
+                });
+            pending.push((handle.id(), readback_buffer, mapped));
+        }
+    }
+    if pending.is_empty() {
+        return;
+    }
+
+    render_device.poll(wgpu::Maintain::Wait);
+    for (id, readback_buffer, mapped) in pending {
+        if !mapped.load(Ordering::Acquire) {
+            continue;
+        }
+        let data = readback_buffer.slice(..).get_mapped_range().to_vec();
+        readback_buffer.unmap();
+        // The receiver only goes away with the main world
+        let _ = sender.0.try_send((id, data));
+    }
+}
+
+/// A render graph node copying the [`ComputeBuffer`]s flagged for readback, after every
+/// [`ComputeDispatch`] ran.
+pub struct ComputeReadbackNode;
+
+impl ComputeReadbackNode {
+    pub const NAME: &str = "compute_readback";
+}
+
+impl Node for ComputeReadbackNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let buffers = world.resource::<RenderAssets<ComputeBuffer>>();
+        for gpu_buffer in buffers.values() {
+            if let Some(readback_buffer) = &gpu_buffer.readback_buffer {
+                render_context.command_encoder().copy_buffer_to_buffer(
+                    &gpu_buffer.buffer,
+                    0,
+                    readback_buffer,
+                    0,
+                    gpu_buffer.size,
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A resource bound by a [`ComputeDispatch`].
+#[derive(Debug, Clone)]
+pub enum ComputeBinding {
+    /// A [`ComputeBuffer`], bound as a storage or uniform buffer.
+    Buffer(Handle<ComputeBuffer>),
+    /// The view of an [`Image`], bound as a sampled or storage texture.
+    ///
+    /// Storage textures need the [`TextureUsages::STORAGE_BINDING`](crate::render_resource::TextureUsages::STORAGE_BINDING)
+    /// usage on the [`Image::texture_descriptor`].
+    Texture(Handle<Image>),
+    /// The sampler of an [`Image`].
+    Sampler(Handle<Image>),
+}
+
+/// A compute shader dispatch, run by the render graph before any camera is drawn.
+///
+/// The [`ComputePipelineDescriptor`] asset is dispatched with its bind group `0` made of the
+/// bound resources. Its layout must be left empty, so that it is derived from the shader.
+///
+/// ```no_run
+/// # use bevy_app::App;
+/// # use bevy_asset::{AssetServer, Assets};
+/// # use bevy_render::{compute::{ComputeBuffer, ComputeDispatch}, render_resource::ComputePipelineDescriptor};
+/// # let mut app = App::new();
+/// let shader = app.world.resource::<AssetServer>().load("shaders/simulate.wgsl");
+/// let pipeline = app.world.resource_mut::<Assets<ComputePipelineDescriptor>>().add(
+///     ComputePipelineDescriptor {
+///         label: None,
+///         layout: Vec::new(),
+///         push_constant_ranges: Vec::new(),
+///         shader,
+///         shader_defs: Vec::new(),
+///         entry_point: "main".into(),
+///     },
+/// );
+/// let buffer = app.world.resource_mut::<Assets<ComputeBuffer>>().add(
+///     ComputeBuffer::zeroed(1024 * 4).with_readback(),
+/// );
+///
+/// ComputeDispatch::new(pipeline)
+///     .with_buffer(0, buffer)
+///     .with_workgroups(1024 / 64, 1, 1)
+///     .add_to_graph(&mut app, "simulate");
+/// ```
+#[derive(Debug, Clone)]
+pub struct ComputeDispatch {
+    pipeline: Handle<ComputePipelineDescriptor>,
+    bindings: Vec<(u32, ComputeBinding)>,
+    workgroups: [u32; 3],
+    once: bool,
+}
+
+impl ComputeDispatch {
+    /// Creates a dispatch of a single workgroup of `pipeline`, every frame.
+    pub fn new(pipeline: Handle<ComputePipelineDescriptor>) -> Self {
+        Self {
+            pipeline,
+            bindings: Vec::new(),
+            workgroups: [1, 1, 1],
+            once: false,
+        }
+    }
+
+    /// Binds `resource` at `binding` of the bind group `0`.
+    pub fn with_binding(mut self, binding: u32, resource: ComputeBinding) -> Self {
+        self.bindings.push((binding, resource));
+        self
+    }
+
+    /// Binds a [`ComputeBuffer`] at `binding`.
+    pub fn with_buffer(self, binding: u32, buffer: Handle<ComputeBuffer>) -> Self {
+        self.with_binding(binding, ComputeBinding::Buffer(buffer))
+    }
+
+    /// Binds the view of an [`Image`] at `binding`.
+    pub fn with_texture(self, binding: u32, image: Handle<Image>) -> Self {
+        self.with_binding(binding, ComputeBinding::Texture(image))
+    }
+
+    /// Binds the sampler of an [`Image`] at `binding`.
+    pub fn with_sampler(self, binding: u32, image: Handle<Image>) -> Self {
+        self.with_binding(binding, ComputeBinding::Sampler(image))
+    }
+
+    /// Sets the number of workgroups dispatched along each dimension.
+    pub fn with_workgroups(mut self, x: u32, y: u32, z: u32) -> Self {
+        self.workgroups = [x, y, z];
+        self
+    }
+
+    /// Only dispatches once, as soon as the pipeline and all bound resources are ready.
+    pub fn once(mut self) -> Self {
+        self.once = true;
+        self
+    }
+
+    /// Adds a node running this dispatch to the render graph of `app`.
+    ///
+    /// Dispatches run in the order they are added, before the [`ComputeReadbackNode`] and
+    /// the cameras.
+    pub fn add_to_graph(self, app: &mut App, name: impl Into<Cow<'static, str>>) {
+        let render_app = match app.get_sub_app_mut(RenderApp) {
+            Ok(render_app) => render_app,
+            Err(_) => return,
+        };
+
+        let mut graph = render_app.world.resource_mut::<RenderGraph>();
+        let node_id = graph.add_node(name, ComputeDispatchNode::new(self));
+        graph.add_node_edge(node_id, ComputeReadbackNode::NAME);
+        let previous = render_app
+            .world
+            .resource_mut::<ComputeDispatchOrder>()
+            .last
+            .replace(node_id);
+        if let Some(previous) = previous {
+            render_app
+                .world
+                .resource_mut::<RenderGraph>()
+                .add_node_edge(previous, node_id);
+        }
+    }
+}
+
+/// The last [`ComputeDispatch`] node added to the render graph.
+#[derive(Resource, Default)]
+struct ComputeDispatchOrder {
+    last: Option<NodeId>,
+}
+
+struct ComputeDispatchNode {
+    dispatch: ComputeDispatch,
+    pipeline: Option<CachedComputePipelineId>,
+    bind_group: Option<BindGroup>,
+    done: bool,
+}
+
+impl ComputeDispatchNode {
+    fn new(dispatch: ComputeDispatch) -> Self {
+        Self {
+            dispatch,
+            pipeline: None,
+            bind_group: None,
+            done: false,
+        }
+    }
+}
+
+impl Node for ComputeDispatchNode {
+    fn update(&mut self, world: &mut World) {
+        // When the pipeline was ready last frame, it has been dispatched
+        if self.dispatch.once && self.pipeline.is_some() {
+            self.done = true;
+        }
+        self.pipeline = None;
+        self.bind_group = None;
+        if self.done {
+            return;
+        }
+
+        let pipeline_id = match world
+            .resource::<RenderAssets<ComputePipelineDescriptor>>()
+            .get(&self.dispatch.pipeline)
+        {
+            Some(pipeline_id) => *pipeline_id,
+            None => return,
+        };
+        let pipeline = match world
+            .resource::<PipelineCache>()
+            .get_compute_pipeline(pipeline_id)
+        {
+            Some(pipeline) => pipeline,
+            None => return,
+        };
+
+        if !self.dispatch.bindings.is_empty() {
+            let buffers = world.resource::<RenderAssets<ComputeBuffer>>();
+            let images = world.resource::<RenderAssets<Image>>();
+            let mut entries = Vec::with_capacity(self.dispatch.bindings.len());
+            for (binding, resource) in &self.dispatch.bindings {
+                let resource = match resource {
+                    ComputeBinding::Buffer(handle) => match buffers.get(handle) {
+                        Some(gpu_buffer) => gpu_buffer.buffer.as_entire_binding(),
+                        None => return,
+                    },
+                    ComputeBinding::Texture(handle) => match images.get(handle) {
+                        Some(gpu_image) => BindingResource::TextureView(&gpu_image.texture_view),
+                        None => return,
+                    },
+                    ComputeBinding::Sampler(handle) => match images.get(handle) {
+                        Some(gpu_image) => BindingResource::Sampler(&gpu_image.sampler),
+                        None => return,
+                    },
+                };
+                entries.push(BindGroupEntry {
+                    binding: *binding,
+                    resource,
+                });
+            }
+
+            let layout = pipeline.get_bind_group_layout(0);
+            self.bind_group = Some(world.resource::<RenderDevice>().create_bind_group(
+                &BindGroupDescriptor {
+                    label: Some("compute_dispatch_bind_group"),
+                    layout: &layout,
+                    entries: &entries,
+                },
+            ));
+        }
+        self.pipeline = Some(pipeline_id);
+    }
+
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let pipeline = match self
+            .pipeline
+            .and_then(|id| world.resource::<PipelineCache>().get_compute_pipeline(id))
+        {
+            Some(pipeline) => pipeline,
+            None => return Ok(()),
+        };
+
+        let mut pass =
+            render_context
+                .command_encoder()
+                .begin_compute_pass(&ComputePassDescriptor {
+                    label: Some("compute_dispatch_pass"),
+                });
+        pass.set_pipeline(pipeline);
+        if let Some(bind_group) = &self.bind_group {
+            pass.set_bind_group(0, bind_group, &[]);
+        }
+        let [x, y, z] = self.dispatch.workgroups;
+        pass.dispatch_workgroups(x, y, z);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        render_resource::{CachedPipelineState, Shader},
+        renderer::{test_render_device, RenderGraphRunner, RenderQueue},
+    };
+    use bevy_asset::HandleUntyped;
+    use bevy_ecs::system::SystemState;
+    use bevy_math::{Vec2, Vec3};
+    use bevy_tasks::{AsyncComputeTaskPool, TaskPool};
+    use std::time::Duration;
+
+    #[derive(ShaderType, Clone, Copy, Debug, PartialEq)]
+    struct Particle {
+        mass: f32,
+        position: Vec3,
+        velocity: Vec2,
+        id: u32,
+    }
+
+    const PARTICLE: Particle = Particle {
+        mass: 2.5,
+        position: Vec3::new(1.0, -2.0, 3.0),
+        velocity: Vec2::new(0.5, 4.0),
+        id: 7,
+    };
+
+    fn buffer_handle() -> Handle<ComputeBuffer> {
+        Handle::weak(HandleId::random::<ComputeBuffer>())
+    }
+
+    fn f32_at(data: &[u8], offset: usize) -> f32 {
+        f32::from_le_bytes(data[offset..offset + 4].try_into().unwrap())
+    }
+
+    #[test]
+    fn value_laid_out_like_in_wgsl() {
+        let data = ComputeBuffer::from_value(&PARTICLE).data;
+        // the vec3 is aligned to 16 bytes, the vec2 to 8, and the struct to 16
+        assert_eq!(data.len(), 48);
+        let floats = [
+            (0, 2.5),
+            (16, 1.0),
+            (20, -2.0),
+            (24, 3.0),
+            (32, 0.5),
+            (36, 4.0),
+        ];
+        for (offset, expected) in floats {
+            assert_eq!(f32_at(&data, offset), expected, "at {offset}");
+        }
+        assert_eq!(data[40..44], 7u32.to_le_bytes());
+    }
+
+    #[test]
+    fn value_read_back_with_its_layout() {
+        let particles = vec![
+            PARTICLE,
+            Particle {
+                id: 8,
+                position: Vec3::ZERO,
+                ..PARTICLE
+            },
+        ];
+        let (particle, array) = (buffer_handle(), buffer_handle());
+        let mut readbacks = ComputeReadbacks::default();
+        readbacks
+            .data
+            .insert(particle.id(), ComputeBuffer::from_value(&PARTICLE).data);
+        readbacks
+            .data
+            .insert(array.id(), ComputeBuffer::from_value(&particles).data);
+
+        assert_eq!(readbacks.read::<Particle>(&particle), Some(PARTICLE));
+        assert_eq!(readbacks.read::<Vec<Particle>>(&array), Some(particles));
+        // the first element of the array
+        assert_eq!(readbacks.read::<Particle>(&array), Some(PARTICLE));
+        assert_eq!(readbacks.get(&particle).map(<[u8]>::len), Some(48));
+    }
+
+    #[test]
+    fn value_not_read_back() {
+        let (missing, small) = (buffer_handle(), buffer_handle());
+        let mut readbacks = ComputeReadbacks::default();
+        readbacks.data.insert(small.id(), vec![0; 16]);
+        assert_eq!(readbacks.get(&missing), None);
+        assert_eq!(readbacks.read::<Particle>(&missing), None);
+        assert_eq!(readbacks.read::<Particle>(&small), None);
+    }
+
+    /// Prepares the GPU buffer of `buffer` in the render `world`
+    fn prepare_buffer(world: &mut World, handle: &Handle<ComputeBuffer>, buffer: ComputeBuffer) {
+        let mut state = SystemState::<Res<RenderDevice>>::new(world);
+        let Ok(gpu_buffer) = ComputeBuffer::prepare_asset(buffer, &mut state.get(world)) else {
+            panic!("the buffer isn't prepared");
+        };
+        world
+            .resource_mut::<RenderAssets<ComputeBuffer>>()
+            .insert(handle.clone_weak(), gpu_buffer);
+    }
+
+    /// A render world with the readback node in its graph, if there is an adapter
+    fn readback_world() -> Option<(World, RenderQueue)> {
+        let (device, queue) = test_render_device()?;
+        let mut world = World::new();
+        let mut graph = RenderGraph::default();
+        graph.add_node(ComputeReadbackNode::NAME, ComputeReadbackNode);
+        world.insert_resource(graph);
+        world.insert_resource(device);
+        world.init_resource::<RenderAssets<ComputeBuffer>>();
+        Some((world, queue))
+    }
+
+    #[test]
+    fn value_read_back_from_the_gpu() {
+        let Some((mut world, queue)) = readback_world() else {
+            return;
+        };
+        let handle = buffer_handle();
+        prepare_buffer(
+            &mut world,
+            &handle,
+            ComputeBuffer::from_value(&PARTICLE).with_readback(),
+        );
+
+        let device = world.resource::<RenderDevice>().clone();
+        RenderGraphRunner::run(world.resource::<RenderGraph>(), device, &queue, &world).unwrap();
+        let (sender, receiver) = async_channel::unbounded();
+        world.insert_resource(ComputeReadbackSender(sender));
+        world.insert_resource(ComputeReadbackReceiver(receiver));
+        world.init_resource::<ComputeReadbacks>();
+        let mut schedule = Schedule::new();
+        schedule.add_system(map_compute_readbacks);
+        schedule.add_system(receive_compute_readbacks.after(map_compute_readbacks));
+        schedule.run(&mut world);
+
+        let readbacks = world.resource::<ComputeReadbacks>();
+        assert_eq!(readbacks.read::<Particle>(&handle), Some(PARTICLE));
+    }
+
+    const SHADER: &str = "
+        @group(0) @binding(0) var<storage, read_write> data: array<u32>;
+        @compute @workgroup_size(1) fn main() { data[0] = 1u; }
+    ";
+
+    /// A render world with a created compute pipeline writing to a buffer, if there is an
+    /// adapter
+    fn dispatch_world() -> Option<(World, Handle<ComputePipelineDescriptor>)> {
+        let (device, _) = test_render_device()?;
+        AsyncComputeTaskPool::init(TaskPool::default);
+        let mut cache = PipelineCache::new(device.clone());
+        let shader = HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1).typed();
+        cache.set_shader(&shader, &Shader::from_wgsl(SHADER));
+        let id = cache.queue_compute_pipeline(ComputePipelineDescriptor {
+            label: None,
+            layout: Vec::new(),
+            push_constant_ranges: Vec::new(),
+            shader,
+            shader_defs: Vec::new(),
+            entry_point: "main".into(),
+        });
+        for _ in 0..1000 {
+            cache.process_queue();
+            if !cache.get_compute_pipeline_state(id).is_pending() {
+                break;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Ok(_)
+        ));
+
+        let pipeline = Handle::weak(HandleId::random::<ComputePipelineDescriptor>());
+        let mut world = World::new();
+        let mut pipelines = RenderAssets::<ComputePipelineDescriptor>::default();
+        pipelines.insert(pipeline.clone_weak(), id);
+        world.insert_resource(pipelines);
+        world.insert_resource(cache);
+        world.insert_resource(device);
+        world.init_resource::<RenderAssets<ComputeBuffer>>();
+        world.init_resource::<RenderAssets<Image>>();
+        Some((world, pipeline))
+    }
+
+    /// Whether the node dispatches in the next frames
+    fn dispatched(node: &mut ComputeDispatchNode, world: &mut World, frames: usize) -> Vec<bool> {
+        (0..frames)
+            .map(|_| {
+                node.update(world);
+                node.pipeline.is_some()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn once_waits_for_the_pipeline() {
+        let mut world = World::new();
+        world.init_resource::<RenderAssets<ComputePipelineDescriptor>>();
+        let pipeline = Handle::weak(HandleId::random::<ComputePipelineDescriptor>());
+        let mut node = ComputeDispatchNode::new(ComputeDispatch::new(pipeline).once());
+        assert_eq!(dispatched(&mut node, &mut world, 3), [false; 3]);
+        assert!(!node.done);
+    }
+
+    #[test]
+    fn dispatched_once_or_every_frame() {
+        let Some((mut world, pipeline)) = dispatch_world() else {
+            return;
+        };
+        let buffer = buffer_handle();
+        prepare_buffer(&mut world, &buffer, ComputeBuffer::zeroed(16));
+        let dispatch = ComputeDispatch::new(pipeline).with_buffer(0, buffer);
+
+        let mut every_frame = ComputeDispatchNode::new(dispatch.clone());
+        assert_eq!(dispatched(&mut every_frame, &mut world, 3), [true; 3]);
+        assert!(every_frame.bind_group.is_some());
+
+        // dispatched on the first frame, and done from the next one
+        let mut once = ComputeDispatchNode::new(dispatch.once());
+        assert_eq!(dispatched(&mut once, &mut world, 1), [true]);
+        assert!(once.bind_group.is_some());
+        assert!(!once.done);
+        assert_eq!(dispatched(&mut once, &mut world, 3), [false; 3]);
+        assert!(once.done);
+        assert!(once.bind_group.is_none());
+    }
+
+    #[test]
+    fn once_waits_for_the_bindings() {
+        let Some((mut world, pipeline)) = dispatch_world() else {
+            return;
+        };
+        let buffer = buffer_handle();
+        let mut once = ComputeDispatchNode::new(
+            ComputeDispatch::new(pipeline)
+                .with_buffer(0, buffer.clone())
+                .once(),
+        );
+        assert_eq!(dispatched(&mut once, &mut world, 2), [false; 2]);
+        assert!(!once.done);
+
+        prepare_buffer(&mut world, &buffer, ComputeBuffer::zeroed(16));
+        assert_eq!(dispatched(&mut once, &mut world, 3), [true, false, false]);
+        assert!(once.done);
+    }
+}
@@ -0,0 +1,369 @@
+use crate::{
+    render_resource::{Buffer, BufferDescriptor, BufferUsages, MapMode, PipelinesReady},
+    renderer::{RenderContext, RenderDevice, RenderQueue},
+    settings::WgpuFeatures,
+    RenderApp,
+};
+use bevy_app::prelude::*;
+use bevy_diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy_ecs::prelude::*;
+use bevy_utils::{tracing::warn, FixedState};
+use parking_lot::Mutex;
+use std::{
+    borrow::Cow,
+    hash::{BuildHasher, Hash, Hasher},
+    mem,
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        Arc,
+    },
+};
+use wgpu::{QuerySet, QuerySetDescriptor, QueryType, QUERY_SET_MAX_QUERIES, QUERY_SIZE};
+
+/// Adds a "gpu time" diagnostic for every node of the render graph, measured with timestamp
+/// queries, as well as a "gpu frame time" diagnostic.
+///
+/// The timings are also available in the [`GpuTimings`] resource, and can be displayed on
+/// screen with a `GpuTimingsText` from `bevy_ui`.
+///
+/// This requires [`WgpuFeatures::TIMESTAMP_QUERY`], which isn't supported on every backend.
+/// The timings of a frame are available a few frames later, once the GPU finished rendering it.
+#[derive(Default)]
+pub struct GpuTimingDiagnosticsPlugin;
+
+impl Plugin for GpuTimingDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = async_channel::unbounded();
+        app.init_resource::<GpuTimings>()
+            .insert_resource(GpuTimingsReceiver(receiver))
+            .add_startup_system(Self::setup_system)
+            .add_system(Self::diagnostic_system.in_base_set(CoreSet::PreUpdate));
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            let render_device = render_app.world.resource::<RenderDevice>();
+            if !render_device
+                .features()
+                .contains(WgpuFeatures::TIMESTAMP_QUERY)
+            {
+                warn!("GPU timings are not available, as timestamp queries are not supported by the render device");
+                return;
+            }
+            let period = render_app
+                .world
+                .resource::<RenderQueue>()
+                .get_timestamp_period();
+            let gpu_timer = GpuTimer::new(render_device, period, sender);
+            render_app.insert_resource(gpu_timer);
+        }
+    }
+}
+
+impl GpuTimingDiagnosticsPlugin {
+    pub const GPU_FRAME_TIME: DiagnosticId =
+        DiagnosticId::from_u128(151012228139064285446846986413016257518);
+
+    /// Returns the id of the diagnostic measuring the node with the given name.
+    ///
+    /// The name of a node is prefixed by the names of the sub graphs it is part of, such as
+    /// `core_3d/main_pass`.
+    pub fn node_diagnostic_id(name: &str) -> DiagnosticId {
+        const NODE_NAMESPACE: u128 = 0x4c93_50e2_a7f1_4d6b;
+
+        let mut hasher = FixedState.build_hasher();
+        "gpu".hash(&mut hasher);
+        name.hash(&mut hasher);
+        DiagnosticId::from_u128(NODE_NAMESPACE << 64 | hasher.finish() as u128)
+    }
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics
+            .add(Diagnostic::new(Self::GPU_FRAME_TIME, "gpu_frame_time", 20).with_suffix("ms"));
+    }
+
+    pub fn diagnostic_system(
+        receiver: Res<GpuTimingsReceiver>,
+        mut diagnostics: ResMut<Diagnostics>,
+        mut gpu_timings: ResMut<GpuTimings>,
+    ) {
+        while let Ok(timings) = receiver.0.try_recv() {
+            diagnostics.add_measurement(Self::GPU_FRAME_TIME, || timings.frame_time);
+            for (name, duration) in &timings.nodes {
+                let id = Self::node_diagnostic_id(name);
+                if diagnostics.get(id).is_none() {
+                    diagnostics
+                        .add(Diagnostic::new(id, format!("gpu/{name}"), 20).with_suffix("ms"));
+                }
+                diagnostics.add_measurement(id, || *duration);
+            }
+            *gpu_timings = timings;
+        }
+    }
+}
+
+/// Adds `pipelines_ready`, `pipelines_pending` and `pipelines_failed` diagnostics to an [`App`],
+/// with the counts of the [`PipelinesReady`] resource.
+#[derive(Default)]
+pub struct PipelineDiagnosticsPlugin;
+
+impl Plugin for PipelineDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PipelinesReady>()
+            .add_startup_system(Self::setup_system)
+            .add_system(Self::diagnostic_system);
+    }
+}
+
+impl PipelineDiagnosticsPlugin {
+    /// The number of pipelines that were created successfully
+    pub const READY: DiagnosticId = DiagnosticId::from_u128(45339767719037302156292115293304790399);
+    /// The number of pipelines that are queued or still being created
+    pub const PENDING: DiagnosticId =
+        DiagnosticId::from_u128(107742576759781058513564461522264252758);
+    /// The number of pipelines that failed to be created
+    pub const FAILED: DiagnosticId =
+        DiagnosticId::from_u128(52249698933076206272803790300207114230);
+
+    /// Registers the pipeline diagnostics for the current application.
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(Self::READY, "pipelines_ready", 20));
+        diagnostics.add(Diagnostic::new(Self::PENDING, "pipelines_pending", 20));
+        diagnostics.add(Diagnostic::new(Self::FAILED, "pipelines_failed", 20));
+    }
+
+    /// Updates the pipeline diagnostics.
+    pub fn diagnostic_system(
+        mut diagnostics: ResMut<Diagnostics>,
+        pipelines_ready: Res<PipelinesReady>,
+    ) {
+        diagnostics.add_measurement(Self::READY, || pipelines_ready.ready as f64);
+        diagnostics.add_measurement(Self::PENDING, || pipelines_ready.pending as f64);
+        diagnostics.add_measurement(Self::FAILED, || pipelines_ready.failed as f64);
+    }
+}
+
+/// The GPU time spent rendering the last measured frame.
+///
+/// This resource is added to the main world by the [`GpuTimingDiagnosticsPlugin`].
+#[derive(Resource, Default, Debug, Clone)]
+pub struct GpuTimings {
+    /// The GPU time spent rendering the whole frame, in milliseconds.
+    pub frame_time: f64,
+    /// The GPU time spent in each node of the render graph, in milliseconds, in the order the
+    /// nodes ran in. Nodes that ran multiple times during the frame, such as the nodes of a
+    /// sub graph run for each camera, are listed once with their total time.
+    pub nodes: Vec<(Cow<'static, str>, f64)>,
+}
+
+/// Receives the [`GpuTimings`] read back in the render world.
+#[derive(Resource)]
+pub struct GpuTimingsReceiver(async_channel::Receiver<GpuTimings>);
+
+const READBACK_PENDING: u8 = 0;
+const READBACK_MAPPED: u8 = 1;
+const READBACK_FAILED: u8 = 2;
+
+/// Records timestamp queries around the nodes of the render graph.
+///
+/// This resource is added to the render world by the [`GpuTimingDiagnosticsPlugin`], and used
+/// by the render graph runner.
+#[derive(Resource)]
+pub struct GpuTimer {
+    device: RenderDevice,
+    /// The number of nanoseconds per timestamp tick.
+    period: f32,
+    sender: async_channel::Sender<GpuTimings>,
+    state: Mutex<GpuTimerState>,
+}
+
+struct GpuTimerState {
+    query_set: QuerySet,
+    query_count: u32,
+    resolve_buffer: Buffer,
+    /// The names of the nodes timed during the current frame, each using two queries.
+    nodes: Vec<Cow<'static, str>>,
+    /// Set when more nodes ran than there are queries, to grow the query set next frame.
+    overflowed: bool,
+    free_readbacks: Vec<Buffer>,
+    submitted_readback: Option<(Buffer, Vec<Cow<'static, str>>)>,
+    mapping_readbacks: Vec<(Buffer, Vec<Cow<'static, str>>, Arc<AtomicU8>)>,
+}
+
+impl GpuTimer {
+    fn new(device: &RenderDevice, period: f32, sender: async_channel::Sender<GpuTimings>) -> Self {
+        let query_count = 128;
+        let (query_set, resolve_buffer) = Self::create_queries(device, query_count);
+        GpuTimer {
+            device: device.clone(),
+            period,
+            sender,
+            state: Mutex::new(GpuTimerState {
+                query_set,
+                query_count,
+                resolve_buffer,
+                nodes: Vec::new(),
+                overflowed: false,
+                free_readbacks: Vec::new(),
+                submitted_readback: None,
+                mapping_readbacks: Vec::new(),
+            }),
+        }
+    }
+
+    fn create_queries(device: &RenderDevice, query_count: u32) -> (QuerySet, Buffer) {
+        let query_set = device.wgpu_device().create_query_set(&QuerySetDescriptor {
+            label: Some("gpu_timer_query_set"),
+            ty: QueryType::Timestamp,
+            count: query_count,
+        });
+        let resolve_buffer = device.create_buffer(&BufferDescriptor {
+            label: Some("gpu_timer_resolve_buffer"),
+            size: query_count as u64 * QUERY_SIZE as u64,
+            usage: BufferUsages::COPY_DST | BufferUsages::COPY_SRC,
+            mapped_at_creation: false,
+        });
+        (query_set, resolve_buffer)
+    }
+
+    /// Starts timing a new frame, sending the timings of the previous frames that were
+    /// read back since the last call.
+    pub(crate) fn begin_frame(&self) {
+        let mut state = self.state.lock();
+
+        let mut i = 0;
+        while i < state.mapping_readbacks.len() {
+            match state.mapping_readbacks[i].2.load(Ordering::Acquire) {
+                READBACK_PENDING => i += 1,
+                status => {
+                    let (buffer, nodes, _) = state.mapping_readbacks.remove(i);
+                    if status == READBACK_MAPPED {
+                        let timings = self.read_timings(&buffer, nodes);
+                        buffer.unmap();
+                        // The receiver only goes away with the main world
+                        let _ = self.sender.try_send(timings);
+                    }
+                    if buffer.size() == state.resolve_buffer.size() {
+                        state.free_readbacks.push(buffer);
+                    }
+                }
+            }
+        }
+
+        if mem::take(&mut state.overflowed) && state.query_count < QUERY_SET_MAX_QUERIES {
+            state.query_count = (state.query_count * 2).min(QUERY_SET_MAX_QUERIES);
+            let (query_set, resolve_buffer) = Self::create_queries(&self.device, state.query_count);
+            state.query_set = query_set;
+            state.resolve_buffer = resolve_buffer;
+            state.free_readbacks.clear();
+        }
+        state.nodes.clear();
+    }
+
+    fn read_timings(&self, buffer: &Buffer, nodes: Vec<Cow<'static, str>>) -> GpuTimings {
+        let data = buffer
+            .slice(..nodes.len() as u64 * 2 * QUERY_SIZE as u64)
+            .get_mapped_range();
+        let timestamps = data
+            .chunks_exact(QUERY_SIZE as usize)
+            .map(|bytes| u64::from_le_bytes(bytes.try_into().unwrap()))
+            .collect::<Vec<_>>();
+        let to_ms = |ticks: u64| ticks as f64 * self.period as f64 / 1_000_000.0;
+
+        let mut timings = GpuTimings::default();
+        for (name, timestamps) in nodes.into_iter().zip(timestamps.chunks_exact(2)) {
+            let duration = to_ms(timestamps[1].saturating_sub(timestamps[0]));
+            match timings.nodes.iter_mut().find(|(node, _)| *node == name) {
+                Some((_, total)) => *total += duration,
+                None => timings.nodes.push((name, duration)),
+            }
+        }
+        let start = timestamps.iter().step_by(2).min();
+        let end = timestamps.iter().skip(1).step_by(2).max();
+        if let (Some(start), Some(end)) = (start, end) {
+            timings.frame_time = to_ms(end.saturating_sub(*start));
+        }
+        timings
+    }
+
+    /// Writes the timestamp starting the node with the given name, returning the index of the
+    /// query to pass to [`GpuTimer::end_node`], if there is one left.
+    pub(crate) fn begin_node(
+        &self,
+        render_context: &mut RenderContext,
+        name: Cow<'static, str>,
+    ) -> Option<u32> {
+        let mut state = self.state.lock();
+        let index = state.nodes.len() as u32 * 2;
+        if index + 2 > state.query_count {
+            state.overflowed = true;
+            return None;
+        }
+        render_context
+            .command_encoder()
+            .write_timestamp(&state.query_set, index);
+        state.nodes.push(name);
+        Some(index)
+    }
+
+    /// Writes the timestamp ending the node started at the given query index.
+    pub(crate) fn end_node(&self, render_context: &mut RenderContext, index: u32) {
+        let state = self.state.lock();
+        render_context
+            .command_encoder()
+            .write_timestamp(&state.query_set, index + 1);
+    }
+
+    /// Copies the timestamps of the frame to a buffer that can be read back once submitted.
+    pub(crate) fn resolve(&self, render_context: &mut RenderContext) {
+        let mut state = self.state.lock();
+        if state.nodes.is_empty() {
+            return;
+        }
+        let query_count = state.nodes.len() as u32 * 2;
+        let readback = state.free_readbacks.pop().unwrap_or_else(|| {
+            self.device.create_buffer(&BufferDescriptor {
+                label: Some("gpu_timer_readback_buffer"),
+                size: state.resolve_buffer.size(),
+                usage: BufferUsages::MAP_READ | BufferUsages::COPY_DST,
+                mapped_at_creation: false,
+            })
+        });
+
+        let command_encoder = render_context.command_encoder();
+        command_encoder.resolve_query_set(
+            &state.query_set,
+            0..query_count,
+            &state.resolve_buffer,
+            0,
+        );
+        command_encoder.copy_buffer_to_buffer(
+            &state.resolve_buffer,
+            0,
+            &readback,
+            0,
+            query_count as u64 * QUERY_SIZE as u64,
+        );
+        let nodes = mem::take(&mut state.nodes);
+        state.submitted_readback = Some((readback, nodes));
+    }
+
+    /// Starts reading back the timestamps of the frame, once its commands were submitted.
+    pub(crate) fn map_readback(&self) {
+        let mut state = self.state.lock();
+        if let Some((buffer, nodes)) = state.submitted_readback.take() {
+            let status = Arc::new(AtomicU8::new(READBACK_PENDING));
+            let status_clone = status.clone();
+            let size = nodes.len() as u64 * 2 * QUERY_SIZE as u64;
+            buffer
+                .slice(..size)
+                .map_async(MapMode::Read, move |result| {
+                    let status = if result.is_ok() {
+                        READBACK_MAPPED
+                    } else {
+                        READBACK_FAILED
+                    };
+                    status_clone.store(status, Ordering::Release);
+                });
+            state.mapping_readbacks.push((buffer, nodes, status));
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use crate::{
     render_resource::{encase::internal::WriteInto, DynamicUniformBuffer, ShaderType},
     renderer::{RenderDevice, RenderQueue},
-    view::ComputedVisibility,
+    view::ViewVisibility,
     Extract, ExtractSchedule, RenderApp, RenderSet,
 };
 use bevy_app::{App, IntoSystemAppConfig, Plugin};
@@ -30,6 +30,14 @@ impl<C: Component> DynamicUniformIndex<C> {
     }
 }
 
+impl<C: Component> Clone for DynamicUniformIndex<C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: Component> Copy for DynamicUniformIndex<C> {}
+
 /// Describes how a component gets extracted for rendering.
 ///
 /// Therefore the component is transferred from the "app world" into the "render world"
@@ -219,11 +227,11 @@ fn extract_components<C: ExtractComponent>(
 fn extract_visible_components<C: ExtractComponent>(
     mut commands: Commands,
     mut previous_len: Local<usize>,
-    query: Extract<Query<(Entity, &ComputedVisibility, C::Query), C::Filter>>,
+    query: Extract<Query<(Entity, &ViewVisibility, C::Query), C::Filter>>,
 ) {
     let mut values = Vec::with_capacity(*previous_len);
-    for (entity, computed_visibility, query_item) in &query {
-        if computed_visibility.is_visible() {
+    for (entity, view_visibility, query_item) in &query {
+        if view_visibility.get() {
             if let Some(component) = C::extract_component(query_item) {
                 values.push((entity, component));
             }
@@ -5,6 +5,8 @@ extern crate core;
 
 pub mod camera;
 pub mod color;
+pub mod compute;
+pub mod diagnostic;
 pub mod extract_component;
 mod extract_param;
 pub mod extract_resource;
@@ -33,8 +35,8 @@ pub mod prelude {
         mesh::{shape, Mesh},
         render_resource::Shader,
         spatial_bundle::SpatialBundle,
-        texture::{Image, ImagePlugin},
-        view::{ComputedVisibility, Msaa, Visibility, VisibilityBundle},
+        texture::{Flipbook, FlipbookMode, Image, ImagePlugin},
+        view::{InheritedVisibility, Msaa, ViewVisibility, Visibility, VisibilityBundle},
         ExtractSchedule,
     };
 }
@@ -45,8 +47,9 @@ pub use once_cell;
 
 use crate::{
     camera::CameraPlugin,
+    compute::ComputePlugin,
     mesh::MeshPlugin,
-    render_resource::{PipelineCache, Shader, ShaderLoader},
+    render_resource::{PipelineCache, PipelinesReady, Shader, ShaderLoader},
     renderer::{render_system, RenderInstance},
     settings::WgpuSettings,
     view::{ViewPlugin, WindowRenderPlugin},
@@ -175,6 +178,7 @@ impl Plugin for RenderPlugin {
     fn build(&self, app: &mut App) {
         app.add_asset::<Shader>()
             .add_debug_asset::<Shader>()
+            .init_resource::<PipelinesReady>()
             .init_asset_loader::<ShaderLoader>()
             .init_debug_asset_loader::<ShaderLoader>();
 
@@ -200,7 +204,7 @@ impl Plugin for RenderPlugin {
                 compatible_surface: surface.as_ref(),
                 ..Default::default()
             };
-            let (device, queue, adapter_info, render_adapter) =
+            let (device, queue, adapter_info, render_adapter, capabilities) =
                 futures_lite::future::block_on(renderer::initialize_renderer(
                     &instance,
                     &self.wgpu_settings,
@@ -212,9 +216,18 @@ impl Plugin for RenderPlugin {
                 .insert_resource(queue.clone())
                 .insert_resource(adapter_info.clone())
                 .insert_resource(render_adapter.clone())
+                .insert_resource(capabilities.clone())
                 .init_resource::<ScratchMainWorld>();
 
-            let pipeline_cache = PipelineCache::new(device.clone());
+            #[allow(unused_mut)]
+            let mut pipeline_cache = PipelineCache::new(device.clone());
+            #[cfg(all(feature = "pipeline_cache_persistence", not(target_arch = "wasm32")))]
+            if let Some(path) = &self.wgpu_settings.pipeline_cache_path {
+                pipeline_cache.persist_to(render_resource::adapter_pipeline_cache_path(
+                    path,
+                    &adapter_info,
+                ));
+            }
             let asset_server = app.world.resource::<AssetServer>().clone();
 
             let mut render_app = App::empty();
@@ -225,7 +238,8 @@ impl Plugin for RenderPlugin {
             render_app.edit_schedule(ExtractSchedule, |schedule| {
                 schedule
                     .set_apply_final_buffers(false)
-                    .add_system(PipelineCache::extract_shaders);
+                    .add_system(PipelineCache::extract_shaders)
+                    .add_system(PipelineCache::extract_pipelines_ready);
             });
 
             // This set applies the commands from the extract stage while the render schedule
@@ -249,6 +263,7 @@ impl Plugin for RenderPlugin {
                 .insert_resource(queue)
                 .insert_resource(render_adapter)
                 .insert_resource(adapter_info)
+                .insert_resource(capabilities)
                 .insert_resource(pipeline_cache)
                 .insert_resource(asset_server);
 
@@ -289,9 +304,10 @@ impl Plugin for RenderPlugin {
             }));
         }
 
-        app.add_plugin(ValidParentCheckPlugin::<view::ComputedVisibility>::default())
+        app.add_plugin(ValidParentCheckPlugin::<view::InheritedVisibility>::default())
             .add_plugin(WindowRenderPlugin)
             .add_plugin(CameraPlugin)
+            .add_plugin(ComputePlugin)
             .add_plugin(ViewPlugin)
             .add_plugin(MeshPlugin)
             .add_plugin(GlobalsPlugin);
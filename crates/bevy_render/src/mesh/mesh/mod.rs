@@ -13,6 +13,7 @@ use bevy_derive::EnumVariantMeta;
 use bevy_ecs::system::{lifetimeless::SRes, SystemParamItem};
 use bevy_math::*;
 use bevy_reflect::TypeUuid;
+use bevy_transform::components::Transform;
 use bevy_utils::{tracing::error, Hashed};
 use std::{collections::BTreeMap, hash::Hash, iter::FusedIterator};
 use thiserror::Error;
@@ -72,7 +73,9 @@ impl Mesh {
     pub const ATTRIBUTE_TANGENT: MeshVertexAttribute =
         MeshVertexAttribute::new("Vertex_Tangent", 3, VertexFormat::Float32x4);
 
-    /// Per vertex coloring. Use in conjunction with [`Mesh::insert_attribute`]
+    /// Per vertex coloring, in linear space. Use in conjunction with [`Mesh::insert_attribute`]
+    ///
+    /// The default materials multiply it into their base color.
     pub const ATTRIBUTE_COLOR: MeshVertexAttribute =
         MeshVertexAttribute::new("Vertex_Color", 4, VertexFormat::Float32x4);
 
@@ -248,6 +251,21 @@ impl Mesh {
         vertex_count.unwrap_or(0)
     }
 
+    /// The memory used by the vertex attributes and the indices of the mesh, in bytes.
+    pub fn estimated_size(&self) -> usize {
+        let attributes_size: usize = self
+            .attributes
+            .values()
+            .map(|attribute_data| attribute_data.values.get_bytes().len())
+            .sum();
+        let indices_size = match &self.indices {
+            Some(Indices::U16(indices)) => indices.len() * std::mem::size_of::<u16>(),
+            Some(Indices::U32(indices)) => indices.len() * std::mem::size_of::<u32>(),
+            None => 0,
+        };
+        attributes_size + indices_size
+    }
+
     /// Computes and returns the vertex data of the mesh as bytes.
     /// Therefore the attributes are located in alphabetical order.
     /// This is used to transform the vertex data into a GPU friendly format.
@@ -395,6 +413,160 @@ impl Mesh {
 
         None
     }
+
+    /// Appends the vertices and indices of `other` to this mesh, with its positions, normals and
+    /// tangents moved by `transform`.
+    ///
+    /// This can be used to build a single mesh out of many procedural shapes. Merging into an
+    /// empty mesh copies the attributes of `other`. Otherwise, both meshes must have the same
+    /// [`PrimitiveTopology`] and the same vertex attributes, in the same formats.
+    pub fn merge(&mut self, other: &Mesh, transform: Transform) -> Result<(), MergeMeshError> {
+        match other.primitive_topology {
+            PrimitiveTopology::LineStrip | PrimitiveTopology::TriangleStrip => {
+                return Err(MergeMeshError::UnsupportedTopology(
+                    other.primitive_topology,
+                ))
+            }
+            _ => {}
+        }
+        let is_empty = self.attributes.is_empty() && self.indices.is_none();
+        if is_empty {
+            self.primitive_topology = other.primitive_topology;
+        } else if self.primitive_topology != other.primitive_topology {
+            return Err(MergeMeshError::IncompatibleTopology(
+                self.primitive_topology,
+                other.primitive_topology,
+            ));
+        }
+        if !is_empty {
+            for (id, data) in &other.attributes {
+                match self.attributes.get(id) {
+                    Some(self_data) if self_data.attribute.format == data.attribute.format => {}
+                    _ => {
+                        return Err(MergeMeshError::IncompatibleVertexAttribute(
+                            data.attribute.name,
+                        ))
+                    }
+                }
+            }
+            if let Some((_, data)) = self
+                .attributes
+                .iter()
+                .find(|(id, _)| !other.attributes.contains_key(id))
+            {
+                return Err(MergeMeshError::IncompatibleVertexAttribute(
+                    data.attribute.name,
+                ));
+            }
+        }
+
+        let vertex_offset = self.count_vertices();
+        let other_vertex_count = other.count_vertices();
+        let mut other = other.clone();
+        other.transform_by(transform);
+
+        for (id, data) in other.attributes {
+            match self.attributes.get_mut(&id) {
+                Some(self_data) => self_data.values.extend(data.values),
+                None => {
+                    self.attributes.insert(id, data);
+                }
+            }
+        }
+
+        if self.indices.is_some() || other.indices.is_some() {
+            let indices = self
+                .indices
+                .take()
+                .map(|indices| indices.iter().collect::<Vec<_>>())
+                .unwrap_or_else(|| (0..vertex_offset).collect());
+            let other_indices = other
+                .indices
+                .map(|indices| indices.iter().collect::<Vec<_>>())
+                .unwrap_or_else(|| (0..other_vertex_count).collect());
+            let indices = indices
+                .into_iter()
+                .chain(other_indices.into_iter().map(|index| index + vertex_offset));
+            self.indices = Some(if vertex_offset + other_vertex_count <= u16::MAX as usize {
+                Indices::U16(indices.map(|index| index as u16).collect())
+            } else {
+                Indices::U32(indices.map(|index| index as u32).collect())
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Moves the positions, normals and tangents of the mesh by `transform`.
+    ///
+    /// Triangles are flipped when `transform` mirrors the mesh, so that they keep facing outwards.
+    fn transform_by(&mut self, transform: Transform) {
+        if let Some(VertexAttributeValues::Float32x3(positions)) =
+            self.attribute_mut(Mesh::ATTRIBUTE_POSITION)
+        {
+            for position in positions {
+                *position = transform.transform_point(Vec3::from(*position)).into();
+            }
+        }
+        if let Some(VertexAttributeValues::Float32x3(normals)) =
+            self.attribute_mut(Mesh::ATTRIBUTE_NORMAL)
+        {
+            for normal in normals {
+                // The normal matrix of a scale is its inverse
+                *normal = (transform.rotation * (Vec3::from(*normal) / transform.scale))
+                    .normalize_or_zero()
+                    .into();
+            }
+        }
+        let mirrored = transform.scale.x * transform.scale.y * transform.scale.z < 0.0;
+        if let Some(VertexAttributeValues::Float32x4(tangents)) =
+            self.attribute_mut(Mesh::ATTRIBUTE_TANGENT)
+        {
+            for tangent in tangents {
+                let sign = if mirrored { -tangent[3] } else { tangent[3] };
+                *tangent = (transform.rotation * (Vec3::from_slice(tangent) * transform.scale))
+                    .normalize_or_zero()
+                    .extend(sign)
+                    .into();
+            }
+        }
+
+        if mirrored && self.primitive_topology == PrimitiveTopology::TriangleList {
+            match &mut self.indices {
+                Some(Indices::U16(indices)) => {
+                    indices
+                        .chunks_exact_mut(3)
+                        .for_each(|triangle| triangle.swap(1, 2));
+                }
+                Some(Indices::U32(indices)) => {
+                    indices
+                        .chunks_exact_mut(3)
+                        .for_each(|triangle| triangle.swap(1, 2));
+                }
+                None => {
+                    let indices = (0..self.count_vertices() as u32)
+                        .collect::<Vec<_>>()
+                        .chunks_exact(3)
+                        .flat_map(|triangle| [triangle[0], triangle[2], triangle[1]])
+                        .collect();
+                    self.indices = Some(Indices::U32(indices));
+                }
+            }
+        }
+    }
+}
+
+/// An error that occurred while merging two [`Mesh`]es with [`Mesh::merge`].
+#[derive(Error, Debug)]
+pub enum MergeMeshError {
+    #[error("cannot merge meshes with a {0:?} topology")]
+    UnsupportedTopology(PrimitiveTopology),
+    #[error("cannot merge a {1:?} mesh into a {0:?} mesh")]
+    IncompatibleTopology(PrimitiveTopology, PrimitiveTopology),
+    #[error(
+        "the '{0}' vertex attribute is missing or has a different format in one of the meshes"
+    )]
+    IncompatibleVertexAttribute(&'static str),
 }
 
 #[derive(Debug, Clone)]
@@ -652,6 +824,50 @@ impl VertexAttributeValues {
         self.len() == 0
     }
 
+    /// Appends the values of `other`, which must be of the same [`VertexFormat`].
+    ///
+    /// # Panics
+    /// Panics if the formats of the values are different.
+    #[allow(clippy::match_same_arms)]
+    pub fn extend(&mut self, other: VertexAttributeValues) {
+        use VertexAttributeValues::*;
+        match (self, other) {
+            (Float32(values), Float32(other)) => values.extend(other),
+            (Sint32(values), Sint32(other)) => values.extend(other),
+            (Uint32(values), Uint32(other)) => values.extend(other),
+            (Float32x2(values), Float32x2(other)) => values.extend(other),
+            (Sint32x2(values), Sint32x2(other)) => values.extend(other),
+            (Uint32x2(values), Uint32x2(other)) => values.extend(other),
+            (Float32x3(values), Float32x3(other)) => values.extend(other),
+            (Sint32x3(values), Sint32x3(other)) => values.extend(other),
+            (Uint32x3(values), Uint32x3(other)) => values.extend(other),
+            (Float32x4(values), Float32x4(other)) => values.extend(other),
+            (Sint32x4(values), Sint32x4(other)) => values.extend(other),
+            (Uint32x4(values), Uint32x4(other)) => values.extend(other),
+            (Sint16x2(values), Sint16x2(other)) => values.extend(other),
+            (Snorm16x2(values), Snorm16x2(other)) => values.extend(other),
+            (Uint16x2(values), Uint16x2(other)) => values.extend(other),
+            (Unorm16x2(values), Unorm16x2(other)) => values.extend(other),
+            (Sint16x4(values), Sint16x4(other)) => values.extend(other),
+            (Snorm16x4(values), Snorm16x4(other)) => values.extend(other),
+            (Uint16x4(values), Uint16x4(other)) => values.extend(other),
+            (Unorm16x4(values), Unorm16x4(other)) => values.extend(other),
+            (Sint8x2(values), Sint8x2(other)) => values.extend(other),
+            (Snorm8x2(values), Snorm8x2(other)) => values.extend(other),
+            (Uint8x2(values), Uint8x2(other)) => values.extend(other),
+            (Unorm8x2(values), Unorm8x2(other)) => values.extend(other),
+            (Sint8x4(values), Sint8x4(other)) => values.extend(other),
+            (Snorm8x4(values), Snorm8x4(other)) => values.extend(other),
+            (Uint8x4(values), Uint8x4(other)) => values.extend(other),
+            (Unorm8x4(values), Unorm8x4(other)) => values.extend(other),
+            (values, other) => panic!(
+                "cannot extend {} vertex attribute values with {} values",
+                values.enum_variant_name(),
+                other.enum_variant_name()
+            ),
+        }
+    }
+
     /// Returns the values as float triples if possible.
     pub fn as_float3(&self) -> Option<&[[f32; 3]]> {
         match self {
@@ -1004,7 +1220,10 @@ fn generate_tangents_for_mesh(mesh: &Mesh) -> Result<Vec<[f32; 4]>, GenerateTang
 
 #[cfg(test)]
 mod tests {
-    use super::Mesh;
+    use super::{Indices, MergeMeshError, Mesh, VertexAttributeValues};
+    use crate::mesh::shape;
+    use bevy_math::{Vec2, Vec3};
+    use bevy_transform::components::Transform;
     use wgpu::PrimitiveTopology;
 
     #[test]
@@ -1013,4 +1232,87 @@ mod tests {
         let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
         mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, vec![[0.0, 0.0, 0.0]]);
     }
+
+    #[test]
+    fn merge_meshes() {
+        let quad = Mesh::from(shape::Quad::default());
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.merge(&quad, Transform::IDENTITY).unwrap();
+        mesh.merge(&quad, Transform::from_xyz(2.0, 0.0, 0.0))
+            .unwrap();
+
+        assert_eq!(mesh.count_vertices(), 8);
+        let Some(Indices::U16(indices)) = mesh.indices() else {
+            panic!("expected u16 indices");
+        };
+        assert_eq!(indices[6..], [4, 6, 5, 4, 7, 6]);
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("expected float3 positions");
+        };
+        assert_eq!(positions[4], [1.5, -0.5, 0.0]);
+    }
+
+    #[test]
+    fn merge_mirrored_mesh() {
+        let quad = Mesh::from(shape::Quad::default());
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.merge(&quad, Transform::from_scale(Vec3::new(-1.0, 1.0, 1.0)))
+            .unwrap();
+
+        // The triangles are flipped to keep facing the normals
+        let Some(Indices::U16(indices)) = mesh.indices() else {
+            panic!("expected u16 indices");
+        };
+        assert_eq!(indices[..], [0, 1, 2, 0, 2, 3]);
+    }
+
+    #[test]
+    fn merge_incompatible_meshes() {
+        let mut mesh = Mesh::from(shape::Quad::default());
+        let mut other = Mesh::from(shape::Quad::default());
+        other.remove_attribute(Mesh::ATTRIBUTE_UV_0);
+
+        assert!(matches!(
+            mesh.merge(&other, Transform::IDENTITY),
+            Err(MergeMeshError::IncompatibleVertexAttribute(_))
+        ));
+        assert!(matches!(
+            mesh.merge(&Mesh::new(PrimitiveTopology::LineList), Transform::IDENTITY),
+            Err(MergeMeshError::IncompatibleTopology(..))
+        ));
+    }
+
+    #[test]
+    fn extrude_concave_outline() {
+        // An L shape, in clockwise order
+        let outline = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.0, 2.0),
+            Vec2::new(1.0, 2.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(2.0, 1.0),
+            Vec2::new(2.0, 0.0),
+        ];
+        let mesh = Mesh::from(shape::Extrusion::new(outline, 1.0));
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            panic!("expected float3 positions");
+        };
+        let indices: Vec<_> = mesh.indices().unwrap().iter().collect();
+        // The front cap follows the sides, and covers the area of the outline facing +Z
+        let front_cap = &indices[outline.len() * 6..outline.len() * 6 + (outline.len() - 2) * 3];
+        let area: f32 = front_cap
+            .chunks_exact(3)
+            .map(|triangle| {
+                let [a, b, c] = [0, 1, 2].map(|i| Vec3::from(positions[triangle[i]]));
+                (b - a).cross(c - a).z / 2.0
+            })
+            .inspect(|area| assert!(*area > 0.0))
+            .sum();
+        assert!((area - 3.0).abs() < 1e-5);
+    }
 }
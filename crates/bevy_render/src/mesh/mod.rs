@@ -16,6 +16,7 @@ pub struct MeshPlugin;
 impl Plugin for MeshPlugin {
     fn build(&self, app: &mut App) {
         app.add_asset::<Mesh>()
+            .set_asset_size_estimator::<Mesh>(Mesh::estimated_size)
             .add_asset::<skinning::SkinnedMeshInverseBindposes>()
             .register_type::<skinning::SkinnedMesh>()
             .register_type::<Vec<Entity>>()
@@ -31,6 +31,22 @@ impl Default for Capsule {
     }
 }
 
+impl Capsule {
+    /// Creates a capsule with the given `radius`, whose middle cylinder is `depth` high.
+    pub fn new(radius: f32, depth: f32) -> Self {
+        Capsule {
+            radius,
+            depth,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a capsule with the given `radius` and total `height`, including the hemispheres.
+    pub fn from_height(radius: f32, height: f32) -> Self {
+        Capsule::new(radius, (height - 2.0 * radius).max(0.0))
+    }
+}
+
 #[derive(Debug, Default, Clone, Copy)]
 /// Manner in which UV coordinates are distributed vertically.
 pub enum CapsuleUvProfile {
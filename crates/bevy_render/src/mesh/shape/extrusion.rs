@@ -0,0 +1,177 @@
+use crate::mesh::{Indices, Mesh};
+use bevy_math::{Vec2, Vec3};
+use wgpu::PrimitiveTopology;
+
+/// A polygon in the `XY` plane extruded along the `Z` axis, centered on the origin.
+#[derive(Debug, Clone)]
+pub struct Extrusion {
+    /// The outline of the polygon in the `XY` plane. It doesn't need to be convex, but must not
+    /// intersect itself.
+    pub outline: Vec<Vec2>,
+    /// Length of the extrusion on the `Z` axis.
+    pub depth: f32,
+    /// Whether the front and back of the extrusion are closed with the polygon.
+    pub caps: bool,
+}
+
+impl Default for Extrusion {
+    fn default() -> Self {
+        Extrusion {
+            outline: vec![
+                Vec2::new(-0.5, -0.5),
+                Vec2::new(0.5, -0.5),
+                Vec2::new(0.5, 0.5),
+                Vec2::new(-0.5, 0.5),
+            ],
+            depth: 1.0,
+            caps: true,
+        }
+    }
+}
+
+impl Extrusion {
+    /// Creates an extrusion of the polygon with the given `outline`, closed at both ends.
+    pub fn new(outline: impl Into<Vec<Vec2>>, depth: f32) -> Self {
+        Extrusion {
+            outline: outline.into(),
+            depth,
+            caps: true,
+        }
+    }
+}
+
+impl From<Extrusion> for Mesh {
+    fn from(extrusion: Extrusion) -> Self {
+        debug_assert!(extrusion.outline.len() > 2);
+
+        // The sides and caps are wound from a counter-clockwise outline
+        let mut outline = extrusion.outline;
+        let area: f32 = outline
+            .iter()
+            .zip(outline.iter().cycle().skip(1))
+            .map(|(a, b)| a.perp_dot(*b))
+            .sum();
+        if area < 0.0 {
+            outline.reverse();
+        }
+
+        let num_vertices = outline.len() * 4 + if extrusion.caps { outline.len() * 2 } else { 0 };
+        let mut positions = Vec::with_capacity(num_vertices);
+        let mut normals = Vec::with_capacity(num_vertices);
+        let mut uvs = Vec::with_capacity(num_vertices);
+        let mut indices = Vec::new();
+
+        let half_depth = extrusion.depth / 2.0;
+
+        // sides, with flat normals so that the edges of the outline stay sharp
+
+        let perimeter: f32 = outline
+            .iter()
+            .zip(outline.iter().cycle().skip(1))
+            .map(|(a, b)| a.distance(*b))
+            .sum();
+        let mut distance = 0.0;
+        for (a, b) in outline.iter().zip(outline.iter().cycle().skip(1)) {
+            let offset = positions.len() as u32;
+            let edge = *b - *a;
+            let normal = Vec3::new(edge.y, -edge.x, 0.0).normalize_or_zero();
+            let (u_a, u_b) = (distance / perimeter, (distance + edge.length()) / perimeter);
+            distance += edge.length();
+
+            for (point, u) in [(a, u_a), (b, u_b)] {
+                positions.push([point.x, point.y, half_depth]);
+                positions.push([point.x, point.y, -half_depth]);
+                uvs.push([u, 0.0]);
+                uvs.push([u, 1.0]);
+                normals.extend([<[f32; 3]>::from(normal); 2]);
+            }
+
+            indices.extend_from_slice(&[
+                offset,
+                offset + 1,
+                offset + 3,
+                offset,
+                offset + 3,
+                offset + 2,
+            ]);
+        }
+
+        // caps
+
+        if extrusion.caps {
+            let min = outline
+                .iter()
+                .fold(Vec2::splat(f32::MAX), |min, point| min.min(*point));
+            let max = outline
+                .iter()
+                .fold(Vec2::splat(f32::MIN), |max, point| max.max(*point));
+            let size = (max - min).max(Vec2::splat(f32::EPSILON));
+            let triangles = triangulate(&outline);
+
+            for (z, normal_z) in [(half_depth, 1.0), (-half_depth, -1.0)] {
+                let offset = positions.len() as u32;
+                for point in &outline {
+                    let uv = (*point - min) / size;
+                    positions.push([point.x, point.y, z]);
+                    normals.push([0.0, 0.0, normal_z]);
+                    uvs.push([uv.x, 1.0 - uv.y]);
+                }
+                for triangle in triangles.chunks_exact(3) {
+                    if normal_z > 0.0 {
+                        indices.extend(triangle.iter().map(|i| offset + i));
+                    } else {
+                        indices.extend(triangle.iter().rev().map(|i| offset + i));
+                    }
+                }
+            }
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_indices(Some(Indices::U32(indices)));
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh
+    }
+}
+
+/// Triangulates a counter-clockwise polygon by clipping its ears.
+fn triangulate(outline: &[Vec2]) -> Vec<u32> {
+    let mut remaining: Vec<u32> = (0..outline.len() as u32).collect();
+    let mut triangles = Vec::with_capacity((outline.len() - 2) * 3);
+
+    while remaining.len() > 3 {
+        let len = remaining.len();
+        let corner = |i: usize| {
+            [
+                remaining[(i + len - 1) % len],
+                remaining[i],
+                remaining[(i + 1) % len],
+            ]
+        };
+        let ear = (0..len).find(|&i| {
+            let corner = corner(i);
+            let [a, b, c] = corner.map(|index| outline[index as usize]);
+            // A convex corner that doesn't contain any of the other points
+            (b - a).perp_dot(c - b) > 0.0
+                && remaining.iter().all(|index| {
+                    let point = outline[*index as usize];
+                    corner.contains(index)
+                        || (b - a).perp_dot(point - a) < 0.0
+                        || (c - b).perp_dot(point - b) < 0.0
+                        || (a - c).perp_dot(point - c) < 0.0
+                })
+        });
+        // Degenerate outlines don't have any ears left, so they are completed with a fan instead
+        let Some(ear) = ear else {
+            break;
+        };
+        triangles.extend(corner(ear));
+        remaining.remove(ear);
+    }
+
+    for i in 1..remaining.len() - 1 {
+        triangles.extend([remaining[0], remaining[i], remaining[i + 1]]);
+    }
+    triangles
+}
@@ -0,0 +1,123 @@
+use crate::mesh::{Indices, Mesh};
+use bevy_math::{Vec2, Vec3};
+use wgpu::PrimitiveTopology;
+
+/// A shape made by revolving a profile around the `Y` axis, like a vase turned on a lathe.
+#[derive(Debug, Clone)]
+pub struct Lathe {
+    /// The profile to revolve, from bottom to top. The `x` coordinate of each point is its
+    /// distance to the `Y` axis, and the `y` coordinate its height.
+    ///
+    /// The normals are smoothed along the profile. A point can be repeated to keep a sharp edge.
+    pub profile: Vec<Vec2>,
+    /// The number of segments around the `Y` axis.
+    pub resolution: usize,
+    /// The angle covered by the revolution, in radians, starting from the `X` axis.
+    /// [`TAU`](std::f32::consts::TAU) makes a closed shape.
+    pub angle: f32,
+}
+
+impl Default for Lathe {
+    fn default() -> Self {
+        Lathe {
+            profile: vec![
+                Vec2::new(0.0, -0.5),
+                Vec2::new(0.3, -0.5),
+                Vec2::new(0.5, 0.0),
+                Vec2::new(0.2, 0.5),
+            ],
+            resolution: 32,
+            angle: std::f32::consts::TAU,
+        }
+    }
+}
+
+impl Lathe {
+    /// Creates a closed shape revolving the `profile` around the `Y` axis.
+    pub fn new(profile: impl Into<Vec<Vec2>>, resolution: usize) -> Self {
+        Lathe {
+            profile: profile.into(),
+            resolution,
+            angle: std::f32::consts::TAU,
+        }
+    }
+}
+
+impl From<Lathe> for Mesh {
+    fn from(lathe: Lathe) -> Self {
+        debug_assert!(lathe.profile.len() > 1);
+        debug_assert!(lathe.resolution > 2);
+
+        let profile = &lathe.profile;
+        let num_vertices = (lathe.resolution + 1) * profile.len();
+        let mut positions = Vec::with_capacity(num_vertices);
+        let mut normals = Vec::with_capacity(num_vertices);
+        let mut uvs = Vec::with_capacity(num_vertices);
+
+        // Each point of the profile gets the average normal of the segments next to it, so that
+        // zero-length segments keep sharp edges
+        let segment_normal = |a: Vec2, b: Vec2| {
+            let tangent = b - a;
+            Vec2::new(tangent.y, -tangent.x).normalize_or_zero()
+        };
+        let profile_normals: Vec<Vec2> = (0..profile.len())
+            .map(|i| {
+                let previous = i.checked_sub(1).map_or(Vec2::ZERO, |previous| {
+                    segment_normal(profile[previous], profile[i])
+                });
+                let next = profile
+                    .get(i + 1)
+                    .map_or(Vec2::ZERO, |next| segment_normal(profile[i], *next));
+                (previous + next).normalize_or_zero()
+            })
+            .collect();
+
+        let mut distances = Vec::with_capacity(profile.len());
+        let mut length = 0.0;
+        for (i, point) in profile.iter().enumerate() {
+            if i > 0 {
+                length += point.distance(profile[i - 1]);
+            }
+            distances.push(length);
+        }
+        let length = length.max(f32::EPSILON);
+
+        let step_theta = lathe.angle / lathe.resolution as f32;
+        for segment in 0..=lathe.resolution {
+            let (sin, cos) = (segment as f32 * step_theta).sin_cos();
+
+            for ((point, normal), distance) in profile.iter().zip(&profile_normals).zip(&distances)
+            {
+                positions.push([point.x * cos, point.y, point.x * sin]);
+                normals.push(<[f32; 3]>::from(
+                    Vec3::new(normal.x * cos, normal.y, normal.x * sin).normalize_or_zero(),
+                ));
+                uvs.push([
+                    segment as f32 / lathe.resolution as f32,
+                    1.0 - distance / length,
+                ]);
+            }
+        }
+
+        let num_points = profile.len() as u32;
+        let mut indices = Vec::with_capacity(lathe.resolution * (profile.len() - 1) * 6);
+        for segment in 0..lathe.resolution as u32 {
+            for point in 0..num_points - 1 {
+                let lt = point + segment * num_points;
+                let rt = (point + 1) + segment * num_points;
+
+                let lb = point + (segment + 1) * num_points;
+                let rb = (point + 1) + (segment + 1) * num_points;
+
+                indices.extend_from_slice(&[lt, rt, lb, rt, rb, lb]);
+            }
+        }
+
+        let mut mesh = Mesh::new(PrimitiveTopology::TriangleList);
+        mesh.set_indices(Some(Indices::U32(indices)));
+        mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, positions);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, normals);
+        mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+        mesh
+    }
+}
@@ -266,14 +266,18 @@ impl From<Plane> for Mesh {
 
 mod capsule;
 mod cylinder;
+mod extrusion;
 mod icosphere;
+mod lathe;
 mod regular_polygon;
 mod torus;
 mod uvsphere;
 
 pub use capsule::{Capsule, CapsuleUvProfile};
 pub use cylinder::Cylinder;
+pub use extrusion::Extrusion;
 pub use icosphere::Icosphere;
+pub use lathe::Lathe;
 pub use regular_polygon::{Circle, RegularPolygon};
 pub use torus::Torus;
 pub use uvsphere::UVSphere;
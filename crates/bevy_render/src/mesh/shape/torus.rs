@@ -5,12 +5,37 @@ use wgpu::PrimitiveTopology;
 /// A torus (donut) shape.
 #[derive(Debug, Clone, Copy)]
 pub struct Torus {
+    /// Distance from the center of the torus to the center of its ring, in the `XZ` plane.
     pub radius: f32,
+    /// Radius of the ring.
     pub ring_radius: f32,
+    /// The number of segments around the `Y` axis.
     pub subdivisions_segments: usize,
+    /// The number of segments around the ring.
     pub subdivisions_sides: usize,
 }
 
+impl Torus {
+    /// Creates a torus from the radii of its hole and of its outer edge.
+    pub fn new(inner_radius: f32, outer_radius: f32) -> Self {
+        Torus {
+            radius: (outer_radius + inner_radius) / 2.0,
+            ring_radius: (outer_radius - inner_radius) / 2.0,
+            ..Default::default()
+        }
+    }
+
+    /// The radius of the hole of the torus.
+    pub fn inner_radius(&self) -> f32 {
+        self.radius - self.ring_radius
+    }
+
+    /// The radius of the outer edge of the torus.
+    pub fn outer_radius(&self) -> f32 {
+        self.radius + self.ring_radius
+    }
+}
+
 impl Default for Torus {
     fn default() -> Self {
         Torus {
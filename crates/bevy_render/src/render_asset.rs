@@ -114,6 +114,26 @@ impl<A: RenderAsset> Default for ExtractedAssets<A> {
     }
 }
 
+impl<A: RenderAsset> ExtractedAssets<A> {
+    /// Takes the extracted assets matching `filter` out, so that they aren't prepared by
+    /// [`prepare_assets`].
+    pub(crate) fn take_extracted(
+        &mut self,
+        mut filter: impl FnMut(&Handle<A>, &A::ExtractedAsset) -> bool,
+    ) -> Vec<(Handle<A>, A::ExtractedAsset)> {
+        let (taken, kept) = std::mem::take(&mut self.extracted)
+            .into_iter()
+            .partition(|(handle, asset)| filter(handle, asset));
+        self.extracted = kept;
+        taken
+    }
+
+    /// The assets removed this frame.
+    pub(crate) fn removed(&self) -> &[Handle<A>] {
+        &self.removed
+    }
+}
+
 /// Stores all GPU representations ([`RenderAsset::PreparedAssets`](RenderAsset::PreparedAsset))
 /// of [`RenderAssets`](RenderAsset) as long as they exist.
 #[derive(Resource, Deref, DerefMut)]
@@ -143,6 +163,7 @@ fn extract_render_asset<A: RenderAsset>(
                 changed_assets.remove(handle);
                 removed.push(handle.clone_weak());
             }
+            AssetEvent::LoadedWithDependencies { .. } => {}
         }
     }
 
@@ -1,5 +1,7 @@
 use crate::{define_atomic_id, render_resource::resource_macros::*};
 use std::ops::Deref;
+#[cfg(feature = "pipeline_cache_persistence")]
+use std::sync::Arc;
 
 define_atomic_id!(BindGroupLayoutId);
 render_resource_wrapper!(ErasedBindGroupLayout, wgpu::BindGroupLayout);
@@ -8,6 +10,8 @@ render_resource_wrapper!(ErasedBindGroupLayout, wgpu::BindGroupLayout);
 pub struct BindGroupLayout {
     id: BindGroupLayoutId,
     value: ErasedBindGroupLayout,
+    #[cfg(feature = "pipeline_cache_persistence")]
+    entries: Option<Arc<[wgpu::BindGroupLayoutEntry]>>,
 }
 
 impl PartialEq for BindGroupLayout {
@@ -26,6 +30,20 @@ impl BindGroupLayout {
     pub fn value(&self) -> &wgpu::BindGroupLayout {
         &self.value
     }
+
+    /// The entries this layout was created from, if it was created by the
+    /// [`RenderDevice`](crate::renderer::RenderDevice).
+    #[cfg(feature = "pipeline_cache_persistence")]
+    #[inline]
+    pub(crate) fn entries(&self) -> Option<&[wgpu::BindGroupLayoutEntry]> {
+        self.entries.as_deref()
+    }
+
+    #[cfg(feature = "pipeline_cache_persistence")]
+    pub(crate) fn with_entries(mut self, entries: &[wgpu::BindGroupLayoutEntry]) -> Self {
+        self.entries = Some(entries.into());
+        self
+    }
 }
 
 impl From<wgpu::BindGroupLayout> for BindGroupLayout {
@@ -33,6 +51,8 @@ impl From<wgpu::BindGroupLayout> for BindGroupLayout {
         BindGroupLayout {
             id: BindGroupLayoutId::new(),
             value: ErasedBindGroupLayout::new(value),
+            #[cfg(feature = "pipeline_cache_persistence")]
+            entries: None,
         }
     }
 }
@@ -4,6 +4,8 @@ mod buffer;
 mod buffer_vec;
 mod pipeline;
 mod pipeline_cache;
+#[cfg(feature = "pipeline_cache_persistence")]
+mod pipeline_cache_persistence;
 mod pipeline_specializer;
 pub mod resource_macros;
 mod shader;
@@ -17,6 +19,8 @@ pub use buffer::*;
 pub use buffer_vec::*;
 pub use pipeline::*;
 pub use pipeline_cache::*;
+#[cfg(feature = "pipeline_cache_persistence")]
+pub use pipeline_cache_persistence::adapter_pipeline_cache_path;
 pub use pipeline_specializer::*;
 pub use shader::*;
 pub use storage_buffer::*;
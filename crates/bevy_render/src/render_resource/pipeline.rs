@@ -4,6 +4,7 @@ use crate::{
     render_resource::{resource_macros::render_resource_wrapper, BindGroupLayout, Shader},
 };
 use bevy_asset::Handle;
+use bevy_reflect::TypeUuid;
 use std::{borrow::Cow, ops::Deref};
 use wgpu::{
     BufferAddress, ColorTargetState, DepthStencilState, MultisampleState, PrimitiveState,
@@ -174,9 +175,16 @@ pub struct FragmentState {
 }
 
 /// Describes a compute pipeline.
-#[derive(Clone, Debug)]
+///
+/// Descriptors can also be added as assets, to be dispatched with a
+/// [`ComputeDispatch`](crate::compute::ComputeDispatch).
+#[derive(Clone, Debug, TypeUuid)]
+#[uuid = "4f7c6b0e-2c5d-4b8a-a3e1-9d6f0c2b7e58"]
 pub struct ComputePipelineDescriptor {
     pub label: Option<Cow<'static, str>>,
+    /// The bind group layouts of the pipeline.
+    ///
+    /// If empty and without push constants, the layout is derived from the shader.
     pub layout: Vec<BindGroupLayout>,
     pub push_constant_ranges: Vec<PushConstantRange>,
     /// The compiled shader module for this stage.
@@ -7,17 +7,27 @@ use crate::{
         ShaderProcessor, ShaderReflectError,
     },
     renderer::RenderDevice,
-    Extract,
+    Extract, MainWorld,
 };
+
+#[cfg(feature = "pipeline_cache_persistence")]
+use super::pipeline_cache_persistence::PersistedPipelines;
 use bevy_asset::{AssetEvent, Assets, Handle};
 use bevy_ecs::system::{Res, ResMut};
 use bevy_ecs::{event::EventReader, system::Resource};
+#[cfg(not(target_arch = "wasm32"))]
+use bevy_tasks::AsyncComputeTaskPool;
+use bevy_tasks::Task;
 use bevy_utils::{
     default,
     tracing::{debug, error},
     Entry, HashMap, HashSet,
 };
+use futures_lite::future;
 use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "pipeline_cache_persistence")]
+use std::path::PathBuf;
 use std::{hash::Hash, iter::FusedIterator, mem, ops::Deref};
 use thiserror::Error;
 use wgpu::{
@@ -47,7 +57,7 @@ pub enum Pipeline {
     ComputePipeline(ComputePipeline),
 }
 
-type CachedPipelineId = usize;
+pub(crate) type CachedPipelineId = usize;
 
 /// Index of a cached render pipeline in a [`PipelineCache`].
 #[derive(Copy, Clone, Debug, Hash, Eq, PartialEq)]
@@ -70,6 +80,38 @@ impl CachedComputePipelineId {
 pub struct CachedPipeline {
     pub descriptor: PipelineDescriptor,
     pub state: CachedPipelineState,
+    /// The pipeline GPU object created before the shaders of this pipeline changed, used
+    /// in its place until the pipeline is created again.
+    pub fallback: Option<Pipeline>,
+}
+
+impl CachedPipeline {
+    fn new(descriptor: PipelineDescriptor) -> Self {
+        Self {
+            descriptor,
+            state: CachedPipelineState::Queued,
+            fallback: None,
+        }
+    }
+
+    /// Queue the pipeline for creation again, keeping its current GPU object as a fallback.
+    fn requeue(&mut self) {
+        if let CachedPipelineState::Ok(pipeline) =
+            mem::replace(&mut self.state, CachedPipelineState::Queued)
+        {
+            self.fallback = Some(pipeline);
+        }
+    }
+
+    /// The pipeline GPU object to use for rendering, if any.
+    ///
+    /// This is the created pipeline if it is ready, or its [`fallback`](Self::fallback) otherwise.
+    fn current(&self) -> Option<&Pipeline> {
+        match &self.state {
+            CachedPipelineState::Ok(pipeline) => Some(pipeline),
+            _ => self.fallback.as_ref(),
+        }
+    }
 }
 
 /// State of a cached pipeline inserted into a [`PipelineCache`].
@@ -77,6 +119,8 @@ pub struct CachedPipeline {
 pub enum CachedPipelineState {
     /// The pipeline GPU object is queued for creation.
     Queued,
+    /// The pipeline GPU object is being created on a background thread.
+    Creating(Task<Pipeline>),
     /// The pipeline GPU object was created successfully and is available (allocated on the GPU).
     Ok(Pipeline),
     /// An error occurred while trying to create the pipeline GPU object.
@@ -100,9 +144,26 @@ impl CachedPipelineState {
             CachedPipelineState::Queued => {
                 panic!("Pipeline has not been compiled yet. It is still in the 'Queued' state.")
             }
+            CachedPipelineState::Creating(_) => {
+                panic!("Pipeline has not been compiled yet. It is still in the 'Creating' state.")
+            }
             CachedPipelineState::Err(err) => panic!("{}", err),
         }
     }
+
+    /// Returns `true` if the pipeline is still expected to be created, either because its
+    /// creation didn't finish or because it is waiting for its shaders to be loaded.
+    pub fn is_pending(&self) -> bool {
+        matches!(
+            self,
+            CachedPipelineState::Queued
+                | CachedPipelineState::Creating(_)
+                | CachedPipelineState::Err(
+                    PipelineCacheError::ShaderNotLoaded(_)
+                        | PipelineCacheError::ShaderImportNotYetAvailable
+                )
+        )
+    }
 }
 
 #[derive(Default)]
@@ -122,7 +183,7 @@ struct ShaderCache {
     processor: ShaderProcessor,
 }
 
-#[derive(Clone, PartialEq, Eq, Debug, Hash)]
+#[derive(Clone, PartialEq, Eq, Debug, Hash, Serialize, Deserialize)]
 pub enum ShaderDefVal {
     Bool(String, bool),
     Int(String, i32),
@@ -312,7 +373,7 @@ impl LayoutCache {
         render_device: &RenderDevice,
         bind_group_layouts: &[BindGroupLayout],
         push_constant_ranges: Vec<PushConstantRange>,
-    ) -> &wgpu::PipelineLayout {
+    ) -> ErasedPipelineLayout {
         let bind_group_ids = bind_group_layouts.iter().map(|l| l.id()).collect();
         self.layouts
             .entry((bind_group_ids, push_constant_ranges))
@@ -329,6 +390,7 @@ impl LayoutCache {
                     },
                 ))
             })
+            .clone()
     }
 }
 
@@ -337,13 +399,20 @@ impl LayoutCache {
 /// The cache stores existing render and compute pipelines allocated on the GPU, as well as
 /// pending creation. Pipelines inserted into the cache are identified by a unique ID, which
 /// can be used to retrieve the actual GPU object once it's ready. The creation of the GPU
-/// pipeline object is deferred to the [`RenderSet::Render`] step, just before the render
+/// pipeline object is started during the [`RenderSet::Render`] step, just before the render
 /// graph starts being processed, as this requires access to the GPU.
 ///
+/// Except on wasm, the GPU pipeline objects are created on the [`AsyncComputeTaskPool`], so that
+/// compiling new pipelines doesn't stall rendering. Until a pipeline is ready, the previously
+/// created pipeline is used in its place if its shaders were modified, and the items using it
+/// are not drawn otherwise. The [`PipelinesReady`] resource of the main world tracks how many
+/// pipelines are still being created.
+///
 /// Note that the cache do not perform automatic deduplication of identical pipelines. It is
 /// up to the user not to insert the same pipeline twice to avoid wasting GPU resources.
 ///
 /// [`RenderSet::Render`]: crate::RenderSet::Render
+/// [`AsyncComputeTaskPool`]: bevy_tasks::AsyncComputeTaskPool
 #[derive(Resource)]
 pub struct PipelineCache {
     layout_cache: LayoutCache,
@@ -352,6 +421,8 @@ pub struct PipelineCache {
     pipelines: Vec<CachedPipeline>,
     waiting_pipelines: HashSet<CachedPipelineId>,
     new_pipelines: Mutex<Vec<CachedPipeline>>,
+    #[cfg(feature = "pipeline_cache_persistence")]
+    persisted_pipelines: Option<Mutex<PersistedPipelines>>,
 }
 
 impl PipelineCache {
@@ -368,9 +439,34 @@ impl PipelineCache {
             waiting_pipelines: default(),
             new_pipelines: default(),
             pipelines: default(),
+            #[cfg(feature = "pipeline_cache_persistence")]
+            persisted_pipelines: None,
         }
     }
 
+    /// Persist the pipelines created by the app to the directory at `path`.
+    ///
+    /// The pipelines persisted by previous runs are queued for creation right away, so that they
+    /// are ready by the time the app queues them again instead of being created on first use.
+    /// Only the pipeline descriptors are persisted: compiling the pipelines again still relies on
+    /// the caches of the graphics driver, as `wgpu` doesn't expose pipeline cache data yet.
+    ///
+    /// Pipelines using a [`BindGroupLayout`] that wasn't created by the [`RenderDevice`] can't
+    /// be persisted. See [`adapter_pipeline_cache_path`](super::adapter_pipeline_cache_path) to
+    /// use a directory per adapter.
+    ///
+    /// This requires the `pipeline_cache_persistence` cargo feature.
+    #[cfg(feature = "pipeline_cache_persistence")]
+    pub fn persist_to(&mut self, path: impl Into<PathBuf>) {
+        let (persisted_pipelines, descriptors) =
+            PersistedPipelines::load(path.into(), &self.device, self.pipelines.len());
+        for descriptor in descriptors {
+            self.waiting_pipelines.insert(self.pipelines.len());
+            self.pipelines.push(CachedPipeline::new(descriptor));
+        }
+        self.persisted_pipelines = Some(Mutex::new(persisted_pipelines));
+    }
+
     /// Get the state of a cached render pipeline.
     ///
     /// See [`PipelineCache::queue_render_pipeline()`].
@@ -420,13 +516,12 @@ impl PipelineCache {
     /// # Returns
     ///
     /// This method returns a successfully created render pipeline if any, or `None` if the pipeline
-    /// was not created yet or if there was an error during creation. You can check the actual creation
-    /// state with [`PipelineCache::get_render_pipeline_state()`].
+    /// was not created yet or if there was an error during creation. While the pipeline is created
+    /// again after one of its shaders changed, the previously created pipeline is returned. You can
+    /// check the actual creation state with [`PipelineCache::get_render_pipeline_state()`].
     #[inline]
     pub fn get_render_pipeline(&self, id: CachedRenderPipelineId) -> Option<&RenderPipeline> {
-        if let CachedPipelineState::Ok(Pipeline::RenderPipeline(pipeline)) =
-            &self.pipelines[id.0].state
-        {
+        if let Some(Pipeline::RenderPipeline(pipeline)) = self.pipelines[id.0].current() {
             Some(pipeline)
         } else {
             None
@@ -438,13 +533,12 @@ impl PipelineCache {
     /// # Returns
     ///
     /// This method returns a successfully created compute pipeline if any, or `None` if the pipeline
-    /// was not created yet or if there was an error during creation. You can check the actual creation
-    /// state with [`PipelineCache::get_compute_pipeline_state()`].
+    /// was not created yet or if there was an error during creation. While the pipeline is created
+    /// again after one of its shaders changed, the previously created pipeline is returned. You can
+    /// check the actual creation state with [`PipelineCache::get_compute_pipeline_state()`].
     #[inline]
     pub fn get_compute_pipeline(&self, id: CachedComputePipelineId) -> Option<&ComputePipeline> {
-        if let CachedPipelineState::Ok(Pipeline::ComputePipeline(pipeline)) =
-            &self.pipelines[id.0].state
-        {
+        if let Some(Pipeline::ComputePipeline(pipeline)) = self.pipelines[id.0].current() {
             Some(pipeline)
         } else {
             None
@@ -468,13 +562,11 @@ impl PipelineCache {
         &self,
         descriptor: RenderPipelineDescriptor,
     ) -> CachedRenderPipelineId {
-        let mut new_pipelines = self.new_pipelines.lock();
-        let id = CachedRenderPipelineId(self.pipelines.len() + new_pipelines.len());
-        new_pipelines.push(CachedPipeline {
-            descriptor: PipelineDescriptor::RenderPipelineDescriptor(Box::new(descriptor)),
-            state: CachedPipelineState::Queued,
-        });
-        id
+        CachedRenderPipelineId(
+            self.queue_pipeline(PipelineDescriptor::RenderPipelineDescriptor(Box::new(
+                descriptor,
+            ))),
+        )
     }
 
     /// Insert a compute pipeline into the cache, and queue its creation.
@@ -494,19 +586,30 @@ impl PipelineCache {
         &self,
         descriptor: ComputePipelineDescriptor,
     ) -> CachedComputePipelineId {
+        CachedComputePipelineId(
+            self.queue_pipeline(PipelineDescriptor::ComputePipelineDescriptor(Box::new(
+                descriptor,
+            ))),
+        )
+    }
+
+    fn queue_pipeline(&self, descriptor: PipelineDescriptor) -> CachedPipelineId {
         let mut new_pipelines = self.new_pipelines.lock();
-        let id = CachedComputePipelineId(self.pipelines.len() + new_pipelines.len());
-        new_pipelines.push(CachedPipeline {
-            descriptor: PipelineDescriptor::ComputePipelineDescriptor(Box::new(descriptor)),
-            state: CachedPipelineState::Queued,
-        });
+        let id = self.pipelines.len() + new_pipelines.len();
+        #[cfg(feature = "pipeline_cache_persistence")]
+        if let Some(persisted_pipelines) = &self.persisted_pipelines {
+            if let Some(preloaded) = persisted_pipelines.lock().queue(&descriptor, id) {
+                return preloaded;
+            }
+        }
+        new_pipelines.push(CachedPipeline::new(descriptor));
         id
     }
 
-    fn set_shader(&mut self, handle: &Handle<Shader>, shader: &Shader) {
+    pub(crate) fn set_shader(&mut self, handle: &Handle<Shader>, shader: &Shader) {
         let pipelines_to_queue = self.shader_cache.set_shader(handle, shader.clone());
         for cached_pipeline in pipelines_to_queue {
-            self.pipelines[cached_pipeline].state = CachedPipelineState::Queued;
+            self.pipelines[cached_pipeline].requeue();
             self.waiting_pipelines.insert(cached_pipeline);
         }
     }
@@ -514,7 +617,7 @@ impl PipelineCache {
     fn remove_shader(&mut self, shader: &Handle<Shader>) {
         let pipelines_to_queue = self.shader_cache.remove(shader);
         for cached_pipeline in pipelines_to_queue {
-            self.pipelines[cached_pipeline].state = CachedPipelineState::Queued;
+            self.pipelines[cached_pipeline].requeue();
             self.waiting_pipelines.insert(cached_pipeline);
         }
     }
@@ -536,38 +639,20 @@ impl PipelineCache {
             }
         };
 
-        let fragment_data = if let Some(fragment) = &descriptor.fragment {
-            let fragment_module = match self.shader_cache.get(
-                &self.device,
-                id,
-                &fragment.shader,
-                &fragment.shader_defs,
-            ) {
-                Ok(module) => module,
+        let fragment_module = if let Some(fragment) = &descriptor.fragment {
+            match self
+                .shader_cache
+                .get(&self.device, id, &fragment.shader, &fragment.shader_defs)
+            {
+                Ok(module) => Some(module),
                 Err(err) => {
                     return CachedPipelineState::Err(err);
                 }
-            };
-            Some((
-                fragment_module,
-                fragment.entry_point.deref(),
-                fragment.targets.as_slice(),
-            ))
+            }
         } else {
             None
         };
 
-        let vertex_buffer_layouts = descriptor
-            .vertex
-            .buffers
-            .iter()
-            .map(|layout| RawVertexBufferLayout {
-                array_stride: layout.array_stride,
-                attributes: &layout.attributes,
-                step_mode: layout.step_mode,
-            })
-            .collect::<Vec<_>>();
-
         let layout = if descriptor.layout.is_empty() && descriptor.push_constant_ranges.is_empty() {
             None
         } else {
@@ -578,30 +663,47 @@ impl PipelineCache {
             ))
         };
 
-        let descriptor = RawRenderPipelineDescriptor {
-            multiview: None,
-            depth_stencil: descriptor.depth_stencil.clone(),
-            label: descriptor.label.as_deref(),
-            layout,
-            multisample: descriptor.multisample,
-            primitive: descriptor.primitive,
-            vertex: RawVertexState {
-                buffers: &vertex_buffer_layouts,
-                entry_point: descriptor.vertex.entry_point.deref(),
-                module: &vertex_module,
-            },
-            fragment: fragment_data
+        let device = self.device.clone();
+        let descriptor = descriptor.clone();
+        create_pipeline(move || {
+            let vertex_buffer_layouts = descriptor
+                .vertex
+                .buffers
+                .iter()
+                .map(|layout| RawVertexBufferLayout {
+                    array_stride: layout.array_stride,
+                    attributes: &layout.attributes,
+                    step_mode: layout.step_mode,
+                })
+                .collect::<Vec<_>>();
+
+            let fragment_data = descriptor
+                .fragment
                 .as_ref()
-                .map(|(module, entry_point, targets)| RawFragmentState {
-                    entry_point,
+                .zip(fragment_module.as_ref())
+                .map(|(fragment, module)| RawFragmentState {
+                    entry_point: fragment.entry_point.deref(),
                     module,
-                    targets,
-                }),
-        };
-
-        let pipeline = self.device.create_render_pipeline(&descriptor);
+                    targets: fragment.targets.as_slice(),
+                });
+
+            let raw_descriptor = RawRenderPipelineDescriptor {
+                multiview: None,
+                depth_stencil: descriptor.depth_stencil.clone(),
+                label: descriptor.label.as_deref(),
+                layout: layout.as_deref(),
+                multisample: descriptor.multisample,
+                primitive: descriptor.primitive,
+                vertex: RawVertexState {
+                    buffers: &vertex_buffer_layouts,
+                    entry_point: descriptor.vertex.entry_point.deref(),
+                    module: &vertex_module,
+                },
+                fragment: fragment_data,
+            };
 
-        CachedPipelineState::Ok(Pipeline::RenderPipeline(pipeline))
+            Pipeline::RenderPipeline(device.create_render_pipeline(&raw_descriptor))
+        })
     }
 
     fn process_compute_pipeline(
@@ -631,22 +733,26 @@ impl PipelineCache {
             ))
         };
 
-        let descriptor = RawComputePipelineDescriptor {
-            label: descriptor.label.as_deref(),
-            layout,
-            module: &compute_module,
-            entry_point: descriptor.entry_point.as_ref(),
-        };
-
-        let pipeline = self.device.create_compute_pipeline(&descriptor);
+        let device = self.device.clone();
+        let descriptor = descriptor.clone();
+        create_pipeline(move || {
+            let raw_descriptor = RawComputePipelineDescriptor {
+                label: descriptor.label.as_deref(),
+                layout: layout.as_deref(),
+                module: &compute_module,
+                entry_point: descriptor.entry_point.as_ref(),
+            };
 
-        CachedPipelineState::Ok(Pipeline::ComputePipeline(pipeline))
+            Pipeline::ComputePipeline(device.create_compute_pipeline(&raw_descriptor))
+        })
     }
 
     /// Process the pipeline queue and create all pending pipelines if possible.
     ///
     /// This is generally called automatically during the [`RenderSet::Render`] step, but can
-    /// be called manually to force creation at a different time.
+    /// be called manually to force creation at a different time. Pipelines created on a
+    /// background thread are only marked as ready by the first call after their creation
+    /// finished.
     ///
     /// [`RenderSet::Render`]: crate::RenderSet::Render
     pub fn process_queue(&mut self) {
@@ -664,21 +770,35 @@ impl PipelineCache {
 
         for id in waiting_pipelines {
             let pipeline = &mut pipelines[id];
-            if matches!(pipeline.state, CachedPipelineState::Ok(_)) {
-                continue;
-            }
-
-            pipeline.state = match &pipeline.descriptor {
-                PipelineDescriptor::RenderPipelineDescriptor(descriptor) => {
-                    self.process_render_pipeline(id, descriptor)
+            pipeline.state = match &mut pipeline.state {
+                CachedPipelineState::Ok(_) => continue,
+                CachedPipelineState::Creating(task) => {
+                    match future::block_on(future::poll_once(task)) {
+                        Some(created) => CachedPipelineState::Ok(created),
+                        None => {
+                            self.waiting_pipelines.insert(id);
+                            continue;
+                        }
+                    }
                 }
-                PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
-                    self.process_compute_pipeline(id, descriptor)
+                CachedPipelineState::Queued | CachedPipelineState::Err(_) => {
+                    match &pipeline.descriptor {
+                        PipelineDescriptor::RenderPipelineDescriptor(descriptor) => {
+                            self.process_render_pipeline(id, descriptor)
+                        }
+                        PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
+                            self.process_compute_pipeline(id, descriptor)
+                        }
+                    }
                 }
             };
 
-            if let CachedPipelineState::Err(err) = &pipeline.state {
-                match err {
+            match &pipeline.state {
+                CachedPipelineState::Ok(_) => pipeline.fallback = None,
+                CachedPipelineState::Creating(_) => {
+                    self.waiting_pipelines.insert(id);
+                }
+                CachedPipelineState::Err(err) => match err {
                     PipelineCacheError::ShaderNotLoaded(_)
                     | PipelineCacheError::ShaderImportNotYetAvailable => {
                         // retry
@@ -697,15 +817,53 @@ impl PipelineCache {
                         error!("failed to create shader module: {}", description);
                         continue;
                     }
-                }
+                },
+                CachedPipelineState::Queued => {}
             }
         }
 
         self.pipelines = pipelines;
     }
 
+    /// Counts the pipelines of the cache that are ready, still being created, or failed.
+    pub fn pipelines_ready(&self) -> PipelinesReady {
+        #[cfg(feature = "pipeline_cache_persistence")]
+        let persisted_pipelines = self.persisted_pipelines.as_ref().map(Mutex::lock);
+        let mut pipelines_ready = PipelinesReady::default();
+        #[allow(unused_variables)]
+        for (id, pipeline) in self.pipelines.iter().enumerate() {
+            #[cfg(feature = "pipeline_cache_persistence")]
+            if let Some(persisted_pipelines) = &persisted_pipelines {
+                // Preloaded pipelines may never be used, so they don't count until they are
+                if persisted_pipelines.is_unused(id) {
+                    continue;
+                }
+            }
+            if let CachedPipelineState::Ok(_) = pipeline.state {
+                pipelines_ready.ready += 1;
+            } else if pipeline.state.is_pending() {
+                pipelines_ready.pending += 1;
+            } else {
+                pipelines_ready.failed += 1;
+            }
+        }
+        pipelines_ready.pending += self.new_pipelines.lock().len();
+        pipelines_ready
+    }
+
     pub(crate) fn process_pipeline_queue_system(mut cache: ResMut<Self>) {
         cache.process_queue();
+
+        #[cfg(feature = "pipeline_cache_persistence")]
+        {
+            let cache = cache.into_inner();
+            if let Some(persisted_pipelines) = &mut cache.persisted_pipelines {
+                persisted_pipelines.get_mut().save(|id| {
+                    let state = &cache.pipelines[id].state;
+                    (!state.is_pending()).then_some(matches!(state, CachedPipelineState::Ok(_)))
+                });
+            }
+        }
     }
 
     pub(crate) fn extract_shaders(
@@ -721,9 +879,56 @@ impl PipelineCache {
                     }
                 }
                 AssetEvent::Removed { handle } => cache.remove_shader(handle),
+                AssetEvent::LoadedWithDependencies { .. } => {}
             }
         }
     }
+
+    pub(crate) fn extract_pipelines_ready(cache: Res<Self>, mut main_world: ResMut<MainWorld>) {
+        *main_world.resource_mut::<PipelinesReady>() = cache.pipelines_ready();
+    }
+}
+
+/// Starts creating a pipeline GPU object on the [`AsyncComputeTaskPool`].
+#[cfg(not(target_arch = "wasm32"))]
+fn create_pipeline(create: impl FnOnce() -> Pipeline + Send + 'static) -> CachedPipelineState {
+    CachedPipelineState::Creating(AsyncComputeTaskPool::get().spawn(async move { create() }))
+}
+
+/// Creates a pipeline GPU object right away, as wasm doesn't support creating them on other threads.
+#[cfg(target_arch = "wasm32")]
+fn create_pipeline(create: impl FnOnce() -> Pipeline) -> CachedPipelineState {
+    CachedPipelineState::Ok(create())
+}
+
+/// The number of pipelines of the [`PipelineCache`], by creation state.
+///
+/// This resource is available in the main world, and is updated every frame during the
+/// [`ExtractSchedule`](crate::ExtractSchedule). It can be used to show a loading screen until
+/// all pipelines used by a scene are ready, as items using a pipeline that is still being
+/// created are not drawn.
+///
+/// The counts can be recorded as diagnostics with the
+/// [`PipelineDiagnosticsPlugin`](crate::diagnostic::PipelineDiagnosticsPlugin).
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PipelinesReady {
+    /// The number of pipelines that were created successfully.
+    pub ready: usize,
+    /// The number of pipelines that are queued or still being created, including the
+    /// pipelines waiting for their shaders to be loaded.
+    pub pending: usize,
+    /// The number of pipelines that failed to be created, because of an error in their shaders.
+    pub failed: usize,
+}
+
+impl PipelinesReady {
+    /// Returns `true` if no pipeline is pending creation.
+    ///
+    /// Note that this is also the case before any pipeline was queued, as the pipelines used by
+    /// an entity are only queued during the frame after it is spawned.
+    pub fn all_ready(&self) -> bool {
+        self.pending == 0
+    }
 }
 
 fn log_shader_error(source: &ProcessedShader, error: &AsModuleDescriptorError) {
@@ -857,3 +1062,153 @@ impl<'a> Iterator for ErrorSources<'a> {
 }
 
 impl<'a> FusedIterator for ErrorSources<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::test_render_device;
+    use bevy_asset::HandleUntyped;
+    use bevy_reflect::TypeUuid;
+    use bevy_tasks::TaskPool;
+    use std::time::Duration;
+
+    const SHADER: &str = "@compute @workgroup_size(1) fn main() {}";
+
+    fn compute_descriptor(shader: &Handle<Shader>) -> ComputePipelineDescriptor {
+        ComputePipelineDescriptor {
+            label: None,
+            layout: Vec::new(),
+            push_constant_ranges: Vec::new(),
+            shader: shader.clone(),
+            shader_defs: Vec::new(),
+            entry_point: "main".into(),
+        }
+    }
+
+    /// Returns an empty cache and the handle of its shader, if there is an adapter.
+    fn test_cache() -> Option<(PipelineCache, Handle<Shader>)> {
+        let (device, _) = test_render_device()?;
+        AsyncComputeTaskPool::init(TaskPool::default);
+        let shader = HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1).typed();
+        Some((PipelineCache::new(device), shader))
+    }
+
+    /// Returns a cache with a compute pipeline queued, if there is an adapter to create it.
+    fn queued_pipeline() -> Option<(PipelineCache, Handle<Shader>, CachedComputePipelineId)> {
+        let (mut cache, shader) = test_cache()?;
+        cache.set_shader(&shader, &Shader::from_wgsl(SHADER));
+        let id = cache.queue_compute_pipeline(compute_descriptor(&shader));
+        Some((cache, shader, id))
+    }
+
+    fn process_until_created(cache: &mut PipelineCache, id: CachedComputePipelineId) {
+        for _ in 0..1000 {
+            cache.process_queue();
+            if !cache.get_compute_pipeline_state(id).is_pending() {
+                return;
+            }
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        panic!("the pipeline is still pending");
+    }
+
+    #[test]
+    fn created_pipeline_is_ready() {
+        let Some((mut cache, _, id)) = queued_pipeline() else {
+            return;
+        };
+        let pending = PipelinesReady {
+            ready: 0,
+            pending: 1,
+            failed: 0,
+        };
+        assert_eq!(cache.pipelines_ready(), pending);
+
+        cache.process_queue();
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Creating(_)
+        ));
+        assert!(cache.get_compute_pipeline(id).is_none());
+        assert_eq!(cache.pipelines_ready(), pending);
+
+        process_until_created(&mut cache, id);
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Ok(Pipeline::ComputePipeline(_))
+        ));
+        assert!(cache.get_compute_pipeline(id).is_some());
+        assert_eq!(
+            cache.pipelines_ready(),
+            PipelinesReady {
+                ready: 1,
+                pending: 0,
+                failed: 0,
+            }
+        );
+    }
+
+    #[test]
+    fn fallback_pipeline_until_recreated() {
+        let Some((mut cache, shader, id)) = queued_pipeline() else {
+            return;
+        };
+        process_until_created(&mut cache, id);
+
+        // modifying the shader queues the pipeline again, and keeps the created one as a fallback
+        cache.set_shader(&shader, &Shader::from_wgsl(SHADER));
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Queued
+        ));
+        assert!(cache.pipelines[id.0].fallback.is_some());
+        assert!(cache.get_compute_pipeline(id).is_some());
+
+        cache.process_queue();
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Creating(_)
+        ));
+        assert!(cache.get_compute_pipeline(id).is_some());
+
+        process_until_created(&mut cache, id);
+        assert!(matches!(
+            cache.get_compute_pipeline_state(id),
+            CachedPipelineState::Ok(_)
+        ));
+        assert!(cache.pipelines[id.0].fallback.is_none());
+        assert!(cache.get_compute_pipeline(id).is_some());
+    }
+
+    #[cfg(feature = "pipeline_cache_persistence")]
+    #[test]
+    fn persisted_pipeline_is_preloaded() {
+        use super::super::pipeline_cache_persistence::PersistedPipeline;
+
+        let Some((mut cache, shader)) = test_cache() else {
+            return;
+        };
+        let descriptor = compute_descriptor(&shader);
+        let persisted = PersistedPipeline::from_descriptor(
+            &PipelineDescriptor::ComputePipelineDescriptor(Box::new(descriptor.clone())),
+        )
+        .unwrap();
+        let path = std::env::temp_dir().join(format!("bevy_pipelines_{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        let source = ron::to_string(&vec![persisted]).unwrap();
+        std::fs::write(path.join("pipelines.ron"), source).unwrap();
+        cache.persist_to(&path);
+        std::fs::remove_dir_all(&path).unwrap();
+        cache.set_shader(&shader, &Shader::from_wgsl(SHADER));
+
+        // the preloaded pipeline isn't counted until it is queued by the app
+        assert_eq!(cache.pipelines_ready(), PipelinesReady::default());
+        let id = cache.queue_compute_pipeline(descriptor);
+        assert_eq!(id.0, 0);
+        assert!(cache.new_pipelines.lock().is_empty());
+        assert_eq!(cache.pipelines_ready().pending, 1);
+
+        process_until_created(&mut cache, id);
+        assert_eq!(cache.pipelines_ready().ready, 1);
+    }
+}
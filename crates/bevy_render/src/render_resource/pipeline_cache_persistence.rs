@@ -0,0 +1,400 @@
+use crate::{
+    render_resource::{
+        BindGroupLayout, CachedPipelineId, ComputePipelineDescriptor, FragmentState,
+        PipelineDescriptor, RenderPipelineDescriptor, ShaderDefVal, VertexBufferLayout,
+        VertexState,
+    },
+    renderer::RenderDevice,
+};
+use bevy_asset::{Handle, HandleId};
+use bevy_tasks::IoTaskPool;
+use bevy_utils::{
+    tracing::{debug, warn},
+    FixedState, HashMap,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    hash::{BuildHasher, Hash, Hasher},
+    path::{Path, PathBuf},
+};
+use wgpu::{
+    AdapterInfo, BindGroupLayoutDescriptor, BindGroupLayoutEntry, BufferAddress, ColorTargetState,
+    DepthStencilState, MultisampleState, PrimitiveState, PushConstantRange, VertexAttribute,
+    VertexStepMode,
+};
+
+const PIPELINES_FILE_NAME: &str = "pipelines.ron";
+
+/// Returns the directory in `path` used to persist the pipelines created on the given adapter.
+///
+/// Pipelines are persisted per adapter, driver and version of Bevy, as the pipelines created
+/// by an app can differ on each of them.
+pub fn adapter_pipeline_cache_path(path: &Path, adapter_info: &AdapterInfo) -> PathBuf {
+    let mut hasher = FixedState.build_hasher();
+    adapter_info.name.hash(&mut hasher);
+    adapter_info.driver.hash(&mut hasher);
+    adapter_info.driver_info.hash(&mut hasher);
+    env!("CARGO_PKG_VERSION").hash(&mut hasher);
+    path.join(format!(
+        "{:?}_{:x}_{:x}_{:016x}",
+        adapter_info.backend,
+        adapter_info.vendor,
+        adapter_info.device,
+        hasher.finish()
+    ))
+}
+
+/// A [`PipelineDescriptor`] in a form that can be saved to disk.
+///
+/// Bind group layouts are stored as their entries, and shaders as their [`HandleId`].
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) enum PersistedPipeline {
+    Render(Box<PersistedRenderPipeline>),
+    Compute(Box<PersistedComputePipeline>),
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct PersistedRenderPipeline {
+    label: Option<String>,
+    layout: Vec<Vec<BindGroupLayoutEntry>>,
+    push_constant_ranges: Vec<PushConstantRange>,
+    vertex_shader: PersistedShader,
+    vertex_buffers: Vec<PersistedVertexBufferLayout>,
+    primitive: PrimitiveState,
+    depth_stencil: Option<DepthStencilState>,
+    multisample: MultisampleState,
+    fragment: Option<(PersistedShader, Vec<Option<ColorTargetState>>)>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct PersistedComputePipeline {
+    label: Option<String>,
+    layout: Vec<Vec<BindGroupLayoutEntry>>,
+    push_constant_ranges: Vec<PushConstantRange>,
+    shader: PersistedShader,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+struct PersistedShader {
+    handle: HandleId,
+    shader_defs: Vec<ShaderDefVal>,
+    entry_point: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+struct PersistedVertexBufferLayout {
+    array_stride: BufferAddress,
+    step_mode: VertexStepMode,
+    attributes: Vec<VertexAttribute>,
+}
+
+/// Returns the entries of each layout, or `None` if one of them wasn't created from entries.
+fn persist_layout(layout: &[BindGroupLayout]) -> Option<Vec<Vec<BindGroupLayoutEntry>>> {
+    layout
+        .iter()
+        .map(|layout| layout.entries().map(<[_]>::to_vec))
+        .collect()
+}
+
+impl PersistedPipeline {
+    /// Converts a descriptor, returning `None` if it can't be persisted.
+    pub(crate) fn from_descriptor(descriptor: &PipelineDescriptor) -> Option<Self> {
+        match descriptor {
+            PipelineDescriptor::RenderPipelineDescriptor(descriptor) => {
+                Some(Self::Render(Box::new(PersistedRenderPipeline {
+                    label: descriptor.label.as_ref().map(|label| label.to_string()),
+                    layout: persist_layout(&descriptor.layout)?,
+                    push_constant_ranges: descriptor.push_constant_ranges.clone(),
+                    vertex_shader: PersistedShader {
+                        handle: descriptor.vertex.shader.id(),
+                        shader_defs: descriptor.vertex.shader_defs.clone(),
+                        entry_point: descriptor.vertex.entry_point.to_string(),
+                    },
+                    vertex_buffers: descriptor
+                        .vertex
+                        .buffers
+                        .iter()
+                        .map(|layout| PersistedVertexBufferLayout {
+                            array_stride: layout.array_stride,
+                            step_mode: layout.step_mode,
+                            attributes: layout.attributes.clone(),
+                        })
+                        .collect(),
+                    primitive: descriptor.primitive,
+                    depth_stencil: descriptor.depth_stencil.clone(),
+                    multisample: descriptor.multisample,
+                    fragment: descriptor.fragment.as_ref().map(|fragment| {
+                        (
+                            PersistedShader {
+                                handle: fragment.shader.id(),
+                                shader_defs: fragment.shader_defs.clone(),
+                                entry_point: fragment.entry_point.to_string(),
+                            },
+                            fragment.targets.clone(),
+                        )
+                    }),
+                })))
+            }
+            PipelineDescriptor::ComputePipelineDescriptor(descriptor) => {
+                Some(Self::Compute(Box::new(PersistedComputePipeline {
+                    label: descriptor.label.as_ref().map(|label| label.to_string()),
+                    layout: persist_layout(&descriptor.layout)?,
+                    push_constant_ranges: descriptor.push_constant_ranges.clone(),
+                    shader: PersistedShader {
+                        handle: descriptor.shader.id(),
+                        shader_defs: descriptor.shader_defs.clone(),
+                        entry_point: descriptor.entry_point.to_string(),
+                    },
+                })))
+            }
+        }
+    }
+
+    /// Converts back to a descriptor, creating the bind group layouts that are not in
+    /// `layouts` yet.
+    fn to_descriptor(
+        &self,
+        device: &RenderDevice,
+        layouts: &mut HashMap<Vec<BindGroupLayoutEntry>, BindGroupLayout>,
+    ) -> PipelineDescriptor {
+        let mut get_layout = |layout: &Vec<Vec<BindGroupLayoutEntry>>| {
+            layout
+                .iter()
+                .map(|entries| {
+                    layouts
+                        .entry(entries.clone())
+                        .or_insert_with(|| {
+                            device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                                label: None,
+                                entries,
+                            })
+                        })
+                        .clone()
+                })
+                .collect()
+        };
+
+        match self {
+            PersistedPipeline::Render(pipeline) => {
+                PipelineDescriptor::RenderPipelineDescriptor(Box::new(RenderPipelineDescriptor {
+                    label: pipeline.label.clone().map(Into::into),
+                    layout: get_layout(&pipeline.layout),
+                    push_constant_ranges: pipeline.push_constant_ranges.clone(),
+                    vertex: VertexState {
+                        shader: Handle::weak(pipeline.vertex_shader.handle),
+                        shader_defs: pipeline.vertex_shader.shader_defs.clone(),
+                        entry_point: pipeline.vertex_shader.entry_point.clone().into(),
+                        buffers: pipeline
+                            .vertex_buffers
+                            .iter()
+                            .map(|layout| VertexBufferLayout {
+                                array_stride: layout.array_stride,
+                                step_mode: layout.step_mode,
+                                attributes: layout.attributes.clone(),
+                            })
+                            .collect(),
+                    },
+                    primitive: pipeline.primitive,
+                    depth_stencil: pipeline.depth_stencil.clone(),
+                    multisample: pipeline.multisample,
+                    fragment: pipeline
+                        .fragment
+                        .as_ref()
+                        .map(|(shader, targets)| FragmentState {
+                            shader: Handle::weak(shader.handle),
+                            shader_defs: shader.shader_defs.clone(),
+                            entry_point: shader.entry_point.clone().into(),
+                            targets: targets.clone(),
+                        }),
+                }))
+            }
+            PersistedPipeline::Compute(pipeline) => {
+                PipelineDescriptor::ComputePipelineDescriptor(Box::new(ComputePipelineDescriptor {
+                    label: pipeline.label.clone().map(Into::into),
+                    layout: get_layout(&pipeline.layout),
+                    push_constant_ranges: pipeline.push_constant_ranges.clone(),
+                    shader: Handle::weak(pipeline.shader.handle),
+                    shader_defs: pipeline.shader.shader_defs.clone(),
+                    entry_point: pipeline.shader.entry_point.clone().into(),
+                }))
+            }
+        }
+    }
+}
+
+/// Tracks the pipelines of a [`PipelineCache`](super::PipelineCache) persisted to disk.
+pub(crate) struct PersistedPipelines {
+    path: PathBuf,
+    /// The pipelines loaded from disk that weren't queued by the app yet.
+    preloaded: HashMap<PersistedPipeline, CachedPipelineId>,
+    /// The pipelines queued by the app, which are saved once created.
+    queued: Vec<(CachedPipelineId, PersistedPipeline)>,
+    /// The number of queued pipelines that were created when the pipelines were last saved.
+    saved_count: usize,
+}
+
+impl PersistedPipelines {
+    /// Loads the pipelines persisted in `path`, returning them as descriptors along with
+    /// the id to give to each of them, starting from `first_id`.
+    pub(crate) fn load(
+        path: PathBuf,
+        device: &RenderDevice,
+        first_id: CachedPipelineId,
+    ) -> (Self, Vec<PipelineDescriptor>) {
+        let persisted: Vec<PersistedPipeline> =
+            match std::fs::read_to_string(path.join(PIPELINES_FILE_NAME)) {
+                Ok(source) => ron::from_str(&source).unwrap_or_else(|err| {
+                    warn!("failed to read the persisted pipeline cache: {}", err);
+                    Vec::new()
+                }),
+                // There is nothing to load the first time the app runs
+                Err(_) => Vec::new(),
+            };
+        debug!("preloading {} persisted pipelines", persisted.len());
+
+        let mut layouts = HashMap::default();
+        let descriptors = persisted
+            .iter()
+            .map(|pipeline| pipeline.to_descriptor(device, &mut layouts))
+            .collect();
+        let preloaded = persisted
+            .into_iter()
+            .enumerate()
+            .map(|(i, pipeline)| (pipeline, first_id + i))
+            .collect();
+
+        (
+            Self {
+                path,
+                preloaded,
+                queued: Vec::new(),
+                saved_count: 0,
+            },
+            descriptors,
+        )
+    }
+
+    /// Records a pipeline queued by the app with the given id.
+    ///
+    /// If an identical pipeline was preloaded, its id is returned instead, and the pipeline
+    /// shouldn't be queued again.
+    pub(crate) fn queue(
+        &mut self,
+        descriptor: &PipelineDescriptor,
+        id: CachedPipelineId,
+    ) -> Option<CachedPipelineId> {
+        let persisted = PersistedPipeline::from_descriptor(descriptor)?;
+        let preloaded = self.preloaded.remove(&persisted);
+        self.queued.push((preloaded.unwrap_or(id), persisted));
+        preloaded
+    }
+
+    /// Returns `true` if the pipeline was preloaded, but not queued by the app yet.
+    pub(crate) fn is_unused(&self, id: CachedPipelineId) -> bool {
+        self.preloaded.values().any(|preloaded| *preloaded == id)
+    }
+
+    /// Saves the queued pipelines that were created successfully, if all queued pipelines
+    /// are done being created and some weren't saved yet.
+    ///
+    /// Pipelines that were preloaded but not used by the app are not saved again.
+    pub(crate) fn save(&mut self, is_ready: impl Fn(CachedPipelineId) -> Option<bool>) {
+        let mut ready = Vec::new();
+        for (id, pipeline) in &self.queued {
+            match is_ready(*id) {
+                Some(true) => ready.push(pipeline.clone()),
+                Some(false) => {}
+                None => return,
+            }
+        }
+        if ready.len() == self.saved_count {
+            return;
+        }
+        self.saved_count = ready.len();
+
+        let path = self.path.clone();
+        IoTaskPool::get()
+            .spawn(async move {
+                let result = ron::to_string(&ready)
+                    .map_err(|err| err.to_string())
+                    .and_then(|source| {
+                        std::fs::create_dir_all(&path)
+                            .and_then(|_| std::fs::write(path.join(PIPELINES_FILE_NAME), source))
+                            .map_err(|err| err.to_string())
+                    });
+                if let Err(err) = result {
+                    warn!("failed to persist the pipeline cache: {}", err);
+                }
+            })
+            .detach();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PersistedPipeline, PersistedPipelines};
+    use crate::render_resource::{ComputePipelineDescriptor, PipelineDescriptor, Shader};
+    use bevy_asset::{HandleId, HandleUntyped};
+    use bevy_reflect::TypeUuid;
+    use std::path::PathBuf;
+
+    fn compute_descriptor(entry_point: &'static str) -> PipelineDescriptor {
+        let shader = HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 1234);
+        PipelineDescriptor::ComputePipelineDescriptor(Box::new(ComputePipelineDescriptor {
+            label: Some("compute_pipeline".into()),
+            layout: Vec::new(),
+            push_constant_ranges: Vec::new(),
+            shader: shader.typed(),
+            shader_defs: vec!["A".into(), super::ShaderDefVal::UInt("B".into(), 2)],
+            entry_point: entry_point.into(),
+        }))
+    }
+
+    #[test]
+    fn persisted_pipeline_round_trip() {
+        let descriptor = compute_descriptor("main");
+        let persisted = PersistedPipeline::from_descriptor(&descriptor).unwrap();
+        let source = ron::to_string(&vec![persisted.clone()]).unwrap();
+        let loaded: Vec<PersistedPipeline> = ron::from_str(&source).unwrap();
+        assert_eq!(loaded, vec![persisted]);
+
+        match &loaded[0] {
+            PersistedPipeline::Compute(pipeline) => {
+                assert_eq!(
+                    pipeline.shader.handle,
+                    HandleId::Id(Shader::TYPE_UUID, 1234)
+                );
+            }
+            PersistedPipeline::Render(_) => panic!("expected a compute pipeline"),
+        }
+    }
+
+    #[test]
+    fn preloaded_pipeline_is_queued_once() {
+        let descriptor = compute_descriptor("main");
+        let persisted = PersistedPipeline::from_descriptor(&descriptor).unwrap();
+        let mut pipelines = PersistedPipelines {
+            path: PathBuf::new(),
+            preloaded: [(persisted, 3)].into_iter().collect(),
+            queued: Vec::new(),
+            saved_count: 0,
+        };
+        assert!(pipelines.is_unused(3));
+
+        // a different pipeline isn't preloaded
+        assert_eq!(pipelines.queue(&compute_descriptor("other"), 5), None);
+        assert!(pipelines.is_unused(3));
+
+        // an identical pipeline gets the id of the preloaded one, which isn't unused anymore
+        assert_eq!(pipelines.queue(&descriptor, 6), Some(3));
+        assert!(!pipelines.is_unused(3));
+        assert_eq!(pipelines.queue(&descriptor, 7), None);
+
+        let queued = pipelines
+            .queued
+            .iter()
+            .map(|(id, _)| *id)
+            .collect::<Vec<_>>();
+        assert_eq!(queued, [5, 3, 7]);
+    }
+}
@@ -5,13 +5,14 @@ use bevy_utils::HashMap;
 use smallvec::{smallvec, SmallVec};
 #[cfg(feature = "trace")]
 use std::ops::Deref;
-use std::{borrow::Cow, collections::VecDeque};
+use std::{any::type_name, borrow::Cow, collections::VecDeque};
 use thiserror::Error;
 
 use crate::{
+    diagnostic::GpuTimer,
     render_graph::{
-        Edge, NodeId, NodeRunError, NodeState, RenderGraph, RenderGraphContext, SlotLabel,
-        SlotType, SlotValue,
+        Edge, EmptyNode, NodeId, NodeRunError, NodeState, RenderGraph, RenderGraphContext,
+        SlotLabel, SlotType, SlotValue,
     },
     renderer::{RenderContext, RenderDevice},
 };
@@ -59,12 +60,22 @@ impl RenderGraphRunner {
         world: &World,
     ) -> Result<(), RenderGraphRunnerError> {
         let mut render_context = RenderContext::new(render_device);
+        let gpu_timer = world.get_resource::<GpuTimer>();
+        if let Some(gpu_timer) = gpu_timer {
+            gpu_timer.begin_frame();
+        }
         Self::run_graph(graph, None, &mut render_context, world, &[])?;
+        if let Some(gpu_timer) = gpu_timer {
+            gpu_timer.resolve(&mut render_context);
+        }
         {
             #[cfg(feature = "trace")]
             let _span = info_span!("submit_graph_commands").entered();
             queue.submit(render_context.finish());
         }
+        if let Some(gpu_timer) = gpu_timer {
+            gpu_timer.map_readback();
+        }
         Ok(())
     }
 
@@ -76,6 +87,7 @@ impl RenderGraphRunner {
         inputs: &[SlotValue],
     ) -> Result<(), RenderGraphRunnerError> {
         let mut node_outputs: HashMap<NodeId, SmallVec<[SlotValue; 4]>> = HashMap::default();
+        let gpu_timer = world.get_resource::<GpuTimer>();
         #[cfg(feature = "trace")]
         let span = if let Some(name) = &graph_name {
             info_span!("run_graph", name = name.deref())
@@ -179,7 +191,29 @@ impl RenderGraphRunner {
                     #[cfg(feature = "trace")]
                     let _span = info_span!("node", name = node_state.type_name).entered();
 
+                    // Empty nodes are only used for ordering, so there is nothing to time
+                    let gpu_timing = match gpu_timer {
+                        Some(gpu_timer) if node_state.type_name != type_name::<EmptyNode>() => {
+                            let node_name = node_state
+                                .name
+                                .clone()
+                                .unwrap_or(Cow::Borrowed(node_state.type_name));
+                            let name = match &graph_name {
+                                Some(graph_name) => format!("{graph_name}/{node_name}").into(),
+                                None => node_name,
+                            };
+                            gpu_timer
+                                .begin_node(render_context, name)
+                                .map(|index| (gpu_timer, index))
+                        }
+                        _ => None,
+                    };
+
                     node_state.node.run(&mut context, render_context, world)?;
+
+                    if let Some((gpu_timer, index)) = gpu_timing {
+                        gpu_timer.end_node(render_context, index);
+                    }
                 }
 
                 for run_sub_graph in context.finish() {
@@ -2,7 +2,7 @@ mod graph_runner;
 mod render_device;
 
 use bevy_derive::{Deref, DerefMut};
-use bevy_utils::tracing::{error, info, info_span};
+use bevy_utils::tracing::{error, info, info_span, warn};
 pub use graph_runner::*;
 pub use render_device::*;
 
@@ -10,9 +10,10 @@ use crate::{
     render_graph::RenderGraph,
     render_phase::TrackedRenderPass,
     render_resource::RenderPassDescriptor,
-    settings::{WgpuSettings, WgpuSettingsPriority},
+    settings::{WgpuFeatures, WgpuLimits, WgpuSettings, WgpuSettingsPriority},
     view::{ExtractedWindows, ViewTarget},
 };
+use bevy_app::App;
 use bevy_ecs::prelude::*;
 use bevy_time::TimeSender;
 use bevy_utils::Instant;
@@ -21,6 +22,18 @@ use wgpu::{
     Adapter, AdapterInfo, CommandBuffer, CommandEncoder, Instance, Queue, RequestAdapterOptions,
 };
 
+/// Creates a render device and queue on the default adapter, for the tests needing a GPU.
+///
+/// Returns `None` when there is no adapter, so that these tests can be skipped.
+#[cfg(test)]
+pub(crate) fn test_render_device() -> Option<(RenderDevice, RenderQueue)> {
+    let instance = Instance::new(Default::default());
+    let adapter = futures_lite::future::block_on(instance.request_adapter(&Default::default()))?;
+    let (device, queue) =
+        futures_lite::future::block_on(adapter.request_device(&Default::default(), None)).ok()?;
+    Some((RenderDevice::from(device), RenderQueue(Arc::new(queue))))
+}
+
 /// Updates the [`RenderGraph`] with all of its nodes and then runs it to render the entire frame.
 pub fn render_system(world: &mut World) {
     world.resource_scope(|world, mut graph: Mut<RenderGraph>| {
@@ -106,6 +119,81 @@ pub struct RenderInstance(pub Instance);
 #[derive(Resource, Clone, Deref, DerefMut)]
 pub struct RenderAdapterInfo(pub AdapterInfo);
 
+/// The features and limits requested from `wgpu` according to the
+/// [`WgpuSettings`], and the ones the render device was actually created with.
+///
+/// Requested features and limits the adapter doesn't support are disabled, or constrained to
+/// what it supports, instead of failing to initialize the renderer. Plugins relying on them can
+/// register fallbacks with [`AddRenderFallback`]. This resource is in both the main world and the
+/// render world.
+#[derive(Resource, Clone, Debug)]
+pub struct RenderCapabilities {
+    /// The features requested according to the [`WgpuSettings`].
+    pub requested_features: WgpuFeatures,
+    /// The features the render device was created with.
+    pub features: WgpuFeatures,
+    /// The limits requested according to the [`WgpuSettings`].
+    pub requested_limits: WgpuLimits,
+    /// The limits the render device was created with.
+    pub limits: WgpuLimits,
+}
+
+impl RenderCapabilities {
+    /// The requested features the render device was created without.
+    pub fn missing_features(&self) -> WgpuFeatures {
+        self.requested_features - self.features
+    }
+
+    /// Returns `true` if the render device was created with all of `features`.
+    pub fn supports(&self, features: WgpuFeatures) -> bool {
+        self.features.contains(features)
+    }
+}
+
+/// Registers fallbacks for the subsystems relying on optional features of the render device.
+pub trait AddRenderFallback {
+    /// Runs `fallback` right away if the render device lacks any of `features`, with the missing
+    /// ones, so that the plugin can disable or replace the subsystems relying on them.
+    ///
+    /// Nothing runs when rendering isn't initialized, as there is no render device then.
+    ///
+    /// ```
+    /// # use bevy_app::prelude::*;
+    /// # use bevy_render::{renderer::AddRenderFallback, settings::WgpuFeatures};
+    /// struct GpuTimingsPlugin;
+    ///
+    /// impl Plugin for GpuTimingsPlugin {
+    ///     fn build(&self, app: &mut App) {
+    ///         app.add_render_feature_fallback(WgpuFeatures::TIMESTAMP_QUERY, |_app, missing| {
+    ///             eprintln!("GPU timings are disabled, {missing:?} are not supported");
+    ///         });
+    ///     }
+    /// }
+    /// ```
+    fn add_render_feature_fallback(
+        &mut self,
+        features: WgpuFeatures,
+        fallback: impl FnOnce(&mut App, WgpuFeatures),
+    ) -> &mut Self;
+}
+
+impl AddRenderFallback for App {
+    fn add_render_feature_fallback(
+        &mut self,
+        features: WgpuFeatures,
+        fallback: impl FnOnce(&mut App, WgpuFeatures),
+    ) -> &mut Self {
+        let Some(capabilities) = self.world.get_resource::<RenderCapabilities>() else {
+            return self;
+        };
+        let missing = features - capabilities.features;
+        if !missing.is_empty() {
+            fallback(self, missing);
+        }
+        self
+    }
+}
+
 const GPU_NOT_FOUND_ERROR_MESSAGE: &str = if cfg!(target_os = "linux") {
     "Unable to find a GPU! Make sure you have installed required drivers! For extra information, see: https://github.com/bevyengine/bevy/blob/latest/docs/linux_dependencies.md"
 } else {
@@ -118,7 +206,13 @@ pub async fn initialize_renderer(
     instance: &Instance,
     options: &WgpuSettings,
     request_adapter_options: &RequestAdapterOptions<'_>,
-) -> (RenderDevice, RenderQueue, RenderAdapterInfo, RenderAdapter) {
+) -> (
+    RenderDevice,
+    RenderQueue,
+    RenderAdapterInfo,
+    RenderAdapter,
+    RenderCapabilities,
+) {
     let adapter = instance
         .request_adapter(request_adapter_options)
         .await
@@ -166,108 +260,35 @@ pub async fn initialize_renderer(
         // specified max_limits. For 'min' limits, take the maximum instead. This is intended to
         // err on the side of being conservative. We can't claim 'higher' limits that are supported
         // but we can constrain to 'lower' limits.
-        limits = wgpu::Limits {
-            max_texture_dimension_1d: limits
-                .max_texture_dimension_1d
-                .min(constrained_limits.max_texture_dimension_1d),
-            max_texture_dimension_2d: limits
-                .max_texture_dimension_2d
-                .min(constrained_limits.max_texture_dimension_2d),
-            max_texture_dimension_3d: limits
-                .max_texture_dimension_3d
-                .min(constrained_limits.max_texture_dimension_3d),
-            max_texture_array_layers: limits
-                .max_texture_array_layers
-                .min(constrained_limits.max_texture_array_layers),
-            max_bind_groups: limits
-                .max_bind_groups
-                .min(constrained_limits.max_bind_groups),
-            max_dynamic_uniform_buffers_per_pipeline_layout: limits
-                .max_dynamic_uniform_buffers_per_pipeline_layout
-                .min(constrained_limits.max_dynamic_uniform_buffers_per_pipeline_layout),
-            max_dynamic_storage_buffers_per_pipeline_layout: limits
-                .max_dynamic_storage_buffers_per_pipeline_layout
-                .min(constrained_limits.max_dynamic_storage_buffers_per_pipeline_layout),
-            max_sampled_textures_per_shader_stage: limits
-                .max_sampled_textures_per_shader_stage
-                .min(constrained_limits.max_sampled_textures_per_shader_stage),
-            max_samplers_per_shader_stage: limits
-                .max_samplers_per_shader_stage
-                .min(constrained_limits.max_samplers_per_shader_stage),
-            max_storage_buffers_per_shader_stage: limits
-                .max_storage_buffers_per_shader_stage
-                .min(constrained_limits.max_storage_buffers_per_shader_stage),
-            max_storage_textures_per_shader_stage: limits
-                .max_storage_textures_per_shader_stage
-                .min(constrained_limits.max_storage_textures_per_shader_stage),
-            max_uniform_buffers_per_shader_stage: limits
-                .max_uniform_buffers_per_shader_stage
-                .min(constrained_limits.max_uniform_buffers_per_shader_stage),
-            max_uniform_buffer_binding_size: limits
-                .max_uniform_buffer_binding_size
-                .min(constrained_limits.max_uniform_buffer_binding_size),
-            max_storage_buffer_binding_size: limits
-                .max_storage_buffer_binding_size
-                .min(constrained_limits.max_storage_buffer_binding_size),
-            max_vertex_buffers: limits
-                .max_vertex_buffers
-                .min(constrained_limits.max_vertex_buffers),
-            max_vertex_attributes: limits
-                .max_vertex_attributes
-                .min(constrained_limits.max_vertex_attributes),
-            max_vertex_buffer_array_stride: limits
-                .max_vertex_buffer_array_stride
-                .min(constrained_limits.max_vertex_buffer_array_stride),
-            max_push_constant_size: limits
-                .max_push_constant_size
-                .min(constrained_limits.max_push_constant_size),
-            min_uniform_buffer_offset_alignment: limits
-                .min_uniform_buffer_offset_alignment
-                .max(constrained_limits.min_uniform_buffer_offset_alignment),
-            min_storage_buffer_offset_alignment: limits
-                .min_storage_buffer_offset_alignment
-                .max(constrained_limits.min_storage_buffer_offset_alignment),
-            max_inter_stage_shader_components: limits
-                .max_inter_stage_shader_components
-                .min(constrained_limits.max_inter_stage_shader_components),
-            max_compute_workgroup_storage_size: limits
-                .max_compute_workgroup_storage_size
-                .min(constrained_limits.max_compute_workgroup_storage_size),
-            max_compute_invocations_per_workgroup: limits
-                .max_compute_invocations_per_workgroup
-                .min(constrained_limits.max_compute_invocations_per_workgroup),
-            max_compute_workgroup_size_x: limits
-                .max_compute_workgroup_size_x
-                .min(constrained_limits.max_compute_workgroup_size_x),
-            max_compute_workgroup_size_y: limits
-                .max_compute_workgroup_size_y
-                .min(constrained_limits.max_compute_workgroup_size_y),
-            max_compute_workgroup_size_z: limits
-                .max_compute_workgroup_size_z
-                .min(constrained_limits.max_compute_workgroup_size_z),
-            max_compute_workgroups_per_dimension: limits
-                .max_compute_workgroups_per_dimension
-                .min(constrained_limits.max_compute_workgroups_per_dimension),
-            max_buffer_size: limits
-                .max_buffer_size
-                .min(constrained_limits.max_buffer_size),
-            max_bindings_per_bind_group: limits
-                .max_bindings_per_bind_group
-                .min(constrained_limits.max_bindings_per_bind_group),
-        };
+        limits = constrain_limits(limits, constrained_limits);
+    }
+
+    // Requesting more than the adapter supports would fail to create the device, so degrade to
+    // what it supports instead, and let the subsystems needing more fall back.
+    let requested_features = features;
+    let requested_limits = limits.clone();
+    let missing_features = features - adapter.features();
+    if !missing_features.is_empty() {
+        warn!("The render adapter doesn't support the requested features {missing_features:?}, they are disabled");
+        features -= missing_features;
     }
+    let adapter_limits = adapter.limits();
+    limits.check_limits_with_fail_fn(&adapter_limits, false, |name, requested, allowed| {
+        warn!("The render adapter doesn't support the requested limit {name} of {requested}, it is constrained to {allowed}");
+    });
+    let limits = constrain_limits(limits, &adapter_limits);
 
     let (device, queue) = adapter
         .request_device(
             &wgpu::DeviceDescriptor {
                 label: options.device_label.as_ref().map(|a| a.as_ref()),
                 features,
-                limits,
+                limits: limits.clone(),
             },
             trace_path,
         )
         .await
-        .unwrap();
+        .expect("Unable to create the render device");
     let queue = Arc::new(queue);
     let adapter = Arc::new(adapter);
     (
@@ -275,9 +296,105 @@ pub async fn initialize_renderer(
         RenderQueue(queue),
         RenderAdapterInfo(adapter_info),
         RenderAdapter(adapter),
+        RenderCapabilities {
+            requested_features,
+            features,
+            requested_limits,
+            limits,
+        },
     )
 }
 
+/// Constrains `limits` to the upper bound `constraints`: the minimum is taken for the 'max'
+/// limits, and the maximum for the 'min' limits.
+fn constrain_limits(limits: wgpu::Limits, constraints: &wgpu::Limits) -> wgpu::Limits {
+    wgpu::Limits {
+        max_texture_dimension_1d: limits
+            .max_texture_dimension_1d
+            .min(constraints.max_texture_dimension_1d),
+        max_texture_dimension_2d: limits
+            .max_texture_dimension_2d
+            .min(constraints.max_texture_dimension_2d),
+        max_texture_dimension_3d: limits
+            .max_texture_dimension_3d
+            .min(constraints.max_texture_dimension_3d),
+        max_texture_array_layers: limits
+            .max_texture_array_layers
+            .min(constraints.max_texture_array_layers),
+        max_bind_groups: limits.max_bind_groups.min(constraints.max_bind_groups),
+        max_dynamic_uniform_buffers_per_pipeline_layout: limits
+            .max_dynamic_uniform_buffers_per_pipeline_layout
+            .min(constraints.max_dynamic_uniform_buffers_per_pipeline_layout),
+        max_dynamic_storage_buffers_per_pipeline_layout: limits
+            .max_dynamic_storage_buffers_per_pipeline_layout
+            .min(constraints.max_dynamic_storage_buffers_per_pipeline_layout),
+        max_sampled_textures_per_shader_stage: limits
+            .max_sampled_textures_per_shader_stage
+            .min(constraints.max_sampled_textures_per_shader_stage),
+        max_samplers_per_shader_stage: limits
+            .max_samplers_per_shader_stage
+            .min(constraints.max_samplers_per_shader_stage),
+        max_storage_buffers_per_shader_stage: limits
+            .max_storage_buffers_per_shader_stage
+            .min(constraints.max_storage_buffers_per_shader_stage),
+        max_storage_textures_per_shader_stage: limits
+            .max_storage_textures_per_shader_stage
+            .min(constraints.max_storage_textures_per_shader_stage),
+        max_uniform_buffers_per_shader_stage: limits
+            .max_uniform_buffers_per_shader_stage
+            .min(constraints.max_uniform_buffers_per_shader_stage),
+        max_uniform_buffer_binding_size: limits
+            .max_uniform_buffer_binding_size
+            .min(constraints.max_uniform_buffer_binding_size),
+        max_storage_buffer_binding_size: limits
+            .max_storage_buffer_binding_size
+            .min(constraints.max_storage_buffer_binding_size),
+        max_vertex_buffers: limits
+            .max_vertex_buffers
+            .min(constraints.max_vertex_buffers),
+        max_vertex_attributes: limits
+            .max_vertex_attributes
+            .min(constraints.max_vertex_attributes),
+        max_vertex_buffer_array_stride: limits
+            .max_vertex_buffer_array_stride
+            .min(constraints.max_vertex_buffer_array_stride),
+        max_push_constant_size: limits
+            .max_push_constant_size
+            .min(constraints.max_push_constant_size),
+        min_uniform_buffer_offset_alignment: limits
+            .min_uniform_buffer_offset_alignment
+            .max(constraints.min_uniform_buffer_offset_alignment),
+        min_storage_buffer_offset_alignment: limits
+            .min_storage_buffer_offset_alignment
+            .max(constraints.min_storage_buffer_offset_alignment),
+        max_inter_stage_shader_components: limits
+            .max_inter_stage_shader_components
+            .min(constraints.max_inter_stage_shader_components),
+        max_compute_workgroup_storage_size: limits
+            .max_compute_workgroup_storage_size
+            .min(constraints.max_compute_workgroup_storage_size),
+        max_compute_invocations_per_workgroup: limits
+            .max_compute_invocations_per_workgroup
+            .min(constraints.max_compute_invocations_per_workgroup),
+        max_compute_workgroup_size_x: limits
+            .max_compute_workgroup_size_x
+            .min(constraints.max_compute_workgroup_size_x),
+        max_compute_workgroup_size_y: limits
+            .max_compute_workgroup_size_y
+            .min(constraints.max_compute_workgroup_size_y),
+        max_compute_workgroup_size_z: limits
+            .max_compute_workgroup_size_z
+            .min(constraints.max_compute_workgroup_size_z),
+        max_compute_workgroups_per_dimension: limits
+            .max_compute_workgroups_per_dimension
+            .min(constraints.max_compute_workgroups_per_dimension),
+        max_buffer_size: limits.max_buffer_size.min(constraints.max_buffer_size),
+        max_bindings_per_bind_group: limits
+            .max_bindings_per_bind_group
+            .min(constraints.max_bindings_per_bind_group),
+    }
+}
+
 /// The context with all information required to interact with the GPU.
 ///
 /// The [`RenderDevice`] is used to create render resources and the
@@ -87,7 +87,11 @@ impl RenderDevice {
         &self,
         desc: &wgpu::BindGroupLayoutDescriptor,
     ) -> BindGroupLayout {
-        BindGroupLayout::from(self.device.create_bind_group_layout(desc))
+        let layout = BindGroupLayout::from(self.device.create_bind_group_layout(desc));
+        // the entries are kept to persist the pipelines using the layout
+        #[cfg(feature = "pipeline_cache_persistence")]
+        let layout = layout.with_entries(desc.entries);
+        layout
     }
 
     /// Creates a [`PipelineLayout`](wgpu::PipelineLayout).
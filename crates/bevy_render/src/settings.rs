@@ -1,4 +1,6 @@
 use std::borrow::Cow;
+#[cfg(feature = "pipeline_cache_persistence")]
+use std::path::PathBuf;
 
 pub use wgpu::{
     Backends, Dx12Compiler, Features as WgpuFeatures, Limits as WgpuLimits, PowerPreference,
@@ -15,9 +17,9 @@ pub enum WgpuSettingsPriority {
     WebGL2,
 }
 
-/// Provides configuration for renderer initialization. Use [`RenderDevice::features`](crate::renderer::RenderDevice::features),
-/// [`RenderDevice::limits`](crate::renderer::RenderDevice::limits), and the [`RenderAdapterInfo`](crate::renderer::RenderAdapterInfo)
-/// resource to get runtime information about the actual adapter, backend, features, and limits.
+/// Provides configuration for renderer initialization. Use the [`RenderCapabilities`](crate::renderer::RenderCapabilities)
+/// and [`RenderAdapterInfo`](crate::renderer::RenderAdapterInfo) resources to get runtime information about the actual
+/// adapter, backend, features, and limits.
 /// NOTE: [`Backends::DX12`](Backends::DX12), [`Backends::METAL`](Backends::METAL), and
 /// [`Backends::VULKAN`](Backends::VULKAN) are enabled by default for non-web and the best choice
 /// is automatically selected. Web using the `webgl` feature uses [`Backends::GL`](Backends::GL).
@@ -30,17 +32,26 @@ pub struct WgpuSettings {
     pub backends: Option<Backends>,
     pub power_preference: PowerPreference,
     pub priority: WgpuSettingsPriority,
-    /// The features to ensure are enabled regardless of what the adapter/backend supports.
-    /// Setting these explicitly may cause renderer initialization to fail.
+    /// The features to enable regardless of the priority.
+    /// The ones the adapter/backend doesn't support are disabled with a warning.
     pub features: WgpuFeatures,
     /// The features to ensure are disabled regardless of what the adapter/backend supports
     pub disabled_features: Option<WgpuFeatures>,
     /// The imposed limits.
+    /// The ones the adapter/backend doesn't support are constrained with a warning.
     pub limits: WgpuLimits,
     /// The constraints on limits allowed regardless of what the adapter/backend supports
     pub constrained_limits: Option<WgpuLimits>,
     /// The shader compiler to use for the DX12 backend.
     pub dx12_shader_compiler: Dx12Compiler,
+    /// The directory in which the pipelines created by the app are persisted, to create them
+    /// again on startup instead of on first use. Each adapter uses its own sub-directory.
+    ///
+    /// See [`PipelineCache::persist_to`](crate::render_resource::PipelineCache::persist_to).
+    /// Persisting pipelines isn't supported on wasm, and requires the
+    /// `pipeline_cache_persistence` cargo feature.
+    #[cfg(feature = "pipeline_cache_persistence")]
+    pub pipeline_cache_path: Option<PathBuf>,
 }
 
 impl Default for WgpuSettings {
@@ -85,6 +96,8 @@ impl Default for WgpuSettings {
             limits,
             constrained_limits: None,
             dx12_shader_compiler: dx12_compiler,
+            #[cfg(feature = "pipeline_cache_persistence")]
+            pipeline_cache_path: None,
         }
     }
 }
@@ -1,24 +1,27 @@
 use bevy_ecs::prelude::Bundle;
 use bevy_transform::prelude::{GlobalTransform, Transform};
 
-use crate::view::{ComputedVisibility, Visibility};
+use crate::view::{InheritedVisibility, ViewVisibility, Visibility};
 
 /// A [`Bundle`] with the following [`Component`](bevy_ecs::component::Component)s:
-/// * [`Visibility`] and [`ComputedVisibility`], which describe the visibility of an entity
+/// * [`Visibility`], [`InheritedVisibility`] and [`ViewVisibility`], which describe the visibility of an entity
 /// * [`Transform`] and [`GlobalTransform`], which describe the position of an entity
 ///
 /// * To show or hide an entity, you should set its [`Visibility`].
-/// * To get the computed visibility of an entity, you should get its [`ComputedVisibility`].
+/// * To get the inherited visibility of an entity, you should get its [`InheritedVisibility`].
+/// * To get whether an entity is visible in any view, you should get its [`ViewVisibility`].
 /// * To place or move an entity, you should set its [`Transform`].
 /// * To get the global transform of an entity, you should get its [`GlobalTransform`].
-/// * For hierarchies to work correctly, you must have all four components.
+/// * For hierarchies to work correctly, you must have all five components.
 ///   * You may use the [`SpatialBundle`] to guarantee this.
 #[derive(Bundle, Debug, Default)]
 pub struct SpatialBundle {
     /// The visibility of the entity.
     pub visibility: Visibility,
-    /// The computed visibility of the entity.
-    pub computed: ComputedVisibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// The view visibility of the entity.
+    pub view_visibility: ViewVisibility,
     /// The transform of the entity.
     pub transform: Transform,
     /// The global transform of the entity.
@@ -40,7 +43,8 @@ impl SpatialBundle {
     /// A visible [`SpatialBundle`], with no translation, rotation, and a scale of 1 on all axes.
     pub const INHERITED_IDENTITY: Self = SpatialBundle {
         visibility: Visibility::Inherited,
-        computed: ComputedVisibility::HIDDEN,
+        inherited_visibility: InheritedVisibility::HIDDEN,
+        view_visibility: ViewVisibility::HIDDEN,
         transform: Transform::IDENTITY,
         global_transform: GlobalTransform::IDENTITY,
     };
@@ -0,0 +1,488 @@
+use anyhow::{anyhow, Result};
+use basis_universal::{
+    BasisTextureFormat, ColorSpace, Compressor, CompressorParams, Transcoder,
+    ETC1S_QUALITY_DEFAULT, ETC1S_QUALITY_MAX, ETC1S_QUALITY_MIN, UASTC_QUALITY_DEFAULT,
+    UASTC_QUALITY_MAX, UASTC_QUALITY_MIN,
+};
+use bevy_asset::AssetSaver;
+use wgpu::TextureFormat;
+
+use crate::texture::Image;
+
+/// The universal texture format the [`CompressedImageSaver`] encodes the images to
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum BasisCompression {
+    /// High quality blocks, written to KTX2 files, losslessly transcoded to ASTC and nearly
+    /// losslessly to BC7 when loading them.
+    #[default]
+    Uastc,
+    /// Much smaller but lower quality blocks, written to `.etc1s.ktx2` files with `BasisLZ`
+    /// supercompression.
+    Etc1s,
+}
+
+/// Saver encoding images to GPU compressed textures with [basis-universal](basis_universal),
+/// like the PNG and JPEG textures of a game before shipping it.
+///
+/// The encoded textures stay compressed in GPU memory: when loading them, the
+/// [`ImageTextureLoader`](crate::texture::ImageTextureLoader) transcodes them to a compressed
+/// format supported by the GPU, like ASTC on mobile or BC7 on desktop, and only decompresses them
+/// when the GPU doesn't support any.
+///
+/// ```no_run
+/// # use bevy_asset::{AssetServer, Assets, Handle};
+/// # use bevy_render::texture::Image;
+/// # fn compress(asset_server: AssetServer, images: Assets<Image>, wall: Handle<Image>) {
+/// // with the default `CompressedImageSaver` of the `ImagePlugin`
+/// asset_server
+///     .save(&images, &wall, "textures/wall.ktx2")
+///     .unwrap();
+/// # }
+/// ```
+///
+/// Only the first mip level of the images is encoded, the mip levels are generated again when
+/// [`generate_mipmaps`](CompressedImageSaver::generate_mipmaps) is set. The images must be 2D
+/// images of a single layer, in a format that can be converted to RGBA8.
+#[derive(Clone, Debug)]
+pub struct CompressedImageSaver {
+    /// The format the images are encoded to, which sets the extension of the saved files
+    pub compression: BasisCompression,
+    /// The encoding quality level, from [`basis_universal::UASTC_QUALITY_MIN`] to
+    /// [`basis_universal::UASTC_QUALITY_MAX`] for UASTC, or from
+    /// [`basis_universal::ETC1S_QUALITY_MIN`] to [`basis_universal::ETC1S_QUALITY_MAX`] for ETC1S
+    pub quality: u32,
+    /// Whether the full mip chain of the images is generated and encoded
+    pub generate_mipmaps: bool,
+}
+
+impl Default for CompressedImageSaver {
+    fn default() -> Self {
+        Self::uastc()
+    }
+}
+
+impl CompressedImageSaver {
+    /// Creates a saver writing UASTC KTX2 files, with mip levels.
+    pub fn uastc() -> Self {
+        Self {
+            compression: BasisCompression::Uastc,
+            quality: UASTC_QUALITY_DEFAULT,
+            generate_mipmaps: true,
+        }
+    }
+
+    /// Creates a saver writing ETC1S `.etc1s.ktx2` files, with mip levels.
+    pub fn etc1s() -> Self {
+        Self {
+            compression: BasisCompression::Etc1s,
+            quality: ETC1S_QUALITY_DEFAULT,
+            generate_mipmaps: true,
+        }
+    }
+
+    /// Encodes the image into a `.basis` file.
+    fn compress(&self, image: &Image) -> Result<(Compressor, bool)> {
+        let size = image.texture_descriptor.size;
+        if size.depth_or_array_layers > 1 {
+            return Err(anyhow!(
+                "only 2D images of a single layer can be compressed, the image has {} layers",
+                size.depth_or_array_layers
+            ));
+        }
+        let format = image.texture_descriptor.format;
+        let is_srgb = format.describe().srgb;
+        let rgba_bytes = size.width as usize * size.height as usize * 4;
+        let rgba = match format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => {
+                if image.data.len() < rgba_bytes {
+                    return Err(anyhow!(
+                        "the image has {} bytes of data but {}x{} RGBA8 pixels need {rgba_bytes}",
+                        image.data.len(),
+                        size.width,
+                        size.height,
+                    ));
+                }
+                image.data[..rgba_bytes].to_vec()
+            }
+            _ => image.clone().try_into_dynamic()?.to_rgba8().into_raw(),
+        };
+
+        let (min_quality, max_quality) = match self.compression {
+            BasisCompression::Uastc => (UASTC_QUALITY_MIN, UASTC_QUALITY_MAX),
+            BasisCompression::Etc1s => (ETC1S_QUALITY_MIN, ETC1S_QUALITY_MAX),
+        };
+        if !(min_quality..=max_quality).contains(&self.quality) {
+            return Err(anyhow!(
+                "the {:?} quality level must be from {min_quality} to {max_quality}, not {}",
+                self.compression,
+                self.quality
+            ));
+        }
+        let mut params = CompressorParams::new();
+        match self.compression {
+            BasisCompression::Uastc => {
+                params.set_basis_format(BasisTextureFormat::UASTC4x4);
+                params.set_uastc_quality_level(self.quality);
+            }
+            BasisCompression::Etc1s => {
+                params.set_basis_format(BasisTextureFormat::ETC1S);
+                params.set_etc1s_quality_level(self.quality);
+            }
+        }
+        params.set_color_space(if is_srgb {
+            ColorSpace::Srgb
+        } else {
+            ColorSpace::Linear
+        });
+        params.set_generate_mipmaps(self.generate_mipmaps);
+        params
+            .source_image_mut(0)
+            .init(&rgba, size.width, size.height, 4);
+
+        let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+        let mut compressor = Compressor::new(threads as u32);
+        // SAFETY: the C++ encoder doesn't validate its parameters, `init` and `process` require:
+        // - a single source image, whose `width * height * 4` bytes of RGBA8 pixels were copied
+        //   from `rgba`, which is checked to hold them above
+        // - a quality level in the range of the basis format, which is checked above
+        // - a basis format and a color space set from the enums of basis-universal, and the default
+        //   values of the other parameters
+        unsafe {
+            if !compressor.init(&params) {
+                return Err(anyhow!("failed to initialize the basis-universal encoder"));
+            }
+            compressor
+                .process()
+                .map_err(|error| anyhow!("failed to encode the image: {error:?}"))?;
+        }
+        Ok((compressor, is_srgb))
+    }
+}
+
+impl AssetSaver for CompressedImageSaver {
+    type Asset = Image;
+
+    fn save(&self, image: &Image) -> Result<Vec<u8>> {
+        let (compressor, is_srgb) = self.compress(image)?;
+        let has_alpha = compressor.any_source_image_has_alpha();
+        match self.compression {
+            BasisCompression::Uastc => {
+                uastc_basis_to_ktx2(compressor.basis_file(), is_srgb, has_alpha)
+            }
+            BasisCompression::Etc1s => {
+                etc1s_basis_to_ktx2(compressor.basis_file(), is_srgb, has_alpha)
+            }
+        }
+    }
+
+    fn extensions(&self) -> &[&str] {
+        match self.compression {
+            BasisCompression::Uastc => &["ktx2"],
+            BasisCompression::Etc1s => &["etc1s.ktx2"],
+        }
+    }
+}
+
+/// Moves the UASTC blocks of the mip levels of the first image of a `.basis` file to a KTX2 file.
+///
+/// The UASTC blocks are stored as is in `.basis` files, and KTX2 files store them without
+/// supercompression, so they don't need to be re-encoded.
+fn uastc_basis_to_ktx2(basis: &[u8], is_srgb: bool, has_alpha: bool) -> Result<Vec<u8>> {
+    const UASTC_BLOCK_BYTES: u32 = 16;
+
+    let transcoder = Transcoder::new();
+    let level_count = transcoder.image_level_count(basis, 0);
+    let mut levels = Vec::new();
+    for level_index in 0..level_count {
+        let info = transcoder
+            .image_level_info(basis, 0, level_index)
+            .ok_or_else(|| anyhow!("missing mip level {level_index} in the encoded image"))?;
+        let start = info.m_rgb_file_ofs as usize;
+        let end = start + info.m_rgb_file_len as usize;
+        if info.m_rgb_file_len != info.m_total_blocks * UASTC_BLOCK_BYTES || end > basis.len() {
+            return Err(anyhow!("invalid UASTC data for mip level {level_index}"));
+        }
+        levels.push((
+            info.m_orig_width,
+            info.m_orig_height,
+            basis[start..end].to_vec(),
+        ));
+    }
+
+    let dfd = data_format_descriptor(
+        166, // UASTC color model
+        is_srgb,
+        UASTC_BLOCK_BYTES as u8,
+        &[(0, 127, if has_alpha { 3 } else { 0 })], // RGBA or RGB channel
+    );
+    ktx2_file(0, &dfd, &[], &levels, 16)
+}
+
+/// Moves the ETC1S slices, codebooks and tables of the mip levels of the first image of a
+/// `.basis` file to a KTX2 file with `BasisLZ` supercompression.
+///
+/// KTX2 files store the codebooks and tables of the `.basis` files in their supercompression
+/// global data, and the RGB and alpha slices of each mip level in its level data.
+fn etc1s_basis_to_ktx2(basis: &[u8], is_srgb: bool, has_alpha: bool) -> Result<Vec<u8>> {
+    const BASIS_HEADER_BYTES: usize = 77;
+
+    if basis.len() < BASIS_HEADER_BYTES {
+        return Err(anyhow!("the encoded image has no header"));
+    }
+    let read = |offset: usize, bytes: usize| {
+        basis[offset..offset + bytes]
+            .iter()
+            .rev()
+            .fold(0, |value, byte| value << 8 | *byte as usize)
+    };
+    let section = |offset: usize, bytes: usize| {
+        basis
+            .get(offset..offset + bytes)
+            .ok_or_else(|| anyhow!("invalid ETC1S data in the encoded image"))
+    };
+    let endpoint_count = read(39, 2);
+    let endpoints = section(read(41, 4), read(45, 3))?;
+    let selector_count = read(48, 2);
+    let selectors = section(read(50, 4), read(54, 3))?;
+    let tables = section(read(57, 4), read(61, 4))?;
+    let extended = section(read(69, 4), read(73, 4))?;
+
+    let transcoder = Transcoder::new();
+    let level_count = transcoder.image_level_count(basis, 0);
+    let mut levels = Vec::new();
+    let mut image_descs = Vec::new();
+    for level_index in 0..level_count {
+        let info = transcoder
+            .image_level_info(basis, 0, level_index)
+            .ok_or_else(|| anyhow!("missing mip level {level_index} in the encoded image"))?;
+        let rgb = section(info.m_rgb_file_ofs as usize, info.m_rgb_file_len as usize)?;
+        let alpha = section(
+            info.m_alpha_file_ofs as usize,
+            info.m_alpha_file_len as usize,
+        )?;
+        for value in [
+            0, // no image flags, all the images are I-frames
+            0,
+            rgb.len(),
+            if alpha.is_empty() { 0 } else { rgb.len() },
+            alpha.len(),
+        ] {
+            image_descs.extend_from_slice(&(value as u32).to_le_bytes());
+        }
+        levels.push((info.m_orig_width, info.m_orig_height, [rgb, alpha].concat()));
+    }
+
+    let mut global_data = Vec::new();
+    global_data.extend_from_slice(&(endpoint_count as u16).to_le_bytes());
+    global_data.extend_from_slice(&(selector_count as u16).to_le_bytes());
+    for section in [endpoints, selectors, tables, extended] {
+        global_data.extend_from_slice(&(section.len() as u32).to_le_bytes());
+    }
+    global_data.append(&mut image_descs);
+    for section in [endpoints, selectors, tables, extended] {
+        global_data.extend_from_slice(section);
+    }
+
+    let mut samples = vec![(0, 63, 0)]; // RGB channel
+    if has_alpha {
+        samples.push((64, 63, 15)); // AAA channel
+    }
+    let dfd = data_format_descriptor(
+        163, // ETC1S color model
+        is_srgb, 0, &samples,
+    );
+    ktx2_file(1, &dfd, &global_data, &levels, 1)
+}
+
+/// Creates a data format descriptor with a basic descriptor block of 4x4 texel blocks, with the
+/// bit offset, bit length - 1 and channel type of each sample.
+fn data_format_descriptor(
+    color_model: u8,
+    is_srgb: bool,
+    block_bytes: u8,
+    samples: &[(u16, u8, u8)],
+) -> Vec<u8> {
+    let block_size = 24 + 16 * samples.len();
+    let mut dfd = Vec::new();
+    dfd.extend_from_slice(&(block_size as u32 + 4).to_le_bytes());
+    dfd.extend_from_slice(&0u32.to_le_bytes()); // Khronos vendor, basic descriptor type
+    dfd.extend_from_slice(&2u16.to_le_bytes()); // version
+    dfd.extend_from_slice(&(block_size as u16).to_le_bytes());
+    dfd.extend_from_slice(&[
+        color_model,
+        1, // BT.709 color primaries
+        if is_srgb { 2 } else { 1 },
+        0, // straight alpha
+        3, // 4x4 texel blocks
+        3,
+        0,
+        0,
+        block_bytes,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+        0,
+    ]);
+    for &(bit_offset, bit_length, channel_type) in samples {
+        dfd.extend_from_slice(&bit_offset.to_le_bytes());
+        dfd.extend_from_slice(&[bit_length, channel_type, 0, 0, 0, 0]);
+        dfd.extend_from_slice(&0u32.to_le_bytes()); // sample lower
+        dfd.extend_from_slice(&u32::MAX.to_le_bytes()); // sample upper
+    }
+    dfd
+}
+
+/// Writes a KTX2 file of a single 2D image with the given mip levels, from the full size level.
+///
+/// The data of the levels is stored from the smallest level, aligned to `alignment`.
+fn ktx2_file(
+    supercompression_scheme: u32,
+    dfd: &[u8],
+    global_data: &[u8],
+    levels: &[(u32, u32, Vec<u8>)],
+    alignment: usize,
+) -> Result<Vec<u8>> {
+    const HEADER_BYTES: usize = 80;
+    const LEVEL_INDEX_BYTES: usize = 24;
+
+    let Some(&(width, height, _)) = levels.first() else {
+        return Err(anyhow!("the encoded image has no mip levels"));
+    };
+    let dfd_offset = HEADER_BYTES + LEVEL_INDEX_BYTES * levels.len();
+    // the supercompression global data is aligned to 8 bytes
+    let global_data_offset = if global_data.is_empty() {
+        0
+    } else {
+        (dfd_offset + dfd.len() + 7) & !7
+    };
+    let mut ktx2 = Vec::new();
+    // header
+    ktx2.extend_from_slice(&[
+        0xAB, b'K', b'T', b'X', b' ', b'2', b'0', 0xBB, b'\r', b'\n', 0x1A, b'\n',
+    ]);
+    for value in [
+        0, // VK_FORMAT_UNDEFINED, the format is described by the DFD
+        1, // type size
+        width,
+        height,
+        0, // depth
+        0, // layer count
+        1, // face count
+        levels.len() as u32,
+        supercompression_scheme,
+        dfd_offset as u32,
+        dfd.len() as u32,
+        0, // no key/value data
+        0,
+    ] {
+        ktx2.extend_from_slice(&value.to_le_bytes());
+    }
+    ktx2.extend_from_slice(&(global_data_offset as u64).to_le_bytes());
+    ktx2.extend_from_slice(&(global_data.len() as u64).to_le_bytes());
+
+    // the level index, the supercompressed levels have no uncompressed length
+    let mut level_offset = (dfd_offset + dfd.len()).max(global_data_offset + global_data.len());
+    let mut level_offsets = vec![0; levels.len()];
+    for (offset, (_, _, data)) in level_offsets.iter_mut().zip(levels).rev() {
+        level_offset = (level_offset + alignment - 1) / alignment * alignment;
+        *offset = level_offset;
+        level_offset += data.len();
+    }
+    for (offset, (_, _, data)) in level_offsets.iter().zip(levels) {
+        let uncompressed_length = if supercompression_scheme == 0 {
+            data.len()
+        } else {
+            0
+        };
+        for value in [*offset, data.len(), uncompressed_length] {
+            ktx2.extend_from_slice(&(value as u64).to_le_bytes());
+        }
+    }
+
+    ktx2.extend_from_slice(dfd);
+    if !global_data.is_empty() {
+        ktx2.resize(global_data_offset, 0);
+        ktx2.extend_from_slice(global_data);
+    }
+    for (offset, (_, _, data)) in level_offsets.iter().zip(levels).rev() {
+        ktx2.resize(*offset, 0);
+        ktx2.extend_from_slice(data);
+    }
+    Ok(ktx2)
+}
+
+#[cfg(all(test, feature = "ktx2"))]
+mod tests {
+    use super::*;
+    use crate::texture::{CompressedImageFormats, ImageType};
+    use wgpu::{Extent3d, TextureDimension};
+
+    fn gradient() -> Image {
+        let data = (0..32 * 16)
+            .flat_map(|i| [(i % 32 * 8) as u8, (i / 32 * 16) as u8, 128, 192])
+            .collect();
+        Image::new(
+            Extent3d {
+                width: 32,
+                height: 16,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    #[test]
+    fn compressed_image_roundtrip() {
+        for (saver, extension) in [
+            (CompressedImageSaver::uastc(), "ktx2"),
+            (CompressedImageSaver::etc1s(), "etc1s.ktx2"),
+        ] {
+            assert_eq!(saver.extensions(), [extension]);
+            let bytes = saver.save(&gradient()).unwrap();
+            let image = Image::from_buffer(
+                &bytes,
+                ImageType::Extension("ktx2"),
+                CompressedImageFormats::NONE,
+                true,
+            )
+            .unwrap();
+            assert_eq!(image.texture_descriptor.size.width, 32);
+            assert_eq!(image.texture_descriptor.size.height, 16);
+            assert_eq!(image.texture_descriptor.mip_level_count, 6);
+            assert_eq!(
+                image.texture_descriptor.format,
+                TextureFormat::Rgba8UnormSrgb
+            );
+
+            // checks the first pixel of the last row, with the error of the lossy encoding
+            let pixel = &image.data[15 * 32 * 4..15 * 32 * 4 + 4];
+            assert!(pixel[0] < 48 && pixel[1] > 208, "{pixel:?}");
+            assert!((176..208).contains(&pixel[3]), "{pixel:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_images_are_rejected() {
+        let mut image = gradient();
+        image.data.truncate(32 * 4);
+        assert!(CompressedImageSaver::uastc().save(&image).is_err());
+
+        for saver in [
+            CompressedImageSaver {
+                quality: UASTC_QUALITY_MAX + 1,
+                ..CompressedImageSaver::uastc()
+            },
+            CompressedImageSaver {
+                quality: 0,
+                ..CompressedImageSaver::etc1s()
+            },
+        ] {
+            assert!(saver.save(&gradient()).is_err());
+        }
+    }
+}
@@ -1,14 +1,52 @@
-use crate::texture::{Image, TextureFormatPixelInfo};
-use anyhow::Result;
+use crate::texture::{FloatPrecision, Image, ImageSamplerSettings};
+use anyhow::{anyhow, Result};
 use bevy_asset::{AssetLoader, LoadContext, LoadedAsset};
 use bevy_utils::BoxedFuture;
-use image::ImageDecoder;
-use wgpu::{Extent3d, TextureDimension, TextureFormat};
+use exr::prelude::{read, AnyChannels, FlatSamples, Layer, ReadChannels, ReadLayers};
+use serde::{Deserialize, Serialize};
+use wgpu::{Extent3d, TextureDimension};
 
 /// Loads EXR textures as Texture assets
+///
+/// The layer and the channels imported, and the precision of the texture, are set by the
+/// [`ExrLoaderSettings`] of the image.
 #[derive(Clone, Default)]
 pub struct ExrTextureLoader;
 
+/// The settings of the [`ExrTextureLoader`] for an image, read from its meta file or passed to
+/// [`AssetServer::load_with_settings`](bevy_asset::AssetServer::load_with_settings)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExrLoaderSettings {
+    /// The name of the layer imported, or `None` to import the first layer.
+    ///
+    /// The layers are the parts of multi-part images, or the channels whose names start with the
+    /// name of the layer, like the `diffuse.R`, `diffuse.G` and `diffuse.B` channels of the
+    /// `diffuse` layer.
+    pub layer: Option<String>,
+    /// The names of the channels of the layer imported as the red, green, blue and alpha channels
+    /// of the texture, from one to four channels.
+    ///
+    /// A missing `A` channel is imported as opaque, like the alpha channel added to the textures
+    /// of three channels.
+    pub channels: Vec<String>,
+    /// The precision of the floats of the texture
+    pub precision: FloatPrecision,
+    /// The sampler of the image
+    pub sampler: ImageSamplerSettings,
+}
+
+impl Default for ExrLoaderSettings {
+    fn default() -> Self {
+        Self {
+            layer: None,
+            channels: ["R", "G", "B", "A"].map(String::from).to_vec(),
+            precision: FloatPrecision::Full,
+            sampler: ImageSamplerSettings::Default,
+        }
+    }
+}
+
 impl AssetLoader for ExrTextureLoader {
     fn load<'a>(
         &'a self,
@@ -16,35 +54,8 @@ impl AssetLoader for ExrTextureLoader {
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<()>> {
         Box::pin(async move {
-            let format = TextureFormat::Rgba32Float;
-            debug_assert_eq!(
-                format.pixel_size(),
-                4 * 4,
-                "Format should have 32bit x 4 size"
-            );
-
-            let decoder = image::codecs::openexr::OpenExrDecoder::with_alpha_preference(
-                std::io::Cursor::new(bytes),
-                Some(true),
-            )?;
-            let (width, height) = decoder.dimensions();
-
-            let total_bytes = decoder.total_bytes() as usize;
-
-            let mut buf = vec![0u8; total_bytes];
-            decoder.read_image(buf.as_mut_slice())?;
-
-            let texture = Image::new(
-                Extent3d {
-                    width,
-                    height,
-                    depth_or_array_layers: 1,
-                },
-                TextureDimension::D2,
-                buf,
-                format,
-            );
-
+            let settings = load_context.settings::<ExrLoaderSettings>()?;
+            let texture = exr_buffer_to_image(bytes, &settings)?;
             load_context.set_default_asset(LoadedAsset::new(texture));
             Ok(())
         })
@@ -54,3 +65,199 @@ impl AssetLoader for ExrTextureLoader {
         &["exr"]
     }
 }
+
+/// Reads the layer and the channels of an EXR image selected by `settings` into a float texture.
+pub fn exr_buffer_to_image(bytes: &[u8], settings: &ExrLoaderSettings) -> Result<Image> {
+    if settings.channels.is_empty() || settings.channels.len() > 4 {
+        return Err(anyhow!(
+            "from one to four EXR channels can be imported, not {}",
+            settings.channels.len()
+        ));
+    }
+
+    let exr_image = read()
+        .no_deep_data()
+        .largest_resolution_level()
+        .all_channels()
+        .all_layers()
+        .all_attributes()
+        .from_buffered(std::io::Cursor::new(bytes))?;
+
+    let (layer, prefix) = match &settings.layer {
+        None => exr_image
+            .layer_data
+            .first()
+            .map(|layer| (layer, String::new())),
+        Some(name) => find_layer(&exr_image.layer_data, name),
+    }
+    .ok_or_else(|| anyhow!("no EXR layer {:?}", settings.layer))?;
+
+    let channels = settings
+        .channels
+        .iter()
+        .map(|name| {
+            let channel_name = format!("{prefix}{name}");
+            match layer
+                .channel_data
+                .list
+                .iter()
+                .find(|channel| channel.name.eq(channel_name.as_str()))
+            {
+                Some(channel) => Ok(Some(&channel.sample_data)),
+                None if name == "A" => Ok(None),
+                None => Err(anyhow!(
+                    "no EXR channel {channel_name}, the channels are {}",
+                    channel_names(layer)
+                )),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let texture_channels = match channels.len() {
+        3 => 4,
+        channel_count => channel_count,
+    };
+    let format = settings.precision.texture_format(texture_channels);
+    let pixel_count = layer.size.area();
+    let mut data = Vec::with_capacity(pixel_count * format.describe().block_size as usize);
+    for pixel in 0..pixel_count {
+        for channel in 0..texture_channels {
+            let value = match channels.get(channel) {
+                Some(Some(FlatSamples::F16(samples))) => samples[pixel].to_f32(),
+                Some(Some(FlatSamples::F32(samples))) => samples[pixel],
+                Some(Some(FlatSamples::U32(samples))) => samples[pixel] as f32,
+                // the missing alpha channel
+                Some(None) | None => 1.0,
+            };
+            settings.precision.push(&mut data, value);
+        }
+    }
+
+    let mut texture = Image::new(
+        Extent3d {
+            width: layer.size.width() as u32,
+            height: layer.size.height() as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        format,
+    );
+    texture.sampler_descriptor = settings.sampler.into();
+    Ok(texture)
+}
+
+type ExrLayer = Layer<AnyChannels<FlatSamples>>;
+
+/// Finds the part named `name`, or else the layer of the channels prefixed by `name.`, returning
+/// the prefix of the names of its channels.
+fn find_layer<'a>(layers: &'a [ExrLayer], name: &str) -> Option<(&'a ExrLayer, String)> {
+    let part = layers
+        .iter()
+        .find(|layer| match &layer.attributes.layer_name {
+            Some(layer_name) => layer_name.eq(name),
+            None => false,
+        });
+    if let Some(part) = part {
+        return Some((part, String::new()));
+    }
+    let prefix = format!("{name}.");
+    layers
+        .iter()
+        .find(|layer| {
+            layer
+                .channel_data
+                .list
+                .iter()
+                .any(|channel| channel.name.to_string().starts_with(&prefix))
+        })
+        .map(|layer| (layer, prefix))
+}
+
+fn channel_names(layer: &ExrLayer) -> String {
+    layer
+        .channel_data
+        .list
+        .iter()
+        .map(|channel| channel.name.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use exr::prelude::{
+        AnyChannel, Encoding, Image as ExrImage, ImageAttributes, IntegerBounds, LayerAttributes,
+        Vec2, WritableImage,
+    };
+    use wgpu::TextureFormat;
+
+    fn layer(name: &str, channels: &[(&str, f32)]) -> ExrLayer {
+        let channels = channels
+            .iter()
+            .map(|(name, value)| AnyChannel::new(*name, FlatSamples::F32(vec![*value; 2 * 2])))
+            .collect::<Vec<_>>();
+        Layer::new(
+            Vec2(2, 2),
+            LayerAttributes::named(name),
+            Encoding::UNCOMPRESSED,
+            AnyChannels::sort(channels.into()),
+        )
+    }
+
+    fn floats(image: &Image) -> Vec<f32> {
+        image
+            .data
+            .chunks(4)
+            .map(|bytes| f32::from_ne_bytes(bytes.try_into().unwrap()))
+            .collect()
+    }
+
+    #[test]
+    fn exr_layers_and_channels() {
+        let exr_image = ExrImage::from_layers(
+            ImageAttributes::new(IntegerBounds::from_dimensions((2, 2))),
+            vec![
+                layer("beauty", &[("R", 1.0), ("G", 2.0), ("B", 3.0)]),
+                layer(
+                    "lightmap",
+                    &[("R", 4.0), ("G", 5.0), ("B", 6.0), ("Y", 7.0)],
+                ),
+            ],
+        );
+        let mut bytes = Vec::new();
+        exr_image
+            .write()
+            .to_buffered(std::io::Cursor::new(&mut bytes))
+            .unwrap();
+
+        let image = exr_buffer_to_image(&bytes, &ExrLoaderSettings::default()).unwrap();
+        assert_eq!(image.texture_descriptor.format, TextureFormat::Rgba32Float);
+        assert_eq!(floats(&image)[..4], [1.0, 2.0, 3.0, 1.0]);
+
+        let settings = ExrLoaderSettings {
+            layer: Some("lightmap".to_string()),
+            channels: vec!["Y".to_string()],
+            ..Default::default()
+        };
+        let image = exr_buffer_to_image(&bytes, &settings).unwrap();
+        assert_eq!(image.texture_descriptor.format, TextureFormat::R32Float);
+        assert_eq!(floats(&image), [7.0; 4]);
+
+        let settings = ExrLoaderSettings {
+            layer: Some("lightmap".to_string()),
+            precision: FloatPrecision::Half,
+            ..Default::default()
+        };
+        let image = exr_buffer_to_image(&bytes, &settings).unwrap();
+        assert_eq!(image.texture_descriptor.format, TextureFormat::Rgba16Float);
+        assert_eq!(image.data.len(), 2 * 2 * 4 * 2);
+
+        let settings = ExrLoaderSettings {
+            channels: vec!["Z".to_string()],
+            ..Default::default()
+        };
+        assert!(exr_buffer_to_image(&bytes, &settings).is_err());
+    }
+}
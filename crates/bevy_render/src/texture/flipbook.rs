@@ -0,0 +1,154 @@
+use crate::render_resource::Shader;
+use bevy_asset::HandleUntyped;
+use bevy_reflect::{FromReflect, Reflect, TypeUuid};
+
+pub const FLIPBOOK_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 10731951191473935291);
+
+/// How a [`Flipbook`], or an animation played on the CPU like a sprite animation, plays through
+/// its frames.
+#[derive(Reflect, FromReflect, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum FlipbookMode {
+    /// Starts over from the first frame after the last one.
+    #[default]
+    Loop,
+    /// Stops on the last frame.
+    Once,
+    /// Plays the frames back and forth.
+    PingPong,
+}
+
+impl FlipbookMode {
+    /// The value of the mode in the shader.
+    ///
+    /// NOTE: These must match the constants in `bevy_render/src/texture/flipbook.wgsl`!
+    pub fn as_u32(self) -> u32 {
+        match self {
+            FlipbookMode::Loop => 0,
+            FlipbookMode::Once => 1,
+            FlipbookMode::PingPong => 2,
+        }
+    }
+}
+
+/// Animates the texture coordinates of a material through the frames of a flipbook texture,
+/// laid out left to right and top to bottom in a grid of equally sized cells.
+///
+/// The animation runs on the GPU from the `globals.time` shader uniform, so animated textures
+/// don't need any per-frame work on the CPU. The UVs of the mesh are expected to cover a single
+/// frame, from `0.0` to `1.0`.
+#[derive(Reflect, FromReflect, Debug, Clone, Copy, PartialEq)]
+pub struct Flipbook {
+    /// The number of frames in each row of the texture.
+    pub columns: u32,
+    /// The number of rows of frames in the texture.
+    pub rows: u32,
+    /// The number of frames to play, which can be lower than `columns * rows` when the last row
+    /// isn't full.
+    pub frame_count: u32,
+    /// The number of frames shown per second.
+    pub fps: f32,
+    /// How the animation plays through its frames.
+    pub mode: FlipbookMode,
+    /// The time at which the animation starts, in seconds, as returned by
+    /// [`Time::elapsed_seconds_wrapped`](bevy_time::Time::elapsed_seconds_wrapped).
+    ///
+    /// The animation shows its first frame before that. Like that time, the animation wraps
+    /// around every hour.
+    pub start_time: f32,
+}
+
+impl Flipbook {
+    /// Creates a looping [`Flipbook`] playing all the cells of a grid at 24 frames per second.
+    pub fn new(columns: u32, rows: u32) -> Self {
+        Flipbook {
+            columns,
+            rows,
+            frame_count: columns * rows,
+            fps: 24.0,
+            mode: FlipbookMode::Loop,
+            start_time: 0.0,
+        }
+    }
+
+    /// Returns this [`Flipbook`] with its number of frames set to `frame_count`.
+    pub fn with_frame_count(mut self, frame_count: u32) -> Self {
+        self.frame_count = frame_count;
+        self
+    }
+
+    /// Returns this [`Flipbook`] with its number of frames per second set to `fps`.
+    pub fn with_fps(mut self, fps: f32) -> Self {
+        self.fps = fps;
+        self
+    }
+
+    /// Returns this [`Flipbook`] with its [`FlipbookMode`] set to `mode`.
+    pub fn with_mode(mut self, mode: FlipbookMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns this [`Flipbook`] with its start time set to `start_time`.
+    pub fn with_start_time(mut self, start_time: f32) -> Self {
+        self.start_time = start_time;
+        self
+    }
+
+    /// The index of the frame shown at `time`, the same way the shaders compute it.
+    pub fn frame(&self, time: f32) -> u32 {
+        let frame_count = self.frame_count.max(1);
+        let frame = ((time - self.start_time).max(0.0) * self.fps) as u32;
+        match self.mode {
+            FlipbookMode::Loop => frame % frame_count,
+            FlipbookMode::Once => frame.min(frame_count - 1),
+            FlipbookMode::PingPong => {
+                if frame_count == 1 {
+                    return 0;
+                }
+                let period = 2 * (frame_count - 1);
+                let frame = frame % period;
+                if frame < frame_count {
+                    frame
+                } else {
+                    period - frame
+                }
+            }
+        }
+    }
+}
+
+impl Default for Flipbook {
+    fn default() -> Self {
+        Flipbook::new(1, 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flipbook_frames() {
+        let flipbook = Flipbook::new(2, 2).with_fps(1.0);
+        let frames = |flipbook: Flipbook| {
+            (0..8)
+                .map(|time| flipbook.frame(time as f32 + 0.5))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(frames(flipbook), [0, 1, 2, 3, 0, 1, 2, 3]);
+        assert_eq!(
+            frames(flipbook.with_mode(FlipbookMode::Once)),
+            [0, 1, 2, 3, 3, 3, 3, 3]
+        );
+        assert_eq!(
+            frames(flipbook.with_mode(FlipbookMode::PingPong)),
+            [0, 1, 2, 3, 2, 1, 0, 1]
+        );
+        assert_eq!(
+            frames(flipbook.with_frame_count(3).with_start_time(2.0)),
+            [0, 0, 0, 1, 2, 0, 1, 2]
+        );
+    }
+}
@@ -0,0 +1,41 @@
+#define_import_path bevy_render::flipbook
+
+// NOTE: These must match `FlipbookMode::as_u32` in bevy_render/src/texture/flipbook.rs!
+const FLIPBOOK_MODE_LOOP: u32      = 0u;
+const FLIPBOOK_MODE_ONCE: u32      = 1u;
+const FLIPBOOK_MODE_PING_PONG: u32 = 2u;
+
+struct Flipbook {
+    columns: u32,
+    rows: u32,
+    frame_count: u32,
+    mode: u32,
+    fps: f32,
+    start_time: f32,
+};
+
+// The index of the frame of the flipbook shown at `time`
+fn flipbook_frame(flipbook: Flipbook, time: f32) -> u32 {
+    let frame_count = max(flipbook.frame_count, 1u);
+    let frame = u32(max(time - flipbook.start_time, 0.0) * flipbook.fps);
+    if (flipbook.mode == FLIPBOOK_MODE_ONCE) {
+        return min(frame, frame_count - 1u);
+    }
+    if (flipbook.mode == FLIPBOOK_MODE_PING_PONG) {
+        if (frame_count == 1u) {
+            return 0u;
+        }
+        let period = 2u * (frame_count - 1u);
+        let ping_pong_frame = frame % period;
+        return select(period - ping_pong_frame, ping_pong_frame, ping_pong_frame < frame_count);
+    }
+    return frame % frame_count;
+}
+
+// Maps the uv of a single frame onto the cell of the flipbook texture shown at `time`
+fn flipbook_uv(flipbook: Flipbook, uv: vec2<f32>, time: f32) -> vec2<f32> {
+    let columns = max(flipbook.columns, 1u);
+    let frame = flipbook_frame(flipbook, time);
+    let cell = vec2<f32>(f32(frame % columns), f32(frame / columns));
+    return (cell + uv) / vec2<f32>(f32(columns), f32(max(flipbook.rows, 1u)));
+}
@@ -1,13 +1,27 @@
-use crate::texture::{Image, TextureFormatPixelInfo};
+use crate::texture::{FloatPrecision, Image, ImageSamplerSettings};
 use anyhow::Result;
 use bevy_asset::{AssetLoader, LoadContext, LoadedAsset};
 use bevy_utils::BoxedFuture;
-use wgpu::{Extent3d, TextureDimension, TextureFormat};
+use serde::{Deserialize, Serialize};
+use wgpu::{Extent3d, TextureDimension};
 
 /// Loads HDR textures as Texture assets
+///
+/// The precision of the texture is set by the [`HdrLoaderSettings`] of the image.
 #[derive(Clone, Default)]
 pub struct HdrTextureLoader;
 
+/// The settings of the [`HdrTextureLoader`] for an image, read from its meta file or passed to
+/// [`AssetServer::load_with_settings`](bevy_asset::AssetServer::load_with_settings)
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HdrLoaderSettings {
+    /// The precision of the floats of the texture
+    pub precision: FloatPrecision,
+    /// The sampler of the image
+    pub sampler: ImageSamplerSettings,
+}
+
 impl AssetLoader for HdrTextureLoader {
     fn load<'a>(
         &'a self,
@@ -15,28 +29,24 @@ impl AssetLoader for HdrTextureLoader {
         load_context: &'a mut LoadContext,
     ) -> BoxedFuture<'a, Result<()>> {
         Box::pin(async move {
-            let format = TextureFormat::Rgba32Float;
-            debug_assert_eq!(
-                format.pixel_size(),
-                4 * 4,
-                "Format should have 32bit x 4 size"
-            );
+            let settings = load_context.settings::<HdrLoaderSettings>()?;
+            let format = settings.precision.texture_format(4);
 
             let decoder = image::codecs::hdr::HdrDecoder::new(bytes)?;
             let info = decoder.metadata();
             let rgb_data = decoder.read_image_hdr()?;
-            let mut rgba_data = Vec::with_capacity(rgb_data.len() * format.pixel_size());
+            let mut rgba_data =
+                Vec::with_capacity(rgb_data.len() * format.describe().block_size as usize);
 
             for rgb in rgb_data {
                 let alpha = 1.0f32;
 
-                rgba_data.extend_from_slice(&rgb.0[0].to_ne_bytes());
-                rgba_data.extend_from_slice(&rgb.0[1].to_ne_bytes());
-                rgba_data.extend_from_slice(&rgb.0[2].to_ne_bytes());
-                rgba_data.extend_from_slice(&alpha.to_ne_bytes());
+                for value in [rgb.0[0], rgb.0[1], rgb.0[2], alpha] {
+                    settings.precision.push(&mut rgba_data, value);
+                }
             }
 
-            let texture = Image::new(
+            let mut texture = Image::new(
                 Extent3d {
                     width: info.width,
                     height: info.height,
@@ -46,6 +56,7 @@ impl AssetLoader for HdrTextureLoader {
                 rgba_data,
                 format,
             );
+            texture.sampler_descriptor = settings.sampler.into();
 
             load_context.set_default_asset(LoadedAsset::new(texture));
             Ok(())
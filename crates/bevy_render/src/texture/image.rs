@@ -7,17 +7,20 @@ use super::ktx2::*;
 
 use crate::{
     render_asset::{PrepareAssetError, RenderAsset},
-    render_resource::{Sampler, Texture, TextureView},
+    render_resource::{PipelineCache, Sampler, SpecializedRenderPipelines, Texture, TextureView},
     renderer::{RenderDevice, RenderQueue},
-    texture::BevyDefault,
+    texture::{supports_mipmap_generation, BevyDefault, MipmapPipeline},
 };
 use bevy_asset::HandleUntyped;
 use bevy_derive::{Deref, DerefMut};
-use bevy_ecs::system::{lifetimeless::SRes, Resource, SystemParamItem};
+use bevy_ecs::system::{
+    lifetimeless::{SRes, SResMut},
+    Resource, SystemParamItem,
+};
 use bevy_math::Vec2;
 use bevy_reflect::{FromReflect, Reflect, TypeUuid};
 
-use std::hash::Hash;
+use std::{hash::Hash, time::Duration};
 use thiserror::Error;
 use wgpu::{Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor};
 
@@ -53,7 +56,7 @@ impl ImageFormat {
             "image/vnd-ms.dds" => ImageFormat::Dds,
             "image/jpeg" => ImageFormat::Jpeg,
             "image/ktx2" => ImageFormat::Ktx2,
-            "image/png" => ImageFormat::Png,
+            "image/png" | "image/apng" => ImageFormat::Png,
             "image/x-exr" => ImageFormat::OpenExr,
             "image/x-targa" | "image/x-tga" => ImageFormat::Tga,
             _ => return None,
@@ -74,7 +77,7 @@ impl ImageFormat {
             "jpg" | "jpeg" => ImageFormat::Jpeg,
             "ktx2" => ImageFormat::Ktx2,
             "pbm" | "pam" | "ppm" | "pgm" => ImageFormat::Pnm,
-            "png" => ImageFormat::Png,
+            "png" | "apng" => ImageFormat::Png,
             "tga" => ImageFormat::Tga,
             "tif" | "tiff" => ImageFormat::Tiff,
             "webp" => ImageFormat::WebP,
@@ -316,6 +319,31 @@ impl Image {
         });
     }
 
+    /// Gives the image a full mip chain, generated on the GPU from its first mip level when the
+    /// image is prepared for rendering.
+    ///
+    /// Does nothing if the image already has several mip levels, isn't a 2d image or its format
+    /// doesn't support [mipmap generation](supports_mipmap_generation).
+    pub fn generate_mipmaps(&mut self) {
+        let descriptor = &mut self.texture_descriptor;
+        if descriptor.mip_level_count == 1
+            && descriptor.dimension == TextureDimension::D2
+            && supports_mipmap_generation(descriptor.format)
+        {
+            descriptor.mip_level_count = descriptor.size.max_mips(descriptor.dimension);
+        }
+    }
+
+    /// Returns `true` if the image has several mip levels but its data only holds the first one,
+    /// the other ones being generated on the GPU.
+    pub fn has_generated_mipmaps(&self) -> bool {
+        let descriptor = &self.texture_descriptor;
+        descriptor.mip_level_count > 1
+            && descriptor.dimension == TextureDimension::D2
+            && supports_mipmap_generation(descriptor.format)
+            && self.data.len() == descriptor.size.volume() * descriptor.format.pixel_size()
+    }
+
     /// Convert a texture from a format to another. Only a few formats are
     /// supported as input and output:
     /// - `TextureFormat::R8Unorm`
@@ -384,6 +412,47 @@ impl Image {
         }
     }
 
+    /// Load a bytes buffer in the [`ImageFrame`]s of an animated image, according to type
+    /// `image_type`, using the `image` crate.
+    ///
+    /// Animated PNGs (APNG) are decoded in all their frames, composited on the full canvas. Other
+    /// images are returned as a single frame shown for no time.
+    pub fn frames_from_buffer(
+        buffer: &[u8],
+        image_type: ImageType,
+        supported_compressed_formats: CompressedImageFormats,
+        is_srgb: bool,
+    ) -> Result<Vec<ImageFrame>, TextureError> {
+        #[cfg(feature = "png")]
+        if matches!(image_type.to_image_format()?, ImageFormat::Png) {
+            use image::AnimationDecoder;
+
+            let decoder = image::codecs::png::PngDecoder::new(std::io::Cursor::new(buffer))?;
+            if decoder.is_apng() {
+                return decoder
+                    .apng()
+                    .into_frames()
+                    .map(|frame| {
+                        let frame = frame?;
+                        Ok(ImageFrame {
+                            duration: frame.delay().into(),
+                            image: Self::from_dynamic(
+                                image::DynamicImage::ImageRgba8(frame.into_buffer()),
+                                is_srgb,
+                            ),
+                        })
+                    })
+                    .collect();
+            }
+        }
+
+        let image = Self::from_buffer(buffer, image_type, supported_compressed_formats, is_srgb)?;
+        Ok(vec![ImageFrame {
+            image,
+            duration: Duration::ZERO,
+        }])
+    }
+
     /// Whether the texture format is compressed or uncompressed
     pub fn is_compressed(&self) -> bool {
         let format_description = self.texture_descriptor.format.describe();
@@ -399,6 +468,15 @@ impl Image {
     }
 }
 
+/// A frame of an animated image, loaded with [`Image::frames_from_buffer`].
+#[derive(Debug, Clone)]
+pub struct ImageFrame {
+    /// The image of the frame, of the size of the whole animation.
+    pub image: Image,
+    /// How long the frame is shown before the next one.
+    pub duration: Duration,
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum DataFormat {
     Rgb,
@@ -509,6 +587,9 @@ impl RenderAsset for Image {
         SRes<RenderDevice>,
         SRes<RenderQueue>,
         SRes<DefaultImageSampler>,
+        SRes<PipelineCache>,
+        SRes<MipmapPipeline>,
+        SResMut<SpecializedRenderPipelines<MipmapPipeline>>,
     );
 
     /// Clones the Image.
@@ -519,13 +600,45 @@ impl RenderAsset for Image {
     /// Converts the extracted image into a [`GpuImage`].
     fn prepare_asset(
         image: Self::ExtractedAsset,
-        (render_device, render_queue, default_sampler): &mut SystemParamItem<Self::Param>,
-    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
-        let texture = render_device.create_texture_with_data(
+        (
+            render_device,
             render_queue,
-            &image.texture_descriptor,
-            &image.data,
-        );
+            default_sampler,
+            pipeline_cache,
+            mipmap_pipeline,
+            mipmap_pipelines,
+        ): &mut SystemParamItem<Self::Param>,
+    ) -> Result<Self::PreparedAsset, PrepareAssetError<Self::ExtractedAsset>> {
+        let texture = if image.has_generated_mipmaps() {
+            let pipeline_id = mipmap_pipelines.specialize(
+                pipeline_cache,
+                mipmap_pipeline,
+                image.texture_descriptor.format,
+            );
+            match pipeline_cache.get_render_pipeline(pipeline_id) {
+                Some(pipeline) => {
+                    mipmap_pipeline.create_texture(&image, pipeline, render_device, render_queue)
+                }
+                None if pipeline_cache
+                    .get_render_pipeline_state(pipeline_id)
+                    .is_pending() =>
+                {
+                    return Err(PrepareAssetError::RetryNextUpdate(image));
+                }
+                // Fall back to the first mip level if the pipeline failed
+                None => {
+                    let mut descriptor = image.texture_descriptor.clone();
+                    descriptor.mip_level_count = 1;
+                    render_device.create_texture_with_data(render_queue, &descriptor, &image.data)
+                }
+            }
+        } else {
+            render_device.create_texture_with_data(
+                render_queue,
+                &image.texture_descriptor,
+                &image.data,
+            )
+        };
 
         let texture_view = texture.create_view(
             image
@@ -544,12 +657,12 @@ impl RenderAsset for Image {
         };
 
         Ok(GpuImage {
+            mip_level_count: texture.mip_level_count(),
             texture,
             texture_view,
             texture_format: image.texture_descriptor.format,
             sampler,
             size,
-            mip_level_count: image.texture_descriptor.mip_level_count,
         })
     }
 }
@@ -635,6 +748,37 @@ mod test {
             image.size()
         );
     }
+    #[test]
+    fn generated_mipmaps() {
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: 200,
+                height: 100,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 255],
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        assert!(!image.has_generated_mipmaps());
+        image.generate_mipmaps();
+        assert_eq!(image.texture_descriptor.mip_level_count, 8);
+        assert!(image.has_generated_mipmaps());
+
+        let mut image = Image::new_fill(
+            Extent3d {
+                width: 200,
+                height: 100,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &[0, 0, 0, 0, 0, 0, 0, 0],
+            TextureFormat::Rgba16Uint,
+        );
+        image.generate_mipmaps();
+        assert_eq!(image.texture_descriptor.mip_level_count, 1);
+    }
+
     #[test]
     fn image_default_size() {
         let image = Image::default();
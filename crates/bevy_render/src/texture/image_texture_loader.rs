@@ -2,11 +2,12 @@ use anyhow::Result;
 use bevy_asset::{AssetLoader, LoadContext, LoadedAsset};
 use bevy_ecs::prelude::{FromWorld, World};
 use bevy_utils::BoxedFuture;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use crate::{
     renderer::RenderDevice,
-    texture::{Image, ImageType, TextureError},
+    texture::{Image, ImageSampler, ImageType, TextureError},
 };
 
 use super::CompressedImageFormats;
@@ -15,6 +16,94 @@ use super::CompressedImageFormats;
 #[derive(Clone)]
 pub struct ImageTextureLoader {
     supported_compressed_formats: CompressedImageFormats,
+    /// Whether the images without mip levels get a full mip chain, see [`Image::generate_mipmaps`].
+    pub(crate) generate_mipmaps: bool,
+}
+
+/// The settings of the [`ImageTextureLoader`] for an image, read from its meta file or passed to
+/// [`AssetServer::load_with_settings`](bevy_asset::AssetServer::load_with_settings)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ImageLoaderSettings {
+    /// Whether the color of the image is in the sRGB color space, which isn't the case for
+    /// normal maps and other data textures.
+    pub is_srgb: bool,
+    /// The sampler of the image.
+    pub sampler: ImageSamplerSettings,
+    /// Whether the image gets a full mip chain if it has no mip levels, or `None` to use the
+    /// setting of the [`ImagePlugin`](super::ImagePlugin).
+    pub generate_mipmaps: Option<bool>,
+}
+
+impl Default for ImageLoaderSettings {
+    fn default() -> Self {
+        Self {
+            is_srgb: true,
+            sampler: ImageSamplerSettings::Default,
+            generate_mipmaps: None,
+        }
+    }
+}
+
+/// The [`ImageSampler`] of an image loaded with [`ImageLoaderSettings`]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImageSamplerSettings {
+    /// The default sampler of the [`ImagePlugin`](super::ImagePlugin), see [`ImageSampler::Default`].
+    #[default]
+    Default,
+    /// A sampler with linear filters, see [`ImageSampler::linear`].
+    Linear,
+    /// A sampler with nearest filters, see [`ImageSampler::nearest`].
+    Nearest,
+}
+
+impl From<ImageSamplerSettings> for ImageSampler {
+    fn from(settings: ImageSamplerSettings) -> Self {
+        match settings {
+            ImageSamplerSettings::Default => ImageSampler::Default,
+            ImageSamplerSettings::Linear => ImageSampler::linear(),
+            ImageSamplerSettings::Nearest => ImageSampler::nearest(),
+        }
+    }
+}
+
+/// The precision of the float textures loaded from HDR and EXR images
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FloatPrecision {
+    /// 32-bit floats, like [`TextureFormat::Rgba32Float`](wgpu::TextureFormat::Rgba32Float).
+    #[default]
+    Full,
+    /// 16-bit floats, like [`TextureFormat::Rgba16Float`](wgpu::TextureFormat::Rgba16Float),
+    /// halving the memory used by environment maps and lightmaps, which rarely need more.
+    Half,
+}
+
+#[cfg(any(feature = "exr", feature = "hdr"))]
+impl FloatPrecision {
+    /// The texture format of the images of `channels` float channels with this precision.
+    ///
+    /// There's no format of 3 channels, the images of 3 channels have an alpha channel added.
+    pub(crate) fn texture_format(self, channels: usize) -> wgpu::TextureFormat {
+        use wgpu::TextureFormat;
+        match (self, channels) {
+            (FloatPrecision::Full, 1) => TextureFormat::R32Float,
+            (FloatPrecision::Full, 2) => TextureFormat::Rg32Float,
+            (FloatPrecision::Full, _) => TextureFormat::Rgba32Float,
+            (FloatPrecision::Half, 1) => TextureFormat::R16Float,
+            (FloatPrecision::Half, 2) => TextureFormat::Rg16Float,
+            (FloatPrecision::Half, _) => TextureFormat::Rgba16Float,
+        }
+    }
+
+    /// Appends `value` to the texture data with this precision.
+    pub(crate) fn push(self, data: &mut Vec<u8>, value: f32) {
+        match self {
+            FloatPrecision::Full => data.extend_from_slice(&value.to_ne_bytes()),
+            FloatPrecision::Half => {
+                data.extend_from_slice(&half::f16::from_f32(value).to_ne_bytes());
+            }
+        }
+    }
 }
 
 const FILE_EXTENSIONS: &[&str] = &[
@@ -24,6 +113,8 @@ const FILE_EXTENSIONS: &[&str] = &[
     "bmp",
     #[cfg(feature = "png")]
     "png",
+    #[cfg(feature = "png")]
+    "apng",
     #[cfg(feature = "dds")]
     "dds",
     #[cfg(feature = "tga")]
@@ -45,17 +136,22 @@ impl AssetLoader for ImageTextureLoader {
         Box::pin(async move {
             // use the file extension for the image type
             let ext = load_context.path().extension().unwrap().to_str().unwrap();
+            let settings = load_context.settings::<ImageLoaderSettings>()?;
 
-            let dyn_img = Image::from_buffer(
+            let mut dyn_img = Image::from_buffer(
                 bytes,
                 ImageType::Extension(ext),
                 self.supported_compressed_formats,
-                true,
+                settings.is_srgb,
             )
             .map_err(|err| FileTextureError {
                 error: err,
                 path: format!("{}", load_context.path().display()),
             })?;
+            if settings.generate_mipmaps.unwrap_or(self.generate_mipmaps) {
+                dyn_img.generate_mipmaps();
+            }
+            dyn_img.sampler_descriptor = settings.sampler.into();
 
             load_context.set_default_asset(LoadedAsset::new(dyn_img));
             Ok(())
@@ -76,6 +172,7 @@ impl FromWorld for ImageTextureLoader {
         };
         Self {
             supported_compressed_formats,
+            generate_mipmaps: false,
         }
     }
 }
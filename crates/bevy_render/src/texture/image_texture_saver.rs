@@ -0,0 +1,28 @@
+use anyhow::Result;
+use bevy_asset::AssetSaver;
+use std::io::Cursor;
+
+use crate::texture::Image;
+
+/// Saver writing images to PNG files, like the images edited or rendered at runtime.
+///
+/// The images are converted with [`Image::try_into_dynamic`], so only its formats can be saved.
+#[derive(Clone, Default)]
+pub struct ImageTextureSaver;
+
+impl AssetSaver for ImageTextureSaver {
+    type Asset = Image;
+
+    fn save(&self, image: &Image) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        image
+            .clone()
+            .try_into_dynamic()?
+            .write_to(&mut Cursor::new(&mut bytes), image::ImageOutputFormat::Png)?;
+        Ok(bytes)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["png"]
+    }
+}
@@ -7,7 +7,7 @@ use basis_universal::{
     DecodeFlags, LowLevelUastcTranscoder, SliceParametersUastc, TranscoderBlockFormat,
 };
 use bevy_utils::default;
-#[cfg(any(feature = "flate2", feature = "ruzstd"))]
+#[cfg(any(feature = "flate2", feature = "ruzstd", feature = "basis-universal"))]
 use ktx2::SupercompressionScheme;
 use ktx2::{
     BasicDataFormatDescriptor, ChannelTypeQualifiers, ColorModel, DataFormatDescriptorHeader,
@@ -41,6 +41,35 @@ pub fn ktx2_buffer_to_image(
     let face_count = face_count.max(1);
     let depth = depth.max(1);
 
+    // BasisLZ supercompression stores the ETC1S slices, codebooks and tables of a `.basis` file,
+    // which is rebuilt to transcode them
+    #[cfg(feature = "basis-universal")]
+    if supercompression_scheme == Some(SupercompressionScheme::BasisLZ) {
+        let mut image = super::basis::basis_buffer_to_image(
+            &basislz_to_basis(&ktx2, is_srgb)?,
+            supported_compressed_formats,
+            is_srgb,
+        )?;
+        let dimension = if face_count == 6 {
+            Some(if layer_count > 1 {
+                TextureViewDimension::CubeArray
+            } else {
+                TextureViewDimension::Cube
+            })
+        } else if layer_count > 1 {
+            Some(TextureViewDimension::D2Array)
+        } else {
+            None
+        };
+        if dimension.is_some() {
+            image.texture_view_descriptor = Some(TextureViewDescriptor {
+                dimension,
+                ..default()
+            });
+        }
+        return Ok(image);
+    }
+
     // Handle supercompression
     let mut levels = Vec::new();
     if let Some(supercompression_scheme) = supercompression_scheme {
@@ -154,12 +183,9 @@ pub fn ktx2_buffer_to_image(
                 TranscodeFormat::Uastc(data_format) => {
                     let (transcode_block_format, texture_format) =
                         get_transcoded_formats(supported_compressed_formats, data_format, is_srgb);
-                    let texture_format_info = texture_format.describe();
-                    let (block_width_pixels, block_height_pixels) = (
-                        texture_format_info.block_dimensions.0 as u32,
-                        texture_format_info.block_dimensions.1 as u32,
-                    );
-                    let block_bytes = texture_format_info.block_size as u32;
+                    // NOTE: The levels are made of UASTC blocks whatever the transcoded format
+                    let (block_width_pixels, block_height_pixels) = (4, 4);
+                    let block_bytes = 16;
 
                     let transcoder = LowLevelUastcTranscoder::new();
                     for (level, level_data) in levels.iter().enumerate() {
@@ -172,6 +198,14 @@ pub fn ktx2_buffer_to_image(
                             ((level_height + block_height_pixels - 1) / block_height_pixels) .max(1),
                         );
                         let level_bytes = (num_blocks_x * num_blocks_y * block_bytes) as usize;
+                        // NOTE: basis-universal computes the output row pitch of the uncompressed
+                        // formats in blocks instead of pixels, so the width is scaled by the block
+                        // width to get the row pitch in pixels. The width is only used for it.
+                        let slice_width = if transcode_block_format.is_compressed() {
+                            level_width
+                        } else {
+                            level_width * transcode_block_format.block_width()
+                        };
 
                         let mut offset = 0;
                         for _layer in 0..layer_count {
@@ -182,7 +216,7 @@ pub fn ktx2_buffer_to_image(
                                     num_blocks_x,
                                     num_blocks_y,
                                     has_alpha: false,
-                                    original_width: level_width,
+                                    original_width: slice_width,
                                     original_height: level_height,
                                 };
                                 transcoder
@@ -308,6 +342,171 @@ pub fn ktx2_buffer_to_image(
     Ok(image)
 }
 
+/// Rebuilds the `.basis` file of the ETC1S images of a KTX2 file with `BasisLZ` supercompression.
+#[cfg(feature = "basis-universal")]
+fn basislz_to_basis(ktx2: &ktx2::Reader<&[u8]>, is_srgb: bool) -> Result<Vec<u8>, TextureError> {
+    const BASIS_HEADER_BYTES: usize = 77;
+    const SLICE_DESC_BYTES: usize = 23;
+    const IMAGE_DESC_BYTES: usize = 20;
+
+    let header = ktx2.header();
+    if header.pixel_depth > 1 {
+        return Err(TextureError::UnsupportedTextureFormat(
+            "BasisLZ supercompression of 3D textures".to_string(),
+        ));
+    }
+    let layer_count = header.layer_count.max(1) as usize;
+    let face_count = header.face_count.max(1) as usize;
+    let image_count = layer_count * face_count;
+    let levels = ktx2.levels().collect::<Vec<_>>();
+
+    let invalid =
+        || TextureError::InvalidData("Invalid BasisLZ supercompression global data".to_string());
+    let global_data = ktx2.supercompression_global_data();
+    let section = |offset: usize, bytes: usize| {
+        offset
+            .checked_add(bytes)
+            .and_then(|end| global_data.get(offset..end))
+            .ok_or_else(invalid)
+    };
+    let read = |offset: usize, bytes: usize| {
+        section(offset, bytes).map(|section| {
+            section
+                .iter()
+                .rev()
+                .fold(0, |value, byte| value << 8 | *byte as usize)
+        })
+    };
+    let endpoint_count = read(0, 2)?;
+    let selector_count = read(2, 2)?;
+    let mut offset = 20 + IMAGE_DESC_BYTES * levels.len() * image_count;
+    let mut codebooks = Vec::new();
+    for length_offset in [4, 8, 12, 16] {
+        let length = read(length_offset, 4)?;
+        codebooks.push(section(offset, length)?);
+        offset += length;
+    }
+
+    // the RGB and alpha slices of each image of each level, from the image descriptions ordered by
+    // level, layer and face
+    let mut slices = Vec::new();
+    for image_index in 0..image_count {
+        for (level_index, level_data) in levels.iter().enumerate() {
+            let desc = 20 + IMAGE_DESC_BYTES * (level_index * image_count + image_index);
+            for (slice_offset, is_alpha) in [(desc + 4, false), (desc + 12, true)] {
+                let start = read(slice_offset, 4)?;
+                let length = read(slice_offset + 4, 4)?;
+                let data = start
+                    .checked_add(length)
+                    .and_then(|end| level_data.get(start..end))
+                    .ok_or_else(invalid)?;
+                if !is_alpha || length > 0 {
+                    slices.push((image_index, level_index, is_alpha, data));
+                }
+            }
+        }
+    }
+    let has_alpha = slices.iter().any(|(_, _, is_alpha, _)| *is_alpha);
+    if has_alpha && slices.len() != 2 * image_count * levels.len() {
+        return Err(invalid());
+    }
+
+    let fields = |fields: &[(usize, usize)]| {
+        let mut bytes = Vec::new();
+        for &(value, size) in fields {
+            bytes.extend_from_slice(&value.to_le_bytes()[..size]);
+        }
+        bytes
+    };
+    let slice_descs_offset = BASIS_HEADER_BYTES;
+    let mut data_offset = slice_descs_offset + SLICE_DESC_BYTES * slices.len();
+    let mut codebook_offsets = Vec::new();
+    for codebook in &codebooks {
+        codebook_offsets.push(data_offset);
+        data_offset += codebook.len();
+    }
+    let mut data = Vec::new();
+    for &(image_index, level_index, is_alpha, slice) in &slices {
+        let (width, height) = (
+            (header.pixel_width as usize >> level_index).max(1),
+            (header.pixel_height as usize >> level_index).max(1),
+        );
+        data.append(&mut fields(&[
+            (image_index, 3),
+            (level_index, 1),
+            (is_alpha as usize, 1), // has alpha flag
+            (width, 2),
+            (height, 2),
+            ((width + 3) / 4, 2),
+            ((height + 3) / 4, 2),
+            (data_offset, 4),
+            (slice.len(), 4),
+            (crc16(slice) as usize, 2),
+        ]));
+        data_offset += slice.len();
+    }
+    for section in codebooks
+        .iter()
+        .chain(slices.iter().map(|(_, _, _, slice)| slice))
+    {
+        data.extend_from_slice(section);
+    }
+
+    let texture_type = if face_count == 6 {
+        2 // cube map array
+    } else if layer_count > 1 {
+        1 // 2D array
+    } else {
+        0 // 2D
+    };
+    let flags = 1 | if has_alpha { 4 } else { 0 } | if is_srgb { 16 } else { 0 };
+    let mut basis_header = fields(&[
+        (data.len(), 4),
+        (crc16(&data) as usize, 2),
+        (slices.len(), 3),
+        (image_count, 3),
+        (0, 1), // ETC1S texture format
+        (flags, 2),
+        (texture_type, 1),
+        (0, 3), // no frame rate
+        (0, 4), // reserved
+        (0, 4), // no user data
+        (0, 4),
+        (endpoint_count, 2),
+        (codebook_offsets[0], 4),
+        (codebooks[0].len(), 3),
+        (selector_count, 2),
+        (codebook_offsets[1], 4),
+        (codebooks[1].len(), 3),
+        (codebook_offsets[2], 4),
+        (codebooks[2].len(), 4),
+        (slice_descs_offset, 4),
+        (codebook_offsets[3], 4),
+        (codebooks[3].len(), 4),
+    ]);
+    let mut basis = fields(&[
+        (0x4273, 2), // signature
+        (0x13, 2),   // version
+        (BASIS_HEADER_BYTES, 2),
+        (crc16(&basis_header) as usize, 2),
+    ]);
+    basis.append(&mut basis_header);
+    basis.append(&mut data);
+    Ok(basis)
+}
+
+/// The CRC-16 of the `.basis` files.
+#[cfg(feature = "basis-universal")]
+fn crc16(bytes: &[u8]) -> u16 {
+    let mut crc = !0u16;
+    for byte in bytes {
+        let q = *byte as u16 ^ (crc >> 8);
+        let k = (q >> 4) ^ q;
+        crc = (crc << 8) ^ k ^ (k << 5) ^ (k << 12);
+    }
+    !crc
+}
+
 #[cfg(feature = "basis-universal")]
 pub fn get_transcoded_formats(
     supported_compressed_formats: CompressedImageFormats,
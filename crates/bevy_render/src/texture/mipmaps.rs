@@ -0,0 +1,187 @@
+use crate::{
+    render_resource::*,
+    renderer::{RenderDevice, RenderQueue},
+    texture::{Image, TextureFormatPixelInfo},
+};
+use bevy_asset::HandleUntyped;
+use bevy_ecs::prelude::*;
+use bevy_reflect::TypeUuid;
+use std::num::NonZeroU32;
+use wgpu::TextureFormatFeatureFlags;
+
+pub const MIPMAP_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 7480213426743577834);
+
+/// Renders the mip levels of the [`Image`]s whose data only holds their first mip level, each
+/// one downsampled from the previous one.
+///
+/// See [`Image::generate_mipmaps`].
+#[derive(Resource)]
+pub struct MipmapPipeline {
+    layout: BindGroupLayout,
+    sampler: Sampler,
+}
+
+impl FromWorld for MipmapPipeline {
+    fn from_world(render_world: &mut World) -> Self {
+        let render_device = render_world.resource::<RenderDevice>();
+
+        let layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            label: Some("mipmap_bind_group_layout"),
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                        multisampled: false,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+        });
+        let sampler = render_device.create_sampler(&SamplerDescriptor {
+            label: Some("mipmap_sampler"),
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            ..Default::default()
+        });
+
+        MipmapPipeline { layout, sampler }
+    }
+}
+
+impl SpecializedRenderPipeline for MipmapPipeline {
+    type Key = TextureFormat;
+
+    fn specialize(&self, format: Self::Key) -> RenderPipelineDescriptor {
+        RenderPipelineDescriptor {
+            label: Some("mipmap_pipeline".into()),
+            layout: vec![self.layout.clone()],
+            vertex: VertexState {
+                shader: MIPMAP_SHADER_HANDLE.typed(),
+                shader_defs: vec![],
+                entry_point: "vertex".into(),
+                buffers: vec![],
+            },
+            fragment: Some(FragmentState {
+                shader: MIPMAP_SHADER_HANDLE.typed(),
+                shader_defs: vec![],
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format,
+                    blend: None,
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            primitive: PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: MultisampleState::default(),
+            push_constant_ranges: Vec::new(),
+        }
+    }
+}
+
+impl MipmapPipeline {
+    /// Creates the texture of `image` from its first mip level, then renders its other mip
+    /// levels with `pipeline`, specialized for the format of the image.
+    pub(crate) fn create_texture(
+        &self,
+        image: &Image,
+        pipeline: &RenderPipeline,
+        render_device: &RenderDevice,
+        render_queue: &RenderQueue,
+    ) -> Texture {
+        let mut descriptor = image.texture_descriptor.clone();
+        descriptor.usage |= TextureUsages::RENDER_ATTACHMENT | TextureUsages::COPY_DST;
+        let texture = render_device.create_texture(&descriptor);
+
+        let size = descriptor.size;
+        render_queue.write_texture(
+            texture.as_image_copy(),
+            &image.data,
+            ImageDataLayout {
+                offset: 0,
+                bytes_per_row: NonZeroU32::new(size.width * descriptor.format.pixel_size() as u32),
+                rows_per_image: NonZeroU32::new(size.height),
+            },
+            size,
+        );
+
+        let mut command_encoder = render_device.create_command_encoder(&CommandEncoderDescriptor {
+            label: Some("mipmap_command_encoder"),
+        });
+        for layer in 0..descriptor.array_layer_count() {
+            let mip_view = |mip_level| {
+                texture.create_view(&TextureViewDescriptor {
+                    label: Some("mipmap_texture_view"),
+                    dimension: Some(TextureViewDimension::D2),
+                    base_mip_level: mip_level,
+                    mip_level_count: NonZeroU32::new(1),
+                    base_array_layer: layer,
+                    array_layer_count: NonZeroU32::new(1),
+                    ..Default::default()
+                })
+            };
+
+            let mut source = mip_view(0);
+            for mip_level in 1..descriptor.mip_level_count {
+                let destination = mip_view(mip_level);
+                let bind_group = render_device.create_bind_group(&BindGroupDescriptor {
+                    label: Some("mipmap_bind_group"),
+                    layout: &self.layout,
+                    entries: &[
+                        BindGroupEntry {
+                            binding: 0,
+                            resource: BindingResource::TextureView(&source),
+                        },
+                        BindGroupEntry {
+                            binding: 1,
+                            resource: BindingResource::Sampler(&self.sampler),
+                        },
+                    ],
+                });
+
+                let mut render_pass = command_encoder.begin_render_pass(&RenderPassDescriptor {
+                    label: Some("mipmap_pass"),
+                    color_attachments: &[Some(RenderPassColorAttachment {
+                        view: &destination,
+                        resolve_target: None,
+                        ops: Operations::default(),
+                    })],
+                    depth_stencil_attachment: None,
+                });
+                render_pass.set_pipeline(pipeline);
+                render_pass.set_bind_group(0, &bind_group, &[]);
+                render_pass.draw(0..3, 0..1);
+                drop(render_pass);
+
+                source = destination;
+            }
+        }
+        render_queue.submit([command_encoder.finish()]);
+
+        texture
+    }
+}
+
+/// Whether the mip levels of textures with this format can be generated on the GPU, which
+/// requires rendering to and filtering them.
+pub fn supports_mipmap_generation(format: TextureFormat) -> bool {
+    let format_info = format.describe();
+    let features = format_info.guaranteed_format_features;
+    format_info.block_dimensions == (1, 1)
+        && features
+            .allowed_usages
+            .contains(TextureUsages::RENDER_ATTACHMENT)
+        && features
+            .flags
+            .contains(TextureFormatFeatureFlags::FILTERABLE)
+}
@@ -1,21 +1,31 @@
 #[cfg(feature = "basis-universal")]
 mod basis;
+#[cfg(feature = "basis-universal")]
+mod compressed_image_saver;
 #[cfg(feature = "dds")]
 mod dds;
 #[cfg(feature = "exr")]
 mod exr_texture_loader;
 mod fallback_image;
+mod flipbook;
 #[cfg(feature = "hdr")]
 mod hdr_texture_loader;
 #[allow(clippy::module_inception)]
 mod image;
 mod image_texture_loader;
+#[cfg(feature = "png")]
+mod image_texture_saver;
 #[cfg(feature = "ktx2")]
 mod ktx2;
+mod mipmaps;
+mod readback;
+mod streaming;
 mod texture_cache;
 
 pub(crate) mod image_texture_conversion;
 
+#[cfg(feature = "basis-universal")]
+pub use self::compressed_image_saver::*;
 pub use self::image::*;
 #[cfg(feature = "ktx2")]
 pub use self::ktx2::*;
@@ -27,16 +37,23 @@ pub use exr_texture_loader::*;
 pub use hdr_texture_loader::*;
 
 pub use fallback_image::*;
+pub use flipbook::*;
 pub use image_texture_loader::*;
+#[cfg(feature = "png")]
+pub use image_texture_saver::*;
+pub use mipmaps::*;
+pub use readback::*;
+pub use streaming::*;
 pub use texture_cache::*;
 
 use crate::{
     render_asset::{PrepareAssetSet, RenderAssetPlugin},
+    render_resource::{Shader, SpecializedRenderPipelines},
     renderer::RenderDevice,
     RenderApp, RenderSet,
 };
 use bevy_app::{App, Plugin};
-use bevy_asset::{AddAsset, Assets};
+use bevy_asset::{load_internal_asset, AddAsset, Assets};
 use bevy_ecs::prelude::*;
 
 // TODO: replace Texture names with Image names?
@@ -44,6 +61,10 @@ use bevy_ecs::prelude::*;
 pub struct ImagePlugin {
     /// The default image sampler to use when [`ImageSampler`] is set to `Default`.
     pub default_sampler: wgpu::SamplerDescriptor<'static>,
+    /// Whether the loaded images without mip levels get a full mip chain generated on the GPU.
+    ///
+    /// This can be set for each image instead with [`Image::generate_mipmaps`].
+    pub generate_mipmaps: bool,
 }
 
 impl Default for ImagePlugin {
@@ -57,6 +78,7 @@ impl ImagePlugin {
     pub fn default_linear() -> ImagePlugin {
         ImagePlugin {
             default_sampler: ImageSampler::linear_descriptor(),
+            generate_mipmaps: false,
         }
     }
 
@@ -64,12 +86,21 @@ impl ImagePlugin {
     pub fn default_nearest() -> ImagePlugin {
         ImagePlugin {
             default_sampler: ImageSampler::nearest_descriptor(),
+            generate_mipmaps: false,
         }
     }
 }
 
 impl Plugin for ImagePlugin {
     fn build(&self, app: &mut App) {
+        load_internal_asset!(app, MIPMAP_SHADER_HANDLE, "mipmaps.wgsl", Shader::from_wgsl);
+        load_internal_asset!(
+            app,
+            FLIPBOOK_SHADER_HANDLE,
+            "flipbook.wgsl",
+            Shader::from_wgsl
+        );
+
         #[cfg(any(
             feature = "png",
             feature = "dds",
@@ -80,7 +111,20 @@ impl Plugin for ImagePlugin {
             feature = "ktx2",
         ))]
         {
-            app.init_asset_loader::<ImageTextureLoader>();
+            let mut loader = ImageTextureLoader::from_world(&mut app.world);
+            loader.generate_mipmaps = self.generate_mipmaps;
+            app.add_asset_loader(loader);
+        }
+
+        #[cfg(feature = "png")]
+        {
+            app.add_asset_saver(ImageTextureSaver);
+        }
+
+        #[cfg(feature = "basis-universal")]
+        {
+            app.add_asset_saver(CompressedImageSaver::uastc())
+                .add_asset_saver(CompressedImageSaver::etc1s());
         }
 
         #[cfg(feature = "exr")]
@@ -96,8 +140,12 @@ impl Plugin for ImagePlugin {
         app.add_plugin(RenderAssetPlugin::<Image>::with_prepare_asset_set(
             PrepareAssetSet::PreAssetPrepare,
         ))
+        .add_plugin(ImageReadbackPlugin)
         .register_type::<Image>()
+        .register_type::<Flipbook>()
+        .register_type::<FlipbookMode>()
         .add_asset::<Image>()
+        .set_asset_size_estimator::<Image>(|image| image.data.len())
         .register_asset_reflect::<Image>();
         app.world
             .resource_mut::<Assets<Image>>()
@@ -110,6 +158,8 @@ impl Plugin for ImagePlugin {
             };
             render_app
                 .insert_resource(DefaultImageSampler(default_sampler))
+                .init_resource::<MipmapPipeline>()
+                .init_resource::<SpecializedRenderPipelines<MipmapPipeline>>()
                 .init_resource::<TextureCache>()
                 .init_resource::<FallbackImage>()
                 .init_resource::<FallbackImageCubemap>()
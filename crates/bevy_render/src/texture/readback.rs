@@ -0,0 +1,297 @@
+use crate::{
+    main_graph,
+    render_asset::{PrepareAssetSet, RenderAssets},
+    render_graph::{Node, NodeRunError, RenderGraph, RenderGraphContext},
+    render_resource::{Buffer, BufferDescriptor, BufferUsages, MapMode, Texture},
+    renderer::{RenderContext, RenderDevice},
+    texture::Image,
+    Extract, ExtractSchedule, RenderApp, RenderSet,
+};
+use bevy_app::prelude::*;
+use bevy_asset::{Handle, HandleId};
+use bevy_ecs::prelude::*;
+use bevy_log::warn;
+use bevy_utils::{HashMap, HashSet};
+use std::{
+    num::NonZeroU32,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use wgpu::{
+    Extent3d, ImageCopyBuffer, ImageDataLayout, TextureDimension, TextureFormat, TextureSampleType,
+    TextureUsages, COPY_BYTES_PER_ROW_ALIGNMENT,
+};
+
+/// Adds support for reading the contents of [`Image`]s back from the GPU with
+/// [`ImageReadbacks`], for example to save what a camera rendered to an image.
+///
+/// This plugin is added by the [`ImagePlugin`](super::ImagePlugin).
+#[derive(Default)]
+pub struct ImageReadbackPlugin;
+
+impl Plugin for ImageReadbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ImageReadbacks>();
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            let (sender, receiver) = async_channel::unbounded();
+            render_app
+                .insert_resource(ImageReadbackSender(sender))
+                .init_resource::<ExtractedImageReadbacks>()
+                .init_resource::<ImageReadbackBuffers>()
+                .add_system(extract_image_readbacks.in_schedule(ExtractSchedule))
+                .add_system(
+                    prepare_image_readbacks
+                        .in_set(RenderSet::Prepare)
+                        .after(PrepareAssetSet::PostAssetPrepare),
+                )
+                .add_system(map_image_readbacks.in_set(RenderSet::Cleanup));
+
+            let mut graph = render_app.world.resource_mut::<RenderGraph>();
+            graph.add_node(ImageReadbackNode::NAME, ImageReadbackNode);
+            graph.add_node_edge(main_graph::node::CAMERA_DRIVER, ImageReadbackNode::NAME);
+
+            app.insert_resource(ImageReadbackReceiver(receiver))
+                .add_system(receive_image_readbacks.in_base_set(CoreSet::PreUpdate));
+        }
+    }
+}
+
+/// The contents of the [`Image`]s read back from the GPU.
+///
+/// Images are read back every frame once requested with [`read_back`](Self::read_back), after
+/// all cameras were drawn. This waits for the GPU to finish the frame, so only request the images
+/// whose contents are needed on the CPU.
+///
+/// The images must have the [`TextureUsages::COPY_SRC`] usage, and an uncompressed format.
+#[derive(Resource, Debug, Default)]
+pub struct ImageReadbacks {
+    requested: HashSet<HandleId>,
+    images: HashMap<HandleId, Image>,
+}
+
+impl ImageReadbacks {
+    /// Starts reading `image` back every frame.
+    pub fn read_back(&mut self, image: &Handle<Image>) {
+        self.requested.insert(image.id());
+    }
+
+    /// Stops reading `image` back, and forgets its last contents.
+    pub fn stop(&mut self, image: &Handle<Image>) {
+        self.requested.remove(&image.id());
+        self.images.remove(&image.id());
+    }
+
+    /// The last contents of `image` read back from the GPU, holding its first mip level.
+    ///
+    /// Returns `None` if the image hasn't been read back yet.
+    pub fn get(&self, image: &Handle<Image>) -> Option<&Image> {
+        self.images.get(&image.id())
+    }
+}
+
+/// The contents of a texture read back from the GPU, without the padding of its rows.
+struct ImageReadback {
+    id: HandleId,
+    size: Extent3d,
+    dimension: TextureDimension,
+    format: TextureFormat,
+    data: Vec<u8>,
+}
+
+#[derive(Resource)]
+struct ImageReadbackSender(async_channel::Sender<ImageReadback>);
+
+#[derive(Resource)]
+struct ImageReadbackReceiver(async_channel::Receiver<ImageReadback>);
+
+fn receive_image_readbacks(
+    receiver: Res<ImageReadbackReceiver>,
+    mut readbacks: ResMut<ImageReadbacks>,
+) {
+    while let Ok(readback) = receiver.0.try_recv() {
+        // Drop the readbacks that were in flight when they were stopped
+        if readbacks.requested.contains(&readback.id) {
+            let image = Image::new(
+                readback.size,
+                readback.dimension,
+                readback.data,
+                readback.format,
+            );
+            readbacks.images.insert(readback.id, image);
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct ExtractedImageReadbacks(Vec<HandleId>);
+
+fn extract_image_readbacks(
+    mut extracted: ResMut<ExtractedImageReadbacks>,
+    readbacks: Extract<Res<ImageReadbacks>>,
+) {
+    extracted.0.clear();
+    extracted.0.extend(readbacks.requested.iter().copied());
+}
+
+/// The buffer a texture is copied into to be read back.
+struct ImageReadbackBuffer {
+    /// The texture copied into the buffer, to recreate it when the [`Image`] changes.
+    texture: Texture,
+    /// `None` if the texture can't be read back.
+    buffer: Option<Buffer>,
+    /// The size of the rows of the texture in the buffer, aligned to
+    /// [`COPY_BYTES_PER_ROW_ALIGNMENT`].
+    padded_bytes_per_row: u32,
+    bytes_per_row: u32,
+}
+
+#[derive(Resource, Default)]
+struct ImageReadbackBuffers(HashMap<HandleId, ImageReadbackBuffer>);
+
+fn prepare_image_readbacks(
+    requested: Res<ExtractedImageReadbacks>,
+    images: Res<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    mut buffers: ResMut<ImageReadbackBuffers>,
+) {
+    buffers.0.retain(|id, _| requested.0.contains(id));
+    for id in &requested.0 {
+        let gpu_image = match images.get(&Handle::weak(*id)) {
+            Some(gpu_image) => gpu_image,
+            None => continue,
+        };
+        if let Some(buffer) = buffers.0.get(id) {
+            if buffer.texture.id() == gpu_image.texture.id() {
+                continue;
+            }
+        }
+
+        let texture = &gpu_image.texture;
+        let format_info = texture.format().describe();
+        let bytes_per_row = texture.width() * format_info.block_size as u32;
+        let padded_bytes_per_row = (bytes_per_row + COPY_BYTES_PER_ROW_ALIGNMENT - 1)
+            / COPY_BYTES_PER_ROW_ALIGNMENT
+            * COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = if !texture.usage().contains(TextureUsages::COPY_SRC) {
+            warn!(
+                "Image {id:?} can't be read back, as its texture doesn't have the COPY_SRC usage"
+            );
+            None
+        } else if format_info.block_dimensions != (1, 1)
+            || format_info.sample_type == TextureSampleType::Depth
+        {
+            warn!(
+                "Image {id:?} can't be read back, as its texture has a compressed or depth format"
+            );
+            None
+        } else {
+            let size = texture.size();
+            Some(render_device.create_buffer(&BufferDescriptor {
+                label: Some("image_readback_buffer"),
+                size: padded_bytes_per_row as u64
+                    * size.height as u64
+                    * size.depth_or_array_layers as u64,
+                usage: BufferUsages::COPY_DST | BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }))
+        };
+
+        buffers.0.insert(
+            *id,
+            ImageReadbackBuffer {
+                texture: texture.clone(),
+                buffer,
+                padded_bytes_per_row,
+                bytes_per_row,
+            },
+        );
+    }
+}
+
+/// Maps the readback buffers filled by the [`ImageReadbackNode`] and sends their contents to the
+/// main world.
+fn map_image_readbacks(
+    buffers: Res<ImageReadbackBuffers>,
+    render_device: Res<RenderDevice>,
+    sender: Res<ImageReadbackSender>,
+) {
+    let mut pending = Vec::new();
+    for (id, readback_buffer) in &buffers.0 {
+        if let Some(buffer) = &readback_buffer.buffer {
+            let mapped = Arc::new(AtomicBool::new(false));
+            let mapped_clone = mapped.clone();
+            buffer.slice(..).map_async(MapMode::Read, move |result| {
+                mapped_clone.store(result.is_ok(), Ordering::Release);
+            });
+            pending.push((*id, readback_buffer, buffer, mapped));
+        }
+    }
+    if pending.is_empty() {
+        return;
+    }
+
+    render_device.poll(wgpu::Maintain::Wait);
+    for (id, readback_buffer, buffer, mapped) in pending {
+        if !mapped.load(Ordering::Acquire) {
+            continue;
+        }
+        let data = buffer
+            .slice(..)
+            .get_mapped_range()
+            .chunks_exact(readback_buffer.padded_bytes_per_row as usize)
+            .flat_map(|row| &row[..readback_buffer.bytes_per_row as usize])
+            .copied()
+            .collect();
+        buffer.unmap();
+
+        let texture = &readback_buffer.texture;
+        // The receiver only goes away with the main world
+        let _ = sender.0.try_send(ImageReadback {
+            id,
+            size: texture.size(),
+            dimension: texture.dimension(),
+            format: texture.format(),
+            data,
+        });
+    }
+}
+
+/// A render graph node copying the [`Image`]s requested in the [`ImageReadbacks`] to their
+/// readback buffers, after all cameras were drawn.
+pub struct ImageReadbackNode;
+
+impl ImageReadbackNode {
+    pub const NAME: &str = "image_readback";
+}
+
+impl Node for ImageReadbackNode {
+    fn run(
+        &self,
+        _graph: &mut RenderGraphContext,
+        render_context: &mut RenderContext,
+        world: &World,
+    ) -> Result<(), NodeRunError> {
+        let buffers = world.resource::<ImageReadbackBuffers>();
+        for readback_buffer in buffers.0.values() {
+            if let Some(buffer) = &readback_buffer.buffer {
+                let texture = &readback_buffer.texture;
+                render_context.command_encoder().copy_texture_to_buffer(
+                    texture.as_image_copy(),
+                    ImageCopyBuffer {
+                        buffer,
+                        layout: ImageDataLayout {
+                            offset: 0,
+                            bytes_per_row: NonZeroU32::new(readback_buffer.padded_bytes_per_row),
+                            rows_per_image: NonZeroU32::new(texture.height()),
+                        },
+                    },
+                    texture.size(),
+                );
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,506 @@
+use crate::{
+    render_asset::{prepare_assets, ExtractedAssets, PrepareAssetSet, RenderAssets},
+    render_resource::Sampler,
+    renderer::{RenderDevice, RenderQueue},
+    texture::{DefaultImageSampler, GpuImage, Image, ImageSampler},
+    ExtractSchedule, MainWorld, RenderApp,
+};
+use bevy_app::{App, IntoSystemAppConfig, Plugin};
+use bevy_asset::{Handle, HandleId};
+use bevy_ecs::prelude::*;
+use bevy_math::Vec2;
+use bevy_utils::HashMap;
+use std::{borrow::Cow, cmp::Reverse};
+use wgpu::{TextureDescriptor, TextureDimension, TextureUsages, TextureViewDescriptor};
+
+/// Streams the mip levels of large textures in and out of GPU memory.
+///
+/// Only the low resolution mips of 2d [`Image`]s with a mip chain are uploaded when they are
+/// loaded. Their more detailed mips are streamed in when they are requested through
+/// [`TextureStreaming`], and streamed back out once they aren't requested anymore and the
+/// [`TextureStreamingBudget`] is exceeded.
+///
+/// `bevy_pbr` requests the textures of visible `StandardMaterial`s based on their screen
+/// coverage. Textures used in any other way must be requested manually, or they stay at their
+/// initial resolution.
+#[derive(Default)]
+pub struct TextureStreamingPlugin;
+
+impl Plugin for TextureStreamingPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TextureStreamingBudget>()
+            .init_resource::<TextureStreaming>()
+            .add_event::<TextureResidencyChanged>();
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<StreamedTextures>()
+                .add_system(extract_texture_streaming.in_schedule(ExtractSchedule))
+                .add_system(
+                    stream_textures
+                        .in_set(PrepareAssetSet::PreAssetPrepare)
+                        .before(prepare_assets::<Image>),
+                );
+        }
+    }
+}
+
+/// Limits the GPU memory used by the textures of the [`TextureStreamingPlugin`].
+#[derive(Resource, Debug, Clone)]
+pub struct TextureStreamingBudget {
+    /// The GPU memory streamed textures may use, in bytes.
+    ///
+    /// When the requested mips don't fit, all requests are lowered by the same number of mip
+    /// levels until they do. The initial mips of the textures are always resident, even if they
+    /// exceed the budget on their own.
+    pub max_bytes: u64,
+    /// The largest width or height, in texels, of the mips uploaded when a texture is loaded.
+    pub initial_size: u32,
+    /// The maximum number of textures whose resident mips change each frame, spreading the
+    /// uploads over several frames.
+    pub max_updates_per_frame: usize,
+}
+
+impl Default for TextureStreamingBudget {
+    fn default() -> Self {
+        Self {
+            max_bytes: 512 * 1024 * 1024,
+            initial_size: 128,
+            max_updates_per_frame: 16,
+        }
+    }
+}
+
+/// The mip levels requested for the streamed textures, and the ones resident on the GPU.
+#[derive(Resource, Debug, Default)]
+pub struct TextureStreaming {
+    requests: HashMap<HandleId, f32>,
+    residency: HashMap<HandleId, TextureResidency>,
+    used_bytes: u64,
+}
+
+impl TextureStreaming {
+    /// Requests enough detail for `image` to cover `pixels` pixels on screen along its largest
+    /// dimension.
+    ///
+    /// Requests only last for the current frame, and the largest one made for an image wins.
+    /// Requests for images that aren't streamed are ignored.
+    pub fn request(&mut self, image: &Handle<Image>, pixels: f32) {
+        let request = self.requests.entry(image.id()).or_insert(0.0);
+        *request = request.max(pixels);
+    }
+
+    /// Requests all the mip levels of `image` for the current frame.
+    pub fn request_full_resolution(&mut self, image: &Handle<Image>) {
+        self.request(image, f32::INFINITY);
+    }
+
+    /// The mip levels of `image` resident on the GPU, or `None` if it isn't streamed.
+    pub fn residency(&self, image: &Handle<Image>) -> Option<TextureResidency> {
+        self.residency.get(&image.id()).copied()
+    }
+
+    /// The GPU memory used by the streamed textures, in bytes.
+    pub fn used_bytes(&self) -> u64 {
+        self.used_bytes
+    }
+}
+
+/// The mip levels of a streamed texture resident on the GPU.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureResidency {
+    /// The most detailed mip level of the [`Image`] on the GPU.
+    pub first_mip_level: u32,
+    /// The number of mip levels of the [`Image`], resident or not.
+    pub mip_level_count: u32,
+}
+
+/// Sent when the resident mip levels of a streamed texture changed.
+///
+/// The [`GpuImage`] of the texture is replaced when this happens, so bind groups using it must be
+/// recreated to sample the new mips.
+#[derive(Debug, Clone)]
+pub struct TextureResidencyChanged {
+    pub image: Handle<Image>,
+}
+
+/// The streamed textures, kept in the render world along with their full mip chain.
+#[derive(Resource, Default)]
+struct StreamedTextures {
+    textures: HashMap<Handle<Image>, StreamedTexture>,
+    requests: HashMap<HandleId, f32>,
+    budget: TextureStreamingBudget,
+    used_bytes: u64,
+    /// The residency changes not sent to the main world yet.
+    changed: Vec<(HandleId, Option<TextureResidency>)>,
+}
+
+struct StreamedTexture {
+    image: Image,
+    /// The most detailed mip level on the GPU.
+    first_mip_level: u32,
+    /// The mip level the texture starts at, and falls back to when it isn't requested.
+    initial_mip_level: u32,
+}
+
+impl StreamedTexture {
+    /// The most detailed mip level needed to cover `pixels` pixels on screen.
+    fn requested_mip_level(&self, pixels: f32) -> u32 {
+        let size = self.image.texture_descriptor.size;
+        let texels = size.width.max(size.height) as f32;
+        let mip_level = (texels / pixels).log2().floor().max(0.0) as u32;
+        mip_level.min(self.initial_mip_level)
+    }
+
+    fn residency(&self) -> TextureResidency {
+        TextureResidency {
+            first_mip_level: self.first_mip_level,
+            mip_level_count: self.image.texture_descriptor.mip_level_count,
+        }
+    }
+}
+
+/// Sends the budget and requests to the render world, and the residency changes back to the main
+/// world.
+fn extract_texture_streaming(
+    mut streamed: ResMut<StreamedTextures>,
+    mut main_world: ResMut<MainWorld>,
+) {
+    streamed.budget = main_world.resource::<TextureStreamingBudget>().clone();
+    let changed = std::mem::take(&mut streamed.changed);
+
+    let mut streaming = main_world.resource_mut::<TextureStreaming>();
+    streamed.requests = std::mem::take(&mut streaming.requests);
+    streaming.used_bytes = streamed.used_bytes;
+    for (id, residency) in &changed {
+        match residency {
+            Some(residency) => {
+                streaming.residency.insert(*id, *residency);
+            }
+            None => {
+                streaming.residency.remove(id);
+            }
+        }
+    }
+
+    main_world.send_event_batch(changed.into_iter().filter_map(|(id, residency)| {
+        residency.map(|_| TextureResidencyChanged {
+            image: Handle::weak(id),
+        })
+    }));
+}
+
+/// Uploads the initial mips of the newly extracted streamable images, then streams mips in and
+/// out of the streamed textures to match the requests within the budget.
+fn stream_textures(
+    mut streamed: ResMut<StreamedTextures>,
+    mut extracted_images: ResMut<ExtractedAssets<Image>>,
+    mut render_images: ResMut<RenderAssets<Image>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    default_sampler: Res<DefaultImageSampler>,
+) {
+    let streamed = &mut *streamed;
+    for removed in extracted_images.removed() {
+        if streamed.textures.remove(removed).is_some() {
+            streamed.changed.push((removed.id(), None));
+        }
+    }
+
+    let initial_size = streamed.budget.initial_size;
+    let new_textures = extracted_images.take_extracted(|handle, image| {
+        let streamable = is_streamable(image) && initial_mip_level(image, initial_size) > 0;
+        // Images that were modified to not be streamable anymore are prepared as usual
+        if !streamable && streamed.textures.remove(handle).is_some() {
+            streamed.changed.push((handle.id(), None));
+        }
+        streamable
+    });
+    for (handle, image) in new_textures {
+        let sampler = match &image.sampler_descriptor {
+            ImageSampler::Default => (**default_sampler).clone(),
+            ImageSampler::Descriptor(descriptor) => render_device.create_sampler(descriptor),
+        };
+        let texture = StreamedTexture {
+            first_mip_level: initial_mip_level(&image, initial_size),
+            initial_mip_level: initial_mip_level(&image, initial_size),
+            image,
+        };
+        let gpu_image = create_gpu_image(
+            &texture.image,
+            texture.first_mip_level,
+            sampler,
+            &render_device,
+            &render_queue,
+        );
+        render_images.insert(handle.clone_weak(), gpu_image);
+        streamed
+            .changed
+            .push((handle.id(), Some(texture.residency())));
+        streamed.textures.insert(handle, texture);
+    }
+
+    // The most detailed mip level each texture was requested at, if it was requested
+    let requested: Vec<_> = streamed
+        .textures
+        .iter()
+        .map(|(handle, texture)| {
+            let requested = streamed
+                .requests
+                .get(&handle.id())
+                .map(|pixels| texture.requested_mip_level(*pixels));
+            (handle, texture, requested)
+        })
+        .collect();
+
+    // Lower the detail of all requests until they fit, counting the textures that weren't
+    // requested at their initial mips as they can be streamed out
+    let requested_bytes = |bias: u32| -> u64 {
+        requested
+            .iter()
+            .map(|(_, texture, requested)| {
+                let mip_level = requested.map_or(texture.initial_mip_level, |mip_level| {
+                    (mip_level + bias).min(texture.initial_mip_level)
+                });
+                resident_bytes(&texture.image.texture_descriptor, mip_level)
+            })
+            .sum()
+    };
+    let max_bias = requested
+        .iter()
+        .map(|(_, texture, _)| texture.initial_mip_level)
+        .max()
+        .unwrap_or(0);
+    let mut bias = 0;
+    while bias < max_bias && requested_bytes(bias) > streamed.budget.max_bytes {
+        bias += 1;
+    }
+
+    // Textures that weren't requested keep their mips, unless they are needed to fit the budget
+    let mut targets: Vec<_> = requested
+        .iter()
+        .map(|(handle, texture, requested)| {
+            let target = requested.map_or(texture.first_mip_level, |mip_level| {
+                (mip_level + bias).min(texture.initial_mip_level)
+            });
+            (*handle, texture, *requested, target)
+        })
+        .collect();
+    let target_bytes: u64 = targets
+        .iter()
+        .map(|(_, texture, _, target)| resident_bytes(&texture.image.texture_descriptor, *target))
+        .sum();
+    if target_bytes > streamed.budget.max_bytes {
+        for (_, texture, requested, target) in &mut targets {
+            if requested.is_none() {
+                *target = texture.initial_mip_level;
+            }
+        }
+    }
+
+    // Stream out first to free memory, then stream in the least detailed mips first as they are
+    // the cheapest
+    let mut updates: Vec<_> = targets
+        .into_iter()
+        .filter(|(_, texture, _, target)| *target != texture.first_mip_level)
+        .map(|(handle, texture, _, target)| {
+            let streams_in = target < texture.first_mip_level;
+            (streams_in, Reverse(target), handle.clone_weak())
+        })
+        .collect();
+    updates.sort_unstable_by_key(|(streams_in, target, _)| (*streams_in, *target));
+    updates.truncate(streamed.budget.max_updates_per_frame);
+
+    for (_, Reverse(target), handle) in updates {
+        let texture = streamed.textures.get_mut(&handle).unwrap();
+        let sampler = match render_images.get(&handle) {
+            Some(gpu_image) => gpu_image.sampler.clone(),
+            None => continue,
+        };
+        texture.first_mip_level = target;
+        let gpu_image = create_gpu_image(
+            &texture.image,
+            texture.first_mip_level,
+            sampler,
+            &render_device,
+            &render_queue,
+        );
+        render_images.insert(handle.clone_weak(), gpu_image);
+        streamed
+            .changed
+            .push((handle.id(), Some(texture.residency())));
+    }
+
+    streamed.used_bytes = streamed
+        .textures
+        .values()
+        .map(|texture| resident_bytes(&texture.image.texture_descriptor, texture.first_mip_level))
+        .sum();
+}
+
+/// Whether the mip levels of `image` can be streamed.
+fn is_streamable(image: &Image) -> bool {
+    let descriptor = &image.texture_descriptor;
+    descriptor.dimension == TextureDimension::D2
+        && descriptor.mip_level_count > 1
+        && !descriptor
+            .usage
+            .intersects(TextureUsages::RENDER_ATTACHMENT | TextureUsages::STORAGE_BINDING)
+        // A view selecting specific mips would break once the mips are streamed
+        && image
+            .texture_view_descriptor
+            .as_ref()
+            .map_or(true, |view| {
+                view.base_mip_level == 0 && view.mip_level_count.is_none()
+            })
+        && image.data.len() as u64 == resident_bytes(descriptor, 0)
+}
+
+/// The most detailed mip level of `image` that is at most `initial_size` texels wide and high.
+///
+/// The most detailed resident mip level of a compressed texture must be a whole number of blocks,
+/// so a more detailed level is used when needed.
+fn initial_mip_level(image: &Image, initial_size: u32) -> u32 {
+    let descriptor = &image.texture_descriptor;
+    let (block_width, block_height) = descriptor.format.describe().block_dimensions;
+    let mut mip_level = 0;
+    while mip_level + 1 < descriptor.mip_level_count {
+        let size = descriptor.mip_level_size(mip_level).unwrap();
+        let next_size = descriptor.mip_level_size(mip_level + 1).unwrap();
+        if size.width.max(size.height) <= initial_size
+            || next_size.width % block_width as u32 != 0
+            || next_size.height % block_height as u32 != 0
+        {
+            break;
+        }
+        mip_level += 1;
+    }
+    mip_level
+}
+
+/// The size of a mip level of a texture, in bytes, across all its array layers.
+fn mip_level_bytes(descriptor: &TextureDescriptor, mip_level: u32) -> u64 {
+    let format_info = descriptor.format.describe();
+    let (block_width, block_height) = format_info.block_dimensions;
+    let size = descriptor
+        .mip_level_size(mip_level)
+        .unwrap()
+        .physical_size(descriptor.format);
+    (size.width / block_width as u32) as u64
+        * (size.height / block_height as u32) as u64
+        * format_info.block_size as u64
+        * size.depth_or_array_layers as u64
+}
+
+/// The size of the mip levels of a texture starting at `first_mip_level`, in bytes.
+fn resident_bytes(descriptor: &TextureDescriptor, first_mip_level: u32) -> u64 {
+    (first_mip_level..descriptor.mip_level_count)
+        .map(|mip_level| mip_level_bytes(descriptor, mip_level))
+        .sum()
+}
+
+/// The data of the mip levels of `image` starting at `first_mip_level`.
+///
+/// The data of images is laid out layer by layer, each layer holding all its mip levels.
+fn mip_levels_data(image: &Image, first_mip_level: u32) -> Cow<'_, [u8]> {
+    let descriptor = &image.texture_descriptor;
+    let layer_count = descriptor.array_layer_count() as usize;
+    let layer_bytes = resident_bytes(descriptor, 0) as usize / layer_count;
+    let skipped_bytes =
+        layer_bytes - resident_bytes(descriptor, first_mip_level) as usize / layer_count;
+    if layer_count == 1 {
+        Cow::Borrowed(&image.data[skipped_bytes..])
+    } else {
+        Cow::Owned(
+            image
+                .data
+                .chunks_exact(layer_bytes)
+                .flat_map(|layer| &layer[skipped_bytes..])
+                .copied()
+                .collect(),
+        )
+    }
+}
+
+/// Creates the [`GpuImage`] of `image` holding the mip levels starting at `first_mip_level`.
+fn create_gpu_image(
+    image: &Image,
+    first_mip_level: u32,
+    sampler: Sampler,
+    render_device: &RenderDevice,
+    render_queue: &RenderQueue,
+) -> GpuImage {
+    let mut descriptor = image.texture_descriptor.clone();
+    descriptor.size = descriptor.mip_level_size(first_mip_level).unwrap();
+    descriptor.mip_level_count -= first_mip_level;
+    let texture = render_device.create_texture_with_data(
+        render_queue,
+        &descriptor,
+        &mip_levels_data(image, first_mip_level),
+    );
+
+    let texture_view = texture.create_view(
+        image
+            .texture_view_descriptor
+            .as_ref()
+            .unwrap_or(&TextureViewDescriptor::default()),
+    );
+    let size = image.texture_descriptor.size;
+
+    GpuImage {
+        texture,
+        texture_view,
+        texture_format: descriptor.format,
+        sampler,
+        size: Vec2::new(size.width as f32, size.height as f32),
+        mip_level_count: descriptor.mip_level_count,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use wgpu::{Extent3d, TextureFormat};
+
+    fn test_image(width: u32, height: u32, layers: u32, format: TextureFormat) -> Image {
+        let mut image = Image::default();
+        image.texture_descriptor.size = Extent3d {
+            width,
+            height,
+            depth_or_array_layers: layers,
+        };
+        image.texture_descriptor.format = format;
+        image.texture_descriptor.mip_level_count = width.max(height).ilog2() + 1;
+        image.data = (0..resident_bytes(&image.texture_descriptor, 0))
+            .map(|byte| byte as u8)
+            .collect();
+        image
+    }
+
+    #[test]
+    fn mip_level_sizes() {
+        let image = test_image(1024, 512, 1, TextureFormat::Rgba8Unorm);
+        let descriptor = &image.texture_descriptor;
+        assert_eq!(mip_level_bytes(descriptor, 0), 1024 * 512 * 4);
+        assert_eq!(mip_level_bytes(descriptor, 10), 4);
+        assert!(is_streamable(&image));
+        assert_eq!(initial_mip_level(&image, 128), 3);
+        assert_eq!(initial_mip_level(&image, 4096), 0);
+
+        // Compressed mips are rounded up to whole blocks, and can't start below a block
+        let image = test_image(256, 256, 1, TextureFormat::Bc1RgbaUnorm);
+        let descriptor = &image.texture_descriptor;
+        assert_eq!(mip_level_bytes(descriptor, 8), 8);
+        assert_eq!(initial_mip_level(&image, 1), 6);
+    }
+
+    #[test]
+    fn layered_mip_levels_data() {
+        let image = test_image(4, 4, 2, TextureFormat::R8Unorm);
+        let layer_bytes = image.data.len() / 2;
+        let data = mip_levels_data(&image, 1);
+        assert_eq!(data.len(), 2 * (4 + 1));
+        assert_eq!(data[..5], image.data[16..layer_bytes]);
+        assert_eq!(data[5..], image.data[layer_bytes + 16..]);
+    }
+}
@@ -6,22 +6,21 @@ pub use visibility::*;
 pub use window::*;
 
 use crate::{
-    camera::ExtractedCamera,
-    extract_resource::{ExtractResource, ExtractResourcePlugin},
+    camera::{Camera, ExtractedCamera},
     prelude::{Image, Shader},
     render_asset::RenderAssets,
     render_phase::ViewRangefinder3d,
     render_resource::{DynamicUniformBuffer, ShaderType, Texture, TextureView},
-    renderer::{RenderDevice, RenderQueue},
+    renderer::{RenderAdapter, RenderDevice, RenderQueue},
     texture::{BevyDefault, TextureCache},
     RenderApp, RenderSet,
 };
-use bevy_app::{App, Plugin};
+use bevy_app::{App, CoreSet, Plugin};
 use bevy_ecs::prelude::*;
 use bevy_math::{Mat4, UVec4, Vec3, Vec4};
-use bevy_reflect::{Reflect, TypeUuid};
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect, TypeUuid};
 use bevy_transform::components::GlobalTransform;
-use bevy_utils::HashMap;
+use bevy_utils::{tracing::warn, HashMap};
 use std::sync::atomic::{AtomicUsize, Ordering};
 use wgpu::{
     Color, Extent3d, Operations, RenderPassColorAttachment, TextureDescriptor, TextureDimension,
@@ -37,16 +36,15 @@ impl Plugin for ViewPlugin {
     fn build(&self, app: &mut App) {
         load_internal_asset!(app, VIEW_TYPE_HANDLE, "view.wgsl", Shader::from_wgsl);
 
-        app.register_type::<ComputedVisibility>()
-            .register_type::<ComputedVisibilityFlags>()
+        app.register_type::<InheritedVisibility>()
             .register_type::<Msaa>()
             .register_type::<RenderLayers>()
             .register_type::<Visibility>()
+            .register_type::<ViewVisibility>()
             .register_type::<VisibleEntities>()
-            .init_resource::<Msaa>()
             // NOTE: windows.is_changed() handles cases where a window was resized
-            .add_plugin(ExtractResourcePlugin::<Msaa>::default())
-            .add_plugin(VisibilityPlugin);
+            .add_plugin(VisibilityPlugin)
+            .add_system(validate_msaa.in_base_set(CoreSet::PostUpdate));
 
         if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
@@ -63,24 +61,33 @@ impl Plugin for ViewPlugin {
     }
 }
 
-/// Configuration resource for [Multi-Sample Anti-Aliasing](https://en.wikipedia.org/wiki/Multisample_anti-aliasing).
+/// Component configuring [Multi-Sample Anti-Aliasing](https://en.wikipedia.org/wiki/Multisample_anti-aliasing)
+/// for a [`Camera`](crate::camera::Camera).
 ///
 /// The number of samples to run for Multi-Sample Anti-Aliasing. Higher numbers result in
 /// smoother edges.
-/// Defaults to 4 samples.
+/// Defaults to 4 samples, which is also used for cameras without this component.
 ///
-/// Note that web currently only supports 1 or 4 samples.
+/// Each camera picks its own sample count, so that for example a 3D view can use 4 samples
+/// while a camera rendering a minimap to a texture uses none. The pipelines drawing to a view
+/// are specialized for its sample count.
+///
+/// Note that web currently only supports 1 or 4 samples. Sample counts that aren't supported by
+/// the device for the format of the view fall back to 4 samples, or no MSAA.
 ///
 /// # Example
 /// ```
-/// # use bevy_app::prelude::App;
-/// # use bevy_render::prelude::Msaa;
-/// App::new()
-///     .insert_resource(Msaa::default())
-///     .run();
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_render::prelude::*;
+/// fn spawn_minimap_camera(mut commands: Commands) {
+///     commands.spawn((Camera::default(), Msaa::Off));
+/// }
+/// # bevy_ecs::system::assert_is_system(spawn_minimap_camera);
 /// ```
-#[derive(Resource, Default, Clone, Copy, ExtractResource, Reflect, PartialEq, PartialOrd)]
-#[reflect(Resource)]
+#[derive(
+    Component, Default, Clone, Copy, Reflect, FromReflect, PartialEq, Eq, PartialOrd, Hash, Debug,
+)]
+#[reflect(Component, Default)]
 pub enum Msaa {
     Off = 1,
     Sample2 = 2,
@@ -348,13 +355,12 @@ fn prepare_view_targets(
     mut commands: Commands,
     windows: Res<ExtractedWindows>,
     images: Res<RenderAssets<Image>>,
-    msaa: Res<Msaa>,
     render_device: Res<RenderDevice>,
     mut texture_cache: ResMut<TextureCache>,
-    cameras: Query<(Entity, &ExtractedCamera, &ExtractedView)>,
+    cameras: Query<(Entity, &ExtractedCamera, &ExtractedView, &Msaa)>,
 ) {
     let mut textures = HashMap::default();
-    for (entity, camera, view) in cameras.iter() {
+    for (entity, camera, view, msaa) in cameras.iter() {
         if let (Some(target_size), Some(target)) = (camera.physical_target_size, &camera.target) {
             if let (Some(out_texture_view), Some(out_texture_format)) = (
                 target.get_texture_view(&windows, &images),
@@ -373,7 +379,7 @@ fn prepare_view_targets(
                 };
 
                 let main_textures = textures
-                    .entry((camera.target.clone(), view.hdr))
+                    .entry((camera.target.clone(), view.hdr, *msaa))
                     .or_insert_with(|| {
                         let descriptor = TextureDescriptor {
                             label: None,
@@ -444,3 +450,45 @@ pub enum ViewSet {
     /// Prepares view uniforms
     PrepareUniforms,
 }
+
+/// Falls back to a supported sample count for the cameras whose [`Msaa`] isn't supported by the
+/// device for the texture format of their view.
+pub fn validate_msaa(
+    render_adapter: Option<Res<RenderAdapter>>,
+    mut cameras: Query<(Entity, &Camera, &mut Msaa), Or<(Changed<Camera>, Changed<Msaa>)>>,
+) {
+    let render_adapter = match render_adapter {
+        Some(render_adapter) => render_adapter,
+        None => return,
+    };
+    for (entity, camera, mut msaa) in &mut cameras {
+        let main_texture_format = if camera.hdr {
+            ViewTarget::TEXTURE_FORMAT_HDR
+        } else {
+            TextureFormat::bevy_default()
+        };
+        let sample_flags = render_adapter
+            .get_texture_format_features(main_texture_format)
+            .flags;
+        if sample_flags.sample_count_supported(msaa.samples()) {
+            continue;
+        }
+
+        let fallback = if sample_flags.sample_count_supported(Msaa::default().samples()) {
+            Msaa::default()
+        } else {
+            Msaa::Off
+        };
+        let fallback_str = if fallback == Msaa::Off {
+            "disabling MSAA".to_owned()
+        } else {
+            format!("MSAA {}x", fallback.samples())
+        };
+        warn!(
+            "MSAA {}x is not supported on this device for camera {entity:?}. Falling back to {}.",
+            msaa.samples(),
+            fallback_str,
+        );
+        *msaa = fallback;
+    }
+}
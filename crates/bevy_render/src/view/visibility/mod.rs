@@ -4,6 +4,7 @@ pub use render_layers::*;
 
 use bevy_app::{CoreSet, Plugin};
 use bevy_asset::{Assets, Handle};
+use bevy_derive::Deref;
 use bevy_ecs::prelude::*;
 use bevy_hierarchy::{Children, Parent};
 use bevy_reflect::Reflect;
@@ -28,7 +29,9 @@ use crate::{
 /// are set to `Inherited` will also be hidden.
 ///
 /// This is done by the `visibility_propagate_system` which uses the entity hierarchy and
-/// `Visibility` to set the values of each entity's [`ComputedVisibility`] component.
+/// `Visibility` to set the values of each entity's [`InheritedVisibility`] component.
+/// Propagation only visits the entities whose [`Visibility`] or [`Parent`] changed, along with
+/// their descendants.
 #[derive(Component, Clone, Copy, Reflect, FromReflect, Debug, PartialEq, Eq, Default)]
 #[reflect(Component, Default)]
 pub enum Visibility {
@@ -62,96 +65,92 @@ impl std::cmp::PartialEq<&Visibility> for Visibility {
     }
 }
 
-bitflags::bitflags! {
-    #[derive(Reflect)]
-    pub(super) struct ComputedVisibilityFlags: u8 {
-        const VISIBLE_IN_VIEW = 1 << 0;
-        const VISIBLE_IN_HIERARCHY = 1 << 1;
-    }
-}
-
-/// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
-#[derive(Component, Clone, Reflect, Debug, Eq, PartialEq)]
+/// Whether or not an entity is visible in the hierarchy.
+/// This will not be accurate until [`VisibilityPropagate`] runs in the [`CoreSet::PostUpdate`] set.
+///
+/// If this is false, then [`ViewVisibility`] should also be false.
+///
+/// [`VisibilityPropagate`]: VisibilitySystems::VisibilityPropagate
+#[derive(Component, Deref, Debug, Default, Clone, Copy, Reflect, FromReflect, PartialEq, Eq)]
 #[reflect(Component, Default)]
-pub struct ComputedVisibility {
-    flags: ComputedVisibilityFlags,
-}
+pub struct InheritedVisibility(bool);
 
-impl Default for ComputedVisibility {
-    fn default() -> Self {
-        Self::HIDDEN
-    }
-}
+impl InheritedVisibility {
+    /// An entity that is invisible in the hierarchy.
+    pub const HIDDEN: Self = Self(false);
+    /// An entity that is visible in the hierarchy.
+    pub const VISIBLE: Self = Self(true);
 
-impl ComputedVisibility {
-    /// A [`ComputedVisibility`], set as invisible.
-    pub const HIDDEN: Self = ComputedVisibility {
-        flags: ComputedVisibilityFlags::empty(),
-    };
-
-    /// Whether this entity is visible to something this frame. This is true if and only if [`Self::is_visible_in_hierarchy`] and [`Self::is_visible_in_view`]
-    /// are true. This is the canonical method to call to determine if an entity should be drawn.
-    /// This value is updated in [`CoreSet::PostUpdate`] by the [`VisibilitySystems::CheckVisibility`] system set.
-    /// Reading it during [`CoreSet::Update`] will yield the value from the previous frame.
+    /// Returns `true` if the entity is visible in the hierarchy.
+    /// Otherwise, returns `false`.
     #[inline]
-    pub fn is_visible(&self) -> bool {
-        self.flags.bits == ComputedVisibilityFlags::all().bits
+    pub fn get(self) -> bool {
+        self.0
     }
+}
 
-    /// Whether this entity is visible in the entity hierarchy, which is determined by the [`Visibility`] component.
-    /// This takes into account "visibility inheritance". If any of this entity's ancestors (see [`Parent`]) are hidden, this entity
-    /// will be hidden as well. This value is updated in the [`VisibilitySystems::VisibilityPropagate`], which lives under the [`CoreSet::PostUpdate`] set.
-    #[inline]
-    pub fn is_visible_in_hierarchy(&self) -> bool {
-        self.flags
-            .contains(ComputedVisibilityFlags::VISIBLE_IN_HIERARCHY)
-    }
+/// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering.
+///
+/// Each frame, this will be reset to `false` during [`VisibilityPropagate`] systems in [`CoreSet::PostUpdate`].
+/// Later in the frame, systems in [`CheckVisibility`] will mark any visible entities using [`ViewVisibility::set`].
+/// Because of this, values of this type will be marked as changed every frame, even when they do not change.
+///
+/// If you wish to add custom visibility system that sets this value, make sure you add it to the [`CheckVisibility`] set.
+///
+/// [`VisibilityPropagate`]: VisibilitySystems::VisibilityPropagate
+/// [`CheckVisibility`]: VisibilitySystems::CheckVisibility
+#[derive(Component, Deref, Debug, Default, Clone, Copy, Reflect, FromReflect, PartialEq, Eq)]
+#[reflect(Component, Default)]
+pub struct ViewVisibility(bool);
 
-    /// Whether this entity is visible in _any_ view (Cameras, Lights, etc). Each entity type (and view type) should choose how to set this
-    /// value. For cameras and drawn entities, this will take into account [`RenderLayers`].
-    ///
-    /// This value is reset to `false` every frame in [`VisibilitySystems::VisibilityPropagate`] during [`CoreSet::PostUpdate`].
-    /// Each entity type then chooses how to set this field in the [`VisibilitySystems::CheckVisibility`] system set, under [`CoreSet::PostUpdate`].
-    /// Meshes might use frustum culling to decide if they are visible in a view.
-    /// Other entities might just set this to `true` every frame.
-    #[inline]
-    pub fn is_visible_in_view(&self) -> bool {
-        self.flags
-            .contains(ComputedVisibilityFlags::VISIBLE_IN_VIEW)
-    }
+impl ViewVisibility {
+    /// An entity that cannot be seen from any views.
+    pub const HIDDEN: Self = Self(false);
 
-    /// Sets `is_visible_in_view` to `true`. This is not reversible for a given frame, as it encodes whether or not this is visible in
-    /// _any_ view. This will be automatically reset to `false` every frame in [`VisibilitySystems::VisibilityPropagate`] and then set
-    /// to the proper value in [`VisibilitySystems::CheckVisibility`]. This should _only_ be set in systems with the [`VisibilitySystems::CheckVisibility`]
-    /// label. Don't call this unless you are defining a custom visibility system. For normal user-defined entity visibility, see [`Visibility`].
+    /// Returns `true` if the entity is visible in any view.
+    /// Otherwise, returns `false`.
+    ///
+    /// This is the canonical method to call to determine if an entity should be drawn, as it is only
+    /// `true` for entities that are also visible in the hierarchy. Reading it during
+    /// [`CoreSet::Update`] will yield the value from the previous frame.
     #[inline]
-    pub fn set_visible_in_view(&mut self) {
-        self.flags.insert(ComputedVisibilityFlags::VISIBLE_IN_VIEW);
+    pub fn get(self) -> bool {
+        self.0
     }
 
+    /// Sets the visibility to `true`. This should not be considered reversible for a given frame,
+    /// as this component tracks whether or not the entity is visible in _any_ view.
+    ///
+    /// This will be automatically reset to `false` every frame in [`VisibilityPropagate`] and then set
+    /// to the proper value in [`CheckVisibility`].
+    ///
+    /// You should only manually set this if you are defining a custom visibility system,
+    /// in which case the system should be placed in the [`CheckVisibility`] set.
+    /// For normal user-defined entity visibility, see [`Visibility`].
+    ///
+    /// [`VisibilityPropagate`]: VisibilitySystems::VisibilityPropagate
+    /// [`CheckVisibility`]: VisibilitySystems::CheckVisibility
     #[inline]
-    fn reset(&mut self, visible_in_hierarchy: bool) {
-        self.flags = if visible_in_hierarchy {
-            ComputedVisibilityFlags::VISIBLE_IN_HIERARCHY
-        } else {
-            ComputedVisibilityFlags::empty()
-        };
+    pub fn set(&mut self) {
+        self.0 = true;
     }
 }
 
-/// A [`Bundle`] of the [`Visibility`] and [`ComputedVisibility`]
+/// A [`Bundle`] of the [`Visibility`], [`InheritedVisibility`], and [`ViewVisibility`]
 /// [`Component`](bevy_ecs::component::Component)s, which describe the visibility of an entity.
 ///
 /// * To show or hide an entity, you should set its [`Visibility`].
-/// * To get the computed visibility of an entity, you should get its [`ComputedVisibility`].
-/// * For visibility hierarchies to work correctly, you must have both a [`Visibility`] and a [`ComputedVisibility`].
+/// * To get the inherited visibility of an entity, you should get its [`InheritedVisibility`].
+/// * For visibility hierarchies to work correctly, you must have all of [`Visibility`], [`InheritedVisibility`], and [`ViewVisibility`].
 ///   * You may use the [`VisibilityBundle`] to guarantee this.
 #[derive(Bundle, Debug, Default)]
 pub struct VisibilityBundle {
     /// The visibility of the entity.
     pub visibility: Visibility,
-    /// The computed visibility of the entity.
-    pub computed: ComputedVisibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// The computed visibility of the entity in any view.
+    pub view_visibility: ViewVisibility,
 }
 
 /// Use this component to opt-out of built-in frustum culling for Mesh entities
@@ -199,7 +198,7 @@ pub enum VisibilitySystems {
     UpdatePerspectiveFrusta,
     UpdateProjectionFrusta,
     VisibilityPropagate,
-    /// Label for the [`check_visibility()`] system updating each frame the [`ComputedVisibility`]
+    /// Label for the [`check_visibility()`] system updating each frame the [`ViewVisibility`]
     /// of each entity and the [`VisibleEntities`] of each view.
     CheckVisibility,
 }
@@ -252,6 +251,7 @@ impl Plugin for VisibilityPlugin {
                     .after(TransformSystem::TransformPropagate),
             )
             .add_system(visibility_propagate_system.in_set(VisibilityPropagate))
+            .add_system(reset_view_visibility.in_set(VisibilityPropagate))
             .add_system(
                 check_visibility
                     .in_set(CheckVisibility)
@@ -295,83 +295,120 @@ pub fn update_frusta<T: Component + CameraProjection + Send + Sync + 'static>(
 }
 
 fn visibility_propagate_system(
-    mut root_query: Query<
+    changed: Query<
+        (Entity, &Visibility, Option<&Parent>, Option<&Children>),
         (
-            Option<&Children>,
-            &Visibility,
-            &mut ComputedVisibility,
-            Entity,
+            With<InheritedVisibility>,
+            Or<(Changed<Visibility>, Changed<Parent>)>,
         ),
-        Without<Parent>,
     >,
-    mut visibility_query: Query<(&Visibility, &mut ComputedVisibility, &Parent)>,
-    children_query: Query<&Children, (With<Parent>, With<Visibility>, With<ComputedVisibility>)>,
+    mut orphaned: RemovedComponents<Parent>,
+    orphaned_query: Query<(Entity, &Visibility, Option<&Children>), Without<Parent>>,
+    mut visibility_query: Query<(&Visibility, &mut InheritedVisibility)>,
+    children_query: Query<&Children, (With<Visibility>, With<InheritedVisibility>)>,
 ) {
-    for (children, visibility, mut computed_visibility, entity) in root_query.iter_mut() {
-        // reset "view" visibility here ... if this entity should be drawn a future system should set this to true
-        computed_visibility
-            .reset(visibility == Visibility::Inherited || visibility == Visibility::Visible);
-        if let Some(children) = children {
-            for child in children.iter() {
-                let _ = propagate_recursive(
-                    computed_visibility.is_visible_in_hierarchy(),
-                    &mut visibility_query,
-                    &children_query,
-                    *child,
-                    entity,
-                );
+    let orphaned = orphaned
+        .iter()
+        .filter_map(|entity| orphaned_query.get(entity).ok())
+        .map(|(entity, visibility, children)| (entity, visibility, None, children));
+    for (entity, visibility, parent, children) in changed.iter().chain(orphaned) {
+        let is_visible = match visibility {
+            Visibility::Visible => true,
+            Visibility::Hidden => false,
+            // Roots are visible, but children of a parent lacking the visibility components
+            // are hidden, as the hierarchy is malformed (see the warning for B0004).
+            Visibility::Inherited => match parent {
+                Some(parent) => visibility_query
+                    .get(parent.get())
+                    .map_or(false, |(_, inherited_visibility)| {
+                        inherited_visibility.get()
+                    }),
+                None => true,
+            },
+        };
+        let (_, mut inherited_visibility) = visibility_query
+            .get_mut(entity)
+            .expect("With<InheritedVisibility> ensures this query will return a value");
+
+        // Only update the visibility if it has changed.
+        // This will also prevent the visibility from propagating multiple times in the same frame
+        // if this entity's visibility has been updated recursively by its parent.
+        if inherited_visibility.get() != is_visible {
+            inherited_visibility.0 = is_visible;
+
+            // Recursively update the visibility of each child.
+            for &child in children.into_iter().flatten() {
+                let _ =
+                    propagate_recursive(is_visible, child, &mut visibility_query, &children_query);
             }
         }
     }
 }
 
 fn propagate_recursive(
-    parent_visible: bool,
-    visibility_query: &mut Query<(&Visibility, &mut ComputedVisibility, &Parent)>,
-    children_query: &Query<&Children, (With<Parent>, With<Visibility>, With<ComputedVisibility>)>,
+    parent_is_visible: bool,
     entity: Entity,
-    expected_parent: Entity,
+    visibility_query: &mut Query<(&Visibility, &mut InheritedVisibility)>,
+    children_query: &Query<&Children, (With<Visibility>, With<InheritedVisibility>)>,
     // BLOCKED: https://github.com/rust-lang/rust/issues/31436
     // We use a result here to use the `?` operator. Ideally we'd use a try block instead
 ) -> Result<(), ()> {
-    let is_visible = {
-        let (visibility, mut computed_visibility, child_parent) =
-            visibility_query.get_mut(entity).map_err(drop)?;
-        assert_eq!(
-            child_parent.get(), expected_parent,
-            "Malformed hierarchy. This probably means that your hierarchy has been improperly maintained, or contains a cycle"
-        );
-        let visible_in_hierarchy = (parent_visible && visibility == Visibility::Inherited)
-            || visibility == Visibility::Visible;
-        // reset "view" visibility here ... if this entity should be drawn a future system should set this to true
-        computed_visibility.reset(visible_in_hierarchy);
-        visible_in_hierarchy
+    // Get the visibility components for the current entity.
+    // If the entity does not have the required components, just return early.
+    let (visibility, mut inherited_visibility) = visibility_query.get_mut(entity).map_err(drop)?;
+
+    let is_visible = match visibility {
+        Visibility::Visible => true,
+        Visibility::Hidden => false,
+        Visibility::Inherited => parent_is_visible,
     };
 
-    for child in children_query.get(entity).map_err(drop)?.iter() {
-        let _ = propagate_recursive(is_visible, visibility_query, children_query, *child, entity);
+    // Only update the visibility if it has changed.
+    if inherited_visibility.get() != is_visible {
+        inherited_visibility.0 = is_visible;
+
+        // Recursively update the visibility of each child.
+        for &child in children_query.get(entity).ok().into_iter().flatten() {
+            let _ = propagate_recursive(is_visible, child, visibility_query, children_query);
+        }
     }
+
     Ok(())
 }
 
+/// Resets the view visibility of every entity.
+/// Entities that are visible will be marked as such later this frame
+/// by a [`VisibilitySystems::CheckVisibility`] system.
+fn reset_view_visibility(mut query: Query<&mut ViewVisibility>) {
+    for mut view_visibility in &mut query {
+        *view_visibility = ViewVisibility::HIDDEN;
+    }
+}
+
 /// System updating the visibility of entities each frame.
 ///
 /// The system is part of the [`VisibilitySystems::CheckVisibility`] set. Each frame, it updates the
-/// [`ComputedVisibility`] of all entities, and for each view also compute the [`VisibleEntities`]
+/// [`ViewVisibility`] of all entities, and for each view also compute the [`VisibleEntities`]
 /// for that view.
 pub fn check_visibility(
     mut thread_queues: Local<ThreadLocal<Cell<Vec<Entity>>>>,
     mut view_query: Query<(&mut VisibleEntities, &Frustum, Option<&RenderLayers>), With<Camera>>,
     mut visible_aabb_query: Query<(
         Entity,
-        &mut ComputedVisibility,
+        &InheritedVisibility,
+        &mut ViewVisibility,
         Option<&RenderLayers>,
         &Aabb,
         &GlobalTransform,
         Option<&NoFrustumCulling>,
     )>,
     mut visible_no_aabb_query: Query<
-        (Entity, &mut ComputedVisibility, Option<&RenderLayers>),
+        (
+            Entity,
+            &InheritedVisibility,
+            &mut ViewVisibility,
+            Option<&RenderLayers>,
+        ),
         Without<Aabb>,
     >,
 ) {
@@ -382,15 +419,16 @@ pub fn check_visibility(
         visible_aabb_query.par_iter_mut().for_each_mut(
             |(
                 entity,
-                mut computed_visibility,
+                inherited_visibility,
+                mut view_visibility,
                 maybe_entity_mask,
                 model_aabb,
                 transform,
                 maybe_no_frustum_culling,
             )| {
-                // skip computing visibility for entities that are configured to be hidden. is_visible_in_view has already been set to false
-                // in visibility_propagate_system
-                if !computed_visibility.is_visible_in_hierarchy() {
+                // skip computing visibility for entities that are configured to be hidden.
+                // ViewVisibility has already been reset in `reset_view_visibility`.
+                if !inherited_visibility.get() {
                     return;
                 }
 
@@ -416,7 +454,7 @@ pub fn check_visibility(
                     }
                 }
 
-                computed_visibility.set_visible_in_view();
+                view_visibility.set();
                 let cell = thread_queues.get_or_default();
                 let mut queue = cell.take();
                 queue.push(entity);
@@ -425,10 +463,10 @@ pub fn check_visibility(
         );
 
         visible_no_aabb_query.par_iter_mut().for_each_mut(
-            |(entity, mut computed_visibility, maybe_entity_mask)| {
-                // skip computing visibility for entities that are configured to be hidden. is_visible_in_view has already been set to false
-                // in visibility_propagate_system
-                if !computed_visibility.is_visible_in_hierarchy() {
+            |(entity, inherited_visibility, mut view_visibility, maybe_entity_mask)| {
+                // skip computing visibility for entities that are configured to be hidden.
+                // ViewVisibility has already been reset in `reset_view_visibility`.
+                if !inherited_visibility.get() {
                     return;
                 }
 
@@ -437,7 +475,7 @@ pub fn check_visibility(
                     return;
                 }
 
-                computed_visibility.set_visible_in_view();
+                view_visibility.set();
                 let cell = thread_queues.get_or_default();
                 let mut queue = cell.take();
                 queue.push(entity);
@@ -467,23 +505,23 @@ mod test {
 
         let root1 = app
             .world
-            .spawn((Visibility::Hidden, ComputedVisibility::default()))
+            .spawn((Visibility::Hidden, InheritedVisibility::default()))
             .id();
         let root1_child1 = app
             .world
-            .spawn((Visibility::default(), ComputedVisibility::default()))
+            .spawn((Visibility::default(), InheritedVisibility::default()))
             .id();
         let root1_child2 = app
             .world
-            .spawn((Visibility::Hidden, ComputedVisibility::default()))
+            .spawn((Visibility::Hidden, InheritedVisibility::default()))
             .id();
         let root1_child1_grandchild1 = app
             .world
-            .spawn((Visibility::default(), ComputedVisibility::default()))
+            .spawn((Visibility::default(), InheritedVisibility::default()))
             .id();
         let root1_child2_grandchild1 = app
             .world
-            .spawn((Visibility::default(), ComputedVisibility::default()))
+            .spawn((Visibility::default(), InheritedVisibility::default()))
             .id();
 
         app.world
@@ -498,23 +536,23 @@ mod test {
 
         let root2 = app
             .world
-            .spawn((Visibility::default(), ComputedVisibility::default()))
+            .spawn((Visibility::default(), InheritedVisibility::default()))
             .id();
         let root2_child1 = app
             .world
-            .spawn((Visibility::default(), ComputedVisibility::default()))
+            .spawn((Visibility::default(), InheritedVisibility::default()))
             .id();
         let root2_child2 = app
             .world
-            .spawn((Visibility::Hidden, ComputedVisibility::default()))
+            .spawn((Visibility::Hidden, InheritedVisibility::default()))
             .id();
         let root2_child1_grandchild1 = app
             .world
-            .spawn((Visibility::default(), ComputedVisibility::default()))
+            .spawn((Visibility::default(), InheritedVisibility::default()))
             .id();
         let root2_child2_grandchild1 = app
             .world
-            .spawn((Visibility::default(), ComputedVisibility::default()))
+            .spawn((Visibility::default(), InheritedVisibility::default()))
             .id();
 
         app.world
@@ -532,9 +570,9 @@ mod test {
         let is_visible = |e: Entity| {
             app.world
                 .entity(e)
-                .get::<ComputedVisibility>()
+                .get::<InheritedVisibility>()
                 .unwrap()
-                .is_visible_in_hierarchy()
+                .get()
         };
         assert!(
             !is_visible(root1),
@@ -586,32 +624,32 @@ mod test {
 
         let root1 = app
             .world
-            .spawn((Visibility::Visible, ComputedVisibility::default()))
+            .spawn((Visibility::Visible, InheritedVisibility::default()))
             .id();
         let root1_child1 = app
             .world
-            .spawn((Visibility::Inherited, ComputedVisibility::default()))
+            .spawn((Visibility::Inherited, InheritedVisibility::default()))
             .id();
         let root1_child2 = app
             .world
-            .spawn((Visibility::Hidden, ComputedVisibility::default()))
+            .spawn((Visibility::Hidden, InheritedVisibility::default()))
             .id();
         let root1_child1_grandchild1 = app
             .world
-            .spawn((Visibility::Visible, ComputedVisibility::default()))
+            .spawn((Visibility::Visible, InheritedVisibility::default()))
             .id();
         let root1_child2_grandchild1 = app
             .world
-            .spawn((Visibility::Visible, ComputedVisibility::default()))
+            .spawn((Visibility::Visible, InheritedVisibility::default()))
             .id();
 
         let root2 = app
             .world
-            .spawn((Visibility::Inherited, ComputedVisibility::default()))
+            .spawn((Visibility::Inherited, InheritedVisibility::default()))
             .id();
         let root3 = app
             .world
-            .spawn((Visibility::Hidden, ComputedVisibility::default()))
+            .spawn((Visibility::Hidden, InheritedVisibility::default()))
             .id();
 
         app.world
@@ -629,9 +667,9 @@ mod test {
         let is_visible = |e: Entity| {
             app.world
                 .entity(e)
-                .get::<ComputedVisibility>()
+                .get::<InheritedVisibility>()
                 .unwrap()
-                .is_visible_in_hierarchy()
+                .get()
         };
         assert!(
             is_visible(root1),
@@ -657,6 +695,55 @@ mod test {
         assert!(!is_visible(root3), "a hidden root is hidden");
     }
 
+    #[test]
+    fn visibility_propagation_change_detection() {
+        let mut app = App::new();
+        app.add_system(visibility_propagate_system);
+
+        let root = app
+            .world
+            .spawn((Visibility::Inherited, InheritedVisibility::default()))
+            .id();
+        let child = app
+            .world
+            .spawn((Visibility::Inherited, InheritedVisibility::default()))
+            .id();
+        let grandchild = app
+            .world
+            .spawn((Visibility::Inherited, InheritedVisibility::default()))
+            .id();
+        app.world.entity_mut(root).push_children(&[child]);
+        app.world.entity_mut(child).push_children(&[grandchild]);
+
+        app.update();
+
+        let is_visible =
+            |world: &World, e: Entity| world.entity(e).get::<InheritedVisibility>().unwrap().get();
+        assert!(is_visible(&app.world, grandchild));
+
+        *app.world.get_mut::<Visibility>(root).unwrap() = Visibility::Hidden;
+        app.update();
+        assert!(
+            !is_visible(&app.world, child),
+            "hiding a root hides its existing children"
+        );
+        assert!(
+            !is_visible(&app.world, grandchild),
+            "hiding a root hides its existing grandchildren"
+        );
+
+        app.world.entity_mut(child).remove_parent();
+        app.update();
+        assert!(
+            is_visible(&app.world, child),
+            "an inheriting entity removed from a hidden parent is visible"
+        );
+        assert!(
+            is_visible(&app.world, grandchild),
+            "the children of an entity removed from a hidden parent are visible"
+        );
+    }
+
     #[test]
     fn ensure_visibility_enum_size() {
         use std::mem;
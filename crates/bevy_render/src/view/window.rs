@@ -12,8 +12,6 @@ use bevy_window::{
 use std::ops::{Deref, DerefMut};
 use wgpu::TextureFormat;
 
-use super::Msaa;
-
 /// Token to ensure a system runs on the main thread.
 #[derive(Resource, Default)]
 pub struct NonSendMarker;
@@ -176,7 +174,6 @@ pub fn prepare_windows(
     render_device: Res<RenderDevice>,
     render_instance: Res<RenderInstance>,
     render_adapter: Res<RenderAdapter>,
-    mut msaa: ResMut<Msaa>,
 ) {
     for window in windows.windows.values_mut() {
         let window_surfaces = window_surfaces.deref_mut();
@@ -231,35 +228,6 @@ pub fn prepare_windows(
             view_formats: vec![],
         };
 
-        // This is an ugly hack to work around drivers that don't support MSAA.
-        // This should be removed once https://github.com/bevyengine/bevy/issues/7194 lands and we're doing proper
-        // feature detection for MSAA.
-        // When removed, we can also remove the `.after(prepare_windows)` of `prepare_core_3d_depth_textures` and `prepare_prepass_textures`
-        let sample_flags = render_adapter
-            .get_texture_format_features(surface_configuration.format)
-            .flags;
-
-        if !sample_flags.sample_count_supported(msaa.samples()) {
-            let fallback = if sample_flags.sample_count_supported(Msaa::default().samples()) {
-                Msaa::default()
-            } else {
-                Msaa::Off
-            };
-
-            let fallback_str = if fallback == Msaa::Off {
-                "disabling MSAA".to_owned()
-            } else {
-                format!("MSAA {}x", fallback.samples())
-            };
-
-            bevy_log::warn!(
-                "MSAA {}x is not supported on this device. Falling back to {}.",
-                msaa.samples(),
-                fallback_str,
-            );
-            *msaa = fallback;
-        }
-
         // A recurring issue is hitting `wgpu::SurfaceError::Timeout` on certain Linux
         // mesa driver implementations. This seems to be a quirk of some drivers.
         // We'd rather keep panicking when not on Linux mesa, because in those case,
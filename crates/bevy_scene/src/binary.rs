@@ -0,0 +1,119 @@
+use bevy_reflect::{
+    serde::{ReflectSerializer, UntypedReflectDeserializer},
+    Reflect, TypeRegistry,
+};
+use serde::{de::DeserializeSeed, Serialize};
+use thiserror::Error;
+
+/// The magic bytes starting the binary scenes and reflected values
+pub const BINARY_MAGIC: [u8; 4] = *b"BVYB";
+
+/// The version of the binary format written by [`serialize_binary`]
+///
+/// It changes when the encoding of the scenes or of the reflected values changes, so that files
+/// written with an older format fail to load with [`BinaryError::UnsupportedVersion`] instead of
+/// loading wrong data.
+pub const BINARY_VERSION: u16 = 1;
+
+const HEADER_LEN: usize = BINARY_MAGIC.len() + std::mem::size_of::<u16>();
+
+/// An error that occurs when serializing or deserializing the binary format
+#[derive(Error, Debug)]
+pub enum BinaryError {
+    /// The bytes don't start with the header of the binary format.
+    #[error("the data doesn't start with the header of the binary format")]
+    InvalidHeader,
+    /// The bytes were written with a version of the binary format that can't be read.
+    #[error("unsupported binary format version {0}, expected version {BINARY_VERSION}")]
+    UnsupportedVersion(u16),
+    /// The data couldn't be encoded or decoded.
+    #[error("invalid binary data: {0}")]
+    Postcard(#[from] postcard::Error),
+}
+
+/// Serializes `value` into the compact binary format, after its versioned header.
+///
+/// The data is encoded with [`postcard`], which is much smaller and faster to parse than RON,
+/// but can only be read back with the same types, as it doesn't store the field names.
+pub fn serialize_binary<S: Serialize>(value: &S) -> Result<Vec<u8>, BinaryError> {
+    let mut bytes = Vec::from(BINARY_MAGIC);
+    bytes.extend_from_slice(&BINARY_VERSION.to_le_bytes());
+    Ok(postcard::to_extend(value, bytes)?)
+}
+
+/// Deserializes a value written by [`serialize_binary`] with `seed`, like a
+/// [`SceneDeserializer`](crate::serde::SceneDeserializer).
+pub fn deserialize_binary<'de, D: DeserializeSeed<'de>>(
+    bytes: &'de [u8],
+    seed: D,
+) -> Result<D::Value, BinaryError> {
+    if bytes.len() < HEADER_LEN || bytes[..BINARY_MAGIC.len()] != BINARY_MAGIC {
+        return Err(BinaryError::InvalidHeader);
+    }
+    let version = u16::from_le_bytes([bytes[BINARY_MAGIC.len()], bytes[BINARY_MAGIC.len() + 1]]);
+    if version != BINARY_VERSION {
+        return Err(BinaryError::UnsupportedVersion(version));
+    }
+    let mut deserializer = postcard::Deserializer::from_bytes(&bytes[HEADER_LEN..]);
+    Ok(seed.deserialize(&mut deserializer)?)
+}
+
+/// Serializes a reflected value with its type name into the binary format, like an asset
+/// deriving [`Reflect`].
+pub fn serialize_reflect_binary(
+    value: &dyn Reflect,
+    registry: &TypeRegistry,
+) -> Result<Vec<u8>, BinaryError> {
+    serialize_binary(&ReflectSerializer::new(value, registry))
+}
+
+/// Deserializes a reflected value written by [`serialize_reflect_binary`].
+///
+/// The value is usually dynamic, and can be converted to its type with
+/// [`FromReflect`](bevy_reflect::FromReflect).
+pub fn deserialize_reflect_binary(
+    bytes: &[u8],
+    registry: &TypeRegistry,
+) -> Result<Box<dyn Reflect>, BinaryError> {
+    deserialize_binary(bytes, UntypedReflectDeserializer::new(registry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_reflect::FromReflect;
+
+    #[derive(Reflect, FromReflect, Debug, PartialEq)]
+    struct Level {
+        name: String,
+        spawn: (f32, f32),
+    }
+
+    #[test]
+    fn binary_header() {
+        let mut registry = TypeRegistry::default();
+        registry.register::<Level>();
+        registry.register::<String>();
+        registry.register::<(f32, f32)>();
+        let level = Level {
+            name: "intro".to_string(),
+            spawn: (1.0, 2.0),
+        };
+
+        let bytes = serialize_reflect_binary(&level, &registry).unwrap();
+        assert_eq!(bytes[..4], BINARY_MAGIC);
+        let value = deserialize_reflect_binary(&bytes, &registry).unwrap();
+        assert_eq!(Level::from_reflect(&*value), Some(level));
+
+        assert!(matches!(
+            deserialize_reflect_binary(b"(name: \"intro\")", &registry),
+            Err(BinaryError::InvalidHeader)
+        ));
+        let mut bytes = bytes;
+        bytes[4] = 2;
+        assert!(matches!(
+            deserialize_reflect_binary(&bytes, &registry),
+            Err(BinaryError::UnsupportedVersion(2))
+        ));
+    }
+}
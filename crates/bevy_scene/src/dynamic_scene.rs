@@ -9,7 +9,12 @@ use bevy_ecs::{
 use bevy_reflect::{Reflect, TypeRegistryArc, TypeUuid};
 
 #[cfg(feature = "serialize")]
-use crate::serde::SceneSerializer;
+use crate::{
+    serde::{SceneDeserializer, SceneSerializer},
+    BinaryError,
+};
+#[cfg(feature = "serialize")]
+use bevy_reflect::TypeRegistry;
 #[cfg(feature = "serialize")]
 use serde::Serialize;
 
@@ -119,12 +124,36 @@ impl DynamicScene {
         self.write_to_world_with(world, entity_map, &registry)
     }
 
-    // TODO: move to AssetSaver when it is implemented
     /// Serialize this dynamic scene into rust object notation (ron).
+    ///
+    /// The scenes in their [`Assets`](bevy_asset::Assets) can also be saved to a file with the
+    /// [`SceneSaver`](crate::SceneSaver).
     #[cfg(feature = "serialize")]
     pub fn serialize_ron(&self, registry: &TypeRegistryArc) -> Result<String, ron::Error> {
         serialize_ron(SceneSerializer::new(self, registry))
     }
+
+    /// Serialize this dynamic scene into the compact binary format of [`serialize_binary`].
+    ///
+    /// The binary scenes are loaded from `.scn.bin` files by the
+    /// [`BinarySceneLoader`](crate::BinarySceneLoader).
+    ///
+    /// [`serialize_binary`]: crate::serialize_binary
+    #[cfg(feature = "serialize")]
+    pub fn serialize_binary(&self, registry: &TypeRegistryArc) -> Result<Vec<u8>, BinaryError> {
+        crate::serialize_binary(&SceneSerializer::new(self, registry))
+    }
+
+    /// Deserialize a dynamic scene written by [`DynamicScene::serialize_binary`].
+    #[cfg(feature = "serialize")]
+    pub fn deserialize_binary(bytes: &[u8], registry: &TypeRegistry) -> Result<Self, BinaryError> {
+        crate::deserialize_binary(
+            bytes,
+            SceneDeserializer {
+                type_registry: registry,
+            },
+        )
+    }
 }
 
 /// Serialize a given Rust data structure into rust object notation (ron).
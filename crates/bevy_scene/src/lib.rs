@@ -1,3 +1,5 @@
+#[cfg(feature = "serialize")]
+mod binary;
 mod bundle;
 mod dynamic_scene;
 mod dynamic_scene_builder;
@@ -8,6 +10,8 @@ mod scene_spawner;
 #[cfg(feature = "serialize")]
 pub mod serde;
 
+#[cfg(feature = "serialize")]
+pub use binary::*;
 pub use bundle::*;
 pub use dynamic_scene::*;
 pub use dynamic_scene_builder::*;
@@ -35,6 +39,9 @@ impl Plugin for ScenePlugin {
         app.add_asset::<DynamicScene>()
             .add_asset::<Scene>()
             .init_asset_loader::<SceneLoader>()
+            .init_asset_saver::<SceneSaver>()
+            .init_asset_loader::<BinarySceneLoader>()
+            .init_asset_saver::<BinarySceneSaver>()
             .init_resource::<SceneSpawner>()
             .add_system(scene_spawner_system)
             // Systems `*_bundle_spawner` must run before `scene_spawner_system`
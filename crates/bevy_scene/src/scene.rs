@@ -59,12 +59,26 @@ impl Scene {
         let mut instance_info = InstanceInfo {
             entity_map: EntityMap::default(),
         };
+        self.write_to_world_with_entity_map(world, &mut instance_info.entity_map, type_registry)?;
+        Ok(instance_info)
+    }
 
+    /// Write the entities and their corresponding components to the given world, replacing the
+    /// components of the entities already in `entity_map`, like the entities of a scene instance
+    /// when its scene is reloaded.
+    ///
+    /// This method will return a [`SceneSpawnError`] if a type either is not registered in the
+    /// provided [`AppTypeRegistry`] or doesn't reflect the [`Component`](bevy_ecs::component::Component) trait.
+    pub fn write_to_world_with_entity_map(
+        &self,
+        world: &mut World,
+        entity_map: &mut EntityMap,
+        type_registry: &AppTypeRegistry,
+    ) -> Result<(), SceneSpawnError> {
         let type_registry = type_registry.read();
         for archetype in self.world.archetypes().iter() {
             for scene_entity in archetype.entities() {
-                let entity = *instance_info
-                    .entity_map
+                let entity = *entity_map
                     .entry(scene_entity.entity())
                     .or_insert_with(|| world.spawn_empty().id());
                 for component_id in archetype.components() {
@@ -93,11 +107,11 @@ impl Scene {
         for registration in type_registry.iter() {
             if let Some(map_entities_reflect) = registration.data::<ReflectMapEntities>() {
                 map_entities_reflect
-                    .map_entities(world, &instance_info.entity_map)
+                    .map_entities(world, entity_map)
                     .unwrap();
             }
         }
 
-        Ok(instance_info)
+        Ok(())
     }
 }
@@ -34,6 +34,7 @@ pub struct SceneSpawner {
     spawned_dynamic_scenes: HashMap<Handle<DynamicScene>, Vec<InstanceId>>,
     spawned_instances: HashMap<InstanceId, InstanceInfo>,
     scene_asset_event_reader: ManualEventReader<AssetEvent<DynamicScene>>,
+    real_scene_asset_event_reader: ManualEventReader<AssetEvent<Scene>>,
     dynamic_scenes_to_spawn: Vec<(Handle<DynamicScene>, InstanceId)>,
     scenes_to_spawn: Vec<(Handle<Scene>, InstanceId)>,
     scenes_to_despawn: Vec<Handle<DynamicScene>>,
@@ -205,6 +206,35 @@ impl SceneSpawner {
         Ok(())
     }
 
+    /// Writes the reloaded [`Scene`]s of `scene_handles` to their spawned instances again.
+    pub fn update_spawned_real_scenes(
+        &mut self,
+        world: &mut World,
+        scene_handles: &[Handle<Scene>],
+    ) -> Result<(), SceneSpawnError> {
+        world.resource_scope(|world, scenes: Mut<Assets<Scene>>| {
+            let type_registry = world.resource::<AppTypeRegistry>().clone();
+            for scene_handle in scene_handles {
+                let (Some(spawned_instances), Some(scene)) = (
+                    self.spawned_scenes.get(scene_handle),
+                    scenes.get(scene_handle),
+                ) else {
+                    continue;
+                };
+                for instance_id in spawned_instances {
+                    if let Some(instance_info) = self.spawned_instances.get_mut(instance_id) {
+                        scene.write_to_world_with_entity_map(
+                            world,
+                            &mut instance_info.entity_map,
+                            &type_registry,
+                        )?;
+                    }
+                }
+            }
+            Ok(())
+        })
+    }
+
     pub fn despawn_queued_scenes(&mut self, world: &mut World) -> Result<(), SceneSpawnError> {
         let scenes_to_despawn = std::mem::take(&mut self.scenes_to_despawn);
 
@@ -329,6 +359,19 @@ pub fn scene_spawner_system(world: &mut World) {
             }
         }
 
+        let real_scene_asset_events = world.resource::<Events<AssetEvent<Scene>>>();
+        let mut updated_spawned_real_scenes = Vec::new();
+        for event in scene_spawner
+            .real_scene_asset_event_reader
+            .iter(real_scene_asset_events)
+        {
+            if let AssetEvent::Modified { handle } = event {
+                if scene_spawner.spawned_scenes.contains_key(handle) {
+                    updated_spawned_real_scenes.push(handle.clone_weak());
+                }
+            }
+        }
+
         scene_spawner.despawn_queued_scenes(world).unwrap();
         scene_spawner.despawn_queued_instances(world);
         scene_spawner
@@ -337,6 +380,9 @@ pub fn scene_spawner_system(world: &mut World) {
         scene_spawner
             .update_spawned_scenes(world, &updated_spawned_scenes)
             .unwrap();
+        scene_spawner
+            .update_spawned_real_scenes(world, &updated_spawned_real_scenes)
+            .unwrap();
         scene_spawner.set_scene_instance_parent_sync(world);
     });
 }
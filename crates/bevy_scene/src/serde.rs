@@ -2,7 +2,7 @@ use crate::{DynamicEntity, DynamicScene};
 use anyhow::Result;
 use bevy_reflect::serde::{TypedReflectDeserializer, TypedReflectSerializer};
 use bevy_reflect::{
-    serde::{TypeRegistrationDeserializer, UntypedReflectDeserializer},
+    serde::{UntypedReflectDeserializer, VersionedRegistrationDeserializer},
     Reflect, TypeRegistry, TypeRegistryArc,
 };
 use bevy_utils::HashSet;
@@ -104,11 +104,16 @@ impl<'a> Serialize for ComponentsSerializer<'a> {
     where
         S: serde::Serializer,
     {
+        let registry = self.registry.read();
         let mut state = serializer.serialize_map(Some(self.components.len()))?;
         for component in self.components {
+            let type_name = match registry.get_with_name(component.type_name()) {
+                Some(registration) => registry.versioned_type_name(registration),
+                None => component.type_name().into(),
+            };
             state.serialize_entry(
-                component.type_name(),
-                &TypedReflectSerializer::new(&**component, &self.registry.read()),
+                &*type_name,
+                &TypedReflectSerializer::new(&**component, &registry),
             )?;
         }
         state.end()
@@ -355,19 +360,21 @@ impl<'a, 'de> Visitor<'de> for ComponentVisitor<'a> {
     {
         let mut added = HashSet::new();
         let mut components = Vec::new();
-        while let Some(registration) =
-            map.next_key_seed(TypeRegistrationDeserializer::new(self.registry))?
+        while let Some(versioned) =
+            map.next_key_seed(VersionedRegistrationDeserializer::new(self.registry))?
         {
-            if !added.insert(registration.type_id()) {
+            if !added.insert(versioned.current.type_id()) {
                 return Err(Error::custom(format_args!(
                     "duplicate component: `{}`",
-                    registration.type_name()
+                    versioned.current.type_name()
                 )));
             }
 
-            components.push(
-                map.next_value_seed(TypedReflectDeserializer::new(registration, self.registry))?,
-            );
+            let component = map.next_value_seed(TypedReflectDeserializer::new(
+                versioned.saved,
+                self.registry,
+            ))?;
+            components.push(versioned.migrate(component).map_err(Error::custom)?);
         }
 
         Ok(components)
@@ -658,6 +665,93 @@ mod tests {
         assert_scene_eq(&scene, &deserialized_scene);
     }
 
+    #[test]
+    fn should_roundtrip_binary_scene() {
+        let mut world = create_world();
+
+        world.spawn(MyComponent {
+            foo: [1, 2, 3],
+            bar: (1.3, 3.7),
+            baz: MyEnum::Tuple("Hello World!".to_string()),
+        });
+
+        let registry = world.resource::<AppTypeRegistry>();
+
+        let scene = DynamicScene::from_world(&world, registry);
+
+        let serialized_scene = scene.serialize_binary(&registry.0).unwrap();
+        assert_eq!(serialized_scene[..4], crate::BINARY_MAGIC);
+
+        let deserialized_scene =
+            DynamicScene::deserialize_binary(&serialized_scene, &registry.0.read()).unwrap();
+
+        assert_eq!(1, deserialized_scene.entities.len());
+        assert_scene_eq(&scene, &deserialized_scene);
+    }
+
+    #[test]
+    fn should_migrate_components() {
+        #[derive(Reflect, FromReflect)]
+        struct HealthV0(u32);
+
+        #[derive(Component, Reflect, FromReflect, Default, Debug, PartialEq)]
+        #[reflect(Component)]
+        struct Health {
+            current: f32,
+            max: f32,
+        }
+
+        let mut world = create_world();
+        world
+            .resource::<AppTypeRegistry>()
+            .write()
+            .register_migration(|HealthV0(hp)| Health {
+                current: hp as f32,
+                max: 100.0,
+            });
+
+        // a scene saved before `Health` was migrated, with its first version
+        let input = r#"(
+  entities: {
+    0: (
+      components: {
+        "bevy_scene::serde::tests::should_migrate_components::Health": (25),
+      },
+    ),
+  },
+)"#;
+        let registry = world.resource::<AppTypeRegistry>().clone();
+        let mut deserializer = ron::de::Deserializer::from_str(input).unwrap();
+        let scene = SceneDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        scene
+            .write_to_world(&mut world, &mut EntityMap::default())
+            .unwrap();
+        let health = world.query::<&Health>().single(&world);
+        assert_eq!(
+            health,
+            &Health {
+                current: 25.0,
+                max: 100.0
+            }
+        );
+
+        let scene = DynamicScene::from_world(&world, &registry);
+        let output = scene.serialize_ron(&registry.0).unwrap();
+        assert!(output
+            .contains(r#""bevy_scene::serde::tests::should_migrate_components::Health@1": ("#));
+        let mut deserializer = ron::de::Deserializer::from_str(&output).unwrap();
+        let deserialized_scene = SceneDeserializer {
+            type_registry: &registry.read(),
+        }
+        .deserialize(&mut deserializer)
+        .unwrap();
+        assert_scene_eq(&scene, &deserialized_scene);
+    }
+
     /// A crude equality checker for [`DynamicScene`], used solely for testing purposes.
     fn assert_scene_eq(expected: &DynamicScene, received: &DynamicScene) {
         assert_eq!(
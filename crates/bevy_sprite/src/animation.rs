@@ -0,0 +1,347 @@
+use std::ops::RangeInclusive;
+
+use bevy_asset::{AssetEvent, Assets, Handle};
+use bevy_ecs::prelude::*;
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect, TypeUuid};
+use bevy_render::texture::FlipbookMode;
+use bevy_time::Time;
+use bevy_utils::HashSet;
+
+use crate::TextureAtlasSprite;
+
+/// Plays an animation on the [`TextureAtlasSprite`] of the entity, by changing its
+/// [`index`](TextureAtlasSprite::index) to each of the [`frames`](Self::frames) in turn.
+///
+/// The animation is played by [`animate_sprites`], which sends a [`SpriteAnimationEvent`] each
+/// time the animation loops or finishes.
+#[derive(Component, Debug, Clone, PartialEq, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct SpriteAnimation {
+    /// The indices in the texture atlas of the frames of the animation, in order.
+    pub frames: Vec<usize>,
+    /// The number of frames shown per second.
+    pub fps: f32,
+    /// How long each of the [`frames`](Self::frames) is shown, in seconds.
+    ///
+    /// When empty, all the frames are shown for the same time, set by [`fps`](Self::fps). The
+    /// frames after the last duration are also shown at `fps`.
+    pub frame_durations: Vec<f32>,
+    /// How the animation plays through its frames.
+    pub mode: FlipbookMode,
+    /// Whether the animation is paused on its current frame.
+    pub paused: bool,
+    /// The time since the animation started, or since its last loop.
+    elapsed: f32,
+    finished: bool,
+}
+
+impl SpriteAnimation {
+    /// Creates a looping animation of the `frames` of a texture atlas, at `fps` frames per
+    /// second.
+    pub fn new(frames: impl Into<Vec<usize>>, fps: f32) -> Self {
+        SpriteAnimation {
+            frames: frames.into(),
+            fps,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a looping animation of the consecutive frames of a texture atlas in `range`, at
+    /// `fps` frames per second.
+    pub fn from_range(range: RangeInclusive<usize>, fps: f32) -> Self {
+        SpriteAnimation::new(range.collect::<Vec<_>>(), fps)
+    }
+
+    /// Returns this [`SpriteAnimation`] with its [`FlipbookMode`] set to `mode`.
+    pub fn with_mode(mut self, mode: FlipbookMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Returns this [`SpriteAnimation`] with each of its frames shown for the matching duration
+    /// in `frame_durations`, in seconds.
+    pub fn with_frame_durations(mut self, frame_durations: impl Into<Vec<f32>>) -> Self {
+        self.frame_durations = frame_durations.into();
+        self
+    }
+
+    /// Plays the animation again from its first frame.
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+        self.finished = false;
+    }
+
+    /// Whether the animation played until its last frame, for [`FlipbookMode::Once`].
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// The position of the frame shown in [`frames`](Self::frames).
+    pub fn current_frame(&self) -> usize {
+        if self.frames.is_empty() {
+            return 0;
+        }
+        let period = self.period();
+        if self.frame_durations.is_empty() {
+            let step = (self.elapsed * self.fps) as usize;
+            return match self.mode {
+                FlipbookMode::Once => step.min(period - 1),
+                FlipbookMode::Loop | FlipbookMode::PingPong => self.step_frame(step % period),
+            };
+        }
+
+        let mut remaining = self.elapsed;
+        for step in 0..period {
+            let frame = self.step_frame(step);
+            remaining -= self.frame_duration(frame);
+            if remaining < 0.0 {
+                return frame;
+            }
+        }
+        self.step_frame(period - 1)
+    }
+
+    /// The time taken to play the animation until it loops, in seconds.
+    pub fn duration(&self) -> f32 {
+        if self.frame_durations.is_empty() {
+            return self.period() as f32 / self.fps;
+        }
+        (0..self.period())
+            .map(|step| self.frame_duration(self.step_frame(step)))
+            .sum()
+    }
+
+    /// The number of frames played before the animation loops.
+    fn period(&self) -> usize {
+        let frame_count = self.frames.len();
+        match self.mode {
+            FlipbookMode::Loop | FlipbookMode::Once => frame_count,
+            FlipbookMode::PingPong => (2 * frame_count.saturating_sub(1)).max(1),
+        }
+    }
+
+    /// The position in [`frames`](Self::frames) of the frame played at `step` of the period.
+    fn step_frame(&self, step: usize) -> usize {
+        let frame_count = self.frames.len();
+        if step < frame_count {
+            step
+        } else {
+            // Going back through the frames when playing in ping-pong
+            self.period() - step
+        }
+    }
+
+    /// The time the frame at `position` in [`frames`](Self::frames) is shown, in seconds.
+    fn frame_duration(&self, position: usize) -> f32 {
+        self.frame_durations
+            .get(position)
+            .copied()
+            .unwrap_or(1.0 / self.fps)
+    }
+
+    /// Whether the animation is showing the last frame of its period.
+    fn reached_last_frame(&self) -> bool {
+        let period = self.period();
+        if self.frame_durations.is_empty() {
+            self.elapsed * self.fps >= (period - 1) as f32
+        } else {
+            self.elapsed >= self.duration() - self.frame_duration(self.step_frame(period - 1))
+        }
+    }
+}
+
+impl Default for SpriteAnimation {
+    fn default() -> Self {
+        SpriteAnimation {
+            frames: Vec::new(),
+            fps: 24.0,
+            frame_durations: Vec::new(),
+            mode: FlipbookMode::Loop,
+            paused: false,
+            elapsed: 0.0,
+            finished: false,
+        }
+    }
+}
+
+/// Sent by [`animate_sprites`] when a [`SpriteAnimation`] loops or finishes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpriteAnimationEvent {
+    /// The animation of the entity went back to its first frame.
+    Looped { entity: Entity },
+    /// The animation of the entity, played with [`FlipbookMode::Once`], reached its last frame.
+    Finished { entity: Entity },
+}
+
+/// Advances each [`SpriteAnimation`], and updates the index of its [`TextureAtlasSprite`].
+pub fn animate_sprites(
+    time: Res<Time>,
+    mut events: EventWriter<SpriteAnimationEvent>,
+    mut query: Query<(Entity, &mut SpriteAnimation, &mut TextureAtlasSprite)>,
+) {
+    for (entity, mut animation, mut sprite) in &mut query {
+        if animation.frames.is_empty() {
+            continue;
+        }
+        let duration = animation.duration();
+        if !duration.is_finite() || duration <= 0.0 {
+            continue;
+        }
+        if !animation.paused && !animation.finished {
+            let animation = animation.as_mut();
+            animation.elapsed += time.delta_seconds();
+
+            if animation.mode == FlipbookMode::Once {
+                if animation.reached_last_frame() {
+                    animation.finished = true;
+                    events.send(SpriteAnimationEvent::Finished { entity });
+                }
+            } else if animation.elapsed >= duration {
+                // Keeps the elapsed time small to stay precise
+                animation.elapsed %= duration;
+                events.send(SpriteAnimationEvent::Looped { entity });
+            }
+        }
+
+        let index = animation.frames[animation.current_frame()];
+        if sprite.index != index {
+            sprite.index = index;
+        }
+    }
+}
+
+/// An animation of the frames of a [`TextureAtlas`](crate::TextureAtlas), such as the ones
+/// loaded with a sprite sheet by the [`SpriteSheetLoader`](crate::SpriteSheetLoader).
+///
+/// A `Handle<SpriteAnimationClip>` added to an entity with a [`TextureAtlasSprite`] plays the
+/// clip, by inserting the [`SpriteAnimation`] of the clip on the entity. The animation is
+/// started over each time the handle or the clip changes.
+#[derive(Debug, Clone, PartialEq, Reflect, FromReflect, TypeUuid)]
+#[uuid = "4a3bd6e1-8a7e-4d5c-9b0f-2f6c1d8e5a73"]
+#[reflect(Debug, Default)]
+pub struct SpriteAnimationClip {
+    /// The indices in the texture atlas of the frames of the clip, in order.
+    pub frames: Vec<usize>,
+    /// How long each of the frames is shown, in seconds, see
+    /// [`SpriteAnimation::frame_durations`].
+    pub frame_durations: Vec<f32>,
+    /// The number of frames shown per second, for the frames without a duration.
+    pub fps: f32,
+    /// How the clip plays through its frames.
+    pub mode: FlipbookMode,
+}
+
+impl SpriteAnimationClip {
+    /// The [`SpriteAnimation`] playing this clip from its start.
+    pub fn animation(&self) -> SpriteAnimation {
+        SpriteAnimation::new(self.frames.clone(), self.fps)
+            .with_frame_durations(self.frame_durations.clone())
+            .with_mode(self.mode)
+    }
+}
+
+impl Default for SpriteAnimationClip {
+    fn default() -> Self {
+        SpriteAnimationClip {
+            frames: Vec::new(),
+            frame_durations: Vec::new(),
+            fps: 24.0,
+            mode: FlipbookMode::Loop,
+        }
+    }
+}
+
+/// Inserts the [`SpriteAnimation`] of the [`SpriteAnimationClip`] of each entity, when its
+/// handle changes or when the clip is loaded or modified.
+pub fn play_sprite_animation_clips(
+    mut commands: Commands,
+    mut clip_events: EventReader<AssetEvent<SpriteAnimationClip>>,
+    clips: Res<Assets<SpriteAnimationClip>>,
+    query: Query<(Entity, Ref<Handle<SpriteAnimationClip>>), With<TextureAtlasSprite>>,
+) {
+    let changed_clips: HashSet<_> = clip_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => Some(handle.id()),
+            AssetEvent::Removed { .. } | AssetEvent::LoadedWithDependencies { .. } => None,
+        })
+        .collect();
+
+    for (entity, handle) in &query {
+        if !handle.is_changed() && !changed_clips.contains(&handle.id()) {
+            continue;
+        }
+        if let Some(clip) = clips.get(&handle) {
+            commands.entity(entity).insert(clip.animation());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use std::time::{Duration, Instant};
+
+    /// Plays an animation for `steps` updates of `step` seconds, returning the frame shown after
+    /// each update and the events sent.
+    fn play(
+        animation: SpriteAnimation,
+        steps: u32,
+        step: f32,
+    ) -> (Vec<usize>, Vec<SpriteAnimationEvent>) {
+        let mut app = App::new();
+        app.init_resource::<Time>()
+            .add_event::<SpriteAnimationEvent>()
+            .add_system(animate_sprites);
+        let entity = app
+            .world
+            .spawn((animation, TextureAtlasSprite::default()))
+            .id();
+
+        let start = Instant::now();
+        app.world.resource_mut::<Time>().update_with_instant(start);
+        let mut frames = Vec::new();
+        let mut events = Vec::new();
+        for i in 1..=steps {
+            let instant = start + Duration::from_secs_f32(step * i as f32);
+            app.world
+                .resource_mut::<Time>()
+                .update_with_instant(instant);
+            app.update();
+            frames.push(app.world.get::<TextureAtlasSprite>(entity).unwrap().index);
+            events.extend(
+                app.world
+                    .resource_mut::<Events<SpriteAnimationEvent>>()
+                    .drain(),
+            );
+        }
+        (frames, events)
+    }
+
+    #[test]
+    fn sprite_animation() {
+        let animation = SpriteAnimation::from_range(4..=6, 4.0);
+        let (frames, events) = play(animation.clone(), 7, 0.25);
+        assert_eq!(frames, [5, 6, 4, 5, 6, 4, 5]);
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|event| matches!(event, SpriteAnimationEvent::Looped { .. })));
+
+        let (frames, events) = play(animation.clone().with_mode(FlipbookMode::Once), 5, 0.25);
+        assert_eq!(frames, [5, 6, 6, 6, 6]);
+        assert!(matches!(
+            events[..],
+            [SpriteAnimationEvent::Finished { .. }]
+        ));
+
+        let (frames, _) = play(animation.clone().with_mode(FlipbookMode::PingPong), 6, 0.25);
+        assert_eq!(frames, [5, 6, 5, 4, 5, 6]);
+
+        let animation = animation.with_frame_durations([0.25, 0.5, 0.25]);
+        let (frames, events) = play(animation, 5, 0.25);
+        assert_eq!(frames, [5, 5, 6, 4, 5]);
+        assert_eq!(events.len(), 1);
+    }
+}
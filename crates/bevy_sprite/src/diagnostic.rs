@@ -0,0 +1,81 @@
+use bevy_app::prelude::*;
+use bevy_diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy_ecs::prelude::*;
+use bevy_render::{RenderApp, RenderSet};
+
+use crate::{queue_sprites, SpriteBatchStats};
+
+/// Adds `sprites`, `sprite_batches` and `sprite_texture_breaks` diagnostics, counting the
+/// sprites queued each frame, the batches they are queued in, and how many of those batches
+/// were started because the image changed.
+///
+/// The counts of the last frame are also available in the [`SpriteBatchStats`] resource. This
+/// plugin must be added after the [`SpritePlugin`](crate::SpritePlugin).
+#[derive(Default)]
+pub struct SpriteBatchDiagnosticsPlugin;
+
+impl Plugin for SpriteBatchDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let (sender, receiver) = async_channel::unbounded();
+        app.init_resource::<SpriteBatchStats>()
+            .insert_resource(SpriteBatchStatsReceiver(receiver))
+            .add_startup_system(Self::setup_system)
+            .add_system(Self::diagnostic_system.in_base_set(CoreSet::PreUpdate));
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .insert_resource(SpriteBatchStatsSender(sender))
+                .add_system(
+                    send_sprite_batch_stats
+                        .in_set(RenderSet::Queue)
+                        .after(queue_sprites),
+                );
+        }
+    }
+}
+
+impl SpriteBatchDiagnosticsPlugin {
+    pub const SPRITES: DiagnosticId =
+        DiagnosticId::from_u128(268051528655230533936689873102151069138);
+    pub const SPRITE_BATCHES: DiagnosticId =
+        DiagnosticId::from_u128(94561955520562416350519025475306381489);
+    pub const SPRITE_TEXTURE_BREAKS: DiagnosticId =
+        DiagnosticId::from_u128(187348520017105576338030077867785148394);
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(Self::SPRITES, "sprites", 20));
+        diagnostics.add(Diagnostic::new(Self::SPRITE_BATCHES, "sprite_batches", 20));
+        diagnostics.add(Diagnostic::new(
+            Self::SPRITE_TEXTURE_BREAKS,
+            "sprite_texture_breaks",
+            20,
+        ));
+    }
+
+    pub fn diagnostic_system(
+        receiver: Res<SpriteBatchStatsReceiver>,
+        mut diagnostics: ResMut<Diagnostics>,
+        mut batch_stats: ResMut<SpriteBatchStats>,
+    ) {
+        while let Ok(stats) = receiver.0.try_recv() {
+            diagnostics.add_measurement(Self::SPRITES, || stats.sprites as f64);
+            diagnostics.add_measurement(Self::SPRITE_BATCHES, || stats.batches as f64);
+            diagnostics
+                .add_measurement(Self::SPRITE_TEXTURE_BREAKS, || stats.texture_breaks as f64);
+            *batch_stats = stats;
+        }
+    }
+}
+
+/// Receives the [`SpriteBatchStats`] of the render world.
+#[derive(Resource)]
+pub struct SpriteBatchStatsReceiver(async_channel::Receiver<SpriteBatchStats>);
+
+/// Sends the [`SpriteBatchStats`] of the render world to the main world.
+#[derive(Resource)]
+struct SpriteBatchStatsSender(async_channel::Sender<SpriteBatchStats>);
+
+fn send_sprite_batch_stats(sender: Res<SpriteBatchStatsSender>, stats: Res<SpriteBatchStats>) {
+    // The channel is only closed when the main world was dropped
+    let _ = sender.0.try_send(*stats);
+}
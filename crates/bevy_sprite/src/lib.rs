@@ -1,41 +1,76 @@
+mod animation;
 mod bundle;
+mod diagnostic;
 mod dynamic_texture_atlas_builder;
 mod mesh2d;
+mod parallax;
+mod picking;
+mod pixel_perfect;
 mod render;
+mod runtime_texture_atlas;
+mod shape;
 mod sprite;
+mod sprite_sheet_loader;
 mod texture_atlas;
 mod texture_atlas_builder;
+mod texture_slice;
+mod tilemap;
 
 pub mod collide_aabb;
 
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
+        animation::{SpriteAnimation, SpriteAnimationClip, SpriteAnimationEvent},
         bundle::{SpriteBundle, SpriteSheetBundle},
-        sprite::Sprite,
+        parallax::ParallaxLayer,
+        picking::{PickableSprite, SpritePointerEvent, SpritePointerEventKind},
+        pixel_perfect::PixelPerfectCamera,
+        runtime_texture_atlas::RuntimeTextureAtlas,
+        shape::{Fill, Shape2d, ShapeBundle, Stroke},
+        sprite::{SortMode2d, Sprite},
         texture_atlas::{TextureAtlas, TextureAtlasSprite},
-        ColorMaterial, ColorMesh2dBundle, TextureAtlasBuilder,
+        texture_slice::{BorderRect, ImageScaleMode, SliceScaleMode, TextureSlicer},
+        tilemap::{Tile, TileAnimation, Tilemap, TilemapBundle},
+        AmbientLight2d, ColorMaterial, ColorMesh2dBundle, PointLight2d, PointLight2dBundle,
+        TextureAtlasBuilder,
     };
 }
 
+pub use animation::*;
 pub use bundle::*;
+pub use diagnostic::*;
 pub use dynamic_texture_atlas_builder::*;
 pub use mesh2d::*;
+pub use parallax::*;
+pub use picking::*;
+pub use pixel_perfect::*;
 pub use render::*;
+pub use runtime_texture_atlas::*;
+pub use shape::*;
 pub use sprite::*;
+pub use sprite_sheet_loader::*;
 pub use texture_atlas::*;
 pub use texture_atlas_builder::*;
+pub use texture_slice::*;
+pub use tilemap::*;
 
 use bevy_app::prelude::*;
-use bevy_asset::{AddAsset, Assets, HandleUntyped};
+use bevy_asset::{AddAsset, Assets, Handle, HandleUntyped};
 use bevy_core_pipeline::core_2d::Transparent2d;
 use bevy_ecs::prelude::*;
+use bevy_math::Vec2;
 use bevy_reflect::TypeUuid;
 use bevy_render::{
+    mesh::Mesh,
+    primitives::Aabb,
     render_phase::AddRenderCommand,
     render_resource::{Shader, SpecializedRenderPipelines},
+    texture::Image,
+    view::{NoFrustumCulling, VisibilitySystems},
     ExtractSchedule, RenderApp, RenderSet,
 };
+use bevy_transform::TransformSystem;
 
 #[derive(Default)]
 pub struct SpritePlugin;
@@ -43,6 +78,9 @@ pub struct SpritePlugin;
 pub const SPRITE_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 2763343953151597127);
 
+pub const TEXTURE_SLICE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 16043826653741616942);
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum SpriteSystem {
     ExtractSprites,
@@ -53,13 +91,38 @@ impl Plugin for SpritePlugin {
         let mut shaders = app.world.resource_mut::<Assets<Shader>>();
         let sprite_shader = Shader::from_wgsl(include_str!("render/sprite.wgsl"));
         shaders.set_untracked(SPRITE_SHADER_HANDLE, sprite_shader);
+        let texture_slice_shader = Shader::from_wgsl(include_str!("render/texture_slice.wgsl"));
+        shaders.set_untracked(TEXTURE_SLICE_SHADER_HANDLE, texture_slice_shader);
         app.add_asset::<TextureAtlas>()
             .register_asset_reflect::<TextureAtlas>()
+            .add_asset::<SpriteAnimationClip>()
+            .register_asset_reflect::<SpriteAnimationClip>()
+            .init_asset_loader::<SpriteSheetLoader>()
             .register_type::<Sprite>()
+            .register_type::<SpriteAnimation>()
             .register_type::<Anchor>()
+            .register_type::<SortMode2d>()
+            .register_type::<ParallaxLayer>()
+            .register_type::<ImageScaleMode>()
+            .register_type::<TextureSlicer>()
+            .register_type::<BorderRect>()
+            .register_type::<SliceScaleMode>()
             .register_type::<Mesh2dHandle>()
             .add_plugin(Mesh2dRenderPlugin)
-            .add_plugin(ColorMaterialPlugin);
+            .add_plugin(ColorMaterialPlugin)
+            .add_plugin(TilemapPlugin)
+            .add_plugin(PixelPerfectPlugin)
+            .add_plugin(ShapePlugin)
+            .add_event::<SpriteAnimationEvent>()
+            .add_system(play_sprite_animation_clips.before(animate_sprites))
+            .add_system(animate_sprites)
+            .add_system(pack_runtime_texture_atlases)
+            .add_system(
+                update_parallax_layers
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(TransformSystem::TransformPropagate),
+            )
+            .add_system(calculate_bounds_2d.in_set(VisibilitySystems::CalculateBounds));
 
         if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
@@ -68,6 +131,7 @@ impl Plugin for SpritePlugin {
                 .init_resource::<SpecializedRenderPipelines<SpritePipeline>>()
                 .init_resource::<SpriteMeta>()
                 .init_resource::<ExtractedSprites>()
+                .init_resource::<SpriteBatchStats>()
                 .init_resource::<SpriteAssetEvents>()
                 .add_render_command::<Transparent2d, DrawSprite>()
                 .add_systems(
@@ -85,3 +149,84 @@ impl Plugin for SpritePlugin {
         };
     }
 }
+
+/// The bounds of a sprite of `size`, positioned around its [`Anchor`] like when it's drawn.
+fn sprite_aabb(size: Vec2, anchor: &Anchor) -> Aabb {
+    Aabb {
+        center: (-anchor.as_vec() * size).extend(0.0).into(),
+        half_extents: (0.5 * size).extend(0.0).into(),
+    }
+}
+
+/// Sets the [`Aabb`] of each 2D mesh, [`Sprite`] and [`TextureAtlasSprite`], so that those
+/// outside of the view of every camera are culled by
+/// [`check_visibility`](bevy_render::view::check_visibility).
+///
+/// The bounds of sprites are updated when their size changes, but the bounds of meshes are only
+/// computed once. Entities with [`NoFrustumCulling`] are left untouched.
+#[allow(clippy::type_complexity)]
+pub fn calculate_bounds_2d(
+    mut commands: Commands,
+    meshes: Res<Assets<Mesh>>,
+    images: Res<Assets<Image>>,
+    atlases: Res<Assets<TextureAtlas>>,
+    meshes_without_aabb: Query<(Entity, &Mesh2dHandle), (Without<Aabb>, Without<NoFrustumCulling>)>,
+    mut sprites: Query<
+        (Entity, &Sprite, &Handle<Image>, Option<&mut Aabb>),
+        (
+            Without<NoFrustumCulling>,
+            Or<(Without<Aabb>, Changed<Sprite>, Changed<Handle<Image>>)>,
+        ),
+    >,
+    mut atlas_sprites: Query<
+        (
+            Entity,
+            &TextureAtlasSprite,
+            &Handle<TextureAtlas>,
+            Option<&mut Aabb>,
+        ),
+        (
+            Without<NoFrustumCulling>,
+            Without<Sprite>,
+            Or<(
+                Without<Aabb>,
+                Changed<TextureAtlasSprite>,
+                Changed<Handle<TextureAtlas>>,
+            )>,
+        ),
+    >,
+) {
+    for (entity, mesh_handle) in &meshes_without_aabb {
+        if let Some(aabb) = meshes.get(&mesh_handle.0).and_then(Mesh::compute_aabb) {
+            commands.entity(entity).insert(aabb);
+        }
+    }
+
+    let mut set_aabb = |entity: Entity, current: Option<Mut<Aabb>>, aabb: Aabb| match current {
+        Some(mut current) => *current = aabb,
+        None => {
+            commands.entity(entity).insert(aabb);
+        }
+    };
+    for (entity, sprite, image_handle, current) in &mut sprites {
+        // Sprites whose image isn't loaded yet are kept visible until it is
+        let size = sprite
+            .custom_size
+            .or_else(|| sprite.rect.map(|rect| rect.size()))
+            .or_else(|| images.get(image_handle).map(Image::size));
+        if let Some(size) = size {
+            set_aabb(entity, current, sprite_aabb(size, &sprite.anchor));
+        }
+    }
+    for (entity, atlas_sprite, atlas_handle, current) in &mut atlas_sprites {
+        let size = atlas_sprite.custom_size.or_else(|| {
+            atlases
+                .get(atlas_handle)
+                .and_then(|atlas| atlas.textures.get(atlas_sprite.index))
+                .map(|rect| rect.size())
+        });
+        if let Some(size) = size {
+            set_aabb(entity, current, sprite_aabb(size, &atlas_sprite.anchor));
+        }
+    }
+}
@@ -3,7 +3,11 @@ use bevy_asset::{load_internal_asset, AddAsset, Assets, Handle, HandleUntyped};
 use bevy_math::Vec4;
 use bevy_reflect::{prelude::*, TypeUuid};
 use bevy_render::{
-    color::Color, prelude::Shader, render_asset::RenderAssets, render_resource::*, texture::Image,
+    color::Color,
+    prelude::Shader,
+    render_asset::RenderAssets,
+    render_resource::*,
+    texture::{Flipbook, Image},
 };
 
 use crate::{Material2d, Material2dPlugin, MaterialMesh2dBundle};
@@ -39,6 +43,12 @@ impl Plugin for ColorMaterialPlugin {
 }
 
 /// A [2d material](Material2d) that renders [2d meshes](crate::Mesh2dHandle) with a texture tinted by a uniform color
+///
+/// The [`Mesh::ATTRIBUTE_COLOR`](bevy_render::mesh::Mesh::ATTRIBUTE_COLOR) vertex colors of the
+/// meshes tint it as well.
+///
+/// Unless it's [`unlit`](Self::unlit), the material is lit by the [`PointLight2d`](crate::PointLight2d)s
+/// and the [`AmbientLight2d`](crate::AmbientLight2d) of the camera.
 #[derive(AsBindGroup, Reflect, FromReflect, Debug, Clone, TypeUuid)]
 #[reflect(Default, Debug)]
 #[uuid = "e228a544-e3ca-4e1e-bb9d-4d8bc1ad8c19"]
@@ -48,6 +58,26 @@ pub struct ColorMaterial {
     #[texture(1)]
     #[sampler(2)]
     pub texture: Option<Handle<Image>>,
+    /// Plays the texture as a [`Flipbook`] animation on the GPU.
+    ///
+    /// The normal map and the emissive texture are played with it.
+    pub flipbook: Option<Flipbook>,
+    /// A tangent space normal map, with its green channel pointing up, lighting the slopes of the
+    /// texture facing the lights.
+    ///
+    /// The normal map must be in a linear format, not sRGB.
+    #[texture(3)]
+    #[sampler(4)]
+    pub normal_map_texture: Option<Handle<Image>>,
+    /// The color the material emits, added to its color after lighting so that it shows in the
+    /// dark.
+    pub emissive: Color,
+    /// The emissive color is multiplied by this texture.
+    #[texture(5)]
+    #[sampler(6)]
+    pub emissive_texture: Option<Handle<Image>>,
+    /// Ignores the lights, drawing the material with its color.
+    pub unlit: bool,
 }
 
 impl Default for ColorMaterial {
@@ -55,6 +85,11 @@ impl Default for ColorMaterial {
         ColorMaterial {
             color: Color::WHITE,
             texture: None,
+            flipbook: None,
+            normal_map_texture: None,
+            emissive: Color::BLACK,
+            emissive_texture: None,
+            unlit: false,
         }
     }
 }
@@ -82,6 +117,10 @@ bitflags::bitflags! {
     #[repr(transparent)]
     pub struct ColorMaterialFlags: u32 {
         const TEXTURE           = (1 << 0);
+        const FLIPBOOK          = (1 << 1);
+        const NORMAL_MAP        = (1 << 2);
+        const EMISSIVE_TEXTURE  = (1 << 3);
+        const UNLIT             = (1 << 4);
         const NONE              = 0;
         const UNINITIALIZED     = 0xFFFF;
     }
@@ -91,7 +130,14 @@ bitflags::bitflags! {
 #[derive(Clone, Default, ShaderType)]
 pub struct ColorMaterialUniform {
     pub color: Vec4,
+    pub emissive: Vec4,
     pub flags: u32,
+    pub flipbook_columns: u32,
+    pub flipbook_rows: u32,
+    pub flipbook_frame_count: u32,
+    pub flipbook_mode: u32,
+    pub flipbook_fps: f32,
+    pub flipbook_start_time: f32,
 }
 
 impl AsBindGroupShaderType<ColorMaterialUniform> for ColorMaterial {
@@ -100,10 +146,30 @@ impl AsBindGroupShaderType<ColorMaterialUniform> for ColorMaterial {
         if self.texture.is_some() {
             flags |= ColorMaterialFlags::TEXTURE;
         }
+        let flipbook = self.flipbook.unwrap_or_default();
+        if self.flipbook.is_some() {
+            flags |= ColorMaterialFlags::FLIPBOOK;
+        }
+        if self.normal_map_texture.is_some() {
+            flags |= ColorMaterialFlags::NORMAL_MAP;
+        }
+        if self.emissive_texture.is_some() {
+            flags |= ColorMaterialFlags::EMISSIVE_TEXTURE;
+        }
+        if self.unlit {
+            flags |= ColorMaterialFlags::UNLIT;
+        }
 
         ColorMaterialUniform {
             color: self.color.as_linear_rgba_f32().into(),
+            emissive: self.emissive.as_linear_rgba_f32().into(),
             flags: flags.bits(),
+            flipbook_columns: flipbook.columns,
+            flipbook_rows: flipbook.rows,
+            flipbook_frame_count: flipbook.frame_count,
+            flipbook_mode: flipbook.mode.as_u32(),
+            flipbook_fps: flipbook.fps,
+            flipbook_start_time: flipbook.start_time,
         }
     }
 }
@@ -112,6 +178,10 @@ impl Material2d for ColorMaterial {
     fn fragment_shader() -> ShaderRef {
         COLOR_MATERIAL_SHADER_HANDLE.typed().into()
     }
+
+    fn instancing() -> bool {
+        true
+    }
 }
 
 /// A component bundle for entities with a [`Mesh2dHandle`](crate::Mesh2dHandle) and a [`ColorMaterial`].
@@ -1,5 +1,7 @@
 #import bevy_sprite::mesh2d_types
 #import bevy_sprite::mesh2d_view_bindings
+#import bevy_render::flipbook
+#import bevy_sprite::mesh2d_lighting
 
 #ifdef TONEMAP_IN_SHADER
 #import bevy_core_pipeline::tonemapping
@@ -7,10 +9,21 @@
 
 struct ColorMaterial {
     color: vec4<f32>,
+    emissive: vec4<f32>,
     // 'flags' is a bit field indicating various options. u32 is 32 bits so we have up to 32 options.
     flags: u32,
+    flipbook_columns: u32,
+    flipbook_rows: u32,
+    flipbook_frame_count: u32,
+    flipbook_mode: u32,
+    flipbook_fps: f32,
+    flipbook_start_time: f32,
 };
-const COLOR_MATERIAL_FLAGS_TEXTURE_BIT: u32 = 1u;
+const COLOR_MATERIAL_FLAGS_TEXTURE_BIT: u32  = 1u;
+const COLOR_MATERIAL_FLAGS_FLIPBOOK_BIT: u32 = 2u;
+const COLOR_MATERIAL_FLAGS_NORMAL_MAP_BIT: u32 = 4u;
+const COLOR_MATERIAL_FLAGS_EMISSIVE_TEXTURE_BIT: u32 = 8u;
+const COLOR_MATERIAL_FLAGS_UNLIT_BIT: u32 = 16u;
 
 @group(1) @binding(0)
 var<uniform> material: ColorMaterial;
@@ -18,6 +31,14 @@ var<uniform> material: ColorMaterial;
 var texture: texture_2d<f32>;
 @group(1) @binding(2)
 var texture_sampler: sampler;
+@group(1) @binding(3)
+var normal_map_texture: texture_2d<f32>;
+@group(1) @binding(4)
+var normal_map_sampler: sampler;
+@group(1) @binding(5)
+var emissive_texture: texture_2d<f32>;
+@group(1) @binding(6)
+var emissive_sampler: sampler;
 
 @group(2) @binding(0)
 var<uniform> mesh: Mesh2d;
@@ -32,9 +53,45 @@ fn fragment(in: FragmentInput) -> @location(0) vec4<f32> {
 #ifdef VERTEX_COLORS
     output_color = output_color * in.color;
 #endif
+    var uv = in.uv;
+    if ((material.flags & COLOR_MATERIAL_FLAGS_FLIPBOOK_BIT) != 0u) {
+        let flipbook = Flipbook(
+            material.flipbook_columns,
+            material.flipbook_rows,
+            material.flipbook_frame_count,
+            material.flipbook_mode,
+            material.flipbook_fps,
+            material.flipbook_start_time,
+        );
+        uv = flipbook_uv(flipbook, uv, globals.time);
+    }
     if ((material.flags & COLOR_MATERIAL_FLAGS_TEXTURE_BIT) != 0u) {
-        output_color = output_color * textureSample(texture, texture_sampler, in.uv);
+        output_color = output_color * textureSample(texture, texture_sampler, uv);
+    }
+    if ((material.flags & COLOR_MATERIAL_FLAGS_UNLIT_BIT) == 0u) {
+        var normal = vec3<f32>(0.0, 0.0, 1.0);
+        if ((material.flags & COLOR_MATERIAL_FLAGS_NORMAL_MAP_BIT) != 0u) {
+            let normal_map = textureSample(normal_map_texture, normal_map_sampler, uv).rgb;
+#ifdef MESH2D_INSTANCING
+            let model = mat4x4<f32>(
+                vec4<f32>(in.instance_model_xy.xy, 0.0, 0.0),
+                vec4<f32>(in.instance_model_xy.zw, 0.0, 0.0),
+                vec4<f32>(0.0, 0.0, 1.0, 0.0),
+                vec4<f32>(0.0, 0.0, 0.0, 1.0)
+            );
+            normal = normal_map_2d(normal_map, model);
+#else
+            normal = normal_map_2d(normal_map, mesh.model);
+#endif
+        }
+        let light = lighting_2d(in.world_position.xy, normal);
+        output_color = vec4<f32>(output_color.rgb * light, output_color.a);
+    }
+    var emissive = material.emissive;
+    if ((material.flags & COLOR_MATERIAL_FLAGS_EMISSIVE_TEXTURE_BIT) != 0u) {
+        emissive = emissive * textureSample(emissive_texture, emissive_sampler, uv);
     }
+    output_color = vec4<f32>(output_color.rgb + emissive.rgb, output_color.a);
 #ifdef TONEMAP_IN_SHADER
     output_color = tone_mapping(output_color);
 #endif
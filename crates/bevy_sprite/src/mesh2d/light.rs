@@ -0,0 +1,187 @@
+use bevy_ecs::prelude::*;
+use bevy_math::{Vec3, Vec4};
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect};
+use bevy_render::{
+    camera::Camera,
+    color::Color,
+    render_resource::{DynamicUniformBuffer, ShaderType},
+    renderer::{RenderDevice, RenderQueue},
+    view::{ExtractedView, InheritedVisibility, ViewVisibility, Visibility},
+    Extract,
+};
+use bevy_transform::components::{GlobalTransform, Transform};
+
+/// The maximum number of [`PointLight2d`]s lighting a view, the ones closest to the view are used.
+pub const MAX_POINT_LIGHTS_2D: usize = 64;
+
+/// A light that shines in all directions around it, on the [2d meshes](crate::Mesh2dHandle) with
+/// a lit [`ColorMaterial`](crate::ColorMaterial).
+///
+/// The light is positioned by the `X` and `Y` of its [`GlobalTransform`], and shines from
+/// [`height`](Self::height) above the meshes, which lights the slopes of their normal maps facing
+/// it.
+#[derive(Component, Debug, Clone, Copy, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct PointLight2d {
+    pub color: Color,
+    /// The light is multiplied by this, and can go above 1.0 to overexpose the meshes.
+    pub intensity: f32,
+    /// The distance at which the light fades out entirely.
+    pub radius: f32,
+    /// The height of the light above the meshes, lower lights only light the slopes facing them.
+    pub height: f32,
+}
+
+impl Default for PointLight2d {
+    fn default() -> Self {
+        PointLight2d {
+            color: Color::WHITE,
+            intensity: 1.0,
+            radius: 250.0,
+            height: 50.0,
+        }
+    }
+}
+
+/// The light received by all the lit [2d meshes](crate::Mesh2dHandle) seen by a camera, in
+/// addition to the [`PointLight2d`]s.
+///
+/// Cameras without an [`AmbientLight2d`] light the meshes with the default, a white light of
+/// brightness 1.0, that leaves their color unchanged. Lower its brightness for point lights to
+/// stand out.
+#[derive(Component, Debug, Clone, Copy, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct AmbientLight2d {
+    pub color: Color,
+    pub brightness: f32,
+}
+
+impl Default for AmbientLight2d {
+    fn default() -> Self {
+        AmbientLight2d {
+            color: Color::WHITE,
+            brightness: 1.0,
+        }
+    }
+}
+
+/// A component bundle for [`PointLight2d`] entities.
+#[derive(Debug, Bundle, Default)]
+pub struct PointLight2dBundle {
+    pub point_light: PointLight2d,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    /// Enables or disables the light
+    pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+}
+
+/// The visible [`PointLight2d`]s, extracted to the render world.
+#[derive(Resource, Default)]
+pub struct ExtractedPointLights2d {
+    pub lights: Vec<(Vec3, PointLight2d)>,
+}
+
+pub fn extract_lights_2d(
+    mut commands: Commands,
+    mut extracted_point_lights: ResMut<ExtractedPointLights2d>,
+    mut previous_len: Local<usize>,
+    point_lights: Extract<Query<(&PointLight2d, &GlobalTransform, &ViewVisibility)>>,
+    ambient_lights: Extract<Query<(Entity, &AmbientLight2d), With<Camera>>>,
+) {
+    extracted_point_lights.lights.clear();
+    extracted_point_lights.lights.extend(
+        point_lights
+            .iter()
+            .filter(|(_, _, view_visibility)| view_visibility.get())
+            .map(|(light, transform, _)| (transform.translation(), *light)),
+    );
+
+    let mut values = Vec::with_capacity(*previous_len);
+    for (entity, ambient_light) in &ambient_lights {
+        values.push((entity, *ambient_light));
+    }
+    *previous_len = values.len();
+    commands.insert_or_spawn_batch(values);
+}
+
+/// The GPU representation of a [`PointLight2d`].
+#[derive(Clone, Copy, Default, ShaderType)]
+pub struct GpuPointLight2d {
+    /// The position of the light, its height, and its radius.
+    pub position_radius: Vec4,
+    /// The color of the light, multiplied by its intensity.
+    pub color: Vec4,
+}
+
+/// The lights of a view, in the `lights` uniform of the 2d view bind group.
+#[derive(Clone, ShaderType)]
+pub struct GpuLights2d {
+    pub point_lights: [GpuPointLight2d; MAX_POINT_LIGHTS_2D],
+    pub ambient: Vec4,
+    pub point_light_count: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct Lights2dUniforms {
+    pub uniforms: DynamicUniformBuffer<GpuLights2d>,
+}
+
+#[derive(Component)]
+pub struct ViewLights2dUniformOffset {
+    pub offset: u32,
+}
+
+/// Writes the lights of each view, keeping the [`MAX_POINT_LIGHTS_2D`] point lights whose radius
+/// is the closest to the view.
+pub fn prepare_lights_2d(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut lights_uniforms: ResMut<Lights2dUniforms>,
+    mut extracted_point_lights: ResMut<ExtractedPointLights2d>,
+    views: Query<(Entity, &ExtractedView, Option<&AmbientLight2d>)>,
+) {
+    lights_uniforms.uniforms.clear();
+    for (entity, view, ambient_light) in &views {
+        let view_position = view.transform.translation().truncate();
+        let point_lights = &mut extracted_point_lights.lights;
+        if point_lights.len() > MAX_POINT_LIGHTS_2D {
+            point_lights.sort_by(|(a_position, a), (b_position, b)| {
+                let a_distance = a_position.truncate().distance(view_position) - a.radius;
+                let b_distance = b_position.truncate().distance(view_position) - b.radius;
+                a_distance.total_cmp(&b_distance)
+            });
+        }
+
+        let mut gpu_lights = GpuLights2d {
+            point_lights: [GpuPointLight2d::default(); MAX_POINT_LIGHTS_2D],
+            ambient: Vec4::ZERO,
+            point_light_count: point_lights.len().min(MAX_POINT_LIGHTS_2D) as u32,
+        };
+        for ((position, light), gpu_light) in point_lights.iter().zip(&mut gpu_lights.point_lights)
+        {
+            *gpu_light = GpuPointLight2d {
+                position_radius: position
+                    .truncate()
+                    .extend(light.height.max(f32::EPSILON))
+                    .extend(light.radius.max(f32::EPSILON)),
+                color: Vec4::from_slice(&light.color.as_linear_rgba_f32()) * light.intensity,
+            };
+        }
+        let ambient_light = ambient_light.copied().unwrap_or_default();
+        gpu_lights.ambient =
+            Vec4::from_slice(&ambient_light.color.as_linear_rgba_f32()) * ambient_light.brightness;
+
+        commands.entity(entity).insert(ViewLights2dUniformOffset {
+            offset: lights_uniforms.uniforms.push(gpu_lights),
+        });
+    }
+
+    lights_uniforms
+        .uniforms
+        .write_buffer(&render_device, &render_queue);
+}
@@ -14,24 +14,25 @@ use bevy_ecs::{
     },
 };
 use bevy_log::error;
+use bevy_math::Vec4;
 use bevy_reflect::TypeUuid;
 use bevy_render::{
-    extract_component::ExtractComponentPlugin,
-    mesh::{Mesh, MeshVertexBufferLayout},
+    extract_component::{DynamicUniformIndex, ExtractComponentPlugin},
+    mesh::{GpuBufferInfo, Mesh, MeshVertexBufferLayout},
     prelude::Image,
     render_asset::{PrepareAssetSet, RenderAssets},
     render_phase::{
-        AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
-        RenderPhase, SetItemPipeline, TrackedRenderPass,
+        AddRenderCommand, BatchedPhaseItem, DrawFunctions, PhaseItem, RenderCommand,
+        RenderCommandResult, RenderPhase, SetItemPipeline, TrackedRenderPass,
     },
     render_resource::{
-        AsBindGroup, AsBindGroupError, BindGroup, BindGroupLayout, OwnedBindingResource,
-        PipelineCache, RenderPipelineDescriptor, Shader, ShaderRef, SpecializedMeshPipeline,
-        SpecializedMeshPipelineError, SpecializedMeshPipelines,
+        AsBindGroup, AsBindGroupError, BindGroup, BindGroupLayout, BufferUsages, BufferVec,
+        OwnedBindingResource, PipelineCache, RenderPipelineDescriptor, Shader, ShaderRef,
+        SpecializedMeshPipeline, SpecializedMeshPipelineError, SpecializedMeshPipelines,
     },
-    renderer::RenderDevice,
+    renderer::{RenderDevice, RenderQueue},
     texture::FallbackImage,
-    view::{ComputedVisibility, ExtractedView, Msaa, Visibility, VisibleEntities},
+    view::{ExtractedView, InheritedVisibility, Msaa, ViewVisibility, Visibility, VisibleEntities},
     Extract, ExtractSchedule, RenderApp, RenderSet,
 };
 use bevy_transform::components::{GlobalTransform, Transform};
@@ -40,8 +41,8 @@ use std::hash::Hash;
 use std::marker::PhantomData;
 
 use crate::{
-    DrawMesh2d, Mesh2dHandle, Mesh2dPipeline, Mesh2dPipelineKey, Mesh2dUniform, SetMesh2dBindGroup,
-    SetMesh2dViewBindGroup,
+    DrawMesh2d, Mesh2dHandle, Mesh2dInstance, Mesh2dInstanceData, Mesh2dPipeline,
+    Mesh2dPipelineKey, Mesh2dUniform, SetMesh2dBindGroup, SetMesh2dViewBindGroup,
 };
 
 /// Materials are used alongside [`Material2dPlugin`] and [`MaterialMesh2dBundle`]
@@ -124,6 +125,19 @@ pub trait Material2d: AsBindGroup + Send + Sync + Clone + TypeUuid + Sized + 'st
         ShaderRef::Default
     }
 
+    /// Returns `true` to draw the entities with the same material and mesh in a single instanced
+    /// draw call, instead of one draw call per entity.
+    ///
+    /// The vertex shader then reads the model matrix of each entity from instance attributes
+    /// instead of the `mesh` uniform, and passes the [`Mesh2dInstanceData`] of the entity to the
+    /// fragment shader as the `instance_data` of the vertex output. The `mesh` uniform should not
+    /// be used by the shaders of instanced materials, as it only holds the first instance of the
+    /// batch. With [`ShaderRef::Default`] as the vertex shader, the `MESH2D_INSTANCING` shader
+    /// def is set for the instanced pipelines.
+    fn instancing() -> bool {
+        false
+    }
+
     /// Customizes the default [`RenderPipelineDescriptor`].
     #[allow(unused_variables)]
     #[inline]
@@ -157,7 +171,9 @@ where
         if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .add_render_command::<Transparent2d, DrawMaterial2d<M>>()
+                .add_render_command::<Transparent2d, DrawMaterial2dInstanced<M>>()
                 .init_resource::<Material2dPipeline<M>>()
+                .init_resource::<Material2dInstances<M>>()
                 .init_resource::<ExtractedMaterials2d<M>>()
                 .init_resource::<RenderMaterials2d<M>>()
                 .init_resource::<SpecializedMeshPipelines<Material2dPipeline<M>>>()
@@ -294,6 +310,14 @@ type DrawMaterial2d<M> = (
     DrawMesh2d,
 );
 
+type DrawMaterial2dInstanced<M> = (
+    SetItemPipeline,
+    SetMesh2dViewBindGroup<0>,
+    SetMaterial2dBindGroup<M, 1>,
+    SetMesh2dBindGroup<2>,
+    DrawMesh2dInstanced<M>,
+);
+
 pub struct SetMaterial2dBindGroup<M: Material2d, const I: usize>(PhantomData<M>);
 impl<P: PhaseItem, M: Material2d, const I: usize> RenderCommand<P>
     for SetMaterial2dBindGroup<M, I>
@@ -316,18 +340,87 @@ impl<P: PhaseItem, M: Material2d, const I: usize> RenderCommand<P>
     }
 }
 
-#[allow(clippy::too_many_arguments)]
+/// Draws the instances of a batch of entities with an [instanced](Material2d::instancing)
+/// material, in the range of the [`Material2dInstances`] of the phase item.
+pub struct DrawMesh2dInstanced<M: Material2d>(PhantomData<M>);
+impl<P: BatchedPhaseItem, M: Material2d> RenderCommand<P> for DrawMesh2dInstanced<M> {
+    type Param = (SRes<RenderAssets<Mesh>>, SRes<Material2dInstances<M>>);
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<Mesh2dHandle>;
+
+    #[inline]
+    fn render<'w>(
+        item: &P,
+        _view: (),
+        mesh_handle: ROQueryItem<'w, Self::ItemWorldQuery>,
+        (meshes, instances): SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let (Some(gpu_mesh), Some(instance_buffer), Some(instance_range)) = (
+            meshes.into_inner().get(&mesh_handle.0),
+            instances.into_inner().instances.buffer(),
+            item.batch_range().clone(),
+        ) else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_vertex_buffer(0, gpu_mesh.vertex_buffer.slice(..));
+        pass.set_vertex_buffer(1, instance_buffer.slice(..));
+        match &gpu_mesh.buffer_info {
+            GpuBufferInfo::Indexed {
+                buffer,
+                index_format,
+                count,
+            } => {
+                pass.set_index_buffer(buffer.slice(..), 0, *index_format);
+                pass.draw_indexed(0..*count, 0, instance_range);
+            }
+            GpuBufferInfo::NonIndexed { vertex_count } => {
+                pass.draw(0..*vertex_count, instance_range);
+            }
+        }
+        RenderCommandResult::Success
+    }
+}
+
+/// The [`Mesh2dInstance`]s of the entities drawn with an [instanced](Material2d::instancing)
+/// material this frame, in every view.
+#[derive(Resource)]
+pub struct Material2dInstances<M: Material2d> {
+    instances: BufferVec<Mesh2dInstance>,
+    marker: PhantomData<M>,
+}
+
+impl<M: Material2d> Default for Material2dInstances<M> {
+    fn default() -> Self {
+        Self {
+            instances: BufferVec::new(BufferUsages::VERTEX),
+            marker: PhantomData,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
 pub fn queue_material2d_meshes<M: Material2d>(
+    mut commands: Commands,
     transparent_draw_functions: Res<DrawFunctions<Transparent2d>>,
     material2d_pipeline: Res<Material2dPipeline<M>>,
     mut pipelines: ResMut<SpecializedMeshPipelines<Material2dPipeline<M>>>,
     pipeline_cache: Res<PipelineCache>,
-    msaa: Res<Msaa>,
     render_meshes: Res<RenderAssets<Mesh>>,
     render_materials: Res<RenderMaterials2d<M>>,
-    material2d_meshes: Query<(&Handle<M>, &Mesh2dHandle, &Mesh2dUniform)>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut material2d_instances: ResMut<Material2dInstances<M>>,
+    material2d_meshes: Query<(
+        &Handle<M>,
+        &Mesh2dHandle,
+        &Mesh2dUniform,
+        &DynamicUniformIndex<Mesh2dUniform>,
+        Option<&Mesh2dInstanceData>,
+    )>,
     mut views: Query<(
         &ExtractedView,
+        &Msaa,
         &VisibleEntities,
         Option<&Tonemapping>,
         Option<&DebandDither>,
@@ -339,12 +432,22 @@ pub fn queue_material2d_meshes<M: Material2d>(
     if material2d_meshes.is_empty() {
         return;
     }
+    material2d_instances.instances.clear();
 
-    for (view, visible_entities, tonemapping, dither, mut transparent_phase) in &mut views {
+    for (view, msaa, visible_entities, tonemapping, dither, mut transparent_phase) in &mut views {
         let draw_transparent_pbr = transparent_draw_functions.read().id::<DrawMaterial2d<M>>();
+        let draw_instanced = transparent_draw_functions
+            .read()
+            .id::<DrawMaterial2dInstanced<M>>();
+        // The entity shared by the phase items of each material and mesh, so that they are
+        // merged by `batch_phase_system()` when they are drawn one after another
+        let mut batch_entities = HashMap::<(Handle<M>, Handle<Mesh>), Entity>::default();
 
         let mut view_key = Mesh2dPipelineKey::from_msaa_samples(msaa.samples())
             | Mesh2dPipelineKey::from_hdr(view.hdr);
+        if M::instancing() {
+            view_key |= Mesh2dPipelineKey::INSTANCED;
+        }
 
         if !view.hdr {
             if let Some(tonemapping) = tonemapping {
@@ -370,7 +473,7 @@ pub fn queue_material2d_meshes<M: Material2d>(
         }
 
         for visible_entity in &visible_entities.entities {
-            if let Ok((material2d_handle, mesh2d_handle, mesh2d_uniform)) =
+            if let Ok((material2d_handle, mesh2d_handle, mesh2d_uniform, uniform_index, data)) =
                 material2d_meshes.get(*visible_entity)
             {
                 if let Some(material2d) = render_materials.get(material2d_handle) {
@@ -397,23 +500,55 @@ pub fn queue_material2d_meshes<M: Material2d>(
                         };
 
                         let mesh_z = mesh2d_uniform.transform.w_axis.z;
+                        // NOTE: Back-to-front ordering for transparent with ascending sort means far should have the
+                        // lowest sort key and getting closer should increase. As we have
+                        // -z in front of the camera, the largest distance is -far with values increasing toward the
+                        // camera. As such we can just use mesh_z as the distance
+                        let sort_key = FloatOrd(mesh_z);
+                        if !M::instancing() {
+                            transparent_phase.add(Transparent2d {
+                                entity: *visible_entity,
+                                draw_function: draw_transparent_pbr,
+                                pipeline: pipeline_id,
+                                sort_key,
+                                // This material is not batched
+                                batch_range: None,
+                            });
+                            continue;
+                        }
+
+                        let instance = Mesh2dInstance::new(
+                            mesh2d_uniform,
+                            data.map_or(Vec4::ZERO, |data| data.0),
+                        );
+                        let index = material2d_instances.instances.push(instance) as u32;
+                        let batch_entity = *batch_entities
+                            .entry((material2d_handle.clone_weak(), mesh2d_handle.0.clone_weak()))
+                            .or_insert_with(|| {
+                                commands
+                                    .spawn((
+                                        material2d_handle.clone_weak(),
+                                        Mesh2dHandle(mesh2d_handle.0.clone_weak()),
+                                        // The `mesh` uniform of the batch is its first instance
+                                        *uniform_index,
+                                    ))
+                                    .id()
+                            });
                         transparent_phase.add(Transparent2d {
-                            entity: *visible_entity,
-                            draw_function: draw_transparent_pbr,
+                            entity: batch_entity,
+                            draw_function: draw_instanced,
                             pipeline: pipeline_id,
-                            // NOTE: Back-to-front ordering for transparent with ascending sort means far should have the
-                            // lowest sort key and getting closer should increase. As we have
-                            // -z in front of the camera, the largest distance is -far with values increasing toward the
-                            // camera. As such we can just use mesh_z as the distance
-                            sort_key: FloatOrd(mesh_z),
-                            // This material is not batched
-                            batch_range: None,
+                            sort_key,
+                            batch_range: Some(index..index + 1),
                         });
                     }
                 }
             }
         }
     }
+    material2d_instances
+        .instances
+        .write_buffer(&render_device, &render_queue);
 }
 
 /// Data prepared for a [`Material2d`] instance.
@@ -466,6 +601,7 @@ fn extract_materials_2d<M: Material2d>(
                 changed_assets.remove(handle);
                 removed.push(handle.clone_weak());
             }
+            AssetEvent::LoadedWithDependencies { .. } => {}
         }
     }
 
@@ -575,8 +711,10 @@ pub struct MaterialMesh2dBundle<M: Material2d> {
     pub global_transform: GlobalTransform,
     /// User indication of whether an entity is visible
     pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
     /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
-    pub computed_visibility: ComputedVisibility,
+    pub view_visibility: ViewVisibility,
 }
 
 impl<M: Material2d> Default for MaterialMesh2dBundle<M> {
@@ -587,7 +725,8 @@ impl<M: Material2d> Default for MaterialMesh2dBundle<M> {
             transform: Default::default(),
             global_transform: Default::default(),
             visibility: Default::default(),
-            computed_visibility: Default::default(),
+            inherited_visibility: Default::default(),
+            view_visibility: Default::default(),
         }
     }
 }
@@ -1,4 +1,4 @@
-use bevy_app::{IntoSystemAppConfig, Plugin};
+use bevy_app::{IntoSystemAppConfigs, Plugin};
 use bevy_asset::{load_internal_asset, Handle, HandleUntyped};
 
 use bevy_ecs::{
@@ -6,10 +6,13 @@ use bevy_ecs::{
     query::ROQueryItem,
     system::{lifetimeless::*, SystemParamItem, SystemState},
 };
-use bevy_math::{Mat4, Vec2};
-use bevy_reflect::{Reflect, TypeUuid};
+use bevy_math::{Mat4, Vec2, Vec4};
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect, TypeUuid};
 use bevy_render::{
-    extract_component::{ComponentUniforms, DynamicUniformIndex, UniformComponentPlugin},
+    extract_component::{
+        ComponentUniforms, DynamicUniformIndex, ExtractComponent, ExtractComponentPlugin,
+        UniformComponentPlugin,
+    },
     globals::{GlobalsBuffer, GlobalsUniform},
     mesh::{GpuBufferInfo, Mesh, MeshVertexBufferLayout},
     render_asset::RenderAssets,
@@ -20,11 +23,17 @@ use bevy_render::{
         BevyDefault, DefaultImageSampler, GpuImage, Image, ImageSampler, TextureFormatPixelInfo,
     },
     view::{
-        ComputedVisibility, ExtractedView, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms,
+        ExtractedView, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms, ViewVisibility,
     },
     Extract, ExtractSchedule, RenderApp, RenderSet,
 };
 use bevy_transform::components::GlobalTransform;
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    extract_lights_2d, prepare_lights_2d, AmbientLight2d, ExtractedPointLights2d, GpuLights2d,
+    Lights2dUniforms, PointLight2d, ViewLights2dUniformOffset,
+};
 
 /// Component for rendering with meshes in the 2d pipeline, usually with a [2d material](crate::Material2d) such as [`ColorMaterial`](crate::ColorMaterial).
 ///
@@ -56,6 +65,8 @@ pub const MESH2D_FUNCTIONS_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 4976379308250389413);
 pub const MESH2D_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 2971387252468633715);
+pub const MESH2D_LIGHTING_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 11154594026040945558);
 
 impl Plugin for Mesh2dRenderPlugin {
     fn build(&self, app: &mut bevy_app::App) {
@@ -96,14 +107,27 @@ impl Plugin for Mesh2dRenderPlugin {
             Shader::from_wgsl
         );
         load_internal_asset!(app, MESH2D_SHADER_HANDLE, "mesh2d.wgsl", Shader::from_wgsl);
+        load_internal_asset!(
+            app,
+            MESH2D_LIGHTING_HANDLE,
+            "mesh2d_lighting.wgsl",
+            Shader::from_wgsl
+        );
 
-        app.add_plugin(UniformComponentPlugin::<Mesh2dUniform>::default());
+        app.register_type::<PointLight2d>()
+            .register_type::<AmbientLight2d>()
+            .register_type::<Mesh2dInstanceData>()
+            .add_plugin(UniformComponentPlugin::<Mesh2dUniform>::default())
+            .add_plugin(ExtractComponentPlugin::<Mesh2dInstanceData>::extract_visible());
 
         if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
             render_app
                 .init_resource::<Mesh2dPipeline>()
                 .init_resource::<SpecializedMeshPipelines<Mesh2dPipeline>>()
-                .add_system(extract_mesh2d.in_schedule(ExtractSchedule))
+                .init_resource::<ExtractedPointLights2d>()
+                .init_resource::<Lights2dUniforms>()
+                .add_systems((extract_mesh2d, extract_lights_2d).in_schedule(ExtractSchedule))
+                .add_system(prepare_lights_2d.in_set(RenderSet::Prepare))
                 .add_system(queue_mesh2d_bind_group.in_set(RenderSet::Queue))
                 .add_system(queue_mesh2d_view_bind_groups.in_set(RenderSet::Queue));
         }
@@ -117,6 +141,66 @@ pub struct Mesh2dUniform {
     pub flags: u32,
 }
 
+/// Data passed to the shaders of an [instanced](crate::Material2d::instancing) 2d material for
+/// each entity, as the `instance_data` of the vertex output.
+///
+/// Entities without this component pass zeros.
+#[derive(
+    Component, Clone, Copy, Debug, Default, PartialEq, Reflect, FromReflect, ExtractComponent,
+)]
+#[reflect(Component, Default)]
+pub struct Mesh2dInstanceData(pub Vec4);
+
+/// The per-instance vertex attributes of an entity drawn with an
+/// [instanced](crate::Material2d::instancing) 2d material.
+#[repr(C)]
+#[derive(Copy, Clone, Debug, Pod, Zeroable)]
+pub struct Mesh2dInstance {
+    /// The columns of the affine part of the model matrix.
+    pub model: [[f32; 3]; 4],
+    /// The columns of the inverse transpose of the linear part of the model matrix, for normals.
+    pub inverse_transpose_model: [[f32; 3]; 3],
+    pub data: [f32; 4],
+}
+
+impl Mesh2dInstance {
+    /// The location of the first instance attribute in the vertex shader, after the ones of the
+    /// mesh.
+    pub const SHADER_LOCATION: u32 = 5;
+
+    pub fn new(uniform: &Mesh2dUniform, data: Vec4) -> Self {
+        let model = uniform.transform;
+        let inverse_transpose_model = uniform.inverse_transpose_model;
+        Mesh2dInstance {
+            model: [
+                model.x_axis.truncate().into(),
+                model.y_axis.truncate().into(),
+                model.z_axis.truncate().into(),
+                model.w_axis.truncate().into(),
+            ],
+            inverse_transpose_model: [
+                inverse_transpose_model.x_axis.truncate().into(),
+                inverse_transpose_model.y_axis.truncate().into(),
+                inverse_transpose_model.z_axis.truncate().into(),
+            ],
+            data: data.into(),
+        }
+    }
+
+    fn vertex_buffer_layout() -> VertexBufferLayout {
+        let mut layout = VertexBufferLayout::from_vertex_formats(
+            VertexStepMode::Instance,
+            [VertexFormat::Float32x3; 7]
+                .into_iter()
+                .chain([VertexFormat::Float32x4]),
+        );
+        for attribute in &mut layout.attributes {
+            attribute.shader_location += Self::SHADER_LOCATION;
+        }
+        layout
+    }
+}
+
 // NOTE: These must match the bit flags in bevy_sprite/src/mesh2d/mesh2d.wgsl!
 bitflags::bitflags! {
     #[repr(transparent)]
@@ -129,11 +213,11 @@ bitflags::bitflags! {
 pub fn extract_mesh2d(
     mut commands: Commands,
     mut previous_len: Local<usize>,
-    query: Extract<Query<(Entity, &ComputedVisibility, &GlobalTransform, &Mesh2dHandle)>>,
+    query: Extract<Query<(Entity, &ViewVisibility, &GlobalTransform, &Mesh2dHandle)>>,
 ) {
     let mut values = Vec::with_capacity(*previous_len);
-    for (entity, computed_visibility, transform, handle) in &query {
-        if !computed_visibility.is_visible() {
+    for (entity, view_visibility, transform, handle) in &query {
+        if !view_visibility.get() {
             continue;
         }
         let transform = transform.compute_matrix();
@@ -189,6 +273,17 @@ impl FromWorld for Mesh2dPipeline {
                     },
                     count: None,
                 },
+                // Lights
+                BindGroupLayoutEntry {
+                    binding: 2,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Buffer {
+                        ty: BufferBindingType::Uniform,
+                        has_dynamic_offset: true,
+                        min_binding_size: Some(GpuLights2d::min_size()),
+                    },
+                    count: None,
+                },
             ],
             label: Some("mesh2d_view_layout"),
         });
@@ -292,6 +387,7 @@ bitflags::bitflags! {
         const HDR                               = (1 << 0);
         const TONEMAP_IN_SHADER                 = (1 << 1);
         const DEBAND_DITHER                     = (1 << 2);
+        const INSTANCED                         = (1 << 3);
         const MSAA_RESERVED_BITS                = Self::MSAA_MASK_BITS << Self::MSAA_SHIFT_BITS;
         const PRIMITIVE_TOPOLOGY_RESERVED_BITS  = Self::PRIMITIVE_TOPOLOGY_MASK_BITS << Self::PRIMITIVE_TOPOLOGY_SHIFT_BITS;
         const TONEMAP_METHOD_RESERVED_BITS      = Self::TONEMAP_METHOD_MASK_BITS << Self::TONEMAP_METHOD_SHIFT_BITS;
@@ -420,7 +516,11 @@ impl SpecializedMeshPipeline for Mesh2dPipeline {
             }
         }
 
-        let vertex_buffer_layout = layout.get_layout(&vertex_attributes)?;
+        let mut buffers = vec![layout.get_layout(&vertex_attributes)?];
+        if key.contains(Mesh2dPipelineKey::INSTANCED) {
+            shader_defs.push("MESH2D_INSTANCING".into());
+            buffers.push(Mesh2dInstance::vertex_buffer_layout());
+        }
 
         let format = match key.contains(Mesh2dPipelineKey::HDR) {
             true => ViewTarget::TEXTURE_FORMAT_HDR,
@@ -432,7 +532,7 @@ impl SpecializedMeshPipeline for Mesh2dPipeline {
                 shader: MESH2D_SHADER_HANDLE.typed::<Shader>(),
                 entry_point: "vertex".into(),
                 shader_defs: shader_defs.clone(),
-                buffers: vec![vertex_buffer_layout],
+                buffers,
             },
             fragment: Some(FragmentState {
                 shader: MESH2D_SHADER_HANDLE.typed::<Shader>(),
@@ -503,10 +603,12 @@ pub fn queue_mesh2d_view_bind_groups(
     view_uniforms: Res<ViewUniforms>,
     views: Query<Entity, With<ExtractedView>>,
     globals_buffer: Res<GlobalsBuffer>,
+    lights_uniforms: Res<Lights2dUniforms>,
 ) {
-    if let (Some(view_binding), Some(globals)) = (
+    if let (Some(view_binding), Some(globals), Some(lights)) = (
         view_uniforms.uniforms.binding(),
         globals_buffer.buffer.binding(),
+        lights_uniforms.uniforms.binding(),
     ) {
         for entity in &views {
             let view_bind_group = render_device.create_bind_group(&BindGroupDescriptor {
@@ -519,6 +621,10 @@ pub fn queue_mesh2d_view_bind_groups(
                         binding: 1,
                         resource: globals.clone(),
                     },
+                    BindGroupEntry {
+                        binding: 2,
+                        resource: lights.clone(),
+                    },
                 ],
                 label: Some("mesh2d_view_bind_group"),
                 layout: &mesh2d_pipeline.view_layout,
@@ -534,18 +640,26 @@ pub fn queue_mesh2d_view_bind_groups(
 pub struct SetMesh2dViewBindGroup<const I: usize>;
 impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetMesh2dViewBindGroup<I> {
     type Param = ();
-    type ViewWorldQuery = (Read<ViewUniformOffset>, Read<Mesh2dViewBindGroup>);
+    type ViewWorldQuery = (
+        Read<ViewUniformOffset>,
+        Read<ViewLights2dUniformOffset>,
+        Read<Mesh2dViewBindGroup>,
+    );
     type ItemWorldQuery = ();
 
     #[inline]
     fn render<'w>(
         _item: &P,
-        (view_uniform, mesh2d_view_bind_group): ROQueryItem<'w, Self::ViewWorldQuery>,
+        (view_uniform, view_lights, mesh2d_view_bind_group): ROQueryItem<'w, Self::ViewWorldQuery>,
         _view: (),
         _param: SystemParamItem<'w, '_, Self::Param>,
         pass: &mut TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
-        pass.set_bind_group(I, &mesh2d_view_bind_group.value, &[view_uniform.offset]);
+        pass.set_bind_group(
+            I,
+            &mesh2d_view_bind_group.value,
+            &[view_uniform.offset, view_lights.offset],
+        );
 
         RenderCommandResult::Success
     }
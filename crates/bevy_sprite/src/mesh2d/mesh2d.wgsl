@@ -24,6 +24,17 @@ struct Vertex {
 #ifdef VERTEX_COLORS
     @location(4) color: vec4<f32>,
 #endif
+#ifdef MESH2D_INSTANCING
+    // NOTE: These must match the layout of `Mesh2dInstance` in bevy_sprite/src/mesh2d/mesh.rs!
+    @location(5) instance_model_x: vec3<f32>,
+    @location(6) instance_model_y: vec3<f32>,
+    @location(7) instance_model_z: vec3<f32>,
+    @location(8) instance_translation: vec3<f32>,
+    @location(9) instance_inverse_transpose_model_x: vec3<f32>,
+    @location(10) instance_inverse_transpose_model_y: vec3<f32>,
+    @location(11) instance_inverse_transpose_model_z: vec3<f32>,
+    @location(12) instance_data: vec4<f32>,
+#endif
 };
 
 struct VertexOutput {
@@ -35,21 +46,42 @@ struct VertexOutput {
 fn vertex(vertex: Vertex) -> VertexOutput {
     var out: VertexOutput;
 
+#ifdef MESH2D_INSTANCING
+    let model = mat4x4<f32>(
+        vec4<f32>(vertex.instance_model_x, 0.0),
+        vec4<f32>(vertex.instance_model_y, 0.0),
+        vec4<f32>(vertex.instance_model_z, 0.0),
+        vec4<f32>(vertex.instance_translation, 1.0)
+    );
+    out.instance_data = vertex.instance_data;
+    out.instance_model_xy = vec4<f32>(model[0].xy, model[1].xy);
+#else
+    let model = mesh.model;
+#endif
+
 #ifdef VERTEX_UVS
     out.uv = vertex.uv;
 #endif
 
 #ifdef VERTEX_POSITIONS
-    out.world_position = mesh2d_position_local_to_world(mesh.model, vec4<f32>(vertex.position, 1.0));
+    out.world_position = mesh2d_position_local_to_world(model, vec4<f32>(vertex.position, 1.0));
     out.clip_position = mesh2d_position_world_to_clip(out.world_position);
 #endif
 
 #ifdef VERTEX_NORMALS
+#ifdef MESH2D_INSTANCING
+    out.world_normal = mat3x3<f32>(
+        vertex.instance_inverse_transpose_model_x,
+        vertex.instance_inverse_transpose_model_y,
+        vertex.instance_inverse_transpose_model_z
+    ) * vertex.normal;
+#else
     out.world_normal = mesh2d_normal_local_to_world(vertex.normal);
 #endif
+#endif
 
 #ifdef VERTEX_TANGENTS
-    out.world_tangent = mesh2d_tangent_local_to_world(mesh.model, vertex.tangent);
+    out.world_tangent = mesh2d_tangent_local_to_world(model, vertex.tangent);
 #endif
 
 #ifdef VERTEX_COLORS
@@ -0,0 +1,32 @@
+#define_import_path bevy_sprite::mesh2d_lighting
+
+// The light received at `world_position` on a surface with `normal`, from the ambient light and
+// the point lights of the view. The normal of flat surfaces faces the camera, along +Z.
+fn lighting_2d(world_position: vec2<f32>, normal: vec3<f32>) -> vec3<f32> {
+    var light = lights.ambient.rgb;
+    for (var i = 0u; i < lights.point_light_count; i = i + 1u) {
+        let point_light = lights.point_lights[i];
+        let to_light = vec3<f32>(
+            point_light.position_radius.xy - world_position,
+            point_light.position_radius.z
+        );
+        let distance = length(to_light.xy) / point_light.position_radius.w;
+        let falloff = saturate(1.0 - distance * distance);
+        let diffuse = max(dot(normal, normalize(to_light)), 0.0);
+        light = light + point_light.color.rgb * falloff * falloff * diffuse;
+    }
+    return light;
+}
+
+// The normal of a surface from a tangent space `normal_map` sample, rotated with the `model`
+// matrix of the mesh.
+fn normal_map_2d(normal_map: vec3<f32>, model: mat4x4<f32>) -> vec3<f32> {
+    let normal = normal_map * 2.0 - 1.0;
+    let rotated = (model * vec4<f32>(normal.xy, 0.0, 0.0)).xy;
+    var xy = vec2<f32>(0.0);
+    if (dot(rotated, rotated) > 0.0) {
+        // Only the direction changes, the scale of the mesh doesn't flatten the normal
+        xy = normalize(rotated) * length(normal.xy);
+    }
+    return normalize(vec3<f32>(xy, normal.z));
+}
@@ -9,3 +9,8 @@
 #ifdef VERTEX_COLORS
 @location(4) color: vec4<f32>,
 #endif
+#ifdef MESH2D_INSTANCING
+@location(5) @interpolate(flat) instance_data: vec4<f32>,
+// The X and Y axes of the model matrix in the XY plane, for normal maps
+@location(6) @interpolate(flat) instance_model_xy: vec4<f32>,
+#endif
@@ -7,3 +7,6 @@ var<uniform> view: View;
 
 @group(0) @binding(1)
 var<uniform> globals: Globals;
+
+@group(0) @binding(2)
+var<uniform> lights: Lights2d;
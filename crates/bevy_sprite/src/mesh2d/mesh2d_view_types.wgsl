@@ -2,3 +2,17 @@
 
 #import bevy_render::view
 #import bevy_render::globals
+
+struct PointLight2d {
+    // xy: the position of the light, z: its height above the meshes, w: its radius
+    position_radius: vec4<f32>,
+    // The color of the light, multiplied by its intensity
+    color: vec4<f32>,
+};
+
+struct Lights2d {
+    // NOTE: this must be kept in sync with MAX_POINT_LIGHTS_2D
+    point_lights: array<PointLight2d, 64u>,
+    ambient: vec4<f32>,
+    point_light_count: u32,
+};
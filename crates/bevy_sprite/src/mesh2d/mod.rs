@@ -1,7 +1,9 @@
 mod color_material;
+mod light;
 mod material;
 mod mesh;
 
 pub use color_material::*;
+pub use light::*;
 pub use material::*;
 pub use mesh::*;
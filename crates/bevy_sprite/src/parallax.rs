@@ -0,0 +1,264 @@
+use bevy_asset::{Assets, Handle};
+use bevy_core_pipeline::core_2d::Camera2d;
+use bevy_ecs::prelude::*;
+use bevy_math::{BVec2, Vec2};
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect};
+use bevy_render::{
+    camera::{Camera, OrthographicProjection},
+    texture::Image,
+};
+use bevy_transform::components::Transform;
+use bevy_utils::HashMap;
+
+use crate::{ImageScaleMode, Sprite};
+
+/// A layer of a 2d background, scrolling at its own speed when the 2d camera moves to look
+/// further away or closer than the rest of the world, like the backgrounds of side-scrollers.
+///
+/// The [`Transform`] of the layer is its position when the camera is at the origin, and
+/// [`update_parallax_layers`] moves it away from there as the camera moves. The layer follows the
+/// [`Transform`] of the active [`Camera2d`] with the lowest order, which shouldn't have a parent.
+///
+/// A layer with a [`Sprite`] can [`repeat`](Self::repeat) its image to fill the view however far
+/// the camera goes. The image is then tiled over a larger sprite following the camera, with the
+/// [`custom_size`](Sprite::custom_size) of the sprite when the layer is added, or otherwise the
+/// size of its image, as the size of each tile.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct ParallaxLayer {
+    /// How much the layer moves with the world when the camera moves, on each axis.
+    ///
+    /// At `1.0` the layer moves like the rest of the world, at `0.0` it stays fixed on screen like
+    /// a distant sky. Values in between look further away than the world, and values above `1.0`
+    /// look closer, for foregrounds.
+    pub factor: Vec2,
+    /// The axes along which the image of the [`Sprite`] of the layer repeats endlessly.
+    pub repeat: BVec2,
+}
+
+impl ParallaxLayer {
+    /// A layer moving by `factor` with the world on both axes, and repeating horizontally.
+    pub fn new(factor: f32) -> Self {
+        ParallaxLayer {
+            factor: Vec2::splat(factor),
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for ParallaxLayer {
+    fn default() -> Self {
+        ParallaxLayer {
+            factor: Vec2::splat(0.5),
+            repeat: BVec2::new(true, false),
+        }
+    }
+}
+
+/// What [`update_parallax_layers`] did to a [`ParallaxLayer`] on the last frame.
+#[derive(Debug, Default)]
+pub struct ParallaxLayerState {
+    /// The offset added to the translation of the layer.
+    offset: Vec2,
+    /// The size of a tile of a repeating layer, in the units of its sprite.
+    tile_size: Option<Vec2>,
+}
+
+/// Moves each [`ParallaxLayer`] with the 2d camera by its [`factor`](ParallaxLayer::factor), and
+/// resizes the sprites of repeating layers to cover the view of the camera.
+pub fn update_parallax_layers(
+    mut states: Local<HashMap<Entity, ParallaxLayerState>>,
+    mut removed: RemovedComponents<ParallaxLayer>,
+    images: Res<Assets<Image>>,
+    cameras: Query<(&Camera, &Transform, &OrthographicProjection), With<Camera2d>>,
+    mut layers: Query<
+        (
+            Entity,
+            &ParallaxLayer,
+            &mut Transform,
+            Option<(&mut Sprite, &Handle<Image>)>,
+        ),
+        Without<Camera>,
+    >,
+) {
+    for entity in &mut removed {
+        states.remove(&entity);
+    }
+
+    let Some((_, camera_transform, projection)) = cameras
+        .iter()
+        .filter(|(camera, ..)| camera.is_active)
+        .min_by_key(|(camera, ..)| camera.order)
+    else {
+        return;
+    };
+    let camera_position = camera_transform.translation.truncate();
+    let view_size = projection.area.size() * camera_transform.scale.truncate().abs();
+
+    for (entity, layer, mut transform, sprite) in &mut layers {
+        let state = states.entry(entity).or_default();
+        let origin = transform.translation.truncate() - state.offset;
+        let mut position = origin + camera_position * (Vec2::ONE - layer.factor);
+
+        if let Some((mut sprite, image_handle)) = sprite.filter(|_| layer.repeat.any()) {
+            let texels = sprite
+                .rect
+                .map(|rect| rect.size())
+                .or_else(|| images.get(image_handle).map(Image::size));
+            if let Some(texels) = texels {
+                let tile_size = *state
+                    .tile_size
+                    .get_or_insert(sprite.custom_size.unwrap_or(texels));
+                let (size, translation) = repeat_layer(
+                    layer.repeat,
+                    tile_size,
+                    transform.scale.truncate().abs(),
+                    sprite.anchor.as_vec(),
+                    position,
+                    camera_position,
+                    view_size,
+                );
+                position = translation;
+
+                let scale_mode = ImageScaleMode::Tiled {
+                    tile_x: layer.repeat.x,
+                    tile_y: layer.repeat.y,
+                    stretch_value: if layer.repeat.x {
+                        tile_size.x / texels.x
+                    } else {
+                        tile_size.y / texels.y
+                    },
+                };
+                if sprite.custom_size != Some(size) || sprite.scale_mode != scale_mode {
+                    sprite.custom_size = Some(size);
+                    sprite.scale_mode = scale_mode;
+                }
+            }
+        }
+
+        let offset = position - origin;
+        if offset != state.offset {
+            state.offset = offset;
+            transform.translation = position.extend(transform.translation.z);
+        }
+    }
+}
+
+/// Returns the size and the translation of the sprite of a layer repeating its tiles of
+/// `tile_size` along the `repeat` axes, so that it covers the view around the camera with its
+/// tiles where they'd be if the layer at `position` repeated endlessly.
+fn repeat_layer(
+    repeat: BVec2,
+    tile_size: Vec2,
+    scale: Vec2,
+    anchor: Vec2,
+    position: Vec2,
+    camera_position: Vec2,
+    view_size: Vec2,
+) -> (Vec2, Vec2) {
+    let tile = (tile_size * scale).max(Vec2::splat(f32::EPSILON));
+    // The sprite is one tile larger than the view, so that it still covers the view when its
+    // center is half a tile away from the camera
+    let count = (view_size / tile).ceil() + 1.0;
+    // The corner of the tile at `position`, and the corner of the sprite a whole number of tiles
+    // away from it, with the camera at the center of the sprite
+    let tile_corner = position - (anchor + 0.5) * tile;
+    let corner =
+        tile_corner + ((camera_position - tile_corner) / tile - count * 0.5).round() * tile;
+    let translation = corner + (anchor + 0.5) * count * tile;
+    (
+        Vec2::select(repeat, count * tile_size, tile_size),
+        Vec2::select(repeat, translation, position),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_math::{Rect, Vec3};
+    use bevy_render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+    #[test]
+    fn parallax_layers() {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default())
+            .add_asset::<Image>()
+            .add_system(update_parallax_layers);
+
+        let image = app
+            .world
+            .resource_mut::<Assets<Image>>()
+            .add(Image::new_fill(
+                Extent3d {
+                    width: 100,
+                    height: 50,
+                    depth_or_array_layers: 1,
+                },
+                TextureDimension::D2,
+                &[255; 4],
+                TextureFormat::Rgba8UnormSrgb,
+            ));
+        let camera = app
+            .world
+            .spawn((
+                Camera::default(),
+                Camera2d::default(),
+                Transform::default(),
+                OrthographicProjection {
+                    area: Rect::new(-200.0, -100.0, 200.0, 100.0),
+                    ..Default::default()
+                },
+            ))
+            .id();
+        let fixed = app
+            .world
+            .spawn((
+                ParallaxLayer {
+                    factor: Vec2::new(0.5, 0.0),
+                    repeat: BVec2::FALSE,
+                },
+                Transform::from_xyz(10.0, 20.0, -1.0),
+            ))
+            .id();
+        let repeating = app
+            .world
+            .spawn((
+                ParallaxLayer::new(0.5),
+                Transform::from_xyz(30.0, 0.0, -2.0),
+                Sprite::default(),
+                image,
+            ))
+            .id();
+        app.update();
+
+        let sprite = app.world.get::<Sprite>(repeating).unwrap();
+        // The view is 4 tiles wide, and the layer one more
+        assert_eq!(sprite.custom_size, Some(Vec2::new(500.0, 50.0)));
+        assert_eq!(
+            sprite.scale_mode,
+            ImageScaleMode::Tiled {
+                tile_x: true,
+                tile_y: false,
+                stretch_value: 1.0
+            }
+        );
+        assert_eq!(
+            app.world.get::<Transform>(repeating).unwrap().translation,
+            Vec3::new(30.0, 0.0, -2.0)
+        );
+
+        app.world.get_mut::<Transform>(camera).unwrap().translation = Vec3::new(1000.0, 300.0, 0.0);
+        app.update();
+        assert_eq!(
+            app.world.get::<Transform>(fixed).unwrap().translation,
+            Vec3::new(510.0, 320.0, -1.0)
+        );
+        // The layer moved by half the camera, and by whole tiles to stay around the camera
+        assert_eq!(
+            app.world.get::<Transform>(repeating).unwrap().translation,
+            Vec3::new(1030.0, 150.0, -2.0)
+        );
+    }
+}
@@ -0,0 +1,418 @@
+use bevy_app::prelude::*;
+use bevy_asset::{Assets, Handle};
+use bevy_core_pipeline::core_2d::Camera2d;
+use bevy_ecs::{prelude::*, reflect::ReflectComponent};
+use bevy_input::{mouse::MouseButton, Input, InputSystem};
+use bevy_math::{Rect, Vec2};
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect};
+use bevy_render::{
+    camera::{Camera, NormalizedRenderTarget},
+    render_resource::TextureFormat,
+    texture::Image,
+    view::{RenderLayers, ViewVisibility},
+};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::{FloatOrd, HashMap};
+use bevy_window::{PrimaryWindow, Window};
+
+use crate::{ImageScaleMode, SortMode2d, Sprite, TextureAtlas, TextureAtlasSprite};
+
+/// Sends a [`SpritePointerEvent`] when the mouse cursor moves over or out of a [`PickableSprite`]
+/// seen by a 2d camera, or when a mouse button is pressed, released or clicked over it.
+///
+/// The sprite under the cursor is also available in the [`SpritePointer`] resource.
+#[derive(Default)]
+pub struct SpritePickingPlugin;
+
+impl Plugin for SpritePickingPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PickableSprite>()
+            .init_resource::<SpritePointer>()
+            .add_event::<SpritePointerEvent>()
+            .add_system(
+                pick_sprites
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(InputSystem),
+            );
+    }
+}
+
+/// Makes a [`Sprite`] or a [`TextureAtlasSprite`] pickable by the mouse cursor, with the
+/// [`SpritePickingPlugin`].
+///
+/// The sprite is picked where it's drawn: in the rect of its image, or the frame of its texture
+/// atlas, that it shows. When several sprites are under the cursor, the one drawn on top of the
+/// others is picked.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct PickableSprite {
+    /// Only picks the sprite where the alpha of its image is at least this threshold, so that
+    /// the cursor over its transparent pixels picks the sprites behind it instead.
+    ///
+    /// This requires the image to be an uncompressed 8 bits or 32 bits float RGBA image, the
+    /// pixels of the other images are all picked. Sprites with an [`ImageScaleMode`] other than
+    /// [`ImageScaleMode::Stretched`] are always picked in their whole rect.
+    pub alpha_threshold: Option<f32>,
+}
+
+impl PickableSprite {
+    /// Picks the sprite where the alpha of its image is at least `alpha_threshold`.
+    pub fn with_alpha_threshold(alpha_threshold: f32) -> Self {
+        PickableSprite {
+            alpha_threshold: Some(alpha_threshold),
+        }
+    }
+}
+
+/// An event sent by the [`SpritePickingPlugin`] when the mouse cursor interacts with a
+/// [`PickableSprite`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpritePointerEvent {
+    /// The sprite entity.
+    pub entity: Entity,
+    /// What happened to it.
+    pub kind: SpritePointerEventKind,
+    /// The position of the cursor in the world, in the plane of the sprite for the events sent
+    /// while the cursor is over it.
+    pub position: Vec2,
+}
+
+/// What happened in a [`SpritePointerEvent`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpritePointerEventKind {
+    /// The cursor moved over the sprite.
+    Over,
+    /// The cursor moved out of the sprite, or the sprite stopped being pickable.
+    Out,
+    /// A mouse button was pressed over the sprite.
+    Down(MouseButton),
+    /// A mouse button was released over the sprite.
+    Up(MouseButton),
+    /// A mouse button was pressed then released over the sprite.
+    Click(MouseButton),
+}
+
+/// The position of the mouse cursor in the world, and the [`PickableSprite`] under it.
+#[derive(Resource, Debug, Default, Clone, Copy, PartialEq)]
+pub struct SpritePointer {
+    /// The 2d camera the cursor is in, the one with the highest order when their viewports
+    /// overlap, or `None` when the cursor isn't in the viewport of any 2d camera.
+    pub camera: Option<Entity>,
+    /// The position of the cursor in the world, as seen by the [`camera`](Self::camera).
+    pub position: Option<Vec2>,
+    /// The sprite under the cursor.
+    pub hovered: Option<Entity>,
+}
+
+/// The region of an image shown by a sprite, and where in the sprite it is.
+struct SpriteFrame<'a> {
+    image: Option<&'a Image>,
+    /// The rect of the image the sprite shows, in pixels.
+    rect: Rect,
+    size: Vec2,
+    anchor: Vec2,
+    flip_x: bool,
+    flip_y: bool,
+    stretched: bool,
+}
+
+impl SpriteFrame<'_> {
+    /// Returns `true` if the point of the sprite at `position`, relative to its transform, is
+    /// picked.
+    fn contains(&self, position: Vec2, alpha_threshold: Option<f32>) -> bool {
+        // The position in the quad of the sprite, from (-0.5, -0.5) to (0.5, 0.5)
+        let quad_position = position / self.size + self.anchor;
+        if quad_position.abs().cmpgt(Vec2::splat(0.5)).any() {
+            return false;
+        }
+        let (Some(alpha_threshold), Some(image), true) =
+            (alpha_threshold, self.image, self.stretched)
+        else {
+            return true;
+        };
+
+        let mut uv = Vec2::new(quad_position.x + 0.5, 0.5 - quad_position.y);
+        if self.flip_x {
+            uv.x = 1.0 - uv.x;
+        }
+        if self.flip_y {
+            uv.y = 1.0 - uv.y;
+        }
+        let texel = (self.rect.min + uv * self.rect.size())
+            .clamp(Vec2::ZERO, image.size() - 1.0)
+            .as_uvec2();
+        let index = (texel.y * image.texture_descriptor.size.width + texel.x) as usize;
+        let alpha = match image.texture_descriptor.format {
+            TextureFormat::Rgba8Unorm
+            | TextureFormat::Rgba8UnormSrgb
+            | TextureFormat::Bgra8Unorm
+            | TextureFormat::Bgra8UnormSrgb => image
+                .data
+                .get(index * 4 + 3)
+                .map(|alpha| *alpha as f32 / 255.0),
+            TextureFormat::Rgba32Float => image
+                .data
+                .get(index * 16 + 12..index * 16 + 16)
+                .map(|alpha| f32::from_le_bytes([alpha[0], alpha[1], alpha[2], alpha[3]])),
+            _ => None,
+        };
+        alpha.map_or(true, |alpha| alpha >= alpha_threshold)
+    }
+}
+
+/// Finds the [`PickableSprite`] under the mouse cursor, updates the [`SpritePointer`] and sends
+/// the [`SpritePointerEvent`]s.
+#[allow(clippy::too_many_arguments, clippy::type_complexity)]
+pub fn pick_sprites(
+    mut pointer: ResMut<SpritePointer>,
+    mut pressed: Local<HashMap<MouseButton, Entity>>,
+    mut pointer_events: EventWriter<SpritePointerEvent>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    windows: Query<&Window>,
+    primary_window: Query<Entity, With<PrimaryWindow>>,
+    cameras: Query<(Entity, &Camera, &GlobalTransform, Option<&RenderLayers>), With<Camera2d>>,
+    sprites: Query<(
+        Entity,
+        &PickableSprite,
+        &GlobalTransform,
+        &ViewVisibility,
+        Option<&SortMode2d>,
+        Option<&RenderLayers>,
+        AnyOf<(
+            (&Sprite, &Handle<Image>),
+            (&TextureAtlasSprite, &Handle<TextureAtlas>),
+        )>,
+    )>,
+    images: Res<Assets<Image>>,
+    atlases: Res<Assets<TextureAtlas>>,
+) {
+    let primary_window = primary_window.iter().next();
+    let mut cameras: Vec<_> = cameras
+        .iter()
+        .filter(|(_, camera, ..)| camera.is_active)
+        .collect();
+    // The cameras with a higher order are drawn on top of the others
+    cameras.sort_by_key(|(_, camera, ..)| -camera.order);
+
+    let mut camera_position = None;
+    let mut hit = None;
+    for (camera_entity, camera, camera_transform, camera_layers) in cameras {
+        let Some(NormalizedRenderTarget::Window(window_ref)) =
+            camera.target.normalize(primary_window)
+        else {
+            continue;
+        };
+        let Some(window) = windows.get(window_ref.entity()).ok() else {
+            continue;
+        };
+        let (Some(cursor), Some((min, max))) =
+            (window.cursor_position(), camera.logical_viewport_rect())
+        else {
+            continue;
+        };
+        // The cursor position is from the bottom left of the window, and the viewport rect from
+        // its top left
+        let viewport_position = Vec2::new(cursor.x - min.x, cursor.y - (window.height() - max.y));
+        if viewport_position.cmplt(Vec2::ZERO).any() || viewport_position.cmpgt(max - min).any() {
+            continue;
+        }
+        let Some(ray) = camera.viewport_to_world(camera_transform, viewport_position) else {
+            continue;
+        };
+        if camera_position.is_none() {
+            camera_position = Some((camera_entity, ray.origin.truncate()));
+        }
+
+        let camera_layers = camera_layers.copied().unwrap_or_default();
+        for (entity, pickable, transform, view_visibility, sort_mode, layers, (sprite, atlas)) in
+            &sprites
+        {
+            if !view_visibility.get()
+                || !camera_layers.intersects(&layers.copied().unwrap_or_default())
+            {
+                continue;
+            }
+            let frame = if let Some((sprite, image_handle)) = sprite {
+                let image = images.get(image_handle);
+                let rect = sprite
+                    .rect
+                    .or_else(|| image.map(|image| Rect::from_corners(Vec2::ZERO, image.size())));
+                let Some(size) = sprite.custom_size.or_else(|| rect.map(|rect| rect.size())) else {
+                    continue;
+                };
+                SpriteFrame {
+                    image,
+                    rect: rect.unwrap_or_default(),
+                    size,
+                    anchor: sprite.anchor.as_vec(),
+                    flip_x: sprite.flip_x,
+                    flip_y: sprite.flip_y,
+                    stretched: sprite.scale_mode == ImageScaleMode::Stretched,
+                }
+            } else if let Some((atlas_sprite, atlas_handle)) = atlas {
+                let Some(atlas) = atlases.get(atlas_handle) else {
+                    continue;
+                };
+                let Some(rect) = atlas.textures.get(atlas_sprite.index).copied() else {
+                    continue;
+                };
+                SpriteFrame {
+                    image: images.get(&atlas.texture),
+                    rect,
+                    size: atlas_sprite.custom_size.unwrap_or_else(|| rect.size()),
+                    anchor: atlas_sprite.anchor.as_vec(),
+                    flip_x: atlas_sprite.flip_x,
+                    flip_y: atlas_sprite.flip_y,
+                    stretched: true,
+                }
+            } else {
+                continue;
+            };
+
+            // Intersect the ray with the plane of the sprite
+            let world_to_sprite = transform.affine().inverse();
+            let origin = world_to_sprite.transform_point3(ray.origin);
+            let direction = world_to_sprite.transform_vector3(ray.direction);
+            if direction.z.abs() <= f32::EPSILON {
+                continue;
+            }
+            let distance = -origin.z / direction.z;
+            if distance < 0.0
+                || !frame.contains(
+                    (origin + direction * distance).truncate(),
+                    pickable.alpha_threshold,
+                )
+            {
+                continue;
+            }
+
+            let (key, y_key) = sort_mode.copied().unwrap_or_default().sort_keys(transform);
+            let sort_keys = (FloatOrd(key), FloatOrd(y_key));
+            if hit.map_or(true, |(_, _, hit_sort_keys)| sort_keys > hit_sort_keys) {
+                let position = (ray.origin + ray.direction * distance).truncate();
+                hit = Some((entity, position, sort_keys));
+            }
+        }
+        // The sprites seen by the cameras below are hidden by the ones seen by this camera
+        if hit.is_some() {
+            break;
+        }
+    }
+
+    let hovered = hit.map(|(entity, position, _)| (entity, position));
+    let position = hovered
+        .map(|(_, position)| position)
+        .or(camera_position.map(|(_, position)| position));
+    if pointer.hovered != hovered.map(|(entity, _)| entity) {
+        if let Some(entity) = pointer.hovered {
+            pointer_events.send(SpritePointerEvent {
+                entity,
+                kind: SpritePointerEventKind::Out,
+                position: position.or(pointer.position).unwrap_or_default(),
+            });
+        }
+        if let Some((entity, position)) = hovered {
+            pointer_events.send(SpritePointerEvent {
+                entity,
+                kind: SpritePointerEventKind::Over,
+                position,
+            });
+        }
+    }
+    *pointer = SpritePointer {
+        camera: camera_position.map(|(camera, _)| camera),
+        position,
+        hovered: hovered.map(|(entity, _)| entity),
+    };
+
+    for button in mouse_button_input.get_just_released() {
+        let pressed_entity = pressed.remove(button);
+        let Some((entity, position)) = hovered else {
+            continue;
+        };
+        pointer_events.send(SpritePointerEvent {
+            entity,
+            kind: SpritePointerEventKind::Up(*button),
+            position,
+        });
+        if pressed_entity == Some(entity) {
+            pointer_events.send(SpritePointerEvent {
+                entity,
+                kind: SpritePointerEventKind::Click(*button),
+                position,
+            });
+        }
+    }
+    for button in mouse_button_input.get_just_pressed() {
+        let Some((entity, position)) = hovered else {
+            pressed.remove(button);
+            continue;
+        };
+        pressed.insert(*button, entity);
+        pointer_events.send(SpritePointerEvent {
+            entity,
+            kind: SpritePointerEventKind::Down(*button),
+            position,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy_math::{Rect, Vec2};
+    use bevy_render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::Image,
+    };
+
+    use super::SpriteFrame;
+
+    #[test]
+    fn sprite_frame_contains() {
+        // A 4x2 image, whose right half is transparent
+        let mut data = Vec::new();
+        for _ in 0..2 {
+            data.extend([255, 255, 255, 255].repeat(2));
+            data.extend([255, 255, 255, 0].repeat(2));
+        }
+        let image = Image::new(
+            Extent3d {
+                width: 4,
+                height: 2,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        );
+        let mut frame = SpriteFrame {
+            image: Some(&image),
+            rect: Rect::new(0.0, 0.0, 4.0, 2.0),
+            size: Vec2::new(40.0, 20.0),
+            anchor: Vec2::ZERO,
+            flip_x: false,
+            flip_y: false,
+            stretched: true,
+        };
+
+        assert!(frame.contains(Vec2::new(-15.0, 5.0), Some(0.5)));
+        assert!(!frame.contains(Vec2::new(15.0, 5.0), Some(0.5)));
+        assert!(frame.contains(Vec2::new(15.0, 5.0), None));
+        assert!(!frame.contains(Vec2::new(25.0, 5.0), None));
+
+        // Flipped sprites show the other half of the image
+        frame.flip_x = true;
+        assert!(!frame.contains(Vec2::new(-15.0, 5.0), Some(0.5)));
+        assert!(frame.contains(Vec2::new(15.0, 5.0), Some(0.5)));
+
+        // Sprites showing a rect of the image are only tested against it
+        frame.flip_x = false;
+        frame.rect = Rect::new(2.0, 0.0, 4.0, 2.0);
+        assert!(!frame.contains(Vec2::new(-15.0, 5.0), Some(0.5)));
+
+        // The anchor moves the sprite around its transform
+        frame.anchor = Vec2::new(-0.5, -0.5);
+        assert!(frame.contains(Vec2::new(35.0, 15.0), None));
+        assert!(!frame.contains(Vec2::new(-5.0, 5.0), None));
+    }
+}
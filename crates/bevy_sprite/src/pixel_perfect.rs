@@ -0,0 +1,279 @@
+use bevy_app::{App, CoreSet, Plugin};
+use bevy_asset::{Assets, Handle};
+use bevy_core_pipeline::{
+    clear_color::ClearColorConfig,
+    core_2d::{Camera2d, Camera2dBundle},
+};
+use bevy_ecs::prelude::*;
+use bevy_math::{UVec2, Vec2};
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect};
+use bevy_render::{
+    camera::{Camera, CameraUpdateSystem, RenderTarget},
+    color::Color,
+    extract_component::{ExtractComponent, ExtractComponentPlugin},
+    render_resource::{Extent3d, TextureDimension, TextureFormat, TextureUsages},
+    texture::{BevyDefault, Image, ImageSampler},
+    view::{Msaa, RenderLayers},
+};
+use bevy_transform::components::Transform;
+use bevy_utils::HashMap;
+
+use crate::{Sprite, SpriteBundle};
+
+/// Renders [`PixelPerfectCamera`]s at their resolution, and upscales them to their target.
+#[derive(Default)]
+pub struct PixelPerfectPlugin;
+
+impl Plugin for PixelPerfectPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<PixelPerfectCamera>()
+            .add_plugin(ExtractComponentPlugin::<PixelPerfectCamera>::default())
+            .add_system(
+                update_pixel_perfect_cameras
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(CameraUpdateSystem),
+            );
+    }
+}
+
+/// Renders a 2d camera at a fixed low [`resolution`](Self::resolution), upscaled to the target
+/// of the camera by the largest integer scale that fits, with black bars around it, for crisp
+/// pixel art.
+///
+/// The camera renders to an image of the resolution instead of its target. The image is drawn
+/// to the target by a second camera seeing only the [`upscale_layer`](Self::upscale_layer) of
+/// the [`RenderLayers`], which shouldn't be used by anything else. Both cameras are drawn without
+/// MSAA.
+///
+/// Each pixel of the resolution is a unit of the world, with the default projection of a
+/// [`Camera2dBundle`].
+#[derive(Component, Debug, Clone, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct PixelPerfectCamera {
+    /// The size of the image the camera renders to, in pixels.
+    pub resolution: UVec2,
+    /// Snaps the corners of the sprites to the pixels of the resolution, so that the sprites
+    /// placed between two pixels aren't distorted.
+    pub snap_sprites: bool,
+    /// The layer of the [`RenderLayers`] the upscaled image is drawn on.
+    pub upscale_layer: u8,
+}
+
+impl PixelPerfectCamera {
+    /// Renders the camera at `resolution`, snapping the sprites to its pixels.
+    pub fn new(resolution: UVec2) -> Self {
+        PixelPerfectCamera {
+            resolution,
+            ..Default::default()
+        }
+    }
+
+    /// The largest integer scale of the resolution fitting in a target of `physical_size`.
+    pub fn scale(&self, physical_size: UVec2) -> u32 {
+        (physical_size / self.resolution.max(UVec2::ONE))
+            .min_element()
+            .max(1)
+    }
+
+    /// Converts a `viewport_position` on the target of the camera, like the cursor position, to
+    /// a position on the image of its resolution, for [`Camera::viewport_to_world_2d`].
+    ///
+    /// Returns `None` if the position is on the bars around the image.
+    pub fn target_to_image_position(
+        &self,
+        viewport_position: Vec2,
+        target_size: Vec2,
+        scale_factor: f32,
+    ) -> Option<Vec2> {
+        let resolution = self.resolution.max(UVec2::ONE).as_vec2();
+        let physical_size = (target_size * scale_factor).as_uvec2();
+        let scale = self.scale(physical_size) as f32 / scale_factor;
+        let position = (viewport_position - (target_size - resolution * scale) * 0.5) / scale;
+        let inside = position.cmpge(Vec2::ZERO).all() && position.cmplt(resolution).all();
+        inside.then_some(position)
+    }
+}
+
+impl Default for PixelPerfectCamera {
+    fn default() -> Self {
+        PixelPerfectCamera {
+            resolution: UVec2::new(320, 180),
+            snap_sprites: true,
+            upscale_layer: (RenderLayers::TOTAL_LAYERS - 1) as u8,
+        }
+    }
+}
+
+/// Added to the views of the [`PixelPerfectCamera`]s with
+/// [`snap_sprites`](PixelPerfectCamera::snap_sprites), to snap their sprites to the pixels.
+#[derive(Component, Clone, Copy, Debug)]
+pub struct SnapToPixelGrid;
+
+impl ExtractComponent for PixelPerfectCamera {
+    type Query = &'static Self;
+    type Filter = ();
+    type Out = SnapToPixelGrid;
+
+    fn extract_component(camera: &Self) -> Option<Self::Out> {
+        camera.snap_sprites.then_some(SnapToPixelGrid)
+    }
+}
+
+/// The entities and the image upscaling a [`PixelPerfectCamera`].
+pub struct PixelPerfectUpscale {
+    camera: Entity,
+    canvas: Entity,
+    image: Handle<Image>,
+}
+
+fn pixel_perfect_image(resolution: UVec2) -> Image {
+    let size = Extent3d {
+        width: resolution.x,
+        height: resolution.y,
+        depth_or_array_layers: 1,
+    };
+    let format = TextureFormat::bevy_default();
+    let mut image = Image::new_fill(size, TextureDimension::D2, &[0; 4], format);
+    image.texture_descriptor.usage =
+        TextureUsages::TEXTURE_BINDING | TextureUsages::COPY_DST | TextureUsages::RENDER_ATTACHMENT;
+    image.sampler_descriptor = ImageSampler::nearest();
+    image
+}
+
+/// Renders each [`PixelPerfectCamera`] to an image of its resolution, and scales the image drawn
+/// to its target with the size of the target.
+#[allow(clippy::type_complexity)]
+pub fn update_pixel_perfect_cameras(
+    mut commands: Commands,
+    mut upscales: Local<HashMap<Entity, PixelPerfectUpscale>>,
+    mut removed: RemovedComponents<PixelPerfectCamera>,
+    mut images: ResMut<Assets<Image>>,
+    mut cameras: Query<(Entity, &PixelPerfectCamera, &mut Camera)>,
+    mut upscale_cameras: Query<&mut Camera, Without<PixelPerfectCamera>>,
+    mut canvases: Query<(&mut Sprite, &mut Transform)>,
+) {
+    for entity in &mut removed {
+        let Some(upscale) = upscales.remove(&entity) else {
+            continue;
+        };
+        // The camera renders to its target again, if it still exists
+        if let Ok([mut camera, upscale_camera]) =
+            upscale_cameras.get_many_mut([entity, upscale.camera])
+        {
+            camera.target = upscale_camera.target.clone();
+        }
+        commands.entity(upscale.camera).despawn();
+        commands.entity(upscale.canvas).despawn();
+    }
+
+    for (entity, pixel_perfect, mut camera) in &mut cameras {
+        let resolution = pixel_perfect.resolution.max(UVec2::ONE);
+        let layer = RenderLayers::layer(pixel_perfect.upscale_layer);
+        let upscale = upscales.entry(entity).or_insert_with(|| {
+            let image = images.add(pixel_perfect_image(resolution));
+            let target = std::mem::replace(&mut camera.target, RenderTarget::Image(image.clone()));
+            let upscale_camera = commands
+                .spawn((
+                    Camera2dBundle {
+                        camera: Camera {
+                            order: camera.order + 1,
+                            target,
+                            ..Default::default()
+                        },
+                        camera_2d: Camera2d {
+                            clear_color: ClearColorConfig::Custom(Color::BLACK),
+                        },
+                        ..Default::default()
+                    },
+                    Msaa::Off,
+                    layer,
+                ))
+                .id();
+            let canvas = commands
+                .spawn((
+                    SpriteBundle {
+                        texture: image.clone(),
+                        ..Default::default()
+                    },
+                    layer,
+                ))
+                .id();
+            commands.entity(entity).insert(Msaa::Off);
+            PixelPerfectUpscale {
+                camera: upscale_camera,
+                canvas,
+                image,
+            }
+        });
+
+        let size = images.get(&upscale.image).map(|image| image.size());
+        if size != Some(resolution.as_vec2()) {
+            if let Some(image) = images.get_mut(&upscale.image) {
+                image.resize(Extent3d {
+                    width: resolution.x,
+                    height: resolution.y,
+                    depth_or_array_layers: 1,
+                });
+            }
+        }
+
+        // The image is scaled by the largest number of physical pixels that fits in the target
+        let Ok(upscale_camera) = upscale_cameras.get(upscale.camera) else {
+            continue;
+        };
+        let (Some(physical_size), Some(logical_size)) = (
+            upscale_camera.physical_target_size(),
+            upscale_camera.logical_target_size(),
+        ) else {
+            continue;
+        };
+        let scale = pixel_perfect.scale(physical_size);
+        let logical_per_physical = logical_size / physical_size.as_vec2();
+        let canvas_size = (resolution * scale).as_vec2() * logical_per_physical;
+        // The center of the target is between two pixels when the bars around the image have
+        // an odd size, the image is then moved by half a pixel for its pixels to be aligned
+        let offset = ((physical_size.as_ivec2() - (resolution * scale).as_ivec2()) % 2).abs();
+        let translation = offset.as_vec2() * 0.5 * logical_per_physical;
+        if let Ok((mut sprite, mut transform)) = canvases.get_mut(upscale.canvas) {
+            if sprite.custom_size != Some(canvas_size) {
+                sprite.custom_size = Some(canvas_size);
+            }
+            if transform.translation.truncate() != translation {
+                transform.translation = translation.extend(0.0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn target_to_image_position() {
+        let camera = PixelPerfectCamera::new(UVec2::new(320, 180));
+        assert_eq!(camera.scale(UVec2::new(1280, 720)), 4);
+        assert_eq!(camera.scale(UVec2::new(1000, 1000)), 3);
+        assert_eq!(camera.scale(UVec2::new(100, 100)), 1);
+
+        // A scale of 3, with bars of 20 pixels on the sides
+        let target_size = Vec2::new(1000.0, 540.0);
+        assert_eq!(
+            camera.target_to_image_position(Vec2::new(20.0, 0.0), target_size, 1.0),
+            Some(Vec2::ZERO)
+        );
+        assert_eq!(
+            camera.target_to_image_position(Vec2::new(500.0, 270.0), target_size, 1.0),
+            Some(Vec2::new(160.0, 90.0))
+        );
+        assert_eq!(
+            camera.target_to_image_position(Vec2::new(10.0, 100.0), target_size, 1.0),
+            None
+        );
+        // With twice as many physical pixels, the scale is 6 physical pixels, 3 logical ones
+        assert_eq!(
+            camera.target_to_image_position(Vec2::new(500.0, 270.0), target_size, 2.0),
+            Some(Vec2::new(160.0, 90.0))
+        );
+    }
+}
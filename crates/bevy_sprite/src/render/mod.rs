@@ -1,8 +1,6 @@
-use std::cmp::Ordering;
-
 use crate::{
     texture_atlas::{TextureAtlas, TextureAtlasSprite},
-    Sprite, SPRITE_SHADER_HANDLE,
+    ImageScaleMode, SnapToPixelGrid, SortMode2d, Sprite, TextureSliceVertex, SPRITE_SHADER_HANDLE,
 };
 use bevy_asset::{AssetEvent, Assets, Handle, HandleId};
 use bevy_core_pipeline::{
@@ -14,7 +12,6 @@ use bevy_ecs::{
     system::{lifetimeless::*, SystemParamItem, SystemState},
 };
 use bevy_math::{Rect, Vec2};
-use bevy_reflect::Uuid;
 use bevy_render::{
     color::Color,
     render_asset::RenderAssets,
@@ -24,12 +21,13 @@ use bevy_render::{
     },
     render_resource::*,
     renderer::{RenderDevice, RenderQueue},
+    settings::WgpuFeatures,
     texture::{
         BevyDefault, DefaultImageSampler, GpuImage, Image, ImageSampler, TextureFormatPixelInfo,
     },
     view::{
-        ComputedVisibility, ExtractedView, Msaa, ViewTarget, ViewUniform, ViewUniformOffset,
-        ViewUniforms, VisibleEntities,
+        ExtractedView, Msaa, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms,
+        ViewVisibility, VisibleEntities,
     },
     Extract,
 };
@@ -38,11 +36,25 @@ use bevy_utils::FloatOrd;
 use bevy_utils::HashMap;
 use bytemuck::{Pod, Zeroable};
 use fixedbitset::FixedBitSet;
+use std::mem;
+
+/// The number of images a batch of sprites can sample from, when the render device supports
+/// binding arrays of textures.
+///
+/// Sprites using different images, like the sprites of different texture atlases, are then drawn
+/// in the same batch, instead of breaking it each time the image changes.
+pub const SPRITE_TEXTURE_ARRAY_SIZE: usize = 16;
+
+/// The vertex attribute location of the index of the image of a sprite in its batch.
+const TEXTURE_INDEX_SHADER_LOCATION: u32 = 9;
 
 #[derive(Resource)]
 pub struct SpritePipeline {
     view_layout: BindGroupLayout,
     material_layout: BindGroupLayout,
+    /// The layout binding [`SPRITE_TEXTURE_ARRAY_SIZE`] textures and samplers, when the render
+    /// device supports it.
+    texture_array_layout: Option<BindGroupLayout>,
     pub dummy_white_gpu_image: GpuImage,
 }
 
@@ -90,6 +102,40 @@ impl FromWorld for SpritePipeline {
             ],
             label: Some("sprite_material_layout"),
         });
+
+        // Each texture and sampler of an array counts towards the limits of the shader stage
+        let limits = render_device.limits();
+        let texture_array_layout = (render_device.features().contains(
+            WgpuFeatures::TEXTURE_BINDING_ARRAY
+                | WgpuFeatures::SAMPLED_TEXTURE_AND_STORAGE_BUFFER_ARRAY_NON_UNIFORM_INDEXING,
+        ) && limits.max_sampled_textures_per_shader_stage as usize
+            >= SPRITE_TEXTURE_ARRAY_SIZE
+            && limits.max_samplers_per_shader_stage as usize >= SPRITE_TEXTURE_ARRAY_SIZE)
+            .then(|| {
+                let count = std::num::NonZeroU32::new(SPRITE_TEXTURE_ARRAY_SIZE as u32);
+                render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    entries: &[
+                        BindGroupLayoutEntry {
+                            binding: 0,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Texture {
+                                multisampled: false,
+                                sample_type: TextureSampleType::Float { filterable: true },
+                                view_dimension: TextureViewDimension::D2,
+                            },
+                            count,
+                        },
+                        BindGroupLayoutEntry {
+                            binding: 1,
+                            visibility: ShaderStages::FRAGMENT,
+                            ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                            count,
+                        },
+                    ],
+                    label: Some("sprite_texture_array_layout"),
+                })
+            });
+
         let dummy_white_gpu_image = {
             let image = Image::new_fill(
                 Extent3d::default(),
@@ -141,11 +187,75 @@ impl FromWorld for SpritePipeline {
         SpritePipeline {
             view_layout,
             material_layout,
+            texture_array_layout,
             dummy_white_gpu_image,
         }
     }
 }
 
+impl SpritePipeline {
+    /// Returns `true` if sprites using different images can be drawn in the same batch, with up
+    /// to [`SPRITE_TEXTURE_ARRAY_SIZE`] images per batch.
+    pub fn supports_texture_arrays(&self) -> bool {
+        self.texture_array_layout.is_some()
+    }
+
+    /// The number of images a batch of sprites can sample from.
+    fn max_batch_images(&self) -> usize {
+        if self.supports_texture_arrays() {
+            SPRITE_TEXTURE_ARRAY_SIZE
+        } else {
+            1
+        }
+    }
+
+    fn create_material_bind_group(
+        &self,
+        render_device: &RenderDevice,
+        images: &[&GpuImage],
+    ) -> BindGroup {
+        let Some(texture_array_layout) = &self.texture_array_layout else {
+            return render_device.create_bind_group(&BindGroupDescriptor {
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&images[0].texture_view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&images[0].sampler),
+                    },
+                ],
+                label: Some("sprite_material_bind_group"),
+                layout: &self.material_layout,
+            });
+        };
+
+        // Every element of the arrays must be bound, the unused ones repeat the first image
+        let image = |i: usize| images.get(i).unwrap_or(&images[0]);
+        let texture_views: Vec<_> = (0..SPRITE_TEXTURE_ARRAY_SIZE)
+            .map(|i| &*image(i).texture_view)
+            .collect();
+        let samplers: Vec<_> = (0..SPRITE_TEXTURE_ARRAY_SIZE)
+            .map(|i| &*image(i).sampler)
+            .collect();
+        render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[
+                BindGroupEntry {
+                    binding: 0,
+                    resource: BindingResource::TextureViewArray(&texture_views),
+                },
+                BindGroupEntry {
+                    binding: 1,
+                    resource: BindingResource::SamplerArray(&samplers),
+                },
+            ],
+            label: Some("sprite_texture_array_bind_group"),
+            layout: texture_array_layout,
+        })
+    }
+}
+
 bitflags::bitflags! {
     #[repr(transparent)]
     // NOTE: Apparently quadro drivers support up to 64x MSAA.
@@ -156,6 +266,9 @@ bitflags::bitflags! {
         const HDR                               = (1 << 1);
         const TONEMAP_IN_SHADER                 = (1 << 2);
         const DEBAND_DITHER                     = (1 << 3);
+        const SLICED                            = (1 << 4);
+        const SNAP_TO_PIXELS                    = (1 << 5);
+        const TEXTURE_ARRAY                     = (1 << 6);
         const MSAA_RESERVED_BITS                = Self::MSAA_MASK_BITS << Self::MSAA_SHIFT_BITS;
         const TONEMAP_METHOD_RESERVED_BITS      = Self::TONEMAP_METHOD_MASK_BITS << Self::TONEMAP_METHOD_SHIFT_BITS;
         const TONEMAP_METHOD_NONE               = 0 << Self::TONEMAP_METHOD_SHIFT_BITS;
@@ -223,13 +336,41 @@ impl SpecializedRenderPipeline for SpritePipeline {
             formats.push(VertexFormat::Float32x4);
         }
 
-        let vertex_layout =
+        if key.contains(SpritePipelineKey::SLICED) {
+            formats.extend(TextureSliceVertex::VERTEX_FORMATS);
+        }
+
+        // texture index
+        formats.push(VertexFormat::Uint32);
+
+        let mut vertex_layout =
             VertexBufferLayout::from_vertex_formats(VertexStepMode::Vertex, formats);
+        // The texture index is at the same location in every kind of vertex
+        if let Some(attribute) = vertex_layout.attributes.last_mut() {
+            attribute.shader_location = TEXTURE_INDEX_SHADER_LOCATION;
+        }
 
         let mut shader_defs = Vec::new();
         if key.contains(SpritePipelineKey::COLORED) {
             shader_defs.push("COLORED".into());
         }
+        if key.contains(SpritePipelineKey::SLICED) {
+            shader_defs.push("SLICED".into());
+        }
+        if key.contains(SpritePipelineKey::SNAP_TO_PIXELS) {
+            shader_defs.push("SNAP_TO_PIXELS".into());
+        }
+        let mut material_layout = self.material_layout.clone();
+        if key.contains(SpritePipelineKey::TEXTURE_ARRAY) {
+            if let Some(texture_array_layout) = &self.texture_array_layout {
+                shader_defs.push("TEXTURE_ARRAY".into());
+                shader_defs.push(ShaderDefVal::UInt(
+                    "SPRITE_TEXTURE_ARRAY_SIZE".into(),
+                    SPRITE_TEXTURE_ARRAY_SIZE as u32,
+                ));
+                material_layout = texture_array_layout.clone();
+            }
+        }
 
         if key.contains(SpritePipelineKey::TONEMAP_IN_SHADER) {
             shader_defs.push("TONEMAP_IN_SHADER".into());
@@ -283,7 +424,7 @@ impl SpecializedRenderPipeline for SpritePipeline {
                     write_mask: ColorWrites::ALL,
                 })],
             }),
-            layout: vec![self.view_layout.clone(), self.material_layout.clone()],
+            layout: vec![self.view_layout.clone(), material_layout],
             primitive: PrimitiveState {
                 front_face: FrontFace::Ccw,
                 cull_mode: None,
@@ -320,6 +461,10 @@ pub struct ExtractedSprite {
     pub flip_x: bool,
     pub flip_y: bool,
     pub anchor: Vec2,
+    /// How the image fills the sprite, when its size differs from the size of the image
+    pub scale_mode: ImageScaleMode,
+    /// The keys the sprite is sorted by, from [`SortMode2d::sort_keys`]
+    pub sort_keys: (f32, f32),
 }
 
 #[derive(Resource, Default)]
@@ -351,6 +496,9 @@ pub fn extract_sprite_events(
             AssetEvent::Removed { handle } => AssetEvent::Removed {
                 handle: handle.clone_weak(),
             },
+            AssetEvent::LoadedWithDependencies { handle } => AssetEvent::LoadedWithDependencies {
+                handle: handle.clone_weak(),
+            },
         });
     }
 }
@@ -361,25 +509,27 @@ pub fn extract_sprites(
     sprite_query: Extract<
         Query<(
             Entity,
-            &ComputedVisibility,
+            &ViewVisibility,
             &Sprite,
             &GlobalTransform,
             &Handle<Image>,
+            Option<&SortMode2d>,
         )>,
     >,
     atlas_query: Extract<
         Query<(
             Entity,
-            &ComputedVisibility,
+            &ViewVisibility,
             &TextureAtlasSprite,
             &GlobalTransform,
             &Handle<TextureAtlas>,
+            Option<&SortMode2d>,
         )>,
     >,
 ) {
     extracted_sprites.sprites.clear();
-    for (entity, visibility, sprite, transform, handle) in sprite_query.iter() {
-        if !visibility.is_visible() {
+    for (entity, visibility, sprite, transform, handle, sort_mode) in sprite_query.iter() {
+        if !visibility.get() {
             continue;
         }
         // PERF: we don't check in this function that the `Image` asset is ready, since it should be in most cases and hashing the handle is expensive
@@ -394,10 +544,14 @@ pub fn extract_sprites(
             flip_y: sprite.flip_y,
             image_handle_id: handle.id(),
             anchor: sprite.anchor.as_vec(),
+            scale_mode: sprite.scale_mode,
+            sort_keys: sort_mode.copied().unwrap_or_default().sort_keys(transform),
         });
     }
-    for (entity, visibility, atlas_sprite, transform, texture_atlas_handle) in atlas_query.iter() {
-        if !visibility.is_visible() {
+    for (entity, visibility, atlas_sprite, transform, texture_atlas_handle, sort_mode) in
+        atlas_query.iter()
+    {
+        if !visibility.get() {
             continue;
         }
         if let Some(texture_atlas) = texture_atlases.get(texture_atlas_handle) {
@@ -414,6 +568,8 @@ pub fn extract_sprites(
                 flip_y: atlas_sprite.flip_y,
                 image_handle_id: texture_atlas.texture.id(),
                 anchor: atlas_sprite.anchor.as_vec(),
+                scale_mode: ImageScaleMode::Stretched,
+                sort_keys: sort_mode.copied().unwrap_or_default().sort_keys(transform),
             });
         }
     }
@@ -424,6 +580,7 @@ pub fn extract_sprites(
 struct SpriteVertex {
     pub position: [f32; 3],
     pub uv: [f32; 2],
+    pub texture_index: u32,
 }
 
 #[repr(C)]
@@ -432,12 +589,26 @@ struct ColoredSpriteVertex {
     pub position: [f32; 3],
     pub uv: [f32; 2],
     pub color: [f32; 4],
+    pub texture_index: u32,
+}
+
+/// The vertex of a sprite with an [`ImageScaleMode`] other than [`ImageScaleMode::Stretched`],
+/// whose `uv` is the coordinate of the vertex inside the quad.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct SlicedSpriteVertex {
+    pub position: [f32; 3],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+    pub slice: TextureSliceVertex,
+    pub texture_index: u32,
 }
 
 #[derive(Resource)]
 pub struct SpriteMeta {
     vertices: BufferVec<SpriteVertex>,
     colored_vertices: BufferVec<ColoredSpriteVertex>,
+    sliced_vertices: BufferVec<SlicedSpriteVertex>,
     view_bind_group: Option<BindGroup>,
 }
 
@@ -446,6 +617,7 @@ impl Default for SpriteMeta {
         Self {
             vertices: BufferVec::new(BufferUsages::VERTEX),
             colored_vertices: BufferVec::new(BufferUsages::VERTEX),
+            sliced_vertices: BufferVec::new(BufferUsages::VERTEX),
             view_bind_group: None,
         }
     }
@@ -467,15 +639,53 @@ const QUAD_UVS: [Vec2; 4] = [
     Vec2::new(0., 0.),
 ];
 
-#[derive(Component, Eq, PartialEq, Copy, Clone)]
+#[derive(Component, Eq, PartialEq, Clone)]
 pub struct SpriteBatch {
-    image_handle_id: HandleId,
+    /// The images sampled by the sprites of the batch, a single one unless the render device
+    /// supports binding arrays of textures.
+    images: Vec<HandleId>,
     colored: bool,
+    sliced: bool,
+}
+
+impl SpriteBatch {
+    /// Returns the index of `image` in the images of the batch, adding it if there are less than
+    /// `max_images`, or `None` if the batch is full.
+    fn texture_index(&mut self, image: HandleId, max_images: usize) -> Option<u32> {
+        let index = match self.images.iter().position(|id| *id == image) {
+            Some(index) => index,
+            None if self.images.len() < max_images => {
+                self.images.push(image);
+                self.images.len() - 1
+            }
+            None => return None,
+        };
+        Some(index as u32)
+    }
 }
 
 #[derive(Resource, Default)]
 pub struct ImageBindGroups {
-    values: HashMap<Handle<Image>, BindGroup>,
+    values: HashMap<Vec<HandleId>, BindGroup>,
+}
+
+/// The number of sprites and batches queued during the last frame, in every view.
+///
+/// This resource is in the render world. The
+/// [`SpriteBatchDiagnosticsPlugin`](crate::SpriteBatchDiagnosticsPlugin) also adds it to the
+/// main world, and measures it with diagnostics.
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpriteBatchStats {
+    /// The number of sprites queued.
+    pub sprites: usize,
+    /// The number of batches the sprites were queued in. Sprites of consecutive batches can
+    /// still be drawn together when their batches are compatible.
+    pub batches: usize,
+    /// The number of batches started because the image of a sprite changed, while the previous
+    /// batch was otherwise compatible. When the render device supports binding arrays of
+    /// textures, this only happens when a batch already samples from
+    /// [`SPRITE_TEXTURE_ARRAY_SIZE`] other images.
+    pub texture_breaks: usize,
 }
 
 #[allow(clippy::too_many_arguments)]
@@ -491,29 +701,32 @@ pub fn queue_sprites(
     mut pipelines: ResMut<SpecializedRenderPipelines<SpritePipeline>>,
     pipeline_cache: Res<PipelineCache>,
     mut image_bind_groups: ResMut<ImageBindGroups>,
+    mut batch_stats: ResMut<SpriteBatchStats>,
     gpu_images: Res<RenderAssets<Image>>,
-    msaa: Res<Msaa>,
     mut extracted_sprites: ResMut<ExtractedSprites>,
     mut views: Query<(
         &mut RenderPhase<Transparent2d>,
         &VisibleEntities,
         &ExtractedView,
+        &Msaa,
         Option<&Tonemapping>,
         Option<&DebandDither>,
+        Option<&SnapToPixelGrid>,
     )>,
     events: Res<SpriteAssetEvents>,
 ) {
     // If an image has changed, the GpuImage has (probably) changed
     for event in &events.images {
         match event {
-            AssetEvent::Created { .. } => None,
+            AssetEvent::Created { .. } | AssetEvent::LoadedWithDependencies { .. } => {}
             AssetEvent::Modified { handle } | AssetEvent::Removed { handle } => {
-                image_bind_groups.values.remove(handle)
+                image_bind_groups
+                    .values
+                    .retain(|images, _| !images.contains(&handle.id()));
             }
-        };
+        }
     }
-
-    let msaa_key = SpritePipelineKey::from_msaa_samples(msaa.samples());
+    *batch_stats = SpriteBatchStats::default();
 
     if let Some(view_binding) = view_uniforms.uniforms.binding() {
         let sprite_meta = &mut sprite_meta;
@@ -521,6 +734,7 @@ pub fn queue_sprites(
         // Clear the vertex buffers
         sprite_meta.vertices.clear();
         sprite_meta.colored_vertices.clear();
+        sprite_meta.sliced_vertices.clear();
 
         sprite_meta.view_bind_group = Some(render_device.create_bind_group(&BindGroupDescriptor {
             entries: &[BindGroupEntry {
@@ -536,28 +750,36 @@ pub fn queue_sprites(
         // Vertex buffer indices
         let mut index = 0;
         let mut colored_index = 0;
+        let mut sliced_index = 0;
 
         // FIXME: VisibleEntities is ignored
 
         let extracted_sprites = &mut extracted_sprites.sprites;
-        // Sort sprites by z for correct transparency and then by handle to improve batching
+        // Sort sprites by their sort keys (z by default) for correct transparency and then by handle to improve batching
         // NOTE: This can be done independent of views by reasonably assuming that all 2D views look along the negative-z axis in world space
-        extracted_sprites.sort_unstable_by(|a, b| {
-            match a
-                .transform
-                .translation()
-                .z
-                .partial_cmp(&b.transform.translation().z)
-            {
-                Some(Ordering::Equal) | None => a.image_handle_id.cmp(&b.image_handle_id),
-                Some(other) => other,
-            }
+        // NOTE: Items with the same sort key keep this order in the render phase, as it is sorted with a stable sort
+        extracted_sprites.sort_unstable_by_key(|sprite| {
+            (
+                FloatOrd(sprite.sort_keys.0),
+                FloatOrd(sprite.sort_keys.1),
+                sprite.image_handle_id,
+            )
         });
-        let image_bind_groups = &mut *image_bind_groups;
-
-        for (mut transparent_phase, visible_entities, view, tonemapping, dither) in &mut views {
-            let mut view_key = SpritePipelineKey::from_hdr(view.hdr) | msaa_key;
-
+        let max_batch_images = sprite_pipeline.max_batch_images();
+        // The batches of every view, whose bind groups are created once they are complete
+        let mut batches = Vec::new();
+
+        for (mut transparent_phase, visible_entities, view, msaa, tonemapping, dither, snap) in
+            &mut views
+        {
+            let mut view_key = SpritePipelineKey::from_hdr(view.hdr)
+                | SpritePipelineKey::from_msaa_samples(msaa.samples());
+            if snap.is_some() {
+                view_key |= SpritePipelineKey::SNAP_TO_PIXELS;
+            }
+            if sprite_pipeline.supports_texture_arrays() {
+                view_key |= SpritePipelineKey::TEXTURE_ARRAY;
+            }
             if !view.hdr {
                 if let Some(tonemapping) = tonemapping {
                     view_key |= SpritePipelineKey::TONEMAP_IN_SHADER;
@@ -595,17 +817,18 @@ pub fn queue_sprites(
                 &sprite_pipeline,
                 view_key | SpritePipelineKey::from_colored(true),
             );
+            let sliced_pipeline = pipelines.specialize(
+                &pipeline_cache,
+                &sprite_pipeline,
+                view_key | SpritePipelineKey::from_colored(true) | SpritePipelineKey::SLICED,
+            );
 
             view_entities.clear();
             view_entities.extend(visible_entities.entities.iter().map(|e| e.index() as usize));
             transparent_phase.items.reserve(extracted_sprites.len());
 
-            // Impossible starting values that will be replaced on the first iteration
-            let mut current_batch = SpriteBatch {
-                image_handle_id: HandleId::Id(Uuid::nil(), u64::MAX),
-                colored: false,
-            };
-            let mut current_batch_entity = Entity::PLACEHOLDER;
+            let mut current_batch: Option<(Entity, SpriteBatch)> = None;
+            let mut current_image = None;
             let mut current_image_size = Vec2::ZERO;
             // Add a phase item for each sprite, and detect when successive items can be batched.
             // Spawn an entity with a `SpriteBatch` component for each possible batch.
@@ -616,45 +839,52 @@ pub fn queue_sprites(
                 if !view_entities.contains(extracted_sprite.entity.index() as usize) {
                     continue;
                 }
-                let new_batch = SpriteBatch {
-                    image_handle_id: extracted_sprite.image_handle_id,
-                    colored: extracted_sprite.color != Color::WHITE,
-                };
-                if new_batch != current_batch {
-                    // Set-up a new possible batch
-                    if let Some(gpu_image) =
-                        gpu_images.get(&Handle::weak(new_batch.image_handle_id))
-                    {
-                        current_batch = new_batch;
+                let image_handle_id = extracted_sprite.image_handle_id;
+                if current_image != Some(image_handle_id) {
+                    if let Some(gpu_image) = gpu_images.get(&Handle::weak(image_handle_id)) {
+                        current_image = Some(image_handle_id);
                         current_image_size = Vec2::new(gpu_image.size.x, gpu_image.size.y);
-                        current_batch_entity = commands.spawn(current_batch).id();
-
-                        image_bind_groups
-                            .values
-                            .entry(Handle::weak(current_batch.image_handle_id))
-                            .or_insert_with(|| {
-                                render_device.create_bind_group(&BindGroupDescriptor {
-                                    entries: &[
-                                        BindGroupEntry {
-                                            binding: 0,
-                                            resource: BindingResource::TextureView(
-                                                &gpu_image.texture_view,
-                                            ),
-                                        },
-                                        BindGroupEntry {
-                                            binding: 1,
-                                            resource: BindingResource::Sampler(&gpu_image.sampler),
-                                        },
-                                    ],
-                                    label: Some("sprite_material_bind_group"),
-                                    layout: &sprite_pipeline.material_layout,
-                                })
-                            });
                     } else {
                         // Skip this item if the texture is not ready
                         continue;
                     }
                 }
+                let colored = extracted_sprite.color != Color::WHITE;
+                let sliced = extracted_sprite.scale_mode != ImageScaleMode::Stretched;
+
+                // Add the sprite to the current batch if it's compatible, or set-up a new
+                // possible batch
+                let batched = match &mut current_batch {
+                    Some((entity, batch)) if batch.colored == colored && batch.sliced == sliced => {
+                        batch
+                            .texture_index(image_handle_id, max_batch_images)
+                            .map(|texture_index| (*entity, texture_index))
+                    }
+                    _ => None,
+                };
+                let (current_batch_entity, texture_index) = match batched {
+                    Some(batched) => batched,
+                    None => {
+                        if let Some((entity, batch)) = current_batch.take() {
+                            if batch.colored == colored && batch.sliced == sliced {
+                                batch_stats.texture_breaks += 1;
+                            }
+                            batches.push((entity, batch));
+                        }
+                        batch_stats.batches += 1;
+                        let entity = commands.spawn_empty().id();
+                        current_batch = Some((
+                            entity,
+                            SpriteBatch {
+                                images: vec![image_handle_id],
+                                colored,
+                                sliced,
+                            },
+                        ));
+                        (entity, 0)
+                    }
+                };
+                batch_stats.sprites += 1;
 
                 // Calculate vertex data for this item
 
@@ -668,6 +898,7 @@ pub fn queue_sprites(
 
                 // By default, the size of the quad is the size of the texture
                 let mut quad_size = current_image_size;
+                let quad_uvs = uvs;
 
                 // If a rect is specified, adjust UVs and the size of the quad
                 if let Some(rect) = extracted_sprite.rect {
@@ -694,16 +925,49 @@ pub fn queue_sprites(
                 });
 
                 // These items will be sorted by depth with other phase items
-                let sort_key = FloatOrd(extracted_sprite.transform.translation().z);
+                let sort_key = FloatOrd(extracted_sprite.sort_keys.0);
 
                 // Store the vertex data and add the item to the render phase
-                if current_batch.colored {
+                if sliced {
+                    // The sliced shader maps coordinates inside the quad to the image itself
+                    let slice = TextureSliceVertex::new(
+                        &extracted_sprite.scale_mode,
+                        extracted_sprite.rect.unwrap_or(Rect {
+                            min: Vec2::ZERO,
+                            max: current_image_size,
+                        }),
+                        current_image_size,
+                        quad_size,
+                    );
+                    let vertex_color = extracted_sprite.color.as_linear_rgba_f32();
+                    for i in QUAD_INDICES {
+                        sprite_meta.sliced_vertices.push(SlicedSpriteVertex {
+                            position: positions[i],
+                            uv: (quad_uvs[i] * quad_size).into(),
+                            color: vertex_color,
+                            slice,
+                            texture_index,
+                        });
+                    }
+                    let item_start = sliced_index;
+                    sliced_index += QUAD_INDICES.len() as u32;
+                    let item_end = sliced_index;
+
+                    transparent_phase.add(Transparent2d {
+                        draw_function: draw_sprite_function,
+                        pipeline: sliced_pipeline,
+                        entity: current_batch_entity,
+                        sort_key,
+                        batch_range: Some(item_start..item_end),
+                    });
+                } else if colored {
                     let vertex_color = extracted_sprite.color.as_linear_rgba_f32();
                     for i in QUAD_INDICES {
                         sprite_meta.colored_vertices.push(ColoredSpriteVertex {
                             position: positions[i],
                             uv: uvs[i].into(),
                             color: vertex_color,
+                            texture_index,
                         });
                     }
                     let item_start = colored_index;
@@ -722,6 +986,7 @@ pub fn queue_sprites(
                         sprite_meta.vertices.push(SpriteVertex {
                             position: positions[i],
                             uv: uvs[i].into(),
+                            texture_index,
                         });
                     }
                     let item_start = index;
@@ -737,13 +1002,40 @@ pub fn queue_sprites(
                     });
                 }
             }
+            batches.extend(current_batch);
         }
+
+        // Reuse the bind groups of the batches sampling from the same images as last frame
+        let mut previous_bind_groups = mem::take(&mut image_bind_groups.values);
+        for (_, batch) in &batches {
+            if image_bind_groups.values.contains_key(&batch.images) {
+                continue;
+            }
+            let bind_group = previous_bind_groups
+                .remove(&batch.images)
+                .unwrap_or_else(|| {
+                    let images: Vec<_> = batch
+                        .images
+                        .iter()
+                        .filter_map(|id| gpu_images.get(&Handle::weak(*id)))
+                        .collect();
+                    sprite_pipeline.create_material_bind_group(&render_device, &images)
+                });
+            image_bind_groups
+                .values
+                .insert(batch.images.clone(), bind_group);
+        }
+        commands.insert_or_spawn_batch(batches);
+
         sprite_meta
             .vertices
             .write_buffer(&render_device, &render_queue);
         sprite_meta
             .colored_vertices
             .write_buffer(&render_device, &render_queue);
+        sprite_meta
+            .sliced_vertices
+            .write_buffer(&render_device, &render_queue);
     }
 }
 
@@ -792,10 +1084,7 @@ impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetSpriteTextureBindGrou
 
         pass.set_bind_group(
             I,
-            image_bind_groups
-                .values
-                .get(&Handle::weak(sprite_batch.image_handle_id))
-                .unwrap(),
+            image_bind_groups.values.get(&sprite_batch.images).unwrap(),
             &[],
         );
         RenderCommandResult::Success
@@ -816,7 +1105,9 @@ impl<P: BatchedPhaseItem> RenderCommand<P> for DrawSpriteBatch {
         pass: &mut TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
         let sprite_meta = sprite_meta.into_inner();
-        if sprite_batch.colored {
+        if sprite_batch.sliced {
+            pass.set_vertex_buffer(0, sprite_meta.sliced_vertices.buffer().unwrap().slice(..));
+        } else if sprite_batch.colored {
             pass.set_vertex_buffer(0, sprite_meta.colored_vertices.buffer().unwrap().slice(..));
         } else {
             pass.set_vertex_buffer(0, sprite_meta.vertices.buffer().unwrap().slice(..));
@@ -825,3 +1116,37 @@ impl<P: BatchedPhaseItem> RenderCommand<P> for DrawSpriteBatch {
         RenderCommandResult::Success
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bevy_asset::HandleId;
+    use bevy_render::texture::Image;
+
+    use super::SpriteBatch;
+
+    #[test]
+    fn sprite_batch_texture_index() {
+        let images: Vec<_> = (0..3).map(|_| HandleId::random::<Image>()).collect();
+        let mut batch = SpriteBatch {
+            images: vec![images[0]],
+            colored: false,
+            sliced: false,
+        };
+
+        // Sprites using different images share the batch until it's full
+        assert_eq!(batch.texture_index(images[1], 2), Some(1));
+        assert_eq!(batch.texture_index(images[0], 2), Some(0));
+        assert_eq!(batch.texture_index(images[1], 2), Some(1));
+        assert_eq!(batch.texture_index(images[2], 2), None);
+        assert_eq!(batch.images, images[..2]);
+
+        // Without texture arrays, each image breaks the batch
+        let mut batch = SpriteBatch {
+            images: vec![images[0]],
+            colored: false,
+            sliced: false,
+        };
+        assert_eq!(batch.texture_index(images[0], 1), Some(0));
+        assert_eq!(batch.texture_index(images[1], 1), None);
+    }
+}
@@ -4,6 +4,10 @@
 
 #import bevy_render::view
 
+#ifdef SLICED
+#import bevy_sprite::texture_slice
+#endif
+
 @group(0) @binding(0)
 var<uniform> view: View;
 
@@ -11,6 +15,17 @@ struct VertexOutput {
     @location(0) uv: vec2<f32>,
 #ifdef COLORED
     @location(1) color: vec4<f32>,
+#endif
+#ifdef SLICED
+    @location(2) uv_rect: vec4<f32>,
+    @location(3) size: vec2<f32>,
+    @location(4) texels: vec2<f32>,
+    @location(5) border: vec4<f32>,
+    @location(6) tile_scale: vec2<f32>,
+    @location(7) @interpolate(flat) slice_flags: u32,
+#endif
+#ifdef TEXTURE_ARRAY
+    @location(8) @interpolate(flat) texture_index: u32,
 #endif
     @builtin(position) position: vec4<f32>,
 };
@@ -22,24 +37,74 @@ fn vertex(
 #ifdef COLORED
     @location(2) vertex_color: vec4<f32>,
 #endif
+#ifdef SLICED
+    @location(3) uv_rect: vec4<f32>,
+    @location(4) size: vec2<f32>,
+    @location(5) texels: vec2<f32>,
+    @location(6) border: vec4<f32>,
+    @location(7) tile_scale: vec2<f32>,
+    @location(8) slice_flags: u32,
+#endif
+#ifdef TEXTURE_ARRAY
+    // NOTE: This location must match `TEXTURE_INDEX_SHADER_LOCATION` in bevy_sprite/src/render/mod.rs!
+    @location(9) texture_index: u32,
+#endif
 ) -> VertexOutput {
     var out: VertexOutput;
     out.uv = vertex_uv;
     out.position = view.view_proj * vec4<f32>(vertex_position, 1.0);
+#ifdef SNAP_TO_PIXELS
+    // Moves the vertex to the closest corner of a pixel of the view
+    let pixel = round((out.position.xy / out.position.w * 0.5 + 0.5) * view.viewport.zw);
+    out.position = vec4<f32>((pixel / view.viewport.zw * 2.0 - 1.0) * out.position.w, out.position.zw);
+#endif
 #ifdef COLORED
     out.color = vertex_color;
+#endif
+#ifdef SLICED
+    out.uv_rect = uv_rect;
+    out.size = size;
+    out.texels = texels;
+    out.border = border;
+    out.tile_scale = tile_scale;
+    out.slice_flags = slice_flags;
+#endif
+#ifdef TEXTURE_ARRAY
+    out.texture_index = texture_index;
 #endif
     return out;
 }
 
+#ifdef TEXTURE_ARRAY
+@group(1) @binding(0)
+var sprite_textures: binding_array<texture_2d<f32>, #{SPRITE_TEXTURE_ARRAY_SIZE}>;
+@group(1) @binding(1)
+var sprite_samplers: binding_array<sampler, #{SPRITE_TEXTURE_ARRAY_SIZE}>;
+#else
 @group(1) @binding(0)
 var sprite_texture: texture_2d<f32>;
 @group(1) @binding(1)
 var sprite_sampler: sampler;
+#endif
 
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+#ifdef SLICED
+    // The UVs of sliced sprites are their coordinates inside the quad
+    let uv = texture_slice_uv(in.uv, in.uv_rect, in.size, in.texels, in.border, in.tile_scale, in.slice_flags);
+    let uv_per_unit = texture_slice_uv_per_unit(in.uv_rect, in.texels);
+#ifdef TEXTURE_ARRAY
+    var color = textureSampleGrad(sprite_textures[in.texture_index], sprite_samplers[in.texture_index], uv, dpdx(in.uv) * uv_per_unit, dpdy(in.uv) * uv_per_unit);
+#else
+    var color = textureSampleGrad(sprite_texture, sprite_sampler, uv, dpdx(in.uv) * uv_per_unit, dpdy(in.uv) * uv_per_unit);
+#endif
+#else
+#ifdef TEXTURE_ARRAY
+    var color = textureSample(sprite_textures[in.texture_index], sprite_samplers[in.texture_index], in.uv);
+#else
     var color = textureSample(sprite_texture, sprite_sampler, in.uv);
+#endif
+#endif
 #ifdef COLORED
     color = in.color * color;
 #endif
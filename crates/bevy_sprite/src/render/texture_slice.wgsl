@@ -0,0 +1,73 @@
+#define_import_path bevy_sprite::texture_slice
+
+// NOTE: These must match the bit flags in bevy_sprite/src/texture_slice.rs!
+const TEXTURE_SLICE_FLAGS_CENTER_TILE_X: u32 = 1u;
+const TEXTURE_SLICE_FLAGS_CENTER_TILE_Y: u32 = 2u;
+const TEXTURE_SLICE_FLAGS_SIDES_TILE_X: u32 = 4u;
+const TEXTURE_SLICE_FLAGS_SIDES_TILE_Y: u32 = 8u;
+
+// Maps a coordinate along one axis of the quad to the same axis of the image, in pixels.
+// The borders are drawn at the size of the image times `fit`, and the part between them is
+// either stretched or tiled.
+fn texture_slice_axis(
+    p: f32,
+    size: f32,
+    texels: f32,
+    border_start: f32,
+    border_end: f32,
+    fit: f32,
+    tile: bool,
+    tile_scale: f32,
+) -> f32 {
+    let start = border_start * fit;
+    let end = border_end * fit;
+    if p < start {
+        return p / fit;
+    }
+    if p > size - end {
+        return texels - (size - p) / fit;
+    }
+
+    let middle_texels = texels - border_start - border_end;
+    var t = (p - start) / max(size - start - end, 0.0001);
+    if tile {
+        t = fract((p - start) / max(middle_texels * tile_scale, 0.0001));
+    }
+    return border_start + t * middle_texels;
+}
+
+// Returns the UV of the texture at the coordinate `local` inside a sliced or tiled quad, from
+// `(0, 0)` at its top left to `size` at its bottom right.
+fn texture_slice_uv(
+    local: vec2<f32>,
+    uv_rect: vec4<f32>,
+    size: vec2<f32>,
+    texels: vec2<f32>,
+    border: vec4<f32>,
+    tile_scale: vec2<f32>,
+    flags: u32,
+) -> vec2<f32> {
+    // The borders shrink when the quad is too small to fit them
+    let fit = min(vec2<f32>(1.0), size / max(border.xz + border.yw, vec2<f32>(0.0001)));
+
+    let middle_x = local.x >= border.x * fit.x && local.x <= size.x - border.y * fit.x;
+    let middle_y = local.y >= border.z * fit.y && local.y <= size.y - border.w * fit.y;
+    let center = middle_x && middle_y;
+
+    // Edges are scaled along their length, like the center
+    let tile_x_bit = select(TEXTURE_SLICE_FLAGS_SIDES_TILE_X, TEXTURE_SLICE_FLAGS_CENTER_TILE_X, center);
+    let tile_y_bit = select(TEXTURE_SLICE_FLAGS_SIDES_TILE_Y, TEXTURE_SLICE_FLAGS_CENTER_TILE_Y, center);
+    let scale = select(tile_scale.y, tile_scale.x, center);
+
+    let texel = vec2<f32>(
+        texture_slice_axis(local.x, size.x, texels.x, border.x, border.y, fit.x, (flags & tile_x_bit) != 0u, scale),
+        texture_slice_axis(local.y, size.y, texels.y, border.z, border.w, fit.y, (flags & tile_y_bit) != 0u, scale),
+    );
+    return mix(uv_rect.xy, uv_rect.zw, texel / max(texels, vec2<f32>(0.0001)));
+}
+
+// The change in UV per unit of `local`, to sample the texture without seams where the UVs jump
+// between slices or tiles.
+fn texture_slice_uv_per_unit(uv_rect: vec4<f32>, texels: vec2<f32>) -> vec2<f32> {
+    return (uv_rect.zw - uv_rect.xy) / max(texels, vec2<f32>(0.0001));
+}
@@ -0,0 +1,190 @@
+use bevy_asset::{AssetEvent, Assets, Handle, HandleId};
+use bevy_ecs::prelude::*;
+use bevy_log::error;
+use bevy_render::texture::Image;
+use bevy_utils::HashSet;
+
+use crate::{TextureAtlas, TextureAtlasBuilder};
+
+/// Packs images loaded at runtime, like the sprites of a mod, into a [`TextureAtlas`].
+///
+/// The images are packed by [`pack_runtime_texture_atlases`] once they're all loaded, and again
+/// each time one of them is modified, for example when it's hot-reloaded. The index of each image
+/// in the atlas stays the same when the atlas is packed again, see
+/// [`TextureAtlas::get_texture_index`], and the atlas keeps its texture, so that all of its
+/// sprites can still be batched together.
+#[derive(Component, Debug, Clone)]
+pub struct RuntimeTextureAtlas {
+    /// The atlas the images are packed into.
+    pub atlas: Handle<TextureAtlas>,
+    /// The images packed into the atlas. Images added later come after the others in the atlas.
+    pub textures: Vec<Handle<Image>>,
+    /// The builder the images are packed with, with its size, padding and bleed options.
+    pub builder: TextureAtlasBuilder,
+}
+
+impl RuntimeTextureAtlas {
+    /// Packs `textures` into `atlas` with a default [`TextureAtlasBuilder`].
+    pub fn new(atlas: Handle<TextureAtlas>, textures: Vec<Handle<Image>>) -> Self {
+        RuntimeTextureAtlas {
+            atlas,
+            textures,
+            builder: TextureAtlasBuilder::default(),
+        }
+    }
+
+    /// Returns this [`RuntimeTextureAtlas`] packing its images with `builder`.
+    pub fn with_builder(mut self, builder: TextureAtlasBuilder) -> Self {
+        self.builder = builder;
+        self
+    }
+}
+
+/// Packs the images of each [`RuntimeTextureAtlas`] again when one of them is loaded or
+/// modified, or when the [`RuntimeTextureAtlas`] itself changes.
+pub fn pack_runtime_texture_atlases(
+    mut image_events: EventReader<AssetEvent<Image>>,
+    mut images: ResMut<Assets<Image>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    runtime_atlases: Query<Ref<RuntimeTextureAtlas>>,
+) {
+    let changed_images: HashSet<HandleId> = image_events
+        .iter()
+        .filter_map(|event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => Some(handle.id()),
+            AssetEvent::Removed { .. } | AssetEvent::LoadedWithDependencies { .. } => None,
+        })
+        .collect();
+
+    for runtime_atlas in &runtime_atlases {
+        let changed = runtime_atlas.is_changed()
+            || runtime_atlas
+                .textures
+                .iter()
+                .any(|texture| changed_images.contains(&texture.id()));
+        // The atlas is packed once all of its images are loaded
+        if !changed
+            || runtime_atlas
+                .textures
+                .iter()
+                .any(|texture| images.get(texture).is_none())
+        {
+            continue;
+        }
+        let Some(atlas) = texture_atlases.get_mut(&runtime_atlas.atlas) else {
+            continue;
+        };
+
+        let mut builder = runtime_atlas.builder.clone();
+        for texture in &runtime_atlas.textures {
+            builder.add_texture(texture.clone_weak(), images.get(texture).unwrap());
+        }
+        if let Err(err) = builder.repack(atlas, &mut images) {
+            error!("Failed to pack runtime texture atlas: {err}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_math::{Rect, Vec2};
+    use bevy_render::render_resource::{Extent3d, TextureDimension, TextureFormat};
+
+    fn image(width: u32, height: u32, color: [u8; 4]) -> Image {
+        Image::new_fill(
+            Extent3d {
+                width,
+                height,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            &color,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    fn pixel(image: &Image, x: u32, y: u32) -> &[u8] {
+        let index = ((y * image.texture_descriptor.size.width + x) * 4) as usize;
+        &image.data[index..index + 4]
+    }
+
+    #[test]
+    fn runtime_texture_atlas() {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default())
+            .add_asset::<Image>()
+            .add_asset::<TextureAtlas>()
+            .add_system(pack_runtime_texture_atlases);
+
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        let red = images.add(image(4, 2, [255, 0, 0, 255]));
+        let blue = images.add(image(3, 3, [0, 0, 255, 255]));
+        let atlas = app
+            .world
+            .resource_mut::<Assets<TextureAtlas>>()
+            .add(TextureAtlas::new_empty(Handle::default(), Vec2::ZERO));
+        let builder = TextureAtlasBuilder::default()
+            .initial_size(Vec2::splat(16.0))
+            .padding(1)
+            .bleed(1);
+        let entity = app
+            .world
+            .spawn(
+                RuntimeTextureAtlas::new(atlas.clone(), vec![red.clone(), blue.clone()])
+                    .with_builder(builder),
+            )
+            .id();
+        app.update();
+
+        let texture_atlases = app.world.resource::<Assets<TextureAtlas>>();
+        let packed = texture_atlases.get(&atlas).unwrap().clone();
+        assert_eq!(packed.len(), 2);
+        assert_eq!(packed.get_texture_index(&red), Some(0));
+        assert_eq!(packed.get_texture_index(&blue), Some(1));
+        let images = app.world.resource::<Assets<Image>>();
+        let atlas_image = images.get(&packed.texture).unwrap();
+        let red_rect = packed.textures[0];
+        assert_eq!(red_rect.size(), Vec2::new(4.0, 2.0));
+        // The edges of the texture are repeated around it
+        let Rect { min, max } = red_rect;
+        for (x, y) in [(min.x - 1.0, min.y - 1.0), (max.x, max.y)] {
+            assert_eq!(pixel(atlas_image, x as u32, y as u32), [255, 0, 0, 255]);
+        }
+        // Then followed by the padding
+        assert_eq!(
+            pixel(atlas_image, max.x as u32 + 1, max.y as u32 + 1),
+            [0, 0, 0, 0]
+        );
+
+        // Hot-reloading an image packs the atlas again, with the same indices and texture
+        app.world
+            .resource_mut::<Assets<Image>>()
+            .set_untracked(red.clone_weak(), image(8, 8, [0, 255, 0, 255]));
+        app.update();
+        app.update();
+        let texture_atlases = app.world.resource::<Assets<TextureAtlas>>();
+        let repacked = texture_atlases.get(&atlas).unwrap();
+        assert_eq!(repacked.texture, packed.texture);
+        assert_eq!(repacked.get_texture_index(&red), Some(0));
+        assert_eq!(repacked.textures[0].size(), Vec2::new(8.0, 8.0));
+        assert_eq!(repacked.textures[1].size(), Vec2::new(3.0, 3.0));
+
+        // Images added later come after the others
+        let mut images = app.world.resource_mut::<Assets<Image>>();
+        let white = images.add(image(2, 2, [255; 4]));
+        app.world
+            .get_mut::<RuntimeTextureAtlas>(entity)
+            .unwrap()
+            .textures
+            .insert(0, white.clone());
+        app.update();
+        let texture_atlases = app.world.resource::<Assets<TextureAtlas>>();
+        let repacked = texture_atlases.get(&atlas).unwrap();
+        assert_eq!(repacked.get_texture_index(&red), Some(0));
+        assert_eq!(repacked.get_texture_index(&blue), Some(1));
+        assert_eq!(repacked.get_texture_index(&white), Some(2));
+    }
+}
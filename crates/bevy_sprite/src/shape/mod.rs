@@ -0,0 +1,261 @@
+mod render;
+
+pub use render::*;
+
+use bevy_app::{App, IntoSystemAppConfig, Plugin};
+use bevy_asset::{load_internal_asset, HandleUntyped};
+use bevy_core_pipeline::core_2d::Transparent2d;
+use bevy_ecs::prelude::*;
+use bevy_math::{Rect, Vec2};
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect, TypeUuid};
+use bevy_render::{
+    color::Color,
+    primitives::Aabb,
+    render_phase::AddRenderCommand,
+    render_resource::{Shader, SpecializedRenderPipelines},
+    view::{InheritedVisibility, NoFrustumCulling, ViewVisibility, Visibility, VisibilitySystems},
+    ExtractSchedule, RenderApp, RenderSet,
+};
+use bevy_transform::components::{GlobalTransform, Transform};
+
+pub const SHAPE_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 5868072117079107873);
+
+/// Renders the [`Shape2d`]s, drawing their signed distance field on a quad for each shape.
+#[derive(Default)]
+pub struct ShapePlugin;
+
+impl Plugin for ShapePlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, SHAPE_SHADER_HANDLE, "shape.wgsl", Shader::from_wgsl);
+
+        app.register_type::<Shape2d>()
+            .register_type::<Fill>()
+            .register_type::<Stroke>()
+            .add_system(calculate_shape_bounds.in_set(VisibilitySystems::CalculateBounds));
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<ShapePipeline>()
+                .init_resource::<SpecializedRenderPipelines<ShapePipeline>>()
+                .init_resource::<ExtractedShapes>()
+                .init_resource::<ShapeMeta>()
+                .add_render_command::<Transparent2d, DrawShape>()
+                .add_system(extract_shapes.in_schedule(ExtractSchedule))
+                .add_system(queue_shapes.in_set(RenderSet::Queue));
+        }
+    }
+}
+
+/// A 2d shape drawn from its signed distance field, with a [`Fill`] inside it and a [`Stroke`]
+/// along its outline.
+///
+/// The shapes are drawn by the GPU on a single quad each, without tessellating them into meshes,
+/// and their edges are anti-aliased whatever their scale. They're sorted with the sprites by the
+/// `Z` of their [`GlobalTransform`], and drawn together in as few draw calls as possible.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub enum Shape2d {
+    /// A circle centered on the origin.
+    Circle { radius: f32 },
+    /// A rectangle centered on the origin, with its corners rounded by `corner_radius`.
+    Rectangle { size: Vec2, corner_radius: f32 },
+    /// A line from `start` to `end` of `width`, with round caps.
+    Line { start: Vec2, end: Vec2, width: f32 },
+}
+
+impl Shape2d {
+    /// Creates a circle of `radius`.
+    pub const fn circle(radius: f32) -> Self {
+        Shape2d::Circle { radius }
+    }
+
+    /// Creates a rectangle of `size` with sharp corners.
+    pub const fn rectangle(size: Vec2) -> Self {
+        Shape2d::Rectangle {
+            size,
+            corner_radius: 0.0,
+        }
+    }
+
+    /// Creates a rectangle of `size` with its corners rounded by `corner_radius`.
+    pub const fn rounded_rectangle(size: Vec2, corner_radius: f32) -> Self {
+        Shape2d::Rectangle {
+            size,
+            corner_radius,
+        }
+    }
+
+    /// Creates a line from `start` to `end` of `width`.
+    pub const fn line(start: Vec2, end: Vec2, width: f32) -> Self {
+        Shape2d::Line { start, end, width }
+    }
+
+    /// The signed distance from `point` to the outline of the shape, negative inside it.
+    ///
+    /// This is the distance the shader draws the shape from.
+    pub fn distance(&self, point: Vec2) -> f32 {
+        // NOTE: This must match the distance functions in `bevy_sprite/src/shape/shape.wgsl`!
+        match *self {
+            Shape2d::Circle { radius } => point.length() - radius,
+            Shape2d::Rectangle {
+                size,
+                corner_radius,
+            } => {
+                let half_size = size.abs() * 0.5;
+                let radius = corner_radius.clamp(0.0, half_size.min_element());
+                let q = point.abs() - half_size + radius;
+                q.max(Vec2::ZERO).length() + q.max_element().min(0.0) - radius
+            }
+            Shape2d::Line { start, end, width } => {
+                let direction = end - start;
+                let t = ((point - start).dot(direction) / direction.length_squared().max(1e-6))
+                    .clamp(0.0, 1.0);
+                (point - start - direction * t).length() - width * 0.5
+            }
+        }
+    }
+
+    /// The bounds of the shape, in its local space.
+    pub fn bounds(&self) -> Rect {
+        match *self {
+            Shape2d::Circle { radius } => {
+                Rect::from_center_half_size(Vec2::ZERO, Vec2::splat(radius))
+            }
+            Shape2d::Rectangle { size, .. } => Rect::from_center_size(Vec2::ZERO, size.abs()),
+            Shape2d::Line { start, end, width } => {
+                let half_width = Vec2::splat(width * 0.5);
+                Rect {
+                    min: start.min(end) - half_width,
+                    max: start.max(end) + half_width,
+                }
+            }
+        }
+    }
+}
+
+impl Default for Shape2d {
+    fn default() -> Self {
+        Shape2d::circle(50.0)
+    }
+}
+
+/// The color inside a [`Shape2d`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct Fill {
+    pub color: Color,
+}
+
+impl Fill {
+    /// Fills a shape with `color`, or leaves it empty with [`Color::NONE`].
+    pub const fn color(color: Color) -> Self {
+        Fill { color }
+    }
+}
+
+impl Default for Fill {
+    fn default() -> Self {
+        Fill {
+            color: Color::WHITE,
+        }
+    }
+}
+
+/// The outline of a [`Shape2d`], centered on its edge.
+///
+/// The default stroke has a width of 0.0, and isn't drawn.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct Stroke {
+    pub color: Color,
+    /// The width of the outline, in the units of the shape.
+    pub width: f32,
+}
+
+impl Stroke {
+    /// Outlines a shape with `color`, over `width`.
+    pub const fn new(color: Color, width: f32) -> Self {
+        Stroke { color, width }
+    }
+}
+
+/// A component bundle for entities with a [`Shape2d`].
+#[derive(Bundle, Clone, Debug, Default)]
+pub struct ShapeBundle {
+    pub shape: Shape2d,
+    pub fill: Fill,
+    pub stroke: Stroke,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    /// User indication of whether an entity is visible
+    pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+}
+
+/// The bounds of `shape` and of the outside half of its `stroke`.
+fn shape_aabb(shape: &Shape2d, stroke: Option<&Stroke>) -> Aabb {
+    let bounds = shape.bounds();
+    let stroke_width = stroke.map_or(0.0, |stroke| stroke.width.max(0.0));
+    Aabb {
+        center: bounds.center().extend(0.0).into(),
+        half_extents: (bounds.half_size() + stroke_width * 0.5).extend(0.0).into(),
+    }
+}
+
+/// Sets the [`Aabb`] of each [`Shape2d`] when it changes, for it to be culled when it's outside
+/// of the view of every camera.
+#[allow(clippy::type_complexity)]
+pub fn calculate_shape_bounds(
+    mut commands: Commands,
+    mut shapes: Query<
+        (Entity, &Shape2d, Option<&Stroke>, Option<&mut Aabb>),
+        (
+            Without<NoFrustumCulling>,
+            Or<(Without<Aabb>, Changed<Shape2d>, Changed<Stroke>)>,
+        ),
+    >,
+) {
+    for (entity, shape, stroke, aabb) in &mut shapes {
+        let new_aabb = shape_aabb(shape, stroke);
+        match aabb {
+            Some(mut aabb) => *aabb = new_aabb,
+            None => {
+                commands.entity(entity).insert(new_aabb);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shape_distance() {
+        let circle = Shape2d::circle(2.0);
+        assert_eq!(circle.distance(Vec2::ZERO), -2.0);
+        assert_eq!(circle.distance(Vec2::new(0.0, 5.0)), 3.0);
+
+        let rectangle = Shape2d::rounded_rectangle(Vec2::new(4.0, 2.0), 0.5);
+        assert_eq!(rectangle.distance(Vec2::ZERO), -1.0);
+        assert_eq!(rectangle.distance(Vec2::new(3.0, 0.0)), 1.0);
+        // The corners are rounded, so they're further than the sides
+        let corner = rectangle.distance(Vec2::new(2.0, 1.0));
+        assert!((corner - (0.5 * 2.0f32.sqrt() - 0.5)).abs() < 1e-6);
+        assert_eq!(rectangle.bounds(), Rect::new(-2.0, -1.0, 2.0, 1.0),);
+
+        let line = Shape2d::line(Vec2::ZERO, Vec2::new(10.0, 0.0), 2.0);
+        assert_eq!(line.distance(Vec2::new(5.0, 3.0)), 2.0);
+        // The caps are round
+        assert_eq!(line.distance(Vec2::new(13.0, 4.0)), 4.0);
+        assert_eq!(line.bounds(), Rect::new(-1.0, -1.0, 11.0, 1.0));
+
+        let aabb = shape_aabb(&line, Some(&Stroke::new(Color::BLACK, 2.0)));
+        assert_eq!(aabb.center, Vec2::new(5.0, 0.0).extend(0.0).into());
+        assert_eq!(aabb.half_extents, Vec2::new(7.0, 2.0).extend(0.0).into());
+    }
+}
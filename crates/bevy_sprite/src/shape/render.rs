@@ -0,0 +1,313 @@
+use bevy_core_pipeline::core_2d::Transparent2d;
+use bevy_ecs::{
+    prelude::*,
+    system::{lifetimeless::*, SystemParamItem},
+};
+use bevy_render::{
+    render_phase::{
+        BatchedPhaseItem, DrawFunctions, RenderCommand, RenderCommandResult, RenderPhase,
+        SetItemPipeline, TrackedRenderPass,
+    },
+    render_resource::*,
+    renderer::{RenderDevice, RenderQueue},
+    texture::BevyDefault,
+    view::{ExtractedView, Msaa, ViewTarget, ViewVisibility, VisibleEntities},
+    Extract,
+};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::FloatOrd;
+use bytemuck::{Pod, Zeroable};
+use fixedbitset::FixedBitSet;
+
+use super::{Fill, Shape2d, Stroke, SHAPE_SHADER_HANDLE};
+use crate::{Mesh2dPipeline, SetMesh2dViewBindGroup};
+
+#[derive(Resource)]
+pub struct ShapePipeline {
+    view_layout: BindGroupLayout,
+}
+
+impl FromWorld for ShapePipeline {
+    fn from_world(world: &mut World) -> Self {
+        // The view and globals are bound like for 2d meshes
+        ShapePipeline {
+            view_layout: world.resource::<Mesh2dPipeline>().view_layout.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct ShapePipelineKey {
+    pub hdr: bool,
+    pub msaa_samples: u32,
+}
+
+impl SpecializedRenderPipeline for ShapePipeline {
+    type Key = ShapePipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        // Each shape is an instance, whose quad is generated from the vertex index
+        let instance_layout = VertexBufferLayout::from_vertex_formats(
+            VertexStepMode::Instance,
+            vec![
+                // transform
+                VertexFormat::Float32x4,
+                VertexFormat::Float32x4,
+                VertexFormat::Float32x4,
+                VertexFormat::Float32x4,
+                // params
+                VertexFormat::Float32x4,
+                // fill
+                VertexFormat::Float32x4,
+                // stroke
+                VertexFormat::Float32x4,
+                // kind
+                VertexFormat::Uint32,
+                // radius
+                VertexFormat::Float32,
+                // stroke_width
+                VertexFormat::Float32,
+            ],
+        );
+
+        RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: SHAPE_SHADER_HANDLE.typed::<Shader>(),
+                entry_point: "vertex".into(),
+                shader_defs: Vec::new(),
+                buffers: vec![instance_layout],
+            },
+            fragment: Some(FragmentState {
+                shader: SHAPE_SHADER_HANDLE.typed::<Shader>(),
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: if key.hdr {
+                        ViewTarget::TEXTURE_FORMAT_HDR
+                    } else {
+                        TextureFormat::bevy_default()
+                    },
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            layout: vec![self.view_layout.clone()],
+            primitive: PrimitiveState {
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: PolygonMode::Fill,
+                conservative: false,
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: key.msaa_samples,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            label: Some("shape_pipeline".into()),
+            push_constant_ranges: Vec::new(),
+        }
+    }
+}
+
+/// The data of a [`Shape2d`] on the GPU.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct ShapeInstance {
+    /// The columns of the transform of the shape
+    pub transform: [[f32; 4]; 4],
+    /// The size of a rectangle, or the start and end of a line
+    pub params: [f32; 4],
+    pub fill: [f32; 4],
+    pub stroke: [f32; 4],
+    pub kind: u32,
+    /// The radius of a circle, the corner radius of a rectangle or the half width of a line
+    pub radius: f32,
+    pub stroke_width: f32,
+}
+
+impl ShapeInstance {
+    // NOTE: These must match the constants in bevy_sprite/src/shape/shape.wgsl!
+    pub const CIRCLE: u32 = 0;
+    pub const RECTANGLE: u32 = 1;
+    pub const LINE: u32 = 2;
+
+    pub fn new(
+        shape: &Shape2d,
+        fill: Option<&Fill>,
+        stroke: Option<&Stroke>,
+        transform: &GlobalTransform,
+    ) -> Self {
+        let (kind, params, radius) = match *shape {
+            Shape2d::Circle { radius } => (Self::CIRCLE, [0.0; 4], radius),
+            Shape2d::Rectangle {
+                size,
+                corner_radius,
+            } => (
+                Self::RECTANGLE,
+                [size.x.abs(), size.y.abs(), 0.0, 0.0],
+                corner_radius,
+            ),
+            Shape2d::Line { start, end, width } => {
+                (Self::LINE, [start.x, start.y, end.x, end.y], width * 0.5)
+            }
+        };
+        let stroke = stroke.copied().unwrap_or_default();
+        ShapeInstance {
+            transform: transform.compute_matrix().to_cols_array_2d(),
+            params,
+            fill: fill.map_or([0.0; 4], |fill| fill.color.as_linear_rgba_f32()),
+            stroke: stroke.color.as_linear_rgba_f32(),
+            kind,
+            radius,
+            stroke_width: stroke.width.max(0.0),
+        }
+    }
+}
+
+pub struct ExtractedShape {
+    pub entity: Entity,
+    pub instance: ShapeInstance,
+    pub sort_key: FloatOrd,
+}
+
+#[derive(Resource, Default)]
+pub struct ExtractedShapes {
+    pub shapes: Vec<ExtractedShape>,
+}
+
+pub fn extract_shapes(
+    mut extracted_shapes: ResMut<ExtractedShapes>,
+    shape_query: Extract<
+        Query<(
+            Entity,
+            &ViewVisibility,
+            &Shape2d,
+            Option<&Fill>,
+            Option<&Stroke>,
+            &GlobalTransform,
+        )>,
+    >,
+) {
+    extracted_shapes.shapes.clear();
+    for (entity, visibility, shape, fill, stroke, transform) in &shape_query {
+        if !visibility.get() {
+            continue;
+        }
+        extracted_shapes.shapes.push(ExtractedShape {
+            entity,
+            instance: ShapeInstance::new(shape, fill, stroke, transform),
+            sort_key: FloatOrd(transform.translation().z),
+        });
+    }
+}
+
+#[derive(Resource)]
+pub struct ShapeMeta {
+    instances: BufferVec<ShapeInstance>,
+}
+
+impl Default for ShapeMeta {
+    fn default() -> Self {
+        ShapeMeta {
+            instances: BufferVec::new(BufferUsages::VERTEX),
+        }
+    }
+}
+
+/// The entity of the phase items drawing shapes, which all share it to be batched together.
+#[derive(Component)]
+pub struct ShapeBatch;
+
+#[allow(clippy::too_many_arguments)]
+pub fn queue_shapes(
+    mut commands: Commands,
+    mut view_entities: Local<FixedBitSet>,
+    draw_functions: Res<DrawFunctions<Transparent2d>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    shape_pipeline: Res<ShapePipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<ShapePipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    mut shape_meta: ResMut<ShapeMeta>,
+    mut extracted_shapes: ResMut<ExtractedShapes>,
+    mut views: Query<(
+        &mut RenderPhase<Transparent2d>,
+        &VisibleEntities,
+        &ExtractedView,
+        &Msaa,
+    )>,
+) {
+    shape_meta.instances.clear();
+    if extracted_shapes.shapes.is_empty() {
+        return;
+    }
+
+    // Consecutive shapes are drawn with a single draw call, once their items are merged by
+    // `batch_phase_system`
+    // NOTE: Items with the same sort key keep this order in the render phase, as it is sorted with
+    // a stable sort
+    let extracted_shapes = &mut extracted_shapes.shapes;
+    extracted_shapes.sort_by_key(|shape| shape.sort_key);
+    let batch = commands.spawn(ShapeBatch).id();
+    let draw_shape_function = draw_functions.read().id::<DrawShape>();
+
+    for (mut transparent_phase, visible_entities, view, msaa) in &mut views {
+        let pipeline = pipelines.specialize(
+            &pipeline_cache,
+            &shape_pipeline,
+            ShapePipelineKey {
+                hdr: view.hdr,
+                msaa_samples: msaa.samples(),
+            },
+        );
+
+        view_entities.clear();
+        view_entities.extend(visible_entities.entities.iter().map(|e| e.index() as usize));
+        for shape in extracted_shapes.iter() {
+            if !view_entities.contains(shape.entity.index() as usize) {
+                continue;
+            }
+            let index = shape_meta.instances.push(shape.instance) as u32;
+            transparent_phase.add(Transparent2d {
+                draw_function: draw_shape_function,
+                pipeline,
+                entity: batch,
+                sort_key: shape.sort_key,
+                batch_range: Some(index..index + 1),
+            });
+        }
+    }
+
+    shape_meta
+        .instances
+        .write_buffer(&render_device, &render_queue);
+}
+
+pub type DrawShape = (SetItemPipeline, SetMesh2dViewBindGroup<0>, DrawShapeBatch);
+
+pub struct DrawShapeBatch;
+impl<P: BatchedPhaseItem> RenderCommand<P> for DrawShapeBatch {
+    type Param = SRes<ShapeMeta>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = ();
+
+    fn render<'w>(
+        item: &P,
+        _view: (),
+        _entity: (),
+        shape_meta: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(buffer) = shape_meta.into_inner().instances.buffer() else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_vertex_buffer(0, buffer.slice(..));
+        // A quad of two triangles for each shape of the batch
+        pass.draw(0..6, item.batch_range().as_ref().unwrap().clone());
+        RenderCommandResult::Success
+    }
+}
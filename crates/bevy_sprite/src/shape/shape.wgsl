@@ -0,0 +1,116 @@
+#import bevy_sprite::mesh2d_view_bindings
+
+// NOTE: These must match the constants in bevy_sprite/src/shape/render.rs!
+const SHAPE_CIRCLE: u32 = 0u;
+const SHAPE_RECTANGLE: u32 = 1u;
+const SHAPE_LINE: u32 = 2u;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    // The position in the local space of the shape
+    @location(0) local: vec2<f32>,
+    @location(1) params: vec4<f32>,
+    @location(2) fill: vec4<f32>,
+    @location(3) stroke: vec4<f32>,
+    @location(4) @interpolate(flat) kind: u32,
+    @location(5) radius: f32,
+    @location(6) stroke_width: f32,
+};
+
+@vertex
+fn vertex(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) transform_0: vec4<f32>,
+    @location(1) transform_1: vec4<f32>,
+    @location(2) transform_2: vec4<f32>,
+    @location(3) transform_3: vec4<f32>,
+    @location(4) params: vec4<f32>,
+    @location(5) fill: vec4<f32>,
+    @location(6) stroke: vec4<f32>,
+    @location(7) kind: u32,
+    @location(8) radius: f32,
+    @location(9) stroke_width: f32,
+) -> VertexOutput {
+    let transform = mat4x4<f32>(transform_0, transform_1, transform_2, transform_3);
+
+    // The bounds of the shape, in its local space
+    var bounds_min = vec2<f32>(-radius);
+    var bounds_max = vec2<f32>(radius);
+    if kind == SHAPE_RECTANGLE {
+        bounds_min = -params.xy * 0.5;
+        bounds_max = params.xy * 0.5;
+    } else if kind == SHAPE_LINE {
+        bounds_min = min(params.xy, params.zw) - radius;
+        bounds_max = max(params.xy, params.zw) + radius;
+    }
+
+    // The quad covers the outside half of the stroke, and a pixel more for the anti-aliasing
+    let clip_from_local = view.view_proj * transform;
+    let pixels_per_unit = vec2<f32>(
+        length(clip_from_local[0].xy * view.viewport.zw * 0.5),
+        length(clip_from_local[1].xy * view.viewport.zw * 0.5),
+    );
+    let margin = stroke_width * 0.5 + 1.0 / max(min(pixels_per_unit.x, pixels_per_unit.y), 0.0001);
+
+    // The two triangles of the quad, with the corners (0, 0), (1, 0), (1, 1) and (0, 0), (1, 1),
+    // (0, 1): the bits of these masks are the coordinates of each of the 6 vertices.
+    let corner = vec2<f32>(f32((0x16u >> vertex_index) & 1u), f32((0x34u >> vertex_index) & 1u));
+    let local = mix(bounds_min - margin, bounds_max + margin, corner);
+
+    var out: VertexOutput;
+    out.position = clip_from_local * vec4<f32>(local, 0.0, 1.0);
+    out.local = local;
+    out.params = params;
+    out.fill = fill;
+    out.stroke = stroke;
+    out.kind = kind;
+    out.radius = radius;
+    out.stroke_width = stroke_width;
+    return out;
+}
+
+fn circle_distance(p: vec2<f32>, radius: f32) -> f32 {
+    return length(p) - radius;
+}
+
+fn rectangle_distance(p: vec2<f32>, half_size: vec2<f32>, corner_radius: f32) -> f32 {
+    let radius = clamp(corner_radius, 0.0, min(half_size.x, half_size.y));
+    let q = abs(p) - half_size + radius;
+    return length(max(q, vec2<f32>(0.0))) + min(max(q.x, q.y), 0.0) - radius;
+}
+
+fn line_distance(p: vec2<f32>, start: vec2<f32>, end: vec2<f32>, half_width: f32) -> f32 {
+    let direction = end - start;
+    let t = clamp(dot(p - start, direction) / max(dot(direction, direction), 1e-6), 0.0, 1.0);
+    return length(p - start - direction * t) - half_width;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    // NOTE: These must match `Shape2d::distance` in bevy_sprite/src/shape/mod.rs!
+    var distance = circle_distance(in.local, in.radius);
+    if in.kind == SHAPE_RECTANGLE {
+        distance = rectangle_distance(in.local, in.params.xy * 0.5, in.radius);
+    } else if in.kind == SHAPE_LINE {
+        distance = line_distance(in.local, in.params.xy, in.params.zw, in.radius);
+    }
+
+    // The edges fade out over a pixel, the size of which is the change of the distance between
+    // neighbouring pixels
+    let pixel = max(length(vec2<f32>(dpdx(distance), dpdy(distance))), 0.0001);
+    let fill_coverage = clamp(0.5 - distance / pixel, 0.0, 1.0);
+    var stroke_coverage = 0.0;
+    if in.stroke_width > 0.0 {
+        stroke_coverage = clamp(0.5 - (abs(distance) - in.stroke_width * 0.5) / pixel, 0.0, 1.0);
+    }
+
+    // The stroke is drawn over the fill
+    let fill_alpha = in.fill.a * fill_coverage;
+    let stroke_alpha = in.stroke.a * stroke_coverage;
+    let alpha = stroke_alpha + fill_alpha * (1.0 - stroke_alpha);
+    if alpha <= 0.0 {
+        discard;
+    }
+    let color = in.stroke.rgb * stroke_alpha + in.fill.rgb * fill_alpha * (1.0 - stroke_alpha);
+    return vec4<f32>(color / alpha, alpha);
+}
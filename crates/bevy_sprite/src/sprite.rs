@@ -1,7 +1,10 @@
-use bevy_ecs::component::Component;
+use bevy_ecs::{component::Component, reflect::ReflectComponent};
 use bevy_math::{Rect, Vec2};
-use bevy_reflect::Reflect;
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect};
 use bevy_render::color::Color;
+use bevy_transform::components::GlobalTransform;
+
+use crate::ImageScaleMode;
 
 #[derive(Component, Debug, Default, Clone, Reflect)]
 #[repr(C)]
@@ -20,6 +23,9 @@ pub struct Sprite {
     pub rect: Option<Rect>,
     /// [`Anchor`] point of the sprite in the world
     pub anchor: Anchor,
+    /// How the image fills the sprite when [`custom_size`](Self::custom_size) differs from the
+    /// size of the image.
+    pub scale_mode: ImageScaleMode,
 }
 
 /// How a sprite is positioned relative to its [`Transform`](bevy_transform::components::Transform).
@@ -58,3 +64,71 @@ impl Anchor {
         }
     }
 }
+
+/// How a sprite is sorted with the other 2d items to decide which are drawn on top, for
+/// [`Sprite`]s, [`TextureAtlasSprite`](crate::TextureAtlasSprite)s and 2d text.
+///
+/// Sprites without a [`SortMode2d`] are sorted by [`SortMode2d::Z`].
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub enum SortMode2d {
+    /// Sorts by the `Z` of the [`GlobalTransform`], higher sprites are drawn on top.
+    #[default]
+    Z,
+    /// Sorts by the `Z` of the [`GlobalTransform`], then draws the sprites lower on the screen
+    /// on top of the others with the same `Z`, for top-down games.
+    ///
+    /// The sprites are sorted by the `Y` of their transform, use an [`Anchor`] to place it at
+    /// their base. Sprites that are not Y-sorted are drawn below the Y-sorted ones with the same
+    /// `Z`.
+    YSort,
+    /// Sorts by this key instead of the `Z` of the [`GlobalTransform`].
+    Key(f32),
+}
+
+impl SortMode2d {
+    /// The keys a sprite at `transform` is sorted by: the key it's sorted by with all the 2d
+    /// items, then the key sorting it with the sprites with the same first key.
+    pub fn sort_keys(&self, transform: &GlobalTransform) -> (f32, f32) {
+        let translation = transform.translation();
+        match *self {
+            SortMode2d::Z => (translation.z, f32::NEG_INFINITY),
+            SortMode2d::YSort => (translation.z, -translation.y),
+            SortMode2d::Key(key) => (key, f32::NEG_INFINITY),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sort_keys() {
+        let at = |x, y, z| GlobalTransform::from_xyz(x, y, z);
+        let mut sprites = [
+            (SortMode2d::YSort, at(0.0, 10.0, 1.0)),
+            (SortMode2d::Z, at(0.0, 0.0, 2.0)),
+            (SortMode2d::YSort, at(5.0, -10.0, 1.0)),
+            (SortMode2d::Key(0.5), at(9.0, 0.0, 3.0)),
+            (SortMode2d::Z, at(0.0, -20.0, 1.0)),
+        ]
+        .map(|(sort_mode, transform)| {
+            let (key, y_key) = sort_mode.sort_keys(&transform);
+            ((key, y_key), transform.translation())
+        });
+        sprites.sort_by(|(a, _), (b, _)| a.partial_cmp(b).unwrap());
+
+        let order = sprites.map(|(_, translation)| translation.truncate().to_array());
+        assert_eq!(
+            order,
+            [
+                [9.0, 0.0],
+                [0.0, -20.0],
+                [0.0, 10.0],
+                [5.0, -10.0],
+                [0.0, 0.0]
+            ]
+        );
+    }
+}
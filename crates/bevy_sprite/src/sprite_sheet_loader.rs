@@ -0,0 +1,359 @@
+use std::fmt;
+
+use anyhow::Result;
+use bevy_asset::{AssetLoader, AssetPath, LoadContext, LoadedAsset};
+use bevy_math::{Rect, Vec2};
+use bevy_render::{
+    render_resource::{Extent3d, TextureDimension, TextureFormat},
+    texture::{CompressedImageFormats, FlipbookMode, Image, ImageType},
+};
+use bevy_utils::BoxedFuture;
+use serde::{
+    de::{MapAccess, SeqAccess, Visitor},
+    Deserialize, Deserializer,
+};
+use thiserror::Error;
+
+use crate::{SpriteAnimationClip, TextureAtlas};
+
+/// Loads sprite sheets as a [`TextureAtlas`], along with their animations as
+/// [`SpriteAnimationClip`]s.
+///
+/// Two kinds of sprite sheets are loaded:
+/// - `*.sheet.json` files, in the JSON format of `TexturePacker` and `Aseprite`, either with an
+///   array or a hash of frames. Their `meta.image` is loaded as the texture of the atlas.
+/// - `*.sheet.png` and `*.sheet.apng` animated PNGs, whose frames are packed in a grid in the
+///   texture of the atlas, labeled `Image`.
+///
+/// The clip of all the frames is labeled `Animation`, and the clip of each `Aseprite` frame tag is
+/// labeled `Animation/<tag name>`. The durations of the frames are kept in the clips, and the
+/// direction of the tags sets the order of their frames and their [`FlipbookMode`].
+///
+/// Rotated `TexturePacker` frames are not supported, and the trimmed frames are drawn without the
+/// space that was trimmed around them.
+#[derive(Default)]
+pub struct SpriteSheetLoader;
+
+/// An error that occurs when loading a sprite sheet.
+#[derive(Error, Debug)]
+pub enum SpriteSheetError {
+    #[error("invalid sprite sheet: {0}")]
+    Json(#[from] serde_json::Error),
+    #[error("the sprite sheet has no `meta.image`")]
+    MissingImage,
+    #[error("the frame {0} of the sprite sheet is rotated, which is not supported")]
+    RotatedFrame(usize),
+    #[error("the frame tag `{0}` goes past the last frame of the sprite sheet")]
+    InvalidFrameTag(String),
+    #[error("the animated image has no frames")]
+    NoFrames,
+}
+
+const FILE_EXTENSIONS: &[&str] = &["sheet.json", "sheet.png", "sheet.apng"];
+
+impl AssetLoader for SpriteSheetLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let ext = load_context.path().extension().unwrap().to_str().unwrap();
+            if ext.eq_ignore_ascii_case("json") {
+                load_sprite_sheet_json(bytes, load_context)
+            } else {
+                let ext = ext.to_string();
+                load_animated_image(bytes, &ext, load_context)
+            }
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        FILE_EXTENSIONS
+    }
+}
+
+fn load_sprite_sheet_json(bytes: &[u8], load_context: &mut LoadContext) -> Result<()> {
+    let sheet: SheetJson = serde_json::from_slice(bytes).map_err(SpriteSheetError::from)?;
+    let image = sheet.meta.image.ok_or(SpriteSheetError::MissingImage)?;
+    let parent = load_context.path().parent().unwrap();
+    let image_path = AssetPath::new(parent.join(image), None);
+    let texture = load_context.get_handle(image_path.clone());
+
+    let size = match sheet.meta.size {
+        Some(size) => Vec2::new(size.w, size.h),
+        None => sheet.frames.0.iter().fold(Vec2::ZERO, |size, frame| {
+            size.max(Vec2::new(
+                frame.frame.x + frame.frame.w,
+                frame.frame.y + frame.frame.h,
+            ))
+        }),
+    };
+    let mut atlas = TextureAtlas::new_empty(texture, size);
+    for (index, frame) in sheet.frames.0.iter().enumerate() {
+        if frame.rotated {
+            return Err(SpriteSheetError::RotatedFrame(index).into());
+        }
+        let SheetRect { x, y, w, h } = frame.frame;
+        atlas.add_texture(Rect::new(x, y, x + w, y + h));
+    }
+
+    // Aseprite durations are in milliseconds, TexturePacker has none
+    let durations: Vec<f32> = sheet
+        .frames
+        .0
+        .iter()
+        .map_while(|frame| frame.duration.map(|duration| duration / 1000.0))
+        .collect();
+    let frame_count = atlas.len();
+    load_context.set_labeled_asset(
+        "Animation",
+        LoadedAsset::new(SpriteAnimationClip {
+            frames: (0..frame_count).collect(),
+            frame_durations: durations.clone(),
+            ..Default::default()
+        }),
+    );
+    for tag in sheet.meta.frame_tags {
+        if tag.from > tag.to || tag.to >= frame_count {
+            return Err(SpriteSheetError::InvalidFrameTag(tag.name).into());
+        }
+        let mut frames: Vec<usize> = (tag.from..=tag.to).collect();
+        let mode = match tag.direction.as_str() {
+            "reverse" => {
+                frames.reverse();
+                FlipbookMode::Loop
+            }
+            "pingpong" => FlipbookMode::PingPong,
+            "pingpong_reverse" => {
+                frames.reverse();
+                FlipbookMode::PingPong
+            }
+            _ => FlipbookMode::Loop,
+        };
+        let frame_durations = frames
+            .iter()
+            .map_while(|&frame| durations.get(frame).copied())
+            .collect();
+        load_context.set_labeled_asset(
+            &format!("Animation/{}", tag.name),
+            LoadedAsset::new(SpriteAnimationClip {
+                frames,
+                frame_durations,
+                mode,
+                ..Default::default()
+            }),
+        );
+    }
+
+    load_context.set_default_asset(LoadedAsset::new(atlas).with_dependency(image_path));
+    Ok(())
+}
+
+fn load_animated_image(bytes: &[u8], ext: &str, load_context: &mut LoadContext) -> Result<()> {
+    let frames = Image::frames_from_buffer(
+        bytes,
+        ImageType::Extension(ext),
+        CompressedImageFormats::NONE,
+        true,
+    )?;
+    let Some(first_frame) = frames.first() else {
+        return Err(SpriteSheetError::NoFrames.into());
+    };
+    let frame_size = first_frame.image.texture_descriptor.size;
+    let (width, height) = (frame_size.width as usize, frame_size.height as usize);
+
+    // Packs the frames in a grid about as wide as it is tall
+    let columns = (frames.len() as f32).sqrt().ceil() as usize;
+    let rows = (frames.len() + columns - 1) / columns;
+    let row_bytes = width * 4;
+    let mut data = vec![0; columns * rows * row_bytes * height];
+    let mut atlas_rects = Vec::with_capacity(frames.len());
+    for (index, frame) in frames.iter().enumerate() {
+        let format = frame.image.texture_descriptor.format;
+        let converted;
+        let frame_data = if format == TextureFormat::Rgba8UnormSrgb {
+            &frame.image.data
+        } else {
+            converted = frame
+                .image
+                .convert(TextureFormat::Rgba8UnormSrgb)
+                .ok_or_else(|| anyhow::anyhow!("unsupported animated image format: {format:?}"))?;
+            &converted.data
+        };
+        let (column, row) = (index % columns, index / columns);
+        for y in 0..height {
+            let source = y * row_bytes;
+            let target = ((row * height + y) * columns + column) * row_bytes;
+            data[target..target + row_bytes]
+                .copy_from_slice(&frame_data[source..source + row_bytes]);
+        }
+        let min = Vec2::new((column * width) as f32, (row * height) as f32);
+        atlas_rects.push(Rect::from_corners(
+            min,
+            min + Vec2::new(width as f32, height as f32),
+        ));
+    }
+
+    let image = Image::new(
+        Extent3d {
+            width: (columns * width) as u32,
+            height: (rows * height) as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    let size = image.size();
+    let texture = load_context.set_labeled_asset("Image", LoadedAsset::new(image));
+    let mut atlas = TextureAtlas::new_empty(texture, size);
+    for rect in atlas_rects {
+        atlas.add_texture(rect);
+    }
+
+    // The single frame of a still image has no duration
+    let frame_durations = if frames.iter().all(|frame| frame.duration.is_zero()) {
+        Vec::new()
+    } else {
+        frames
+            .iter()
+            .map(|frame| frame.duration.as_secs_f32())
+            .collect()
+    };
+    load_context.set_labeled_asset(
+        "Animation",
+        LoadedAsset::new(SpriteAnimationClip {
+            frames: (0..frames.len()).collect(),
+            frame_durations,
+            ..Default::default()
+        }),
+    );
+    load_context.set_default_asset(LoadedAsset::new(atlas));
+    Ok(())
+}
+
+/// A sprite sheet exported by `TexturePacker` or `Aseprite`.
+#[derive(Deserialize)]
+struct SheetJson {
+    frames: SheetFrames,
+    #[serde(default)]
+    meta: SheetMeta,
+}
+
+/// The frames of a sprite sheet, in the order of the file.
+///
+/// They are either an array, or a hash from their file names to the frames.
+struct SheetFrames(Vec<SheetFrame>);
+
+impl<'de> Deserialize<'de> for SheetFrames {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SheetFramesVisitor;
+
+        impl<'de> Visitor<'de> for SheetFramesVisitor {
+            type Value = SheetFrames;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an array or a map of frames")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut frames = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(frame) = seq.next_element()? {
+                    frames.push(frame);
+                }
+                Ok(SheetFrames(frames))
+            }
+
+            // The frame tags of Aseprite refer to the frames by their position in the map, which
+            // has to be kept
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut frames = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some((_, frame)) = map.next_entry::<String, SheetFrame>()? {
+                    frames.push(frame);
+                }
+                Ok(SheetFrames(frames))
+            }
+        }
+
+        deserializer.deserialize_any(SheetFramesVisitor)
+    }
+}
+
+#[derive(Deserialize)]
+struct SheetFrame {
+    frame: SheetRect,
+    #[serde(default)]
+    rotated: bool,
+    /// The duration of the frame in milliseconds, only exported by `Aseprite`.
+    duration: Option<f32>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+struct SheetRect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+#[derive(Deserialize, Default)]
+struct SheetMeta {
+    image: Option<String>,
+    size: Option<SheetSize>,
+    #[serde(default, rename = "frameTags")]
+    frame_tags: Vec<SheetFrameTag>,
+}
+
+#[derive(Deserialize)]
+struct SheetSize {
+    w: f32,
+    h: f32,
+}
+
+/// A named range of frames in an `Aseprite` sprite sheet.
+#[derive(Deserialize)]
+struct SheetFrameTag {
+    name: String,
+    from: usize,
+    to: usize,
+    #[serde(default)]
+    direction: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sprite_sheet_frames() {
+        let array = r#"{
+            "frames": [
+                { "filename": "b", "frame": { "x": 16, "y": 0, "w": 16, "h": 16 } },
+                { "filename": "a", "frame": { "x": 0, "y": 0, "w": 16, "h": 16 } }
+            ],
+            "meta": { "image": "sheet.png" }
+        }"#;
+        let sheet: SheetJson = serde_json::from_str(array).unwrap();
+        let x: Vec<_> = sheet.frames.0.iter().map(|frame| frame.frame.x).collect();
+        assert_eq!(x, [16.0, 0.0]);
+        assert_eq!(sheet.meta.image.as_deref(), Some("sheet.png"));
+
+        // The hash keeps the frames in order, instead of sorting them by name
+        let hash = r#"{
+            "frames": {
+                "hero 10.ase": { "frame": { "x": 32, "y": 0, "w": 16, "h": 16 }, "duration": 50 },
+                "hero 2.ase": { "frame": { "x": 0, "y": 0, "w": 16, "h": 16 }, "duration": 100 }
+            },
+            "meta": {
+                "frameTags": [ { "name": "run", "from": 0, "to": 1, "direction": "pingpong" } ]
+            }
+        }"#;
+        let sheet: SheetJson = serde_json::from_str(hash).unwrap();
+        let x: Vec<_> = sheet.frames.0.iter().map(|frame| frame.frame.x).collect();
+        assert_eq!(x, [32.0, 0.0]);
+        assert_eq!(sheet.frames.0[1].duration, Some(100.0));
+        assert_eq!(sheet.meta.frame_tags[0].name, "run");
+        assert_eq!(sheet.meta.frame_tags[0].direction, "pingpong");
+    }
+}
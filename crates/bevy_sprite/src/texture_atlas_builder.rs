@@ -1,14 +1,13 @@
 use bevy_asset::{Assets, Handle};
 use bevy_log::{debug, error, warn};
-use bevy_math::{Rect, Vec2};
+use bevy_math::{Rect, UVec2, Vec2};
 use bevy_render::{
     render_resource::{Extent3d, TextureDimension, TextureFormat},
     texture::{Image, TextureFormatPixelInfo},
 };
-use bevy_utils::HashMap;
 use rectangle_pack::{
-    contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, PackedLocation,
-    RectToInsert, TargetBin,
+    contains_smallest_box, pack_rects, volume_heuristic, GroupedRectsToPlace, RectToInsert,
+    TargetBin,
 };
 use thiserror::Error;
 
@@ -22,14 +21,14 @@ pub enum TextureAtlasBuilderError {
     WrongFormat,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 #[must_use]
 /// A builder which is used to create a texture atlas from many individual
 /// sprites.
 pub struct TextureAtlasBuilder {
-    /// The grouped rects which must be placed with a key value pair of a
-    /// texture handle to an index.
-    rects_to_place: GroupedRectsToPlace<Handle<Image>>,
+    /// The textures which must be placed, with their size, in the order they
+    /// were added.
+    textures_to_place: Vec<(Handle<Image>, UVec2)>,
     /// The initial atlas size in pixels.
     initial_size: Vec2,
     /// The absolute maximum size of the texture atlas in pixels.
@@ -38,16 +37,22 @@ pub struct TextureAtlasBuilder {
     format: TextureFormat,
     /// Enable automatic format conversion for textures if they are not in the atlas format.
     auto_format_conversion: bool,
+    /// The number of transparent pixels between textures.
+    padding: u32,
+    /// The number of pixels the edges of each texture are extended by.
+    bleed: u32,
 }
 
 impl Default for TextureAtlasBuilder {
     fn default() -> Self {
         Self {
-            rects_to_place: GroupedRectsToPlace::new(),
+            textures_to_place: Vec::new(),
             initial_size: Vec2::new(256., 256.),
             max_size: Vec2::new(2048., 2048.),
             format: TextureFormat::Rgba8UnormSrgb,
             auto_format_conversion: true,
+            padding: 0,
+            bleed: 0,
         }
     }
 }
@@ -79,55 +84,89 @@ impl TextureAtlasBuilder {
         self
     }
 
+    /// Sets the number of transparent pixels left between the textures of the atlas.
+    pub fn padding(mut self, padding: u32) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// Sets the number of pixels the edges of each texture are repeated by around it.
+    ///
+    /// This keeps linear filtering and mipmaps from blending the pixels of neighboring textures
+    /// at the edges of a sprite. The extended edges are not part of the rects of the atlas.
+    pub fn bleed(mut self, bleed: u32) -> Self {
+        self.bleed = bleed;
+        self
+    }
+
     /// Adds a texture to be copied to the texture atlas.
+    ///
+    /// Adding the same texture again replaces it.
     pub fn add_texture(&mut self, texture_handle: Handle<Image>, texture: &Image) {
-        self.rects_to_place.push_rect(
-            texture_handle,
-            None,
-            RectToInsert::new(
-                texture.texture_descriptor.size.width,
-                texture.texture_descriptor.size.height,
-                1,
-            ),
+        let size = UVec2::new(
+            texture.texture_descriptor.size.width,
+            texture.texture_descriptor.size.height,
         );
+        match self
+            .textures_to_place
+            .iter_mut()
+            .find(|(handle, _)| *handle == texture_handle)
+        {
+            Some((_, texture_size)) => *texture_size = size,
+            None => self.textures_to_place.push((texture_handle, size)),
+        }
     }
 
+    /// Copies `texture` to `atlas_texture` with its top left corner at `position`, repeating its
+    /// edges by `bleed` pixels around it.
     fn copy_texture_to_atlas(
         atlas_texture: &mut Image,
         texture: &Image,
-        packed_location: &PackedLocation,
+        position: UVec2,
+        bleed: u32,
     ) {
-        let rect_width = packed_location.width() as usize;
-        let rect_height = packed_location.height() as usize;
-        let rect_x = packed_location.x() as usize;
-        let rect_y = packed_location.y() as usize;
+        let width = texture.texture_descriptor.size.width as usize;
+        let height = texture.texture_descriptor.size.height as usize;
+        if width == 0 || height == 0 {
+            return;
+        }
+        let x = position.x as usize;
+        let y = position.y as usize;
+        let bleed = bleed as usize;
         let atlas_width = atlas_texture.texture_descriptor.size.width as usize;
         let format_size = atlas_texture.texture_descriptor.format.pixel_size();
+        let row_size = width * format_size;
+
+        // The rows at the top and bottom edges are repeated above and below the texture
+        for bound_y in y - bleed..y + height + bleed {
+            let texture_y = bound_y.saturating_sub(y).min(height - 1);
+            let begin = (bound_y * atlas_width + x) * format_size;
+            let texture_begin = texture_y * row_size;
+            atlas_texture.data[begin..begin + row_size]
+                .copy_from_slice(&texture.data[texture_begin..texture_begin + row_size]);
 
-        for (texture_y, bound_y) in (rect_y..rect_y + rect_height).enumerate() {
-            let begin = (bound_y * atlas_width + rect_x) * format_size;
-            let end = begin + rect_width * format_size;
-            let texture_begin = texture_y * rect_width * format_size;
-            let texture_end = texture_begin + rect_width * format_size;
-            atlas_texture.data[begin..end]
-                .copy_from_slice(&texture.data[texture_begin..texture_end]);
+            let last = begin + row_size - format_size;
+            for i in 1..=bleed {
+                let offset = i * format_size;
+                atlas_texture
+                    .data
+                    .copy_within(begin..begin + format_size, begin - offset);
+                atlas_texture
+                    .data
+                    .copy_within(last..last + format_size, last + offset);
+            }
         }
     }
 
-    fn copy_converted_texture(
-        &self,
-        atlas_texture: &mut Image,
-        texture: &Image,
-        packed_location: &PackedLocation,
-    ) {
+    fn copy_converted_texture(&self, atlas_texture: &mut Image, texture: &Image, position: UVec2) {
         if self.format == texture.texture_descriptor.format {
-            Self::copy_texture_to_atlas(atlas_texture, texture, packed_location);
+            Self::copy_texture_to_atlas(atlas_texture, texture, position, self.bleed);
         } else if let Some(converted_texture) = texture.convert(self.format) {
             debug!(
                 "Converting texture from '{:?}' to '{:?}'",
                 texture.texture_descriptor.format, self.format
             );
-            Self::copy_texture_to_atlas(atlas_texture, &converted_texture, packed_location);
+            Self::copy_texture_to_atlas(atlas_texture, &converted_texture, position, self.bleed);
         } else {
             error!(
                 "Error converting texture from '{:?}' to '{:?}', ignoring",
@@ -136,26 +175,25 @@ impl TextureAtlasBuilder {
         }
     }
 
-    /// Consumes the builder and returns a result with a new texture atlas.
-    ///
-    /// Internally it copies all rectangles from the textures and copies them
-    /// into a new texture which the texture atlas will use. It is not useful to
-    /// hold a strong handle to the texture afterwards else it will exist twice
-    /// in memory.
-    ///
-    /// # Errors
-    ///
-    /// If there is not enough space in the atlas texture, an error will
-    /// be returned. It is then recommended to make a larger sprite sheet.
-    pub fn finish(
-        self,
-        textures: &mut Assets<Image>,
-    ) -> Result<TextureAtlas, TextureAtlasBuilderError> {
+    /// Packs the textures into a new atlas texture, returning it with the rect of each texture,
+    /// in the order they were added.
+    fn pack(&self, textures: &Assets<Image>) -> TextureAtlasBuilderResult<(Image, Vec<Rect>)> {
         let initial_width = self.initial_size.x as u32;
         let initial_height = self.initial_size.y as u32;
         let max_width = self.max_size.x as u32;
         let max_height = self.max_size.y as u32;
 
+        // Each texture takes its bleed on every side, and its padding on the right and bottom
+        let margin = 2 * self.bleed + self.padding;
+        let mut rects_to_place = GroupedRectsToPlace::<usize>::new();
+        for (index, (_, size)) in self.textures_to_place.iter().enumerate() {
+            rects_to_place.push_rect(
+                index,
+                None,
+                RectToInsert::new(size.x + margin, size.y + margin, 1),
+            );
+        }
+
         let mut current_width = initial_width;
         let mut current_height = initial_height;
         let mut rect_placements = None;
@@ -171,7 +209,7 @@ impl TextureAtlasBuilder {
             let mut target_bins = std::collections::BTreeMap::new();
             target_bins.insert(0, TargetBin::new(current_width, current_height, 1));
             rect_placements = match pack_rects(
-                &self.rects_to_place,
+                &rects_to_place,
                 &mut target_bins,
                 &volume_heuristic,
                 &contains_smallest_box,
@@ -206,18 +244,17 @@ impl TextureAtlasBuilder {
 
         let rect_placements = rect_placements.ok_or(TextureAtlasBuilderError::NotEnoughSpace)?;
 
-        let mut texture_rects = Vec::with_capacity(rect_placements.packed_locations().len());
-        let mut texture_handles = HashMap::default();
-        for (texture_handle, (_, packed_location)) in rect_placements.packed_locations().iter() {
+        let mut texture_rects = Vec::with_capacity(self.textures_to_place.len());
+        for (index, (texture_handle, size)) in self.textures_to_place.iter().enumerate() {
             let texture = textures.get(texture_handle).unwrap();
-            let min = Vec2::new(packed_location.x() as f32, packed_location.y() as f32);
-            let max = min
-                + Vec2::new(
-                    packed_location.width() as f32,
-                    packed_location.height() as f32,
-                );
-            texture_handles.insert(texture_handle.clone_weak(), texture_rects.len());
-            texture_rects.push(Rect { min, max });
+            let (_, packed_location) = &rect_placements.packed_locations()[&index];
+            let position =
+                UVec2::new(packed_location.x(), packed_location.y()) + UVec2::splat(self.bleed);
+            let min = position.as_vec2();
+            texture_rects.push(Rect {
+                min,
+                max: min + size.as_vec2(),
+            });
             if texture.texture_descriptor.format != self.format && !self.auto_format_conversion {
                 warn!(
                     "Loading a texture of format '{:?}' in an atlas with format '{:?}'",
@@ -225,8 +262,35 @@ impl TextureAtlasBuilder {
                 );
                 return Err(TextureAtlasBuilderError::WrongFormat);
             }
-            self.copy_converted_texture(&mut atlas_texture, texture, packed_location);
+            self.copy_converted_texture(&mut atlas_texture, texture, position);
         }
+        Ok((atlas_texture, texture_rects))
+    }
+
+    /// Consumes the builder and returns a result with a new texture atlas.
+    ///
+    /// Internally it copies all rectangles from the textures and copies them
+    /// into a new texture which the texture atlas will use. It is not useful to
+    /// hold a strong handle to the texture afterwards else it will exist twice
+    /// in memory.
+    ///
+    /// The index of each texture in the atlas is the order in which it was added.
+    ///
+    /// # Errors
+    ///
+    /// If there is not enough space in the atlas texture, an error will
+    /// be returned. It is then recommended to make a larger sprite sheet.
+    pub fn finish(
+        self,
+        textures: &mut Assets<Image>,
+    ) -> Result<TextureAtlas, TextureAtlasBuilderError> {
+        let (atlas_texture, texture_rects) = self.pack(textures)?;
+        let texture_handles = self
+            .textures_to_place
+            .iter()
+            .enumerate()
+            .map(|(index, (texture_handle, _))| (texture_handle.clone_weak(), index))
+            .collect();
         Ok(TextureAtlas {
             size: Vec2::new(
                 atlas_texture.texture_descriptor.size.width as f32,
@@ -237,4 +301,53 @@ impl TextureAtlasBuilder {
             texture_handles: Some(texture_handles),
         })
     }
+
+    /// Consumes the builder and packs its textures again into the texture of an existing
+    /// `atlas`, for example after some of them were loaded or hot-reloaded.
+    ///
+    /// The textures that were already in `atlas` keep their index, so that the
+    /// [`TextureAtlasSprite`](crate::TextureAtlasSprite)s using it keep drawing the same texture,
+    /// and the new ones are added after them. The texture of `atlas` keeps its handle, so that
+    /// its sprites can still be batched together.
+    ///
+    /// The textures of `atlas` that weren't added to the builder keep their index too, with an
+    /// empty rect.
+    ///
+    /// # Errors
+    ///
+    /// If there is not enough space in the atlas texture, an error will be returned and `atlas`
+    /// is left untouched.
+    pub fn repack(
+        self,
+        atlas: &mut TextureAtlas,
+        textures: &mut Assets<Image>,
+    ) -> Result<(), TextureAtlasBuilderError> {
+        let (atlas_texture, rects) = self.pack(textures)?;
+
+        let mut texture_handles = atlas.texture_handles.take().unwrap_or_default();
+        let mut texture_rects = vec![Rect::default(); atlas.textures.len()];
+        for ((texture_handle, _), rect) in self.textures_to_place.iter().zip(rects) {
+            if let Some(&index) = texture_handles.get(texture_handle) {
+                if index >= texture_rects.len() {
+                    texture_rects.resize(index + 1, Rect::default());
+                }
+                texture_rects[index] = rect;
+            } else {
+                texture_handles.insert(texture_handle.clone_weak(), texture_rects.len());
+                texture_rects.push(rect);
+            }
+        }
+
+        atlas.size = Vec2::new(
+            atlas_texture.texture_descriptor.size.width as f32,
+            atlas_texture.texture_descriptor.size.height as f32,
+        );
+        atlas.textures = texture_rects;
+        atlas.texture_handles = Some(texture_handles);
+        match textures.get_mut(&atlas.texture) {
+            Some(image) => *image = atlas_texture,
+            None => atlas.texture = textures.add(atlas_texture),
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,230 @@
+use bevy_math::{Rect, Vec2};
+use bevy_reflect::{FromReflect, Reflect};
+use bevy_render::render_resource::VertexFormat;
+use bytemuck::{Pod, Zeroable};
+
+/// How a [`Sprite`](crate::Sprite) or a UI image fills its on-screen size when it's different from
+/// the size of its image.
+///
+/// The scaling is done in the shader, so a sliced or tiled image is still drawn as a single quad.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub enum ImageScaleMode {
+    /// Stretches the whole image over the quad.
+    #[default]
+    Stretched,
+    /// Keeps the borders of the image at their size and scales the rest with a [`TextureSlicer`],
+    /// also known as 9-slicing. This lets panels and buttons be resized without distorting their
+    /// corners.
+    Sliced(TextureSlicer),
+    /// Repeats the image along the tiled axes, and stretches it along the others.
+    Tiled {
+        /// Whether the image repeats horizontally.
+        tile_x: bool,
+        /// Whether the image repeats vertically.
+        tile_y: bool,
+        /// The size of each tile relative to the size of the image.
+        stretch_value: f32,
+    },
+}
+
+/// The sizes of the borders of an image on each of its sides, in pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub struct BorderRect {
+    pub left: f32,
+    pub right: f32,
+    pub top: f32,
+    pub bottom: f32,
+}
+
+impl BorderRect {
+    /// Creates borders of the same size on every side.
+    pub const fn square(value: f32) -> Self {
+        BorderRect {
+            left: value,
+            right: value,
+            top: value,
+            bottom: value,
+        }
+    }
+
+    /// Creates borders of size `horizontal` on the left and right sides, and of size `vertical`
+    /// on the top and bottom sides.
+    pub const fn rectangle(horizontal: f32, vertical: f32) -> Self {
+        BorderRect {
+            left: horizontal,
+            right: horizontal,
+            top: vertical,
+            bottom: vertical,
+        }
+    }
+}
+
+impl From<f32> for BorderRect {
+    fn from(value: f32) -> Self {
+        BorderRect::square(value)
+    }
+}
+
+/// How the parts of a sliced image between its borders fill the space left by the borders.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub enum SliceScaleMode {
+    /// Stretches the part of the image over the space.
+    #[default]
+    Stretch,
+    /// Repeats the part of the image over the space.
+    Tile {
+        /// The size of each tile relative to the size of the part of the image.
+        stretch_value: f32,
+    },
+}
+
+/// Slices an image into 9 parts: the corners are drawn at the size of the image, the edges are
+/// scaled along their length and the center is scaled in both directions.
+///
+/// When the quad is smaller than the borders, they're scaled down to fit.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub struct TextureSlicer {
+    /// The sizes of the borders of the image, in pixels.
+    pub border: BorderRect,
+    /// How the center of the image fills the space between the edges.
+    pub center_scale_mode: SliceScaleMode,
+    /// How the edges of the image fill the space between the corners.
+    pub sides_scale_mode: SliceScaleMode,
+}
+
+/// The part of the vertices of a quad telling the sprite and UI shaders how to slice or tile its
+/// image, when it has an [`ImageScaleMode`] other than [`ImageScaleMode::Stretched`].
+///
+/// The vertices also need the coordinates of each corner inside the quad, from `(0, 0)` at its
+/// top left to the size of the quad, instead of UVs.
+#[repr(C)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Pod, Zeroable)]
+pub struct TextureSliceVertex {
+    /// The minimum and maximum UVs of the region of the texture in the quad.
+    pub uv_rect: [f32; 4],
+    /// The size of the quad.
+    pub size: [f32; 2],
+    /// The size of the region of the texture in the quad, in pixels.
+    pub texels: [f32; 2],
+    /// The left, right, top and bottom borders of the region, in pixels.
+    pub border: [f32; 4],
+    /// The tile size relative to the image in the center, and on the edges.
+    pub tile_scale: [f32; 2],
+    /// Which axes are tiled in the center and on the edges.
+    pub flags: u32,
+}
+
+impl TextureSliceVertex {
+    // NOTE: These must match the constants in `bevy_sprite/src/render/texture_slice.wgsl`!
+    pub const CENTER_TILE_X: u32 = 1 << 0;
+    pub const CENTER_TILE_Y: u32 = 1 << 1;
+    pub const SIDES_TILE_X: u32 = 1 << 2;
+    pub const SIDES_TILE_Y: u32 = 1 << 3;
+
+    /// The formats of the fields, to add to the layout of the vertex buffers.
+    pub const VERTEX_FORMATS: [VertexFormat; 6] = [
+        // uv_rect
+        VertexFormat::Float32x4,
+        // size
+        VertexFormat::Float32x2,
+        // texels
+        VertexFormat::Float32x2,
+        // border
+        VertexFormat::Float32x4,
+        // tile_scale
+        VertexFormat::Float32x2,
+        // flags
+        VertexFormat::Uint32,
+    ];
+
+    /// Creates the vertex data drawing the `rect` of an image of `image_size` pixels with the
+    /// `scale_mode`, over a quad of `size`.
+    pub fn new(scale_mode: &ImageScaleMode, rect: Rect, image_size: Vec2, size: Vec2) -> Self {
+        let uv_min = rect.min / image_size;
+        let uv_max = rect.max / image_size;
+        let mut vertex = TextureSliceVertex {
+            uv_rect: [uv_min.x, uv_min.y, uv_max.x, uv_max.y],
+            size: size.into(),
+            texels: rect.size().into(),
+            tile_scale: [1.0; 2],
+            ..Default::default()
+        };
+
+        match *scale_mode {
+            ImageScaleMode::Stretched => {}
+            ImageScaleMode::Sliced(slicer) => {
+                let border = slicer.border;
+                vertex.border = [border.left, border.right, border.top, border.bottom];
+                if let SliceScaleMode::Tile { stretch_value } = slicer.center_scale_mode {
+                    vertex.flags |= Self::CENTER_TILE_X | Self::CENTER_TILE_Y;
+                    vertex.tile_scale[0] = stretch_value;
+                }
+                if let SliceScaleMode::Tile { stretch_value } = slicer.sides_scale_mode {
+                    vertex.flags |= Self::SIDES_TILE_X | Self::SIDES_TILE_Y;
+                    vertex.tile_scale[1] = stretch_value;
+                }
+            }
+            ImageScaleMode::Tiled {
+                tile_x,
+                tile_y,
+                stretch_value,
+            } => {
+                // Without borders, the whole quad is the center
+                if tile_x {
+                    vertex.flags |= Self::CENTER_TILE_X;
+                }
+                if tile_y {
+                    vertex.flags |= Self::CENTER_TILE_Y;
+                }
+                vertex.tile_scale[0] = stretch_value;
+            }
+        }
+        vertex
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slice_vertex() {
+        let rect = Rect::new(16.0, 0.0, 48.0, 32.0);
+        let image_size = Vec2::new(64.0, 32.0);
+        let size = Vec2::new(100.0, 50.0);
+
+        let stretched = TextureSliceVertex::new(&ImageScaleMode::Stretched, rect, image_size, size);
+        assert_eq!(stretched.uv_rect, [0.25, 0.0, 0.75, 1.0]);
+        assert_eq!(stretched.texels, [32.0, 32.0]);
+        assert_eq!(stretched.size, [100.0, 50.0]);
+        assert_eq!(stretched.flags, 0);
+
+        let slicer = TextureSlicer {
+            border: BorderRect::rectangle(4.0, 8.0),
+            center_scale_mode: SliceScaleMode::Tile { stretch_value: 2.0 },
+            ..Default::default()
+        };
+        let sliced =
+            TextureSliceVertex::new(&ImageScaleMode::Sliced(slicer), rect, image_size, size);
+        assert_eq!(sliced.border, [4.0, 4.0, 8.0, 8.0]);
+        assert_eq!(
+            sliced.flags,
+            TextureSliceVertex::CENTER_TILE_X | TextureSliceVertex::CENTER_TILE_Y
+        );
+        assert_eq!(sliced.tile_scale, [2.0, 1.0]);
+
+        let tiled = TextureSliceVertex::new(
+            &ImageScaleMode::Tiled {
+                tile_x: false,
+                tile_y: true,
+                stretch_value: 0.5,
+            },
+            rect,
+            image_size,
+            size,
+        );
+        assert_eq!(tiled.border, [0.0; 4]);
+        assert_eq!(tiled.flags, TextureSliceVertex::CENTER_TILE_Y);
+        assert_eq!(tiled.tile_scale, [0.5, 1.0]);
+    }
+}
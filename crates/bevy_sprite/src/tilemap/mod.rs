@@ -0,0 +1,314 @@
+mod render;
+
+pub use render::*;
+
+use bevy_app::{App, IntoSystemAppConfig, Plugin};
+use bevy_asset::{load_internal_asset, Handle, HandleUntyped};
+use bevy_core_pipeline::core_2d::Transparent2d;
+use bevy_ecs::prelude::*;
+use bevy_math::{IVec2, UVec2, Vec2};
+use bevy_reflect::{FromReflect, Reflect, TypeUuid};
+use bevy_render::{
+    color::Color,
+    render_phase::AddRenderCommand,
+    render_resource::{Shader, SpecializedRenderPipelines},
+    texture::{Image, DEFAULT_IMAGE_HANDLE},
+    view::{InheritedVisibility, ViewVisibility, Visibility},
+    ExtractSchedule, RenderApp, RenderSet,
+};
+use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_utils::HashMap;
+
+pub const TILEMAP_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 9046776617361594294);
+
+/// Renders the [`Tilemap`]s, with a single draw call for each of their chunks.
+#[derive(Default)]
+pub struct TilemapPlugin;
+
+impl Plugin for TilemapPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(
+            app,
+            TILEMAP_SHADER_HANDLE,
+            "tilemap.wgsl",
+            Shader::from_wgsl
+        );
+
+        app.register_type::<Tile>().register_type::<TileAnimation>();
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<TilemapPipeline>()
+                .init_resource::<SpecializedRenderPipelines<TilemapPipeline>>()
+                .init_resource::<ExtractedTilemaps>()
+                .init_resource::<TilemapMeta>()
+                .init_resource::<TilemapImageBindGroups>()
+                .add_render_command::<Transparent2d, DrawTilemapChunk>()
+                .add_system(extract_tilemaps.in_schedule(ExtractSchedule))
+                .add_system(prepare_tilemaps.in_set(RenderSet::Prepare))
+                .add_system(queue_tilemaps.in_set(RenderSet::Queue));
+        }
+    }
+}
+
+/// A tile of a [`Tilemap`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub struct Tile {
+    /// The layer of the tileset array texture drawn on the tile.
+    pub index: u32,
+    /// The color the tile is tinted with.
+    pub color: Color,
+    /// Flip the tile along the `X` axis
+    pub flip_x: bool,
+    /// Flip the tile along the `Y` axis
+    pub flip_y: bool,
+    /// Plays the layers of the tileset following [`index`](Self::index) as an animation.
+    pub animation: Option<TileAnimation>,
+}
+
+impl Tile {
+    /// Creates a tile drawing the layer `index` of the tileset.
+    pub fn new(index: u32) -> Self {
+        Tile {
+            index,
+            ..Default::default()
+        }
+    }
+
+    /// Returns this [`Tile`] playing through `frame_count` layers of the tileset at `fps` frames
+    /// per second.
+    pub fn with_animation(mut self, frame_count: u32, fps: f32) -> Self {
+        self.animation = Some(TileAnimation { frame_count, fps });
+        self
+    }
+}
+
+impl Default for Tile {
+    fn default() -> Self {
+        Tile {
+            index: 0,
+            color: Color::WHITE,
+            flip_x: false,
+            flip_y: false,
+            animation: None,
+        }
+    }
+}
+
+/// A looping animation of a [`Tile`] through consecutive layers of the tileset.
+///
+/// The animation is played by the shader from the `globals.time` uniform, so animated tiles don't
+/// need any per-frame work on the CPU, and all the tiles with the same animation play in sync.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub struct TileAnimation {
+    /// The number of layers of the animation.
+    pub frame_count: u32,
+    /// The number of frames shown per second.
+    pub fps: f32,
+}
+
+/// A grid of [`Tile`]s, drawn with the tileset array texture of the entity's [`Handle<Image>`].
+///
+/// The tiles are stored in square chunks, which are each rendered with a single draw call, and
+/// uploaded to the GPU again only when one of their tiles changes. The tile at `(x, y)` is
+/// centered on `(x, y) * tile_size` in the space of the [`Transform`] of the tilemap.
+///
+/// Each layer of the tileset is a tile, see [`Image::reinterpret_stacked_2d_as_array`] to create
+/// one from an image with the tiles stacked vertically.
+#[derive(Component, Debug, Clone)]
+pub struct Tilemap {
+    tile_size: Vec2,
+    chunk_size: UVec2,
+    chunks: HashMap<IVec2, TilemapChunk>,
+    revision: u32,
+}
+
+/// The tiles of a chunk of a [`Tilemap`], row by row.
+#[derive(Debug, Clone)]
+struct TilemapChunk {
+    tiles: Vec<Option<Tile>>,
+    tile_count: usize,
+    /// Changes when any tile of the chunk changes, for the renderer to upload it again
+    revision: u32,
+}
+
+impl Tilemap {
+    /// The size of the chunks of a [`Tilemap`] created with [`Tilemap::new`], in tiles.
+    pub const DEFAULT_CHUNK_SIZE: UVec2 = UVec2::new(32, 32);
+
+    /// Creates an empty tilemap with tiles of `tile_size`.
+    pub fn new(tile_size: Vec2) -> Self {
+        Tilemap::with_chunk_size(tile_size, Self::DEFAULT_CHUNK_SIZE)
+    }
+
+    /// Creates an empty tilemap with tiles of `tile_size`, stored in chunks of `chunk_size` tiles.
+    ///
+    /// Bigger chunks need fewer draw calls, but more data to upload when one of their tiles
+    /// changes.
+    pub fn with_chunk_size(tile_size: Vec2, chunk_size: UVec2) -> Self {
+        assert!(chunk_size.x > 0 && chunk_size.y > 0);
+        Tilemap {
+            tile_size,
+            chunk_size,
+            chunks: HashMap::default(),
+            revision: 0,
+        }
+    }
+
+    /// The size of each tile, in the space of the [`Transform`] of the tilemap.
+    pub fn tile_size(&self) -> Vec2 {
+        self.tile_size
+    }
+
+    /// Sets the size of each tile.
+    pub fn set_tile_size(&mut self, tile_size: Vec2) {
+        self.tile_size = tile_size;
+    }
+
+    /// The size of the chunks of the tilemap, in tiles.
+    pub fn chunk_size(&self) -> UVec2 {
+        self.chunk_size
+    }
+
+    /// The chunk containing the tile at `position`, and the index of the tile in that chunk.
+    fn locate(&self, position: IVec2) -> (IVec2, usize) {
+        let chunk_size = self.chunk_size.as_ivec2();
+        let chunk = IVec2::new(
+            position.x.div_euclid(chunk_size.x),
+            position.y.div_euclid(chunk_size.y),
+        );
+        let local = position - chunk * chunk_size;
+        (chunk, (local.y * chunk_size.x + local.x) as usize)
+    }
+
+    /// Returns the tile at `position`, if there's one.
+    pub fn get_tile(&self, position: IVec2) -> Option<&Tile> {
+        let (chunk, index) = self.locate(position);
+        self.chunks.get(&chunk)?.tiles[index].as_ref()
+    }
+
+    /// Sets or removes the tile at `position`, returning the previous one.
+    ///
+    /// Only the chunk containing the tile is uploaded again to the GPU, so tiles can be changed
+    /// every frame.
+    pub fn set_tile(&mut self, position: IVec2, tile: impl Into<Option<Tile>>) -> Option<Tile> {
+        let tile = tile.into();
+        let (chunk_position, index) = self.locate(position);
+        let tiles_per_chunk = (self.chunk_size.x * self.chunk_size.y) as usize;
+
+        if tile.is_none() && !self.chunks.contains_key(&chunk_position) {
+            return None;
+        }
+        let chunk = self
+            .chunks
+            .entry(chunk_position)
+            .or_insert_with(|| TilemapChunk {
+                tiles: vec![None; tiles_per_chunk],
+                tile_count: 0,
+                revision: 0,
+            });
+        if chunk.tiles[index] == tile {
+            return tile;
+        }
+
+        let previous = std::mem::replace(&mut chunk.tiles[index], tile);
+        match (&previous, &tile) {
+            (None, Some(_)) => chunk.tile_count += 1,
+            (Some(_), None) => chunk.tile_count -= 1,
+            _ => {}
+        }
+        self.revision = self.revision.wrapping_add(1);
+        chunk.revision = self.revision;
+        if chunk.tile_count == 0 {
+            self.chunks.remove(&chunk_position);
+        }
+        previous
+    }
+
+    /// Removes all the tiles.
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Iterates over the tiles and their positions, in no particular order.
+    pub fn tiles(&self) -> impl Iterator<Item = (IVec2, &Tile)> + '_ {
+        let chunk_size = self.chunk_size.as_ivec2();
+        self.chunks.iter().flat_map(move |(chunk_position, chunk)| {
+            chunk
+                .tiles
+                .iter()
+                .enumerate()
+                .filter_map(move |(index, tile)| {
+                    let index = index as i32;
+                    let local = IVec2::new(index % chunk_size.x, index / chunk_size.x);
+                    Some((*chunk_position * chunk_size + local, tile.as_ref()?))
+                })
+        })
+    }
+}
+
+/// A bundle of components for drawing a [`Tilemap`].
+#[derive(Bundle, Clone)]
+pub struct TilemapBundle {
+    pub tilemap: Tilemap,
+    /// The tileset array texture, with a tile in each layer
+    pub texture: Handle<Image>,
+    pub transform: Transform,
+    pub global_transform: GlobalTransform,
+    /// User indication of whether an entity is visible
+    pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+}
+
+impl Default for TilemapBundle {
+    fn default() -> Self {
+        Self {
+            tilemap: Tilemap::new(Vec2::splat(16.0)),
+            texture: DEFAULT_IMAGE_HANDLE.typed(),
+            transform: Default::default(),
+            global_transform: Default::default(),
+            visibility: Default::default(),
+            inherited_visibility: Default::default(),
+            view_visibility: Default::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_tiles() {
+        let mut tilemap = Tilemap::with_chunk_size(Vec2::ONE, UVec2::new(4, 4));
+        assert_eq!(tilemap.set_tile(IVec2::new(-1, 5), Tile::new(1)), None);
+        assert_eq!(tilemap.set_tile(IVec2::new(2, 2), Tile::new(2)), None);
+        assert_eq!(tilemap.chunks.len(), 2);
+        assert!(tilemap.chunks.contains_key(&IVec2::new(-1, 1)));
+
+        assert_eq!(tilemap.get_tile(IVec2::new(-1, 5)), Some(&Tile::new(1)));
+        assert_eq!(tilemap.get_tile(IVec2::new(3, 5)), None);
+
+        let revision = tilemap.chunks[&IVec2::ZERO].revision;
+        assert_eq!(
+            tilemap.set_tile(IVec2::new(2, 2), Tile::new(3)),
+            Some(Tile::new(2))
+        );
+        assert_ne!(tilemap.chunks[&IVec2::ZERO].revision, revision);
+
+        // Chunks are removed with their last tile
+        assert_eq!(
+            tilemap.set_tile(IVec2::new(-1, 5), None),
+            Some(Tile::new(1))
+        );
+        assert_eq!(tilemap.chunks.len(), 1);
+
+        let mut tiles: Vec<_> = tilemap.tiles().collect();
+        tiles.sort_by_key(|(position, _)| (position.x, position.y));
+        assert_eq!(tiles, [(IVec2::new(2, 2), &Tile::new(3))]);
+    }
+}
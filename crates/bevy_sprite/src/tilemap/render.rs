@@ -0,0 +1,553 @@
+use bevy_asset::{AssetEvent, Handle, HandleId};
+use bevy_core_pipeline::core_2d::Transparent2d;
+use bevy_ecs::{
+    prelude::*,
+    system::{lifetimeless::*, SystemParamItem},
+};
+use bevy_math::{IVec2, Mat4, Vec2};
+use bevy_render::{
+    render_asset::RenderAssets,
+    render_phase::{
+        DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult, RenderPhase, SetItemPipeline,
+        TrackedRenderPass,
+    },
+    render_resource::*,
+    renderer::{RenderDevice, RenderQueue},
+    texture::{BevyDefault, Image},
+    view::{ExtractedView, Msaa, ViewTarget, ViewVisibility, VisibleEntities},
+    Extract,
+};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::{FloatOrd, HashMap};
+use bytemuck::{Pod, Zeroable};
+
+use super::{Tilemap, TILEMAP_SHADER_HANDLE};
+use crate::{Mesh2dPipeline, SetMesh2dViewBindGroup, SpriteAssetEvents};
+
+#[derive(Resource)]
+pub struct TilemapPipeline {
+    view_layout: BindGroupLayout,
+    tileset_layout: BindGroupLayout,
+    tilemap_layout: BindGroupLayout,
+}
+
+impl FromWorld for TilemapPipeline {
+    fn from_world(world: &mut World) -> Self {
+        // The view and globals are bound like for 2d meshes
+        let view_layout = world.resource::<Mesh2dPipeline>().view_layout.clone();
+        let render_device = world.resource::<RenderDevice>();
+
+        let tileset_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2Array,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("tilemap_tileset_layout"),
+        });
+
+        let tilemap_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: Some(TilemapUniform::min_size()),
+                },
+                count: None,
+            }],
+            label: Some("tilemap_layout"),
+        });
+
+        TilemapPipeline {
+            view_layout,
+            tileset_layout,
+            tilemap_layout,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct TilemapPipelineKey {
+    pub hdr: bool,
+    pub msaa_samples: u32,
+}
+
+impl SpecializedRenderPipeline for TilemapPipeline {
+    type Key = TilemapPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        // Each tile is an instance, whose quad is generated from the vertex index
+        let instance_layout = VertexBufferLayout::from_vertex_formats(
+            VertexStepMode::Instance,
+            vec![
+                // position
+                VertexFormat::Float32x2,
+                // index
+                VertexFormat::Uint32,
+                // flags
+                VertexFormat::Uint32,
+                // frame_count
+                VertexFormat::Uint32,
+                // fps
+                VertexFormat::Float32,
+                // color
+                VertexFormat::Float32x4,
+            ],
+        );
+
+        RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: TILEMAP_SHADER_HANDLE.typed::<Shader>(),
+                entry_point: "vertex".into(),
+                shader_defs: Vec::new(),
+                buffers: vec![instance_layout],
+            },
+            fragment: Some(FragmentState {
+                shader: TILEMAP_SHADER_HANDLE.typed::<Shader>(),
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: if key.hdr {
+                        ViewTarget::TEXTURE_FORMAT_HDR
+                    } else {
+                        TextureFormat::bevy_default()
+                    },
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            layout: vec![
+                self.view_layout.clone(),
+                self.tileset_layout.clone(),
+                self.tilemap_layout.clone(),
+            ],
+            primitive: PrimitiveState {
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: PolygonMode::Fill,
+                conservative: false,
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: key.msaa_samples,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            label: Some("tilemap_pipeline".into()),
+            push_constant_ranges: Vec::new(),
+        }
+    }
+}
+
+/// The data of a tile on the GPU.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct TileInstance {
+    /// The position of the tile in the tilemap, in tiles
+    pub position: [f32; 2],
+    pub index: u32,
+    pub flags: u32,
+    pub frame_count: u32,
+    pub fps: f32,
+    pub color: [f32; 4],
+}
+
+impl TileInstance {
+    // NOTE: These must match the bit flags in bevy_sprite/src/tilemap/tilemap.wgsl!
+    pub const FLIP_X: u32 = 1 << 0;
+    pub const FLIP_Y: u32 = 1 << 1;
+}
+
+pub struct ExtractedTilemapChunk {
+    pub position: IVec2,
+    pub revision: u32,
+    /// The tiles of the chunk, only extracted when they changed since they were last uploaded
+    pub tiles: Option<Vec<TileInstance>>,
+}
+
+pub struct ExtractedTilemap {
+    pub entity: Entity,
+    pub transform: GlobalTransform,
+    pub tile_size: Vec2,
+    pub image_handle_id: HandleId,
+    pub chunks: Vec<ExtractedTilemapChunk>,
+}
+
+#[derive(Resource, Default)]
+pub struct ExtractedTilemaps {
+    pub tilemaps: Vec<ExtractedTilemap>,
+}
+
+pub fn extract_tilemaps(
+    mut extracted_tilemaps: ResMut<ExtractedTilemaps>,
+    tilemap_meta: Res<TilemapMeta>,
+    tilemap_query: Extract<
+        Query<(
+            Entity,
+            &ViewVisibility,
+            &Tilemap,
+            &GlobalTransform,
+            &Handle<Image>,
+        )>,
+    >,
+) {
+    extracted_tilemaps.tilemaps.clear();
+    for (entity, visibility, tilemap, transform, handle) in &tilemap_query {
+        if !visibility.get() {
+            continue;
+        }
+
+        let chunk_size = tilemap.chunk_size.as_ivec2();
+        let chunks = tilemap
+            .chunks
+            .iter()
+            .map(|(&position, chunk)| {
+                // Only the chunks that changed are uploaded again
+                let uploaded = tilemap_meta
+                    .chunks
+                    .get(&(entity, position))
+                    .map_or(false, |gpu_chunk| gpu_chunk.revision == chunk.revision);
+                let tiles = (!uploaded).then(|| {
+                    chunk
+                        .tiles
+                        .iter()
+                        .enumerate()
+                        .filter_map(|(index, tile)| {
+                            let tile = tile.as_ref()?;
+                            let index = index as i32;
+                            let local = IVec2::new(index % chunk_size.x, index / chunk_size.x);
+                            let mut flags = 0;
+                            if tile.flip_x {
+                                flags |= TileInstance::FLIP_X;
+                            }
+                            if tile.flip_y {
+                                flags |= TileInstance::FLIP_Y;
+                            }
+                            Some(TileInstance {
+                                position: (position * chunk_size + local).as_vec2().into(),
+                                index: tile.index,
+                                flags,
+                                frame_count: tile.animation.map_or(1, |a| a.frame_count),
+                                fps: tile.animation.map_or(0.0, |a| a.fps),
+                                color: tile.color.as_linear_rgba_f32(),
+                            })
+                        })
+                        .collect()
+                });
+                ExtractedTilemapChunk {
+                    position,
+                    revision: chunk.revision,
+                    tiles,
+                }
+            })
+            .collect();
+
+        extracted_tilemaps.tilemaps.push(ExtractedTilemap {
+            entity,
+            transform: *transform,
+            tile_size: tilemap.tile_size,
+            image_handle_id: handle.id(),
+            chunks,
+        });
+    }
+}
+
+#[derive(ShaderType)]
+pub struct TilemapUniform {
+    pub transform: Mat4,
+    pub tile_size: Vec2,
+}
+
+/// The tiles of a chunk uploaded to the GPU.
+pub struct GpuTilemapChunk {
+    pub revision: u32,
+    pub buffer: Buffer,
+    pub instance_count: u32,
+}
+
+#[derive(Resource, Default)]
+pub struct TilemapMeta {
+    /// The chunks of each tilemap, kept until they change or their tilemap isn't extracted
+    pub chunks: HashMap<(Entity, IVec2), GpuTilemapChunk>,
+    uniforms: DynamicUniformBuffer<TilemapUniform>,
+    /// The offset of each tilemap in `uniforms`
+    uniform_offsets: HashMap<Entity, u32>,
+    bind_group: Option<BindGroup>,
+}
+
+pub fn prepare_tilemaps(
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    tilemap_pipeline: Res<TilemapPipeline>,
+    mut tilemap_meta: ResMut<TilemapMeta>,
+    mut extracted_tilemaps: ResMut<ExtractedTilemaps>,
+) {
+    let tilemap_meta = &mut *tilemap_meta;
+    let mut extracted_chunks = bevy_utils::HashSet::default();
+    tilemap_meta.uniforms.clear();
+    tilemap_meta.uniform_offsets.clear();
+
+    for tilemap in &mut extracted_tilemaps.tilemaps {
+        for chunk in &mut tilemap.chunks {
+            let key = (tilemap.entity, chunk.position);
+            extracted_chunks.insert(key);
+            let Some(tiles) = chunk.tiles.take() else {
+                continue;
+            };
+            let buffer = render_device.create_buffer_with_data(&BufferInitDescriptor {
+                label: Some("tilemap_chunk_buffer"),
+                contents: bytemuck::cast_slice(&tiles),
+                usage: BufferUsages::VERTEX,
+            });
+            tilemap_meta.chunks.insert(
+                key,
+                GpuTilemapChunk {
+                    revision: chunk.revision,
+                    buffer,
+                    instance_count: tiles.len() as u32,
+                },
+            );
+        }
+
+        let offset = tilemap_meta.uniforms.push(TilemapUniform {
+            transform: tilemap.transform.compute_matrix(),
+            tile_size: tilemap.tile_size,
+        });
+        tilemap_meta.uniform_offsets.insert(tilemap.entity, offset);
+    }
+
+    // Drop the chunks that were removed, or whose tilemap wasn't extracted
+    tilemap_meta
+        .chunks
+        .retain(|key, _| extracted_chunks.contains(key));
+
+    tilemap_meta
+        .uniforms
+        .write_buffer(&render_device, &render_queue);
+    tilemap_meta.bind_group = tilemap_meta.uniforms.binding().map(|binding| {
+        render_device.create_bind_group(&BindGroupDescriptor {
+            entries: &[BindGroupEntry {
+                binding: 0,
+                resource: binding,
+            }],
+            label: Some("tilemap_bind_group"),
+            layout: &tilemap_pipeline.tilemap_layout,
+        })
+    });
+}
+
+/// The tileset bind group of each image, with an array view of the texture.
+#[derive(Resource, Default)]
+pub struct TilemapImageBindGroups {
+    values: HashMap<Handle<Image>, BindGroup>,
+}
+
+/// A chunk of a tilemap to draw.
+#[derive(Component)]
+pub struct TilemapChunkBatch {
+    pub tilemap: Entity,
+    pub chunk: IVec2,
+    pub image_handle_id: HandleId,
+    pub uniform_offset: u32,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn queue_tilemaps(
+    mut commands: Commands,
+    draw_functions: Res<DrawFunctions<Transparent2d>>,
+    render_device: Res<RenderDevice>,
+    tilemap_pipeline: Res<TilemapPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<TilemapPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    tilemap_meta: Res<TilemapMeta>,
+    extracted_tilemaps: Res<ExtractedTilemaps>,
+    mut image_bind_groups: ResMut<TilemapImageBindGroups>,
+    gpu_images: Res<RenderAssets<Image>>,
+    events: Res<SpriteAssetEvents>,
+    mut views: Query<(
+        &mut RenderPhase<Transparent2d>,
+        &VisibleEntities,
+        &ExtractedView,
+        &Msaa,
+    )>,
+) {
+    // If an image has changed, the GpuImage has (probably) changed
+    for event in &events.images {
+        match event {
+            AssetEvent::Created { .. } | AssetEvent::LoadedWithDependencies { .. } => None,
+            AssetEvent::Modified { handle } | AssetEvent::Removed { handle } => {
+                image_bind_groups.values.remove(handle)
+            }
+        };
+    }
+
+    // Spawn an entity for each chunk, shared by all the views
+    let mut batches = Vec::new();
+    for tilemap in &extracted_tilemaps.tilemaps {
+        let image = Handle::weak(tilemap.image_handle_id);
+        let Some(gpu_image) = gpu_images.get(&image) else {
+            continue;
+        };
+        image_bind_groups.values.entry(image).or_insert_with(|| {
+            // Single layer images are viewed as 2d textures by default
+            let texture_view = gpu_image.texture.create_view(&TextureViewDescriptor {
+                dimension: Some(TextureViewDimension::D2Array),
+                ..Default::default()
+            });
+            render_device.create_bind_group(&BindGroupDescriptor {
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&texture_view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&gpu_image.sampler),
+                    },
+                ],
+                label: Some("tilemap_tileset_bind_group"),
+                layout: &tilemap_pipeline.tileset_layout,
+            })
+        });
+
+        let uniform_offset = tilemap_meta.uniform_offsets[&tilemap.entity];
+        let sort_key = FloatOrd(tilemap.transform.translation().z);
+        for chunk in &tilemap.chunks {
+            let batch = commands
+                .spawn(TilemapChunkBatch {
+                    tilemap: tilemap.entity,
+                    chunk: chunk.position,
+                    image_handle_id: tilemap.image_handle_id,
+                    uniform_offset,
+                })
+                .id();
+            batches.push((tilemap.entity, batch, sort_key));
+        }
+    }
+    if batches.is_empty() {
+        return;
+    }
+
+    let draw_tilemap_function = draw_functions.read().id::<DrawTilemapChunk>();
+    for (mut transparent_phase, visible_entities, view, msaa) in &mut views {
+        let pipeline = pipelines.specialize(
+            &pipeline_cache,
+            &tilemap_pipeline,
+            TilemapPipelineKey {
+                hdr: view.hdr,
+                msaa_samples: msaa.samples(),
+            },
+        );
+        for &(tilemap, entity, sort_key) in &batches {
+            if !visible_entities.entities.contains(&tilemap) {
+                continue;
+            }
+            transparent_phase.add(Transparent2d {
+                draw_function: draw_tilemap_function,
+                pipeline,
+                entity,
+                sort_key,
+                batch_range: None,
+            });
+        }
+    }
+}
+
+pub type DrawTilemapChunk = (
+    SetItemPipeline,
+    SetMesh2dViewBindGroup<0>,
+    SetTilemapTilesetBindGroup<1>,
+    SetTilemapBindGroup<2>,
+    DrawTilemapChunkBatch,
+);
+
+pub struct SetTilemapTilesetBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetTilemapTilesetBindGroup<I> {
+    type Param = SRes<TilemapImageBindGroups>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<TilemapChunkBatch>;
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: &'_ TilemapChunkBatch,
+        image_bind_groups: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(bind_group) = image_bind_groups
+            .into_inner()
+            .values
+            .get(&Handle::weak(batch.image_handle_id))
+        else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+pub struct SetTilemapBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetTilemapBindGroup<I> {
+    type Param = SRes<TilemapMeta>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<TilemapChunkBatch>;
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: &'_ TilemapChunkBatch,
+        tilemap_meta: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(bind_group) = tilemap_meta.into_inner().bind_group.as_ref() else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, bind_group, &[batch.uniform_offset]);
+        RenderCommandResult::Success
+    }
+}
+
+pub struct DrawTilemapChunkBatch;
+impl<P: PhaseItem> RenderCommand<P> for DrawTilemapChunkBatch {
+    type Param = SRes<TilemapMeta>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<TilemapChunkBatch>;
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: &'_ TilemapChunkBatch,
+        tilemap_meta: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(chunk) = tilemap_meta
+            .into_inner()
+            .chunks
+            .get(&(batch.tilemap, batch.chunk))
+        else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_vertex_buffer(0, chunk.buffer.slice(..));
+        // A quad of two triangles for each tile
+        pass.draw(0..6, 0..chunk.instance_count);
+        RenderCommandResult::Success
+    }
+}
@@ -0,0 +1,66 @@
+#import bevy_sprite::mesh2d_view_bindings
+
+struct Tilemap {
+    transform: mat4x4<f32>,
+    tile_size: vec2<f32>,
+};
+
+@group(1) @binding(0)
+var tileset_texture: texture_2d_array<f32>;
+@group(1) @binding(1)
+var tileset_sampler: sampler;
+
+@group(2) @binding(0)
+var<uniform> tilemap: Tilemap;
+
+// NOTE: These must match the bit flags in bevy_sprite/src/tilemap/render.rs!
+const TILE_FLAGS_FLIP_X: u32 = 1u;
+const TILE_FLAGS_FLIP_Y: u32 = 2u;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) @interpolate(flat) layer: u32,
+    @location(2) color: vec4<f32>,
+};
+
+@vertex
+fn vertex(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) tile_position: vec2<f32>,
+    @location(1) index: u32,
+    @location(2) flags: u32,
+    @location(3) frame_count: u32,
+    @location(4) fps: f32,
+    @location(5) color: vec4<f32>,
+) -> VertexOutput {
+    // The two triangles of the tile, with the corners (0, 0), (1, 0), (1, 1) and (0, 0), (1, 1),
+    // (0, 1): the bits of these masks are the coordinates of each of the 6 vertices.
+    let corner = vec2<f32>(f32((0x16u >> vertex_index) & 1u), f32((0x34u >> vertex_index) & 1u));
+
+    var out: VertexOutput;
+    let position = (tile_position + corner - 0.5) * tilemap.tile_size;
+    out.position = view.view_proj * tilemap.transform * vec4<f32>(position, 0.0, 1.0);
+
+    out.uv = vec2<f32>(corner.x, 1.0 - corner.y);
+    if (flags & TILE_FLAGS_FLIP_X) != 0u {
+        out.uv.x = 1.0 - out.uv.x;
+    }
+    if (flags & TILE_FLAGS_FLIP_Y) != 0u {
+        out.uv.y = 1.0 - out.uv.y;
+    }
+
+    // Animated tiles loop through the layers following their index
+    var frame = 0u;
+    if frame_count > 1u {
+        frame = u32(globals.time * fps) % frame_count;
+    }
+    out.layer = index + frame;
+    out.color = color;
+    return out;
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    return in.color * textureSample(tileset_texture, tileset_sampler, in.uv, i32(in.layer));
+}
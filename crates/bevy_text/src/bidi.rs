@@ -0,0 +1,670 @@
+//! A subset of the [Unicode Bidirectional Algorithm](https://www.unicode.org/reports/tr9/), to
+//! lay out mixed left-to-right and right-to-left text.
+//!
+//! The explicit embeddings, overrides and isolates are not supported, nor are the bracket pairs
+//! resolved. The levels of the characters are resolved for each paragraph, and the glyphs of
+//! each line laid out by `glyph_brush_layout` are then reordered by their levels. The shaped
+//! text is reordered by clusters instead, before being positioned, see `shaping`.
+
+use ab_glyph::{Font as _, FontArc, ScaleFont as _};
+use glyph_brush_layout::{SectionGlyph, ToSectionText};
+
+use crate::TextDirection;
+
+/// The bidirectional class of a character.
+#[allow(clippy::upper_case_acronyms)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BidiClass {
+    /// Left-to-right letter.
+    L,
+    /// Right-to-left letter.
+    R,
+    /// Arabic letter.
+    AL,
+    /// European number.
+    EN,
+    /// European number separator.
+    ES,
+    /// European number terminator.
+    ET,
+    /// Arabic number.
+    AN,
+    /// Common number separator.
+    CS,
+    /// Non-spacing mark.
+    NSM,
+    /// Paragraph separator.
+    B,
+    /// Segment separator.
+    S,
+    /// Whitespace.
+    WS,
+    /// Other neutral.
+    ON,
+}
+
+use BidiClass::*;
+
+impl BidiClass {
+    fn of(c: char) -> BidiClass {
+        match c {
+            '\n' | '\r' | '\u{1C}'..='\u{1E}' | '\u{85}' | '\u{2029}' => B,
+            '\t' | '\u{B}' | '\u{1F}' => S,
+            ' ' | '\u{C}' | '\u{2000}'..='\u{200A}' | '\u{2028}' | '\u{205F}' | '\u{3000}' => WS,
+            '0'..='9'
+            | '\u{B2}'
+            | '\u{B3}'
+            | '\u{B9}'
+            | '\u{6F0}'..='\u{6F9}'
+            | '\u{FF10}'..='\u{FF19}' => EN,
+            '+' | '-' | '\u{207A}' | '\u{207B}' | '\u{FF0B}' | '\u{FF0D}' => ES,
+            '#'
+            | '$'
+            | '%'
+            | '\u{A2}'..='\u{A5}'
+            | '\u{B0}'
+            | '\u{B1}'
+            | '\u{20A0}'..='\u{20CF}' => ET,
+            ',' | '.' | '/' | ':' | '\u{A0}' | '\u{60C}' | '\u{202F}' | '\u{2044}' => CS,
+            '\u{600}'..='\u{605}' | '\u{660}'..='\u{669}' | '\u{66B}' | '\u{66C}' => AN,
+            '\u{300}'..='\u{36F}'
+            | '\u{483}'..='\u{489}'
+            | '\u{591}'..='\u{5BD}'
+            | '\u{5BF}'
+            | '\u{5C1}'
+            | '\u{5C2}'
+            | '\u{5C4}'
+            | '\u{5C5}'
+            | '\u{5C7}'
+            | '\u{610}'..='\u{61A}'
+            | '\u{64B}'..='\u{65F}'
+            | '\u{670}'
+            | '\u{6D6}'..='\u{6DC}'
+            | '\u{6DF}'..='\u{6E4}'
+            | '\u{6E7}'
+            | '\u{6E8}'
+            | '\u{6EA}'..='\u{6ED}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FE20}'..='\u{FE2F}' => NSM,
+            '\u{200E}' => L,
+            '\u{200F}'
+            | '\u{590}'..='\u{5FF}'
+            | '\u{7C0}'..='\u{85F}'
+            | '\u{FB1D}'..='\u{FB4F}'
+            | '\u{10800}'..='\u{10FFF}'
+            | '\u{1E800}'..='\u{1EFFF}' => R,
+            '\u{61C}'
+            | '\u{600}'..='\u{7BF}'
+            | '\u{860}'..='\u{8FF}'
+            | '\u{FB50}'..='\u{FDFF}'
+            | '\u{FE70}'..='\u{FEFF}' => AL,
+            c if c.is_alphanumeric() => L,
+            _ => ON,
+        }
+    }
+
+    fn is_neutral(self) -> bool {
+        matches!(self, B | S | WS | ON)
+    }
+
+    fn is_rtl(self) -> bool {
+        matches!(self, R | AL | AN)
+    }
+}
+
+/// The character mirrored in right-to-left text, like the opening and closing brackets.
+fn mirrored(c: char) -> Option<char> {
+    Some(match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        '‹' => '›',
+        '›' => '‹',
+        _ => return None,
+    })
+}
+
+/// Resolves the embedding levels of the `classes` of a paragraph, at the `base` level.
+///
+/// The odd levels are right-to-left, and the even ones left-to-right.
+fn resolve_levels(classes: &mut [BidiClass], base: u8) -> Vec<u8> {
+    let base_class = if base % 2 == 1 { R } else { L };
+
+    // W1: non-spacing marks take the class of the previous character
+    let mut previous = base_class;
+    for class in classes.iter_mut() {
+        if *class == NSM {
+            *class = previous;
+        }
+        previous = *class;
+    }
+
+    // W2 and W3: numbers after Arabic letters are Arabic numbers, and Arabic letters are
+    // right-to-left
+    let mut last_strong = base_class;
+    for class in classes.iter_mut() {
+        match *class {
+            L | R => last_strong = *class,
+            AL => {
+                last_strong = AL;
+                *class = R;
+            }
+            EN if last_strong == AL => *class = AN,
+            _ => {}
+        }
+    }
+
+    // W4: a single separator between two numbers of the same kind joins them
+    for i in 1..classes.len().saturating_sub(1) {
+        let (before, after) = (classes[i - 1], classes[i + 1]);
+        classes[i] = match (before, classes[i], after) {
+            (EN, ES | CS, EN) => EN,
+            (AN, CS, AN) => AN,
+            _ => continue,
+        };
+    }
+
+    // W5: terminators next to European numbers are part of them
+    let mut i = 0;
+    while i < classes.len() {
+        if classes[i] != ET {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < classes.len() && classes[i] == ET {
+            i += 1;
+        }
+        let before_is_number = start > 0 && classes[start - 1] == EN;
+        let after_is_number = i < classes.len() && classes[i] == EN;
+        if before_is_number || after_is_number {
+            classes[start..i].fill(EN);
+        }
+    }
+
+    // W6 and W7: the remaining separators are neutral, and European numbers after
+    // left-to-right letters are left-to-right
+    let mut last_strong = base_class;
+    for class in classes.iter_mut() {
+        match *class {
+            ES | ET | CS => *class = ON,
+            L | R => last_strong = *class,
+            EN if last_strong == L => *class = L,
+            _ => {}
+        }
+    }
+
+    // N1 and N2: the neutrals between two characters of the same direction take it, and the
+    // others take the direction of the paragraph
+    let direction = |class: BidiClass| match class {
+        L => L,
+        _ => R,
+    };
+    let mut i = 0;
+    while i < classes.len() {
+        if !classes[i].is_neutral() {
+            i += 1;
+            continue;
+        }
+        let start = i;
+        while i < classes.len() && classes[i].is_neutral() {
+            i += 1;
+        }
+        let before = if start == 0 {
+            base_class
+        } else {
+            direction(classes[start - 1])
+        };
+        let after = if i == classes.len() {
+            base_class
+        } else {
+            direction(classes[i])
+        };
+        let resolved = if before == after { before } else { base_class };
+        classes[start..i].fill(resolved);
+    }
+
+    // I1 and I2
+    classes
+        .iter()
+        .map(|class| match (base % 2 == 1, class) {
+            (false, R) | (true, L | EN | AN) => base + 1,
+            (false, AN | EN) => base + 2,
+            _ => base,
+        })
+        .collect()
+}
+
+/// Reorders the glyphs of each line laid out from `sections`, so that the right-to-left runs of
+/// characters are drawn from right to left.
+///
+/// The glyphs are moved within the bounds of their line, which keeps its alignment, and the
+/// mirrored characters like brackets are swapped in the right-to-left runs.
+pub(crate) fn reorder_glyphs<S: ToSectionText>(
+    glyphs: &mut [SectionGlyph],
+    sections: &[S],
+    direction: TextDirection,
+    fonts: &[FontArc],
+) {
+    let sections: Vec<_> = sections
+        .iter()
+        .map(|section| section.to_section_text())
+        .collect();
+    if direction != TextDirection::RightToLeft
+        && !sections
+            .iter()
+            .any(|section| section.text.chars().any(|c| BidiClass::of(c).is_rtl()))
+    {
+        // Most text has nothing to reorder
+        return;
+    }
+
+    let (levels, paragraph_levels) = resolve_section_levels(&sections, direction);
+
+    let mut line_start = 0;
+    while line_start < glyphs.len() {
+        let line_y = glyphs[line_start].glyph.position.y;
+        let line_end = glyphs[line_start..]
+            .iter()
+            .position(|glyph| glyph.glyph.position.y != line_y)
+            .map_or(glyphs.len(), |length| line_start + length);
+        reorder_line(
+            &mut glyphs[line_start..line_end],
+            &sections,
+            &levels,
+            &paragraph_levels,
+            fonts,
+        );
+        line_start = line_end;
+    }
+}
+
+/// The levels of the characters of `sections` by their byte index, and the levels of their
+/// paragraphs.
+pub(crate) fn resolve_section_levels(
+    sections: &[glyph_brush_layout::SectionText],
+    direction: TextDirection,
+) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+    let mut levels: Vec<Vec<u8>> = sections
+        .iter()
+        .map(|section| vec![0; section.text.len()])
+        .collect();
+    let mut paragraph_levels: Vec<Vec<u8>> = levels.clone();
+    let mut paragraph: Vec<(usize, usize, char)> = Vec::new();
+    let characters = sections
+        .iter()
+        .enumerate()
+        .flat_map(|(section_index, section)| {
+            section
+                .text
+                .char_indices()
+                .map(move |(byte_index, c)| (section_index, byte_index, c))
+        })
+        .map(Some)
+        .chain(std::iter::once(None));
+    for character in characters {
+        if let Some(character) = character {
+            paragraph.push(character);
+            if BidiClass::of(character.2) != B {
+                continue;
+            }
+        }
+
+        // P2 and P3: the level of the paragraph is set by its first strong character
+        let base = match direction {
+            TextDirection::LeftToRight => 0,
+            TextDirection::RightToLeft => 1,
+            TextDirection::Auto => paragraph
+                .iter()
+                .map(|(_, _, c)| BidiClass::of(*c))
+                .find(|class| matches!(class, L | R | AL))
+                .map_or(0, |class| u8::from(class != L)),
+        };
+        let mut classes: Vec<_> = paragraph
+            .iter()
+            .map(|(_, _, c)| BidiClass::of(*c))
+            .collect();
+        let original_classes = classes.clone();
+        let paragraph_resolved = resolve_levels(&mut classes, base);
+        for (i, &(section_index, byte_index, _)) in paragraph.iter().enumerate() {
+            // L1: the separators are reset to the level of the paragraph
+            levels[section_index][byte_index] = match original_classes[i] {
+                B | S => base,
+                _ => paragraph_resolved[i],
+            };
+            paragraph_levels[section_index][byte_index] = base;
+        }
+        paragraph.clear();
+    }
+    (levels, paragraph_levels)
+}
+
+/// Reorders the glyphs of a line by the levels of their characters (L2), and mirrors their
+/// characters in the right-to-left runs (L4).
+fn reorder_line(
+    line: &mut [SectionGlyph],
+    sections: &[glyph_brush_layout::SectionText],
+    levels: &[Vec<u8>],
+    paragraph_levels: &[Vec<u8>],
+    fonts: &[FontArc],
+) {
+    let character = |glyph: &SectionGlyph| {
+        sections[glyph.section_index].text[glyph.byte_index..]
+            .chars()
+            .next()
+            .unwrap_or(' ')
+    };
+    let mut glyph_levels: Vec<u8> = line
+        .iter()
+        .map(|glyph| levels[glyph.section_index][glyph.byte_index])
+        .collect();
+    // L1: the whitespace at the end of the line is reset to the level of the paragraph
+    for (glyph, level) in line.iter().zip(&mut glyph_levels).rev() {
+        if !character(glyph).is_whitespace() {
+            break;
+        }
+        *level = paragraph_levels[glyph.section_index][glyph.byte_index];
+    }
+    let Some(&highest) = glyph_levels.iter().max() else {
+        return;
+    };
+    let lowest_odd = glyph_levels
+        .iter()
+        .copied()
+        .filter(|level| level % 2 == 1)
+        .min()
+        .unwrap_or(highest + 1);
+
+    let advances: Vec<f32> = line
+        .iter()
+        .map(|glyph| {
+            fonts[glyph.font_id.0]
+                .as_scaled(glyph.glyph.scale)
+                .h_advance(glyph.glyph.id)
+        })
+        .collect();
+
+    // The glyphs in visual order, each run of glyphs at a level of at least `level` being
+    // reversed from the highest level to the lowest odd one
+    let mut order: Vec<usize> = (0..line.len()).collect();
+    for level in (lowest_odd..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if glyph_levels[order[i]] < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < order.len() && glyph_levels[order[i]] >= level {
+                i += 1;
+            }
+            let run = &mut order[start..i];
+            let min = run
+                .iter()
+                .map(|&glyph| line[glyph].glyph.position.x)
+                .fold(f32::MAX, f32::min);
+            let max = run
+                .iter()
+                .map(|&glyph| line[glyph].glyph.position.x + advances[glyph])
+                .fold(f32::MIN, f32::max);
+            for &glyph in run.iter() {
+                let position = &mut line[glyph].glyph.position;
+                position.x = min + max - (position.x + advances[glyph]);
+            }
+            run.reverse();
+        }
+    }
+
+    for (glyph, level) in line.iter_mut().zip(glyph_levels) {
+        if level % 2 != 1 {
+            continue;
+        }
+        if let Some(mirrored) = mirrored(character(glyph)) {
+            let font = &fonts[glyph.font_id.0];
+            let id = font.glyph_id(mirrored);
+            if id.0 != 0 {
+                glyph.glyph.id = id;
+            }
+        }
+    }
+}
+
+/// The visual order of the items of a line at `levels` (L2), each run of items at a level of at
+/// least `level` being reversed from the highest level to the lowest odd one.
+pub(crate) fn visual_order(levels: &[u8]) -> Vec<usize> {
+    let mut order: Vec<usize> = (0..levels.len()).collect();
+    let Some(&highest) = levels.iter().max() else {
+        return order;
+    };
+    let lowest_odd = levels
+        .iter()
+        .copied()
+        .filter(|level| level % 2 == 1)
+        .min()
+        .unwrap_or(highest + 1);
+    for level in (lowest_odd..=highest).rev() {
+        let mut i = 0;
+        while i < order.len() {
+            if levels[order[i]] < level {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < order.len() && levels[order[i]] >= level {
+                i += 1;
+            }
+            order[start..i].reverse();
+        }
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{glyph_brush::GlyphBrush, test_font, BreakLineOn, TextAlignment, TextOverflow};
+    use bevy_asset::Handle;
+    use bevy_math::Vec2;
+    use glyph_brush_layout::{ab_glyph::PxScale, SectionText};
+
+    /// The levels of the characters of `text`, as digits
+    fn levels(text: &str, base: u8) -> String {
+        let mut classes: Vec<_> = text.chars().map(BidiClass::of).collect();
+        resolve_levels(&mut classes, base)
+            .into_iter()
+            .map(|level| char::from(b'0' + level))
+            .collect()
+    }
+
+    #[test]
+    fn resolved_levels() {
+        // (text, level of the paragraph, levels of its characters)
+        let cases = [
+            ("abc", 0, "000"),
+            ("abc", 1, "222"),
+            ("אבג", 0, "111"),
+            ("אבג", 1, "111"),
+            // the neutrals between two directions take the one of the paragraph
+            ("abc אבג", 0, "0000111"),
+            ("abc אבג", 1, "2221111"),
+            ("אבג abc", 0, "1110000"),
+            // the neutrals between the same directions take it
+            ("a (b) c", 1, "2222222"),
+            ("א (ב) ג", 0, "1111111"),
+            // the numbers are at a higher level than right-to-left letters
+            ("אבג 123", 0, "1111222"),
+            ("abc 123", 0, "0000000"),
+            ("123", 1, "222"),
+            // the numbers after Arabic letters are Arabic numbers
+            ("عرب 123", 0, "1111222"),
+            ("١٢٣", 0, "222"),
+            // the separators and terminators are part of the numbers
+            ("א 1.5", 0, "11222"),
+            ("א 1+5", 0, "11222"),
+            ("א 1..5", 0, "112112"),
+            ("א $10", 1, "11222"),
+            ("א 10%", 1, "11222"),
+            // the non-spacing marks take the level of the character before them
+            ("אְb", 0, "110"),
+            ("a\u{301}א", 1, "221"),
+        ];
+        for (text, base, expected) in cases {
+            assert_eq!(levels(text, base), expected, "{text:?} at level {base}");
+        }
+    }
+
+    /// Lays out `text` on a line for each paragraph, and returns the characters of each line
+    /// sorted by the position of their glyphs
+    fn reorder(text: &str, direction: TextDirection) -> Vec<String> {
+        let font = test_font().font;
+        let mut brush = GlyphBrush::default();
+        let font_id = brush.add_font(Handle::default(), font);
+        let sections = [SectionText {
+            text,
+            scale: PxScale::from(20.0),
+            font_id,
+        }];
+        let glyphs = brush
+            .compute_glyphs(
+                &sections,
+                Vec2::splat(f32::INFINITY),
+                TextAlignment::Left,
+                BreakLineOn::WordBoundary,
+                direction,
+                TextOverflow::default(),
+            )
+            .unwrap();
+
+        let mut glyphs: Vec<_> = glyphs
+            .iter()
+            .map(|glyph| {
+                let character = text[glyph.byte_index..].chars().next().unwrap();
+                (glyph.glyph.position.y, glyph.glyph.position.x, character)
+            })
+            .filter(|(_, _, character)| *character != '\n')
+            .collect();
+        glyphs.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.total_cmp(&b.1)));
+        let mut lines: Vec<(f32, String)> = Vec::new();
+        for (y, _, character) in glyphs {
+            match lines.last_mut() {
+                Some((line_y, line)) if *line_y == y => line.push(character),
+                _ => lines.push((y, character.to_string())),
+            }
+        }
+        lines.into_iter().map(|(_, line)| line).collect()
+    }
+
+    #[test]
+    fn reordered_glyphs() {
+        use TextDirection::*;
+        // (text, direction, characters of each line from left to right)
+        let cases: &[(&str, TextDirection, &[&str])] = &[
+            ("abc", Auto, &["abc"]),
+            ("abc", RightToLeft, &["abc"]),
+            ("אבג", Auto, &["גבא"]),
+            ("abc אבג def", Auto, &["abc גבא def"]),
+            // the direction of the paragraph is the one of its first letter
+            ("אבג abc", Auto, &["abc גבא"]),
+            ("אבג abc", LeftToRight, &["גבא abc"]),
+            ("abc אבג", RightToLeft, &["גבא abc"]),
+            // the numbers keep their order in right-to-left text
+            ("אבג 123", Auto, &["123 גבא"]),
+            ("אב 12 גד", Auto, &["דג 12 בא"]),
+            // each paragraph has its own direction
+            ("abc אבג\nאבג abc", Auto, &["abc גבא", "abc גבא"]),
+            // the whitespace at the end of the line keeps the direction of the paragraph
+            ("אבג  ", LeftToRight, &["גבא  "]),
+        ];
+        for (text, direction, expected) in cases {
+            assert_eq!(
+                reorder(text, *direction),
+                *expected,
+                "{text:?} {direction:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn reordered_glyphs_keep_line_bounds() {
+        let font = test_font().font;
+        let mut brush = GlyphBrush::default();
+        let font_id = brush.add_font(Handle::default(), font);
+        let layout = |text| {
+            let sections = [SectionText {
+                text,
+                scale: PxScale::from(20.0),
+                font_id,
+            }];
+            let glyphs = brush
+                .compute_glyphs(
+                    &sections,
+                    Vec2::new(200.0, f32::INFINITY),
+                    TextAlignment::Center,
+                    BreakLineOn::WordBoundary,
+                    TextDirection::Auto,
+                    TextOverflow::default(),
+                )
+                .unwrap();
+            let mut positions: Vec<i32> = glyphs
+                .iter()
+                .map(|glyph| (glyph.glyph.position.x * 100.0).round() as i32)
+                .collect();
+            positions.sort_unstable();
+            positions
+        };
+        // the glyphs of a monospace font take the same places in any order
+        assert_eq!(layout("abc דהו ghi"), layout("abc def ghi"));
+    }
+
+    #[test]
+    fn brackets_are_mirrored_in_right_to_left_runs() {
+        let font = test_font().font;
+        let mut brush = GlyphBrush::default();
+        let font_id = brush.add_font(Handle::default(), font.clone());
+        let glyph_ids = |text, direction| {
+            let sections = [SectionText {
+                text,
+                scale: PxScale::from(20.0),
+                font_id,
+            }];
+            let mut glyphs = brush
+                .compute_glyphs(
+                    &sections,
+                    Vec2::splat(f32::INFINITY),
+                    TextAlignment::Left,
+                    BreakLineOn::WordBoundary,
+                    direction,
+                    TextOverflow::default(),
+                )
+                .unwrap();
+            glyphs.sort_by(|a, b| a.glyph.position.x.total_cmp(&b.glyph.position.x));
+            glyphs
+                .iter()
+                .map(|glyph| glyph.glyph.id)
+                .collect::<Vec<_>>()
+        };
+        let [open, close, a, b] = ['(', ')', 'a', 'b'].map(|c| font.glyph_id(c));
+        let alef = font.glyph_id('א');
+
+        // drawn from right to left, the closing bracket is drawn at the left
+        assert_eq!(glyph_ids("(א)", TextDirection::Auto), [open, alef, close]);
+        assert_eq!(
+            glyph_ids("(ab)", TextDirection::RightToLeft),
+            [open, a, b, close]
+        );
+        assert_eq!(
+            glyph_ids("(ab)", TextDirection::LeftToRight),
+            [open, a, b, close]
+        );
+    }
+}
@@ -0,0 +1,536 @@
+use ab_glyph::{v2::GlyphImage, Font as _, FontArc, Glyph, GlyphImageFormat, OutlinedGlyph, Rect};
+use bevy_render::{
+    render_resource::{Extent3d, TextureDimension, TextureFormat},
+    texture::{CompressedImageFormats, Image, ImageType},
+};
+use ttf_parser::{
+    colr::{ClipBox, CompositeMode, Paint, Painter},
+    Face, GlyphId, RgbaColor, Tag, Transform,
+};
+
+/// A glyph drawn in color, from the color tables of its font instead of its outline.
+pub(crate) enum ColorGlyph<'a> {
+    /// A bitmap of the `CBDT` or `sbix` tables, like the emojis of Noto Color Emoji and Apple
+    /// Color Emoji, scaled to the font size.
+    Bitmap { image: GlyphImage<'a>, bounds: Rect },
+    /// The layers of the `COLR` table, like the emojis of Segoe UI Emoji, each outlined and
+    /// filled with its color.
+    Layers {
+        layers: Vec<(OutlinedGlyph, RgbaColor)>,
+        bounds: Rect,
+    },
+}
+
+impl<'a> ColorGlyph<'a> {
+    /// The color version of `glyph` in `font`, if it has one.
+    pub(crate) fn new(font: &'a FontArc, glyph: &Glyph) -> Option<Self> {
+        Self::bitmap(font, glyph).or_else(|| Self::layers(font, glyph))
+    }
+
+    fn bitmap(font: &'a FontArc, glyph: &Glyph) -> Option<Self> {
+        let font_size = glyph.scale.y;
+        let image = font.glyph_raster_image2(glyph.id, font_size.ceil() as u16)?;
+        // The other bitmaps are monochrome, and drawn from the outlines
+        if !matches!(
+            image.format,
+            GlyphImageFormat::Png | GlyphImageFormat::BitmapPremulBgra32
+        ) {
+            return None;
+        }
+        let scale = font_size / image.pixels_per_em as f32;
+        // The offset of the image is from its bottom left corner, upwards
+        let left = (glyph.position.x + image.origin.x * scale).round();
+        let bottom = (glyph.position.y - image.origin.y * scale).round();
+        let width = (image.width as f32 * scale).round().max(1.0);
+        let height = (image.height as f32 * scale).round().max(1.0);
+        let bounds = Rect {
+            min: ab_glyph::point(left, bottom - height),
+            max: ab_glyph::point(left + width, bottom),
+        };
+        Some(ColorGlyph::Bitmap { image, bounds })
+    }
+
+    fn layers(font: &'a FontArc, glyph: &Glyph) -> Option<Self> {
+        let data = font.font_data();
+        // Skips parsing the font again for the fonts without any color layers
+        let raw_face = ttf_parser::RawFace::parse(data, 0).ok()?;
+        raw_face.table(Tag::from_bytes(b"COLR"))?;
+        let face = Face::parse(data, 0).ok()?;
+        let glyph_id = GlyphId(glyph.id.0);
+        if !face.is_color_glyph(glyph_id) {
+            return None;
+        }
+
+        let mut painter = LayerPainter {
+            font,
+            glyph,
+            outline: None,
+            clips: Vec::new(),
+            layers: Vec::new(),
+        };
+        face.paint_color_glyph(
+            glyph_id,
+            0,
+            RgbaColor::new(255, 255, 255, 255),
+            &mut painter,
+        )?;
+        let layers = painter.layers;
+        let bounds = layers
+            .iter()
+            .map(|(outlined_glyph, _)| outlined_glyph.px_bounds())
+            .reduce(|a, b| Rect {
+                min: ab_glyph::point(a.min.x.min(b.min.x), a.min.y.min(b.min.y)),
+                max: ab_glyph::point(a.max.x.max(b.max.x), a.max.y.max(b.max.y)),
+            })?;
+        Some(ColorGlyph::Layers { layers, bounds })
+    }
+
+    /// The pixel bounds of the glyph, like [`OutlinedGlyph::px_bounds`].
+    pub(crate) fn px_bounds(&self) -> Rect {
+        match self {
+            ColorGlyph::Bitmap { bounds, .. } | ColorGlyph::Layers { bounds, .. } => *bounds,
+        }
+    }
+
+    /// Draws the glyph in an image the size of its [`px_bounds`](Self::px_bounds), or returns
+    /// `None` if its bitmap can't be decoded.
+    pub(crate) fn texture(&self) -> Option<Image> {
+        let bounds = self.px_bounds();
+        let width = bounds.width() as usize;
+        let height = bounds.height() as usize;
+        let data = match self {
+            ColorGlyph::Bitmap { image, .. } => {
+                let (image_width, image_height, rgba) = decode_bitmap(image)?;
+                resize(&rgba, image_width, image_height, width, height)
+            }
+            ColorGlyph::Layers { layers, .. } => {
+                let mut rgba = vec![0; width * height * 4];
+                for (outlined_glyph, color) in layers {
+                    let layer_bounds = outlined_glyph.px_bounds();
+                    let offset_x = (layer_bounds.min.x - bounds.min.x) as usize;
+                    let offset_y = (layer_bounds.min.y - bounds.min.y) as usize;
+                    outlined_glyph.draw(|x, y, coverage| {
+                        let index = ((offset_y + y as usize) * width + offset_x + x as usize) * 4;
+                        blend_over(&mut rgba[index..index + 4], *color, coverage);
+                    });
+                }
+                rgba
+            }
+        };
+        Some(Image::new(
+            Extent3d {
+                width: width as u32,
+                height: height as u32,
+                depth_or_array_layers: 1,
+            },
+            TextureDimension::D2,
+            data,
+            TextureFormat::Rgba8UnormSrgb,
+        ))
+    }
+}
+
+/// Decodes the bitmap of `image` to straight RGBA, returned with its size.
+fn decode_bitmap(image: &GlyphImage) -> Option<(usize, usize, Vec<u8>)> {
+    match image.format {
+        GlyphImageFormat::Png => {
+            let decoded = Image::from_buffer(
+                image.data,
+                ImageType::Extension("png"),
+                CompressedImageFormats::NONE,
+                true,
+            )
+            .ok()?;
+            let decoded = if decoded.texture_descriptor.format == TextureFormat::Rgba8UnormSrgb {
+                decoded
+            } else {
+                decoded.convert(TextureFormat::Rgba8UnormSrgb)?
+            };
+            let size = decoded.texture_descriptor.size;
+            Some((size.width as usize, size.height as usize, decoded.data))
+        }
+        GlyphImageFormat::BitmapPremulBgra32 => {
+            let (width, height) = (image.width as usize, image.height as usize);
+            let bgra = image.data.get(..width * height * 4)?;
+            let rgba = bgra
+                .chunks_exact(4)
+                .flat_map(|pixel| {
+                    let unpremultiply = |channel: u8| match pixel[3] {
+                        0 => 0,
+                        alpha => (channel as u32 * 255 / alpha as u32).min(255) as u8,
+                    };
+                    [
+                        unpremultiply(pixel[2]),
+                        unpremultiply(pixel[1]),
+                        unpremultiply(pixel[0]),
+                        pixel[3],
+                    ]
+                })
+                .collect();
+            Some((width, height, rgba))
+        }
+        _ => None,
+    }
+}
+
+/// Resizes the straight RGBA `data` of a `width` by `height` image, averaging the pixels under
+/// each new pixel weighted by their alpha.
+fn resize(
+    data: &[u8],
+    width: usize,
+    height: usize,
+    new_width: usize,
+    new_height: usize,
+) -> Vec<u8> {
+    let scale_x = width as f32 / new_width as f32;
+    let scale_y = height as f32 / new_height as f32;
+    let mut resized = Vec::with_capacity(new_width * new_height * 4);
+    for y in 0..new_height {
+        let (y0, y1) = (y as f32 * scale_y, (y + 1) as f32 * scale_y);
+        for x in 0..new_width {
+            let (x0, x1) = (x as f32 * scale_x, (x + 1) as f32 * scale_x);
+            let mut sum = [0.0; 4];
+            let mut area = 0.0;
+            for source_y in y0 as usize..(y1.ceil() as usize).min(height) {
+                let weight_y = y1.min(source_y as f32 + 1.0) - y0.max(source_y as f32);
+                for source_x in x0 as usize..(x1.ceil() as usize).min(width) {
+                    let weight =
+                        weight_y * (x1.min(source_x as f32 + 1.0) - x0.max(source_x as f32));
+                    let pixel = &data[(source_y * width + source_x) * 4..][..4];
+                    let alpha = pixel[3] as f32 * weight;
+                    for channel in 0..3 {
+                        sum[channel] += pixel[channel] as f32 * alpha;
+                    }
+                    sum[3] += alpha;
+                    area += weight;
+                }
+            }
+            if sum[3] > 0.0 {
+                resized.extend(sum[..3].iter().map(|channel| (channel / sum[3]) as u8));
+                resized.push((sum[3] / area) as u8);
+            } else {
+                resized.extend([0; 4]);
+            }
+        }
+    }
+    resized
+}
+
+/// Blends `color` with `coverage` over the straight RGBA `pixel`.
+fn blend_over(pixel: &mut [u8], color: RgbaColor, coverage: f32) {
+    let alpha = color.alpha as f32 / 255.0 * coverage.clamp(0.0, 1.0);
+    let below = pixel[3] as f32 / 255.0;
+    let blended = alpha + below * (1.0 - alpha);
+    if blended <= 0.0 {
+        return;
+    }
+    for (channel, value) in pixel[..3]
+        .iter_mut()
+        .zip([color.red, color.green, color.blue])
+    {
+        *channel =
+            ((value as f32 * alpha + *channel as f32 * below * (1.0 - alpha)) / blended) as u8;
+    }
+    pixel[3] = (blended * 255.0) as u8;
+}
+
+/// Collects the color layers of a `COLR` glyph.
+///
+/// The gradients are filled with the average color of their stops, and the transforms and
+/// composite modes of the layers are ignored.
+struct LayerPainter<'a> {
+    font: &'a FontArc,
+    glyph: &'a Glyph,
+    /// The glyph outlined last, to fill or clip with.
+    outline: Option<GlyphId>,
+    /// The glyphs clipping the paints, the last one being the innermost.
+    clips: Vec<Option<GlyphId>>,
+    layers: Vec<(OutlinedGlyph, RgbaColor)>,
+}
+
+impl<'a, 'b> Painter<'b> for LayerPainter<'a> {
+    fn outline_glyph(&mut self, glyph_id: GlyphId) {
+        self.outline = Some(glyph_id);
+    }
+
+    fn paint(&mut self, paint: Paint<'b>) {
+        let color = match paint {
+            Paint::Solid(color) => color,
+            Paint::LinearGradient(gradient) => average_color(gradient.stops(0, &[])),
+            Paint::RadialGradient(gradient) => average_color(gradient.stops(0, &[])),
+            Paint::SweepGradient(gradient) => average_color(gradient.stops(0, &[])),
+        };
+        // The layers of the version 0 of the table are painted right after being outlined
+        let Some(glyph_id) = self
+            .outline
+            .take()
+            .or_else(|| self.clips.last().copied().flatten())
+        else {
+            return;
+        };
+        let glyph = Glyph {
+            id: ab_glyph::GlyphId(glyph_id.0),
+            ..self.glyph.clone()
+        };
+        if let Some(outlined_glyph) = self.font.outline_glyph(glyph) {
+            self.layers.push((outlined_glyph, color));
+        }
+    }
+
+    fn push_clip(&mut self) {
+        self.clips.push(self.outline.take());
+    }
+
+    fn push_clip_box(&mut self, _: ClipBox) {
+        // Keeps clipping with the glyph
+        let glyph = self.clips.last().copied().flatten();
+        self.clips.push(glyph);
+    }
+
+    fn pop_clip(&mut self) {
+        self.clips.pop();
+    }
+
+    fn push_layer(&mut self, _: CompositeMode) {}
+
+    fn pop_layer(&mut self) {}
+
+    fn push_transform(&mut self, _: Transform) {}
+
+    fn pop_transform(&mut self) {}
+}
+
+fn average_color(stops: impl Iterator<Item = ttf_parser::colr::ColorStop>) -> RgbaColor {
+    let mut sum = [0u32; 4];
+    let mut count = 0;
+    for stop in stops {
+        let color = stop.color;
+        for (sum, channel) in sum
+            .iter_mut()
+            .zip([color.red, color.green, color.blue, color.alpha])
+        {
+            *sum += channel as u32;
+        }
+        count += 1;
+    }
+    let [red, green, blue, alpha] = sum.map(|sum| (sum / count.max(1)) as u8);
+    RgbaColor::new(red, green, blue, alpha)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_font, BreakLineOn, Font, FontAtlasSet, FontAtlasWarning, FontFallbacks,
+        GlyphAtlasMode, TextAlignment, TextDirection, TextOverflow, TextPipeline, TextSection,
+        TextSettings, TextStyle, YAxisOrientation,
+    };
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin, Assets};
+    use bevy_math::Vec2;
+    use bevy_render::color::Color;
+    use bevy_sprite::TextureAtlas;
+
+    /// A test font of the `COLR` table, drawing all its characters in color
+    fn colr_font() -> FontArc {
+        FontArc::try_from_slice(include_bytes!("../../../assets/fonts/COLRv1Test.ttf")).unwrap()
+    }
+
+    /// A glyph of the `COLR` test font filled with a single solid color
+    const SOLID: char = '\u{F0B00}';
+
+    fn glyph(id: ab_glyph::GlyphId) -> Glyph {
+        id.with_scale_and_position(40.0, ab_glyph::point(0.0, 40.0))
+    }
+
+    #[test]
+    fn color_and_outline_glyphs() {
+        let mono = test_font().font;
+        let colr = colr_font();
+        let cases = [
+            (
+                &mono,
+                mono.glyph_id('a'),
+                None,
+                "a font without color tables",
+            ),
+            (&colr, colr.glyph_id(SOLID), Some(1), "a solid color glyph"),
+            (
+                &colr,
+                colr.glyph_id('\u{F0300}'),
+                Some(2),
+                "a glyph of two layers",
+            ),
+            // the glyph outlining the layers of a color glyph
+            (
+                &colr,
+                ab_glyph::GlyphId(176),
+                None,
+                "an outline glyph of a color font",
+            ),
+        ];
+        for (font, id, layer_count, message) in cases {
+            let glyph = glyph(id);
+            assert!(font.outline_glyph(glyph.clone()).is_some(), "{message}");
+            let layers = ColorGlyph::new(font, &glyph).map(|color_glyph| match color_glyph {
+                ColorGlyph::Layers { layers, .. } => layers.len(),
+                ColorGlyph::Bitmap { .. } => panic!("{message}: drawn from a bitmap"),
+            });
+            assert_eq!(layers, layer_count, "{message}");
+        }
+    }
+
+    #[test]
+    fn layers_in_their_colors() {
+        let font = colr_font();
+        let glyph = glyph(font.glyph_id('\u{F0300}'));
+        let Some(ColorGlyph::Layers { layers, bounds }) = ColorGlyph::new(&font, &glyph) else {
+            panic!("the glyph has no color layers");
+        };
+        let colors: Vec<_> = layers
+            .iter()
+            .map(|(_, color)| (color.red, color.green, color.blue, color.alpha))
+            .collect();
+        assert_eq!(colors, [(0, 0, 255, 127), (255, 165, 0, 178)]);
+        // the bounds contain all the layers
+        for (outlined_glyph, _) in &layers {
+            let layer_bounds = outlined_glyph.px_bounds();
+            assert!(layer_bounds.min.x >= bounds.min.x && layer_bounds.min.y >= bounds.min.y);
+            assert!(layer_bounds.max.x <= bounds.max.x && layer_bounds.max.y <= bounds.max.y);
+        }
+    }
+
+    #[test]
+    fn texture_drawn_in_color() {
+        let font = colr_font();
+        let color_glyph = ColorGlyph::new(&font, &glyph(font.glyph_id(SOLID))).unwrap();
+        let bounds = color_glyph.px_bounds();
+        let texture = color_glyph.texture().unwrap();
+        let size = texture.texture_descriptor.size;
+        assert_eq!(
+            (size.width as f32, size.height as f32),
+            (bounds.width(), bounds.height())
+        );
+        assert_eq!(
+            texture.texture_descriptor.format,
+            TextureFormat::Rgba8UnormSrgb
+        );
+
+        // the pixels covered by the glyph have its color instead of white
+        let covered: Vec<&[u8]> = texture
+            .data
+            .chunks_exact(4)
+            .filter(|pixel| pixel[3] > 0)
+            .collect();
+        assert!(!covered.is_empty());
+        assert!(covered.iter().any(|pixel| pixel[3] == 255));
+        for pixel in covered {
+            for (channel, expected) in pixel[..3].iter().zip([255, 195, 85]) {
+                assert!(channel.abs_diff(expected) <= 1, "{pixel:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn layers_blended_over_each_other() {
+        let red = RgbaColor::new(255, 0, 0, 255);
+        let blue = RgbaColor::new(0, 0, 255, 255);
+        let cases = [
+            ([0, 0, 0, 0], red, 1.0, [255, 0, 0, 255]),
+            ([0, 0, 0, 0], red, 0.0, [0, 0, 0, 0]),
+            ([0, 0, 255, 255], red, 1.0, [255, 0, 0, 255]),
+            ([0, 0, 255, 255], red, 0.5, [127, 0, 127, 255]),
+            (
+                [255, 0, 0, 255],
+                RgbaColor::new(0, 0, 255, 0),
+                1.0,
+                [255, 0, 0, 255],
+            ),
+            ([0, 0, 0, 0], blue, 0.5, [0, 0, 255, 127]),
+        ];
+        for (below, color, coverage, expected) in cases {
+            let mut pixel = below;
+            blend_over(&mut pixel, color, coverage);
+            assert_eq!(pixel, expected, "{color:?} with {coverage} over {below:?}");
+        }
+    }
+
+    #[test]
+    fn bitmaps_resized() {
+        // a 2 by 2 bitmap of a red, a transparent, a blue and a half transparent green pixel
+        let bitmap = [
+            255, 0, 0, 255, 0, 0, 0, 0, //
+            0, 0, 255, 255, 0, 255, 0, 128,
+        ];
+        assert_eq!(resize(&bitmap, 2, 2, 2, 2), bitmap);
+        // the transparent pixel doesn't darken the others, which are weighted by their alpha
+        let resized = resize(&bitmap, 2, 2, 1, 1);
+        assert_eq!(resized[3], 159);
+        assert_eq!(&resized[..3], [101, 51, 101]);
+        // upscaled, each pixel covers 4 pixels
+        let resized = resize(&bitmap[..4], 1, 1, 2, 2);
+        assert_eq!(resized, [255, 0, 0, 255].repeat(4));
+    }
+
+    #[test]
+    fn color_glyphs_not_tinted() {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default())
+            .add_asset::<Font>()
+            .add_asset::<FontAtlasSet>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<Image>();
+        let world = &mut app.world;
+        let mut fonts = world.remove_resource::<Assets<Font>>().unwrap();
+        let mut font_atlas_sets = world.remove_resource::<Assets<FontAtlasSet>>().unwrap();
+        let mut texture_atlases = world.remove_resource::<Assets<TextureAtlas>>().unwrap();
+        let mut images = world.remove_resource::<Assets<Image>>().unwrap();
+
+        let style = |font| TextStyle {
+            font,
+            font_size: 20.0,
+            color: Color::rgba(1.0, 0.0, 0.0, 0.5),
+            ..Default::default()
+        };
+        let sections = [
+            TextSection::new(SOLID, style(fonts.add(Font { font: colr_font() }))),
+            TextSection::new("a", style(fonts.add(test_font()))),
+        ];
+        let layout_info = TextPipeline::default()
+            .queue_text(
+                &fonts,
+                &FontFallbacks::default(),
+                &sections,
+                1.0,
+                TextAlignment::Left,
+                BreakLineOn::WordBoundary,
+                TextDirection::Auto,
+                TextOverflow::default(),
+                Vec2::splat(f32::INFINITY),
+                &mut font_atlas_sets,
+                &mut texture_atlases,
+                &mut images,
+                &TextSettings::default(),
+                &mut FontAtlasWarning::default(),
+                YAxisOrientation::TopToBottom,
+                GlyphAtlasMode::Bitmap,
+            )
+            .unwrap();
+
+        let glyphs: Vec<_> = layout_info
+            .fill_glyphs()
+            .map(|glyph| {
+                let style = &sections[glyph.section_index].style;
+                (glyph.atlas_info.is_color, glyph.color(style))
+            })
+            .collect();
+        // the color glyph keeps the alpha of the text, but not its color
+        assert_eq!(
+            glyphs,
+            [
+                (true, Color::rgba(1.0, 1.0, 1.0, 0.5)),
+                (false, Color::rgba(1.0, 0.0, 0.0, 0.5))
+            ]
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use bevy_math::Vec2;
+use bevy_reflect::{FromReflect, Reflect};
+use bevy_render::{
+    color::Color,
+    render_resource::{Extent3d, TextureDimension, TextureFormat},
+    texture::Image,
+};
+
+/// An outline drawn around the glyphs of a [`TextSection`](crate::TextSection), to keep them
+/// readable over any background.
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub struct TextOutline {
+    /// The width of the outline, in logical pixels.
+    pub width: f32,
+    pub color: Color,
+}
+
+impl Default for TextOutline {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            color: Color::BLACK,
+        }
+    }
+}
+
+/// A drop shadow drawn below the glyphs of a [`TextSection`](crate::TextSection), and below
+/// their [`TextOutline`].
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub struct TextShadow {
+    /// The offset of the shadow from the glyphs, in logical pixels, with `y` pointing down the
+    /// screen for both UI and 2d text.
+    pub offset: Vec2,
+    /// The distance the shadow is blurred over, in logical pixels, or `0.0` for a sharp shadow.
+    pub blur: f32,
+    pub color: Color,
+}
+
+impl Default for TextShadow {
+    fn default() -> Self {
+        Self {
+            offset: Vec2::new(2.0, 2.0),
+            blur: 0.0,
+            color: Color::rgba(0.0, 0.0, 0.0, 0.75),
+        }
+    }
+}
+
+/// A rectangle drawn behind all the sections of a [`Text`](crate::Text).
+#[derive(Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+pub struct TextBackground {
+    pub color: Color,
+    /// The space between the text and the horizontal and vertical edges of the rectangle, in
+    /// logical pixels.
+    pub padding: Vec2,
+}
+
+impl Default for TextBackground {
+    fn default() -> Self {
+        Self {
+            color: Color::rgba(0.0, 0.0, 0.0, 0.5),
+            padding: Vec2::splat(4.0),
+        }
+    }
+}
+
+/// What a [`PositionedGlyph`](crate::PositionedGlyph) draws of its glyph, each layer being
+/// drawn over the layers before it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum GlyphLayer {
+    /// The shadow of the glyph, in the color of the [`TextShadow`].
+    Shadow,
+    /// The outline of the glyph, in the color of the [`TextOutline`].
+    Outline,
+    /// The glyph itself, in the color of its text section.
+    #[default]
+    Fill,
+}
+
+/// The pixels added on each side of a glyph spread by `spread` pixels and blurred by `blur`
+/// pixels.
+pub(crate) fn effect_padding(spread: f32, blur: f32) -> usize {
+    (spread + blur).ceil() as usize + 1
+}
+
+/// Draws the outline or the shadow of a glyph from its `coverage`, spread by `spread` pixels
+/// then blurred by `blur` pixels, in a white image with [`effect_padding`] added on each side.
+pub(crate) fn effect_texture(
+    coverage: &[f32],
+    width: usize,
+    height: usize,
+    spread: f32,
+    blur: f32,
+) -> Image {
+    let padding = effect_padding(spread, blur);
+    let padded_width = width + 2 * padding;
+    let padded_height = height + 2 * padding;
+    let mut alpha = vec![0.0; padded_width * padded_height];
+    for (y, row) in coverage.chunks_exact(width.max(1)).enumerate() {
+        let start = (y + padding) * padded_width + padding;
+        alpha[start..start + row.len()].copy_from_slice(row);
+    }
+    if spread > 0.0 {
+        alpha = dilate(&alpha, padded_width, padded_height, spread);
+    }
+    if blur > 0.0 {
+        alpha = blur_alpha(&alpha, padded_width, padded_height, blur);
+    }
+
+    Image::new(
+        Extent3d {
+            width: padded_width as u32,
+            height: padded_height as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        alpha
+            .iter()
+            .flat_map(|a| [255, 255, 255, (a.clamp(0.0, 1.0) * 255.0) as u8])
+            .collect(),
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+/// Grows the shape in `alpha` by `radius` pixels in every direction, with antialiased edges.
+fn dilate(alpha: &[f32], width: usize, height: usize, radius: f32) -> Vec<f32> {
+    let reach = radius.ceil() as isize;
+    let offsets: Vec<_> = (-reach..=reach)
+        .flat_map(|dy| (-reach..=reach).map(move |dx| (dx, dy)))
+        .filter_map(|(dx, dy)| {
+            let distance = ((dx * dx + dy * dy) as f32).sqrt();
+            let weight = (radius + 1.0 - distance).clamp(0.0, 1.0);
+            (weight > 0.0).then_some((dx, dy, weight))
+        })
+        .collect();
+    let mut dilated = vec![0.0; alpha.len()];
+    for y in 0..height as isize {
+        for x in 0..width as isize {
+            let mut value: f32 = 0.0;
+            for &(dx, dy, weight) in &offsets {
+                let (sample_x, sample_y) = (x + dx, y + dy);
+                if sample_x >= 0
+                    && sample_y >= 0
+                    && (sample_x as usize) < width
+                    && (sample_y as usize) < height
+                {
+                    value =
+                        value.max(alpha[sample_y as usize * width + sample_x as usize] * weight);
+                }
+            }
+            dilated[y as usize * width + x as usize] = value;
+        }
+    }
+    dilated
+}
+
+/// Blurs `alpha` with a gaussian reaching `radius` pixels, at twice its standard deviation.
+fn blur_alpha(alpha: &[f32], width: usize, height: usize, radius: f32) -> Vec<f32> {
+    let sigma = radius / 2.0;
+    let reach = radius.ceil() as isize;
+    let kernel: Vec<f32> = (-reach..=reach)
+        .map(|offset| (-((offset * offset) as f32) / (2.0 * sigma * sigma)).exp())
+        .collect();
+    let total: f32 = kernel.iter().sum();
+
+    // The blur is applied along the rows then along the columns
+    let blur_pass = |source: &[f32], step: (isize, isize)| {
+        let mut blurred = vec![0.0; source.len()];
+        for y in 0..height as isize {
+            for x in 0..width as isize {
+                let mut value = 0.0;
+                for (weight, offset) in kernel.iter().zip(-reach..=reach) {
+                    let (sample_x, sample_y) = (x + offset * step.0, y + offset * step.1);
+                    if sample_x >= 0
+                        && sample_y >= 0
+                        && (sample_x as usize) < width
+                        && (sample_y as usize) < height
+                    {
+                        value += source[sample_y as usize * width + sample_x as usize] * weight;
+                    }
+                }
+                blurred[y as usize * width + x as usize] = value / total;
+            }
+        }
+        blurred
+    };
+    let rows = blur_pass(alpha, (1, 0));
+    blur_pass(&rows, (0, 1))
+}
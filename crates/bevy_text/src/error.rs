@@ -8,3 +8,26 @@ pub enum TextError {
     #[error("failed to add glyph to newly-created atlas {0:?}")]
     FailedToAddGlyph(GlyphId),
 }
+
+/// An error that occurs when parsing the markup of a text with [`Text::from_markup`].
+///
+/// [`Text::from_markup`]: crate::Text::from_markup
+#[derive(Debug, PartialEq, Eq, Error)]
+pub enum MarkupError {
+    #[error("unknown tag `{0}`")]
+    UnknownTag(String),
+    #[error("the tag `{0}` needs an argument")]
+    MissingArgument(String),
+    #[error("invalid color `{0}`")]
+    InvalidColor(String),
+    #[error("invalid font size `{0}`")]
+    InvalidSize(String),
+    #[error("the span `{0}` is defined more than once")]
+    DuplicateSpan(String),
+    #[error("the closing tag `{0}` doesn't match the last opened tag")]
+    UnexpectedClosingTag(String),
+    #[error("the tag `{0}` is never closed")]
+    UnclosedTag(String),
+    #[error("a tag is missing its closing `]`")]
+    UnterminatedTag,
+}
@@ -43,3 +43,10 @@ impl Font {
         )
     }
 }
+
+/// The monospace font of the examples, for the tests laying out text.
+#[cfg(test)]
+pub(crate) fn test_font() -> Font {
+    Font::try_from_bytes(include_bytes!("../../../assets/fonts/FiraMono-Medium.ttf").to_vec())
+        .unwrap()
+}
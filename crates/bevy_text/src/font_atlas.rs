@@ -50,6 +50,22 @@ impl FontAtlas {
         textures: &mut Assets<Image>,
         texture_atlases: &mut Assets<TextureAtlas>,
         size: Vec2,
+    ) -> FontAtlas {
+        Self::new_with_format(
+            textures,
+            texture_atlases,
+            size,
+            TextureFormat::Rgba8UnormSrgb,
+        )
+    }
+
+    /// Creates an atlas for glyph textures of another `format` than sRGB, like the signed
+    /// distance fields of the glyphs.
+    pub fn new_with_format(
+        textures: &mut Assets<Image>,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        size: Vec2,
+        format: TextureFormat,
     ) -> FontAtlas {
         let atlas_texture = textures.add(Image::new_fill(
             Extent3d {
@@ -59,7 +75,7 @@ impl FontAtlas {
             },
             TextureDimension::D2,
             &[0, 0, 0, 0],
-            TextureFormat::Rgba8UnormSrgb,
+            format,
         ));
         let texture_atlas = TextureAtlas::new_empty(atlas_texture, size);
         Self {
@@ -6,7 +6,7 @@ use bevy_reflect::TypeUuid;
 use bevy_render::texture::Image;
 use bevy_sprite::TextureAtlas;
 use bevy_utils::FloatOrd;
-use bevy_utils::HashMap;
+use bevy_utils::{HashMap, HashSet};
 
 type FontSizeKey = FloatOrd;
 
@@ -14,6 +14,12 @@ type FontSizeKey = FloatOrd;
 #[uuid = "73ba778b-b6b5-4f45-982d-d21b6b86ace2"]
 pub struct FontAtlasSet {
     font_atlases: HashMap<FontSizeKey, Vec<FontAtlas>>,
+    /// The glyphs added in color, drawn without the color of their text.
+    color_glyphs: HashSet<GlyphId>,
+    /// The atlases of the outlines and shadows of the glyphs, by font size, spread and blur.
+    effect_atlases: HashMap<(FontSizeKey, FloatOrd, FloatOrd), Vec<FontAtlas>>,
+    /// The atlases of the signed distance fields of the glyphs, shared by all the font sizes.
+    msdf_atlases: Vec<FontAtlas>,
     // TODO unused, remove
     #[allow(dead_code)]
     queue: Vec<FontSizeKey>,
@@ -23,12 +29,21 @@ pub struct FontAtlasSet {
 pub struct GlyphAtlasInfo {
     pub texture_atlas: Handle<TextureAtlas>,
     pub glyph_index: usize,
+    /// Whether the glyph was drawn in color from the color tables of its font, like an emoji,
+    /// instead of in white to be tinted with the color of its text.
+    pub is_color: bool,
+    /// Whether the glyph is a multi-channel signed distance field, see
+    /// [`GlyphAtlasMode::Msdf`](crate::GlyphAtlasMode::Msdf).
+    pub is_msdf: bool,
 }
 
 impl Default for FontAtlasSet {
     fn default() -> Self {
         FontAtlasSet {
             font_atlases: HashMap::with_capacity_and_hasher(1, Default::default()),
+            color_glyphs: HashSet::default(),
+            effect_atlases: HashMap::default(),
+            msdf_atlases: Vec::new(),
             queue: Vec::new(),
         }
     }
@@ -59,52 +74,56 @@ impl FontAtlasSet {
         let glyph_id = glyph.id;
         let glyph_position = glyph.position;
         let font_size = glyph.scale.y;
-        let font_atlases = self
-            .font_atlases
-            .entry(FloatOrd(font_size))
-            .or_insert_with(|| {
-                vec![FontAtlas::new(
-                    textures,
-                    texture_atlases,
-                    Vec2::splat(512.0),
-                )]
-            });
-
         let glyph_texture = Font::get_outlined_glyph_texture(outlined_glyph);
-        let add_char_to_font_atlas = |atlas: &mut FontAtlas| -> bool {
-            atlas.add_glyph(
-                textures,
-                texture_atlases,
-                glyph_id,
-                glyph_position.into(),
-                &glyph_texture,
-            )
-        };
-        if !font_atlases.iter_mut().any(add_char_to_font_atlas) {
-            // Find the largest dimension of the glyph, either its width or its height
-            let glyph_max_size: u32 = glyph_texture
-                .texture_descriptor
-                .size
-                .height
-                .max(glyph_texture.texture_descriptor.size.width);
-            // Pick the higher  of 512 or the smallest power of 2 greater than glyph_max_size
-            let containing = (1u32 << (32 - glyph_max_size.leading_zeros())).max(512) as f32;
-            font_atlases.push(FontAtlas::new(
-                textures,
-                texture_atlases,
-                Vec2::new(containing, containing),
-            ));
-            if !font_atlases.last_mut().unwrap().add_glyph(
-                textures,
-                texture_atlases,
-                glyph_id,
-                glyph_position.into(),
-                &glyph_texture,
-            ) {
-                return Err(TextError::FailedToAddGlyph(glyph_id));
-            }
-        }
+        self.add_glyph_texture(
+            texture_atlases,
+            textures,
+            glyph_id,
+            glyph_position,
+            font_size,
+            &glyph_texture,
+        )
+    }
+
+    /// Adds the texture of a glyph drawn in color, like an emoji.
+    pub fn add_color_glyph_to_atlas(
+        &mut self,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        textures: &mut Assets<Image>,
+        glyph_id: GlyphId,
+        glyph_position: Point,
+        font_size: f32,
+        glyph_texture: &Image,
+    ) -> Result<GlyphAtlasInfo, TextError> {
+        self.color_glyphs.insert(glyph_id);
+        self.add_glyph_texture(
+            texture_atlases,
+            textures,
+            glyph_id,
+            glyph_position,
+            font_size,
+            glyph_texture,
+        )
+    }
 
+    fn add_glyph_texture(
+        &mut self,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        textures: &mut Assets<Image>,
+        glyph_id: GlyphId,
+        glyph_position: Point,
+        font_size: f32,
+        glyph_texture: &Image,
+    ) -> Result<GlyphAtlasInfo, TextError> {
+        let font_atlases = self.font_atlases.entry(FloatOrd(font_size)).or_default();
+        add_to_font_atlases(
+            font_atlases,
+            texture_atlases,
+            textures,
+            glyph_id,
+            glyph_position,
+            glyph_texture,
+        )?;
         Ok(self
             .get_glyph_atlas_info(font_size, glyph_id, glyph_position)
             .unwrap())
@@ -118,22 +137,162 @@ impl FontAtlasSet {
     ) -> Option<GlyphAtlasInfo> {
         self.font_atlases
             .get(&FloatOrd(font_size))
-            .and_then(|font_atlases| {
-                font_atlases
-                    .iter()
-                    .find_map(|atlas| {
-                        atlas
-                            .get_glyph_index(glyph_id, position.into())
-                            .map(|glyph_index| (glyph_index, atlas.texture_atlas.clone_weak()))
-                    })
-                    .map(|(glyph_index, texture_atlas)| GlyphAtlasInfo {
-                        texture_atlas,
-                        glyph_index,
-                    })
+            .and_then(|font_atlases| find_glyph(font_atlases, glyph_id, position))
+            .map(|(glyph_index, texture_atlas)| GlyphAtlasInfo {
+                texture_atlas,
+                glyph_index,
+                is_color: self.color_glyphs.contains(&glyph_id),
+                is_msdf: false,
+            })
+    }
+
+    /// Adds the white texture of the outline or of the shadow of a glyph, its coverage spread
+    /// by `spread` pixels then blurred by `blur` pixels.
+    #[allow(clippy::too_many_arguments)]
+    pub fn add_effect_glyph_to_atlas(
+        &mut self,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        textures: &mut Assets<Image>,
+        glyph_id: GlyphId,
+        glyph_position: Point,
+        font_size: f32,
+        spread: f32,
+        blur: f32,
+        glyph_texture: &Image,
+    ) -> Result<GlyphAtlasInfo, TextError> {
+        let font_atlases = self
+            .effect_atlases
+            .entry((FloatOrd(font_size), FloatOrd(spread), FloatOrd(blur)))
+            .or_default();
+        add_to_font_atlases(
+            font_atlases,
+            texture_atlases,
+            textures,
+            glyph_id,
+            glyph_position,
+            glyph_texture,
+        )?;
+        Ok(self
+            .get_effect_glyph_atlas_info(font_size, spread, blur, glyph_id, glyph_position)
+            .unwrap())
+    }
+
+    /// The texture of the outline or of the shadow of a glyph, see
+    /// [`FontAtlasSet::add_effect_glyph_to_atlas`].
+    pub fn get_effect_glyph_atlas_info(
+        &self,
+        font_size: f32,
+        spread: f32,
+        blur: f32,
+        glyph_id: GlyphId,
+        position: Point,
+    ) -> Option<GlyphAtlasInfo> {
+        self.effect_atlases
+            .get(&(FloatOrd(font_size), FloatOrd(spread), FloatOrd(blur)))
+            .and_then(|font_atlases| find_glyph(font_atlases, glyph_id, position))
+            .map(|(glyph_index, texture_atlas)| GlyphAtlasInfo {
+                texture_atlas,
+                glyph_index,
+                is_color: false,
+                is_msdf: false,
             })
     }
 
+    /// Adds the signed distance field of a glyph, generated at any font size.
+    pub fn add_msdf_glyph_to_atlas(
+        &mut self,
+        texture_atlases: &mut Assets<TextureAtlas>,
+        textures: &mut Assets<Image>,
+        glyph_id: GlyphId,
+        glyph_texture: &Image,
+    ) -> Result<GlyphAtlasInfo, TextError> {
+        add_to_font_atlases(
+            &mut self.msdf_atlases,
+            texture_atlases,
+            textures,
+            glyph_id,
+            Point::default(),
+            glyph_texture,
+        )?;
+        Ok(self.get_msdf_glyph_atlas_info(glyph_id).unwrap())
+    }
+
+    /// The signed distance field of a glyph, see [`FontAtlasSet::add_msdf_glyph_to_atlas`].
+    pub fn get_msdf_glyph_atlas_info(&self, glyph_id: GlyphId) -> Option<GlyphAtlasInfo> {
+        find_glyph(&self.msdf_atlases, glyph_id, Point::default()).map(
+            |(glyph_index, texture_atlas)| GlyphAtlasInfo {
+                texture_atlas,
+                glyph_index,
+                is_color: false,
+                is_msdf: true,
+            },
+        )
+    }
+
+    /// Whether the glyph was added in color, like an emoji, at any font size.
+    pub fn is_color_glyph(&self, glyph_id: GlyphId) -> bool {
+        self.color_glyphs.contains(&glyph_id)
+    }
+
     pub fn num_font_atlases(&self) -> usize {
         self.font_atlases.len()
     }
 }
+
+/// Adds a glyph to the first of the `font_atlases` with enough space for it, or to a new atlas.
+fn add_to_font_atlases(
+    font_atlases: &mut Vec<FontAtlas>,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    textures: &mut Assets<Image>,
+    glyph_id: GlyphId,
+    glyph_position: Point,
+    glyph_texture: &Image,
+) -> Result<(), TextError> {
+    let add_char_to_font_atlas = |atlas: &mut FontAtlas| -> bool {
+        atlas.add_glyph(
+            textures,
+            texture_atlases,
+            glyph_id,
+            glyph_position.into(),
+            glyph_texture,
+        )
+    };
+    if !font_atlases.iter_mut().any(add_char_to_font_atlas) {
+        // Find the largest dimension of the glyph, either its width or its height
+        let glyph_max_size: u32 = glyph_texture
+            .texture_descriptor
+            .size
+            .height
+            .max(glyph_texture.texture_descriptor.size.width);
+        // Pick the higher  of 512 or the smallest power of 2 greater than glyph_max_size
+        let containing = (1u32 << (32 - glyph_max_size.leading_zeros())).max(512) as f32;
+        font_atlases.push(FontAtlas::new_with_format(
+            textures,
+            texture_atlases,
+            Vec2::new(containing, containing),
+            glyph_texture.texture_descriptor.format,
+        ));
+        if !font_atlases.last_mut().unwrap().add_glyph(
+            textures,
+            texture_atlases,
+            glyph_id,
+            glyph_position.into(),
+            glyph_texture,
+        ) {
+            return Err(TextError::FailedToAddGlyph(glyph_id));
+        }
+    }
+    Ok(())
+}
+
+fn find_glyph(
+    font_atlases: &[FontAtlas],
+    glyph_id: GlyphId,
+    position: Point,
+) -> Option<(usize, Handle<TextureAtlas>)> {
+    font_atlases.iter().find_map(|atlas| {
+        atlas
+            .get_glyph_index(glyph_id, position.into())
+            .map(|glyph_index| (glyph_index, atlas.texture_atlas.clone_weak()))
+    })
+}
@@ -0,0 +1,219 @@
+use std::ops::{Range, RangeInclusive};
+
+use ab_glyph::Font as _;
+use bevy_asset::{Assets, Handle};
+use bevy_ecs::system::Resource;
+
+use crate::Font;
+
+/// A font drawing the characters missing from the font of a text section, see
+/// [`FontFallbacks`].
+#[derive(Debug, Clone)]
+pub struct FontFallback {
+    /// The font drawing the missing characters.
+    pub font: Handle<Font>,
+    /// The ranges of the characters drawn with this font, or all of them when empty, like the
+    /// characters of a script.
+    pub ranges: Vec<RangeInclusive<char>>,
+}
+
+impl FontFallback {
+    /// Whether this fallback is used for `c`, when its font has a glyph for it.
+    pub fn covers(&self, c: char) -> bool {
+        self.ranges.is_empty() || self.ranges.iter().any(|range| range.contains(&c))
+    }
+}
+
+/// The ordered fonts drawing the characters missing from the font of each [`TextSection`],
+/// instead of the "tofu" boxes of the missing glyphs.
+///
+/// Each character missing from a section is drawn with the first fallback that covers it and
+/// has a glyph for it. The fallback fonts that are not loaded yet are skipped, and are only used
+/// once the text is laid out again.
+///
+/// [`TextSection`]: crate::TextSection
+#[derive(Resource, Debug, Default, Clone)]
+pub struct FontFallbacks {
+    fallbacks: Vec<FontFallback>,
+}
+
+impl FontFallbacks {
+    /// The ranges of the Chinese, Japanese and Korean characters, and of their punctuation.
+    pub const CJK: &'static [RangeInclusive<char>] = &[
+        '\u{1100}'..='\u{11FF}',
+        '\u{2E80}'..='\u{2FDF}',
+        '\u{3000}'..='\u{9FFF}',
+        '\u{A960}'..='\u{A97F}',
+        '\u{AC00}'..='\u{D7FF}',
+        '\u{F900}'..='\u{FAFF}',
+        '\u{FE30}'..='\u{FE4F}',
+        '\u{FF00}'..='\u{FFEF}',
+        '\u{20000}'..='\u{3134F}',
+    ];
+
+    /// The ranges of the emojis and of the other pictographic symbols.
+    pub const SYMBOLS: &'static [RangeInclusive<char>] =
+        &['\u{2190}'..='\u{2BFF}', '\u{1F000}'..='\u{1FAFF}'];
+
+    /// Adds `font` after the other fallbacks, for all the characters.
+    pub fn add(&mut self, font: Handle<Font>) -> &mut Self {
+        self.fallbacks.push(FontFallback {
+            font,
+            ranges: Vec::new(),
+        });
+        self
+    }
+
+    /// Adds `font` after the other fallbacks, only for the characters in `ranges`.
+    pub fn add_for_ranges(
+        &mut self,
+        font: Handle<Font>,
+        ranges: impl IntoIterator<Item = RangeInclusive<char>>,
+    ) -> &mut Self {
+        self.fallbacks.push(FontFallback {
+            font,
+            ranges: ranges.into_iter().collect(),
+        });
+        self
+    }
+
+    /// Removes all the fallbacks.
+    pub fn clear(&mut self) {
+        self.fallbacks.clear();
+    }
+
+    /// The fallbacks, in the order they are tried in.
+    pub fn iter(&self) -> impl Iterator<Item = &FontFallback> {
+        self.fallbacks.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fallbacks.is_empty()
+    }
+
+    /// The fallbacks whose font is loaded, with their font.
+    pub(crate) fn loaded<'a>(
+        &'a self,
+        fonts: &'a Assets<Font>,
+    ) -> Vec<(&'a FontFallback, &'a Font)> {
+        self.fallbacks
+            .iter()
+            .filter_map(|fallback| Some((fallback, fonts.get(&fallback.font)?)))
+            .collect()
+    }
+}
+
+/// Splits `text` in the runs of characters drawn with the same font, as the index of their
+/// fallback in `fallbacks`, or `None` for `font`, and the byte range of the run.
+pub(crate) fn split_font_runs(
+    text: &str,
+    font: &Font,
+    fallbacks: &[(&FontFallback, &Font)],
+) -> Vec<(Option<usize>, Range<usize>)> {
+    let mut runs: Vec<(Option<usize>, Range<usize>)> = Vec::new();
+    for (byte_index, c) in text.char_indices() {
+        let has_glyph = |font: &Font| font.font.glyph_id(c).0 != 0;
+        let current = runs.last().and_then(|(run_font, _)| *run_font);
+        let current_font = current.map_or(font, |index| fallbacks[index].1);
+        // The line breaks and the spaces stay in the run they're in, to keep the runs long
+        let run_font = if c.is_control() || (c.is_whitespace() && has_glyph(current_font)) {
+            current
+        } else if has_glyph(font) {
+            None
+        } else {
+            fallbacks.iter().position(|(fallback, fallback_font)| {
+                fallback.covers(c) && has_glyph(fallback_font)
+            })
+        };
+        let end = byte_index + c.len_utf8();
+        match runs.last_mut() {
+            Some((last_font, range)) if *last_font == run_font => range.end = end,
+            _ => runs.push((run_font, byte_index..end)),
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_font;
+
+    fn fira_sans() -> Font {
+        Font::try_from_bytes(include_bytes!("../../../assets/fonts/FiraSans-Bold.ttf").to_vec())
+            .unwrap()
+    }
+
+    fn fallback(ranges: &[RangeInclusive<char>]) -> FontFallback {
+        FontFallback {
+            font: Handle::default(),
+            ranges: ranges.to_vec(),
+        }
+    }
+
+    #[test]
+    fn runs_of_the_fallbacks() {
+        // Fira Sans has no glyphs for the box drawing characters, which are drawn with the
+        // monospace font of the examples, and neither has glyphs for the CJK characters
+        let font = fira_sans();
+        let mono = test_font();
+        let all = fallback(&[]);
+        let cjk = fallback(FontFallbacks::CJK);
+        let cases: [(&str, &[&FontFallback], &[(Option<usize>, Range<usize>)]); 9] = [
+            ("", &[&all], &[]),
+            ("abc", &[&all], &[(None, 0..3)]),
+            ("ab┌┐cd", &[], &[(None, 0..10)]),
+            (
+                "ab┌┐cd",
+                &[&all],
+                &[(None, 0..2), (Some(0), 2..8), (None, 8..10)],
+            ),
+            // the characters are split on their boundaries, whatever their length in bytes
+            (
+                "é┌é",
+                &[&all],
+                &[(None, 0..2), (Some(0), 2..5), (None, 5..7)],
+            ),
+            // the fallbacks not covering a character are skipped
+            ("a┌", &[&cjk, &all], &[(None, 0..1), (Some(1), 1..4)]),
+            ("a┌", &[&all, &all], &[(None, 0..1), (Some(0), 1..4)]),
+            // a character no font has a glyph for is drawn with the font of the section
+            ("a中b", &[&all], &[(None, 0..5)]),
+            // the spaces and line breaks are kept in the run of the fallback, when it has a glyph
+            // for them
+            ("┌ ┐\n└", &[&all], &[(Some(0), 0..11)]),
+        ];
+        for (text, fallbacks, expected) in cases {
+            let fallbacks: Vec<(&FontFallback, &Font)> = fallbacks
+                .iter()
+                .map(|fallback| (*fallback, &mono))
+                .collect();
+            assert_eq!(
+                split_font_runs(text, &font, &fallbacks),
+                expected,
+                "{text:?} with {} fallbacks",
+                fallbacks.len()
+            );
+        }
+    }
+
+    #[test]
+    fn ranges_covered() {
+        let cases = [
+            (fallback(&[]), 'a', true),
+            (fallback(FontFallbacks::CJK), '中', true),
+            (fallback(FontFallbacks::CJK), 'a', false),
+            (fallback(FontFallbacks::SYMBOLS), '😀', true),
+            (fallback(&['a'..='c', 'x'..='z']), 'y', true),
+            (fallback(&['a'..='c', 'x'..='z']), 'd', false),
+        ];
+        for (fallback, c, covered) in cases {
+            assert_eq!(
+                fallback.covers(c),
+                covered,
+                "{c:?} in {:?}",
+                fallback.ranges
+            );
+        }
+    }
+}
@@ -1,7 +1,9 @@
-use ab_glyph::{Font as _, FontArc, Glyph, ScaleFont as _};
+use std::ops::Range;
+
+use ab_glyph::{Font as _, FontArc, Glyph, Rect, ScaleFont as _};
 use bevy_asset::{Assets, Handle};
 use bevy_math::Vec2;
-use bevy_render::texture::Image;
+use bevy_render::{color::Color, texture::Image};
 use bevy_sprite::TextureAtlas;
 use bevy_utils::tracing::warn;
 use glyph_brush_layout::{
@@ -10,8 +12,14 @@ use glyph_brush_layout::{
 };
 
 use crate::{
-    error::TextError, BreakLineOn, Font, FontAtlasSet, FontAtlasWarning, GlyphAtlasInfo,
-    TextAlignment, TextSettings, YAxisOrientation,
+    bidi,
+    color_glyph::ColorGlyph,
+    effects::{effect_padding, effect_texture},
+    error::TextError,
+    msdf::{msdf_bounds, msdf_texture},
+    overflow, shaping, BreakLineOn, Font, FontAtlasSet, FontAtlasWarning, GlyphAtlasInfo,
+    GlyphAtlasMode, GlyphLayer, TextAlignment, TextDirection, TextOutline, TextOverflow,
+    TextSettings, TextShadow, TextStyle, YAxisOrientation,
 };
 
 pub struct GlyphBrush {
@@ -37,26 +45,94 @@ impl GlyphBrush {
         bounds: Vec2,
         text_alignment: TextAlignment,
         linebreak_behaviour: BreakLineOn,
+        text_direction: TextDirection,
+        overflow: TextOverflow,
     ) -> Result<Vec<SectionGlyph>, TextError> {
+        self.compute_clustered_glyphs(
+            sections,
+            bounds,
+            text_alignment,
+            linebreak_behaviour,
+            text_direction,
+            overflow,
+        )
+        .map(|(glyphs, _)| glyphs)
+    }
+
+    /// Lays out the glyphs of `sections` like [`GlyphBrush::compute_glyphs`], with the byte
+    /// index of the first character of each cluster of glyphs of each section when the text is
+    /// shaped, see [`PositionedGlyph::byte_range`].
+    ///
+    /// The glyphs of a cluster all have the byte index of its first character.
+    pub(crate) fn compute_clustered_glyphs<S: ToSectionText>(
+        &self,
+        sections: &[S],
+        bounds: Vec2,
+        text_alignment: TextAlignment,
+        linebreak_behaviour: BreakLineOn,
+        text_direction: TextDirection,
+        overflow: TextOverflow,
+    ) -> Result<(Vec<SectionGlyph>, Vec<Vec<usize>>), TextError> {
+        // The lines that aren't wrapped are laid out without a width
+        let width = match linebreak_behaviour {
+            BreakLineOn::NoWrap => f32::INFINITY,
+            _ => bounds.x,
+        };
         let geom = SectionGeometry {
-            bounds: (bounds.x, bounds.y),
+            bounds: (width, bounds.y),
             ..Default::default()
         };
 
         let lbb: BuiltInLineBreaker = linebreak_behaviour.into();
 
-        let section_glyphs = Layout::default()
-            .h_align(text_alignment.into())
-            .line_breaker(lbb)
-            .calculate_glyphs(&self.fonts, &geom, sections);
-        Ok(section_glyphs)
+        let section_texts: Vec<_> = sections
+            .iter()
+            .map(|section| section.to_section_text())
+            .collect();
+        let shaped = section_texts
+            .iter()
+            .any(|section| shaping::needs_shaping(section.text))
+            .then(|| {
+                shaping::layout_shaped(
+                    &self.fonts,
+                    &section_texts,
+                    geom.bounds,
+                    text_alignment,
+                    lbb,
+                    text_direction,
+                )
+            })
+            .flatten();
+        let (mut section_glyphs, cluster_starts) = match shaped {
+            // The shaped clusters are already reordered
+            Some(shaped) => (shaped.glyphs, shaped.cluster_starts),
+            None => {
+                let mut section_glyphs = Layout::default()
+                    .h_align(text_alignment.into())
+                    .line_breaker(lbb)
+                    .calculate_glyphs(&self.fonts, &geom, sections);
+                bidi::reorder_glyphs(&mut section_glyphs, sections, text_direction, &self.fonts);
+                (section_glyphs, Vec::new())
+            }
+        };
+        overflow::truncate_lines(
+            &mut section_glyphs,
+            sections,
+            &self.fonts,
+            bounds.x,
+            text_alignment,
+            overflow,
+        );
+        Ok((section_glyphs, cluster_starts))
     }
 
     #[allow(clippy::too_many_arguments)]
     pub fn process_glyphs(
         &self,
         glyphs: Vec<SectionGlyph>,
+        cluster_starts: &[Vec<usize>],
         sections: &[SectionText],
+        section_effects: &[(Option<TextOutline>, Option<TextShadow>)],
         font_atlas_set_storage: &mut Assets<FontAtlasSet>,
         fonts: &Assets<Font>,
         texture_atlases: &mut Assets<TextureAtlas>,
@@ -64,6 +140,7 @@ impl GlyphBrush {
         text_settings: &TextSettings,
         font_atlas_warning: &mut FontAtlasWarning,
         y_axis_orientation: YAxisOrientation,
+        atlas_mode: GlyphAtlasMode,
     ) -> Result<Vec<PositionedGlyph>, TextError> {
         if glyphs.is_empty() {
             return Ok(Vec::new());
@@ -99,7 +176,11 @@ impl GlyphBrush {
         min_y = min_y.floor();
         max_y = max_y.floor();
 
+        // The shadows of all the glyphs are drawn below their outlines, themselves drawn below
+        // all the glyphs
         let mut positioned_glyphs = Vec::new();
+        let mut outline_glyphs = Vec::new();
+        let mut shadow_glyphs = Vec::new();
         for sg in glyphs {
             let SectionGlyph {
                 section_index: _,
@@ -109,50 +190,188 @@ impl GlyphBrush {
             } = sg;
             let glyph_id = glyph.id;
             let glyph_position = glyph.position;
+            // The glyphs of the shaped text draw the characters up to the next cluster
+            let byte_end = match cluster_starts.get(sg.section_index) {
+                Some(starts) => starts.iter().copied().find(|&start| start > byte_index),
+                None => sections[sg.section_index].text[byte_index..]
+                    .chars()
+                    .next()
+                    .map(|character| byte_index + character.len_utf8()),
+            };
+            let byte_range = byte_index..byte_end.unwrap_or(byte_index + 1);
             let adjust = GlyphPlacementAdjuster::new(&mut glyph);
+            let effect_glyph = glyph.clone();
             let section_data = sections_data[sg.section_index];
-            if let Some(outlined_glyph) = section_data.1.font.outline_glyph(glyph) {
-                let bounds = outlined_glyph.px_bounds();
-                let handle_font_atlas: Handle<FontAtlasSet> = section_data.0.cast_weak();
-                let font_atlas_set = font_atlas_set_storage
-                    .get_or_insert_with(handle_font_atlas, FontAtlasSet::default);
-
-                let atlas_info = font_atlas_set
-                    .get_glyph_atlas_info(section_data.2, glyph_id, glyph_position)
-                    .map(Ok)
-                    .unwrap_or_else(|| {
-                        font_atlas_set.add_glyph_to_atlas(texture_atlases, textures, outlined_glyph)
-                    })?;
-
-                if !text_settings.allow_dynamic_font_size
-                    && !font_atlas_warning.warned
-                    && font_atlas_set.num_font_atlases() > text_settings.max_font_atlases.get()
-                {
-                    warn!("warning[B0005]: Number of font atlases has exceeded the maximum of {}. Performance and memory usage may suffer.", text_settings.max_font_atlases.get());
-                    font_atlas_warning.warned = true;
+            let font = &section_data.1.font;
+            let font_size = section_data.2;
+            let handle_font_atlas: Handle<FontAtlasSet> = section_data.0.cast_weak();
+            let font_atlas_set =
+                font_atlas_set_storage.get_or_insert_with(handle_font_atlas, FontAtlasSet::default);
+
+            let msdf_glyph = match atlas_mode {
+                GlyphAtlasMode::Msdf => {
+                    msdf_glyph(font_atlas_set, font, &glyph, texture_atlases, textures)?
                 }
+                GlyphAtlasMode::Bitmap => None,
+            };
 
-                let texture_atlas = texture_atlases.get(&atlas_info.texture_atlas).unwrap();
-                let glyph_rect = texture_atlas.textures[atlas_info.glyph_index];
-                let size = Vec2::new(glyph_rect.width(), glyph_rect.height());
+            // The glyphs are drawn in color when their font has a color version of them
+            let (bounds, atlas_info) = if let Some(msdf_glyph) = msdf_glyph {
+                msdf_glyph
+            } else {
+                match font_atlas_set.get_glyph_atlas_info(font_size, glyph_id, glyph_position) {
+                    Some(atlas_info) => {
+                        let bounds = if atlas_info.is_color {
+                            ColorGlyph::new(font, &glyph).map(|color_glyph| color_glyph.px_bounds())
+                        } else {
+                            font.outline_glyph(glyph)
+                                .map(|outlined_glyph| outlined_glyph.px_bounds())
+                        };
+                        let Some(bounds) = bounds else {
+                            continue;
+                        };
+                        (bounds, atlas_info)
+                    }
+                    None => {
+                        let color_glyph = ColorGlyph::new(font, &glyph).and_then(|color_glyph| {
+                            let texture = color_glyph.texture()?;
+                            Some((color_glyph.px_bounds(), texture))
+                        });
+                        if let Some((bounds, texture)) = color_glyph {
+                            let atlas_info = font_atlas_set.add_color_glyph_to_atlas(
+                                texture_atlases,
+                                textures,
+                                glyph_id,
+                                glyph_position,
+                                font_size,
+                                &texture,
+                            )?;
+                            (bounds, atlas_info)
+                        } else if let Some(outlined_glyph) = font.outline_glyph(glyph) {
+                            let bounds = outlined_glyph.px_bounds();
+                            let atlas_info = font_atlas_set.add_glyph_to_atlas(
+                                texture_atlases,
+                                textures,
+                                outlined_glyph,
+                            )?;
+                            (bounds, atlas_info)
+                        } else {
+                            continue;
+                        }
+                    }
+                }
+            };
+
+            if !text_settings.allow_dynamic_font_size
+                && !font_atlas_warning.warned
+                && font_atlas_set.num_font_atlases() > text_settings.max_font_atlases.get()
+            {
+                warn!("warning[B0005]: Number of font atlases has exceeded the maximum of {}. Performance and memory usage may suffer.", text_settings.max_font_atlases.get());
+                font_atlas_warning.warned = true;
+            }
 
-                let x = bounds.min.x + size.x / 2.0 - min_x;
+            let place = |bounds: Rect, size: Vec2, offset: Vec2| {
+                let x = bounds.min.x + size.x / 2.0 - min_x + offset.x;
 
                 let y = match y_axis_orientation {
-                    YAxisOrientation::BottomToTop => max_y - bounds.max.y + size.y / 2.0,
-                    YAxisOrientation::TopToBottom => bounds.min.y + size.y / 2.0 - min_y,
+                    YAxisOrientation::BottomToTop => max_y - bounds.max.y + size.y / 2.0 - offset.y,
+                    YAxisOrientation::TopToBottom => bounds.min.y + size.y / 2.0 - min_y + offset.y,
                 };
 
-                let position = adjust.position(Vec2::new(x, y));
+                adjust.position(Vec2::new(x, y))
+            };
 
-                positioned_glyphs.push(PositionedGlyph {
+            let (outline, shadow) = section_effects[sg.section_index];
+            let spread = outline.map_or(0.0, |outline| outline.width);
+            let effects = [
+                outline.map(|_| (GlyphLayer::Outline, 0.0, Vec2::ZERO)),
+                shadow.map(|shadow| (GlyphLayer::Shadow, shadow.blur, shadow.offset)),
+            ];
+            for (layer, blur, offset) in effects.into_iter().flatten() {
+                let effect_info = match font_atlas_set.get_effect_glyph_atlas_info(
+                    font_size,
+                    spread,
+                    blur,
+                    glyph_id,
+                    glyph_position,
+                ) {
+                    Some(effect_info) => effect_info,
+                    None => {
+                        let Some((coverage, width, height)) =
+                            glyph_coverage(font, effect_glyph.clone(), atlas_info.is_color)
+                        else {
+                            continue;
+                        };
+                        font_atlas_set.add_effect_glyph_to_atlas(
+                            texture_atlases,
+                            textures,
+                            glyph_id,
+                            glyph_position,
+                            font_size,
+                            spread,
+                            blur,
+                            &effect_texture(&coverage, width, height, spread, blur),
+                        )?
+                    }
+                };
+                // The signed distance fields are larger than the outlined glyphs
+                let glyph_bounds = if atlas_info.is_msdf {
+                    let Some(outlined_glyph) = font.outline_glyph(effect_glyph.clone()) else {
+                        continue;
+                    };
+                    outlined_glyph.px_bounds()
+                } else {
+                    bounds
+                };
+                let padding = effect_padding(spread, blur) as f32;
+                let effect_bounds = Rect {
+                    min: ab_glyph::point(
+                        glyph_bounds.min.x - padding,
+                        glyph_bounds.min.y - padding,
+                    ),
+                    max: ab_glyph::point(
+                        glyph_bounds.max.x + padding,
+                        glyph_bounds.max.y + padding,
+                    ),
+                };
+                let size = glyph_size(texture_atlases, &effect_info);
+                let position = place(effect_bounds, size, offset);
+                let effect_glyph = PositionedGlyph {
                     position,
                     size,
-                    atlas_info,
+                    atlas_info: effect_info,
                     section_index: sg.section_index,
                     byte_index,
-                });
+                    byte_range: byte_range.clone(),
+                    layer,
+                };
+                match layer {
+                    GlyphLayer::Shadow => shadow_glyphs.push(effect_glyph),
+                    _ => outline_glyphs.push(effect_glyph),
+                }
             }
+
+            // The signed distance fields are scaled to the font size
+            let size = if atlas_info.is_msdf {
+                Vec2::new(bounds.width(), bounds.height())
+            } else {
+                glyph_size(texture_atlases, &atlas_info)
+            };
+            let position = place(bounds, size, Vec2::ZERO);
+            positioned_glyphs.push(PositionedGlyph {
+                position,
+                size,
+                atlas_info,
+                section_index: sg.section_index,
+                byte_index,
+                byte_range,
+                layer: GlyphLayer::Fill,
+            });
+        }
+        if !shadow_glyphs.is_empty() || !outline_glyphs.is_empty() {
+            shadow_glyphs.append(&mut outline_glyphs);
+            shadow_glyphs.append(&mut positioned_glyphs);
+            positioned_glyphs = shadow_glyphs;
         }
         Ok(positioned_glyphs)
     }
@@ -166,13 +385,98 @@ impl GlyphBrush {
     }
 }
 
+/// A glyph laid out in a [`TextLayoutInfo`](crate::TextLayoutInfo).
 #[derive(Debug, Clone)]
 pub struct PositionedGlyph {
+    /// The center of the glyph, in physical pixels from the corner of the text.
     pub position: Vec2,
+    /// The size of the glyph, in physical pixels.
     pub size: Vec2,
     pub atlas_info: GlyphAtlasInfo,
+    /// The index of the [`TextSection`](crate::TextSection) of the glyph.
     pub section_index: usize,
+    /// The index of the first byte of the characters of the glyph in the text of its section.
     pub byte_index: usize,
+    /// The bytes of the characters drawn by the glyph in the text of its section.
+    ///
+    /// The shaped text can draw several characters with a glyph, like a ligature, or a
+    /// character with several glyphs, like a letter and its marks, the glyphs of the cluster
+    /// then all having the bytes of all its characters.
+    pub byte_range: Range<usize>,
+    /// Whether this draws the glyph, or its outline or shadow.
+    pub layer: GlyphLayer,
+}
+
+impl PositionedGlyph {
+    /// The color to draw the glyph with, from the `style` of its section.
+    ///
+    /// The color glyphs keep their own colors, and only take the alpha of the text.
+    pub fn color(&self, style: &TextStyle) -> Color {
+        match self.layer {
+            GlyphLayer::Fill if self.atlas_info.is_color => Color::WHITE.with_a(style.color.a()),
+            GlyphLayer::Fill => style.color,
+            GlyphLayer::Outline => style.outline.map_or(Color::NONE, |outline| outline.color),
+            GlyphLayer::Shadow => style.shadow.map_or(Color::NONE, |shadow| shadow.color),
+        }
+    }
+}
+
+/// The signed distance field of `glyph` and its bounds, added to the `font_atlas_set` the first
+/// time it's drawn, or `None` if the glyph is drawn in color or has no outline.
+fn msdf_glyph(
+    font_atlas_set: &mut FontAtlasSet,
+    font: &FontArc,
+    glyph: &Glyph,
+    texture_atlases: &mut Assets<TextureAtlas>,
+    textures: &mut Assets<Image>,
+) -> Result<Option<(Rect, GlyphAtlasInfo)>, TextError> {
+    let atlas_info = match font_atlas_set.get_msdf_glyph_atlas_info(glyph.id) {
+        Some(atlas_info) => atlas_info,
+        None => {
+            if font_atlas_set.is_color_glyph(glyph.id) || ColorGlyph::new(font, glyph).is_some() {
+                return Ok(None);
+            }
+            let Some(texture) = msdf_texture(font, glyph.id) else {
+                return Ok(None);
+            };
+            font_atlas_set.add_msdf_glyph_to_atlas(texture_atlases, textures, glyph.id, &texture)?
+        }
+    };
+    Ok(msdf_bounds(font, glyph).map(|bounds| (bounds, atlas_info)))
+}
+
+/// The size of the texture of a glyph in its atlas.
+fn glyph_size(texture_atlases: &Assets<TextureAtlas>, atlas_info: &GlyphAtlasInfo) -> Vec2 {
+    let texture_atlas = texture_atlases.get(&atlas_info.texture_atlas).unwrap();
+    let glyph_rect = texture_atlas.textures[atlas_info.glyph_index];
+    Vec2::new(glyph_rect.width(), glyph_rect.height())
+}
+
+/// The coverage of the pixels of `glyph`, with the width and height of its pixel bounds.
+fn glyph_coverage(
+    font: &FontArc,
+    glyph: Glyph,
+    is_color: bool,
+) -> Option<(Vec<f32>, usize, usize)> {
+    if is_color {
+        let texture = ColorGlyph::new(font, &glyph)?.texture()?;
+        let size = texture.texture_descriptor.size;
+        let coverage = texture
+            .data
+            .chunks_exact(4)
+            .map(|pixel| pixel[3] as f32 / 255.0)
+            .collect();
+        return Some((coverage, size.width as usize, size.height as usize));
+    }
+    let outlined_glyph = font.outline_glyph(glyph)?;
+    let bounds = outlined_glyph.px_bounds();
+    let width = bounds.width() as usize;
+    let height = bounds.height() as usize;
+    let mut coverage = vec![0.0; width * height];
+    outlined_glyph.draw(|x, y, v| {
+        coverage[y as usize * width + x as usize] = v;
+    });
+    Some((coverage, width, height))
 }
 
 #[cfg(feature = "subpixel_glyph_atlas")]
@@ -1,26 +1,49 @@
+mod bidi;
+mod color_glyph;
+mod effects;
 mod error;
 mod font;
 mod font_atlas;
 mod font_atlas_set;
+mod font_fallback;
 mod font_loader;
 mod glyph_brush;
+mod markup;
+mod measure;
+mod msdf;
+mod overflow;
 mod pipeline;
+mod shaping;
+mod spacing;
+#[cfg(feature = "system_fonts")]
+mod system_font;
 mod text;
 mod text2d;
+mod text3d;
 
+pub use effects::*;
 pub use error::*;
 pub use font::*;
 pub use font_atlas::*;
 pub use font_atlas_set::*;
+pub use font_fallback::*;
 pub use font_loader::*;
 pub use glyph_brush::*;
+pub use markup::*;
+pub use measure::*;
+pub use msdf::*;
 pub use pipeline::*;
 pub use text::*;
 pub use text2d::*;
+pub use text3d::*;
 
 pub mod prelude {
     #[doc(hidden)]
-    pub use crate::{Font, Text, Text2dBundle, TextAlignment, TextError, TextSection, TextStyle};
+    pub use crate::{
+        Billboard, Font, FontFallbacks, GlyphAtlasMode, Text, Text2dBundle, Text3d, Text3dBundle,
+        TextAlignment, TextBackground, TextDirection, TextError, TextLayoutInfo, TextMarkupStyle,
+        TextMeasurer, TextOutline, TextSection, TextShadow, TextSpans, TextStyle,
+    };
 }
 
 use bevy_app::prelude::*;
@@ -73,12 +96,20 @@ impl Plugin for TextPlugin {
             .register_type::<TextSection>()
             .register_type::<Vec<TextSection>>()
             .register_type::<TextStyle>()
+            .register_type::<TextOutline>()
+            .register_type::<TextShadow>()
+            .register_type::<TextBackground>()
             .register_type::<Text>()
             .register_type::<TextAlignment>()
+            .register_type::<TextDirection>()
+            .register_type::<TextOverflow>()
+            .register_type::<TextSpans>()
             .init_asset_loader::<FontLoader>()
             .init_resource::<TextSettings>()
+            .init_resource::<FontFallbacks>()
             .init_resource::<FontAtlasWarning>()
             .insert_resource(TextPipeline::default())
+            .add_plugin(Text3dPlugin)
             .add_system(
                 update_text2d_layout
                     .in_base_set(CoreSet::PostUpdate)
@@ -0,0 +1,585 @@
+use std::ops::Range;
+
+use bevy_asset::Handle;
+use bevy_ecs::{prelude::Component, reflect::ReflectComponent};
+use bevy_reflect::{prelude::*, FromReflect};
+use bevy_render::color::Color;
+use bevy_utils::HashMap;
+
+use crate::{Font, MarkupError, Text, TextSection, TextStyle};
+
+/// The styles of the text parsed by [`Text::from_markup`].
+///
+/// The text starts with the [`style`](Self::style), and the `[b]` and `[i]` tags swap its font
+/// for the bold and italic fonts. The tags are ignored when the font is not set.
+#[derive(Debug, Default, Clone)]
+pub struct TextMarkupStyle {
+    /// The style of the text outside of any tag.
+    pub style: TextStyle,
+    /// The font of the text in `[b]` tags.
+    pub bold_font: Option<Handle<Font>>,
+    /// The font of the text in `[i]` tags.
+    pub italic_font: Option<Handle<Font>>,
+    /// The font of the text in both `[b]` and `[i]` tags, instead of the bold font.
+    pub bold_italic_font: Option<Handle<Font>>,
+}
+
+impl TextMarkupStyle {
+    /// Creates a [`TextMarkupStyle`] with the same font for all the text.
+    pub fn new(style: TextStyle) -> Self {
+        Self {
+            style,
+            ..Default::default()
+        }
+    }
+
+    /// Returns this [`TextMarkupStyle`] with its bold font set to `font`.
+    pub fn with_bold_font(mut self, font: Handle<Font>) -> Self {
+        self.bold_font = Some(font);
+        self
+    }
+
+    /// Returns this [`TextMarkupStyle`] with its italic font set to `font`.
+    pub fn with_italic_font(mut self, font: Handle<Font>) -> Self {
+        self.italic_font = Some(font);
+        self
+    }
+
+    /// Returns this [`TextMarkupStyle`] with its bold italic font set to `font`.
+    pub fn with_bold_italic_font(mut self, font: Handle<Font>) -> Self {
+        self.bold_italic_font = Some(font);
+        self
+    }
+
+    fn font(&self, bold: bool, italic: bool) -> &Handle<Font> {
+        let font = match (bold, italic) {
+            (true, true) => self
+                .bold_italic_font
+                .as_ref()
+                .or(self.bold_font.as_ref())
+                .or(self.italic_font.as_ref()),
+            (true, false) => self.bold_font.as_ref(),
+            (false, true) => self.italic_font.as_ref(),
+            (false, false) => None,
+        };
+        font.unwrap_or(&self.style.font)
+    }
+}
+
+/// The sections of each named span of a [`Text`], parsed from the `[span=name]` tags of its
+/// markup by [`Text::from_markup`].
+///
+/// The spans are updated in place, without parsing the markup again nor rebuilding the other
+/// sections of the text.
+#[derive(Component, Debug, Default, Clone, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct TextSpans {
+    spans: HashMap<String, Range<usize>>,
+}
+
+impl TextSpans {
+    /// The range of the sections of the `name` span in [`Text::sections`].
+    ///
+    /// A span has at least one section, even when it's empty, and more than one when the style
+    /// changes inside of it.
+    pub fn sections(&self, name: &str) -> Option<Range<usize>> {
+        self.spans.get(name).cloned()
+    }
+
+    /// The sections of the `name` span of `text`.
+    pub fn get_mut<'a>(&self, text: &'a mut Text, name: &str) -> Option<&'a mut [TextSection]> {
+        let range = self.spans.get(name)?;
+        text.sections.get_mut(range.clone())
+    }
+
+    /// Sets the value of the `name` span of `text`, in its first section.
+    ///
+    /// The other sections of the span are cleared, and so lose the value they had with a
+    /// different style. Returns `false` if `text` has no such span.
+    pub fn set_value(&self, text: &mut Text, name: &str, value: &str) -> bool {
+        let Some((first, others)) = self
+            .get_mut(text, name)
+            .and_then(|sections| sections.split_first_mut())
+        else {
+            return false;
+        };
+        // Reuses the allocations of the sections
+        first.value.clear();
+        first.value.push_str(value);
+        for section in others {
+            section.value.clear();
+        }
+        true
+    }
+
+    /// Sets the color of all the sections of the `name` span of `text`.
+    ///
+    /// Returns `false` if `text` has no such span.
+    pub fn set_color(&self, text: &mut Text, name: &str, color: Color) -> bool {
+        let Some(sections) = self.get_mut(text, name) else {
+            return false;
+        };
+        for section in sections {
+            section.style.color = color;
+        }
+        true
+    }
+
+    /// The names of the spans, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.spans.keys().map(String::as_str)
+    }
+}
+
+/// A tag opened in the markup.
+enum Tag {
+    Bold,
+    Italic,
+    Color(Color),
+    Size(f32),
+    Span { name: String, start: usize },
+}
+
+impl Tag {
+    fn name(&self) -> &'static str {
+        match self {
+            Tag::Bold => "b",
+            Tag::Italic => "i",
+            Tag::Color(_) => "color",
+            Tag::Size(_) => "size",
+            Tag::Span { .. } => "span",
+        }
+    }
+}
+
+impl Text {
+    /// Constructs a [`Text`] from a string with inline formatting tags, returned along with its
+    /// named [`TextSpans`].
+    ///
+    /// The text is split in a [`TextSection`] each time its style changes, with the tags:
+    /// - `[b]bold[/b]` and `[i]italic[/i]`, drawn with the fonts of the [`TextMarkupStyle`].
+    /// - `[color=#ff8000]colored[/color]`, with a hex color or the name of a basic color like
+    ///   `red`.
+    /// - `[size=30]resized[/size]`, with a font size.
+    /// - `[span=name]named[/span]`, a span that can be updated later through the [`TextSpans`].
+    ///
+    /// The tags can be nested, and are closed in the reverse order they were opened in. `[[` is
+    /// replaced by a single `[` that doesn't start a tag.
+    ///
+    /// ```
+    /// # use bevy_asset::Handle;
+    /// # use bevy_text::{Font, Text, TextMarkupStyle, TextStyle};
+    /// #
+    /// # let font: Handle<Font> = Default::default();
+    /// # let bold_font: Handle<Font> = Default::default();
+    /// #
+    /// let style = TextMarkupStyle::new(TextStyle {
+    ///     font,
+    ///     font_size: 30.0,
+    ///     ..Default::default()
+    /// })
+    /// .with_bold_font(bold_font);
+    /// let (mut text, spans) = Text::from_markup(
+    ///     "[b]Health:[/b] [color=red][span=health]100[/span][/color]",
+    ///     &style,
+    /// )
+    /// .unwrap();
+    /// assert_eq!(text.sections.len(), 3);
+    ///
+    /// spans.set_value(&mut text, "health", "42");
+    /// assert_eq!(text.sections[2].value, "42");
+    /// ```
+    pub fn from_markup(
+        markup: &str,
+        style: &TextMarkupStyle,
+    ) -> Result<(Text, TextSpans), MarkupError> {
+        let mut sections: Vec<TextSection> = Vec::new();
+        let mut spans = TextSpans::default();
+        let mut tags: Vec<Tag> = Vec::new();
+        let mut value = String::new();
+
+        let mut rest = markup;
+        while let Some(tag_start) = rest.find('[') {
+            value.push_str(&rest[..tag_start]);
+            rest = &rest[tag_start + 1..];
+            if let Some(escaped) = rest.strip_prefix('[') {
+                value.push('[');
+                rest = escaped;
+                continue;
+            }
+            let Some(tag_end) = rest.find(']') else {
+                return Err(MarkupError::UnterminatedTag);
+            };
+            let tag = &rest[..tag_end];
+            rest = &rest[tag_end + 1..];
+
+            // The text before the tag keeps the style of the tags opened before it
+            if !value.is_empty() {
+                sections.push(TextSection::new(
+                    std::mem::take(&mut value),
+                    current_style(style, &tags),
+                ));
+            }
+
+            if let Some(name) = tag.strip_prefix('/') {
+                let open = tags
+                    .pop()
+                    .ok_or_else(|| MarkupError::UnexpectedClosingTag(name.to_string()))?;
+                if open.name() != name {
+                    return Err(MarkupError::UnexpectedClosingTag(name.to_string()));
+                }
+                if let Tag::Span { name, start } = open {
+                    // Gives the empty spans a section to be updated later
+                    if start == sections.len() {
+                        sections.push(TextSection::from_style(current_style(style, &tags)));
+                    }
+                    spans.spans.insert(name, start..sections.len());
+                }
+                continue;
+            }
+
+            let (name, argument) = match tag.split_once('=') {
+                Some((name, argument)) => (name, Some(argument)),
+                None => (tag, None),
+            };
+            let argument =
+                || argument.ok_or_else(|| MarkupError::MissingArgument(name.to_string()));
+            tags.push(match name {
+                "b" => Tag::Bold,
+                "i" => Tag::Italic,
+                "color" => {
+                    let argument = argument()?;
+                    Tag::Color(
+                        parse_color(argument)
+                            .ok_or_else(|| MarkupError::InvalidColor(argument.to_string()))?,
+                    )
+                }
+                "size" => {
+                    let argument = argument()?;
+                    Tag::Size(
+                        argument
+                            .parse()
+                            .map_err(|_| MarkupError::InvalidSize(argument.to_string()))?,
+                    )
+                }
+                "span" => {
+                    let name = argument()?;
+                    let opened = tags
+                        .iter()
+                        .any(|tag| matches!(tag, Tag::Span { name: open, .. } if open == name));
+                    if opened || spans.spans.contains_key(name) {
+                        return Err(MarkupError::DuplicateSpan(name.to_string()));
+                    }
+                    Tag::Span {
+                        name: name.to_string(),
+                        start: sections.len(),
+                    }
+                }
+                _ => return Err(MarkupError::UnknownTag(name.to_string())),
+            });
+        }
+        value.push_str(rest);
+
+        if let Some(tag) = tags.last() {
+            return Err(MarkupError::UnclosedTag(tag.name().to_string()));
+        }
+        if !value.is_empty() {
+            sections.push(TextSection::new(value, style.style.clone()));
+        }
+        Ok((Text::from_sections(sections), spans))
+    }
+}
+
+/// The style of the text inside of the opened `tags`.
+fn current_style(style: &TextMarkupStyle, tags: &[Tag]) -> TextStyle {
+    let mut bold = false;
+    let mut italic = false;
+    let mut text_style = style.style.clone();
+    for tag in tags {
+        match tag {
+            Tag::Bold => bold = true,
+            Tag::Italic => italic = true,
+            Tag::Color(color) => text_style.color = *color,
+            Tag::Size(size) => text_style.font_size = *size,
+            Tag::Span { .. } => {}
+        }
+    }
+    text_style.font = style.font(bold, italic).clone();
+    text_style
+}
+
+fn parse_color(color: &str) -> Option<Color> {
+    Some(match color {
+        "white" => Color::WHITE,
+        "black" => Color::BLACK,
+        "gray" | "grey" => Color::GRAY,
+        "red" => Color::RED,
+        "green" => Color::GREEN,
+        "blue" => Color::BLUE,
+        "yellow" => Color::YELLOW,
+        "cyan" => Color::CYAN,
+        "orange" => Color::ORANGE,
+        "pink" => Color::PINK,
+        "purple" => Color::PURPLE,
+        _ => return Color::hex(color.strip_prefix('#')?).ok(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_asset::HandleId;
+
+    const REGULAR: usize = 0;
+    const BOLD: usize = 1;
+    const ITALIC: usize = 2;
+    const BOLD_ITALIC: usize = 3;
+
+    fn fonts() -> [Handle<Font>; 4] {
+        [(); 4].map(|_| Handle::weak(HandleId::random::<Font>()))
+    }
+
+    fn markup_style(fonts: &[Handle<Font>; 4]) -> TextMarkupStyle {
+        TextMarkupStyle::new(TextStyle {
+            font: fonts[REGULAR].clone(),
+            font_size: 20.0,
+            color: Color::WHITE,
+            ..Default::default()
+        })
+        .with_bold_font(fonts[BOLD].clone())
+        .with_italic_font(fonts[ITALIC].clone())
+        .with_bold_italic_font(fonts[BOLD_ITALIC].clone())
+    }
+
+    /// The value, font, color and size of each section of `text`
+    fn sections(text: &Text, fonts: &[Handle<Font>; 4]) -> Vec<(String, usize, Color, f32)> {
+        text.sections
+            .iter()
+            .map(|section| {
+                let font = fonts
+                    .iter()
+                    .position(|font| *font == section.style.font)
+                    .unwrap();
+                (
+                    section.value.clone(),
+                    font,
+                    section.style.color,
+                    section.style.font_size,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn markup_sections() {
+        let white = Color::WHITE;
+        let cases: &[(&str, &[(&str, usize, Color, f32)])] = &[
+            ("", &[]),
+            ("plain text", &[("plain text", REGULAR, white, 20.0)]),
+            ("[[b]", &[("[b]", REGULAR, white, 20.0)]),
+            (
+                "a [[[b]b[/b]",
+                &[("a [", REGULAR, white, 20.0), ("b", BOLD, white, 20.0)],
+            ),
+            ("[[]]", &[("[]]", REGULAR, white, 20.0)]),
+            (
+                "a [b]b[/b] c",
+                &[
+                    ("a ", REGULAR, white, 20.0),
+                    ("b", BOLD, white, 20.0),
+                    (" c", REGULAR, white, 20.0),
+                ],
+            ),
+            (
+                "[b]a[i]b[/i]c[/b][i]d[/i]",
+                &[
+                    ("a", BOLD, white, 20.0),
+                    ("b", BOLD_ITALIC, white, 20.0),
+                    ("c", BOLD, white, 20.0),
+                    ("d", ITALIC, white, 20.0),
+                ],
+            ),
+            (
+                "[color=red]a[size=30.5]b[color=#0000ff]c[/color][/size][/color]",
+                &[
+                    ("a", REGULAR, Color::RED, 20.0),
+                    ("b", REGULAR, Color::RED, 30.5),
+                    ("c", REGULAR, Color::BLUE, 30.5),
+                ],
+            ),
+            (
+                "[color=grey]a[/color]",
+                &[("a", REGULAR, Color::GRAY, 20.0)],
+            ),
+            // the tags without text don't make sections
+            ("[b][/b]a[i][/i]", &[("a", REGULAR, white, 20.0)]),
+        ];
+        let fonts = fonts();
+        let style = markup_style(&fonts);
+        for (markup, expected) in cases {
+            let (text, _) = Text::from_markup(markup, &style).unwrap();
+            let expected = expected
+                .iter()
+                .map(|(value, font, color, size)| (value.to_string(), *font, *color, *size))
+                .collect::<Vec<_>>();
+            assert_eq!(sections(&text, &fonts), expected, "{markup:?}");
+        }
+    }
+
+    #[test]
+    fn markup_fonts_fall_back() {
+        let fonts = fonts();
+        let markup = "[b]a[/b][i]b[/i][b][i]c[/i][/b]";
+        // (fonts set, fonts of the bold, italic and bold italic sections)
+        let cases = [
+            ((false, false, false), [REGULAR, REGULAR, REGULAR]),
+            ((true, false, false), [BOLD, REGULAR, BOLD]),
+            ((false, true, false), [REGULAR, ITALIC, ITALIC]),
+            ((true, true, false), [BOLD, ITALIC, BOLD]),
+            ((false, false, true), [REGULAR, REGULAR, BOLD_ITALIC]),
+        ];
+        for ((bold, italic, bold_italic), expected) in cases {
+            let style = TextMarkupStyle {
+                style: TextStyle {
+                    font: fonts[REGULAR].clone(),
+                    ..Default::default()
+                },
+                bold_font: bold.then(|| fonts[BOLD].clone()),
+                italic_font: italic.then(|| fonts[ITALIC].clone()),
+                bold_italic_font: bold_italic.then(|| fonts[BOLD_ITALIC].clone()),
+            };
+            let (text, _) = Text::from_markup(markup, &style).unwrap();
+            let used = sections(&text, &fonts)
+                .into_iter()
+                .map(|(_, font, ..)| font)
+                .collect::<Vec<_>>();
+            assert_eq!(
+                used, expected,
+                "bold {bold}, italic {italic}, bold italic {bold_italic}"
+            );
+        }
+    }
+
+    #[test]
+    fn markup_errors() {
+        let cases = [
+            ("[u]a[/u]", MarkupError::UnknownTag("u".to_string())),
+            ("[B]a[/B]", MarkupError::UnknownTag("B".to_string())),
+            (
+                "[color]a[/color]",
+                MarkupError::MissingArgument("color".to_string()),
+            ),
+            (
+                "[size]a[/size]",
+                MarkupError::MissingArgument("size".to_string()),
+            ),
+            (
+                "[span]a[/span]",
+                MarkupError::MissingArgument("span".to_string()),
+            ),
+            (
+                "[color=redish]",
+                MarkupError::InvalidColor("redish".to_string()),
+            ),
+            (
+                "[color=#12345]",
+                MarkupError::InvalidColor("#12345".to_string()),
+            ),
+            // the hex colors start with a `#`
+            (
+                "[color=00ff00]",
+                MarkupError::InvalidColor("00ff00".to_string()),
+            ),
+            ("[size=big]", MarkupError::InvalidSize("big".to_string())),
+            (
+                "[span=a][/span][span=a][/span]",
+                MarkupError::DuplicateSpan("a".to_string()),
+            ),
+            (
+                "[span=a][span=a][/span][/span]",
+                MarkupError::DuplicateSpan("a".to_string()),
+            ),
+            ("a[/b]", MarkupError::UnexpectedClosingTag("b".to_string())),
+            (
+                "[b][i]a[/b][/i]",
+                MarkupError::UnexpectedClosingTag("b".to_string()),
+            ),
+            ("[b]a", MarkupError::UnclosedTag("b".to_string())),
+            (
+                "[b][span=a]a[/span]",
+                MarkupError::UnclosedTag("b".to_string()),
+            ),
+            ("a [b", MarkupError::UnterminatedTag),
+            ("[", MarkupError::UnterminatedTag),
+        ];
+        let style = markup_style(&fonts());
+        for (markup, expected) in cases {
+            assert_eq!(
+                Text::from_markup(markup, &style).unwrap_err(),
+                expected,
+                "{markup:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn markup_spans() {
+        // (markup, span, range of its sections)
+        let cases = [
+            ("[span=a]x[/span]", "a", 0..1),
+            ("x [span=a]y[/span] z", "a", 1..2),
+            ("[span=a]x[b]y[/b]z[/span]", "a", 0..3),
+            ("[span=a][span=b]x[/span]y[/span]", "b", 0..1),
+            ("[span=a][span=b]x[/span]y[/span]", "a", 0..2),
+            // the empty spans have a section
+            ("[span=a][/span]", "a", 0..1),
+            ("x[span=a][/span]y", "a", 1..2),
+            ("[span=a][span=b][/span][/span]", "a", 0..1),
+        ];
+        let style = markup_style(&fonts());
+        for (markup, name, expected) in cases {
+            let (_, spans) = Text::from_markup(markup, &style).unwrap();
+            assert_eq!(spans.sections(name), Some(expected), "{name} in {markup:?}");
+            assert_eq!(spans.sections("c"), None);
+        }
+    }
+
+    #[test]
+    fn empty_span_section_keeps_style() {
+        let fonts = fonts();
+        let style = markup_style(&fonts);
+        let (mut text, spans) =
+            Text::from_markup("a[color=red][b][span=s][/span][/b][/color]", &style).unwrap();
+        assert_eq!(
+            sections(&text, &fonts),
+            [
+                ("a".to_string(), REGULAR, Color::WHITE, 20.0),
+                (String::new(), BOLD, Color::RED, 20.0)
+            ]
+        );
+
+        assert!(spans.set_value(&mut text, "s", "42"));
+        assert_eq!(text.sections[1].value, "42");
+        assert!(!spans.set_value(&mut text, "t", "42"));
+    }
+
+    #[test]
+    fn span_updates_keep_other_sections() {
+        let style = markup_style(&fonts());
+        let (mut text, spans) =
+            Text::from_markup("hp: [span=hp]1[b]00[/b][/span]!", &style).unwrap();
+
+        assert!(spans.set_value(&mut text, "hp", "42"));
+        let values = text.sections.iter().map(|section| section.value.as_str());
+        assert_eq!(values.collect::<Vec<_>>(), ["hp: ", "42", "", "!"]);
+
+        assert!(spans.set_color(&mut text, "hp", Color::RED));
+        let colors = text.sections.iter().map(|section| section.style.color);
+        assert_eq!(
+            colors.collect::<Vec<_>>(),
+            [Color::WHITE, Color::RED, Color::RED, Color::WHITE]
+        );
+        assert!(!spans.set_color(&mut text, "mp", Color::RED));
+        assert_eq!(spans.names().collect::<Vec<_>>(), ["hp"]);
+    }
+}
@@ -0,0 +1,183 @@
+use bevy_asset::Assets;
+use bevy_ecs::{
+    prelude::With,
+    system::{Query, Res, ResMut, SystemParam},
+};
+use bevy_math::Vec2;
+use bevy_window::{PrimaryWindow, Window};
+
+use crate::{Font, FontFallbacks, Text, TextError, TextPipeline};
+
+/// Measures the size of a [`Text`] without spawning it, like to size the container of a tooltip
+/// before spawning it.
+///
+/// The text is laid out at the scale factor of the primary window, the same way as the spawned
+/// text, and measured in logical pixels.
+///
+/// ```
+/// # use bevy_text::{Text, TextMeasurer, TextStyle};
+/// fn size_tooltip(mut text_measurer: TextMeasurer) {
+///     let text = Text::from_section("Deals 12 fire damage", TextStyle::default());
+///     if let Ok(size) = text_measurer.measure(&text, Some(200.0)) {
+///         assert!(size.x <= 200.0);
+///     }
+/// }
+/// # bevy_ecs::system::assert_is_system(size_tooltip);
+/// ```
+#[derive(SystemParam)]
+pub struct TextMeasurer<'w, 's> {
+    text_pipeline: ResMut<'w, TextPipeline>,
+    fonts: Res<'w, Assets<Font>>,
+    font_fallbacks: Res<'w, FontFallbacks>,
+    windows: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+}
+
+impl<'w, 's> TextMeasurer<'w, 's> {
+    /// The size of `text` wrapped to `max_width`, or laid out on lines as long as its own
+    /// when `None`, in logical pixels.
+    ///
+    /// Returns [`TextError::NoSuchFont`] while the fonts of the text are loading.
+    pub fn measure(&mut self, text: &Text, max_width: Option<f32>) -> Result<Vec2, TextError> {
+        let scale_factor = self
+            .windows
+            .get_single()
+            .map(|window| window.resolution.scale_factor())
+            .unwrap_or(1.0);
+        self.measure_with_scale_factor(text, max_width, scale_factor)
+    }
+
+    /// The size of `text` wrapped to `max_width`, laid out at `scale_factor` instead of the
+    /// scale factor of the primary window, in logical pixels.
+    pub fn measure_with_scale_factor(
+        &mut self,
+        text: &Text,
+        max_width: Option<f32>,
+        scale_factor: f64,
+    ) -> Result<Vec2, TextError> {
+        let max_width = max_width.map_or(f32::MAX, |width| (width as f64 * scale_factor) as f32);
+        let size = self.text_pipeline.measure(
+            &self.fonts,
+            &self.font_fallbacks,
+            &text.sections,
+            scale_factor,
+            text.linebreak_behaviour,
+            text.direction,
+            text.overflow,
+            Vec2::new(max_width, f32::MAX),
+        )?;
+        Ok(size / scale_factor as f32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_font, BreakLineOn, TextStyle};
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_ecs::system::SystemState;
+    use bevy_window::WindowResolution;
+
+    /// Measures `text`, in the monospace font of the examples at a size of 20 pixels, where each
+    /// character is 10 pixels wide and each line 20 pixels high
+    fn measure(
+        text: &str,
+        max_width: Option<f32>,
+        linebreak_behaviour: BreakLineOn,
+        scale_factor: Option<f64>,
+    ) -> Result<Vec2, TextError> {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default())
+            .add_asset::<Font>()
+            .init_resource::<TextPipeline>()
+            .init_resource::<FontFallbacks>();
+        if let Some(scale_factor) = scale_factor {
+            let mut resolution = WindowResolution::default();
+            resolution.set_scale_factor_override(Some(scale_factor));
+            app.world.spawn((
+                Window {
+                    resolution,
+                    ..Default::default()
+                },
+                PrimaryWindow,
+            ));
+        }
+        let font = app.world.resource_mut::<Assets<Font>>().add(test_font());
+        let mut text = Text::from_section(
+            text,
+            TextStyle {
+                font,
+                font_size: 20.0,
+                ..Default::default()
+            },
+        );
+        text.linebreak_behaviour = linebreak_behaviour;
+
+        let mut state = SystemState::<TextMeasurer>::new(&mut app.world);
+        let size = state.get_mut(&mut app.world).measure(&text, max_width)?;
+        // rounded, as the advances of the glyphs aren't exactly 10 pixels
+        Ok((size * 1000.0).round() / 1000.0)
+    }
+
+    #[test]
+    fn unwrapped_and_wrapped_text() {
+        use BreakLineOn::*;
+        let cases = [
+            ("", None, WordBoundary, Vec2::ZERO),
+            ("ab cd ef", None, WordBoundary, Vec2::new(80.0, 20.0)),
+            ("ab\ncdef", None, WordBoundary, Vec2::new(40.0, 40.0)),
+            ("ab cd ef", Some(80.0), WordBoundary, Vec2::new(80.0, 20.0)),
+            // the spaces ending the wrapped lines are measured, like in the laid out text
+            ("ab cd ef", Some(70.0), WordBoundary, Vec2::new(60.0, 40.0)),
+            ("ab cd ef", Some(30.0), WordBoundary, Vec2::new(30.0, 60.0)),
+            ("abcdef", Some(35.0), AnyCharacter, Vec2::new(30.0, 40.0)),
+            ("ab cd ef", Some(30.0), NoWrap, Vec2::new(80.0, 20.0)),
+        ];
+        for (text, max_width, linebreak_behaviour, expected) in cases {
+            for scale_factor in [None, Some(2.0)] {
+                assert_eq!(
+                    measure(text, max_width, linebreak_behaviour, scale_factor),
+                    Ok(expected),
+                    "{text:?} in {max_width:?} with {linebreak_behaviour:?}, at {scale_factor:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn min_and_max_content_sizes() {
+        use BreakLineOn::*;
+        // the min content size is measured without room for any character on a line, breaking
+        // all the lines it can, and the max content size without any limit
+        let cases = [
+            ("abc de", Some(0.0), WordBoundary, Vec2::new(40.0, 40.0)),
+            ("abc de", Some(0.0), AnyCharacter, Vec2::new(10.0, 120.0)),
+            ("abc de", Some(0.0), NoWrap, Vec2::new(60.0, 20.0)),
+            ("abc de", None, WordBoundary, Vec2::new(60.0, 20.0)),
+            ("abc de", None, AnyCharacter, Vec2::new(60.0, 20.0)),
+            ("abc\nde", None, WordBoundary, Vec2::new(30.0, 40.0)),
+        ];
+        for (text, max_width, linebreak_behaviour, expected) in cases {
+            assert_eq!(
+                measure(text, max_width, linebreak_behaviour, None),
+                Ok(expected),
+                "{text:?} in {max_width:?} with {linebreak_behaviour:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn fonts_loading() {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default())
+            .add_asset::<Font>()
+            .init_resource::<TextPipeline>()
+            .init_resource::<FontFallbacks>();
+        let text = Text::from_section("a", TextStyle::default());
+        let mut state = SystemState::<TextMeasurer>::new(&mut app.world);
+        assert_eq!(
+            state.get_mut(&mut app.world).measure(&text, None),
+            Err(TextError::NoSuchFont)
+        );
+    }
+}
@@ -0,0 +1,669 @@
+use ab_glyph::{Font as _, FontArc, Glyph, GlyphId, OutlineCurve, Rect, ScaleFont as _};
+use bevy_math::{IVec2, Vec2};
+use bevy_reflect::{FromReflect, Reflect};
+use bevy_render::{
+    render_resource::{Extent3d, TextureDimension, TextureFormat},
+    texture::Image,
+};
+
+/// How the glyphs of a [`Text3d`](crate::Text3d) are stored in their font atlases.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect, FromReflect)]
+pub enum GlyphAtlasMode {
+    /// Each glyph is drawn at the font size of the text, and is sharp when a pixel of the text
+    /// covers about a pixel of the screen.
+    #[default]
+    Bitmap,
+    /// Each glyph is stored once as a multi-channel signed distance field, the distances to its
+    /// edges, and stays sharp when the text is scaled, seen up close or at an angle.
+    ///
+    /// The color glyphs, the [`TextOutline`](crate::TextOutline)s and the
+    /// [`TextShadow`](crate::TextShadow)s are still drawn as bitmaps.
+    Msdf,
+}
+
+/// The font size the signed distance fields of the glyphs are generated at.
+pub(crate) const MSDF_FONT_SIZE: f32 = 48.0;
+
+/// The distances stored in the signed distance fields, from the inside to the outside of the
+/// edges, in pixels of the field.
+pub const MSDF_DISTANCE_RANGE: f32 = 4.0;
+
+const RED: u8 = 1;
+const GREEN: u8 = 2;
+const BLUE: u8 = 4;
+const WHITE: u8 = RED | GREEN | BLUE;
+const CORNER_COLORS: [u8; 3] = [GREEN | BLUE, RED | BLUE, RED | GREEN];
+
+/// The segments an edge of a glyph is flattened to, for its distance to be computed.
+const CURVE_SEGMENTS: usize = 8;
+
+/// The bounds of the signed distance field of `glyph`, placed at its position and scaled to its
+/// font size, or `None` if the glyph has no outline.
+pub(crate) fn msdf_bounds(font: &FontArc, glyph: &Glyph) -> Option<Rect> {
+    let (min, max) = texel_bounds(font, glyph.id)?;
+    let scale = glyph.scale.y / MSDF_FONT_SIZE;
+    Some(Rect {
+        min: ab_glyph::point(
+            glyph.position.x + min.x as f32 * scale,
+            glyph.position.y + min.y as f32 * scale,
+        ),
+        max: ab_glyph::point(
+            glyph.position.x + max.x as f32 * scale,
+            glyph.position.y + max.y as f32 * scale,
+        ),
+    })
+}
+
+/// The pixels of the signed distance field of a glyph from its origin at [`MSDF_FONT_SIZE`],
+/// with `y` pointing down.
+fn texel_bounds(font: &FontArc, glyph_id: GlyphId) -> Option<(IVec2, IVec2)> {
+    let outline = font.outline(glyph_id)?;
+    let scale = font.as_scaled(MSDF_FONT_SIZE).scale_factor();
+    // Keeps the distances up to half the range outside the glyph, with a pixel to filter them
+    let padding = (MSDF_DISTANCE_RANGE / 2.0).ceil() as i32 + 1;
+    let min = IVec2::new(
+        (outline.bounds.min.x * scale.horizontal).floor() as i32,
+        (outline.bounds.min.y * -scale.vertical).floor() as i32,
+    );
+    let max = IVec2::new(
+        (outline.bounds.max.x * scale.horizontal).ceil() as i32,
+        (outline.bounds.max.y * -scale.vertical).ceil() as i32,
+    );
+    Some((min - padding, max + padding))
+}
+
+/// Generates the multi-channel signed distance field of a glyph, with the true signed distance
+/// in its alpha channel.
+///
+/// The edges of the glyph are colored so that its corners are between edges of different
+/// colors, keeping them sharp in the median of the channels.
+pub(crate) fn msdf_texture(font: &FontArc, glyph_id: GlyphId) -> Option<Image> {
+    let (min, max) = texel_bounds(font, glyph_id)?;
+    let outline = font.outline(glyph_id)?;
+    let scale = font.as_scaled(MSDF_FONT_SIZE).scale_factor();
+    let to_pixels =
+        |point: &ab_glyph::Point| Vec2::new(point.x * scale.horizontal, point.y * -scale.vertical);
+
+    let mut contours: Vec<Vec<Edge>> = Vec::new();
+    let mut last_end: Option<Vec2> = None;
+    for curve in &outline.curves {
+        let points = match curve {
+            OutlineCurve::Line(p0, p1) => vec![to_pixels(p0), to_pixels(p1)],
+            OutlineCurve::Quad(p0, p1, p2) => {
+                let [p0, p1, p2] = [p0, p1, p2].map(to_pixels);
+                flatten(|t| p0.lerp(p1, t).lerp(p1.lerp(p2, t), t))
+            }
+            OutlineCurve::Cubic(p0, p1, p2, p3) => {
+                let [p0, p1, p2, p3] = [p0, p1, p2, p3].map(to_pixels);
+                flatten(|t| {
+                    let (a, b, c) = (p0.lerp(p1, t), p1.lerp(p2, t), p2.lerp(p3, t));
+                    a.lerp(b, t).lerp(b.lerp(c, t), t)
+                })
+            }
+        };
+        let edge = Edge {
+            points,
+            color: WHITE,
+        };
+        // A curve starting away from the end of the last one starts a new contour
+        match last_end {
+            Some(end) if end.distance_squared(edge.start()) < 1e-6 => {
+                contours.last_mut().unwrap().push(edge);
+            }
+            _ => contours.push(vec![edge]),
+        }
+        last_end = Some(contours.last().unwrap().last().unwrap().end());
+    }
+    for contour in &mut contours {
+        color_edges(contour);
+    }
+    let edges: Vec<Edge> = contours.into_iter().flatten().collect();
+    if edges.is_empty() {
+        return None;
+    }
+
+    // The distances are positive inside the glyph, whichever way its contours are wound
+    let area: f32 = edges
+        .iter()
+        .flat_map(|edge| edge.points.windows(2))
+        .map(|segment| segment[0].perp_dot(segment[1]))
+        .sum();
+    let orientation = if area < 0.0 { -1.0 } else { 1.0 };
+
+    let size = max - min;
+    let mut data = Vec::with_capacity(size.x as usize * size.y as usize * 4);
+    for y in 0..size.y {
+        for x in 0..size.x {
+            let point = (min + IVec2::new(x, y)).as_vec2() + 0.5;
+            let mut channels = [Nearest::default(); 3];
+            let mut nearest = Nearest::default();
+            for edge in &edges {
+                let distance = edge.distance(point);
+                for (channel, nearest) in channels.iter_mut().enumerate() {
+                    if edge.color & (1 << channel) != 0 && distance.is_closer(nearest) {
+                        *nearest = distance;
+                    }
+                }
+                if distance.is_closer(&nearest) {
+                    nearest = distance;
+                }
+            }
+            let true_distance = nearest.signed * orientation;
+            let mut distances = channels.map(|channel| channel.pseudo * orientation);
+            // The median is only trusted when it's on the same side of the edges as the pixel
+            let inside = winding_number(&edges, point) != 0;
+            if (median(distances) > 0.0) != inside {
+                distances = [true_distance; 3];
+            }
+            data.extend(
+                distances
+                    .into_iter()
+                    .chain([true_distance])
+                    .map(|distance| {
+                        ((distance / MSDF_DISTANCE_RANGE + 0.5).clamp(0.0, 1.0) * 255.0).round()
+                            as u8
+                    }),
+            );
+        }
+    }
+
+    Some(Image::new(
+        Extent3d {
+            width: size.x as u32,
+            height: size.y as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        // The distances are sampled linearly
+        TextureFormat::Rgba8Unorm,
+    ))
+}
+
+fn flatten(curve: impl Fn(f32) -> Vec2) -> Vec<Vec2> {
+    (0..=CURVE_SEGMENTS)
+        .map(|i| curve(i as f32 / CURVE_SEGMENTS as f32))
+        .collect()
+}
+
+fn median([a, b, c]: [f32; 3]) -> f32 {
+    a.min(b).max(a.max(b).min(c))
+}
+
+/// An edge of the outline of a glyph, flattened to segments, with the channels of the signed
+/// distance field it's part of.
+struct Edge {
+    points: Vec<Vec2>,
+    color: u8,
+}
+
+/// The distance from a point to an [`Edge`].
+#[derive(Clone, Copy)]
+struct Nearest {
+    /// The distance to the nearest point of the edge.
+    distance: f32,
+    /// How perpendicular the edge is to the direction of the point, to pick the edge along
+    /// which the point is at a corner.
+    orthogonality: f32,
+    /// The distance, signed with the side of the edge the point is on.
+    signed: f32,
+    /// The signed distance to the edge extended along its ends.
+    pseudo: f32,
+}
+
+impl Default for Nearest {
+    fn default() -> Self {
+        Nearest {
+            distance: f32::MAX,
+            orthogonality: 0.0,
+            signed: -f32::MAX,
+            pseudo: -f32::MAX,
+        }
+    }
+}
+
+impl Nearest {
+    fn is_closer(&self, other: &Nearest) -> bool {
+        if (self.distance - other.distance).abs() < 1e-4 {
+            self.orthogonality > other.orthogonality
+        } else {
+            self.distance < other.distance
+        }
+    }
+}
+
+impl Edge {
+    fn start(&self) -> Vec2 {
+        self.points[0]
+    }
+
+    fn end(&self) -> Vec2 {
+        self.points[self.points.len() - 1]
+    }
+
+    /// The direction the edge starts in.
+    fn start_direction(&self) -> Vec2 {
+        self.points
+            .windows(2)
+            .map(|segment| segment[1] - segment[0])
+            .find(|direction| direction.length_squared() > 1e-12)
+            .unwrap_or(Vec2::X)
+            .normalize()
+    }
+
+    /// The direction the edge ends in.
+    fn end_direction(&self) -> Vec2 {
+        self.points
+            .windows(2)
+            .rev()
+            .map(|segment| segment[1] - segment[0])
+            .find(|direction| direction.length_squared() > 1e-12)
+            .unwrap_or(Vec2::X)
+            .normalize()
+    }
+
+    fn distance(&self, point: Vec2) -> Nearest {
+        let last = self.points.len() - 2;
+        let mut nearest = Nearest::default();
+        for (index, segment) in self.points.windows(2).enumerate() {
+            let (start, end) = (segment[0], segment[1]);
+            let direction = end - start;
+            let length_squared = direction.length_squared();
+            if length_squared <= 1e-12 {
+                continue;
+            }
+            let t = (point - start).dot(direction) / length_squared;
+            let closest = start + direction * t.clamp(0.0, 1.0);
+            let offset = point - closest;
+            let distance = offset.length();
+            if distance > nearest.distance + 1e-4 {
+                continue;
+            }
+            let direction = direction / length_squared.sqrt();
+            let side = direction.perp_dot(offset).signum();
+            let orthogonality = if distance > 0.0 {
+                direction.perp_dot(offset / distance).abs()
+            } else {
+                1.0
+            };
+            let candidate = Nearest {
+                distance,
+                orthogonality,
+                signed: distance * side,
+                pseudo: distance * side,
+            };
+            if !candidate.is_closer(&nearest) {
+                continue;
+            }
+            nearest = candidate;
+            // Past the ends of the edge, the distance is to the lines they extend along
+            if (index == 0 && t < 0.0) || (index == last && t > 1.0) {
+                let pseudo = direction.perp_dot(point - start);
+                if pseudo.abs() <= distance {
+                    nearest.pseudo = pseudo;
+                }
+            }
+        }
+        nearest
+    }
+}
+
+/// Colors the edges of a contour so that the edges meeting at each of its corners don't share
+/// all their channels.
+fn color_edges(contour: &mut Vec<Edge>) {
+    let corners: Vec<usize> = (0..contour.len())
+        .filter(|&index| {
+            let previous = &contour[(index + contour.len() - 1) % contour.len()];
+            let (incoming, outgoing) = (previous.end_direction(), contour[index].start_direction());
+            // More than about 3 degrees between the directions
+            incoming.dot(outgoing) <= 0.0 || incoming.perp_dot(outgoing).abs() > 0.05
+        })
+        .collect();
+
+    match corners.len() {
+        // A smooth contour, like the one of an `O`, doesn't need several channels
+        0 => {}
+        // A teardrop is split in three, for its corner to be between two colors
+        1 => {
+            contour.rotate_left(corners[0]);
+            while contour.len() < 3 {
+                split_longest_edge(contour);
+            }
+            let count = contour.len();
+            for (index, edge) in contour.iter_mut().enumerate() {
+                edge.color = match index * 3 / count {
+                    0 => CORNER_COLORS[1],
+                    1 => WHITE,
+                    _ => CORNER_COLORS[2],
+                };
+            }
+        }
+        _ => {
+            let count = contour.len();
+            for (index, &corner) in corners.iter().enumerate() {
+                let end = corners
+                    .get(index + 1)
+                    .copied()
+                    .unwrap_or(corners[0] + count);
+                // The last edges meet the first ones, so they can't have the same color
+                let color = if index == corners.len() - 1 && index % 3 == 0 {
+                    CORNER_COLORS[1]
+                } else {
+                    CORNER_COLORS[index % 3]
+                };
+                for edge in corner..end {
+                    contour[edge % count].color = color;
+                }
+            }
+        }
+    }
+}
+
+fn split_longest_edge(contour: &mut Vec<Edge>) {
+    let length = |edge: &Edge| edge.start().distance(edge.end());
+    let Some(index) =
+        (0..contour.len()).max_by(|&a, &b| length(&contour[a]).total_cmp(&length(&contour[b])))
+    else {
+        return;
+    };
+    let edge = &mut contour[index];
+    if edge.points.len() < 3 {
+        let middle = edge.start().lerp(edge.end(), 0.5);
+        edge.points.insert(1, middle);
+    }
+    let middle = edge.points.len() / 2;
+    let second = Edge {
+        points: edge.points[middle..].to_vec(),
+        color: edge.color,
+    };
+    edge.points.truncate(middle + 1);
+    contour.insert(index + 1, second);
+}
+
+/// The number of times the edges wind around `point`, not `0` when it's inside the glyph.
+fn winding_number(edges: &[Edge], point: Vec2) -> i32 {
+    let mut winding = 0;
+    for segment in edges.iter().flat_map(|edge| edge.points.windows(2)) {
+        let (start, end) = (segment[0], segment[1]);
+        if (start.y <= point.y) != (end.y <= point.y) {
+            let t = (point.y - start.y) / (end.y - start.y);
+            if start.x + (end.x - start.x) * t > point.x {
+                winding += if end.y > start.y { 1 } else { -1 };
+            }
+        }
+    }
+    winding
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        test_font, BreakLineOn, Font, FontAtlasSet, FontAtlasWarning, FontFallbacks, TextAlignment,
+        TextDirection, TextOverflow, TextPipeline, TextSection, TextSettings, TextStyle,
+        YAxisOrientation,
+    };
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin, Assets};
+    use bevy_sprite::TextureAtlas;
+
+    /// The distances of the alpha channel of a texel over half the range are inside the glyph
+    fn is_inside(distance: u8) -> bool {
+        distance > 127
+    }
+
+    /// A square contour of 10 by 10 pixels
+    fn square() -> Vec<Edge> {
+        let corners = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        (0..4)
+            .map(|index| Edge {
+                points: vec![corners[index], corners[(index + 1) % 4]],
+                color: WHITE,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn distance_field_of_a_glyph() {
+        let font = test_font().font;
+        for c in ['a', 'o', 'W', '%'] {
+            let glyph_id = font.glyph_id(c);
+            let texture = msdf_texture(&font, glyph_id).unwrap();
+            let (min, max) = texel_bounds(&font, glyph_id).unwrap();
+            let size = texture.texture_descriptor.size;
+            assert_eq!(IVec2::new(size.width as i32, size.height as i32), max - min);
+            assert_eq!(texture.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+
+            // The inside of the field is the inside of the glyph outlined at the same size, except
+            // for the pixels only partly covered by its edges
+            let glyph = glyph_id.with_scale(MSDF_FONT_SIZE);
+            let outlined_glyph = font.outline_glyph(glyph).unwrap();
+            let bounds = outlined_glyph.px_bounds();
+            let width = size.width as usize;
+            let texel = |x: i32, y: i32| {
+                let (x, y) = ((x - min.x) as usize, (y - min.y) as usize);
+                &texture.data[(y * width + x) * 4..][..4]
+            };
+            outlined_glyph.draw(|x, y, coverage| {
+                let (x, y) = (
+                    bounds.min.x as i32 + x as i32,
+                    bounds.min.y as i32 + y as i32,
+                );
+                let texel = texel(x, y);
+                let [red, green, blue, alpha] = [texel[0], texel[1], texel[2], texel[3]];
+                let median = median([red as f32, green as f32, blue as f32]) as u8;
+                if coverage > 0.99 {
+                    assert!(is_inside(alpha) && is_inside(median), "{c:?} at {x}, {y}");
+                } else if coverage < 0.01 {
+                    assert!(!is_inside(alpha) && !is_inside(median), "{c:?} at {x}, {y}");
+                }
+            });
+            // the padding around the glyph is outside of it
+            for x in min.x..max.x {
+                assert!(!is_inside(texel(x, min.y)[3]) && !is_inside(texel(x, max.y - 1)[3]));
+            }
+        }
+
+        // A glyph without an outline has no field
+        assert!(msdf_texture(&font, font.glyph_id(' ')).is_none());
+        assert!(msdf_bounds(&font, &font.glyph_id(' ').with_scale(20.0)).is_none());
+    }
+
+    #[test]
+    fn bounds_scaled_to_the_font_size() {
+        let font = test_font().font;
+        let glyph_id = font.glyph_id('a');
+        let (min, max) = texel_bounds(&font, glyph_id).unwrap();
+        let cases = [
+            (MSDF_FONT_SIZE, ab_glyph::point(0.0, 0.0), 1.0),
+            (MSDF_FONT_SIZE * 2.0, ab_glyph::point(0.0, 0.0), 2.0),
+            (MSDF_FONT_SIZE / 2.0, ab_glyph::point(10.0, 20.0), 0.5),
+        ];
+        for (font_size, position, scale) in cases {
+            let glyph = glyph_id.with_scale_and_position(font_size, position);
+            let bounds = msdf_bounds(&font, &glyph).unwrap();
+            assert_eq!(
+                (bounds.min.x, bounds.min.y),
+                (
+                    position.x + min.x as f32 * scale,
+                    position.y + min.y as f32 * scale
+                ),
+                "{font_size}"
+            );
+            assert_eq!(
+                (bounds.width(), bounds.height()),
+                (
+                    (max.x - min.x) as f32 * scale,
+                    (max.y - min.y) as f32 * scale
+                ),
+                "{font_size}"
+            );
+        }
+    }
+
+    #[test]
+    fn edges_colored_at_the_corners() {
+        let mut contour = square();
+        color_edges(&mut contour);
+        for index in 0..4 {
+            let (edge, next) = (&contour[index], &contour[(index + 1) % 4]);
+            assert_ne!(edge.color & next.color, WHITE, "corner {index}");
+            // the channels still cover both edges
+            assert_ne!(edge.color & next.color, 0, "corner {index}");
+        }
+
+        // A smooth contour keeps all the channels
+        let mut circle: Vec<Edge> = (0..16)
+            .map(|index| {
+                let point = |turns: f32| {
+                    let angle = turns / 16.0 * std::f32::consts::TAU;
+                    Vec2::new(angle.cos(), angle.sin()) * 10.0
+                };
+                Edge {
+                    points: flatten(|t| point(index as f32 + t)),
+                    color: WHITE,
+                }
+            })
+            .collect();
+        color_edges(&mut circle);
+        assert!(circle.iter().all(|edge| edge.color == WHITE));
+
+        // A teardrop, a single edge with a corner where it starts and ends, is split in three
+        let mut teardrop = vec![Edge {
+            points: flatten(|t| {
+                let (p0, p1, p2) = (Vec2::ZERO, Vec2::new(20.0, 10.0), Vec2::new(0.0, 20.0));
+                let (p3, p4) = (Vec2::new(-20.0, 10.0), Vec2::ZERO);
+                // a loop of two quadratic curves through the control points
+                if t < 0.5 {
+                    let t = t * 2.0;
+                    p0.lerp(p1, t).lerp(p1.lerp(p2, t), t)
+                } else {
+                    let t = t * 2.0 - 1.0;
+                    p2.lerp(p3, t).lerp(p3.lerp(p4, t), t)
+                }
+            }),
+            color: WHITE,
+        }];
+        color_edges(&mut teardrop);
+        assert_eq!(teardrop.len(), 3);
+        let (first, last) = (teardrop[0].color, teardrop[2].color);
+        assert_ne!(first & last, WHITE);
+        assert!(teardrop[0].start().distance(teardrop[2].end()) < 1e-6);
+    }
+
+    #[test]
+    fn inside_of_the_contours() {
+        let edges = square();
+        let cases = [
+            (Vec2::new(5.0, 5.0), true),
+            (Vec2::new(0.5, 9.5), true),
+            (Vec2::new(-1.0, 5.0), false),
+            (Vec2::new(5.0, 11.0), false),
+            (Vec2::new(15.0, 5.0), false),
+        ];
+        for (point, inside) in cases {
+            assert_eq!(winding_number(&edges, point) != 0, inside, "{point}");
+            // the distance to the nearest edge is positive on the inside of this contour
+            let nearest = edges
+                .iter()
+                .map(|edge| edge.distance(point))
+                .reduce(|a, b| if b.is_closer(&a) { b } else { a })
+                .unwrap();
+            assert_eq!(nearest.signed > 0.0, inside, "{point}");
+        }
+        assert_eq!(median([1.0, 3.0, 2.0]), 2.0);
+        assert_eq!(median([3.0, -1.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn glyphs_placed_once_in_the_atlas() {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default())
+            .add_asset::<Font>()
+            .add_asset::<FontAtlasSet>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<Image>();
+        let world = &mut app.world;
+        let mut fonts = world.remove_resource::<Assets<Font>>().unwrap();
+        let mut font_atlas_sets = world.remove_resource::<Assets<FontAtlasSet>>().unwrap();
+        let mut texture_atlases = world.remove_resource::<Assets<TextureAtlas>>().unwrap();
+        let mut images = world.remove_resource::<Assets<Image>>().unwrap();
+
+        let font = fonts.add(test_font());
+        let style = |font_size| TextStyle {
+            font: font.clone(),
+            font_size,
+            ..Default::default()
+        };
+        let sections = [
+            TextSection::new("ab a", style(20.0)),
+            TextSection::new("a", style(40.0)),
+        ];
+        let layout_info = TextPipeline::default()
+            .queue_text(
+                &fonts,
+                &FontFallbacks::default(),
+                &sections,
+                1.0,
+                TextAlignment::Left,
+                BreakLineOn::WordBoundary,
+                TextDirection::Auto,
+                TextOverflow::default(),
+                Vec2::splat(f32::INFINITY),
+                &mut font_atlas_sets,
+                &mut texture_atlases,
+                &mut images,
+                &TextSettings::default(),
+                &mut FontAtlasWarning::default(),
+                YAxisOrientation::TopToBottom,
+                GlyphAtlasMode::Msdf,
+            )
+            .unwrap();
+
+        // the space has no glyph
+        let glyphs: Vec<_> = layout_info.fill_glyphs().collect();
+        assert_eq!(glyphs.len(), 4);
+        assert!(glyphs.iter().all(|glyph| glyph.atlas_info.is_msdf));
+        let [a, b, a_again, large_a] = [glyphs[0], glyphs[1], glyphs[2], glyphs[3]];
+        let texture = |glyph: &crate::PositionedGlyph| {
+            (
+                glyph.atlas_info.texture_atlas.clone(),
+                glyph.atlas_info.glyph_index,
+            )
+        };
+        // the field of a glyph is shared by all its font sizes, and scaled to them
+        assert_eq!(texture(a), texture(a_again));
+        assert_eq!(texture(a), texture(large_a));
+        assert_ne!(texture(a), texture(b));
+        assert_eq!(a.size, a_again.size);
+        assert!(large_a.size.abs_diff_eq(a.size * 2.0, 1e-4));
+
+        // the field is copied in the atlas, at the size it's generated at
+        let font = &fonts.get(&font).unwrap().font;
+        let field = msdf_texture(font, font.glyph_id('a')).unwrap();
+        let texture_atlas = texture_atlases.get(&a.atlas_info.texture_atlas).unwrap();
+        let rect = texture_atlas.textures[a.atlas_info.glyph_index];
+        let field_size = field.texture_descriptor.size;
+        assert_eq!(
+            rect.size(),
+            Vec2::new(field_size.width as f32, field_size.height as f32)
+        );
+        let atlas = images.get(&texture_atlas.texture).unwrap();
+        assert_eq!(atlas.texture_descriptor.format, TextureFormat::Rgba8Unorm);
+        let atlas_width = atlas.texture_descriptor.size.width as usize;
+        let row_length = field_size.width as usize * 4;
+        for y in 0..field_size.height as usize {
+            let start = ((rect.min.y as usize + y) * atlas_width + rect.min.x as usize) * 4;
+            assert_eq!(
+                atlas.data[start..start + row_length],
+                field.data[y * row_length..(y + 1) * row_length],
+                "row {y}"
+            );
+        }
+    }
+}
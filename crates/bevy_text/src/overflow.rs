@@ -0,0 +1,354 @@
+use ab_glyph::{Font as _, FontArc, ScaleFont as _};
+use glyph_brush_layout::{SectionGlyph, ToSectionText};
+
+use crate::{TextAlignment, TextOverflow};
+
+/// The width a line can exceed its bounds by, from the rounding errors of its alignment
+const WIDTH_TOLERANCE: f32 = 0.01;
+
+/// Cuts off the lines of the laid out `glyphs` after the [`TextOverflow::max_lines`], and ends
+/// the last line and the lines wider than `max_width` in an ellipsis when asked to.
+///
+/// The shortened lines are aligned again with the `text_alignment`.
+pub(crate) fn truncate_lines<S: ToSectionText>(
+    glyphs: &mut Vec<SectionGlyph>,
+    sections: &[S],
+    fonts: &[FontArc],
+    max_width: f32,
+    text_alignment: TextAlignment,
+    overflow: TextOverflow,
+) {
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    while line_start < glyphs.len() {
+        let line_y = glyphs[line_start].glyph.position.y;
+        let line_end = glyphs[line_start..]
+            .iter()
+            .position(|glyph| glyph.glyph.position.y != line_y)
+            .map_or(glyphs.len(), |length| line_start + length);
+        lines.push(line_start..line_end);
+        line_start = line_end;
+    }
+
+    let cut_off = matches!(overflow.max_lines, Some(max_lines) if lines.len() > max_lines);
+    if cut_off {
+        let max_lines = overflow.max_lines.unwrap_or_default();
+        lines.truncate(max_lines);
+        glyphs.truncate(lines.last().map_or(0, |line| line.end));
+    }
+    if !overflow.ellipsis {
+        return;
+    }
+
+    let sections: Vec<_> = sections
+        .iter()
+        .map(|section| section.to_section_text())
+        .collect();
+    let advance = |glyph: &SectionGlyph| {
+        fonts[glyph.font_id.0]
+            .as_scaled(glyph.glyph.scale)
+            .h_advance(glyph.glyph.id)
+    };
+    let is_whitespace = |glyph: &SectionGlyph| match sections[glyph.section_index].text
+        [glyph.byte_index..]
+        .chars()
+        .next()
+    {
+        Some(character) => character.is_whitespace(),
+        None => true,
+    };
+    let alignment_factor = match text_alignment {
+        TextAlignment::Left => 0.0,
+        TextAlignment::Center => 0.5,
+        TextAlignment::Right => 1.0,
+    };
+
+    // The lines are shortened from the last one, to keep the ranges of the ones before
+    for (index, line) in lines.iter().enumerate().rev() {
+        let is_last = index == lines.len() - 1;
+        let start = line
+            .clone()
+            .map(|glyph| glyphs[glyph].glyph.position.x)
+            .fold(f32::MAX, f32::min);
+        // The spaces at the end of a line aren't drawn, so they can run out of bounds
+        let end = |line: &[SectionGlyph]| {
+            line.iter()
+                .filter(|glyph| !is_whitespace(glyph))
+                .map(|glyph| glyph.glyph.position.x + advance(glyph))
+                .fold(start, f32::max)
+        };
+        let width = end(&glyphs[line.clone()]) - start;
+        if !(cut_off && is_last) && width <= max_width + WIDTH_TOLERANCE {
+            continue;
+        }
+
+        let mut line_glyphs: Vec<SectionGlyph> = glyphs[line.clone()].to_vec();
+        let Some(style_glyph) = line_glyphs.last().cloned() else {
+            continue;
+        };
+        let font = &fonts[style_glyph.font_id.0];
+        let ellipsis = match font.glyph_id('…') {
+            ab_glyph::GlyphId(0) => vec![font.glyph_id('.'); 3],
+            id => vec![id],
+        };
+        let ellipsis_width: f32 = ellipsis
+            .iter()
+            .map(|&id| font.as_scaled(style_glyph.glyph.scale).h_advance(id))
+            .sum();
+
+        // Removes the rightmost glyphs until the ellipsis fits, and the spaces before it
+        while let Some((rightmost, glyph)) = line_glyphs
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.glyph.position.x.total_cmp(&b.glyph.position.x))
+        {
+            let fits = end(&line_glyphs) - start + ellipsis_width <= max_width + WIDTH_TOLERANCE;
+            if fits && !is_whitespace(glyph) {
+                break;
+            }
+            // The glyphs of a shaped cluster are removed together
+            let cluster = (glyph.section_index, glyph.byte_index);
+            line_glyphs.remove(rightmost);
+            line_glyphs.retain(|glyph| (glyph.section_index, glyph.byte_index) != cluster);
+        }
+
+        // The ellipsis stands for the last character of the line, in its style
+        let mut x = end(&line_glyphs);
+        for id in ellipsis {
+            let mut glyph = style_glyph.clone();
+            glyph.glyph.id = id;
+            glyph.glyph.position.x = x;
+            x += font.as_scaled(glyph.glyph.scale).h_advance(id);
+            line_glyphs.push(glyph);
+        }
+
+        let offset = (width - (x - start)) * alignment_factor;
+        for glyph in &mut line_glyphs {
+            glyph.glyph.position.x += offset;
+        }
+        glyphs.splice(line.clone(), line_glyphs);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{glyph_brush::GlyphBrush, test_font, BreakLineOn, TextDirection};
+    use bevy_asset::Handle;
+    use bevy_math::Vec2;
+    use glyph_brush_layout::{ab_glyph::PxScale, SectionText};
+
+    const FONT_SIZE: f32 = 20.0;
+
+    /// Lays out `text` in lines of up to `width` characters, and returns the characters of each
+    /// line with the bounds of the line, in characters
+    fn truncate(
+        text: &str,
+        width: f32,
+        text_alignment: TextAlignment,
+        linebreak_behaviour: BreakLineOn,
+        overflow: TextOverflow,
+    ) -> Vec<(String, f32, f32)> {
+        let font = test_font().font;
+        let advance = font.as_scaled(FONT_SIZE).h_advance(font.glyph_id('a'));
+        let ellipsis = font.glyph_id('…');
+        let mut brush = GlyphBrush::default();
+        let font_id = brush.add_font(Handle::default(), font);
+        let sections = [SectionText {
+            text,
+            scale: PxScale::from(FONT_SIZE),
+            font_id,
+        }];
+        let glyphs = brush
+            .compute_glyphs(
+                &sections,
+                Vec2::new(width * advance, f32::INFINITY),
+                text_alignment,
+                linebreak_behaviour,
+                TextDirection::Auto,
+                overflow,
+            )
+            .unwrap();
+
+        let mut lines: Vec<(f32, String, f32, f32)> = Vec::new();
+        for glyph in glyphs {
+            let character = if glyph.glyph.id == ellipsis {
+                '…'
+            } else {
+                text[glyph.byte_index..].chars().next().unwrap()
+            };
+            let (y, x) = (glyph.glyph.position.y, glyph.glyph.position.x / advance);
+            match lines.last_mut() {
+                Some((line_y, line, _, end)) if *line_y == y => {
+                    line.push(character);
+                    *end = x + 1.0;
+                }
+                _ => lines.push((y, character.to_string(), x, x + 1.0)),
+            }
+        }
+        lines
+            .into_iter()
+            // rounded to half characters, for the centered lines
+            .map(|(_, line, start, end)| {
+                (line, (start * 2.0).round() / 2.0, (end * 2.0).round() / 2.0)
+            })
+            .collect()
+    }
+
+    fn overflow(max_lines: Option<usize>, ellipsis: bool) -> TextOverflow {
+        TextOverflow {
+            max_lines,
+            ellipsis,
+        }
+    }
+
+    #[test]
+    fn truncated_lines() {
+        use BreakLineOn::*;
+        // (text, width in characters, line breaks, overflow, lines)
+        let cases: &[(&str, f32, BreakLineOn, TextOverflow, &[&str])] = &[
+            (
+                "abcdefgh",
+                5.0,
+                NoWrap,
+                overflow(None, false),
+                &["abcdefgh"],
+            ),
+            ("abcdefgh", 5.0, NoWrap, overflow(None, true), &["abcd…"]),
+            ("abcde", 5.0, NoWrap, overflow(None, true), &["abcde"]),
+            ("abcdef", 5.0, NoWrap, overflow(Some(1), true), &["abcd…"]),
+            // the spaces before the ellipsis are removed
+            ("aa bbbbbb", 4.0, NoWrap, overflow(None, true), &["aa…"]),
+            (
+                "ab\ncdefgh",
+                5.0,
+                NoWrap,
+                overflow(None, true),
+                &["ab", "cdef…"],
+            ),
+            // the lines after the limit are cut off
+            (
+                "aaa bbb ccc",
+                4.0,
+                WordBoundary,
+                overflow(None, false),
+                &["aaa ", "bbb ", "ccc"],
+            ),
+            (
+                "aaa bbb ccc",
+                4.0,
+                WordBoundary,
+                overflow(Some(2), false),
+                &["aaa ", "bbb "],
+            ),
+            (
+                "aaa bbb ccc",
+                4.0,
+                WordBoundary,
+                overflow(Some(2), true),
+                &["aaa ", "bbb…"],
+            ),
+            (
+                "aaaa bbbb cc",
+                4.0,
+                WordBoundary,
+                overflow(Some(2), true),
+                &["aaaa ", "bbb…"],
+            ),
+            (
+                "aaa bbb ccc",
+                4.0,
+                WordBoundary,
+                overflow(Some(3), true),
+                &["aaa ", "bbb ", "ccc"],
+            ),
+            (
+                "aaa bbb ccc",
+                4.0,
+                WordBoundary,
+                overflow(Some(0), true),
+                &[],
+            ),
+            // the words too long to be wrapped are shortened
+            (
+                "aaaaaa bb",
+                4.0,
+                WordBoundary,
+                overflow(None, true),
+                &["aaa…", "bb"],
+            ),
+            (
+                "abcdefgh",
+                4.0,
+                AnyCharacter,
+                overflow(Some(1), true),
+                &["abc…"],
+            ),
+        ];
+        for (text, width, linebreak_behaviour, overflow, expected) in cases {
+            for text_alignment in [
+                TextAlignment::Left,
+                TextAlignment::Center,
+                TextAlignment::Right,
+            ] {
+                let lines: Vec<_> = truncate(
+                    text,
+                    *width,
+                    text_alignment,
+                    *linebreak_behaviour,
+                    *overflow,
+                )
+                .into_iter()
+                .map(|(line, ..)| line)
+                .collect();
+                assert_eq!(
+                    lines, *expected,
+                    "{text:?} in {width} characters, {linebreak_behaviour:?}, {overflow:?}, {text_alignment:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_lines_are_aligned() {
+        // (alignment, bounds of the line, in characters)
+        let cases = [
+            (TextAlignment::Left, (0.0, 5.0)),
+            (TextAlignment::Center, (-2.5, 2.5)),
+            (TextAlignment::Right, (-5.0, 0.0)),
+        ];
+        for (text_alignment, (start, end)) in cases {
+            let lines = truncate(
+                "abcdefgh",
+                5.0,
+                text_alignment,
+                BreakLineOn::NoWrap,
+                overflow(None, true),
+            );
+            assert_eq!(
+                lines,
+                [("abcd…".to_string(), start, end)],
+                "{text_alignment:?}"
+            );
+
+            // with the spaces removed before the ellipsis
+            let lines = truncate(
+                "ab cdefgh",
+                4.0,
+                text_alignment,
+                BreakLineOn::NoWrap,
+                overflow(None, true),
+            );
+            let offset = match text_alignment {
+                TextAlignment::Left => 0.0,
+                TextAlignment::Center => -1.5,
+                TextAlignment::Right => -3.0,
+            };
+            assert_eq!(
+                lines,
+                [("ab…".to_string(), offset, offset + 3.0)],
+                "{text_alignment:?}"
+            );
+        }
+    }
+}
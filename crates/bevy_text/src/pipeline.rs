@@ -1,51 +1,112 @@
-use ab_glyph::{PxScale, ScaleFont};
+use ab_glyph::{FontArc, PxScale, PxScaleFont, ScaleFont};
 use bevy_asset::{Assets, Handle, HandleId};
 use bevy_ecs::component::Component;
 use bevy_ecs::system::Resource;
 use bevy_math::Vec2;
 use bevy_render::texture::Image;
 use bevy_sprite::TextureAtlas;
-use bevy_utils::HashMap;
+use bevy_utils::{FloatOrd, HashMap};
 
-use glyph_brush_layout::{FontId, SectionText};
+use glyph_brush_layout::{FontId, SectionGlyph, SectionText};
 
 use crate::{
-    error::TextError, glyph_brush::GlyphBrush, scale_value, BreakLineOn, Font, FontAtlasSet,
-    FontAtlasWarning, PositionedGlyph, TextAlignment, TextSection, TextSettings, YAxisOrientation,
+    error::TextError, font_fallback::split_font_runs, glyph_brush::GlyphBrush, scale_value,
+    spacing::SpacedFont, BreakLineOn, Font, FontAtlasSet, FontAtlasWarning, FontFallbacks,
+    GlyphAtlasMode, GlyphLayer, PositionedGlyph, TextAlignment, TextDirection, TextOutline,
+    TextOverflow, TextSection, TextSettings, TextShadow, YAxisOrientation,
 };
 
 #[derive(Default, Resource)]
 pub struct TextPipeline {
     brush: GlyphBrush,
-    map_font_id: HashMap<HandleId, FontId>,
+    /// The font ids of the fonts, by their letter and word spacings in font units
+    map_font_id: HashMap<(HandleId, FloatOrd, FloatOrd), FontId>,
 }
 
 /// Render information for a corresponding [`Text`](crate::Text) component.
 ///
 ///  Contains scaled glyphs and their size. Generated via [`TextPipeline::queue_text`].
+///
+/// The glyphs are positioned in physical pixels from the top left corner of the text for UI
+/// text, and from its bottom left corner for 2d text, and can be read to animate or hit test
+/// the characters of the text. They're only laid out again when the text changes, so changing
+/// them with [`bypass_change_detection`](bevy_ecs::change_detection::DetectChangesMut::bypass_change_detection)
+/// keeps the changes drawn until then.
 #[derive(Component, Clone, Default, Debug)]
 pub struct TextLayoutInfo {
+    /// The glyphs of the text, in the order they're drawn in, the glyphs of each
+    /// [`GlyphLayer`] being drawn over the layers before.
     pub glyphs: Vec<PositionedGlyph>,
+    /// The size of the text, in physical pixels.
     pub size: Vec2,
 }
 
+impl TextLayoutInfo {
+    /// The glyphs drawing the characters of the text, without their outlines and shadows.
+    pub fn fill_glyphs(&self) -> impl Iterator<Item = &PositionedGlyph> {
+        self.glyphs
+            .iter()
+            .filter(|glyph| glyph.layer == GlyphLayer::Fill)
+    }
+
+    /// The glyph of the character drawn at `position`, in the same coordinates as the glyph
+    /// positions, or `None` if there is no character there.
+    ///
+    /// The whitespaces aren't drawn, so they don't have glyphs.
+    pub fn glyph_at(&self, position: Vec2) -> Option<&PositionedGlyph> {
+        self.fill_glyphs().find(|glyph| {
+            let offset = (position - glyph.position).abs();
+            offset.x <= glyph.size.x / 2.0 && offset.y <= glyph.size.y / 2.0
+        })
+    }
+}
+
 impl TextPipeline {
     pub fn get_or_insert_font_id(&mut self, handle: &Handle<Font>, font: &Font) -> FontId {
         let brush = &mut self.brush;
         *self
             .map_font_id
-            .entry(handle.id())
+            .entry((handle.id(), FloatOrd(0.0), FloatOrd(0.0)))
             .or_insert_with(|| brush.add_font(handle.clone(), font.font.clone()))
     }
 
+    /// The font id of `font` laid out with the letter and word spacings of a section, in pixels
+    /// at `font_size`.
+    fn get_or_insert_spaced_font_id(
+        &mut self,
+        handle: &Handle<Font>,
+        font: &Font,
+        font_size: f32,
+        letter_spacing: f32,
+        word_spacing: f32,
+    ) -> FontId {
+        if (letter_spacing == 0.0 && word_spacing == 0.0) || font_size <= 0.0 {
+            return self.get_or_insert_font_id(handle, font);
+        }
+        let spaced_font =
+            SpacedFont::new(font.font.clone(), font_size, letter_spacing, word_spacing);
+        let brush = &mut self.brush;
+        *self
+            .map_font_id
+            .entry((
+                handle.id(),
+                FloatOrd(spaced_font.letter_spacing),
+                FloatOrd(spaced_font.word_spacing),
+            ))
+            .or_insert_with(|| brush.add_font(handle.clone(), FontArc::new(spaced_font)))
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn queue_text(
         &mut self,
         fonts: &Assets<Font>,
+        font_fallbacks: &FontFallbacks,
         sections: &[TextSection],
         scale_factor: f64,
         text_alignment: TextAlignment,
         linebreak_behaviour: BreakLineOn,
+        text_direction: TextDirection,
+        overflow: TextOverflow,
         bounds: Vec2,
         font_atlas_set_storage: &mut Assets<FontAtlasSet>,
         texture_atlases: &mut Assets<TextureAtlas>,
@@ -53,56 +114,35 @@ impl TextPipeline {
         text_settings: &TextSettings,
         font_atlas_warning: &mut FontAtlasWarning,
         y_axis_orientation: YAxisOrientation,
+        atlas_mode: GlyphAtlasMode,
     ) -> Result<TextLayoutInfo, TextError> {
-        let mut scaled_fonts = Vec::new();
-        let sections = sections
-            .iter()
-            .map(|section| {
-                let font = fonts
-                    .get(&section.style.font)
-                    .ok_or(TextError::NoSuchFont)?;
-                let font_id = self.get_or_insert_font_id(&section.style.font, font);
-                let font_size = scale_value(section.style.font_size, scale_factor);
-
-                scaled_fonts.push(ab_glyph::Font::as_scaled(&font.font, font_size));
-
-                let section = SectionText {
-                    font_id,
-                    scale: PxScale::from(font_size),
-                    text: &section.value,
-                };
+        let SplitSections {
+            section_texts: sections,
+            scaled_fonts,
+            section_sources,
+            section_effects,
+        } = self.split_sections(fonts, font_fallbacks, sections, scale_factor)?;
 
-                Ok(section)
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-
-        let section_glyphs =
-            self.brush
-                .compute_glyphs(&sections, bounds, text_alignment, linebreak_behaviour)?;
+        let (section_glyphs, cluster_starts) = self.brush.compute_clustered_glyphs(
+            &sections,
+            bounds,
+            text_alignment,
+            linebreak_behaviour,
+            text_direction,
+            overflow,
+        )?;
 
         if section_glyphs.is_empty() {
             return Ok(TextLayoutInfo::default());
         }
 
-        let mut min_x: f32 = std::f32::MAX;
-        let mut min_y: f32 = std::f32::MAX;
-        let mut max_x: f32 = std::f32::MIN;
-        let mut max_y: f32 = std::f32::MIN;
-
-        for sg in &section_glyphs {
-            let scaled_font = scaled_fonts[sg.section_index];
-            let glyph = &sg.glyph;
-            min_x = min_x.min(glyph.position.x);
-            min_y = min_y.min(glyph.position.y - scaled_font.ascent());
-            max_x = max_x.max(glyph.position.x + scaled_font.h_advance(glyph.id));
-            max_y = max_y.max(glyph.position.y - scaled_font.descent());
-        }
-
-        let size = Vec2::new(max_x - min_x, max_y - min_y);
+        let size = laid_out_size(&section_glyphs, &scaled_fonts);
 
-        let glyphs = self.brush.process_glyphs(
+        let mut glyphs = self.brush.process_glyphs(
             section_glyphs,
+            &cluster_starts,
             &sections,
+            &section_effects,
             font_atlas_set_storage,
             fonts,
             texture_atlases,
@@ -110,8 +150,289 @@ impl TextPipeline {
             text_settings,
             font_atlas_warning,
             y_axis_orientation,
+            atlas_mode,
         )?;
+        for glyph in &mut glyphs {
+            let (section_index, byte_offset) = section_sources[glyph.section_index];
+            glyph.section_index = section_index;
+            glyph.byte_index += byte_offset;
+            glyph.byte_range =
+                glyph.byte_range.start + byte_offset..glyph.byte_range.end + byte_offset;
+        }
 
         Ok(TextLayoutInfo { glyphs, size })
     }
+
+    /// Computes the size `sections` would be laid out in, in physical pixels like the
+    /// [`TextLayoutInfo::size`], without drawing their glyphs nor spawning any entity.
+    ///
+    /// See [`TextMeasurer`](crate::TextMeasurer) to measure text from a system.
+    #[allow(clippy::too_many_arguments)]
+    pub fn measure(
+        &mut self,
+        fonts: &Assets<Font>,
+        font_fallbacks: &FontFallbacks,
+        sections: &[TextSection],
+        scale_factor: f64,
+        linebreak_behaviour: BreakLineOn,
+        text_direction: TextDirection,
+        overflow: TextOverflow,
+        bounds: Vec2,
+    ) -> Result<Vec2, TextError> {
+        let SplitSections {
+            section_texts,
+            scaled_fonts,
+            ..
+        } = self.split_sections(fonts, font_fallbacks, sections, scale_factor)?;
+        // The alignment moves the lines inside of the bounds without changing their size
+        let section_glyphs = self.brush.compute_glyphs(
+            &section_texts,
+            bounds,
+            TextAlignment::Left,
+            linebreak_behaviour,
+            text_direction,
+            overflow,
+        )?;
+        if section_glyphs.is_empty() {
+            return Ok(Vec2::ZERO);
+        }
+        Ok(laid_out_size(&section_glyphs, &scaled_fonts))
+    }
+
+    /// Splits `sections` in the sections laid out by the glyph brush, each with a single font.
+    fn split_sections<'a>(
+        &mut self,
+        fonts: &'a Assets<Font>,
+        font_fallbacks: &'a FontFallbacks,
+        sections: &'a [TextSection],
+        scale_factor: f64,
+    ) -> Result<SplitSections<'a>, TextError> {
+        let fallbacks = font_fallbacks.loaded(fonts);
+        let mut split = SplitSections::default();
+        for (section_index, section) in sections.iter().enumerate() {
+            let font = fonts
+                .get(&section.style.font)
+                .ok_or(TextError::NoSuchFont)?;
+            let font_size = scale_value(section.style.font_size, scale_factor);
+            let outline = section.style.outline.map(|outline| TextOutline {
+                width: scale_value(outline.width, scale_factor),
+                ..outline
+            });
+            let shadow = section.style.shadow.map(|shadow| TextShadow {
+                offset: shadow.offset * scale_factor as f32,
+                blur: scale_value(shadow.blur, scale_factor),
+                ..shadow
+            });
+
+            // The characters missing from the font are laid out in sections of their fallbacks
+            let runs = if fallbacks.is_empty() {
+                vec![(None, 0..section.value.len())]
+            } else {
+                split_font_runs(&section.value, font, &fallbacks)
+            };
+            for (fallback, range) in runs {
+                let (handle, font) = match fallback {
+                    Some(index) => (&fallbacks[index].0.font, fallbacks[index].1),
+                    None => (&section.style.font, font),
+                };
+                let font_id = self.get_or_insert_spaced_font_id(
+                    handle,
+                    font,
+                    font_size,
+                    scale_value(section.style.letter_spacing, scale_factor),
+                    scale_value(section.style.word_spacing, scale_factor),
+                );
+
+                split
+                    .scaled_fonts
+                    .push(ab_glyph::Font::as_scaled(&font.font, font_size));
+
+                split.section_sources.push((section_index, range.start));
+                split.section_effects.push((outline, shadow));
+                split.section_texts.push(SectionText {
+                    font_id,
+                    scale: PxScale::from(font_size),
+                    text: &section.value[range],
+                });
+            }
+        }
+        Ok(split)
+    }
+}
+
+/// The sections laid out by the glyph brush, split from the sections of a text.
+#[derive(Default)]
+struct SplitSections<'a> {
+    section_texts: Vec<SectionText<'a>>,
+    scaled_fonts: Vec<PxScaleFont<&'a FontArc>>,
+    /// The text section each laid out section comes from, with its byte offset in the value
+    section_sources: Vec<(usize, usize)>,
+    section_effects: Vec<(Option<TextOutline>, Option<TextShadow>)>,
+}
+
+/// The size of the laid out `section_glyphs`, from the ascent and descent of their fonts.
+fn laid_out_size(section_glyphs: &[SectionGlyph], scaled_fonts: &[PxScaleFont<&FontArc>]) -> Vec2 {
+    let mut min_x: f32 = std::f32::MAX;
+    let mut min_y: f32 = std::f32::MAX;
+    let mut max_x: f32 = std::f32::MIN;
+    let mut max_y: f32 = std::f32::MIN;
+
+    for sg in section_glyphs {
+        let scaled_font = scaled_fonts[sg.section_index];
+        let glyph = &sg.glyph;
+        min_x = min_x.min(glyph.position.x);
+        min_y = min_y.min(glyph.position.y - scaled_font.ascent());
+        max_x = max_x.max(glyph.position.x + scaled_font.h_advance(glyph.id));
+        max_y = max_y.max(glyph.position.y - scaled_font.descent());
+    }
+
+    Vec2::new(max_x - min_x, max_y - min_y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_font;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+
+    /// Lays out `sections` as UI text at a scale factor of 1, in unbounded space
+    fn layout(sections: impl Fn(Handle<Font>) -> Vec<TextSection>) -> TextLayoutInfo {
+        let mut app = App::new();
+        app.add_plugin(AssetPlugin::default())
+            .add_asset::<Font>()
+            .add_asset::<FontAtlasSet>()
+            .add_asset::<TextureAtlas>()
+            .add_asset::<Image>();
+        let world = &mut app.world;
+        let mut fonts = world.remove_resource::<Assets<Font>>().unwrap();
+        let mut font_atlas_sets = world.remove_resource::<Assets<FontAtlasSet>>().unwrap();
+        let mut texture_atlases = world.remove_resource::<Assets<TextureAtlas>>().unwrap();
+        let mut images = world.remove_resource::<Assets<Image>>().unwrap();
+
+        let font = fonts.add(test_font());
+        TextPipeline::default()
+            .queue_text(
+                &fonts,
+                &FontFallbacks::default(),
+                &sections(font),
+                1.0,
+                TextAlignment::Left,
+                BreakLineOn::WordBoundary,
+                TextDirection::Auto,
+                TextOverflow::default(),
+                Vec2::splat(f32::INFINITY),
+                &mut font_atlas_sets,
+                &mut texture_atlases,
+                &mut images,
+                &TextSettings::default(),
+                &mut FontAtlasWarning::default(),
+                YAxisOrientation::TopToBottom,
+                GlyphAtlasMode::Bitmap,
+            )
+            .unwrap()
+    }
+
+    fn section(value: &str, font: &Handle<Font>) -> TextSection {
+        TextSection::new(
+            value,
+            crate::TextStyle {
+                font: font.clone(),
+                font_size: 20.0,
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn glyph_byte_ranges() {
+        let layout_info = layout(|font| {
+            vec![
+                section("aé ü", &font),
+                section("€b", &font),
+                section("\nc", &font),
+            ]
+        });
+        let ranges: Vec<_> = layout_info
+            .fill_glyphs()
+            .map(|glyph| {
+                (
+                    glyph.section_index,
+                    glyph.byte_index,
+                    glyph.byte_range.clone(),
+                )
+            })
+            .collect();
+        // the whitespaces aren't drawn
+        assert_eq!(
+            ranges,
+            [
+                (0, 0, 0..1),
+                (0, 1, 1..3),
+                (0, 4, 4..6),
+                (1, 0, 0..3),
+                (1, 3, 3..4),
+                (2, 1, 1..2),
+            ]
+        );
+    }
+
+    #[test]
+    fn shaped_glyph_byte_ranges() {
+        let layout_info = layout(|font| vec![section("e\u{301} x\u{301}\u{302}", &font)]);
+        let ranges: Vec<_> = layout_info
+            .fill_glyphs()
+            .map(|glyph| (glyph.byte_index, glyph.byte_range.clone()))
+            .collect();
+        // the glyphs of a cluster draw all its characters
+        assert_eq!(ranges, [(0, 0..3), (4, 4..9), (4, 4..9), (4, 4..9)]);
+    }
+
+    #[test]
+    fn glyph_hit_testing() {
+        let layout_info = layout(|font| vec![section("ab cd\né", &font)]);
+        let glyphs: Vec<_> = layout_info.fill_glyphs().cloned().collect();
+        assert_eq!(glyphs.len(), 5);
+
+        // each glyph contains its center and the points inside of its bounds
+        for glyph in &glyphs {
+            for offset in [Vec2::ZERO, glyph.size * 0.49, glyph.size * -0.49] {
+                let hit = layout_info.glyph_at(glyph.position + offset).unwrap();
+                assert_eq!(hit.byte_range, glyph.byte_range);
+            }
+        }
+
+        // the space between `b` and `c` has no glyph
+        let (b, c) = (&glyphs[1], &glyphs[2]);
+        let space = Vec2::new(
+            (b.position.x + b.size.x / 2.0 + c.position.x - c.size.x / 2.0) / 2.0,
+            b.position.y,
+        );
+        assert!(layout_info.glyph_at(space).is_none());
+        // nor the points outside of the text
+        assert!(layout_info.glyph_at(Vec2::new(-10.0, -10.0)).is_none());
+        assert!(layout_info.glyph_at(layout_info.size + 10.0).is_none());
+
+        // the glyph of the second line is below the first one
+        let e = &glyphs[4];
+        assert_eq!(e.byte_range, 6..8);
+        assert!(e.position.y - e.size.y / 2.0 > b.position.y + b.size.y / 2.0);
+    }
+
+    #[test]
+    fn outlined_glyphs_hit_their_fill() {
+        let layout_info = layout(|font| {
+            let mut section = section("ab", &font);
+            section.style.outline = Some(TextOutline::default());
+            vec![section]
+        });
+        assert_eq!(layout_info.glyphs.len(), 4);
+        let fill: Vec<_> = layout_info.fill_glyphs().cloned().collect();
+        assert_eq!(fill.len(), 2);
+        for glyph in &fill {
+            let hit = layout_info.glyph_at(glyph.position).unwrap();
+            assert_eq!(hit.layer, GlyphLayer::Fill);
+            assert_eq!(hit.byte_range, glyph.byte_range);
+        }
+    }
 }
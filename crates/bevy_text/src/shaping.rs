@@ -0,0 +1,553 @@
+//! Shaping of the text of the complex scripts with [`rustybuzz`], like the joined letters of
+//! the Arabic script or the reordered vowels of the Indic scripts.
+//!
+//! Each run of characters of a section at the same bidirectional level is shaped in clusters of
+//! glyphs, each cluster drawing one or more characters: a ligature, or a letter with its marks.
+//! The clusters are then wrapped and aligned in lines like `glyph_brush_layout` lays out the
+//! characters, the lines only being broken between two clusters, and reordered by their levels.
+
+use std::ops::Range;
+
+use ab_glyph::{point, Font as _, FontArc, Glyph, GlyphId, ScaleFont as _};
+use bevy_utils::HashMap;
+use glyph_brush_layout::{FontId, LineBreak, LineBreaker, SectionGlyph, SectionText};
+use rustybuzz::{Direction, Face, UnicodeBuffer};
+
+use crate::{bidi, TextAlignment, TextDirection};
+
+/// Whether `text` has characters that are drawn wrong without being shaped: the letters of the
+/// scripts joining or reordering them, and the marks combined with the letters before them.
+///
+/// The other text is laid out one character at a time, which is faster.
+pub(crate) fn needs_shaping(text: &str) -> bool {
+    text.chars().any(|c| {
+        matches!(c,
+            // combining marks
+            '\u{300}'..='\u{36F}'
+            | '\u{483}'..='\u{489}'
+            | '\u{591}'..='\u{5C7}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE20}'..='\u{FE2F}'
+            // Arabic, Syriac, Thaana, N'Ko and the other scripts of the Middle East
+            | '\u{600}'..='\u{8FF}'
+            // the Indic scripts, and the scripts of Southeast Asia
+            | '\u{900}'..='\u{109F}'
+            | '\u{1700}'..='\u{18AF}'
+            | '\u{1900}'..='\u{1CFF}'
+            | '\u{A800}'..='\u{A9FF}'
+            | '\u{AA00}'..='\u{AAFF}'
+            // the joiners, and the variation selectors
+            | '\u{200C}'
+            | '\u{200D}'
+            | '\u{FE00}'..='\u{FE0F}'
+        )
+    })
+}
+
+/// The glyphs of the shaped text, see [`layout_shaped`].
+pub(crate) struct ShapedText {
+    /// The glyphs of the clusters, in the order of their characters.
+    pub(crate) glyphs: Vec<SectionGlyph>,
+    /// The byte index of the first character of each cluster, for each section, ending with the
+    /// length of its text.
+    pub(crate) cluster_starts: Vec<Vec<usize>>,
+}
+
+/// A cluster of the glyphs drawing one or more characters together.
+struct Cluster {
+    section_index: usize,
+    font_id: FontId,
+    /// The bytes of the characters of the cluster in the text of its section.
+    byte_range: Range<usize>,
+    level: u8,
+    /// The glyphs of the cluster, positioned from its origin.
+    glyphs: Vec<Glyph>,
+    advance: f32,
+    /// Whether the characters of the cluster are all whitespaces.
+    whitespace: bool,
+    /// The line break after the cluster.
+    line_break: Option<LineBreak>,
+}
+
+/// The vertical metrics of the fonts of a word or of a line.
+#[derive(Debug, Clone, Copy, Default)]
+struct VMetrics {
+    ascent: f32,
+    descent: f32,
+    line_gap: f32,
+}
+
+impl VMetrics {
+    fn height(&self) -> f32 {
+        self.ascent - self.descent + self.line_gap
+    }
+
+    fn max(self, other: Self) -> Self {
+        if other.height() > self.height() {
+            other
+        } else {
+            self
+        }
+    }
+}
+
+/// The clusters up to a line break.
+struct Word {
+    clusters: Range<usize>,
+    width: f32,
+    /// The width of the word without its trailing whitespaces.
+    width_no_trail: f32,
+    v_metrics: VMetrics,
+    hard_break: bool,
+}
+
+/// Shapes `sections` and lays out their clusters in lines of up to `bounds.0` pixels, broken
+/// by the `line_breaker`, and positioned from the top left corner like `glyph_brush_layout`.
+///
+/// Returns `None` when a font can't be shaped.
+pub(crate) fn layout_shaped<L: LineBreaker>(
+    fonts: &[FontArc],
+    sections: &[SectionText],
+    bounds: (f32, f32),
+    text_alignment: TextAlignment,
+    line_breaker: L,
+    direction: TextDirection,
+) -> Option<ShapedText> {
+    let (levels, paragraph_levels) = bidi::resolve_section_levels(sections, direction);
+    let mut faces: HashMap<usize, Face> = HashMap::default();
+    let mut clusters = Vec::new();
+    let mut cluster_starts = Vec::new();
+    for (section_index, section) in sections.iter().enumerate() {
+        let section_clusters = clusters.len();
+        if section.scale.x > 0.0 && section.scale.y > 0.0 && !section.text.is_empty() {
+            let font = &fonts[section.font_id.0];
+            if !faces.contains_key(&section.font_id.0) {
+                faces.insert(section.font_id.0, Face::from_slice(font.font_data(), 0)?);
+            }
+            let face = &faces[&section.font_id.0];
+
+            let mut run_start = 0;
+            let mut chars = section.text.char_indices().peekable();
+            while let Some((byte_index, _)) = chars.next() {
+                let level = levels[section_index][byte_index];
+                let run_end = chars.peek().map_or(section.text.len(), |(next, _)| *next);
+                if run_end < section.text.len() && levels[section_index][run_end] == level {
+                    continue;
+                }
+                shape_run(
+                    face,
+                    font,
+                    section,
+                    section_index,
+                    run_start..run_end,
+                    level,
+                    &mut clusters,
+                );
+                run_start = run_end;
+            }
+
+            let mut line_breaks = line_breaker.line_breaks(section.text).peekable();
+            for cluster in &mut clusters[section_clusters..] {
+                while line_breaks.peek().map_or(false, |line_break| {
+                    line_break.offset() < cluster.byte_range.end
+                }) {
+                    line_breaks.next();
+                }
+                cluster.line_break = line_breaks
+                    .peek()
+                    .copied()
+                    .filter(|line_break| line_break.offset() == cluster.byte_range.end);
+            }
+            // The end of the text only breaks the line after the characters breaking it
+            if let (Some(cluster), Some(last)) =
+                (clusters.last_mut(), section.text.chars().next_back())
+            {
+                cluster.line_break = end_line_break(last, &line_breaker);
+            }
+        }
+        let mut starts: Vec<usize> = clusters[section_clusters..]
+            .iter()
+            .map(|cluster| cluster.byte_range.start)
+            .collect();
+        starts.push(section.text.len());
+        cluster_starts.push(starts);
+    }
+
+    let words = words(&clusters, fonts, sections);
+    let mut glyphs = Vec::new();
+    let mut line_top = 0.0;
+    let mut words = words.into_iter().peekable();
+    while words.peek().is_some() {
+        if line_top >= bounds.1 {
+            break;
+        }
+        let (line, v_metrics, rightmost) = next_line(&mut words, &clusters, bounds.0);
+        let shift = match text_alignment {
+            TextAlignment::Left => 0.0,
+            TextAlignment::Center => rightmost / 2.0,
+            TextAlignment::Right => rightmost,
+        };
+
+        // L1: the whitespaces at the end of the line take the level of the paragraph
+        let line_clusters = &clusters[line];
+        let mut line_levels: Vec<u8> = line_clusters.iter().map(|cluster| cluster.level).collect();
+        for (cluster, level) in line_clusters.iter().zip(&mut line_levels).rev() {
+            if !cluster.whitespace && !cluster.glyphs.is_empty() {
+                break;
+            }
+            *level = paragraph_levels[cluster.section_index][cluster.byte_range.start];
+        }
+        let mut origins = vec![0.0; line_clusters.len()];
+        let mut x = -shift;
+        for index in bidi::visual_order(&line_levels) {
+            origins[index] = x;
+            x += line_clusters[index].advance;
+        }
+
+        let baseline = line_top + v_metrics.ascent;
+        for (cluster, origin) in line_clusters.iter().zip(origins) {
+            glyphs.extend(cluster.glyphs.iter().map(|glyph| {
+                let mut glyph = glyph.clone();
+                glyph.position = point(glyph.position.x + origin, glyph.position.y + baseline);
+                SectionGlyph {
+                    section_index: cluster.section_index,
+                    byte_index: cluster.byte_range.start,
+                    glyph,
+                    font_id: cluster.font_id,
+                }
+            }));
+        }
+        line_top += v_metrics.height();
+    }
+
+    Some(ShapedText {
+        glyphs,
+        cluster_starts,
+    })
+}
+
+/// Shapes the `run` of characters of the `section` at the bidirectional `level`, and adds its
+/// clusters to `clusters` in the order of their characters.
+fn shape_run(
+    face: &Face,
+    font: &FontArc,
+    section: &SectionText,
+    section_index: usize,
+    run: Range<usize>,
+    level: u8,
+    clusters: &mut Vec<Cluster>,
+) {
+    let text = section.text;
+    let mut buffer = UnicodeBuffer::new();
+    buffer.push_str(&text[run.clone()]);
+    buffer.set_pre_context(&text[..run.start]);
+    buffer.set_post_context(&text[run.end..]);
+    buffer.set_direction(if level % 2 == 1 {
+        Direction::RightToLeft
+    } else {
+        Direction::LeftToRight
+    });
+    buffer.guess_segment_properties();
+    let shaped = rustybuzz::shape(face, &[], buffer);
+
+    let scaled_font = font.as_scaled(section.scale);
+    let (h_scale, v_scale) = (scaled_font.h_scale_factor(), scaled_font.v_scale_factor());
+    // The glyphs of a right-to-left run are shaped from its end
+    let run_clusters = clusters.len();
+    for (info, position) in shaped.glyph_infos().iter().zip(shaped.glyph_positions()) {
+        let start = run.start + info.cluster as usize;
+        if clusters[run_clusters..]
+            .last()
+            .map_or(true, |cluster| cluster.byte_range.start != start)
+        {
+            clusters.push(Cluster {
+                section_index,
+                font_id: section.font_id,
+                byte_range: start..start,
+                level,
+                glyphs: Vec::new(),
+                advance: 0.0,
+                whitespace: false,
+                line_break: None,
+            });
+        }
+        let cluster = clusters.last_mut().unwrap();
+        let id = GlyphId(info.glyph_id as u16);
+        // The spaced fonts add their letter and word spacings to the advances of the glyphs
+        let spacing = if position.x_advance == 0 {
+            0.0
+        } else {
+            let advance = face.glyph_hor_advance(rustybuzz::ttf_parser::GlyphId(id.0));
+            font.h_advance_unscaled(id) - advance.unwrap_or_default() as f32
+        };
+        cluster.glyphs.push(Glyph {
+            id,
+            scale: section.scale,
+            position: point(
+                cluster.advance + position.x_offset as f32 * h_scale,
+                -position.y_offset as f32 * v_scale,
+            ),
+        });
+        cluster.advance += (position.x_advance as f32 + spacing) * h_scale;
+    }
+    if level % 2 == 1 {
+        clusters[run_clusters..].reverse();
+    }
+
+    let starts: Vec<usize> = clusters[run_clusters..]
+        .iter()
+        .map(|cluster| cluster.byte_range.start)
+        .skip(1)
+        .chain(Some(run.end))
+        .collect();
+    for (cluster, end) in clusters[run_clusters..].iter_mut().zip(starts) {
+        cluster.byte_range.end = end;
+        let characters = &text[cluster.byte_range.clone()];
+        cluster.whitespace = characters.chars().all(char::is_whitespace);
+        // The control characters like the line breaks aren't drawn
+        if characters.chars().all(char::is_control) {
+            cluster.glyphs.clear();
+            cluster.advance = 0.0;
+        }
+    }
+}
+
+/// The line break after the character `last` ending a section, that only breaks the line when
+/// the character itself breaks it, like a space or a line feed, as in `glyph_brush_layout`.
+fn end_line_break<L: LineBreaker>(last: char, line_breaker: &L) -> Option<LineBreak> {
+    [' ', 'a'].into_iter().find_map(|next| {
+        let padded = format!("{last}{next}");
+        let line_break = line_breaker.line_breaks(&padded).next();
+        line_break.filter(|line_break| line_break.offset() == last.len_utf8())
+    })
+}
+
+/// Groups the `clusters` in words ending at their line breaks.
+fn words(clusters: &[Cluster], fonts: &[FontArc], sections: &[SectionText]) -> Vec<Word> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut width = 0.0;
+    let mut width_no_trail = 0.0;
+    let mut v_metrics = VMetrics::default();
+    for (index, cluster) in clusters.iter().enumerate() {
+        let section = &sections[cluster.section_index];
+        let scaled_font = fonts[cluster.font_id.0].as_scaled(section.scale);
+        v_metrics = v_metrics.max(VMetrics {
+            ascent: scaled_font.ascent(),
+            descent: scaled_font.descent(),
+            line_gap: scaled_font.line_gap(),
+        });
+        width += cluster.advance;
+        if !cluster.whitespace {
+            width_no_trail = width;
+        }
+
+        let is_last = index + 1 == clusters.len();
+        if cluster.line_break.is_none() && !is_last {
+            continue;
+        }
+        words.push(Word {
+            clusters: start..index + 1,
+            width,
+            width_no_trail,
+            v_metrics,
+            // The end of the text is a hard break
+            hard_break: matches!(cluster.line_break, Some(LineBreak::Hard(_)))
+                || (is_last && cluster.line_break.is_some()),
+        });
+        start = index + 1;
+        width = 0.0;
+        width_no_trail = 0.0;
+        v_metrics = VMetrics::default();
+    }
+    words
+}
+
+/// Takes the words of the next line from `words`, as wide as `max_width` unless its first word
+/// is wider, and returns its clusters, its metrics and its width without its trailing
+/// whitespaces.
+fn next_line(
+    words: &mut std::iter::Peekable<std::vec::IntoIter<Word>>,
+    clusters: &[Cluster],
+    max_width: f32,
+) -> (Range<usize>, VMetrics, f32) {
+    let mut line: Option<Range<usize>> = None;
+    let mut v_metrics = VMetrics::default();
+    let mut has_glyphs = false;
+    let mut x = 0.0;
+    let mut rightmost = 0.0;
+    while let Some(word) = words.peek() {
+        // The trailing whitespaces may run out of bounds, unless the line ends after the word
+        let word_right = x + if word.hard_break {
+            word.width
+        } else {
+            word.width_no_trail
+        };
+        let in_bounds = word_right <= max_width
+            || (word_right - max_width).abs() <= f32::EPSILON * word_right.abs();
+        if !in_bounds && line.is_some() {
+            break;
+        }
+
+        let word = words.next().unwrap();
+        rightmost = word_right;
+        let word_has_glyphs = clusters[word.clusters.clone()]
+            .iter()
+            .any(|cluster| !cluster.glyphs.is_empty());
+        if (!has_glyphs || word_has_glyphs) && word.v_metrics.height() > v_metrics.height() {
+            v_metrics = word.v_metrics;
+        }
+        has_glyphs |= word_has_glyphs;
+        line = Some(line.map_or(word.clusters.clone(), |line| line.start..word.clusters.end));
+        x += word.width;
+        if word.hard_break {
+            break;
+        }
+    }
+    (line.unwrap_or_default(), v_metrics, rightmost)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{glyph_brush::GlyphBrush, test_font, BreakLineOn, TextOverflow};
+    use ab_glyph::PxScale;
+    use bevy_asset::Handle;
+    use bevy_math::Vec2;
+
+    /// Lays out `text` with `font` in lines of up to `width` pixels, and returns the byte index,
+    /// the id and the position of each glyph
+    fn layout(
+        font: FontArc,
+        text: &str,
+        width: f32,
+        linebreak_behaviour: BreakLineOn,
+        direction: TextDirection,
+    ) -> Vec<(usize, GlyphId, f32, f32)> {
+        let mut brush = GlyphBrush::default();
+        let font_id = brush.add_font(Handle::default(), font);
+        let sections = [SectionText {
+            text,
+            scale: PxScale::from(20.0),
+            font_id,
+        }];
+        brush
+            .compute_glyphs(
+                &sections,
+                Vec2::new(width, f32::INFINITY),
+                TextAlignment::Left,
+                linebreak_behaviour,
+                direction,
+                TextOverflow::default(),
+            )
+            .unwrap()
+            .into_iter()
+            .map(|glyph| {
+                let position = glyph.glyph.position;
+                (glyph.byte_index, glyph.glyph.id, position.x, position.y)
+            })
+            .collect()
+    }
+
+    fn fira_sans() -> FontArc {
+        FontArc::try_from_slice(include_bytes!("../../../assets/fonts/FiraSans-Bold.ttf")).unwrap()
+    }
+
+    #[test]
+    fn only_complex_text_is_shaped() {
+        assert!(!needs_shaping("Hello, world!"));
+        assert!(!needs_shaping("éàü €"));
+        assert!(needs_shaping("e\u{301}"));
+        assert!(needs_shaping("بيت"));
+        assert!(needs_shaping("नमस्ते"));
+    }
+
+    #[test]
+    fn characters_are_drawn_in_clusters() {
+        let font = fira_sans();
+        // the letter and its combining mark are drawn with the composed letter, and the `fi`
+        // ligature of the font is drawn for the letters after it
+        let glyphs = layout(
+            font.clone(),
+            "e\u{301}fi",
+            f32::INFINITY,
+            BreakLineOn::WordBoundary,
+            TextDirection::Auto,
+        );
+        let ids: Vec<_> = glyphs
+            .iter()
+            .map(|&(byte_index, id, ..)| (byte_index, id))
+            .collect();
+        assert_eq!(ids[0], (0, font.glyph_id('é')));
+        assert_eq!(ids.len(), 2);
+        assert_eq!(ids[1].0, 3);
+        assert_ne!(ids[1].1, font.glyph_id('f'));
+
+        // the letters without a ligature keep their glyphs
+        let glyphs = layout(
+            font.clone(),
+            "e\u{301}xy",
+            f32::INFINITY,
+            BreakLineOn::WordBoundary,
+            TextDirection::Auto,
+        );
+        let ids: Vec<_> = glyphs
+            .iter()
+            .map(|&(byte_index, id, ..)| (byte_index, id))
+            .collect();
+        assert_eq!(
+            ids,
+            [
+                (0, font.glyph_id('é')),
+                (3, font.glyph_id('x')),
+                (4, font.glyph_id('y'))
+            ]
+        );
+    }
+
+    #[test]
+    fn lines_are_broken_between_clusters() {
+        // each character can start a line, but the marks stay on the line of their letter
+        let glyphs = layout(
+            test_font().font,
+            "x\u{301}\u{302}y",
+            1.0,
+            BreakLineOn::AnyCharacter,
+            TextDirection::Auto,
+        );
+        assert_eq!(glyphs.len(), 4);
+        let lines: Vec<_> = glyphs
+            .iter()
+            .map(|&(byte_index, _, _, y)| (byte_index, y))
+            .collect();
+        assert!(lines[..3].iter().all(|&line| line == (0, lines[0].1)));
+        assert_eq!(lines[3].0, 5);
+        assert!(lines[3].1 > lines[0].1);
+        // which starts at the left of the line
+        assert_eq!(glyphs[3].2, 0.0);
+    }
+
+    #[test]
+    fn right_to_left_clusters_are_reordered() {
+        // the second letter and its mark are drawn at the left of the first letter
+        let glyphs = layout(
+            test_font().font,
+            "אב\u{5B0}",
+            f32::INFINITY,
+            BreakLineOn::WordBoundary,
+            TextDirection::Auto,
+        );
+        let x = |byte_index| {
+            glyphs
+                .iter()
+                .filter(|glyph| glyph.0 == byte_index)
+                .map(|glyph| glyph.2)
+                .fold(f32::MAX, f32::min)
+        };
+        assert_eq!(glyphs.iter().filter(|glyph| glyph.0 == 2).count(), 2);
+        assert!(x(2) < x(0));
+        assert_eq!(x(2), 0.0);
+    }
+}
@@ -0,0 +1,107 @@
+use ab_glyph::{v2, CodepointIdIter, Font as _, FontArc, GlyphId, GlyphSvg, Outline};
+
+/// A font laid out with more space after each character and after each space character, see
+/// [`TextStyle::letter_spacing`](crate::TextStyle::letter_spacing).
+///
+/// Only the advances of the glyphs change, they're drawn from the same outlines.
+pub(crate) struct SpacedFont {
+    pub(crate) font: FontArc,
+    /// The space added after each glyph, in font units.
+    pub(crate) letter_spacing: f32,
+    /// The space added after the space glyph, in font units.
+    pub(crate) word_spacing: f32,
+    pub(crate) space_id: GlyphId,
+}
+
+impl SpacedFont {
+    /// Converts the spacings from pixels at `font_size` to the units of the `font`.
+    pub(crate) fn new(
+        font: FontArc,
+        font_size: f32,
+        letter_spacing: f32,
+        word_spacing: f32,
+    ) -> Self {
+        let units_per_pixel = font.height_unscaled() / font_size;
+        SpacedFont {
+            space_id: font.glyph_id(' '),
+            letter_spacing: letter_spacing * units_per_pixel,
+            word_spacing: word_spacing * units_per_pixel,
+            font,
+        }
+    }
+}
+
+impl ab_glyph::Font for SpacedFont {
+    fn units_per_em(&self) -> Option<f32> {
+        self.font.units_per_em()
+    }
+
+    fn ascent_unscaled(&self) -> f32 {
+        self.font.ascent_unscaled()
+    }
+
+    fn descent_unscaled(&self) -> f32 {
+        self.font.descent_unscaled()
+    }
+
+    fn line_gap_unscaled(&self) -> f32 {
+        self.font.line_gap_unscaled()
+    }
+
+    fn italic_angle(&self) -> f32 {
+        self.font.italic_angle()
+    }
+
+    fn glyph_id(&self, c: char) -> GlyphId {
+        self.font.glyph_id(c)
+    }
+
+    fn h_advance_unscaled(&self, id: GlyphId) -> f32 {
+        let word_spacing = if id == self.space_id {
+            self.word_spacing
+        } else {
+            0.0
+        };
+        self.font.h_advance_unscaled(id) + self.letter_spacing + word_spacing
+    }
+
+    fn h_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
+        self.font.h_side_bearing_unscaled(id)
+    }
+
+    fn v_advance_unscaled(&self, id: GlyphId) -> f32 {
+        self.font.v_advance_unscaled(id)
+    }
+
+    fn v_side_bearing_unscaled(&self, id: GlyphId) -> f32 {
+        self.font.v_side_bearing_unscaled(id)
+    }
+
+    fn kern_unscaled(&self, first: GlyphId, second: GlyphId) -> f32 {
+        self.font.kern_unscaled(first, second)
+    }
+
+    fn outline(&self, id: GlyphId) -> Option<Outline> {
+        self.font.outline(id)
+    }
+
+    fn glyph_count(&self) -> usize {
+        self.font.glyph_count()
+    }
+
+    fn codepoint_ids(&self) -> CodepointIdIter<'_> {
+        self.font.codepoint_ids()
+    }
+
+    fn glyph_raster_image2(&self, id: GlyphId, pixel_size: u16) -> Option<v2::GlyphImage<'_>> {
+        self.font.glyph_raster_image2(id, pixel_size)
+    }
+
+    fn glyph_svg_image(&self, id: GlyphId) -> Option<GlyphSvg<'_>> {
+        self.font.glyph_svg_image(id)
+    }
+
+    fn font_data(&self) -> &[u8] {
+        self.font.font_data()
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use ab_glyph::{FontArc, FontVec};
+use ttf_parser::{name_id, Face};
+
+use crate::Font;
+
+impl Font {
+    /// Loads the font of the `family` installed on the system, like `"DejaVu Sans"`, preferring
+    /// its regular face.
+    ///
+    /// The font directories of Linux, macOS and Windows are searched, reading all the fonts in
+    /// them, so this blocks and should only be called once per family, for example to add the
+    /// returned font to the [`FontFallbacks`](crate::FontFallbacks). Returns `None` if no font of
+    /// the family is installed.
+    pub fn from_system_family(family: &str) -> Option<Font> {
+        let mut found = None;
+        for path in system_font_files() {
+            let Ok(data) = std::fs::read(&path) else {
+                continue;
+            };
+            let faces = ttf_parser::fonts_in_collection(&data).unwrap_or(1);
+            for index in 0..faces {
+                let Ok(face) = Face::parse(&data, index) else {
+                    continue;
+                };
+                if !has_name(
+                    &face,
+                    &[name_id::TYPOGRAPHIC_FAMILY, name_id::FAMILY],
+                    family,
+                ) {
+                    continue;
+                }
+                let regular = has_name(&face, &[name_id::SUBFAMILY], "Regular");
+                if regular || found.is_none() {
+                    found = Some((path.clone(), index));
+                }
+                if regular {
+                    return load_face(&path, index);
+                }
+            }
+        }
+        let (path, index) = found?;
+        load_face(&path, index)
+    }
+}
+
+/// Whether `face` has a name with one of the `ids` that is `value`, ignoring the case.
+fn has_name(face: &Face, ids: &[u16], value: &str) -> bool {
+    face.names().into_iter().any(|name| {
+        ids.contains(&name.name_id)
+            && matches!(name.to_string(), Some(name) if name.eq_ignore_ascii_case(value))
+    })
+}
+
+fn load_face(path: &Path, index: u32) -> Option<Font> {
+    let data = std::fs::read(path).ok()?;
+    let font = FontVec::try_from_vec_and_index(data, index).ok()?;
+    Some(Font {
+        font: FontArc::new(font),
+    })
+}
+
+/// The font directories of the current platform. The ones missing are skipped.
+fn system_font_directories() -> Vec<PathBuf> {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    let mut directories = Vec::new();
+    if cfg!(target_os = "windows") {
+        if let Some(windows) = std::env::var_os("WINDIR") {
+            directories.push(PathBuf::from(windows).join("Fonts"));
+        }
+        if let Some(local) = std::env::var_os("LOCALAPPDATA") {
+            directories.push(PathBuf::from(local).join("Microsoft/Windows/Fonts"));
+        }
+    } else if cfg!(target_os = "macos") {
+        directories.push(PathBuf::from("/System/Library/Fonts"));
+        directories.push(PathBuf::from("/Library/Fonts"));
+        directories.extend(home.map(|home| home.join("Library/Fonts")));
+    } else {
+        match std::env::var_os("XDG_DATA_HOME") {
+            Some(data) => directories.push(PathBuf::from(data).join("fonts")),
+            None => directories.extend(home.as_ref().map(|home| home.join(".local/share/fonts"))),
+        }
+        directories.extend(home.map(|home| home.join(".fonts")));
+        directories.push(PathBuf::from("/usr/local/share/fonts"));
+        directories.push(PathBuf::from("/usr/share/fonts"));
+    }
+    directories
+}
+
+/// The font files in the [`system_font_directories`] and their sub-directories.
+fn system_font_files() -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    // Searches the user directories first, as they're popped in reverse order
+    let mut directories = system_font_directories();
+    directories.reverse();
+    while let Some(directory) = directories.pop() {
+        let Ok(entries) = std::fs::read_dir(&directory) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                directories.push(path);
+                continue;
+            }
+            let Some(extension) = path.extension() else {
+                continue;
+            };
+            let extension = extension.to_string_lossy();
+            if ["ttf", "otf", "ttc", "otc"]
+                .iter()
+                .any(|font| extension.eq_ignore_ascii_case(font))
+            {
+                files.push(path);
+            }
+        }
+    }
+    files
+}
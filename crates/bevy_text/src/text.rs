@@ -5,7 +5,7 @@ use bevy_render::color::Color;
 use bevy_utils::default;
 use serde::{Deserialize, Serialize};
 
-use crate::Font;
+use crate::{Font, TextBackground, TextOutline, TextShadow};
 
 #[derive(Component, Debug, Clone, Reflect)]
 #[reflect(Component, Default)]
@@ -16,6 +16,13 @@ pub struct Text {
     pub alignment: TextAlignment,
     /// How the text should linebreak when running out of the bounds determined by max_size
     pub linebreak_behaviour: BreakLineOn,
+    /// The direction of the paragraphs of the text, in which mixed left-to-right and
+    /// right-to-left runs of characters are ordered.
+    pub direction: TextDirection,
+    /// The rectangle drawn behind the text, if any.
+    pub background: Option<TextBackground>,
+    /// How many lines of the text are drawn, and how the cut off lines end.
+    pub overflow: TextOverflow,
 }
 
 impl Default for Text {
@@ -24,6 +31,9 @@ impl Default for Text {
             sections: Default::default(),
             alignment: TextAlignment::Left,
             linebreak_behaviour: BreakLineOn::WordBoundary,
+            direction: TextDirection::Auto,
+            background: None,
+            overflow: TextOverflow::default(),
         }
     }
 }
@@ -46,6 +56,7 @@ impl Text {
     ///         font: font_handle.clone(),
     ///         font_size: 60.0,
     ///         color: Color::WHITE,
+    ///         ..Default::default()
     ///     },
     /// );
     ///
@@ -55,6 +66,7 @@ impl Text {
     ///         font: font_handle,
     ///         font_size: 60.0,
     ///         color: Color::WHITE,
+    ///         ..Default::default()
     ///     },
     /// ) // You can still add an alignment.
     /// .with_alignment(TextAlignment::Center);
@@ -82,6 +94,7 @@ impl Text {
     ///             font: font_handle.clone(),
     ///             font_size: 60.0,
     ///             color: Color::BLUE,
+    ///             ..Default::default()
     ///         },
     ///     ),
     ///     TextSection::new(
@@ -90,6 +103,7 @@ impl Text {
     ///             font: font_handle,
     ///             font_size: 60.0,
     ///             color: Color::RED,
+    ///             ..Default::default()
     ///         },
     ///     ),
     /// ]);
@@ -106,6 +120,30 @@ impl Text {
         self.alignment = alignment;
         self
     }
+
+    /// Returns this [`Text`] with a new [`TextDirection`].
+    pub const fn with_direction(mut self, direction: TextDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// Returns this [`Text`] with a [`TextBackground`] drawn behind it.
+    pub const fn with_background(mut self, background: TextBackground) -> Self {
+        self.background = Some(background);
+        self
+    }
+
+    /// Returns this [`Text`] with a new [`BreakLineOn`].
+    pub const fn with_linebreak_behaviour(mut self, linebreak_behaviour: BreakLineOn) -> Self {
+        self.linebreak_behaviour = linebreak_behaviour;
+        self
+    }
+
+    /// Returns this [`Text`] with a new [`TextOverflow`].
+    pub const fn with_overflow(mut self, overflow: TextOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
 }
 
 #[derive(Debug, Default, Clone, FromReflect, Reflect)]
@@ -157,11 +195,38 @@ impl From<TextAlignment> for glyph_brush_layout::HorizontalAlign {
     }
 }
 
+/// The direction of the paragraphs of a [`Text`].
+///
+/// The runs of characters written in the other direction are reversed within the lines of the
+/// paragraph, like a Latin name in an Arabic sentence. The direction doesn't change the
+/// [`TextAlignment`] of the text.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub enum TextDirection {
+    /// Each paragraph takes the direction of its first letter, or is left-to-right when it has
+    /// none.
+    #[default]
+    Auto,
+    /// The paragraphs are left-to-right, like English.
+    LeftToRight,
+    /// The paragraphs are right-to-left, like Arabic or Hebrew.
+    RightToLeft,
+}
+
 #[derive(Clone, Debug, Reflect, FromReflect)]
 pub struct TextStyle {
     pub font: Handle<Font>,
     pub font_size: f32,
     pub color: Color,
+    /// The outline drawn around the glyphs, if any.
+    pub outline: Option<TextOutline>,
+    /// The shadow drawn below the glyphs, if any.
+    pub shadow: Option<TextShadow>,
+    /// The space added after each character, in logical pixels, or removed when negative.
+    pub letter_spacing: f32,
+    /// The space added after each space character, on top of the `letter_spacing`, in logical
+    /// pixels.
+    pub word_spacing: f32,
 }
 
 impl Default for TextStyle {
@@ -170,6 +235,10 @@ impl Default for TextStyle {
             font: Default::default(),
             font_size: 12.0,
             color: Color::WHITE,
+            outline: None,
+            shadow: None,
+            letter_spacing: 0.0,
+            word_spacing: 0.0,
         }
     }
 }
@@ -186,13 +255,44 @@ pub enum BreakLineOn {
     /// This is closer to the behaviour one might expect from text in a terminal.
     /// However it may lead to words being broken up across linebreaks.
     AnyCharacter,
+    /// Lines are only broken on the line breaks of the text, like `\n`, and run out of bounds
+    /// when they're too long, unless they're cut off by a [`TextOverflow`].
+    NoWrap,
 }
 
 impl From<BreakLineOn> for glyph_brush_layout::BuiltInLineBreaker {
     fn from(val: BreakLineOn) -> Self {
         match val {
-            BreakLineOn::WordBoundary => glyph_brush_layout::BuiltInLineBreaker::UnicodeLineBreaker,
+            // The lines that aren't wrapped are laid out in unbounded widths, where only the
+            // hard breaks are taken
+            BreakLineOn::WordBoundary | BreakLineOn::NoWrap => {
+                glyph_brush_layout::BuiltInLineBreaker::UnicodeLineBreaker
+            }
             BreakLineOn::AnyCharacter => glyph_brush_layout::BuiltInLineBreaker::AnyCharLineBreaker,
         }
     }
 }
+
+/// Cuts off the lines of a [`Text`] after a number of lines, or when they run out of bounds.
+///
+/// ```
+/// # use bevy_text::{BreakLineOn, Text, TextOverflow, TextStyle};
+/// // A file name ending in "…" when it's too long for its bounds
+/// let file_name = Text::from_section("a_very_long_file_name.png", TextStyle::default())
+///     .with_linebreak_behaviour(BreakLineOn::NoWrap)
+///     .with_overflow(TextOverflow {
+///         max_lines: Some(1),
+///         ellipsis: true,
+///     });
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Reflect, Serialize, Deserialize)]
+#[reflect(Serialize, Deserialize)]
+pub struct TextOverflow {
+    /// The number of lines drawn, the lines after them being cut off, or `None` to draw all the
+    /// lines.
+    pub max_lines: Option<usize>,
+    /// Whether the last line drawn ends in an ellipsis, "…", when the lines after it are cut
+    /// off. The lines longer than the bounds of the text, with [`BreakLineOn::NoWrap`] or with
+    /// words too long to be wrapped, are also shortened to end in an ellipsis in bounds.
+    pub ellipsis: bool,
+}
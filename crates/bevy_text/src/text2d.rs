@@ -5,26 +5,27 @@ use bevy_ecs::{
     component::Component,
     entity::Entity,
     event::EventReader,
-    prelude::With,
+    prelude::{With, Without},
     reflect::ReflectComponent,
     system::{Commands, Local, Query, Res, ResMut},
 };
 use bevy_math::{Vec2, Vec3};
 use bevy_reflect::Reflect;
 use bevy_render::{
-    prelude::Color,
-    texture::Image,
-    view::{ComputedVisibility, Visibility},
+    texture::{Image, DEFAULT_IMAGE_HANDLE},
+    view::{InheritedVisibility, ViewVisibility, Visibility},
     Extract,
 };
-use bevy_sprite::{Anchor, ExtractedSprite, ExtractedSprites, TextureAtlas};
+use bevy_sprite::{
+    Anchor, ExtractedSprite, ExtractedSprites, ImageScaleMode, SortMode2d, TextureAtlas,
+};
 use bevy_transform::prelude::{GlobalTransform, Transform};
 use bevy_utils::HashSet;
 use bevy_window::{PrimaryWindow, Window, WindowScaleFactorChanged};
 
 use crate::{
-    Font, FontAtlasSet, FontAtlasWarning, Text, TextError, TextLayoutInfo, TextPipeline,
-    TextSettings, YAxisOrientation,
+    Font, FontAtlasSet, FontAtlasWarning, FontFallbacks, GlyphAtlasMode, GlyphLayer, Text, Text3d,
+    TextError, TextLayoutInfo, TextPipeline, TextSettings, YAxisOrientation,
 };
 
 /// The maximum width and height of text. The text will wrap according to the specified size.
@@ -70,8 +71,10 @@ pub struct Text2dBundle {
     pub global_transform: GlobalTransform,
     /// The visibility properties of the text.
     pub visibility: Visibility,
+    /// The inherited visibility of the text.
+    pub inherited_visibility: InheritedVisibility,
     /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering.
-    pub computed_visibility: ComputedVisibility,
+    pub view_visibility: ViewVisibility,
 }
 
 pub fn extract_text2d_sprite(
@@ -79,14 +82,18 @@ pub fn extract_text2d_sprite(
     texture_atlases: Extract<Res<Assets<TextureAtlas>>>,
     windows: Extract<Query<&Window, With<PrimaryWindow>>>,
     text2d_query: Extract<
-        Query<(
-            Entity,
-            &ComputedVisibility,
-            &Text,
-            &TextLayoutInfo,
-            &Anchor,
-            &GlobalTransform,
-        )>,
+        Query<
+            (
+                Entity,
+                &ViewVisibility,
+                &Text,
+                &TextLayoutInfo,
+                &Anchor,
+                &GlobalTransform,
+                Option<&SortMode2d>,
+            ),
+            Without<Text3d>,
+        >,
     >,
 ) {
     // TODO: Support window-independent scaling: https://github.com/bevyengine/bevy/issues/5621
@@ -95,26 +102,52 @@ pub fn extract_text2d_sprite(
         .map(|window| window.resolution.scale_factor() as f32)
         .unwrap_or(1.0);
 
-    for (entity, computed_visibility, text, text_layout_info, anchor, text_transform) in
+    for (entity, view_visibility, text, text_layout_info, anchor, text_transform, sort_mode) in
         text2d_query.iter()
     {
-        if !computed_visibility.is_visible() {
+        if !view_visibility.get() {
             continue;
         }
 
+        // The glyphs are sorted by the transform of the text, to stay together
+        let sort_keys = sort_mode
+            .copied()
+            .unwrap_or_default()
+            .sort_keys(text_transform);
+
         let text_glyphs = &text_layout_info.glyphs;
         let text_anchor = anchor.as_vec() * Vec2::new(1., -1.) - 0.5;
         let alignment_offset = text_layout_info.size * text_anchor;
-        let mut color = Color::WHITE;
-        let mut current_section = usize::MAX;
+        let text_transform =
+            *text_transform * GlobalTransform::from_scale(Vec3::splat(scale_factor.recip()));
+        // The background, shadows and outlines are sorted just below the glyphs, as the sprites
+        // with the same sort keys are drawn in any order
+        let sort_keys_below = |steps: f32| {
+            let step = sort_keys.0.abs().max(1.0) * f32::EPSILON;
+            (sort_keys.0 - step * steps, sort_keys.1)
+        };
+
+        if let Some(background) = text.background {
+            let center = alignment_offset + text_layout_info.size / 2.0;
+            extracted_sprites.sprites.push(ExtractedSprite {
+                entity,
+                transform: text_transform * Transform::from_translation(center.extend(0.)),
+                color: background.color,
+                rect: None,
+                custom_size: Some(text_layout_info.size + 2.0 * background.padding * scale_factor),
+                image_handle_id: DEFAULT_IMAGE_HANDLE.id(),
+                flip_x: false,
+                flip_y: false,
+                anchor: Anchor::Center.as_vec(),
+                scale_mode: ImageScaleMode::Stretched,
+                sort_keys: sort_keys_below(3.0),
+            });
+        }
+
         for text_glyph in text_glyphs {
-            if text_glyph.section_index != current_section {
-                color = text.sections[text_glyph.section_index]
-                    .style
-                    .color
-                    .as_rgba_linear();
-                current_section = text_glyph.section_index;
-            }
+            let color = text_glyph
+                .color(&text.sections[text_glyph.section_index].style)
+                .as_rgba_linear();
             let atlas = texture_atlases
                 .get(&text_glyph.atlas_info.texture_atlas)
                 .unwrap();
@@ -125,9 +158,7 @@ pub fn extract_text2d_sprite(
             let glyph_transform =
                 Transform::from_translation((alignment_offset + text_glyph.position).extend(0.));
 
-            let transform = *text_transform
-                * GlobalTransform::from_scale(Vec3::splat(scale_factor.recip()))
-                * glyph_transform;
+            let transform = text_transform * glyph_transform;
 
             extracted_sprites.sprites.push(ExtractedSprite {
                 entity,
@@ -139,6 +170,12 @@ pub fn extract_text2d_sprite(
                 flip_x: false,
                 flip_y: false,
                 anchor: Anchor::Center.as_vec(),
+                scale_mode: ImageScaleMode::Stretched,
+                sort_keys: match text_glyph.layer {
+                    GlyphLayer::Shadow => sort_keys_below(2.0),
+                    GlyphLayer::Outline => sort_keys_below(1.0),
+                    GlyphLayer::Fill => sort_keys,
+                },
             });
         }
     }
@@ -158,6 +195,7 @@ pub fn update_text2d_layout(
     mut queue: Local<HashSet<Entity>>,
     mut textures: ResMut<Assets<Image>>,
     fonts: Res<Assets<Font>>,
+    font_fallbacks: Res<FontFallbacks>,
     text_settings: Res<TextSettings>,
     mut font_atlas_warning: ResMut<FontAtlasWarning>,
     windows: Query<&Window, With<PrimaryWindow>>,
@@ -170,6 +208,7 @@ pub fn update_text2d_layout(
         Ref<Text>,
         &Text2dBounds,
         Option<&mut TextLayoutInfo>,
+        Option<Ref<Text3d>>,
     )>,
 ) {
     // We need to consume the entire iterator, hence `last`
@@ -181,8 +220,9 @@ pub fn update_text2d_layout(
         .map(|window| window.resolution.scale_factor())
         .unwrap_or(1.0);
 
-    for (entity, text, bounds, text_layout_info) in &mut text_query {
-        if factor_changed || text.is_changed() || queue.remove(&entity) {
+    for (entity, text, bounds, text_layout_info, text_3d) in &mut text_query {
+        let text_3d_changed = matches!(&text_3d, Some(text_3d) if text_3d.is_changed());
+        if factor_changed || text.is_changed() || text_3d_changed || queue.remove(&entity) {
             let text_bounds = Vec2::new(
                 scale_value(bounds.size.x, scale_factor),
                 scale_value(bounds.size.y, scale_factor),
@@ -190,10 +230,13 @@ pub fn update_text2d_layout(
 
             match text_pipeline.queue_text(
                 &fonts,
+                &font_fallbacks,
                 &text.sections,
                 scale_factor,
                 text.alignment,
                 text.linebreak_behaviour,
+                text.direction,
+                text.overflow,
                 text_bounds,
                 &mut font_atlas_set_storage,
                 &mut texture_atlases,
@@ -201,6 +244,7 @@ pub fn update_text2d_layout(
                 text_settings.as_ref(),
                 &mut font_atlas_warning,
                 YAxisOrientation::BottomToTop,
+                text_3d.map_or(GlyphAtlasMode::Bitmap, |text_3d| text_3d.atlas_mode),
             ) {
                 Err(TextError::NoSuchFont) => {
                     // There was an error processing the text layout, let's add this entity to the
@@ -0,0 +1,118 @@
+mod render;
+
+pub use render::*;
+
+use bevy_app::{App, IntoSystemAppConfig, Plugin};
+use bevy_asset::{load_internal_asset, HandleUntyped};
+use bevy_core_pipeline::core_3d::Transparent3d;
+use bevy_ecs::prelude::*;
+use bevy_math::Vec3;
+use bevy_reflect::{std_traits::ReflectDefault, FromReflect, Reflect, TypeUuid};
+use bevy_render::{
+    render_phase::AddRenderCommand,
+    render_resource::{Shader, SpecializedRenderPipelines},
+    view::{InheritedVisibility, ViewVisibility, Visibility},
+    ExtractSchedule, RenderApp, RenderSet,
+};
+use bevy_sprite::Anchor;
+use bevy_transform::components::{GlobalTransform, Transform};
+
+use crate::{GlyphAtlasMode, Text, Text2dBounds};
+
+pub const TEXT3D_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 3792924423413767932);
+
+/// Renders the [`Text3d`]s in the 3d cameras.
+#[derive(Default)]
+pub struct Text3dPlugin;
+
+impl Plugin for Text3dPlugin {
+    fn build(&self, app: &mut App) {
+        load_internal_asset!(app, TEXT3D_SHADER_HANDLE, "text3d.wgsl", Shader::from_wgsl);
+
+        app.register_type::<Text3d>()
+            .register_type::<Billboard>()
+            .register_type::<GlyphAtlasMode>();
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .init_resource::<Text3dPipeline>()
+                .init_resource::<SpecializedRenderPipelines<Text3dPipeline>>()
+                .init_resource::<ExtractedTexts3d>()
+                .init_resource::<Text3dMeta>()
+                .init_resource::<Text3dImageBindGroups>()
+                .add_render_command::<Transparent3d, DrawText3d>()
+                .add_system(extract_texts_3d.in_schedule(ExtractSchedule))
+                .add_system(queue_texts_3d.in_set(RenderSet::Queue));
+        }
+    }
+}
+
+/// Draws a [`Text`] in the 3d world instead of a 2d scene, for name tags above characters or
+/// damage numbers.
+///
+/// The text is laid out like a [`Text2dBundle`](crate::Text2dBundle), in the `XY` plane of its
+/// [`Transform`], and faces the camera when it has a [`Billboard`]. It's hidden behind the
+/// objects in front of it, and drawn over the ones behind it, with the other transparent objects.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub struct Text3d {
+    /// The number of pixels of the font size per unit of the world. A text with a font size of
+    /// `pixels_per_unit` is about a unit tall.
+    pub pixels_per_unit: f32,
+    /// How the glyphs are stored in their font atlases, as signed distance fields to stay sharp
+    /// when seen up close.
+    pub atlas_mode: GlyphAtlasMode,
+}
+
+impl Default for Text3d {
+    fn default() -> Self {
+        Text3d {
+            pixels_per_unit: 100.0,
+            atlas_mode: GlyphAtlasMode::Bitmap,
+        }
+    }
+}
+
+/// Turns a [`Text3d`] to face each camera it's seen by.
+///
+/// The text keeps the position and the scale of its [`Transform`], but its rotation is ignored
+/// when it faces the camera.
+#[derive(Component, Debug, Default, Clone, Copy, PartialEq, Reflect, FromReflect)]
+#[reflect(Component, Default)]
+pub enum Billboard {
+    /// The text is oriented by the rotation of its [`Transform`].
+    Off,
+    /// The text faces the camera, and stays upright on the screen.
+    #[default]
+    FaceCamera,
+    /// The text stays upright along an axis of the world, like [`Vec3::Y`], and only turns
+    /// around it to face the camera. This keeps name tags upright when the camera looks down on
+    /// them.
+    LockAxis(Vec3),
+}
+
+/// The bundle of components needed to draw text in the 3d world, by the 3d cameras.
+#[derive(Bundle, Clone, Debug, Default)]
+pub struct Text3dBundle {
+    /// Contains the text.
+    pub text: Text,
+    /// How the text is positioned relative to its transform.
+    pub text_anchor: Anchor,
+    /// The maximum width and height of the text, in pixels.
+    pub text_2d_bounds: Text2dBounds,
+    /// The size of the text in the world.
+    pub text_3d: Text3d,
+    /// How the text faces the camera.
+    pub billboard: Billboard,
+    /// The transform of the text.
+    pub transform: Transform,
+    /// The global transform of the text.
+    pub global_transform: GlobalTransform,
+    /// The visibility properties of the text.
+    pub visibility: Visibility,
+    /// The inherited visibility of the text.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering.
+    pub view_visibility: ViewVisibility,
+}
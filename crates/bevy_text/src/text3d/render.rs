@@ -0,0 +1,553 @@
+use std::ops::Range;
+
+use bevy_asset::{AssetEvent, Assets, Handle, HandleId};
+use bevy_core_pipeline::core_3d::Transparent3d;
+use bevy_ecs::{
+    prelude::*,
+    system::{lifetimeless::*, SystemParamItem},
+};
+use bevy_math::{Mat4, Vec2};
+use bevy_render::{
+    render_asset::RenderAssets,
+    render_phase::{
+        DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult, RenderPhase, SetItemPipeline,
+        TrackedRenderPass,
+    },
+    render_resource::*,
+    renderer::{RenderDevice, RenderQueue},
+    texture::{BevyDefault, Image, DEFAULT_IMAGE_HANDLE},
+    view::{
+        ExtractedView, Msaa, ViewTarget, ViewUniform, ViewUniformOffset, ViewUniforms,
+        ViewVisibility, VisibleEntities,
+    },
+    Extract,
+};
+use bevy_sprite::{Anchor, SpriteAssetEvents, TextureAtlas};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::HashMap;
+use bevy_window::{PrimaryWindow, Window};
+use bytemuck::{Pod, Zeroable};
+
+use super::{Billboard, Text3d, TEXT3D_SHADER_HANDLE};
+use crate::{Text, TextLayoutInfo, MSDF_DISTANCE_RANGE};
+
+#[derive(Resource)]
+pub struct Text3dPipeline {
+    view_layout: BindGroupLayout,
+    texture_layout: BindGroupLayout,
+}
+
+impl FromWorld for Text3dPipeline {
+    fn from_world(world: &mut World) -> Self {
+        let render_device = world.resource::<RenderDevice>();
+
+        let view_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[BindGroupLayoutEntry {
+                binding: 0,
+                visibility: ShaderStages::VERTEX,
+                ty: BindingType::Buffer {
+                    ty: BufferBindingType::Uniform,
+                    has_dynamic_offset: true,
+                    min_binding_size: Some(ViewUniform::min_size()),
+                },
+                count: None,
+            }],
+            label: Some("text3d_view_layout"),
+        });
+
+        let texture_layout = render_device.create_bind_group_layout(&BindGroupLayoutDescriptor {
+            entries: &[
+                BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Texture {
+                        multisampled: false,
+                        sample_type: TextureSampleType::Float { filterable: true },
+                        view_dimension: TextureViewDimension::D2,
+                    },
+                    count: None,
+                },
+                BindGroupLayoutEntry {
+                    binding: 1,
+                    visibility: ShaderStages::FRAGMENT,
+                    ty: BindingType::Sampler(SamplerBindingType::Filtering),
+                    count: None,
+                },
+            ],
+            label: Some("text3d_texture_layout"),
+        });
+
+        Text3dPipeline {
+            view_layout,
+            texture_layout,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Hash, PartialEq, Eq)]
+pub struct Text3dPipelineKey {
+    pub hdr: bool,
+    pub msaa_samples: u32,
+}
+
+impl SpecializedRenderPipeline for Text3dPipeline {
+    type Key = Text3dPipelineKey;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        // Each glyph is an instance, whose quad is generated from the vertex index
+        let instance_layout = VertexBufferLayout::from_vertex_formats(
+            VertexStepMode::Instance,
+            vec![
+                // origin
+                VertexFormat::Float32x3,
+                // x_axis
+                VertexFormat::Float32x3,
+                // y_axis
+                VertexFormat::Float32x3,
+                // position
+                VertexFormat::Float32x2,
+                // size
+                VertexFormat::Float32x2,
+                // uv_rect
+                VertexFormat::Float32x4,
+                // color
+                VertexFormat::Float32x4,
+                // billboard_axis
+                VertexFormat::Float32x3,
+                // billboard
+                VertexFormat::Uint32,
+                // distance_range
+                VertexFormat::Float32,
+            ],
+        );
+
+        RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: TEXT3D_SHADER_HANDLE.typed::<Shader>(),
+                entry_point: "vertex".into(),
+                shader_defs: Vec::new(),
+                buffers: vec![instance_layout],
+            },
+            fragment: Some(FragmentState {
+                shader: TEXT3D_SHADER_HANDLE.typed::<Shader>(),
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: if key.hdr {
+                        ViewTarget::TEXTURE_FORMAT_HDR
+                    } else {
+                        TextureFormat::bevy_default()
+                    },
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            layout: vec![self.view_layout.clone(), self.texture_layout.clone()],
+            primitive: PrimitiveState {
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: PolygonMode::Fill,
+                conservative: false,
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+            },
+            // The text is hidden by what's in front of it, without hiding what's behind it
+            depth_stencil: Some(DepthStencilState {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: false,
+                depth_compare: CompareFunction::GreaterEqual,
+                stencil: StencilState::default(),
+                bias: DepthBiasState::default(),
+            }),
+            multisample: MultisampleState {
+                count: key.msaa_samples,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            label: Some("text3d_pipeline".into()),
+            push_constant_ranges: Vec::new(),
+        }
+    }
+}
+
+/// The data of a glyph of a [`Text3d`] on the GPU.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+pub struct GlyphInstance {
+    /// The position of the text in the world
+    pub origin: [f32; 3],
+    /// The axes of the text in the world, scaled to the size of a pixel of the text
+    pub x_axis: [f32; 3],
+    pub y_axis: [f32; 3],
+    /// The center of the glyph in the text, in pixels
+    pub position: [f32; 2],
+    /// The size of the glyph, in pixels
+    pub size: [f32; 2],
+    /// The minimum and maximum UVs of the glyph in its atlas
+    pub uv_rect: [f32; 4],
+    pub color: [f32; 4],
+    /// The axis the text is upright along, with [`GlyphInstance::BILLBOARD_LOCK_AXIS`]
+    pub billboard_axis: [f32; 3],
+    pub billboard: u32,
+    /// The [`MSDF_DISTANCE_RANGE`] of a signed distance field, or `0.0` for a bitmap
+    pub distance_range: f32,
+}
+
+impl GlyphInstance {
+    // NOTE: These must match the constants in bevy_text/src/text3d/text3d.wgsl!
+    pub const BILLBOARD_OFF: u32 = 0;
+    pub const BILLBOARD_FACE_CAMERA: u32 = 1;
+    pub const BILLBOARD_LOCK_AXIS: u32 = 2;
+}
+
+/// Consecutive glyphs of a [`Text3d`] sharing a font atlas texture, drawn together.
+pub struct ExtractedText3d {
+    pub entity: Entity,
+    pub transform: Mat4,
+    pub image_handle_id: HandleId,
+    /// The glyphs in [`ExtractedTexts3d::glyphs`]
+    pub range: Range<u32>,
+}
+
+#[derive(Resource, Default)]
+pub struct ExtractedTexts3d {
+    pub texts: Vec<ExtractedText3d>,
+    pub glyphs: Vec<GlyphInstance>,
+}
+
+pub fn extract_texts_3d(
+    mut extracted_texts: ResMut<ExtractedTexts3d>,
+    texture_atlases: Extract<Res<Assets<TextureAtlas>>>,
+    windows: Extract<Query<&Window, With<PrimaryWindow>>>,
+    text_query: Extract<
+        Query<(
+            Entity,
+            &ViewVisibility,
+            &Text,
+            &TextLayoutInfo,
+            &Anchor,
+            &Text3d,
+            Option<&Billboard>,
+            &GlobalTransform,
+        )>,
+    >,
+) {
+    let ExtractedTexts3d { texts, glyphs } = &mut *extracted_texts;
+    texts.clear();
+    glyphs.clear();
+
+    // The glyphs are laid out in physical pixels, like for 2d text
+    let scale_factor = windows
+        .get_single()
+        .map(|window| window.resolution.scale_factor() as f32)
+        .unwrap_or(1.0);
+
+    for (entity, view_visibility, text, text_layout_info, anchor, text_3d, billboard, transform) in
+        &text_query
+    {
+        if !view_visibility.get() {
+            continue;
+        }
+
+        let pixel_size = (scale_factor * text_3d.pixels_per_unit).recip();
+        let affine = transform.affine();
+        let (billboard, billboard_axis) = match billboard.copied().unwrap_or(Billboard::Off) {
+            Billboard::Off => (GlyphInstance::BILLBOARD_OFF, [0.0; 3]),
+            Billboard::FaceCamera => (GlyphInstance::BILLBOARD_FACE_CAMERA, [0.0; 3]),
+            Billboard::LockAxis(axis) => (
+                GlyphInstance::BILLBOARD_LOCK_AXIS,
+                axis.normalize_or_zero().into(),
+            ),
+        };
+        let origin = affine.translation.into();
+        let x_axis = (affine.matrix3.x_axis * pixel_size).into();
+        let y_axis = (affine.matrix3.y_axis * pixel_size).into();
+
+        let text_anchor = anchor.as_vec() * Vec2::new(1., -1.) - 0.5;
+        let alignment_offset = text_layout_info.size * text_anchor;
+
+        if let Some(background) = text.background {
+            let index = glyphs.len() as u32;
+            texts.push(ExtractedText3d {
+                entity,
+                transform: transform.compute_matrix(),
+                image_handle_id: DEFAULT_IMAGE_HANDLE.id(),
+                range: index..index + 1,
+            });
+            glyphs.push(GlyphInstance {
+                origin,
+                x_axis,
+                y_axis,
+                position: (alignment_offset + text_layout_info.size / 2.0).into(),
+                size: (text_layout_info.size + 2.0 * background.padding * scale_factor).into(),
+                uv_rect: [0.0, 0.0, 1.0, 1.0],
+                color: background.color.as_linear_rgba_f32(),
+                billboard_axis,
+                billboard,
+                distance_range: 0.0,
+            });
+        }
+
+        for text_glyph in &text_layout_info.glyphs {
+            let color = text_glyph
+                .color(&text.sections[text_glyph.section_index].style)
+                .as_linear_rgba_f32();
+            let Some(atlas) = texture_atlases.get(&text_glyph.atlas_info.texture_atlas) else {
+                continue;
+            };
+            let rect = atlas.textures[text_glyph.atlas_info.glyph_index];
+            let uv_min = rect.min / atlas.size;
+            let uv_max = rect.max / atlas.size;
+
+            // The glyphs of the text are split into a batch for each font atlas texture
+            let index = glyphs.len() as u32;
+            let image_handle_id = atlas.texture.id();
+            match texts.last_mut() {
+                Some(batch)
+                    if batch.entity == entity && batch.image_handle_id == image_handle_id =>
+                {
+                    batch.range.end = index + 1;
+                }
+                _ => texts.push(ExtractedText3d {
+                    entity,
+                    transform: transform.compute_matrix(),
+                    image_handle_id,
+                    range: index..index + 1,
+                }),
+            }
+            glyphs.push(GlyphInstance {
+                origin,
+                x_axis,
+                y_axis,
+                position: (alignment_offset + text_glyph.position).into(),
+                size: text_glyph.size.into(),
+                uv_rect: [uv_min.x, uv_min.y, uv_max.x, uv_max.y],
+                color,
+                billboard_axis,
+                billboard,
+                distance_range: if text_glyph.atlas_info.is_msdf {
+                    MSDF_DISTANCE_RANGE
+                } else {
+                    0.0
+                },
+            });
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct Text3dMeta {
+    glyphs: BufferVec<GlyphInstance>,
+    view_bind_group: Option<BindGroup>,
+}
+
+impl Default for Text3dMeta {
+    fn default() -> Self {
+        Text3dMeta {
+            glyphs: BufferVec::new(BufferUsages::VERTEX),
+            view_bind_group: None,
+        }
+    }
+}
+
+/// The bind group of each font atlas texture.
+#[derive(Resource, Default)]
+pub struct Text3dImageBindGroups {
+    values: HashMap<Handle<Image>, BindGroup>,
+}
+
+/// Glyphs of a [`Text3d`] to draw.
+#[derive(Component)]
+pub struct Text3dBatch {
+    pub image_handle_id: HandleId,
+    pub range: Range<u32>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn queue_texts_3d(
+    mut commands: Commands,
+    draw_functions: Res<DrawFunctions<Transparent3d>>,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    text_pipeline: Res<Text3dPipeline>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<Text3dPipeline>>,
+    pipeline_cache: Res<PipelineCache>,
+    view_uniforms: Res<ViewUniforms>,
+    mut text_meta: ResMut<Text3dMeta>,
+    extracted_texts: Res<ExtractedTexts3d>,
+    mut image_bind_groups: ResMut<Text3dImageBindGroups>,
+    gpu_images: Res<RenderAssets<Image>>,
+    events: Res<SpriteAssetEvents>,
+    mut views: Query<(
+        &mut RenderPhase<Transparent3d>,
+        &VisibleEntities,
+        &ExtractedView,
+        &Msaa,
+    )>,
+) {
+    // If an image has changed, the GpuImage has (probably) changed
+    for event in &events.images {
+        match event {
+            AssetEvent::Created { .. } | AssetEvent::LoadedWithDependencies { .. } => None,
+            AssetEvent::Modified { handle } | AssetEvent::Removed { handle } => {
+                image_bind_groups.values.remove(handle)
+            }
+        };
+    }
+
+    let text_meta = &mut *text_meta;
+    text_meta.glyphs.clear();
+    let Some(view_binding) = view_uniforms.uniforms.binding() else {
+        return;
+    };
+    if extracted_texts.texts.is_empty() {
+        return;
+    }
+    text_meta.view_bind_group = Some(render_device.create_bind_group(&BindGroupDescriptor {
+        entries: &[BindGroupEntry {
+            binding: 0,
+            resource: view_binding,
+        }],
+        label: Some("text3d_view_bind_group"),
+        layout: &text_pipeline.view_layout,
+    }));
+
+    // The glyphs are the same for every view, which each turn the billboards to face themselves
+    for glyph in &extracted_texts.glyphs {
+        text_meta.glyphs.push(*glyph);
+    }
+    text_meta.glyphs.write_buffer(&render_device, &render_queue);
+
+    // Spawn an entity for each batch of glyphs, shared by all the views
+    let mut batches = Vec::new();
+    for text in &extracted_texts.texts {
+        let image = Handle::weak(text.image_handle_id);
+        let Some(gpu_image) = gpu_images.get(&image) else {
+            continue;
+        };
+        image_bind_groups.values.entry(image).or_insert_with(|| {
+            render_device.create_bind_group(&BindGroupDescriptor {
+                entries: &[
+                    BindGroupEntry {
+                        binding: 0,
+                        resource: BindingResource::TextureView(&gpu_image.texture_view),
+                    },
+                    BindGroupEntry {
+                        binding: 1,
+                        resource: BindingResource::Sampler(&gpu_image.sampler),
+                    },
+                ],
+                label: Some("text3d_texture_bind_group"),
+                layout: &text_pipeline.texture_layout,
+            })
+        });
+        let batch = commands
+            .spawn(Text3dBatch {
+                image_handle_id: text.image_handle_id,
+                range: text.range.clone(),
+            })
+            .id();
+        batches.push((text, batch));
+    }
+
+    let draw_text_function = draw_functions.read().id::<DrawText3d>();
+    for (mut transparent_phase, visible_entities, view, msaa) in &mut views {
+        let pipeline = pipelines.specialize(
+            &pipeline_cache,
+            &text_pipeline,
+            Text3dPipelineKey {
+                hdr: view.hdr,
+                msaa_samples: msaa.samples(),
+            },
+        );
+        let rangefinder = view.rangefinder3d();
+        for &(text, batch) in &batches {
+            if !visible_entities.entities.contains(&text.entity) {
+                continue;
+            }
+            transparent_phase.add(Transparent3d {
+                distance: rangefinder.distance(&text.transform),
+                pipeline,
+                entity: batch,
+                draw_function: draw_text_function,
+            });
+        }
+    }
+}
+
+pub type DrawText3d = (
+    SetItemPipeline,
+    SetText3dViewBindGroup<0>,
+    SetText3dTextureBindGroup<1>,
+    DrawText3dBatch,
+);
+
+pub struct SetText3dViewBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetText3dViewBindGroup<I> {
+    type Param = SRes<Text3dMeta>;
+    type ViewWorldQuery = Read<ViewUniformOffset>;
+    type ItemWorldQuery = ();
+
+    fn render<'w>(
+        _item: &P,
+        view_uniform: &'_ ViewUniformOffset,
+        _entity: (),
+        text_meta: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(bind_group) = text_meta.into_inner().view_bind_group.as_ref() else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, bind_group, &[view_uniform.offset]);
+        RenderCommandResult::Success
+    }
+}
+
+pub struct SetText3dTextureBindGroup<const I: usize>;
+impl<P: PhaseItem, const I: usize> RenderCommand<P> for SetText3dTextureBindGroup<I> {
+    type Param = SRes<Text3dImageBindGroups>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<Text3dBatch>;
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: &'_ Text3dBatch,
+        image_bind_groups: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(bind_group) = image_bind_groups
+            .into_inner()
+            .values
+            .get(&Handle::weak(batch.image_handle_id))
+        else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+pub struct DrawText3dBatch;
+impl<P: PhaseItem> RenderCommand<P> for DrawText3dBatch {
+    type Param = SRes<Text3dMeta>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<Text3dBatch>;
+
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: &'_ Text3dBatch,
+        text_meta: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(buffer) = text_meta.into_inner().glyphs.buffer() else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_vertex_buffer(0, buffer.slice(..));
+        // A quad of two triangles for each glyph
+        pass.draw(0..6, batch.range.clone());
+        RenderCommandResult::Success
+    }
+}
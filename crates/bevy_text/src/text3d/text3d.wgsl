@@ -0,0 +1,86 @@
+#import bevy_render::view
+
+@group(0) @binding(0)
+var<uniform> view: View;
+
+@group(1) @binding(0)
+var atlas_texture: texture_2d<f32>;
+@group(1) @binding(1)
+var atlas_sampler: sampler;
+
+// NOTE: These must match the constants in bevy_text/src/text3d/render.rs!
+const BILLBOARD_OFF: u32 = 0u;
+const BILLBOARD_FACE_CAMERA: u32 = 1u;
+const BILLBOARD_LOCK_AXIS: u32 = 2u;
+
+struct VertexOutput {
+    @builtin(position) position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+    @location(1) color: vec4<f32>,
+    @location(2) @interpolate(flat) distance_range: f32,
+};
+
+@vertex
+fn vertex(
+    @builtin(vertex_index) vertex_index: u32,
+    @location(0) origin: vec3<f32>,
+    @location(1) x_axis: vec3<f32>,
+    @location(2) y_axis: vec3<f32>,
+    @location(3) glyph_position: vec2<f32>,
+    @location(4) size: vec2<f32>,
+    @location(5) uv_rect: vec4<f32>,
+    @location(6) color: vec4<f32>,
+    @location(7) billboard_axis: vec3<f32>,
+    @location(8) billboard: u32,
+    @location(9) distance_range: f32,
+) -> VertexOutput {
+    // Billboards keep the scale of their axes, but turn them to face the camera
+    var right = x_axis;
+    var up = y_axis;
+    if billboard == BILLBOARD_FACE_CAMERA {
+        right = view.view[0].xyz * length(x_axis);
+        up = view.view[1].xyz * length(y_axis);
+    } else if billboard == BILLBOARD_LOCK_AXIS {
+        var direction = cross(billboard_axis, view.world_position - origin);
+        // Looking along the axis, the text faces the camera like without the lock
+        if dot(direction, direction) < 1e-8 {
+            direction = view.view[0].xyz;
+        }
+        right = normalize(direction) * length(x_axis);
+        up = billboard_axis * length(y_axis);
+    }
+
+    // The two triangles of the glyph, with the corners (0, 0), (1, 0), (1, 1) and (0, 0), (1, 1),
+    // (0, 1): the bits of these masks are the coordinates of each of the 6 vertices.
+    let corner = vec2<f32>(f32((0x16u >> vertex_index) & 1u), f32((0x34u >> vertex_index) & 1u));
+    let local = glyph_position + (corner - 0.5) * size;
+
+    var out: VertexOutput;
+    let world_position = origin + right * local.x + up * local.y;
+    out.position = view.view_proj * vec4<f32>(world_position, 1.0);
+    out.uv = mix(uv_rect.xy, uv_rect.zw, vec2<f32>(corner.x, 1.0 - corner.y));
+    out.color = color;
+    out.distance_range = distance_range;
+    return out;
+}
+
+fn median(a: f32, b: f32, c: f32) -> f32 {
+    return max(min(a, b), min(max(a, b), c));
+}
+
+@fragment
+fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+    let sample = textureSample(atlas_texture, atlas_sampler, in.uv);
+
+    // The signed distance fields are sharpened to the pixels of the screen they cover
+    let unit_range = vec2<f32>(in.distance_range) / vec2<f32>(textureDimensions(atlas_texture));
+    let screen_texture_size = vec2<f32>(1.0) / fwidth(in.uv);
+    let screen_pixel_range = max(0.5 * dot(unit_range, screen_texture_size), 1.0);
+    let distance = median(sample.r, sample.g, sample.b) - 0.5;
+    let coverage = clamp(distance * screen_pixel_range + 0.5, 0.0, 1.0);
+
+    if in.distance_range > 0.0 {
+        return vec4<f32>(in.color.rgb, in.color.a * coverage);
+    }
+    return in.color * sample;
+}
@@ -0,0 +1,483 @@
+use crate::Node;
+use bevy_ecs::{
+    entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    prelude::{Component, With, Without},
+    reflect::{ReflectComponent, ReflectMapEntities},
+    system::Query,
+    world::{FromWorld, World},
+};
+use bevy_hierarchy::{Children, HierarchyQueryExt};
+use bevy_math::{Vec2, Vec3, Vec3A};
+use bevy_reflect::{FromReflect, Reflect, ReflectDeserialize, ReflectSerialize};
+use bevy_render::{camera::Camera, view::ViewVisibility};
+use bevy_transform::components::GlobalTransform;
+use serde::{Deserialize, Serialize};
+
+/// Positions a UI node over an entity of the world, at the projection of its
+/// [`GlobalTransform`] by a camera, like health bars, name plates or waypoint markers.
+///
+/// The center of the node is moved over the entity each frame, with its children, by
+/// [`world_anchor_system`]. The node is still laid out like the other nodes, so it should
+/// usually have a [`PositionType::Absolute`](crate::PositionType::Absolute) to not take space
+/// among its siblings.
+///
+/// The entity is projected by the camera of the [`UiTargetCamera`] of the node if it has one,
+/// or else by the camera the node is laid out on.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, MapEntities, PartialEq)]
+pub struct WorldAnchor {
+    /// The entity the node is positioned over
+    pub entity: Entity,
+    /// The offset from the translation of the entity in world space, like the height of the
+    /// head of a character
+    pub offset: Vec3,
+    /// The offset from the projection of the entity in logical pixels, to the right and down
+    pub screen_offset: Vec2,
+    /// What happens to the node when the entity is out of the view of the camera
+    pub off_screen: OffScreen,
+}
+
+impl WorldAnchor {
+    /// A node positioned over `entity`, hidden when the entity is out of the view
+    pub const fn new(entity: Entity) -> Self {
+        Self {
+            entity,
+            offset: Vec3::ZERO,
+            screen_offset: Vec2::ZERO,
+            off_screen: OffScreen::DEFAULT,
+        }
+    }
+
+    /// Sets the [`offset`](Self::offset) of the node in world space
+    pub const fn with_offset(mut self, offset: Vec3) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Sets the [`screen_offset`](Self::screen_offset) of the node in logical pixels
+    pub const fn with_screen_offset(mut self, screen_offset: Vec2) -> Self {
+        self.screen_offset = screen_offset;
+        self
+    }
+
+    /// Sets what happens to the node when the entity is out of the view
+    pub const fn with_off_screen(mut self, off_screen: OffScreen) -> Self {
+        self.off_screen = off_screen;
+        self
+    }
+}
+
+// Like for `Parent`, the anchor can only be deserialized by patching an instance holding a
+// placeholder entity.
+impl FromWorld for WorldAnchor {
+    fn from_world(_world: &mut World) -> Self {
+        WorldAnchor::new(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for WorldAnchor {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        if let Ok(mapped_entity) = entity_map.get(self.entity) {
+            self.entity = mapped_entity;
+        }
+        Ok(())
+    }
+}
+
+/// What happens to a node with a [`WorldAnchor`] when its entity is out of the view of the camera
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect, FromReflect)]
+#[reflect(PartialEq, Serialize, Deserialize)]
+pub enum OffScreen {
+    /// The node is hidden, with its children
+    Hide,
+    /// The node is kept inside the viewport of the camera, on the edge closest to the entity,
+    /// like a marker pointing to a waypoint
+    Clamp,
+    /// The node follows the entity out of the viewport, and is hidden when the entity is
+    /// behind the camera
+    Overflow,
+}
+
+impl OffScreen {
+    pub const DEFAULT: Self = Self::Hide;
+}
+
+impl Default for OffScreen {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The camera a UI tree is laid out and drawn on, when added to its root node, instead of the
+/// [`DefaultUiCamera`](crate::DefaultUiCamera)
+///
+/// Each camera lays out its trees in its own viewport, with the scale factor of its render
+/// target, so the UI can be shown on other windows than the primary one, or on an image like
+/// the screen of a computer in the world. On a node with a [`WorldAnchor`], it's also the camera
+/// projecting the entity of the anchor.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component, MapEntities, PartialEq)]
+pub struct UiTargetCamera(pub Entity);
+
+impl FromWorld for UiTargetCamera {
+    fn from_world(_world: &mut World) -> Self {
+        UiTargetCamera(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for UiTargetCamera {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        if let Ok(mapped_entity) = entity_map.get(self.0) {
+            self.0 = mapped_entity;
+        }
+        Ok(())
+    }
+}
+
+/// The system that moves the UI nodes with a [`WorldAnchor`] over their entity
+///
+/// It runs after the propagation of the transforms, to use the transforms of the entity and the
+/// camera of this frame, and moves the [`GlobalTransform`] of the nodes and their descendants.
+/// The layout of the nodes is left untouched.
+#[allow(clippy::type_complexity)]
+pub fn world_anchor_system(
+    cameras: Query<(Entity, &Camera, &GlobalTransform), Without<Node>>,
+    targets: Query<&GlobalTransform, Without<Node>>,
+    anchor_query: Query<(Entity, &WorldAnchor, Option<&UiTargetCamera>), With<Node>>,
+    mut node_query: Query<(&Node, &mut GlobalTransform, Option<&mut ViewVisibility>), With<Node>>,
+    children_query: Query<&Children>,
+) {
+    for (entity, anchor, target_camera) in &anchor_query {
+        let Ok((node, ..)) = node_query.get(entity) else {
+            continue;
+        };
+        let Some((_, camera, camera_transform)) = target_camera
+            .map(|target_camera| target_camera.0)
+            .or(node.target_camera())
+            .and_then(|camera| cameras.get(camera).ok())
+        else {
+            continue;
+        };
+        let (Ok(target_transform), Some((viewport_min, viewport_max))) =
+            (targets.get(anchor.entity), camera.logical_viewport_rect())
+        else {
+            continue;
+        };
+        // The node is positioned from the top left of the viewport of the camera it's laid out on
+        let layout_origin = node
+            .target_camera()
+            .and_then(|camera| cameras.get(camera).ok())
+            .and_then(|(_, camera, _)| camera.logical_viewport_rect())
+            .map_or(viewport_min, |(min, _)| min);
+        let half_size = node.size() / 2.0;
+
+        let position = camera.world_to_ndc(
+            camera_transform,
+            target_transform.translation() + anchor.offset,
+        );
+        let position = match position {
+            // Behind the camera, the projection is mirrored
+            Some(ndc) if ndc.z < 0.0 => match anchor.off_screen {
+                OffScreen::Clamp => {
+                    let direction = -ndc.truncate();
+                    let max = direction.abs().max_element();
+                    Some(if max > 0.0 {
+                        // Far out of the viewport, on the side of the entity
+                        direction / max * 2.0
+                    } else {
+                        Vec2::NEG_Y * 2.0
+                    })
+                }
+                OffScreen::Hide | OffScreen::Overflow => None,
+            },
+            Some(ndc) => Some(ndc.truncate()),
+            None => None,
+        };
+        // From the normalized device coordinates to the logical pixels of the window, from its
+        // top left
+        let viewport_size = viewport_max - viewport_min;
+        let position = position
+            .map(|ndc| {
+                viewport_min
+                    + Vec2::new(ndc.x + 1.0, 1.0 - ndc.y) / 2.0 * viewport_size
+                    + anchor.screen_offset
+            })
+            .and_then(|position| match anchor.off_screen {
+                OffScreen::Hide => {
+                    // Hidden only once the node is completely out of the viewport
+                    let visible = position.cmpge(viewport_min - half_size).all()
+                        && position.cmple(viewport_max + half_size).all();
+                    visible.then_some(position)
+                }
+                OffScreen::Clamp => Some(position.clamp(
+                    viewport_min + half_size,
+                    (viewport_max - half_size).max(viewport_min + half_size),
+                )),
+                OffScreen::Overflow => Some(position),
+            });
+
+        let Ok((_, global_transform, _)) = node_query.get(entity) else {
+            continue;
+        };
+        let delta = position
+            .map(|position| position - layout_origin - global_transform.translation().truncate());
+        for entity in std::iter::once(entity).chain(children_query.iter_descendants(entity)) {
+            let Ok((_, mut global_transform, view_visibility)) = node_query.get_mut(entity) else {
+                continue;
+            };
+            match delta {
+                Some(delta) if delta != Vec2::ZERO => {
+                    let mut affine = global_transform.affine();
+                    affine.translation += Vec3A::from(delta.extend(0.0));
+                    *global_transform = GlobalTransform::from(affine);
+                }
+                Some(_) => {}
+                None => {
+                    if let Some(mut view_visibility) = view_visibility {
+                        *view_visibility = ViewVisibility::HIDDEN;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_ecs::schedule::IntoSystemConfig;
+    use bevy_hierarchy::BuildWorldChildren;
+    use bevy_render::{
+        camera::{camera_system, PerspectiveProjection},
+        texture::Image,
+    };
+    use bevy_window::{PrimaryWindow, Window, WindowCreated, WindowResized, WindowResolution};
+
+    /// A window of 800 by 400 logical pixels, with a perspective camera at the origin looking
+    /// down the negative Z axis
+    struct Scene {
+        app: App,
+        camera: Entity,
+    }
+
+    impl Scene {
+        fn new() -> Self {
+            let mut app = App::new();
+            app.add_plugin(AssetPlugin::default())
+                .add_asset::<Image>()
+                .add_event::<WindowCreated>()
+                .add_event::<WindowResized>()
+                .add_system(camera_system::<PerspectiveProjection>)
+                .add_system(world_anchor_system.after(camera_system::<PerspectiveProjection>));
+            app.world.spawn((
+                Window {
+                    resolution: WindowResolution::new(800.0, 400.0),
+                    ..Default::default()
+                },
+                PrimaryWindow,
+            ));
+            let camera = app
+                .world
+                .spawn((
+                    Camera::default(),
+                    PerspectiveProjection::default(),
+                    GlobalTransform::IDENTITY,
+                ))
+                .id();
+            Self { app, camera }
+        }
+
+        /// Spawns a node of 20 by 20 pixels anchored to an entity at `translation`, with a child
+        /// node 5 pixels to the right and 2 pixels down from its center
+        fn spawn(&mut self, translation: Vec3, anchor: WorldAnchor) -> (Entity, Entity) {
+            let target = self
+                .app
+                .world
+                .spawn(GlobalTransform::from_translation(translation))
+                .id();
+            let mut visible = ViewVisibility::HIDDEN;
+            visible.set();
+            let child = self
+                .app
+                .world
+                .spawn((
+                    Node {
+                        calculated_size: Vec2::splat(10.0),
+                        target_camera: Some(self.camera),
+                    },
+                    GlobalTransform::from_xyz(15.0, 12.0, 0.0),
+                    visible,
+                ))
+                .id();
+            let node = self
+                .app
+                .world
+                .spawn((
+                    Node {
+                        calculated_size: Vec2::splat(20.0),
+                        target_camera: Some(self.camera),
+                    },
+                    GlobalTransform::from_xyz(10.0, 10.0, 0.0),
+                    visible,
+                    WorldAnchor {
+                        entity: target,
+                        ..anchor
+                    },
+                ))
+                .push_children(&[child])
+                .id();
+            (node, child)
+        }
+
+        /// The center of `node` from the top left of the window, if it's visible
+        fn position(&self, node: Entity) -> Option<Vec2> {
+            let visible = self.app.world.get::<ViewVisibility>(node).unwrap().get();
+            let position = self.app.world.get::<GlobalTransform>(node).unwrap();
+            visible.then(|| position.translation().truncate())
+        }
+    }
+
+    /// The point at 10 units in front of the camera projected at `x` and `y`, in normalized device
+    /// coordinates
+    fn in_front(x: f32, y: f32) -> Vec3 {
+        // the half height of the view 10 units in front of the camera, with its field of view of
+        // a quarter turn and an aspect ratio of 2
+        let half_height = (std::f32::consts::PI / 8.0).tan() * 10.0;
+        Vec3::new(x * 2.0 * half_height, y * half_height, -10.0)
+    }
+
+    fn anchored(translation: Vec3, anchor: WorldAnchor) -> (Option<Vec2>, Option<Vec2>) {
+        let mut scene = Scene::new();
+        let (node, child) = scene.spawn(translation, anchor);
+        scene.app.update();
+        (scene.position(node), scene.position(child))
+    }
+
+    fn assert_near(actual: Option<Vec2>, expected: Option<Vec2>, message: &str) {
+        match (actual, expected) {
+            (Some(actual), Some(expected)) => assert!(
+                actual.abs_diff_eq(expected, 1e-3),
+                "{message}: {actual} != {expected}"
+            ),
+            _ => assert_eq!(actual, expected, "{message}"),
+        }
+    }
+
+    #[test]
+    fn projected_over_the_entity() {
+        let anchor = WorldAnchor::new(Entity::PLACEHOLDER);
+        let half_height = in_front(0.0, 1.0).y;
+        let cases = [
+            (
+                in_front(0.0, 0.0),
+                anchor,
+                Vec2::new(400.0, 200.0),
+                "center",
+            ),
+            (
+                in_front(0.5, 0.5),
+                anchor,
+                Vec2::new(600.0, 100.0),
+                "top right",
+            ),
+            (
+                in_front(-1.0, -1.0),
+                anchor,
+                Vec2::new(0.0, 400.0),
+                "bottom left corner",
+            ),
+            (
+                in_front(0.0, 0.0),
+                anchor.with_offset(Vec3::new(0.0, half_height, 0.0)),
+                Vec2::new(400.0, 0.0),
+                "offset in world space",
+            ),
+            (
+                in_front(0.0, 0.0),
+                anchor.with_screen_offset(Vec2::new(5.0, 10.0)),
+                Vec2::new(405.0, 210.0),
+                "offset in logical pixels",
+            ),
+        ];
+        for (translation, anchor, expected, message) in cases {
+            let (node, child) = anchored(translation, anchor);
+            assert_near(node, Some(expected), message);
+            assert_near(child, Some(expected + Vec2::new(5.0, 2.0)), message);
+        }
+    }
+
+    #[test]
+    fn off_screen_modes() {
+        let behind = Vec3::new(0.0, 0.0, 10.0);
+        let behind_right = Vec3::new(5.0, 0.0, 10.0);
+        let cases = [
+            (
+                OffScreen::Hide,
+                in_front(1.02, 0.0),
+                Some(Vec2::new(808.0, 200.0)),
+                "partly in the viewport",
+            ),
+            (
+                OffScreen::Hide,
+                in_front(1.1, 0.0),
+                None,
+                "out of the viewport",
+            ),
+            (OffScreen::Hide, behind, None, "behind the camera"),
+            (
+                OffScreen::Clamp,
+                in_front(0.5, 0.0),
+                Some(Vec2::new(600.0, 200.0)),
+                "in the viewport",
+            ),
+            (
+                OffScreen::Clamp,
+                in_front(1.5, 0.0),
+                Some(Vec2::new(790.0, 200.0)),
+                "right",
+            ),
+            (
+                OffScreen::Clamp,
+                in_front(0.0, 2.0),
+                Some(Vec2::new(400.0, 10.0)),
+                "above",
+            ),
+            (
+                OffScreen::Clamp,
+                in_front(-3.0, -3.0),
+                Some(Vec2::new(10.0, 390.0)),
+                "bottom left",
+            ),
+            (
+                OffScreen::Clamp,
+                behind_right,
+                Some(Vec2::new(790.0, 200.0)),
+                "behind, on the right",
+            ),
+            (
+                OffScreen::Clamp,
+                behind,
+                Some(Vec2::new(400.0, 390.0)),
+                "right behind the camera",
+            ),
+            (
+                OffScreen::Overflow,
+                in_front(1.5, 0.0),
+                Some(Vec2::new(1000.0, 200.0)),
+                "out of the viewport",
+            ),
+            (OffScreen::Overflow, behind, None, "behind the camera"),
+        ];
+        for (off_screen, translation, expected, message) in cases {
+            let anchor = WorldAnchor::new(Entity::PLACEHOLDER).with_off_screen(off_screen);
+            let (node, child) = anchored(translation, anchor);
+            let message = format!("{off_screen:?}, {message}");
+            assert_near(node, expected, &message);
+            // the child is moved and hidden with the node
+            let expected = expected.map(|expected| expected + Vec2::new(5.0, 2.0));
+            assert_near(child, expected, &message);
+        }
+    }
+}
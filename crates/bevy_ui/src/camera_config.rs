@@ -1,9 +1,12 @@
 //! Configuration for cameras related to UI.
 
 use bevy_ecs::component::Component;
+use bevy_ecs::entity::Entity;
 use bevy_ecs::prelude::With;
-use bevy_render::camera::Camera;
+use bevy_ecs::system::{Query, SystemParam};
+use bevy_render::camera::{Camera, NormalizedRenderTarget};
 use bevy_render::extract_component::ExtractComponent;
+use bevy_window::PrimaryWindow;
 
 /// Configuration for cameras related to UI.
 ///
@@ -25,3 +28,33 @@ impl Default for UiCameraConfig {
         Self { show_ui: true }
     }
 }
+
+/// The camera the UI trees without a [`UiTargetCamera`](crate::UiTargetCamera) on their root
+/// node are laid out and drawn on.
+///
+/// It's the active camera rendering to the primary window with the highest order, so that the UI
+/// is drawn over the other cameras, skipping the cameras whose [`UiCameraConfig`] hides the UI.
+#[derive(SystemParam)]
+pub struct DefaultUiCamera<'w, 's> {
+    cameras: Query<'w, 's, (Entity, &'static Camera, Option<&'static UiCameraConfig>)>,
+    primary_window: Query<'w, 's, Entity, With<PrimaryWindow>>,
+}
+
+impl<'w, 's> DefaultUiCamera<'w, 's> {
+    /// The default UI camera, if any
+    pub fn get(&self) -> Option<Entity> {
+        let primary_window = self.primary_window.iter().next();
+        self.cameras
+            .iter()
+            .filter(|(_, camera, camera_ui)| {
+                camera.is_active
+                    && !matches!(camera_ui, Some(&UiCameraConfig { show_ui: false }))
+                    && matches!(
+                        camera.target.normalize(primary_window),
+                        Some(NormalizedRenderTarget::Window(window)) if Some(window.entity()) == primary_window
+                    )
+            })
+            .max_by_key(|(_, camera, _)| camera.order)
+            .map(|(entity, ..)| entity)
+    }
+}
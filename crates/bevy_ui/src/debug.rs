@@ -0,0 +1,54 @@
+use bevy_ecs::system::Resource;
+use bevy_render::color::Color;
+
+/// Draws the layout of the UI nodes over them, to find why a node isn't where it's expected
+///
+/// When [`enabled`](Self::enabled), each node visible and laid out gets an outline along its
+/// edges, its margins and paddings are filled with translucent gutters, and the nodes laying out
+/// their children with a [`Display::Flex`](crate::Display::Flex) show their main axis, ending
+/// with a square on the side the children flow to, and their cross axis. The overlay is drawn
+/// over all the nodes, and isn't clipped by the [`Overflow`](crate::Overflow) of their ancestors.
+///
+/// The margins set to [`Val::Auto`](crate::Val::Auto) aren't drawn, as the layout doesn't keep
+/// the space it gives them. The layout computed for a tree can also be logged with
+/// [`FlexSurface::debug_print`](crate::FlexSurface::debug_print).
+#[derive(Resource, Clone, Debug)]
+pub struct UiDebugOverlay {
+    /// Whether the overlay is drawn
+    pub enabled: bool,
+    /// The width of the outlines and the axes, in logical pixels
+    pub line_width: f32,
+    /// The color of the outlines of the nodes, or [`Color::NONE`] to not draw them
+    pub outline_color: Color,
+    /// The color of the margin gutters, or [`Color::NONE`] to not draw them
+    pub margin_color: Color,
+    /// The color of the padding gutters, or [`Color::NONE`] to not draw them
+    pub padding_color: Color,
+    /// The color of the main axes of the flex containers, or [`Color::NONE`] to not draw them
+    pub main_axis_color: Color,
+    /// The color of the cross axes of the flex containers, or [`Color::NONE`] to not draw them
+    pub cross_axis_color: Color,
+}
+
+impl UiDebugOverlay {
+    pub const DEFAULT: Self = Self {
+        enabled: false,
+        line_width: 1.0,
+        outline_color: Color::rgb(1.0, 0.0, 1.0),
+        margin_color: Color::rgba(1.0, 0.6, 0.0, 0.3),
+        padding_color: Color::rgba(0.3, 0.8, 0.3, 0.3),
+        main_axis_color: Color::rgb(1.0, 0.2, 0.2),
+        cross_axis_color: Color::rgba(0.2, 0.5, 1.0, 0.6),
+    };
+
+    /// Shows the overlay if it's hidden, or hides it
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+}
+
+impl Default for UiDebugOverlay {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
@@ -0,0 +1,681 @@
+use crate::{ActiveModal, CalculatedClip, FocusPolicy, Node, UiStack};
+use bevy_ecs::{
+    entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    event::EventWriter,
+    prelude::{Component, With},
+    query::WorldQuery,
+    reflect::{ReflectComponent, ReflectMapEntities},
+    system::{Query, Res, ResMut, Resource, SystemParam},
+};
+use bevy_hierarchy::{HierarchyQueryExt, Parent};
+use bevy_input::{mouse::MouseButton, touch::Touches, Input};
+use bevy_math::{Rect, Vec2};
+use bevy_reflect::Reflect;
+use bevy_render::view::ViewVisibility;
+use bevy_transform::components::GlobalTransform;
+use bevy_window::{PrimaryWindow, Window};
+
+/// The default [`Draggable::threshold`], in logical pixels
+pub const DRAG_THRESHOLD: f32 = 4.0;
+
+/// Marks a UI node that can be dragged with the mouse or a touch.
+///
+/// The drag is reported with [`DragStart`], [`Drag`], [`DragEnter`], [`DragLeave`], [`Drop`] and
+/// [`DragEnd`] events, sent by [`ui_drag_system`]. The node isn't moved: the events can be used to
+/// move it, or a preview of it, with the cursor.
+///
+/// A press on the node only starts a drag once the cursor moved further than the
+/// [`threshold`](Self::threshold), so the node can still be clicked. Once the drag started, no
+/// [`PointerClick`](crate::PointerClick) is sent when the node is released.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, MapEntities, PartialEq)]
+pub struct Draggable {
+    /// The entity carried by the drag, like the item held by an inventory slot.
+    ///
+    /// If `None`, the payload of the events is the dragged node itself.
+    pub payload: Option<Entity>,
+    /// The distance in logical pixels the cursor has to move while pressed on the node before
+    /// its drag starts, like 0 for a scrubbing widget dragged as soon as it's pressed
+    pub threshold: f32,
+}
+
+impl Draggable {
+    pub const DEFAULT: Self = Self {
+        payload: None,
+        threshold: DRAG_THRESHOLD,
+    };
+
+    /// A node carrying the `payload` entity when dragged
+    pub const fn with_payload(payload: Entity) -> Self {
+        Self {
+            payload: Some(payload),
+            ..Self::DEFAULT
+        }
+    }
+
+    /// Sets the [`threshold`](Self::threshold) the cursor has to move past to start the drag
+    pub const fn with_threshold(mut self, threshold: f32) -> Self {
+        self.threshold = threshold;
+        self
+    }
+}
+
+impl Default for Draggable {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl MapEntities for Draggable {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        if let Some(payload) = &mut self.payload {
+            if let Ok(mapped_entity) = entity_map.get(*payload) {
+                *payload = mapped_entity;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Marks a UI node on which [`Draggable`] nodes can be dropped
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, PartialEq)]
+pub struct DropTarget;
+
+/// An event sent when a [`Draggable`] node starts being dragged
+#[derive(Clone, Debug)]
+pub struct DragStart {
+    /// The dragged node
+    pub source: Entity,
+    /// The [`Draggable::payload`] of the node, or the node itself
+    pub payload: Entity,
+    /// The position of the cursor when the node was pressed, in logical pixels from the top left
+    /// of the window
+    pub position: Vec2,
+}
+
+/// An event sent every frame the cursor moves while dragging a node
+#[derive(Clone, Debug)]
+pub struct Drag {
+    /// The dragged node
+    pub source: Entity,
+    /// The [`Draggable::payload`] of the node, or the node itself
+    pub payload: Entity,
+    /// The position of the cursor, in logical pixels from the top left of the window
+    pub position: Vec2,
+    /// How far the cursor moved since the last frame
+    pub delta: Vec2,
+    /// How far the cursor moved since the node was pressed
+    pub distance: Vec2,
+}
+
+/// An event sent when a dragged node enters a [`DropTarget`]
+#[derive(Clone, Debug)]
+pub struct DragEnter {
+    /// The dragged node
+    pub source: Entity,
+    /// The [`Draggable::payload`] of the node, or the node itself
+    pub payload: Entity,
+    /// The drop target under the cursor
+    pub target: Entity,
+}
+
+/// An event sent when a dragged node leaves a [`DropTarget`], or is released over it
+#[derive(Clone, Debug)]
+pub struct DragLeave {
+    /// The dragged node
+    pub source: Entity,
+    /// The [`Draggable::payload`] of the node, or the node itself
+    pub payload: Entity,
+    /// The drop target that was under the cursor
+    pub target: Entity,
+}
+
+/// An event sent when a dragged node is released over a [`DropTarget`]
+#[derive(Clone, Debug)]
+pub struct Drop {
+    /// The dropped node
+    pub source: Entity,
+    /// The [`Draggable::payload`] of the node, or the node itself
+    pub payload: Entity,
+    /// The drop target under the cursor
+    pub target: Entity,
+    /// The position of the cursor, in logical pixels from the top left of the window
+    pub position: Vec2,
+}
+
+/// An event sent when a dragged node is released, after the [`Drop`] event if it was dropped on
+/// a [`DropTarget`]
+#[derive(Clone, Debug)]
+pub struct DragEnd {
+    /// The dragged node
+    pub source: Entity,
+    /// The [`Draggable::payload`] of the node, or the node itself
+    pub payload: Entity,
+    /// The drop target the node was dropped on, if any
+    pub target: Option<Entity>,
+}
+
+/// The drag of a [`Draggable`] node, from the press on it to its release
+#[derive(Clone, Debug)]
+pub struct ActiveDrag {
+    /// The dragged node
+    pub source: Entity,
+    /// The [`Draggable::payload`] of the node, or the node itself
+    pub payload: Entity,
+    /// The position of the cursor when the node was pressed
+    pub start_position: Vec2,
+    /// The last position of the cursor
+    pub position: Vec2,
+    /// The [`DropTarget`] under the cursor
+    pub target: Option<Entity>,
+    /// The [`Draggable::threshold`] of the node
+    pub threshold: f32,
+    /// Whether the cursor moved further than the threshold, starting the drag
+    pub started: bool,
+}
+
+/// The state of the drag and drop of the UI nodes, updated by [`ui_drag_system`]
+#[derive(Resource, Default, Debug)]
+pub struct DragState {
+    drag: Option<ActiveDrag>,
+}
+
+impl DragState {
+    /// The node being dragged, if any
+    pub fn active(&self) -> Option<&ActiveDrag> {
+        self.drag.as_ref().filter(|drag| drag.started)
+    }
+
+    /// Whether a node is being dragged
+    pub fn is_dragging(&self) -> bool {
+        self.active().is_some()
+    }
+
+    /// Whether `entity` is the node being dragged
+    pub fn is_dragged(&self, entity: Entity) -> bool {
+        matches!(self.active(), Some(drag) if drag.source == entity)
+    }
+}
+
+/// Main query for [`ui_drag_system`]
+#[derive(WorldQuery)]
+pub struct DragNodeQuery {
+    node: &'static Node,
+    global_transform: &'static GlobalTransform,
+    draggable: Option<&'static Draggable>,
+    drop_target: Option<&'static DropTarget>,
+    focus_policy: Option<&'static FocusPolicy>,
+    calculated_clip: Option<&'static CalculatedClip>,
+    view_visibility: Option<&'static ViewVisibility>,
+}
+
+/// The events of the drag and drop, sent by [`ui_drag_system`]
+#[derive(SystemParam)]
+pub struct DragEvents<'w> {
+    drag_start: EventWriter<'w, DragStart>,
+    drag: EventWriter<'w, Drag>,
+    drag_enter: EventWriter<'w, DragEnter>,
+    drag_leave: EventWriter<'w, DragLeave>,
+    drop: EventWriter<'w, Drop>,
+    drag_end: EventWriter<'w, DragEnd>,
+}
+
+/// The system that drags the [`Draggable`] nodes pressed with the left mouse button or a touch,
+/// and drops them on the [`DropTarget`] under the cursor
+///
+/// Like for [`Interaction`](crate::Interaction), only the top nodes under the cursor are
+/// considered, down to the first node with a [`FocusPolicy::Block`]. The dragged node and its
+/// descendants are skipped when looking for the drop target, as they usually follow the cursor.
+/// The nodes outside of the [`ActiveModal`] are neither dragged nor dropped on.
+#[allow(clippy::too_many_arguments)]
+pub fn ui_drag_system(
+    mut drag_state: ResMut<DragState>,
+    mut events: DragEvents,
+    mouse_button_input: Res<Input<MouseButton>>,
+    touches_input: Res<Touches>,
+    ui_stack: Res<UiStack>,
+    active_modal: Res<ActiveModal>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    node_query: Query<DragNodeQuery>,
+    parent_query: Query<&Parent>,
+) {
+    let cursor_position = windows
+        .get_single()
+        .ok()
+        .and_then(|window| {
+            window.cursor_position().map(|mut cursor_position| {
+                cursor_position.y = window.height() - cursor_position.y;
+                cursor_position
+            })
+        })
+        .or_else(|| touches_input.first_pressed_position());
+
+    // The nodes under the cursor from the top one, down to the first one blocking the others
+    let nodes_under = |position: Vec2| {
+        let mut nodes = Vec::new();
+        for entity in ui_stack.uinodes.iter().rev() {
+            let Ok(node) = node_query.get(*entity) else {
+                continue;
+            };
+            if !active_modal.allows(*entity)
+                || matches!(node.view_visibility, Some(view_visibility) if !view_visibility.get())
+            {
+                continue;
+            }
+            let node_rect = Rect::from_center_size(
+                node.global_transform.translation().truncate(),
+                node.node.size(),
+            );
+            let visible_rect = match node.calculated_clip {
+                Some(clip) => clip.clip.intersect(node_rect),
+                None => node_rect,
+            };
+            if !visible_rect.contains(position) {
+                continue;
+            }
+            let blocks = *node.focus_policy.unwrap_or(&FocusPolicy::Block) == FocusPolicy::Block;
+            nodes.push((*entity, node));
+            if blocks {
+                break;
+            }
+        }
+        nodes
+    };
+
+    let pressed =
+        mouse_button_input.just_pressed(MouseButton::Left) || touches_input.any_just_pressed();
+    if pressed && drag_state.drag.is_none() {
+        if let Some(position) = cursor_position {
+            drag_state.drag = nodes_under(position)
+                .into_iter()
+                .find_map(|(entity, node)| {
+                    node.draggable.map(|draggable| ActiveDrag {
+                        source: entity,
+                        payload: draggable.payload.unwrap_or(entity),
+                        start_position: position,
+                        position,
+                        target: None,
+                        threshold: draggable.threshold,
+                        started: false,
+                    })
+                });
+        }
+    }
+
+    let Some(drag) = &mut drag_state.drag else {
+        return;
+    };
+    // The drag is cancelled if its node is despawned or hidden
+    let source_visible = matches!(
+        node_query.get(drag.source),
+        Ok(node) if !matches!(node.view_visibility, Some(view_visibility) if !view_visibility.get())
+    );
+    if !source_visible {
+        if drag.started {
+            if let Some(target) = drag.target {
+                events.drag_leave.send(DragLeave {
+                    source: drag.source,
+                    payload: drag.payload,
+                    target,
+                });
+            }
+            events.drag_end.send(DragEnd {
+                source: drag.source,
+                payload: drag.payload,
+                target: None,
+            });
+        }
+        drag_state.drag = None;
+        return;
+    }
+
+    if let Some(position) = cursor_position {
+        if !drag.started && position.distance(drag.start_position) >= drag.threshold {
+            drag.started = true;
+            events.drag_start.send(DragStart {
+                source: drag.source,
+                payload: drag.payload,
+                position: drag.start_position,
+            });
+        }
+        if drag.started && position != drag.position {
+            events.drag.send(Drag {
+                source: drag.source,
+                payload: drag.payload,
+                position,
+                delta: position - drag.position,
+                distance: position - drag.start_position,
+            });
+        }
+        drag.position = position;
+    }
+
+    if drag.started {
+        let source = drag.source;
+        let target = nodes_under(drag.position)
+            .into_iter()
+            .filter(|(entity, _)| {
+                *entity != source && !parent_query.iter_ancestors(*entity).any(|e| e == source)
+            })
+            .find_map(|(entity, node)| node.drop_target.map(|_| entity));
+        if target != drag.target {
+            if let Some(target) = drag.target {
+                events.drag_leave.send(DragLeave {
+                    source,
+                    payload: drag.payload,
+                    target,
+                });
+            }
+            if let Some(target) = target {
+                events.drag_enter.send(DragEnter {
+                    source,
+                    payload: drag.payload,
+                    target,
+                });
+            }
+            drag.target = target;
+        }
+    }
+
+    let released =
+        mouse_button_input.just_released(MouseButton::Left) || touches_input.any_just_released();
+    if released {
+        if drag.started {
+            if let Some(target) = drag.target {
+                events.drag_leave.send(DragLeave {
+                    source: drag.source,
+                    payload: drag.payload,
+                    target,
+                });
+                events.drop.send(Drop {
+                    source: drag.source,
+                    payload: drag.payload,
+                    target,
+                    position: drag.position,
+                });
+            }
+            events.drag_end.send(DragEnd {
+                source: drag.source,
+                payload: drag.payload,
+                target: drag.target,
+            });
+        }
+        drag_state.drag = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{event::Events, schedule::Schedule, world::World};
+    use bevy_hierarchy::BuildWorldChildren;
+    use bevy_math::Vec3;
+
+    /// The events sent in a frame, grouped by type in the order they are sent on a release
+    #[derive(Debug, PartialEq)]
+    enum Sent {
+        Start(Vec2),
+        Drag { delta: Vec2, distance: Vec2 },
+        Leave(Entity),
+        Enter(Entity),
+        Drop(Entity),
+        End(Option<Entity>),
+    }
+
+    /// Nodes dragged with the mouse by running the [`ui_drag_system`]
+    struct Scene {
+        world: World,
+        schedule: Schedule,
+        window: Entity,
+        source: Entity,
+        payload: Entity,
+    }
+
+    impl Scene {
+        /// A [`Draggable`] node at the top left of the window, with [`DropTarget`]s of the
+        /// same size to its right
+        fn new(draggable: impl FnOnce(Entity) -> Draggable, targets: usize) -> (Self, Vec<Entity>) {
+            let mut world = World::new();
+            world.init_resource::<DragState>();
+            world.init_resource::<Input<MouseButton>>();
+            world.init_resource::<Touches>();
+            world.init_resource::<ActiveModal>();
+            world.init_resource::<Events<DragStart>>();
+            world.init_resource::<Events<Drag>>();
+            world.init_resource::<Events<DragEnter>>();
+            world.init_resource::<Events<DragLeave>>();
+            world.init_resource::<Events<Drop>>();
+            world.init_resource::<Events<DragEnd>>();
+            let window = world.spawn((Window::default(), PrimaryWindow)).id();
+            let payload = world.spawn_empty().id();
+            let draggable = draggable(payload);
+            let source = Self::spawn_node(&mut world, 0, draggable);
+            let targets: Vec<Entity> = (1..=targets)
+                .map(|column| Self::spawn_node(&mut world, column, DropTarget))
+                .collect();
+            let mut uinodes = targets.clone();
+            uinodes.push(source);
+            world.insert_resource(UiStack { uinodes });
+
+            let mut schedule = Schedule::new();
+            schedule.add_system(ui_drag_system);
+            let scene = Self {
+                world,
+                schedule,
+                window,
+                source,
+                payload: draggable.payload.unwrap_or(source),
+            };
+            (scene, targets)
+        }
+
+        /// A node of 100 by 100 pixels in the `column` of the first row of the window
+        fn spawn_node(world: &mut World, column: usize, component: impl Component) -> Entity {
+            let node = Node {
+                calculated_size: Vec2::splat(100.0),
+                ..Default::default()
+            };
+            let center = Vec3::new(50.0 + 200.0 * column as f32, 50.0, 0.0);
+            world
+                .spawn((node, GlobalTransform::from_translation(center), component))
+                .id()
+        }
+
+        /// Moves the cursor to `position`, from the top left of the window, and runs a frame
+        fn move_to(&mut self, x: f32, y: f32) -> Vec<Sent> {
+            let mut window = self.world.get_mut::<Window>(self.window).unwrap();
+            let height = window.height();
+            window.set_cursor_position(Some(Vec2::new(x, height - y)));
+            self.run()
+        }
+
+        fn press(&mut self) -> Vec<Sent> {
+            let mut mouse_button_input = self.world.resource_mut::<Input<MouseButton>>();
+            mouse_button_input.press(MouseButton::Left);
+            self.run()
+        }
+
+        fn release(&mut self) -> Vec<Sent> {
+            let mut mouse_button_input = self.world.resource_mut::<Input<MouseButton>>();
+            mouse_button_input.release(MouseButton::Left);
+            self.run()
+        }
+
+        /// Runs a frame, returning the events sent, which are all sent for the source and the
+        /// payload of the scene
+        fn run(&mut self) -> Vec<Sent> {
+            self.schedule.run(&mut self.world);
+            self.world.resource_mut::<Input<MouseButton>>().clear();
+
+            let (source, payload) = (self.source, self.payload);
+            let mut sent = Vec::new();
+            macro_rules! drain {
+                ($event:ty, |$e:ident| $sent:expr) => {
+                    for $e in self.world.resource_mut::<Events<$event>>().drain() {
+                        assert_eq!(($e.source, $e.payload), (source, payload));
+                        sent.push($sent);
+                    }
+                };
+            }
+            drain!(DragStart, |e| Sent::Start(e.position));
+            drain!(Drag, |e| Sent::Drag {
+                delta: e.delta,
+                distance: e.distance,
+            });
+            drain!(DragLeave, |e| Sent::Leave(e.target));
+            drain!(DragEnter, |e| Sent::Enter(e.target));
+            drain!(Drop, |e| Sent::Drop(e.target));
+            drain!(DragEnd, |e| Sent::End(e.target));
+            sent
+        }
+
+        fn state(&self) -> &DragState {
+            self.world.resource::<DragState>()
+        }
+    }
+
+    fn drag(x: f32, y: f32, distance_x: f32, distance_y: f32) -> Sent {
+        Sent::Drag {
+            delta: Vec2::new(x, y),
+            distance: Vec2::new(distance_x, distance_y),
+        }
+    }
+
+    #[test]
+    fn start_enter_leave_drop_sequence() {
+        let (mut scene, targets) = Scene::new(|_| Draggable::DEFAULT, 2);
+        let (a, b) = (targets[0], targets[1]);
+        let source = scene.source;
+
+        assert_eq!(scene.move_to(50.0, 50.0), []);
+        assert_eq!(scene.press(), []);
+        // under the threshold
+        assert_eq!(scene.move_to(52.0, 50.0), []);
+        assert!(!scene.state().is_dragging());
+        assert_eq!(
+            scene.move_to(250.0, 50.0),
+            [
+                Sent::Start(Vec2::new(50.0, 50.0)),
+                drag(198.0, 0.0, 200.0, 0.0),
+                Sent::Enter(a),
+            ]
+        );
+        assert!(scene.state().is_dragged(source));
+        assert_eq!(scene.state().active().unwrap().target, Some(a));
+        // moving over the same target
+        assert_eq!(scene.move_to(260.0, 60.0), [drag(10.0, 10.0, 210.0, 10.0)]);
+        assert_eq!(
+            scene.move_to(450.0, 50.0),
+            [
+                drag(190.0, -10.0, 400.0, 0.0),
+                Sent::Leave(a),
+                Sent::Enter(b)
+            ]
+        );
+        // out of the targets
+        assert_eq!(
+            scene.move_to(450.0, 300.0),
+            [drag(0.0, 250.0, 400.0, 250.0), Sent::Leave(b)]
+        );
+        assert_eq!(scene.state().active().unwrap().target, None);
+        assert_eq!(
+            scene.move_to(450.0, 50.0),
+            [drag(0.0, -250.0, 400.0, 0.0), Sent::Enter(b)]
+        );
+        // not moving
+        assert_eq!(scene.run(), []);
+        assert_eq!(
+            scene.release(),
+            [Sent::Leave(b), Sent::Drop(b), Sent::End(Some(b))]
+        );
+        assert!(!scene.state().is_dragging());
+        assert_eq!(scene.move_to(250.0, 50.0), []);
+    }
+
+    #[test]
+    fn released_out_of_the_targets() {
+        let (mut scene, _) = Scene::new(|_| Draggable::DEFAULT, 1);
+        scene.move_to(50.0, 50.0);
+        scene.press();
+        assert_eq!(
+            scene.move_to(50.0, 300.0),
+            [
+                Sent::Start(Vec2::new(50.0, 50.0)),
+                drag(0.0, 250.0, 0.0, 250.0),
+            ]
+        );
+        assert_eq!(scene.release(), [Sent::End(None)]);
+    }
+
+    #[test]
+    fn click_under_the_threshold() {
+        let (mut scene, _) = Scene::new(|_| Draggable::DEFAULT.with_threshold(10.0), 1);
+        scene.move_to(50.0, 50.0);
+        scene.press();
+        assert_eq!(scene.move_to(57.0, 57.0), []);
+        assert_eq!(scene.release(), []);
+        assert!(scene.state().drag.is_none());
+    }
+
+    #[test]
+    fn press_out_of_the_draggable_nodes() {
+        let (mut scene, _) = Scene::new(|_| Draggable::DEFAULT, 1);
+        scene.move_to(250.0, 50.0);
+        scene.press();
+        assert!(scene.state().drag.is_none());
+        assert_eq!(scene.move_to(50.0, 50.0), []);
+    }
+
+    #[test]
+    fn zero_threshold_starts_on_press() {
+        // the events are checked to carry the payload
+        let (mut scene, targets) = Scene::new(
+            |payload| Draggable::with_payload(payload).with_threshold(0.0),
+            1,
+        );
+        scene.move_to(50.0, 50.0);
+        assert_eq!(scene.press(), [Sent::Start(Vec2::new(50.0, 50.0))]);
+        assert_eq!(
+            scene.move_to(250.0, 50.0),
+            [drag(200.0, 0.0, 200.0, 0.0), Sent::Enter(targets[0])]
+        );
+        assert_eq!(
+            scene.release(),
+            [
+                Sent::Leave(targets[0]),
+                Sent::Drop(targets[0]),
+                Sent::End(Some(targets[0])),
+            ]
+        );
+    }
+
+    #[test]
+    fn despawned_source_cancels_the_drag() {
+        let (mut scene, targets) = Scene::new(|_| Draggable::DEFAULT, 1);
+        scene.move_to(50.0, 50.0);
+        scene.press();
+        scene.move_to(250.0, 50.0);
+        scene.world.despawn(scene.source);
+        assert_eq!(scene.run(), [Sent::Leave(targets[0]), Sent::End(None)]);
+        assert!(scene.state().drag.is_none());
+        assert_eq!(scene.release(), []);
+    }
+
+    #[test]
+    fn descendants_of_the_source_are_not_targets() {
+        let (mut scene, targets) = Scene::new(|_| Draggable::DEFAULT, 1);
+        let source = scene.source;
+        scene.world.entity_mut(source).push_children(&targets);
+        scene.move_to(50.0, 50.0);
+        scene.press();
+        assert_eq!(
+            scene.move_to(250.0, 50.0),
+            [
+                Sent::Start(Vec2::new(50.0, 50.0)),
+                drag(200.0, 0.0, 200.0, 0.0),
+            ]
+        );
+        assert_eq!(scene.release(), [Sent::End(None)]);
+    }
+}
@@ -100,7 +100,8 @@ impl From<AlignContent> for taffy::style::AlignContent {
 impl From<Display> for taffy::style::Display {
     fn from(value: Display) -> Self {
         match value {
-            Display::Flex => taffy::style::Display::Flex,
+            // the items of grids are positioned absolutely by the `grid` module
+            Display::Flex | Display::Grid => taffy::style::Display::Flex,
             Display::None => taffy::style::Display::None,
         }
     }
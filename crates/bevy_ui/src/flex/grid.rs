@@ -0,0 +1,873 @@
+//! Grid layout on top of the flexbox layout of taffy.
+//!
+//! The tracks of a grid are sized from the sizes of its items, measured by taffy, and the items
+//! are then positioned absolutely in their cells. The items stretch to fill their cells, unless
+//! they have a size of their own, in which case they are placed at the start of the cell
+//! horizontally and aligned by `align_self` or the `align_items` of the grid vertically.
+
+use super::{convert, FlexSurface};
+use crate::{AlignItems, AlignSelf, GridAutoFlow, GridPlacement, GridTrack, Style, Val};
+use bevy_ecs::entity::Entity;
+use std::ops::Range;
+use taffy::{prelude::Size, style::Dimension, tree::LayoutTree};
+
+/// A node with [`Display::Grid`](crate::Display::Grid) and the children laid out in its cells
+pub(crate) struct GridNode<'a> {
+    pub entity: Entity,
+    pub style: &'a Style,
+    pub items: Vec<(Entity, &'a Style)>,
+    /// The scale factor of the target of the grid
+    pub scale_factor: f64,
+}
+
+/// The cells of a grid an item is placed over
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct GridArea {
+    columns: Range<usize>,
+    rows: Range<usize>,
+}
+
+/// The items of a grid placed in its cells, with the grid sized to its items
+struct PlacedGrid {
+    node: taffy::node::Node,
+    depth: usize,
+    padding_border: [f32; 4],
+    border: [f32; 4],
+    areas: Vec<GridArea>,
+    columns: usize,
+    rows: usize,
+}
+
+impl FlexSurface {
+    /// Sizes the `grids` to their items and places their items in their cells, computing the
+    /// layouts again as needed
+    pub(crate) fn update_grids(&mut self, grids: &[GridNode]) {
+        // the grids are sized from the innermost ones, which are items of the outer ones
+        let mut placed_grids: Vec<(&GridNode, PlacedGrid)> = grids
+            .iter()
+            .filter_map(|grid| {
+                let node = *self.entity_to_taffy.get(&grid.entity)?;
+                let mut depth = 0;
+                let mut parent = self.taffy.parent(node);
+                while let Some(node) = parent {
+                    depth += 1;
+                    parent = self.taffy.parent(node);
+                }
+                let placed = PlacedGrid {
+                    node,
+                    depth,
+                    padding_border: [0.0; 4],
+                    border: [0.0; 4],
+                    areas: Vec::new(),
+                    columns: 0,
+                    rows: 0,
+                };
+                Some((grid, placed))
+            })
+            .collect();
+        placed_grids.sort_by_key(|(_, placed)| usize::MAX - placed.depth);
+
+        for (grid, placed) in &mut placed_grids {
+            let scale_factor = grid.scale_factor;
+            for &(entity, style) in &grid.items {
+                self.measure_grid_item(entity, style, scale_factor);
+            }
+
+            let item_styles: Vec<&Style> = grid.items.iter().map(|(_, style)| *style).collect();
+            let (areas, columns, rows) = place_items(grid.style, &item_styles);
+            (placed.padding_border, placed.border) =
+                self.padding_border(placed.node, grid.style, scale_factor);
+            placed.areas = areas;
+            placed.columns = columns;
+            placed.rows = rows;
+
+            // the grid is at least as large as its tracks sized to its items
+            let (column_sizes, row_sizes) = self.track_sizes(grid, placed, None, scale_factor);
+            let [left, right, top, bottom] = placed.padding_border;
+            let mut style = convert::from_style(scale_factor, grid.style);
+            if is_auto(style.size.width) && is_auto(style.min_size.width) {
+                style.min_size.width = Dimension::Points(
+                    span_size(
+                        &column_sizes,
+                        0..columns,
+                        gap(grid.style.gap.width, scale_factor),
+                    ) + left
+                        + right,
+                );
+            }
+            if is_auto(style.size.height) && is_auto(style.min_size.height) {
+                style.min_size.height = Dimension::Points(
+                    span_size(
+                        &row_sizes,
+                        0..rows,
+                        gap(grid.style.gap.height, scale_factor),
+                    ) + top
+                        + bottom,
+                );
+            }
+            self.set_style_if_neq(placed.node, style);
+        }
+        self.compute_camera_layouts();
+
+        // the items are placed in the tracks sized to the grid, from the outermost grids
+        for (grid, placed) in placed_grids.iter().rev() {
+            let scale_factor = grid.scale_factor;
+            let layout = *self.taffy.layout(placed.node).unwrap();
+            let [left, right, top, bottom] = placed.padding_border;
+            let inner_size = Size {
+                width: (layout.size.width - left - right).max(0.0),
+                height: (layout.size.height - top - bottom).max(0.0),
+            };
+            let (column_sizes, row_sizes) =
+                self.track_sizes(grid, placed, Some(inner_size), scale_factor);
+            let column_gap = gap(grid.style.gap.width, scale_factor);
+            let row_gap = gap(grid.style.gap.height, scale_factor);
+            // taffy offsets the absolute items by the border, but not by the padding
+            let [border_left, _, border_top, _] = placed.border;
+
+            let mut changed = false;
+            for (&(entity, item_style), area) in grid.items.iter().zip(&placed.areas) {
+                let Some(&node) = self.entity_to_taffy.get(&entity) else {
+                    continue;
+                };
+                let x = left - border_left
+                    + span_size(&column_sizes, 0..area.columns.start, column_gap)
+                    + if area.columns.start > 0 {
+                        column_gap
+                    } else {
+                        0.0
+                    };
+                let y = top - border_top
+                    + span_size(&row_sizes, 0..area.rows.start, row_gap)
+                    + if area.rows.start > 0 { row_gap } else { 0.0 };
+                let cell_size = Size {
+                    width: span_size(&column_sizes, area.columns.clone(), column_gap),
+                    height: span_size(&row_sizes, area.rows.clone(), row_gap),
+                };
+                let measured_size = self.grid_items.get(&entity).copied().unwrap_or(Size::ZERO);
+                let mut style = convert::from_style(scale_factor, item_style);
+                let margin = margin_size(item_style, cell_size.width, scale_factor);
+
+                style.position_type = taffy::style::PositionType::Absolute;
+                style.position = taffy::geometry::Rect {
+                    left: Dimension::Points(x),
+                    right: Dimension::Undefined,
+                    top: Dimension::Points(y),
+                    bottom: Dimension::Undefined,
+                };
+                style.size.width = match style.size.width {
+                    Dimension::Points(width) => Dimension::Points(width),
+                    Dimension::Percent(percent) => Dimension::Points(cell_size.width * percent),
+                    Dimension::Auto | Dimension::Undefined => {
+                        Dimension::Points((cell_size.width - margin.width).max(0.0))
+                    }
+                };
+                let align = match item_style.align_self {
+                    AlignSelf::Auto => match grid.style.align_items {
+                        AlignItems::FlexStart | AlignItems::Baseline => AlignSelf::FlexStart,
+                        AlignItems::FlexEnd => AlignSelf::FlexEnd,
+                        AlignItems::Center => AlignSelf::Center,
+                        AlignItems::Stretch => AlignSelf::Stretch,
+                    },
+                    align_self => align_self,
+                };
+                style.size.height = match style.size.height {
+                    Dimension::Percent(percent) => Dimension::Points(cell_size.height * percent),
+                    Dimension::Auto | Dimension::Undefined if align == AlignSelf::Stretch => {
+                        Dimension::Points((cell_size.height - margin.height).max(0.0))
+                    }
+                    height => height,
+                };
+                let height = match style.size.height {
+                    Dimension::Points(height) => height + margin.height,
+                    _ => measured_size.height,
+                };
+                let free_height = cell_size.height - height;
+                style.position.top = Dimension::Points(
+                    y + match align {
+                        AlignSelf::Center => free_height / 2.0,
+                        AlignSelf::FlexEnd => free_height,
+                        _ => 0.0,
+                    },
+                );
+                changed |= self.set_style_if_neq(node, style);
+            }
+            if changed {
+                self.compute_camera_layouts();
+            }
+        }
+    }
+
+    /// Measures the size of a grid item, with its margins, when its layout has changed
+    fn measure_grid_item(&mut self, entity: Entity, style: &Style, scale_factor: f64) {
+        let Some(&node) = self.entity_to_taffy.get(&entity) else {
+            return;
+        };
+        if self.grid_items.contains_key(&entity) && !self.taffy.dirty(node).unwrap() {
+            return;
+        }
+        // the item is measured with its own style, not its placement in the grid
+        let placed_style = *self.taffy.style(node).unwrap();
+        self.taffy
+            .set_style(node, convert::from_style(scale_factor, style))
+            .unwrap();
+        self.taffy.compute_layout(node, Size::MAX_CONTENT).unwrap();
+        let size = self.taffy.layout(node).unwrap().size;
+        self.taffy.set_style(node, placed_style).unwrap();
+
+        let margin = margin_size(style, 0.0, scale_factor);
+        self.grid_items.insert(
+            entity,
+            Size {
+                width: size.width + margin.width,
+                height: size.height + margin.height,
+            },
+        );
+    }
+
+    /// The left, right, top and bottom padding and border of a grid, and its border alone
+    fn padding_border(
+        &self,
+        node: taffy::node::Node,
+        style: &Style,
+        scale_factor: f64,
+    ) -> ([f32; 4], [f32; 4]) {
+        // percentages are of the width of the parent, like in flexbox
+        let parent_width = self
+            .taffy
+            .parent(node)
+            .map_or(0.0, |parent| self.taffy.layout(parent).unwrap().size.width);
+        let resolve = |rect: crate::UiRect| {
+            [rect.left, rect.right, rect.top, rect.bottom]
+                .map(|val| resolve_val(val, parent_width, scale_factor))
+        };
+        let (padding, border) = (resolve(style.padding), resolve(style.border));
+        (
+            [0, 1, 2, 3].map(|side| padding[side] + border[side]),
+            border,
+        )
+    }
+
+    /// The sizes of the columns and the rows of a grid, sized to its items when the
+    /// `inner_size` of the grid is not known yet
+    fn track_sizes(
+        &self,
+        grid: &GridNode,
+        placed: &PlacedGrid,
+        inner_size: Option<Size<f32>>,
+        scale_factor: f64,
+    ) -> (Vec<f32>, Vec<f32>) {
+        let item_sizes: Vec<Size<f32>> = grid
+            .items
+            .iter()
+            .map(|(entity, _)| self.grid_items.get(entity).copied().unwrap_or(Size::ZERO))
+            .collect();
+        let columns = track_sizes(
+            &grid.style.grid_template_columns,
+            grid.style.grid_auto_columns,
+            placed.columns,
+            placed
+                .areas
+                .iter()
+                .zip(&item_sizes)
+                .map(|(area, size)| (area.columns.clone(), size.width)),
+            inner_size.map(|size| size.width),
+            gap(grid.style.gap.width, scale_factor),
+            scale_factor,
+        );
+        let rows = track_sizes(
+            &grid.style.grid_template_rows,
+            grid.style.grid_auto_rows,
+            placed.rows,
+            placed
+                .areas
+                .iter()
+                .zip(&item_sizes)
+                .map(|(area, size)| (area.rows.clone(), size.height)),
+            inner_size.map(|size| size.height),
+            gap(grid.style.gap.height, scale_factor),
+            scale_factor,
+        );
+        (columns, rows)
+    }
+
+    /// Sets the style of a node, only marking the layout as outdated when it has changed
+    fn set_style_if_neq(&mut self, node: taffy::node::Node, style: taffy::style::Style) -> bool {
+        if *self.taffy.style(node).unwrap() == style {
+            return false;
+        }
+        self.taffy.set_style(node, style).unwrap();
+        true
+    }
+}
+
+fn is_auto(dimension: Dimension) -> bool {
+    matches!(dimension, Dimension::Auto | Dimension::Undefined)
+}
+
+fn resolve_val(val: Val, basis: f32, scale_factor: f64) -> f32 {
+    match val {
+        Val::Px(value) => (scale_factor * value as f64) as f32,
+        Val::Percent(percent) => basis * percent / 100.0,
+        Val::Auto | Val::Undefined => 0.0,
+    }
+}
+
+fn gap(gap: Val, scale_factor: f64) -> f32 {
+    match gap {
+        Val::Px(value) => (scale_factor * value as f64) as f32,
+        _ => 0.0,
+    }
+}
+
+fn margin_size(style: &Style, basis: f32, scale_factor: f64) -> Size<f32> {
+    let margin = |val| resolve_val(val, basis, scale_factor);
+    Size {
+        width: margin(style.margin.left) + margin(style.margin.right),
+        height: margin(style.margin.top) + margin(style.margin.bottom),
+    }
+}
+
+/// The size of the `tracks` in the `span`, with the gaps between them
+fn span_size(tracks: &[f32], span: Range<usize>, gap: f32) -> f32 {
+    let count = span.len();
+    tracks[span].iter().sum::<f32>() + gap * count.saturating_sub(1) as f32
+}
+
+/// The sizes of `count` tracks from the `template`, with the tracks after the template sized
+/// by `auto_track`
+///
+/// The tracks sized to their content fit the sizes of the `items` spanning them, and the
+/// flexible tracks share the space left in the `inner_size` of the grid when it is known.
+fn track_sizes(
+    template: &[GridTrack],
+    auto_track: GridTrack,
+    count: usize,
+    items: impl Iterator<Item = (Range<usize>, f32)>,
+    inner_size: Option<f32>,
+    gap: f32,
+    scale_factor: f64,
+) -> Vec<f32> {
+    let tracks: Vec<GridTrack> = (0..count)
+        .map(|index| template.get(index).copied().unwrap_or(auto_track))
+        .collect();
+    let sized_by_content = |track: GridTrack| match track {
+        GridTrack::Auto => true,
+        GridTrack::Px(_) => false,
+        GridTrack::Percent(_) | GridTrack::Fr(_) => inner_size.is_none(),
+    };
+    let mut sizes: Vec<f32> = tracks
+        .iter()
+        .map(|&track| match (track, inner_size) {
+            (GridTrack::Px(value), _) => (scale_factor * value as f64) as f32,
+            (GridTrack::Percent(percent), Some(inner_size)) => inner_size * percent / 100.0,
+            _ => 0.0,
+        })
+        .collect();
+
+    // the items spanning a single track size it first, then the items spanning several tracks
+    // grow the tracks sized to their content evenly
+    let (single, multiple): (Vec<_>, Vec<_>) = items
+        .filter(|(span, _)| span.end <= count)
+        .partition(|(span, _)| span.len() == 1);
+    for (span, size) in single {
+        if sized_by_content(tracks[span.start]) {
+            sizes[span.start] = sizes[span.start].max(size);
+        }
+    }
+    for (span, size) in multiple {
+        let growing: Vec<usize> = span
+            .clone()
+            .filter(|&index| sized_by_content(tracks[index]))
+            .collect();
+        let extra = size - span_size(&sizes, span, gap);
+        if extra > 0.0 && !growing.is_empty() {
+            for index in &growing {
+                sizes[*index] += extra / growing.len() as f32;
+            }
+        }
+    }
+
+    if let Some(inner_size) = inner_size {
+        let fr_total: f32 = tracks
+            .iter()
+            .map(|track| match track {
+                GridTrack::Fr(fr) => fr.max(0.0),
+                _ => 0.0,
+            })
+            .sum();
+        if fr_total > 0.0 {
+            let free = (inner_size - span_size(&sizes, 0..count, gap)).max(0.0);
+            // less than a fraction in total leaves some of the space free, like in CSS
+            for (size, track) in sizes.iter_mut().zip(&tracks) {
+                if let GridTrack::Fr(fr) = track {
+                    *size = free * fr.max(0.0) / fr_total.max(1.0);
+                }
+            }
+        }
+    }
+    sizes
+}
+
+/// Places the `items` in the cells of the `grid`, returning their areas and the number of
+/// columns and rows of the grid
+fn place_items(grid: &Style, items: &[&Style]) -> (Vec<GridArea>, usize, usize) {
+    let template_areas: Vec<Vec<&str>> = grid
+        .grid_template_areas
+        .iter()
+        .map(|row| row.split_whitespace().collect())
+        .collect();
+    let explicit = [
+        grid.grid_template_columns
+            .len()
+            .max(template_areas.iter().map(Vec::len).max().unwrap_or(0)),
+        grid.grid_template_rows.len().max(template_areas.len()),
+    ];
+
+    // the start and the span of each item along the columns and the rows
+    let placements: Vec<[(Option<usize>, usize); 2]> = items
+        .iter()
+        .map(|item| {
+            let area = item
+                .grid_area
+                .as_deref()
+                .and_then(|name| find_area(&template_areas, name));
+            match area {
+                Some(area) => [
+                    (Some(area.columns.start), area.columns.len()),
+                    (Some(area.rows.start), area.rows.len()),
+                ],
+                None => [
+                    resolve_placement(item.grid_column, explicit[0]),
+                    resolve_placement(item.grid_row, explicit[1]),
+                ],
+            }
+        })
+        .collect();
+
+    // the items fill the tracks along the `fixed` axis before tracks are added along the other
+    let (fixed, growing) = match grid.grid_auto_flow {
+        GridAutoFlow::Row => (0, 1),
+        GridAutoFlow::Column => (1, 0),
+    };
+    let mut fixed_count = placements
+        .iter()
+        .map(|placement| match placement[fixed] {
+            (Some(start), span) => start + span,
+            (None, span) => span,
+        })
+        .fold(explicit[fixed].max(1), usize::max);
+
+    // the occupied cells, by track along the growing axis
+    let mut occupied: Vec<Vec<bool>> = Vec::new();
+    let is_free = |occupied: &Vec<Vec<bool>>, growing: Range<usize>, fixed: Range<usize>| {
+        growing.into_iter().all(|g| match occupied.get(g) {
+            Some(cells) => fixed
+                .clone()
+                .all(|f| !cells.get(f).copied().unwrap_or(false)),
+            None => true,
+        })
+    };
+    let occupy = |occupied: &mut Vec<Vec<bool>>,
+                  (g, f): (usize, usize),
+                  placement: &[(Option<usize>, usize); 2]| {
+        let (growing_span, fixed_span) = (placement[growing].1, placement[fixed].1);
+        if occupied.len() < g + growing_span {
+            occupied.resize(g + growing_span, Vec::new());
+        }
+        for cells in &mut occupied[g..g + growing_span] {
+            if cells.len() < f + fixed_span {
+                cells.resize(f + fixed_span, false);
+            }
+            for cell in &mut cells[f..f + fixed_span] {
+                *cell = true;
+            }
+        }
+    };
+    let mut starts = vec![None; placements.len()];
+
+    // the items placed along both axes first, then the items placed along the tracks the
+    // grid grows by, then the others in order, like in CSS
+    for (index, placement) in placements.iter().enumerate() {
+        if let ((Some(fixed_start), _), (Some(growing_start), _)) =
+            (placement[fixed], placement[growing])
+        {
+            starts[index] = Some((growing_start, fixed_start));
+            occupy(&mut occupied, (growing_start, fixed_start), placement);
+        }
+    }
+    for (index, placement) in placements.iter().enumerate() {
+        let ((None, fixed_span), (Some(g), growing_span)) = (placement[fixed], placement[growing])
+        else {
+            continue;
+        };
+        // in the first free cells of its track, adding tracks when it is full
+        let f = (0..)
+            .find(|&f| is_free(&occupied, g..g + growing_span, f..f + fixed_span))
+            .unwrap();
+        fixed_count = fixed_count.max(f + fixed_span);
+        starts[index] = Some((g, f));
+        occupy(&mut occupied, (g, f), placement);
+    }
+    let mut cursor = (0, 0);
+    for (index, placement) in placements.iter().enumerate() {
+        if starts[index].is_some() {
+            continue;
+        }
+        let (fixed_start, fixed_span) = placement[fixed];
+        let growing_span = placement[growing].1;
+        // after the items placed automatically before it
+        let (mut g, mut f) = cursor;
+        let start = loop {
+            let candidate = fixed_start.unwrap_or(f);
+            if candidate < f || candidate + fixed_span > fixed_count {
+                g += 1;
+                f = 0;
+                continue;
+            }
+            if is_free(
+                &occupied,
+                g..g + growing_span,
+                candidate..candidate + fixed_span,
+            ) {
+                break (g, candidate);
+            }
+            if fixed_start.is_some() {
+                g += 1;
+                f = 0;
+            } else {
+                f += 1;
+            }
+        };
+        cursor = (start.0, start.1 + fixed_span);
+        starts[index] = Some(start);
+        occupy(&mut occupied, start, placement);
+    }
+
+    let growing_count = occupied.len().max(explicit[growing]);
+    let areas = placements
+        .iter()
+        .zip(starts)
+        .map(|(placement, start)| {
+            let (g, f) = start.unwrap_or_default();
+            let fixed_range = f..f + placement[fixed].1;
+            let growing_range = g..g + placement[growing].1;
+            match grid.grid_auto_flow {
+                GridAutoFlow::Row => GridArea {
+                    columns: fixed_range,
+                    rows: growing_range,
+                },
+                GridAutoFlow::Column => GridArea {
+                    columns: growing_range,
+                    rows: fixed_range,
+                },
+            }
+        })
+        .collect();
+    match grid.grid_auto_flow {
+        GridAutoFlow::Row => (areas, fixed_count, growing_count),
+        GridAutoFlow::Column => (areas, growing_count, fixed_count),
+    }
+}
+
+/// The start and the span of a placement along `explicit` tracks, with lines from the end
+/// counted back from the end of the explicit tracks
+fn resolve_placement(placement: GridPlacement, explicit: usize) -> (Option<usize>, usize) {
+    let span = placement.span.max(1) as usize;
+    let start = match placement.start {
+        Some(line) if line > 0 => Some(line as usize - 1),
+        Some(line) if line < 0 => Some((explicit as isize + 1 + line as isize).max(0) as usize),
+        _ => None,
+    };
+    (start, span)
+}
+
+/// The cells of the area with this `name` in the template areas of a grid
+fn find_area(template_areas: &[Vec<&str>], name: &str) -> Option<GridArea> {
+    if name == "." {
+        return None;
+    }
+    let mut area: Option<GridArea> = None;
+    for (row, names) in template_areas.iter().enumerate() {
+        for (column, _) in names.iter().enumerate().filter(|(_, cell)| **cell == name) {
+            let area = area.get_or_insert(GridArea {
+                columns: column..column + 1,
+                rows: row..row + 1,
+            });
+            area.columns = area.columns.start.min(column)..area.columns.end.max(column + 1);
+            area.rows = area.rows.start.min(row)..area.rows.end.max(row + 1);
+        }
+    }
+    area
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The columns and the rows of the cells of each item
+    type Cells = Vec<(Range<usize>, Range<usize>)>;
+
+    fn place(grid: &Style, items: &[Style]) -> (Cells, usize, usize) {
+        let items: Vec<&Style> = items.iter().collect();
+        let (areas, columns, rows) = place_items(grid, &items);
+        let cells = areas
+            .into_iter()
+            .map(|area| (area.columns, area.rows))
+            .collect();
+        (cells, columns, rows)
+    }
+
+    fn grid(columns: usize, rows: usize, grid_auto_flow: GridAutoFlow) -> Style {
+        Style {
+            grid_template_columns: GridTrack::repeat(columns, GridTrack::Auto),
+            grid_template_rows: GridTrack::repeat(rows, GridTrack::Auto),
+            grid_auto_flow,
+            ..Default::default()
+        }
+    }
+
+    fn item(grid_column: GridPlacement, grid_row: GridPlacement) -> Style {
+        Style {
+            grid_column,
+            grid_row,
+            ..Default::default()
+        }
+    }
+
+    fn area(name: &str) -> Style {
+        Style {
+            grid_area: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn auto_placement() {
+        let auto = GridPlacement::DEFAULT;
+        let cases = [
+            (
+                "fills the rows",
+                grid(3, 0, GridAutoFlow::Row),
+                vec![item(auto, auto); 4],
+                vec![(0..1, 0..1), (1..2, 0..1), (2..3, 0..1), (0..1, 1..2)],
+                (3, 2),
+            ),
+            (
+                "fills the columns",
+                grid(0, 2, GridAutoFlow::Column),
+                vec![item(auto, auto); 3],
+                vec![(0..1, 0..1), (0..1, 1..2), (1..2, 0..1)],
+                (2, 2),
+            ),
+            (
+                "one track without a template",
+                Style::default(),
+                vec![item(auto, auto); 2],
+                vec![(0..1, 0..1), (0..1, 1..2)],
+                (1, 2),
+            ),
+            (
+                "spans wrap to the next row",
+                grid(3, 0, GridAutoFlow::Row),
+                vec![item(GridPlacement::span(2), auto); 2],
+                vec![(0..2, 0..1), (0..2, 1..2)],
+                (3, 2),
+            ),
+            (
+                "a span wider than the template adds columns",
+                grid(2, 0, GridAutoFlow::Row),
+                vec![item(GridPlacement::span(3), auto), item(auto, auto)],
+                vec![(0..3, 0..1), (0..1, 1..2)],
+                (3, 2),
+            ),
+            (
+                "spans along the rows",
+                grid(2, 0, GridAutoFlow::Row),
+                vec![
+                    item(auto, GridPlacement::span(2)),
+                    item(auto, auto),
+                    item(auto, auto),
+                ],
+                vec![(0..1, 0..2), (1..2, 0..1), (1..2, 1..2)],
+                (2, 2),
+            ),
+            (
+                "more explicit rows than items",
+                grid(2, 3, GridAutoFlow::Row),
+                vec![item(auto, auto)],
+                vec![(0..1, 0..1)],
+                (2, 3),
+            ),
+        ];
+        for (name, grid, items, cells, (columns, rows)) in cases {
+            assert_eq!(place(&grid, &items), (cells, columns, rows), "{name}");
+        }
+    }
+
+    #[test]
+    fn explicit_placement() {
+        let auto = GridPlacement::DEFAULT;
+        let start = GridPlacement::start;
+        let cases = [
+            (
+                "placed along both axes before the automatic items",
+                grid(2, 0, GridAutoFlow::Row),
+                vec![item(auto, auto), item(start(1), start(1))],
+                vec![(1..2, 0..1), (0..1, 0..1)],
+                (2, 1),
+            ),
+            (
+                "automatic items skip the occupied cells",
+                grid(3, 0, GridAutoFlow::Row),
+                vec![
+                    item(start(2), start(1)),
+                    item(auto, auto),
+                    item(auto, auto),
+                    item(auto, auto),
+                ],
+                vec![(1..2, 0..1), (0..1, 0..1), (2..3, 0..1), (0..1, 1..2)],
+                (3, 2),
+            ),
+            (
+                "lines counted from the end of the template",
+                grid(3, 2, GridAutoFlow::Row),
+                vec![item(start(-2), start(-3))],
+                vec![(2..3, 0..1)],
+                (3, 2),
+            ),
+            (
+                "a line after the template adds columns",
+                grid(2, 0, GridAutoFlow::Row),
+                vec![item(start(4), auto)],
+                vec![(3..4, 0..1)],
+                (4, 1),
+            ),
+            (
+                "placed on a row before the automatic items",
+                grid(2, 0, GridAutoFlow::Row),
+                vec![item(auto, auto), item(auto, start(1)), item(auto, start(1))],
+                vec![(0..1, 1..2), (0..1, 0..1), (1..2, 0..1)],
+                (2, 2),
+            ),
+            (
+                "a full row adds columns",
+                grid(2, 0, GridAutoFlow::Row),
+                vec![item(auto, start(1)); 3],
+                vec![(0..1, 0..1), (1..2, 0..1), (2..3, 0..1)],
+                (3, 1),
+            ),
+            (
+                "placed on a row, in its first free cells",
+                grid(3, 0, GridAutoFlow::Row),
+                vec![
+                    item(start(1), start(1)),
+                    item(GridPlacement::span(2), start(1)),
+                ],
+                vec![(0..1, 0..1), (1..3, 0..1)],
+                (3, 1),
+            ),
+            (
+                "a row placement doesn't move the automatic items",
+                grid(2, 0, GridAutoFlow::Row),
+                vec![item(auto, auto), item(auto, start(2)), item(auto, auto)],
+                vec![(0..1, 0..1), (0..1, 1..2), (1..2, 0..1)],
+                (2, 2),
+            ),
+            (
+                "placed on a column, after the automatic items before it",
+                grid(2, 0, GridAutoFlow::Row),
+                vec![item(auto, auto), item(start(1), auto), item(auto, auto)],
+                vec![(0..1, 0..1), (0..1, 1..2), (1..2, 1..2)],
+                (2, 2),
+            ),
+            (
+                "placed on a row, filling the columns",
+                grid(0, 2, GridAutoFlow::Column),
+                vec![item(auto, auto), item(auto, start(1)), item(auto, auto)],
+                vec![(0..1, 0..1), (1..2, 0..1), (1..2, 1..2)],
+                (2, 2),
+            ),
+        ];
+        for (name, grid, items, cells, (columns, rows)) in cases {
+            assert_eq!(place(&grid, &items), (cells, columns, rows), "{name}");
+        }
+    }
+
+    #[test]
+    fn template_areas() {
+        let areas = |rows: &[&str]| Style {
+            grid_template_areas: rows.iter().map(|row| row.to_string()).collect(),
+            ..Default::default()
+        };
+        let cases = [
+            (
+                "areas over their cells",
+                areas(&["header header", "side main"]),
+                vec![area("main"), area("header"), area("side")],
+                vec![(1..2, 1..2), (0..2, 0..1), (0..1, 1..2)],
+                (2, 2),
+            ),
+            (
+                "an area over several rows",
+                areas(&["a b", "a c"]),
+                vec![area("a"), area("c")],
+                vec![(0..1, 0..2), (1..2, 1..2)],
+                (2, 2),
+            ),
+            (
+                "the names are separated by any whitespace",
+                areas(&["  a\tb  "]),
+                vec![area("b")],
+                vec![(1..2, 0..1)],
+                (2, 1),
+            ),
+            (
+                "the areas make the explicit tracks",
+                areas(&["a b c", "d"]),
+                vec![area("d")],
+                vec![(0..1, 1..2)],
+                (3, 2),
+            ),
+            (
+                "a non rectangular area covers its bounds",
+                areas(&["a a", "a b"]),
+                vec![area("a")],
+                vec![(0..2, 0..2)],
+                (2, 2),
+            ),
+            (
+                "automatic items fill the cells left",
+                areas(&["a a", ". b"]),
+                vec![Style::default(), area("a"), area("b")],
+                vec![(0..1, 1..2), (0..2, 0..1), (1..2, 1..2)],
+                (2, 2),
+            ),
+            (
+                "an unknown area is placed automatically",
+                areas(&["a b"]),
+                vec![area("a"), area("missing"), area("."), area("b")],
+                vec![(0..1, 0..1), (0..1, 1..2), (1..2, 1..2), (1..2, 0..1)],
+                (2, 2),
+            ),
+        ];
+        for (name, grid, items, cells, (columns, rows)) in cases {
+            assert_eq!(place(&grid, &items), (cells, columns, rows), "{name}");
+        }
+    }
+
+    #[test]
+    fn area_overrides_the_placement() {
+        let grid = Style {
+            grid_template_areas: vec!["a b".to_string()],
+            ..Default::default()
+        };
+        let item = Style {
+            grid_area: Some("b".to_string()),
+            grid_column: GridPlacement::start(1),
+            grid_row: GridPlacement::start_span(2, 2),
+            ..Default::default()
+        };
+        assert_eq!(place(&grid, &[item]), (vec![(1..2, 0..1)], 2, 1));
+    }
+}
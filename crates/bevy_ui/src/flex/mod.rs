@@ -1,21 +1,28 @@
 mod convert;
+mod grid;
 
-use crate::{CalculatedSize, Node, Style, UiScale};
+use crate::{
+    camera_config::DefaultUiCamera, CalculatedSize, Display, Node, Overflow, PositionType,
+    ScrollPosition, Style, UiScale, UiTargetCamera,
+};
 use bevy_ecs::{
-    change_detection::DetectChanges,
+    change_detection::{DetectChanges, DetectChangesMut},
     entity::Entity,
     event::EventReader,
     query::{Changed, ReadOnlyWorldQuery, With, Without},
     removal_detection::RemovedComponents,
     system::{Query, Res, ResMut, Resource},
+    world::Ref,
 };
 use bevy_hierarchy::{Children, Parent};
-use bevy_log::warn;
-use bevy_math::Vec2;
+use bevy_log::{info, warn};
+use bevy_math::{Rect, UVec2, Vec2};
+use bevy_render::camera::Camera;
 use bevy_transform::components::Transform;
 use bevy_utils::HashMap;
-use bevy_window::{PrimaryWindow, Window, WindowResolution, WindowScaleFactorChanged};
-use std::fmt;
+use bevy_window::WindowScaleFactorChanged;
+use grid::GridNode;
+use std::fmt::{self, Write};
 use taffy::{
     prelude::{AvailableSpace, Size},
     Taffy,
@@ -24,7 +31,12 @@ use taffy::{
 #[derive(Resource)]
 pub struct FlexSurface {
     entity_to_taffy: HashMap<Entity, taffy::node::Node>,
-    window_nodes: HashMap<Entity, taffy::node::Node>,
+    /// The nodes of the viewports of the cameras, parents of the root UI nodes drawn on them
+    camera_nodes: HashMap<Entity, taffy::node::Node>,
+    /// The cameras and scale factors of the root UI nodes during the last layout
+    root_targets: HashMap<Entity, (Entity, f64)>,
+    /// The sizes of the items of grids, measured when their layout changes
+    grid_items: HashMap<Entity, Size<f32>>,
     taffy: Taffy,
 }
 
@@ -42,7 +54,7 @@ impl fmt::Debug for FlexSurface {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.debug_struct("FlexSurface")
             .field("entity_to_taffy", &self.entity_to_taffy)
-            .field("window_nodes", &self.window_nodes)
+            .field("camera_nodes", &self.camera_nodes)
             .finish()
     }
 }
@@ -51,7 +63,9 @@ impl Default for FlexSurface {
     fn default() -> Self {
         Self {
             entity_to_taffy: Default::default(),
-            window_nodes: Default::default(),
+            camera_nodes: Default::default(),
+            root_targets: Default::default(),
+            grid_items: Default::default(),
             taffy: Taffy::new(),
         }
     }
@@ -144,11 +158,12 @@ without UI components as a child of an entity with UI components, results may be
         }
     }
 
-    pub fn update_window(&mut self, window: Entity, window_resolution: &WindowResolution) {
+    /// Sizes the node of the viewport of `camera` to its `physical_size`
+    pub fn update_camera(&mut self, camera: Entity, physical_size: UVec2) {
         let taffy = &mut self.taffy;
         let node = self
-            .window_nodes
-            .entry(window)
+            .camera_nodes
+            .entry(camera)
             .or_insert_with(|| taffy.new_leaf(taffy::style::Style::default()).unwrap());
 
         taffy
@@ -156,12 +171,8 @@ without UI components as a child of an entity with UI components, results may be
                 *node,
                 taffy::style::Style {
                     size: taffy::geometry::Size {
-                        width: taffy::style::Dimension::Points(
-                            window_resolution.physical_width() as f32
-                        ),
-                        height: taffy::style::Dimension::Points(
-                            window_resolution.physical_height() as f32,
-                        ),
+                        width: taffy::style::Dimension::Points(physical_size.x as f32),
+                        height: taffy::style::Dimension::Points(physical_size.y as f32),
                     },
                     ..Default::default()
                 },
@@ -169,22 +180,42 @@ without UI components as a child of an entity with UI components, results may be
             .unwrap();
     }
 
-    pub fn set_window_children(
-        &mut self,
-        parent_window: Entity,
-        children: impl Iterator<Item = Entity>,
-    ) {
-        let taffy_node = self.window_nodes.get(&parent_window).unwrap();
-        let child_nodes = children
-            .map(|e| *self.entity_to_taffy.get(&e).unwrap())
-            .collect::<Vec<taffy::node::Node>>();
-        self.taffy.set_children(*taffy_node, &child_nodes).unwrap();
+    /// Removes the nodes of the viewports of the cameras that aren't in `cameras` anymore
+    pub fn retain_cameras(&mut self, mut cameras: impl FnMut(Entity) -> bool) {
+        let taffy = &mut self.taffy;
+        self.camera_nodes.retain(|camera, node| {
+            let retain = cameras(*camera);
+            if !retain {
+                // taffy doesn't detach the children of the removed nodes
+                taffy.set_children(*node, &[]).unwrap();
+                taffy.remove(*node).unwrap();
+            }
+            retain
+        });
+    }
+
+    /// Sets the root UI nodes laid out in the viewports of the cameras
+    pub fn set_camera_roots(&mut self, camera_roots: &HashMap<Entity, Vec<Entity>>) {
+        // the roots are removed from their former camera before being added to the new one
+        for camera_node in self.camera_nodes.values() {
+            self.taffy.set_children(*camera_node, &[]).unwrap();
+        }
+        for (camera, roots) in camera_roots {
+            let Some(camera_node) = self.camera_nodes.get(camera) else {
+                continue;
+            };
+            let child_nodes = roots
+                .iter()
+                .filter_map(|e| self.entity_to_taffy.get(e).copied())
+                .collect::<Vec<taffy::node::Node>>();
+            self.taffy.set_children(*camera_node, &child_nodes).unwrap();
+        }
     }
 
-    pub fn compute_window_layouts(&mut self) {
-        for window_node in self.window_nodes.values() {
+    pub fn compute_camera_layouts(&mut self) {
+        for camera_node in self.camera_nodes.values() {
             self.taffy
-                .compute_layout(*window_node, Size::MAX_CONTENT)
+                .compute_layout(*camera_node, Size::MAX_CONTENT)
                 .unwrap();
         }
     }
@@ -192,6 +223,7 @@ without UI components as a child of an entity with UI components, results may be
     /// Removes each entity from the internal map and then removes their associated node from taffy
     pub fn remove_entities(&mut self, entities: impl IntoIterator<Item = Entity>) {
         for entity in entities {
+            self.grid_items.remove(&entity);
             if let Some(node) = self.entity_to_taffy.remove(&entity) {
                 self.taffy.remove(node).unwrap();
             }
@@ -211,6 +243,63 @@ with UI components as a child of an entity without UI components, results may be
             Err(FlexError::InvalidHierarchy)
         }
     }
+
+    /// Logs the layout computed for `entity` and its descendants, in the physical pixels of their
+    /// target
+    ///
+    /// Each node is shown with its display and flex direction, and with the size and the position
+    /// from its parent the layout gave it, to diagnose a layout without drawing the
+    /// [`UiDebugOverlay`](crate::UiDebugOverlay).
+    pub fn debug_print(&self, entity: Entity) {
+        let Some(taffy_node) = self.entity_to_taffy.get(&entity) else {
+            warn!("{entity:?} isn't a laid out UI node");
+            return;
+        };
+        let taffy_to_entity: HashMap<taffy::node::Node, Entity> = self
+            .entity_to_taffy
+            .iter()
+            .map(|(entity, taffy_node)| (*taffy_node, *entity))
+            .collect();
+        let mut tree = String::new();
+        self.write_debug_tree(&mut tree, &taffy_to_entity, *taffy_node, 0);
+        info!("UI layout of {entity:?}:\n{tree}");
+    }
+
+    fn write_debug_tree(
+        &self,
+        tree: &mut String,
+        taffy_to_entity: &HashMap<taffy::node::Node, Entity>,
+        taffy_node: taffy::node::Node,
+        depth: usize,
+    ) {
+        let (Ok(style), Ok(layout)) = (self.taffy.style(taffy_node), self.taffy.layout(taffy_node))
+        else {
+            return;
+        };
+        let Some(entity) = taffy_to_entity.get(&taffy_node) else {
+            return;
+        };
+        let _ = writeln!(
+            tree,
+            "{:indent$}{entity:?} {:?} {:?}: {}x{} at ({}, {}){}",
+            "",
+            style.display,
+            style.flex_direction,
+            layout.size.width,
+            layout.size.height,
+            layout.location.x,
+            layout.location.y,
+            if self.grid_items.contains_key(entity) {
+                ", grid item"
+            } else {
+                ""
+            },
+            indent = 2 * depth,
+        );
+        for child in self.taffy.children(taffy_node).unwrap_or_default() {
+            self.write_debug_tree(tree, taffy_to_entity, child, depth + 1);
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -219,49 +308,98 @@ pub enum FlexError {
     TaffyError(taffy::error::TaffyError),
 }
 
+/// The system that lays out the UI nodes, each tree in the viewport of the camera of its root node
+///
+/// The camera of a tree is the one of the [`UiTargetCamera`] of its root node, or else the
+/// [`DefaultUiCamera`]. The trees without a camera, or whose camera doesn't have a viewport yet,
+/// aren't laid out.
+///
+/// The edges of the nodes are rounded to the physical pixels of the target of their camera.
 #[allow(clippy::too_many_arguments)]
 pub fn flex_node_system(
-    primary_window: Query<(Entity, &Window), With<PrimaryWindow>>,
-    windows: Query<(Entity, &Window)>,
+    default_ui_camera: DefaultUiCamera,
+    cameras: Query<&Camera>,
     ui_scale: Res<UiScale>,
     mut scale_factor_events: EventReader<WindowScaleFactorChanged>,
     mut flex_surface: ResMut<FlexSurface>,
-    root_node_query: Query<Entity, (With<Node>, Without<Parent>)>,
+    root_node_query: Query<(Entity, Option<&UiTargetCamera>), (With<Node>, Without<Parent>)>,
     node_query: Query<(Entity, &Style, Option<&CalculatedSize>), (With<Node>, Changed<Style>)>,
     full_node_query: Query<(Entity, &Style, Option<&CalculatedSize>), With<Node>>,
     changed_size_query: Query<
         (Entity, &Style, &CalculatedSize),
         (With<Node>, Changed<CalculatedSize>),
     >,
-    children_query: Query<(Entity, &Children), (With<Node>, Changed<Children>)>,
+    children_query: Query<(Entity, Ref<Children>), With<Node>>,
     mut removed_children: RemovedComponents<Children>,
     mut node_transform_query: Query<(Entity, &mut Node, &mut Transform, Option<&Parent>)>,
+    mut scroll_position_query: Query<
+        (Entity, &Style, &mut ScrollPosition, Option<&Children>),
+        With<Node>,
+    >,
+    grid_query: Query<(Entity, &Style, &Children), With<Node>>,
     mut removed_nodes: RemovedComponents<Node>,
 ) {
-    // assume one window for time being...
+    // the cameras of the root nodes, with the factors from their logical pixels to their physical
+    // pixels
     // TODO: Support window-independent scaling: https://github.com/bevyengine/bevy/issues/5621
-    let (primary_window_entity, logical_to_physical_factor) =
-        if let Ok((entity, primary_window)) = primary_window.get_single() {
-            (entity, primary_window.resolution.scale_factor())
-        } else {
-            return;
+    let default_camera = default_ui_camera.get();
+    let mut camera_roots: HashMap<Entity, Vec<Entity>> = HashMap::default();
+    let mut root_targets: HashMap<Entity, (Entity, f64)> = HashMap::default();
+    for (root, target_camera) in &root_node_query {
+        let Some(camera_entity) = target_camera
+            .map(|target_camera| target_camera.0)
+            .or(default_camera)
+        else {
+            continue;
         };
-
-    // update window root nodes
-    for (entity, window) in windows.iter() {
-        flex_surface.update_window(entity, &window.resolution);
+        let Some((physical_size, logical_to_physical_factor)) =
+            cameras.get(camera_entity).ok().and_then(|camera| {
+                Some((
+                    camera.physical_viewport_size()?,
+                    camera.target_scaling_factor()?,
+                ))
+            })
+        else {
+            continue;
+        };
+        // update camera root nodes
+        if !camera_roots.contains_key(&camera_entity) {
+            flex_surface.update_camera(camera_entity, physical_size);
+        }
+        camera_roots.entry(camera_entity).or_default().push(root);
+        root_targets.insert(root, (camera_entity, logical_to_physical_factor));
     }
-
-    let scale_factor = logical_to_physical_factor * ui_scale.scale;
+    flex_surface.retain_cameras(|camera| camera_roots.contains_key(&camera));
+
+    // the cameras of all the nodes, from their root
+    let mut targets: HashMap<Entity, (Entity, f64)> = HashMap::default();
+    let mut stack: Vec<(Entity, (Entity, f64))> = root_targets
+        .iter()
+        .map(|(root, target)| (*root, *target))
+        .collect();
+    while let Some((entity, target)) = stack.pop() {
+        targets.insert(entity, target);
+        if let Ok((_, children)) = children_query.get(entity) {
+            stack.extend(children.iter().map(|child| (*child, target)));
+        }
+    }
+    let scale_factor = |entity: Entity| {
+        targets
+            .get(&entity)
+            .map(|(_, logical_to_physical_factor)| logical_to_physical_factor * ui_scale.scale)
+    };
 
     fn update_changed<F: ReadOnlyWorldQuery>(
         flex_surface: &mut FlexSurface,
-        scaling_factor: f64,
-        query: Query<(Entity, &Style, Option<&CalculatedSize>), F>,
+        scale_factor: impl Fn(Entity) -> Option<f64>,
+        query: &Query<(Entity, &Style, Option<&CalculatedSize>), F>,
     ) {
         // update changed nodes
-        for (entity, style, calculated_size) in &query {
-            // TODO: remove node from old hierarchy if its root has changed
+        for (entity, style, calculated_size) in query {
+            // the nodes without a camera are added once their tree gets one
+            let Some(scaling_factor) = scale_factor(entity) else {
+                continue;
+            };
             if let Some(calculated_size) = calculated_size {
                 flex_surface.upsert_leaf(entity, style, *calculated_size, scaling_factor);
             } else {
@@ -270,58 +408,188 @@ pub fn flex_node_system(
         }
     }
 
-    if !scale_factor_events.is_empty() || ui_scale.is_changed() {
+    // the trees moved to another camera, or whose camera got another scale factor, are updated
+    // entirely
+    if !scale_factor_events.is_empty()
+        || ui_scale.is_changed()
+        || root_targets != flex_surface.root_targets
+    {
         scale_factor_events.clear();
-        update_changed(&mut flex_surface, scale_factor, full_node_query);
+        update_changed(&mut flex_surface, scale_factor, &full_node_query);
     } else {
-        update_changed(&mut flex_surface, scale_factor, node_query);
+        update_changed(&mut flex_surface, scale_factor, &node_query);
     }
+    flex_surface.root_targets = root_targets;
 
     for (entity, style, calculated_size) in &changed_size_query {
-        flex_surface.upsert_leaf(entity, style, *calculated_size, scale_factor);
+        if let Some(scale_factor) = scale_factor(entity) {
+            flex_surface.upsert_leaf(entity, style, *calculated_size, scale_factor);
+        }
     }
 
     // clean up removed nodes
     flex_surface.remove_entities(removed_nodes.iter());
 
-    // update window children (for now assuming all Nodes live in the primary window)
-    flex_surface.set_window_children(primary_window_entity, root_node_query.iter());
+    // update camera children
+    flex_surface.set_camera_roots(&camera_roots);
 
     // update and remove children
     for entity in removed_children.iter() {
         flex_surface.try_remove_children(entity);
     }
     for (entity, children) in &children_query {
-        flex_surface.update_children(entity, children);
+        if children.is_changed() {
+            flex_surface.update_children(entity, &children);
+        }
+    }
+
+    // the children of grids are laid out in their cells, with their own styles once they leave them
+    let grids: Vec<GridNode> = grid_query
+        .iter()
+        .filter(|(_, style, _)| style.display == Display::Grid)
+        .filter_map(|(entity, style, children)| {
+            Some(GridNode {
+                entity,
+                style,
+                items: children
+                    .iter()
+                    .filter_map(|child| full_node_query.get(*child).ok())
+                    .filter(|(_, style, _)| {
+                        style.display != Display::None
+                            && style.position_type != PositionType::Absolute
+                    })
+                    .map(|(entity, style, _)| (entity, style))
+                    .collect(),
+                scale_factor: scale_factor(entity)?,
+            })
+        })
+        .collect();
+    let former_grid_items: Vec<Entity> = flex_surface
+        .grid_items
+        .keys()
+        .filter(|entity| {
+            !grids
+                .iter()
+                .any(|grid| grid.items.iter().any(|(item, _)| item == *entity))
+        })
+        .copied()
+        .collect();
+    for entity in former_grid_items {
+        flex_surface.grid_items.remove(&entity);
+        if let (Ok((entity, style, calculated_size)), Some(scale_factor)) =
+            (full_node_query.get(entity), scale_factor(entity))
+        {
+            if let Some(calculated_size) = calculated_size {
+                flex_surface.upsert_leaf(entity, style, *calculated_size, scale_factor);
+            } else {
+                flex_surface.upsert_node(entity, style, scale_factor);
+            }
+        }
     }
 
     // compute layouts
-    flex_surface.compute_window_layouts();
+    flex_surface.compute_camera_layouts();
+    if !grids.is_empty() {
+        flex_surface.update_grids(&grids);
+    }
 
-    let physical_to_logical_factor = 1. / logical_to_physical_factor;
+    let to_logical =
+        |logical_to_physical_factor: f64, v: f32| (v as f64 / logical_to_physical_factor) as f32;
 
-    let to_logical = |v| (physical_to_logical_factor * v as f64) as f32;
+    // clamp the scroll positions to the content overflowing the scrolling nodes
+    for (entity, style, mut scroll_position, children) in &mut scroll_position_query {
+        let Some(&(_, logical_to_physical_factor)) = targets.get(&entity) else {
+            continue;
+        };
+        let to_logical = |v| to_logical(logical_to_physical_factor, v);
+        let mut max_offset = Vec2::ZERO;
+        if style.overflow == Overflow::Scroll {
+            let layout = flex_surface.get_layout(entity).unwrap();
+            let content_end = children
+                .into_iter()
+                .flatten()
+                .filter_map(|child| flex_surface.get_layout(*child).ok())
+                .fold(Vec2::ZERO, |content_end, child_layout| {
+                    content_end.max(Vec2::new(
+                        child_layout.location.x + child_layout.size.width,
+                        child_layout.location.y + child_layout.size.height,
+                    ))
+                });
+            max_offset = Vec2::new(
+                to_logical(content_end.x - layout.size.width),
+                to_logical(content_end.y - layout.size.height),
+            )
+            .max(Vec2::ZERO);
+        }
+        scroll_position.set_if_neq(ScrollPosition {
+            offset: scroll_position.offset.clamp(Vec2::ZERO, max_offset),
+            max_offset,
+        });
+    }
+
+    // the layouts are rounded to the physical pixels of the targets, so that the edges and the
+    // text of the nodes don't fall between two pixels, whatever the scale factor and the
+    // `UiScale`. the edges are rounded from their position in the viewport rather than from
+    // their parent, so that the nodes next to each other stay next to each other
+    let mut physical_rects: HashMap<Entity, Rect> = HashMap::default();
+    let mut stack: Vec<(Entity, Vec2)> = flex_surface
+        .root_targets
+        .keys()
+        .map(|root| (*root, Vec2::ZERO))
+        .collect();
+    while let Some((entity, parent_position)) = stack.pop() {
+        let (Ok(layout), Some(&(_, logical_to_physical_factor))) =
+            (flex_surface.get_layout(entity), targets.get(&entity))
+        else {
+            continue;
+        };
+        let position = parent_position + Vec2::new(layout.location.x, layout.location.y);
+        let size = Vec2::new(layout.size.width, layout.size.height);
+        physical_rects.insert(
+            entity,
+            Rect {
+                min: position.round(),
+                max: (position + size).round(),
+            },
+        );
+        // the content of scrolling nodes is moved up and to the left by the scroll offset
+        let content_position = match scroll_position_query.get(entity) {
+            Ok((_, _, scroll_position, _)) => {
+                position - scroll_position.offset * logical_to_physical_factor as f32
+            }
+            Err(_) => position,
+        };
+        if let Ok((_, children)) = children_query.get(entity) {
+            stack.extend(children.iter().map(|child| (*child, content_position)));
+        }
+    }
 
     // PERF: try doing this incrementally
     for (entity, mut node, mut transform, parent) in &mut node_transform_query {
-        let layout = flex_surface.get_layout(entity).unwrap();
-        let new_size = Vec2::new(
-            to_logical(layout.size.width),
-            to_logical(layout.size.height),
-        );
+        let (Some(&(camera, logical_to_physical_factor)), Some(rect)) =
+            (targets.get(&entity), physical_rects.get(&entity))
+        else {
+            if node.target_camera.is_some() {
+                node.target_camera = None;
+            }
+            continue;
+        };
+        let to_logical = |v| to_logical(logical_to_physical_factor, v);
+        let new_size = Vec2::new(to_logical(rect.width()), to_logical(rect.height()));
         // only trigger change detection when the new value is different
         if node.calculated_size != new_size {
             node.calculated_size = new_size;
         }
-        let mut new_position = transform.translation;
-        new_position.x = to_logical(layout.location.x + layout.size.width / 2.0);
-        new_position.y = to_logical(layout.location.y + layout.size.height / 2.0);
-        if let Some(parent) = parent {
-            if let Ok(parent_layout) = flex_surface.get_layout(**parent) {
-                new_position.x -= to_logical(parent_layout.size.width / 2.0);
-                new_position.y -= to_logical(parent_layout.size.height / 2.0);
-            }
+        if node.target_camera != Some(camera) {
+            node.target_camera = Some(camera);
+        }
+        let mut center = rect.center();
+        if let Some(parent_rect) = parent.and_then(|parent| physical_rects.get(&parent.get())) {
+            center -= parent_rect.center();
         }
+        let mut new_position = transform.translation;
+        new_position.x = to_logical(center.x);
+        new_position.y = to_logical(center.y);
         // only trigger change detection when the new value is different
         if transform.translation != new_position {
             transform.translation = new_position;
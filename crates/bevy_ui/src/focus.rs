@@ -1,4 +1,6 @@
-use crate::{camera_config::UiCameraConfig, CalculatedClip, Node, UiStack};
+use crate::{
+    camera_config::UiCameraConfig, ActiveModal, CalculatedClip, NavigationFocus, Node, UiStack,
+};
 use bevy_derive::{Deref, DerefMut};
 use bevy_ecs::{
     change_detection::DetectChangesMut,
@@ -11,8 +13,9 @@ use bevy_ecs::{
 use bevy_input::{mouse::MouseButton, touch::Touches, Input};
 use bevy_math::Vec2;
 use bevy_reflect::{Reflect, ReflectDeserialize, ReflectSerialize};
-use bevy_render::{camera::NormalizedRenderTarget, prelude::Camera, view::ComputedVisibility};
+use bevy_render::{camera::NormalizedRenderTarget, prelude::Camera, view::ViewVisibility};
 use bevy_transform::components::GlobalTransform;
+use bevy_utils::HashMap;
 
 use bevy_window::{PrimaryWindow, Window};
 use serde::{Deserialize, Serialize};
@@ -24,9 +27,13 @@ use smallvec::SmallVec;
 ///
 /// Updated in [`ui_focus_system`].
 ///
-/// If a UI node has both [`Interaction`] and [`ComputedVisibility`] components,
+/// It only follows the left mouse button and the touches. The other buttons, the touches one by
+/// one and the positions of the pointers are reported by the pointer events, like
+/// [`PointerClick`](crate::PointerClick), and by the [`UiPointerMap`](crate::UiPointerMap).
+///
+/// If a UI node has both [`Interaction`] and [`ViewVisibility`] components,
 /// [`Interaction`] will always be [`Interaction::None`]
-/// when [`ComputedVisibility::is_visible()`] is false.
+/// when [`ViewVisibility::get()`] is false.
 /// This ensures that hidden UI nodes are not interactable,
 /// and do not end up stuck in an active state if hidden at the wrong time.
 ///
@@ -57,7 +64,9 @@ impl Default for Interaction {
 /// If the mouse is not over the node, the value will go beyond the range of (0., 0.) to (1., 1.)
 /// A None value means that the cursor position is unknown.
 ///
-/// It can be used alongside interaction to get the position of the press.
+/// It can be used alongside interaction to get the position of the press, like to set the value
+/// of a slider at the cursor. The position is relative to the whole node, even when parts of it
+/// are clipped, like a scrolled list.
 #[derive(
     Component,
     Deref,
@@ -84,6 +93,13 @@ impl RelativeCursorPosition {
             .map(|position| (0.0..1.).contains(&position.x) && (0.0..1.).contains(&position.y))
             .unwrap_or(false)
     }
+
+    /// The position of the cursor clamped inside the node, like the value of a slider dragged past
+    /// its ends
+    pub fn clamped(&self) -> Option<Vec2> {
+        self.normalized
+            .map(|position| position.clamp(Vec2::ZERO, Vec2::ONE))
+    }
 }
 
 /// Describes whether the node should block interactions with lower nodes
@@ -123,22 +139,26 @@ pub struct NodeQuery {
     relative_cursor_position: Option<&'static mut RelativeCursorPosition>,
     focus_policy: Option<&'static FocusPolicy>,
     calculated_clip: Option<&'static CalculatedClip>,
-    computed_visibility: Option<&'static ComputedVisibility>,
+    view_visibility: Option<&'static ViewVisibility>,
 }
 
 /// The system that sets Interaction for all UI elements based on the mouse cursor activity
 ///
-/// Entities with a hidden [`ComputedVisibility`] are always treated as released.
+/// Entities with a hidden [`ViewVisibility`], and the entities outside of the [`ActiveModal`], are
+/// always treated as released. The node focused by the [`NavigationFocus`] stays hovered when the
+/// cursor isn't over it.
 #[allow(clippy::too_many_arguments)]
 pub fn ui_focus_system(
     mut state: Local<State>,
-    camera: Query<(&Camera, Option<&UiCameraConfig>)>,
+    camera: Query<(Entity, &Camera, Option<&UiCameraConfig>)>,
     windows: Query<&Window>,
     mouse_button_input: Res<Input<MouseButton>>,
     touches_input: Res<Touches>,
     ui_stack: Res<UiStack>,
     mut node_query: Query<NodeQuery>,
     primary_window: Query<Entity, With<PrimaryWindow>>,
+    navigation_focus: Res<NavigationFocus>,
+    active_modal: Res<ActiveModal>,
 ) {
     let primary_window = primary_window.iter().next();
 
@@ -167,27 +187,31 @@ pub fn ui_focus_system(
     let is_ui_disabled =
         |camera_ui| matches!(camera_ui, Some(&UiCameraConfig { show_ui: false, .. }));
 
-    let cursor_position = camera
+    // the cursor positions in the viewports of the cameras the nodes are laid out on, from their
+    // top left. the cameras rendering to images don't have a cursor
+    let cursor_positions: HashMap<Entity, Vec2> = camera
         .iter()
-        .filter(|(_, camera_ui)| !is_ui_disabled(*camera_ui))
-        .filter_map(|(camera, _)| {
-            if let Some(NormalizedRenderTarget::Window(window_id)) =
+        .filter(|(_, _, camera_ui)| !is_ui_disabled(*camera_ui))
+        .filter_map(|(entity, camera, _)| {
+            let Some(NormalizedRenderTarget::Window(window_ref)) =
                 camera.target.normalize(primary_window)
-            {
-                Some(window_id)
-            } else {
-                None
-            }
-        })
-        .find_map(|window_ref| {
-            windows.get(window_ref.entity()).ok().and_then(|window| {
-                window.cursor_position().map(|mut cursor_pos| {
+            else {
+                return None;
+            };
+            let window = windows.get(window_ref.entity()).ok()?;
+            let cursor_position = window
+                .cursor_position()
+                .map(|mut cursor_pos| {
                     cursor_pos.y = window.height() - cursor_pos.y;
                     cursor_pos
                 })
-            })
+                .or_else(|| touches_input.first_pressed_position())?;
+            let viewport_origin = camera
+                .logical_viewport_rect()
+                .map_or(Vec2::ZERO, |(min, _)| min);
+            Some((entity, cursor_position - viewport_origin))
         })
-        .or_else(|| touches_input.first_pressed_position());
+        .collect();
 
     // prepare an iterator that contains all the nodes that have the cursor in their rect,
     // from the top node to the bottom one. this will also reset the interaction to `None`
@@ -199,28 +223,30 @@ pub fn ui_focus_system(
         .rev()
         .filter_map(|entity| {
             if let Ok(node) = node_query.get_mut(*entity) {
-                // Nodes that are not rendered should not be interactable
-                if let Some(computed_visibility) = node.computed_visibility {
-                    if !computed_visibility.is_visible() {
-                        // Reset their interaction to None to avoid strange stuck state
-                        if let Some(mut interaction) = node.interaction {
-                            // We cannot simply set the interaction to None, as that will trigger change detection repeatedly
-                            interaction.set_if_neq(Interaction::None);
-                        }
-
-                        return None;
+                // Nodes that are not rendered, or blocked by a modal, should not be interactable
+                let hidden =
+                    matches!(node.view_visibility, Some(view_visibility) if !view_visibility.get());
+                if hidden || !active_modal.allows(*entity) {
+                    // Reset their interaction to None to avoid strange stuck state
+                    if let Some(mut interaction) = node.interaction {
+                        // We cannot simply set the interaction to None, as that will trigger change detection repeatedly
+                        interaction.set_if_neq(Interaction::None);
                     }
+
+                    return None;
                 }
 
+                let cursor_position = node
+                    .node
+                    .target_camera()
+                    .and_then(|camera| cursor_positions.get(&camera).copied());
+
                 let position = node.global_transform.translation();
                 let ui_position = position.truncate();
                 let extents = node.node.size() / 2.0;
-                let mut min = ui_position - extents;
-                if let Some(clip) = node.calculated_clip {
-                    min = Vec2::max(min, clip.clip.min);
-                }
+                let min = ui_position - extents;
 
-                // The mouse position relative to the node
+                // The mouse position relative to the node, even where it's clipped
                 // (0., 0.) is the top-left corner, (1., 1.) is the bottom-right corner
                 let relative_cursor_position = cursor_position.map(|cursor_position| {
                     Vec2::new(
@@ -235,7 +261,14 @@ pub fn ui_focus_system(
                     normalized: relative_cursor_position,
                 };
 
-                let contains_cursor = relative_cursor_position_component.mouse_over();
+                // The clipped out parts of the node, like the items scrolled out of a list,
+                // aren't interactable
+                let clip_contains_cursor = match (node.calculated_clip, cursor_position) {
+                    (Some(clip), Some(cursor_position)) => clip.clip.contains(cursor_position),
+                    _ => true,
+                };
+                let contains_cursor =
+                    relative_cursor_position_component.mouse_over() && clip_contains_cursor;
 
                 // Save the relative cursor position to the correct component
                 if let Some(mut node_relative_cursor_position_component) =
@@ -248,7 +281,11 @@ pub fn ui_focus_system(
                     Some(*entity)
                 } else {
                     if let Some(mut interaction) = node.interaction {
-                        if *interaction == Interaction::Hovered || (cursor_position.is_none()) {
+                        if navigation_focus.is_focused(*entity) {
+                            // The navigation keeps the interaction of its focused node
+                        } else if *interaction == Interaction::Hovered
+                            || (cursor_position.is_none())
+                        {
                             interaction.set_if_neq(Interaction::None);
                         }
                     }
@@ -294,7 +331,7 @@ pub fn ui_focus_system(
     while let Some(node) = iter.fetch_next() {
         if let Some(mut interaction) = node.interaction {
             // don't reset clicked nodes because they're handled separately
-            if *interaction != Interaction::Clicked {
+            if *interaction != Interaction::Clicked && !navigation_focus.is_focused(node.entity) {
                 interaction.set_if_neq(Interaction::None);
             }
         }
@@ -2,11 +2,22 @@
 //! # Basic usage
 //! Spawn UI elements with [`node_bundles::ButtonBundle`], [`node_bundles::ImageBundle`], [`node_bundles::TextBundle`] and [`node_bundles::NodeBundle`]
 //! This UI is laid out with the Flexbox paradigm (see <https://cssreference.io/flexbox/>)
+mod anchor;
+mod debug;
+mod drag;
 mod flex;
 mod focus;
 mod geometry;
+mod modal;
+mod navigation;
+mod pointer;
 mod render;
+mod scroll;
 mod stack;
+mod theme;
+mod tooltip;
+mod transition;
+mod ui_material;
 mod ui_node;
 
 pub mod camera_config;
@@ -14,31 +25,54 @@ pub mod node_bundles;
 pub mod update;
 pub mod widget;
 
+pub use anchor::*;
 #[cfg(feature = "bevy_text")]
 use bevy_render::camera::CameraUpdateSystem;
-use bevy_render::extract_component::ExtractComponentPlugin;
+use bevy_render::{extract_component::ExtractComponentPlugin, view::VisibilitySystems};
+pub use debug::*;
+pub use drag::*;
 pub use flex::*;
 pub use focus::*;
 pub use geometry::*;
+pub use modal::*;
+pub use navigation::*;
+pub use pointer::*;
 pub use render::*;
+pub use scroll::*;
+pub use theme::*;
+pub use tooltip::*;
+pub use transition::*;
+pub use ui_material::*;
 pub use ui_node::*;
 
 #[doc(hidden)]
 pub mod prelude {
     #[doc(hidden)]
     pub use crate::{
-        camera_config::*, geometry::*, node_bundles::*, ui_node::*, widget::Button, Interaction,
-        UiScale,
+        camera_config::*,
+        geometry::*,
+        node_bundles::*,
+        ui_material::*,
+        ui_node::*,
+        widget::{
+            Button, CheckMark, Checkbox, Dropdown, DropdownList, DropdownOption, RadioButton,
+            RadioGroup, Slider, SliderFill, SliderThumb, VirtualList, VirtualListRow,
+        },
+        ActiveModal, CaptureNavigation, Class, DragState, Draggable, DropTarget, Easing, Focusable,
+        Interaction, InteractionTransition, Modal, NavDirection, NavigationFocus, OffScreen,
+        PointerButton, PointerId, Tooltip, TooltipPlacement, Transition, UiDebugOverlay,
+        UiMaterialPlugin, UiPointerMap, UiScale, UiTargetCamera, UiTheme, WorldAnchor,
     };
 }
 
 use bevy_app::prelude::*;
+use bevy_asset::AddAsset;
 use bevy_ecs::prelude::*;
 use bevy_input::InputSystem;
 use bevy_transform::TransformSystem;
 use stack::ui_stack_system;
 pub use stack::UiStack;
-use update::update_clipping_system;
+use update::{update_clipping_system, update_opacity_system};
 
 use crate::prelude::UiCameraConfig;
 
@@ -51,16 +85,34 @@ pub struct UiPlugin;
 pub enum UiSystem {
     /// After this label, the ui flex state has been updated
     Flex,
-    /// After this label, input interactions with UI entities have been updated for this frame
+    /// After this label, the [`ActiveModal`] has been updated, and the modal events have been sent for this frame
+    Modal,
+    /// After this label, input interactions with UI entities, the [`UiPointerMap`] and the pointer events have been updated for this frame
     Focus,
     /// After this label, the [`UiStack`] resource has been updated
     Stack,
+    /// After this label, the [`ScrollPosition`] of the scrolled UI entities has been updated for this frame
+    Scroll,
+    /// After this label, the drag and drop events of the UI entities have been sent for this frame
+    Drag,
+    /// After this label, the properties animated by a [`Transition`] have been updated for this frame
+    Transition,
+    /// After this label, the [`NavigationFocus`] and the [`Interaction`] of the focused UI entity have been updated for this frame
+    Navigation,
+    /// After this label, the [`ActiveTooltip`] has been updated, and the content of its tooltip displayed or hidden for this frame
+    Tooltip,
+    /// After this label, the values of the checkboxes, radio buttons, sliders and dropdowns, and the rows of the virtual lists have been updated for this frame
+    Widgets,
 }
 
 /// The current scale of the UI.
 ///
 /// A multiplier to fixed-sized ui values.
 /// **Note:** This will only affect fixed ui values like [`Val::Px`]
+///
+/// The UI can be zoomed in or out with it, on top of the scale factor of the windows. The
+/// layouts of the nodes are rounded to the physical pixels of their target afterwards, so the
+/// borders and the text stay sharp whatever the scale.
 #[derive(Debug, Resource)]
 pub struct UiScale {
     /// The scale to be applied.
@@ -79,33 +131,228 @@ impl Plugin for UiPlugin {
             .init_resource::<FlexSurface>()
             .init_resource::<UiScale>()
             .init_resource::<UiStack>()
+            .init_resource::<DragState>()
+            .init_resource::<NavigationFocus>()
+            .init_resource::<ActiveModal>()
+            .init_resource::<ActiveTooltip>()
+            .init_resource::<UiPointerMap>()
+            .init_resource::<NavigationBindings>()
+            .init_resource::<UiTheme>()
+            .init_resource::<UiDebugOverlay>()
+            .add_asset::<Theme>()
+            .init_asset_loader::<ThemeLoader>()
+            .add_event::<ModalOpened>()
+            .add_event::<ModalClosed>()
+            .add_event::<PointerOver>()
+            .add_event::<PointerOut>()
+            .add_event::<PointerDown>()
+            .add_event::<PointerUp>()
+            .add_event::<PointerClick>()
+            .add_event::<NavigationCancel>()
+            .add_event::<NavigationCaptured>()
+            .add_event::<DragStart>()
+            .add_event::<Drag>()
+            .add_event::<DragEnter>()
+            .add_event::<DragLeave>()
+            .add_event::<Drop>()
+            .add_event::<DragEnd>()
+            .add_event::<widget::CheckboxChanged>()
+            .add_event::<widget::DropdownChanged>()
+            .add_event::<widget::RadioGroupChanged>()
+            .add_event::<widget::SliderChanged>()
             .register_type::<AlignContent>()
             .register_type::<AlignItems>()
             .register_type::<AlignSelf>()
+            .register_type::<BorderColor>()
+            .register_type::<BorderRadius>()
+            .register_type::<CalculatedOpacity>()
             .register_type::<CalculatedSize>()
+            .register_type::<CaptureNavigation>()
             .register_type::<Direction>()
             .register_type::<Display>()
+            .register_type::<Draggable>()
+            .register_type::<DropTarget>()
+            .register_type::<Easing>()
             .register_type::<FlexDirection>()
             .register_type::<FlexWrap>()
+            .register_type::<Focusable>()
             .register_type::<FocusPolicy>()
+            .register_type::<GridAutoFlow>()
+            .register_type::<GridPlacement>()
+            .register_type::<GridTrack>()
             .register_type::<Interaction>()
             .register_type::<JustifyContent>()
+            .register_type::<Modal>()
+            .register_type::<NavDirection>()
             .register_type::<Node>()
+            .register_type::<OffScreen>()
             // NOTE: used by Style::aspect_ratio
             .register_type::<Option<f32>>()
+            // NOTE: used by Draggable::payload and Focusable
+            .register_type::<Option<Entity>>()
+            .register_type::<Option<i16>>()
+            .register_type::<Option<String>>()
             .register_type::<Overflow>()
             .register_type::<PositionType>()
+            .register_type::<ScrollPosition>()
+            .register_type::<Scrollbar>()
             .register_type::<Size>()
+            .register_type::<StackingContext>()
+            .register_type::<Tooltip>()
+            .register_type::<TooltipPlacement>()
             .register_type::<UiRect>()
             .register_type::<Style>()
             .register_type::<BackgroundColor>()
+            .register_type::<BackgroundGradient>()
+            .register_type::<BoxShadow>()
+            .register_type::<ColorStop>()
+            // NOTE: used by BackgroundGradient
+            .register_type::<Vec<ColorStop>>()
+            .register_type::<Class>()
             .register_type::<UiImage>()
+            .register_type::<UiOpacity>()
+            .register_type::<UiTargetCamera>()
+            .register_type::<UiTextureAtlasImage>()
             .register_type::<Val>()
             .register_type::<widget::Button>()
-            .configure_set(UiSystem::Focus.in_base_set(CoreSet::PreUpdate))
+            .register_type::<widget::Checkbox>()
+            .register_type::<widget::CheckMark>()
+            .register_type::<widget::Dropdown>()
+            .register_type::<widget::DropdownList>()
+            .register_type::<widget::DropdownOption>()
+            .register_type::<widget::RadioButton>()
+            .register_type::<widget::RadioGroup>()
+            .register_type::<widget::Slider>()
+            .register_type::<widget::SliderFill>()
+            .register_type::<widget::SliderThumb>()
+            .register_type::<widget::VirtualListRow>()
+            .register_type::<widget::VirtualListSpacer>()
+            .register_type::<WorldAnchor>()
+            .register_type::<ZIndex>()
+            .configure_set(UiSystem::Modal.in_base_set(CoreSet::PreUpdate))
+            .configure_set(
+                UiSystem::Focus
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(UiSystem::Modal),
+            )
             .configure_set(UiSystem::Flex.in_base_set(CoreSet::PostUpdate))
             .configure_set(UiSystem::Stack.in_base_set(CoreSet::PostUpdate))
-            .add_system(ui_focus_system.in_set(UiSystem::Focus).after(InputSystem));
+            .configure_set(UiSystem::Scroll.in_base_set(CoreSet::PreUpdate))
+            .configure_set(UiSystem::Drag.in_base_set(CoreSet::PreUpdate))
+            .configure_set(UiSystem::Navigation.in_base_set(CoreSet::PreUpdate))
+            .configure_set(
+                UiSystem::Tooltip
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(UiSystem::Navigation),
+            )
+            .configure_set(
+                UiSystem::Widgets
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(UiSystem::Navigation),
+            )
+            .configure_set(
+                UiSystem::Transition
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(UiSystem::Flex),
+            )
+            .add_plugin(UiTransitionPlugin::<BackgroundColorProperty>::default())
+            .add_plugin(UiTransitionPlugin::<BorderColorProperty>::default())
+            .add_plugin(UiTransitionPlugin::<OpacityProperty>::default())
+            .add_plugin(UiTransitionPlugin::<PositionProperty>::default())
+            .add_plugin(UiTransitionPlugin::<SizeProperty>::default())
+            .add_plugin(UiTransitionPlugin::<ScaleProperty>::default())
+            .add_system(ui_modal_system.in_set(UiSystem::Modal))
+            .add_system(ui_focus_system.in_set(UiSystem::Focus).after(InputSystem))
+            .add_system(ui_pointer_system.in_set(UiSystem::Focus).after(InputSystem))
+            .add_system(
+                ui_scroll_system
+                    .in_set(UiSystem::Scroll)
+                    .after(InputSystem)
+                    .after(UiSystem::Modal),
+            )
+            .add_system(
+                ui_drag_system
+                    .in_set(UiSystem::Drag)
+                    .after(InputSystem)
+                    .after(UiSystem::Focus),
+            )
+            .add_system({
+                let system = ui_navigation_system
+                    .in_set(UiSystem::Navigation)
+                    .after(InputSystem)
+                    .after(UiSystem::Focus);
+                // The keys focusing and unfocusing the text inputs don't navigate
+                #[cfg(feature = "bevy_text")]
+                let system = system.after(widget::text_input_system);
+
+                system
+            })
+            .add_system(ui_tooltip_system.in_set(UiSystem::Tooltip))
+            // The cancels closing a dropdown are seen before the dropdown is closed
+            .add_system(
+                ui_modal_close_system
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(UiSystem::Navigation)
+                    .before(UiSystem::Widgets),
+            )
+            .add_systems(
+                (
+                    widget::checkbox_system,
+                    widget::radio_button_system,
+                    widget::slider_system,
+                    widget::dropdown_system,
+                    // The rows in view are spawned before the update, to be filled in it
+                    widget::virtual_list_system.after(UiSystem::Scroll),
+                )
+                    .in_set(UiSystem::Widgets),
+            )
+            // The widgets changed during the update are shown in this frame
+            .add_systems(
+                (
+                    widget::checkbox_mark_system,
+                    widget::radio_mark_system,
+                    widget::slider_thumb_system,
+                    widget::dropdown_list_system,
+                )
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(UiSystem::Flex)
+                    .before(VisibilitySystems::VisibilityPropagate),
+            )
+            // The classes set during the update are styled in this frame, before being animated
+            .add_system(
+                theme_system
+                    .in_base_set(CoreSet::PostUpdate)
+                    .before(UiSystem::Transition),
+            );
+        #[cfg(feature = "bevy_text")]
+        app.add_system(
+            theme_text_system
+                .in_base_set(CoreSet::PostUpdate)
+                .before(widget::text_system),
+        );
+        #[cfg(feature = "bevy_text")]
+        app.register_type::<widget::GpuTimingsText>()
+            .add_system(widget::gpu_timings_text_system);
+        #[cfg(feature = "bevy_text")]
+        app.register_type::<widget::TextInput>()
+            .register_type::<widget::TextInputStyle>()
+            .init_resource::<widget::TextInputFocus>()
+            .init_resource::<widget::Clipboard>()
+            .add_event::<widget::TextInputChanged>()
+            .add_event::<widget::TextInputSubmitted>()
+            .add_systems(
+                (widget::text_input_focus_system, widget::text_input_system)
+                    .chain()
+                    .in_base_set(CoreSet::PreUpdate)
+                    .after(UiSystem::Focus),
+            )
+            .add_systems(
+                (
+                    widget::text_input_layout_system.before(widget::text_system),
+                    widget::text_input_ime_system.after(widget::text_input_layout_system),
+                )
+                    .in_base_set(CoreSet::PostUpdate),
+            );
         // add these systems to front because these must run before transform update systems
         #[cfg(feature = "bevy_text")]
         app.add_system(
@@ -137,17 +384,48 @@ impl Plugin for UiPlugin {
 
             system
         })
+        .add_system({
+            let system = widget::update_atlas_image_calculated_size_system
+                .in_base_set(CoreSet::PostUpdate)
+                .before(UiSystem::Flex);
+            // Potential conflicts: `Assets<TextureAtlas>`
+            // They run independently since `widget::text_system` and
+            // `bevy_text::update_text2d_layout` only ever insert new atlases and modify the
+            // atlases of the fonts.
+            #[cfg(feature = "bevy_text")]
+            let system = system
+                .ambiguous_with(bevy_text::update_text2d_layout)
+                .ambiguous_with(widget::text_system);
+
+            system
+        })
         .add_system(
             flex_node_system
                 .in_set(UiSystem::Flex)
                 .before(TransformSystem::TransformPropagate),
         )
         .add_system(ui_stack_system.in_set(UiSystem::Stack))
+        .add_system(
+            world_anchor_system
+                .in_base_set(CoreSet::PostUpdate)
+                .after(TransformSystem::TransformPropagate)
+                .after(bevy_render::camera::CameraUpdateSystem)
+                // Hides the nodes whose entity is out of the view
+                .after(VisibilitySystems::CheckVisibility)
+                .before(update_clipping_system),
+        )
+        .add_system(
+            tooltip_placement_system
+                .in_base_set(CoreSet::PostUpdate)
+                .after(world_anchor_system)
+                .before(update_clipping_system),
+        )
         .add_system(
             update_clipping_system
                 .after(TransformSystem::TransformPropagate)
                 .in_base_set(CoreSet::PostUpdate),
-        );
+        )
+        .add_system(update_opacity_system.in_base_set(CoreSet::PostUpdate));
 
         crate::render::build_ui_render(app);
     }
@@ -0,0 +1,616 @@
+use crate::{
+    widget::Dropdown, NavigationCancel, NavigationFocus, Node, PointerId, UiPointerMap, UiStack,
+};
+use bevy_ecs::{
+    entity::Entity,
+    event::{EventReader, EventWriter},
+    prelude::{Component, With},
+    reflect::ReflectComponent,
+    system::{Query, Res, ResMut, Resource},
+};
+use bevy_hierarchy::{Children, HierarchyQueryExt, Parent};
+use bevy_input::{mouse::MouseButton, touch::Touches, Input};
+use bevy_math::Vec2;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::{
+    color::Color,
+    view::{ViewVisibility, Visibility},
+};
+use bevy_utils::HashSet;
+
+/// Makes a UI node and its descendants a modal layer, capturing the input while it's shown
+///
+/// While the node is visible, the nodes outside of it aren't hovered, clicked, scrolled or
+/// dragged, and the keyboard and gamepad navigation only focuses the [`Focusable`] nodes inside
+/// of it. The rest of the UI is dimmed by the [`backdrop`](Self::backdrop) drawn behind the node,
+/// over the whole viewport of its camera.
+///
+/// When several modals are shown, the top one in the [`UiStack`] captures the input, like a
+/// confirmation dialog opened from a settings menu.
+///
+/// The modal capturing the input can close itself, by hiding its [`Visibility`], with
+/// [`close_on_cancel`](Self::close_on_cancel) and [`close_on_backdrop`](Self::close_on_backdrop).
+///
+/// [`Focusable`]: crate::Focusable
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct Modal {
+    /// The color drawn over the rest of the UI, behind the modal
+    pub backdrop: Color,
+    /// Whether the modal is closed by a [`NavigationCancel`], like when pressing the escape key,
+    /// unless it's sent to close an open [`Dropdown`] of the modal
+    pub close_on_cancel: bool,
+    /// Whether the modal is closed by pressing the mouse or touching the screen over its
+    /// backdrop, outside of its nodes
+    pub close_on_backdrop: bool,
+}
+
+impl Modal {
+    pub const DEFAULT: Self = Self {
+        backdrop: Color::rgba(0.0, 0.0, 0.0, 0.5),
+        close_on_cancel: false,
+        close_on_backdrop: false,
+    };
+
+    /// Returns this [`Modal`] closed by a [`NavigationCancel`] and by pressing its backdrop
+    pub const fn closable(mut self) -> Self {
+        self.close_on_cancel = true;
+        self.close_on_backdrop = true;
+        self
+    }
+}
+
+impl Default for Modal {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The [`Modal`] capturing the input, updated by [`ui_modal_system`]
+///
+/// The systems of the game can check [`is_open`](Self::is_open) to ignore the input going to an
+/// open modal, like to not move the player while a dialog is shown.
+#[derive(Resource, Default, Debug)]
+pub struct ActiveModal {
+    modal: Option<Entity>,
+    layer: HashSet<Entity>,
+    open: Vec<Entity>,
+}
+
+impl ActiveModal {
+    /// The modal capturing the input, if any
+    pub fn get(&self) -> Option<Entity> {
+        self.modal
+    }
+
+    /// Whether a modal is capturing the input
+    pub fn is_open(&self) -> bool {
+        self.modal.is_some()
+    }
+
+    /// Whether `entity` can be interacted with: when it's the modal capturing the input, one of
+    /// its descendants, or when no modal is open
+    pub fn allows(&self, entity: Entity) -> bool {
+        self.modal.is_none() || self.layer.contains(&entity)
+    }
+}
+
+/// An event sent when a [`Modal`] is shown
+#[derive(Clone, Debug)]
+pub struct ModalOpened {
+    /// The modal node
+    pub modal: Entity,
+}
+
+/// An event sent when a [`Modal`] is hidden or despawned
+#[derive(Clone, Debug)]
+pub struct ModalClosed {
+    /// The modal node
+    pub modal: Entity,
+}
+
+/// The system that finds the [`Modal`] capturing the input, and sends the [`ModalOpened`] and
+/// [`ModalClosed`] events
+///
+/// A modal is open while it's visible and laid out, as it's usually opened and closed with its
+/// [`Visibility`](bevy_render::view::Visibility) or its [`Display`](crate::Display). The
+/// [`NavigationFocus`] and the focus of the text inputs are cleared when the focused node is
+/// outside of the modal capturing the input.
+#[allow(clippy::too_many_arguments)]
+pub fn ui_modal_system(
+    mut active_modal: ResMut<ActiveModal>,
+    ui_stack: Res<UiStack>,
+    mut navigation_focus: ResMut<NavigationFocus>,
+    #[cfg(feature = "bevy_text")] mut text_input_focus: ResMut<crate::widget::TextInputFocus>,
+    mut opened_events: EventWriter<ModalOpened>,
+    mut closed_events: EventWriter<ModalClosed>,
+    modal_query: Query<(&Node, Option<&ViewVisibility>), With<Modal>>,
+    children_query: Query<&Children>,
+) {
+    let is_open = |entity: Entity| {
+        matches!(
+            modal_query.get(entity),
+            Ok((node, view_visibility))
+                if node.size() != Vec2::ZERO
+                    && !matches!(view_visibility, Some(view_visibility) if !view_visibility.get())
+        )
+    };
+
+    for modal in &active_modal.open {
+        if !is_open(*modal) {
+            closed_events.send(ModalClosed { modal: *modal });
+        }
+    }
+    // The open modals from the bottom one to the top one
+    let open: Vec<Entity> = ui_stack
+        .uinodes
+        .iter()
+        .copied()
+        .filter(|entity| is_open(*entity))
+        .collect();
+    for modal in &open {
+        if !active_modal.open.contains(modal) {
+            opened_events.send(ModalOpened { modal: *modal });
+        }
+    }
+    active_modal.open = open;
+
+    let previous = active_modal.modal;
+    active_modal.modal = active_modal.open.last().copied();
+    // The layer is rebuilt every frame, to follow the nodes spawned and despawned in the modal
+    active_modal.layer.clear();
+    let Some(modal) = active_modal.modal else {
+        return;
+    };
+    let mut stack = vec![modal];
+    while let Some(entity) = stack.pop() {
+        active_modal.layer.insert(entity);
+        if let Ok(children) = children_query.get(entity) {
+            stack.extend(children.iter().copied());
+        }
+    }
+
+    if previous != Some(modal) {
+        if let Some(focused) = navigation_focus.focused() {
+            if !active_modal.allows(focused) {
+                navigation_focus.clear();
+            }
+        }
+        #[cfg(feature = "bevy_text")]
+        if let Some(focused) = text_input_focus.0 {
+            if !active_modal.allows(focused) {
+                text_input_focus.0 = None;
+            }
+        }
+    }
+}
+
+/// The system that closes the [`Modal`] capturing the input on a [`NavigationCancel`] or a press
+/// over its backdrop, if it's [`close_on_cancel`](Modal::close_on_cancel) or
+/// [`close_on_backdrop`](Modal::close_on_backdrop)
+///
+/// The modal is hidden with its [`Visibility`], and the [`NavigationFocus`] is cleared when it's
+/// in the modal. The [`ModalClosed`] event is then sent by [`ui_modal_system`] in the next frame.
+#[allow(clippy::too_many_arguments)]
+pub fn ui_modal_close_system(
+    active_modal: Res<ActiveModal>,
+    pointer_map: Res<UiPointerMap>,
+    mut navigation_focus: ResMut<NavigationFocus>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    touches_input: Res<Touches>,
+    mut cancel_events: EventReader<NavigationCancel>,
+    dropdown_query: Query<&Dropdown>,
+    parent_query: Query<&Parent>,
+    mut modal_query: Query<(&Modal, &mut Visibility)>,
+) {
+    // The cancels closing an open dropdown first are left to its system
+    let in_open_dropdown = |entity: Entity| {
+        std::iter::once(entity)
+            .chain(parent_query.iter_ancestors(entity))
+            .any(|entity| matches!(dropdown_query.get(entity), Ok(dropdown) if dropdown.open))
+    };
+    let cancelled = cancel_events
+        .iter()
+        .filter(|event| !matches!(event.focused, Some(focused) if in_open_dropdown(focused)))
+        .count()
+        > 0;
+    let Some(entity) = active_modal.get() else {
+        return;
+    };
+    let Ok((modal, mut visibility)) = modal_query.get_mut(entity) else {
+        return;
+    };
+
+    // Only the nodes of the modal are under the pointers, so a pointer over no node is over the
+    // backdrop
+    let over_backdrop =
+        |pointer| matches!(pointer_map.get(pointer), Some(hits) if hits.nodes.is_empty());
+    let backdrop_pressed = (mouse_button_input.get_just_pressed().next().is_some()
+        && over_backdrop(PointerId::Mouse))
+        || touches_input
+            .iter_just_pressed()
+            .any(|touch| over_backdrop(PointerId::Touch(touch.id())));
+
+    if (modal.close_on_cancel && cancelled) || (modal.close_on_backdrop && backdrop_pressed) {
+        *visibility = Visibility::Hidden;
+        if let Some(focused) = navigation_focus.focused() {
+            if active_modal.layer.contains(&focused) {
+                navigation_focus.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        ui_navigation_system, ui_pointer_system, DragState, FocusPolicy, Focusable,
+        NavigationBindings, NavigationCaptured, PointerClick, PointerDown, PointerOut, PointerOver,
+        PointerUp,
+    };
+    use bevy_ecs::{
+        event::Events,
+        schedule::{IntoSystemConfig, Schedule},
+        world::World,
+    };
+    use bevy_hierarchy::BuildWorldChildren;
+    use bevy_input::{
+        gamepad::{GamepadAxis, GamepadButton, Gamepads},
+        keyboard::KeyCode,
+        Axis,
+    };
+    use bevy_transform::components::GlobalTransform;
+    use bevy_window::{CursorMoved, PrimaryWindow, Window};
+
+    /// A UI with a modal, updated by the modal, pointer and navigation systems
+    struct Scene {
+        world: World,
+        schedule: Schedule,
+        window: Entity,
+    }
+
+    impl Scene {
+        fn new() -> Self {
+            let mut world = World::new();
+            world.init_resource::<ActiveModal>();
+            world.init_resource::<UiStack>();
+            world.init_resource::<UiPointerMap>();
+            world.init_resource::<DragState>();
+            world.init_resource::<NavigationFocus>();
+            world.init_resource::<NavigationBindings>();
+            world.init_resource::<Input<KeyCode>>();
+            world.init_resource::<Input<MouseButton>>();
+            world.init_resource::<Touches>();
+            world.init_resource::<Gamepads>();
+            world.init_resource::<Input<GamepadButton>>();
+            world.init_resource::<Axis<GamepadAxis>>();
+            world.init_resource::<Events<CursorMoved>>();
+            world.init_resource::<Events<ModalOpened>>();
+            world.init_resource::<Events<ModalClosed>>();
+            world.init_resource::<Events<NavigationCancel>>();
+            world.init_resource::<Events<NavigationCaptured>>();
+            world.init_resource::<Events<PointerOver>>();
+            world.init_resource::<Events<PointerOut>>();
+            world.init_resource::<Events<PointerDown>>();
+            world.init_resource::<Events<PointerUp>>();
+            world.init_resource::<Events<PointerClick>>();
+            #[cfg(feature = "bevy_text")]
+            world.init_resource::<crate::widget::TextInputFocus>();
+            let window = world.spawn((Window::default(), PrimaryWindow)).id();
+            let mut schedule = Schedule::new();
+            schedule.add_system(ui_modal_system);
+            schedule.add_system(ui_pointer_system.after(ui_modal_system));
+            schedule.add_system(ui_navigation_system.after(ui_modal_system));
+            schedule.add_system(
+                ui_modal_close_system
+                    .after(ui_pointer_system)
+                    .after(ui_navigation_system),
+            );
+            Self {
+                world,
+                schedule,
+                window,
+            }
+        }
+
+        /// Spawns a focusable node of 100 by 100 pixels with its top left at `x` and `y` from the
+        /// top left of the window, over the nodes spawned before it
+        fn spawn(&mut self, x: f32, y: f32) -> Entity {
+            let size = Vec2::splat(100.0);
+            let node = Node {
+                calculated_size: size,
+                ..Default::default()
+            };
+            let center = Vec2::new(x, y) + size / 2.0;
+            let entity = self
+                .world
+                .spawn((
+                    node,
+                    GlobalTransform::from_translation(center.extend(0.0)),
+                    FocusPolicy::Block,
+                    Focusable::DEFAULT,
+                    Visibility::Inherited,
+                ))
+                .id();
+            self.world.resource_mut::<UiStack>().uinodes.push(entity);
+            entity
+        }
+
+        /// Spawns a modal of 100 by 100 pixels over the nodes spawned before it, with a child
+        /// node over it
+        fn spawn_modal(&mut self, x: f32, y: f32, modal: Modal) -> (Entity, Entity) {
+            let entity = self.spawn(x, y);
+            let child = self.spawn(x, y);
+            self.world
+                .entity_mut(entity)
+                .insert(modal)
+                .push_children(&[child]);
+            (entity, child)
+        }
+
+        /// Runs a frame, returning the modals opened and closed in it
+        fn run(&mut self) -> (Vec<Entity>, Vec<Entity>) {
+            self.schedule.run(&mut self.world);
+            self.world.resource_mut::<Input<MouseButton>>().clear();
+            self.world.resource_mut::<Input<KeyCode>>().clear();
+            self.world
+                .resource_mut::<Events<NavigationCancel>>()
+                .update();
+            let opened = self
+                .world
+                .resource_mut::<Events<ModalOpened>>()
+                .drain()
+                .map(|event| event.modal)
+                .collect();
+            let closed = self
+                .world
+                .resource_mut::<Events<ModalClosed>>()
+                .drain()
+                .map(|event| event.modal)
+                .collect();
+            (opened, closed)
+        }
+
+        /// Moves the cursor to `x` and `y` from the top left of the window, and presses the left
+        /// mouse button in the same frame
+        fn click(&mut self, x: f32, y: f32) {
+            let mut window = self.world.get_mut::<Window>(self.window).unwrap();
+            let height = window.height();
+            window.set_cursor_position(Some(Vec2::new(x, height - y)));
+            self.world
+                .resource_mut::<Input<MouseButton>>()
+                .press(MouseButton::Left);
+            self.run();
+            self.world
+                .resource_mut::<Input<MouseButton>>()
+                .release(MouseButton::Left);
+            self.run();
+        }
+
+        fn press(&mut self, key: KeyCode) {
+            self.world.resource_mut::<Input<KeyCode>>().press(key);
+            self.run();
+            self.world.resource_mut::<Input<KeyCode>>().release(key);
+            self.run();
+        }
+
+        fn hide(&mut self, entity: Entity) {
+            self.world.entity_mut(entity).insert(ViewVisibility::HIDDEN);
+        }
+
+        fn is_hidden(&self, entity: Entity) -> bool {
+            self.world.get::<Visibility>(entity) == Some(&Visibility::Hidden)
+        }
+
+        fn hovered(&self) -> Option<Entity> {
+            self.world.resource::<UiPointerMap>().top(PointerId::Mouse)
+        }
+
+        fn focused(&self) -> Option<Entity> {
+            self.world.resource::<NavigationFocus>().focused()
+        }
+    }
+
+    #[test]
+    fn opened_and_closed_events() {
+        let mut scene = Scene::new();
+        let (modal, _) = scene.spawn_modal(0.0, 0.0, Modal::DEFAULT);
+        assert_eq!(scene.run(), (vec![modal], vec![]));
+        assert_eq!(
+            scene.run(),
+            (vec![], vec![]),
+            "no events while it stays open"
+        );
+        assert!(scene.world.resource::<ActiveModal>().is_open());
+
+        scene.hide(modal);
+        assert_eq!(scene.run(), (vec![], vec![modal]));
+        assert!(!scene.world.resource::<ActiveModal>().is_open());
+
+        scene.world.entity_mut(modal).remove::<ViewVisibility>();
+        assert_eq!(scene.run(), (vec![modal], vec![]));
+        // a node that isn't laid out, like with `Display::None`, is closed
+        scene.world.get_mut::<Node>(modal).unwrap().calculated_size = Vec2::ZERO;
+        assert_eq!(scene.run(), (vec![], vec![modal]));
+
+        scene.world.get_mut::<Node>(modal).unwrap().calculated_size = Vec2::splat(100.0);
+        assert_eq!(scene.run(), (vec![modal], vec![]));
+        scene.world.despawn(modal);
+        scene.world.resource_mut::<UiStack>().uinodes.clear();
+        assert_eq!(scene.run(), (vec![], vec![modal]));
+    }
+
+    #[test]
+    fn input_blocked_outside_of_the_modal() {
+        let mut scene = Scene::new();
+        let before = scene.spawn(0.0, 0.0);
+        let (modal, child) = scene.spawn_modal(200.0, 0.0, Modal::DEFAULT);
+        let after = scene.spawn(400.0, 0.0);
+        scene.run();
+
+        let active_modal = scene.world.resource::<ActiveModal>();
+        assert_eq!(active_modal.get(), Some(modal));
+        let cases = [
+            (before, false),
+            (modal, true),
+            (child, true),
+            (after, false),
+        ];
+        for (entity, allowed) in cases {
+            assert_eq!(active_modal.allows(entity), allowed, "{entity:?}");
+        }
+
+        let cases = [
+            (50.0, None, "under the modal"),
+            (250.0, Some(child), "the top node of the modal"),
+            (450.0, None, "over the modal, but outside of it"),
+        ];
+        for (x, hovered, message) in cases {
+            scene.click(x, 50.0);
+            assert_eq!(scene.hovered(), hovered, "{message}");
+        }
+
+        scene.hide(modal);
+        scene.run();
+        assert!(scene.world.resource::<ActiveModal>().allows(before));
+        scene.click(50.0, 50.0);
+        assert_eq!(scene.hovered(), Some(before), "once the modal is closed");
+    }
+
+    #[test]
+    fn focus_trapped_in_the_modal() {
+        let mut scene = Scene::new();
+        let outside = scene.spawn(0.0, 0.0);
+        scene.world.resource_mut::<NavigationFocus>().focus(outside);
+        let (modal, child) = scene.spawn_modal(200.0, 0.0, Modal::DEFAULT);
+        let right = scene.spawn(400.0, 0.0);
+        scene.run();
+        assert_eq!(scene.focused(), None, "the focus outside of it is cleared");
+
+        scene.world.resource_mut::<NavigationFocus>().focus(child);
+        for key in [KeyCode::Left, KeyCode::Right] {
+            scene.press(key);
+            assert_eq!(scene.focused(), Some(child), "{key:?}");
+        }
+
+        scene.hide(modal);
+        scene.run();
+        scene.press(KeyCode::Right);
+        assert_eq!(scene.focused(), Some(right), "once the modal is closed");
+    }
+
+    #[test]
+    fn top_modal_captures_the_input() {
+        let mut scene = Scene::new();
+        let (settings, settings_child) = scene.spawn_modal(0.0, 0.0, Modal::DEFAULT);
+        let (confirm, confirm_child) = scene.spawn_modal(200.0, 0.0, Modal::DEFAULT);
+        assert_eq!(scene.run(), (vec![settings, confirm], vec![]));
+        assert_eq!(scene.world.resource::<ActiveModal>().get(), Some(confirm));
+        scene.click(50.0, 50.0);
+        assert_eq!(scene.hovered(), None);
+
+        scene.hide(confirm);
+        scene.run();
+        assert_eq!(scene.world.resource::<ActiveModal>().get(), Some(settings));
+        scene.click(50.0, 50.0);
+        assert_eq!(scene.hovered(), Some(settings_child));
+        assert!(!scene.world.resource::<ActiveModal>().allows(confirm_child));
+    }
+
+    #[test]
+    fn closed_on_cancel() {
+        let cases = [
+            (Modal::DEFAULT, false),
+            (
+                Modal {
+                    close_on_cancel: true,
+                    ..Modal::DEFAULT
+                },
+                true,
+            ),
+            (
+                Modal {
+                    close_on_backdrop: true,
+                    ..Modal::DEFAULT
+                },
+                false,
+            ),
+        ];
+        for (modal, closed) in cases {
+            let mut scene = Scene::new();
+            let (modal_entity, child) = scene.spawn_modal(0.0, 0.0, modal);
+            scene.run();
+            scene.world.resource_mut::<NavigationFocus>().focus(child);
+            scene.press(KeyCode::Escape);
+            assert_eq!(scene.is_hidden(modal_entity), closed, "{modal:?}");
+            let focused = if closed { None } else { Some(child) };
+            assert_eq!(scene.focused(), focused, "{modal:?}");
+        }
+    }
+
+    #[test]
+    fn cancel_closing_a_dropdown_is_ignored() {
+        let mut scene = Scene::new();
+        let (modal, child) = scene.spawn_modal(0.0, 0.0, Modal::DEFAULT.closable());
+        let option = scene.spawn(0.0, 0.0);
+        scene
+            .world
+            .entity_mut(child)
+            .insert(Dropdown {
+                selected: None,
+                open: true,
+            })
+            .push_children(&[option]);
+        scene.run();
+
+        for focused in [child, option] {
+            scene.world.resource_mut::<NavigationFocus>().focus(focused);
+            scene.press(KeyCode::Escape);
+            assert!(!scene.is_hidden(modal), "{focused:?}");
+        }
+
+        scene.world.get_mut::<Dropdown>(child).unwrap().open = false;
+        scene.press(KeyCode::Escape);
+        assert!(scene.is_hidden(modal), "once the dropdown is closed");
+    }
+
+    #[test]
+    fn closed_on_backdrop_press() {
+        let cases = [
+            (Modal::DEFAULT, 250.0, false, "not closable"),
+            (Modal::DEFAULT.closable(), 50.0, false, "over the modal"),
+            (Modal::DEFAULT.closable(), 250.0, true, "over the backdrop"),
+            (
+                Modal {
+                    close_on_backdrop: true,
+                    ..Modal::DEFAULT
+                },
+                250.0,
+                true,
+                "only close_on_backdrop",
+            ),
+            (
+                Modal {
+                    close_on_cancel: true,
+                    ..Modal::DEFAULT
+                },
+                250.0,
+                false,
+                "only close_on_cancel",
+            ),
+        ];
+        for (modal, x, closed, message) in cases {
+            let mut scene = Scene::new();
+            // the nodes under the backdrop aren't pressed
+            scene.spawn(200.0, 0.0);
+            let (modal_entity, child) = scene.spawn_modal(0.0, 0.0, modal);
+            scene.run();
+            scene.world.resource_mut::<NavigationFocus>().focus(child);
+            scene.click(x, 50.0);
+            assert_eq!(scene.is_hidden(modal_entity), closed, "{message}");
+            let focused = if closed { None } else { Some(child) };
+            assert_eq!(scene.focused(), focused, "{message}");
+        }
+    }
+}
@@ -0,0 +1,918 @@
+use crate::{ActiveModal, CalculatedClip, Interaction, Node};
+#[cfg(feature = "bevy_text")]
+use bevy_ecs::{change_detection::DetectChanges, query::With};
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    event::{EventReader, EventWriter},
+    prelude::Component,
+    reflect::{ReflectComponent, ReflectMapEntities},
+    system::{Local, Query, Res, ResMut, Resource},
+};
+use bevy_input::{
+    gamepad::{Gamepad, GamepadAxis, GamepadAxisType, GamepadButton, GamepadButtonType, Gamepads},
+    keyboard::KeyCode,
+    Axis, Input,
+};
+use bevy_math::Rect;
+use bevy_reflect::{FromReflect, Reflect, ReflectDeserialize, ReflectSerialize};
+use bevy_render::view::ViewVisibility;
+use bevy_transform::components::GlobalTransform;
+use bevy_window::CursorMoved;
+use serde::{Deserialize, Serialize};
+
+/// How far the left stick of a gamepad has to be pushed to move the focus
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// Marks a UI node that can be focused with the keyboard or a gamepad, moving the focus from
+/// node to node in a direction.
+///
+/// The focus moves to the closest focusable node in the direction, unless the node has an
+/// explicit neighbor in this direction, like to wrap around the edges of a menu.
+///
+/// The [`Interaction`] of the focused node is [`Interaction::Hovered`], and
+/// [`Interaction::Clicked`] while the confirm button is pressed, so buttons react to the
+/// navigation like to the mouse.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, MapEntities, PartialEq)]
+pub struct Focusable {
+    /// The node focused when navigating up from this node
+    pub up: Option<Entity>,
+    /// The node focused when navigating down from this node
+    pub down: Option<Entity>,
+    /// The node focused when navigating left from this node
+    pub left: Option<Entity>,
+    /// The node focused when navigating right from this node
+    pub right: Option<Entity>,
+}
+
+impl Focusable {
+    pub const DEFAULT: Self = Self {
+        up: None,
+        down: None,
+        left: None,
+        right: None,
+    };
+
+    /// Sets the node focused when navigating in `direction` from this node
+    pub const fn with_neighbor(mut self, direction: NavDirection, neighbor: Entity) -> Self {
+        match direction {
+            NavDirection::Up => self.up = Some(neighbor),
+            NavDirection::Down => self.down = Some(neighbor),
+            NavDirection::Left => self.left = Some(neighbor),
+            NavDirection::Right => self.right = Some(neighbor),
+        }
+        self
+    }
+
+    /// The explicit neighbor of the node in `direction`, if any
+    pub const fn neighbor(&self, direction: NavDirection) -> Option<Entity> {
+        match direction {
+            NavDirection::Up => self.up,
+            NavDirection::Down => self.down,
+            NavDirection::Left => self.left,
+            NavDirection::Right => self.right,
+        }
+    }
+}
+
+impl MapEntities for Focusable {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        for neighbor in [
+            &mut self.up,
+            &mut self.down,
+            &mut self.left,
+            &mut self.right,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if let Ok(mapped_entity) = entity_map.get(*neighbor) {
+                *neighbor = mapped_entity;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Keeps the focus on a [`Focusable`] node when navigating along an axis, sending the
+/// directions as [`NavigationCaptured`] events instead, like for the left and right keys to
+/// change the value of a slider
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, PartialEq)]
+pub enum CaptureNavigation {
+    /// The left and right directions are captured
+    #[default]
+    Horizontal,
+    /// The up and down directions are captured
+    Vertical,
+    /// All the directions are captured
+    All,
+}
+
+impl CaptureNavigation {
+    /// Whether the navigation in `direction` is captured
+    pub const fn captures(&self, direction: NavDirection) -> bool {
+        match self {
+            CaptureNavigation::Horizontal => {
+                matches!(direction, NavDirection::Left | NavDirection::Right)
+            }
+            CaptureNavigation::Vertical => {
+                matches!(direction, NavDirection::Up | NavDirection::Down)
+            }
+            CaptureNavigation::All => true,
+        }
+    }
+}
+
+/// A direction in which the focus moves between the [`Focusable`] nodes
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug, Serialize, Deserialize, Reflect, FromReflect)]
+#[reflect(PartialEq, Hash, Serialize, Deserialize)]
+pub enum NavDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+/// The [`Focusable`] node focused by the keyboard or a gamepad, updated by
+/// [`ui_navigation_system`]
+///
+/// Moving the mouse clears the focus, and the next navigation starts again from the last focused
+/// node.
+///
+/// This focus is only the one of the UI: it isn't synchronized with the focus of an accessibility
+/// tree, as the engine doesn't build one (with `accesskit`, for instance) yet. A screen reader
+/// doesn't announce the focused node, and can't move the focus either.
+#[derive(Resource, Default, Debug)]
+pub struct NavigationFocus {
+    focused: Option<Entity>,
+    last: Option<Entity>,
+}
+
+impl NavigationFocus {
+    /// The focused node, if any
+    pub fn focused(&self) -> Option<Entity> {
+        self.focused
+    }
+
+    /// Whether `entity` is the focused node
+    pub fn is_focused(&self, entity: Entity) -> bool {
+        self.focused == Some(entity)
+    }
+
+    /// Focuses `entity`, like the first button of a menu when it's opened
+    pub fn focus(&mut self, entity: Entity) {
+        self.focused = Some(entity);
+        self.last = Some(entity);
+    }
+
+    /// Clears the focus
+    pub fn clear(&mut self) {
+        self.focused = None;
+    }
+}
+
+/// The keys and gamepad buttons of the navigation between the [`Focusable`] nodes
+///
+/// The directional pad and the left stick of the gamepads always move the focus.
+#[derive(Resource, Clone, Debug)]
+pub struct NavigationBindings {
+    /// The keys moving the focus up
+    pub up: Vec<KeyCode>,
+    /// The keys moving the focus down
+    pub down: Vec<KeyCode>,
+    /// The keys moving the focus left
+    pub left: Vec<KeyCode>,
+    /// The keys moving the focus right
+    pub right: Vec<KeyCode>,
+    /// The keys clicking the focused node
+    pub confirm: Vec<KeyCode>,
+    /// The keys sending a [`NavigationCancel`] event
+    pub cancel: Vec<KeyCode>,
+    /// The gamepad buttons clicking the focused node
+    pub gamepad_confirm: Vec<GamepadButtonType>,
+    /// The gamepad buttons sending a [`NavigationCancel`] event
+    pub gamepad_cancel: Vec<GamepadButtonType>,
+}
+
+impl Default for NavigationBindings {
+    fn default() -> Self {
+        Self {
+            up: vec![KeyCode::Up],
+            down: vec![KeyCode::Down],
+            left: vec![KeyCode::Left],
+            right: vec![KeyCode::Right],
+            confirm: vec![KeyCode::Return, KeyCode::Space],
+            cancel: vec![KeyCode::Escape],
+            gamepad_confirm: vec![GamepadButtonType::South],
+            gamepad_cancel: vec![GamepadButtonType::East],
+        }
+    }
+}
+
+/// An event sent when a cancel key or gamepad button of the [`NavigationBindings`] is pressed,
+/// like to close a menu or go back to the previous one
+#[derive(Clone, Debug)]
+pub struct NavigationCancel {
+    /// The node focused when the cancel button was pressed
+    pub focused: Option<Entity>,
+}
+
+/// An event sent when navigating from a focused node with a [`CaptureNavigation`] along its
+/// axis, instead of moving the focus
+#[derive(Clone, Debug)]
+pub struct NavigationCaptured {
+    /// The focused node
+    pub focused: Entity,
+    /// The direction of the navigation
+    pub direction: NavDirection,
+}
+
+/// The buttons of the navigation pressed during this frame
+#[derive(Default)]
+struct NavigationInput {
+    direction: Option<NavDirection>,
+    confirm_pressed: bool,
+    confirm_released: bool,
+    cancel: bool,
+}
+
+impl NavigationInput {
+    fn read_keyboard(&mut self, bindings: &NavigationBindings, keyboard_input: &Input<KeyCode>) {
+        for (direction, keys) in [
+            (NavDirection::Up, &bindings.up),
+            (NavDirection::Down, &bindings.down),
+            (NavDirection::Left, &bindings.left),
+            (NavDirection::Right, &bindings.right),
+        ] {
+            if keyboard_input.any_just_pressed(keys.iter().copied()) {
+                self.direction = Some(direction);
+            }
+        }
+        self.confirm_pressed |= keyboard_input.any_just_pressed(bindings.confirm.iter().copied());
+        self.confirm_released |= keyboard_input.any_just_released(bindings.confirm.iter().copied());
+        self.cancel |= keyboard_input.any_just_pressed(bindings.cancel.iter().copied());
+    }
+
+    fn read_gamepad(
+        &mut self,
+        bindings: &NavigationBindings,
+        gamepad: Gamepad,
+        button_input: &Input<GamepadButton>,
+    ) {
+        for (direction, button_type) in [
+            (NavDirection::Up, GamepadButtonType::DPadUp),
+            (NavDirection::Down, GamepadButtonType::DPadDown),
+            (NavDirection::Left, GamepadButtonType::DPadLeft),
+            (NavDirection::Right, GamepadButtonType::DPadRight),
+        ] {
+            if button_input.just_pressed(GamepadButton::new(gamepad, button_type)) {
+                self.direction = Some(direction);
+            }
+        }
+        let buttons = |button_types: &[GamepadButtonType]| {
+            button_types
+                .iter()
+                .map(|button_type| GamepadButton::new(gamepad, *button_type))
+                .collect::<Vec<_>>()
+        };
+        let confirm = buttons(&bindings.gamepad_confirm);
+        self.confirm_pressed |= button_input.any_just_pressed(confirm.iter().copied());
+        self.confirm_released |= button_input.any_just_released(confirm);
+        self.cancel |= button_input.any_just_pressed(buttons(&bindings.gamepad_cancel));
+    }
+}
+
+/// The direction the left stick of a gamepad is pushed in, if it's pushed far enough
+fn stick_direction(gamepad: Gamepad, axes: &Axis<GamepadAxis>) -> Option<NavDirection> {
+    let x = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX))?;
+    let y = axes.get(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY))?;
+    if x.abs().max(y.abs()) < STICK_THRESHOLD {
+        None
+    } else if x.abs() > y.abs() {
+        Some(if x > 0.0 {
+            NavDirection::Right
+        } else {
+            NavDirection::Left
+        })
+    } else {
+        Some(if y > 0.0 {
+            NavDirection::Up
+        } else {
+            NavDirection::Down
+        })
+    }
+}
+
+/// How far `to` is from `from` when navigating in `direction`, or `None` if it isn't in this
+/// direction
+///
+/// The nodes overlapping in the perpendicular direction, like the buttons of the same row when
+/// navigating right, are preferred to the closer nodes off to the side.
+fn navigation_distance(from: Rect, to: Rect, direction: NavDirection) -> Option<f32> {
+    let (from_center, to_center) = (from.center(), to.center());
+    let (ahead, along, from_range, to_range) = match direction {
+        NavDirection::Up => (
+            to_center.y < from_center.y,
+            from.min.y - to.max.y,
+            (from.min.x, from.max.x),
+            (to.min.x, to.max.x),
+        ),
+        NavDirection::Down => (
+            to_center.y > from_center.y,
+            to.min.y - from.max.y,
+            (from.min.x, from.max.x),
+            (to.min.x, to.max.x),
+        ),
+        NavDirection::Left => (
+            to_center.x < from_center.x,
+            from.min.x - to.max.x,
+            (from.min.y, from.max.y),
+            (to.min.y, to.max.y),
+        ),
+        NavDirection::Right => (
+            to_center.x > from_center.x,
+            to.min.x - from.max.x,
+            (from.min.y, from.max.y),
+            (to.min.y, to.max.y),
+        ),
+    };
+    if !ahead {
+        return None;
+    }
+    let across = (to_range.0 - from_range.1).max(from_range.0 - to_range.1);
+    Some(along.max(0.0) + 2.0 * across.max(0.0))
+}
+
+/// The system that moves the [`NavigationFocus`] between the [`Focusable`] nodes with the
+/// [`NavigationBindings`], and updates the [`Interaction`] of the focused node
+///
+/// The keyboard doesn't move the focus while a [`TextInput`](crate::widget::TextInput) is
+/// focused, as its keys edit the text. Confirming on a text input focuses it. While a
+/// [`Modal`](crate::Modal) is open, only the nodes of the [`ActiveModal`] are focused.
+#[allow(clippy::too_many_arguments)]
+pub fn ui_navigation_system(
+    mut focus: ResMut<NavigationFocus>,
+    active_modal: Res<ActiveModal>,
+    bindings: Res<NavigationBindings>,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepads: Res<Gamepads>,
+    gamepad_button_input: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    mut last_stick_direction: Local<Option<NavDirection>>,
+    mut cursor_moved_events: EventReader<CursorMoved>,
+    mut cancel_events: EventWriter<NavigationCancel>,
+    mut captured_events: EventWriter<NavigationCaptured>,
+    #[cfg(feature = "bevy_text")] mut text_input_focus: ResMut<crate::widget::TextInputFocus>,
+    #[cfg(feature = "bevy_text")] text_inputs: Query<(), With<crate::widget::TextInput>>,
+    focusable_query: Query<(
+        Entity,
+        &Focusable,
+        &Node,
+        &GlobalTransform,
+        Option<&CalculatedClip>,
+        Option<&ViewVisibility>,
+        Option<&CaptureNavigation>,
+    )>,
+    mut interaction_query: Query<&mut Interaction>,
+) {
+    let mut input = NavigationInput::default();
+    // Also while the text input unfocused by the keys of this frame
+    #[cfg(feature = "bevy_text")]
+    let typing = text_input_focus.0.is_some() || text_input_focus.is_changed();
+    #[cfg(not(feature = "bevy_text"))]
+    let typing = false;
+    if !typing {
+        input.read_keyboard(&bindings, &keyboard_input);
+    }
+    let mut stick = None;
+    for gamepad in gamepads.iter() {
+        input.read_gamepad(&bindings, gamepad, &gamepad_button_input);
+        stick = stick.or_else(|| stick_direction(gamepad, &gamepad_axes));
+    }
+    // The stick moves the focus once when pushed, like a button
+    if stick != *last_stick_direction {
+        *last_stick_direction = stick;
+        input.direction = input.direction.or(stick);
+    }
+
+    // The visible area of the focusable nodes, outside of which they can't be focused
+    let focusable_rect = |entity: Entity| {
+        let (_, _, node, global_transform, clip, view_visibility, _) =
+            focusable_query.get(entity).ok()?;
+        if !active_modal.allows(entity)
+            || matches!(view_visibility, Some(view_visibility) if !view_visibility.get())
+        {
+            return None;
+        }
+        let rect = Rect::from_center_size(global_transform.translation().truncate(), node.size());
+        let rect = match clip {
+            Some(clip) => clip.clip.intersect(rect),
+            None => rect,
+        };
+        (!rect.is_empty()).then_some(rect)
+    };
+
+    let previous = focus.focused;
+    if cursor_moved_events.iter().next().is_some() {
+        focus.focused = None;
+    }
+    // The focus is lost when its node is despawned. It's kept on hidden nodes, like the button of
+    // a menu focused before being shown.
+    if let Some(focused) = focus.focused {
+        if !focusable_query.contains(focused) {
+            focus.focused = None;
+        }
+    }
+
+    // The focused node keeps the focus in the directions it captures
+    if let (Some(direction), Some(focused)) = (input.direction, focus.focused) {
+        if matches!(
+            focusable_query.get(focused),
+            Ok((.., Some(capture))) if capture.captures(direction)
+        ) {
+            captured_events.send(NavigationCaptured { focused, direction });
+            input.direction = None;
+        }
+    }
+
+    if let Some(direction) = input.direction {
+        let next = match focus.focused {
+            Some(focused) => {
+                let focused_rect =
+                    focusable_query
+                        .get(focused)
+                        .ok()
+                        .map(|(_, _, node, global_transform, ..)| {
+                            Rect::from_center_size(
+                                global_transform.translation().truncate(),
+                                node.size(),
+                            )
+                        });
+                match focusable_query.get(focused) {
+                    // The neighbors outside of the modal aren't focused
+                    Ok((_, focusable, ..)) if focusable.neighbor(direction).is_some() => focusable
+                        .neighbor(direction)
+                        .filter(|neighbor| active_modal.allows(*neighbor)),
+                    _ => focused_rect.and_then(|focused_rect| {
+                        focusable_query
+                            .iter()
+                            .filter(|(entity, ..)| *entity != focused)
+                            .filter_map(|(entity, ..)| {
+                                let rect = focusable_rect(entity)?;
+                                let distance = navigation_distance(focused_rect, rect, direction)?;
+                                let center_distance = focused_rect.center().distance(rect.center());
+                                Some((entity, distance, center_distance))
+                            })
+                            .min_by(|(_, a, a_center), (_, b, b_center)| {
+                                a.total_cmp(b).then(a_center.total_cmp(b_center))
+                            })
+                            .map(|(entity, ..)| entity)
+                    }),
+                }
+            }
+            // The first navigation focuses the last focused node, or else the top left one
+            None => focus
+                .last
+                .filter(|last| focusable_rect(*last).is_some())
+                .or_else(|| {
+                    focusable_query
+                        .iter()
+                        .filter_map(|(entity, ..)| Some((entity, focusable_rect(entity)?)))
+                        .min_by(|(_, a), (_, b)| {
+                            let (a, b) = (a.min, b.min);
+                            a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+                        })
+                        .map(|(entity, _)| entity)
+                }),
+        };
+        // Moving toward the edge keeps the focus on the last node
+        if next.is_some() {
+            focus.focused = next;
+        }
+    }
+
+    let focused = focus.focused;
+    if focused != previous {
+        if let Some(previous) = previous {
+            if let Ok(mut interaction) = interaction_query.get_mut(previous) {
+                interaction.set_if_neq(Interaction::None);
+            }
+        }
+        if let Some(focused) = focused {
+            focus.last = Some(focused);
+        }
+        #[cfg(feature = "bevy_text")]
+        if text_input_focus.0.is_some() && text_input_focus.0 != focused && focused.is_some() {
+            text_input_focus.0 = None;
+        }
+    }
+
+    // The interaction of the hidden nodes is reset by `ui_focus_system`
+    if let Some(focused) = focused.filter(|focused| focusable_rect(*focused).is_some()) {
+        if let Ok(mut interaction) = interaction_query.get_mut(focused) {
+            if input.confirm_pressed {
+                interaction.set_if_neq(Interaction::Clicked);
+            } else if input.confirm_released || *interaction == Interaction::None {
+                interaction.set_if_neq(Interaction::Hovered);
+            }
+        }
+        #[cfg(feature = "bevy_text")]
+        if input.confirm_pressed && text_inputs.contains(focused) {
+            text_input_focus.0 = Some(focused);
+        }
+    }
+
+    if input.cancel {
+        cancel_events.send(NavigationCancel { focused });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ui_modal_system, Modal, ModalClosed, ModalOpened, UiStack};
+    use bevy_ecs::{event::Events, schedule::Schedule, world::World};
+    use bevy_hierarchy::BuildWorldChildren;
+    use bevy_math::Vec2;
+
+    #[test]
+    fn distances() {
+        let from = Rect::new(0.0, 0.0, 100.0, 100.0);
+        use NavDirection::*;
+        let cases = [
+            (
+                "overlapping across",
+                Rect::new(200.0, 0.0, 300.0, 100.0),
+                Right,
+                Some(100.0),
+            ),
+            (
+                "partly overlapping across",
+                Rect::new(200.0, 50.0, 300.0, 150.0),
+                Right,
+                Some(100.0),
+            ),
+            (
+                "off to the side",
+                Rect::new(150.0, 150.0, 250.0, 250.0),
+                Right,
+                Some(150.0),
+            ),
+            (
+                "overlapping along",
+                Rect::new(50.0, 0.0, 150.0, 100.0),
+                Right,
+                Some(0.0),
+            ),
+            ("behind", Rect::new(-200.0, 0.0, -100.0, 100.0), Right, None),
+            ("up", Rect::new(0.0, -200.0, 100.0, -100.0), Up, Some(100.0)),
+            (
+                "down and to the side",
+                Rect::new(300.0, 200.0, 400.0, 300.0),
+                Down,
+                Some(500.0),
+            ),
+            (
+                "left",
+                Rect::new(-150.0, 0.0, -50.0, 100.0),
+                Left,
+                Some(50.0),
+            ),
+            (
+                "the center behind",
+                Rect::new(-150.0, 0.0, 50.0, 100.0),
+                Right,
+                None,
+            ),
+        ];
+        for (name, to, direction, expected) in cases {
+            assert_eq!(navigation_distance(from, to, direction), expected, "{name}");
+        }
+        // not ahead when centered on the node
+        for direction in [Up, Down, Left, Right] {
+            let to = Rect::new(25.0, 25.0, 75.0, 75.0);
+            assert_eq!(
+                navigation_distance(from, to, direction),
+                None,
+                "{direction:?}"
+            );
+        }
+    }
+
+    /// Focusable nodes navigated with the arrow keys by running the [`ui_navigation_system`]
+    struct Menu {
+        world: World,
+        schedule: Schedule,
+    }
+
+    impl Menu {
+        fn new() -> Self {
+            let mut world = World::new();
+            world.init_resource::<NavigationFocus>();
+            world.init_resource::<ActiveModal>();
+            world.init_resource::<NavigationBindings>();
+            world.init_resource::<Input<KeyCode>>();
+            world.init_resource::<Gamepads>();
+            world.init_resource::<Input<GamepadButton>>();
+            world.init_resource::<Axis<GamepadAxis>>();
+            world.init_resource::<Events<CursorMoved>>();
+            world.init_resource::<Events<NavigationCancel>>();
+            world.init_resource::<Events<NavigationCaptured>>();
+            #[cfg(feature = "bevy_text")]
+            world.init_resource::<crate::widget::TextInputFocus>();
+            let mut schedule = Schedule::new();
+            schedule.add_system(ui_navigation_system);
+            // the keys are ignored while the focus of the text inputs was just changed, like
+            // when it's added
+            schedule.run(&mut world);
+            Self { world, schedule }
+        }
+
+        /// A focusable node of 100 by 50 pixels with its top left corner at `x` and `y`
+        fn spawn(&mut self, x: f32, y: f32) -> Entity {
+            let size = Vec2::new(100.0, 50.0);
+            let node = Node {
+                calculated_size: size,
+                ..Default::default()
+            };
+            let center = Vec2::new(x, y) + size / 2.0;
+            self.world
+                .spawn((
+                    Focusable::DEFAULT,
+                    node,
+                    GlobalTransform::from_translation(center.extend(0.0)),
+                    Interaction::None,
+                ))
+                .id()
+        }
+
+        /// A row of nodes of 100 by 50 pixels, spaced by 100 pixels
+        fn spawn_row(&mut self, y: f32, count: usize) -> Vec<Entity> {
+            (0..count)
+                .map(|column| self.spawn(200.0 * column as f32, y))
+                .collect()
+        }
+
+        fn focus(&mut self, entity: Entity) {
+            self.world.resource_mut::<NavigationFocus>().focus(entity);
+        }
+
+        fn press(&mut self, key: KeyCode) -> Option<Entity> {
+            self.world.resource_mut::<Input<KeyCode>>().press(key);
+            self.schedule.run(&mut self.world);
+            let mut keyboard_input = self.world.resource_mut::<Input<KeyCode>>();
+            keyboard_input.release(key);
+            keyboard_input.clear();
+            self.focused()
+        }
+
+        fn navigate(&mut self, direction: NavDirection) -> Option<Entity> {
+            self.press(match direction {
+                NavDirection::Up => KeyCode::Up,
+                NavDirection::Down => KeyCode::Down,
+                NavDirection::Left => KeyCode::Left,
+                NavDirection::Right => KeyCode::Right,
+            })
+        }
+
+        fn focused(&self) -> Option<Entity> {
+            self.world.resource::<NavigationFocus>().focused()
+        }
+
+        fn interaction(&self, entity: Entity) -> Interaction {
+            *self.world.get::<Interaction>(entity).unwrap()
+        }
+    }
+
+    #[test]
+    fn closest_node_in_the_direction() {
+        use NavDirection::*;
+        // A B C
+        // D E
+        //     F, to the right of E and further down
+        let mut menu = Menu::new();
+        let [a, b, c] = menu.spawn_row(0.0, 3)[..] else {
+            unreachable!()
+        };
+        let [d, e] = menu.spawn_row(100.0, 2)[..] else {
+            unreachable!()
+        };
+        let f = menu.spawn(450.0, 250.0);
+        let cases = [
+            (a, Right, b),
+            (a, Down, d),
+            (b, Left, a),
+            (b, Right, c),
+            (b, Down, e),
+            (c, Left, b),
+            (e, Up, b),
+            (e, Left, d),
+            // further along the row above, but overlapping across
+            (c, Down, f),
+            (e, Right, c),
+            (c, Right, f),
+            (d, Down, f),
+            (f, Up, c),
+            (f, Left, e),
+            // toward the edge, keeping the focus
+            (a, Up, a),
+            (a, Left, a),
+            (f, Right, f),
+            (f, Down, f),
+        ];
+        for (from, direction, expected) in cases {
+            menu.focus(from);
+            assert_eq!(
+                menu.navigate(direction),
+                Some(expected),
+                "{from:?} {direction:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn overlapping_nodes_are_preferred() {
+        let mut menu = Menu::new();
+        let from = menu.spawn(0.0, 0.0);
+        // further, but in the same row
+        let far = menu.spawn(250.0, 25.0);
+        let _closer_to_the_side = menu.spawn(150.0, 110.0);
+        menu.focus(from);
+        assert_eq!(menu.navigate(NavDirection::Right), Some(far));
+    }
+
+    #[test]
+    fn same_distance_closest_center() {
+        let mut menu = Menu::new();
+        let from = menu.spawn(0.0, 0.0);
+        // both overlapping the row, 100 pixels to the right
+        let _lower = menu.spawn(200.0, 40.0);
+        let level = menu.spawn(200.0, 10.0);
+        menu.focus(from);
+        assert_eq!(menu.navigate(NavDirection::Right), Some(level));
+    }
+
+    #[test]
+    fn explicit_neighbors() {
+        let mut menu = Menu::new();
+        let [a, b, c] = menu.spawn_row(0.0, 3)[..] else {
+            unreachable!()
+        };
+        // wrapping around the row
+        menu.world.get_mut::<Focusable>(c).unwrap().right = Some(a);
+        menu.world.get_mut::<Focusable>(a).unwrap().left = Some(c);
+        // even further than the closest node
+        menu.world.get_mut::<Focusable>(a).unwrap().right = Some(c);
+        let cases = [
+            (c, NavDirection::Right, a),
+            (a, NavDirection::Left, c),
+            (a, NavDirection::Right, c),
+            (b, NavDirection::Right, c),
+        ];
+        for (from, direction, expected) in cases {
+            menu.focus(from);
+            assert_eq!(
+                menu.navigate(direction),
+                Some(expected),
+                "{from:?} {direction:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn unfocusable_nodes_are_skipped() {
+        let cases: [(&str, fn(&mut Menu, Entity)); 3] = [
+            ("hidden", |menu, entity| {
+                menu.world.entity_mut(entity).insert(ViewVisibility::HIDDEN);
+            }),
+            ("clipped out", |menu, entity| {
+                menu.world.entity_mut(entity).insert(CalculatedClip {
+                    clip: Rect::new(0.0, 0.0, 150.0, 50.0),
+                    ..Default::default()
+                });
+            }),
+            ("without a focusable", |menu, entity| {
+                menu.world.entity_mut(entity).remove::<Focusable>();
+            }),
+        ];
+        for (name, make_unfocusable) in cases {
+            let mut menu = Menu::new();
+            let [a, b, c] = menu.spawn_row(0.0, 3)[..] else {
+                unreachable!()
+            };
+            make_unfocusable(&mut menu, b);
+            menu.focus(a);
+            assert_eq!(menu.navigate(NavDirection::Right), Some(c), "{name}");
+        }
+    }
+
+    #[test]
+    fn only_the_nodes_of_the_modal() {
+        let mut menu = Menu::new();
+        let [a, b, c] = menu.spawn_row(0.0, 3)[..] else {
+            unreachable!()
+        };
+        let modal = menu.spawn(0.0, 300.0);
+        menu.world
+            .entity_mut(modal)
+            .insert(Modal::DEFAULT)
+            .push_children(&[a, c]);
+        menu.world.insert_resource(UiStack {
+            uinodes: vec![modal],
+        });
+        menu.world.init_resource::<Events<ModalOpened>>();
+        menu.world.init_resource::<Events<ModalClosed>>();
+        let mut schedule = Schedule::new();
+        schedule.add_system(ui_modal_system);
+        schedule.run(&mut menu.world);
+        assert_eq!(menu.world.resource::<ActiveModal>().get(), Some(modal));
+
+        menu.focus(a);
+        assert_eq!(menu.navigate(NavDirection::Right), Some(c));
+        // the explicit neighbors outside of it aren't focused
+        menu.world.get_mut::<Focusable>(c).unwrap().left = Some(b);
+        assert_eq!(menu.navigate(NavDirection::Left), Some(c));
+    }
+
+    #[test]
+    fn first_navigation() {
+        let mut menu = Menu::new();
+        let [a, b] = menu.spawn_row(100.0, 2)[..] else {
+            unreachable!()
+        };
+        // above the others, but not the leftmost
+        let top = menu.spawn(300.0, 0.0);
+        let left = menu.spawn(-200.0, 50.0);
+        // the top left node first, whatever the direction
+        assert_eq!(menu.navigate(NavDirection::Left), Some(top));
+
+        // then the last focused node, once the focus was cleared by the mouse
+        menu.focus(b);
+        menu.world.send_event(CursorMoved {
+            window: Entity::from_raw(0),
+            position: Vec2::ZERO,
+        });
+        menu.schedule.run(&mut menu.world);
+        assert_eq!(menu.focused(), None);
+        assert_eq!(menu.navigate(NavDirection::Up), Some(b));
+
+        // unless it's hidden since
+        menu.world.resource_mut::<NavigationFocus>().clear();
+        menu.world.entity_mut(b).insert(ViewVisibility::HIDDEN);
+        menu.world.entity_mut(top).insert(ViewVisibility::HIDDEN);
+        assert_eq!(menu.navigate(NavDirection::Up), Some(left));
+        assert_eq!(menu.navigate(NavDirection::Right), Some(a));
+    }
+
+    #[test]
+    fn captured_directions() {
+        let mut menu = Menu::new();
+        let [a, b] = menu.spawn_row(0.0, 2)[..] else {
+            unreachable!()
+        };
+        let below = menu.spawn(0.0, 100.0);
+        menu.focus(b);
+        assert_eq!(menu.navigate(NavDirection::Left), Some(a));
+        menu.world
+            .entity_mut(a)
+            .insert(CaptureNavigation::Horizontal);
+        menu.focus(a);
+        assert_eq!(menu.navigate(NavDirection::Right), Some(a));
+        let captured: Vec<_> = menu
+            .world
+            .resource_mut::<Events<NavigationCaptured>>()
+            .drain()
+            .map(|event| (event.focused, event.direction))
+            .collect();
+        assert_eq!(captured, [(a, NavDirection::Right)]);
+        assert_eq!(menu.navigate(NavDirection::Down), Some(below));
+    }
+
+    #[test]
+    fn interaction_of_the_focused_node() {
+        let mut menu = Menu::new();
+        let [a, b] = menu.spawn_row(0.0, 2)[..] else {
+            unreachable!()
+        };
+        menu.navigate(NavDirection::Right);
+        assert_eq!(menu.interaction(a), Interaction::Hovered);
+        menu.world
+            .resource_mut::<Input<KeyCode>>()
+            .press(KeyCode::Return);
+        menu.schedule.run(&mut menu.world);
+        assert_eq!(menu.interaction(a), Interaction::Clicked);
+        let mut keyboard_input = menu.world.resource_mut::<Input<KeyCode>>();
+        keyboard_input.clear();
+        keyboard_input.release(KeyCode::Return);
+        menu.schedule.run(&mut menu.world);
+        assert_eq!(menu.interaction(a), Interaction::Hovered);
+        menu.world.resource_mut::<Input<KeyCode>>().clear();
+        menu.navigate(NavDirection::Right);
+        assert_eq!(
+            (menu.interaction(a), menu.interaction(b)),
+            (Interaction::None, Interaction::Hovered)
+        );
+    }
+}
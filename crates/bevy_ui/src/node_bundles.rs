@@ -1,14 +1,21 @@
 //! This module contains basic node bundles used to build UIs
 
 use crate::{
-    widget::Button, BackgroundColor, CalculatedSize, FocusPolicy, Interaction, Node, Style,
-    UiImage, ZIndex,
+    widget::Button, BackgroundColor, BorderColor, BorderRadius, CalculatedSize, FocusPolicy,
+    Interaction, Node, ScrollPosition, Style, UiImage, UiMaterial, UiTextureAtlasImage, ZIndex,
 };
+#[cfg(feature = "bevy_text")]
+use crate::{
+    widget::{TextInput, TextInputStyle},
+    RelativeCursorPosition,
+};
+use bevy_asset::Handle;
 use bevy_ecs::bundle::Bundle;
 use bevy_render::{
-    prelude::{Color, ComputedVisibility},
+    prelude::{Color, InheritedVisibility, ViewVisibility},
     view::Visibility,
 };
+use bevy_sprite::TextureAtlas;
 #[cfg(feature = "bevy_text")]
 use bevy_text::{Text, TextAlignment, TextSection, TextStyle};
 use bevy_transform::prelude::{GlobalTransform, Transform};
@@ -24,6 +31,10 @@ pub struct NodeBundle {
     pub style: Style,
     /// The background color, which serves as a "fill" for this node
     pub background_color: BackgroundColor,
+    /// The colors of the borders of the node, whose widths are set by [`Style::border`]
+    pub border_color: BorderColor,
+    /// The radii of the rounded corners of the node
+    pub border_radius: BorderRadius,
     /// Whether this node should block interaction with lower nodes
     pub focus_policy: FocusPolicy,
     /// The transform of the node
@@ -38,10 +49,14 @@ pub struct NodeBundle {
     pub global_transform: GlobalTransform,
     /// Describes the visibility properties of the node
     pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
     /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
-    pub computed_visibility: ComputedVisibility,
+    pub view_visibility: ViewVisibility,
     /// Indicates the depth at which the node should appear in the UI
     pub z_index: ZIndex,
+    /// How far the content is scrolled with [`Overflow::Scroll`](crate::Overflow::Scroll)
+    pub scroll_position: ScrollPosition,
 }
 
 impl Default for NodeBundle {
@@ -49,14 +64,18 @@ impl Default for NodeBundle {
         NodeBundle {
             // Transparent background
             background_color: Color::NONE.into(),
+            border_color: Default::default(),
+            border_radius: Default::default(),
             node: Default::default(),
             style: Default::default(),
             focus_policy: Default::default(),
             transform: Default::default(),
             global_transform: Default::default(),
             visibility: Default::default(),
-            computed_visibility: Default::default(),
+            inherited_visibility: Default::default(),
+            view_visibility: Default::default(),
             z_index: Default::default(),
+            scroll_position: Default::default(),
         }
     }
 }
@@ -90,8 +109,49 @@ pub struct ImageBundle {
     pub global_transform: GlobalTransform,
     /// Describes the visibility properties of the node
     pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
     /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
-    pub computed_visibility: ComputedVisibility,
+    pub view_visibility: ViewVisibility,
+    /// Indicates the depth at which the node should appear in the UI
+    pub z_index: ZIndex,
+}
+
+/// A UI node that is a region of a texture atlas
+#[derive(Bundle, Clone, Debug, Default)]
+pub struct AtlasImageBundle {
+    /// Describes the size of the node
+    pub node: Node,
+    /// Describes the style including flexbox settings
+    pub style: Style,
+    /// The calculated size based on the given region of the atlas
+    pub calculated_size: CalculatedSize,
+    /// The background color, which serves as a "fill" for this node
+    ///
+    /// Combines with the atlas to tint the provided region.
+    pub background_color: BackgroundColor,
+    /// The texture atlas of the node
+    pub texture_atlas: Handle<TextureAtlas>,
+    /// The region of the atlas displayed
+    pub texture_atlas_image: UiTextureAtlasImage,
+    /// Whether this node should block interaction with lower nodes
+    pub focus_policy: FocusPolicy,
+    /// The transform of the node
+    ///
+    /// This field is automatically managed by the UI layout system.
+    /// To alter the position of the `NodeBundle`, use the properties of the [`Style`] component.
+    pub transform: Transform,
+    /// The global transform of the node
+    ///
+    /// This field is automatically managed by the UI layout system.
+    /// To alter the position of the `NodeBundle`, use the properties of the [`Style`] component.
+    pub global_transform: GlobalTransform,
+    /// Describes the visibility properties of the node
+    pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
     /// Indicates the depth at which the node should appear in the UI
     pub z_index: ZIndex,
 }
@@ -122,8 +182,10 @@ pub struct TextBundle {
     pub global_transform: GlobalTransform,
     /// Describes the visibility properties of the node
     pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
     /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
-    pub computed_visibility: ComputedVisibility,
+    pub view_visibility: ViewVisibility,
     /// Indicates the depth at which the node should appear in the UI
     pub z_index: ZIndex,
     /// The background color that will fill the containing node
@@ -144,7 +206,8 @@ impl Default for TextBundle {
             transform: Default::default(),
             global_transform: Default::default(),
             visibility: Default::default(),
-            computed_visibility: Default::default(),
+            inherited_visibility: Default::default(),
+            view_visibility: Default::default(),
             z_index: Default::default(),
         }
     }
@@ -179,7 +242,7 @@ impl TextBundle {
     }
 
     /// Returns this [`TextBundle`] with a new [`Style`].
-    pub const fn with_style(mut self, style: Style) -> Self {
+    pub fn with_style(mut self, style: Style) -> Self {
         self.style = style;
         self
     }
@@ -191,6 +254,108 @@ impl TextBundle {
     }
 }
 
+#[cfg(feature = "bevy_text")]
+/// A UI node that is a single line text input
+#[derive(Bundle, Clone, Debug)]
+pub struct TextInputBundle {
+    /// Describes the size of the node
+    pub node: Node,
+    /// Describes the style including flexbox settings
+    pub style: Style,
+    /// Contains the text typed in the node, the caret and the selection
+    pub text_input: TextInput,
+    /// The styles of the text, the caret and the selection
+    pub text_input_style: TextInputStyle,
+    /// Contains the text of the node, laid out from the [`TextInput`]
+    pub text: Text,
+    /// The calculated size based on the text
+    pub calculated_size: CalculatedSize,
+    /// Describes whether and how the input has been interacted with, to focus it
+    pub interaction: Interaction,
+    /// The position of the cursor in the node, to move the caret
+    pub relative_cursor_position: RelativeCursorPosition,
+    /// Whether this node should block interaction with lower nodes
+    pub focus_policy: FocusPolicy,
+    /// The transform of the node
+    ///
+    /// This field is automatically managed by the UI layout system.
+    /// To alter the position of the `TextInputBundle`, use the properties of the [`Style`] component.
+    pub transform: Transform,
+    /// The global transform of the node
+    ///
+    /// This field is automatically managed by the UI layout system.
+    /// To alter the position of the `TextInputBundle`, use the properties of the [`Style`] component.
+    pub global_transform: GlobalTransform,
+    /// Describes the visibility properties of the node
+    pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+    /// Indicates the depth at which the node should appear in the UI
+    pub z_index: ZIndex,
+    /// The background color that will fill the containing node
+    pub background_color: BackgroundColor,
+}
+
+#[cfg(feature = "bevy_text")]
+impl Default for TextInputBundle {
+    fn default() -> Self {
+        Self {
+            text_input: Default::default(),
+            text_input_style: Default::default(),
+            text: Default::default(),
+            calculated_size: Default::default(),
+            interaction: Default::default(),
+            relative_cursor_position: Default::default(),
+            focus_policy: FocusPolicy::Block,
+            // Transparent background
+            background_color: BackgroundColor(Color::NONE),
+            node: Default::default(),
+            style: Default::default(),
+            transform: Default::default(),
+            global_transform: Default::default(),
+            visibility: Default::default(),
+            inherited_visibility: Default::default(),
+            view_visibility: Default::default(),
+            z_index: Default::default(),
+        }
+    }
+}
+
+#[cfg(feature = "bevy_text")]
+impl TextInputBundle {
+    /// Create a [`TextInputBundle`] with `value` typed in, laid out with `text_style`.
+    pub fn new(value: impl Into<String>, text_style: TextStyle) -> Self {
+        Self {
+            text_input: TextInput::new(value),
+            text_input_style: TextInputStyle {
+                text_style,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Returns this [`TextInputBundle`] with a new placeholder on [`TextInput`].
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.text_input.placeholder = placeholder.into();
+        self
+    }
+
+    /// Returns this [`TextInputBundle`] with a new [`Style`].
+    pub fn with_style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Returns this [`TextInputBundle`] with a new [`BackgroundColor`].
+    pub fn with_background_color(mut self, color: Color) -> Self {
+        self.background_color = BackgroundColor(color);
+        self
+    }
+}
+
 /// A UI node that is a button
 #[derive(Bundle, Clone, Debug)]
 pub struct ButtonBundle {
@@ -208,6 +373,10 @@ pub struct ButtonBundle {
     ///
     /// When combined with `UiImage`, tints the provided image.
     pub background_color: BackgroundColor,
+    /// The colors of the borders of the node, whose widths are set by [`Style::border`]
+    pub border_color: BorderColor,
+    /// The radii of the rounded corners of the node
+    pub border_radius: BorderRadius,
     /// The image of the node
     pub image: UiImage,
     /// The transform of the node
@@ -222,8 +391,10 @@ pub struct ButtonBundle {
     pub global_transform: GlobalTransform,
     /// Describes the visibility properties of the node
     pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
     /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
-    pub computed_visibility: ComputedVisibility,
+    pub view_visibility: ViewVisibility,
     /// Indicates the depth at which the node should appear in the UI
     pub z_index: ZIndex,
 }
@@ -237,11 +408,64 @@ impl Default for ButtonBundle {
             style: Default::default(),
             interaction: Default::default(),
             background_color: Default::default(),
+            border_color: Default::default(),
+            border_radius: Default::default(),
             image: Default::default(),
             transform: Default::default(),
             global_transform: Default::default(),
             visibility: Default::default(),
-            computed_visibility: Default::default(),
+            inherited_visibility: Default::default(),
+            view_visibility: Default::default(),
+            z_index: Default::default(),
+        }
+    }
+}
+
+/// A UI node that is drawn with a custom [`UiMaterial`]
+///
+/// The material replaces the background color and image of the node.
+#[derive(Bundle, Clone, Debug)]
+pub struct MaterialNodeBundle<M: UiMaterial> {
+    /// Describes the size of the node
+    pub node: Node,
+    /// Describes the style including flexbox settings
+    pub style: Style,
+    /// The material of the node, drawn by a [`UiMaterialPlugin`](crate::UiMaterialPlugin)
+    pub material: Handle<M>,
+    /// Whether this node should block interaction with lower nodes
+    pub focus_policy: FocusPolicy,
+    /// The transform of the node
+    ///
+    /// This field is automatically managed by the UI layout system.
+    /// To alter the position of the `MaterialNodeBundle`, use the properties of the [`Style`] component.
+    pub transform: Transform,
+    /// The global transform of the node
+    ///
+    /// This field is automatically managed by the UI layout system.
+    /// To alter the position of the `MaterialNodeBundle`, use the properties of the [`Style`] component.
+    pub global_transform: GlobalTransform,
+    /// Describes the visibility properties of the node
+    pub visibility: Visibility,
+    /// The inherited visibility of the entity.
+    pub inherited_visibility: InheritedVisibility,
+    /// Algorithmically-computed indication of whether an entity is visible and should be extracted for rendering
+    pub view_visibility: ViewVisibility,
+    /// Indicates the depth at which the node should appear in the UI
+    pub z_index: ZIndex,
+}
+
+impl<M: UiMaterial> Default for MaterialNodeBundle<M> {
+    fn default() -> Self {
+        MaterialNodeBundle {
+            node: Default::default(),
+            style: Default::default(),
+            material: Default::default(),
+            focus_policy: Default::default(),
+            transform: Default::default(),
+            global_transform: Default::default(),
+            visibility: Default::default(),
+            inherited_visibility: Default::default(),
+            view_visibility: Default::default(),
             z_index: Default::default(),
         }
     }
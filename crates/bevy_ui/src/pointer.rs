@@ -0,0 +1,694 @@
+use crate::{ActiveModal, CalculatedClip, DragState, FocusPolicy, Node, UiStack};
+use bevy_ecs::{
+    entity::Entity,
+    event::EventWriter,
+    prelude::With,
+    query::WorldQuery,
+    system::{Local, Query, Res, ResMut, Resource, SystemParam},
+};
+use bevy_input::{mouse::MouseButton, touch::Touches, Input};
+use bevy_math::{Rect, Vec2};
+use bevy_render::view::ViewVisibility;
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::HashMap;
+use bevy_window::{PrimaryWindow, Window};
+
+/// A pointer over the UI: the mouse, or a finger on a touch screen
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PointerId {
+    /// The mouse cursor
+    Mouse,
+    /// A touch, by its id
+    Touch(u64),
+}
+
+/// A button of a pointer
+///
+/// The touches only have a [`PointerButton::Primary`] button.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PointerButton {
+    /// The left mouse button, or a touch
+    Primary,
+    /// The right mouse button, opening the context menus
+    Secondary,
+    /// The middle mouse button
+    Middle,
+}
+
+impl PointerButton {
+    /// The mouse buttons reported by the pointer events, with their pointer button
+    const MOUSE_BUTTONS: [(MouseButton, PointerButton); 3] = [
+        (MouseButton::Left, PointerButton::Primary),
+        (MouseButton::Right, PointerButton::Secondary),
+        (MouseButton::Middle, PointerButton::Middle),
+    ];
+}
+
+/// The nodes under a pointer, in the [`UiPointerMap`]
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PointerHits {
+    /// The position of the pointer in the window, in logical pixels from its top left
+    pub position: Vec2,
+    /// The nodes under the pointer, from the top one down to the first one with a
+    /// [`FocusPolicy::Block`]
+    pub nodes: Vec<Entity>,
+}
+
+/// What is under each pointer, updated by [`ui_pointer_system`]
+///
+/// Like for [`Interaction`](crate::Interaction), only the visible nodes in the
+/// [`ActiveModal`] are hit, from the top node under the pointer down to the first node with a
+/// [`FocusPolicy::Block`].
+#[derive(Resource, Default, Debug)]
+pub struct UiPointerMap {
+    pointers: HashMap<PointerId, PointerHits>,
+}
+
+impl UiPointerMap {
+    /// The position of `pointer` and the nodes under it, if it's over a window
+    pub fn get(&self, pointer: PointerId) -> Option<&PointerHits> {
+        self.pointers.get(&pointer)
+    }
+
+    /// The top node under `pointer`, if any
+    pub fn top(&self, pointer: PointerId) -> Option<Entity> {
+        self.get(pointer)?.nodes.first().copied()
+    }
+
+    /// Whether `entity` is under `pointer`
+    pub fn is_under(&self, pointer: PointerId, entity: Entity) -> bool {
+        matches!(self.get(pointer), Some(hits) if hits.nodes.contains(&entity))
+    }
+
+    /// Whether any pointer is over `entity`
+    pub fn is_hovered(&self, entity: Entity) -> bool {
+        self.pointers
+            .values()
+            .any(|hits| hits.nodes.contains(&entity))
+    }
+
+    /// The pointers over a window, with what is under them
+    pub fn iter(&self) -> impl Iterator<Item = (PointerId, &PointerHits)> {
+        self.pointers.iter().map(|(pointer, hits)| (*pointer, hits))
+    }
+
+    /// Sets what is under `pointer`, or removes it from the window, for the tests of the systems
+    /// reading the map
+    #[cfg(test)]
+    pub(crate) fn set(&mut self, pointer: PointerId, hits: Option<PointerHits>) {
+        match hits {
+            Some(hits) => self.pointers.insert(pointer, hits),
+            None => self.pointers.remove(&pointer),
+        };
+    }
+}
+
+/// An event sent when a pointer moves over a node
+#[derive(Clone, Debug)]
+pub struct PointerOver {
+    pub pointer: PointerId,
+    /// The node under the pointer
+    pub target: Entity,
+    /// The position of the pointer in the window, in logical pixels from its top left
+    pub position: Vec2,
+    /// The position of the pointer in the node, in logical pixels from its top left
+    pub node_position: Vec2,
+}
+
+/// An event sent when a pointer moves out of a node, leaves the window or is lifted from the
+/// touch screen
+#[derive(Clone, Debug)]
+pub struct PointerOut {
+    pub pointer: PointerId,
+    /// The node the pointer was over
+    pub target: Entity,
+}
+
+/// An event sent when a button of a pointer is pressed over a node
+#[derive(Clone, Debug)]
+pub struct PointerDown {
+    pub pointer: PointerId,
+    pub button: PointerButton,
+    /// The node under the pointer
+    pub target: Entity,
+    /// The position of the pointer in the window, in logical pixels from its top left
+    pub position: Vec2,
+    /// The position of the pointer in the node, in logical pixels from its top left
+    pub node_position: Vec2,
+}
+
+/// An event sent when a button of a pointer is released over a node
+#[derive(Clone, Debug)]
+pub struct PointerUp {
+    pub pointer: PointerId,
+    pub button: PointerButton,
+    /// The node under the pointer
+    pub target: Entity,
+    /// The position of the pointer in the window, in logical pixels from its top left
+    pub position: Vec2,
+    /// The position of the pointer in the node, in logical pixels from its top left
+    pub node_position: Vec2,
+}
+
+/// An event sent when a button of a pointer is released over the node it was pressed on
+///
+/// No click is sent for the release of a drag started on a [`Draggable`](crate::Draggable) node,
+/// once the pointer moved past its [`threshold`](crate::Draggable::threshold).
+#[derive(Clone, Debug)]
+pub struct PointerClick {
+    pub pointer: PointerId,
+    pub button: PointerButton,
+    /// The node under the pointer
+    pub target: Entity,
+    /// The position of the pointer in the window, in logical pixels from its top left
+    pub position: Vec2,
+    /// The position of the pointer in the node, in logical pixels from its top left
+    pub node_position: Vec2,
+}
+
+/// Main query for [`ui_pointer_system`]
+#[derive(WorldQuery)]
+pub struct PointerNodeQuery {
+    node: &'static Node,
+    global_transform: &'static GlobalTransform,
+    focus_policy: Option<&'static FocusPolicy>,
+    calculated_clip: Option<&'static CalculatedClip>,
+    view_visibility: Option<&'static ViewVisibility>,
+}
+
+/// The events of the pointers, sent by [`ui_pointer_system`]
+#[derive(SystemParam)]
+pub struct PointerEvents<'w> {
+    over: EventWriter<'w, PointerOver>,
+    out: EventWriter<'w, PointerOut>,
+    down: EventWriter<'w, PointerDown>,
+    up: EventWriter<'w, PointerUp>,
+    click: EventWriter<'w, PointerClick>,
+}
+
+/// A change of the buttons of a pointer during this frame
+enum ButtonChange {
+    Pressed(PointerButton),
+    Released(PointerButton),
+}
+
+/// The system that updates the [`UiPointerMap`], and sends the [`PointerOver`], [`PointerOut`],
+/// [`PointerDown`], [`PointerUp`] and [`PointerClick`] events of the nodes under the pointers
+///
+/// The events are sent to every node under the pointer in the map, from the top one, so a panel
+/// still gets the right clicks on its children with a [`FocusPolicy::Pass`], like to open its
+/// context menu. The [`Interaction`](crate::Interaction) of the nodes is still updated by
+/// [`ui_focus_system`](crate::ui_focus_system), for the nodes that are only clicked.
+#[allow(clippy::too_many_arguments)]
+pub fn ui_pointer_system(
+    mut pointer_map: ResMut<UiPointerMap>,
+    mut pressed: Local<HashMap<(PointerId, PointerButton), Vec<Entity>>>,
+    mut events: PointerEvents,
+    mouse_button_input: Res<Input<MouseButton>>,
+    touches_input: Res<Touches>,
+    ui_stack: Res<UiStack>,
+    active_modal: Res<ActiveModal>,
+    drag_state: Res<DragState>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    node_query: Query<PointerNodeQuery>,
+) {
+    // The rect of the nodes that can be hit
+    let node_rect = |entity: Entity| {
+        let node = node_query.get(entity).ok()?;
+        if !active_modal.allows(entity)
+            || matches!(node.view_visibility, Some(view_visibility) if !view_visibility.get())
+        {
+            return None;
+        }
+        let rect = Rect::from_center_size(
+            node.global_transform.translation().truncate(),
+            node.node.size(),
+        );
+        Some((rect, node.calculated_clip, node.focus_policy))
+    };
+    let nodes_under = |position: Vec2| {
+        let mut nodes = Vec::new();
+        for entity in ui_stack.uinodes.iter().rev() {
+            let Some((rect, clip, focus_policy)) = node_rect(*entity) else {
+                continue;
+            };
+            let visible_rect = match clip {
+                Some(clip) => clip.clip.intersect(rect),
+                None => rect,
+            };
+            if !visible_rect.contains(position) {
+                continue;
+            }
+            nodes.push(*entity);
+            if *focus_policy.unwrap_or(&FocusPolicy::Block) == FocusPolicy::Block {
+                break;
+            }
+        }
+        nodes
+    };
+    let node_position = |entity: Entity, position: Vec2| {
+        node_rect(entity).map_or(Vec2::ZERO, |(rect, ..)| position - rect.min)
+    };
+
+    // The pointers over the window this frame, with the changes of their buttons
+    let mut pointers = Vec::new();
+    let cursor_position = windows.get_single().ok().and_then(|window| {
+        window.cursor_position().map(|mut cursor_position| {
+            cursor_position.y = window.height() - cursor_position.y;
+            cursor_position
+        })
+    });
+    let mouse_changes = PointerButton::MOUSE_BUTTONS
+        .into_iter()
+        .filter_map(|(mouse_button, button)| {
+            if mouse_button_input.just_pressed(mouse_button) {
+                Some(ButtonChange::Pressed(button))
+            } else if mouse_button_input.just_released(mouse_button) {
+                Some(ButtonChange::Released(button))
+            } else {
+                None
+            }
+        })
+        .collect::<Vec<_>>();
+    pointers.push((PointerId::Mouse, cursor_position, mouse_changes, false));
+    for touch in touches_input.iter() {
+        let changes = if touches_input.just_pressed(touch.id()) {
+            vec![ButtonChange::Pressed(PointerButton::Primary)]
+        } else {
+            Vec::new()
+        };
+        pointers.push((
+            PointerId::Touch(touch.id()),
+            Some(touch.position()),
+            changes,
+            false,
+        ));
+    }
+    // The lifted touches are released where they were, then leave
+    for touch in touches_input.iter_just_released() {
+        pointers.push((
+            PointerId::Touch(touch.id()),
+            Some(touch.position()),
+            vec![ButtonChange::Released(PointerButton::Primary)],
+            true,
+        ));
+    }
+    for touch in touches_input.iter_just_cancelled() {
+        pressed.remove(&(PointerId::Touch(touch.id()), PointerButton::Primary));
+        pointers.push((PointerId::Touch(touch.id()), None, Vec::new(), true));
+    }
+
+    // The pointers gone since the last frame leave their nodes
+    let gone: Vec<PointerId> = pointer_map
+        .pointers
+        .keys()
+        .filter(|pointer| !pointers.iter().any(|(id, ..)| id == *pointer))
+        .copied()
+        .collect();
+    for pointer in gone {
+        for target in pointer_map.pointers.remove(&pointer).unwrap().nodes {
+            events.out.send(PointerOut { pointer, target });
+        }
+    }
+
+    for (pointer, position, changes, lifted) in pointers {
+        let nodes = position.map(nodes_under).unwrap_or_default();
+        let previous = match position {
+            Some(position) => pointer_map
+                .pointers
+                .insert(
+                    pointer,
+                    PointerHits {
+                        position,
+                        nodes: nodes.clone(),
+                    },
+                )
+                .map(|hits| hits.nodes),
+            None => pointer_map.pointers.remove(&pointer).map(|hits| hits.nodes),
+        }
+        .unwrap_or_default();
+
+        for target in previous.iter().filter(|target| !nodes.contains(target)) {
+            events.out.send(PointerOut {
+                pointer,
+                target: *target,
+            });
+        }
+        let Some(position) = position else {
+            continue;
+        };
+        for target in nodes.iter().filter(|target| !previous.contains(target)) {
+            events.over.send(PointerOver {
+                pointer,
+                target: *target,
+                position,
+                node_position: node_position(*target, position),
+            });
+        }
+
+        for change in changes {
+            match change {
+                ButtonChange::Pressed(button) => {
+                    for target in &nodes {
+                        events.down.send(PointerDown {
+                            pointer,
+                            button,
+                            target: *target,
+                            position,
+                            node_position: node_position(*target, position),
+                        });
+                    }
+                    pressed.insert((pointer, button), nodes.clone());
+                }
+                ButtonChange::Released(button) => {
+                    let mut pressed_nodes = pressed.remove(&(pointer, button)).unwrap_or_default();
+                    // The drags are released by the drag system after this one
+                    if button == PointerButton::Primary && drag_state.is_dragging() {
+                        pressed_nodes.clear();
+                    }
+                    for target in &nodes {
+                        let node_position = node_position(*target, position);
+                        events.up.send(PointerUp {
+                            pointer,
+                            button,
+                            target: *target,
+                            position,
+                            node_position,
+                        });
+                        if pressed_nodes.contains(target) {
+                            events.click.send(PointerClick {
+                                pointer,
+                                button,
+                                target: *target,
+                                position,
+                                node_position,
+                            });
+                        }
+                    }
+                }
+            }
+        }
+
+        if lifted {
+            for target in pointer_map.pointers.remove(&pointer).unwrap().nodes {
+                events.out.send(PointerOut { pointer, target });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{event::Events, schedule::Schedule, world::World};
+
+    /// The events sent in a frame, grouped by type in the order they are sent
+    #[derive(Debug, PartialEq)]
+    enum Sent {
+        Out(Entity),
+        Over(Entity),
+        Down(Entity, PointerButton, Vec2),
+        Up(Entity, PointerButton),
+        Click(Entity, PointerButton),
+    }
+
+    /// Nodes under the mouse, updated by running the [`ui_pointer_system`]
+    struct Scene {
+        world: World,
+        schedule: Schedule,
+        window: Entity,
+    }
+
+    impl Scene {
+        fn new() -> Self {
+            let mut world = World::new();
+            world.init_resource::<UiPointerMap>();
+            world.init_resource::<Input<MouseButton>>();
+            world.init_resource::<Touches>();
+            world.init_resource::<ActiveModal>();
+            world.init_resource::<DragState>();
+            world.init_resource::<UiStack>();
+            world.init_resource::<Events<PointerOver>>();
+            world.init_resource::<Events<PointerOut>>();
+            world.init_resource::<Events<PointerDown>>();
+            world.init_resource::<Events<PointerUp>>();
+            world.init_resource::<Events<PointerClick>>();
+            let window = world.spawn((Window::default(), PrimaryWindow)).id();
+            let mut schedule = Schedule::new();
+            schedule.add_system(ui_pointer_system);
+            Self {
+                world,
+                schedule,
+                window,
+            }
+        }
+
+        /// Spawns a node of `size` with its top left at `position` from the top left of the
+        /// window, over the nodes spawned before it
+        fn spawn_node(&mut self, position: Vec2, size: Vec2, focus_policy: FocusPolicy) -> Entity {
+            let node = Node {
+                calculated_size: size,
+                ..Default::default()
+            };
+            let center = (position + size / 2.0).extend(0.0);
+            let entity = self
+                .world
+                .spawn((
+                    node,
+                    GlobalTransform::from_translation(center),
+                    focus_policy,
+                ))
+                .id();
+            self.world.resource_mut::<UiStack>().uinodes.push(entity);
+            entity
+        }
+
+        /// Moves the cursor to `position`, from the top left of the window, or out of the window,
+        /// and runs a frame
+        fn move_to(&mut self, position: Option<Vec2>) -> Vec<Sent> {
+            let mut window = self.world.get_mut::<Window>(self.window).unwrap();
+            let height = window.height();
+            window.set_cursor_position(
+                position.map(|position| Vec2::new(position.x, height - position.y)),
+            );
+            self.run()
+        }
+
+        fn press(&mut self, button: MouseButton) -> Vec<Sent> {
+            self.world
+                .resource_mut::<Input<MouseButton>>()
+                .press(button);
+            self.run()
+        }
+
+        fn release(&mut self, button: MouseButton) -> Vec<Sent> {
+            self.world
+                .resource_mut::<Input<MouseButton>>()
+                .release(button);
+            self.run()
+        }
+
+        /// Runs a frame, returning the events sent, which are all sent for the mouse
+        fn run(&mut self) -> Vec<Sent> {
+            self.schedule.run(&mut self.world);
+            self.world.resource_mut::<Input<MouseButton>>().clear();
+
+            let mut sent = Vec::new();
+            macro_rules! drain {
+                ($event:ty, |$e:ident| $sent:expr) => {
+                    for $e in self.world.resource_mut::<Events<$event>>().drain() {
+                        assert_eq!($e.pointer, PointerId::Mouse);
+                        sent.push($sent);
+                    }
+                };
+            }
+            drain!(PointerOut, |e| Sent::Out(e.target));
+            drain!(PointerOver, |e| Sent::Over(e.target));
+            drain!(PointerDown, |e| Sent::Down(
+                e.target,
+                e.button,
+                e.node_position
+            ));
+            drain!(PointerUp, |e| Sent::Up(e.target, e.button));
+            drain!(PointerClick, |e| Sent::Click(e.target, e.button));
+            sent
+        }
+
+        fn hits(&self) -> Option<&PointerHits> {
+            self.world.resource::<UiPointerMap>().get(PointerId::Mouse)
+        }
+    }
+
+    const SIZE: Vec2 = Vec2::splat(100.0);
+    const PRIMARY: PointerButton = PointerButton::Primary;
+
+    #[test]
+    fn over_down_up_click_out_sequence() {
+        let mut scene = Scene::new();
+        let a = scene.spawn_node(Vec2::ZERO, SIZE, FocusPolicy::Block);
+        let b = scene.spawn_node(Vec2::new(200.0, 0.0), SIZE, FocusPolicy::Block);
+
+        assert_eq!(scene.move_to(Some(Vec2::new(150.0, 50.0))), []);
+        assert_eq!(scene.hits().unwrap().nodes, []);
+        assert_eq!(scene.move_to(Some(Vec2::new(20.0, 30.0))), [Sent::Over(a)]);
+        assert_eq!(scene.hits().unwrap().nodes, [a]);
+        assert_eq!(scene.hits().unwrap().position, Vec2::new(20.0, 30.0));
+        // moving over the same node sends nothing
+        assert_eq!(scene.move_to(Some(Vec2::new(25.0, 30.0))), []);
+        assert_eq!(
+            scene.press(MouseButton::Left),
+            [Sent::Down(a, PRIMARY, Vec2::new(25.0, 30.0))]
+        );
+        assert_eq!(scene.run(), []);
+        assert_eq!(
+            scene.release(MouseButton::Left),
+            [Sent::Up(a, PRIMARY), Sent::Click(a, PRIMARY)]
+        );
+        assert_eq!(
+            scene.move_to(Some(Vec2::new(250.0, 50.0))),
+            [Sent::Out(a), Sent::Over(b)]
+        );
+        assert_eq!(scene.move_to(None), [Sent::Out(b)]);
+        assert_eq!(scene.hits(), None);
+    }
+
+    #[test]
+    fn releasing_outside_the_pressed_node_doesnt_click() {
+        let mut scene = Scene::new();
+        let a = scene.spawn_node(Vec2::ZERO, SIZE, FocusPolicy::Block);
+        let b = scene.spawn_node(Vec2::new(200.0, 0.0), SIZE, FocusPolicy::Block);
+
+        scene.move_to(Some(Vec2::new(50.0, 50.0)));
+        scene.press(MouseButton::Left);
+        assert_eq!(
+            scene.move_to(Some(Vec2::new(250.0, 50.0))),
+            [Sent::Out(a), Sent::Over(b)]
+        );
+        assert_eq!(scene.release(MouseButton::Left), [Sent::Up(b, PRIMARY)]);
+
+        // nor over no node
+        scene.press(MouseButton::Left);
+        scene.move_to(Some(Vec2::new(150.0, 50.0)));
+        assert_eq!(scene.release(MouseButton::Left), []);
+
+        // but it clicks once back over the pressed node
+        scene.move_to(Some(Vec2::new(50.0, 50.0)));
+        scene.press(MouseButton::Left);
+        scene.move_to(Some(Vec2::new(250.0, 50.0)));
+        scene.move_to(Some(Vec2::new(50.0, 50.0)));
+        assert_eq!(
+            scene.release(MouseButton::Left),
+            [Sent::Up(a, PRIMARY), Sent::Click(a, PRIMARY)]
+        );
+    }
+
+    #[test]
+    fn each_button_clicks_the_node_it_pressed() {
+        let mut scene = Scene::new();
+        let a = scene.spawn_node(Vec2::ZERO, SIZE, FocusPolicy::Block);
+        let b = scene.spawn_node(Vec2::new(200.0, 0.0), SIZE, FocusPolicy::Block);
+        let secondary = PointerButton::Secondary;
+
+        scene.move_to(Some(Vec2::new(50.0, 50.0)));
+        assert_eq!(
+            scene.press(MouseButton::Right),
+            [Sent::Down(a, secondary, Vec2::splat(50.0))]
+        );
+        assert_eq!(
+            scene.release(MouseButton::Right),
+            [Sent::Up(a, secondary), Sent::Click(a, secondary)]
+        );
+
+        scene.press(MouseButton::Left);
+        scene.move_to(Some(Vec2::new(250.0, 50.0)));
+        scene.press(MouseButton::Right);
+        scene.move_to(Some(Vec2::new(50.0, 50.0)));
+        assert_eq!(
+            scene.release(MouseButton::Right),
+            [Sent::Up(a, secondary)],
+            "pressed over the other node"
+        );
+        assert_eq!(
+            scene.release(MouseButton::Left),
+            [Sent::Up(a, PRIMARY), Sent::Click(a, PRIMARY)]
+        );
+        assert_eq!(
+            scene.move_to(Some(Vec2::new(250.0, 50.0))),
+            [Sent::Out(a), Sent::Over(b)]
+        );
+    }
+
+    #[test]
+    fn events_pass_through_to_the_nodes_below() {
+        let mut scene = Scene::new();
+        let panel = scene.spawn_node(Vec2::ZERO, Vec2::new(400.0, 100.0), FocusPolicy::Block);
+        let button = scene.spawn_node(Vec2::new(200.0, 0.0), SIZE, FocusPolicy::Pass);
+
+        assert_eq!(
+            scene.move_to(Some(Vec2::new(50.0, 50.0))),
+            [Sent::Over(panel)]
+        );
+        assert_eq!(
+            scene.move_to(Some(Vec2::new(250.0, 50.0))),
+            [Sent::Over(button)]
+        );
+        assert_eq!(scene.hits().unwrap().nodes, [button, panel]);
+        assert_eq!(
+            scene.press(MouseButton::Left),
+            [
+                Sent::Down(button, PRIMARY, Vec2::splat(50.0)),
+                Sent::Down(panel, PRIMARY, Vec2::new(250.0, 50.0)),
+            ]
+        );
+        assert_eq!(
+            scene.release(MouseButton::Left),
+            [
+                Sent::Up(button, PRIMARY),
+                Sent::Up(panel, PRIMARY),
+                Sent::Click(button, PRIMARY),
+                Sent::Click(panel, PRIMARY),
+            ]
+        );
+        assert_eq!(
+            scene.move_to(Some(Vec2::new(50.0, 50.0))),
+            [Sent::Out(button)]
+        );
+
+        // a blocking node hides the nodes below
+        let blocking = scene.spawn_node(Vec2::ZERO, SIZE, FocusPolicy::Block);
+        assert_eq!(scene.run(), [Sent::Out(panel), Sent::Over(blocking)]);
+    }
+
+    #[test]
+    fn hidden_and_clipped_nodes_arent_hit() {
+        let mut scene = Scene::new();
+        let hidden = scene.spawn_node(Vec2::ZERO, SIZE, FocusPolicy::Block);
+        scene
+            .world
+            .entity_mut(hidden)
+            .insert(ViewVisibility::default());
+        let clipped = scene.spawn_node(Vec2::new(200.0, 0.0), SIZE, FocusPolicy::Block);
+        scene.world.entity_mut(clipped).insert(CalculatedClip {
+            clip: Rect::new(200.0, 0.0, 250.0, 100.0),
+            ..Default::default()
+        });
+
+        assert_eq!(scene.move_to(Some(Vec2::new(50.0, 50.0))), []);
+        assert_eq!(scene.move_to(Some(Vec2::new(275.0, 50.0))), []);
+        assert_eq!(
+            scene.move_to(Some(Vec2::new(225.0, 50.0))),
+            [Sent::Over(clipped)]
+        );
+    }
+
+    #[test]
+    fn despawned_nodes_are_left() {
+        let mut scene = Scene::new();
+        let a = scene.spawn_node(Vec2::ZERO, SIZE, FocusPolicy::Block);
+        scene.move_to(Some(Vec2::new(50.0, 50.0)));
+        scene.world.despawn(a);
+        scene.world.resource_mut::<UiStack>().uinodes.clear();
+        assert_eq!(scene.run(), [Sent::Out(a)]);
+        assert_eq!(scene.hits().unwrap().nodes, []);
+    }
+}
@@ -0,0 +1,84 @@
+use bevy_app::prelude::*;
+use bevy_diagnostic::{Diagnostic, DiagnosticId, Diagnostics};
+use bevy_ecs::prelude::*;
+use bevy_render::RenderApp;
+use std::sync::{Arc, Mutex};
+
+/// Adds `ui_batches`, `ui_vertices` and `ui_nodes` diagnostics, counting the draw calls, the
+/// vertices and the quads the UI is drawn with each frame
+///
+/// The counts of the last frame drawn are also available in the [`UiRenderStats`] resource. The
+/// nodes drawn with a [`UiMaterial`](crate::UiMaterial) aren't counted.
+#[derive(Default)]
+pub struct UiRenderDiagnosticsPlugin;
+
+impl Plugin for UiRenderDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        let channel = UiRenderStatsChannel::default();
+        app.init_resource::<UiRenderStats>()
+            .insert_resource(channel.clone())
+            .add_startup_system(Self::setup_system)
+            .add_system(Self::diagnostic_system.in_base_set(CoreSet::PreUpdate));
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app.insert_resource(channel);
+        }
+    }
+}
+
+impl UiRenderDiagnosticsPlugin {
+    pub const BATCHES: DiagnosticId =
+        DiagnosticId::from_u128(112739913395329912738718520606552859640);
+    pub const VERTICES: DiagnosticId =
+        DiagnosticId::from_u128(33412535564072482143431188748069341573);
+    pub const NODES: DiagnosticId =
+        DiagnosticId::from_u128(186082333247257752709808530197272539635);
+
+    pub fn setup_system(mut diagnostics: ResMut<Diagnostics>) {
+        diagnostics.add(Diagnostic::new(Self::BATCHES, "ui_batches", 20));
+        diagnostics.add(Diagnostic::new(Self::VERTICES, "ui_vertices", 20));
+        diagnostics.add(Diagnostic::new(Self::NODES, "ui_nodes", 20));
+    }
+
+    pub fn diagnostic_system(
+        channel: Res<UiRenderStatsChannel>,
+        mut diagnostics: ResMut<Diagnostics>,
+        mut ui_render_stats: ResMut<UiRenderStats>,
+    ) {
+        let Some(stats) = channel.0.lock().unwrap().take() else {
+            return;
+        };
+        diagnostics.add_measurement(Self::BATCHES, || stats.batches as f64);
+        diagnostics.add_measurement(Self::VERTICES, || stats.vertices as f64);
+        diagnostics.add_measurement(Self::NODES, || stats.nodes as f64);
+        *ui_render_stats = stats;
+    }
+}
+
+/// The batches, vertices and quads the UI was drawn with during the last frame drawn
+///
+/// This resource is added to the main world by the [`UiRenderDiagnosticsPlugin`].
+#[derive(Resource, Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UiRenderStats {
+    /// The draw calls of the UI, one for each batch of nodes drawn with the same image
+    pub batches: u32,
+    /// The vertices of the nodes, six for each quad
+    pub vertices: u32,
+    /// The quads of the nodes that weren't completely clipped, with one quad for the background
+    /// of a node, each of its borders, its shadow, and each glyph of a text
+    pub nodes: u32,
+}
+
+/// Holds the [`UiRenderStats`] of the last frame prepared in the render world, until they're read
+/// in the main world
+///
+/// This resource is added to both worlds by the [`UiRenderDiagnosticsPlugin`].
+#[derive(Resource, Default, Clone)]
+pub struct UiRenderStatsChannel(Arc<Mutex<Option<UiRenderStats>>>);
+
+impl UiRenderStatsChannel {
+    /// Replaces the stats that weren't read yet
+    pub fn send(&self, stats: UiRenderStats) {
+        *self.0.lock().unwrap() = Some(stats);
+    }
+}
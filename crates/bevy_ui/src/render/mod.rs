@@ -1,17 +1,29 @@
+mod diagnostic;
 mod pipeline;
 mod render_pass;
+mod ui_material_pipeline;
 
 use bevy_core_pipeline::{core_2d::Camera2d, core_3d::Camera3d};
 use bevy_render::ExtractSchedule;
 #[cfg(feature = "bevy_text")]
 use bevy_window::{PrimaryWindow, Window};
+pub use diagnostic::*;
 pub use pipeline::*;
 pub use render_pass::*;
+pub use ui_material_pipeline::*;
 
-use crate::{prelude::UiCameraConfig, BackgroundColor, CalculatedClip, Node, UiImage, UiStack};
+#[cfg(feature = "bevy_text")]
+use crate::widget::{TextInput, TextInputFocus, TextInputStyle, CARET_BLINK_TIME, CARET_WIDTH};
+use crate::{
+    prelude::UiCameraConfig, ActiveModal, BackgroundColor, BackgroundGradient, BorderColor,
+    BorderRadius, BoxShadow, CalculatedClip, CalculatedOpacity, Display, FlexDirection, Modal,
+    Node, Overflow, ScrollPosition, Scrollbar, Style, UiDebugOverlay, UiImage, UiRect, UiScale,
+    UiStack, UiTextureAtlasImage, Val, MAX_GRADIENT_STOPS,
+};
 use bevy_app::prelude::*;
 use bevy_asset::{load_internal_asset, AssetEvent, Assets, Handle, HandleUntyped};
 use bevy_ecs::prelude::*;
+use bevy_hierarchy::{Children, HierarchyQueryExt, Parent};
 use bevy_math::{Mat4, Rect, UVec4, Vec2, Vec3, Vec4Swizzles};
 use bevy_reflect::TypeUuid;
 use bevy_render::texture::DEFAULT_IMAGE_HANDLE;
@@ -24,14 +36,14 @@ use bevy_render::{
     render_resource::*,
     renderer::{RenderDevice, RenderQueue},
     texture::Image,
-    view::{ComputedVisibility, ExtractedView, ViewUniforms},
+    view::{ExtractedView, ViewUniforms, ViewVisibility},
     Extract, RenderApp, RenderSet,
 };
-use bevy_sprite::SpriteAssetEvents;
-#[cfg(feature = "bevy_text")]
-use bevy_sprite::TextureAtlas;
+use bevy_sprite::{ImageScaleMode, SpriteAssetEvents, TextureAtlas, TextureSliceVertex};
 #[cfg(feature = "bevy_text")]
 use bevy_text::{Text, TextLayoutInfo};
+#[cfg(feature = "bevy_text")]
+use bevy_time::Time;
 use bevy_transform::components::GlobalTransform;
 use bevy_utils::FloatOrd;
 use bevy_utils::HashMap;
@@ -55,6 +67,12 @@ pub mod draw_ui_graph {
 pub const UI_SHADER_HANDLE: HandleUntyped =
     HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 13012847047162779583);
 
+pub const UI_MATERIAL_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 10074188772096983955);
+
+pub const UI_VERTEX_OUTPUT_SHADER_HANDLE: HandleUntyped =
+    HandleUntyped::weak_from_u64(Shader::TYPE_UUID, 10123618247720234751);
+
 #[derive(Debug, Hash, PartialEq, Eq, Clone, SystemSet)]
 pub enum RenderUiSystem {
     ExtractNode,
@@ -62,6 +80,18 @@ pub enum RenderUiSystem {
 
 pub fn build_ui_render(app: &mut App) {
     load_internal_asset!(app, UI_SHADER_HANDLE, "ui.wgsl", Shader::from_wgsl);
+    load_internal_asset!(
+        app,
+        UI_MATERIAL_SHADER_HANDLE,
+        "ui_material.wgsl",
+        Shader::from_wgsl
+    );
+    load_internal_asset!(
+        app,
+        UI_VERTEX_OUTPUT_SHADER_HANDLE,
+        "ui_vertex_output.wgsl",
+        Shader::from_wgsl
+    );
 
     let render_app = match app.get_sub_app_mut(RenderApp) {
         Ok(render_app) => render_app,
@@ -82,7 +112,17 @@ pub fn build_ui_render(app: &mut App) {
                 extract_default_ui_camera_view::<Camera3d>,
                 extract_uinodes.in_set(RenderUiSystem::ExtractNode),
                 #[cfg(feature = "bevy_text")]
-                extract_text_uinodes.after(RenderUiSystem::ExtractNode),
+                extract_text_input_uinodes
+                    .after(RenderUiSystem::ExtractNode)
+                    .before(extract_text_uinodes),
+                #[cfg(feature = "bevy_text")]
+                extract_text_uinodes
+                    .after(RenderUiSystem::ExtractNode)
+                    .before(extract_scrollbar_uinodes),
+                extract_scrollbar_uinodes.after(RenderUiSystem::ExtractNode),
+                extract_uinode_opacities.after(extract_scrollbar_uinodes),
+                // The overlay isn't faded with the nodes
+                extract_debug_overlay_uinodes.after(extract_uinode_opacities),
             )
                 .in_schedule(ExtractSchedule),
         )
@@ -167,74 +207,640 @@ fn get_ui_graph(render_app: &mut App) -> RenderGraph {
 
 pub struct ExtractedUiNode {
     pub stack_index: usize,
+    /// The camera the node is drawn on
+    pub camera_entity: Entity,
     pub transform: Mat4,
     pub color: Color,
     pub rect: Rect,
     pub image: Handle<Image>,
     pub atlas_size: Option<Vec2>,
+    /// The region of the atlas drawn over the whole [`rect`](Self::rect), in pixels, or `None`
+    /// when it's the `rect` itself
+    pub atlas_rect: Option<Rect>,
     pub clip: Option<Rect>,
+    /// The radii of the rounded corners of the [`clip`](Self::clip): top left, top right,
+    /// bottom right and bottom left
+    pub clip_radius: [f32; 4],
     pub flip_x: bool,
     pub flip_y: bool,
+    /// How the image fills the node
+    pub scale_mode: ImageScaleMode,
+    /// The size of the image in pixels, used when it's sliced or tiled
+    pub image_size: Vec2,
+    /// The radii of the rounded corners of the node: top left, top right, bottom right and
+    /// bottom left
+    pub border_radius: [f32; 4],
+    /// The widths of the borders of the node: left, top, right and bottom
+    pub border: [f32; 4],
+    /// The part of the node that is drawn
+    pub part: UiNodePart,
+    /// The gradient filling the node, multiplied by its color
+    pub gradient: Option<ExtractedGradient>,
+}
+
+/// A [`BackgroundGradient`] of an [`ExtractedUiNode`], in the coordinates of the node from its
+/// center
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ExtractedGradient {
+    /// The start and end of a linear gradient, or the center and radius of a radial gradient
+    pub line: [f32; 4],
+    pub radial: bool,
+    /// The linear colors of the stops
+    pub colors: [[f32; 4]; MAX_GRADIENT_STOPS],
+    /// The positions of the stops, in increasing order
+    pub positions: [f32; MAX_GRADIENT_STOPS],
+    pub stop_count: usize,
+}
+
+impl ExtractedGradient {
+    /// The `gradient` drawn over a node of `size`
+    pub fn new(gradient: &BackgroundGradient, size: Vec2) -> Self {
+        let (line, radial) = match *gradient {
+            BackgroundGradient::Linear { angle, .. } => {
+                // With y growing downwards, the line of a gradient going to the top is upwards
+                let direction = Vec2::new(angle.sin(), -angle.cos());
+                let length = (size.x * direction.x).abs() + (size.y * direction.y).abs();
+                let end = direction * length / 2.0;
+                ([-end.x, -end.y, end.x, end.y], false)
+            }
+            BackgroundGradient::Radial { center, .. } => {
+                let center = (center - 0.5) * size;
+                let radius = (size / 2.0 + center.abs()).length();
+                ([center.x, center.y, radius, 0.0], true)
+            }
+        };
+        let mut extracted = Self {
+            line,
+            radial,
+            colors: [[0.0; 4]; MAX_GRADIENT_STOPS],
+            positions: [0.0; MAX_GRADIENT_STOPS],
+            stop_count: 0,
+        };
+        let mut last_position = f32::MIN;
+        for stop in gradient.stops().iter().take(MAX_GRADIENT_STOPS) {
+            // Like in CSS, a stop before the previous one is moved to it
+            last_position = stop.position.max(last_position);
+            extracted.colors[extracted.stop_count] = stop.color.as_linear_rgba_f32();
+            extracted.positions[extracted.stop_count] = last_position;
+            extracted.stop_count += 1;
+        }
+        extracted
+    }
+}
+
+/// The part of a node drawn by an [`ExtractedUiNode`], inside its rounded corners
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum UiNodePart {
+    /// The whole node
+    #[default]
+    Node,
+    /// Its left border
+    LeftBorder,
+    /// Its top border
+    TopBorder,
+    /// Its right border
+    RightBorder,
+    /// Its bottom border
+    BottomBorder,
+    /// Its [`BoxShadow`], blurred inside its quad by the first of its `border` widths
+    Shadow,
 }
 
 #[derive(Resource, Default)]
 pub struct ExtractedUiNodes {
     pub uinodes: Vec<ExtractedUiNode>,
+    /// The stack indices of the nodes drawn with a [`UiMaterial`](crate::UiMaterial), that the
+    /// batches of the other nodes don't draw over
+    pub material_stack_indices: Vec<usize>,
+}
+
+/// The size of `val` in logical pixels, with percentages of `basis`
+pub(crate) fn resolve_val(val: Val, basis: f32, ui_scale: f64) -> f32 {
+    match val {
+        Val::Px(value) => (ui_scale * value as f64) as f32,
+        Val::Percent(percent) => basis * percent / 100.0,
+        Val::Auto | Val::Undefined => 0.0,
+    }
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn extract_uinodes(
     mut extracted_uinodes: ResMut<ExtractedUiNodes>,
     images: Extract<Res<Assets<Image>>>,
+    texture_atlases: Extract<Res<Assets<TextureAtlas>>>,
     ui_stack: Extract<Res<UiStack>>,
+    ui_scale: Extract<Res<UiScale>>,
     uinode_query: Extract<
         Query<(
             &Node,
             &GlobalTransform,
+            &Style,
             &BackgroundColor,
             Option<&UiImage>,
-            &ComputedVisibility,
+            Option<(&Handle<TextureAtlas>, &UiTextureAtlasImage)>,
+            (
+                Option<&BorderColor>,
+                Option<&BorderRadius>,
+                Option<&BoxShadow>,
+                Option<&BackgroundGradient>,
+                Option<&Modal>,
+            ),
+            &ViewVisibility,
             Option<&CalculatedClip>,
+            Option<&Parent>,
         )>,
     >,
+    parent_node_query: Extract<Query<&Node>>,
+    active_modal: Extract<Res<ActiveModal>>,
+    camera_query: Extract<Query<&Camera>>,
 ) {
     extracted_uinodes.uinodes.clear();
+    extracted_uinodes.material_stack_indices.clear();
     for (stack_index, entity) in ui_stack.uinodes.iter().enumerate() {
-        if let Ok((uinode, transform, color, maybe_image, visibility, clip)) =
-            uinode_query.get(*entity)
+        let Ok((
+            uinode,
+            transform,
+            style,
+            color,
+            maybe_image,
+            maybe_atlas_image,
+            (border_color, border_radius, box_shadow, gradient, modal),
+            visibility,
+            clip,
+            parent,
+        )) = uinode_query.get(*entity)
+        else {
+            continue;
+        };
+        // Skip invisible nodes, and the nodes without a camera to be drawn on
+        let (true, Some(camera_entity)) = (visibility.get(), uinode.target_camera()) else {
+            continue;
+        };
+
+        let size = uinode.size();
+        // The radii are limited to half of the smallest side, like with percentages of it
+        let min_side = size.min_element();
+        let border_radius = border_radius.map_or([0.0; 4], |radius| {
+            [
+                radius.top_left,
+                radius.top_right,
+                radius.bottom_right,
+                radius.bottom_left,
+            ]
+            .map(|radius| resolve_val(radius, min_side, ui_scale.scale).clamp(0.0, min_side / 2.0))
+        });
+        // Percentages of borders are of the width of the parent, like in the layout
+        let parent_width = parent
+            .and_then(|parent| parent_node_query.get(**parent).ok())
+            .map_or(0.0, |parent| parent.size().x);
+        let border = [
+            style.border.left,
+            style.border.top,
+            style.border.right,
+            style.border.bottom,
+        ]
+        .map(|width| resolve_val(width, parent_width, ui_scale.scale));
+
+        // The backdrop of the modal capturing the input dims the nodes under it
+        if let Some(modal) =
+            modal.filter(|modal| modal.backdrop.a() != 0.0 && active_modal.get() == Some(*entity))
         {
-            // Skip invisible and completely transparent nodes
-            if !visibility.is_visible() || color.0.a() == 0.0 {
-                continue;
+            if let Some(viewport_size) = camera_query
+                .get(camera_entity)
+                .ok()
+                .and_then(Camera::logical_viewport_size)
+            {
+                extracted_uinodes.uinodes.push(ExtractedUiNode {
+                    stack_index,
+                    camera_entity,
+                    transform: Mat4::from_translation((viewport_size / 2.0).extend(0.0)),
+                    color: modal.backdrop,
+                    rect: Rect {
+                        min: Vec2::ZERO,
+                        max: viewport_size,
+                    },
+                    image: DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+                    atlas_size: None,
+                    atlas_rect: None,
+                    gradient: None,
+                    clip: None,
+                    clip_radius: [0.0; 4],
+                    flip_x: false,
+                    flip_y: false,
+                    scale_mode: ImageScaleMode::Stretched,
+                    image_size: Vec2::ONE,
+                    border_radius: [0.0; 4],
+                    border: [0.0; 4],
+                    part: UiNodePart::Node,
+                });
             }
+        }
 
-            let (image, flip_x, flip_y) = if let Some(image) = maybe_image {
-                // Skip loading images
-                if !images.contains(&image.texture) {
-                    continue;
-                }
-                (image.texture.clone_weak(), image.flip_x, image.flip_y)
-            } else {
-                (DEFAULT_IMAGE_HANDLE.typed().clone_weak(), false, false)
-            };
+        if let Some(shadow) = box_shadow.filter(|shadow| shadow.color.a() != 0.0) {
+            let offset = Vec2::new(
+                resolve_val(shadow.x_offset, size.x, ui_scale.scale),
+                resolve_val(shadow.y_offset, size.y, ui_scale.scale),
+            );
+            let spread = resolve_val(shadow.spread_radius, min_side, ui_scale.scale);
+            let blur = resolve_val(shadow.blur_radius, min_side, ui_scale.scale).max(0.0);
+            let shadow_size = (size + 2.0 * spread).max(Vec2::ZERO);
+            // The corners grow and shrink with the shadow
+            let shadow_radius = border_radius
+                .map(|radius| (radius + spread).clamp(0.0, shadow_size.min_element() / 2.0));
+            if shadow_size.min_element() > 0.0 || blur > 0.0 {
+                // The quad is grown by the blur radius, for the shadow to fade out in it
+                extracted_uinodes.uinodes.push(ExtractedUiNode {
+                    stack_index,
+                    camera_entity,
+                    transform: transform.compute_matrix()
+                        * Mat4::from_translation(offset.extend(0.0)),
+                    color: shadow.color,
+                    rect: Rect {
+                        min: Vec2::ZERO,
+                        max: shadow_size + 2.0 * blur,
+                    },
+                    image: DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+                    atlas_size: None,
+                    atlas_rect: None,
+                    gradient: None,
+                    clip: clip.map(|clip| clip.clip),
+                    clip_radius: clip.map_or([0.0; 4], |clip| clip.radius),
+                    flip_x: false,
+                    flip_y: false,
+                    scale_mode: ImageScaleMode::Stretched,
+                    image_size: Vec2::ONE,
+                    border_radius: shadow_radius,
+                    border: [blur; 4],
+                    part: UiNodePart::Shadow,
+                });
+            }
+        }
+
+        // Skip completely transparent backgrounds, but not their borders
+        if color.0.a() != 0.0 || gradient.is_some() {
+            let mut atlas_rect = None;
+            let (image, flip_x, flip_y, scale_mode, image_size) =
+                if let Some((atlas, atlas_image)) = maybe_atlas_image {
+                    // Skip loading atlases, and the indices out of them
+                    let Some((atlas, &rect)) = texture_atlases
+                        .get(atlas)
+                        .and_then(|atlas| Some((atlas, atlas.textures.get(atlas_image.index)?)))
+                    else {
+                        continue;
+                    };
+                    if images.get(&atlas.texture).is_none() {
+                        continue;
+                    }
+                    atlas_rect = Some(rect);
+                    (
+                        atlas.texture.clone_weak(),
+                        atlas_image.flip_x,
+                        atlas_image.flip_y,
+                        atlas_image.scale_mode,
+                        atlas.size,
+                    )
+                } else if let Some(image) = maybe_image {
+                    // Skip loading images
+                    let Some(image_asset) = images.get(&image.texture) else {
+                        continue;
+                    };
+                    (
+                        image.texture.clone_weak(),
+                        image.flip_x,
+                        image.flip_y,
+                        image.scale_mode,
+                        image_asset.size(),
+                    )
+                } else {
+                    (
+                        DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+                        false,
+                        false,
+                        ImageScaleMode::Stretched,
+                        Vec2::ONE,
+                    )
+                };
 
             extracted_uinodes.uinodes.push(ExtractedUiNode {
                 stack_index,
+                camera_entity,
                 transform: transform.compute_matrix(),
-                color: color.0,
+                // The gradient is drawn instead of the background color
+                color: if gradient.is_some() {
+                    Color::WHITE
+                } else {
+                    color.0
+                },
                 rect: Rect {
                     min: Vec2::ZERO,
                     max: uinode.calculated_size,
                 },
                 image,
-                atlas_size: None,
+                atlas_size: atlas_rect.map(|_| image_size),
+                atlas_rect,
+                gradient: gradient.map(|gradient| ExtractedGradient::new(gradient, size)),
                 clip: clip.map(|clip| clip.clip),
+                clip_radius: clip.map_or([0.0; 4], |clip| clip.radius),
                 flip_x,
                 flip_y,
+                scale_mode,
+                image_size,
+                border_radius,
+                border,
+                part: UiNodePart::Node,
+            });
+        }
+
+        let Some(border_color) = border_color else {
+            continue;
+        };
+        let edges = [
+            (border[0], border_color.left, UiNodePart::LeftBorder),
+            (border[1], border_color.top, UiNodePart::TopBorder),
+            (border[2], border_color.right, UiNodePart::RightBorder),
+            (border[3], border_color.bottom, UiNodePart::BottomBorder),
+        ];
+        for (width, color, part) in edges {
+            if width <= 0.0 || color.a() == 0.0 {
+                continue;
+            }
+            // Each edge is drawn over the whole node, and only kept on its own side by the shader
+            extracted_uinodes.uinodes.push(ExtractedUiNode {
+                stack_index,
+                camera_entity,
+                transform: transform.compute_matrix(),
+                color,
+                rect: Rect {
+                    min: Vec2::ZERO,
+                    max: uinode.calculated_size,
+                },
+                image: DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+                atlas_size: None,
+                atlas_rect: None,
+                gradient: None,
+                clip: clip.map(|clip| clip.clip),
+                clip_radius: clip.map_or([0.0; 4], |clip| clip.radius),
+                flip_x: false,
+                flip_y: false,
+                scale_mode: ImageScaleMode::Stretched,
+                image_size: Vec2::ONE,
+                border_radius,
+                border,
+                part,
             });
         }
     }
 }
 
+pub fn extract_scrollbar_uinodes(
+    mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    ui_stack: Extract<Res<UiStack>>,
+    uinode_query: Extract<
+        Query<(
+            &Node,
+            &GlobalTransform,
+            &Style,
+            &ScrollPosition,
+            &Scrollbar,
+            &ViewVisibility,
+            Option<&CalculatedClip>,
+        )>,
+    >,
+    parent_query: Extract<Query<&Parent>>,
+) {
+    for (stack_index, entity) in ui_stack.uinodes.iter().enumerate() {
+        let Ok((uinode, transform, style, scroll_position, scrollbar, visibility, clip)) =
+            uinode_query.get(*entity)
+        else {
+            continue;
+        };
+        let max_offset = scroll_position.max_offset();
+        if !visibility.get() || style.overflow != Overflow::Scroll || max_offset == Vec2::ZERO {
+            continue;
+        }
+        let Some(camera_entity) = uinode.target_camera() else {
+            continue;
+        };
+
+        // The scrollbars are drawn over the content, with the last of the nodes in the scrolled node
+        let last_stack_index = stack_index
+            + ui_stack.uinodes[stack_index + 1..]
+                .iter()
+                .take_while(|node| parent_query.iter_ancestors(**node).any(|a| a == *entity))
+                .count();
+        let size = uinode.size();
+        let width = scrollbar.width;
+        let mut push_quad = |center: Vec2, quad_size: Vec2, color: Color| {
+            extracted_uinodes.uinodes.push(ExtractedUiNode {
+                stack_index: last_stack_index,
+                camera_entity,
+                transform: transform.compute_matrix() * Mat4::from_translation(center.extend(0.)),
+                color,
+                rect: Rect {
+                    min: Vec2::ZERO,
+                    max: quad_size,
+                },
+                image: DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+                atlas_size: None,
+                atlas_rect: None,
+                gradient: None,
+                clip: clip.map(|clip| clip.clip),
+                clip_radius: clip.map_or([0.0; 4], |clip| clip.radius),
+                flip_x: false,
+                flip_y: false,
+                scale_mode: ImageScaleMode::Stretched,
+                image_size: Vec2::ONE,
+                border_radius: [0.0; 4],
+                border: [0.0; 4],
+                part: UiNodePart::Node,
+            });
+        };
+
+        for axis in 0..2 {
+            if max_offset[axis] == 0.0 {
+                continue;
+            }
+            let cross_axis = 1 - axis;
+            // The tracks leave the corner between them free when both are shown
+            let track_length = size[axis]
+                - if max_offset[cross_axis] > 0.0 {
+                    width
+                } else {
+                    0.0
+                };
+            let thumb_length = (track_length * size[axis] / (size[axis] + max_offset[axis]))
+                .clamp(width.min(track_length), track_length);
+            let thumb_start =
+                (track_length - thumb_length) * scroll_position.offset[axis] / max_offset[axis];
+
+            let mut track_center = Vec2::ZERO;
+            track_center[cross_axis] = (size[cross_axis] - width) / 2.0;
+            track_center[axis] = (track_length - size[axis]) / 2.0;
+            let mut track_size = Vec2::splat(width);
+            track_size[axis] = track_length;
+            push_quad(track_center, track_size, scrollbar.track_color);
+
+            let mut thumb_center = track_center;
+            thumb_center[axis] = (thumb_length - size[axis]) / 2.0 + thumb_start;
+            let mut thumb_size = Vec2::splat(width);
+            thumb_size[axis] = thumb_length;
+            push_quad(thumb_center, thumb_size, scrollbar.thumb_color);
+        }
+    }
+}
+
+/// Draws the [`UiDebugOverlay`] over all the nodes, once they've all been extracted
+pub fn extract_debug_overlay_uinodes(
+    mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    debug_overlay: Extract<Res<UiDebugOverlay>>,
+    ui_stack: Extract<Res<UiStack>>,
+    ui_scale: Extract<Res<UiScale>>,
+    uinode_query: Extract<
+        Query<(
+            &Node,
+            &GlobalTransform,
+            &Style,
+            &ViewVisibility,
+            Option<&Parent>,
+            Option<&Children>,
+        )>,
+    >,
+    parent_node_query: Extract<Query<&Node>>,
+) {
+    if !debug_overlay.enabled {
+        return;
+    }
+    let stack_index = ui_stack.uinodes.len();
+    let line_width = debug_overlay.line_width;
+    for entity in &ui_stack.uinodes {
+        let Ok((uinode, transform, style, visibility, parent, children)) =
+            uinode_query.get(*entity)
+        else {
+            continue;
+        };
+        let (true, Some(camera_entity)) = (visibility.get(), uinode.target_camera()) else {
+            continue;
+        };
+        let transform = transform.compute_matrix();
+        let mut push_quad = |min: Vec2, max: Vec2, color: Color| {
+            if color.a() == 0.0 || min.cmpge(max).any() {
+                return;
+            }
+            extracted_uinodes.uinodes.push(ExtractedUiNode {
+                stack_index,
+                camera_entity,
+                transform: transform * Mat4::from_translation(((min + max) / 2.0).extend(0.)),
+                color,
+                rect: Rect {
+                    min: Vec2::ZERO,
+                    max: max - min,
+                },
+                image: DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+                atlas_size: None,
+                atlas_rect: None,
+                gradient: None,
+                clip: None,
+                clip_radius: [0.0; 4],
+                flip_x: false,
+                flip_y: false,
+                scale_mode: ImageScaleMode::Stretched,
+                image_size: Vec2::ONE,
+                border_radius: [0.0; 4],
+                border: [0.0; 4],
+                part: UiNodePart::Node,
+            });
+        };
+        // Fills the frame between the edges of a rect and the insets of its left, top, right and
+        // bottom sides, from the center of the node
+        let mut push_frame = |min: Vec2, max: Vec2, insets: [f32; 4], color: Color| {
+            let [left, top, right, bottom] = insets;
+            push_quad(min, Vec2::new(max.x, min.y + top), color);
+            push_quad(Vec2::new(min.x, max.y - bottom), max, color);
+            push_quad(
+                Vec2::new(min.x, min.y + top),
+                Vec2::new(min.x + left, max.y - bottom),
+                color,
+            );
+            push_quad(
+                Vec2::new(max.x - right, min.y + top),
+                Vec2::new(max.x, max.y - bottom),
+                color,
+            );
+        };
+
+        let half_size = uinode.size() / 2.0;
+        // Percentages of margins, borders and paddings are of the width of the parent, like in
+        // the layout
+        let parent_width = parent
+            .and_then(|parent| parent_node_query.get(**parent).ok())
+            .map_or(0.0, |parent| parent.size().x);
+        let resolve = |rect: UiRect| {
+            [rect.left, rect.top, rect.right, rect.bottom]
+                .map(|val| resolve_val(val, parent_width, ui_scale.scale))
+        };
+        let [margin, border, padding] = [style.margin, style.border, style.padding].map(resolve);
+
+        push_frame(
+            -half_size - Vec2::new(margin[0], margin[1]),
+            half_size + Vec2::new(margin[2], margin[3]),
+            margin,
+            debug_overlay.margin_color,
+        );
+        let padding_min = -half_size + Vec2::new(border[0], border[1]);
+        let padding_max = half_size - Vec2::new(border[2], border[3]);
+        push_frame(
+            padding_min,
+            padding_max,
+            padding,
+            debug_overlay.padding_color,
+        );
+        push_frame(
+            -half_size,
+            half_size,
+            [line_width; 4],
+            debug_overlay.outline_color,
+        );
+
+        let has_children = matches!(children, Some(children) if !children.is_empty());
+        if style.display != Display::Flex || !has_children {
+            continue;
+        }
+        // The axes go through the middle of the content, with a square at the end of the main
+        // axis the children flow to
+        let content_min = padding_min + Vec2::new(padding[0], padding[1]);
+        let content_max = padding_max - Vec2::new(padding[2], padding[3]);
+        let center = (content_min + content_max) / 2.0;
+        let (main_axis, reverse) = match style.flex_direction {
+            FlexDirection::Row => (0, false),
+            FlexDirection::RowReverse => (0, true),
+            FlexDirection::Column => (1, false),
+            FlexDirection::ColumnReverse => (1, true),
+        };
+        let cross_axis = 1 - main_axis;
+        for (axis, color) in [
+            (main_axis, debug_overlay.main_axis_color),
+            (cross_axis, debug_overlay.cross_axis_color),
+        ] {
+            let (mut min, mut max) = (content_min, content_max);
+            min[1 - axis] = center[1 - axis] - line_width / 2.0;
+            max[1 - axis] = center[1 - axis] + line_width / 2.0;
+            push_quad(min, max, color);
+        }
+        let head_size = Vec2::splat(4.0 * line_width);
+        let mut head = center;
+        head[main_axis] = if reverse {
+            content_min[main_axis] + head_size[main_axis] / 2.0
+        } else {
+            content_max[main_axis] - head_size[main_axis] / 2.0
+        };
+        push_quad(
+            head - head_size / 2.0,
+            head + head_size / 2.0,
+            debug_overlay.main_axis_color,
+        );
+    }
+}
+
 /// The UI camera is "moved back" by this many units (plus the [`UI_CAMERA_TRANSFORM_OFFSET`]) and also has a view
 /// distance of this many units. This ensures that with a left-handed projection,
 /// as ui elements are "stacked on top of each other", they are within the camera's view
@@ -249,6 +855,34 @@ const UI_CAMERA_TRANSFORM_OFFSET: f32 = -0.1;
 #[derive(Component)]
 pub struct DefaultCameraView(pub Entity);
 
+/// Multiplies the alpha of the colors of the extracted nodes by their [`CalculatedOpacity`],
+/// once all the parts of the nodes have been extracted
+pub fn extract_uinode_opacities(
+    mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    ui_stack: Extract<Res<UiStack>>,
+    opacity_query: Extract<Query<&CalculatedOpacity>>,
+) {
+    if opacity_query.is_empty() {
+        return;
+    }
+    let opacities: Vec<f32> = ui_stack
+        .uinodes
+        .iter()
+        .map(|entity| {
+            opacity_query
+                .get(*entity)
+                .map_or(1.0, |opacity| opacity.opacity)
+        })
+        .collect();
+    for extracted_uinode in &mut extracted_uinodes.uinodes {
+        let opacity = opacities[extracted_uinode.stack_index];
+        if opacity != 1.0 {
+            let alpha = extracted_uinode.color.a();
+            extracted_uinode.color.set_a(alpha * opacity);
+        }
+    }
+}
+
 pub fn extract_default_ui_camera_view<T: Component>(
     mut commands: Commands,
     query: Extract<Query<(Entity, &Camera, Option<&UiCameraConfig>), With<T>>>,
@@ -305,7 +939,7 @@ pub fn extract_text_uinodes(
             &GlobalTransform,
             &Text,
             &TextLayoutInfo,
-            &ComputedVisibility,
+            &ViewVisibility,
             Option<&CalculatedClip>,
         )>,
     >,
@@ -320,9 +954,9 @@ pub fn extract_text_uinodes(
         if let Ok((uinode, global_transform, text, text_layout_info, visibility, clip)) =
             uinode_query.get(*entity)
         {
-            if !visibility.is_visible() {
+            let (true, Some(camera_entity)) = (visibility.get(), uinode.target_camera()) else {
                 continue;
-            }
+            };
             // Skip if size is set to zero (e.g. when a parent is set to `Display::None`)
             if uinode.size() == Vec2::ZERO {
                 continue;
@@ -330,16 +964,45 @@ pub fn extract_text_uinodes(
             let text_glyphs = &text_layout_info.glyphs;
             let alignment_offset = (uinode.size() / -2.0).extend(0.0);
 
-            let mut color = Color::WHITE;
-            let mut current_section = usize::MAX;
+            // NOTE: Should match `bevy_text::text2d::extract_text2d_sprite`
+            let text_transform = global_transform.compute_matrix()
+                * Mat4::from_scale(Vec3::splat(scale_factor.recip()));
+
+            if let Some(background) = text.background {
+                let padding = background.padding * scale_factor;
+                let center = text_layout_info.size / 2.0;
+                extracted_uinodes.uinodes.push(ExtractedUiNode {
+                    stack_index,
+                    camera_entity,
+                    transform: text_transform
+                        * Mat4::from_translation(
+                            alignment_offset * scale_factor + center.extend(0.),
+                        ),
+                    color: background.color,
+                    rect: Rect {
+                        min: Vec2::ZERO,
+                        max: text_layout_info.size + 2.0 * padding,
+                    },
+                    image: DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+                    atlas_size: None,
+                    atlas_rect: None,
+                    gradient: None,
+                    clip: clip.map(|clip| clip.clip),
+                    clip_radius: clip.map_or([0.0; 4], |clip| clip.radius),
+                    flip_x: false,
+                    flip_y: false,
+                    scale_mode: ImageScaleMode::Stretched,
+                    image_size: Vec2::ONE,
+                    border_radius: [0.0; 4],
+                    border: [0.0; 4],
+                    part: UiNodePart::Node,
+                });
+            }
+
             for text_glyph in text_glyphs {
-                if text_glyph.section_index != current_section {
-                    color = text.sections[text_glyph.section_index]
-                        .style
-                        .color
-                        .as_rgba_linear();
-                    current_section = text_glyph.section_index;
-                }
+                let color = text_glyph
+                    .color(&text.sections[text_glyph.section_index].style)
+                    .as_rgba_linear();
                 let atlas = texture_atlases
                     .get(&text_glyph.atlas_info.texture_atlas)
                     .unwrap();
@@ -348,40 +1011,245 @@ pub fn extract_text_uinodes(
                 let rect = atlas.textures[index];
                 let atlas_size = Some(atlas.size);
 
-                // NOTE: Should match `bevy_text::text2d::extract_text2d_sprite`
-                let extracted_transform = global_transform.compute_matrix()
-                    * Mat4::from_scale(Vec3::splat(scale_factor.recip()))
+                let extracted_transform = text_transform
                     * Mat4::from_translation(
                         alignment_offset * scale_factor + text_glyph.position.extend(0.),
                     );
 
                 extracted_uinodes.uinodes.push(ExtractedUiNode {
                     stack_index,
+                    camera_entity,
                     transform: extracted_transform,
                     color,
                     rect,
                     image: texture,
                     atlas_size,
+                    atlas_rect: None,
+                    gradient: None,
                     clip: clip.map(|clip| clip.clip),
+                    clip_radius: clip.map_or([0.0; 4], |clip| clip.radius),
                     flip_x: false,
                     flip_y: false,
+                    scale_mode: ImageScaleMode::Stretched,
+                    image_size: atlas.size,
+                    border_radius: [0.0; 4],
+                    border: [0.0; 4],
+                    part: UiNodePart::Node,
                 });
             }
         }
     }
 }
 
+/// Extracts the selection, the line under the preedit and the blinking caret of the focused
+/// [`TextInput`], drawn behind its text.
+#[cfg(feature = "bevy_text")]
+pub fn extract_text_input_uinodes(
+    mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    ui_stack: Extract<Res<UiStack>>,
+    focus: Extract<Res<TextInputFocus>>,
+    time: Extract<Res<Time>>,
+    uinode_query: Extract<
+        Query<(
+            &Node,
+            &GlobalTransform,
+            &TextInput,
+            &TextInputStyle,
+            &ViewVisibility,
+            Option<&CalculatedClip>,
+        )>,
+    >,
+) {
+    let Some(entity) = focus.0 else {
+        return;
+    };
+    let Ok((uinode, transform, text_input, style, visibility, clip)) = uinode_query.get(entity)
+    else {
+        return;
+    };
+    let (true, Some(camera_entity), Some(layout), Some(stack_index)) = (
+        visibility.get(),
+        uinode.target_camera(),
+        &text_input.layout,
+        ui_stack.uinodes.iter().position(|node| *node == entity),
+    ) else {
+        return;
+    };
+
+    let top_left = uinode.size() / -2.0;
+    let mut push_quad = |left: f32, top: f32, size: Vec2, color: Color| {
+        let center = top_left + Vec2::new(left, top) + size / 2.0;
+        extracted_uinodes.uinodes.push(ExtractedUiNode {
+            stack_index,
+            camera_entity,
+            transform: transform.compute_matrix() * Mat4::from_translation(center.extend(0.)),
+            color,
+            rect: Rect {
+                min: Vec2::ZERO,
+                max: size,
+            },
+            image: DEFAULT_IMAGE_HANDLE.typed().clone_weak(),
+            atlas_size: None,
+            atlas_rect: None,
+            gradient: None,
+            clip: clip.map(|clip| clip.clip),
+            clip_radius: clip.map_or([0.0; 4], |clip| clip.radius),
+            flip_x: false,
+            flip_y: false,
+            scale_mode: ImageScaleMode::Stretched,
+            image_size: Vec2::ONE,
+            border_radius: [0.0; 4],
+            border: [0.0; 4],
+            part: UiNodePart::Node,
+        });
+    };
+
+    let selection = &layout.selection;
+    if selection.end > selection.start {
+        let size = Vec2::new(selection.end - selection.start, layout.line_height);
+        push_quad(selection.start, 0.0, size, style.selection_color);
+    }
+    let preedit = &layout.preedit;
+    if preedit.end > preedit.start {
+        let size = Vec2::new(preedit.end - preedit.start, CARET_WIDTH / 2.0);
+        push_quad(preedit.start, layout.line_height, size, style.caret_color);
+    }
+    // The caret is shown for the first half of each blink
+    let blink_time = time.elapsed_seconds() - text_input.blink_start;
+    if let (Some(caret), true) = (
+        layout.caret,
+        blink_time % (2.0 * CARET_BLINK_TIME) < CARET_BLINK_TIME,
+    ) {
+        let size = Vec2::new(CARET_WIDTH, layout.line_height);
+        push_quad(caret - CARET_WIDTH / 2.0, 0.0, size, style.caret_color);
+    }
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 struct UiVertex {
     pub position: [f32; 3],
     pub uv: [f32; 2],
     pub color: [f32; 4],
+    pub shape: UiShapeVertex,
+}
+
+/// The rounded shape of a node and its borders, drawn anti-aliased by the shader
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct UiShapeVertex {
+    /// The position of the vertex from the center of the node
+    pub point: [f32; 2],
+    pub half_size: [f32; 2],
+    /// The radii of the corners: top left, top right, bottom right and bottom left
+    pub radius: [f32; 4],
+    /// The widths of the borders: left, top, right and bottom, or the blur radius of a shadow
+    pub border: [f32; 4],
+    /// The rect the node is clipped to, from the center of the node: left, top, right and bottom
+    pub clip: [f32; 4],
+    /// The radii of the corners of the clip, which is only rounded when one isn't 0
+    pub clip_radius: [f32; 4],
+    /// 0 for the whole node, 1 to 4 for its left, top, right or bottom border, or 5 for its
+    /// shadow
+    pub part: u32,
+}
+
+impl UiShapeVertex {
+    const VERTEX_FORMATS: [VertexFormat; 7] = [
+        // point
+        VertexFormat::Float32x2,
+        // half_size
+        VertexFormat::Float32x2,
+        // radius
+        VertexFormat::Float32x4,
+        // border
+        VertexFormat::Float32x4,
+        // clip
+        VertexFormat::Float32x4,
+        // clip_radius
+        VertexFormat::Float32x4,
+        // part
+        VertexFormat::Uint32,
+    ];
+}
+
+/// The vertex of a node with an [`ImageScaleMode`] other than [`ImageScaleMode::Stretched`],
+/// whose `uv` is the coordinate of the vertex inside the node.
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct SlicedUiVertex {
+    pub position: [f32; 3],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+    pub shape: UiShapeVertex,
+    pub slice: TextureSliceVertex,
+}
+
+/// The part of the vertices of a node with a [`BackgroundGradient`] telling the shader how to
+/// draw it
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct UiGradientVertex {
+    /// The end of a linear gradient going through the center of the node, followed by 0 and 0,
+    /// or the center and radius of a radial gradient, followed by 1
+    pub line: [f32; 4],
+    /// The colors of the stops, where the missing stops repeat the last one
+    pub colors: [[f32; 4]; MAX_GRADIENT_STOPS],
+    pub positions: [f32; MAX_GRADIENT_STOPS],
+}
+
+impl UiGradientVertex {
+    const VERTEX_FORMATS: [VertexFormat; 6] = [
+        // line
+        VertexFormat::Float32x4,
+        // colors
+        VertexFormat::Float32x4,
+        VertexFormat::Float32x4,
+        VertexFormat::Float32x4,
+        VertexFormat::Float32x4,
+        // positions
+        VertexFormat::Float32x4,
+    ];
+
+    fn new(gradient: &ExtractedGradient) -> Self {
+        let line = gradient.line;
+        let mut vertex = Self {
+            line: if gradient.radial {
+                [line[0], line[1], line[2], 1.0]
+            } else {
+                [line[2], line[3], 0.0, 0.0]
+            },
+            colors: gradient.colors,
+            positions: gradient.positions,
+        };
+        // The shader always blends all the stops, which doesn't change the colors after the last
+        // one when it's repeated
+        if let Some(last) = gradient.stop_count.checked_sub(1) {
+            for i in gradient.stop_count..MAX_GRADIENT_STOPS {
+                vertex.colors[i] = gradient.colors[last];
+                vertex.positions[i] = gradient.positions[last];
+            }
+        }
+        vertex
+    }
+}
+
+/// The vertex of a node with a [`BackgroundGradient`]
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct GradientUiVertex {
+    pub position: [f32; 3],
+    pub uv: [f32; 2],
+    pub color: [f32; 4],
+    pub shape: UiShapeVertex,
+    pub gradient: UiGradientVertex,
 }
 
 #[derive(Resource)]
 pub struct UiMeta {
     vertices: BufferVec<UiVertex>,
+    sliced_vertices: BufferVec<SlicedUiVertex>,
+    gradient_vertices: BufferVec<GradientUiVertex>,
     view_bind_group: Option<BindGroup>,
 }
 
@@ -389,6 +1257,8 @@ impl Default for UiMeta {
     fn default() -> Self {
         Self {
             vertices: BufferVec::new(BufferUsages::VERTEX),
+            sliced_vertices: BufferVec::new(BufferUsages::VERTEX),
+            gradient_vertices: BufferVec::new(BufferUsages::VERTEX),
             view_bind_group: None,
         }
     }
@@ -407,7 +1277,118 @@ const QUAD_INDICES: [usize; 6] = [0, 2, 3, 0, 1, 2];
 pub struct UiBatch {
     pub range: Range<u32>,
     pub image: Handle<Image>,
-    pub z: f32,
+    /// The camera the batch is drawn on
+    pub camera_entity: Entity,
+    /// The stack index of the first node of the batch, ordering it among the other batches
+    pub stack_index: usize,
+    /// The position of the batch among the batches of the frame, ordering the batches of the
+    /// same stack index
+    pub order: u32,
+    /// Whether the batch draws sliced or tiled images, from the sliced vertex buffer
+    pub sliced: bool,
+    /// Whether the batch draws gradients, from the gradient vertex buffer
+    pub gradient: bool,
+}
+
+/// The number of batches before the last one a node is checked against, to be drawn with an
+/// earlier batch of the same image
+const MAX_BATCH_LOOKBACK: usize = 64;
+
+/// The nodes drawn together in a [`UiBatch`], while the nodes are batched
+struct PendingBatch {
+    image: Handle<Image>,
+    camera_entity: Entity,
+    stack_index: usize,
+    sliced: bool,
+    gradient: bool,
+    /// The bounds of the nodes of the batch, that the nodes of another batch drawn after it
+    /// have to stay out of for the later nodes to join it
+    bounds: Rect,
+    /// The indices of the nodes in the [`ExtractedUiNodes`], in the order they're drawn
+    nodes: Vec<usize>,
+}
+
+/// The rect covered by a node on its camera, inside its clip
+fn uinode_bounds(extracted_uinode: &ExtractedUiNode) -> Rect {
+    let rect_size = extracted_uinode.rect.size().extend(1.0);
+    let mut bounds = Rect {
+        min: Vec2::splat(f32::INFINITY),
+        max: Vec2::splat(f32::NEG_INFINITY),
+    };
+    for position in QUAD_VERTEX_POSITIONS {
+        let position = (extracted_uinode.transform * (position * rect_size).extend(1.)).xy();
+        bounds.min = bounds.min.min(position);
+        bounds.max = bounds.max.max(position);
+    }
+    match extracted_uinode.clip {
+        Some(clip) => bounds.intersect(clip),
+        None => bounds,
+    }
+}
+
+/// Splits the nodes, sorted by their stack index, into the batches they're drawn in
+///
+/// A node can be drawn with an earlier batch of the same image instead of starting a new one,
+/// when none of the nodes drawn in between are under it, so the texts and the images between
+/// the backgrounds of the panels don't split their batches. The clipped nodes are cut on the
+/// CPU, so their clips don't split the batches either.
+fn batch_uinodes(
+    uinodes: &[ExtractedUiNode],
+    material_stack_indices: &[usize],
+) -> Vec<PendingBatch> {
+    let mut batches: Vec<PendingBatch> = Vec::new();
+    for (index, extracted_uinode) in uinodes.iter().enumerate() {
+        let gradient = extracted_uinode.gradient.is_some();
+        // The images of the nodes with a gradient are stretched
+        let sliced = !gradient && extracted_uinode.scale_mode != ImageScaleMode::Stretched;
+        let bounds = uinode_bounds(extracted_uinode);
+
+        let mut joined = None;
+        for (batch_index, batch) in batches.iter().enumerate().rev().take(MAX_BATCH_LOOKBACK) {
+            // The batches of the other cameras are drawn in other passes
+            if batch.camera_entity != extracted_uinode.camera_entity {
+                continue;
+            }
+            // A batch can't be drawn at once over a node with a material between its nodes
+            let next_material =
+                material_stack_indices.partition_point(|&index| index <= batch.stack_index);
+            if matches!(
+                material_stack_indices.get(next_material),
+                Some(&index) if index <= extracted_uinode.stack_index
+            ) {
+                break;
+            }
+            if batch.image == extracted_uinode.image
+                && batch.sliced == sliced
+                && batch.gradient == gradient
+            {
+                joined = Some(batch_index);
+                break;
+            }
+            if !batch.bounds.intersect(bounds).is_empty() {
+                break;
+            }
+        }
+        match joined {
+            Some(batch_index) => {
+                let batch = &mut batches[batch_index];
+                batch.nodes.push(index);
+                if !bounds.is_empty() {
+                    batch.bounds = batch.bounds.union(bounds);
+                }
+            }
+            None => batches.push(PendingBatch {
+                image: extracted_uinode.image.clone_weak(),
+                camera_entity: extracted_uinode.camera_entity,
+                stack_index: extracted_uinode.stack_index,
+                sliced,
+                gradient,
+                bounds,
+                nodes: vec![index],
+            }),
+        }
+    }
+    batches
 }
 
 pub fn prepare_uinodes(
@@ -416,138 +1397,227 @@ pub fn prepare_uinodes(
     render_queue: Res<RenderQueue>,
     mut ui_meta: ResMut<UiMeta>,
     mut extracted_uinodes: ResMut<ExtractedUiNodes>,
+    diagnostics: Option<Res<UiRenderStatsChannel>>,
 ) {
     ui_meta.vertices.clear();
+    ui_meta.sliced_vertices.clear();
+    ui_meta.gradient_vertices.clear();
 
     // sort by ui stack index, starting from the deepest node
     extracted_uinodes
         .uinodes
         .sort_by_key(|node| node.stack_index);
 
-    let mut start = 0;
-    let mut end = 0;
-    let mut current_batch_handle = Default::default();
-    let mut last_z = 0.0;
-    for extracted_uinode in &extracted_uinodes.uinodes {
-        if current_batch_handle != extracted_uinode.image {
-            if start != end {
-                commands.spawn(UiBatch {
-                    range: start..end,
-                    image: current_batch_handle,
-                    z: last_z,
-                });
-                start = end;
+    let mut material_stack_indices = std::mem::take(&mut extracted_uinodes.material_stack_indices);
+    material_stack_indices.sort_unstable();
+    let batches = batch_uinodes(&extracted_uinodes.uinodes, &material_stack_indices);
+
+    let mut stats = UiRenderStats::default();
+    for (order, batch) in batches.into_iter().enumerate() {
+        // Sliced nodes and gradients are stored in their own vertex buffers
+        let vertex_count = |ui_meta: &UiMeta| {
+            if batch.gradient {
+                ui_meta.gradient_vertices.len() as u32
+            } else if batch.sliced {
+                ui_meta.sliced_vertices.len() as u32
+            } else {
+                ui_meta.vertices.len() as u32
+            }
+        };
+        let start = vertex_count(&ui_meta);
+        for index in batch.nodes {
+            let extracted_uinode = &extracted_uinodes.uinodes[index];
+            if push_uinode_vertices(&mut ui_meta, extracted_uinode, batch.sliced) {
+                stats.nodes += 1;
             }
-            current_batch_handle = extracted_uinode.image.clone_weak();
         }
+        let end = vertex_count(&ui_meta);
+        if start != end {
+            stats.batches += 1;
+            stats.vertices += end - start;
+            commands.spawn(UiBatch {
+                range: start..end,
+                image: batch.image,
+                camera_entity: batch.camera_entity,
+                stack_index: batch.stack_index,
+                // The materials are drawn before the batches of their stack index
+                order: order as u32 + 1,
+                sliced: batch.sliced,
+                gradient: batch.gradient,
+            });
+        }
+    }
+    if let Some(diagnostics) = diagnostics {
+        diagnostics.send(stats);
+    }
 
-        let uinode_rect = extracted_uinode.rect;
-        let rect_size = uinode_rect.size().extend(1.0);
-
-        // Specify the corners of the node
-        let positions = QUAD_VERTEX_POSITIONS
-            .map(|pos| (extracted_uinode.transform * (pos * rect_size).extend(1.)).xyz());
-
-        // Calculate the effect of clipping
-        // Note: this won't work with rotation/scaling, but that's much more complex (may need more that 2 quads)
-        let positions_diff = if let Some(clip) = extracted_uinode.clip {
-            [
-                Vec2::new(
-                    f32::max(clip.min.x - positions[0].x, 0.),
-                    f32::max(clip.min.y - positions[0].y, 0.),
-                ),
-                Vec2::new(
-                    f32::min(clip.max.x - positions[1].x, 0.),
-                    f32::max(clip.min.y - positions[1].y, 0.),
-                ),
-                Vec2::new(
-                    f32::min(clip.max.x - positions[2].x, 0.),
-                    f32::min(clip.max.y - positions[2].y, 0.),
-                ),
-                Vec2::new(
-                    f32::max(clip.min.x - positions[3].x, 0.),
-                    f32::min(clip.max.y - positions[3].y, 0.),
-                ),
-            ]
-        } else {
-            [Vec2::ZERO; 4]
-        };
+    ui_meta.vertices.write_buffer(&render_device, &render_queue);
+    ui_meta
+        .sliced_vertices
+        .write_buffer(&render_device, &render_queue);
+    ui_meta
+        .gradient_vertices
+        .write_buffer(&render_device, &render_queue);
+}
 
-        let positions_clipped = [
-            positions[0] + positions_diff[0].extend(0.),
-            positions[1] + positions_diff[1].extend(0.),
-            positions[2] + positions_diff[2].extend(0.),
-            positions[3] + positions_diff[3].extend(0.),
-        ];
+/// Pushes the vertices of a node to the vertex buffer of its batch, returning whether it's drawn
+/// or completely clipped
+fn push_uinode_vertices(
+    ui_meta: &mut UiMeta,
+    extracted_uinode: &ExtractedUiNode,
+    sliced: bool,
+) -> bool {
+    let uinode_rect = extracted_uinode.rect;
+    let rect_size = uinode_rect.size().extend(1.0);
 
-        let transformed_rect_size = extracted_uinode.transform.transform_vector3(rect_size);
-
-        // Don't try to cull nodes that have a rotation
-        // In a rotation around the Z-axis, this value is 0.0 for an angle of 0.0 or π
-        // In those two cases, the culling check can proceed normally as corners will be on
-        // horizontal / vertical lines
-        // For all other angles, bypass the culling check
-        // This does not properly handles all rotations on all axis
-        if extracted_uinode.transform.x_axis[1] == 0.0 {
-            // Cull nodes that are completely clipped
-            if positions_diff[0].x - positions_diff[1].x >= transformed_rect_size.x
-                || positions_diff[1].y - positions_diff[2].y >= transformed_rect_size.y
-            {
-                continue;
-            }
-        }
+    // Specify the corners of the node
+    let positions = QUAD_VERTEX_POSITIONS
+        .map(|pos| (extracted_uinode.transform * (pos * rect_size).extend(1.)).xyz());
 
-        let atlas_extent = extracted_uinode.atlas_size.unwrap_or(uinode_rect.max);
-        let mut uvs = [
+    // Calculate the effect of clipping
+    // Note: this won't work with rotation/scaling, but that's much more complex (may need more that 2 quads)
+    let positions_diff = if let Some(clip) = extracted_uinode.clip {
+        [
             Vec2::new(
-                uinode_rect.min.x + positions_diff[0].x,
-                uinode_rect.min.y + positions_diff[0].y,
+                f32::max(clip.min.x - positions[0].x, 0.),
+                f32::max(clip.min.y - positions[0].y, 0.),
             ),
             Vec2::new(
-                uinode_rect.max.x + positions_diff[1].x,
-                uinode_rect.min.y + positions_diff[1].y,
+                f32::min(clip.max.x - positions[1].x, 0.),
+                f32::max(clip.min.y - positions[1].y, 0.),
             ),
             Vec2::new(
-                uinode_rect.max.x + positions_diff[2].x,
-                uinode_rect.max.y + positions_diff[2].y,
+                f32::min(clip.max.x - positions[2].x, 0.),
+                f32::min(clip.max.y - positions[2].y, 0.),
             ),
             Vec2::new(
-                uinode_rect.min.x + positions_diff[3].x,
-                uinode_rect.max.y + positions_diff[3].y,
+                f32::max(clip.min.x - positions[3].x, 0.),
+                f32::min(clip.max.y - positions[3].y, 0.),
             ),
         ]
-        .map(|pos| pos / atlas_extent);
+    } else {
+        [Vec2::ZERO; 4]
+    };
 
-        if extracted_uinode.flip_x {
-            uvs = [uvs[1], uvs[0], uvs[3], uvs[2]];
-        }
-        if extracted_uinode.flip_y {
-            uvs = [uvs[3], uvs[2], uvs[1], uvs[0]];
+    let positions_clipped = [
+        positions[0] + positions_diff[0].extend(0.),
+        positions[1] + positions_diff[1].extend(0.),
+        positions[2] + positions_diff[2].extend(0.),
+        positions[3] + positions_diff[3].extend(0.),
+    ];
+
+    let transformed_rect_size = extracted_uinode.transform.transform_vector3(rect_size);
+
+    // Don't try to cull nodes that have a rotation
+    // In a rotation around the Z-axis, this value is 0.0 for an angle of 0.0 or π
+    // In those two cases, the culling check can proceed normally as corners will be on
+    // horizontal / vertical lines
+    // For all other angles, bypass the culling check
+    // This does not properly handles all rotations on all axis
+    if extracted_uinode.transform.x_axis[1] == 0.0 {
+        // Cull nodes that are completely clipped
+        if positions_diff[0].x - positions_diff[1].x >= transformed_rect_size.x
+            || positions_diff[1].y - positions_diff[2].y >= transformed_rect_size.y
+        {
+            return false;
         }
+    }
+
+    let atlas_extent = extracted_uinode.atlas_size.unwrap_or(uinode_rect.max);
+    let mut points = [
+        Vec2::new(
+            uinode_rect.min.x + positions_diff[0].x,
+            uinode_rect.min.y + positions_diff[0].y,
+        ),
+        Vec2::new(
+            uinode_rect.max.x + positions_diff[1].x,
+            uinode_rect.min.y + positions_diff[1].y,
+        ),
+        Vec2::new(
+            uinode_rect.max.x + positions_diff[2].x,
+            uinode_rect.max.y + positions_diff[2].y,
+        ),
+        Vec2::new(
+            uinode_rect.min.x + positions_diff[3].x,
+            uinode_rect.max.y + positions_diff[3].y,
+        ),
+    ];
+
+    if extracted_uinode.flip_x {
+        points = [points[1], points[0], points[3], points[2]];
+    }
+    if extracted_uinode.flip_y {
+        points = [points[3], points[2], points[1], points[0]];
+    }
 
-        let color = extracted_uinode.color.as_linear_rgba_f32();
+    let color = extracted_uinode.color.as_linear_rgba_f32();
+    // The clip from the center of the node, which is rounded in the shader
+    let center = extracted_uinode.transform.w_axis.truncate().truncate();
+    let clip = extracted_uinode.clip.map_or([0.0; 4], |clip| {
+        let (min, max) = (clip.min - center, clip.max - center);
+        [min.x, min.y, max.x, max.y]
+    });
+    let shape = |i: usize| UiShapeVertex {
+        point: (QUAD_VERTEX_POSITIONS[i].truncate() * uinode_rect.size() + positions_diff[i])
+            .into(),
+        half_size: (uinode_rect.size() / 2.0).into(),
+        radius: extracted_uinode.border_radius,
+        border: extracted_uinode.border,
+        clip,
+        clip_radius: extracted_uinode.clip_radius,
+        part: extracted_uinode.part as u32,
+    };
+    // The points are mapped from the rect to the region of the atlas drawn over it
+    let uv = |point: Vec2| match extracted_uinode.atlas_rect {
+        Some(atlas_rect) => {
+            atlas_rect.min + (point - uinode_rect.min) * atlas_rect.size() / uinode_rect.size()
+        }
+        None => point,
+    } / atlas_extent;
+    if let Some(gradient) = &extracted_uinode.gradient {
+        let gradient = UiGradientVertex::new(gradient);
+        for i in QUAD_INDICES {
+            ui_meta.gradient_vertices.push(GradientUiVertex {
+                position: positions_clipped[i].into(),
+                uv: uv(points[i]).into(),
+                color,
+                shape: shape(i),
+                gradient,
+            });
+        }
+    } else if sliced {
+        // The sliced shader maps the coordinates inside the node to the image itself
+        let image_size = extracted_uinode.image_size;
+        let slice = TextureSliceVertex::new(
+            &extracted_uinode.scale_mode,
+            extracted_uinode.atlas_rect.unwrap_or(Rect {
+                min: Vec2::ZERO,
+                max: image_size,
+            }),
+            image_size,
+            uinode_rect.size(),
+        );
+        for i in QUAD_INDICES {
+            ui_meta.sliced_vertices.push(SlicedUiVertex {
+                position: positions_clipped[i].into(),
+                uv: points[i].into(),
+                color,
+                shape: shape(i),
+                slice,
+            });
+        }
+    } else {
         for i in QUAD_INDICES {
             ui_meta.vertices.push(UiVertex {
                 position: positions_clipped[i].into(),
-                uv: uvs[i].into(),
+                uv: uv(points[i]).into(),
                 color,
+                shape: shape(i),
             });
         }
-
-        last_z = extracted_uinode.transform.w_axis[2];
-        end += QUAD_INDICES.len() as u32;
-    }
-
-    // if start != end, there is one last batch to process
-    if start != end {
-        commands.spawn(UiBatch {
-            range: start..end,
-            image: current_batch_handle,
-            z: last_z,
-        });
     }
 
-    ui_meta.vertices.write_buffer(&render_device, &render_queue);
+    true
 }
 
 #[derive(Resource, Default)]
@@ -567,13 +1637,13 @@ pub fn queue_uinodes(
     mut image_bind_groups: ResMut<UiImageBindGroups>,
     gpu_images: Res<RenderAssets<Image>>,
     ui_batches: Query<(Entity, &UiBatch)>,
-    mut views: Query<(&ExtractedView, &mut RenderPhase<TransparentUi>)>,
+    mut views: Query<(Entity, &ExtractedView, &mut RenderPhase<TransparentUi>)>,
     events: Res<SpriteAssetEvents>,
 ) {
     // If an image has changed, the GpuImage has (probably) changed
     for event in &events.images {
         match event {
-            AssetEvent::Created { .. } => None,
+            AssetEvent::Created { .. } | AssetEvent::LoadedWithDependencies { .. } => None,
             AssetEvent::Modified { handle } | AssetEvent::Removed { handle } => {
                 image_bind_groups.values.remove(handle)
             }
@@ -590,13 +1660,39 @@ pub fn queue_uinodes(
             layout: &ui_pipeline.view_layout,
         }));
         let draw_ui_function = draw_functions.read().id::<DrawUi>();
-        for (view, mut transparent_phase) in &mut views {
+        for (view_entity, view, mut transparent_phase) in &mut views {
             let pipeline = pipelines.specialize(
                 &pipeline_cache,
                 &ui_pipeline,
-                UiPipelineKey { hdr: view.hdr },
+                UiPipelineKey {
+                    hdr: view.hdr,
+                    sliced: false,
+                    gradient: false,
+                },
+            );
+            let sliced_pipeline = pipelines.specialize(
+                &pipeline_cache,
+                &ui_pipeline,
+                UiPipelineKey {
+                    hdr: view.hdr,
+                    sliced: true,
+                    gradient: false,
+                },
             );
-            for (entity, batch) in &ui_batches {
+            let gradient_pipeline = pipelines.specialize(
+                &pipeline_cache,
+                &ui_pipeline,
+                UiPipelineKey {
+                    hdr: view.hdr,
+                    sliced: false,
+                    gradient: true,
+                },
+            );
+            // The views are the cameras, with the same entities as in the main world
+            for (entity, batch) in ui_batches
+                .iter()
+                .filter(|(_, batch)| batch.camera_entity == view_entity)
+            {
                 image_bind_groups
                     .values
                     .entry(batch.image.clone_weak())
@@ -619,11 +1715,229 @@ pub fn queue_uinodes(
                     });
                 transparent_phase.add(TransparentUi {
                     draw_function: draw_ui_function,
-                    pipeline,
+                    pipeline: if batch.gradient {
+                        gradient_pipeline
+                    } else if batch.sliced {
+                        sliced_pipeline
+                    } else {
+                        pipeline
+                    },
                     entity,
-                    sort_key: FloatOrd(batch.z),
+                    sort_key: (FloatOrd(batch.stack_index as f32), batch.order),
                 });
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_asset::HandleId;
+    use bevy_render::MainWorld;
+
+    /// A 10 by 10 node centered on `center`, drawn with `image` on `camera_entity`
+    fn uinode(
+        stack_index: usize,
+        camera_entity: Entity,
+        image: &Handle<Image>,
+        center: Vec2,
+    ) -> ExtractedUiNode {
+        ExtractedUiNode {
+            stack_index,
+            camera_entity,
+            transform: Mat4::from_translation(center.extend(0.0)),
+            color: Color::WHITE,
+            rect: Rect::new(0.0, 0.0, 10.0, 10.0),
+            image: image.clone_weak(),
+            atlas_size: None,
+            atlas_rect: None,
+            clip: None,
+            clip_radius: [0.0; 4],
+            flip_x: false,
+            flip_y: false,
+            scale_mode: ImageScaleMode::Stretched,
+            image_size: Vec2::ZERO,
+            border_radius: [0.0; 4],
+            border: [0.0; 4],
+            part: UiNodePart::Node,
+            gradient: None,
+        }
+    }
+
+    /// The nodes of each batch
+    fn batches(uinodes: &[ExtractedUiNode], material_stack_indices: &[usize]) -> Vec<Vec<usize>> {
+        batch_uinodes(uinodes, material_stack_indices)
+            .into_iter()
+            .map(|batch| batch.nodes)
+            .collect()
+    }
+
+    struct Images {
+        a: Handle<Image>,
+        b: Handle<Image>,
+        camera: Entity,
+    }
+
+    fn images() -> Images {
+        Images {
+            a: Handle::weak(HandleId::random::<Image>()),
+            b: Handle::weak(HandleId::random::<Image>()),
+            camera: Entity::from_raw(0),
+        }
+    }
+
+    #[test]
+    fn overlapping_nodes_keep_their_order() {
+        let Images { a, b, camera } = images();
+        let uinodes = [
+            uinode(0, camera, &a, Vec2::ZERO),
+            uinode(1, camera, &b, Vec2::new(5.0, 5.0)),
+            uinode(2, camera, &a, Vec2::new(10.0, 0.0)),
+        ];
+        // the last node is over the second one, so it can't be drawn with the first one
+        assert_eq!(batches(&uinodes, &[]), [vec![0], vec![1], vec![2]]);
+    }
+
+    #[test]
+    fn separate_nodes_of_an_image_are_merged() {
+        let Images { a, b, camera } = images();
+        let uinodes = [
+            uinode(0, camera, &a, Vec2::ZERO),
+            uinode(1, camera, &b, Vec2::new(50.0, 0.0)),
+            uinode(2, camera, &a, Vec2::new(100.0, 0.0)),
+            uinode(3, camera, &b, Vec2::new(150.0, 0.0)),
+            uinode(4, camera, &a, Vec2::new(0.0, 50.0)),
+        ];
+        assert_eq!(batches(&uinodes, &[]), [vec![0, 2, 4], vec![1, 3]]);
+    }
+
+    #[test]
+    fn nodes_touching_at_an_edge_are_merged() {
+        let Images { a, b, camera } = images();
+        let uinodes = [
+            uinode(0, camera, &a, Vec2::ZERO),
+            uinode(1, camera, &b, Vec2::new(10.0, 0.0)),
+            uinode(2, camera, &a, Vec2::new(20.0, 0.0)),
+        ];
+        assert_eq!(batches(&uinodes, &[]), [vec![0, 2], vec![1]]);
+    }
+
+    #[test]
+    fn materials_split_the_batches() {
+        let Images { a, camera, .. } = images();
+        let uinodes = [
+            uinode(0, camera, &a, Vec2::ZERO),
+            uinode(2, camera, &a, Vec2::new(100.0, 0.0)),
+            uinode(3, camera, &a, Vec2::new(200.0, 0.0)),
+        ];
+        // a material node at stack index 1, drawn between the first two nodes
+        assert_eq!(batches(&uinodes, &[1]), [vec![0], vec![1, 2]]);
+        // or after them
+        assert_eq!(batches(&uinodes, &[4]), [vec![0, 1, 2]]);
+    }
+
+    #[test]
+    fn batches_of_other_cameras_are_skipped() {
+        let Images { a, b, camera } = images();
+        let other_camera = Entity::from_raw(1);
+        let uinodes = [
+            uinode(0, camera, &a, Vec2::ZERO),
+            // over the nodes of the first camera, but drawn in another pass
+            uinode(1, other_camera, &b, Vec2::ZERO),
+            uinode(2, camera, &a, Vec2::new(5.0, 0.0)),
+            // the same image on another camera starts its own batch
+            uinode(3, other_camera, &a, Vec2::new(100.0, 0.0)),
+        ];
+        assert_eq!(batches(&uinodes, &[]), [vec![0, 2], vec![1], vec![3]]);
+    }
+
+    #[test]
+    fn clipped_nodes_dont_grow_the_bounds() {
+        let Images { a, b, camera } = images();
+        let mut clipped = uinode(1, camera, &a, Vec2::new(100.0, 0.0));
+        clipped.clip = Some(Rect::new(200.0, 200.0, 300.0, 300.0));
+        let uinodes = [
+            uinode(0, camera, &a, Vec2::ZERO),
+            clipped,
+            // where the clipped node would be drawn
+            uinode(2, camera, &b, Vec2::new(100.0, 0.0)),
+            uinode(3, camera, &a, Vec2::new(200.0, 0.0)),
+        ];
+        let pending = batch_uinodes(&uinodes, &[]);
+        assert_eq!(pending[0].nodes, [0, 1, 3]);
+        assert_eq!(
+            pending[0].bounds,
+            Rect::new(-5.0, -5.0, 205.0, 5.0),
+            "the bounds of the first and last nodes"
+        );
+        assert_eq!(pending[1].nodes, [2]);
+
+        // a partly clipped node only grows the bounds by its visible part
+        let mut clipped = uinode(1, camera, &a, Vec2::new(100.0, 0.0));
+        clipped.clip = Some(Rect::new(0.0, -100.0, 101.0, 100.0));
+        let uinodes = [uinode(0, camera, &a, Vec2::ZERO), clipped];
+        assert_eq!(
+            batch_uinodes(&uinodes, &[])[0].bounds,
+            Rect::new(-5.0, -5.0, 101.0, 5.0)
+        );
+    }
+
+    #[test]
+    fn sliced_nodes_are_batched_apart() {
+        let Images { a, camera, .. } = images();
+        let mut sliced = uinode(1, camera, &a, Vec2::new(100.0, 0.0));
+        sliced.scale_mode = ImageScaleMode::Tiled {
+            tile_x: true,
+            tile_y: true,
+            stretch_value: 1.0,
+        };
+        let uinodes = [
+            uinode(0, camera, &a, Vec2::ZERO),
+            sliced,
+            uinode(2, camera, &a, Vec2::new(200.0, 0.0)),
+        ];
+        assert_eq!(batches(&uinodes, &[]), [vec![0, 2], vec![1]]);
+    }
+
+    #[test]
+    fn opacity_applied_to_the_extracted_colors() {
+        let Images { a, camera, .. } = images();
+        let mut main_world = MainWorld::default();
+        let faded = main_world.spawn(CalculatedOpacity { opacity: 0.5 }).id();
+        let opaque = main_world.spawn(()).id();
+        main_world.insert_resource(UiStack {
+            uinodes: vec![opaque, faded],
+        });
+
+        let mut render_world = World::new();
+        render_world.insert_resource(main_world);
+        let mut background = uinode(1, camera, &a, Vec2::ZERO);
+        background.color = Color::rgba(1.0, 0.0, 0.0, 0.8);
+        let mut border = uinode(1, camera, &a, Vec2::ZERO);
+        border.part = UiNodePart::LeftBorder;
+        render_world.insert_resource(ExtractedUiNodes {
+            uinodes: vec![uinode(0, camera, &a, Vec2::ZERO), background, border],
+            material_stack_indices: Vec::new(),
+        });
+        let mut schedule = Schedule::new();
+        schedule.add_system(extract_uinode_opacities);
+        schedule.run(&mut render_world);
+
+        let colors: Vec<Color> = render_world
+            .resource::<ExtractedUiNodes>()
+            .uinodes
+            .iter()
+            .map(|uinode| uinode.color)
+            .collect();
+        // all the parts of the faded node have their alpha multiplied, not their other channels
+        assert_eq!(
+            colors,
+            [
+                Color::WHITE,
+                Color::rgba(1.0, 0.0, 0.0, 0.4),
+                Color::rgba(1.0, 1.0, 1.0, 0.5)
+            ]
+        );
+    }
+}
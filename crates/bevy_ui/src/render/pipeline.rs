@@ -5,6 +5,7 @@ use bevy_render::{
     texture::BevyDefault,
     view::{ViewTarget, ViewUniform},
 };
+use bevy_sprite::TextureSliceVertex;
 
 #[derive(Resource)]
 pub struct UiPipeline {
@@ -62,24 +63,36 @@ impl FromWorld for UiPipeline {
 #[derive(Clone, Copy, Hash, PartialEq, Eq)]
 pub struct UiPipelineKey {
     pub hdr: bool,
+    /// Whether the nodes have a sliced or tiled image
+    pub sliced: bool,
+    /// Whether the nodes are filled with a gradient
+    pub gradient: bool,
 }
 
 impl SpecializedRenderPipeline for UiPipeline {
     type Key = UiPipelineKey;
 
     fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
-        let vertex_layout = VertexBufferLayout::from_vertex_formats(
-            VertexStepMode::Vertex,
-            vec![
-                // position
-                VertexFormat::Float32x3,
-                // uv
-                VertexFormat::Float32x2,
-                // color
-                VertexFormat::Float32x4,
-            ],
-        );
-        let shader_defs = Vec::new();
+        let mut formats = vec![
+            // position
+            VertexFormat::Float32x3,
+            // uv
+            VertexFormat::Float32x2,
+            // color
+            VertexFormat::Float32x4,
+        ];
+        formats.extend(super::UiShapeVertex::VERTEX_FORMATS);
+        let mut shader_defs = Vec::new();
+        if key.sliced {
+            formats.extend(TextureSliceVertex::VERTEX_FORMATS);
+            shader_defs.push("SLICED".into());
+        }
+        if key.gradient {
+            formats.extend(super::UiGradientVertex::VERTEX_FORMATS);
+            shader_defs.push("GRADIENT".into());
+        }
+        let vertex_layout =
+            VertexBufferLayout::from_vertex_formats(VertexStepMode::Vertex, formats);
 
         RenderPipelineDescriptor {
             vertex: VertexState {
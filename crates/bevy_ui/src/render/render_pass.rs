@@ -55,10 +55,10 @@ impl Node for UiPassNode {
         let input_view_entity = graph.get_input_entity(Self::IN_VIEW)?;
 
         let Ok((transparent_phase, target, camera_ui)) =
-                self.ui_view_query.get_manual(world, input_view_entity)
-             else {
-                return Ok(());
-            };
+            self.ui_view_query.get_manual(world, input_view_entity)
+        else {
+            return Ok(());
+        };
         if transparent_phase.items.is_empty() {
             return Ok(());
         }
@@ -92,14 +92,16 @@ impl Node for UiPassNode {
 }
 
 pub struct TransparentUi {
-    pub sort_key: FloatOrd,
+    /// The stack index of the first node drawn, and the order of the batch among the batches of
+    /// the same stack index
+    pub sort_key: (FloatOrd, u32),
     pub entity: Entity,
     pub pipeline: CachedRenderPipelineId,
     pub draw_function: DrawFunctionId,
 }
 
 impl PhaseItem for TransparentUi {
-    type SortKey = FloatOrd;
+    type SortKey = (FloatOrd, u32);
 
     #[inline]
     fn entity(&self) -> Entity {
@@ -185,7 +187,14 @@ impl<P: PhaseItem> RenderCommand<P> for DrawUiNode {
         ui_meta: SystemParamItem<'w, '_, Self::Param>,
         pass: &mut TrackedRenderPass<'w>,
     ) -> RenderCommandResult {
-        pass.set_vertex_buffer(0, ui_meta.into_inner().vertices.buffer().unwrap().slice(..));
+        let ui_meta = ui_meta.into_inner();
+        if batch.gradient {
+            pass.set_vertex_buffer(0, ui_meta.gradient_vertices.buffer().unwrap().slice(..));
+        } else if batch.sliced {
+            pass.set_vertex_buffer(0, ui_meta.sliced_vertices.buffer().unwrap().slice(..));
+        } else {
+            pass.set_vertex_buffer(0, ui_meta.vertices.buffer().unwrap().slice(..));
+        }
         pass.draw(batch.range.clone(), 0..1);
         RenderCommandResult::Success
     }
@@ -1,11 +1,38 @@
 #import bevy_render::view
 
+#ifdef SLICED
+#import bevy_sprite::texture_slice
+#endif
+
 @group(0) @binding(0)
 var<uniform> view: View;
 
 struct VertexOutput {
     @location(0) uv: vec2<f32>,
     @location(1) color: vec4<f32>,
+    @location(2) point: vec2<f32>,
+    @location(3) half_size: vec2<f32>,
+    @location(4) radius: vec4<f32>,
+    @location(5) node_border: vec4<f32>,
+    @location(6) @interpolate(flat) part: u32,
+    @location(7) @interpolate(flat) clip: vec4<f32>,
+    @location(8) @interpolate(flat) clip_radius: vec4<f32>,
+#ifdef SLICED
+    @location(9) uv_rect: vec4<f32>,
+    // The size of the node in xy and the size of its texels in zw, packed to fit in the locations
+    @location(10) size_texels: vec4<f32>,
+    @location(11) border: vec4<f32>,
+    @location(12) tile_scale: vec2<f32>,
+    @location(13) @interpolate(flat) slice_flags: u32,
+#endif
+#ifdef GRADIENT
+    @location(9) @interpolate(flat) gradient_line: vec4<f32>,
+    @location(10) @interpolate(flat) color_0: vec4<f32>,
+    @location(11) @interpolate(flat) color_1: vec4<f32>,
+    @location(12) @interpolate(flat) color_2: vec4<f32>,
+    @location(13) @interpolate(flat) color_3: vec4<f32>,
+    @location(14) @interpolate(flat) stop_positions: vec4<f32>,
+#endif
     @builtin(position) position: vec4<f32>,
 };
 
@@ -14,11 +41,56 @@ fn vertex(
     @location(0) vertex_position: vec3<f32>,
     @location(1) vertex_uv: vec2<f32>,
     @location(2) vertex_color: vec4<f32>,
+    @location(3) point: vec2<f32>,
+    @location(4) half_size: vec2<f32>,
+    @location(5) radius: vec4<f32>,
+    @location(6) node_border: vec4<f32>,
+    @location(7) clip: vec4<f32>,
+    @location(8) clip_radius: vec4<f32>,
+    @location(9) part: u32,
+#ifdef SLICED
+    @location(10) uv_rect: vec4<f32>,
+    @location(11) size: vec2<f32>,
+    @location(12) texels: vec2<f32>,
+    @location(13) border: vec4<f32>,
+    @location(14) tile_scale: vec2<f32>,
+    @location(15) slice_flags: u32,
+#endif
+#ifdef GRADIENT
+    @location(10) gradient_line: vec4<f32>,
+    @location(11) color_0: vec4<f32>,
+    @location(12) color_1: vec4<f32>,
+    @location(13) color_2: vec4<f32>,
+    @location(14) color_3: vec4<f32>,
+    @location(15) stop_positions: vec4<f32>,
+#endif
 ) -> VertexOutput {
     var out: VertexOutput;
     out.uv = vertex_uv;
     out.position = view.view_proj * vec4<f32>(vertex_position, 1.0);
     out.color = vertex_color;
+    out.point = point;
+    out.half_size = half_size;
+    out.radius = radius;
+    out.node_border = node_border;
+    out.part = part;
+    out.clip = clip;
+    out.clip_radius = clip_radius;
+#ifdef SLICED
+    out.uv_rect = uv_rect;
+    out.size_texels = vec4<f32>(size, texels);
+    out.border = border;
+    out.tile_scale = tile_scale;
+    out.slice_flags = slice_flags;
+#endif
+#ifdef GRADIENT
+    out.gradient_line = gradient_line;
+    out.color_0 = color_0;
+    out.color_1 = color_1;
+    out.color_2 = color_2;
+    out.color_3 = color_3;
+    out.stop_positions = stop_positions;
+#endif
     return out;
 }
 
@@ -27,9 +99,135 @@ var sprite_texture: texture_2d<f32>;
 @group(1) @binding(1)
 var sprite_sampler: sampler;
 
+// The signed distance from `point` to the edge of a box of `half_size` centered on the origin,
+// with the `radius` of its top left, top right, bottom right and bottom left corners
+fn sd_rounded_box(point: vec2<f32>, half_size: vec2<f32>, radius: vec4<f32>) -> f32 {
+    // The radius of the corner in the quarter of the point, with y growing downwards
+    let side_radius = select(radius.xw, radius.yz, point.x > 0.0);
+    let corner_radius = select(side_radius.x, side_radius.y, point.y > 0.0);
+    let q = abs(point) - half_size + corner_radius;
+    return min(max(q.x, q.y), 0.0) + length(max(q, vec2<f32>(0.0))) - corner_radius;
+}
+
+// The coverage of a pixel at a signed `distance` from an edge, anti-aliased over a pixel
+fn coverage(distance: f32) -> f32 {
+    return saturate(0.5 - distance / max(fwidth(distance), 0.0001));
+}
+
+// How much of the pixel is in the part of the node drawn, inside its rounded corners and, for a
+// border, between the edge of the node and the edge of its content on the side of the border
+fn shape_coverage(in: VertexOutput) -> f32 {
+    if in.part == 5u {
+        // Shadows fade out over their blur radius, around the edge of the box they're cast by
+        let blur = in.node_border.x;
+        let distance = sd_rounded_box(in.point, max(in.half_size - blur, vec2<f32>(0.0)), in.radius);
+        if blur <= 0.0 {
+            return coverage(distance);
+        }
+        return 1.0 - smoothstep(-blur, blur, distance);
+    }
+
+    let border = in.node_border;
+    let outer = sd_rounded_box(in.point, in.half_size, in.radius);
+
+    // The corners of the content are rounded by what the borders leave of the outer radius
+    let inner_center = vec2<f32>(border.x - border.z, border.y - border.w) / 2.0;
+    let inner_half_size = max(in.half_size - (border.xy + border.zw) / 2.0, vec2<f32>(0.0));
+    let inner_radius = max(
+        in.radius - vec4<f32>(
+            max(border.x, border.y),
+            max(border.z, border.y),
+            max(border.z, border.w),
+            max(border.x, border.w),
+        ),
+        vec4<f32>(0.0),
+    );
+    let inner = sd_rounded_box(in.point - inner_center, inner_half_size, inner_radius);
+    let outer_coverage = coverage(outer);
+    let border_coverage = outer_coverage * coverage(-inner);
+
+    if in.part == 0u {
+        // The nodes without rounded corners cover their whole quad
+        return select(outer_coverage, 1.0, all(in.radius == vec4<f32>(0.0)));
+    }
+
+    // The pixel is on the side of the closest edge, in proportion to the widths of the borders,
+    // so the corners are split between the borders from the corner of the node to the corner
+    // of its content
+    let from_edges = vec4<f32>(
+        in.point.x + in.half_size.x,
+        in.point.y + in.half_size.y,
+        in.half_size.x - in.point.x,
+        in.half_size.y - in.point.y,
+    ) / max(border, vec4<f32>(0.0001));
+    var closest = 1u;
+    var closest_distance = from_edges.x;
+    if from_edges.y < closest_distance {
+        closest = 2u;
+        closest_distance = from_edges.y;
+    }
+    if from_edges.z < closest_distance {
+        closest = 3u;
+        closest_distance = from_edges.z;
+    }
+    if from_edges.w < closest_distance {
+        closest = 4u;
+    }
+    return select(0.0, border_coverage, closest == in.part);
+}
+
+#ifdef GRADIENT
+// The color of the gradient at the point of the pixel in the node
+fn gradient_color(in: VertexOutput) -> vec4<f32> {
+    var t: f32;
+    if in.gradient_line.w == 0.0 {
+        // The position of the point along the line through the center of the node, from the
+        // opposite of its end to its end
+        let end = in.gradient_line.xy;
+        t = 0.5 + dot(in.point, end) / max(2.0 * dot(end, end), 0.0001);
+    } else {
+        // The distance to the center of the gradient, relative to its radius
+        t = length(in.point - in.gradient_line.xy) / max(in.gradient_line.z, 0.0001);
+    }
+
+    var colors = array<vec4<f32>, 4>(in.color_0, in.color_1, in.color_2, in.color_3);
+    // The missing stops repeat the last one, so they don't change the color
+    var color = colors[0];
+    for (var i = 1u; i < 4u; i += 1u) {
+        let start = in.stop_positions[i - 1u];
+        let end = in.stop_positions[i];
+        let step = saturate((t - start) / max(end - start, 0.0001));
+        color = select(color, mix(colors[i - 1u], colors[i], step), t > start);
+    }
+    return color;
+}
+#endif
+
+// How much of the pixel is inside the rect the node is clipped to, which only has to be checked
+// here when its corners are rounded
+fn clip_coverage(in: VertexOutput) -> f32 {
+    if all(in.clip_radius == vec4<f32>(0.0)) {
+        return 1.0;
+    }
+    let center = (in.clip.xy + in.clip.zw) / 2.0;
+    let half_size = (in.clip.zw - in.clip.xy) / 2.0;
+    return coverage(sd_rounded_box(in.point - center, half_size, in.clip_radius));
+}
+
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
+#ifdef SLICED
+    // The UVs of sliced nodes are their coordinates inside the node
+    let texels = in.size_texels.zw;
+    let uv = texture_slice_uv(in.uv, in.uv_rect, in.size_texels.xy, texels, in.border, in.tile_scale, in.slice_flags);
+    let uv_per_unit = texture_slice_uv_per_unit(in.uv_rect, texels);
+    var color = textureSampleGrad(sprite_texture, sprite_sampler, uv, dpdx(in.uv) * uv_per_unit, dpdy(in.uv) * uv_per_unit);
+#else
     var color = textureSample(sprite_texture, sprite_sampler, in.uv);
+#endif
     color = in.color * color;
-    return color;
+#ifdef GRADIENT
+    color = color * gradient_color(in);
+#endif
+    return vec4<f32>(color.rgb, color.a * shape_coverage(in) * clip_coverage(in));
 }
@@ -0,0 +1,612 @@
+use std::{hash::Hash, marker::PhantomData, ops::Range};
+
+use bevy_app::prelude::*;
+use bevy_asset::{AddAsset, AssetEvent, AssetServer, Assets, Handle};
+use bevy_derive::{Deref, DerefMut};
+use bevy_ecs::{
+    prelude::*,
+    system::{
+        lifetimeless::{Read, SRes},
+        SystemParamItem,
+    },
+};
+use bevy_math::{Mat4, Rect, Vec2, Vec3, Vec4Swizzles};
+use bevy_render::{
+    render_asset::{PrepareAssetSet, RenderAssets},
+    render_phase::{
+        AddRenderCommand, DrawFunctions, PhaseItem, RenderCommand, RenderCommandResult,
+        RenderPhase, SetItemPipeline, TrackedRenderPass,
+    },
+    render_resource::*,
+    renderer::{RenderDevice, RenderQueue},
+    texture::{BevyDefault, FallbackImage, Image},
+    view::{ExtractedView, ViewTarget, ViewVisibility},
+    Extract, ExtractSchedule, RenderApp, RenderSet,
+};
+use bevy_transform::components::GlobalTransform;
+use bevy_utils::{FloatOrd, HashMap, HashSet};
+use bytemuck::{Pod, Zeroable};
+
+use crate::{
+    resolve_val, CalculatedClip, ExtractedUiNodes, Node, RenderUiSystem, SetUiViewBindGroup, Style,
+    TransparentUi, UiMaterial, UiMaterialKey, UiPipeline, UiScale, UiStack,
+    UI_MATERIAL_SHADER_HANDLE,
+};
+use bevy_hierarchy::Parent;
+
+/// Adds the necessary ECS resources and render logic to enable rendering UI nodes with the
+/// given [`UiMaterial`] asset type.
+pub struct UiMaterialPlugin<M: UiMaterial>(PhantomData<M>);
+
+impl<M: UiMaterial> Default for UiMaterialPlugin<M> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+impl<M: UiMaterial> Plugin for UiMaterialPlugin<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    fn build(&self, app: &mut App) {
+        app.add_asset::<M>();
+
+        if let Ok(render_app) = app.get_sub_app_mut(RenderApp) {
+            render_app
+                .add_render_command::<TransparentUi, DrawUiMaterial<M>>()
+                .init_resource::<UiMaterialPipeline<M>>()
+                .init_resource::<UiMaterialMeta<M>>()
+                .init_resource::<ExtractedUiMaterialNodes<M>>()
+                .init_resource::<ExtractedUiMaterials<M>>()
+                .init_resource::<RenderUiMaterials<M>>()
+                .init_resource::<SpecializedRenderPipelines<UiMaterialPipeline<M>>>()
+                .add_systems(
+                    (
+                        extract_ui_materials::<M>,
+                        extract_ui_material_nodes::<M>.after(RenderUiSystem::ExtractNode),
+                    )
+                        .in_schedule(ExtractSchedule),
+                )
+                .add_system(
+                    prepare_ui_materials::<M>
+                        .in_set(RenderSet::Prepare)
+                        .after(PrepareAssetSet::PreAssetPrepare),
+                )
+                .add_system(prepare_ui_material_nodes::<M>.in_set(RenderSet::Prepare))
+                .add_system(queue_ui_material_nodes::<M>.in_set(RenderSet::Queue));
+        }
+    }
+}
+
+/// Render pipeline data for a given [`UiMaterial`]
+#[derive(Resource)]
+pub struct UiMaterialPipeline<M: UiMaterial> {
+    pub view_layout: BindGroupLayout,
+    pub ui_material_layout: BindGroupLayout,
+    pub vertex_shader: Option<Handle<Shader>>,
+    pub fragment_shader: Option<Handle<Shader>>,
+    marker: PhantomData<M>,
+}
+
+impl<M: UiMaterial> SpecializedRenderPipeline for UiMaterialPipeline<M>
+where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    type Key = UiMaterialKey<M>;
+
+    fn specialize(&self, key: Self::Key) -> RenderPipelineDescriptor {
+        let vertex_layout = VertexBufferLayout::from_vertex_formats(
+            VertexStepMode::Vertex,
+            vec![
+                // position
+                VertexFormat::Float32x3,
+                // uv
+                VertexFormat::Float32x2,
+                // size
+                VertexFormat::Float32x2,
+                // border_widths
+                VertexFormat::Float32x4,
+            ],
+        );
+
+        let mut descriptor = RenderPipelineDescriptor {
+            vertex: VertexState {
+                shader: self
+                    .vertex_shader
+                    .clone()
+                    .unwrap_or_else(|| UI_MATERIAL_SHADER_HANDLE.typed()),
+                entry_point: "vertex".into(),
+                shader_defs: Vec::new(),
+                buffers: vec![vertex_layout],
+            },
+            fragment: Some(FragmentState {
+                shader: self
+                    .fragment_shader
+                    .clone()
+                    .unwrap_or_else(|| UI_MATERIAL_SHADER_HANDLE.typed()),
+                shader_defs: Vec::new(),
+                entry_point: "fragment".into(),
+                targets: vec![Some(ColorTargetState {
+                    format: if key.hdr {
+                        ViewTarget::TEXTURE_FORMAT_HDR
+                    } else {
+                        TextureFormat::bevy_default()
+                    },
+                    blend: Some(BlendState::ALPHA_BLENDING),
+                    write_mask: ColorWrites::ALL,
+                })],
+            }),
+            layout: vec![self.view_layout.clone(), self.ui_material_layout.clone()],
+            push_constant_ranges: Vec::new(),
+            primitive: PrimitiveState {
+                front_face: FrontFace::Ccw,
+                cull_mode: None,
+                unclipped_depth: false,
+                polygon_mode: PolygonMode::Fill,
+                conservative: false,
+                topology: PrimitiveTopology::TriangleList,
+                strip_index_format: None,
+            },
+            depth_stencil: None,
+            multisample: MultisampleState {
+                count: 1,
+                mask: !0,
+                alpha_to_coverage_enabled: false,
+            },
+            label: Some("ui_material_pipeline".into()),
+        };
+
+        M::specialize(&mut descriptor, key);
+        descriptor
+    }
+}
+
+impl<M: UiMaterial> FromWorld for UiMaterialPipeline<M> {
+    fn from_world(world: &mut World) -> Self {
+        let asset_server = world.resource::<AssetServer>();
+        let render_device = world.resource::<RenderDevice>();
+        let ui_material_layout = M::bind_group_layout(render_device);
+
+        UiMaterialPipeline {
+            // The view is bound like for the other UI nodes
+            view_layout: world.resource::<UiPipeline>().view_layout.clone(),
+            ui_material_layout,
+            vertex_shader: match M::vertex_shader() {
+                ShaderRef::Default => None,
+                ShaderRef::Handle(handle) => Some(handle),
+                ShaderRef::Path(path) => Some(asset_server.load(path)),
+            },
+            fragment_shader: match M::fragment_shader() {
+                ShaderRef::Default => None,
+                ShaderRef::Handle(handle) => Some(handle),
+                ShaderRef::Path(path) => Some(asset_server.load(path)),
+            },
+            marker: PhantomData,
+        }
+    }
+}
+
+type DrawUiMaterial<M> = (
+    SetItemPipeline,
+    SetUiViewBindGroup<0>,
+    SetUiMaterialBindGroup<M, 1>,
+    DrawUiMaterialNode<M>,
+);
+
+pub struct SetUiMaterialBindGroup<M: UiMaterial, const I: usize>(PhantomData<M>);
+impl<P: PhaseItem, M: UiMaterial, const I: usize> RenderCommand<P>
+    for SetUiMaterialBindGroup<M, I>
+{
+    type Param = SRes<RenderUiMaterials<M>>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<UiMaterialBatch<M>>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: &'w UiMaterialBatch<M>,
+        materials: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(material) = materials.into_inner().get(&batch.material) else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_bind_group(I, &material.bind_group, &[]);
+        RenderCommandResult::Success
+    }
+}
+
+pub struct DrawUiMaterialNode<M: UiMaterial>(PhantomData<M>);
+impl<P: PhaseItem, M: UiMaterial> RenderCommand<P> for DrawUiMaterialNode<M> {
+    type Param = SRes<UiMaterialMeta<M>>;
+    type ViewWorldQuery = ();
+    type ItemWorldQuery = Read<UiMaterialBatch<M>>;
+
+    #[inline]
+    fn render<'w>(
+        _item: &P,
+        _view: (),
+        batch: &'w UiMaterialBatch<M>,
+        ui_meta: SystemParamItem<'w, '_, Self::Param>,
+        pass: &mut TrackedRenderPass<'w>,
+    ) -> RenderCommandResult {
+        let Some(vertices) = ui_meta.into_inner().vertices.buffer() else {
+            return RenderCommandResult::Failure;
+        };
+        pass.set_vertex_buffer(0, vertices.slice(..));
+        pass.draw(batch.range.clone(), 0..1);
+        RenderCommandResult::Success
+    }
+}
+
+/// A node drawn with a [`UiMaterial`], extracted to the render world
+pub struct ExtractedUiMaterialNode<M: UiMaterial> {
+    pub stack_index: usize,
+    /// The camera the node is drawn on
+    pub camera_entity: Entity,
+    pub transform: Mat4,
+    pub size: Vec2,
+    /// The widths of the borders of the node: left, top, right and bottom
+    pub border: [f32; 4],
+    pub material: Handle<M>,
+    pub clip: Option<Rect>,
+}
+
+#[derive(Resource)]
+pub struct ExtractedUiMaterialNodes<M: UiMaterial> {
+    pub uinodes: Vec<ExtractedUiMaterialNode<M>>,
+}
+
+impl<M: UiMaterial> Default for ExtractedUiMaterialNodes<M> {
+    fn default() -> Self {
+        Self {
+            uinodes: Default::default(),
+        }
+    }
+}
+
+pub fn extract_ui_material_nodes<M: UiMaterial>(
+    mut extracted_uinodes: ResMut<ExtractedUiMaterialNodes<M>>,
+    mut extracted_other_uinodes: ResMut<ExtractedUiNodes>,
+    ui_stack: Extract<Res<UiStack>>,
+    ui_scale: Extract<Res<UiScale>>,
+    uinode_query: Extract<
+        Query<(
+            &Node,
+            &GlobalTransform,
+            &Style,
+            &Handle<M>,
+            &ViewVisibility,
+            Option<&CalculatedClip>,
+            Option<&Parent>,
+        )>,
+    >,
+    parent_node_query: Extract<Query<&Node>>,
+) {
+    extracted_uinodes.uinodes.clear();
+    for (stack_index, entity) in ui_stack.uinodes.iter().enumerate() {
+        let Ok((uinode, transform, style, material, visibility, clip, parent)) =
+            uinode_query.get(*entity)
+        else {
+            continue;
+        };
+        let (true, Some(camera_entity)) = (visibility.get(), uinode.target_camera()) else {
+            continue;
+        };
+
+        // Percentages of borders are of the width of the parent, like in the layout
+        let parent_width = parent
+            .and_then(|parent| parent_node_query.get(**parent).ok())
+            .map_or(0.0, |parent| parent.size().x);
+        let border = [
+            style.border.left,
+            style.border.top,
+            style.border.right,
+            style.border.bottom,
+        ]
+        .map(|width| resolve_val(width, parent_width, ui_scale.scale));
+
+        extracted_uinodes.uinodes.push(ExtractedUiMaterialNode {
+            stack_index,
+            camera_entity,
+            transform: transform.compute_matrix(),
+            size: uinode.size(),
+            border,
+            material: material.clone_weak(),
+            clip: clip.map(|clip| clip.clip),
+        });
+        // The batches of the other nodes are split around this one, to be drawn in stack order
+        extracted_other_uinodes
+            .material_stack_indices
+            .push(stack_index);
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, Pod, Zeroable)]
+struct UiMaterialVertex {
+    pub position: [f32; 3],
+    pub uv: [f32; 2],
+    pub size: [f32; 2],
+    pub border_widths: [f32; 4],
+}
+
+#[derive(Resource)]
+pub struct UiMaterialMeta<M: UiMaterial> {
+    vertices: BufferVec<UiMaterialVertex>,
+    marker: PhantomData<M>,
+}
+
+impl<M: UiMaterial> Default for UiMaterialMeta<M> {
+    fn default() -> Self {
+        Self {
+            vertices: BufferVec::new(BufferUsages::VERTEX),
+            marker: PhantomData,
+        }
+    }
+}
+
+/// The consecutive nodes of the [`UiStack`] drawn with the same [`UiMaterial`], drawn in a
+/// single draw call
+#[derive(Component)]
+pub struct UiMaterialBatch<M: UiMaterial> {
+    pub range: Range<u32>,
+    pub material: Handle<M>,
+    /// The camera the batch is drawn on
+    pub camera_entity: Entity,
+    /// The stack index of the first node of the batch
+    pub stack_index: usize,
+}
+
+const QUAD_VERTEX_POSITIONS: [Vec3; 4] = [
+    Vec3::new(-0.5, -0.5, 0.0),
+    Vec3::new(0.5, -0.5, 0.0),
+    Vec3::new(0.5, 0.5, 0.0),
+    Vec3::new(-0.5, 0.5, 0.0),
+];
+
+const QUAD_INDICES: [usize; 6] = [0, 2, 3, 0, 1, 2];
+
+pub fn prepare_ui_material_nodes<M: UiMaterial>(
+    mut commands: Commands,
+    render_device: Res<RenderDevice>,
+    render_queue: Res<RenderQueue>,
+    mut ui_meta: ResMut<UiMaterialMeta<M>>,
+    mut extracted_uinodes: ResMut<ExtractedUiMaterialNodes<M>>,
+) {
+    ui_meta.vertices.clear();
+    extracted_uinodes
+        .uinodes
+        .sort_by_key(|node| node.stack_index);
+
+    let mut batch: Option<UiMaterialBatch<M>> = None;
+    let mut last_stack_index = 0;
+    for extracted_uinode in &extracted_uinodes.uinodes {
+        let size = extracted_uinode.size.extend(1.0);
+        let positions = QUAD_VERTEX_POSITIONS
+            .map(|pos| (extracted_uinode.transform * (pos * size).extend(1.)).xyz());
+
+        // Clip the corners like the other nodes, assuming the node isn't rotated
+        let (min, max) = (positions[0].truncate(), positions[2].truncate());
+        let (clipped_min, clipped_max) = match extracted_uinode.clip {
+            Some(clip) => (min.max(clip.min), max.min(clip.max)),
+            None => (min, max),
+        };
+        if clipped_min.x >= clipped_max.x || clipped_min.y >= clipped_max.y {
+            continue;
+        }
+        let uv_min = (clipped_min - min) / (max - min);
+        let uv_max = (clipped_max - min) / (max - min);
+        let corners = [
+            (clipped_min, uv_min),
+            (
+                Vec2::new(clipped_max.x, clipped_min.y),
+                Vec2::new(uv_max.x, uv_min.y),
+            ),
+            (clipped_max, uv_max),
+            (
+                Vec2::new(clipped_min.x, clipped_max.y),
+                Vec2::new(uv_min.x, uv_max.y),
+            ),
+        ];
+
+        // The batches only hold nodes next to each other in the stack, with the same material
+        let start = ui_meta.vertices.len() as u32;
+        let continues_batch = matches!(
+            &batch,
+            Some(batch) if batch.material == extracted_uinode.material
+                && batch.camera_entity == extracted_uinode.camera_entity
+                && extracted_uinode.stack_index == last_stack_index + 1
+        );
+        if !continues_batch {
+            if let Some(batch) = batch.take() {
+                commands.spawn(batch);
+            }
+            batch = Some(UiMaterialBatch {
+                range: start..start,
+                material: extracted_uinode.material.clone_weak(),
+                camera_entity: extracted_uinode.camera_entity,
+                stack_index: extracted_uinode.stack_index,
+            });
+        }
+        for i in QUAD_INDICES {
+            let (position, uv) = corners[i];
+            ui_meta.vertices.push(UiMaterialVertex {
+                position: position.extend(positions[i].z).into(),
+                uv: uv.into(),
+                size: extracted_uinode.size.into(),
+                border_widths: extracted_uinode.border,
+            });
+        }
+        if let Some(batch) = &mut batch {
+            batch.range.end = ui_meta.vertices.len() as u32;
+        }
+        last_stack_index = extracted_uinode.stack_index;
+    }
+    if let Some(batch) = batch {
+        commands.spawn(batch);
+    }
+
+    ui_meta.vertices.write_buffer(&render_device, &render_queue);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn queue_ui_material_nodes<M: UiMaterial>(
+    draw_functions: Res<DrawFunctions<TransparentUi>>,
+    ui_material_pipeline: Res<UiMaterialPipeline<M>>,
+    mut pipelines: ResMut<SpecializedRenderPipelines<UiMaterialPipeline<M>>>,
+    pipeline_cache: Res<PipelineCache>,
+    render_materials: Res<RenderUiMaterials<M>>,
+    ui_batches: Query<(Entity, &UiMaterialBatch<M>)>,
+    mut views: Query<(Entity, &ExtractedView, &mut RenderPhase<TransparentUi>)>,
+) where
+    M::Data: PartialEq + Eq + Hash + Clone,
+{
+    let draw_function = draw_functions.read().id::<DrawUiMaterial<M>>();
+    for (view_entity, view, mut transparent_phase) in &mut views {
+        for (entity, batch) in ui_batches
+            .iter()
+            .filter(|(_, batch)| batch.camera_entity == view_entity)
+        {
+            let Some(material) = render_materials.get(&batch.material) else {
+                continue;
+            };
+            let pipeline = pipelines.specialize(
+                &pipeline_cache,
+                &ui_material_pipeline,
+                UiMaterialKey {
+                    hdr: view.hdr,
+                    bind_group_data: material.key.clone(),
+                },
+            );
+            transparent_phase.add(TransparentUi {
+                draw_function,
+                pipeline,
+                entity,
+                sort_key: (FloatOrd(batch.stack_index as f32), 0),
+            });
+        }
+    }
+}
+
+/// Data prepared for a [`UiMaterial`] instance.
+pub struct PreparedUiMaterial<M: UiMaterial> {
+    pub bindings: Vec<OwnedBindingResource>,
+    pub bind_group: BindGroup,
+    pub key: M::Data,
+}
+
+#[derive(Resource)]
+struct ExtractedUiMaterials<M: UiMaterial> {
+    extracted: Vec<(Handle<M>, M)>,
+    removed: Vec<Handle<M>>,
+}
+
+impl<M: UiMaterial> Default for ExtractedUiMaterials<M> {
+    fn default() -> Self {
+        Self {
+            extracted: Default::default(),
+            removed: Default::default(),
+        }
+    }
+}
+
+/// Stores all prepared representations of [`UiMaterial`] assets for as long as they exist.
+#[derive(Resource, Deref, DerefMut)]
+pub struct RenderUiMaterials<M: UiMaterial>(HashMap<Handle<M>, PreparedUiMaterial<M>>);
+
+impl<M: UiMaterial> Default for RenderUiMaterials<M> {
+    fn default() -> Self {
+        Self(Default::default())
+    }
+}
+
+/// This system extracts all created or modified assets of the corresponding [`UiMaterial`] type
+/// into the "render world".
+fn extract_ui_materials<M: UiMaterial>(
+    mut commands: Commands,
+    mut events: Extract<EventReader<AssetEvent<M>>>,
+    assets: Extract<Res<Assets<M>>>,
+) {
+    let mut changed_assets = HashSet::default();
+    let mut removed = Vec::new();
+    for event in events.iter() {
+        match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                changed_assets.insert(handle.clone_weak());
+            }
+            AssetEvent::Removed { handle } => {
+                changed_assets.remove(handle);
+                removed.push(handle.clone_weak());
+            }
+            AssetEvent::LoadedWithDependencies { .. } => {}
+        }
+    }
+
+    let mut extracted_assets = Vec::new();
+    for handle in changed_assets.drain() {
+        if let Some(asset) = assets.get(&handle) {
+            extracted_assets.push((handle, asset.clone()));
+        }
+    }
+
+    commands.insert_resource(ExtractedUiMaterials {
+        extracted: extracted_assets,
+        removed,
+    });
+}
+
+/// All [`UiMaterial`] values of a given type that should be prepared next frame.
+pub struct PrepareNextFrameUiMaterials<M: UiMaterial> {
+    assets: Vec<(Handle<M>, M)>,
+}
+
+impl<M: UiMaterial> Default for PrepareNextFrameUiMaterials<M> {
+    fn default() -> Self {
+        Self {
+            assets: Default::default(),
+        }
+    }
+}
+
+/// This system prepares all assets of the corresponding [`UiMaterial`] type
+/// which where extracted this frame for the GPU.
+fn prepare_ui_materials<M: UiMaterial>(
+    mut prepare_next_frame: Local<PrepareNextFrameUiMaterials<M>>,
+    mut extracted_assets: ResMut<ExtractedUiMaterials<M>>,
+    mut render_materials: ResMut<RenderUiMaterials<M>>,
+    render_device: Res<RenderDevice>,
+    images: Res<RenderAssets<Image>>,
+    fallback_image: Res<FallbackImage>,
+    pipeline: Res<UiMaterialPipeline<M>>,
+) {
+    let queued_assets = std::mem::take(&mut prepare_next_frame.assets);
+    for removed in std::mem::take(&mut extracted_assets.removed) {
+        render_materials.remove(&removed);
+    }
+
+    let extracted = std::mem::take(&mut extracted_assets.extracted);
+    for (handle, material) in queued_assets.into_iter().chain(extracted) {
+        match material.as_bind_group(
+            &pipeline.ui_material_layout,
+            &render_device,
+            &images,
+            &fallback_image,
+        ) {
+            Ok(prepared) => {
+                render_materials.insert(
+                    handle,
+                    PreparedUiMaterial {
+                        bindings: prepared.bindings,
+                        bind_group: prepared.bind_group,
+                        key: prepared.data,
+                    },
+                );
+            }
+            Err(AsBindGroupError::RetryNextUpdate) => {
+                prepare_next_frame.assets.push((handle, material));
+            }
+        }
+    }
+}
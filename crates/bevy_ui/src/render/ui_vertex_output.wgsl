@@ -0,0 +1,8 @@
+#define_import_path bevy_ui::ui_vertex_output
+
+// The coordinates of the fragment in the node, from 0 at its top left to 1 at its bottom right
+@location(0) uv: vec2<f32>,
+// The size of the node in logical pixels
+@location(1) size: vec2<f32>,
+// The widths of the borders of the node in logical pixels: left, top, right and bottom
+@location(2) border_widths: vec4<f32>,
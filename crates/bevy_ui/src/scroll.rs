@@ -0,0 +1,112 @@
+use crate::{ActiveModal, CalculatedClip, Node, Overflow, ScrollPosition, Style, UiStack};
+use bevy_ecs::{
+    event::EventReader,
+    prelude::With,
+    query::WorldQuery,
+    system::{Query, Res},
+};
+use bevy_input::{
+    mouse::{MouseScrollUnit, MouseWheel},
+    touch::Touches,
+};
+use bevy_math::{Rect, Vec2};
+use bevy_render::view::ViewVisibility;
+use bevy_transform::components::GlobalTransform;
+use bevy_window::{PrimaryWindow, Window};
+
+/// The distance scrolled by a line of the mouse wheel, in logical pixels
+pub const SCROLL_LINE_HEIGHT: f32 = 20.0;
+
+/// Main query for [`ui_scroll_system`]
+#[derive(WorldQuery)]
+#[world_query(mutable)]
+pub struct ScrollNodeQuery {
+    node: &'static Node,
+    global_transform: &'static GlobalTransform,
+    style: &'static Style,
+    scroll_position: &'static mut ScrollPosition,
+    calculated_clip: Option<&'static CalculatedClip>,
+    view_visibility: Option<&'static ViewVisibility>,
+}
+
+/// The system that scrolls the nodes with [`Overflow::Scroll`] under the cursor by the mouse
+/// wheel, and under the touches by dragging them
+///
+/// Only the top node that can scroll in the direction is scrolled, so nested nodes scroll
+/// their parents once their content ends. The nodes outside of the [`ActiveModal`] aren't
+/// scrolled.
+pub fn ui_scroll_system(
+    mut mouse_wheel_events: EventReader<MouseWheel>,
+    touches_input: Res<Touches>,
+    ui_stack: Res<UiStack>,
+    active_modal: Res<ActiveModal>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut node_query: Query<ScrollNodeQuery>,
+) {
+    let cursor_position = windows.get_single().ok().and_then(|window| {
+        window.cursor_position().map(|mut cursor_position| {
+            cursor_position.y = window.height() - cursor_position.y;
+            cursor_position
+        })
+    });
+
+    let mut scrolls = Vec::new();
+    if let Some(cursor_position) = cursor_position {
+        for mouse_wheel_event in mouse_wheel_events.iter() {
+            let delta = Vec2::new(mouse_wheel_event.x, mouse_wheel_event.y);
+            let delta = match mouse_wheel_event.unit {
+                MouseScrollUnit::Line => delta * SCROLL_LINE_HEIGHT,
+                MouseScrollUnit::Pixel => delta,
+            };
+            scrolls.push((cursor_position, delta));
+        }
+    } else {
+        mouse_wheel_events.clear();
+    }
+    // the content follows the touches dragged over it
+    for touch in touches_input.iter() {
+        if touch.delta() != Vec2::ZERO {
+            scrolls.push((touch.previous_position(), touch.delta()));
+        }
+    }
+
+    for (position, delta) in scrolls {
+        // the nodes from the closest to the furthest
+        for entity in ui_stack.uinodes.iter().rev() {
+            let Ok(mut node) = node_query.get_mut(*entity) else {
+                continue;
+            };
+            if node.style.overflow != Overflow::Scroll
+                || !active_modal.allows(*entity)
+                || matches!(node.view_visibility, Some(view_visibility) if !view_visibility.get())
+            {
+                continue;
+            }
+
+            let node_rect = Rect::from_center_size(
+                node.global_transform.translation().truncate(),
+                node.node.size(),
+            );
+            let visible_rect = match node.calculated_clip {
+                Some(clip) => clip.clip.intersect(node_rect),
+                None => node_rect,
+            };
+            if !visible_rect.contains(position) {
+                continue;
+            }
+
+            let max_offset = node.scroll_position.max_offset;
+            // a vertical wheel scrolls the content that only overflows horizontally
+            let delta = if max_offset.y == 0.0 && delta.x == 0.0 {
+                Vec2::new(delta.y, 0.0)
+            } else {
+                delta
+            };
+            let offset = (node.scroll_position.offset - delta).clamp(Vec2::ZERO, max_offset);
+            if offset != node.scroll_position.offset {
+                node.scroll_position.offset = offset;
+                break;
+            }
+        }
+    }
+}
@@ -3,7 +3,7 @@
 use bevy_ecs::prelude::*;
 use bevy_hierarchy::prelude::*;
 
-use crate::{Node, ZIndex};
+use crate::{Node, StackingContext, ZIndex};
 
 /// The current UI stack, which contains all UI nodes ordered by their depth.
 ///
@@ -15,14 +15,14 @@ pub struct UiStack {
 }
 
 #[derive(Default)]
-struct StackingContext {
+struct StackingContextEntries {
     pub entries: Vec<StackingContextEntry>,
 }
 
 struct StackingContextEntry {
     pub z_index: i32,
     pub entity: Entity,
-    pub stack: StackingContext,
+    pub stack: StackingContextEntries,
 }
 
 /// Generates the render stack for UI nodes.
@@ -30,14 +30,16 @@ pub fn ui_stack_system(
     mut ui_stack: ResMut<UiStack>,
     root_node_query: Query<Entity, (With<Node>, Without<Parent>)>,
     zindex_query: Query<&ZIndex, With<Node>>,
+    stacking_context_query: Query<(), (With<StackingContext>, With<Node>)>,
     children_query: Query<&Children>,
 ) {
-    let mut global_context = StackingContext::default();
+    let mut global_context = StackingContextEntries::default();
 
     let mut total_entry_count: usize = 0;
     for entity in &root_node_query {
         insert_context_hierarchy(
             &zindex_query,
+            &stacking_context_query,
             &children_query,
             entity,
             &mut global_context,
@@ -53,26 +55,42 @@ pub fn ui_stack_system(
 
 fn insert_context_hierarchy(
     zindex_query: &Query<&ZIndex, With<Node>>,
+    stacking_context_query: &Query<(), (With<StackingContext>, With<Node>)>,
     children_query: &Query<&Children>,
     entity: Entity,
-    global_context: &mut StackingContext,
-    parent_context: Option<&mut StackingContext>,
+    global_context: &mut StackingContextEntries,
+    parent_context: Option<&mut StackingContextEntries>,
     total_entry_count: &mut usize,
 ) {
-    let mut new_context = StackingContext::default();
+    let mut new_context = StackingContextEntries::default();
     if let Ok(children) = children_query.get(entity) {
         // reserve space for all children. in practice, some may not get pushed.
         new_context.entries.reserve_exact(children.len());
 
+        let is_stacking_context = stacking_context_query.contains(entity);
         for entity in children {
-            insert_context_hierarchy(
-                zindex_query,
-                children_query,
-                *entity,
-                global_context,
-                Some(&mut new_context),
-                total_entry_count,
-            );
+            if is_stacking_context {
+                // the global z-indices of the descendants are relative to this node
+                insert_context_hierarchy(
+                    zindex_query,
+                    stacking_context_query,
+                    children_query,
+                    *entity,
+                    &mut new_context,
+                    None,
+                    total_entry_count,
+                );
+            } else {
+                insert_context_hierarchy(
+                    zindex_query,
+                    stacking_context_query,
+                    children_query,
+                    *entity,
+                    global_context,
+                    Some(&mut new_context),
+                    total_entry_count,
+                );
+            }
         }
     }
 
@@ -90,7 +108,7 @@ fn insert_context_hierarchy(
     });
 }
 
-fn fill_stack_recursively(result: &mut Vec<Entity>, stack: &mut StackingContext) {
+fn fill_stack_recursively(result: &mut Vec<Entity>, stack: &mut StackingContextEntries) {
     // sort entries by ascending z_index, while ensuring that siblings
     // with the same local z_index will keep their ordering.
     stack.entries.sort_by_key(|e| e.z_index);
@@ -111,7 +129,7 @@ mod tests {
     };
     use bevy_hierarchy::BuildChildren;
 
-    use crate::{Node, UiStack, ZIndex};
+    use crate::{Node, StackingContext, UiStack, ZIndex};
 
     use super::ui_stack_system;
 
@@ -215,4 +233,56 @@ mod tests {
         ];
         assert_eq!(actual_result, expected_result);
     }
+
+    #[test]
+    fn test_ui_stack_system_with_stacking_context() {
+        let mut world = World::default();
+        world.init_resource::<UiStack>();
+
+        let mut queue = CommandQueue::default();
+        let mut commands = Commands::new(&mut queue, &world);
+        commands
+            .spawn(node_with_zindex("0", ZIndex::Global(1)))
+            .with_children(|parent| {
+                parent.spawn(node_with_zindex("0-0", ZIndex::Global(3)));
+            });
+
+        commands
+            .spawn((node_with_zindex("1", ZIndex::Global(2)), StackingContext))
+            .with_children(|parent| {
+                parent
+                    .spawn(node_without_zindex("1-0"))
+                    .with_children(|parent| {
+                        parent.spawn(node_with_zindex("1-0-0", ZIndex::Global(4)));
+                        parent.spawn(node_without_zindex("1-0-1"));
+                    });
+                parent.spawn(node_with_zindex("1-1", ZIndex::Local(1)));
+                parent.spawn(node_with_zindex("1-2", ZIndex::Global(-1)));
+            });
+
+        queue.apply(&mut world);
+
+        let mut schedule = Schedule::default();
+        schedule.add_system(ui_stack_system);
+        schedule.run(&mut world);
+
+        let mut query = world.query::<&Label>();
+        let ui_stack = world.resource::<UiStack>();
+        let actual_result = ui_stack
+            .uinodes
+            .iter()
+            .map(|entity| query.get(&world, *entity).unwrap().clone())
+            .collect::<Vec<_>>();
+        let expected_result = vec![
+            (Label("0")),   // ZIndex::Global(1)
+            (Label("1")),   // ZIndex::Global(2)
+            (Label("1-2")), // ZIndex::Global(-1) in the stacking context of "1"
+            (Label("1-0")),
+            (Label("1-0-1")),
+            (Label("1-1")),   // ZIndex::Local(1)
+            (Label("1-0-0")), // ZIndex::Global(4) in the stacking context of "1"
+            (Label("0-0")),   // ZIndex::Global(3)
+        ];
+        assert_eq!(actual_result, expected_result);
+    }
 }
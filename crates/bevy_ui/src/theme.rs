@@ -0,0 +1,587 @@
+use crate::{
+    AlignItems, BackgroundColor, BorderColor, BorderRadius, Display, FlexDirection, Interaction,
+    JustifyContent, Size, Style, UiRect,
+};
+use anyhow::Result;
+#[cfg(feature = "bevy_text")]
+use bevy_asset::AssetServer;
+use bevy_asset::{AssetEvent, AssetLoader, Assets, Handle, LoadContext, LoadedAsset};
+use bevy_ecs::{
+    change_detection::DetectChanges,
+    event::EventReader,
+    prelude::{Component, Ref},
+    reflect::ReflectComponent,
+    system::{Query, Res, Resource},
+};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect, TypeUuid};
+use bevy_render::color::Color;
+#[cfg(feature = "bevy_text")]
+use bevy_text::Text;
+use bevy_utils::{BoxedFuture, HashMap};
+use serde::{Deserialize, Serialize};
+
+/// The style classes of a node, separated by whitespace like the classes of an HTML element,
+/// styled by the [`UiTheme`]
+///
+/// The properties set by the classes override the ones the node was spawned with, and the
+/// classes are applied in order, so the last one wins. The properties a class doesn't set are
+/// left as they are, so a property the theme stops setting keeps its last themed value.
+///
+/// While the [`Interaction`] of the node is [`Interaction::Hovered`], the `name:hovered` classes
+/// of its class names are applied after them, and the `name:pressed` ones after those while it's
+/// [`Interaction::Clicked`], like the `:hover` and `:active` selectors of CSS. A pressed node is
+/// still hovered.
+#[derive(Component, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct Class(pub String);
+
+impl Class {
+    /// The classes named in `names`, separated by whitespace
+    pub fn new(names: impl Into<String>) -> Self {
+        Self(names.into())
+    }
+
+    /// The names of the classes, in order
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.0.split_whitespace()
+    }
+}
+
+/// The values set on the nodes with a [`Class`], from the [`Theme`] of its name
+///
+/// Every value is optional, and only overrides the property of the node when it's set.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeClass {
+    /// Overrides [`Style::display`]
+    pub display: Option<Display>,
+    /// Overrides [`Style::flex_direction`]
+    pub flex_direction: Option<FlexDirection>,
+    /// Overrides [`Style::align_items`]
+    pub align_items: Option<AlignItems>,
+    /// Overrides [`Style::justify_content`]
+    pub justify_content: Option<JustifyContent>,
+    /// Overrides [`Style::size`]
+    pub size: Option<Size>,
+    /// Overrides [`Style::margin`]
+    pub margin: Option<UiRect>,
+    /// Overrides [`Style::padding`]
+    pub padding: Option<UiRect>,
+    /// Overrides [`Style::border`]
+    pub border: Option<UiRect>,
+    /// Overrides [`Style::gap`]
+    pub gap: Option<Size>,
+    /// Overrides the [`BackgroundColor`] of the node
+    pub background_color: Option<Color>,
+    /// Overrides every edge of the [`BorderColor`] of the node
+    pub border_color: Option<Color>,
+    /// Overrides the [`BorderRadius`] of the node
+    pub border_radius: Option<BorderRadius>,
+    /// Overrides the color of every section of the text of the node
+    pub text_color: Option<Color>,
+    /// Overrides the font of every section of the text of the node, as the path of the font
+    /// asset
+    pub font: Option<String>,
+    /// Overrides the font size of every section of the text of the node
+    pub font_size: Option<f32>,
+}
+
+impl ThemeClass {
+    /// Sets the properties of `style` set by this class
+    pub fn apply_style(&self, style: &mut Style) {
+        fn set<T: Copy>(property: &mut T, value: Option<T>) {
+            if let Some(value) = value {
+                *property = value;
+            }
+        }
+        set(&mut style.display, self.display);
+        set(&mut style.flex_direction, self.flex_direction);
+        set(&mut style.align_items, self.align_items);
+        set(&mut style.justify_content, self.justify_content);
+        set(&mut style.size, self.size);
+        set(&mut style.margin, self.margin);
+        set(&mut style.padding, self.padding);
+        set(&mut style.border, self.border);
+        set(&mut style.gap, self.gap);
+    }
+}
+
+/// A stylesheet, mapping the names of [`Class`]es to the values they set
+///
+/// Themes are assets, loaded from `.theme.ron` files with the [`ThemeLoader`], and reapplied to
+/// the nodes when they're reloaded.
+///
+/// The `:hovered` and `:pressed` classes should only set properties their base class sets too,
+/// for the base value to come back once the node isn't hovered or pressed anymore.
+///
+/// ```ron
+/// (
+///     classes: {
+///         "danger-button": (
+///             background_color: Some(Rgba(red: 0.8, green: 0.1, blue: 0.1, alpha: 1.0)),
+///             padding: Some((left: Px(12.0), right: Px(12.0), top: Px(6.0), bottom: Px(6.0))),
+///         ),
+///         "danger-button:hovered": (
+///             background_color: Some(Rgba(red: 1.0, green: 0.2, blue: 0.2, alpha: 1.0)),
+///         ),
+///         "title": (
+///             font: Some("fonts/FiraSans-Bold.ttf"),
+///             font_size: Some(40.0),
+///         ),
+///     },
+/// )
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize, TypeUuid)]
+#[uuid = "2f4c8b1e-7a3d-4e9b-b6c5-1d0e8f7a9c23"]
+#[serde(default)]
+pub struct Theme {
+    /// The classes of the theme, by name
+    pub classes: HashMap<String, ThemeClass>,
+}
+
+impl Theme {
+    /// The classes of this theme named by `class`, in the order they're applied to a node with
+    /// this `interaction`: the classes of the names, then their `:hovered` classes and then their
+    /// `:pressed` classes
+    pub fn classes_of<'a>(
+        &'a self,
+        class: &'a Class,
+        interaction: Interaction,
+    ) -> impl Iterator<Item = &'a ThemeClass> {
+        let states: &[&str] = match interaction {
+            Interaction::None => &[""],
+            Interaction::Hovered => &["", ":hovered"],
+            Interaction::Clicked => &["", ":hovered", ":pressed"],
+        };
+        states.iter().flat_map(move |state| {
+            class.names().filter_map(move |name| {
+                if state.is_empty() {
+                    self.classes.get(name)
+                } else {
+                    self.classes.get(&format!("{name}{state}"))
+                }
+            })
+        })
+    }
+}
+
+/// Loads [`Theme`]s from RON files.
+#[derive(Default)]
+pub struct ThemeLoader;
+
+impl AssetLoader for ThemeLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<()>> {
+        Box::pin(async move {
+            let theme = ron::de::from_bytes::<Theme>(bytes)?;
+            load_context.set_default_asset(LoadedAsset::new(theme));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["theme.ron"]
+    }
+}
+
+/// The [`Theme`] styling the nodes with a [`Class`]
+///
+/// Replacing it restyles every node with a [`Class`].
+#[derive(Resource, Clone, Debug, Default)]
+pub struct UiTheme(pub Handle<Theme>);
+
+/// Whether every node with a [`Class`] should be restyled, because the [`UiTheme`] was replaced,
+/// or its [`Theme`] was loaded or reloaded
+pub(crate) fn theme_changed(
+    ui_theme: &Res<UiTheme>,
+    theme_events: &mut EventReader<AssetEvent<Theme>>,
+) -> bool {
+    // Every event is read, to not see them again next frame
+    theme_events
+        .iter()
+        .fold(ui_theme.is_changed(), |changed, event| match event {
+            AssetEvent::Created { handle } | AssetEvent::Modified { handle } => {
+                changed || *handle == ui_theme.0
+            }
+            AssetEvent::Removed { .. } | AssetEvent::LoadedWithDependencies { .. } => changed,
+        })
+}
+
+/// The system that sets the [`Style`], the colors and the [`BorderRadius`] of the nodes to the
+/// values of their [`Class`] in the [`UiTheme`]
+///
+/// The nodes are restyled when their [`Class`] or their [`Interaction`] changes, and all at once
+/// when the theme changes.
+pub fn theme_system(
+    ui_theme: Res<UiTheme>,
+    themes: Res<Assets<Theme>>,
+    mut theme_events: EventReader<AssetEvent<Theme>>,
+    mut node_query: Query<(
+        Ref<Class>,
+        Option<Ref<Interaction>>,
+        &mut Style,
+        Option<&mut BackgroundColor>,
+        Option<&mut BorderColor>,
+        Option<&mut BorderRadius>,
+    )>,
+) {
+    let theme_changed = theme_changed(&ui_theme, &mut theme_events);
+    let Some(theme) = themes.get(&ui_theme.0) else {
+        return;
+    };
+    for (
+        class,
+        interaction,
+        mut style,
+        mut background_color,
+        mut border_color,
+        mut border_radius,
+    ) in &mut node_query
+    {
+        let interaction_changed =
+            matches!(&interaction, Some(interaction) if interaction.is_changed());
+        if !theme_changed && !class.is_changed() && !interaction_changed {
+            continue;
+        }
+        let interaction = interaction.map_or(Interaction::None, |interaction| *interaction);
+        for theme_class in theme.classes_of(&class, interaction) {
+            theme_class.apply_style(&mut style);
+            if let (Some(background_color), Some(color)) =
+                (background_color.as_mut(), theme_class.background_color)
+            {
+                background_color.0 = color;
+            }
+            if let (Some(border_color), Some(color)) =
+                (border_color.as_mut(), theme_class.border_color)
+            {
+                **border_color = BorderColor::all(color);
+            }
+            if let (Some(border_radius), Some(radius)) =
+                (border_radius.as_mut(), theme_class.border_radius)
+            {
+                **border_radius = radius;
+            }
+        }
+    }
+}
+
+/// The system that sets the fonts, sizes and colors of the texts of the nodes to the values of
+/// their [`Class`] in the [`UiTheme`]
+#[cfg(feature = "bevy_text")]
+pub fn theme_text_system(
+    ui_theme: Res<UiTheme>,
+    themes: Res<Assets<Theme>>,
+    asset_server: Res<AssetServer>,
+    mut theme_events: EventReader<AssetEvent<Theme>>,
+    mut text_query: Query<(Ref<Class>, Option<Ref<Interaction>>, &mut Text)>,
+) {
+    let theme_changed = theme_changed(&ui_theme, &mut theme_events);
+    let Some(theme) = themes.get(&ui_theme.0) else {
+        return;
+    };
+    for (class, interaction, mut text) in &mut text_query {
+        let interaction_changed =
+            matches!(&interaction, Some(interaction) if interaction.is_changed());
+        if !theme_changed && !class.is_changed() && !interaction_changed {
+            continue;
+        }
+        let interaction = interaction.map_or(Interaction::None, |interaction| *interaction);
+        for theme_class in theme.classes_of(&class, interaction) {
+            let font = theme_class
+                .font
+                .as_ref()
+                .map(|path| asset_server.load(path.as_str()));
+            for section in &mut text.sections {
+                if let Some(font) = &font {
+                    section.style.font = font.clone();
+                }
+                if let Some(font_size) = theme_class.font_size {
+                    section.style.font_size = font_size;
+                }
+                if let Some(color) = theme_class.text_color {
+                    section.style.color = color;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Val;
+    use bevy_app::App;
+    use bevy_asset::{AddAsset, AssetPlugin};
+    use bevy_ecs::entity::Entity;
+
+    fn background(color: Color) -> ThemeClass {
+        ThemeClass {
+            background_color: Some(color),
+            ..Default::default()
+        }
+    }
+
+    fn theme(classes: impl IntoIterator<Item = (&'static str, ThemeClass)>) -> Theme {
+        Theme {
+            classes: classes
+                .into_iter()
+                .map(|(name, class)| (name.to_string(), class))
+                .collect(),
+        }
+    }
+
+    /// A theme where the buttons are gray, lighter when hovered and darker when pressed
+    fn button_theme() -> Theme {
+        theme([
+            (
+                "button",
+                ThemeClass {
+                    background_color: Some(Color::GRAY),
+                    padding: Some(UiRect::all(Val::Px(10.0))),
+                    ..Default::default()
+                },
+            ),
+            ("button:hovered", background(Color::SILVER)),
+            ("button:pressed", background(Color::DARK_GRAY)),
+            ("danger", background(Color::RED)),
+            ("danger:hovered", background(Color::ORANGE_RED)),
+            (
+                "wide",
+                ThemeClass {
+                    size: Some(Size::width(Val::Px(300.0))),
+                    ..Default::default()
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn classes_in_order() {
+        let theme = button_theme();
+        let backgrounds = |names: &str, interaction| -> Vec<Option<Color>> {
+            theme
+                .classes_of(&Class::new(names), interaction)
+                .map(|class| class.background_color)
+                .collect()
+        };
+        let (gray, silver, dark_gray, red, orange_red) = (
+            Some(Color::GRAY),
+            Some(Color::SILVER),
+            Some(Color::DARK_GRAY),
+            Some(Color::RED),
+            Some(Color::ORANGE_RED),
+        );
+        // (class names, interaction, classes by their background)
+        let cases = [
+            ("button", Interaction::None, vec![gray]),
+            ("button danger", Interaction::None, vec![gray, red]),
+            ("danger button", Interaction::None, vec![red, gray]),
+            (
+                "  button\tmissing   danger ",
+                Interaction::None,
+                vec![gray, red],
+            ),
+            ("", Interaction::None, vec![]),
+            ("button", Interaction::Hovered, vec![gray, silver]),
+            (
+                "button",
+                Interaction::Clicked,
+                vec![gray, silver, dark_gray],
+            ),
+            // the states come after all the base classes
+            (
+                "button danger",
+                Interaction::Hovered,
+                vec![gray, red, silver, orange_red],
+            ),
+            (
+                "button danger",
+                Interaction::Clicked,
+                vec![gray, red, silver, orange_red, dark_gray],
+            ),
+            ("wide", Interaction::Clicked, vec![None]),
+            // the states aren't class names of their own
+            ("button:hovered", Interaction::None, vec![silver]),
+        ];
+        for (names, interaction, expected) in cases {
+            assert_eq!(
+                backgrounds(names, interaction),
+                expected,
+                "{names:?} {interaction:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn styles_set_their_properties_only() {
+        let mut style = Style {
+            display: Display::None,
+            size: Size::width(Val::Px(10.0)),
+            ..Default::default()
+        };
+        ThemeClass {
+            size: Some(Size::height(Val::Px(20.0))),
+            padding: Some(UiRect::all(Val::Px(5.0))),
+            ..Default::default()
+        }
+        .apply_style(&mut style);
+        assert_eq!(style.display, Display::None);
+        assert_eq!(style.size, Size::height(Val::Px(20.0)));
+        assert_eq!(style.padding, UiRect::all(Val::Px(5.0)));
+        assert_eq!(style.margin, Style::default().margin);
+    }
+
+    /// Nodes styled by running the [`theme_system`] with a [`UiTheme`]
+    struct Scene {
+        app: App,
+    }
+
+    impl Scene {
+        fn new(theme: Theme) -> Self {
+            let mut app = App::new();
+            app.add_plugin(AssetPlugin::default())
+                .add_asset::<Theme>()
+                .add_system(theme_system);
+            let handle = app.world.resource_mut::<Assets<Theme>>().add(theme);
+            app.insert_resource(UiTheme(handle));
+            Self { app }
+        }
+
+        fn spawn(&mut self, names: &str, interaction: Interaction) -> Entity {
+            self.app
+                .world
+                .spawn((
+                    Class::new(names),
+                    interaction,
+                    Style::default(),
+                    BackgroundColor(Color::WHITE),
+                ))
+                .id()
+        }
+
+        fn background(&self, entity: Entity) -> Color {
+            self.app.world.get::<BackgroundColor>(entity).unwrap().0
+        }
+
+        fn set_interaction(&mut self, entity: Entity, interaction: Interaction) {
+            *self.app.world.get_mut::<Interaction>(entity).unwrap() = interaction;
+            self.app.update();
+        }
+    }
+
+    #[test]
+    fn nodes_are_styled_by_their_classes() {
+        let mut scene = Scene::new(button_theme());
+        let button = scene.spawn("button", Interaction::None);
+        let danger = scene.spawn("button danger", Interaction::None);
+        let unknown = scene.spawn("unknown", Interaction::None);
+        scene.app.update();
+        assert_eq!(scene.background(button), Color::GRAY);
+        assert_eq!(
+            scene.app.world.get::<Style>(button).unwrap().padding,
+            UiRect::all(Val::Px(10.0))
+        );
+        assert_eq!(scene.background(danger), Color::RED);
+        assert_eq!(scene.background(unknown), Color::WHITE);
+
+        // the nodes are restyled when their class changes
+        scene.app.world.get_mut::<Class>(button).unwrap().0 = "danger".to_string();
+        scene.app.update();
+        assert_eq!(scene.background(button), Color::RED);
+        // and only then
+        scene
+            .app
+            .world
+            .get_mut::<BackgroundColor>(danger)
+            .unwrap()
+            .0 = Color::BLUE;
+        scene.app.update();
+        assert_eq!(scene.background(danger), Color::BLUE);
+    }
+
+    #[test]
+    fn hovered_and_pressed_nodes_are_restyled() {
+        let mut scene = Scene::new(button_theme());
+        let button = scene.spawn("button", Interaction::None);
+        let danger = scene.spawn("button danger", Interaction::None);
+        scene.app.update();
+
+        scene.set_interaction(button, Interaction::Hovered);
+        assert_eq!(scene.background(button), Color::SILVER);
+        scene.set_interaction(button, Interaction::Clicked);
+        assert_eq!(scene.background(button), Color::DARK_GRAY);
+        scene.set_interaction(button, Interaction::Hovered);
+        assert_eq!(scene.background(button), Color::SILVER);
+        scene.set_interaction(button, Interaction::None);
+        assert_eq!(scene.background(button), Color::GRAY);
+
+        // the state of the last class wins
+        scene.set_interaction(danger, Interaction::Hovered);
+        assert_eq!(scene.background(danger), Color::ORANGE_RED);
+        // without a state class of its own, the states of the other classes apply
+        scene.set_interaction(danger, Interaction::Clicked);
+        assert_eq!(scene.background(danger), Color::DARK_GRAY);
+        assert_eq!(scene.background(button), Color::GRAY);
+    }
+
+    #[test]
+    fn nodes_are_restyled_when_the_theme_changes() {
+        let mut scene = Scene::new(button_theme());
+        let button = scene.spawn("button", Interaction::None);
+        let hovered = scene.spawn("button", Interaction::Hovered);
+        scene.app.update();
+
+        // replacing the theme
+        let dark = theme([
+            ("button", background(Color::BLACK)),
+            ("button:hovered", background(Color::MIDNIGHT_BLUE)),
+        ]);
+        let handle = scene.app.world.resource_mut::<Assets<Theme>>().add(dark);
+        scene.app.insert_resource(UiTheme(handle.clone()));
+        scene.app.update();
+        assert_eq!(scene.background(button), Color::BLACK);
+        assert_eq!(scene.background(hovered), Color::MIDNIGHT_BLUE);
+
+        // editing it, like when it's reloaded
+        scene
+            .app
+            .world
+            .resource_mut::<Assets<Theme>>()
+            .get_mut(&handle)
+            .unwrap()
+            .classes
+            .insert("button".to_string(), background(Color::NAVY));
+        scene.app.update();
+        scene.app.update();
+        assert_eq!(scene.background(button), Color::NAVY);
+        assert_eq!(scene.background(hovered), Color::MIDNIGHT_BLUE);
+
+        // editing another theme doesn't restyle the nodes
+        let light = scene
+            .app
+            .world
+            .resource_mut::<Assets<Theme>>()
+            .add(button_theme());
+        scene.app.update();
+        scene
+            .app
+            .world
+            .get_mut::<BackgroundColor>(button)
+            .unwrap()
+            .0 = Color::WHITE;
+        scene
+            .app
+            .world
+            .resource_mut::<Assets<Theme>>()
+            .get_mut(&light)
+            .unwrap()
+            .classes
+            .clear();
+        scene.app.update();
+        scene.app.update();
+        assert_eq!(scene.background(button), Color::WHITE);
+    }
+}
@@ -0,0 +1,719 @@
+use crate::{Display, NavigationFocus, Node, PointerId, Style, UiPointerMap};
+use bevy_ecs::{
+    entity::{Entity, EntityMap, MapEntities, MapEntitiesError},
+    prelude::{Component, With, Without},
+    reflect::{ReflectComponent, ReflectMapEntities},
+    system::{Query, Res, ResMut, Resource},
+    world::{FromWorld, World},
+};
+use bevy_hierarchy::{Children, HierarchyQueryExt};
+use bevy_input::{mouse::MouseButton, touch::Touches, Input};
+use bevy_math::{Rect, Vec2, Vec3A};
+use bevy_reflect::{FromReflect, Reflect, ReflectDeserialize, ReflectSerialize};
+use bevy_render::camera::Camera;
+use bevy_time::Time;
+use bevy_transform::components::GlobalTransform;
+use bevy_window::{PrimaryWindow, Window};
+use serde::{Deserialize, Serialize};
+
+/// Shows another UI node as the tooltip of this node, once the mouse rests over it or once it's
+/// focused by the navigation
+///
+/// The [`content`](Self::content) can be any UI tree, like a single
+/// [`TextBundle`](crate::node_bundles::TextBundle) for a simple text. It should be a root node
+/// spawned with a [`Display::None`], so it's hidden until the tooltip is shown, and usually has a
+/// [`PositionType::Absolute`](crate::PositionType::Absolute) and a global
+/// [`ZIndex`](crate::ZIndex) to be drawn over the rest of the UI.
+///
+/// The content is displayed by [`ui_tooltip_system`] after the [`delay`](Self::delay), and
+/// placed next to the node or the mouse by [`tooltip_placement_system`], on the other side when
+/// it would go past the edges of the viewport. It's hidden when the mouse leaves the node or
+/// presses a button, when the node loses the focus of the navigation, or when the window loses the
+/// focus.
+///
+/// The content is only shown on the screen: nothing describes the node with it to assistive
+/// technologies, which would need an accessibility tree (like the one of `accesskit`) the engine
+/// doesn't have yet. The text of a tooltip isn't read by screen readers.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, MapEntities, PartialEq)]
+pub struct Tooltip {
+    /// The node shown as the tooltip
+    pub content: Entity,
+    /// The seconds the mouse has to rest over the node, or the node has to be focused, before
+    /// the tooltip is shown
+    pub delay: f32,
+    /// Where the tooltip is shown, when it's inside the viewport
+    pub placement: TooltipPlacement,
+    /// The space between the tooltip and the node or the mouse, in logical pixels
+    pub gap: f32,
+}
+
+impl Tooltip {
+    /// The default [`delay`](Self::delay), in seconds
+    pub const DEFAULT_DELAY: f32 = 0.5;
+    /// The default [`gap`](Self::gap), in logical pixels
+    pub const DEFAULT_GAP: f32 = 8.0;
+
+    /// A tooltip showing `content` under the mouse after the default delay
+    pub const fn new(content: Entity) -> Self {
+        Self {
+            content,
+            delay: Self::DEFAULT_DELAY,
+            placement: TooltipPlacement::DEFAULT,
+            gap: Self::DEFAULT_GAP,
+        }
+    }
+
+    /// Sets the [`delay`](Self::delay) before the tooltip is shown, in seconds
+    pub const fn with_delay(mut self, delay: f32) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    /// Sets where the tooltip is shown
+    pub const fn with_placement(mut self, placement: TooltipPlacement) -> Self {
+        self.placement = placement;
+        self
+    }
+
+    /// Sets the [`gap`](Self::gap) between the tooltip and the node or the mouse, in logical
+    /// pixels
+    pub const fn with_gap(mut self, gap: f32) -> Self {
+        self.gap = gap;
+        self
+    }
+}
+
+// Like for `Parent`, the tooltip can only be deserialized by patching an instance holding a
+// placeholder entity.
+impl FromWorld for Tooltip {
+    fn from_world(_world: &mut World) -> Self {
+        Tooltip::new(Entity::PLACEHOLDER)
+    }
+}
+
+impl MapEntities for Tooltip {
+    fn map_entities(&mut self, entity_map: &EntityMap) -> Result<(), MapEntitiesError> {
+        if let Ok(mapped_entity) = entity_map.get(self.content) {
+            self.content = mapped_entity;
+        }
+        Ok(())
+    }
+}
+
+/// Where a [`Tooltip`] is shown
+///
+/// When the tooltip would go past an edge of the viewport, it's moved to the other side of the
+/// node or the mouse if there's more room there, and then kept inside the viewport.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect, FromReflect)]
+#[reflect(PartialEq, Serialize, Deserialize)]
+pub enum TooltipPlacement {
+    /// Under the mouse, starting from it towards the right, or under the node when it's focused
+    /// by the navigation
+    Cursor,
+    /// Centered above the node
+    Above,
+    /// Centered under the node
+    Below,
+    /// Centered on the left of the node
+    Left,
+    /// Centered on the right of the node
+    Right,
+}
+
+impl TooltipPlacement {
+    pub const DEFAULT: Self = Self::Cursor;
+}
+
+impl Default for TooltipPlacement {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The [`Tooltip`] waiting to be shown or shown, updated by [`ui_tooltip_system`]
+#[derive(Resource, Default, Debug)]
+pub struct ActiveTooltip {
+    /// The node with the tooltip
+    target: Option<Entity>,
+    /// The mouse position while the target is hovered, or `None` while it's focused
+    cursor: Option<Vec2>,
+    /// The seconds since the mouse stopped over the target, or since it was focused
+    elapsed: f32,
+    /// The content displayed, kept to be hidden even once the target is despawned
+    shown: Option<Entity>,
+    /// Whether the tooltip was hidden by a button, until the target changes
+    dismissed: bool,
+}
+
+impl ActiveTooltip {
+    /// The node whose tooltip is shown, if any
+    pub fn shown(&self) -> Option<Entity> {
+        self.target.filter(|_| self.shown.is_some())
+    }
+}
+
+/// Sets the display of the content of a tooltip, if it changed
+fn display_content(style_query: &mut Query<&mut Style>, content: Entity, display: Display) {
+    if let Ok(mut style) = style_query.get_mut(content) {
+        if style.display != display {
+            style.display = display;
+        }
+    }
+}
+
+/// The system that shows the [`Tooltip`] of the node under the mouse, or else of the node
+/// focused by the navigation, after its delay, and hides it
+///
+/// The delay starts again each time the mouse moves over the node, so the tooltips of the
+/// nodes the mouse only passes over aren't shown.
+#[allow(clippy::too_many_arguments)]
+pub fn ui_tooltip_system(
+    mut active_tooltip: ResMut<ActiveTooltip>,
+    time: Res<Time>,
+    pointer_map: Res<UiPointerMap>,
+    navigation_focus: Res<NavigationFocus>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    touches_input: Res<Touches>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    tooltip_query: Query<&Tooltip>,
+    mut style_query: Query<&mut Style>,
+) {
+    let window_focused = matches!(windows.get_single(), Ok(window) if window.focused);
+    // The top node with a tooltip under the mouse, or else the focused node with a tooltip
+    let hovered = pointer_map.get(PointerId::Mouse).and_then(|hits| {
+        hits.nodes
+            .iter()
+            .find(|entity| tooltip_query.contains(**entity))
+            .map(|entity| (*entity, Some(hits.position)))
+    });
+    let target = hovered
+        .or_else(|| {
+            navigation_focus
+                .focused()
+                .filter(|entity| tooltip_query.contains(*entity))
+                .map(|entity| (entity, None))
+        })
+        .filter(|_| window_focused);
+
+    if target.map(|(entity, _)| entity) != active_tooltip.target {
+        if let Some(content) = active_tooltip.shown {
+            display_content(&mut style_query, content, Display::None);
+        }
+        *active_tooltip = ActiveTooltip {
+            target: target.map(|(entity, _)| entity),
+            cursor: target.and_then(|(_, cursor)| cursor),
+            ..Default::default()
+        };
+        return;
+    }
+    let Some((entity, cursor)) = target else {
+        return;
+    };
+    let Ok(tooltip) = tooltip_query.get(entity) else {
+        return;
+    };
+
+    let pressed = mouse_button_input.get_just_pressed().next().is_some()
+        || touches_input.iter_just_pressed().next().is_some();
+    if pressed {
+        active_tooltip.dismissed = true;
+    }
+    if active_tooltip.dismissed {
+        if let Some(content) = active_tooltip.shown.take() {
+            display_content(&mut style_query, content, Display::None);
+        }
+        return;
+    }
+    if active_tooltip.shown.is_some() {
+        return;
+    }
+
+    // The tooltip only shows up once the mouse rests over the node
+    if cursor != active_tooltip.cursor {
+        active_tooltip.cursor = cursor;
+        active_tooltip.elapsed = 0.0;
+    } else {
+        active_tooltip.elapsed += time.delta_seconds();
+    }
+    if active_tooltip.elapsed >= tooltip.delay {
+        active_tooltip.shown = Some(tooltip.content);
+        display_content(&mut style_query, tooltip.content, Display::Flex);
+    }
+}
+
+/// The center of a tooltip of `size`, placed next to the `target` rect or the `cursor` inside the
+/// `viewport`
+fn place_tooltip(
+    placement: TooltipPlacement,
+    target: Rect,
+    cursor: Option<Vec2>,
+    size: Vec2,
+    gap: f32,
+    viewport: Rect,
+) -> Vec2 {
+    let half_size = size / 2.0;
+    let center = target.center();
+    let below = Vec2::new(center.x, target.max.y + gap + half_size.y);
+    let above = Vec2::new(center.x, target.min.y - gap - half_size.y);
+    let right = Vec2::new(target.max.x + gap + half_size.x, center.y);
+    let left = Vec2::new(target.min.x - gap - half_size.x, center.y);
+    // The center on the side of the placement and on the other side, and whether they're
+    // above each other
+    let (preferred, other, vertical) = match (placement, cursor) {
+        (TooltipPlacement::Cursor, Some(cursor)) => {
+            let x = cursor.x + half_size.x;
+            (
+                Vec2::new(x, cursor.y + gap + half_size.y),
+                Vec2::new(x, cursor.y - gap - half_size.y),
+                true,
+            )
+        }
+        (TooltipPlacement::Cursor | TooltipPlacement::Below, _) => (below, above, true),
+        (TooltipPlacement::Above, _) => (above, below, true),
+        (TooltipPlacement::Right, _) => (right, left, false),
+        (TooltipPlacement::Left, _) => (left, right, false),
+    };
+
+    // How far the tooltip goes past the edges of the viewport, across the side it's on
+    let overflow = |center: Vec2| {
+        let rect = Rect::from_center_size(center, size);
+        let overflow =
+            (viewport.min - rect.min).max(Vec2::ZERO) + (rect.max - viewport.max).max(Vec2::ZERO);
+        if vertical {
+            overflow.y
+        } else {
+            overflow.x
+        }
+    };
+    let center = if overflow(other) < overflow(preferred) {
+        other
+    } else {
+        preferred
+    };
+    center.clamp(
+        viewport.min + half_size,
+        (viewport.max - half_size).max(viewport.min + half_size),
+    )
+}
+
+/// The system that moves the content of the shown [`Tooltip`] next to its node or the mouse
+///
+/// Like [`world_anchor_system`](crate::world_anchor_system), it runs after the layout and the
+/// propagation of the transforms, to place the content with its size of this frame, and moves
+/// the [`GlobalTransform`] of the content and its descendants.
+pub fn tooltip_placement_system(
+    active_tooltip: Res<ActiveTooltip>,
+    cameras: Query<&Camera, Without<Node>>,
+    tooltip_query: Query<&Tooltip>,
+    mut node_query: Query<(&Node, &mut GlobalTransform)>,
+    children_query: Query<&Children>,
+) {
+    let Some(target) = active_tooltip.shown() else {
+        return;
+    };
+    let (Ok(tooltip), Ok((target_node, target_transform))) =
+        (tooltip_query.get(target), node_query.get(target))
+    else {
+        return;
+    };
+    let target_rect = Rect::from_center_size(
+        target_transform.translation().truncate(),
+        target_node.size(),
+    );
+    let Ok((content_node, content_transform)) = node_query.get(tooltip.content) else {
+        return;
+    };
+    // The nodes are laid out from the top left of the viewport of their camera
+    let Some(viewport) = content_node
+        .target_camera()
+        .and_then(|camera| cameras.get(camera).ok())
+        .and_then(|camera| camera.logical_viewport_size())
+        .map(|size| Rect::from_corners(Vec2::ZERO, size))
+    else {
+        return;
+    };
+    let center = place_tooltip(
+        tooltip.placement,
+        target_rect,
+        active_tooltip.cursor,
+        content_node.size(),
+        tooltip.gap,
+        viewport,
+    );
+
+    let delta = center - content_transform.translation().truncate();
+    if delta == Vec2::ZERO {
+        return;
+    }
+    for entity in
+        std::iter::once(tooltip.content).chain(children_query.iter_descendants(tooltip.content))
+    {
+        if let Ok((_, mut global_transform)) = node_query.get_mut(entity) {
+            let mut affine = global_transform.affine();
+            affine.translation += Vec3A::from(delta.extend(0.0));
+            *global_transform = GlobalTransform::from(affine);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::PointerHits;
+    use bevy_ecs::schedule::Schedule;
+    use bevy_utils::{Duration, Instant};
+
+    #[test]
+    fn placements() {
+        let viewport = Rect::new(0.0, 0.0, 200.0, 100.0);
+        let target = Rect::new(80.0, 40.0, 120.0, 60.0);
+        let size = Vec2::new(20.0, 10.0);
+        // (placement, target, cursor, size, center)
+        let cases = [
+            (
+                TooltipPlacement::Below,
+                target,
+                None,
+                size,
+                Vec2::new(100.0, 68.0),
+            ),
+            (
+                TooltipPlacement::Above,
+                target,
+                None,
+                size,
+                Vec2::new(100.0, 32.0),
+            ),
+            (
+                TooltipPlacement::Left,
+                target,
+                None,
+                size,
+                Vec2::new(67.0, 50.0),
+            ),
+            (
+                TooltipPlacement::Right,
+                target,
+                None,
+                size,
+                Vec2::new(133.0, 50.0),
+            ),
+            (
+                TooltipPlacement::Cursor,
+                target,
+                Some(Vec2::new(90.0, 45.0)),
+                size,
+                Vec2::new(100.0, 53.0),
+            ),
+            // focused by the navigation, without a cursor
+            (
+                TooltipPlacement::Cursor,
+                target,
+                None,
+                size,
+                Vec2::new(100.0, 68.0),
+            ),
+            // moved to the other side when there's more room there
+            (
+                TooltipPlacement::Below,
+                Rect::new(80.0, 80.0, 120.0, 95.0),
+                None,
+                size,
+                Vec2::new(100.0, 72.0),
+            ),
+            (
+                TooltipPlacement::Above,
+                Rect::new(80.0, 2.0, 120.0, 20.0),
+                None,
+                size,
+                Vec2::new(100.0, 28.0),
+            ),
+            (
+                TooltipPlacement::Right,
+                Rect::new(170.0, 40.0, 190.0, 60.0),
+                None,
+                size,
+                Vec2::new(157.0, 50.0),
+            ),
+            (
+                TooltipPlacement::Cursor,
+                target,
+                Some(Vec2::new(50.0, 95.0)),
+                size,
+                Vec2::new(60.0, 87.0),
+            ),
+            // kept on its side when the other side has less room
+            (
+                TooltipPlacement::Below,
+                Rect::new(80.0, 10.0, 120.0, 82.0),
+                None,
+                Vec2::new(20.0, 30.0),
+                Vec2::new(100.0, 85.0),
+            ),
+            // kept inside the viewport
+            (
+                TooltipPlacement::Below,
+                Rect::new(0.0, 40.0, 10.0, 60.0),
+                None,
+                size,
+                Vec2::new(10.0, 68.0),
+            ),
+            (
+                TooltipPlacement::Cursor,
+                target,
+                Some(Vec2::new(195.0, 45.0)),
+                size,
+                Vec2::new(190.0, 53.0),
+            ),
+            (
+                TooltipPlacement::Left,
+                Rect::new(80.0, 0.0, 120.0, 4.0),
+                None,
+                size,
+                Vec2::new(67.0, 5.0),
+            ),
+            // and at its top left when it's bigger than the viewport
+            (
+                TooltipPlacement::Below,
+                target,
+                None,
+                Vec2::new(300.0, 150.0),
+                Vec2::new(150.0, 75.0),
+            ),
+        ];
+        for (placement, target, cursor, size, expected) in cases {
+            assert_eq!(
+                place_tooltip(placement, target, cursor, size, 3.0, viewport),
+                expected,
+                "{size} tooltip {placement:?} {target:?} with the cursor at {cursor:?}",
+            );
+        }
+    }
+
+    /// A node with a tooltip, updated by running the [`ui_tooltip_system`] every tenth of a
+    /// second
+    struct Scene {
+        world: World,
+        schedule: Schedule,
+        node: Entity,
+        content: Entity,
+        now: Instant,
+    }
+
+    impl Scene {
+        fn new() -> Self {
+            let now = Instant::now();
+            let mut time = Time::new(now);
+            time.update_with_instant(now);
+            let mut world = World::new();
+            world.insert_resource(time);
+            world.init_resource::<ActiveTooltip>();
+            world.init_resource::<UiPointerMap>();
+            world.init_resource::<NavigationFocus>();
+            world.init_resource::<Input<MouseButton>>();
+            world.init_resource::<Touches>();
+            world.spawn((
+                Window {
+                    focused: true,
+                    ..Default::default()
+                },
+                PrimaryWindow,
+            ));
+            let content = world
+                .spawn(Style {
+                    display: Display::None,
+                    ..Default::default()
+                })
+                .id();
+            let node = world.spawn(Tooltip::new(content).with_delay(0.25)).id();
+            let mut schedule = Schedule::new();
+            schedule.add_system(ui_tooltip_system);
+            Self {
+                world,
+                schedule,
+                node,
+                content,
+                now,
+            }
+        }
+
+        /// Runs a frame a tenth of a second after the last one
+        fn step(&mut self) {
+            self.now += Duration::from_millis(100);
+            let now = self.now;
+            self.world.resource_mut::<Time>().update_with_instant(now);
+            self.schedule.run(&mut self.world);
+            self.world.resource_mut::<Input<MouseButton>>().clear();
+        }
+
+        /// Moves the mouse to `position`, over the nodes
+        fn hover(&mut self, position: Vec2, nodes: Vec<Entity>) {
+            self.world
+                .resource_mut::<UiPointerMap>()
+                .set(PointerId::Mouse, Some(PointerHits { position, nodes }));
+        }
+
+        fn leave(&mut self) {
+            self.world
+                .resource_mut::<UiPointerMap>()
+                .set(PointerId::Mouse, None);
+        }
+
+        /// Whether the content of the tooltip is displayed
+        fn shown(&self) -> bool {
+            let displayed = self.world.get::<Style>(self.content).unwrap().display == Display::Flex;
+            let active = self.world.resource::<ActiveTooltip>().shown();
+            assert_eq!(active.is_some(), displayed);
+            displayed
+        }
+    }
+
+    #[test]
+    fn shown_once_the_mouse_rests_over_the_node() {
+        let mut scene = Scene::new();
+        let node = scene.node;
+        scene.hover(Vec2::new(10.0, 10.0), vec![node]);
+        scene.step();
+        scene.step();
+        scene.step();
+        assert!(!scene.shown());
+        scene.step();
+        assert!(scene.shown());
+        assert_eq!(scene.world.resource::<ActiveTooltip>().shown(), Some(node));
+    }
+
+    #[test]
+    fn moving_over_the_node_restarts_the_delay() {
+        let mut scene = Scene::new();
+        let node = scene.node;
+        for x in 0..6 {
+            scene.hover(Vec2::new(x as f32, 10.0), vec![node]);
+            scene.step();
+            assert!(!scene.shown());
+        }
+        scene.step();
+        scene.step();
+        assert!(!scene.shown());
+        scene.step();
+        assert!(scene.shown());
+
+        // once shown, the tooltip stays while the mouse moves over the node
+        scene.hover(Vec2::new(20.0, 10.0), vec![node]);
+        scene.step();
+        assert!(scene.shown());
+    }
+
+    #[test]
+    fn the_top_node_with_a_tooltip_is_shown() {
+        let mut scene = Scene::new();
+        let node = scene.node;
+        let above = scene.world.spawn_empty().id();
+        let below = scene.world.spawn(Tooltip::new(Entity::PLACEHOLDER)).id();
+        scene.hover(Vec2::new(10.0, 10.0), vec![above, node, below]);
+        for _ in 0..4 {
+            scene.step();
+        }
+        assert!(scene.shown());
+    }
+
+    #[test]
+    fn hidden_when_the_mouse_leaves() {
+        let mut scene = Scene::new();
+        let node = scene.node;
+        scene.hover(Vec2::new(10.0, 10.0), vec![node]);
+        for _ in 0..4 {
+            scene.step();
+        }
+        assert!(scene.shown());
+
+        // to another node
+        let other = scene.world.spawn_empty().id();
+        scene.hover(Vec2::new(50.0, 10.0), vec![other]);
+        scene.step();
+        assert!(!scene.shown());
+
+        // and out of the window, before the delay
+        scene.hover(Vec2::new(10.0, 10.0), vec![node]);
+        scene.step();
+        scene.step();
+        scene.leave();
+        for _ in 0..4 {
+            scene.step();
+        }
+        assert!(!scene.shown());
+    }
+
+    #[test]
+    fn hidden_by_a_button_until_the_mouse_leaves() {
+        let mut scene = Scene::new();
+        let node = scene.node;
+        scene.hover(Vec2::new(10.0, 10.0), vec![node]);
+        for _ in 0..4 {
+            scene.step();
+        }
+        scene
+            .world
+            .resource_mut::<Input<MouseButton>>()
+            .press(MouseButton::Left);
+        scene.step();
+        assert!(!scene.shown());
+        for _ in 0..4 {
+            scene.step();
+        }
+        assert!(!scene.shown());
+
+        scene.leave();
+        scene.step();
+        scene.hover(Vec2::new(10.0, 10.0), vec![node]);
+        for _ in 0..4 {
+            scene.step();
+        }
+        assert!(scene.shown());
+    }
+
+    #[test]
+    fn shown_while_focused() {
+        let mut scene = Scene::new();
+        let node = scene.node;
+        scene.world.resource_mut::<NavigationFocus>().focus(node);
+        for _ in 0..3 {
+            scene.step();
+        }
+        assert!(!scene.shown());
+        scene.step();
+        assert!(scene.shown());
+
+        scene.world.resource_mut::<NavigationFocus>().clear();
+        scene.step();
+        assert!(!scene.shown());
+    }
+
+    #[test]
+    fn hidden_when_the_window_loses_the_focus() {
+        let mut scene = Scene::new();
+        let node = scene.node;
+        scene.hover(Vec2::new(10.0, 10.0), vec![node]);
+        for _ in 0..4 {
+            scene.step();
+        }
+        let mut windows = scene.world.query::<&mut Window>();
+        windows.single_mut(&mut scene.world).focused = false;
+        scene.step();
+        assert!(!scene.shown());
+    }
+
+    #[test]
+    fn hidden_when_the_node_is_despawned() {
+        let mut scene = Scene::new();
+        let node = scene.node;
+        scene.hover(Vec2::new(10.0, 10.0), vec![node]);
+        for _ in 0..4 {
+            scene.step();
+        }
+        scene.world.despawn(node);
+        scene.leave();
+        scene.step();
+        assert!(!scene.shown());
+    }
+}
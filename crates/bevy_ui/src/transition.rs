@@ -0,0 +1,726 @@
+use std::marker::PhantomData;
+
+use crate::{BackgroundColor, BorderColor, Interaction, Size, Style, UiOpacity, UiRect, Val};
+use bevy_app::{App, CoreSet, Plugin};
+use bevy_ecs::{
+    prelude::{Component, Query, Res},
+    query::Changed,
+    schedule::IntoSystemConfig,
+};
+use bevy_math::Vec3;
+use bevy_reflect::{FromReflect, Reflect, ReflectDeserialize, ReflectSerialize};
+use bevy_render::color::Color;
+use bevy_time::Time;
+use bevy_transform::components::Transform;
+use serde::{Deserialize, Serialize};
+
+use crate::UiSystem;
+
+/// A property of a component animated by a [`Transition`]
+///
+/// Bevy provides the properties of the colors, the opacity and the position, size and scale of
+/// the UI nodes. Others are added by implementing this trait and the
+/// [`UiTransitionPlugin`] of the property.
+pub trait TransitionProperty: Send + Sync + 'static {
+    /// The component holding the property
+    type Component: Component;
+    /// The value of the property
+    type Value: Clone + PartialEq + Send + Sync + 'static;
+
+    /// The value of the property in `component`
+    fn get(component: &Self::Component) -> Self::Value;
+
+    /// Sets the value of the property in `component`
+    fn set(component: &mut Self::Component, value: Self::Value);
+
+    /// The value between `start` and `end`, at `t` from 0 for `start` to 1 for `end`
+    fn interpolate(start: &Self::Value, end: &Self::Value, t: f32) -> Self::Value;
+}
+
+/// How the progress of a [`Transition`] accelerates over its duration, like in CSS
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect, FromReflect)]
+#[reflect(PartialEq, Serialize, Deserialize)]
+pub enum Easing {
+    /// At a constant speed
+    Linear,
+    /// Slowly at the start
+    EaseIn,
+    /// Slowly at the end
+    EaseOut,
+    /// Slowly at the start and at the end
+    EaseInOut,
+}
+
+impl Easing {
+    pub const DEFAULT: Self = Self::EaseInOut;
+
+    /// The progress of the value of the transition at `t`, from 0 at its start to 1 at its end
+    pub fn ease(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Easing::Linear => t,
+            Easing::EaseIn => t * t * t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
+
+impl Default for Easing {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Animates a [`TransitionProperty`] of the component of an entity to the target set by
+/// [`Transition::set_target`], over a duration
+///
+/// The target can be set by the systems reacting to a change of state, or from the
+/// [`Interaction`] of the node with an [`InteractionTransition`].
+///
+/// The transition starts from the current value of the property, so setting a new target in
+/// the middle of a transition smoothly turns it around.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_ui::prelude::*;
+/// # use bevy_ui::BackgroundColorProperty;
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_render::color::Color;
+/// #[derive(Component)]
+/// struct Menu;
+///
+/// fn open_menu(mut menus: Query<&mut Transition<BackgroundColorProperty>, With<Menu>>) {
+///     for mut transition in &mut menus {
+///         transition.set_target(Color::rgba(0.0, 0.0, 0.0, 0.8));
+///     }
+/// }
+/// ```
+#[derive(Component)]
+pub struct Transition<P: TransitionProperty> {
+    /// The duration of the transition in seconds
+    pub duration: f32,
+    /// How the transition accelerates over its duration
+    pub easing: Easing,
+    target: Option<P::Value>,
+    start: Option<P::Value>,
+    elapsed: f32,
+    running: bool,
+}
+
+impl<P: TransitionProperty> Transition<P> {
+    /// A transition of `duration` seconds, that doesn't animate the property until a target is
+    /// set
+    pub const fn new(duration: f32) -> Self {
+        Self {
+            duration,
+            easing: Easing::DEFAULT,
+            target: None,
+            start: None,
+            elapsed: 0.0,
+            running: false,
+        }
+    }
+
+    /// Sets how the transition accelerates over its duration
+    pub const fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Animates the property from its current value to `target`
+    ///
+    /// Nothing happens if it's already the target of the transition.
+    pub fn set_target(&mut self, target: P::Value) {
+        if self.target.as_ref() == Some(&target) {
+            return;
+        }
+        self.target = Some(target);
+        // The start is the value of the property when the transition is next updated
+        self.start = None;
+        self.elapsed = 0.0;
+        self.running = true;
+    }
+
+    /// The last target of the transition
+    pub fn target(&self) -> Option<&P::Value> {
+        self.target.as_ref()
+    }
+
+    /// Whether the property is being animated to its target
+    pub fn is_running(&self) -> bool {
+        self.running
+    }
+}
+
+impl<P: TransitionProperty> Clone for Transition<P> {
+    fn clone(&self) -> Self {
+        Self {
+            duration: self.duration,
+            easing: self.easing,
+            target: self.target.clone(),
+            start: self.start.clone(),
+            elapsed: self.elapsed,
+            running: self.running,
+        }
+    }
+}
+
+impl<P: TransitionProperty> Default for Transition<P> {
+    fn default() -> Self {
+        Self::new(0.2)
+    }
+}
+
+/// Sets the target of the [`Transition`] of an entity from its [`Interaction`], like a button
+/// highlighted when hovered
+#[derive(Component)]
+pub struct InteractionTransition<P: TransitionProperty> {
+    /// The target of the transition when the node isn't hovered or clicked
+    pub none: P::Value,
+    /// The target of the transition when the node is hovered
+    pub hovered: P::Value,
+    /// The target of the transition when the node is clicked
+    pub clicked: P::Value,
+}
+
+impl<P: TransitionProperty> Clone for InteractionTransition<P> {
+    fn clone(&self) -> Self {
+        Self {
+            none: self.none.clone(),
+            hovered: self.hovered.clone(),
+            clicked: self.clicked.clone(),
+        }
+    }
+}
+
+impl<P: TransitionProperty> InteractionTransition<P> {
+    /// The target of the transition for `interaction`
+    pub fn target(&self, interaction: Interaction) -> &P::Value {
+        match interaction {
+            Interaction::None => &self.none,
+            Interaction::Hovered => &self.hovered,
+            Interaction::Clicked => &self.clicked,
+        }
+    }
+}
+
+/// Adds the systems animating the [`Transition`]s of a [`TransitionProperty`]
+///
+/// The [`UiPlugin`](crate::UiPlugin) adds it for the properties provided by Bevy.
+pub struct UiTransitionPlugin<P: TransitionProperty>(PhantomData<P>);
+
+impl<P: TransitionProperty> Default for UiTransitionPlugin<P> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P: TransitionProperty> Plugin for UiTransitionPlugin<P> {
+    fn build(&self, app: &mut App) {
+        app.add_system(
+            interaction_transition_system::<P>
+                .in_base_set(CoreSet::PostUpdate)
+                .before(UiSystem::Transition),
+        )
+        .add_system(transition_system::<P>.in_set(UiSystem::Transition));
+    }
+}
+
+/// The system setting the targets of the [`Transition`]s from the changed [`Interaction`]s
+pub fn interaction_transition_system<P: TransitionProperty>(
+    mut query: Query<
+        (&Interaction, &InteractionTransition<P>, &mut Transition<P>),
+        Changed<Interaction>,
+    >,
+) {
+    for (interaction, interaction_transition, mut transition) in &mut query {
+        transition.set_target(interaction_transition.target(*interaction).clone());
+    }
+}
+
+/// The system animating the properties of the running [`Transition`]s
+pub fn transition_system<P: TransitionProperty>(
+    time: Res<Time>,
+    mut query: Query<(&mut Transition<P>, &mut P::Component)>,
+) {
+    for (mut transition, mut component) in &mut query {
+        let (true, Some(target)) = (transition.running, transition.target.clone()) else {
+            continue;
+        };
+        let start = transition
+            .start
+            .get_or_insert_with(|| P::get(&component))
+            .clone();
+        transition.elapsed += time.delta_seconds();
+        let t = if transition.duration > 0.0 {
+            transition.elapsed / transition.duration
+        } else {
+            1.0
+        };
+        if t >= 1.0 {
+            P::set(&mut component, target);
+            transition.running = false;
+            transition.start = None;
+        } else {
+            let value = P::interpolate(&start, &target, transition.easing.ease(t));
+            P::set(&mut component, value);
+        }
+    }
+}
+
+fn lerp(start: f32, end: f32, t: f32) -> f32 {
+    start + (end - start) * t
+}
+
+/// Interpolates the colors in their sRGB components, whatever their color space
+fn lerp_color(start: Color, end: Color, t: f32) -> Color {
+    let [r, g, b, a] = start.as_rgba_f32();
+    let [end_r, end_g, end_b, end_a] = end.as_rgba_f32();
+    Color::rgba(
+        lerp(r, end_r, t),
+        lerp(g, end_g, t),
+        lerp(b, end_b, t),
+        lerp(a, end_a, t),
+    )
+}
+
+/// Interpolates the values of the same kind, and switches from one kind to the other halfway
+fn lerp_val(start: Val, end: Val, t: f32) -> Val {
+    match (start, end) {
+        (Val::Px(start), Val::Px(end)) => Val::Px(lerp(start, end, t)),
+        (Val::Percent(start), Val::Percent(end)) => Val::Percent(lerp(start, end, t)),
+        _ if t < 0.5 => start,
+        _ => end,
+    }
+}
+
+/// The color of the [`BackgroundColor`] of a node
+pub struct BackgroundColorProperty;
+
+impl TransitionProperty for BackgroundColorProperty {
+    type Component = BackgroundColor;
+    type Value = Color;
+
+    fn get(component: &BackgroundColor) -> Color {
+        component.0
+    }
+
+    fn set(component: &mut BackgroundColor, value: Color) {
+        component.0 = value;
+    }
+
+    fn interpolate(start: &Color, end: &Color, t: f32) -> Color {
+        lerp_color(*start, *end, t)
+    }
+}
+
+/// The colors of the [`BorderColor`] of a node
+pub struct BorderColorProperty;
+
+impl TransitionProperty for BorderColorProperty {
+    type Component = BorderColor;
+    type Value = BorderColor;
+
+    fn get(component: &BorderColor) -> BorderColor {
+        *component
+    }
+
+    fn set(component: &mut BorderColor, value: BorderColor) {
+        *component = value;
+    }
+
+    fn interpolate(start: &BorderColor, end: &BorderColor, t: f32) -> BorderColor {
+        BorderColor {
+            left: lerp_color(start.left, end.left, t),
+            top: lerp_color(start.top, end.top, t),
+            right: lerp_color(start.right, end.right, t),
+            bottom: lerp_color(start.bottom, end.bottom, t),
+        }
+    }
+}
+
+/// The [`UiOpacity`] of a node
+pub struct OpacityProperty;
+
+impl TransitionProperty for OpacityProperty {
+    type Component = UiOpacity;
+    type Value = f32;
+
+    fn get(component: &UiOpacity) -> f32 {
+        component.0
+    }
+
+    fn set(component: &mut UiOpacity, value: f32) {
+        component.0 = value;
+    }
+
+    fn interpolate(start: &f32, end: &f32, t: f32) -> f32 {
+        lerp(*start, *end, t)
+    }
+}
+
+/// The [`Style::position`] of a node, to slide it in and out
+pub struct PositionProperty;
+
+impl TransitionProperty for PositionProperty {
+    type Component = Style;
+    type Value = UiRect;
+
+    fn get(component: &Style) -> UiRect {
+        component.position
+    }
+
+    fn set(component: &mut Style, value: UiRect) {
+        component.position = value;
+    }
+
+    fn interpolate(start: &UiRect, end: &UiRect, t: f32) -> UiRect {
+        UiRect {
+            left: lerp_val(start.left, end.left, t),
+            right: lerp_val(start.right, end.right, t),
+            top: lerp_val(start.top, end.top, t),
+            bottom: lerp_val(start.bottom, end.bottom, t),
+        }
+    }
+}
+
+/// The [`Style::size`] of a node
+pub struct SizeProperty;
+
+impl TransitionProperty for SizeProperty {
+    type Component = Style;
+    type Value = Size;
+
+    fn get(component: &Style) -> Size {
+        component.size
+    }
+
+    fn set(component: &mut Style, value: Size) {
+        component.size = value;
+    }
+
+    fn interpolate(start: &Size, end: &Size, t: f32) -> Size {
+        Size::new(
+            lerp_val(start.width, end.width, t),
+            lerp_val(start.height, end.height, t),
+        )
+    }
+}
+
+/// The scale of the [`Transform`] of a node, drawn scaled around its center
+///
+/// The layout and the interactions of the node aren't scaled.
+pub struct ScaleProperty;
+
+impl TransitionProperty for ScaleProperty {
+    type Component = Transform;
+    type Value = Vec3;
+
+    fn get(component: &Transform) -> Vec3 {
+        component.scale
+    }
+
+    fn set(component: &mut Transform, value: Vec3) {
+        component.scale = value;
+    }
+
+    fn interpolate(start: &Vec3, end: &Vec3, t: f32) -> Vec3 {
+        start.lerp(*end, t)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{
+        entity::Entity,
+        schedule::Schedule,
+        world::{Mut, World},
+    };
+    use bevy_utils::{Duration, Instant};
+
+    fn assert_close(actual: f32, expected: f32, message: &str) {
+        assert!(
+            (actual - expected).abs() < 1e-5,
+            "{message}: {actual} is not {expected}"
+        );
+    }
+
+    #[test]
+    fn easing() {
+        // the progress at 0, 0.25, 0.5, 0.75 and 1
+        let cases = [
+            (Easing::Linear, [0.0, 0.25, 0.5, 0.75, 1.0]),
+            (Easing::EaseIn, [0.0, 0.015625, 0.125, 0.421875, 1.0]),
+            (Easing::EaseOut, [0.0, 0.578125, 0.875, 0.984375, 1.0]),
+            (Easing::EaseInOut, [0.0, 0.0625, 0.5, 0.9375, 1.0]),
+        ];
+        for (easing, progress) in cases {
+            for (index, expected) in progress.into_iter().enumerate() {
+                let t = index as f32 / 4.0;
+                assert_close(easing.ease(t), expected, &format!("{easing:?} at {t}"));
+            }
+            // clamped before the start and after the end
+            assert_eq!(easing.ease(-1.0), 0.0, "{easing:?}");
+            assert_eq!(easing.ease(2.0), 1.0, "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn interpolated_vals() {
+        let cases = [
+            (Val::Px(10.0), Val::Px(20.0), 0.25, Val::Px(12.5)),
+            (Val::Px(10.0), Val::Px(-10.0), 0.5, Val::Px(0.0)),
+            (
+                Val::Percent(0.0),
+                Val::Percent(100.0),
+                0.75,
+                Val::Percent(75.0),
+            ),
+            // switching from one kind to the other halfway
+            (Val::Px(10.0), Val::Percent(100.0), 0.25, Val::Px(10.0)),
+            (Val::Px(10.0), Val::Percent(100.0), 0.5, Val::Percent(100.0)),
+            (Val::Auto, Val::Px(10.0), 0.25, Val::Auto),
+            (Val::Auto, Val::Px(10.0), 0.75, Val::Px(10.0)),
+            (Val::Undefined, Val::Auto, 1.0, Val::Auto),
+        ];
+        for (start, end, t, expected) in cases {
+            assert_eq!(
+                lerp_val(start, end, t),
+                expected,
+                "{start:?} to {end:?} at {t}"
+            );
+        }
+    }
+
+    #[test]
+    fn interpolated_colors() {
+        let cases = [
+            (
+                Color::rgba(0.0, 0.2, 1.0, 1.0),
+                Color::rgba(1.0, 0.6, 0.0, 0.0),
+                [0.5, 0.4, 0.5, 0.5],
+            ),
+            // in sRGB, whatever the color space of the colors
+            (
+                Color::rgba_linear(0.0, 0.0, 0.0, 1.0),
+                Color::WHITE,
+                [0.5, 0.5, 0.5, 1.0],
+            ),
+            (Color::hsl(0.0, 1.0, 0.5), Color::BLUE, [0.5, 0.0, 0.5, 1.0]),
+        ];
+        for (start, end, expected) in cases {
+            let color = lerp_color(start, end, 0.5);
+            assert!(matches!(color, Color::Rgba { .. }), "{color:?}");
+            for (actual, expected) in color.as_rgba_f32().into_iter().zip(expected) {
+                assert_close(actual, expected, &format!("{start:?} to {end:?}"));
+            }
+        }
+        assert_eq!(lerp_color(Color::RED, Color::BLUE, 0.0), Color::RED);
+        assert_eq!(lerp_color(Color::RED, Color::BLUE, 1.0), Color::BLUE);
+    }
+
+    #[test]
+    fn interpolated_properties() {
+        let position = PositionProperty::interpolate(
+            &UiRect::left(Val::Px(0.0)),
+            &UiRect::new(
+                Val::Px(100.0),
+                Val::Auto,
+                Val::Percent(50.0),
+                Val::Undefined,
+            ),
+            0.25,
+        );
+        assert_eq!(
+            position,
+            UiRect::new(
+                Val::Px(25.0),
+                Val::Undefined,
+                Val::Undefined,
+                Val::Undefined
+            )
+        );
+        let size = SizeProperty::interpolate(
+            &Size::new(Val::Px(0.0), Val::Percent(10.0)),
+            &Size::new(Val::Px(40.0), Val::Percent(20.0)),
+            0.5,
+        );
+        assert_eq!(size, Size::new(Val::Px(20.0), Val::Percent(15.0)));
+        let scale = ScaleProperty::interpolate(&Vec3::ONE, &Vec3::new(2.0, 3.0, 1.0), 0.5);
+        assert_eq!(scale, Vec3::new(1.5, 2.0, 1.0));
+        let border = BorderColorProperty::interpolate(
+            &BorderColor::all(Color::BLACK),
+            &BorderColor {
+                left: Color::WHITE,
+                ..BorderColor::all(Color::BLACK)
+            },
+            0.5,
+        );
+        assert_eq!(border.left, Color::rgba(0.5, 0.5, 0.5, 1.0));
+        assert_eq!(border.right, Color::rgba(0.0, 0.0, 0.0, 1.0));
+    }
+
+    /// The opacity of a node animated by running the [`transition_system`] every quarter of a
+    /// second
+    struct Animation {
+        world: World,
+        schedule: Schedule,
+        entity: Entity,
+        now: Instant,
+    }
+
+    impl Animation {
+        fn new(opacity: f32, transition: Transition<OpacityProperty>) -> Self {
+            let now = Instant::now();
+            let mut time = Time::new(now);
+            time.update_with_instant(now);
+            let mut world = World::new();
+            world.insert_resource(time);
+            let entity = world.spawn((UiOpacity(opacity), transition)).id();
+            let mut schedule = Schedule::new();
+            schedule.add_system(interaction_transition_system::<OpacityProperty>);
+            schedule.add_system(
+                transition_system::<OpacityProperty>
+                    .after(interaction_transition_system::<OpacityProperty>),
+            );
+            Self {
+                world,
+                schedule,
+                entity,
+                now,
+            }
+        }
+
+        /// Runs a frame a quarter of a second after the last one, returning the opacity
+        fn step(&mut self) -> f32 {
+            self.now += Duration::from_millis(250);
+            let now = self.now;
+            self.world.resource_mut::<Time>().update_with_instant(now);
+            self.schedule.run(&mut self.world);
+            self.opacity()
+        }
+
+        fn opacity(&self) -> f32 {
+            self.world.get::<UiOpacity>(self.entity).unwrap().0
+        }
+
+        fn transition(&mut self) -> Mut<'_, Transition<OpacityProperty>> {
+            self.world
+                .get_mut::<Transition<OpacityProperty>>(self.entity)
+                .unwrap()
+        }
+    }
+
+    fn linear(duration: f32) -> Transition<OpacityProperty> {
+        Transition::new(duration).with_easing(Easing::Linear)
+    }
+
+    #[test]
+    fn animated_to_the_target() {
+        let cases = [
+            (Easing::Linear, vec![0.25, 0.5, 0.75, 1.0]),
+            (Easing::EaseIn, vec![0.015625, 0.125, 0.421875, 1.0]),
+            (Easing::EaseInOut, vec![0.0625, 0.5, 0.9375, 1.0]),
+        ];
+        for (easing, opacities) in cases {
+            let mut animation = Animation::new(0.0, Transition::new(1.0).with_easing(easing));
+            animation.transition().set_target(1.0);
+            for expected in opacities {
+                assert!(animation.transition().is_running(), "{easing:?}");
+                assert_close(animation.step(), expected, &format!("{easing:?}"));
+            }
+            assert!(!animation.transition().is_running(), "{easing:?}");
+            assert_eq!(animation.step(), 1.0, "{easing:?}");
+        }
+    }
+
+    #[test]
+    fn without_a_target() {
+        let mut animation = Animation::new(0.5, linear(1.0));
+        assert_eq!(animation.step(), 0.5);
+        assert!(!animation.transition().is_running());
+        assert_eq!(animation.transition().target(), None);
+    }
+
+    #[test]
+    fn started_from_the_value_at_the_next_update() {
+        let mut animation = Animation::new(0.0, linear(1.0));
+        animation.transition().set_target(1.0);
+        // changed before the transition is updated
+        animation
+            .world
+            .get_mut::<UiOpacity>(animation.entity)
+            .unwrap()
+            .0 = 0.5;
+        assert_close(animation.step(), 0.625, "the first step");
+    }
+
+    #[test]
+    fn new_target_turns_the_transition_around() {
+        let mut animation = Animation::new(0.0, linear(1.0));
+        animation.transition().set_target(1.0);
+        animation.step();
+        animation.step();
+        animation.transition().set_target(0.0);
+        // back from 0.5, over the whole duration
+        for expected in [0.375, 0.25, 0.125, 0.0] {
+            assert_close(animation.step(), expected, "turned around");
+        }
+        assert!(!animation.transition().is_running());
+    }
+
+    #[test]
+    fn same_target_keeps_the_progress() {
+        let mut animation = Animation::new(0.0, linear(1.0));
+        animation.transition().set_target(1.0);
+        animation.step();
+        animation.transition().set_target(1.0);
+        assert_close(animation.step(), 0.5, "the second step");
+    }
+
+    #[test]
+    fn zero_duration_jumps_to_the_target() {
+        let mut animation = Animation::new(0.0, linear(0.0));
+        animation.transition().set_target(0.8);
+        assert_eq!(animation.step(), 0.8);
+        assert!(!animation.transition().is_running());
+    }
+
+    #[test]
+    fn targets_from_the_interaction() {
+        let mut animation = Animation::new(0.0, linear(0.5));
+        animation.world.entity_mut(animation.entity).insert((
+            Interaction::None,
+            InteractionTransition::<OpacityProperty> {
+                none: 0.2,
+                hovered: 0.6,
+                clicked: 1.0,
+            },
+        ));
+        assert_close(animation.step(), 0.1, "not hovered");
+        assert_close(animation.step(), 0.2, "not hovered");
+        *animation
+            .world
+            .get_mut::<Interaction>(animation.entity)
+            .unwrap() = Interaction::Hovered;
+        assert_close(animation.step(), 0.4, "hovered");
+        *animation
+            .world
+            .get_mut::<Interaction>(animation.entity)
+            .unwrap() = Interaction::Clicked;
+        assert_close(animation.step(), 0.7, "clicked");
+        assert_close(animation.step(), 1.0, "clicked");
+        // the target is only set when the interaction changes
+        animation.transition().set_target(0.0);
+        assert_close(animation.step(), 0.5, "set by a system");
+    }
+}
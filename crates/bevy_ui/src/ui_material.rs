@@ -0,0 +1,123 @@
+use std::hash::Hash;
+
+use bevy_reflect::TypeUuid;
+use bevy_render::render_resource::{AsBindGroup, RenderPipelineDescriptor, ShaderRef};
+
+/// Materials are used alongside [`UiMaterialPlugin`](crate::UiMaterialPlugin) and
+/// [`MaterialNodeBundle`](crate::prelude::MaterialNodeBundle) to spawn UI nodes drawn with a
+/// custom shader, in place of their background color and image.
+///
+/// UI materials must implement [`AsBindGroup`] to define how data will be transferred to the GPU
+/// and bound in shaders, and [`TypeUuid`] so they can be treated as an
+/// [`Asset`](bevy_asset::Asset), like [`Material2d`](bevy_sprite::Material2d)s.
+///
+/// The nodes are drawn in the order of the [`UiStack`](crate::UiStack), among the other nodes,
+/// and the consecutive nodes with the same material are batched in a single draw call.
+///
+/// # Example
+///
+/// ```
+/// # use bevy_ui::prelude::*;
+/// # use bevy_ecs::prelude::*;
+/// # use bevy_reflect::TypeUuid;
+/// # use bevy_render::{render_resource::{AsBindGroup, ShaderRef}, color::Color};
+/// # use bevy_asset::Assets;
+/// #[derive(AsBindGroup, TypeUuid, Debug, Clone)]
+/// #[uuid = "9c5a0ddd-0b1a-4bd4-9e57-f0a9a2b9ba57"]
+/// pub struct GradientMaterial {
+///     #[uniform(0)]
+///     from: Color,
+///     #[uniform(1)]
+///     to: Color,
+/// }
+///
+/// impl UiMaterial for GradientMaterial {
+///     fn fragment_shader() -> ShaderRef {
+///         "shaders/gradient_material.wgsl".into()
+///     }
+/// }
+///
+/// fn setup(mut commands: Commands, mut materials: ResMut<Assets<GradientMaterial>>) {
+///     commands.spawn(MaterialNodeBundle {
+///         material: materials.add(GradientMaterial {
+///             from: Color::RED,
+///             to: Color::BLUE,
+///         }),
+///         ..Default::default()
+///     });
+/// }
+/// ```
+/// In WGSL shaders, the material's bindings and the inputs of the fragment shader would look
+/// like this:
+///
+/// ```wgsl
+/// @group(1) @binding(0)
+/// var<uniform> from: vec4<f32>;
+/// @group(1) @binding(1)
+/// var<uniform> to: vec4<f32>;
+///
+/// @fragment
+/// fn fragment(
+///     #import bevy_ui::ui_vertex_output
+/// ) -> @location(0) vec4<f32> {
+///     return mix(from, to, uv.x);
+/// }
+/// ```
+pub trait UiMaterial: AsBindGroup + Send + Sync + Clone + TypeUuid + Sized + 'static {
+    /// Returns this material's vertex shader. If [`ShaderRef::Default`] is returned, the default
+    /// UI material vertex shader will be used.
+    fn vertex_shader() -> ShaderRef {
+        ShaderRef::Default
+    }
+
+    /// Returns this material's fragment shader. If [`ShaderRef::Default`] is returned, the
+    /// default UI material fragment shader will be used, drawing the nodes in white.
+    fn fragment_shader() -> ShaderRef {
+        ShaderRef::Default
+    }
+
+    /// Customizes the default [`RenderPipelineDescriptor`].
+    #[allow(unused_variables)]
+    #[inline]
+    fn specialize(descriptor: &mut RenderPipelineDescriptor, key: UiMaterialKey<Self>) {}
+}
+
+/// The key of the pipelines of a [`UiMaterial`], passed to [`UiMaterial::specialize`]
+pub struct UiMaterialKey<M: UiMaterial> {
+    /// Whether the nodes are drawn to an HDR view
+    pub hdr: bool,
+    pub bind_group_data: M::Data,
+}
+
+impl<M: UiMaterial> Eq for UiMaterialKey<M> where M::Data: PartialEq {}
+
+impl<M: UiMaterial> PartialEq for UiMaterialKey<M>
+where
+    M::Data: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.hdr == other.hdr && self.bind_group_data == other.bind_group_data
+    }
+}
+
+impl<M: UiMaterial> Clone for UiMaterialKey<M>
+where
+    M::Data: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            hdr: self.hdr,
+            bind_group_data: self.bind_group_data.clone(),
+        }
+    }
+}
+
+impl<M: UiMaterial> Hash for UiMaterialKey<M>
+where
+    M::Data: Hash,
+{
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.hdr.hash(state);
+        self.bind_group_data.hash(state);
+    }
+}
@@ -1,12 +1,13 @@
 use crate::{Size, UiRect};
 use bevy_asset::Handle;
-use bevy_ecs::{prelude::Component, reflect::ReflectComponent};
+use bevy_ecs::{entity::Entity, prelude::Component, reflect::ReflectComponent};
 use bevy_math::{Rect, Vec2};
 use bevy_reflect::prelude::*;
 use bevy_render::{
     color::Color,
     texture::{Image, DEFAULT_IMAGE_HANDLE},
 };
+use bevy_sprite::ImageScaleMode;
 use serde::{Deserialize, Serialize};
 use std::ops::{Div, DivAssign, Mul, MulAssign};
 use thiserror::Error;
@@ -18,6 +19,10 @@ pub struct Node {
     /// The size of the node as width and height in pixels
     /// automatically calculated by [`super::flex::flex_node_system`]
     pub(crate) calculated_size: Vec2,
+    /// The camera the node is laid out and drawn on
+    /// automatically calculated by [`super::flex::flex_node_system`]
+    #[reflect(ignore)]
+    pub(crate) target_camera: Option<Entity>,
 }
 
 impl Node {
@@ -26,11 +31,19 @@ impl Node {
     pub fn size(&self) -> Vec2 {
         self.calculated_size
     }
+
+    /// The camera the node is laid out and drawn on, from the [`UiTargetCamera`](crate::UiTargetCamera)
+    /// of its root node or else the default UI camera
+    /// automatically calculated by [`super::flex::flex_node_system`]
+    pub fn target_camera(&self) -> Option<Entity> {
+        self.target_camera
+    }
 }
 
 impl Node {
     pub const DEFAULT: Self = Self {
         calculated_size: Vec2::ZERO,
+        target_camera: None,
     };
 }
 
@@ -209,11 +222,12 @@ impl Val {
 
 /// Describes the style of a UI node
 ///
-/// It uses the [Flexbox](https://cssreference.io/flexbox/) system.
+/// It uses the [Flexbox](https://cssreference.io/flexbox/) system, or the
+/// [Grid](https://cssreference.io/css-grid/) system with [`Display::Grid`].
 #[derive(Component, Clone, PartialEq, Debug, Reflect)]
 #[reflect(Component, Default, PartialEq)]
 pub struct Style {
-    /// Whether to arrange this node and its children with flexbox layout
+    /// Whether to arrange this node and its children with flexbox or grid layout
     ///
     /// If this is set to [`Display::None`], this node will be collapsed.
     pub display: Display,
@@ -304,10 +318,46 @@ pub struct Style {
     pub aspect_ratio: Option<f32>,
     /// How to handle overflow
     pub overflow: Overflow,
-    /// The size of the gutters between the rows and columns of the flexbox layout
+    /// The size of the gutters between the rows and columns of the flexbox or grid layout
     ///
     /// Values of `Size::UNDEFINED` and `Size::AUTO` are treated as zero.
     pub gap: Size,
+    /// The sizes of the columns of the grid, from the left
+    pub grid_template_columns: Vec<GridTrack>,
+    /// The sizes of the rows of the grid, from the top
+    pub grid_template_rows: Vec<GridTrack>,
+    /// The names of the areas of the grid, as a string of whitespace separated cell names per
+    /// row, where `.` is a cell without a name
+    ///
+    /// # Example
+    /// ```
+    /// # use bevy_ui::{Display, Style};
+    /// let style = Style {
+    ///     display: Display::Grid,
+    ///     grid_template_areas: vec![
+    ///         "header header".to_string(),
+    ///         "sidebar content".to_string(),
+    ///     ],
+    ///     ..Default::default()
+    /// };
+    /// ```
+    /// A child with a `grid_area` of `"header"` spans both columns of the first row.
+    pub grid_template_areas: Vec<String>,
+    /// The size of the columns added after the template columns to place the items in
+    pub grid_auto_columns: GridTrack,
+    /// The size of the rows added after the template rows to place the items in
+    pub grid_auto_rows: GridTrack,
+    /// Whether the automatically placed items fill the rows or the columns of the grid
+    pub grid_auto_flow: GridAutoFlow,
+    /// The rows of the parent grid this item is placed in
+    pub grid_row: GridPlacement,
+    /// The columns of the parent grid this item is placed in
+    pub grid_column: GridPlacement,
+    /// The named area of the parent grid this item is placed in, over its rows and columns
+    ///
+    /// Overrides `grid_row` and `grid_column` when the area is in the `grid_template_areas` of
+    /// the parent.
+    pub grid_area: Option<String>,
 }
 
 impl Style {
@@ -334,6 +384,15 @@ impl Style {
         aspect_ratio: None,
         overflow: Overflow::DEFAULT,
         gap: Size::UNDEFINED,
+        grid_template_columns: Vec::new(),
+        grid_template_rows: Vec::new(),
+        grid_template_areas: Vec::new(),
+        grid_auto_columns: GridTrack::DEFAULT,
+        grid_auto_rows: GridTrack::DEFAULT,
+        grid_auto_flow: GridAutoFlow::DEFAULT,
+        grid_row: GridPlacement::DEFAULT,
+        grid_column: GridPlacement::DEFAULT,
+        grid_area: None,
     };
 }
 
@@ -454,7 +513,7 @@ impl Default for Direction {
     }
 }
 
-/// Whether to use a Flexbox layout model.
+/// Whether to use a Flexbox or a Grid layout model.
 ///
 /// Part of the [`Style`] component.
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect)]
@@ -462,6 +521,12 @@ impl Default for Direction {
 pub enum Display {
     /// Use Flexbox layout model to determine the position of this [`Node`].
     Flex,
+    /// Use Grid layout model to place the children of this [`Node`] in the cells of a grid.
+    ///
+    /// The grid is described by the `grid_template_*` and `grid_auto_*` fields of the [`Style`]
+    /// of this node, and its children are placed by their `grid_row`, `grid_column` and
+    /// `grid_area` fields.
+    Grid,
     /// Use no layout, don't render this node and its children.
     ///
     /// If you want to hide a node and its children,
@@ -479,6 +544,117 @@ impl Default for Display {
     }
 }
 
+/// The size of a row or a column of a grid
+///
+/// Part of the [`Style`] component.
+#[derive(Copy, Clone, PartialEq, Debug, Serialize, Deserialize, Reflect, FromReflect)]
+#[reflect(PartialEq, Serialize, Deserialize)]
+pub enum GridTrack {
+    /// Sized to fit the largest of the items placed only in this track
+    Auto,
+    /// Set this track to a fixed size in pixels
+    Px(f32),
+    /// Set this track to a percentage of the inner size of the grid
+    Percent(f32),
+    /// Share the space left by the other tracks with the other flexible tracks, in proportion
+    /// to this factor
+    ///
+    /// Sized like [`GridTrack::Auto`] when the grid is sized to its content.
+    Fr(f32),
+}
+
+impl GridTrack {
+    pub const DEFAULT: Self = Self::Auto;
+
+    /// `count` tracks of the same `size`, like `repeat()` in CSS
+    ///
+    /// ```
+    /// # use bevy_ui::GridTrack;
+    /// let columns = GridTrack::repeat(3, GridTrack::Fr(1.0));
+    /// assert_eq!(columns, vec![GridTrack::Fr(1.0); 3]);
+    /// ```
+    pub fn repeat(count: usize, size: GridTrack) -> Vec<GridTrack> {
+        vec![size; count]
+    }
+}
+
+impl Default for GridTrack {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Whether the items without a placement fill the rows or the columns of a grid
+///
+/// Part of the [`Style`] component.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect)]
+#[reflect(PartialEq, Serialize, Deserialize)]
+pub enum GridAutoFlow {
+    /// Fill each row before the next one, adding rows as needed
+    Row,
+    /// Fill each column before the next one, adding columns as needed
+    Column,
+}
+
+impl GridAutoFlow {
+    pub const DEFAULT: Self = Self::Row;
+}
+
+impl Default for GridAutoFlow {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Where an item is placed along the rows or the columns of its grid
+///
+/// Part of the [`Style`] component.
+#[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect)]
+#[reflect(PartialEq, Serialize, Deserialize)]
+pub struct GridPlacement {
+    /// The line the item starts at, counted from 1 at the start of the grid, or from -1 at the
+    /// end of its template
+    ///
+    /// The item is placed automatically when this is `None`.
+    pub start: Option<i16>,
+    /// The number of tracks the item spans
+    pub span: u16,
+}
+
+impl GridPlacement {
+    pub const DEFAULT: Self = Self {
+        start: None,
+        span: 1,
+    };
+
+    /// Placed automatically over `span` tracks
+    pub const fn span(span: u16) -> Self {
+        Self { start: None, span }
+    }
+
+    /// Placed on the track after the `start` line
+    pub const fn start(start: i16) -> Self {
+        Self {
+            start: Some(start),
+            span: 1,
+        }
+    }
+
+    /// Placed over `span` tracks from the `start` line
+    pub const fn start_span(start: i16, span: u16) -> Self {
+        Self {
+            start: Some(start),
+            span,
+        }
+    }
+}
+
+impl Default for GridPlacement {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
 /// Defines how flexbox items are ordered within a flexbox
 #[derive(Copy, Clone, PartialEq, Eq, Debug, Serialize, Deserialize, Reflect)]
 #[reflect(PartialEq, Serialize, Deserialize)]
@@ -539,6 +715,8 @@ pub enum Overflow {
     Visible,
     /// Hide overflowing items
     Hidden,
+    /// Hide overflowing items, and scroll them into view by the [`ScrollPosition`] of the node
+    Scroll,
 }
 
 impl Overflow {
@@ -642,6 +820,250 @@ impl From<Color> for BackgroundColor {
     }
 }
 
+/// The opacity of the node and its descendants, from 0 for transparent to 1 for opaque
+///
+/// It multiplies the alpha of the background color, the image, the borders and the text of the
+/// node and of all its descendants, whose own opacities are multiplied with it, to fade whole
+/// panels in and out. The nodes drawn with a [`UiMaterial`](crate::UiMaterial) aren't affected.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct UiOpacity(pub f32);
+
+impl UiOpacity {
+    pub const DEFAULT: Self = Self(1.0);
+}
+
+impl Default for UiOpacity {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The colors of the borders of the node, drawn over the widths set by
+/// [`Style::border`](Style)
+///
+/// The borders are drawn with the rounded corners of the [`BorderRadius`] of the node, over
+/// its background.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct BorderColor {
+    /// The color of the left border
+    pub left: Color,
+    /// The color of the top border
+    pub top: Color,
+    /// The color of the right border
+    pub right: Color,
+    /// The color of the bottom border
+    pub bottom: Color,
+}
+
+impl BorderColor {
+    pub const DEFAULT: Self = Self::all(Color::NONE);
+
+    /// The same `color` on every edge
+    pub const fn all(color: Color) -> Self {
+        Self {
+            left: color,
+            top: color,
+            right: color,
+            bottom: color,
+        }
+    }
+}
+
+impl Default for BorderColor {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl From<Color> for BorderColor {
+    fn from(color: Color) -> Self {
+        Self::all(color)
+    }
+}
+
+/// The radii of the rounded corners of the node, rounding its background, image and borders
+///
+/// A percentage is of the smallest side of the node, and the radii are limited to half of it,
+/// so [`BorderRadius::MAX`] rounds the short sides of the node into half circles.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Serialize, Deserialize, Reflect)]
+#[reflect(Component, Default, PartialEq, Serialize, Deserialize)]
+pub struct BorderRadius {
+    /// The radius of the top left corner
+    pub top_left: Val,
+    /// The radius of the top right corner
+    pub top_right: Val,
+    /// The radius of the bottom right corner
+    pub bottom_right: Val,
+    /// The radius of the bottom left corner
+    pub bottom_left: Val,
+}
+
+impl BorderRadius {
+    pub const DEFAULT: Self = Self::all(Val::Px(0.0));
+
+    /// The largest radius on every corner
+    pub const MAX: Self = Self::all(Val::Px(f32::MAX));
+
+    /// The same `radius` on every corner
+    pub const fn all(radius: Val) -> Self {
+        Self {
+            top_left: radius,
+            top_right: radius,
+            bottom_right: radius,
+            bottom_left: radius,
+        }
+    }
+
+    /// The `top` radius on the top corners, and the `bottom` radius on the bottom corners
+    pub const fn vertical(top: Val, bottom: Val) -> Self {
+        Self {
+            top_left: top,
+            top_right: top,
+            bottom_right: bottom,
+            bottom_left: bottom,
+        }
+    }
+
+    /// The `left` radius on the left corners, and the `right` radius on the right corners
+    pub const fn horizontal(left: Val, right: Val) -> Self {
+        Self {
+            top_left: left,
+            top_right: right,
+            bottom_right: right,
+            bottom_left: left,
+        }
+    }
+}
+
+impl Default for BorderRadius {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+impl From<Val> for BorderRadius {
+    fn from(radius: Val) -> Self {
+        Self::all(radius)
+    }
+}
+
+/// A shadow drawn behind the node, following its rounded corners
+///
+/// Like a CSS `box-shadow`, it's the shape of the node moved by the offsets, grown by the spread
+/// radius and blurred over the blur radius. Percentages are of the width of the node for
+/// [`x_offset`](Self::x_offset), of its height for [`y_offset`](Self::y_offset), and of its
+/// smallest side for the radii.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct BoxShadow {
+    /// The color of the shadow
+    pub color: Color,
+    /// How far the shadow is moved to the right
+    pub x_offset: Val,
+    /// How far the shadow is moved down
+    pub y_offset: Val,
+    /// How much the shadow is grown on every side, or shrunk when negative
+    pub spread_radius: Val,
+    /// How far the edges of the shadow are blurred, inwards and outwards
+    pub blur_radius: Val,
+}
+
+impl BoxShadow {
+    pub const DEFAULT: Self = Self {
+        color: Color::rgba(0.0, 0.0, 0.0, 0.5),
+        x_offset: Val::Px(0.0),
+        y_offset: Val::Px(0.0),
+        spread_radius: Val::Px(0.0),
+        blur_radius: Val::Px(0.0),
+    };
+}
+
+impl Default for BoxShadow {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// The maximum number of stops of a [`BackgroundGradient`], the next ones are ignored
+pub const MAX_GRADIENT_STOPS: usize = 4;
+
+/// A color of a [`BackgroundGradient`], at a position along the gradient
+#[derive(Copy, Clone, Debug, PartialEq, Reflect, FromReflect)]
+pub struct ColorStop {
+    /// The color at the stop
+    pub color: Color,
+    /// The position of the stop, from `0.0` at the start of the gradient to `1.0` at its end
+    pub position: f32,
+}
+
+impl ColorStop {
+    /// The `color` at `position`, from `0.0` to `1.0`
+    pub const fn new(color: Color, position: f32) -> Self {
+        Self { color, position }
+    }
+}
+
+/// A gradient filling the node instead of its [`BackgroundColor`], inside its rounded corners
+///
+/// It tints the [`UiImage`] of the node like a [`BackgroundColor`], but the image is always
+/// stretched. The colors are interpolated between the stops, and are the ones of the first and
+/// last stops before and after them. Up to [`MAX_GRADIENT_STOPS`] stops are drawn.
+#[derive(Component, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub enum BackgroundGradient {
+    /// A gradient along a line through the center of the node, long enough for its start and
+    /// end to be in the corners, like a CSS `linear-gradient`
+    Linear {
+        /// The direction of the line, in radians clockwise from the top of the node
+        angle: f32,
+        /// The colors along the line
+        stops: Vec<ColorStop>,
+    },
+    /// A circular gradient, ending in the farthest corner of the node
+    Radial {
+        /// The center of the gradient, as a fraction of the size of the node from its top left
+        center: Vec2,
+        /// The colors from the center
+        stops: Vec<ColorStop>,
+    },
+}
+
+impl BackgroundGradient {
+    /// A linear gradient towards `angle`, in radians clockwise from the top of the node
+    pub fn linear(angle: f32, stops: impl Into<Vec<ColorStop>>) -> Self {
+        Self::Linear {
+            angle,
+            stops: stops.into(),
+        }
+    }
+
+    /// A radial gradient around `center`, as a fraction of the size of the node
+    pub fn radial(center: Vec2, stops: impl Into<Vec<ColorStop>>) -> Self {
+        Self::Radial {
+            center,
+            stops: stops.into(),
+        }
+    }
+
+    /// The colors of the gradient
+    pub fn stops(&self) -> &[ColorStop] {
+        match self {
+            Self::Linear { stops, .. } | Self::Radial { stops, .. } => stops,
+        }
+    }
+}
+
+impl Default for BackgroundGradient {
+    fn default() -> Self {
+        Self::Linear {
+            angle: 0.0,
+            stops: Vec::new(),
+        }
+    }
+}
+
 /// The 2D texture displayed for this UI node
 #[derive(Component, Clone, Debug, Reflect)]
 #[reflect(Component, Default)]
@@ -652,6 +1074,8 @@ pub struct UiImage {
     pub flip_x: bool,
     /// Whether the image should be flipped along its y-axis
     pub flip_y: bool,
+    /// How the image fills the node when their sizes differ
+    pub scale_mode: ImageScaleMode,
 }
 
 impl Default for UiImage {
@@ -660,6 +1084,7 @@ impl Default for UiImage {
             texture: DEFAULT_IMAGE_HANDLE.typed(),
             flip_x: false,
             flip_y: false,
+            scale_mode: ImageScaleMode::Stretched,
         }
     }
 }
@@ -679,12 +1104,151 @@ impl From<Handle<Image>> for UiImage {
     }
 }
 
+/// The region of a [`TextureAtlas`](bevy_sprite::TextureAtlas) displayed for this UI node,
+/// from the `Handle<TextureAtlas>` next to it
+///
+/// Like a [`UiImage`], it can be sliced or tiled to fill the node with its [`ImageScaleMode`],
+/// so that skinned panels and buttons can be packed in a single texture.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct UiTextureAtlasImage {
+    /// The index of the region of the atlas displayed
+    pub index: usize,
+    /// Whether the image should be flipped along its x-axis
+    pub flip_x: bool,
+    /// Whether the image should be flipped along its y-axis
+    pub flip_y: bool,
+    /// How the region fills the node when their sizes differ
+    pub scale_mode: ImageScaleMode,
+}
+
+impl UiTextureAtlasImage {
+    /// The region of the atlas at `index`
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            ..Default::default()
+        }
+    }
+}
+
 /// The calculated clip of the node
-#[derive(Component, Default, Copy, Clone, Debug, Reflect)]
+///
+/// It's the intersection of the insides of the borders of the ancestors with an
+/// [`Overflow::Hidden`] or an [`Overflow::Scroll`], rounded by their [`BorderRadius`].
+#[derive(Component, Default, Copy, Clone, Debug, PartialEq, Reflect)]
 #[reflect(Component)]
 pub struct CalculatedClip {
     /// The rect of the clip
     pub clip: Rect,
+    /// The radii of the rounded corners of the clip: top left, top right, bottom right and
+    /// bottom left
+    pub radius: [f32; 4],
+}
+
+impl CalculatedClip {
+    /// The intersection of this clip and `other`
+    ///
+    /// The corners of the intersection that are corners of a clip are rounded like the corners
+    /// of the clip, by the largest radius when they're corners of both.
+    pub fn intersect(&self, other: &CalculatedClip) -> Self {
+        let clip = self.clip.intersect(other.clip);
+        let corners = |rect: Rect| {
+            [
+                rect.min,
+                Vec2::new(rect.max.x, rect.min.y),
+                rect.max,
+                Vec2::new(rect.min.x, rect.max.y),
+            ]
+        };
+        let mut radius = [0.0_f32; 4];
+        for other in [self, other] {
+            for (i, corner) in corners(other.clip).into_iter().enumerate() {
+                if corner == corners(clip)[i] {
+                    radius[i] = radius[i].max(other.radius[i]);
+                }
+            }
+        }
+        let max_radius = (clip.size().min_element() / 2.0).max(0.0);
+        Self {
+            clip,
+            radius: radius.map(|radius| radius.min(max_radius)),
+        }
+    }
+}
+
+/// The calculated opacity of the node, multiplying the [`UiOpacity`] of the node and its
+/// ancestors
+///
+/// Only the nodes with an opacity other than 1 have it.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, PartialEq)]
+pub struct CalculatedOpacity {
+    /// The opacity multiplying the alpha of the colors of the node
+    pub opacity: f32,
+}
+
+impl Default for CalculatedOpacity {
+    fn default() -> Self {
+        Self { opacity: 1.0 }
+    }
+}
+
+/// How far the content of a node with [`Overflow::Scroll`] is scrolled
+///
+/// Scrolled by the mouse wheel and touch drags in [`super::scroll::ui_scroll_system`], and
+/// clamped to the content overflowing the node by [`super::flex::flex_node_system`].
+#[derive(Component, Copy, Clone, Default, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct ScrollPosition {
+    /// How far the content is scrolled to the left and up, in logical pixels
+    pub offset: Vec2,
+    /// The offset where the end of the content is shown
+    pub(crate) max_offset: Vec2,
+}
+
+impl ScrollPosition {
+    pub fn new(offset: Vec2) -> Self {
+        Self {
+            offset,
+            max_offset: Vec2::ZERO,
+        }
+    }
+
+    /// The offset where the end of the content is shown, in logical pixels
+    /// automatically calculated by [`super::flex::flex_node_system`]
+    ///
+    /// Zero on the axes the content doesn't overflow the node.
+    pub fn max_offset(&self) -> Vec2 {
+        self.max_offset
+    }
+}
+
+/// Draws scrollbars on the edges of a node with [`Overflow::Scroll`], on the axes its content
+/// overflows
+#[derive(Component, Copy, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct Scrollbar {
+    /// The thickness of the scrollbars in logical pixels
+    pub width: f32,
+    /// The color of the tracks along the edges of the node
+    pub track_color: Color,
+    /// The color of the thumbs, sized and positioned by the visible part of the content
+    pub thumb_color: Color,
+}
+
+impl Scrollbar {
+    pub const DEFAULT: Self = Self {
+        width: 8.0,
+        track_color: Color::rgba(0.0, 0.0, 0.0, 0.2),
+        thumb_color: Color::rgba(1.0, 1.0, 1.0, 0.5),
+    };
+}
+
+impl Default for Scrollbar {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
 
 /// Indicates that this [`Node`] entity's front-to-back ordering is not controlled solely
@@ -696,8 +1260,9 @@ pub struct CalculatedClip {
 /// will appear in front of this parent's other children.
 ///
 /// Internally, nodes with a global z-index share the stacking context of root UI nodes
-/// (nodes that have no parent). Because of this, there is no difference between using
-/// [`ZIndex::Local(n)`] and [`ZIndex::Global(n)`] for root nodes.
+/// (nodes that have no parent), or of their closest ancestor with a [`StackingContext`].
+/// Because of this, there is no difference between using [`ZIndex::Local(n)`] and
+/// [`ZIndex::Global(n)`] for root nodes.
 ///
 /// Nodes without this component will be treated as if they had a value of [`ZIndex::Local(0)`].
 #[derive(Component, Copy, Clone, Debug, Reflect)]
@@ -715,11 +1280,22 @@ impl Default for ZIndex {
     }
 }
 
+/// Makes this [`Node`] an explicit stacking context: the descendants with a [`ZIndex::Global`]
+/// are ordered among the children of this node instead of among the root nodes.
+///
+/// The descendants stay in front of the nodes behind this node and behind the nodes in front of
+/// it, whatever their z-index. Like for a modal window, whose dropdowns should appear in front of
+/// its other widgets but not in front of the other windows.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct StackingContext;
+
 #[cfg(test)]
 mod tests {
     use crate::ValArithmeticError;
+    use bevy_math::Rect;
 
-    use super::Val;
+    use super::{CalculatedClip, Val};
 
     #[test]
     fn val_try_add() {
@@ -878,4 +1454,26 @@ mod tests {
             "the given variant of Val is not evaluateable (non-numeric)"
         );
     }
+
+    #[test]
+    fn calculated_clip_intersect_keeps_the_radii_of_its_corners() {
+        let outer = CalculatedClip {
+            clip: Rect::new(0.0, 0.0, 100.0, 100.0),
+            radius: [10.0; 4],
+        };
+        // Shares the top left corner of the outer clip, and sticks out of its bottom right
+        let inner = CalculatedClip {
+            clip: Rect::new(0.0, 0.0, 150.0, 50.0),
+            radius: [4.0, 30.0, 30.0, 4.0],
+        };
+
+        let clip = inner.intersect(&outer);
+
+        assert_eq!(clip.clip, Rect::new(0.0, 0.0, 100.0, 50.0));
+        // The top left corner is rounded by the largest radius, the top right one like the
+        // outer clip, the bottom left one like the inner clip, and the bottom right one is
+        // between the edges of different clips
+        assert_eq!(clip.radius, [10.0, 10.0, 0.0, 4.0]);
+        assert_eq!(clip, outer.intersect(&inner));
+    }
 }
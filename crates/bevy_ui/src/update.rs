@@ -1,22 +1,30 @@
 //! This module contains systems that update the UI when something changes
 
-use crate::{CalculatedClip, Overflow, Style};
+use crate::{
+    render::resolve_val, BorderRadius, CalculatedClip, CalculatedOpacity, Overflow, Style,
+    UiOpacity, UiScale,
+};
 
 use super::Node;
 use bevy_ecs::{
     entity::Entity,
     query::{With, Without},
-    system::{Commands, Query},
+    system::{Commands, Query, Res},
 };
 use bevy_hierarchy::{Children, Parent};
-use bevy_math::Rect;
+use bevy_math::{Rect, Vec2};
 use bevy_transform::components::GlobalTransform;
 
 /// Updates clipping for all nodes
+///
+/// The descendants of the nodes with an [`Overflow::Hidden`] or an [`Overflow::Scroll`] are
+/// clipped to the inside of their borders, rounded like the content of the node by its
+/// [`BorderRadius`], and the clips of nested nodes are intersected.
 pub fn update_clipping_system(
     mut commands: Commands,
+    ui_scale: Res<UiScale>,
     root_node_query: Query<Entity, (With<Node>, Without<Parent>)>,
-    mut node_query: Query<(&Node, &GlobalTransform, &Style, Option<&mut CalculatedClip>)>,
+    mut node_query: Query<ClipNodeQuery>,
     children_query: Query<&Children>,
 ) {
     for root_node in &root_node_query {
@@ -26,18 +34,31 @@ pub fn update_clipping_system(
             &mut node_query,
             root_node,
             None,
+            0.0,
+            ui_scale.scale,
         );
     }
 }
 
+type ClipNodeQuery = (
+    &'static Node,
+    &'static GlobalTransform,
+    &'static Style,
+    Option<&'static BorderRadius>,
+    Option<&'static mut CalculatedClip>,
+);
+
 fn update_clipping(
     commands: &mut Commands,
     children_query: &Query<&Children>,
-    node_query: &mut Query<(&Node, &GlobalTransform, &Style, Option<&mut CalculatedClip>)>,
+    node_query: &mut Query<ClipNodeQuery>,
     entity: Entity,
-    clip: Option<Rect>,
+    clip: Option<CalculatedClip>,
+    parent_width: f32,
+    ui_scale: f64,
 ) {
-    let (node, global_transform, style, calculated_clip) = node_query.get_mut(entity).unwrap();
+    let (node, global_transform, style, border_radius, calculated_clip) =
+        node_query.get_mut(entity).unwrap();
     // Update this node's CalculatedClip component
     match (clip, calculated_clip) {
         (None, None) => {}
@@ -45,11 +66,11 @@ fn update_clipping(
             commands.entity(entity).remove::<CalculatedClip>();
         }
         (Some(clip), None) => {
-            commands.entity(entity).insert(CalculatedClip { clip });
+            commands.entity(entity).insert(clip);
         }
         (Some(clip), Some(mut old_clip)) => {
-            if old_clip.clip != clip {
-                *old_clip = CalculatedClip { clip };
+            if *old_clip != clip {
+                *old_clip = clip;
             }
         }
     }
@@ -57,16 +78,175 @@ fn update_clipping(
     // Calculate new clip for its children
     let children_clip = match style.overflow {
         Overflow::Visible => clip,
-        Overflow::Hidden => {
-            let node_center = global_transform.translation().truncate();
-            let node_rect = Rect::from_center_size(node_center, node.calculated_size);
-            Some(clip.map_or(node_rect, |c| c.intersect(node_rect)))
+        Overflow::Hidden | Overflow::Scroll => {
+            let size = node.size();
+            let node_rect = Rect::from_center_size(global_transform.translation().truncate(), size);
+            // Percentages of borders are of the width of the parent, like in the layout
+            let [left, top, right, bottom] = [
+                style.border.left,
+                style.border.top,
+                style.border.right,
+                style.border.bottom,
+            ]
+            .map(|width| resolve_val(width, parent_width, ui_scale).max(0.0));
+            let min = node_rect.min + Vec2::new(left, top);
+            let content_rect = Rect {
+                min,
+                max: (node_rect.max - Vec2::new(right, bottom)).max(min),
+            };
+
+            // The corners of the content are rounded by what the borders leave of the outer
+            // radius, like when they're drawn
+            let min_side = size.min_element();
+            let radius = border_radius.map_or([0.0; 4], |radius| {
+                [
+                    radius.top_left,
+                    radius.top_right,
+                    radius.bottom_right,
+                    radius.bottom_left,
+                ]
+                .map(|radius| resolve_val(radius, min_side, ui_scale).clamp(0.0, min_side / 2.0))
+            });
+            let node_clip = CalculatedClip {
+                clip: content_rect,
+                radius: [
+                    radius[0] - left.max(top),
+                    radius[1] - right.max(top),
+                    radius[2] - right.max(bottom),
+                    radius[3] - left.max(bottom),
+                ]
+                .map(|radius| radius.max(0.0)),
+            };
+            Some(clip.map_or(node_clip, |clip| clip.intersect(&node_clip)))
         }
     };
 
+    let width = node.size().x;
     if let Ok(children) = children_query.get(entity) {
         for child in children.iter().cloned() {
-            update_clipping(commands, children_query, node_query, child, children_clip);
+            update_clipping(
+                commands,
+                children_query,
+                node_query,
+                child,
+                children_clip,
+                width,
+                ui_scale,
+            );
+        }
+    }
+}
+
+/// Updates the [`CalculatedOpacity`] of all nodes from their [`UiOpacity`] and the one of their
+/// ancestors
+pub fn update_opacity_system(
+    mut commands: Commands,
+    root_node_query: Query<Entity, (With<Node>, Without<Parent>)>,
+    mut node_query: Query<(Option<&UiOpacity>, Option<&mut CalculatedOpacity>), With<Node>>,
+    children_query: Query<&Children>,
+) {
+    for root_node in &root_node_query {
+        update_opacity(
+            &mut commands,
+            &children_query,
+            &mut node_query,
+            root_node,
+            1.0,
+        );
+    }
+}
+
+fn update_opacity(
+    commands: &mut Commands,
+    children_query: &Query<&Children>,
+    node_query: &mut Query<(Option<&UiOpacity>, Option<&mut CalculatedOpacity>), With<Node>>,
+    entity: Entity,
+    parent_opacity: f32,
+) {
+    let Ok((opacity, calculated_opacity)) = node_query.get_mut(entity) else {
+        return;
+    };
+    let opacity = parent_opacity * opacity.map_or(1.0, |opacity| opacity.0.clamp(0.0, 1.0));
+    // Update this node's CalculatedOpacity component
+    match (opacity != 1.0, calculated_opacity) {
+        (false, None) => {}
+        (false, Some(_)) => {
+            commands.entity(entity).remove::<CalculatedOpacity>();
+        }
+        (true, None) => {
+            commands
+                .entity(entity)
+                .insert(CalculatedOpacity { opacity });
+        }
+        (true, Some(mut old_opacity)) => {
+            if old_opacity.opacity != opacity {
+                old_opacity.opacity = opacity;
+            }
+        }
+    }
+
+    if let Ok(children) = children_query.get(entity) {
+        for child in children.iter().cloned() {
+            update_opacity(commands, children_query, node_query, child, opacity);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::Schedule, world::World};
+    use bevy_hierarchy::BuildWorldChildren;
+
+    fn calculated_opacity(world: &World, entity: Entity) -> Option<f32> {
+        world
+            .get::<CalculatedOpacity>(entity)
+            .map(|calculated_opacity| calculated_opacity.opacity)
+    }
+
+    #[test]
+    fn opacity_multiplied_down_the_hierarchy() {
+        let mut world = World::new();
+        let mut schedule = Schedule::new();
+        schedule.add_system(update_opacity_system);
+
+        let opaque_root = world.spawn(Node::default()).id();
+        let grandchild = world.spawn(Node::default()).id();
+        let child = world
+            .spawn((Node::default(), UiOpacity(0.5)))
+            .push_children(&[grandchild])
+            .id();
+        let bright_child = world.spawn((Node::default(), UiOpacity(2.0))).id();
+        let transparent_child = world.spawn((Node::default(), UiOpacity(0.0))).id();
+        let root = world
+            .spawn((Node::default(), UiOpacity(0.5)))
+            .push_children(&[child, bright_child, transparent_child])
+            .id();
+        schedule.run(&mut world);
+
+        let cases = [
+            (opaque_root, None, "opaque nodes have no calculated opacity"),
+            (root, Some(0.5), "root"),
+            (child, Some(0.25), "multiplied by its parent"),
+            (grandchild, Some(0.25), "inherited from its ancestors"),
+            (bright_child, Some(0.5), "clamped to 1"),
+            (transparent_child, Some(0.0), "transparent"),
+        ];
+        for (entity, expected, message) in cases {
+            assert_eq!(calculated_opacity(&world, entity), expected, "{message}");
+        }
+
+        world.get_mut::<UiOpacity>(child).unwrap().0 = 0.2;
+        schedule.run(&mut world);
+        assert_eq!(calculated_opacity(&world, grandchild), Some(0.1));
+
+        // the nodes becoming opaque have their calculated opacity removed
+        world.entity_mut(root).remove::<UiOpacity>();
+        world.get_mut::<UiOpacity>(child).unwrap().0 = 1.0;
+        schedule.run(&mut world);
+        for entity in [root, child, grandchild, bright_child] {
+            assert_eq!(calculated_opacity(&world, entity), None, "{entity:?}");
         }
+        assert_eq!(calculated_opacity(&world, transparent_child), Some(0.0));
     }
 }
@@ -0,0 +1,208 @@
+use crate::Interaction;
+use bevy_ecs::{
+    change_detection::DetectChangesMut,
+    entity::Entity,
+    event::EventWriter,
+    prelude::{Component, With},
+    query::Changed,
+    reflect::ReflectComponent,
+    system::Query,
+};
+use bevy_hierarchy::{Children, HierarchyQueryExt};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::view::Visibility;
+
+/// A checkbox, checked and unchecked when clicked
+///
+/// Like a [`Button`](super::Button), it's clicked with the mouse, or with the confirm button of
+/// the [`NavigationBindings`](crate::NavigationBindings) while focused, so it needs an
+/// [`Interaction`]. Its [`CheckMark`] descendants are shown while it's checked.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct Checkbox {
+    /// Whether the checkbox is checked
+    pub checked: bool,
+}
+
+impl Checkbox {
+    /// A checkbox checked or not
+    pub const fn new(checked: bool) -> Self {
+        Self { checked }
+    }
+}
+
+/// Marks the descendant of a [`Checkbox`] or a [`RadioButton`](super::RadioButton) shown while
+/// it's checked, like a tick or a dot
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct CheckMark;
+
+/// An event sent when a [`Checkbox`] is checked or unchecked by a click
+#[derive(Clone, Debug)]
+pub struct CheckboxChanged {
+    /// The checkbox
+    pub entity: Entity,
+    /// Whether the checkbox is now checked
+    pub checked: bool,
+}
+
+/// The system that toggles the [`Checkbox`]es when clicked
+pub fn checkbox_system(
+    mut checkbox_query: Query<(Entity, &Interaction, &mut Checkbox), Changed<Interaction>>,
+    mut changed_events: EventWriter<CheckboxChanged>,
+) {
+    for (entity, interaction, mut checkbox) in &mut checkbox_query {
+        if *interaction == Interaction::Clicked {
+            checkbox.checked = !checkbox.checked;
+            changed_events.send(CheckboxChanged {
+                entity,
+                checked: checkbox.checked,
+            });
+        }
+    }
+}
+
+/// The system that shows the [`CheckMark`]s of the checked [`Checkbox`]es, and hides the others
+pub fn checkbox_mark_system(
+    checkbox_query: Query<(Entity, &Checkbox), Changed<Checkbox>>,
+    children_query: Query<&Children>,
+    mut mark_query: Query<&mut Visibility, With<CheckMark>>,
+) {
+    for (entity, checkbox) in &checkbox_query {
+        set_check_marks(entity, checkbox.checked, &children_query, &mut mark_query);
+    }
+}
+
+/// Shows or hides the [`CheckMark`]s among the descendants of `entity`
+pub(crate) fn set_check_marks(
+    entity: Entity,
+    checked: bool,
+    children_query: &Query<&Children>,
+    mark_query: &mut Query<&mut Visibility, With<CheckMark>>,
+) {
+    let visibility = if checked {
+        Visibility::Inherited
+    } else {
+        Visibility::Hidden
+    };
+    for descendant in children_query.iter_descendants(entity) {
+        if let Ok(mut mark_visibility) = mark_query.get_mut(descendant) {
+            mark_visibility.set_if_neq(visibility);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{
+        event::Events,
+        schedule::{IntoSystemConfig, Schedule},
+        world::World,
+    };
+    use bevy_hierarchy::BuildWorldChildren;
+
+    /// A checkbox with a mark, updated by running the checkbox systems
+    struct Form {
+        world: World,
+        schedule: Schedule,
+        checkbox: Entity,
+        mark: Entity,
+    }
+
+    impl Form {
+        fn new(checked: bool) -> Self {
+            let mut world = World::new();
+            world.init_resource::<Events<CheckboxChanged>>();
+            let mark = world.spawn((CheckMark, Visibility::Inherited)).id();
+            let checkbox = world
+                .spawn((Checkbox::new(checked), Interaction::None))
+                .push_children(&[mark])
+                .id();
+            let mut schedule = Schedule::new();
+            schedule.add_system(checkbox_system);
+            schedule.add_system(checkbox_mark_system.after(checkbox_system));
+            schedule.run(&mut world);
+            Self {
+                world,
+                schedule,
+                checkbox,
+                mark,
+            }
+        }
+
+        /// Clicks the checkbox, with the mouse or the confirm button of the navigation, and runs
+        /// the systems
+        fn click(&mut self) {
+            *self.world.get_mut::<Interaction>(self.checkbox).unwrap() = Interaction::Clicked;
+            self.schedule.run(&mut self.world);
+            *self.world.get_mut::<Interaction>(self.checkbox).unwrap() = Interaction::None;
+            self.schedule.run(&mut self.world);
+        }
+
+        fn checked(&self) -> bool {
+            self.world.get::<Checkbox>(self.checkbox).unwrap().checked
+        }
+
+        fn mark_visibility(&self) -> Visibility {
+            *self.world.get::<Visibility>(self.mark).unwrap()
+        }
+
+        fn changes(&mut self) -> Vec<bool> {
+            self.world
+                .resource_mut::<Events<CheckboxChanged>>()
+                .drain()
+                .map(|event| {
+                    assert_eq!(event.entity, self.checkbox);
+                    event.checked
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn marks_follow_the_initial_value() {
+        assert_eq!(Form::new(true).mark_visibility(), Visibility::Inherited);
+        assert_eq!(Form::new(false).mark_visibility(), Visibility::Hidden);
+    }
+
+    #[test]
+    fn clicks_toggle_the_checkbox() {
+        let mut form = Form::new(false);
+        assert!(form.changes().is_empty());
+
+        form.click();
+        assert!(form.checked());
+        assert_eq!(form.mark_visibility(), Visibility::Inherited);
+        assert_eq!(form.changes(), [true]);
+
+        form.click();
+        assert!(!form.checked());
+        assert_eq!(form.mark_visibility(), Visibility::Hidden);
+        assert_eq!(form.changes(), [false]);
+    }
+
+    #[test]
+    fn hovering_and_releasing_dont_toggle() {
+        let mut form = Form::new(false);
+        for interaction in [Interaction::Hovered, Interaction::None] {
+            *form.world.get_mut::<Interaction>(form.checkbox).unwrap() = interaction;
+            form.schedule.run(&mut form.world);
+        }
+        assert!(!form.checked());
+        assert!(form.changes().is_empty());
+    }
+
+    #[test]
+    fn setting_the_value_updates_the_marks() {
+        let mut form = Form::new(false);
+        form.world
+            .get_mut::<Checkbox>(form.checkbox)
+            .unwrap()
+            .checked = true;
+        form.schedule.run(&mut form.world);
+        assert_eq!(form.mark_visibility(), Visibility::Inherited);
+        // only clicks are reported
+        assert!(form.changes().is_empty());
+    }
+}
@@ -0,0 +1,470 @@
+use super::{set_check_marks, CheckMark};
+use crate::{Display, Interaction, NavigationCancel, NavigationFocus, Style};
+use bevy_ecs::{
+    entity::Entity,
+    event::{EventReader, EventWriter},
+    prelude::{Component, With},
+    query::Changed,
+    reflect::ReflectComponent,
+    system::{Query, Res, ResMut},
+};
+use bevy_hierarchy::{Children, HierarchyQueryExt, Parent};
+use bevy_input::{mouse::MouseButton, touch::Touches, Input};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::view::Visibility;
+
+/// A button opening and closing a list of [`DropdownOption`]s when clicked, of which one is
+/// selected
+///
+/// Like a [`Button`](super::Button), it's clicked with the mouse, or with the confirm button of
+/// the [`NavigationBindings`](crate::NavigationBindings) while focused, so it needs an
+/// [`Interaction`]. Its [`DropdownList`] descendants are shown while it's open, and the
+/// [`CheckMark`]s of its selected option.
+///
+/// The dropdown is closed when an option is clicked, when clicking anywhere else, or on a
+/// [`NavigationCancel`] while it or one of its options is focused. The navigation focuses the
+/// selected option when the dropdown is opened while focused, and the dropdown again once it's
+/// closed.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct Dropdown {
+    /// The value of the selected option, if any
+    pub selected: Option<usize>,
+    /// Whether the list of options is shown
+    pub open: bool,
+}
+
+impl Dropdown {
+    /// A closed dropdown with the option of the `selected` value selected
+    pub const fn new(selected: usize) -> Self {
+        Self {
+            selected: Some(selected),
+            open: false,
+        }
+    }
+}
+
+/// Marks the descendant of a [`Dropdown`] holding its options, displayed while it's open
+///
+/// It usually has a [`PositionType::Absolute`](crate::PositionType::Absolute) to be shown under
+/// the dropdown without moving the other nodes, and a global [`ZIndex`](crate::ZIndex) to be
+/// drawn over them.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct DropdownList;
+
+/// An option of its closest [`Dropdown`] ancestor, selecting its value when clicked
+///
+/// Like a [`Button`](super::Button), it needs an [`Interaction`].
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct DropdownOption {
+    /// The value selected by the option, like its index
+    pub value: usize,
+}
+
+impl DropdownOption {
+    /// An option selecting `value`
+    pub const fn new(value: usize) -> Self {
+        Self { value }
+    }
+}
+
+/// An event sent when another [`DropdownOption`] of a [`Dropdown`] is selected by a click
+#[derive(Clone, Debug)]
+pub struct DropdownChanged {
+    /// The dropdown
+    pub entity: Entity,
+    /// The value of the selected option
+    pub selected: usize,
+}
+
+/// The system that opens and closes the [`Dropdown`]s, and selects their [`DropdownOption`]s
+/// when clicked
+#[allow(clippy::too_many_arguments)]
+pub fn dropdown_system(
+    mouse_button_input: Res<Input<MouseButton>>,
+    touches_input: Res<Touches>,
+    mut focus: ResMut<NavigationFocus>,
+    mut cancel_events: EventReader<NavigationCancel>,
+    clicked_query: Query<(Entity, &Interaction), Changed<Interaction>>,
+    option_query: Query<&DropdownOption>,
+    parent_query: Query<&Parent>,
+    children_query: Query<&Children>,
+    mut dropdown_query: Query<(Entity, &mut Dropdown)>,
+    mut changed_events: EventWriter<DropdownChanged>,
+) {
+    let mut clicked_dropdowns = Vec::new();
+    for (entity, interaction) in &clicked_query {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        if dropdown_query.contains(entity) {
+            clicked_dropdowns.push(entity);
+            let Ok((_, mut dropdown)) = dropdown_query.get_mut(entity) else {
+                continue;
+            };
+            dropdown.open = !dropdown.open;
+            // Opened with the keyboard or a gamepad, the navigation continues in the options
+            if dropdown.open && focus.is_focused(entity) {
+                let options: Vec<(Entity, usize)> = children_query
+                    .iter_descendants(entity)
+                    .filter_map(|option| Some((option, option_query.get(option).ok()?.value)))
+                    .collect();
+                let option = options
+                    .iter()
+                    .find(|(_, value)| Some(*value) == dropdown.selected)
+                    .or(options.first());
+                if let Some((option, _)) = option {
+                    focus.focus(*option);
+                }
+            }
+        } else if let (Ok(option), Some(dropdown_entity)) = (
+            option_query.get(entity),
+            dropdown_of(entity, &parent_query, &dropdown_query),
+        ) {
+            clicked_dropdowns.push(dropdown_entity);
+            let Ok((_, mut dropdown)) = dropdown_query.get_mut(dropdown_entity) else {
+                continue;
+            };
+            dropdown.open = false;
+            if dropdown.selected != Some(option.value) {
+                dropdown.selected = Some(option.value);
+                changed_events.send(DropdownChanged {
+                    entity: dropdown_entity,
+                    selected: option.value,
+                });
+            }
+            if focus.is_focused(entity) {
+                focus.focus(dropdown_entity);
+            }
+        }
+    }
+
+    for event in cancel_events.iter() {
+        let Some(focused) = event.focused else {
+            continue;
+        };
+        let dropdown_entity = if dropdown_query.contains(focused) {
+            focused
+        } else if let Some(dropdown_entity) = dropdown_of(focused, &parent_query, &dropdown_query) {
+            dropdown_entity
+        } else {
+            continue;
+        };
+        if let Ok((_, mut dropdown)) = dropdown_query.get_mut(dropdown_entity) {
+            if dropdown.open {
+                dropdown.open = false;
+                focus.focus(dropdown_entity);
+            }
+        }
+    }
+
+    // Clicking anywhere else closes the open dropdowns
+    if mouse_button_input.just_pressed(MouseButton::Left) || touches_input.any_just_pressed() {
+        for (entity, mut dropdown) in &mut dropdown_query {
+            if dropdown.open && !clicked_dropdowns.contains(&entity) {
+                dropdown.open = false;
+            }
+        }
+    }
+}
+
+/// The closest [`Dropdown`] ancestor of `entity`
+fn dropdown_of(
+    entity: Entity,
+    parent_query: &Query<&Parent>,
+    dropdown_query: &Query<(Entity, &mut Dropdown)>,
+) -> Option<Entity> {
+    parent_query
+        .iter_ancestors(entity)
+        .find(|ancestor| dropdown_query.contains(*ancestor))
+}
+
+/// The system that displays the [`DropdownList`]s of the open [`Dropdown`]s, hides the others,
+/// and shows the [`CheckMark`]s of the selected options
+pub fn dropdown_list_system(
+    dropdown_query: Query<(Entity, &Dropdown), Changed<Dropdown>>,
+    option_query: Query<&DropdownOption>,
+    children_query: Query<&Children>,
+    mut list_query: Query<&mut Style, With<DropdownList>>,
+    mut mark_query: Query<&mut Visibility, With<CheckMark>>,
+) {
+    for (entity, dropdown) in &dropdown_query {
+        let display = if dropdown.open {
+            Display::Flex
+        } else {
+            Display::None
+        };
+        for descendant in children_query.iter_descendants(entity) {
+            if let Ok(mut style) = list_query.get_mut(descendant) {
+                if style.display != display {
+                    style.display = display;
+                }
+            }
+            if let Ok(option) = option_query.get(descendant) {
+                let checked = dropdown.selected == Some(option.value);
+                set_check_marks(descendant, checked, &children_query, &mut mark_query);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{
+        event::Events,
+        schedule::{IntoSystemConfig, Schedule},
+        world::World,
+    };
+    use bevy_hierarchy::BuildWorldChildren;
+
+    /// A dropdown of three options with a mark each, updated by running the dropdown systems
+    struct Form {
+        world: World,
+        schedule: Schedule,
+        dropdown: Entity,
+        list: Entity,
+        options: [Entity; 3],
+        marks: [Entity; 3],
+    }
+
+    impl Form {
+        fn new(dropdown: Dropdown) -> Self {
+            let mut world = World::new();
+            world.init_resource::<Input<MouseButton>>();
+            world.init_resource::<Touches>();
+            world.init_resource::<NavigationFocus>();
+            world.init_resource::<Events<NavigationCancel>>();
+            world.init_resource::<Events<DropdownChanged>>();
+            let marks = [(); 3].map(|_| world.spawn((CheckMark, Visibility::Inherited)).id());
+            let options = [0, 1, 2].map(|value| {
+                world
+                    .spawn((DropdownOption::new(value), Interaction::None))
+                    .push_children(&[marks[value]])
+                    .id()
+            });
+            let list = world
+                .spawn((DropdownList, Style::default()))
+                .push_children(&options)
+                .id();
+            let dropdown = world
+                .spawn((dropdown, Interaction::None))
+                .push_children(&[list])
+                .id();
+            let mut schedule = Schedule::new();
+            schedule.add_system(dropdown_system);
+            schedule.add_system(dropdown_list_system.after(dropdown_system));
+            schedule.run(&mut world);
+            Self {
+                world,
+                schedule,
+                dropdown,
+                list,
+                options,
+                marks,
+            }
+        }
+
+        /// Runs the systems with `entity` clicked, with the mouse if `mouse`, or else with the
+        /// confirm button of the navigation
+        fn click(&mut self, entity: Entity, mouse: bool) {
+            if mouse {
+                self.world
+                    .resource_mut::<Input<MouseButton>>()
+                    .press(MouseButton::Left);
+            }
+            *self.world.get_mut::<Interaction>(entity).unwrap() = Interaction::Clicked;
+            self.schedule.run(&mut self.world);
+            *self.world.get_mut::<Interaction>(entity).unwrap() = Interaction::None;
+            self.release();
+        }
+
+        /// Runs the systems with the mouse pressed over no node
+        fn click_elsewhere(&mut self) {
+            self.world
+                .resource_mut::<Input<MouseButton>>()
+                .press(MouseButton::Left);
+            self.schedule.run(&mut self.world);
+            self.release();
+        }
+
+        fn release(&mut self) {
+            let mut mouse_button_input = self.world.resource_mut::<Input<MouseButton>>();
+            mouse_button_input.release(MouseButton::Left);
+            mouse_button_input.clear();
+            self.schedule.run(&mut self.world);
+        }
+
+        fn cancel(&mut self) {
+            let focused = self.focused();
+            self.world.send_event(NavigationCancel { focused });
+            self.schedule.run(&mut self.world);
+        }
+
+        fn focus(&mut self, entity: Entity) {
+            self.world.resource_mut::<NavigationFocus>().focus(entity);
+        }
+
+        fn focused(&self) -> Option<Entity> {
+            self.world.resource::<NavigationFocus>().focused()
+        }
+
+        fn dropdown(&self) -> Dropdown {
+            *self.world.get::<Dropdown>(self.dropdown).unwrap()
+        }
+
+        fn list_display(&self) -> Display {
+            self.world.get::<Style>(self.list).unwrap().display
+        }
+
+        /// The values of the options whose mark is shown
+        fn marked(&self) -> Vec<usize> {
+            (0..3)
+                .filter(|value| {
+                    self.world.get::<Visibility>(self.marks[*value]) == Some(&Visibility::Inherited)
+                })
+                .collect()
+        }
+
+        fn changes(&mut self) -> Vec<usize> {
+            self.world
+                .resource_mut::<Events<DropdownChanged>>()
+                .drain()
+                .map(|event| {
+                    assert_eq!(event.entity, self.dropdown);
+                    event.selected
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn starts_closed_with_the_selection_marked() {
+        let form = Form::new(Dropdown::new(1));
+        assert_eq!(form.list_display(), Display::None);
+        assert_eq!(form.marked(), [1]);
+    }
+
+    #[test]
+    fn clicking_opens_and_closes() {
+        let mut form = Form::new(Dropdown::new(0));
+        form.click(form.dropdown, true);
+        assert!(form.dropdown().open);
+        assert_eq!(form.list_display(), Display::Flex);
+
+        form.click(form.dropdown, true);
+        assert!(!form.dropdown().open);
+        assert_eq!(form.list_display(), Display::None);
+        assert!(form.changes().is_empty());
+    }
+
+    #[test]
+    fn clicking_an_option_selects_it_and_closes() {
+        let mut form = Form::new(Dropdown::new(0));
+        form.click(form.dropdown, true);
+        form.click(form.options[2], true);
+        assert_eq!(
+            form.dropdown(),
+            Dropdown {
+                selected: Some(2),
+                open: false
+            }
+        );
+        assert_eq!(form.list_display(), Display::None);
+        assert_eq!(form.marked(), [2]);
+        assert_eq!(form.changes(), [2]);
+
+        // selecting the same option again only closes the dropdown
+        form.click(form.dropdown, true);
+        form.click(form.options[2], true);
+        assert!(!form.dropdown().open);
+        assert!(form.changes().is_empty());
+    }
+
+    #[test]
+    fn clicking_elsewhere_closes() {
+        let mut form = Form::new(Dropdown::new(0));
+        form.click(form.dropdown, true);
+        form.click_elsewhere();
+        assert_eq!(
+            form.dropdown(),
+            Dropdown {
+                selected: Some(0),
+                open: false
+            }
+        );
+        assert_eq!(form.list_display(), Display::None);
+
+        // and another dropdown clicked at the same time stays open
+        form.click(form.dropdown, true);
+        let other = form.world.spawn((Dropdown::new(0), Interaction::None)).id();
+        form.click(other, true);
+        assert!(!form.dropdown().open);
+        assert!(form.world.get::<Dropdown>(other).unwrap().open);
+    }
+
+    #[test]
+    fn navigation_moves_into_the_options_and_back() {
+        let mut form = Form::new(Dropdown::new(1));
+        form.focus(form.dropdown);
+        form.click(form.dropdown, false);
+        assert!(form.dropdown().open);
+        assert_eq!(form.focused(), Some(form.options[1]));
+
+        form.focus(form.options[2]);
+        form.click(form.options[2], false);
+        assert_eq!(form.dropdown().selected, Some(2));
+        assert!(!form.dropdown().open);
+        assert_eq!(form.focused(), Some(form.dropdown));
+        assert_eq!(form.changes(), [2]);
+    }
+
+    #[test]
+    fn navigation_focuses_the_first_option_without_a_selection() {
+        let mut form = Form::new(Dropdown::default());
+        form.focus(form.dropdown);
+        form.click(form.dropdown, false);
+        assert_eq!(form.focused(), Some(form.options[0]));
+    }
+
+    #[test]
+    fn opening_with_the_mouse_keeps_the_focus() {
+        let mut form = Form::new(Dropdown::new(1));
+        form.click(form.dropdown, true);
+        assert_eq!(form.focused(), None);
+    }
+
+    #[test]
+    fn cancelling_closes_and_refocuses_the_dropdown() {
+        let mut form = Form::new(Dropdown::new(0));
+        form.focus(form.dropdown);
+        form.click(form.dropdown, false);
+        form.focus(form.options[2]);
+        form.cancel();
+        assert_eq!(
+            form.dropdown(),
+            Dropdown {
+                selected: Some(0),
+                open: false
+            }
+        );
+        assert_eq!(form.focused(), Some(form.dropdown));
+        assert!(form.changes().is_empty());
+
+        // from the dropdown itself too
+        form.click(form.dropdown, false);
+        form.focus(form.dropdown);
+        form.cancel();
+        assert!(!form.dropdown().open);
+
+        // a cancel while another node is focused leaves the dropdown open
+        form.click(form.dropdown, true);
+        let other = form.world.spawn_empty().id();
+        form.focus(other);
+        form.cancel();
+        assert!(form.dropdown().open);
+        assert_eq!(form.focused(), Some(other));
+    }
+}
@@ -0,0 +1,67 @@
+use bevy_diagnostic::Diagnostics;
+use bevy_ecs::{
+    change_detection::DetectChanges,
+    prelude::Component,
+    query::With,
+    reflect::ReflectComponent,
+    system::{Query, Res},
+};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::diagnostic::{GpuTimingDiagnosticsPlugin, GpuTimings};
+use bevy_text::{Text, TextSection};
+
+/// Marker struct for texts displaying the [`GpuTimings`] measured by the
+/// [`GpuTimingDiagnosticsPlugin`], with one line per node of the render graph.
+///
+/// The sections of the [`Text`] are replaced every frame, using the style of its first section.
+#[derive(Component, Debug, Default, Clone, Copy, Reflect)]
+#[reflect(Component, Default)]
+pub struct GpuTimingsText;
+
+/// Updates the texts with a [`GpuTimingsText`] whenever new [`GpuTimings`] are available.
+pub fn gpu_timings_text_system(
+    gpu_timings: Option<Res<GpuTimings>>,
+    diagnostics: Option<Res<Diagnostics>>,
+    mut texts: Query<&mut Text, With<GpuTimingsText>>,
+) {
+    let gpu_timings = match gpu_timings {
+        Some(gpu_timings) if gpu_timings.is_changed() => gpu_timings,
+        _ => return,
+    };
+    // Show the smoothed values of the diagnostics when available, as raw timings are noisy
+    let smoothed = |id, value| {
+        diagnostics
+            .as_ref()
+            .and_then(|diagnostics| diagnostics.get(id))
+            .and_then(|diagnostic| diagnostic.smoothed())
+            .unwrap_or(value)
+    };
+
+    for mut text in &mut texts {
+        let style = match text.sections.first() {
+            Some(section) => section.style.clone(),
+            None => continue,
+        };
+        text.sections.clear();
+        text.sections.push(TextSection::new(
+            format!(
+                "gpu frame time: {:.2} ms",
+                smoothed(
+                    GpuTimingDiagnosticsPlugin::GPU_FRAME_TIME,
+                    gpu_timings.frame_time
+                )
+            ),
+            style.clone(),
+        ));
+        for (name, duration) in &gpu_timings.nodes {
+            let duration = smoothed(
+                GpuTimingDiagnosticsPlugin::node_diagnostic_id(name),
+                *duration,
+            );
+            text.sections.push(TextSection::new(
+                format!("\n{name}: {duration:.2} ms"),
+                style.clone(),
+            ));
+        }
+    }
+}
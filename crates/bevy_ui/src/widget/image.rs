@@ -1,10 +1,11 @@
-use crate::{CalculatedSize, UiImage};
-use bevy_asset::Assets;
+use crate::{CalculatedSize, UiImage, UiTextureAtlasImage};
+use bevy_asset::{Assets, Handle};
 #[cfg(feature = "bevy_text")]
 use bevy_ecs::query::Without;
 use bevy_ecs::system::{Query, Res};
 use bevy_math::Vec2;
 use bevy_render::texture::Image;
+use bevy_sprite::TextureAtlas;
 #[cfg(feature = "bevy_text")]
 use bevy_text::Text;
 
@@ -28,3 +29,35 @@ pub fn update_image_calculated_size_system(
         }
     }
 }
+
+/// Updates calculated size of the node based on the region of the texture atlas provided
+pub fn update_atlas_image_calculated_size_system(
+    texture_atlases: Res<Assets<TextureAtlas>>,
+    #[cfg(feature = "bevy_text")] mut query: Query<
+        (
+            &mut CalculatedSize,
+            &Handle<TextureAtlas>,
+            &UiTextureAtlasImage,
+        ),
+        Without<Text>,
+    >,
+    #[cfg(not(feature = "bevy_text"))] mut query: Query<(
+        &mut CalculatedSize,
+        &Handle<TextureAtlas>,
+        &UiTextureAtlasImage,
+    )>,
+) {
+    for (mut calculated_size, atlas, atlas_image) in &mut query {
+        if let Some(rect) = texture_atlases
+            .get(atlas)
+            .and_then(|atlas| atlas.textures.get(atlas_image.index))
+        {
+            let size = rect.size();
+            // Update only if size has changed to avoid needless layout calculations
+            if size != calculated_size.size {
+                calculated_size.size = size;
+                calculated_size.preserve_aspect_ratio = true;
+            }
+        }
+    }
+}
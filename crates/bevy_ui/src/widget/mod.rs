@@ -1,11 +1,33 @@
 //! This module contains the basic building blocks of Bevy's UI
+//!
+//! The checkboxes, radio buttons, sliders and dropdowns aren't described to assistive
+//! technologies: without an accessibility tree (like the one of `accesskit`) in the engine, a
+//! screen reader can't tell their role, label or value yet.
 
 mod button;
+mod checkbox;
+mod dropdown;
+#[cfg(feature = "bevy_text")]
+mod gpu_timings;
 mod image;
+mod radio;
+mod slider;
 #[cfg(feature = "bevy_text")]
 mod text;
+#[cfg(feature = "bevy_text")]
+mod text_input;
+mod virtual_list;
 
 pub use button::*;
+pub use checkbox::*;
+pub use dropdown::*;
+#[cfg(feature = "bevy_text")]
+pub use gpu_timings::*;
 pub use image::*;
+pub use radio::*;
+pub use slider::*;
 #[cfg(feature = "bevy_text")]
 pub use text::*;
+#[cfg(feature = "bevy_text")]
+pub use text_input::*;
+pub use virtual_list::*;
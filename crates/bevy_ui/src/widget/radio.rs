@@ -0,0 +1,237 @@
+use super::{set_check_marks, CheckMark};
+use crate::Interaction;
+use bevy_ecs::{
+    entity::Entity,
+    event::EventWriter,
+    prelude::{Component, With},
+    query::Changed,
+    reflect::ReflectComponent,
+    system::Query,
+};
+use bevy_hierarchy::{Children, HierarchyQueryExt, Parent};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::view::Visibility;
+
+/// A group of [`RadioButton`]s among its descendants, of which a single one is selected
+///
+/// The [`CheckMark`]s of the selected button are shown, and the ones of the other buttons hidden.
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct RadioGroup {
+    /// The value of the selected button, if any
+    pub selected: Option<usize>,
+}
+
+impl RadioGroup {
+    /// A group with the button of the `selected` value selected
+    pub const fn new(selected: usize) -> Self {
+        Self {
+            selected: Some(selected),
+        }
+    }
+}
+
+/// A button selecting its value in its closest [`RadioGroup`] ancestor when clicked
+///
+/// Like a [`Button`](super::Button), it's clicked with the mouse, or with the confirm button of
+/// the [`NavigationBindings`](crate::NavigationBindings) while focused, so it needs an
+/// [`Interaction`].
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct RadioButton {
+    /// The value selected by the button, like the index of its option
+    pub value: usize,
+}
+
+impl RadioButton {
+    /// A button selecting `value`
+    pub const fn new(value: usize) -> Self {
+        Self { value }
+    }
+}
+
+/// An event sent when another [`RadioButton`] of a [`RadioGroup`] is selected by a click
+#[derive(Clone, Debug)]
+pub struct RadioGroupChanged {
+    /// The group
+    pub entity: Entity,
+    /// The value of the selected button
+    pub selected: usize,
+}
+
+/// The system that selects the [`RadioButton`]s in their [`RadioGroup`] when clicked
+pub fn radio_button_system(
+    button_query: Query<(Entity, &Interaction, &RadioButton), Changed<Interaction>>,
+    parent_query: Query<&Parent>,
+    mut group_query: Query<&mut RadioGroup>,
+    mut changed_events: EventWriter<RadioGroupChanged>,
+) {
+    for (entity, interaction, button) in &button_query {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        let Some(group_entity) = parent_query
+            .iter_ancestors(entity)
+            .find(|ancestor| group_query.contains(*ancestor))
+        else {
+            continue;
+        };
+        let Ok(mut group) = group_query.get_mut(group_entity) else {
+            continue;
+        };
+        if group.selected != Some(button.value) {
+            group.selected = Some(button.value);
+            changed_events.send(RadioGroupChanged {
+                entity: group_entity,
+                selected: button.value,
+            });
+        }
+    }
+}
+
+/// The system that shows the [`CheckMark`]s of the selected [`RadioButton`]s, and hides the others
+pub fn radio_mark_system(
+    group_query: Query<(Entity, &RadioGroup), Changed<RadioGroup>>,
+    button_query: Query<&RadioButton>,
+    children_query: Query<&Children>,
+    mut mark_query: Query<&mut Visibility, With<CheckMark>>,
+) {
+    for (entity, group) in &group_query {
+        for descendant in children_query.iter_descendants(entity) {
+            if let Ok(button) = button_query.get(descendant) {
+                let checked = group.selected == Some(button.value);
+                set_check_marks(descendant, checked, &children_query, &mut mark_query);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{
+        event::Events,
+        schedule::{IntoSystemConfig, Schedule},
+        world::World,
+    };
+    use bevy_hierarchy::BuildWorldChildren;
+
+    /// A group of three buttons with a mark each, the third one nested in another node, updated
+    /// by running the radio systems
+    struct Form {
+        world: World,
+        schedule: Schedule,
+        group: Entity,
+        buttons: [Entity; 3],
+        marks: [Entity; 3],
+    }
+
+    impl Form {
+        fn new(group: RadioGroup) -> Self {
+            let mut world = World::new();
+            world.init_resource::<Events<RadioGroupChanged>>();
+            let marks = [(); 3].map(|_| world.spawn((CheckMark, Visibility::Inherited)).id());
+            let buttons = [0, 1, 2].map(|value| {
+                world
+                    .spawn((RadioButton::new(value), Interaction::None))
+                    .push_children(&[marks[value]])
+                    .id()
+            });
+            let row = world.spawn_empty().push_children(&[buttons[2]]).id();
+            let group = world
+                .spawn(group)
+                .push_children(&[buttons[0], buttons[1], row])
+                .id();
+            let mut schedule = Schedule::new();
+            schedule.add_system(radio_button_system);
+            schedule.add_system(radio_mark_system.after(radio_button_system));
+            schedule.run(&mut world);
+            Self {
+                world,
+                schedule,
+                group,
+                buttons,
+                marks,
+            }
+        }
+
+        /// Clicks the button of `value`, with the mouse or the confirm button of the navigation,
+        /// and runs the systems
+        fn click(&mut self, value: usize) {
+            let button = self.buttons[value];
+            *self.world.get_mut::<Interaction>(button).unwrap() = Interaction::Clicked;
+            self.schedule.run(&mut self.world);
+            *self.world.get_mut::<Interaction>(button).unwrap() = Interaction::None;
+            self.schedule.run(&mut self.world);
+        }
+
+        fn selected(&self) -> Option<usize> {
+            self.world.get::<RadioGroup>(self.group).unwrap().selected
+        }
+
+        /// The values of the buttons whose mark is shown
+        fn marked(&self) -> Vec<usize> {
+            (0..3)
+                .filter(|value| {
+                    self.world.get::<Visibility>(self.marks[*value]) == Some(&Visibility::Inherited)
+                })
+                .collect()
+        }
+
+        fn changes(&mut self) -> Vec<usize> {
+            self.world
+                .resource_mut::<Events<RadioGroupChanged>>()
+                .drain()
+                .map(|event| {
+                    assert_eq!(event.entity, self.group);
+                    event.selected
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn marks_follow_the_initial_selection() {
+        assert_eq!(Form::new(RadioGroup::new(1)).marked(), [1]);
+        assert!(Form::new(RadioGroup::default()).marked().is_empty());
+    }
+
+    #[test]
+    fn a_single_button_is_selected() {
+        let mut form = Form::new(RadioGroup::default());
+        form.click(0);
+        assert_eq!(form.selected(), Some(0));
+        assert_eq!(form.marked(), [0]);
+        assert_eq!(form.changes(), [0]);
+
+        // the nested button is in the group too
+        form.click(2);
+        assert_eq!(form.selected(), Some(2));
+        assert_eq!(form.marked(), [2]);
+        assert_eq!(form.changes(), [2]);
+
+        form.click(1);
+        assert_eq!(form.selected(), Some(1));
+        assert_eq!(form.marked(), [1]);
+        assert_eq!(form.changes(), [1]);
+    }
+
+    #[test]
+    fn clicking_the_selected_button_keeps_it_selected() {
+        let mut form = Form::new(RadioGroup::new(1));
+        form.click(1);
+        assert_eq!(form.selected(), Some(1));
+        assert_eq!(form.marked(), [1]);
+        assert!(form.changes().is_empty());
+    }
+
+    #[test]
+    fn buttons_outside_a_group_are_ignored() {
+        let mut form = Form::new(RadioGroup::new(0));
+        form.world
+            .spawn((RadioButton::new(1), Interaction::Clicked));
+        form.schedule.run(&mut form.world);
+        assert_eq!(form.selected(), Some(0));
+        assert!(form.changes().is_empty());
+    }
+}
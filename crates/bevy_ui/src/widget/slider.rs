@@ -0,0 +1,420 @@
+use crate::{Interaction, NavDirection, NavigationCaptured, RelativeCursorPosition, Style, Val};
+use bevy_ecs::{
+    entity::Entity,
+    event::{EventReader, EventWriter},
+    prelude::{Component, With},
+    query::{Changed, Without},
+    reflect::ReflectComponent,
+    system::{Query, Res},
+};
+use bevy_hierarchy::{Children, HierarchyQueryExt};
+use bevy_input::{mouse::MouseButton, touch::Touches, Input};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+
+/// A horizontal slider, whose value is set by dragging it, or by navigating left and right while
+/// it's focused
+///
+/// It's dragged while its [`Interaction`] is [`Interaction::Clicked`], to the position of the
+/// cursor in its [`RelativeCursorPosition`], so it needs both. To change it with the keyboard and
+/// gamepads, it needs a [`CaptureNavigation::Horizontal`](crate::CaptureNavigation::Horizontal)
+/// next to its [`Focusable`](crate::Focusable).
+///
+/// Its [`SliderThumb`] and [`SliderFill`] descendants follow the value.
+#[derive(Component, Copy, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct Slider {
+    /// The value, between [`min`](Self::min) and [`max`](Self::max)
+    pub value: f32,
+    /// The value at the left of the slider
+    pub min: f32,
+    /// The value at the right of the slider
+    pub max: f32,
+    /// The step the value is snapped to, and moved by when navigating, or `0.0` for a continuous
+    /// value moved by a tenth of the range when navigating
+    pub step: f32,
+}
+
+impl Slider {
+    pub const DEFAULT: Self = Self {
+        value: 0.0,
+        min: 0.0,
+        max: 1.0,
+        step: 0.0,
+    };
+
+    /// A continuous slider from `min` to `max`, at `value`
+    pub fn new(min: f32, max: f32, value: f32) -> Self {
+        Self {
+            value: value.clamp(min, max),
+            min,
+            max,
+            step: 0.0,
+        }
+    }
+
+    /// Returns this [`Slider`] with a new [`step`](Self::step)
+    pub fn with_step(mut self, step: f32) -> Self {
+        self.step = step;
+        self.value = self.snapped(self.value);
+        self
+    }
+
+    /// The position of the value in the range, from `0.0` at the left to `1.0` at the right
+    pub fn fraction(&self) -> f32 {
+        if self.max > self.min {
+            ((self.value - self.min) / (self.max - self.min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        }
+    }
+
+    /// `value` clamped in the range, and snapped to the [`step`](Self::step)
+    pub fn snapped(&self, value: f32) -> f32 {
+        let value = if self.step > 0.0 {
+            self.min + ((value - self.min) / self.step).round() * self.step
+        } else {
+            value
+        };
+        value.clamp(self.min, self.max.max(self.min))
+    }
+
+    /// The change of the value when navigating
+    fn navigation_step(&self) -> f32 {
+        if self.step > 0.0 {
+            self.step
+        } else {
+            (self.max - self.min) / 10.0
+        }
+    }
+}
+
+impl Default for Slider {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+/// Marks the descendant of a [`Slider`] moved to the position of its value, from the left of
+/// its parent
+///
+/// It should have a [`PositionType::Absolute`](crate::PositionType::Absolute), and a negative
+/// left margin of half its width to be centered on the value.
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct SliderThumb;
+
+/// Marks the descendant of a [`Slider`] whose width is resized to the position of its value,
+/// like the filled part of a progress bar
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct SliderFill;
+
+/// An event sent when the value of a [`Slider`] is changed by dragging it or navigating
+#[derive(Clone, Debug)]
+pub struct SliderChanged {
+    /// The slider
+    pub entity: Entity,
+    /// The new value of the slider
+    pub value: f32,
+}
+
+/// The system that sets the value of the [`Slider`]s dragged with the mouse or a touch, or
+/// navigated from while focused
+pub fn slider_system(
+    mouse_button_input: Res<Input<MouseButton>>,
+    touches_input: Res<Touches>,
+    mut captured_events: EventReader<NavigationCaptured>,
+    mut slider_query: Query<(
+        Entity,
+        &mut Slider,
+        Option<&Interaction>,
+        Option<&RelativeCursorPosition>,
+    )>,
+    mut changed_events: EventWriter<SliderChanged>,
+) {
+    let pressed =
+        mouse_button_input.pressed(MouseButton::Left) || touches_input.iter().next().is_some();
+    let mut navigated = Vec::new();
+    for event in captured_events.iter() {
+        let sign = match event.direction {
+            NavDirection::Left => -1.0,
+            NavDirection::Right => 1.0,
+            NavDirection::Up | NavDirection::Down => continue,
+        };
+        navigated.push((event.focused, sign));
+    }
+
+    for (entity, mut slider, interaction, relative_cursor_position) in &mut slider_query {
+        let mut value = slider.value;
+        if let (true, Some(Interaction::Clicked), Some(position)) = (
+            pressed,
+            interaction,
+            relative_cursor_position.and_then(|position| position.clamped()),
+        ) {
+            value = slider.min + position.x * (slider.max - slider.min);
+        }
+        for (_, sign) in navigated.iter().filter(|(focused, _)| *focused == entity) {
+            value += sign * slider.navigation_step();
+        }
+        let value = slider.snapped(value);
+        if value != slider.value {
+            slider.value = value;
+            changed_events.send(SliderChanged { entity, value });
+        }
+    }
+}
+
+/// The system that moves the [`SliderThumb`]s and resizes the [`SliderFill`]s to the value of
+/// their [`Slider`]
+pub fn slider_thumb_system(
+    slider_query: Query<(Entity, &Slider), Changed<Slider>>,
+    children_query: Query<&Children>,
+    mut thumb_query: Query<&mut Style, With<SliderThumb>>,
+    mut fill_query: Query<&mut Style, (With<SliderFill>, Without<SliderThumb>)>,
+) {
+    for (entity, slider) in &slider_query {
+        let position = Val::Percent(slider.fraction() * 100.0);
+        for descendant in children_query.iter_descendants(entity) {
+            if let Ok(mut style) = thumb_query.get_mut(descendant) {
+                if style.position.left != position {
+                    style.position.left = position;
+                }
+            }
+            if let Ok(mut style) = fill_query.get_mut(descendant) {
+                if style.size.width != position {
+                    style.size.width = position;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{
+        event::Events,
+        schedule::{IntoSystemConfig, Schedule},
+        world::World,
+    };
+    use bevy_hierarchy::BuildWorldChildren;
+    use bevy_math::Vec2;
+
+    #[test]
+    fn snapping() {
+        let stepped = Slider::new(0.0, 10.0, 0.0).with_step(2.5);
+        // (slider, value, snapped)
+        let cases = [
+            (Slider::DEFAULT, 0.25, 0.25),
+            (Slider::DEFAULT, -1.0, 0.0),
+            (Slider::DEFAULT, 2.0, 1.0),
+            (stepped, 3.0, 2.5),
+            (stepped, 4.0, 5.0),
+            (stepped, 9.9, 10.0),
+            (stepped, 12.0, 10.0),
+            (stepped, -3.0, 0.0),
+            // the steps start at the minimum
+            (Slider::new(1.0, 4.0, 1.0).with_step(2.0), 2.5, 3.0),
+            // the last step past the maximum is clamped
+            (Slider::new(0.0, 5.0, 0.0).with_step(2.0), 5.0, 5.0),
+            // an empty range holds its minimum
+            (Slider::new(2.0, 2.0, 2.0), 3.0, 2.0),
+            (
+                Slider {
+                    min: 2.0,
+                    max: 1.0,
+                    ..Slider::DEFAULT
+                },
+                0.0,
+                2.0,
+            ),
+        ];
+        for (slider, value, expected) in cases {
+            assert_eq!(slider.snapped(value), expected, "{value} in {slider:?}");
+        }
+    }
+
+    #[test]
+    fn constructors_clamp_and_snap_the_value() {
+        assert_eq!(Slider::new(0.0, 10.0, 12.0).value, 10.0);
+        assert_eq!(Slider::new(0.0, 10.0, -2.0).value, 0.0);
+        assert_eq!(Slider::new(0.0, 10.0, 4.0).with_step(3.0).value, 3.0);
+    }
+
+    #[test]
+    fn fractions() {
+        assert_eq!(Slider::new(-10.0, 10.0, 5.0).fraction(), 0.75);
+        assert_eq!(Slider::new(2.0, 2.0, 2.0).fraction(), 0.0);
+        let mut slider = Slider::DEFAULT;
+        slider.value = 3.0;
+        assert_eq!(slider.fraction(), 1.0);
+    }
+
+    /// A slider with a thumb and a fill, updated by running the slider systems
+    struct Form {
+        world: World,
+        schedule: Schedule,
+        slider: Entity,
+        thumb: Entity,
+        fill: Entity,
+    }
+
+    impl Form {
+        fn new(slider: Slider) -> Self {
+            let mut world = World::new();
+            world.init_resource::<Input<MouseButton>>();
+            world.init_resource::<Touches>();
+            world.init_resource::<Events<NavigationCaptured>>();
+            world.init_resource::<Events<SliderChanged>>();
+            let thumb = world.spawn((SliderThumb, Style::default())).id();
+            let fill = world.spawn((SliderFill, Style::default())).id();
+            let slider = world
+                .spawn((slider, Interaction::None, RelativeCursorPosition::default()))
+                .push_children(&[thumb, fill])
+                .id();
+            let mut schedule = Schedule::new();
+            schedule.add_system(slider_system);
+            schedule.add_system(slider_thumb_system.after(slider_system));
+            schedule.run(&mut world);
+            Self {
+                world,
+                schedule,
+                slider,
+                thumb,
+                fill,
+            }
+        }
+
+        /// Runs the systems with the slider dragged to `x` in its node, from `0.0` at its left to
+        /// `1.0` at its right
+        fn drag(&mut self, x: f32) {
+            self.world
+                .resource_mut::<Input<MouseButton>>()
+                .press(MouseButton::Left);
+            *self.world.get_mut::<Interaction>(self.slider).unwrap() = Interaction::Clicked;
+            self.world
+                .get_mut::<RelativeCursorPosition>(self.slider)
+                .unwrap()
+                .normalized = Some(Vec2::new(x, 0.5));
+            self.schedule.run(&mut self.world);
+        }
+
+        /// Runs the systems after navigating in `direction` while the `focused` node is focused
+        fn navigate(&mut self, focused: Entity, direction: NavDirection) {
+            self.world
+                .send_event(NavigationCaptured { focused, direction });
+            self.schedule.run(&mut self.world);
+        }
+
+        fn value(&self) -> f32 {
+            self.world.get::<Slider>(self.slider).unwrap().value
+        }
+
+        fn changes(&mut self) -> Vec<f32> {
+            self.world
+                .resource_mut::<Events<SliderChanged>>()
+                .drain()
+                .map(|event| {
+                    assert_eq!(event.entity, self.slider);
+                    event.value
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn dragging_sets_the_value() {
+        let mut form = Form::new(Slider::new(0.0, 10.0, 0.0));
+        form.drag(0.25);
+        assert_eq!(form.value(), 2.5);
+        form.drag(0.75);
+        assert_eq!(form.value(), 7.5);
+        // dragged past the ends, the value is clamped
+        form.drag(1.5);
+        assert_eq!(form.value(), 10.0);
+        form.drag(-0.5);
+        assert_eq!(form.value(), 0.0);
+        assert_eq!(form.changes(), [2.5, 7.5, 10.0, 0.0]);
+
+        let thumb = form.world.get::<Style>(form.thumb).unwrap();
+        assert_eq!(thumb.position.left, Val::Percent(0.0));
+        form.drag(0.5);
+        let thumb = form.world.get::<Style>(form.thumb).unwrap();
+        assert_eq!(thumb.position.left, Val::Percent(50.0));
+        let fill = form.world.get::<Style>(form.fill).unwrap();
+        assert_eq!(fill.size.width, Val::Percent(50.0));
+    }
+
+    #[test]
+    fn dragging_snaps_to_the_step() {
+        let mut form = Form::new(Slider::new(0.0, 10.0, 0.0).with_step(2.0));
+        form.drag(0.29);
+        assert_eq!(form.value(), 2.0);
+        form.drag(0.31);
+        assert_eq!(form.value(), 4.0);
+        // moves within a step don't change the value
+        form.drag(0.35);
+        assert_eq!(form.changes(), [2.0, 4.0]);
+    }
+
+    #[test]
+    fn the_cursor_only_drags_while_pressed_on_the_slider() {
+        let mut form = Form::new(Slider::new(0.0, 10.0, 5.0));
+        // hovered
+        form.world
+            .get_mut::<RelativeCursorPosition>(form.slider)
+            .unwrap()
+            .normalized = Some(Vec2::new(0.1, 0.5));
+        *form.world.get_mut::<Interaction>(form.slider).unwrap() = Interaction::Hovered;
+        form.schedule.run(&mut form.world);
+        assert_eq!(form.value(), 5.0);
+
+        // pressed, then released
+        form.drag(0.2);
+        assert_eq!(form.value(), 2.0);
+        form.world
+            .resource_mut::<Input<MouseButton>>()
+            .release(MouseButton::Left);
+        form.world
+            .get_mut::<RelativeCursorPosition>(form.slider)
+            .unwrap()
+            .normalized = Some(Vec2::new(0.9, 0.5));
+        form.schedule.run(&mut form.world);
+        assert_eq!(form.value(), 2.0);
+    }
+
+    #[test]
+    fn navigating_moves_by_a_step() {
+        let mut form = Form::new(Slider::new(0.0, 1.0, 0.5).with_step(0.25));
+        let slider = form.slider;
+        form.navigate(slider, NavDirection::Right);
+        assert_eq!(form.value(), 0.75);
+        form.navigate(slider, NavDirection::Right);
+        form.navigate(slider, NavDirection::Right);
+        assert_eq!(form.value(), 1.0);
+        form.navigate(slider, NavDirection::Left);
+        assert_eq!(form.value(), 0.75);
+        assert_eq!(form.changes(), [0.75, 1.0, 0.75]);
+
+        // vertical navigation isn't captured by horizontal sliders
+        form.navigate(slider, NavDirection::Up);
+        form.navigate(slider, NavDirection::Down);
+        assert_eq!(form.value(), 0.75);
+        // and another focused node doesn't move the slider
+        let other = form.world.spawn_empty().id();
+        form.navigate(other, NavDirection::Left);
+        assert_eq!(form.value(), 0.75);
+    }
+
+    #[test]
+    fn navigating_moves_a_continuous_slider_by_a_tenth() {
+        let mut form = Form::new(Slider::new(0.0, 20.0, 0.0));
+        let slider = form.slider;
+        form.navigate(slider, NavDirection::Right);
+        assert_eq!(form.value(), 2.0);
+        form.navigate(slider, NavDirection::Left);
+        form.navigate(slider, NavDirection::Left);
+        assert_eq!(form.value(), 0.0);
+        assert_eq!(form.changes(), [2.0, 0.0]);
+    }
+}
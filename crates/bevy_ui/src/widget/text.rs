@@ -9,8 +9,8 @@ use bevy_math::Vec2;
 use bevy_render::texture::Image;
 use bevy_sprite::TextureAtlas;
 use bevy_text::{
-    Font, FontAtlasSet, FontAtlasWarning, Text, TextError, TextLayoutInfo, TextPipeline,
-    TextSettings, YAxisOrientation,
+    Font, FontAtlasSet, FontAtlasWarning, FontFallbacks, GlyphAtlasMode, Text, TextError,
+    TextLayoutInfo, TextPipeline, TextSettings, YAxisOrientation,
 };
 use bevy_window::{PrimaryWindow, Window};
 
@@ -46,6 +46,7 @@ pub fn text_system(
     mut last_scale_factor: Local<f64>,
     mut textures: ResMut<Assets<Image>>,
     fonts: Res<Assets<Font>>,
+    font_fallbacks: Res<FontFallbacks>,
     windows: Query<&Window, With<PrimaryWindow>>,
     text_settings: Res<TextSettings>,
     mut font_atlas_warning: ResMut<FontAtlasWarning>,
@@ -114,10 +115,13 @@ pub fn text_system(
 
             match text_pipeline.queue_text(
                 &fonts,
+                &font_fallbacks,
                 &text.sections,
                 scale_factor,
                 text.alignment,
                 text.linebreak_behaviour,
+                text.direction,
+                text.overflow,
                 node_size,
                 &mut font_atlas_set_storage,
                 &mut texture_atlases,
@@ -125,6 +129,7 @@ pub fn text_system(
                 text_settings.as_ref(),
                 &mut font_atlas_warning,
                 YAxisOrientation::TopToBottom,
+                GlyphAtlasMode::Bitmap,
             ) {
                 Err(TextError::NoSuchFont) => {
                     // There was an error processing the text layout, let's add this entity to the
@@ -0,0 +1,881 @@
+use crate::{Interaction, Node, Overflow, RelativeCursorPosition, ScrollPosition, Style, UiStack};
+use bevy_ecs::{
+    change_detection::{DetectChanges, DetectChangesMut, Ref},
+    entity::Entity,
+    event::{EventReader, EventWriter},
+    prelude::{Component, With, Without},
+    reflect::ReflectComponent,
+    system::{Query, Res, ResMut, Resource},
+};
+use bevy_hierarchy::Parent;
+use bevy_input::{
+    keyboard::{KeyCode, KeyboardInput},
+    mouse::MouseButton,
+    touch::Touches,
+    ButtonState, Input,
+};
+use bevy_math::Vec2;
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use bevy_render::color::Color;
+use bevy_text::{BreakLineOn, Text, TextMeasurer, TextSection, TextStyle};
+use bevy_time::Time;
+use bevy_transform::components::{GlobalTransform, Transform};
+use bevy_window::{Ime, PrimaryWindow, ReceivedCharacter, Window};
+use std::ops::Range;
+
+/// The width of the caret of the focused [`TextInput`], in logical pixels
+pub const CARET_WIDTH: f32 = 2.0;
+
+/// The time the caret of the focused [`TextInput`] is shown, then hidden, in seconds
+pub const CARET_BLINK_TIME: f32 = 0.5;
+
+/// A single line of text edited with the keyboard once focused, by clicking on it or pressing Tab
+///
+/// The [`Text`] of the node is laid out from the value with the [`TextInputStyle`], and is
+/// replaced when either changes.
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct TextInput {
+    /// The text typed in the input
+    pub value: String,
+    /// The byte index of the caret in the value
+    ///
+    /// Moved to the previous character boundary when it's in the middle of a character.
+    pub cursor: usize,
+    /// The byte index of the other end of the selection, at the cursor when nothing is selected
+    pub selection_anchor: usize,
+    /// The text shown in the placeholder color while the value is empty
+    pub placeholder: String,
+    /// The text being composed by the IME, shown at the caret until it's committed
+    preedit: String,
+    /// The byte index of the caret in the preedit, hidden when `None`
+    preedit_cursor: Option<usize>,
+    /// The laid out caret, selection and preedit, `None` until the fonts are loaded
+    #[reflect(ignore)]
+    pub(crate) layout: Option<TextInputLayout>,
+    /// When the caret was last moved, to show it without blinking while typing
+    #[reflect(ignore)]
+    pub(crate) blink_start: f32,
+}
+
+/// The positions of the caret, the selection and the preedit of a [`TextInput`], in logical
+/// pixels from the left of its text
+#[derive(Clone, Debug, Default)]
+pub(crate) struct TextInputLayout {
+    /// The position of each character boundary of the value, with its byte index
+    pub boundaries: Vec<(usize, f32)>,
+    /// The position of the caret, hidden when `None`
+    pub caret: Option<f32>,
+    /// The start and end of the selection
+    pub selection: Range<f32>,
+    /// The start and end of the preedit
+    pub preedit: Range<f32>,
+    /// The height of a line of the text
+    pub line_height: f32,
+}
+
+impl TextInput {
+    /// An input with `value` typed in, with the caret at its end
+    pub fn new(value: impl Into<String>) -> Self {
+        let value = value.into();
+        Self {
+            cursor: value.len(),
+            selection_anchor: value.len(),
+            value,
+            ..Default::default()
+        }
+    }
+
+    /// Returns this [`TextInput`] with a new placeholder
+    pub fn with_placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = placeholder.into();
+        self
+    }
+
+    /// The byte range of the selection in the value, empty when nothing is selected
+    pub fn selection(&self) -> Range<usize> {
+        self.cursor.min(self.selection_anchor)..self.cursor.max(self.selection_anchor)
+    }
+
+    /// The selected part of the value
+    pub fn selected_text(&self) -> &str {
+        &self.value[self.selection()]
+    }
+
+    /// The text being composed by the IME, empty when nothing is composed
+    pub fn preedit(&self) -> &str {
+        &self.preedit
+    }
+
+    /// Selects the whole value
+    pub fn select_all(&mut self) {
+        self.selection_anchor = 0;
+        self.cursor = self.value.len();
+    }
+
+    /// Replaces the selection by `text`, and moves the caret after it
+    pub fn insert(&mut self, text: &str) {
+        let selection = self.selection();
+        self.value.replace_range(selection.clone(), text);
+        self.cursor = selection.start + text.len();
+        self.selection_anchor = self.cursor;
+    }
+
+    /// Moves the caret to `cursor`, extending the selection to it when `select` is set
+    fn move_cursor(&mut self, cursor: usize, select: bool) {
+        self.cursor = cursor;
+        if !select {
+            self.selection_anchor = cursor;
+        }
+    }
+
+    /// The byte index of the character boundary before the caret
+    fn previous_boundary(&self) -> usize {
+        self.value[..self.cursor]
+            .char_indices()
+            .next_back()
+            .map_or(0, |(index, _)| index)
+    }
+
+    /// The byte index of the character boundary after the caret
+    fn next_boundary(&self) -> usize {
+        self.value[self.cursor..]
+            .chars()
+            .next()
+            .map_or(self.cursor, |character| self.cursor + character.len_utf8())
+    }
+
+    /// Moves the cursor and the selection anchor inside of the value, at character boundaries
+    fn clamp_cursor(&mut self) {
+        let clamp = |value: &str, mut index: usize| {
+            index = index.min(value.len());
+            while !value.is_char_boundary(index) {
+                index -= 1;
+            }
+            index
+        };
+        self.cursor = clamp(&self.value, self.cursor);
+        self.selection_anchor = clamp(&self.value, self.selection_anchor);
+    }
+}
+
+/// The styles of the text, the caret and the selection of a [`TextInput`]
+#[derive(Component, Clone, Debug, Reflect)]
+#[reflect(Component, Default)]
+pub struct TextInputStyle {
+    /// The style of the value, and of the placeholder except for its color
+    pub text_style: TextStyle,
+    /// The color of the placeholder
+    pub placeholder_color: Color,
+    /// The color of the caret, and of the line under the preedit
+    pub caret_color: Color,
+    /// The color of the box behind the selection
+    pub selection_color: Color,
+}
+
+impl Default for TextInputStyle {
+    fn default() -> Self {
+        Self {
+            text_style: TextStyle::default(),
+            placeholder_color: Color::rgba(1.0, 1.0, 1.0, 0.4),
+            caret_color: Color::WHITE,
+            selection_color: Color::rgba(0.2, 0.4, 0.9, 0.6),
+        }
+    }
+}
+
+/// The [`TextInput`] receiving the keyboard input, if any
+///
+/// Changed by clicking on an input or outside of them, Tab and Escape, and can be changed by
+/// the app to focus an input.
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TextInputFocus(pub Option<Entity>);
+
+/// The text copied and cut from the [`TextInput`]s, and pasted in them
+///
+/// The contents aren't shared with the clipboard of the operating system, and can be synced
+/// with it by the app.
+#[derive(Resource, Clone, Debug, Default)]
+pub struct Clipboard {
+    pub contents: String,
+}
+
+/// An event sent when the value of a [`TextInput`] is edited
+#[derive(Clone, Debug)]
+pub struct TextInputChanged {
+    /// The entity of the [`TextInput`]
+    pub entity: Entity,
+    /// The new value of the input
+    pub value: String,
+}
+
+/// An event sent when Enter is pressed in the focused [`TextInput`]
+#[derive(Clone, Debug)]
+pub struct TextInputSubmitted {
+    /// The entity of the [`TextInput`]
+    pub entity: Entity,
+    /// The value of the input
+    pub value: String,
+}
+
+/// Focuses the [`TextInput`]s, and moves their caret and selection with the mouse and touches
+#[allow(clippy::too_many_arguments)]
+pub fn text_input_focus_system(
+    mut focus: ResMut<TextInputFocus>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    touches_input: Res<Touches>,
+    keyboard_input: Res<Input<KeyCode>>,
+    ui_stack: Res<UiStack>,
+    time: Res<Time>,
+    mut input_query: Query<(
+        Entity,
+        &Interaction,
+        &RelativeCursorPosition,
+        &Node,
+        &mut TextInput,
+    )>,
+) {
+    let pressed =
+        mouse_button_input.just_pressed(MouseButton::Left) || touches_input.any_just_pressed();
+    let shift = keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+
+    if pressed {
+        // Pressing outside of the inputs unfocuses them
+        let clicked = input_query
+            .iter()
+            .find(|(_, interaction, ..)| **interaction == Interaction::Clicked)
+            .map(|(entity, ..)| entity);
+        focus.set_if_neq(TextInputFocus(clicked));
+    } else if keyboard_input.just_pressed(KeyCode::Escape) {
+        focus.set_if_neq(TextInputFocus(None));
+    } else if keyboard_input.just_pressed(KeyCode::Tab) {
+        // The inputs are focused in the order they're drawn in, backwards with Shift
+        let mut inputs: Vec<Entity> = ui_stack
+            .uinodes
+            .iter()
+            .filter(|entity| input_query.contains(**entity))
+            .copied()
+            .collect();
+        if shift {
+            inputs.reverse();
+        }
+        let next = match focus
+            .0
+            .and_then(|focused| inputs.iter().position(|e| *e == focused))
+        {
+            Some(index) => inputs.get(index + 1).or(inputs.first()),
+            None => inputs.first(),
+        };
+        focus.set_if_neq(TextInputFocus(next.copied()));
+    }
+
+    // The caret follows the pointer held on the focused input
+    let Some(focused) = focus.0 else {
+        return;
+    };
+    let Ok((_, interaction, relative_cursor_position, node, mut input)) =
+        input_query.get_mut(focused)
+    else {
+        return;
+    };
+    let (Interaction::Clicked, Some(normalized), Some(layout)) = (
+        interaction,
+        relative_cursor_position.normalized,
+        &input.layout,
+    ) else {
+        return;
+    };
+    if !input.preedit.is_empty() {
+        return;
+    }
+    let x = normalized.x * node.size().x;
+    let Some(&(cursor, _)) = layout
+        .boundaries
+        .iter()
+        .min_by(|(_, a), (_, b)| (a - x).abs().total_cmp(&(b - x).abs()))
+    else {
+        return;
+    };
+    if pressed || input.cursor != cursor {
+        input.move_cursor(cursor, shift || !pressed);
+        input.blink_start = time.elapsed_seconds();
+    }
+}
+
+/// Edits the focused [`TextInput`] with the keyboard, the IME and the [`Clipboard`]
+#[allow(clippy::too_many_arguments)]
+pub fn text_input_system(
+    focus: Res<TextInputFocus>,
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut character_events: EventReader<ReceivedCharacter>,
+    mut ime_events: EventReader<Ime>,
+    mut clipboard: ResMut<Clipboard>,
+    mut changed_events: EventWriter<TextInputChanged>,
+    mut submitted_events: EventWriter<TextInputSubmitted>,
+    mut input_query: Query<&mut TextInput>,
+) {
+    let Some((entity, mut input)) = focus
+        .0
+        .and_then(|entity| Some((entity, input_query.get_mut(entity).ok()?)))
+    else {
+        keyboard_events.clear();
+        character_events.clear();
+        ime_events.clear();
+        return;
+    };
+
+    let shift = keyboard_input.any_pressed([KeyCode::LShift, KeyCode::RShift]);
+    let command = keyboard_input.any_pressed([
+        KeyCode::LControl,
+        KeyCode::RControl,
+        KeyCode::LWin,
+        KeyCode::RWin,
+    ]);
+    let old_value = input.value.clone();
+
+    // The repeated key presses are read from the events
+    for event in keyboard_events.iter() {
+        let (ButtonState::Pressed, Some(key_code)) = (event.state, event.key_code) else {
+            continue;
+        };
+        let selection = input.selection();
+        match key_code {
+            KeyCode::Left => {
+                let cursor = if selection.is_empty() || shift {
+                    input.previous_boundary()
+                } else {
+                    selection.start
+                };
+                input.move_cursor(cursor, shift);
+            }
+            KeyCode::Right => {
+                let cursor = if selection.is_empty() || shift {
+                    input.next_boundary()
+                } else {
+                    selection.end
+                };
+                input.move_cursor(cursor, shift);
+            }
+            KeyCode::Home => input.move_cursor(0, shift),
+            KeyCode::End => {
+                let end = input.value.len();
+                input.move_cursor(end, shift);
+            }
+            KeyCode::Back => {
+                if selection.is_empty() {
+                    input.selection_anchor = input.previous_boundary();
+                }
+                input.insert("");
+            }
+            KeyCode::Delete => {
+                if selection.is_empty() {
+                    input.selection_anchor = input.next_boundary();
+                }
+                input.insert("");
+            }
+            KeyCode::Return | KeyCode::NumpadEnter => submitted_events.send(TextInputSubmitted {
+                entity,
+                value: input.value.clone(),
+            }),
+            KeyCode::A if command => input.select_all(),
+            KeyCode::C | KeyCode::X if command && !selection.is_empty() => {
+                clipboard.contents = input.selected_text().to_string();
+                if key_code == KeyCode::X {
+                    input.insert("");
+                }
+            }
+            KeyCode::V if command => {
+                // The input only has a single line
+                let contents: String = clipboard
+                    .contents
+                    .chars()
+                    .map(|character| {
+                        if character.is_control() {
+                            ' '
+                        } else {
+                            character
+                        }
+                    })
+                    .collect();
+                input.insert(&contents);
+            }
+            _ => {}
+        }
+    }
+
+    for event in character_events.iter() {
+        if !event.char.is_control() {
+            input.insert(event.char.encode_utf8(&mut [0; 4]));
+        }
+    }
+
+    for event in ime_events.iter() {
+        match event {
+            Ime::Preedit { value, cursor, .. } => {
+                // The composed text replaces the selection once committed
+                if !value.is_empty() && !input.selection().is_empty() {
+                    input.insert("");
+                }
+                input.preedit = value.clone();
+                input.preedit_cursor = cursor.map(|(start, _)| start);
+            }
+            Ime::Commit { value, .. } => {
+                input.preedit.clear();
+                input.insert(value);
+            }
+            Ime::Disabled { .. } => input.preedit.clear(),
+            Ime::Enabled { .. } => {}
+        }
+    }
+
+    if input.is_changed() {
+        input.blink_start = time.elapsed_seconds();
+    }
+    if input.value != old_value {
+        changed_events.send(TextInputChanged {
+            entity,
+            value: input.value.clone(),
+        });
+    }
+}
+
+/// Lays out the [`Text`] of the [`TextInput`]s, and positions their caret, selection and
+/// preedit
+///
+/// Scrolls the parent with [`Overflow::Scroll`] of the focused input to show its caret.
+pub fn text_input_layout_system(
+    mut text_measurer: TextMeasurer,
+    focus: Res<TextInputFocus>,
+    mut input_query: Query<(
+        Entity,
+        &mut TextInput,
+        Ref<TextInputStyle>,
+        &mut Text,
+        &Node,
+        &Transform,
+        Option<&Parent>,
+    )>,
+    mut scroll_query: Query<(&Node, &Style, &mut ScrollPosition), Without<TextInput>>,
+) {
+    for (entity, mut input, style, mut text, node, transform, parent) in &mut input_query {
+        if !input.is_changed() && !style.is_changed() && input.layout.is_some() {
+            continue;
+        }
+        let input = input.bypass_change_detection();
+        input.clamp_cursor();
+
+        text.sections = text_sections(input, &style);
+        text.linebreak_behaviour = BreakLineOn::NoWrap;
+
+        // The positions are measured from the text before them, as the whitespaces don't have
+        // laid out glyphs
+        let mut measure = |value: &str| {
+            let text = Text::from_section(value, style.text_style.clone())
+                .with_linebreak_behaviour(BreakLineOn::NoWrap);
+            text_measurer.measure(&text, None).ok()
+        };
+        let Some(line_size) = measure(" ") else {
+            // The fonts are loading
+            input.layout = None;
+            continue;
+        };
+        let boundaries: Vec<(usize, f32)> = input
+            .value
+            .char_indices()
+            .map(|(index, _)| index)
+            .chain([input.value.len()])
+            .filter_map(|index| Some((index, measure(&input.value[..index])?.x)))
+            .collect();
+        let boundary_x = |index: usize| {
+            boundaries
+                .iter()
+                .find(|(boundary, _)| *boundary == index)
+                .map_or(0.0, |(_, x)| *x)
+        };
+        let cursor_x = boundary_x(input.cursor);
+        let layout = if input.preedit.is_empty() {
+            TextInputLayout {
+                caret: Some(cursor_x),
+                selection: cursor_x.min(boundary_x(input.selection_anchor))
+                    ..cursor_x.max(boundary_x(input.selection_anchor)),
+                preedit: cursor_x..cursor_x,
+                line_height: line_size.y,
+                boundaries,
+            }
+        } else {
+            let before = &input.value[..input.cursor];
+            let preedit_end = measure(&format!("{before}{}", input.preedit)).unwrap_or_default();
+            let caret = input.preedit_cursor.map(|preedit_cursor| {
+                let preedit = &input.preedit[..preedit_cursor.min(input.preedit.len())];
+                measure(&format!("{before}{preedit}")).unwrap_or_default().x
+            });
+            TextInputLayout {
+                caret,
+                selection: cursor_x..cursor_x,
+                preedit: cursor_x..preedit_end.x,
+                line_height: line_size.y,
+                boundaries,
+            }
+        };
+
+        // Keeps the caret of the focused input in view of the parent scrolling it
+        if let (true, Some(Ok((parent_node, parent_style, mut scroll_position)))) = (
+            focus.0 == Some(entity),
+            parent.map(|parent| scroll_query.get_mut(parent.get())),
+        ) {
+            if parent_style.overflow == Overflow::Scroll {
+                let view_width = parent_node.size().x;
+                let left = transform.translation.x - node.size().x / 2.0
+                    + view_width / 2.0
+                    + scroll_position.offset.x;
+                let caret_x = left + layout.caret.unwrap_or(layout.preedit.end);
+                let offset = &mut scroll_position.offset.x;
+                if caret_x < *offset {
+                    *offset = caret_x;
+                } else if caret_x + CARET_WIDTH > *offset + view_width {
+                    *offset = caret_x + CARET_WIDTH - view_width;
+                }
+            }
+        }
+        input.layout = Some(layout);
+    }
+}
+
+/// Enables the IME of the window while a [`TextInput`] is focused, with the candidate box under
+/// its caret
+pub fn text_input_ime_system(
+    focus: Res<TextInputFocus>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    input_query: Query<(&TextInput, &Node, &GlobalTransform)>,
+) {
+    let Ok(mut window) = windows.get_single_mut() else {
+        return;
+    };
+    let focused = focus.0.and_then(|entity| input_query.get(entity).ok());
+    if window.ime_enabled != focused.is_some() {
+        window.ime_enabled = focused.is_some();
+    }
+
+    let Some((
+        TextInput {
+            layout: Some(layout),
+            ..
+        },
+        node,
+        global_transform,
+    )) = focused
+    else {
+        return;
+    };
+    let top_left = global_transform.translation().truncate() - node.size() / 2.0;
+    let caret_x = layout.caret.unwrap_or(layout.preedit.end);
+    let ime_position = top_left + Vec2::new(caret_x, layout.line_height);
+    if window.ime_position != ime_position {
+        window.ime_position = ime_position;
+    }
+}
+
+/// The sections of the text of `input`, with the preedit at the caret
+fn text_sections(input: &TextInput, style: &TextInputStyle) -> Vec<TextSection> {
+    if input.value.is_empty() && input.preedit.is_empty() {
+        let placeholder_style = TextStyle {
+            color: style.placeholder_color,
+            ..style.text_style.clone()
+        };
+        return vec![TextSection::new(
+            input.placeholder.clone(),
+            placeholder_style,
+        )];
+    }
+    if input.preedit.is_empty() {
+        return vec![TextSection::new(
+            input.value.clone(),
+            style.text_style.clone(),
+        )];
+    }
+    [
+        &input.value[..input.cursor],
+        &input.preedit,
+        &input.value[input.cursor..],
+    ]
+    .into_iter()
+    .map(|value| TextSection::new(value, style.text_style.clone()))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{event::Events, schedule::Schedule, world::World};
+
+    /// A focused input edited by running the [`text_input_system`]
+    struct Editor {
+        world: World,
+        schedule: Schedule,
+        entity: Entity,
+    }
+
+    impl Editor {
+        fn new(value: &str, cursor: usize, selection_anchor: usize) -> Self {
+            let mut world = World::new();
+            world.init_resource::<Time>();
+            world.init_resource::<Input<KeyCode>>();
+            world.init_resource::<Clipboard>();
+            world.init_resource::<Events<KeyboardInput>>();
+            world.init_resource::<Events<ReceivedCharacter>>();
+            world.init_resource::<Events<Ime>>();
+            world.init_resource::<Events<TextInputChanged>>();
+            world.init_resource::<Events<TextInputSubmitted>>();
+            let input = TextInput {
+                cursor,
+                selection_anchor,
+                ..TextInput::new(value)
+            };
+            let entity = world.spawn(input).id();
+            world.insert_resource(TextInputFocus(Some(entity)));
+            let mut schedule = Schedule::new();
+            schedule.add_system(text_input_system);
+            Self {
+                world,
+                schedule,
+                entity,
+            }
+        }
+
+        /// Presses `key` while holding the `modifiers`
+        fn press(&mut self, modifiers: &[KeyCode], key: KeyCode) -> &mut Self {
+            let mut keyboard_input = self.world.resource_mut::<Input<KeyCode>>();
+            for modifier in modifiers {
+                keyboard_input.press(*modifier);
+            }
+            self.world.send_event(KeyboardInput {
+                scan_code: 0,
+                key_code: Some(key),
+                state: ButtonState::Pressed,
+            });
+            self.schedule.run(&mut self.world);
+            self.world.resource_mut::<Input<KeyCode>>().reset_all();
+            self
+        }
+
+        fn type_text(&mut self, text: &str) -> &mut Self {
+            let window = Entity::from_raw(0);
+            for char in text.chars() {
+                self.world.send_event(ReceivedCharacter { window, char });
+            }
+            self.schedule.run(&mut self.world);
+            self
+        }
+
+        fn ime(&mut self, event: Ime) -> &mut Self {
+            self.world.send_event(event);
+            self.schedule.run(&mut self.world);
+            self
+        }
+
+        fn input(&self) -> &TextInput {
+            self.world.get::<TextInput>(self.entity).unwrap()
+        }
+
+        /// The value, the cursor and the selection anchor of the input
+        fn state(&self) -> (&str, usize, usize) {
+            let input = self.input();
+            (&input.value, input.cursor, input.selection_anchor)
+        }
+    }
+
+    const SHIFT: &[KeyCode] = &[KeyCode::LShift];
+    const CONTROL: &[KeyCode] = &[KeyCode::LControl];
+
+    #[test]
+    fn caret_and_selection_movement() {
+        use KeyCode::*;
+        // (cursor, selection anchor, modifiers, key, new cursor and selection anchor)
+        // in "héllo", where "é" is 2 bytes long
+        let cases: &[(usize, usize, &[KeyCode], KeyCode, (usize, usize))] = &[
+            (3, 3, &[], Left, (1, 1)),
+            (1, 1, &[], Right, (3, 3)),
+            (0, 0, &[], Left, (0, 0)),
+            (6, 6, &[], Right, (6, 6)),
+            (3, 3, &[], Home, (0, 0)),
+            (3, 3, &[], End, (6, 6)),
+            // the selection is extended with Shift
+            (3, 3, SHIFT, Left, (1, 3)),
+            (3, 3, SHIFT, Right, (4, 3)),
+            (3, 3, SHIFT, Home, (0, 3)),
+            (3, 3, SHIFT, End, (6, 3)),
+            (1, 4, SHIFT, Right, (3, 4)),
+            // the arrows move to the side of the selection
+            (1, 4, &[], Left, (1, 1)),
+            (4, 1, &[], Left, (1, 1)),
+            (1, 4, &[], Right, (4, 4)),
+            (1, 4, &[], Home, (0, 0)),
+            (4, 4, CONTROL, A, (6, 0)),
+        ];
+        for (cursor, anchor, modifiers, key, expected) in cases {
+            let mut editor = Editor::new("héllo", *cursor, *anchor);
+            editor.press(modifiers, *key);
+            let (_, cursor, anchor) = editor.state();
+            assert_eq!(
+                (cursor, anchor),
+                *expected,
+                "{modifiers:?} {key:?} from {cursor}, {anchor}"
+            );
+        }
+    }
+
+    #[test]
+    fn deleting() {
+        use KeyCode::*;
+        // (cursor, selection anchor, key, new value and cursor) in "héllo"
+        let cases = [
+            (3, 3, Back, ("hllo", 1)),
+            (1, 1, Back, ("éllo", 0)),
+            (0, 0, Back, ("héllo", 0)),
+            (1, 1, Delete, ("hllo", 1)),
+            (6, 6, Delete, ("héllo", 6)),
+            (1, 4, Back, ("hlo", 1)),
+            (4, 1, Delete, ("hlo", 1)),
+        ];
+        for (cursor, anchor, key, (value, new_cursor)) in cases {
+            let mut editor = Editor::new("héllo", cursor, anchor);
+            editor.press(&[], key);
+            assert_eq!(
+                editor.state(),
+                (value, new_cursor, new_cursor),
+                "{key:?} from {cursor}, {anchor}"
+            );
+        }
+    }
+
+    #[test]
+    fn typing_replaces_selection() {
+        let mut editor = Editor::new("héllo", 1, 1);
+        editor.type_text("ab");
+        assert_eq!(editor.state(), ("habéllo", 3, 3));
+
+        editor.press(SHIFT, KeyCode::End).type_text("ç");
+        assert_eq!(editor.state(), ("habç", 5, 5));
+
+        // the control characters aren't typed
+        editor.type_text("\u{8}\t\r");
+        assert_eq!(editor.state(), ("habç", 5, 5));
+
+        editor.press(CONTROL, KeyCode::A).type_text("x");
+        assert_eq!(editor.state(), ("x", 1, 1));
+    }
+
+    #[test]
+    fn clipboard() {
+        let mut editor = Editor::new("hello world", 0, 5);
+        editor.press(CONTROL, KeyCode::C);
+        assert_eq!(editor.world.resource::<Clipboard>().contents, "hello");
+        assert_eq!(editor.state(), ("hello world", 0, 5));
+
+        // copying nothing keeps the clipboard
+        editor.press(&[], KeyCode::End).press(CONTROL, KeyCode::C);
+        assert_eq!(editor.world.resource::<Clipboard>().contents, "hello");
+
+        editor.press(CONTROL, KeyCode::V);
+        assert_eq!(editor.state(), ("hello worldhello", 16, 16));
+
+        editor
+            .press(SHIFT, KeyCode::Home)
+            .press(CONTROL, KeyCode::X);
+        assert_eq!(
+            editor.world.resource::<Clipboard>().contents,
+            "hello worldhello"
+        );
+        assert_eq!(editor.state(), ("", 0, 0));
+
+        // the pasted line breaks are replaced by spaces
+        editor.world.resource_mut::<Clipboard>().contents = "a\nb".to_string();
+        editor.press(CONTROL, KeyCode::V);
+        assert_eq!(editor.state(), ("a b", 3, 3));
+    }
+
+    #[test]
+    fn ime_composition() {
+        let window = Entity::from_raw(0);
+        let preedit = |value: &str, cursor| Ime::Preedit {
+            window,
+            value: value.to_string(),
+            cursor,
+        };
+        let mut editor = Editor::new("abcd", 1, 3);
+
+        // the composition replaces the selection
+        editor.ime(preedit("", None));
+        assert_eq!(editor.state(), ("abcd", 1, 3));
+        editor.ime(preedit("に", Some((3, 3))));
+        assert_eq!(editor.state(), ("ad", 1, 1));
+        assert_eq!(editor.input().preedit(), "に");
+        assert_eq!(editor.input().preedit_cursor, Some(3));
+
+        editor.ime(Ime::Commit {
+            window,
+            value: "日本".to_string(),
+        });
+        assert_eq!(editor.state(), ("a日本d", 7, 7));
+        assert_eq!(editor.input().preedit(), "");
+
+        editor
+            .ime(preedit("ご", None))
+            .ime(Ime::Disabled { window });
+        assert_eq!(editor.state(), ("a日本d", 7, 7));
+        assert_eq!(editor.input().preedit(), "");
+    }
+
+    #[test]
+    fn changed_and_submitted_events() {
+        let mut editor = Editor::new("ab", 2, 2);
+        editor.press(&[], KeyCode::Left).type_text("c");
+        editor.press(&[], KeyCode::Return);
+
+        let changed = editor.world.resource::<Events<TextInputChanged>>();
+        let changed: Vec<_> = changed
+            .iter_current_update_events()
+            .map(|event| event.value.as_str())
+            .collect();
+        // moving the caret doesn't change the value
+        assert_eq!(changed, ["acb"]);
+        let submitted = editor.world.resource::<Events<TextInputSubmitted>>();
+        let submitted: Vec<_> = submitted
+            .iter_current_update_events()
+            .map(|event| (event.entity, event.value.as_str()))
+            .collect();
+        assert_eq!(submitted, [(editor.entity, "acb")]);
+    }
+
+    #[test]
+    fn unfocused_input_is_not_edited() {
+        let mut editor = Editor::new("ab", 2, 2);
+        editor.world.insert_resource(TextInputFocus(None));
+        editor.type_text("c").press(&[], KeyCode::Back);
+        assert_eq!(editor.state(), ("ab", 2, 2));
+
+        // the events sent while unfocused are dropped
+        editor
+            .world
+            .insert_resource(TextInputFocus(Some(editor.entity)));
+        editor.type_text("");
+        assert_eq!(editor.state(), ("ab", 2, 2));
+    }
+
+    #[test]
+    fn cursor_is_clamped_to_character_boundaries() {
+        // (cursor, selection anchor, clamped cursor and selection anchor) in "héllo"
+        let cases = [(2, 2, (1, 1)), (10, 2, (6, 1)), (3, 7, (3, 6))];
+        for (cursor, selection_anchor, expected) in cases {
+            let mut input = TextInput {
+                cursor,
+                selection_anchor,
+                ..TextInput::new("héllo")
+            };
+            input.clamp_cursor();
+            assert_eq!((input.cursor, input.selection_anchor), expected);
+        }
+    }
+}
@@ -0,0 +1,423 @@
+use crate::{
+    node_bundles::NodeBundle, Display, Node, PositionType, ScrollPosition, Size, Style, UiRect,
+    UiScale, Val,
+};
+use bevy_ecs::{
+    entity::Entity,
+    prelude::{Component, With},
+    query::Without,
+    reflect::ReflectComponent,
+    system::{Commands, EntityCommands, Query, Res},
+};
+use bevy_hierarchy::{BuildChildren, Children};
+use bevy_reflect::{std_traits::ReflectDefault, Reflect};
+use std::{ops::Range, sync::Arc};
+
+/// A scrolling list of rows of the same height, where only the rows in view are spawned and laid
+/// out, so that it stays cheap whatever the number of items
+///
+/// The list should have an [`Overflow::Scroll`](crate::Overflow::Scroll) and a
+/// [`ScrollPosition`]. Its rows are spawned as children by the row factory, and recycled to show
+/// other items when they're scrolled out of view, so their content should be set from their
+/// [`VirtualListRow::index`] by a system querying `Changed<VirtualListRow>`, which is true both
+/// when a row is spawned and when it's recycled.
+///
+/// The list should have no other children.
+#[derive(Component, Clone)]
+pub struct VirtualList {
+    /// The number of items in the list
+    pub item_count: usize,
+    /// The height of each row, in logical pixels
+    pub row_height: f32,
+    /// The number of rows kept spawned above and under the rows in view, so that their content
+    /// is ready before they're scrolled into view
+    pub overscan: usize,
+    spawn_row: Arc<dyn Fn(&mut EntityCommands) + Send + Sync>,
+}
+
+impl VirtualList {
+    /// A list of `item_count` rows of `row_height` logical pixels, spawned by `spawn_row`
+    ///
+    /// `spawn_row` is given the commands of a new row, which already has a [`NodeBundle`] and a
+    /// [`VirtualListRow`], to insert its components and spawn its children.
+    pub fn new(
+        item_count: usize,
+        row_height: f32,
+        spawn_row: impl Fn(&mut EntityCommands) + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            item_count,
+            row_height,
+            overscan: 2,
+            spawn_row: Arc::new(spawn_row),
+        }
+    }
+
+    /// Returns this [`VirtualList`] with a new [`overscan`](Self::overscan)
+    pub fn with_overscan(mut self, overscan: usize) -> Self {
+        self.overscan = overscan;
+        self
+    }
+
+    /// The indices of the items shown in a list `height` logical pixels high, scrolled by
+    /// `offset` logical pixels, including the overscan
+    pub fn visible_range(&self, offset: f32, height: f32, ui_scale: f64) -> Range<usize> {
+        let row_height = self.row_height * ui_scale as f32;
+        if row_height <= 0.0 {
+            return 0..0;
+        }
+        let start = (offset.max(0.0) / row_height).floor() as usize;
+        let end = ((offset.max(0.0) + height.max(0.0)) / row_height).ceil() as usize;
+        start.saturating_sub(self.overscan).min(self.item_count)
+            ..end.saturating_add(self.overscan).min(self.item_count)
+    }
+}
+
+/// A row of a [`VirtualList`], showing the item at [`index`](Self::index)
+#[derive(Component, Copy, Clone, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component, Default, PartialEq)]
+pub struct VirtualListRow {
+    /// The index of the item shown in the row
+    pub index: usize,
+}
+
+/// Marks the child of a [`VirtualList`] as high as all its rows, so that it scrolls to its last
+/// item
+#[derive(Component, Copy, Clone, Debug, Default, Reflect)]
+#[reflect(Component, Default)]
+pub struct VirtualListSpacer;
+
+/// The system that spawns the rows of the [`VirtualList`]s in view, and recycles the rows
+/// scrolled out of view to show the items scrolled into view
+///
+/// The rows scrolled out of view and not needed for other items are hidden with
+/// [`Display::None`], and kept to be recycled later.
+pub fn virtual_list_system(
+    mut commands: Commands,
+    ui_scale: Res<UiScale>,
+    list_query: Query<(
+        Entity,
+        &VirtualList,
+        &Node,
+        &ScrollPosition,
+        Option<&Children>,
+    )>,
+    mut row_query: Query<(&mut VirtualListRow, &mut Style), Without<VirtualListSpacer>>,
+    mut spacer_query: Query<&mut Style, (With<VirtualListSpacer>, Without<VirtualListRow>)>,
+) {
+    for (entity, list, node, scroll_position, children) in &list_query {
+        let visible = list.visible_range(scroll_position.offset.y, node.size().y, ui_scale.scale);
+        let height = Val::Px(list.item_count as f32 * list.row_height);
+
+        let mut has_spacer = false;
+        let mut shown = vec![false; visible.len()];
+        let mut free_rows = Vec::new();
+        for child in children.into_iter().flatten() {
+            if let Ok(mut style) = spacer_query.get_mut(*child) {
+                if style.size.height != height {
+                    style.size.height = height;
+                }
+                has_spacer = true;
+            } else if let Ok((row, mut style)) = row_query.get_mut(*child) {
+                if visible.contains(&row.index) && !shown[row.index - visible.start] {
+                    shown[row.index - visible.start] = true;
+                    if style.display != Display::Flex {
+                        style.display = Display::Flex;
+                    }
+                } else {
+                    free_rows.push(*child);
+                }
+            }
+        }
+
+        if !has_spacer {
+            let spacer = commands
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(0.0), height),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    },
+                    VirtualListSpacer,
+                ))
+                .id();
+            commands.entity(entity).add_child(spacer);
+        }
+
+        let start = visible.start;
+        let mut free_rows = free_rows.into_iter();
+        for index in visible.filter(|index| !shown[index - start]) {
+            let top = Val::Px(index as f32 * list.row_height);
+            if let Some(row) = free_rows.next() {
+                let (mut row, mut style) = row_query.get_mut(row).unwrap();
+                row.index = index;
+                style.display = Display::Flex;
+                style.position.top = top;
+                style.size.height = Val::Px(list.row_height);
+            } else {
+                let mut row = commands.spawn((
+                    NodeBundle {
+                        style: row_style(top, list.row_height),
+                        ..Default::default()
+                    },
+                    VirtualListRow { index },
+                ));
+                (list.spawn_row)(&mut row);
+                let row = row.id();
+                commands.entity(entity).add_child(row);
+            }
+        }
+
+        for row in free_rows {
+            let (_, mut style) = row_query.get_mut(row).unwrap();
+            if style.display != Display::None {
+                style.display = Display::None;
+            }
+        }
+    }
+}
+
+/// The style of a new row of a [`VirtualList`], at `top` logical pixels from the top of the list
+fn row_style(top: Val, row_height: f32) -> Style {
+    Style {
+        position_type: PositionType::Absolute,
+        position: UiRect {
+            left: Val::Px(0.0),
+            top,
+            ..Default::default()
+        },
+        size: Size::new(Val::Percent(100.0), Val::Px(row_height)),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy_ecs::{schedule::Schedule, world::World};
+    use bevy_math::Vec2;
+
+    #[test]
+    fn visible_ranges() {
+        let list = |item_count, overscan| {
+            VirtualList::new(item_count, 10.0, |_| {}).with_overscan(overscan)
+        };
+        // (list, offset, height, ui scale, range)
+        let cases = [
+            (list(100, 0), 0.0, 50.0, 1.0, 0..5),
+            (list(100, 0), 10.0, 10.0, 1.0, 1..2),
+            (list(100, 0), 10.0, 15.0, 1.0, 1..3),
+            (list(100, 0), 5.0, 10.0, 1.0, 0..2),
+            (list(100, 0), 100.0, 0.0, 1.0, 10..10),
+            (list(100, 2), 0.0, 50.0, 1.0, 0..7),
+            (list(100, 2), 25.0, 50.0, 1.0, 0..10),
+            (list(100, 2), 100.0, 50.0, 1.0, 8..17),
+            (list(100, 2), -30.0, 50.0, 1.0, 0..7),
+            (list(100, 2), 100.0, -50.0, 1.0, 8..12),
+            // the rows are scaled by the UI scale
+            (list(100, 2), 100.0, 50.0, 2.0, 3..10),
+            // the overscan is clamped to the items
+            (list(100, 2), 955.0, 50.0, 1.0, 93..100),
+            (list(3, 5), 0.0, 50.0, 1.0, 0..3),
+            (list(3, 5), 10.0, 5.0, 1.0, 0..3),
+            (list(100, 2), 2000.0, 50.0, 1.0, 100..100),
+            (list(0, 2), 0.0, 50.0, 1.0, 0..0),
+            (VirtualList::new(100, 0.0, |_| {}), 0.0, 50.0, 1.0, 0..0),
+        ];
+        for (list, offset, height, ui_scale, expected) in cases {
+            assert_eq!(
+                list.visible_range(offset, height, ui_scale),
+                expected,
+                "{} items with an overscan of {}, at {offset} over {height} pixels, scaled by {ui_scale}",
+                list.item_count,
+                list.overscan,
+            );
+        }
+    }
+
+    /// A list 50 logical pixels high, updated by running the [`virtual_list_system`]
+    struct Scroller {
+        world: World,
+        schedule: Schedule,
+        list: Entity,
+    }
+
+    impl Scroller {
+        fn new(list: VirtualList) -> Self {
+            let mut world = World::new();
+            world.init_resource::<UiScale>();
+            let node = Node {
+                calculated_size: Vec2::new(100.0, 50.0),
+                ..Default::default()
+            };
+            let list = world.spawn((list, node, ScrollPosition::default())).id();
+            let mut schedule = Schedule::new();
+            schedule.add_system(virtual_list_system);
+            let mut scroller = Self {
+                world,
+                schedule,
+                list,
+            };
+            scroller.scroll_to(0.0);
+            scroller
+        }
+
+        fn scroll_to(&mut self, offset: f32) {
+            let mut scroll_position = self.world.get_mut::<ScrollPosition>(self.list).unwrap();
+            scroll_position.offset.y = offset;
+            self.schedule.run(&mut self.world);
+        }
+
+        fn children(&self) -> Vec<Entity> {
+            self.world.get::<Children>(self.list).unwrap().to_vec()
+        }
+
+        /// The indices of the shown rows and their top, in the order of the children, and the
+        /// number of hidden rows
+        fn rows(&self) -> (Vec<(usize, Val)>, usize) {
+            let mut shown = Vec::new();
+            let mut hidden = 0;
+            for child in self.children() {
+                let Some(row) = self.world.get::<VirtualListRow>(child) else {
+                    continue;
+                };
+                let style = self.world.get::<Style>(child).unwrap();
+                match style.display {
+                    Display::Flex => {
+                        assert_eq!(style.size.height, Val::Px(10.0));
+                        shown.push((row.index, style.position.top));
+                    }
+                    _ => hidden += 1,
+                }
+            }
+            (shown, hidden)
+        }
+
+        /// The sorted indices of the shown rows, checking that their top is at their index
+        fn shown_indices(&self) -> Vec<usize> {
+            let (shown, _) = self.rows();
+            let mut indices: Vec<usize> = shown
+                .into_iter()
+                .map(|(index, top)| {
+                    assert_eq!(top, Val::Px(index as f32 * 10.0), "row {index}");
+                    index
+                })
+                .collect();
+            indices.sort_unstable();
+            indices
+        }
+
+        fn spacer_height(&self) -> Val {
+            let spacers: Vec<Val> = self
+                .children()
+                .into_iter()
+                .filter(|child| self.world.get::<VirtualListSpacer>(*child).is_some())
+                .map(|child| self.world.get::<Style>(child).unwrap().size.height)
+                .collect();
+            assert_eq!(spacers.len(), 1);
+            spacers[0]
+        }
+    }
+
+    fn list(item_count: usize) -> VirtualList {
+        VirtualList::new(item_count, 10.0, |row| {
+            row.insert(Marker);
+        })
+    }
+
+    #[derive(Component)]
+    struct Marker;
+
+    #[test]
+    fn spawns_the_rows_in_view() {
+        let scroller = Scroller::new(list(100));
+        assert_eq!(scroller.shown_indices(), (0..7).collect::<Vec<_>>());
+        assert_eq!(scroller.rows().1, 0);
+        assert_eq!(scroller.spacer_height(), Val::Px(1000.0));
+        // spawned by the row factory
+        for child in scroller.children() {
+            let is_row = scroller.world.get::<VirtualListRow>(child).is_some();
+            assert_eq!(scroller.world.get::<Marker>(child).is_some(), is_row);
+        }
+    }
+
+    #[test]
+    fn overscan_clamped_to_the_items() {
+        let scroller = Scroller::new(list(3).with_overscan(5));
+        assert_eq!(scroller.shown_indices(), [0, 1, 2]);
+        assert_eq!(scroller.rows().1, 0);
+        assert_eq!(scroller.spacer_height(), Val::Px(30.0));
+    }
+
+    #[test]
+    fn rows_recycled_when_scrolled() {
+        let mut scroller = Scroller::new(list(100));
+        let rows = scroller.children();
+        scroller.scroll_to(100.0);
+        assert_eq!(scroller.shown_indices(), (8..17).collect::<Vec<_>>());
+        // the 7 rows scrolled out of view are recycled, and 2 rows are spawned
+        let children = scroller.children();
+        assert_eq!(children.len(), rows.len() + 2);
+        assert!(rows.iter().all(|row| children.contains(row)));
+        assert_eq!(scroller.rows().1, 0);
+
+        // the rows not needed anymore are hidden
+        scroller.scroll_to(0.0);
+        assert_eq!(scroller.shown_indices(), (0..7).collect::<Vec<_>>());
+        assert_eq!(scroller.rows().1, 2);
+        assert_eq!(scroller.children().len(), children.len());
+    }
+
+    #[test]
+    fn no_duplicate_rows_after_scrolling() {
+        let mut scroller = Scroller::new(list(100));
+        for offset in [
+            5.0, 35.0, 70.0, 75.0, 500.0, 480.0, 990.0, 2000.0, 960.0, 0.0, 1.0,
+        ] {
+            scroller.scroll_to(offset);
+            let expected: Vec<usize> = list(100).visible_range(offset, 50.0, 1.0).collect();
+            assert_eq!(scroller.shown_indices(), expected, "scrolled to {offset}");
+            // at most the rows of the longest range are spawned
+            let (shown, hidden) = scroller.rows();
+            assert!(shown.len() + hidden <= 10, "scrolled to {offset}");
+        }
+    }
+
+    #[test]
+    fn duplicate_rows_are_recycled() {
+        let mut scroller = Scroller::new(list(100));
+        let rows: Vec<Entity> = scroller
+            .children()
+            .into_iter()
+            .filter(|child| scroller.world.get::<VirtualListRow>(*child).is_some())
+            .collect();
+        scroller
+            .world
+            .get_mut::<VirtualListRow>(rows[1])
+            .unwrap()
+            .index = 0;
+        scroller.scroll_to(0.0);
+        assert_eq!(scroller.shown_indices(), (0..7).collect::<Vec<_>>());
+        assert_eq!(scroller.children().len(), rows.len() + 1);
+    }
+
+    #[test]
+    fn item_count_changed() {
+        let mut scroller = Scroller::new(list(100));
+        scroller.scroll_to(100.0);
+        scroller
+            .world
+            .get_mut::<VirtualList>(scroller.list)
+            .unwrap()
+            .item_count = 3;
+        scroller.scroll_to(100.0);
+        assert!(scroller.shown_indices().is_empty());
+        assert_eq!(scroller.rows().1, 9);
+        assert_eq!(scroller.spacer_height(), Val::Px(30.0));
+        scroller.scroll_to(0.0);
+        assert_eq!(scroller.shown_indices(), [0, 1, 2]);
+        assert_eq!(scroller.rows().1, 6);
+    }
+}
@@ -0,0 +1,53 @@
+//! Draws lines and shapes in 2D with [`Gizmos`], which only last for the frame they were drawn in.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(draw_gizmos)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+}
+
+fn draw_gizmos(mut gizmos: Gizmos, time: Res<Time>) {
+    let t = time.elapsed_seconds();
+
+    gizmos.line_2d(
+        Vec2::new(-300.0, -200.0),
+        Vec2::new(300.0, -200.0),
+        Color::WHITE,
+    );
+    gizmos.line_gradient_2d(
+        Vec2::new(-300.0, 200.0),
+        Vec2::new(300.0, 200.0),
+        Color::RED,
+        Color::BLUE,
+    );
+    gizmos.linestrip_2d(
+        (0..=50).map(|i| {
+            let x = i as f32 * 12.0 - 300.0;
+            Vec2::new(x, (x / 40.0 + t * 2.0).sin() * 50.0)
+        }),
+        Color::CYAN,
+    );
+
+    gizmos.circle_2d(Vec2::new(-200.0, 100.0), 60.0, Color::GREEN);
+    gizmos.rect_2d(
+        Vec2::new(200.0, 100.0),
+        t,
+        Vec2::splat(100.0),
+        Color::YELLOW,
+    );
+    gizmos.arrow_2d(
+        Vec2::new(0.0, -120.0),
+        Vec2::new(0.0, -120.0) + Vec2::from_angle(t * PI / 2.0) * 60.0,
+        Color::ORANGE,
+    );
+}
@@ -0,0 +1,94 @@
+//! Animates a sprite sheet on the GPU with a [`Flipbook`], instead of changing the displayed
+//! frame from a system every few frames.
+
+use bevy::{prelude::*, sprite::MaterialMesh2dBundle};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest())) // prevents blurry sprites
+        .add_startup_system(setup)
+        .add_system(restart_once)
+        .run();
+}
+
+/// Marks the material whose animation only plays once.
+#[derive(Resource)]
+struct OnceMaterial(Handle<ColorMaterial>);
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn(Camera2dBundle::default());
+
+    let texture = asset_server.load("textures/rpg/chars/gabe/gabe-idle-run.png");
+    let quad = meshes.add(shape::Quad::new(Vec2::splat(144.0)).into());
+    let flipbook = Flipbook::new(7, 1).with_fps(10.0);
+
+    let mut once_material = None;
+    for (i, mode) in [
+        FlipbookMode::Loop,
+        FlipbookMode::Once,
+        FlipbookMode::PingPong,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let material = materials.add(ColorMaterial {
+            texture: Some(texture.clone()),
+            flipbook: Some(flipbook.with_mode(mode)),
+            ..default()
+        });
+        if mode == FlipbookMode::Once {
+            once_material = Some(material.clone());
+        }
+        commands.spawn(MaterialMesh2dBundle {
+            mesh: quad.clone().into(),
+            material,
+            transform: Transform::from_xyz((i as f32 - 1.0) * 200.0, 0.0, 0.0),
+            ..default()
+        });
+    }
+    commands.insert_resource(OnceMaterial(once_material.unwrap()));
+
+    commands.spawn(
+        TextBundle::from_section(
+            "Loop, Once and PingPong flipbooks\nPress Space to restart the Once flipbook",
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        }),
+    );
+}
+
+fn restart_once(
+    keyboard_input: Res<Input<KeyCode>>,
+    time: Res<Time>,
+    once_material: Res<OnceMaterial>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    if let Some(flipbook) = materials
+        .get_mut(&once_material.0)
+        .and_then(|material| material.flipbook.as_mut())
+    {
+        // The shaders play flipbooks on the same clock as `Time::elapsed_seconds_wrapped`
+        flipbook.start_time = time.elapsed_seconds_wrapped();
+    }
+}
@@ -0,0 +1,213 @@
+//! Lights a normal-mapped brick wall with [`PointLight2d`]s, and a lantern with an emissive
+//! color that glows in the dark.
+//!
+//! Move the cursor to move the white light.
+
+use std::f32::consts::TAU;
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    sprite::MaterialMesh2dBundle,
+    window::PrimaryWindow,
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_systems((follow_cursor, orbit_lights))
+        .run();
+}
+
+const WALL_SIZE: u32 = 256;
+const BRICK_SIZE: UVec2 = UVec2::new(32, 16);
+/// The width of the bevel around the bricks, in pixels.
+const BEVEL: f32 = 3.0;
+
+/// Creates the color texture and the normal map of a brick wall.
+fn brick_wall() -> (Image, Image) {
+    let mut colors = Vec::with_capacity((WALL_SIZE * WALL_SIZE * 4) as usize);
+    let mut normals = Vec::with_capacity((WALL_SIZE * WALL_SIZE * 4) as usize);
+    for y in 0..WALL_SIZE {
+        for x in 0..WALL_SIZE {
+            // Every other row of bricks is offset by half a brick
+            let row = y / BRICK_SIZE.y;
+            let x = x + (row % 2) * BRICK_SIZE.x / 2;
+            let local = Vec2::new(
+                (x % BRICK_SIZE.x) as f32 + 0.5,
+                (y % BRICK_SIZE.y) as f32 + 0.5,
+            );
+            let size = BRICK_SIZE.as_vec2();
+
+            // The slopes of the bevel face away from the center of the brick. The rows of the
+            // image go down, while the Y axis of the normal map goes up.
+            let mut slope = Vec2::ZERO;
+            if local.x < BEVEL {
+                slope.x = -1.0;
+            } else if local.x > size.x - BEVEL {
+                slope.x = 1.0;
+            }
+            if local.y < BEVEL {
+                slope.y = 1.0;
+            } else if local.y > size.y - BEVEL {
+                slope.y = -1.0;
+            }
+            let normal = slope.extend(1.0).normalize();
+            let encoded = (normal * 0.5 + 0.5) * 255.0;
+            normals.extend_from_slice(&[encoded.x as u8, encoded.y as u8, encoded.z as u8, 255]);
+
+            let noise = ((x * 7 + y * 13 + (x / BRICK_SIZE.x) * 29) % 9) as u8;
+            let color = if slope == Vec2::ZERO {
+                [150 + noise * 4, 70 + noise * 2, 50, 255]
+            } else {
+                [110 + noise * 2, 55, 40, 255]
+            };
+            colors.extend_from_slice(&color);
+        }
+    }
+
+    let size = Extent3d {
+        width: WALL_SIZE,
+        height: WALL_SIZE,
+        depth_or_array_layers: 1,
+    };
+    (
+        Image::new(
+            size,
+            TextureDimension::D2,
+            colors,
+            TextureFormat::Rgba8UnormSrgb,
+        ),
+        // Normal maps must not be sRGB
+        Image::new(
+            size,
+            TextureDimension::D2,
+            normals,
+            TextureFormat::Rgba8Unorm,
+        ),
+    )
+}
+
+/// Marks the light following the cursor.
+#[derive(Component)]
+struct CursorLight;
+
+/// A light orbiting around the center of the wall.
+#[derive(Component)]
+struct Orbit {
+    radius: f32,
+    speed: f32,
+    phase: f32,
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+) {
+    // A dim ambient light, so that the point lights stand out
+    commands.spawn((
+        Camera2dBundle::default(),
+        AmbientLight2d {
+            color: Color::rgb(0.6, 0.7, 1.0),
+            brightness: 0.1,
+        },
+    ));
+
+    let (texture, normal_map) = brick_wall();
+    commands.spawn(MaterialMesh2dBundle {
+        mesh: meshes
+            .add(shape::Quad::new(Vec2::splat(WALL_SIZE as f32 * 3.0)).into())
+            .into(),
+        material: materials.add(ColorMaterial {
+            texture: Some(images.add(texture)),
+            normal_map_texture: Some(images.add(normal_map)),
+            ..default()
+        }),
+        ..default()
+    });
+
+    // The lantern glows even where it isn't lit
+    commands.spawn(MaterialMesh2dBundle {
+        mesh: meshes.add(shape::Circle::new(20.0).into()).into(),
+        material: materials.add(ColorMaterial {
+            color: Color::rgb(0.2, 0.15, 0.1),
+            emissive: Color::rgb(1.0, 0.7, 0.2),
+            ..default()
+        }),
+        transform: Transform::from_xyz(-250.0, 150.0, 1.0),
+        ..default()
+    });
+    commands.spawn(PointLight2dBundle {
+        point_light: PointLight2d {
+            color: Color::rgb(1.0, 0.7, 0.2),
+            intensity: 1.5,
+            radius: 300.0,
+            height: 40.0,
+        },
+        transform: Transform::from_xyz(-250.0, 150.0, 1.0),
+        ..default()
+    });
+
+    commands.spawn((
+        PointLight2dBundle {
+            point_light: PointLight2d {
+                intensity: 2.0,
+                ..default()
+            },
+            ..default()
+        },
+        CursorLight,
+    ));
+
+    for (i, color) in [Color::RED, Color::GREEN, Color::BLUE]
+        .into_iter()
+        .enumerate()
+    {
+        commands.spawn((
+            PointLight2dBundle {
+                point_light: PointLight2d {
+                    color,
+                    intensity: 1.5,
+                    radius: 200.0,
+                    height: 20.0,
+                },
+                ..default()
+            },
+            Orbit {
+                radius: 250.0,
+                speed: 0.5,
+                phase: i as f32 / 3.0 * TAU,
+            },
+        ));
+    }
+}
+
+fn follow_cursor(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mut lights: Query<&mut Transform, With<CursorLight>>,
+) {
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+    else {
+        return;
+    };
+    for mut transform in &mut lights {
+        transform.translation = cursor.extend(transform.translation.z);
+    }
+}
+
+fn orbit_lights(time: Res<Time>, mut lights: Query<(&mut Transform, &Orbit)>) {
+    for (mut transform, orbit) in &mut lights {
+        let angle = orbit.phase + time.elapsed_seconds() * orbit.speed;
+        transform.translation = (Vec2::from_angle(angle) * orbit.radius).extend(0.0);
+    }
+}
@@ -0,0 +1,107 @@
+//! A side-scrolling background made of [`ParallaxLayer`]s, which move slower the further away
+//! they are, and repeat endlessly.
+//!
+//! Use the left and right arrow keys to scroll faster or backwards.
+
+use std::f32::consts::TAU;
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(scroll_camera)
+        .run();
+}
+
+const LAYER_SIZE: UVec2 = UVec2::new(512, 256);
+
+/// Creates the image of a layer of hills, whose ridge is the sum of `waves` of an amplitude and a
+/// whole number of periods over the width of the image, so that the image tiles seamlessly.
+fn hills(base: f32, waves: &[(f32, f32)], color: [u8; 4]) -> Image {
+    let mut data = Vec::with_capacity((LAYER_SIZE.x * LAYER_SIZE.y * 4) as usize);
+    for y in 0..LAYER_SIZE.y {
+        for x in 0..LAYER_SIZE.x {
+            let t = x as f32 / LAYER_SIZE.x as f32;
+            let ridge = waves.iter().fold(base, |ridge, (amplitude, periods)| {
+                ridge + amplitude * (t * periods * TAU).sin()
+            });
+            // The rows of the image go down from the top
+            let height = (LAYER_SIZE.y - y) as f32;
+            data.extend_from_slice(if height < ridge { &color } else { &[0; 4] });
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: LAYER_SIZE.x,
+            height: LAYER_SIZE.y,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    commands.spawn(Camera2dBundle::default());
+
+    // The sky stays fixed on screen, the hills further away move slower, and the ground moves with
+    // the world
+    commands.spawn((
+        SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(0.5, 0.7, 0.9),
+                custom_size: Some(Vec2::new(4000.0, 4000.0)),
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 0.0, -4.0),
+            ..default()
+        },
+        ParallaxLayer {
+            factor: Vec2::ZERO,
+            repeat: BVec2::FALSE,
+        },
+    ));
+    let layers = [
+        (0.1, 180.0, [(30.0, 2.0), (15.0, 5.0)], [150, 160, 190, 255]),
+        (0.3, 140.0, [(25.0, 3.0), (10.0, 7.0)], [90, 130, 110, 255]),
+        (0.6, 100.0, [(20.0, 4.0), (8.0, 11.0)], [60, 110, 60, 255]),
+        (1.0, 40.0, [(4.0, 8.0), (2.0, 19.0)], [90, 70, 40, 255]),
+    ];
+    for (i, (factor, base, waves, color)) in layers.into_iter().enumerate() {
+        commands.spawn((
+            SpriteBundle {
+                texture: images.add(hills(base, &waves, color)),
+                sprite: Sprite {
+                    anchor: bevy::sprite::Anchor::BottomCenter,
+                    ..default()
+                },
+                transform: Transform::from_xyz(0.0, -360.0, -3.0 + i as f32),
+                ..default()
+            },
+            ParallaxLayer::new(factor),
+        ));
+    }
+}
+
+fn scroll_camera(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut cameras: Query<&mut Transform, With<Camera>>,
+) {
+    let mut speed = 150.0;
+    if keyboard_input.pressed(KeyCode::Right) {
+        speed *= 4.0;
+    }
+    if keyboard_input.pressed(KeyCode::Left) {
+        speed *= -4.0;
+    }
+    for mut transform in &mut cameras {
+        transform.translation.x += speed * time.delta_seconds();
+    }
+}
@@ -1,4 +1,7 @@
 //! Renders a 2D scene containing pixelated bevy logo in a pixel perfect style
+//!
+//! The scene is rendered at a low resolution with a [`PixelPerfectCamera`], then upscaled to the
+//! window by a whole number, so that the pixels of the logo stay square while it moves.
 
 use bevy::prelude::*;
 
@@ -17,11 +20,14 @@ enum Direction {
 }
 
 fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.spawn(Camera2dBundle::default());
+    commands.spawn((
+        Camera2dBundle::default(),
+        PixelPerfectCamera::new(UVec2::new(320, 180)),
+    ));
     commands.spawn((
         SpriteBundle {
             texture: asset_server.load("pixel/bevy_pixel_light.png"),
-            transform: Transform::from_xyz(100., 0., 0.),
+            transform: Transform::from_xyz(50., 0., 0.),
             ..default()
         },
         Direction::Right,
@@ -35,9 +41,9 @@ fn sprite_movement(time: Res<Time>, mut sprite_position: Query<(&mut Direction,
             Direction::Left => transform.translation.x -= 30. * time.delta_seconds(),
         }
 
-        if transform.translation.x > 200. {
+        if transform.translation.x > 90. {
             *logo = Direction::Left;
-        } else if transform.translation.x < -200. {
+        } else if transform.translation.x < -90. {
             *logo = Direction::Right;
         }
     }
@@ -0,0 +1,70 @@
+//! Draws circles, rounded rectangles and lines with [`ShapeBundle`]s, which are rendered from
+//! their signed distance fields on the GPU, without meshes.
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(rotate)
+        .run();
+}
+
+/// Rotates a shape at this speed, in radians per second.
+#[derive(Component)]
+struct Rotate(f32);
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+
+    commands.spawn(ShapeBundle {
+        shape: Shape2d::circle(80.0),
+        fill: Fill::color(Color::rgb(0.9, 0.4, 0.3)),
+        stroke: Stroke::new(Color::WHITE, 6.0),
+        transform: Transform::from_xyz(-300.0, 0.0, 0.0),
+        ..default()
+    });
+
+    commands.spawn((
+        ShapeBundle {
+            shape: Shape2d::rounded_rectangle(Vec2::new(200.0, 120.0), 24.0),
+            fill: Fill::color(Color::rgb(0.3, 0.6, 0.9)),
+            ..default()
+        },
+        Rotate(0.5),
+    ));
+
+    // An outline without a fill
+    commands.spawn((
+        ShapeBundle {
+            shape: Shape2d::rectangle(Vec2::new(120.0, 120.0)),
+            fill: Fill::color(Color::NONE),
+            stroke: Stroke::new(Color::YELLOW, 3.0),
+            transform: Transform::from_xyz(300.0, 0.0, 0.0),
+            ..default()
+        },
+        Rotate(-1.0),
+    ));
+
+    // Lines of increasing widths
+    for i in 0..5 {
+        let y = -200.0 + i as f32 * 20.0;
+        commands.spawn(ShapeBundle {
+            shape: Shape2d::line(
+                Vec2::new(-400.0, y),
+                Vec2::new(400.0, y + 60.0),
+                1.0 + i as f32 * 2.0,
+            ),
+            fill: Fill::color(Color::rgba(1.0, 1.0, 1.0, 0.8)),
+            transform: Transform::from_xyz(0.0, 0.0, 1.0),
+            ..default()
+        });
+    }
+}
+
+fn rotate(time: Res<Time>, mut shapes: Query<(&mut Transform, &Rotate)>) {
+    for (mut transform, rotate) in &mut shapes {
+        transform.rotate_z(rotate.0 * time.delta_seconds());
+    }
+}
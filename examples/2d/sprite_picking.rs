@@ -0,0 +1,106 @@
+//! Highlights the sprites under the mouse cursor and spins the clicked ones, with
+//! [`PickableSprite`]s. The Bevy logos are only picked where they are opaque.
+
+use bevy::{prelude::*, sprite::SpritePickingPlugin};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest()))
+        .add_plugin(SpritePickingPlugin)
+        .add_startup_system(setup)
+        .add_systems((highlight, spin))
+        .run();
+}
+
+/// Spins a sprite until it's back where it started.
+#[derive(Component, Default)]
+struct Spin(f32);
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+) {
+    commands.spawn(Camera2dBundle::default());
+
+    // Overlapping logos: the cursor over the transparent corners of the one on top picks the
+    // one below it
+    for (i, x) in [-250.0, -130.0].into_iter().enumerate() {
+        commands.spawn((
+            SpriteBundle {
+                texture: asset_server.load("branding/icon.png"),
+                transform: Transform::from_xyz(x, 0.0, i as f32).with_scale(Vec3::splat(0.6)),
+                ..default()
+            },
+            PickableSprite::with_alpha_threshold(0.5),
+            Spin::default(),
+        ));
+    }
+
+    // Frames of a texture atlas, picked in their whole rect
+    let texture_atlas = TextureAtlas::from_grid(
+        asset_server.load("textures/rpg/chars/gabe/gabe-idle-run.png"),
+        Vec2::new(24.0, 24.0),
+        7,
+        1,
+        None,
+        None,
+    );
+    let texture_atlas = texture_atlases.add(texture_atlas);
+    for i in 0..3 {
+        commands.spawn((
+            SpriteSheetBundle {
+                texture_atlas: texture_atlas.clone(),
+                sprite: TextureAtlasSprite {
+                    index: i * 2,
+                    flip_x: i == 1,
+                    ..default()
+                },
+                transform: Transform::from_xyz(120.0 + i as f32 * 110.0, 0.0, 0.0)
+                    .with_scale(Vec3::splat(4.0)),
+                ..default()
+            },
+            PickableSprite::default(),
+            Spin::default(),
+        ));
+    }
+}
+
+fn highlight(
+    mut pointer_events: EventReader<SpritePointerEvent>,
+    mut sprites: Query<&mut Sprite>,
+    mut atlas_sprites: Query<&mut TextureAtlasSprite>,
+) {
+    for event in pointer_events.iter() {
+        let color = match event.kind {
+            SpritePointerEventKind::Over => Color::rgb(1.0, 0.8, 0.4),
+            SpritePointerEventKind::Out => Color::WHITE,
+            _ => continue,
+        };
+        if let Ok(mut sprite) = sprites.get_mut(event.entity) {
+            sprite.color = color;
+        }
+        if let Ok(mut sprite) = atlas_sprites.get_mut(event.entity) {
+            sprite.color = color;
+        }
+    }
+}
+
+fn spin(
+    time: Res<Time>,
+    mut pointer_events: EventReader<SpritePointerEvent>,
+    mut sprites: Query<(&mut Transform, &mut Spin)>,
+) {
+    for event in pointer_events.iter() {
+        if event.kind == SpritePointerEventKind::Click(MouseButton::Left) {
+            if let Ok((_, mut spin)) = sprites.get_mut(event.entity) {
+                spin.0 = std::f32::consts::TAU;
+            }
+        }
+    }
+    for (mut transform, mut spin) in &mut sprites {
+        let angle = spin.0.min(8.0 * time.delta_seconds());
+        transform.rotate_z(angle);
+        spin.0 -= angle;
+    }
+}
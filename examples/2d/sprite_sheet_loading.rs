@@ -0,0 +1,48 @@
+//! Loads a sprite sheet exported by Aseprite as a [`TextureAtlas`], along with the animation clip
+//! of each of its tags. Press space to switch between the clips.
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest())) // prevents blurry sprites
+        .add_startup_system(setup)
+        .add_system(switch_clip)
+        .run();
+}
+
+const CLIPS: [&str; 3] = ["run", "moonwalk", "idle"];
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+    commands.spawn((
+        SpriteSheetBundle {
+            // The frames of the sheet are loaded as the default asset of the file
+            texture_atlas: asset_server.load("textures/rpg/chars/gabe/gabe-idle-run.sheet.json"),
+            transform: Transform::from_scale(Vec3::splat(6.0)),
+            ..default()
+        },
+        // The clip is played with a `SpriteAnimation` as soon as it's loaded
+        asset_server.load::<SpriteAnimationClip, _>(clip_path(CLIPS[0])),
+    ));
+}
+
+fn clip_path(tag: &str) -> String {
+    format!("textures/rpg/chars/gabe/gabe-idle-run.sheet.json#Animation/{tag}")
+}
+
+fn switch_clip(
+    keyboard_input: Res<Input<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    mut clip_index: Local<usize>,
+    mut clips: Query<&mut Handle<SpriteAnimationClip>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Space) {
+        return;
+    }
+    *clip_index = (*clip_index + 1) % CLIPS.len();
+    info!("Playing the {} clip", CLIPS[*clip_index]);
+    for mut clip in &mut clips {
+        *clip = asset_server.load(clip_path(CLIPS[*clip_index]));
+    }
+}
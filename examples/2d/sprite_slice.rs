@@ -0,0 +1,122 @@
+//! Scales sprites and UI images with an [`ImageScaleMode`], keeping the borders of a panel sharp
+//! with 9-slicing and repeating an image with tiling.
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest())) // prevents blurry sprites
+        .add_startup_system(setup)
+        .run();
+}
+
+/// The size of the borders of the panel image, in pixels.
+const BORDER: f32 = 8.0;
+
+/// Creates a 32x32 panel with a light frame around a darker center.
+fn panel_image() -> Image {
+    const SIZE: u32 = 32;
+    let mut data = Vec::with_capacity((SIZE * SIZE * 4) as usize);
+    for y in 0..SIZE {
+        for x in 0..SIZE {
+            let edge = x.min(y).min(SIZE - 1 - x).min(SIZE - 1 - y);
+            let color: [u8; 4] = match edge {
+                0 => [20, 20, 30, 255],
+                1..=2 => [230, 200, 120, 255],
+                3..=7 => [150, 110, 60, 255],
+                // A checkerboard shows how the center is scaled
+                _ if (x / 4 + y / 4) % 2 == 0 => [60, 60, 80, 255],
+                _ => [50, 50, 65, 255],
+            };
+            data.extend_from_slice(&color);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+fn setup(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn(Camera2dBundle::default());
+
+    let panel = images.add(panel_image());
+    let slicer = TextureSlicer {
+        border: BorderRect::square(BORDER),
+        ..default()
+    };
+
+    // The same panel stretched, sliced, and sliced with a tiled center and edges
+    for (i, scale_mode) in [
+        ImageScaleMode::Stretched,
+        ImageScaleMode::Sliced(slicer),
+        ImageScaleMode::Sliced(TextureSlicer {
+            center_scale_mode: SliceScaleMode::Tile { stretch_value: 1.0 },
+            sides_scale_mode: SliceScaleMode::Tile { stretch_value: 1.0 },
+            ..slicer
+        }),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        commands.spawn(SpriteBundle {
+            texture: panel.clone(),
+            sprite: Sprite {
+                custom_size: Some(Vec2::new(260.0, 140.0)),
+                scale_mode,
+                ..default()
+            },
+            transform: Transform::from_xyz((i as f32 - 1.0) * 300.0, 150.0, 0.0),
+            ..default()
+        });
+    }
+
+    // A strip of repeated icons
+    commands.spawn(SpriteBundle {
+        texture: asset_server.load("branding/icon.png"),
+        sprite: Sprite {
+            custom_size: Some(Vec2::new(860.0, 64.0)),
+            scale_mode: ImageScaleMode::Tiled {
+                tile_x: true,
+                tile_y: false,
+                stretch_value: 0.25,
+            },
+            ..default()
+        },
+        transform: Transform::from_xyz(0.0, -20.0, 0.0),
+        ..default()
+    });
+
+    // A UI panel using the same image
+    commands.spawn(ImageBundle {
+        style: Style {
+            size: Size::new(Val::Px(400.0), Val::Px(120.0)),
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(30.0),
+                left: Val::Px(30.0),
+                ..default()
+            },
+            ..default()
+        },
+        image: UiImage {
+            texture: panel,
+            scale_mode: ImageScaleMode::Sliced(slicer),
+            ..default()
+        },
+        ..default()
+    });
+}
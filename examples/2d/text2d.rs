@@ -35,6 +35,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         font: font.clone(),
         font_size: 60.0,
         color: Color::WHITE,
+        ..default()
     };
     let text_alignment = TextAlignment::Center;
     // 2d camera
@@ -69,6 +70,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         font,
         font_size: 42.0,
         color: Color::WHITE,
+        ..default()
     };
     let box_size = Vec2::new(300.0, 200.0);
     let box_position = Vec2::new(0.0, -250.0);
@@ -91,6 +93,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                     )],
                     alignment: TextAlignment::Left,
                     linebreak_behaviour: BreakLineOn::WordBoundary,
+                    ..default()
                 },
                 text_2d_bounds: Text2dBounds {
                     // Wrap text in the rectangle
@@ -123,6 +126,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                     )],
                     alignment: TextAlignment::Left,
                     linebreak_behaviour: BreakLineOn::AnyCharacter,
+                    ..default()
                 },
                 text_2d_bounds: Text2dBounds {
                     // Wrap text in the rectangle
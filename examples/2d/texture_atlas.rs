@@ -5,7 +5,7 @@ use bevy::{asset::LoadState, prelude::*};
 
 fn main() {
     App::new()
-        .init_resource::<RpgSpriteHandles>()
+        .init_resource::<RpgSpriteFolder>()
         .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest())) // prevents blurry sprites
         .add_state::<AppState>()
         .add_system(load_textures.in_schedule(OnEnter(AppState::Setup)))
@@ -30,38 +30,40 @@ impl States for AppState {
 }
 
 #[derive(Resource, Default)]
-struct RpgSpriteHandles {
-    handles: Vec<HandleUntyped>,
-}
+struct RpgSpriteFolder(Handle<LoadedFolder>);
 
-fn load_textures(mut rpg_sprite_handles: ResMut<RpgSpriteHandles>, asset_server: Res<AssetServer>) {
-    rpg_sprite_handles.handles = asset_server.load_folder("textures/rpg").unwrap();
+fn load_textures(mut rpg_sprite_folder: ResMut<RpgSpriteFolder>, asset_server: Res<AssetServer>) {
+    // load the PNG images of the folder and of its subfolders
+    rpg_sprite_folder.0 = asset_server.load_folder("textures/rpg/**/*.png");
 }
 
 fn check_textures(
     mut next_state: ResMut<NextState<AppState>>,
-    rpg_sprite_handles: ResMut<RpgSpriteHandles>,
+    rpg_sprite_folder: Res<RpgSpriteFolder>,
     asset_server: Res<AssetServer>,
 ) {
-    if let LoadState::Loaded = asset_server
-        .get_group_load_state(rpg_sprite_handles.handles.iter().map(|handle| handle.id()))
-    {
+    // the folder is loaded once all its images are loaded
+    if let LoadState::Loaded = asset_server.get_load_state(&rpg_sprite_folder.0) {
         next_state.set(AppState::Finished);
     }
 }
 
 fn setup(
     mut commands: Commands,
-    rpg_sprite_handles: Res<RpgSpriteHandles>,
+    rpg_sprite_folder: Res<RpgSpriteFolder>,
     asset_server: Res<AssetServer>,
+    loaded_folders: Res<Assets<LoadedFolder>>,
     mut texture_atlases: ResMut<Assets<TextureAtlas>>,
     mut textures: ResMut<Assets<Image>>,
 ) {
     let mut texture_atlas_builder = TextureAtlasBuilder::default();
-    for handle in &rpg_sprite_handles.handles {
-        let handle = handle.typed_weak();
+    let loaded_folder = loaded_folders.get(&rpg_sprite_folder.0).unwrap();
+    for handle in loaded_folder.handles_of::<Image>() {
         let Some(texture) = textures.get(&handle) else {
-            warn!("{:?} did not resolve to an `Image` asset.", asset_server.get_handle_path(handle));
+            warn!(
+                "{:?} did not resolve to an `Image` asset.",
+                asset_server.get_handle_path(&handle)
+            );
             continue;
         };
 
@@ -0,0 +1,124 @@
+//! Draws a large [`Tilemap`] with animated water, and paints tiles under the cursor.
+//!
+//! Each chunk of the tilemap is a single draw call, instead of a sprite for each tile.
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+    window::PrimaryWindow,
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest())) // prevents blurry tiles
+        .add_startup_system(setup)
+        .add_system(paint_tiles)
+        .run();
+}
+
+const TILE_SIZE: u32 = 16;
+const MAP_SIZE: i32 = 256;
+
+const GRASS: u32 = 0;
+const SAND: u32 = 1;
+/// The first of the 4 frames of the water animation.
+const WATER: u32 = 2;
+
+/// Creates a tileset with a tile in each layer: grass, sand, then the frames of the water.
+fn tileset() -> Image {
+    const LAYERS: u32 = 6;
+    let mut data = Vec::with_capacity((TILE_SIZE * TILE_SIZE * 4 * LAYERS) as usize);
+    for layer in 0..LAYERS {
+        for y in 0..TILE_SIZE {
+            for x in 0..TILE_SIZE {
+                let noise = (x * 7 + y * 13 + x * y) % 5;
+                let color: [u8; 4] = match layer {
+                    GRASS => [60, 140 + noise as u8 * 8, 60, 255],
+                    SAND => [220, 200 + noise as u8 * 6, 140, 255],
+                    // Waves that move along the frames of the animation
+                    frame => {
+                        let wave = (y + TILE_SIZE - (frame - WATER) * 4 + x / 4) % 8 == 0;
+                        if wave {
+                            [140, 190, 240, 255]
+                        } else {
+                            [40, 90, 200, 255]
+                        }
+                    }
+                };
+                data.extend_from_slice(&color);
+            }
+        }
+    }
+    Image::new(
+        Extent3d {
+            width: TILE_SIZE,
+            height: TILE_SIZE,
+            depth_or_array_layers: LAYERS,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+fn setup(mut commands: Commands, mut images: ResMut<Assets<Image>>) {
+    commands.spawn(Camera2dBundle::default());
+
+    // An island in the middle of the sea
+    let mut tilemap = Tilemap::new(Vec2::splat(TILE_SIZE as f32));
+    for y in -MAP_SIZE / 2..MAP_SIZE / 2 {
+        for x in -MAP_SIZE / 2..MAP_SIZE / 2 {
+            let distance = Vec2::new(x as f32, y as f32).length()
+                + 6.0 * (x as f32 * 0.2).sin() * (y as f32 * 0.15).cos();
+            let tile = if distance < 20.0 {
+                Tile::new(GRASS)
+            } else if distance < 24.0 {
+                Tile::new(SAND)
+            } else {
+                Tile::new(WATER).with_animation(4, 4.0)
+            };
+            tilemap.set_tile(IVec2::new(x, y), tile);
+        }
+    }
+
+    commands.spawn(TilemapBundle {
+        tilemap,
+        texture: images.add(tileset()),
+        ..default()
+    });
+}
+
+/// Turns the tile under the cursor into grass.
+fn paint_tiles(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform)>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    mut tilemaps: Query<(&mut Tilemap, &GlobalTransform)>,
+) {
+    if !mouse_button_input.pressed(MouseButton::Left) {
+        return;
+    }
+    let (Ok(window), Ok((camera, camera_transform))) = (windows.get_single(), cameras.get_single())
+    else {
+        return;
+    };
+    let Some(cursor) = window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor))
+    else {
+        return;
+    };
+
+    for (mut tilemap, transform) in &mut tilemaps {
+        let local = transform
+            .compute_matrix()
+            .inverse()
+            .transform_point3(cursor.extend(0.0))
+            .truncate();
+        let position = (local / tilemap.tile_size()).round().as_ivec2();
+        // Only the chunk of the tile is uploaded again
+        if tilemap.get_tile(position).map(|tile| tile.index) != Some(GRASS) {
+            tilemap.set_tile(position, Tile::new(GRASS));
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Draws lines and shapes in 3D with [`Gizmos`], which only last for the frame they were drawn in.
+//!
+//! Press space to toggle whether the gizmos are hidden behind the scene.
+
+use std::f32::consts::PI;
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_systems((draw_gizmos, toggle_depth_test))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(5.0).into()),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+        material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
+        transform: Transform::from_xyz(0.0, 0.5, 0.0),
+        ..default()
+    });
+    commands.spawn(PointLightBundle {
+        point_light: PointLight {
+            intensity: 1500.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 4.0, 7.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+}
+
+fn draw_gizmos(mut gizmos: Gizmos, time: Res<Time>) {
+    let t = time.elapsed_seconds();
+
+    // The axes of the world
+    gizmos.arrow(Vec3::ZERO, Vec3::X * 2.0, Color::RED);
+    gizmos.arrow(Vec3::ZERO, Vec3::Y * 2.0, Color::GREEN);
+    gizmos.arrow(Vec3::ZERO, Vec3::Z * 2.0, Color::BLUE);
+
+    // The bounds of the cube, slightly larger than it
+    gizmos.cuboid(
+        Transform::from_xyz(0.0, 0.5, 0.0).with_scale(Vec3::splat(1.1)),
+        Color::BLACK,
+    );
+
+    gizmos.sphere(
+        Vec3::new(-1.5, 1.0 + (t * 2.0).sin() * 0.5, 0.0),
+        Quat::from_rotation_y(t),
+        0.5,
+        Color::WHITE,
+    );
+    gizmos.circle(Vec3::new(1.5, 0.01, 0.0), Vec3::Y, 0.5, Color::YELLOW);
+    gizmos.rect(
+        Vec3::new(0.0, 1.5, -1.5),
+        Quat::from_rotation_z(t * PI / 4.0),
+        Vec2::splat(1.0),
+        Color::FUCHSIA,
+    );
+    gizmos.linestrip_gradient([
+        (Vec3::new(-2.5, 0.0, 2.5), Color::RED),
+        (Vec3::new(-2.5, 1.0, 2.5), Color::GREEN),
+        (Vec3::new(2.5, 1.0, 2.5), Color::BLUE),
+    ]);
+}
+
+fn toggle_depth_test(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut config_store: ResMut<GizmoConfigStore>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        let config = config_store.config_mut::<DefaultGizmoConfigGroup>();
+        config.depth_test = !config.depth_test;
+    }
+}
@@ -20,7 +20,7 @@ fn main() {
 #[derive(Component)]
 struct Shape;
 
-const X_EXTENT: f32 = 14.5;
+const X_EXTENT: f32 = 18.0;
 
 fn setup(
     mut commands: Commands,
@@ -41,6 +41,9 @@ fn setup(
         meshes.add(shape::Cylinder::default().into()),
         meshes.add(shape::Icosphere::default().try_into().unwrap()),
         meshes.add(shape::UVSphere::default().into()),
+        meshes.add(star().into()),
+        meshes.add(shape::Lathe::default().into()),
+        meshes.add(dumbbell()),
     ];
 
     let num_shapes = shapes.len();
@@ -81,11 +84,42 @@ fn setup(
     });
 
     commands.spawn(Camera3dBundle {
-        transform: Transform::from_xyz(0.0, 6., 12.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
+        transform: Transform::from_xyz(0.0, 7., 14.0).looking_at(Vec3::new(0., 1., 0.), Vec3::Y),
         ..default()
     });
 }
 
+/// A five-pointed star extruded along the `Z` axis.
+fn star() -> shape::Extrusion {
+    let outline: Vec<_> = (0..10)
+        .map(|i| {
+            let angle = i as f32 * PI / 5.0;
+            let radius = if i % 2 == 0 { 0.6 } else { 0.25 };
+            Vec2::new(-angle.sin(), angle.cos()) * radius
+        })
+        .collect();
+    shape::Extrusion::new(outline, 0.3)
+}
+
+/// A single mesh merged from two spheres and the cylinder joining them.
+fn dumbbell() -> Mesh {
+    let mut dumbbell = Mesh::from(shape::Cylinder {
+        radius: 0.1,
+        height: 1.0,
+        ..default()
+    });
+    let weight = Mesh::from(shape::UVSphere {
+        radius: 0.3,
+        ..default()
+    });
+    for y in [-0.5, 0.5] {
+        dumbbell
+            .merge(&weight, Transform::from_xyz(0.0, y, 0.0))
+            .unwrap();
+    }
+    dumbbell
+}
+
 fn rotate(mut query: Query<&mut Transform, With<Shape>>, time: Res<Time>) {
     for mut transform in &mut query {
         transform.rotate_y(time.delta_seconds() / 2.);
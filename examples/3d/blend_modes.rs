@@ -20,12 +20,6 @@ fn main() {
         .add_startup_system(setup)
         .add_system(example_control_system);
 
-    // Unfortunately, MSAA and HDR are not supported simultaneously under WebGL.
-    // Since this example uses HDR, we must disable MSAA for WASM builds, at least
-    // until WebGPU is ready and no longer behind a feature flag in Web browsers.
-    #[cfg(target_arch = "wasm32")]
-    app.insert_resource(Msaa { samples: 1 }); // Default is 4 samples (MSAA on)
-
     app.run();
 }
 
@@ -181,6 +175,11 @@ fn setup(
     // Camera
     commands.spawn(Camera3dBundle {
         transform: Transform::from_xyz(0.0, 2.5, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+        // Unfortunately, MSAA and HDR are not supported simultaneously under WebGL.
+        // Since this example uses HDR, we must disable MSAA for WASM builds, at least
+        // until WebGPU is ready and no longer behind a feature flag in Web browsers.
+        #[cfg(target_arch = "wasm32")]
+        msaa: Msaa::Off,
         ..default()
     });
 
@@ -189,12 +188,14 @@ fn setup(
         font: asset_server.load("fonts/FiraMono-Medium.ttf"),
         font_size: 18.0,
         color: Color::BLACK,
+        ..default()
     };
 
     let label_text_style = TextStyle {
         font: asset_server.load("fonts/FiraMono-Medium.ttf"),
         font_size: 25.0,
         color: Color::ORANGE,
+        ..default()
     };
 
     commands.spawn(
@@ -0,0 +1,115 @@
+//! Projects decals onto the surfaces of a scene, without modifying their meshes.
+//!
+//! Decals are drawn on top of the depth prepass, which currently doesn't work on `WebGL`.
+
+use bevy::{core_pipeline::prepass::DepthPrepass, prelude::*};
+use std::f32::consts::PI;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(rotate_decals)
+        .run();
+}
+
+#[derive(Component)]
+struct Rotates;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut decal_materials: ResMut<Assets<DecalMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    // ground
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(10.0).into()),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+        ..default()
+    });
+    // a few boxes and a sphere for the decals to wrap around
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+        material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
+        transform: Transform::from_xyz(-1.0, 0.5, 0.0),
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(
+            shape::Icosphere {
+                radius: 0.5,
+                subdivisions: 5,
+            }
+            .try_into()
+            .unwrap(),
+        ),
+        material: materials.add(Color::rgb(0.6, 0.7, 0.8).into()),
+        transform: Transform::from_xyz(1.0, 0.5, 0.0),
+        ..default()
+    });
+
+    // a road marking painted on the ground, slightly glossier than the grass below it
+    commands.spawn(DecalBundle {
+        material: decal_materials.add(DecalMaterial {
+            base_color: Color::rgba(1.0, 1.0, 1.0, 0.9),
+            perceptual_roughness: 0.3,
+            ..default()
+        }),
+        transform: Transform::from_xyz(0.0, 0.0, 2.5).with_scale(Vec3::new(6.0, 0.5, 0.3)),
+        ..default()
+    });
+
+    // a logo projected across the edge of the box and the ground, rotating
+    commands.spawn((
+        DecalBundle {
+            material: decal_materials.add(DecalMaterial {
+                base_color_texture: Some(asset_server.load("branding/icon.png")),
+                ..default()
+            }),
+            transform: Transform::from_xyz(-1.0, 1.0, 0.5).with_scale(Vec3::new(1.5, 2.0, 1.5)),
+            ..default()
+        },
+        Rotates,
+    ));
+
+    // an unlit red splat projected sideways onto the sphere
+    commands.spawn(DecalBundle {
+        material: decal_materials.add(DecalMaterial {
+            base_color: Color::rgb(0.6, 0.0, 0.0),
+            unlit: true,
+            ..default()
+        }),
+        transform: Transform::from_xyz(1.0, 0.5, 0.5)
+            .with_rotation(Quat::from_rotation_x(PI / 2.0))
+            .with_scale(Vec3::new(0.4, 0.5, 0.4)),
+        ..default()
+    });
+
+    // light
+    commands.spawn(PointLightBundle {
+        point_light: PointLight {
+            intensity: 1500.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+    // camera
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(-3.0, 4.5, 7.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        // Decals are projected onto the surfaces written to the depth prepass
+        DepthPrepass,
+    ));
+}
+
+fn rotate_decals(time: Res<Time>, mut query: Query<&mut Transform, With<Rotates>>) {
+    for mut transform in &mut query {
+        transform.rotate_y(time.delta_seconds() * 0.5);
+    }
+}
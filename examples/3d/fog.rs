@@ -143,6 +143,7 @@ fn setup_instructions(mut commands: Commands, asset_server: Res<AssetServer>) {
             font: asset_server.load("fonts/FiraMono-Medium.ttf"),
             font_size: 15.0,
             color: Color::WHITE,
+            ..default()
         },
     )
     .with_style(Style {
@@ -14,8 +14,6 @@ use bevy::{
 
 fn main() {
     App::new()
-        // Disable MSAA by default
-        .insert_resource(Msaa::Off)
         .add_plugins(DefaultPlugins)
         .add_startup_system(setup)
         .add_system(toggle_fxaa)
@@ -100,12 +98,14 @@ fn setup(
             },
             transform: Transform::from_xyz(0.7, 0.7, 1.0)
                 .looking_at(Vec3::new(0.0, 0.3, 0.0), Vec3::Y),
+            // Disable MSAA by default
+            msaa: Msaa::Off,
             ..default()
         })
         .insert(Fxaa::default());
 }
 
-fn toggle_fxaa(keys: Res<Input<KeyCode>>, mut query: Query<&mut Fxaa>, mut msaa: ResMut<Msaa>) {
+fn toggle_fxaa(keys: Res<Input<KeyCode>>, mut query: Query<(&mut Fxaa, &mut Msaa)>) {
     let set_no_aa = keys.just_pressed(KeyCode::Key1);
     let set_msaa = keys.just_pressed(KeyCode::Key2);
     let set_fxaa = keys.just_pressed(KeyCode::Key3);
@@ -116,7 +116,7 @@ fn toggle_fxaa(keys: Res<Input<KeyCode>>, mut query: Query<&mut Fxaa>, mut msaa:
     let fxaa_extreme = keys.just_pressed(KeyCode::Key0);
     let set_fxaa = set_fxaa | fxaa_low | fxaa_med | fxaa_high | fxaa_ultra | fxaa_extreme;
 
-    for mut fxaa in &mut query {
+    for (mut fxaa, mut msaa) in &mut query {
         if set_msaa {
             fxaa.enabled = false;
             *msaa = Msaa::Sample4;
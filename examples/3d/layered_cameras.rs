@@ -0,0 +1,79 @@
+//! Draws a 2D HUD camera over a 3D camera rendering to the same window.
+//!
+//! The 3D camera uses HDR while the HUD camera doesn't, so they don't share their intermediate
+//! textures: the HUD camera clears to a transparent color, and blends its output over the window.
+
+use bevy::{
+    core_pipeline::clear_color::ClearColorConfig,
+    prelude::*,
+    render::{
+        camera::CameraOutputMode,
+        render_resource::{BlendState, LoadOp},
+    },
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(rotate)
+        .run();
+}
+
+#[derive(Component)]
+struct Rotates;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+            material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
+            ..default()
+        },
+        Rotates,
+    ));
+    commands.spawn(PointLightBundle {
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+    commands.spawn(Camera3dBundle {
+        camera: Camera {
+            hdr: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    // The HUD camera renders after the 3D camera, as it has a higher order
+    commands.spawn(Camera2dBundle {
+        camera: Camera {
+            order: 1,
+            output_mode: CameraOutputMode::Write {
+                blend_state: Some(BlendState::ALPHA_BLENDING),
+                color_attachment_load_op: LoadOp::Load,
+            },
+            ..default()
+        },
+        camera_2d: Camera2d {
+            clear_color: ClearColorConfig::Custom(Color::NONE),
+        },
+        ..default()
+    });
+    commands.spawn(SpriteBundle {
+        texture: asset_server.load("branding/icon.png"),
+        transform: Transform::from_xyz(-250.0, 150.0, 0.0).with_scale(Vec3::splat(0.3)),
+        ..default()
+    });
+}
+
+fn rotate(time: Res<Time>, mut query: Query<&mut Transform, With<Rotates>>) {
+    for mut transform in &mut query {
+        transform.rotate_y(time.delta_seconds());
+    }
+}
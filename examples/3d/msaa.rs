@@ -8,7 +8,6 @@ use bevy::prelude::*;
 
 fn main() {
     App::new()
-        .insert_resource(Msaa::default())
         .add_plugins(DefaultPlugins)
         .add_startup_system(setup)
         .add_system(cycle_msaa)
@@ -42,7 +41,8 @@ fn setup(
     });
 }
 
-fn cycle_msaa(input: Res<Input<KeyCode>>, mut msaa: ResMut<Msaa>) {
+fn cycle_msaa(input: Res<Input<KeyCode>>, mut cameras: Query<&mut Msaa>) {
+    let mut msaa = cameras.single_mut();
     if input.just_pressed(KeyCode::Key1) {
         info!("Not using MSAA");
         *msaa = Msaa::Off;
@@ -0,0 +1,96 @@
+//! Spawns GPU simulated particle effects, one loaded from a `.particles.ron` asset
+//! and two created in code, one of them drawing its particles as meshes.
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(move_emitter)
+        .run();
+}
+
+#[derive(Component)]
+struct Orbit;
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut effects: ResMut<Assets<ParticleEffect>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    // An effect loaded from a file, which can be edited without recompiling
+    commands.spawn(ParticleEffectBundle {
+        effect: asset_server.load("particles/fountain.particles.ron"),
+        ..default()
+    });
+
+    // An effect created in code, leaving a trail behind its moving emitter
+    let color_over_lifetime = Gradient::linear(Color::YELLOW, Color::rgba(1.0, 0.0, 0.0, 0.0))
+        .with_key(0.3, Color::ORANGE_RED);
+    commands.spawn((
+        ParticleEffectBundle {
+            effect: effects.add(ParticleEffect {
+                capacity: 2048,
+                spawn_rate: 500.0,
+                lifetime: ValueRange::new(0.5, 1.0),
+                shape: EmitterShape::Sphere { radius: 0.1 },
+                initial_speed: ValueRange::new(0.2, 0.6),
+                acceleration: Vec3::new(0.0, 1.0, 0.0),
+                color_over_lifetime,
+                size_over_lifetime: Gradient::linear(0.1, 0.02),
+                blend_mode: ParticleBlendMode::Additive,
+                ..default()
+            }),
+            transform: Transform::from_xyz(2.0, 0.5, 0.0),
+            ..default()
+        },
+        Orbit,
+    ));
+
+    // An effect drawing each particle as a cube instead of a camera facing quad
+    commands.spawn((
+        ParticleEffectBundle {
+            effect: effects.add(ParticleEffect {
+                capacity: 256,
+                spawn_rate: 40.0,
+                lifetime: ValueRange::new(1.0, 1.5),
+                shape: EmitterShape::Cone {
+                    angle: 0.4,
+                    radius: 0.1,
+                },
+                initial_speed: ValueRange::new(3.0, 4.0),
+                acceleration: Vec3::new(0.0, -9.8, 0.0),
+                color_over_lifetime: Gradient::linear(Color::CYAN, Color::rgba(0.0, 0.0, 1.0, 0.0)),
+                size_over_lifetime: Gradient::constant(0.08),
+                ..default()
+            }),
+            transform: Transform::from_xyz(-2.5, 0.0, -1.0),
+            ..default()
+        },
+        ParticleMesh(meshes.add(shape::Cube { size: 1.0 }.into())),
+    ));
+
+    // ground
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(10.0).into()),
+        material: materials.add(Color::DARK_GRAY.into()),
+        ..default()
+    });
+
+    // camera
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(-4.0, 3.0, 6.0)
+            .looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y),
+        ..default()
+    });
+}
+
+fn move_emitter(time: Res<Time>, mut emitters: Query<&mut Transform, With<Orbit>>) {
+    let t = time.elapsed_seconds();
+    for mut transform in &mut emitters {
+        transform.translation = Vec3::new(2.0 * t.cos(), 0.5, 2.0 * t.sin());
+    }
+}
@@ -81,6 +81,7 @@ fn setup(
                 font: asset_server.load("fonts/FiraMono-Medium.ttf"),
                 font_size: 36.0,
                 color: Color::WHITE,
+                ..default()
             },
         )
         .with_style(Style {
@@ -101,6 +102,7 @@ fn setup(
                 font: asset_server.load("fonts/FiraMono-Medium.ttf"),
                 font_size: 36.0,
                 color: Color::WHITE,
+                ..default()
             },
         ),
         style: Style {
@@ -126,6 +128,7 @@ fn setup(
                 font: asset_server.load("fonts/FiraMono-Medium.ttf"),
                 font_size: 36.0,
                 color: Color::RED,
+                ..default()
             },
         )
         .with_style(Style {
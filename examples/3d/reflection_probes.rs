@@ -0,0 +1,188 @@
+//! Lights the inside of a room with a reflection probe, while the outside is lit by the
+//! environment map of the camera.
+//!
+//! Reflection probes currently don't work on `WebGL`.
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{
+        Extent3d, TextureDimension, TextureFormat, TextureViewDescriptor, TextureViewDimension,
+    },
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(orbit_camera)
+        .add_system(toggle_parallax_correction)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+) {
+    let wall_material = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.8, 0.75, 0.7),
+        perceptual_roughness: 0.8,
+        ..default()
+    });
+    let mirror_material = materials.add(StandardMaterial {
+        base_color: Color::WHITE,
+        metallic: 1.0,
+        perceptual_roughness: 0.1,
+        ..default()
+    });
+
+    // a room, open towards the camera
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(20.0).into()),
+        material: wall_material.clone(),
+        ..default()
+    });
+    for transform in [
+        Transform::from_xyz(0.0, 2.0, -4.0).with_scale(Vec3::new(8.0, 4.0, 0.2)),
+        Transform::from_xyz(-4.0, 2.0, 0.0).with_scale(Vec3::new(0.2, 4.0, 8.0)),
+        Transform::from_xyz(0.0, 4.0, 0.0).with_scale(Vec3::new(8.0, 0.2, 8.0)),
+    ] {
+        commands.spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+            material: wall_material.clone(),
+            transform,
+            ..default()
+        });
+    }
+
+    // shiny spheres inside and outside of the room
+    let sphere = meshes.add(
+        shape::UVSphere {
+            radius: 0.8,
+            ..default()
+        }
+        .into(),
+    );
+    commands.spawn(PbrBundle {
+        mesh: sphere.clone(),
+        material: mirror_material.clone(),
+        transform: Transform::from_xyz(-1.0, 0.8, -1.0),
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: sphere,
+        material: mirror_material,
+        transform: Transform::from_xyz(6.0, 0.8, 3.0),
+        ..default()
+    });
+
+    // the reflection probe covering the room, with a warm cubemap standing in for a capture of it
+    let room_cubemap = images.add(room_cubemap());
+    commands.spawn(ReflectionProbeBundle {
+        reflection_probe: ReflectionProbe {
+            diffuse_map: room_cubemap.clone(),
+            specular_map: room_cubemap,
+            ..default()
+        },
+        transform: Transform::from_xyz(0.0, 2.0, 0.0).with_scale(Vec3::new(8.0, 4.0, 8.0)),
+        ..default()
+    });
+
+    // light
+    commands.spawn(PointLightBundle {
+        point_light: PointLight {
+            intensity: 800.0,
+            ..default()
+        },
+        transform: Transform::from_xyz(0.0, 3.5, 0.0),
+        ..default()
+    });
+
+    // camera
+    commands.spawn((
+        Camera3dBundle {
+            transform: Transform::from_xyz(8.0, 4.0, 10.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        EnvironmentMapLight {
+            diffuse_map: asset_server.load("environment_maps/pisa_diffuse_rgb9e5_zstd.ktx2"),
+            specular_map: asset_server.load("environment_maps/pisa_specular_rgb9e5_zstd.ktx2"),
+        },
+    ));
+
+    commands.spawn(
+        TextBundle::from_section(
+            "Press Space to toggle the parallax correction of the reflection probe",
+            TextStyle {
+                font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                font_size: 15.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        }),
+    );
+}
+
+/// Creates a cubemap with a differently tinted face for each side of the room.
+fn room_cubemap() -> Image {
+    const SIZE: u32 = 16;
+    // +X, -X, +Y, -Y, +Z, -Z
+    let faces = [
+        [230, 160, 100],
+        [200, 120, 80],
+        [255, 240, 200],
+        [120, 90, 60],
+        [90, 110, 160],
+        [220, 180, 120],
+    ];
+    let data = faces
+        .iter()
+        .flat_map(|&[r, g, b]| [r, g, b, 255].repeat((SIZE * SIZE) as usize))
+        .collect();
+
+    let mut image = Image::new(
+        Extent3d {
+            width: SIZE,
+            height: SIZE,
+            depth_or_array_layers: 6,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    );
+    image.texture_view_descriptor = Some(TextureViewDescriptor {
+        dimension: Some(TextureViewDimension::Cube),
+        ..default()
+    });
+    image
+}
+
+fn orbit_camera(time: Res<Time>, mut cameras: Query<&mut Transform, With<Camera>>) {
+    for mut transform in &mut cameras {
+        let angle = time.elapsed_seconds() * 0.2;
+        *transform = Transform::from_xyz(angle.cos() * 12.0 + 2.0, 4.0, angle.sin() * 6.0 + 8.0)
+            .looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y);
+    }
+}
+
+fn toggle_parallax_correction(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut probes: Query<&mut ReflectionProbe>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        for mut probe in &mut probes {
+            probe.parallax_correction = !probe.parallax_correction;
+        }
+    }
+}
@@ -0,0 +1,140 @@
+//! Reflects the scene in a glossy floor with screen space reflections.
+//!
+//! Screen space reflections rely on the prepass, which currently doesn't work on `WebGL`.
+
+use bevy::{
+    core_pipeline::prepass::{DepthPrepass, NormalPrepass},
+    pbr::ScreenSpaceReflectionsSettings,
+    prelude::*,
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(rotate_shapes)
+        .add_system(toggle_screen_space_reflections)
+        .run();
+}
+
+#[derive(Component)]
+struct Rotates;
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    // a glossy floor
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(20.0).into()),
+        material: materials.add(StandardMaterial {
+            base_color: Color::rgb(0.1, 0.1, 0.12),
+            perceptual_roughness: 0.1,
+            metallic: 0.5,
+            ..default()
+        }),
+        ..default()
+    });
+
+    // colorful shapes for the floor to reflect
+    let cube = meshes.add(Mesh::from(shape::Cube { size: 1.0 }));
+    for (i, color) in [Color::RED, Color::GREEN, Color::BLUE, Color::YELLOW]
+        .into_iter()
+        .enumerate()
+    {
+        commands.spawn((
+            PbrBundle {
+                mesh: cube.clone(),
+                material: materials.add(StandardMaterial {
+                    base_color: color,
+                    emissive: color * 0.2,
+                    ..default()
+                }),
+                transform: Transform::from_xyz(i as f32 * 2.0 - 3.0, 0.8, 0.0),
+                ..default()
+            },
+            Rotates,
+        ));
+    }
+
+    // light
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    // camera
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                hdr: true,
+                ..default()
+            },
+            transform: Transform::from_xyz(0.0, 2.5, 8.0)
+                .looking_at(Vec3::new(0.0, 0.5, 0.0), Vec3::Y),
+            ..default()
+        },
+        // Reflections are marched through the depth and normals of the prepass
+        DepthPrepass,
+        NormalPrepass,
+        ScreenSpaceReflectionsSettings::default(),
+        // Reflections that leave the screen fall back to the environment map
+        EnvironmentMapLight {
+            diffuse_map: asset_server.load("environment_maps/pisa_diffuse_rgb9e5_zstd.ktx2"),
+            specular_map: asset_server.load("environment_maps/pisa_specular_rgb9e5_zstd.ktx2"),
+        },
+    ));
+
+    commands.spawn(
+        TextBundle::from_section(
+            "Press Space to toggle screen space reflections",
+            TextStyle {
+                font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                font_size: 15.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        }),
+    );
+}
+
+fn rotate_shapes(time: Res<Time>, mut query: Query<&mut Transform, With<Rotates>>) {
+    for mut transform in &mut query {
+        transform.rotate_y(time.delta_seconds() * 0.5);
+    }
+}
+
+fn toggle_screen_space_reflections(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    cameras: Query<(Entity, Option<&ScreenSpaceReflectionsSettings>), With<Camera>>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        for (entity, settings) in &cameras {
+            if settings.is_some() {
+                commands
+                    .entity(entity)
+                    .remove::<ScreenSpaceReflectionsSettings>();
+            } else {
+                commands
+                    .entity(entity)
+                    .insert(ScreenSpaceReflectionsSettings::default());
+            }
+        }
+    }
+}
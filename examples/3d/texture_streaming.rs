@@ -0,0 +1,128 @@
+//! Streams the mip levels of textures in and out of GPU memory as the camera moves.
+//!
+//! Each mip level of the texture has its own color, so the resident mips of each plane show
+//! through: planes close to the camera get their detailed mips, while far away planes are left
+//! with the initial ones.
+
+use bevy::{
+    prelude::*,
+    render::{
+        render_resource::{Extent3d, TextureDimension, TextureFormat},
+        texture::{TextureStreaming, TextureStreamingBudget, TextureStreamingPlugin},
+    },
+};
+
+const TEXTURE_SIZE: u32 = 1024;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(TextureStreamingPlugin)
+        .insert_resource(TextureStreamingBudget {
+            max_bytes: 32 * 1024 * 1024,
+            initial_size: 32,
+            ..default()
+        })
+        .add_startup_system(setup)
+        .add_systems((move_camera, update_text))
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    asset_server: Res<AssetServer>,
+) {
+    let plane = meshes.add(shape::Plane::from_size(4.0).into());
+    for x in -5..5 {
+        for z in -10..10 {
+            // Each plane has its own texture, so that they are streamed independently
+            let material = materials.add(StandardMaterial {
+                base_color_texture: Some(images.add(mip_colored_image())),
+                unlit: true,
+                ..default()
+            });
+            commands.spawn(PbrBundle {
+                mesh: plane.clone(),
+                material,
+                transform: Transform::from_xyz(x as f32 * 5.0, 0.0, z as f32 * 5.0),
+                ..default()
+            });
+        }
+    }
+
+    commands.spawn(Camera3dBundle::default());
+
+    commands.spawn(
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                font_size: 20.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        }),
+    );
+}
+
+/// Creates a texture with a full mip chain, filling each mip level with its own color.
+fn mip_colored_image() -> Image {
+    let colors: [[u8; 4]; 8] = [
+        [255, 255, 255, 255],
+        [255, 0, 0, 255],
+        [255, 128, 0, 255],
+        [255, 255, 0, 255],
+        [0, 255, 0, 255],
+        [0, 255, 255, 255],
+        [0, 0, 255, 255],
+        [128, 0, 255, 255],
+    ];
+    let mip_level_count = TEXTURE_SIZE.ilog2() + 1;
+    let mut data = Vec::new();
+    for mip_level in 0..mip_level_count {
+        let size = (TEXTURE_SIZE >> mip_level) as usize;
+        let color = colors[(mip_level as usize).min(colors.len() - 1)];
+        data.extend(color.iter().copied().cycle().take(size * size * 4));
+    }
+
+    let mut image = Image { data, ..default() };
+    image.texture_descriptor.dimension = TextureDimension::D2;
+    image.texture_descriptor.format = TextureFormat::Rgba8UnormSrgb;
+    image.texture_descriptor.size = Extent3d {
+        width: TEXTURE_SIZE,
+        height: TEXTURE_SIZE,
+        depth_or_array_layers: 1,
+    };
+    image.texture_descriptor.mip_level_count = mip_level_count;
+    image
+}
+
+fn move_camera(time: Res<Time>, mut cameras: Query<&mut Transform, With<Camera>>) {
+    let t = time.elapsed_seconds() * 0.3;
+    for mut transform in &mut cameras {
+        *transform =
+            Transform::from_xyz(t.sin() * 20.0, 3.0 + t.cos().abs() * 10.0, t.cos() * 40.0)
+                .looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+fn update_text(streaming: Res<TextureStreaming>, mut texts: Query<&mut Text>) {
+    for mut text in &mut texts {
+        text.sections[0].value = format!(
+            "streamed textures: {:.1} MiB",
+            streaming.used_bytes() as f64 / (1024.0 * 1024.0)
+        );
+    }
+}
@@ -68,6 +68,7 @@ fn setup(
                 font: asset_server.load("fonts/FiraMono-Medium.ttf"),
                 font_size: 18.0,
                 color: Color::WHITE,
+                ..default()
             },
         )
         .with_style(Style {
@@ -269,6 +270,7 @@ fn setup_image_viewer_scene(
                     font: asset_server.load("fonts/FiraMono-Medium.ttf"),
                     font_size: 36.0,
                     color: Color::BLACK,
+                    ..default()
                 },
             )
             .with_text_alignment(TextAlignment::Center)
@@ -0,0 +1,139 @@
+//! Casts shafts of light through volumetric fog, from a directional light shining between
+//! pillars.
+//!
+//! Volumetric fog currently doesn't work on `WebGL`.
+
+use bevy::{core_pipeline::prepass::DepthPrepass, prelude::*};
+
+fn main() {
+    App::new()
+        .insert_resource(AmbientLight {
+            color: Color::rgb(0.4, 0.5, 0.7),
+            brightness: 0.05,
+        })
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(rotate_light)
+        .add_system(adjust_density)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    let stone = materials.add(StandardMaterial {
+        base_color: Color::rgb(0.6, 0.55, 0.5),
+        perceptual_roughness: 0.9,
+        ..default()
+    });
+
+    // ground
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(40.0).into()),
+        material: stone.clone(),
+        ..default()
+    });
+
+    // rows of pillars, with a roof casting the light into shafts between them
+    let pillar = meshes.add(Mesh::from(shape::Box::new(0.8, 6.0, 0.8)));
+    for x in -3..=3 {
+        for z in [-2.5, 2.5] {
+            commands.spawn(PbrBundle {
+                mesh: pillar.clone(),
+                material: stone.clone(),
+                transform: Transform::from_xyz(x as f32 * 2.5, 3.0, z),
+                ..default()
+            });
+        }
+    }
+    for x in -3..3 {
+        commands.spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Box::new(1.5, 0.4, 6.0))),
+            material: stone.clone(),
+            transform: Transform::from_xyz(x as f32 * 2.5 + 1.25, 6.2, 0.0),
+            ..default()
+        });
+    }
+
+    // the sun, lighting the fog
+    commands.spawn((
+        DirectionalLightBundle {
+            directional_light: DirectionalLight {
+                illuminance: 50_000.0,
+                shadows_enabled: true,
+                ..default()
+            },
+            transform: Transform::from_xyz(2.0, 6.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+            ..default()
+        },
+        VolumetricLight,
+    ));
+
+    // camera
+    commands.spawn((
+        Camera3dBundle {
+            camera: Camera {
+                hdr: true,
+                ..default()
+            },
+            transform: Transform::from_xyz(-6.0, 2.0, 12.0)
+                .looking_at(Vec3::new(0.0, 2.5, 0.0), Vec3::Y),
+            ..default()
+        },
+        // The fog is composited over the scene using its depth
+        DepthPrepass,
+        VolumetricFogSettings {
+            max_distance: 40.0,
+            ..default()
+        },
+    ));
+
+    commands.spawn(
+        TextBundle::from_section(
+            "Press Up/Down to change the density of the fog",
+            TextStyle {
+                font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                font_size: 15.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..default()
+            },
+            ..default()
+        }),
+    );
+}
+
+fn rotate_light(time: Res<Time>, mut lights: Query<&mut Transform, With<DirectionalLight>>) {
+    for mut transform in &mut lights {
+        let angle = time.elapsed_seconds() * 0.2;
+        *transform = Transform::from_xyz(angle.cos() * 3.0, 6.0, angle.sin() * 3.0 + 1.0)
+            .looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+fn adjust_density(
+    time: Res<Time>,
+    keyboard_input: Res<Input<KeyCode>>,
+    mut settings: Query<&mut VolumetricFogSettings>,
+) {
+    let change = if keyboard_input.pressed(KeyCode::Up) {
+        1.0
+    } else if keyboard_input.pressed(KeyCode::Down) {
+        -1.0
+    } else {
+        return;
+    };
+    for mut settings in &mut settings {
+        settings.density = (settings.density + change * 0.05 * time.delta_seconds()).max(0.0);
+    }
+}
@@ -1,21 +1,20 @@
 //! Showcases wireframe rendering.
+//!
+//! Press space to toggle the wireframes of all meshes.
 
 use bevy::{
-    pbr::wireframe::{Wireframe, WireframeConfig, WireframePlugin},
+    pbr::wireframe::{NoWireframe, Wireframe, WireframeColor, WireframeConfig, WireframePlugin},
     prelude::*,
-    render::{render_resource::WgpuFeatures, settings::WgpuSettings, RenderPlugin},
 };
 
 fn main() {
     App::new()
-        .add_plugins(DefaultPlugins.set(RenderPlugin {
-            wgpu_settings: WgpuSettings {
-                features: WgpuFeatures::POLYGON_MODE_LINE,
-                ..default()
-            },
-        }))
+        // Wireframes are drawn with lines where the `POLYGON_MODE_LINE` feature is supported,
+        // and with a shader fallback elsewhere
+        .add_plugins(DefaultPlugins)
         .add_plugin(WireframePlugin)
         .add_startup_system(setup)
+        .add_system(toggle_global_wireframe)
         .run();
 }
 
@@ -28,23 +27,44 @@ fn setup(
 ) {
     // To draw the wireframe on all entities, set this to 'true'
     wireframe_config.global = false;
+    // The color of the wireframes of entities without a `WireframeColor`
+    wireframe_config.default_color = Color::WHITE;
     // plane
-    commands.spawn(PbrBundle {
-        mesh: meshes.add(shape::Plane::from_size(5.0).into()),
-        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
-        ..default()
-    });
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(shape::Plane::from_size(5.0).into()),
+            material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+            ..default()
+        },
+        // This disables wireframe drawing on this entity, even when it is enabled globally
+        NoWireframe,
+    ));
     // cube
     commands.spawn((
         PbrBundle {
             mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
             material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
-            transform: Transform::from_xyz(0.0, 0.5, 0.0),
+            transform: Transform::from_xyz(-1.0, 0.5, 0.0),
             ..default()
         },
         // This enables wireframe drawing on this entity
         Wireframe,
     ));
+    // sphere
+    commands.spawn((
+        PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::UVSphere {
+                radius: 0.5,
+                ..default()
+            })),
+            material: materials.add(Color::rgb(0.6, 0.7, 0.8).into()),
+            transform: Transform::from_xyz(1.0, 0.5, 0.0),
+            ..default()
+        },
+        Wireframe,
+        // This overrides the color of the wireframe of this entity
+        WireframeColor { color: Color::RED },
+    ));
     // light
     commands.spawn(PointLightBundle {
         transform: Transform::from_xyz(4.0, 8.0, 4.0),
@@ -56,3 +76,12 @@ fn setup(
         ..default()
     });
 }
+
+fn toggle_global_wireframe(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut wireframe_config: ResMut<WireframeConfig>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        wireframe_config.global = !wireframe_config.global;
+    }
+}
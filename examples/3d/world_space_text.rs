@@ -0,0 +1,186 @@
+//! Draws name tags above characters and damage numbers with [`Text3dBundle`]s, which are laid out
+//! in the 3d world, face the camera and are hidden behind the walls in front of them.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_systems((
+            orbit_camera,
+            walk,
+            spawn_damage_numbers,
+            float_damage_numbers,
+        ))
+        .run();
+}
+
+/// A character walking in a circle.
+#[derive(Component)]
+struct Walk {
+    radius: f32,
+    speed: f32,
+}
+
+/// A damage number floating up and fading out.
+#[derive(Component)]
+struct DamageNumber {
+    timer: Timer,
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(20.0).into()),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+        ..default()
+    });
+    // The name tags are hidden behind the wall when the characters walk behind it
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Box::new(4.0, 3.0, 0.3).into()),
+        material: materials.add(Color::rgb(0.7, 0.6, 0.5).into()),
+        transform: Transform::from_xyz(0.0, 1.5, 0.0),
+        ..default()
+    });
+    commands.spawn(DirectionalLightBundle {
+        transform: Transform::from_xyz(4.0, 8.0, 4.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    for (i, (name, color)) in [("Alice", Color::ORANGE), ("Bob", Color::CYAN)]
+        .into_iter()
+        .enumerate()
+    {
+        commands
+            .spawn((
+                PbrBundle {
+                    mesh: meshes.add(shape::Capsule::default().into()),
+                    material: materials.add(color.into()),
+                    ..default()
+                },
+                Walk {
+                    radius: 3.0 + i as f32 * 1.5,
+                    speed: 0.6 - i as f32 * 0.2,
+                },
+            ))
+            .with_children(|parent| {
+                // The name tag stays upright when the camera looks down on it
+                parent.spawn(Text3dBundle {
+                    text: Text::from_section(
+                        name,
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 40.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    )
+                    .with_alignment(TextAlignment::Center),
+                    billboard: Billboard::LockAxis(Vec3::Y),
+                    transform: Transform::from_xyz(0.0, 1.4, 0.0),
+                    ..default()
+                });
+            });
+    }
+
+    // A sign on the wall, oriented by its transform, staying sharp when the camera gets close
+    commands.spawn(Text3dBundle {
+        text: Text::from_section(
+            "No loitering",
+            TextStyle {
+                font,
+                font_size: 60.0,
+                color: Color::BLACK,
+                ..default()
+            },
+        ),
+        text_3d: Text3d {
+            atlas_mode: GlyphAtlasMode::Msdf,
+            ..default()
+        },
+        billboard: Billboard::Off,
+        transform: Transform::from_xyz(0.0, 2.0, 0.16),
+        ..default()
+    });
+
+    commands.spawn(Camera3dBundle::default());
+}
+
+fn orbit_camera(time: Res<Time>, mut cameras: Query<&mut Transform, With<Camera>>) {
+    let angle = time.elapsed_seconds() * 0.2;
+    for mut transform in &mut cameras {
+        *transform = Transform::from_xyz(10.0 * angle.sin(), 5.0, 10.0 * angle.cos())
+            .looking_at(Vec3::new(0.0, 1.0, 0.0), Vec3::Y);
+    }
+}
+
+fn walk(time: Res<Time>, mut characters: Query<(&mut Transform, &Walk)>) {
+    for (mut transform, walk) in &mut characters {
+        let angle = time.elapsed_seconds() * walk.speed;
+        transform.translation =
+            Vec3::new(walk.radius * angle.cos(), 1.0, walk.radius * angle.sin());
+    }
+}
+
+/// Spawns a damage number above a character every second.
+fn spawn_damage_numbers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut timer: Local<Option<Timer>>,
+    asset_server: Res<AssetServer>,
+    characters: Query<&GlobalTransform, With<Walk>>,
+) {
+    let timer = timer.get_or_insert_with(|| Timer::from_seconds(1.0, TimerMode::Repeating));
+    if !timer.tick(time.delta()).just_finished() {
+        return;
+    }
+    let seed = time.elapsed_seconds_wrapped() * 7.0;
+    for (i, character) in characters.iter().enumerate() {
+        let damage = (seed * (i + 3) as f32).sin().abs() * 99.0 + 1.0;
+        let offset = Vec3::new((seed + i as f32).sin() * 0.5, 2.0, 0.0);
+        commands.spawn((
+            Text3dBundle {
+                text: Text::from_section(
+                    format!("{damage:.0}"),
+                    TextStyle {
+                        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                        font_size: 50.0,
+                        color: Color::RED,
+                        ..default()
+                    },
+                ),
+                transform: Transform::from_translation(character.translation() + offset),
+                ..default()
+            },
+            DamageNumber {
+                timer: Timer::from_seconds(1.5, TimerMode::Once),
+            },
+        ));
+    }
+}
+
+fn float_damage_numbers(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut numbers: Query<(Entity, &mut Transform, &mut Text, &mut DamageNumber)>,
+) {
+    for (entity, mut transform, mut text, mut number) in &mut numbers {
+        if number.timer.tick(time.delta()).finished() {
+            commands.entity(entity).despawn();
+            continue;
+        }
+        transform.translation.y += time.delta_seconds();
+        let fade = 1.0 - number.timer.percent();
+        text.sections[0].style.color.set_a(fade);
+        transform.scale = Vec3::splat(1.0 + 0.3 * (fade * TAU).sin().abs());
+    }
+}
@@ -0,0 +1,135 @@
+//! Renders a scene without any window, then reads the rendered image back from the GPU and
+//! saves it to a file.
+//!
+//! This runs in environments without a display, like CI containers, for rendering regression
+//! tests or generating thumbnails.
+
+use bevy::{
+    app::{AppExit, ScheduleRunnerPlugin},
+    prelude::*,
+    render::{
+        camera::RenderTarget,
+        render_resource::{
+            Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages,
+        },
+        texture::ImageReadbacks,
+    },
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+
+/// The number of frames rendered before the image is saved.
+const FRAMES: u32 = 10;
+
+fn main() {
+    App::new()
+        .add_plugins(
+            DefaultPlugins
+                .set(WindowPlugin {
+                    primary_window: None,
+                    // Without windows, the app would exit right away
+                    exit_condition: ExitCondition::DontExit,
+                    close_when_requested: false,
+                })
+                .disable::<WinitPlugin>(),
+        )
+        // Winit usually runs the app, so loop without it
+        .add_plugin(ScheduleRunnerPlugin)
+        .add_startup_system(setup)
+        .add_system(save_image)
+        .run();
+}
+
+#[derive(Resource)]
+struct RenderedImage(Handle<Image>);
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut images: ResMut<Assets<Image>>,
+    mut readbacks: ResMut<ImageReadbacks>,
+) {
+    let size = Extent3d {
+        width: 512,
+        height: 512,
+        ..default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Rgba8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            // Reading the image back copies from it
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::COPY_SRC
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let image = images.add(image);
+    readbacks.read_back(&image);
+
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(5.0).into()),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Cube { size: 1.0 })),
+        material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
+        transform: Transform::from_xyz(0.0, 0.5, 0.0),
+        ..default()
+    });
+    commands.spawn(PointLightBundle {
+        point_light: PointLight {
+            intensity: 1500.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+    commands.spawn(Camera3dBundle {
+        camera: Camera {
+            target: RenderTarget::Image(image.clone()),
+            ..default()
+        },
+        transform: Transform::from_xyz(-2.0, 2.5, 5.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    commands.insert_resource(RenderedImage(image));
+}
+
+fn save_image(
+    rendered_image: Res<RenderedImage>,
+    readbacks: Res<ImageReadbacks>,
+    mut frames: Local<u32>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    *frames += 1;
+    if *frames < FRAMES {
+        return;
+    }
+    let image = match readbacks.get(&rendered_image.0) {
+        Some(image) => image,
+        None => return,
+    };
+
+    let path = "headless_render.png";
+    match image.clone().try_into_dynamic() {
+        Ok(dynamic_image) => match dynamic_image.to_rgba8().save(path) {
+            Ok(()) => info!("Saved the rendered image to {path}"),
+            Err(error) => error!("Failed to save the rendered image: {error}"),
+        },
+        Err(error) => error!("Failed to convert the rendered image: {error:?}"),
+    }
+    app_exit.send(AppExit);
+}
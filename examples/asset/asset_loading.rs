@@ -37,8 +37,8 @@ fn setup(
     }
 
     // You can load all assets in a folder like this. They will be loaded in parallel without
-    // blocking
-    let _scenes: Vec<HandleUntyped> = asset_server.load_folder("models/monkey").unwrap();
+    // blocking, and the `LoadedFolder` will hold their handles once they're all loaded
+    let _scenes: Handle<LoadedFolder> = asset_server.load_folder("models/monkey");
 
     // Then any asset in the folder can be accessed like this:
     let monkey_handle = asset_server.get_handle("models/monkey/Monkey.gltf#Mesh0/Primitive0");
@@ -0,0 +1,34 @@
+//! Embeds an asset in the executable, and loads it from an asset source added for the
+//! `embedded://` scheme.
+//! The other assets are still loaded from the asset folder.
+
+use bevy::{asset::EmbeddedAssetIo, prelude::*};
+
+fn main() {
+    // the bytes of the asset are included in the executable when it's built
+    let embedded = EmbeddedAssetIo::default();
+    embedded.insert(
+        "branding/icon.png",
+        include_bytes!("../../assets/branding/icon.png").as_slice(),
+    );
+
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_asset_source("embedded", embedded)
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+    commands.spawn(SpriteBundle {
+        texture: asset_server.load("embedded://branding/icon.png"),
+        transform: Transform::from_xyz(-150.0, 0.0, 0.0),
+        ..default()
+    });
+    commands.spawn(SpriteBundle {
+        texture: asset_server.load("branding/icon.png"),
+        transform: Transform::from_xyz(150.0, 0.0, 0.0),
+        ..default()
+    });
+}
@@ -2,7 +2,7 @@
 //! running. This lets you immediately see the results of your changes without restarting the game.
 //! This example illustrates hot reloading mesh changes.
 
-use bevy::prelude::*;
+use bevy::{asset::ReloadCause, prelude::*};
 
 fn main() {
     App::new()
@@ -12,6 +12,7 @@ fn main() {
             ..default()
         }))
         .add_startup_system(setup)
+        .add_system(log_reloads)
         .run();
 }
 
@@ -20,7 +21,8 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     let scene_handle = asset_server.load("models/monkey/Monkey.gltf#Scene0");
 
     // Any changes to the mesh will be reloaded automatically! Try making a change to Monkey.gltf.
-    // You should see the changes immediately show up in your app.
+    // You should see the changes immediately show up in your app. The assets depending on a
+    // changed asset are reloaded too, and the spawned scenes are updated.
 
     // mesh
     commands.spawn(SceneBundle {
@@ -38,3 +40,16 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
         ..default()
     });
 }
+
+fn log_reloads(mut reloaded_events: EventReader<AssetReloaded>) {
+    for reloaded in reloaded_events.iter() {
+        match &reloaded.cause {
+            ReloadCause::Changed => info!("reloaded {:?}", reloaded.path.path()),
+            ReloadCause::Dependency(dependency) => info!(
+                "reloaded {:?} after {:?} changed",
+                reloaded.path.path(),
+                dependency.path()
+            ),
+        }
+    }
+}
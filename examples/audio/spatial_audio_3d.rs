@@ -1,5 +1,5 @@
 //! This example illustrates how to load and play an audio file, and control where the sounds seems to come from.
-use bevy::prelude::*;
+use bevy::{audio::SpatialPanning, prelude::*};
 
 fn main() {
     App::new()
@@ -28,7 +28,9 @@ fn setup(
         gap,
         Vec3::ZERO,
     ));
-    commands.insert_resource(AudioController(handle));
+
+    // listener
+    commands.spawn((TransformBundle::default(), AudioListener));
 
     // left ear
     commands.spawn(PbrBundle {
@@ -57,6 +59,8 @@ fn setup(
             transform: Transform::from_xyz(0.0, 0.0, 0.0),
             ..default()
         },
+        // the sound follows the transform of the emitter
+        SpatialAudioSource::new(handle).with_panning(SpatialPanning::Binaural),
         Emitter,
     ));
 
@@ -80,19 +84,8 @@ fn setup(
 #[derive(Component)]
 struct Emitter;
 
-#[derive(Resource)]
-struct AudioController(Handle<SpatialAudioSink>);
-
-fn update_positions(
-    audio_sinks: Res<Assets<SpatialAudioSink>>,
-    music_controller: Res<AudioController>,
-    time: Res<Time>,
-    mut emitter: Query<&mut Transform, With<Emitter>>,
-) {
-    if let Some(sink) = audio_sinks.get(&music_controller.0) {
-        let mut emitter_transform = emitter.single_mut();
-        emitter_transform.translation.x = time.elapsed_seconds().sin() * 3.0;
-        emitter_transform.translation.z = time.elapsed_seconds().cos() * 3.0;
-        sink.set_emitter_position(emitter_transform.translation);
-    }
+fn update_positions(time: Res<Time>, mut emitter: Query<&mut Transform, With<Emitter>>) {
+    let mut emitter_transform = emitter.single_mut();
+    emitter_transform.translation.x = time.elapsed_seconds().sin() * 3.0;
+    emitter_transform.translation.z = time.elapsed_seconds().cos() * 3.0;
 }
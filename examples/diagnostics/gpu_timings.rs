@@ -0,0 +1,76 @@
+//! Measures the GPU time spent in each node of the render graph, and displays it on screen.
+//!
+//! This requires timestamp queries, which are not supported on every backend.
+
+use bevy::{
+    diagnostic::LogDiagnosticsPlugin, prelude::*, render::diagnostic::GpuTimingDiagnosticsPlugin,
+    ui::widget::GpuTimingsText,
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        // Adds a GPU time diagnostic for every node of the render graph
+        .add_plugin(GpuTimingDiagnosticsPlugin)
+        // The GPU timings are regular diagnostics, so they are logged to the console as well
+        .add_plugin(LogDiagnosticsPlugin::default())
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    asset_server: Res<AssetServer>,
+) {
+    // a few shadow casting cubes, to give the shadow pass some work
+    for x in -2..=2 {
+        commands.spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 0.5 })),
+            material: materials.add(Color::rgb(0.8, 0.7, 0.6).into()),
+            transform: Transform::from_xyz(x as f32, 0.25, 0.0),
+            ..default()
+        });
+    }
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(8.0).into()),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+        ..default()
+    });
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(2.0, 4.0, 3.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 4.0, 6.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+
+    // The text is filled with the GPU timings, using the style of its first section
+    commands.spawn((
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+                font_size: 16.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(5.0),
+                left: Val::Px(5.0),
+                ..default()
+            },
+            ..default()
+        }),
+        GpuTimingsText,
+    ));
+}
@@ -75,6 +75,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                         font: asset_server.load("fonts/FiraSans-Bold.ttf"),
                         font_size: 80.0,
                         color: Color::ORANGE,
+                        ..default()
                     },
                 ),
                 AppleCount,
@@ -86,6 +87,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                         font: asset_server.load("fonts/FiraSans-Bold.ttf"),
                         font_size: 80.0,
                         color: Color::ORANGE,
+                        ..default()
                     },
                 ),
                 OrangeCount,
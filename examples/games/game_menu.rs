@@ -194,6 +194,7 @@ mod game {
                                     font: font.clone(),
                                     font_size: 80.0,
                                     color: TEXT_COLOR,
+                                    ..default()
                                 },
                             )
                             .with_style(Style {
@@ -209,6 +210,7 @@ mod game {
                                         font: font.clone(),
                                         font_size: 60.0,
                                         color: Color::BLUE,
+                                        ..default()
                                     },
                                 ),
                                 TextSection::new(
@@ -217,6 +219,7 @@ mod game {
                                         font: font.clone(),
                                         font_size: 60.0,
                                         color: TEXT_COLOR,
+                                        ..default()
                                     },
                                 ),
                                 TextSection::new(
@@ -225,6 +228,7 @@ mod game {
                                         font: font.clone(),
                                         font_size: 60.0,
                                         color: Color::GREEN,
+                                        ..default()
                                     },
                                 ),
                             ])
@@ -414,6 +418,7 @@ mod menu {
             font: font.clone(),
             font_size: 40.0,
             color: TEXT_COLOR,
+            ..default()
         };
 
         commands
@@ -449,6 +454,7 @@ mod menu {
                                     font: font.clone(),
                                     font_size: 80.0,
                                     color: TEXT_COLOR,
+                                    ..default()
                                 },
                             )
                             .with_style(Style {
@@ -538,6 +544,7 @@ mod menu {
             font: asset_server.load("fonts/FiraSans-Bold.ttf"),
             font_size: 40.0,
             color: TEXT_COLOR,
+            ..default()
         };
 
         commands
@@ -606,6 +613,7 @@ mod menu {
             font: asset_server.load("fonts/FiraSans-Bold.ttf"),
             font_size: 40.0,
             color: TEXT_COLOR,
+            ..default()
         };
 
         commands
@@ -708,6 +716,7 @@ mod menu {
             font: asset_server.load("fonts/FiraSans-Bold.ttf"),
             font_size: 40.0,
             color: TEXT_COLOR,
+            ..default()
         };
 
         commands
@@ -31,6 +31,7 @@ fn setup_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font: font.clone_weak(),
                     font_size: 20.0,
                     color: Color::WHITE,
+                    ..default()
                 },
             },
             TextSection {
@@ -39,6 +40,7 @@ fn setup_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font: font.clone_weak(),
                     font_size: 30.0,
                     color: Color::WHITE,
+                    ..default()
                 },
             },
             TextSection {
@@ -47,6 +49,7 @@ fn setup_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font: font.clone_weak(),
                     font_size: 20.0,
                     color: Color::WHITE,
+                    ..default()
                 },
             },
             TextSection {
@@ -55,6 +58,7 @@ fn setup_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font: font.clone_weak(),
                     font_size: 30.0,
                     color: Color::WHITE,
+                    ..default()
                 },
             },
             TextSection {
@@ -63,6 +67,7 @@ fn setup_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font: font.clone_weak(),
                     font_size: 18.0,
                     color: Color::WHITE,
+                    ..default()
                 },
             },
             TextSection {
@@ -71,6 +76,7 @@ fn setup_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font,
                     font_size: 25.0,
                     color: Color::WHITE,
+                    ..default()
                 },
             },
         ])
@@ -92,6 +98,7 @@ fn setup_scene(mut commands: Commands, asset_server: Res<AssetServer>) {
                 font: asset_server.load("fonts/FiraMono-Medium.ttf"),
                 font_size: 100.0,
                 color: Color::WHITE,
+                ..default()
             },
         ),
         ..default()
@@ -1,8 +1,5 @@
 //! This example illustrates loading scenes from files.
-use std::fs::File;
-use std::io::Write;
-
-use bevy::{prelude::*, tasks::IoTaskPool, utils::Duration};
+use bevy::{prelude::*, utils::Duration};
 
 fn main() {
     App::new()
@@ -108,18 +105,19 @@ fn save_scene_system(world: &mut World) {
     // Showing the scene in the console
     info!("{}", serialized_scene);
 
-    // Writing the scene to a new file. Using a task to avoid calling the filesystem APIs in a system
-    // as they are blocking
+    // Writing the scene to a new file in the asset folder, with the saver of the scenes. The file is
+    // written in the background, to avoid calling the filesystem APIs in a system as they are
+    // blocking
     // This can't work in WASM as there is no filesystem access
-    #[cfg(not(target_arch = "wasm32"))]
-    IoTaskPool::get()
-        .spawn(async move {
-            // Write the scene RON data to file
-            File::create(format!("assets/{NEW_SCENE_FILE_PATH}"))
-                .and_then(|mut file| file.write(serialized_scene.as_bytes()))
-                .expect("Error while writing scene to file");
-        })
-        .detach();
+    let handle = world.resource_mut::<Assets<DynamicScene>>().add(scene);
+    world
+        .resource::<AssetServer>()
+        .save(
+            world.resource::<Assets<DynamicScene>>(),
+            &handle,
+            NEW_SCENE_FILE_PATH,
+        )
+        .expect("Error while saving the scene");
 }
 
 // This is only necessary for the info message in the UI. See examples/ui/text.rs for a standalone
@@ -133,6 +131,7 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
                 font: asset_server.load("fonts/FiraSans-Bold.ttf"),
                 font_size: 50.0,
                 color: Color::WHITE,
+                ..default()
             },
         )
         .with_style(Style {
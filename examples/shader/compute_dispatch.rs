@@ -0,0 +1,71 @@
+//! Runs a compute shader on a buffer and reads the results back on the CPU,
+//! without writing a render graph node.
+
+use bevy::{
+    prelude::*,
+    render::{
+        compute::{ComputeBuffer, ComputeDispatch, ComputeReadbacks},
+        render_resource::ComputePipelineDescriptor,
+    },
+};
+
+const VALUE_COUNT: u32 = 256;
+const WORKGROUP_SIZE: u32 = 64;
+
+fn main() {
+    let mut app = App::new();
+    app.add_plugins(DefaultPlugins).add_system(print_results);
+
+    let shader = app
+        .world
+        .resource::<AssetServer>()
+        .load("shaders/collatz.wgsl");
+    let pipeline = app
+        .world
+        .resource_mut::<Assets<ComputePipelineDescriptor>>()
+        .add(ComputePipelineDescriptor {
+            label: Some("collatz_pipeline".into()),
+            // Leaving the layout empty derives it from the shader
+            layout: Vec::new(),
+            push_constant_ranges: Vec::new(),
+            shader,
+            shader_defs: Vec::new(),
+            entry_point: "collatz".into(),
+        });
+
+    let values: Vec<u32> = (1..=VALUE_COUNT).collect();
+    let buffer = app
+        .world
+        .resource_mut::<Assets<ComputeBuffer>>()
+        .add(ComputeBuffer::from_value(&values).with_readback());
+
+    ComputeDispatch::new(pipeline)
+        .with_buffer(0, buffer.clone())
+        .with_workgroups(VALUE_COUNT / WORKGROUP_SIZE, 1, 1)
+        .once()
+        .add_to_graph(&mut app, "collatz");
+
+    app.insert_resource(CollatzBuffer(buffer)).run();
+}
+
+#[derive(Resource)]
+struct CollatzBuffer(Handle<ComputeBuffer>);
+
+fn print_results(
+    buffer: Res<CollatzBuffer>,
+    readbacks: Res<ComputeReadbacks>,
+    mut printed: Local<bool>,
+) {
+    if *printed {
+        return;
+    }
+    // The buffer is read back every frame, so it holds the input values until the dispatch ran
+    if let Some(steps) = readbacks.read::<Vec<u32>>(&buffer.0) {
+        if steps.iter().copied().ne(1..=VALUE_COUNT) {
+            for (value, steps) in (1..).zip(&steps) {
+                info!("{value} reaches 1 in {steps} steps");
+            }
+            *printed = true;
+        }
+    }
+}
@@ -1,15 +1,11 @@
 //! A shader that reads a mesh's custom vertex attribute.
 
 use bevy::{
-    pbr::{MaterialPipeline, MaterialPipelineKey},
     prelude::*,
     reflect::TypeUuid,
     render::{
-        mesh::{MeshVertexAttribute, MeshVertexBufferLayout},
-        render_resource::{
-            AsBindGroup, RenderPipelineDescriptor, ShaderRef, SpecializedMeshPipelineError,
-            VertexFormat,
-        },
+        mesh::{MeshVertexAttribute, VertexAttributeDescriptor},
+        render_resource::{AsBindGroup, ShaderRef, VertexFormat},
     },
 };
 
@@ -72,17 +68,8 @@ impl Material for CustomMaterial {
         "shaders/custom_vertex_attribute.wgsl".into()
     }
 
-    fn specialize(
-        _pipeline: &MaterialPipeline<Self>,
-        descriptor: &mut RenderPipelineDescriptor,
-        layout: &MeshVertexBufferLayout,
-        _key: MaterialPipelineKey<Self>,
-    ) -> Result<(), SpecializedMeshPipelineError> {
-        let vertex_layout = layout.get_layout(&[
-            Mesh::ATTRIBUTE_POSITION.at_shader_location(0),
-            ATTRIBUTE_BLEND_COLOR.at_shader_location(1),
-        ])?;
-        descriptor.vertex.buffers = vec![vertex_layout];
-        Ok(())
+    // The mesh pipeline binds the standard attributes of the mesh, and these on top of them
+    fn custom_vertex_attributes() -> Vec<VertexAttributeDescriptor> {
+        vec![ATTRIBUTE_BLEND_COLOR.at_shader_location(7)]
     }
 }
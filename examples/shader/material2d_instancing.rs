@@ -0,0 +1,67 @@
+//! Draws thousands of 2d meshes sharing a mesh and an instanced [`Material2d`] in a single draw
+//! call, each with its own [`Mesh2dInstanceData`] read in the shader.
+
+use bevy::{
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{AsBindGroup, ShaderRef},
+    sprite::{Material2d, Material2dPlugin, MaterialMesh2dBundle, Mesh2dInstanceData},
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(Material2dPlugin::<BlinkingMaterial>::default())
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<BlinkingMaterial>>,
+) {
+    commands.spawn(Camera2dBundle::default());
+
+    let mesh = meshes.add(shape::Circle::new(6.0).into());
+    let material = materials.add(BlinkingMaterial {
+        color: Color::rgb(0.4, 0.8, 1.0),
+    });
+    for x in -40..40 {
+        for y in -25..25 {
+            let (x, y) = (x as f32, y as f32);
+            commands.spawn((
+                MaterialMesh2dBundle {
+                    mesh: mesh.clone().into(),
+                    material: material.clone(),
+                    transform: Transform::from_xyz(x * 15.0, y * 15.0, 0.0),
+                    ..default()
+                },
+                // The phase and the speed of the blinking of each circle
+                Mesh2dInstanceData(Vec4::new(
+                    (x * 0.3).sin() + y * 0.2,
+                    2.0 + x.abs() * 0.05,
+                    0.0,
+                    0.0,
+                )),
+            ));
+        }
+    }
+}
+
+#[derive(AsBindGroup, TypeUuid, Debug, Clone)]
+#[uuid = "4a8c9a34-f6f4-4a4b-9a63-5d6c2b8f0e21"]
+struct BlinkingMaterial {
+    #[uniform(0)]
+    color: Color,
+}
+
+impl Material2d for BlinkingMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/material2d_instancing.wgsl".into()
+    }
+
+    fn instancing() -> bool {
+        true
+    }
+}
@@ -131,6 +131,7 @@ fn setup(
         font: asset_server.load("fonts/FiraMono-Medium.ttf"),
         font_size: 18.0,
         color: Color::WHITE,
+        ..default()
     };
 
     commands.spawn(
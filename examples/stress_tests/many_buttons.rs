@@ -1,6 +1,7 @@
 use bevy::{
     diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
     prelude::*,
+    ui::UiRenderDiagnosticsPlugin,
     window::{PresentMode, WindowPlugin},
 };
 
@@ -19,6 +20,8 @@ fn main() {
             ..default()
         }))
         .add_plugin(FrameTimeDiagnosticsPlugin::default())
+        // The buttons and their texts are drawn in a few batches, logged with the frame time
+        .add_plugin(UiRenderDiagnosticsPlugin::default())
         .add_plugin(LogDiagnosticsPlugin::default())
         .init_resource::<UiFont>()
         .add_startup_system(setup)
@@ -112,6 +115,7 @@ fn spawn_button(
                     font,
                     font_size: FONT_SIZE,
                     color: Color::rgb(0.2, 0.2, 0.2),
+                    ..default()
                 },
             ));
         });
@@ -1,12 +1,13 @@
 //! Simple benchmark to test rendering many point lights.
 //! Run with `WGPU_SETTINGS_PRIO=webgl2` to restrict to uniform buffers and max 256 lights.
+//! Press C to cycle through the debug visualizations of the light clusters.
 
 use std::f64::consts::PI;
 
 use bevy::{
     diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
     math::{DVec2, DVec3},
-    pbr::{ExtractedPointLight, GlobalLightMeta},
+    pbr::{ClusterDebugVisualization, Clusters, ExtractedPointLight, GlobalLightMeta},
     prelude::*,
     render::{camera::ScalingMode, RenderApp, RenderSet},
     window::{PresentMode, WindowPlugin},
@@ -29,6 +30,7 @@ fn main() {
         .add_startup_system(setup)
         .add_system(move_camera)
         .add_system(print_light_count)
+        .add_system(cycle_cluster_debug_visualization)
         .add_plugin(LogVisibleLights)
         .run();
 }
@@ -139,11 +141,43 @@ fn move_camera(time: Res<Time>, mut camera_query: Query<&mut Transform, With<Cam
 }
 
 // System for printing the number of meshes on every tick of the timer
-fn print_light_count(time: Res<Time>, mut timer: Local<PrintingTimer>, lights: Query<&PointLight>) {
+fn print_light_count(
+    time: Res<Time>,
+    mut timer: Local<PrintingTimer>,
+    lights: Query<&PointLight>,
+    clusters: Query<&Clusters>,
+) {
     timer.0.tick(time.delta());
 
     if timer.0.just_finished() {
         info!("Lights: {}", lights.iter().len(),);
+        for clusters in &clusters {
+            info!(
+                "Clusters: {}, Max lights per cluster: {}",
+                clusters.dimensions(),
+                clusters.max_lights_per_cluster()
+            );
+        }
+    }
+}
+
+fn cycle_cluster_debug_visualization(
+    mut commands: Commands,
+    keyboard_input: Res<Input<KeyCode>>,
+    cameras: Query<(Entity, Option<&ClusterDebugVisualization>), With<Camera>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::C) {
+        return;
+    }
+    for (entity, visualization) in &cameras {
+        let next = match visualization.copied().unwrap_or_default() {
+            ClusterDebugVisualization::None => ClusterDebugVisualization::ZSlices,
+            ClusterDebugVisualization::ZSlices => ClusterDebugVisualization::LightCount,
+            ClusterDebugVisualization::LightCount => ClusterDebugVisualization::Coherency,
+            ClusterDebugVisualization::Coherency => ClusterDebugVisualization::None,
+        };
+        info!("Cluster debug visualization: {next:?}");
+        commands.entity(entity).insert(next);
     }
 }
 
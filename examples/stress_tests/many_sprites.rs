@@ -13,6 +13,7 @@
 use bevy::{
     diagnostic::{FrameTimeDiagnosticsPlugin, LogDiagnosticsPlugin},
     prelude::*,
+    sprite::SpriteBatchDiagnosticsPlugin,
     window::{PresentMode, WindowPlugin},
 };
 
@@ -40,6 +41,7 @@ fn main() {
             }),
             ..default()
         }))
+        .add_plugin(SpriteBatchDiagnosticsPlugin)
         .add_startup_system(setup)
         .add_system(print_sprite_count)
         .add_system(move_camera.after(print_sprite_count))
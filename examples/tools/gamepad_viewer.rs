@@ -325,6 +325,7 @@ fn setup_sticks(
                     font_size: 16.,
                     color: TEXT_COLOR,
                     font: font.clone(),
+                    ..default()
                 };
                 parent.spawn((
                     Text2dBundle {
@@ -408,6 +409,7 @@ fn setup_triggers(
                                 font: font.clone(),
                                 font_size: 16.,
                                 color: TEXT_COLOR,
+                                ..default()
                             },
                         ),
                         ..default()
@@ -434,6 +436,7 @@ fn setup_connected(mut commands: Commands, font: Res<FontHandle>) {
         color: TEXT_COLOR,
         font_size: 30.,
         font: font.clone(),
+        ..default()
     };
     commands.spawn((
         TextBundle::from_sections([
@@ -0,0 +1,73 @@
+//! Draws nodes with rounded corners and borders of different widths and colors on each edge.
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+
+    let radii = [
+        BorderRadius::DEFAULT,
+        BorderRadius::all(Val::Px(10.0)),
+        BorderRadius::all(Val::Percent(25.0)),
+        BorderRadius::MAX,
+        BorderRadius::vertical(Val::Px(30.0), Val::Px(0.0)),
+        BorderRadius {
+            top_left: Val::Px(40.0),
+            bottom_right: Val::Px(40.0),
+            ..default()
+        },
+    ];
+    let borders = [
+        UiRect::all(Val::Px(0.0)),
+        UiRect::all(Val::Px(4.0)),
+        UiRect::all(Val::Px(10.0)),
+        UiRect::new(Val::Px(2.0), Val::Px(8.0), Val::Px(4.0), Val::Px(12.0)),
+    ];
+    let border_colors = [
+        BorderColor::all(Color::WHITE),
+        BorderColor {
+            left: Color::RED,
+            top: Color::YELLOW,
+            right: Color::GREEN,
+            bottom: Color::BLUE,
+        },
+    ];
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_wrap: FlexWrap::Wrap,
+                align_content: AlignContent::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(20.0)),
+                padding: UiRect::all(Val::Px(20.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (index, border) in borders.iter().enumerate() {
+                for radius in radii {
+                    parent.spawn(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(120.0), Val::Px(80.0)),
+                            border: *border,
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.25, 0.35, 0.6).into(),
+                        border_color: border_colors[index % border_colors.len()],
+                        border_radius: radius,
+                        ..default()
+                    });
+                }
+            }
+        });
+}
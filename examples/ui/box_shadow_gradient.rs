@@ -0,0 +1,94 @@
+//! Draws cards with [`BoxShadow`]s of different offsets, blurs and spreads, filled with linear
+//! and radial [`BackgroundGradient`]s.
+
+use bevy::prelude::*;
+use std::f32::consts::PI;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+
+    let cards = [
+        (
+            BoxShadow {
+                y_offset: Val::Px(4.0),
+                blur_radius: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundGradient::linear(
+                PI,
+                [
+                    ColorStop::new(Color::rgb(0.3, 0.6, 1.0), 0.0),
+                    ColorStop::new(Color::rgb(0.1, 0.2, 0.6), 1.0),
+                ],
+            ),
+        ),
+        (
+            BoxShadow {
+                color: Color::rgba(0.0, 0.0, 0.0, 0.8),
+                x_offset: Val::Px(10.0),
+                y_offset: Val::Px(10.0),
+                ..default()
+            },
+            BackgroundGradient::linear(
+                PI / 2.0,
+                [
+                    ColorStop::new(Color::rgb(1.0, 0.3, 0.3), 0.0),
+                    ColorStop::new(Color::rgb(1.0, 0.8, 0.2), 0.5),
+                    ColorStop::new(Color::rgb(0.3, 0.9, 0.4), 1.0),
+                ],
+            ),
+        ),
+        (
+            BoxShadow {
+                color: Color::rgba(0.6, 0.3, 1.0, 0.8),
+                spread_radius: Val::Px(6.0),
+                blur_radius: Val::Px(24.0),
+                ..default()
+            },
+            BackgroundGradient::radial(
+                Vec2::new(0.3, 0.3),
+                [
+                    ColorStop::new(Color::WHITE, 0.0),
+                    ColorStop::new(Color::rgb(0.6, 0.3, 1.0), 0.6),
+                    ColorStop::new(Color::rgb(0.2, 0.1, 0.4), 1.0),
+                ],
+            ),
+        ),
+    ];
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(60.0)),
+                ..default()
+            },
+            background_color: Color::rgb(0.85, 0.85, 0.9).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            for (shadow, gradient) in cards {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(200.0), Val::Px(260.0)),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(20.0)),
+                    shadow,
+                    gradient,
+                ));
+            }
+        });
+}
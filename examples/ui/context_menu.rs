@@ -0,0 +1,215 @@
+//! Opens a context menu where the files of a list are right clicked, with the [`PointerClick`]
+//! events, and closes it when a pointer is pressed outside of it, checking the [`UiPointerMap`].
+
+use bevy::{
+    prelude::*,
+    ui::{FocusPolicy, PointerClick, PointerDown},
+};
+
+const FILES: [&str; 4] = ["notes.txt", "save_01.dat", "screenshot.png", "config.ron"];
+const ACTIONS: [&str; 3] = ["Open", "Rename", "Delete"];
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_systems((
+            open_menu_system,
+            menu_action_system,
+            close_menu_system,
+            highlight_system,
+        ))
+        .run();
+}
+
+/// A file of the list, by its index in [`FILES`].
+#[derive(Component)]
+struct File(usize);
+
+/// The context menu, with the file it was opened on.
+#[derive(Component, Default)]
+struct ContextMenu {
+    file: Option<usize>,
+}
+
+/// An item of the context menu, by its index in [`ACTIONS`].
+#[derive(Component)]
+struct MenuAction(usize);
+
+/// The text showing the last action.
+#[derive(Component)]
+struct Status;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    let text_style = TextStyle {
+        font,
+        font_size: 24.0,
+        color: Color::WHITE,
+        ..default()
+    };
+    commands.spawn(Camera2dBundle::default());
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(8.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section("Right click a file", text_style.clone()),
+                Status,
+            ));
+            for (index, file) in FILES.iter().enumerate() {
+                parent
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(300.0), Val::Px(40.0)),
+                                align_items: AlignItems::Center,
+                                padding: UiRect::left(Val::Px(10.0)),
+                                ..default()
+                            },
+                            background_color: Color::rgb(0.2, 0.2, 0.25).into(),
+                            focus_policy: FocusPolicy::Block,
+                            ..default()
+                        },
+                        File(index),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(*file, text_style.clone()));
+                    });
+            }
+        });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    display: Display::None,
+                    position_type: PositionType::Absolute,
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                },
+                background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                focus_policy: FocusPolicy::Block,
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            BoxShadow {
+                y_offset: Val::Px(4.0),
+                blur_radius: Val::Px(8.0),
+                ..default()
+            },
+            ContextMenu::default(),
+        ))
+        .with_children(|parent| {
+            for (index, action) in ACTIONS.iter().enumerate() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(160.0), Val::Px(36.0)),
+                                align_items: AlignItems::Center,
+                                padding: UiRect::left(Val::Px(10.0)),
+                                ..default()
+                            },
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        MenuAction(index),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(*action, text_style.clone()));
+                    });
+            }
+        });
+}
+
+/// Opens the menu at the pointer when a file is right clicked.
+fn open_menu_system(
+    mut click_events: EventReader<PointerClick>,
+    file_query: Query<&File>,
+    mut menu_query: Query<(&mut Style, &mut ContextMenu)>,
+) {
+    for event in click_events.iter() {
+        let Ok(file) = file_query.get(event.target) else {
+            continue;
+        };
+        if event.button != PointerButton::Secondary {
+            continue;
+        }
+        let (mut style, mut menu) = menu_query.single_mut();
+        style.display = Display::Flex;
+        style.position.left = Val::Px(event.position.x);
+        style.position.top = Val::Px(event.position.y);
+        menu.file = Some(file.0);
+    }
+}
+
+fn menu_action_system(
+    action_query: Query<(&Interaction, &MenuAction), Changed<Interaction>>,
+    mut menu_query: Query<(&mut Style, &ContextMenu)>,
+    mut status_query: Query<&mut Text, With<Status>>,
+) {
+    for (interaction, action) in &action_query {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        let (mut style, menu) = menu_query.single_mut();
+        if let Some(file) = menu.file {
+            status_query.single_mut().sections[0].value =
+                format!("{} {}", ACTIONS[action.0], FILES[file]);
+        }
+        style.display = Display::None;
+    }
+}
+
+/// Closes the menu when a pointer is pressed outside of it.
+fn close_menu_system(
+    mut down_events: EventReader<PointerDown>,
+    mouse_button_input: Res<Input<MouseButton>>,
+    pointer_map: Res<UiPointerMap>,
+    mut menu_query: Query<(Entity, &mut Style, &Children), With<ContextMenu>>,
+) {
+    let (menu, mut style, children) = menu_query.single_mut();
+    // The events are only sent for the nodes under the pointer, so the mouse is also checked
+    let mut pointers: Vec<PointerId> = down_events.iter().map(|event| event.pointer).collect();
+    if mouse_button_input.get_just_pressed().next().is_some() {
+        pointers.push(PointerId::Mouse);
+    }
+    for pointer in pointers {
+        let on_menu = pointer_map.is_under(pointer, menu)
+            || children
+                .iter()
+                .any(|child| pointer_map.is_under(pointer, *child));
+        if !on_menu && style.display != Display::None {
+            style.display = Display::None;
+        }
+    }
+}
+
+/// Highlights the items of the menu under a pointer.
+fn highlight_system(
+    pointer_map: Res<UiPointerMap>,
+    mut item_query: Query<(Entity, &mut BackgroundColor), With<MenuAction>>,
+) {
+    for (entity, mut color) in &mut item_query {
+        let hovered_color = if pointer_map.is_hovered(entity) {
+            Color::rgb(0.3, 0.3, 0.45)
+        } else {
+            Color::NONE
+        };
+        if color.0 != hovered_color {
+            color.0 = hovered_color;
+        }
+    }
+}
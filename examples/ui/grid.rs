@@ -0,0 +1,131 @@
+//! Lays out an inventory and a settings panel with [`Display::Grid`], placing the slots of the
+//! inventory in rows and columns and the parts of the panel in named areas.
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        font_size: 24.0,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    // The window is split into a header, a sidebar and the content between them
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                display: Display::Grid,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                padding: UiRect::all(Val::Px(10.0)),
+                gap: Size::all(Val::Px(10.0)),
+                grid_template_columns: vec![GridTrack::Px(250.0), GridTrack::Fr(1.0)],
+                grid_template_rows: vec![GridTrack::Auto, GridTrack::Fr(1.0)],
+                grid_template_areas: vec![
+                    "header header".to_string(),
+                    "settings inventory".to_string(),
+                ],
+                ..default()
+            },
+            background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        grid_area: Some("header".to_string()),
+                        padding: UiRect::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.2, 0.2, 0.3).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section("Inventory", text_style.clone()));
+                });
+
+            // The labels and their values line up in two columns
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_area: Some("settings".to_string()),
+                        padding: UiRect::all(Val::Px(10.0)),
+                        gap: Size::new(Val::Px(10.0), Val::Px(5.0)),
+                        grid_template_columns: vec![GridTrack::Fr(1.0), GridTrack::Auto],
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.15, 0.15, 0.2).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (label, value) in [
+                        ("Volume", "80%"),
+                        ("Resolution", "1280x720"),
+                        ("Fullscreen", "Off"),
+                        ("Language", "English"),
+                    ] {
+                        parent.spawn(TextBundle::from_section(label, text_style.clone()));
+                        parent.spawn(
+                            TextBundle::from_section(
+                                value,
+                                TextStyle {
+                                    color: Color::GRAY,
+                                    ..text_style.clone()
+                                },
+                            )
+                            .with_style(Style {
+                                padding: UiRect::horizontal(Val::Px(5.0)),
+                                ..default()
+                            })
+                            .with_background_color(Color::rgb(0.25, 0.25, 0.3)),
+                        );
+                    }
+                });
+
+            // The slots fill rows of 6 columns, with a large slot spanning 2 rows and 2 columns
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        display: Display::Grid,
+                        grid_area: Some("inventory".to_string()),
+                        padding: UiRect::all(Val::Px(10.0)),
+                        gap: Size::all(Val::Px(5.0)),
+                        grid_template_columns: GridTrack::repeat(6, GridTrack::Fr(1.0)),
+                        grid_auto_rows: GridTrack::Px(80.0),
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.15, 0.15, 0.2).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(NodeBundle {
+                        style: Style {
+                            grid_row: GridPlacement::span(2),
+                            grid_column: GridPlacement::start_span(-3, 2),
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.6, 0.4, 0.2).into(),
+                        ..default()
+                    });
+                    for index in 0..20 {
+                        let color = Color::hsl(index as f32 * 18.0, 0.4, 0.4);
+                        parent.spawn(NodeBundle {
+                            background_color: color.into(),
+                            ..default()
+                        });
+                    }
+                });
+        });
+}
@@ -0,0 +1,207 @@
+//! Opens a confirmation dialog as a [`Modal`], which dims the menu behind it and keeps the mouse,
+//! the keyboard and the gamepads from interacting with the menu until it's closed.
+//!
+//! Press Escape or the East button of a gamepad, or click outside of the dialog, to close it.
+
+use bevy::{
+    prelude::*,
+    ui::{ModalClosed, ModalOpened},
+};
+
+const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
+const HOVERED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.45);
+const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_systems((button_system, dialog_system, modal_events_system))
+        .run();
+}
+
+/// What a button does when clicked.
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    OpenDialog,
+    CloseDialog,
+    Other,
+}
+
+/// The button of the dialog focused when it's opened.
+#[derive(Resource)]
+struct DialogButton(Entity);
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    commands.spawn(Camera2dBundle::default());
+
+    let button = |parent: &mut ChildBuilder, label: &str, action: Action| {
+        parent
+            .spawn((
+                ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(200.0), Val::Px(60.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                    background_color: NORMAL_BUTTON.into(),
+                    ..default()
+                },
+                Focusable::default(),
+                action,
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    label,
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 26.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ));
+            })
+            .id()
+    };
+
+    // The menu, blocked while the dialog is open
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            button(parent, "New game", Action::Other);
+            button(parent, "Load game", Action::Other);
+            button(parent, "Quit", Action::OpenDialog);
+        });
+
+    // The dialog, over the menu
+    let mut dialog_button = None;
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            z_index: ZIndex::Global(10),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            padding: UiRect::all(Val::Px(30.0)),
+                            gap: Size::all(Val::Px(20.0)),
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.1, 0.1, 0.12).into(),
+                        // Shown while the dialog is open
+                        visibility: Visibility::Hidden,
+                        ..default()
+                    },
+                    BorderRadius::all(Val::Px(12.0)),
+                    // Closed by the cancel buttons of the navigation, and by clicking outside
+                    Modal::DEFAULT.closable(),
+                ))
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        "Quit without saving?",
+                        TextStyle {
+                            font: font.clone(),
+                            font_size: 30.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                    parent
+                        .spawn(NodeBundle {
+                            style: Style {
+                                gap: Size::all(Val::Px(10.0)),
+                                ..default()
+                            },
+                            ..default()
+                        })
+                        .with_children(|parent| {
+                            dialog_button = Some(button(parent, "Cancel", Action::CloseDialog));
+                            button(parent, "Quit", Action::CloseDialog);
+                        });
+                });
+        });
+    commands.insert_resource(DialogButton(dialog_button.unwrap()));
+}
+
+fn button_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Clicked => PRESSED_BUTTON,
+            Interaction::Hovered => HOVERED_BUTTON,
+            Interaction::None => NORMAL_BUTTON,
+        }
+        .into();
+    }
+}
+
+/// Opens and closes the dialog with the buttons.
+fn dialog_system(
+    action_query: Query<(&Interaction, &Action), Changed<Interaction>>,
+    mut modal_query: Query<&mut Visibility, With<Modal>>,
+    dialog_button: Res<DialogButton>,
+    mut navigation_focus: ResMut<NavigationFocus>,
+) {
+    let mut open = None;
+    for (interaction, action) in &action_query {
+        if *interaction == Interaction::Clicked {
+            match action {
+                Action::OpenDialog => open = Some(true),
+                Action::CloseDialog => open = Some(false),
+                Action::Other => {}
+            }
+        }
+    }
+    if let Some(open) = open {
+        *modal_query.single_mut() = if open {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if open {
+            // The navigation starts from the safe choice
+            navigation_focus.focus(dialog_button.0);
+        } else {
+            navigation_focus.clear();
+        }
+    }
+}
+
+fn modal_events_system(
+    mut opened_events: EventReader<ModalOpened>,
+    mut closed_events: EventReader<ModalClosed>,
+) {
+    for event in opened_events.iter() {
+        info!("Modal {:?} opened", event.modal);
+    }
+    for event in closed_events.iter() {
+        info!("Modal {:?} closed", event.modal);
+    }
+}
@@ -0,0 +1,91 @@
+//! Fades a whole panel in and out with a [`UiOpacity`], without changing the colors of its
+//! children.
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(fade)
+        .run();
+}
+
+/// The panel fading in and out.
+#[derive(Component)]
+struct Fading;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            background_color: Color::rgb(0.4, 0.4, 1.0).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            padding: UiRect::all(Val::Px(20.0)),
+                            gap: Size::all(Val::Px(10.0)),
+                            border: UiRect::all(Val::Px(4.0)),
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                        border_color: Color::WHITE.into(),
+                        border_radius: BorderRadius::all(Val::Px(10.0)),
+                        ..default()
+                    },
+                    UiOpacity::DEFAULT,
+                    Fading,
+                ))
+                .with_children(|parent| {
+                    parent.spawn(ImageBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(128.0), Val::Px(128.0)),
+                            ..default()
+                        },
+                        image: asset_server.load("branding/icon.png").into(),
+                        ..default()
+                    });
+                    parent.spawn(TextBundle::from_section(
+                        "Fading in and out",
+                        TextStyle {
+                            font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                            font_size: 30.0,
+                            color: Color::WHITE,
+                            ..default()
+                        },
+                    ));
+                    // Opacities multiply: this text is never more than half opaque
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "with its children",
+                            TextStyle {
+                                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                                font_size: 20.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ),
+                        UiOpacity(0.5),
+                    ));
+                });
+        });
+}
+
+fn fade(time: Res<Time>, mut panels: Query<&mut UiOpacity, With<Fading>>) {
+    for mut opacity in &mut panels {
+        opacity.0 = 0.5 + 0.5 * time.elapsed_seconds().sin();
+    }
+}
@@ -0,0 +1,65 @@
+//! Builds a dialog from a string with inline formatting tags, with [`Text::from_markup`], and
+//! updates its named spans in place every frame.
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(update_spans)
+        .run();
+}
+
+const DIALOG: &str = "[b]Guard:[/b] Halt! You have been wandering these halls for \
+[color=yellow][span=time]0[/span][/color] seconds.\n\
+[i]Your torch flickers.[/i] Its flame is [span=flame]steady[/span].\n\
+[size=20][color=gray]Press [[Space] to keep walking.[/color][/size]";
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+
+    // There is no italic font in the assets, so a monospace one is swapped in instead
+    let style = TextMarkupStyle::new(TextStyle {
+        font: asset_server.load("fonts/FiraMono-Medium.ttf"),
+        font_size: 30.0,
+        color: Color::WHITE,
+        ..default()
+    })
+    .with_bold_font(asset_server.load("fonts/FiraSans-Bold.ttf"))
+    .with_italic_font(asset_server.load("fonts/FiraMono-Medium.ttf"));
+    let (text, spans) = Text::from_markup(DIALOG, &style).expect("the dialog markup is valid");
+
+    commands.spawn((
+        TextBundle {
+            text,
+            style: Style {
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(20.0),
+                    bottom: Val::Px(20.0),
+                    ..default()
+                },
+                max_size: Size::width(Val::Px(700.0)),
+                ..default()
+            },
+            ..default()
+        },
+        spans,
+    ));
+}
+
+fn update_spans(time: Res<Time>, mut texts: Query<(&mut Text, &TextSpans)>) {
+    let seconds = time.elapsed_seconds();
+    let (flame, color) = if (seconds * 3.0).sin() > 0.6 {
+        ("flickering", Color::ORANGE_RED)
+    } else {
+        ("steady", Color::ORANGE)
+    };
+    for (mut text, spans) in &mut texts {
+        // Only the sections of the spans are changed, the rest of the dialog is kept as it is
+        spans.set_value(&mut text, "time", &format!("{seconds:.0}"));
+        spans.set_value(&mut text, "flame", flame);
+        spans.set_color(&mut text, "flame", color);
+    }
+}
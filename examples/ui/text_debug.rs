@@ -3,6 +3,7 @@
 use bevy::{
     diagnostic::{Diagnostics, FrameTimeDiagnosticsPlugin},
     prelude::*,
+    text::{BreakLineOn, TextOverflow},
     window::{PresentMode, WindowPlugin},
 };
 
@@ -34,6 +35,7 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
                 font: font.clone(),
                 font_size: 50.0,
                 color: Color::WHITE,
+                ..default()
             },
         )
         .with_style(Style {
@@ -52,6 +54,7 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
                 font: font.clone(),
                 font_size: 50.0,
                 color: Color::rgb(0.8, 0.2, 0.7),
+                ..default()
             },
         )
         .with_text_alignment(TextAlignment::Center)
@@ -77,6 +80,7 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font: font.clone(),
                     font_size: 30.0,
                     color: Color::WHITE,
+                    ..default()
                 },
             ),
             TextSection::new(
@@ -85,12 +89,14 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font: font.clone(),
                     font_size: 30.0,
                     color: Color::RED,
+                    ..default()
                 },
             ),
             TextSection::from_style(TextStyle {
                 font: font.clone(),
                 font_size: 30.0,
                 color: Color::ORANGE_RED,
+                ..default()
             }),
             TextSection::new(
                 " fps, ",
@@ -98,12 +104,14 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font: font.clone(),
                     font_size: 30.0,
                     color: Color::YELLOW,
+                    ..default()
                 },
             ),
             TextSection::from_style(TextStyle {
                 font: font.clone(),
                 font_size: 30.0,
                 color: Color::GREEN,
+                ..default()
             }),
             TextSection::new(
                 " ms/frame",
@@ -111,6 +119,7 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
                     font: font.clone(),
                     font_size: 30.0,
                     color: Color::BLUE,
+                    ..default()
                 },
             ),
         ])
@@ -129,9 +138,10 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
         TextBundle::from_section(
             "This\ntext has\nline breaks and also a set width in the bottom left",
             TextStyle {
-                font,
+                font: font.clone(),
                 font_size: 50.0,
                 color: Color::WHITE,
+                ..default()
             },
         )
         .with_style(Style {
@@ -149,6 +159,61 @@ fn infotext_system(mut commands: Commands, asset_server: Res<AssetServer>) {
             ..default()
         }),
     );
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            "This text isn't wrapped, and ends in an ellipsis when it runs out of its width",
+            TextStyle {
+                font: font.clone(),
+                font_size: 30.0,
+                color: Color::CYAN,
+                ..default()
+            },
+        )
+        .with_linebreak_behaviour(BreakLineOn::NoWrap)
+        .with_overflow(TextOverflow {
+            max_lines: None,
+            ellipsis: true,
+        }),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(250.0),
+                left: Val::Px(15.0),
+                ..default()
+            },
+            max_size: Size::width(Val::Px(400.0)),
+            ..default()
+        },
+        ..default()
+    });
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            "This text is spaced out, and cut off after two lines of the many it would take",
+            TextStyle {
+                font,
+                font_size: 30.0,
+                color: Color::GOLD,
+                letter_spacing: 2.0,
+                word_spacing: 8.0,
+                ..default()
+            },
+        )
+        .with_overflow(TextOverflow {
+            max_lines: Some(2),
+            ellipsis: true,
+        }),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(300.0),
+                left: Val::Px(15.0),
+                ..default()
+            },
+            max_size: Size::width(Val::Px(400.0)),
+            ..default()
+        },
+        ..default()
+    });
 }
 
 fn change_text_system(
@@ -0,0 +1,116 @@
+//! Keeps text readable over a bright, changing background with [`TextOutline`]s,
+//! [`TextShadow`]s and a [`TextBackground`], on both UI and 2d text.
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(ClearColor(Color::rgb(0.9, 0.9, 0.8)))
+        .add_startup_system(setup)
+        .add_system(cycle_clear_color)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    let style = TextStyle {
+        font: font.clone(),
+        font_size: 40.0,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                gap: Size::height(Val::Px(20.0)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(20.0),
+                    top: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("No effect", style.clone()));
+            parent.spawn(TextBundle::from_section(
+                "Outline",
+                TextStyle {
+                    outline: Some(TextOutline {
+                        width: 2.0,
+                        color: Color::BLACK,
+                    }),
+                    ..style.clone()
+                },
+            ));
+            parent.spawn(TextBundle::from_section(
+                "Drop shadow",
+                TextStyle {
+                    shadow: Some(TextShadow::default()),
+                    ..style.clone()
+                },
+            ));
+            parent.spawn(TextBundle::from_section(
+                "Blurred shadow and outline",
+                TextStyle {
+                    outline: Some(TextOutline::default()),
+                    shadow: Some(TextShadow {
+                        offset: Vec2::new(3.0, 4.0),
+                        blur: 4.0,
+                        color: Color::rgba(0.0, 0.0, 0.0, 0.8),
+                    }),
+                    ..style.clone()
+                },
+            ));
+            parent.spawn(TextBundle {
+                text: Text::from_section("Background box", style.clone())
+                    .with_background(TextBackground::default()),
+                ..default()
+            });
+        });
+
+    // The same effects are drawn for 2d text
+    commands.spawn(Text2dBundle {
+        text: Text::from_sections([
+            TextSection::new(
+                "2d text ",
+                TextStyle {
+                    outline: Some(TextOutline {
+                        width: 2.0,
+                        color: Color::MIDNIGHT_BLUE,
+                    }),
+                    ..style.clone()
+                },
+            ),
+            TextSection::new(
+                "with effects",
+                TextStyle {
+                    color: Color::GOLD,
+                    shadow: Some(TextShadow {
+                        blur: 3.0,
+                        ..default()
+                    }),
+                    ..style
+                },
+            ),
+        ])
+        .with_background(TextBackground {
+            color: Color::rgba(0.2, 0.1, 0.3, 0.6),
+            padding: Vec2::new(12.0, 6.0),
+        }),
+        transform: Transform::from_xyz(100.0, -200.0, 0.0),
+        ..default()
+    });
+}
+
+fn cycle_clear_color(time: Res<Time>, mut clear_color: ResMut<ClearColor>) {
+    let t = time.elapsed_seconds();
+    clear_color.0 = Color::hsl((t * 30.0) % 360.0, 0.6, 0.85);
+}
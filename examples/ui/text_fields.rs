@@ -0,0 +1,111 @@
+//! Edits text in [`TextInput`]s, focused by clicking on them or pressing Tab, and shows their
+//! values as they change and once submitted with Enter.
+
+use bevy::{
+    prelude::*,
+    ui::widget::{TextInput, TextInputChanged, TextInputSubmitted},
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(show_changed_values)
+        .add_system(show_submitted_values)
+        .run();
+}
+
+/// The text showing the values of the inputs.
+#[derive(Component)]
+struct Log;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    let text_style = TextStyle {
+        font: font.clone(),
+        font_size: 30.0,
+        color: Color::WHITE,
+        ..default()
+    };
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Column,
+                gap: Size::height(Val::Px(20.0)),
+                position_type: PositionType::Absolute,
+                position: UiRect {
+                    left: Val::Px(20.0),
+                    top: Val::Px(20.0),
+                    ..default()
+                },
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for placeholder in ["Name", "A long description scrolled with the caret"] {
+                // The field scrolls the input when it's longer than the field
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            size: Size::new(Val::Px(400.0), Val::Px(40.0)),
+                            padding: UiRect::horizontal(Val::Px(5.0)),
+                            align_items: AlignItems::Center,
+                            overflow: Overflow::Scroll,
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.15, 0.15, 0.2).into(),
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn(
+                            TextInputBundle::new("", text_style.clone())
+                                .with_placeholder(placeholder)
+                                .with_style(Style {
+                                    flex_shrink: 0.0,
+                                    ..default()
+                                }),
+                        );
+                    });
+            }
+
+            parent.spawn((
+                TextBundle::from_section(
+                    "Type in the inputs",
+                    TextStyle {
+                        font,
+                        font_size: 20.0,
+                        color: Color::GOLD,
+                        ..default()
+                    },
+                ),
+                Log,
+            ));
+        });
+}
+
+fn show_changed_values(
+    mut changed_events: EventReader<TextInputChanged>,
+    mut log: Query<&mut Text, With<Log>>,
+) {
+    for event in changed_events.iter() {
+        log.single_mut().sections[0].value = format!("Typing: {}", event.value);
+    }
+}
+
+fn show_submitted_values(
+    mut submitted_events: EventReader<TextInputSubmitted>,
+    mut inputs: Query<&mut TextInput>,
+    mut log: Query<&mut Text, With<Log>>,
+) {
+    for event in submitted_events.iter() {
+        log.single_mut().sections[0].value = format!("Submitted: {}", event.value);
+        // The input is cleared to type the next value
+        if let Ok(mut input) = inputs.get_mut(event.entity) {
+            input.value.clear();
+        }
+    }
+}
@@ -0,0 +1,150 @@
+//! Animates the characters of a text from its laid out glyphs in [`TextLayoutInfo`], revealing
+//! them one after another in a wave, and finds the character clicked on.
+
+use bevy::{prelude::*, text::PositionedGlyph, ui::widget::text_system};
+
+/// The number of characters revealed each second.
+const CHARACTERS_PER_SECOND: f32 = 20.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(
+            animate_glyphs
+                .in_base_set(CoreSet::PostUpdate)
+                .after(text_system),
+        )
+        .add_system(show_clicked_character)
+        .run();
+}
+
+/// The glyphs of the text as they were laid out, before being animated.
+#[derive(Component, Default)]
+struct LaidOutGlyphs(Vec<PositionedGlyph>);
+
+/// The number of characters of the text revealed so far.
+#[derive(Component, Default)]
+struct Typewriter(f32);
+
+#[derive(Component)]
+struct ClickedCharacter;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.spawn(Camera2dBundle::default());
+
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    commands.spawn((
+        TextBundle::from_section(
+            "Each character of this text is revealed one after another, \
+            and waves up and down. Click on a character to show it below.",
+            TextStyle {
+                font: font.clone(),
+                font_size: 40.0,
+                color: Color::WHITE,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(20.0),
+                top: Val::Px(20.0),
+                ..default()
+            },
+            max_size: Size::width(Val::Px(600.0)),
+            ..default()
+        }),
+        LaidOutGlyphs::default(),
+        Typewriter::default(),
+    ));
+
+    commands.spawn((
+        TextBundle::from_section(
+            "Clicked character:",
+            TextStyle {
+                font,
+                font_size: 30.0,
+                color: Color::GOLD,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                left: Val::Px(20.0),
+                bottom: Val::Px(20.0),
+                ..default()
+            },
+            ..default()
+        }),
+        ClickedCharacter,
+    ));
+}
+
+/// Draws the revealed glyphs moved up and down, from the glyphs laid out last.
+fn animate_glyphs(
+    time: Res<Time>,
+    mut query: Query<(
+        &Text,
+        &mut TextLayoutInfo,
+        &mut LaidOutGlyphs,
+        &mut Typewriter,
+    )>,
+) {
+    for (text, mut text_layout_info, mut laid_out_glyphs, mut typewriter) in &mut query {
+        // The glyphs are only changed by the layout, as they're animated without change detection
+        if text_layout_info.is_changed() {
+            laid_out_glyphs.0 = text_layout_info.glyphs.clone();
+        }
+
+        // Starts over a second after revealing the whole text
+        let text_len = text.sections[0].value.len() as f32;
+        typewriter.0 += time.delta_seconds() * CHARACTERS_PER_SECOND;
+        if typewriter.0 > text_len + CHARACTERS_PER_SECOND {
+            typewriter.0 = 0.0;
+        }
+
+        let elapsed = time.elapsed_seconds();
+        text_layout_info.bypass_change_detection().glyphs = laid_out_glyphs
+            .0
+            .iter()
+            .filter(|glyph| (glyph.byte_index as f32) < typewriter.0)
+            .map(|glyph| {
+                let mut glyph = glyph.clone();
+                glyph.position.y += (elapsed * 6.0 - glyph.position.x * 0.02).sin() * 6.0;
+                glyph
+            })
+            .collect();
+    }
+}
+
+/// Shows the character under the cursor when clicking.
+fn show_clicked_character(
+    mouse_button_input: Res<Input<MouseButton>>,
+    windows: Query<&Window>,
+    animated_text: Query<(&Node, &GlobalTransform, &Text, &TextLayoutInfo), With<Typewriter>>,
+    mut clicked_character: Query<&mut Text, (With<ClickedCharacter>, Without<Typewriter>)>,
+) {
+    if !mouse_button_input.just_pressed(MouseButton::Left) {
+        return;
+    }
+    let window = windows.single();
+    let Some(mut cursor_position) = window.cursor_position() else {
+        return;
+    };
+    // The cursor position is from the bottom of the window, and the UI from its top
+    cursor_position.y = window.height() - cursor_position.y;
+
+    for (node, transform, text, text_layout_info) in &animated_text {
+        // The glyphs are positioned in physical pixels from the top left corner of the text
+        let top_left = transform.translation().truncate() - node.size() / 2.0;
+        let position = (cursor_position - top_left) * window.scale_factor() as f32;
+        if let Some(glyph) = text_layout_info.glyph_at(position) {
+            let section = &text.sections[glyph.section_index];
+            let character = &section.value[glyph.byte_range.clone()];
+            clicked_character.single_mut().sections[0].value =
+                format!("Clicked character: {character}");
+        }
+    }
+}
@@ -178,6 +178,7 @@ fn spawn_nested_text_bundle(
                     font,
                     font_size: 24.0,
                     color: Color::BLACK,
+                    ..default()
                 },
             ));
         });
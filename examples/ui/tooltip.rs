@@ -0,0 +1,130 @@
+//! Shows [`Tooltip`]s when the mouse rests over the buttons of a toolbar, or when they're focused
+//! with the keyboard or a gamepad: simple texts, and a panel with a title and a description.
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .run();
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: font.clone(),
+        font_size,
+        color,
+        ..default()
+    };
+    commands.spawn(Camera2dBundle::default());
+
+    // The contents of the tooltips are hidden root nodes, drawn over the rest of the UI
+    let tooltip_style = Style {
+        display: Display::None,
+        position_type: PositionType::Absolute,
+        flex_direction: FlexDirection::Column,
+        padding: UiRect::all(Val::Px(8.0)),
+        max_size: Size::width(Val::Px(240.0)),
+        ..default()
+    };
+    let simple_tooltip = |commands: &mut Commands, text: &str| {
+        commands
+            .spawn((
+                NodeBundle {
+                    style: tooltip_style.clone(),
+                    background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                    z_index: ZIndex::Global(10),
+                    ..default()
+                },
+                BorderRadius::all(Val::Px(4.0)),
+            ))
+            .with_children(|parent| {
+                parent.spawn(TextBundle::from_section(
+                    text,
+                    text_style(18.0, Color::WHITE),
+                ));
+            })
+            .id()
+    };
+    let tooltips = [
+        Tooltip::new(simple_tooltip(&mut commands, "New file")),
+        Tooltip::new(simple_tooltip(&mut commands, "Open a file"))
+            .with_placement(TooltipPlacement::Above),
+        Tooltip::new(simple_tooltip(&mut commands, "Save"))
+            .with_placement(TooltipPlacement::Below)
+            .with_delay(0.0),
+    ];
+    let panel = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    gap: Size::all(Val::Px(6.0)),
+                    ..tooltip_style.clone()
+                },
+                background_color: Color::rgb(0.15, 0.15, 0.25).into(),
+                z_index: ZIndex::Global(10),
+                ..default()
+            },
+            BorderRadius::all(Val::Px(6.0)),
+            BoxShadow {
+                y_offset: Val::Px(2.0),
+                blur_radius: Val::Px(6.0),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Export",
+                text_style(20.0, Color::WHITE),
+            ));
+            parent.spawn(TextBundle::from_section(
+                "Writes the document to another format, keeping the original file.",
+                text_style(16.0, Color::rgb(0.7, 0.7, 0.8)),
+            ));
+        })
+        .id();
+    let tooltips = tooltips
+        .into_iter()
+        .chain([Tooltip::new(panel).with_placement(TooltipPlacement::Left)]);
+
+    // The toolbar, on the left edge so the tooltips on the left are moved to the right
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(10.0)),
+                padding: UiRect::left(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (label, tooltip) in ["New", "Open", "Save", "Export"].into_iter().zip(tooltips) {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(100.0), Val::Px(50.0)),
+                                justify_content: JustifyContent::Center,
+                                align_items: AlignItems::Center,
+                                ..default()
+                            },
+                            background_color: Color::rgb(0.25, 0.25, 0.3).into(),
+                            ..default()
+                        },
+                        Focusable::default(),
+                        tooltip,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            label,
+                            text_style(22.0, Color::WHITE),
+                        ));
+                    });
+            }
+        });
+}
@@ -46,6 +46,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                             font_size: 40.0,
                             // Alpha channel of the color controls transparency.
                             color: Color::rgba(1.0, 1.0, 1.0, 0.2),
+                            ..default()
                         },
                     ));
                 });
@@ -71,6 +72,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
                             font_size: 40.0,
                             // Alpha channel of the color controls transparency.
                             color: Color::rgba(1.0, 1.0, 1.0, 0.2),
+                            ..default()
                         },
                     ));
                 });
@@ -0,0 +1,114 @@
+//! Draws the [`UiDebugOverlay`] over a small layout: the outlines of the nodes, their margins
+//! and paddings, and the axes of the flex containers.
+//!
+//! Press Space to show or hide the overlay, and P to log the layout computed for the tree.
+
+use bevy::{prelude::*, ui::FlexSurface};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .insert_resource(UiDebugOverlay {
+            enabled: true,
+            ..default()
+        })
+        .add_startup_system(setup)
+        .add_system(keyboard_system)
+        .run();
+}
+
+/// The root node of the layout, logged when P is pressed.
+#[derive(Resource)]
+struct Root(Entity);
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let text_style = TextStyle {
+        font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+        font_size: 24.0,
+        color: Color::WHITE,
+        ..default()
+    };
+    commands.spawn(Camera2dBundle::default());
+
+    let root = commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(20.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Space: toggle the overlay, P: log the layout",
+                text_style.clone(),
+            ));
+            // A row of cards with margins and paddings, reversed to show the direction of its
+            // main axis
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::RowReverse,
+                        padding: UiRect::all(Val::Px(20.0)),
+                        border: UiRect::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.15, 0.15, 0.2).into(),
+                    border_color: Color::rgb(0.4, 0.4, 0.5).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (index, margin) in [10.0, 20.0, 30.0].into_iter().enumerate() {
+                        parent
+                            .spawn(NodeBundle {
+                                style: Style {
+                                    flex_direction: FlexDirection::Column,
+                                    margin: UiRect::all(Val::Px(margin)),
+                                    padding: UiRect::new(
+                                        Val::Px(16.0),
+                                        Val::Px(16.0),
+                                        Val::Px(8.0),
+                                        Val::Px(8.0),
+                                    ),
+                                    ..default()
+                                },
+                                background_color: Color::rgb(0.3, 0.3, 0.4).into(),
+                                ..default()
+                            })
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    format!("Card {}", index + 1),
+                                    text_style.clone(),
+                                ));
+                                parent.spawn(TextBundle::from_section(
+                                    format!("margin: {margin}px"),
+                                    TextStyle {
+                                        font_size: 18.0,
+                                        ..text_style.clone()
+                                    },
+                                ));
+                            });
+                    }
+                });
+        })
+        .id();
+    commands.insert_resource(Root(root));
+}
+
+fn keyboard_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    mut debug_overlay: ResMut<UiDebugOverlay>,
+    flex_surface: Res<FlexSurface>,
+    root: Res<Root>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        debug_overlay.toggle();
+    }
+    if keyboard_input.just_pressed(KeyCode::P) {
+        flex_surface.debug_print(root.0);
+    }
+}
@@ -0,0 +1,150 @@
+//! Moves items between the slots of an inventory by dragging and dropping them.
+
+use bevy::{
+    prelude::*,
+    ui::{Drag, DragEnd, DragEnter, DragLeave, Drop, FocusPolicy},
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_systems((follow_cursor, highlight_slots, drop_items))
+        .run();
+}
+
+const SLOT_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
+const HOVERED_SLOT_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
+
+fn setup(mut commands: Commands) {
+    commands.spawn(Camera2dBundle::default());
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        size: Size::width(Val::Px(5.0 * 90.0)),
+                        flex_wrap: FlexWrap::Wrap,
+                        padding: UiRect::all(Val::Px(5.0)),
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.05, 0.05, 0.05).into(),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for index in 0..15 {
+                        parent
+                            .spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        size: Size::all(Val::Px(80.0)),
+                                        margin: UiRect::all(Val::Px(5.0)),
+                                        align_items: AlignItems::Center,
+                                        justify_content: JustifyContent::Center,
+                                        ..default()
+                                    },
+                                    background_color: SLOT_COLOR.into(),
+                                    ..default()
+                                },
+                                DropTarget,
+                            ))
+                            .with_children(|parent| {
+                                // Only some of the slots hold an item
+                                if index % 3 == 0 {
+                                    return;
+                                }
+                                parent.spawn((
+                                    NodeBundle {
+                                        style: Style {
+                                            size: Size::all(Val::Px(60.0)),
+                                            ..default()
+                                        },
+                                        background_color: Color::hsl(index as f32 * 24.0, 0.6, 0.5)
+                                            .into(),
+                                        border_radius: BorderRadius::all(Val::Px(10.0)),
+                                        // The slots under the item are found while it's dragged
+                                        focus_policy: FocusPolicy::Pass,
+                                        ..default()
+                                    },
+                                    Draggable::default(),
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+/// Moves the dragged items with the cursor, drawing them over the other nodes
+fn follow_cursor(
+    mut drag_events: EventReader<Drag>,
+    mut drag_end_events: EventReader<DragEnd>,
+    mut items: Query<(&mut Style, &mut ZIndex)>,
+) {
+    for event in drag_events.iter() {
+        if let Ok((mut style, mut z_index)) = items.get_mut(event.source) {
+            style.position = UiRect {
+                left: Val::Px(event.distance.x),
+                top: Val::Px(event.distance.y),
+                ..default()
+            };
+            *z_index = ZIndex::Global(1);
+        }
+    }
+    // Released items go back to their slot, which is their new slot if they were dropped
+    for event in drag_end_events.iter() {
+        if let Ok((mut style, mut z_index)) = items.get_mut(event.source) {
+            style.position = UiRect::default();
+            *z_index = ZIndex::default();
+        }
+    }
+}
+
+/// Highlights the slot under the dragged item
+fn highlight_slots(
+    mut drag_enter_events: EventReader<DragEnter>,
+    mut drag_leave_events: EventReader<DragLeave>,
+    mut slots: Query<&mut BackgroundColor, With<DropTarget>>,
+) {
+    for event in drag_leave_events.iter() {
+        if let Ok(mut color) = slots.get_mut(event.target) {
+            color.0 = SLOT_COLOR;
+        }
+    }
+    for event in drag_enter_events.iter() {
+        if let Ok(mut color) = slots.get_mut(event.target) {
+            color.0 = HOVERED_SLOT_COLOR;
+        }
+    }
+}
+
+/// Moves the dropped items to their new slot, swapping them with the item already there
+fn drop_items(
+    mut commands: Commands,
+    mut drop_events: EventReader<Drop>,
+    parents: Query<&Parent>,
+    children: Query<&Children>,
+) {
+    for event in drop_events.iter() {
+        let Ok(source_slot) = parents.get(event.payload) else {
+            continue;
+        };
+        if let Ok(target_items) = children.get(event.target) {
+            commands
+                .entity(source_slot.get())
+                .push_children(target_items);
+        }
+        commands
+            .entity(event.target)
+            .push_children(&[event.payload]);
+    }
+}
@@ -0,0 +1,80 @@
+//! Draws progress bars with a custom [`UiMaterial`], filling them with a gradient over time.
+
+use bevy::{
+    prelude::*,
+    reflect::TypeUuid,
+    render::render_resource::{AsBindGroup, ShaderRef},
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(UiMaterialPlugin::<ProgressBarMaterial>::default())
+        .add_startup_system(setup)
+        .add_system(update_progress)
+        .run();
+}
+
+fn setup(mut commands: Commands, mut materials: ResMut<Assets<ProgressBarMaterial>>) {
+    commands.spawn(Camera2dBundle::default());
+
+    let gradients = [
+        (Color::RED, Color::YELLOW),
+        (Color::CYAN, Color::BLUE),
+        (Color::GREEN, Color::WHITE),
+    ];
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(20.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (from_color, to_color) in gradients {
+                parent.spawn(MaterialNodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(400.0), Val::Px(40.0)),
+                        border: UiRect::all(Val::Px(4.0)),
+                        ..default()
+                    },
+                    material: materials.add(ProgressBarMaterial {
+                        from_color,
+                        to_color,
+                        progress: 0.0,
+                    }),
+                    ..default()
+                });
+            }
+        });
+}
+
+fn update_progress(time: Res<Time>, mut materials: ResMut<Assets<ProgressBarMaterial>>) {
+    for (index, (_, material)) in materials.iter_mut().enumerate() {
+        let speed = 0.2 + 0.1 * index as f32;
+        material.progress = (time.elapsed_seconds() * speed).fract();
+    }
+}
+
+#[derive(AsBindGroup, TypeUuid, Debug, Clone)]
+#[uuid = "3b8a2b5e-9f1c-4c43-8f6e-7c0b9d3c2a61"]
+struct ProgressBarMaterial {
+    #[uniform(0)]
+    from_color: Color,
+    #[uniform(0)]
+    to_color: Color,
+    #[uniform(0)]
+    progress: f32,
+}
+
+impl UiMaterial for ProgressBarMaterial {
+    fn fragment_shader() -> ShaderRef {
+        "shaders/progress_bar_material.wgsl".into()
+    }
+}
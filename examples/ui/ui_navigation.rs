@@ -0,0 +1,145 @@
+//! Navigates a menu of buttons with the arrow keys or a gamepad, with [`Focusable`] nodes.
+//!
+//! Press Enter, Space or the South button of a gamepad to click the focused button.
+
+use bevy::{prelude::*, ui::NavigationCancel};
+
+const NORMAL_BUTTON: Color = Color::rgb(0.15, 0.15, 0.15);
+const FOCUSED_BUTTON: Color = Color::rgb(0.25, 0.25, 0.45);
+const PRESSED_BUTTON: Color = Color::rgb(0.35, 0.75, 0.35);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_systems((button_system, status_system))
+        .run();
+}
+
+/// The text showing the last clicked button.
+#[derive(Component)]
+struct Status;
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut navigation_focus: ResMut<NavigationFocus>,
+) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    commands.spawn(Camera2dBundle::default());
+
+    let mut rows = Vec::new();
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "Use the arrow keys or a gamepad",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 30.0,
+                        color: Color::WHITE,
+                        ..default()
+                    },
+                ),
+                Status,
+            ));
+            for row in 0..3 {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            gap: Size::all(Val::Px(10.0)),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        let buttons: Vec<Entity> = (0..3)
+                            .map(|column| {
+                                parent
+                                    .spawn((
+                                        ButtonBundle {
+                                            style: Style {
+                                                size: Size::new(Val::Px(150.0), Val::Px(65.0)),
+                                                justify_content: JustifyContent::Center,
+                                                align_items: AlignItems::Center,
+                                                ..default()
+                                            },
+                                            background_color: NORMAL_BUTTON.into(),
+                                            ..default()
+                                        },
+                                        Focusable::DEFAULT,
+                                        Name::new(format!("Button {}", row * 3 + column + 1)),
+                                    ))
+                                    .with_children(|parent| {
+                                        parent.spawn(TextBundle::from_section(
+                                            format!("{}", row * 3 + column + 1),
+                                            TextStyle {
+                                                font: font.clone(),
+                                                font_size: 40.0,
+                                                color: Color::WHITE,
+                                                ..default()
+                                            },
+                                        ));
+                                    })
+                                    .id()
+                            })
+                            .collect();
+                        rows.push(buttons);
+                    });
+            }
+        });
+
+    // The focus wraps around the ends of the rows, instead of stopping there
+    for buttons in &rows {
+        let (first, last) = (buttons[0], buttons[buttons.len() - 1]);
+        commands
+            .entity(first)
+            .insert(Focusable::DEFAULT.with_neighbor(NavDirection::Left, last));
+        commands
+            .entity(last)
+            .insert(Focusable::DEFAULT.with_neighbor(NavDirection::Right, first));
+    }
+    navigation_focus.focus(rows[0][0]);
+}
+
+fn button_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Button>),
+    >,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Clicked => PRESSED_BUTTON.into(),
+            Interaction::Hovered => FOCUSED_BUTTON.into(),
+            Interaction::None => NORMAL_BUTTON.into(),
+        };
+    }
+}
+
+fn status_system(
+    mut cancel_events: EventReader<NavigationCancel>,
+    buttons: Query<(&Interaction, &Name), Changed<Interaction>>,
+    mut status: Query<&mut Text, With<Status>>,
+) {
+    let mut status = status.single_mut();
+    for (interaction, name) in &buttons {
+        if *interaction == Interaction::Clicked {
+            status.sections[0].value = format!("{name} clicked");
+        }
+    }
+    if cancel_events.iter().next().is_some() {
+        status.sections[0].value = "Cancelled".to_string();
+    }
+}
@@ -0,0 +1,136 @@
+//! Skins buttons of different sizes with the 9-sliced regions of a texture atlas, switching
+//! between the normal, hovered and pressed regions of the atlas with their [`Interaction`].
+
+use bevy::{
+    prelude::*,
+    render::render_resource::{Extent3d, TextureDimension, TextureFormat},
+};
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(ImagePlugin::default_nearest())) // prevents blurry images
+        .add_startup_system(setup)
+        .add_system(button_system)
+        .run();
+}
+
+/// The size of each region of the atlas, in pixels.
+const REGION: u32 = 32;
+
+/// The size of the borders of the regions, in pixels.
+const BORDER: f32 = 8.0;
+
+/// Creates an atlas of 3 panels side by side, with frames of different colors around a darker
+/// center: the normal, hovered and pressed skins of the buttons.
+fn atlas_image() -> Image {
+    const FRAMES: [[u8; 4]; 3] = [[150, 110, 60, 255], [190, 150, 80, 255], [100, 70, 40, 255]];
+    let width = REGION * FRAMES.len() as u32;
+    let mut data = Vec::with_capacity((width * REGION * 4) as usize);
+    for y in 0..REGION {
+        for x in 0..width {
+            let (region, x) = ((x / REGION) as usize, x % REGION);
+            let edge = x.min(y).min(REGION - 1 - x).min(REGION - 1 - y);
+            let color: [u8; 4] = match edge {
+                0 => [20, 20, 30, 255],
+                1..=2 => [230, 200, 120, 255],
+                3..=7 => FRAMES[region],
+                _ => [50, 50, 65, 255],
+            };
+            data.extend_from_slice(&color);
+        }
+    }
+    Image::new(
+        Extent3d {
+            width,
+            height: REGION,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8UnormSrgb,
+    )
+}
+
+fn setup(
+    mut commands: Commands,
+    mut images: ResMut<Assets<Image>>,
+    mut texture_atlases: ResMut<Assets<TextureAtlas>>,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn(Camera2dBundle::default());
+
+    let texture_atlas = texture_atlases.add(TextureAtlas::from_grid(
+        images.add(atlas_image()),
+        Vec2::splat(REGION as f32),
+        3,
+        1,
+        None,
+        None,
+    ));
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(20.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (label, width, height) in [
+                ("Play", 200.0, 60.0),
+                ("Options", 320.0, 80.0),
+                ("Quit", 150.0, 120.0),
+            ] {
+                parent
+                    .spawn((
+                        AtlasImageBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(width), Val::Px(height)),
+                                align_items: AlignItems::Center,
+                                justify_content: JustifyContent::Center,
+                                ..default()
+                            },
+                            texture_atlas: texture_atlas.clone(),
+                            texture_atlas_image: UiTextureAtlasImage {
+                                scale_mode: ImageScaleMode::Sliced(TextureSlicer {
+                                    border: BorderRect::square(BORDER),
+                                    ..default()
+                                }),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        Interaction::default(),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section(
+                            label,
+                            TextStyle {
+                                font: font.clone(),
+                                font_size: 30.0,
+                                color: Color::WHITE,
+                                ..default()
+                            },
+                        ));
+                    });
+            }
+        });
+}
+
+fn button_system(
+    mut interaction_query: Query<(&Interaction, &mut UiTextureAtlasImage), Changed<Interaction>>,
+) {
+    for (interaction, mut atlas_image) in &mut interaction_query {
+        atlas_image.index = match *interaction {
+            Interaction::None => 0,
+            Interaction::Hovered => 1,
+            Interaction::Clicked => 2,
+        };
+    }
+}
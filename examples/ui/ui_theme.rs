@@ -0,0 +1,92 @@
+//! Styles a menu with the [`Class`]es of a [`UiTheme`], switched between a light and a dark
+//! theme with the space bar.
+//!
+//! The themes are in `assets/themes`, and the menu is restyled when they're edited while the
+//! example runs. The buttons change color while hovered and pressed, with the `:hovered` and
+//! `:pressed` classes of the themes.
+
+use bevy::prelude::*;
+use bevy::ui::Theme;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins.set(AssetPlugin {
+            // Reloads the themes when they're edited
+            watch_for_changes: true,
+            ..default()
+        }))
+        .add_startup_system(setup)
+        .add_system(switch_theme_system)
+        .run();
+}
+
+/// The light and dark themes.
+#[derive(Resource)]
+struct Themes([Handle<Theme>; 2]);
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let themes = Themes([
+        asset_server.load("themes/light.theme.ron"),
+        asset_server.load("themes/dark.theme.ron"),
+    ]);
+    commands.insert_resource(UiTheme(themes.0[0].clone()));
+    commands.insert_resource(themes);
+    commands.spawn(Camera2dBundle::default());
+
+    // Only the layout is set here, the look of the nodes comes from their classes
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            ..default()
+                        },
+                        ..default()
+                    },
+                    Class::new("panel"),
+                ))
+                .with_children(|parent| {
+                    parent.spawn((
+                        TextBundle::from_section("Press space to switch theme", default()),
+                        Class::new("title"),
+                    ));
+                    for (label, class) in [
+                        ("Continue", "button"),
+                        ("Settings", "button"),
+                        ("Delete save", "button danger-button"),
+                    ] {
+                        parent
+                            .spawn((ButtonBundle::default(), Class::new(class)))
+                            .with_children(|parent| {
+                                parent.spawn((
+                                    TextBundle::from_section(label, default()),
+                                    Class::new("label"),
+                                ));
+                            });
+                    }
+                });
+        });
+}
+
+fn switch_theme_system(
+    keyboard_input: Res<Input<KeyCode>>,
+    themes: Res<Themes>,
+    mut ui_theme: ResMut<UiTheme>,
+) {
+    if keyboard_input.just_pressed(KeyCode::Space) {
+        let next = if ui_theme.0 == themes.0[0] { 1 } else { 0 };
+        ui_theme.0 = themes.0[next].clone();
+    }
+}
@@ -0,0 +1,145 @@
+//! Highlights buttons when hovered with [`InteractionTransition`]s, and slides a menu in and out
+//! by setting the target of a [`Transition`].
+
+use bevy::{
+    prelude::*,
+    ui::{BackgroundColorProperty, PositionProperty, ScaleProperty},
+};
+
+const MENU_WIDTH: f32 = 250.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(toggle_menu)
+        .run();
+}
+
+/// The menu sliding in from the left.
+#[derive(Component)]
+struct Menu;
+
+/// The button opening and closing the menu.
+#[derive(Component)]
+struct MenuButton;
+
+/// The highlight of a button when it's hovered or clicked.
+fn button_transitions() -> impl Bundle {
+    (
+        Transition::<BackgroundColorProperty>::new(0.15),
+        InteractionTransition::<BackgroundColorProperty> {
+            none: Color::rgb(0.15, 0.15, 0.15),
+            hovered: Color::rgb(0.3, 0.3, 0.6),
+            clicked: Color::rgb(0.4, 0.6, 0.4),
+        },
+        Transition::<ScaleProperty>::new(0.15).with_easing(Easing::EaseOut),
+        InteractionTransition::<ScaleProperty> {
+            none: Vec3::ONE,
+            hovered: Vec3::splat(1.05),
+            clicked: Vec3::splat(0.95),
+        },
+    )
+}
+
+fn spawn_button(parent: &mut ChildBuilder, text: &str, font: Handle<Font>, bundle: impl Bundle) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    size: Size::new(Val::Px(200.0), Val::Px(50.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                background_color: Color::rgb(0.15, 0.15, 0.15).into(),
+                border_radius: BorderRadius::all(Val::Px(8.0)),
+                ..default()
+            },
+            button_transitions(),
+            bundle,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                text,
+                TextStyle {
+                    font,
+                    font_size: 30.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    commands.spawn(Camera2dBundle::default());
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+            background_color: Color::rgb(0.4, 0.4, 0.4).into(),
+            ..default()
+        })
+        .with_children(|parent| {
+            spawn_button(parent, "Menu", font.clone(), MenuButton);
+
+            // The menu is out of the window, on the left, until opened
+            parent
+                .spawn((
+                    NodeBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            position: UiRect {
+                                left: Val::Px(-MENU_WIDTH),
+                                top: Val::Px(0.0),
+                                ..default()
+                            },
+                            size: Size::new(Val::Px(MENU_WIDTH), Val::Percent(100.0)),
+                            flex_direction: FlexDirection::Column,
+                            align_items: AlignItems::Center,
+                            padding: UiRect::all(Val::Px(20.0)),
+                            gap: Size::all(Val::Px(10.0)),
+                            ..default()
+                        },
+                        background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                        ..default()
+                    },
+                    Transition::<PositionProperty>::new(0.4),
+                    Menu,
+                ))
+                .with_children(|parent| {
+                    for text in ["Play", "Options", "Quit"] {
+                        spawn_button(parent, text, font.clone(), ());
+                    }
+                });
+        });
+}
+
+fn toggle_menu(
+    menu_buttons: Query<&Interaction, (Changed<Interaction>, With<MenuButton>)>,
+    mut menus: Query<(&Style, &mut Transition<PositionProperty>), With<Menu>>,
+) {
+    for interaction in &menu_buttons {
+        if *interaction != Interaction::Clicked {
+            continue;
+        }
+        for (style, mut transition) in &mut menus {
+            let opened = match transition.target() {
+                Some(target) => target.left == Val::Px(0.0),
+                None => false,
+            };
+            let left = if opened { -MENU_WIDTH } else { 0.0 };
+            transition.set_target(UiRect {
+                left: Val::Px(left),
+                ..style.position
+            });
+        }
+    }
+}
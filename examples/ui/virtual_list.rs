@@ -0,0 +1,105 @@
+//! Shows a leaderboard of 10,000 players in a [`VirtualList`], which only spawns the rows in view
+//! and recycles them while it's scrolled with the mouse wheel.
+
+use bevy::prelude::*;
+
+const PLAYERS: usize = 10_000;
+const ROW_HEIGHT: f32 = 32.0;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_system(fill_rows_system)
+        .run();
+}
+
+/// The text of a row, set from the index of the row once it's spawned or recycled.
+#[derive(Component)]
+struct RowText;
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font: Handle<Font> = asset_server.load("fonts/FiraSans-Bold.ttf");
+    commands.spawn(Camera2dBundle::default());
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Leaderboard",
+                TextStyle {
+                    font: font.clone(),
+                    font_size: 40.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(400.0), Val::Percent(70.0)),
+                        overflow: Overflow::Scroll,
+                        ..default()
+                    },
+                    background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                    ..default()
+                },
+                VirtualList::new(PLAYERS, ROW_HEIGHT, move |row| {
+                    // Every row has the same layout, only its text and color change with its index
+                    let font = font.clone();
+                    row.with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font,
+                                    font_size: 20.0,
+                                    color: Color::WHITE,
+                                    ..default()
+                                },
+                            )
+                            .with_style(Style {
+                                margin: UiRect::left(Val::Px(10.0)),
+                                align_self: AlignSelf::Center,
+                                ..default()
+                            }),
+                            RowText,
+                        ));
+                    });
+                }),
+            ));
+        });
+}
+
+fn fill_rows_system(
+    mut row_query: Query<
+        (&VirtualListRow, &Children, &mut BackgroundColor),
+        Changed<VirtualListRow>,
+    >,
+    mut text_query: Query<&mut Text, With<RowText>>,
+) {
+    for (row, children, mut color) in &mut row_query {
+        let rank = row.index + 1;
+        let score = (PLAYERS - row.index) * 10;
+        *color = if row.index % 2 == 0 {
+            Color::rgb(0.15, 0.15, 0.15).into()
+        } else {
+            Color::rgb(0.2, 0.2, 0.2).into()
+        };
+        for child in children {
+            if let Ok(mut text) = text_query.get_mut(*child) {
+                text.sections[0].value = format!("#{rank:<6} Player {rank:<8} {score} pts");
+            }
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Builds a settings panel with a [`Checkbox`], [`RadioButton`]s, a [`Slider`] and a
+//! [`Dropdown`], showing the last changed value.
+//!
+//! The widgets are also used with the arrow keys or a gamepad, once focused.
+
+use bevy::{
+    prelude::*,
+    ui::{
+        widget::{CheckboxChanged, DropdownChanged, RadioGroupChanged, SliderChanged},
+        RelativeCursorPosition,
+    },
+};
+
+const QUALITIES: [&str; 3] = ["Low", "Medium", "High"];
+const RESOLUTIONS: [&str; 3] = ["1280x720", "1920x1080", "2560x1440"];
+
+const NORMAL: Color = Color::rgb(0.15, 0.15, 0.15);
+const HOVERED: Color = Color::rgb(0.25, 0.25, 0.45);
+const MARK: Color = Color::rgb(0.35, 0.75, 0.35);
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_systems((hover_system, status_system))
+        .run();
+}
+
+/// The text showing the last changed value.
+#[derive(Component)]
+struct Status;
+
+/// The text of the dropdown, showing the selected resolution.
+#[derive(Component)]
+struct ResolutionLabel;
+
+/// Marks the widgets highlighted when hovered or focused.
+#[derive(Component)]
+struct Highlighted;
+
+fn text(text: &str, font: &Handle<Font>) -> TextBundle {
+    TextBundle::from_section(
+        text,
+        TextStyle {
+            font: font.clone(),
+            font_size: 24.0,
+            color: Color::WHITE,
+            ..default()
+        },
+    )
+}
+
+fn row(
+    parent: &mut ChildBuilder,
+    label: &str,
+    font: &Handle<Font>,
+    spawn: impl FnOnce(&mut ChildBuilder),
+) {
+    parent
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                gap: Size::all(Val::Px(20.0)),
+                size: Size::new(Val::Px(500.0), Val::Px(40.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(text(label, font).with_style(Style {
+                size: Size::width(Val::Px(140.0)),
+                ..default()
+            }));
+            spawn(parent);
+        });
+}
+
+/// A square box with a check mark inside, shown while checked.
+fn spawn_box(parent: &mut ChildBuilder, bundle: impl Bundle, radius: f32) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    size: Size::all(Val::Px(28.0)),
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: NORMAL.into(),
+                border_radius: BorderRadius::all(Val::Px(radius)),
+                ..default()
+            },
+            Focusable::DEFAULT,
+            Highlighted,
+            bundle,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: Style {
+                        size: Size::all(Val::Px(16.0)),
+                        ..default()
+                    },
+                    background_color: MARK.into(),
+                    border_radius: BorderRadius::all(Val::Px(radius / 2.0)),
+                    ..default()
+                },
+                CheckMark,
+            ));
+        });
+}
+
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    commands.spawn(Camera2dBundle::default());
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                gap: Size::all(Val::Px(10.0)),
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn((text("Change a setting", &font), Status));
+
+            row(parent, "Fullscreen", &font, |parent| {
+                spawn_box(parent, Checkbox::new(false), 4.0);
+            });
+
+            row(parent, "Quality", &font, |parent| {
+                parent
+                    .spawn((
+                        NodeBundle {
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                gap: Size::all(Val::Px(10.0)),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        RadioGroup::new(1),
+                    ))
+                    .with_children(|parent| {
+                        for (value, quality) in QUALITIES.iter().enumerate() {
+                            spawn_box(parent, RadioButton::new(value), 14.0);
+                            parent.spawn(text(quality, &font));
+                        }
+                    });
+            });
+
+            row(parent, "Volume", &font, |parent| {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(300.0), Val::Px(12.0)),
+                                ..default()
+                            },
+                            background_color: NORMAL.into(),
+                            border_radius: BorderRadius::all(Val::Px(6.0)),
+                            ..default()
+                        },
+                        Slider::new(0.0, 100.0, 50.0).with_step(5.0),
+                        RelativeCursorPosition::default(),
+                        Focusable::DEFAULT,
+                        CaptureNavigation::Horizontal,
+                        Highlighted,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    size: Size::new(Val::Percent(50.0), Val::Percent(100.0)),
+                                    ..default()
+                                },
+                                background_color: MARK.into(),
+                                border_radius: BorderRadius::all(Val::Px(6.0)),
+                                ..default()
+                            },
+                            SliderFill,
+                        ));
+                        parent.spawn((
+                            NodeBundle {
+                                style: Style {
+                                    position_type: PositionType::Absolute,
+                                    position: UiRect {
+                                        left: Val::Percent(50.0),
+                                        top: Val::Px(-6.0),
+                                        ..default()
+                                    },
+                                    margin: UiRect::left(Val::Px(-12.0)),
+                                    size: Size::all(Val::Px(24.0)),
+                                    ..default()
+                                },
+                                background_color: Color::WHITE.into(),
+                                border_radius: BorderRadius::all(Val::Px(12.0)),
+                                ..default()
+                            },
+                            SliderThumb,
+                        ));
+                    });
+            });
+
+            row(parent, "Resolution", &font, |parent| {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                size: Size::new(Val::Px(200.0), Val::Px(36.0)),
+                                align_items: AlignItems::Center,
+                                padding: UiRect::horizontal(Val::Px(10.0)),
+                                ..default()
+                            },
+                            background_color: NORMAL.into(),
+                            border_radius: BorderRadius::all(Val::Px(4.0)),
+                            ..default()
+                        },
+                        Dropdown::new(1),
+                        Focusable::DEFAULT,
+                        Highlighted,
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((text(RESOLUTIONS[1], &font), ResolutionLabel));
+                        // The list of options, under the dropdown and over the other nodes
+                        parent
+                            .spawn((
+                                NodeBundle {
+                                    style: Style {
+                                        display: Display::None,
+                                        position_type: PositionType::Absolute,
+                                        position: UiRect {
+                                            left: Val::Px(0.0),
+                                            top: Val::Percent(100.0),
+                                            ..default()
+                                        },
+                                        size: Size::width(Val::Percent(100.0)),
+                                        flex_direction: FlexDirection::Column,
+                                        ..default()
+                                    },
+                                    background_color: Color::rgb(0.1, 0.1, 0.1).into(),
+                                    z_index: ZIndex::Global(1),
+                                    ..default()
+                                },
+                                DropdownList,
+                            ))
+                            .with_children(|parent| {
+                                for (value, resolution) in RESOLUTIONS.iter().enumerate() {
+                                    parent
+                                        .spawn((
+                                            ButtonBundle {
+                                                style: Style {
+                                                    size: Size::new(
+                                                        Val::Percent(100.0),
+                                                        Val::Px(36.0),
+                                                    ),
+                                                    align_items: AlignItems::Center,
+                                                    padding: UiRect::horizontal(Val::Px(10.0)),
+                                                    gap: Size::all(Val::Px(8.0)),
+                                                    ..default()
+                                                },
+                                                background_color: NORMAL.into(),
+                                                ..default()
+                                            },
+                                            DropdownOption::new(value),
+                                            Focusable::DEFAULT,
+                                            Highlighted,
+                                        ))
+                                        .with_children(|parent| {
+                                            parent.spawn((
+                                                NodeBundle {
+                                                    style: Style {
+                                                        size: Size::all(Val::Px(8.0)),
+                                                        ..default()
+                                                    },
+                                                    background_color: MARK.into(),
+                                                    ..default()
+                                                },
+                                                CheckMark,
+                                            ));
+                                            parent.spawn(text(resolution, &font));
+                                        });
+                                }
+                            });
+                    });
+            });
+        });
+}
+
+fn hover_system(
+    mut interaction_query: Query<
+        (&Interaction, &mut BackgroundColor),
+        (Changed<Interaction>, With<Highlighted>),
+    >,
+) {
+    for (interaction, mut color) in &mut interaction_query {
+        *color = match *interaction {
+            Interaction::Clicked | Interaction::Hovered => HOVERED.into(),
+            Interaction::None => NORMAL.into(),
+        };
+    }
+}
+
+fn status_system(
+    mut checkbox_events: EventReader<CheckboxChanged>,
+    mut radio_events: EventReader<RadioGroupChanged>,
+    mut slider_events: EventReader<SliderChanged>,
+    mut dropdown_events: EventReader<DropdownChanged>,
+    mut status: Query<&mut Text, (With<Status>, Without<ResolutionLabel>)>,
+    mut resolution_label: Query<&mut Text, With<ResolutionLabel>>,
+) {
+    let mut status = status.single_mut();
+    let value = &mut status.sections[0].value;
+    for event in checkbox_events.iter() {
+        *value = format!("Fullscreen: {}", event.checked);
+    }
+    for event in radio_events.iter() {
+        *value = format!("Quality: {}", QUALITIES[event.selected]);
+    }
+    for event in slider_events.iter() {
+        *value = format!("Volume: {}", event.value);
+    }
+    for event in dropdown_events.iter() {
+        *value = format!("Resolution: {}", RESOLUTIONS[event.selected]);
+        resolution_label.single_mut().sections[0].value = RESOLUTIONS[event.selected].to_string();
+    }
+}
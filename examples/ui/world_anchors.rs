@@ -0,0 +1,169 @@
+//! Positions health bars over characters walking around a 3d scene with [`WorldAnchor`]s, and a
+//! marker pointing to a waypoint that stays on the edges of the window when it's out of view.
+
+use std::f32::consts::TAU;
+
+use bevy::prelude::*;
+
+fn main() {
+    App::new()
+        .add_plugins(DefaultPlugins)
+        .add_startup_system(setup)
+        .add_systems((orbit_camera, walk, update_health_bars))
+        .run();
+}
+
+/// A character walking in a circle.
+#[derive(Component)]
+struct Walk {
+    radius: f32,
+    speed: f32,
+}
+
+/// The fill of the health bar of a character, whose health goes up and down over time.
+#[derive(Component)]
+struct HealthBar {
+    phase: f32,
+}
+
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.spawn(Camera3dBundle {
+        transform: Transform::from_xyz(0.0, 6.0, 12.0).looking_at(Vec3::ZERO, Vec3::Y),
+        ..default()
+    });
+    commands.spawn(PointLightBundle {
+        point_light: PointLight {
+            intensity: 3000.0,
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform::from_xyz(4.0, 8.0, 4.0),
+        ..default()
+    });
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(shape::Plane::from_size(20.0).into()),
+        material: materials.add(Color::rgb(0.3, 0.5, 0.3).into()),
+        ..default()
+    });
+
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    let character_mesh = meshes.add(Mesh::from(shape::Capsule::default()));
+    for (index, color) in [Color::RED, Color::BLUE, Color::YELLOW]
+        .into_iter()
+        .enumerate()
+    {
+        let character = commands
+            .spawn((
+                PbrBundle {
+                    mesh: character_mesh.clone(),
+                    material: materials.add(color.into()),
+                    transform: Transform::from_xyz(0.0, 1.0, 0.0),
+                    ..default()
+                },
+                Walk {
+                    radius: 2.0 + index as f32 * 2.0,
+                    speed: 0.5 - index as f32 * 0.1,
+                },
+            ))
+            .id();
+
+        // The health bar is drawn above the head of the character
+        commands
+            .spawn((
+                NodeBundle {
+                    style: Style {
+                        position_type: PositionType::Absolute,
+                        size: Size::new(Val::Px(80.0), Val::Px(10.0)),
+                        border: UiRect::all(Val::Px(2.0)),
+                        ..default()
+                    },
+                    background_color: Color::BLACK.into(),
+                    ..default()
+                },
+                WorldAnchor::new(character).with_offset(Vec3::Y * 1.5),
+            ))
+            .with_children(|parent| {
+                parent.spawn((
+                    NodeBundle {
+                        style: Style {
+                            size: Size::height(Val::Percent(100.0)),
+                            ..default()
+                        },
+                        background_color: Color::GREEN.into(),
+                        ..default()
+                    },
+                    HealthBar {
+                        phase: index as f32,
+                    },
+                ));
+            });
+    }
+
+    // The waypoint marker stays visible when the waypoint is out of view
+    let waypoint = commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Cube { size: 0.5 })),
+            material: materials.add(Color::WHITE.into()),
+            transform: Transform::from_xyz(8.0, 0.25, -8.0),
+            ..default()
+        })
+        .id();
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    padding: UiRect::all(Val::Px(5.0)),
+                    ..default()
+                },
+                background_color: Color::rgba(0.0, 0.0, 0.0, 0.6).into(),
+                border_radius: BorderRadius::all(Val::Px(5.0)),
+                ..default()
+            },
+            WorldAnchor::new(waypoint)
+                .with_offset(Vec3::Y)
+                .with_off_screen(OffScreen::Clamp),
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Waypoint",
+                TextStyle {
+                    font,
+                    font_size: 20.0,
+                    color: Color::WHITE,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn orbit_camera(time: Res<Time>, mut cameras: Query<&mut Transform, With<Camera>>) {
+    let angle = time.elapsed_seconds() * 0.2;
+    for mut transform in &mut cameras {
+        *transform = Transform::from_xyz(12.0 * angle.cos(), 6.0, 12.0 * angle.sin())
+            .looking_at(Vec3::ZERO, Vec3::Y);
+    }
+}
+
+fn walk(time: Res<Time>, mut characters: Query<(&Walk, &mut Transform)>) {
+    for (walk, mut transform) in &mut characters {
+        let angle = (time.elapsed_seconds() * walk.speed) % TAU;
+        transform.translation = Vec3::new(
+            walk.radius * angle.cos(),
+            transform.translation.y,
+            walk.radius * angle.sin(),
+        );
+    }
+}
+
+fn update_health_bars(time: Res<Time>, mut health_bars: Query<(&HealthBar, &mut Style)>) {
+    for (health_bar, mut style) in &mut health_bars {
+        let health = 0.5 + 0.5 * (time.elapsed_seconds() + health_bar.phase).sin();
+        style.size.width = Val::Percent(health * 100.0);
+    }
+}